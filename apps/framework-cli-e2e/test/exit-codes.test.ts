@@ -0,0 +1,66 @@
+/**
+ * E2E tests for the CLI's standardized exit codes.
+ *
+ * Scripts wrapping the CLI rely on these values (see EXIT CODES in
+ * `moose --help`), so they are asserted against the real binary.
+ */
+import { expect } from "chai";
+import { spawnSync } from "child_process";
+import * as fs from "fs";
+import * as path from "path";
+import { createTempTestDirectory, removeTestProject } from "./utils/file-utils";
+import { logger } from "./utils/logger";
+
+const testLogger = logger.scope("exit-codes");
+
+const CLI_PATH = path.resolve(__dirname, "../../../target/debug/moose-cli");
+
+const EXIT_VALIDATION_FAILURE = 3;
+
+describe("CLI exit codes", function () {
+  this.timeout(60_000);
+
+  let projectDir: string;
+
+  before(function () {
+    projectDir = createTempTestDirectory("exit-codes", { logger: testLogger });
+    fs.mkdirSync(projectDir, { recursive: true });
+  });
+
+  after(function () {
+    if (projectDir) {
+      removeTestProject(projectDir, { logger: testLogger });
+    }
+  });
+
+  it("exits with the validation failure code for an invalid config", function () {
+    fs.writeFileSync(
+      path.join(projectDir, "moose.config.toml"),
+      "language = \n",
+    );
+
+    const result = spawnSync(CLI_PATH, ["check", "preflight", "--json"], {
+      cwd: projectDir,
+      encoding: "utf-8",
+    });
+    testLogger.info("Preflight output", {
+      status: result.status,
+      stdout: result.stdout,
+      stderr: result.stderr,
+    });
+
+    expect(result.status).to.equal(EXIT_VALIDATION_FAILURE);
+
+    const report = JSON.parse(result.stdout);
+    expect(report.checks[0].name).to.equal("config");
+    expect(report.checks[0].status).to.equal("fail");
+  });
+
+  it("prints the exit code table in --help", function () {
+    const result = spawnSync(CLI_PATH, ["--help"], { encoding: "utf-8" });
+
+    expect(result.status).to.equal(0);
+    expect(result.stdout).to.include("EXIT CODES");
+    expect(result.stdout).to.include("130  Interrupted");
+  });
+});
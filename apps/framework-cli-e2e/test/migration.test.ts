@@ -229,6 +229,22 @@ describe("typescript template tests - migration", () => {
       testLogger.info("✓ Migration applied successfully");
       testLogger.info("✓ State saved to _MOOSE_STATE");
     });
+
+    it("should exit 0 from moose plan when there are no changes", async function () {
+      this.timeout(TIMEOUTS.MIGRATION_MS);
+
+      // execAsync rejects on any non-zero exit code, including 2 (plan has changes)
+      const { stdout } = await execAsync(
+        `"${CLI_PATH}" plan --clickhouse-url "${CLICKHOUSE_URL}" --json`,
+        {
+          cwd: innerMooseDir,
+        },
+      );
+
+      const plan = JSON.parse(stdout);
+      expect(plan).to.have.property("changes");
+      testLogger.info("✓ No-changes plan exited with code 0");
+    });
   });
 
   describe("Drift detection", () => {
@@ -2,7 +2,9 @@
 pub(crate) mod display;
 
 mod commands;
+pub mod exit_code;
 pub mod local_webserver;
+pub mod log_rotation;
 pub mod logger;
 pub mod processing_coordinator;
 pub mod routines;
@@ -18,26 +20,31 @@ use super::metrics::Metrics;
 use crate::utilities::{constants, docker::DockerClient};
 use clap::Parser;
 use commands::{
-    Commands, ComponentSubCommands, DbCommands, DocsCommands, GenerateCommand, KafkaArgs,
-    KafkaCommands, TemplateSubCommands, WorkflowCommands,
+    CheckSubcommands, Commands, ComponentSubCommands, DbCommands, DocsCommands, GenerateCommand,
+    KafkaArgs, KafkaCommands, TemplateSubCommands, WorkflowCommands,
 };
 use config::ConfigError;
 use display::with_spinner_completion;
+use exit_code::CliExitCode;
+use local_webserver::default_management_port;
 use regex::Regex;
 use routines::auth::{display_hash_token_result, generate_hash_token};
 use routines::build::build_package;
-use routines::clean::clean_project;
+use routines::check_olap::check_olap;
+use routines::clean::{clean_project, CleanOptions};
 use routines::docker_packager::{build_dockerfile, create_dockerfile};
 use routines::kafka_pull::write_external_topics;
-use routines::metrics_console::run_console;
-use routines::peek::peek;
+use routines::metrics_console::{run_console, AdminClient};
+use routines::openapi::{generate_openapi, OpenAPIError, OpenAPIFormat};
+use routines::peek::{peek, PeekFormat, PeekOptions, PeekQueryError};
+use routines::preflight::{preflight, preflight_without_project};
 use routines::ps::show_processes;
 use routines::query::query;
 use routines::scripts::{
     cancel_workflow, get_workflow_status, list_workflows_history, pause_workflow, run_workflow,
     terminate_workflow, unpause_workflow,
 };
-use routines::templates::list_available_templates;
+use routines::templates::{list_available_templates, show_template};
 use tracing::{debug, info, warn};
 
 use settings::Settings;
@@ -45,7 +52,10 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 
-use crate::cli::routines::logs::{follow_logs, show_logs};
+use crate::cli::log_rotation::{active_log_file_name, log_files_since};
+use crate::cli::routines::logs::{
+    follow_logs, parse_since, show_logs, LogLevel, LogPattern, LogQuery, LogQueryError,
+};
 use crate::cli::routines::remote_refresh;
 use crate::cli::routines::setup_redis_client;
 use crate::cli::routines::{RoutineFailure, RoutineSuccess};
@@ -60,39 +70,134 @@ use crate::infrastructure::olap::clickhouse::config::{
     parse_clickhouse_connection_string, parse_clickhouse_connection_string_with_metadata,
 };
 use crate::infrastructure::olap::clickhouse::config_resolver::store_remote_clickhouse_credentials;
+use crate::infrastructure::olap::clickhouse::diagnostics::{DiagnosticOptions, Severity};
 use crate::metrics::TelemetryMetadata;
 use crate::project::{ClickHouseProtocol, Project, RemoteClickHouseConfig};
 use crate::utilities::capture::{wait_for_usage_capture, ActivityType};
-use crate::utilities::constants::KEY_REMOTE_CLICKHOUSE_URL;
 use crate::utilities::constants::{
     CLI_VERSION, ENV_CLICKHOUSE_URL, MIGRATION_AFTER_STATE_FILE, MIGRATION_BEFORE_STATE_FILE,
-    MIGRATION_FILE, PROJECT_NAME_ALLOW_PATTERN,
+    MIGRATION_FILE, MIGRATION_SUMMARY_FILE, PROJECT_NAME_ALLOW_PATTERN,
+};
+use crate::utilities::constants::{KEY_REMOTE_CLICKHOUSE_URL, REMOTE_CLICKHOUSE_URL_SECRET};
+use crate::utilities::git::current_revision;
+use crate::utilities::secret_store::project_secrets;
+use crate::utilities::telemetry::telemetry_enabled;
+
+use crate::cli::commands::{
+    AddComponent, ConfigArgs, ConfigCommands, DbArgs, SecretArgs, SecretCommands, TableArgs,
+    TableCommands, TelemetryCommands,
 };
-use crate::utilities::keyring::{KeyringSecretRepository, SecretRepository};
-
-use crate::cli::commands::{AddComponent, DbArgs};
 use crate::cli::routines::code_generation::{
     db_pull, db_pull_from_remote, db_to_dmv2, prompt_user_for_remote_ch_http,
 };
+use crate::cli::routines::db_diff::{db_diff, DbDiffOptions};
+use crate::cli::routines::describe_table::describe_table;
 use crate::cli::routines::ls::ls;
+use crate::cli::routines::secret::{delete_secret, get_secret, list_secrets, set_secret};
+use crate::cli::routines::show_config::show_config;
 use crate::cli::routines::templates::create_project_from_template;
+use crate::cli::routines::validate_config::validate_config;
 use crate::framework::core::migration_plan::MIGRATION_SCHEMA;
 use crate::framework::languages::SupportedLanguages;
 use crate::infrastructure::olap::clickhouse::config_resolver::resolve_remote_clickhouse;
-use crate::utilities::constants::{QUIET_STDOUT, SHOW_TIMESTAMPS, SHOW_TIMING};
+use crate::project::config_overrides::ConfigOverride;
+use crate::utilities::constants::{NON_INTERACTIVE, QUIET_STDOUT, SHOW_TIMESTAMPS, SHOW_TIMING};
 use anyhow::Result;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
 use tokio::time::timeout;
 
+/// A choice the CLI asks the user to make through [`prompt_user`] or
+/// [`prompt_password`].
+///
+/// In non-interactive mode the prompt is never shown. Its default is taken when
+/// there is one and it is safe to take, otherwise the command fails with an error
+/// naming the decision and the flag that would supply it.
+#[derive(Debug, Clone, Copy)]
+pub struct Decision<'a> {
+    /// What the prompt decides, e.g. "project language"
+    pub name: &'a str,
+    /// Flag that answers the prompt up front, e.g. "--language"
+    pub flag: Option<&'a str>,
+    default_is_safe: bool,
+}
+
+impl<'a> Decision<'a> {
+    pub fn new(name: &'a str, flag: Option<&'a str>) -> Self {
+        Decision {
+            name,
+            flag,
+            default_is_safe: true,
+        }
+    }
+
+    /// Confirmation of a destructive action. Its default is never taken in
+    /// non-interactive mode, only an explicit flag such as `--yes` answers it.
+    pub fn confirmation(name: &'a str, flag: &'a str) -> Self {
+        Decision {
+            name,
+            flag: Some(flag),
+            default_is_safe: false,
+        }
+    }
+}
+
+/// Whether `--non-interactive` or `MOOSE_NON_INTERACTIVE` is in effect.
+pub fn is_non_interactive() -> bool {
+    NON_INTERACTIVE.load(Ordering::Relaxed)
+}
+
+/// Whether a prompt can be shown: a terminal is attached to stdin and the CLI
+/// is not running in non-interactive mode.
+pub fn can_prompt() -> bool {
+    use std::io::IsTerminal;
+
+    !is_non_interactive() && std::io::stdin().is_terminal()
+}
+
+/// Reads `MOOSE_NON_INTERACTIVE`, accepting "1", "true" and "yes".
+pub fn non_interactive_from_env() -> bool {
+    std::env::var("MOOSE_NON_INTERACTIVE")
+        .map(|val| matches!(val.to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Answers `decision` without prompting, as done in non-interactive mode.
+fn non_interactive_answer(
+    decision: &Decision,
+    default: Option<&str>,
+) -> Result<String, RoutineFailure> {
+    if let Some(default) = default.filter(|_| decision.default_is_safe) {
+        return Ok(default.to_string());
+    }
+
+    let remedy = match decision.flag {
+        Some(flag) => format!("Pass {flag} to provide it."),
+        None => "Run the command without --non-interactive to answer it.".to_string(),
+    };
+    Err(RoutineFailure::error(Message::new(
+        "Non-interactive".to_string(),
+        format!(
+            "A decision is required for {} but prompts are disabled. {remedy}",
+            decision.name
+        ),
+    ))
+    .with_exit_code(CliExitCode::ValidationFailure))
+}
+
 /// Generic prompt function with hints, default values, and better formatting
 pub fn prompt_user(
+    decision: Decision,
     prompt_text: &str,
     default: Option<&str>,
     hint: Option<&str>,
 ) -> Result<String, RoutineFailure> {
     use std::io::{self, Write};
 
+    if is_non_interactive() {
+        return non_interactive_answer(&decision, default);
+    }
+
     // Build the prompt with proper formatting
     let mut full_prompt = String::new();
 
@@ -139,13 +244,17 @@ pub fn prompt_user(
 /// Prompts user for password input with masked characters (shows * instead of typed chars)
 ///
 /// Uses crossterm for terminal manipulation to hide the actual password input.
-pub fn prompt_password(prompt_text: &str) -> Result<String, RoutineFailure> {
+pub fn prompt_password(decision: Decision, prompt_text: &str) -> Result<String, RoutineFailure> {
     use crossterm::{
         event::{read, Event, KeyCode, KeyModifiers},
         terminal::{disable_raw_mode, enable_raw_mode},
     };
     use std::io::{self, Write};
 
+    if is_non_interactive() {
+        return non_interactive_answer(&decision, None);
+    }
+
     // Print the prompt
     print!("{}\n> ", prompt_text);
     let _ = io::stdout().flush();
@@ -175,7 +284,8 @@ pub fn prompt_password(prompt_text: &str) -> Result<String, RoutineFailure> {
                     return Err(RoutineFailure::error(Message {
                         action: "Password".to_string(),
                         details: "Input cancelled by user".to_string(),
-                    }));
+                    })
+                    .with_exit_code(CliExitCode::Interrupted));
                 }
 
                 // Ignore control key combinations (Ctrl+V, Ctrl+A, etc.) to prevent
@@ -242,6 +352,15 @@ pub fn prompt_password(prompt_text: &str) -> Result<String, RoutineFailure> {
   Join Slack:     moose feedback --community
   Email:          hello@fiveonefour.com
 
+\x1b[1;4mEXIT CODES\x1b[0m
+  0    Success
+  1    Generic error
+  2    Plan has changes (moose plan)
+  3    Validation failure
+  4    Connectivity failure
+  5    Authentication failure
+  130  Interrupted
+
 \x1b[1;4mHOSTING\x1b[0m
   Try Fiveonefour's hosting platform built for MooseStack apps.
   Sign up for a free trial: https://fiveonefour.boreal.cloud/sign-up"
@@ -259,6 +378,30 @@ pub struct Cli {
     )]
     pub backtrace: bool,
 
+    /// Never prompt: take defaults where defined, otherwise fail naming the flag to pass
+    /// (same as MOOSE_NON_INTERACTIVE=1)
+    #[arg(long, global = true)]
+    pub non_interactive: bool,
+
+    /// Show debug logs on the console (-vv for trace), file logs keep the configured level
+    #[arg(short = 'v', global = true, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Only show errors on the console
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Override a project config value for this invocation, e.g.
+    /// --set clickhouse_config.host_port=9123 (repeatable, takes precedence over
+    /// moose.config.toml and MOOSE_* environment variables)
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE")]
+    pub config_overrides: Vec<ConfigOverride>,
+
+    /// Merge the named [profiles.<NAME>] section of moose.config.toml into the
+    /// config (same as MOOSE_PROFILE=<NAME>)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -271,6 +414,7 @@ fn determine_environment(command: &Commands) -> crate::utilities::dotenv::MooseE
         // Production commands
         Commands::Prod { .. } => MooseEnvironment::Production,
         Commands::Build { .. } => MooseEnvironment::Production,
+        Commands::Clean { prod: true, .. } => MooseEnvironment::Production,
 
         // All other commands default to development
         _ => MooseEnvironment::Development,
@@ -284,6 +428,10 @@ pub fn load_project(command: &Commands) -> Result<Project, RoutineFailure> {
             action: "Loading".to_string(),
             details: "No project found, please run `moose init` to create a project".to_string(),
         }),
+        ConfigError::Message(details) => RoutineFailure::error(Message {
+            action: "Loading".to_string(),
+            details: format!("Please validate the project's configs: {details}"),
+        }),
         _ => RoutineFailure::error(Message {
             action: "Loading".to_string(),
             details: format!("Please validate the project's configs: {e:?}"),
@@ -300,6 +448,10 @@ pub fn load_project_dev() -> Result<Project, RoutineFailure> {
             action: "Loading".to_string(),
             details: "No project found, please run `moose init` to create a project".to_string(),
         }),
+        ConfigError::Message(details) => RoutineFailure::error(Message {
+            action: "Loading".to_string(),
+            details: format!("Please validate the project's configs: {details}"),
+        }),
         _ => RoutineFailure::error(Message {
             action: "Loading".to_string(),
             details: format!("Please validate the project's configs: {e:?}"),
@@ -450,6 +602,7 @@ pub async fn top_command_handler(
             from_remote,
             language,
             custom_dockerfile,
+            vars,
         } => {
             info!(
                 "Running init command with name: {}, location: {:?}, template: {:?}, language: {:?}, custom_dockerfile: {}",
@@ -477,6 +630,7 @@ pub async fn top_command_handler(
                             ),
                         );
                         let input = prompt_user(
+                            Decision::new("the project language", Some("--language")),
                             "Select language [1] TypeScript [2] Python",
                             Some("1"),
                             None,
@@ -509,6 +663,7 @@ pub async fn top_command_handler(
                 dir_path,
                 *no_fail_already_exists,
                 *custom_dockerfile,
+                vars,
             )
             .await?;
 
@@ -519,7 +674,7 @@ pub async fn top_command_handler(
                 }
                 Some(None) => {
                     // --from-remote flag provided, but no URL given - use interactive prompts
-                    let url = prompt_user_for_remote_ch_http()?;
+                    let url = prompt_user_for_remote_ch_http(Some("--from-remote <URL>"))?;
                     db_to_dmv2(&url, dir_path).await?;
                     Some(url)
                 }
@@ -593,8 +748,9 @@ pub async fn top_command_handler(
                 }
 
                 // Also store the full URL for backwards compatibility
-                let repo = KeyringSecretRepository;
-                if let Err(e) = repo.store(name, KEY_REMOTE_CLICKHOUSE_URL, connection_string) {
+                if let Err(e) =
+                    project_secrets(name).set(REMOTE_CLICKHOUSE_URL_SECRET, connection_string)
+                {
                     display::show_message_wrapper(
                         MessageType::Warning,
                         Message::new(
@@ -617,7 +773,81 @@ pub async fn top_command_handler(
         // This command is used to check the project for errors that are not related to runtime
         // For example, it checks that the project is valid and that all the primitives are loaded
         // It is used in the build process to ensure that the project is valid while building docker images
-        Commands::Check { write_infra_map } => {
+        Commands::Check {
+            command: Some(CheckSubcommands::Preflight { json, timeout }),
+            ..
+        } => {
+            info!("Running check preflight command");
+            if *json {
+                QUIET_STDOUT.store(true, Ordering::Relaxed);
+            }
+
+            let capture_handle = crate::utilities::capture::capture_usage(
+                ActivityType::CheckCommand,
+                None,
+                &settings,
+                machine_id.clone(),
+                HashMap::new(),
+            );
+
+            let timeout = Duration::from_secs(*timeout);
+            let result = match load_project(commands) {
+                Ok(project) => preflight(&project, timeout, *json).await,
+                Err(e) => {
+                    let project_dir = std::env::current_dir().unwrap_or_default();
+                    preflight_without_project(&project_dir, *json, e)
+                }
+            };
+
+            wait_for_usage_capture(capture_handle).await;
+
+            result
+        }
+        Commands::Check {
+            command:
+                Some(CheckSubcommands::Olap {
+                    tables,
+                    severity,
+                    since,
+                    diagnostics,
+                    json,
+                }),
+            ..
+        } => {
+            info!("Running check olap command");
+            if *json {
+                QUIET_STDOUT.store(true, Ordering::Relaxed);
+            }
+
+            let project = load_project(commands)?;
+
+            let capture_handle = crate::utilities::capture::capture_usage(
+                ActivityType::CheckCommand,
+                Some(project.name()),
+                &settings,
+                machine_id.clone(),
+                HashMap::new(),
+            );
+
+            let options = DiagnosticOptions {
+                diagnostic_names: diagnostics.clone(),
+                min_severity: match severity.as_str() {
+                    "error" => Severity::Error,
+                    "warning" => Severity::Warning,
+                    _ => Severity::Info,
+                },
+                since: since.clone(),
+            };
+            let result = check_olap(&project, tables, options, *json).await;
+
+            wait_for_usage_capture(capture_handle).await;
+
+            result
+        }
+        Commands::Check {
+            write_infra_map,
+            command: None,
+        } => {
             info!(
                 "Running check command with write_infra_map: {}",
                 *write_infra_map
@@ -652,6 +882,7 @@ pub async fn top_command_handler(
                         action: "Build".to_string(),
                         details: format!("Failed to load InfrastructureMap: {e:?}"),
                     })
+                    .with_exit_code(CliExitCode::ValidationFailure)
                 })?;
 
             if *write_infra_map {
@@ -746,6 +977,10 @@ pub async fn top_command_handler(
             timestamps,
             timing,
             log_payloads,
+            port,
+            management_port,
+            auto_port,
+            yes,
         } => {
             info!("Running dev command");
             info!("Moose Version: {}", CLI_VERSION);
@@ -757,6 +992,18 @@ pub async fn top_command_handler(
             let mut project = load_project(commands)?;
             project.set_is_production_env(false);
             project.log_payloads = *log_payloads;
+            if *yes {
+                project.dev.confirm_destructive_changes = false;
+            }
+
+            routines::dev::resolve_dev_ports(
+                &mut project.http_server_config,
+                routines::dev::DevPortOptions {
+                    port: *port,
+                    management_port: *management_port,
+                    auto_port: *auto_port,
+                },
+            )?;
 
             if *log_payloads {
                 info!("Payload logging enabled");
@@ -801,8 +1048,10 @@ pub async fn top_command_handler(
                     is_moose_developer: settings.telemetry.is_moose_developer,
                     is_production: project_arc.is_production,
                     project_name: project_arc.name().to_string(),
-                    export_metrics: settings.telemetry.export_metrics,
+                    export_metrics: settings.telemetry.export_metrics
+                        && telemetry_enabled(&settings),
                     metric_endpoints: settings.metric.endpoints.clone(),
+                    metric_spill: settings.metric.spill.clone(),
                 },
                 if settings.features.metrics_v2 {
                     Some(redis_client.clone())
@@ -930,12 +1179,14 @@ pub async fn top_command_handler(
                 check_project_name(&project.name())?;
 
                 // Determine which remote source to use and generate migration
+                let remote_descriptor;
                 let result = if let Some(ref moose_url) = url {
                     // Using Moose server - no need for Redis URL (server handles state)
                     let remote = routines::RemoteSource::Moose {
                         url: moose_url,
                         token,
                     };
+                    remote_descriptor = remote.descriptor();
                     routines::remote_gen_migration(&project, remote).await
                 } else if clickhouse_url.is_some() || std::env::var(ENV_CLICKHOUSE_URL).is_ok() {
                     // Using direct ClickHouse - need to resolve URLs and validate Redis if needed
@@ -961,6 +1212,7 @@ pub async fn top_command_handler(
                         clickhouse_url: &ch_url,
                         redis_url: &resolved_redis_url,
                     };
+                    remote_descriptor = remote.descriptor();
                     routines::remote_gen_migration(&project, remote).await
                 } else {
                     return Err(RoutineFailure::error(Message {
@@ -1068,6 +1320,32 @@ pub async fn top_command_handler(
                             e,
                         )
                     })?;
+                    let summary = result.summary(
+                        remote_descriptor,
+                        current_revision(&project.project_location),
+                        &project.migration_config.ignore_operations,
+                    );
+                    std::fs::write(
+                        MIGRATION_SUMMARY_FILE,
+                        summary.to_yaml().map_err(|e| {
+                            RoutineFailure::new(
+                                Message::new(
+                                    "Error".to_string(),
+                                    "serializing plan summary.".to_string(),
+                                ),
+                                e,
+                            )
+                        })?,
+                    )
+                    .map_err(|e| {
+                        RoutineFailure::new(
+                            Message::new(
+                                "Migration".to_string(),
+                                "plan writing failed.".to_string(),
+                            ),
+                            e,
+                        )
+                    })?;
                 } else {
                     println!("Changes: \n\n{}", plan_yaml);
                 }
@@ -1084,6 +1362,7 @@ pub async fn top_command_handler(
         },
         Commands::Prod {
             start_include_dependencies,
+            wait_for_writable,
         } => {
             info!("Running prod command");
             info!("Moose Version: {}", CLI_VERSION);
@@ -1091,6 +1370,9 @@ pub async fn top_command_handler(
             let mut project = load_project(commands)?;
 
             project.set_is_production_env(true);
+            if let Some(wait) = wait_for_writable {
+                project.migration_config.wait_for_writable_secs = Some(wait.as_secs());
+            }
 
             let project_arc = Arc::new(project);
 
@@ -1122,8 +1404,10 @@ pub async fn top_command_handler(
                     is_moose_developer: settings.telemetry.is_moose_developer,
                     is_production: project_arc.is_production,
                     project_name: project_arc.name().to_string(),
-                    export_metrics: settings.telemetry.export_metrics,
+                    export_metrics: settings.telemetry.export_metrics
+                        && telemetry_enabled(&settings),
                     metric_endpoints: settings.metric.endpoints.clone(),
+                    metric_spill: settings.metric.spill.clone(),
                 },
                 if settings.features.metrics_v2 {
                     Some(redis_client.clone())
@@ -1164,6 +1448,7 @@ pub async fn top_command_handler(
             token,
             clickhouse_url,
             json,
+            dry_run_sql,
         } => {
             info!("Running plan command");
 
@@ -1185,36 +1470,49 @@ pub async fn top_command_handler(
 
             check_project_name(&project.name())?;
 
-            let result = routines::remote_plan(&project, url, token, clickhouse_url, *json).await;
+            let result =
+                routines::remote_plan(&project, url, token, clickhouse_url, *json, *dry_run_sql)
+                    .await;
 
-            result.map_err(|e| {
+            let has_changes = result.map_err(|e| {
                 RoutineFailure::error(Message {
                     action: "Plan".to_string(),
                     details: format!("Failed to plan changes: {e:?}"),
                 })
+                .with_exit_code(routines::remote_error_exit_code(&e))
             })?;
 
             wait_for_usage_capture(capture_handle).await;
 
             // When --json is used, output is already printed, so suppress success message
-            if *json {
-                Ok(RoutineSuccess::success(Message::new(
-                    "".to_string(),
-                    "".to_string(),
-                )))
+            let success = if *json {
+                RoutineSuccess::success(Message::new("".to_string(), "".to_string()))
             } else {
-                Ok(RoutineSuccess::success(Message::new(
+                RoutineSuccess::success(Message::new(
                     "Plan".to_string(),
                     "Successfully planned changes to the infrastructure".to_string(),
-                )))
+                ))
+            };
+
+            if has_changes {
+                Ok(success.with_exit_code(CliExitCode::PlanHasChanges))
+            } else {
+                Ok(success)
             }
         }
         Commands::Migrate {
             clickhouse_url,
             redis_url,
+            dry_run,
+            out,
+            wait_for_writable,
+            allow_stale,
         } => {
             info!("Running migrate command");
             let mut project = load_project(commands)?;
+            if let Some(wait) = wait_for_writable {
+                project.migration_config.wait_for_writable_secs = Some(wait.as_secs());
+            }
 
             let capture_handle = crate::utilities::capture::capture_usage(
                 ActivityType::MigrateCommand,
@@ -1242,17 +1540,42 @@ pub async fn top_command_handler(
 
             override_project_config_from_url(&mut project, &resolved_clickhouse_url)?;
 
-            routines::migrate::execute_migration(&project, resolved_redis_url.as_deref()).await?;
+            let dry_run = (*dry_run).then(|| routines::migrate::DryRun { out: out.clone() });
+            routines::migrate::execute_migration(
+                &project,
+                resolved_redis_url.as_deref(),
+                dry_run.as_ref(),
+                *allow_stale,
+            )
+            .await?;
 
             wait_for_usage_capture(capture_handle).await;
 
             Ok(RoutineSuccess::success(Message::new(
                 "Migrate".to_string(),
-                "Successfully executed migration plan".to_string(),
+                if dry_run.is_some() {
+                    "Recorded the migration plan without executing it".to_string()
+                } else {
+                    "Successfully executed migration plan".to_string()
+                },
             )))
         }
-        Commands::Clean {} => {
-            let project = load_project(commands)?;
+        Commands::Clean {
+            olap,
+            dropped,
+            streams,
+            state,
+            containers,
+            all,
+            dry_run,
+            yes,
+            prod,
+            i_know_what_im_doing,
+            keep_workflows,
+            terminate,
+        } => {
+            let mut project = load_project(commands)?;
+            project.set_is_production_env(*prod);
             let project_arc = Arc::new(project);
 
             let capture_handle = crate::utilities::capture::capture_usage(
@@ -1266,16 +1589,33 @@ pub async fn top_command_handler(
             check_project_name(&project_arc.name())?;
 
             let docker_client = DockerClient::new(&settings);
-            let _ = clean_project(&project_arc, &docker_client)?;
+            let options = CleanOptions {
+                olap: *olap,
+                dropped: *dropped,
+                streams: *streams,
+                state: *state,
+                containers: *containers,
+                all: *all,
+                dry_run: *dry_run,
+                yes: *yes,
+                i_know_what_im_doing: *i_know_what_im_doing,
+                keep_workflows: *keep_workflows,
+                terminate_workflows: *terminate,
+            };
+            let result = clean_project(&project_arc, &docker_client, &options).await;
 
             wait_for_usage_capture(capture_handle).await;
 
-            Ok(RoutineSuccess::success(Message::new(
-                "Cleaned".to_string(),
-                "Project".to_string(),
-            )))
+            result
         }
-        Commands::Logs { tail, filter } => {
+        Commands::Logs {
+            tail,
+            lines,
+            filter,
+            level,
+            since,
+            source,
+        } => {
             info!("Running logs command");
 
             let project = load_project(commands)?;
@@ -1290,37 +1630,63 @@ pub async fn top_command_handler(
 
             check_project_name(&project.name())?;
 
-            let log_file_path = chrono::Local::now()
-                .format(&settings.logger.log_file_date_format)
-                .to_string();
-
-            let log_file_path = user_directory()
-                .map_err(|e| {
-                    RoutineFailure::new(
-                        Message::new("Failed".to_string(), "to resolve log directory".to_string()),
-                        e,
-                    )
-                })?
-                .join(log_file_path)
-                .to_str()
-                .unwrap()
-                .to_string();
+            let log_dir = user_directory().map_err(|e| {
+                RoutineFailure::new(
+                    Message::new("Failed".to_string(), "to resolve log directory".to_string()),
+                    e,
+                )
+            })?;
+            let log_file_path = log_dir.join(active_log_file_name(
+                &settings.logger.log_file_date_format,
+                &settings.logger.rotation,
+                chrono::Local::now(),
+            ));
+
+            let query_error = |e: LogQueryError| {
+                RoutineFailure::new(Message::new("Logs".to_string(), e.to_string()), e)
+            };
+            let query = LogQuery {
+                level: level
+                    .as_deref()
+                    .map(str::parse::<LogLevel>)
+                    .transpose()
+                    .map_err(query_error)?,
+                pattern: filter
+                    .as_deref()
+                    .map(LogPattern::parse)
+                    .transpose()
+                    .map_err(query_error)?,
+                since: since
+                    .as_deref()
+                    .map(|s| parse_since(s, chrono::Utc::now()))
+                    .transpose()
+                    .map_err(query_error)?,
+                source: source.clone(),
+            };
 
-            let filter_value = filter.clone().unwrap_or_else(|| "".to_string());
+            // Rotated files only matter when asking for older entries
+            let rotated = query
+                .since
+                .map(|since| log_files_since(&log_dir, &log_file_path, since))
+                .unwrap_or_default();
 
             let result = if *tail {
-                follow_logs(log_file_path, filter_value)
+                follow_logs(log_file_path, &rotated, &query, Some(*lines))
             } else {
-                show_logs(log_file_path, filter_value)
+                show_logs(log_file_path, &rotated, &query, Some(*lines))
             };
 
             wait_for_usage_capture(capture_handle).await;
 
             result
         }
-        Commands::Ps {} => {
+        Commands::Ps { json, all } => {
             info!("Running ps command");
 
+            if *json {
+                QUIET_STDOUT.store(true, Ordering::Relaxed);
+            }
+
             let project = load_project(commands)?;
             let project_arc = Arc::new(project);
 
@@ -1332,7 +1698,8 @@ pub async fn top_command_handler(
                 HashMap::new(),
             );
 
-            let result = show_processes(project_arc);
+            let docker_client = DockerClient::new(&settings);
+            let result = show_processes(project_arc, &docker_client, *json, *all);
 
             wait_for_usage_capture(capture_handle).await;
 
@@ -1341,6 +1708,11 @@ pub async fn top_command_handler(
         Commands::Ls { _type, name, json } => {
             info!("Running ls command");
 
+            // Keep stdout clean so `moose ls --json | jq` only ever sees JSON
+            if *json {
+                QUIET_STDOUT.store(true, Ordering::Relaxed);
+            }
+
             let project = load_project(commands)?;
             let project_arc = Arc::new(project);
 
@@ -1362,11 +1734,25 @@ pub async fn top_command_handler(
             name,
             limit,
             file,
+            order_by,
+            desc,
+            where_clause,
+            format,
+            follow,
+            interval,
             table: _,
             stream,
         } => {
             info!("Running peek command");
 
+            let format: PeekFormat = format.parse().map_err(|e: PeekQueryError| {
+                RoutineFailure::error(Message::new("Peek".to_string(), e.to_string()))
+            })?;
+            // Keep stdout clean for machine-readable output
+            if file.is_none() && format != PeekFormat::Table {
+                QUIET_STDOUT.store(true, Ordering::Relaxed);
+            }
+
             let project = load_project(commands)?;
             let project_arc = Arc::new(project);
 
@@ -1386,13 +1772,24 @@ pub async fn top_command_handler(
                 false
             };
 
-            let result = peek(project_arc, name, *limit, file.clone(), is_stream).await;
+            let options = PeekOptions {
+                limit: *limit,
+                order_by: order_by.clone(),
+                desc: *desc,
+                where_clause: where_clause.clone(),
+                format,
+                follow: follow.then_some(Duration::from_secs(*interval)),
+            };
+            let result = peek(project_arc, name, options, file.clone(), is_stream).await;
 
             wait_for_usage_capture(capture_handle).await;
 
             result
         }
-        Commands::Metrics {} => {
+        Commands::Metrics {
+            management_port,
+            token,
+        } => {
             let capture_handle = crate::utilities::capture::capture_usage(
                 ActivityType::MetricsCommand,
                 None,
@@ -1401,7 +1798,23 @@ pub async fn top_command_handler(
                 HashMap::new(),
             );
 
-            let result = run_console().await;
+            let project = load_project(commands).ok();
+            let management_port = management_port.unwrap_or_else(|| {
+                project
+                    .as_ref()
+                    .map(|project| project.http_server_config.management_port)
+                    .unwrap_or_else(default_management_port)
+            });
+            let admin = AdminClient {
+                http_port: project
+                    .as_ref()
+                    .map(|project| project.http_server_config.port)
+                    .unwrap_or_else(|| local_webserver::LocalWebserverConfig::default().port),
+                token: token
+                    .clone()
+                    .or_else(|| std::env::var("MOOSE_ADMIN_TOKEN").ok()),
+            };
+            let result = run_console(management_port, admin).await;
 
             wait_for_usage_capture(capture_handle).await;
 
@@ -1439,8 +1852,16 @@ pub async fn top_command_handler(
             );
 
             let result = match &workflow_args.command {
-                Some(WorkflowCommands::Run { name, input }) => {
-                    run_workflow(&project, name, input.clone()).await
+                Some(WorkflowCommands::Run {
+                    name,
+                    input,
+                    follow,
+                    json,
+                }) => {
+                    if *json {
+                        QUIET_STDOUT.store(true, Ordering::Relaxed);
+                    }
+                    run_workflow(&project, name, input.clone(), *follow, *json).await
                 }
                 Some(WorkflowCommands::List { json }) => {
                     ls(&project, Some("workflows"), None, *json).await
@@ -1481,7 +1902,11 @@ pub async fn top_command_handler(
 
             let template_cmd = template_args.command.as_ref().unwrap();
             match template_cmd {
-                TemplateSubCommands::List {} => {
+                TemplateSubCommands::List { language, json } => {
+                    if *json {
+                        QUIET_STDOUT.store(true, Ordering::Relaxed);
+                    }
+
                     let capture_handle = crate::utilities::capture::capture_usage(
                         ActivityType::TemplateListCommand,
                         None,
@@ -1490,7 +1915,22 @@ pub async fn top_command_handler(
                         HashMap::new(),
                     );
 
-                    let result = list_available_templates(CLI_VERSION).await;
+                    let result = list_available_templates(CLI_VERSION, *language, *json).await;
+
+                    wait_for_usage_capture(capture_handle).await;
+
+                    result
+                }
+                TemplateSubCommands::Show { name } => {
+                    let capture_handle = crate::utilities::capture::capture_usage(
+                        ActivityType::TemplateShowCommand,
+                        None,
+                        &settings,
+                        machine_id.clone(),
+                        HashMap::new(),
+                    );
+
+                    let result = show_template(name, CLI_VERSION).await;
 
                     wait_for_usage_capture(capture_handle).await;
 
@@ -1555,8 +1995,9 @@ pub async fn top_command_handler(
                 }
                 None => {
                     // Try keychain URL first (from moose init --from-remote)
-                    let repo = KeyringSecretRepository;
-                    match repo.get(&project.name(), KEY_REMOTE_CLICKHOUSE_URL) {
+                    match project_secrets(&project.name())
+                        .get_or_migrate(REMOTE_CLICKHOUSE_URL_SECRET, KEY_REMOTE_CLICKHOUSE_URL)
+                    {
                         Ok(Some(url)) => {
                             db_pull(&url, &project, file_path.as_deref())
                                 .await
@@ -1608,6 +2049,208 @@ pub async fn top_command_handler(
                 "External models refreshed".to_string(),
             )))
         }
+        Commands::Db(DbArgs {
+            command:
+                DbCommands::Diff {
+                    from,
+                    to,
+                    database,
+                    json,
+                    show_sql,
+                    ignore_operations,
+                },
+        }) => {
+            info!("Running db diff command");
+            if *json {
+                QUIET_STDOUT.store(true, Ordering::Relaxed);
+            }
+
+            // Comparing two databases needs no project, so fall back to one rooted
+            // at the current directory when run outside of a Moose project
+            let project = match load_project(commands) {
+                Ok(project) => project,
+                Err(_) => Project::new(
+                    &std::env::current_dir().unwrap_or_default(),
+                    "db-diff".to_string(),
+                    SupportedLanguages::Typescript,
+                ),
+            };
+
+            let capture_handle = crate::utilities::capture::capture_usage(
+                ActivityType::DbDiffCommand,
+                Some(project.name()),
+                &settings,
+                machine_id.clone(),
+                HashMap::new(),
+            );
+
+            let result = db_diff(
+                &project,
+                DbDiffOptions {
+                    from_url: from,
+                    to_url: to,
+                    database: database.as_deref(),
+                    json: *json,
+                    show_sql: *show_sql,
+                    ignore_operations: ignore_operations
+                        .as_deref()
+                        .unwrap_or(&project.migration_config.ignore_operations),
+                },
+            )
+            .await;
+
+            wait_for_usage_capture(capture_handle).await;
+
+            result
+        }
+        Commands::Table(TableArgs {
+            command: TableCommands::Describe { name, live, json },
+        }) => {
+            info!("Running table describe command");
+            if *json {
+                QUIET_STDOUT.store(true, Ordering::Relaxed);
+            }
+
+            let project = load_project(commands)?;
+
+            let capture_handle = crate::utilities::capture::capture_usage(
+                ActivityType::TableDescribeCommand,
+                Some(project.name()),
+                &settings,
+                machine_id.clone(),
+                HashMap::new(),
+            );
+
+            let result = describe_table(&project, name, *live, *json).await;
+
+            wait_for_usage_capture(capture_handle).await;
+
+            result
+        }
+        Commands::Config(ConfigArgs {
+            command: ConfigCommands::Show {},
+        }) => {
+            info!("Running config show command");
+
+            let project = load_project(commands)?;
+
+            let capture_handle = crate::utilities::capture::capture_usage(
+                ActivityType::ConfigShowCommand,
+                Some(project.name()),
+                &settings,
+                machine_id.clone(),
+                HashMap::new(),
+            );
+
+            let result = show_config(&project);
+
+            wait_for_usage_capture(capture_handle).await;
+
+            result
+        }
+        Commands::Config(ConfigArgs {
+            command: ConfigCommands::Validate {},
+        }) => {
+            info!("Running config validate command");
+
+            // Unknown keys are part of the report instead of load warnings
+            crate::project::config_validation::disable_unknown_key_warnings();
+            let project = load_project(commands)?;
+
+            let capture_handle = crate::utilities::capture::capture_usage(
+                ActivityType::ConfigValidateCommand,
+                Some(project.name()),
+                &settings,
+                machine_id.clone(),
+                HashMap::new(),
+            );
+
+            let result = validate_config(&project).await;
+
+            wait_for_usage_capture(capture_handle).await;
+
+            result
+        }
+        Commands::Config(ConfigArgs {
+            command: ConfigCommands::Telemetry { command },
+        }) => {
+            info!("Running config telemetry command");
+
+            let project_name = load_project(commands).ok().map(|project| project.name());
+
+            // Opting out is not reported
+            let capture_handle = if matches!(command, Some(TelemetryCommands::Off)) {
+                None
+            } else {
+                crate::utilities::capture::capture_usage(
+                    ActivityType::ConfigTelemetryCommand,
+                    project_name.clone(),
+                    &settings,
+                    machine_id.clone(),
+                    HashMap::new(),
+                )
+            };
+
+            let result = match command {
+                Some(TelemetryCommands::On) => routines::telemetry::set_telemetry(true),
+                Some(TelemetryCommands::Off) => routines::telemetry::set_telemetry(false),
+                Some(TelemetryCommands::Status) | None => {
+                    routines::telemetry::show_telemetry_status(&settings, project_name)
+                }
+            };
+
+            wait_for_usage_capture(capture_handle).await;
+
+            result
+        }
+        Commands::Secret(SecretArgs { command }) => {
+            info!("Running secret command");
+
+            let project = load_project(commands)?;
+
+            let capture_handle = crate::utilities::capture::capture_usage(
+                ActivityType::SecretCommand,
+                Some(project.name()),
+                &settings,
+                machine_id.clone(),
+                HashMap::new(),
+            );
+
+            let secrets = project_secrets(&project.name());
+            let result = match command {
+                SecretCommands::Set { name } => set_secret(&secrets, name),
+                SecretCommands::Get { name } => get_secret(&secrets, name),
+                SecretCommands::List {} => list_secrets(&secrets),
+                SecretCommands::Delete { name } => delete_secret(&secrets, name),
+            };
+
+            wait_for_usage_capture(capture_handle).await;
+
+            result
+        }
+        Commands::Openapi { out, format } => {
+            info!("Running openapi command");
+
+            let format: OpenAPIFormat = format.parse().map_err(|e: OpenAPIError| {
+                RoutineFailure::error(Message::new("OpenAPI".to_string(), e.to_string()))
+            })?;
+
+            let project = load_project(commands)?;
+
+            let capture_handle = crate::utilities::capture::capture_usage(
+                ActivityType::OpenapiCommand,
+                Some(project.name()),
+                &settings,
+                machine_id.clone(),
+                HashMap::from([("format".to_string(), format!("{format:?}"))]),
+            );
+
+            let result = generate_openapi(&project, out.as_deref(), format).await;
+
+            wait_for_usage_capture(capture_handle).await;
+
+            result
+        }
         Commands::Refresh { url, token } => {
             info!("Running refresh command");
 
@@ -1622,7 +2265,9 @@ pub async fn top_command_handler(
             );
 
             let output = remote_refresh(&project, url, token).await.map_err(|e| {
+                let exit_code = routines::remote_error_exit_code(&e);
                 RoutineFailure::new(Message::new("failed".to_string(), "".to_string()), e)
+                    .with_exit_code(exit_code)
             });
 
             wait_for_usage_capture(capture_handle).await;
@@ -1634,9 +2279,23 @@ pub async fn top_command_handler(
 
             seed_data::handle_seed_command(seed_args, &project).await
         }
-        Commands::Truncate { tables, all, rows } => {
+        Commands::Truncate {
+            tables,
+            all,
+            rows,
+            cascade,
+            yes,
+        } => {
             let project = load_project(commands)?;
-            routines::truncate_table::truncate_tables(&project, tables.clone(), *all, *rows).await
+            routines::truncate_table::truncate_tables(
+                &project,
+                tables.clone(),
+                *all,
+                *rows,
+                *cascade,
+                *yes,
+            )
+            .await
         }
         Commands::Kafka(KafkaArgs { command }) => match command {
             KafkaCommands::Pull {
@@ -1892,4 +2551,165 @@ mod tests {
         assert!(success_message.contains("- typescript (typescript)"));
         assert!(success_message.contains("- python (python)"));
     }
+
+    #[test]
+    fn test_non_interactive_takes_the_default() {
+        let decision = Decision::new("the project language", Some("--language"));
+
+        let answer = non_interactive_answer(&decision, Some("1")).unwrap();
+        assert_eq!(answer, "1");
+    }
+
+    #[test]
+    fn test_non_interactive_without_default_names_decision_and_flag() {
+        let decision = Decision::new("the remote ClickHouse URL", Some("--from-remote <URL>"));
+
+        let failure = non_interactive_answer(&decision, None).unwrap_err();
+        assert_eq!(failure.exit_code, CliExitCode::ValidationFailure);
+        assert_eq!(
+            failure.message.details,
+            "A decision is required for the remote ClickHouse URL but prompts are disabled. \
+             Pass --from-remote <URL> to provide it."
+        );
+    }
+
+    #[test]
+    fn test_non_interactive_never_takes_a_confirmation_default() {
+        let decision = Decision::confirmation("removing project resources", "--yes");
+
+        let failure = non_interactive_answer(&decision, Some("n")).unwrap_err();
+        assert!(failure
+            .message
+            .details
+            .contains("removing project resources"));
+        assert!(failure.message.details.contains("Pass --yes"));
+    }
+
+    #[test]
+    fn test_non_interactive_without_flag_suggests_running_interactively() {
+        let decision = Decision::new("the remote ClickHouse password", None);
+
+        let failure = non_interactive_answer(&decision, None).unwrap_err();
+        assert!(failure
+            .message
+            .details
+            .ends_with("Run the command without --non-interactive to answer it."));
+    }
+
+    #[test]
+    fn test_set_is_repeatable_and_global() {
+        let cli = Cli::parse_from([
+            "moose",
+            "--set",
+            "clickhouse_config.host_port=9123",
+            "ls",
+            "--set",
+            "features.workflows=true",
+        ]);
+        let keys: Vec<&str> = cli
+            .config_overrides
+            .iter()
+            .map(|o| o.key.as_str())
+            .collect();
+        assert_eq!(
+            keys,
+            vec!["clickhouse_config.host_port", "features.workflows"]
+        );
+
+        assert!(Cli::try_parse_from(["moose", "--set", "host_port", "ls"]).is_err());
+    }
+
+    #[test]
+    fn test_verbosity_flags() {
+        let cli = Cli::parse_from(["moose", "ls", "-vv"]);
+        assert_eq!(cli.verbose, 2);
+        assert!(!cli.quiet);
+
+        let cli = Cli::parse_from(["moose", "--quiet", "ls"]);
+        assert!(cli.quiet);
+
+        let err = Cli::try_parse_from(["moose", "-q", "-vv", "ls"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn test_non_interactive_is_a_global_flag() {
+        let cli = Cli::parse_from(["moose", "clean", "--non-interactive"]);
+        assert!(cli.non_interactive);
+    }
+
+    #[test]
+    fn test_check_olap_flags() {
+        let cli = Cli::parse_from([
+            "moose",
+            "check",
+            "olap",
+            "--table",
+            "events",
+            "--table",
+            "users",
+            "--since",
+            "-1h",
+            "--severity",
+            "warning",
+        ]);
+        match cli.command {
+            Commands::Check {
+                command:
+                    Some(CheckSubcommands::Olap {
+                        tables,
+                        severity,
+                        since,
+                        diagnostics,
+                        json,
+                    }),
+                ..
+            } => {
+                assert_eq!(tables, vec!["events", "users"]);
+                assert_eq!(severity, "warning");
+                assert_eq!(since.as_deref(), Some("-1h"));
+                assert!(diagnostics.is_empty());
+                assert!(!json);
+            }
+            _ => panic!("expected moose check olap"),
+        }
+
+        assert!(Cli::try_parse_from(["moose", "check", "olap", "--severity", "all"]).is_err());
+    }
+
+    #[test]
+    fn test_config_telemetry_defaults_to_status() {
+        let cli = Cli::parse_from(["moose", "config", "telemetry"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Config(ConfigArgs {
+                command: ConfigCommands::Telemetry { command: None }
+            })
+        ));
+
+        let cli = Cli::parse_from(["moose", "config", "telemetry", "off"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Config(ConfigArgs {
+                command: ConfigCommands::Telemetry {
+                    command: Some(TelemetryCommands::Off)
+                }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_profile_is_a_global_flag() {
+        let cli = Cli::parse_from(["moose", "config", "show", "--profile", "staging"]);
+        assert_eq!(cli.profile.as_deref(), Some("staging"));
+        assert!(matches!(
+            cli.command,
+            Commands::Config(ConfigArgs {
+                command: ConfigCommands::Show {}
+            })
+        ));
+
+        let cli = Cli::parse_from(["moose", "ls"]);
+        assert_eq!(cli.profile, None);
+    }
 }
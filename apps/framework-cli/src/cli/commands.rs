@@ -2,9 +2,14 @@
 //! A module for all the commands that can be run from the CLI
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use clap::{Args, Subcommand};
 
+use crate::cli::routines::templates::TemplateVar;
+use crate::framework::languages::SupportedLanguages;
+use crate::infrastructure::olap::clickhouse::IgnorableOperation;
+
 #[derive(Subcommand)]
 pub enum Commands {
     // Initializes the developer environment with all the necessary directories including temporary ones for data storage
@@ -45,6 +50,10 @@ pub enum Commands {
         /// Generate a custom Dockerfile at project root for customization
         #[arg(long)]
         custom_dockerfile: bool,
+
+        /// Value for a template variable, repeatable. Variables without a value are prompted for
+        #[arg(long = "var", value_name = "NAME=VALUE")]
+        vars: Vec<TemplateVar>,
     },
     /// Builds your moose project
     #[command(visible_alias = "b")]
@@ -64,6 +73,9 @@ pub enum Commands {
     Check {
         #[arg(long, default_value = "false")]
         write_infra_map: bool,
+
+        #[command(subcommand)]
+        command: Option<CheckSubcommands>,
     },
     /// Displays the changes that will be applied to the infrastructure during the next deployment
     /// to production, considering the current state of the project
@@ -85,6 +97,10 @@ pub enum Commands {
         /// Output plan as JSON for programmatic use
         #[arg(long)]
         json: bool,
+
+        /// Also print the SQL statements of each change, without running them
+        #[arg(long, conflicts_with = "json")]
+        dry_run_sql: bool,
     },
 
     /// Execute a migration plan against a remote ClickHouse database
@@ -99,12 +115,29 @@ pub enum Commands {
         /// Required when state_config.storage = "redis"
         #[arg(long)]
         redis_url: Option<String>,
+
+        /// Record the SQL statements of the migration plan instead of executing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// File the SQL statements of a dry run are written to (defaults to stdout)
+        #[arg(long, requires = "dry_run")]
+        out: Option<PathBuf>,
+
+        /// Wait up to this long (e.g. `5m`) for a readonly ClickHouse server to accept DDL
+        /// instead of failing right away
+        #[arg(long, value_parser = humantime::parse_duration)]
+        wait_for_writable: Option<Duration>,
+
+        /// Run the plan even though the remote changed since it was generated
+        #[arg(long)]
+        allow_stale: bool,
     },
 
     /// View some data from a table or stream
     #[command(visible_alias = "pk")]
     Peek {
-        /// Name of the table or stream to peek
+        /// Name of the table, view, materialized view or stream to peek
         name: String,
         /// Limit the number of rows to view
         #[arg(short, long, default_value = "5")]
@@ -113,6 +146,30 @@ pub enum Commands {
         #[arg(short, long)]
         file: Option<PathBuf>,
 
+        /// Column to order table rows by
+        #[arg(long, value_name = "COLUMN", conflicts_with = "stream")]
+        order_by: Option<String>,
+
+        /// Sort descending when used with --order-by
+        #[arg(long, requires = "order_by")]
+        desc: bool,
+
+        /// Only return table rows matching this predicate (e.g. "status = 'failed'")
+        #[arg(long = "where", value_name = "PREDICATE", conflicts_with = "stream")]
+        where_clause: Option<String>,
+
+        /// Output format
+        #[arg(long, default_value = "json", value_parser = ["json", "csv", "table"])]
+        format: String,
+
+        /// Keep polling the target table of a materialized view, printing rows with new ORDER BY keys
+        #[arg(long, conflicts_with_all = ["stream", "file"])]
+        follow: bool,
+
+        /// Seconds between two polls with --follow
+        #[arg(long, value_name = "SECONDS", default_value = "5", requires = "follow")]
+        interval: u64,
+
         /// View data from a table
         #[arg(short = 't', long = "table", group = "resource_type")]
         table: bool,
@@ -143,6 +200,22 @@ pub enum Commands {
         /// Log payloads at ingest API and streaming functions for debugging
         #[arg(long)]
         log_payloads: bool,
+
+        /// Port for the API server, overriding http_server_config.port for this run
+        #[arg(long)]
+        port: Option<u16>,
+
+        /// Port for the management server, overriding http_server_config.management_port for this run
+        #[arg(long)]
+        management_port: Option<u16>,
+
+        /// Automatically use the next free port when a configured port is already in use
+        #[arg(long)]
+        auto_port: bool,
+
+        /// Apply database changes that lose data without asking, overriding dev.confirm_destructive_changes
+        #[arg(short = 'y', long)]
+        yes: bool,
     },
     /// Start a remote environment for use in cloud deployments
     #[command(visible_alias = "p")]
@@ -150,13 +223,66 @@ pub enum Commands {
         /// Include and manage dependencies (ClickHouse, Redpanda, etc.) using Docker containers
         #[arg(long)]
         start_include_dependencies: bool,
+
+        /// Wait up to this long (e.g. `5m`) for a readonly ClickHouse server to accept DDL
+        /// instead of failing right away
+        #[arg(long, value_parser = humantime::parse_duration)]
+        wait_for_writable: Option<Duration>,
     },
     /// Generates helpers for your data models (i.e. sdk, api tokens)
     #[command(visible_alias = "g")]
     Generate(GenerateArgs),
     /// Clears all temporary data and stops development infrastructure
     #[command(visible_alias = "cl")]
-    Clean {},
+    Clean {
+        /// Truncate the tables in the local ClickHouse database
+        #[arg(long)]
+        olap: bool,
+
+        /// Drop the tables renamed by the `rename` drop strategy once their retention has passed
+        #[arg(long)]
+        dropped: bool,
+
+        /// Delete the project's Kafka/Redpanda topics
+        #[arg(long)]
+        streams: bool,
+
+        /// Clear the stored infrastructure map from state storage
+        #[arg(long)]
+        state: bool,
+
+        /// Stop and remove the project's Docker containers (the default when no other flag is set)
+        #[arg(long)]
+        containers: bool,
+
+        /// Clean OLAP data, streams, state and containers
+        #[arg(long, conflicts_with_all = ["olap", "dropped", "streams", "state", "containers"])]
+        all: bool,
+
+        /// List what would be removed without removing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip the confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        /// Load the project with its production configuration
+        #[arg(long)]
+        prod: bool,
+
+        /// Allow --olap and --state to delete production data
+        #[arg(long, requires = "prod")]
+        i_know_what_im_doing: bool,
+
+        /// Leave the project's running workflows alone instead of cancelling them
+        #[arg(long)]
+        keep_workflows: bool,
+
+        /// Terminate the running workflows instead of cancelling them
+        #[arg(long, conflicts_with = "keep_workflows")]
+        terminate: bool,
+    },
     /// View Moose logs
     #[command(visible_alias = "l")]
     Logs {
@@ -164,12 +290,36 @@ pub enum Commands {
         #[arg(short, long)]
         tail: bool,
 
-        /// Filter logs by a specific string
+        /// Number of most recent matching lines to show
+        #[arg(short = 'n', long, value_name = "N", default_value = "10")]
+        lines: usize,
+
+        /// Filter logs by a substring, or a regex when prefixed with `re:` (e.g. `re:Code: \d+`)
         #[arg(short, long)]
         filter: Option<String>,
+
+        /// Only show entries at or above this level (error, warn, info, debug)
+        #[arg(long)]
+        level: Option<String>,
+
+        /// Only show entries newer than a duration (e.g. 1h, 30m) or an RFC 3339 timestamp
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only show entries from modules whose path contains this value (e.g. olap::clickhouse)
+        #[arg(long, value_name = "MODULE")]
+        source: Option<String>,
     },
     /// View Moose processes
-    Ps {},
+    Ps {
+        /// Output processes and containers as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Include stopped containers from this project
+        #[arg(long)]
+        all: bool,
+    },
     /// View Moose primitives & infrastructure
     Ls {
         /// Filter by infrastructure type (tables, streams, ingestion, sql_resource, consumption, workflows, web_apps)
@@ -187,7 +337,15 @@ pub enum Commands {
 
     /// Opens metrics console for viewing live metrics from your moose app
     #[command(visible_alias = "m")]
-    Metrics {},
+    Metrics {
+        /// Management port of the running dev server (default: from moose.config.toml)
+        #[arg(long)]
+        management_port: Option<u16>,
+
+        /// Admin API token, needed to change the dev server's log level with (L) (default: MOOSE_ADMIN_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+    },
     /// Manage data processing workflows
     #[command(visible_alias = "w")]
     Workflow(WorkflowArgs),
@@ -201,6 +359,22 @@ pub enum Commands {
     Component(ComponentCommands),
     /// Manage database schema import
     Db(DbArgs),
+    /// Inspect individual tables
+    Table(TableArgs),
+    /// Inspect the project configuration
+    Config(ConfigArgs),
+    /// Manage named secrets of the project, referenced from config as secret:<name>
+    Secret(SecretArgs),
+    /// Writes the OpenAPI spec of the project's ingest and analytics APIs, without starting dev mode
+    Openapi {
+        /// Where to write the spec (default: openapi.yaml or openapi.json in the current directory)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+
+        /// Output format
+        #[arg(long, default_value = "yaml", value_parser = ["json", "yaml"])]
+        format: String,
+    },
     /// Integrate matching tables from a remote Moose instance into the local project
     #[command(visible_alias = "r")]
     Refresh {
@@ -232,6 +406,14 @@ pub enum Commands {
         /// Number of most recent rows to delete per table. Omit to delete all rows.
         #[arg(long)]
         rows: Option<u64>,
+
+        /// Also truncate the tables that materialized views feed from the given tables
+        #[arg(long, conflicts_with_all = ["all", "rows"])]
+        cascade: bool,
+
+        /// Skip the confirmation prompt of --cascade
+        #[arg(short = 'y', long, requires = "cascade")]
+        yes: bool,
     },
     /// Manage Kafka-related operations
     #[command(visible_alias = "k")]
@@ -383,6 +565,14 @@ pub enum WorkflowCommands {
         /// JSON input parameters for the workflow
         #[arg(short, long)]
         input: Option<String>,
+
+        /// Wait for the workflow to finish, printing each status change
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
     },
     /// Resume a workflow from a specific task
     #[command(visible_alias = "rs")]
@@ -416,8 +606,8 @@ pub enum WorkflowCommands {
         #[arg(long)]
         json: bool,
     },
-    /// Terminate a workflow
-    #[command(hide = true)]
+    /// Terminate a workflow immediately, without running cleanup
+    #[command(visible_alias = "t")]
     Terminate {
         /// Name of the workflow to terminate
         name: String,
@@ -467,11 +657,60 @@ pub struct TemplateCommands {
     pub command: Option<TemplateSubCommands>,
 }
 
+#[derive(Debug, Subcommand)]
+pub enum CheckSubcommands {
+    /// Validate the project config and connectivity to every enabled service
+    Preflight {
+        /// Output the results as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Seconds to wait for each service before reporting it as failed
+        #[arg(long, default_value = "10")]
+        timeout: u64,
+    },
+    /// Run the ClickHouse diagnostics on the deployed tables, failing on any error issue
+    Olap {
+        /// Only diagnose this table, can be repeated (default: every table)
+        #[arg(long = "table", value_name = "NAME")]
+        tables: Vec<String>,
+
+        /// Minimum severity of the issues to report
+        #[arg(long, default_value = "info", value_parser = ["error", "warning", "info"])]
+        severity: String,
+
+        /// Only consider events since then (e.g. -1h, -30m, -1d) or an RFC 3339 timestamp
+        #[arg(long, allow_hyphen_values = true)]
+        since: Option<String>,
+
+        /// Only run this diagnostic, can be repeated (e.g. MutationDiagnostic)
+        #[arg(long = "diagnostic", value_name = "NAME")]
+        diagnostics: Vec<String>,
+
+        /// Output the diagnostic results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 pub enum TemplateSubCommands {
     /// List available templates
     #[command(visible_alias = "l")]
-    List {},
+    List {
+        /// Only list templates for this language
+        #[arg(long, value_enum)]
+        language: Option<SupportedLanguages>,
+
+        /// Output the template list as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show a template's description, file tree and post-init instructions
+    Show {
+        /// Name of the template
+        name: String,
+    },
 }
 
 #[derive(Debug, Args)]
@@ -514,12 +753,120 @@ pub enum SeedSubcommands {
         #[arg(long)]
         order_by: Option<String>,
         /// Only seed a specific table (optional)
-        #[arg(long, value_name = "TABLE_NAME")]
+        #[arg(long, value_name = "TABLE_NAME", conflicts_with = "tables")]
         table: Option<String>,
+        /// Only seed tables matching these glob patterns (comma-separated), e.g. `--tables 'events_*,users'`
+        #[arg(long, value_name = "PATTERN", value_delimiter = ',', num_args = 1..)]
+        tables: Vec<String>,
+        /// Predicate applied to the remote SELECT, e.g. `--where "timestamp > now() - INTERVAL 7 DAY"`.
+        /// Combined with any per-table seedFilter.where using AND
+        #[arg(long = "where", value_name = "PREDICATE")]
+        where_clause: Option<String>,
         /// Report row counts after seeding. Counts shown for default database only (use --report=false to skip)
         #[arg(long, default_value = "true", action = clap::ArgAction::Set)]
         report: bool,
     },
+    /// Seed a ClickHouse table from a local CSV or Parquet file
+    #[command(visible_alias = "d")]
+    Data {
+        /// Path to the file to load (.csv or .parquet)
+        #[arg(long, value_name = "PATH")]
+        file: PathBuf,
+        /// Name of the table to insert into
+        #[arg(long, value_name = "TABLE_NAME")]
+        table: String,
+        /// Truncate the table before inserting
+        #[arg(long, default_value = "false")]
+        truncate_first: bool,
+        /// Stop after inserting this many rows
+        #[arg(long, value_name = "LIMIT")]
+        limit: Option<u64>,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(arg_required_else_help = true)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommands {
+    /// Print the effective configuration: moose.config.toml with the selected --profile,
+    /// MOOSE_* environment variables and --set overrides applied. Secrets are redacted
+    Show {},
+    /// Check moose.config.toml for unknown keys and inconsistent settings, including
+    /// clusters used by tables that are not configured. Exits non-zero on any issue
+    Validate {},
+    /// Turn anonymous usage telemetry on or off, or show what would be sent and where
+    Telemetry {
+        #[command(subcommand)]
+        command: Option<TelemetryCommands>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TelemetryCommands {
+    /// Send anonymous usage telemetry
+    On,
+    /// Stop sending anonymous usage telemetry
+    Off,
+    /// Show whether telemetry is sent, what an event contains and where it goes (default)
+    Status,
+}
+
+#[derive(Debug, Args)]
+#[command(arg_required_else_help = true)]
+pub struct SecretArgs {
+    #[command(subcommand)]
+    pub command: SecretCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SecretCommands {
+    /// Store a secret. The value is read from stdin when it is piped, otherwise prompted for
+    Set {
+        /// Secret name (letters, digits, '_', '-' and '.')
+        name: String,
+    },
+    /// Print the value of a secret
+    Get {
+        /// Secret name
+        name: String,
+    },
+    /// List the names of the stored secrets
+    List {},
+    /// Delete a secret
+    Delete {
+        /// Secret name
+        name: String,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(arg_required_else_help = true)]
+pub struct TableArgs {
+    #[command(subcommand)]
+    pub command: TableCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TableCommands {
+    /// Show columns, engine, keys, settings, indexes and lineage of a table
+    #[command(visible_alias = "d")]
+    Describe {
+        /// Table name, optionally qualified as db.table
+        name: String,
+
+        /// Describe the table as it exists in ClickHouse and highlight differences from the code
+        #[arg(long, default_value = "false")]
+        live: bool,
+
+        /// Output results in JSON format
+        #[arg(long, default_value = "false")]
+        json: bool,
+    },
 }
 
 #[derive(Debug, Args)]
@@ -541,6 +888,29 @@ pub enum DbCommands {
         #[arg(long)]
         file_path: Option<String>,
     },
+    /// Compare the schemas of two ClickHouse databases, independent of Moose state
+    #[command(visible_alias = "d")]
+    Diff {
+        /// ClickHouse connection URL of the database to compare from (e.g., staging)
+        #[arg(long, value_name = "CLICKHOUSE_URL")]
+        from: String,
+        /// ClickHouse connection URL of the database to compare to (e.g., production)
+        #[arg(long, value_name = "CLICKHOUSE_URL")]
+        to: String,
+        /// Database to compare on both instances, overriding the database in each URL
+        #[arg(long)]
+        database: Option<String>,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+        /// Print the SQL that would bring the `from` database in line with `to`
+        #[arg(long)]
+        show_sql: bool,
+        /// Differences to leave out, e.g. ModifyTableTtl,ModifyColumnTtl. Defaults to
+        /// `ignore_operations` of the migration config when run in a Moose project
+        #[arg(long, value_name = "OPERATION", value_delimiter = ',', num_args = 1..)]
+        ignore_operations: Option<Vec<IgnorableOperation>>,
+    },
 }
 
 #[derive(Debug, Args)]
@@ -31,9 +31,10 @@ use super::terminal::{write_styled_line, StyledText, ACTION_WIDTH};
 use crate::framework::core::{
     infrastructure::table::{ColumnType, EnumValue},
     infrastructure_map::{
-        ApiChange, Change, FilteredChange, OlapChange, ProcessChange, StreamingChange, TableChange,
-        WorkflowChange,
+        ApiChange, Change, FilteredChange, InfrastructureMap, OlapChange, ProcessChange,
+        StreamingChange, TableChange, WorkflowChange,
     },
+    partial_infrastructure_map::AppliedDatabaseDefaults,
     plan::InfraPlan,
 };
 use crate::utilities::constants::{NO_ANSI, QUIET_STDOUT, SHOW_TIMESTAMPS};
@@ -206,10 +207,12 @@ fn format_column_constraints(primary_key: bool, unique: bool) -> Option<String>
     }
 }
 
-/// Formats a table for display with columns shown vertically
+/// Formats a table for display with columns shown vertically, along with the
+/// `[databases.<name>]` defaults it was built with, if any
 /// Returns a tuple of (title, details) for use with detailed display functions
 fn format_table_display(
     table: &crate::framework::core::infrastructure::table::Table,
+    applied_defaults: Option<&AppliedDatabaseDefaults>,
 ) -> (String, Vec<String>) {
     let mut details = Vec::new();
 
@@ -256,6 +259,15 @@ fn format_table_display(
         details.push(format!("Cluster: {}", cluster));
     }
 
+    // Values the table took from its database's defaults in the project config
+    if let Some(applied) = applied_defaults {
+        details.push(format!(
+            "Defaults from [databases.{}]: {}",
+            applied.database,
+            applied.keys.join(", ")
+        ));
+    }
+
     // Engine section
     details.push(format!(
         "Engine: {}",
@@ -429,6 +441,7 @@ pub fn infra_updated_detailed(title: &str, details: &[String]) {
 /// # Arguments
 ///
 /// * `olap_changes` - A slice of OLAP changes to display
+/// * `target_infra_map` - The map the changes lead to, whose tables' database defaults are shown
 ///
 /// # Change Types Handled
 ///
@@ -440,18 +453,44 @@ pub fn infra_updated_detailed(title: &str, details: &[String]) {
 ///
 /// ```rust
 /// # use crate::cli::display::infrastructure::show_olap_changes;
-/// show_olap_changes(&infrastructure_plan.changes.olap_changes);
+/// show_olap_changes(
+///     &infrastructure_plan.changes.olap_changes,
+///     Some(&infrastructure_plan.target_infra_map),
+/// );
 /// ```
-pub fn show_olap_changes(olap_changes: &[OlapChange]) {
+pub fn show_olap_changes(
+    olap_changes: &[OlapChange],
+    target_infra_map: Option<&InfrastructureMap>,
+) {
     olap_changes.iter().for_each(|change| match change {
         OlapChange::Table(TableChange::Added(infra)) => {
-            let (title, details) = format_table_display(infra);
+            let applied_defaults = target_infra_map.and_then(|infra_map| {
+                infra_map
+                    .applied_database_defaults
+                    .get(&infra.id(&infra_map.default_database))
+            });
+            let (title, details) = format_table_display(infra, applied_defaults);
             infra_added_detailed(&title, &details);
         }
         OlapChange::Table(TableChange::Removed(infra)) => {
-            let (title, details) = format_table_display(infra);
+            let (title, details) = format_table_display(infra, None);
             infra_removed_detailed(&title, &details);
         }
+        OlapChange::Table(TableChange::Truncated(infra)) => {
+            infra_updated_detailed(
+                &format!("Table: {}", infra.display_name()),
+                &["  ~ TRUNCATE, the empty table is kept (drop_strategy = \"truncate_only\")"
+                    .to_string()],
+            );
+        }
+        OlapChange::Table(TableChange::Renamed { before, new_name }) => {
+            infra_updated_detailed(
+                &format!("Table: {}", before.display_name()),
+                &[format!(
+                    "  ~ RENAME TO {new_name}, kept until `moose clean --dropped` (drop_strategy = \"rename\")"
+                )],
+            );
+        }
         OlapChange::Table(TableChange::Updated {
             name,
             column_changes,
@@ -504,7 +543,8 @@ pub fn show_olap_changes(olap_changes: &[OlapChange]) {
                             after,
                         } => {
                             // Check if there are actual visible changes
-                            let type_changed = before.data_type != after.data_type;
+                            let type_changed =
+                                !before.data_type.eq_ignoring_field_comments(&after.data_type);
                             let nullable_changed = before.required != after.required;
                             let default_changed = before.default != after.default;
                             let unique_changed = before.unique != after.unique;
@@ -795,11 +835,38 @@ fn format_workflow_details(workflow: &crate::framework::scripts::Workflow) -> Ve
     if config.schedule.is_empty() {
         details.push("Schedule: manual trigger only".to_string());
     } else {
-        details.push(format!("Schedule: {}", config.schedule));
+        let timezone = config.timezone.as_deref().unwrap_or("UTC");
+        details.push(format!("Schedule: {} ({timezone})", config.schedule));
     }
 
     details.push(format!("Retries: {}", config.retries));
     details.push(format!("Timeout: {}", config.timeout));
+    if let Some(execution_timeout) = &config.execution_timeout {
+        details.push(format!("Execution timeout: {execution_timeout}"));
+    }
+
+    if let Some(policy) = &config.retry_policy {
+        let mut settings = Vec::new();
+        if let Some(max_attempts) = policy.max_attempts {
+            settings.push(format!("max attempts {max_attempts}"));
+        }
+        if let Some(initial_interval) = &policy.initial_interval {
+            settings.push(format!("initial interval {initial_interval}"));
+        }
+        if let Some(backoff_coefficient) = policy.backoff_coefficient {
+            settings.push(format!("backoff {backoff_coefficient}"));
+        }
+        if let Some(maximum_interval) = &policy.maximum_interval {
+            settings.push(format!("maximum interval {maximum_interval}"));
+        }
+        if !policy.non_retryable_error_types.is_empty() {
+            settings.push(format!(
+                "non-retryable {}",
+                policy.non_retryable_error_types.join(", ")
+            ));
+        }
+        details.push(format!("Retry policy: {}", settings.join(", ")));
+    }
 
     details
 }
@@ -931,7 +998,10 @@ pub fn show_filtered_changes(filtered_changes: &[FilteredChange], default_databa
 /// ```
 pub fn show_changes(infra_plan: &InfraPlan) {
     show_streaming_changes(&infra_plan.changes.streaming_engine_changes);
-    show_olap_changes(&infra_plan.changes.olap_changes);
+    show_olap_changes(
+        &infra_plan.changes.olap_changes,
+        Some(&infra_plan.target_infra_map),
+    );
     show_process_changes(&infra_plan.changes.processes_changes);
     show_api_changes(&infra_plan.changes.api_changes);
     show_workflow_changes(&infra_plan.changes.workflow_changes);
@@ -968,7 +1038,7 @@ mod tests {
     #[test]
     fn test_show_functions_exist() {
         // Verify function signatures exist and are callable
-        let _f1: fn(&[OlapChange]) = show_olap_changes;
+        let _f1: fn(&[OlapChange], Option<&InfrastructureMap>) = show_olap_changes;
         let _f2: fn(&[StreamingChange]) = show_streaming_changes;
         let _f3: fn(&[ProcessChange]) = show_process_changes;
         let _f4: fn(&[ApiChange]) = show_api_changes;
@@ -984,7 +1054,7 @@ mod tests {
         let empty_api: &[ApiChange] = &[];
 
         // These should not panic
-        show_olap_changes(empty_olap);
+        show_olap_changes(empty_olap, None);
         show_streaming_changes(empty_streaming);
         show_process_changes(empty_process);
         show_api_changes(empty_api);
@@ -7,9 +7,11 @@ use super::{
     message::{Message, MessageType},
     terminal::{write_styled_line, StyledText},
 };
-use crate::utilities::constants::{NO_ANSI, QUIET_STDOUT, SHOW_TIMESTAMPS};
+use crate::utilities::constants::{
+    JSON_LOGS, NO_ANSI, QUIET_STDOUT, SHOW_TIMESTAMPS, SUPPRESS_DISPLAY,
+};
 use std::sync::atomic::Ordering;
-use tracing::info;
+use tracing::{error, info, warn};
 
 /// Displays a message about a batch database insertion.
 ///
@@ -109,6 +111,12 @@ pub fn show_message_impl(
     let action = message.action.clone();
     let details = message.details.clone();
 
+    // JSON logs own stdout, the message is logged instead of printed
+    if JSON_LOGS.load(Ordering::Relaxed) {
+        log_message(message_type, &action, &details);
+        return Ok(());
+    }
+
     // Create styled prefix based on message type
     let styled_prefix = match message_type {
         MessageType::Info => StyledText::new(action.clone()).cyan().bold(),
@@ -118,14 +126,20 @@ pub fn show_message_impl(
         MessageType::Highlight => StyledText::new(action.clone()).on_green().bold(),
     };
 
+    // `--quiet` only lets errors through, suppressed messages are still logged
+    let suppressed =
+        SUPPRESS_DISPLAY.load(Ordering::Relaxed) && !matches!(message_type, MessageType::Error);
+
     // Write styled prefix and details in one line
-    write_styled_line(
-        &styled_prefix,
-        &details,
-        no_ansi,
-        show_timestamps,
-        quiet_stdout,
-    )?;
+    if !suppressed {
+        write_styled_line(
+            &styled_prefix,
+            &details,
+            no_ansi,
+            show_timestamps,
+            quiet_stdout,
+        )?;
+    }
 
     if should_log {
         let log_action = action.replace('\n', " ");
@@ -136,6 +150,20 @@ pub fn show_message_impl(
     Ok(())
 }
 
+/// Logs a display message as an event at the level matching its type, with
+/// the action and message type as fields.
+pub(crate) fn log_message(message_type: MessageType, action: &str, details: &str) {
+    let action = action.replace('\n', " ");
+    let action = action.trim();
+    let details = details.replace('\n', " ");
+    let details = details.trim();
+    match message_type {
+        MessageType::Error => error!(action, message_type = ?message_type, "{}", details),
+        MessageType::Warning => warn!(action, message_type = ?message_type, "{}", details),
+        _ => info!(action, message_type = ?message_type, "{}", details),
+    }
+}
+
 /// Macro for displaying styled messages to the terminal.
 ///
 /// This macro provides a unified interface for displaying messages with consistent
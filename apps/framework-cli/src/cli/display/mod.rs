@@ -87,7 +87,7 @@ pub(crate) use infrastructure::write_detail_lines;
 pub use message::{Message, MessageType};
 pub use message_display::{batch_inserted, show_message_wrapper};
 pub use spinner::{with_spinner_completion, with_spinner_completion_async};
-pub use table::show_table;
+pub use table::{render_table, show_table};
 pub use timing::{with_timing, with_timing_async};
 
 // Legacy compatibility - maintain the crossterm_utils module for existing code
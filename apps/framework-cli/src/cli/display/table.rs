@@ -30,6 +30,12 @@ use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, ContentArra
 /// );
 /// ```
 pub fn show_table(title: String, headers: Vec<String>, rows: Vec<Vec<String>>) {
+    println!("{title}\n{}", render_table(headers, rows));
+}
+
+/// Renders headers and rows with the same styling as [`show_table`], returning
+/// the table as a string instead of printing it.
+pub fn render_table(headers: Vec<String>, rows: Vec<Vec<String>>) -> String {
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
@@ -41,7 +47,7 @@ pub fn show_table(title: String, headers: Vec<String>, rows: Vec<Vec<String>>) {
         table.add_row(row);
     }
 
-    println!("{title}\n{table}");
+    table.to_string()
 }
 
 #[cfg(test)]
@@ -0,0 +1,65 @@
+//! Process exit codes shared by all CLI commands.
+//!
+//! Scripts wrapping the CLI rely on these values, so existing codes must not be
+//! renumbered. Keep the EXIT CODES section of `moose --help` in sync.
+
+/// Category of outcome a command ends with, mapped to a process exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CliExitCode {
+    /// The command completed successfully
+    #[default]
+    Success,
+    /// Any error that does not fit a more specific category
+    Error,
+    /// `moose plan` succeeded and found changes to apply
+    PlanHasChanges,
+    /// The project, its config, or a computed plan failed validation
+    ValidationFailure,
+    /// A required service (ClickHouse, Redis, Kafka, Temporal, a remote Moose instance) could not be reached
+    ConnectivityFailure,
+    /// Credentials were missing or rejected
+    AuthFailure,
+    /// The user interrupted the command (Ctrl+C)
+    Interrupted,
+}
+
+impl CliExitCode {
+    pub const fn code(self) -> u8 {
+        match self {
+            CliExitCode::Success => 0,
+            CliExitCode::Error => 1,
+            CliExitCode::PlanHasChanges => 2,
+            CliExitCode::ValidationFailure => 3,
+            CliExitCode::ConnectivityFailure => 4,
+            CliExitCode::AuthFailure => 5,
+            CliExitCode::Interrupted => 130,
+        }
+    }
+}
+
+impl From<CliExitCode> for std::process::ExitCode {
+    fn from(code: CliExitCode) -> Self {
+        std::process::ExitCode::from(code.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_stable() {
+        let codes = [
+            (CliExitCode::Success, 0),
+            (CliExitCode::Error, 1),
+            (CliExitCode::PlanHasChanges, 2),
+            (CliExitCode::ValidationFailure, 3),
+            (CliExitCode::ConnectivityFailure, 4),
+            (CliExitCode::AuthFailure, 5),
+            (CliExitCode::Interrupted, 130),
+        ];
+        for (kind, code) in codes {
+            assert_eq!(kind.code(), code);
+        }
+    }
+}
@@ -33,17 +33,22 @@ use crate::metrics::MetricEvent;
 
 use crate::cli::logger::{context, resource_type};
 
+use crate::framework::consumption::api_metrics::{self, ApiBudgetConfig};
+use crate::framework::core::drop_strategy;
 use crate::framework::core::infrastructure::api_endpoint::APIType;
 use crate::framework::core::infrastructure_map::Change;
 use crate::framework::core::infrastructure_map::{ApiChange, InfrastructureMap};
 use crate::framework::core::infrastructure_map::{InfraChanges, OlapChange, TableChange};
 use crate::framework::versions::Version;
 use crate::metrics::Metrics;
+use crate::metrics_otlp::OtlpMetricsExporter;
 use crate::utilities::auth::{get_claims, validate_jwt};
 use crate::utilities::constants::SHOW_TIMING;
 
 use crate::framework::core::infrastructure::topic::{KafkaSchemaKind, SchemaRegistryReference};
 use crate::infrastructure::olap::clickhouse;
+use crate::infrastructure::olap::clickhouse::credentials::SharedClient;
+use crate::infrastructure::orchestration::workflows;
 use crate::infrastructure::stream::kafka;
 use crate::infrastructure::stream::kafka::models::ConfiguredProducer;
 use crate::project::{JwtConfig, Project};
@@ -112,7 +117,7 @@ pub struct RouterRequest {
 
 /// Default management port for the webserver.
 /// This is used when no management port is specified in the configuration.
-fn default_management_port() -> u16 {
+pub fn default_management_port() -> u16 {
     5001
 }
 
@@ -286,6 +291,9 @@ pub struct LocalWebserverConfig {
     /// Python always uses 1 worker regardless of this setting
     #[serde(default)]
     pub api_workers: Option<usize>,
+    /// Latency budgets of the analytics APIs, by API name
+    #[serde(default)]
+    pub api_budgets: HashMap<String, ApiBudgetConfig>,
 }
 
 pub fn default_proxy_port() -> u16 {
@@ -447,6 +455,7 @@ impl Default for LocalWebserverConfig {
             on_reload_complete_script: None,
             on_first_start_script: None,
             api_workers: None,
+            api_budgets: HashMap::new(),
         }
     }
 }
@@ -532,7 +541,7 @@ async fn get_consumption_api_res(
     host: String,
     api_context: ConsumptionApiContext,
     is_prod: bool,
-    proxy_port: u16,
+    http_server_config: &LocalWebserverConfig,
 ) -> Result<Response<Full<Bytes>>, anyhow::Error> {
     // Extract the Authorization header and check the bearer token
     let auth_header = req.headers().get(hyper::header::AUTHORIZATION);
@@ -551,7 +560,7 @@ async fn get_consumption_api_res(
     let url = format!(
         "http://{}:{}{}{}",
         host,
-        proxy_port,
+        http_server_config.proxy_port,
         full_path,
         req.uri()
             .query()
@@ -579,10 +588,24 @@ async fn get_consumption_api_res(
         headers.insert(key, value.clone());
     }
 
-    // Send request
-    let res = http_client.execute(client_req).await?;
-    let status = res.status();
-    let body = res.bytes().await?;
+    let request = async {
+        let res = http_client.execute(client_req).await?;
+        let query_time = res
+            .headers()
+            .get("server-timing")
+            .and_then(|value| value.to_str().ok())
+            .and_then(api_metrics::query_duration);
+        let status = res.status();
+        let body = res.bytes().await?;
+        Ok::<_, anyhow::Error>(((status, body), query_time))
+    };
+    let (status, body) = api_metrics::observe_request(
+        &api_metrics::LATENCY_BUDGETS,
+        &api_context.api_name,
+        http_server_config.api_budgets.get(&api_context.api_name),
+        request,
+    )
+    .await?;
 
     let returned_response = add_cors_headers(Response::builder())
         .status(status)
@@ -608,6 +631,7 @@ struct RouteService {
     http_client: Arc<Client>,
     project: Arc<Project>,
     redis_client: Arc<RedisClient>,
+    shared_client: Arc<SharedClient>,
 }
 
 #[derive(Clone)]
@@ -620,12 +644,59 @@ struct ManagementService<I: InfraMapProvider + Clone> {
     max_request_body_size: usize,
 }
 
+/// Credentials accepted by the /mcp endpoint
+#[derive(Clone)]
+struct McpAuth {
+    admin_api_key: Option<String>,
+    mcp_api_key: Option<String>,
+    allow_unauthenticated: bool,
+}
+
+impl McpAuth {
+    fn new(project: &Project) -> Self {
+        McpAuth {
+            admin_api_key: project.authentication.admin_api_key.clone(),
+            mcp_api_key: MOOSE_MCP_API_KEY.clone(),
+            allow_unauthenticated: project
+                .dev
+                .mcp
+                .allow_unauthenticated
+                .unwrap_or(!project.is_production),
+        }
+    }
+
+    /// Checks the bearer token against the admin API key and the MCP API key,
+    /// returning the message of the 401 response on failure.
+    async fn authorize(&self, auth_header: Option<&HeaderValue>) -> Result<(), &'static str> {
+        if self.allow_unauthenticated {
+            return Ok(());
+        }
+        if self.admin_api_key.is_none() && self.mcp_api_key.is_none() {
+            return Err("Unauthorized: /mcp requires a token but none is configured. Set authentication.admin_api_key in moose.config.toml or MOOSE_MCP_API_KEY, or allow_unauthenticated in [dev.mcp] for local development");
+        }
+
+        let bearer_token = auth_header
+            .and_then(|header_value| header_value.to_str().ok())
+            .and_then(|header_str| header_str.strip_prefix("Bearer "));
+        for key in [&self.admin_api_key, &self.mcp_api_key]
+            .into_iter()
+            .flatten()
+        {
+            if validate_token(bearer_token, key).await {
+                return Ok(());
+            }
+        }
+        Err("Unauthorized: Invalid or missing token. Send `Authorization: Bearer <token>` with the admin API token or an MCP API token")
+    }
+}
+
 /// ApiService delegates requests to either the MCP service or the RouteService
 /// based on the request path. This provides clean separation between MCP and
 /// regular API routes without nesting MCP handling inside the main router.
 #[derive(Clone)]
 struct ApiService {
     route_service: RouteService,
+    mcp_auth: McpAuth,
     mcp_service: Option<
         hyper_util::service::TowerToHyperService<
             crate::mcp::StreamableHttpService<
@@ -647,9 +718,20 @@ impl Service<Request<Incoming>> for ApiService {
         // Route to MCP service if path starts with /mcp and MCP is enabled
         if path.starts_with("/mcp") {
             if let Some(mcp) = self.mcp_service.clone() {
+                let mcp_auth = self.mcp_auth.clone();
                 return Box::pin(async move {
                     use http_body_util::BodyExt;
 
+                    if let Err(message) = mcp_auth
+                        .authorize(req.headers().get(hyper::header::AUTHORIZATION))
+                        .await
+                    {
+                        return Response::builder()
+                            .status(StatusCode::UNAUTHORIZED)
+                            .header(hyper::header::WWW_AUTHENTICATE, "Bearer")
+                            .body(Full::new(Bytes::from(message)));
+                    }
+
                     // Call the MCP service - TowerToHyperService handles the conversion
                     let mcp_response = match mcp.call(req).await {
                         Ok(response) => response,
@@ -706,6 +788,7 @@ impl Service<Request<Incoming>> for RouteService {
             },
             self.project.clone(),
             self.redis_client.clone(),
+            self.shared_client.clone(),
         ))
     }
 }
@@ -745,6 +828,121 @@ struct WorkflowQueryParams {
     limit: Option<u32>,
 }
 
+#[derive(Deserialize, Default)]
+struct LogLevelQueryParams {
+    #[serde(default)]
+    persist: bool,
+}
+
+fn log_level_response(persisted: bool) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
+    let level = crate::cli::logger::current_log_level()
+        .map(|level| level.to_string())
+        .unwrap_or_default();
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(Full::new(Bytes::from(
+            json!({
+                "status": "success",
+                "level": level,
+                "persisted": persisted,
+            })
+            .to_string(),
+        )))
+}
+
+/// Reads the ClickHouse credentials again and rebuilds the shared client if
+/// they changed. Operations in flight finish with the previous client.
+#[instrument(name = "reload_credentials", skip_all, fields(context = context::RUNTIME))]
+async fn admin_reload_credentials_route(
+    req: Request<hyper::body::Incoming>,
+    admin_api_key: &Option<String>,
+    shared_client: &SharedClient,
+) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
+    let auth_header = req.headers().get(hyper::header::AUTHORIZATION);
+    if let Err(e) = validate_admin_auth(auth_header, admin_api_key).await {
+        return e.to_response();
+    }
+
+    match shared_client.reload().await {
+        Ok(reloaded) => Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/json")
+            .body(Full::new(Bytes::from(
+                json!({
+                    "status": "success",
+                    "reloaded": reloaded,
+                })
+                .to_string(),
+            ))),
+        Err(e) => IntegrationError::InternalError(e.to_string()).to_response(),
+    }
+}
+
+/// Returns the current level of the local log layers.
+#[instrument(name = "log_level", skip_all, fields(context = context::RUNTIME))]
+async fn admin_get_log_level_route(
+    req: Request<hyper::body::Incoming>,
+    admin_api_key: &Option<String>,
+) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
+    let auth_header = req.headers().get(hyper::header::AUTHORIZATION);
+    if let Err(e) = validate_admin_auth(auth_header, admin_api_key).await {
+        return e.to_response();
+    }
+    log_level_response(false)
+}
+
+/// Changes the log level of the running process to the level in the body, e.g.
+/// `debug`. The CLI settings file is only updated with `?persist=true`.
+#[instrument(name = "log_level", skip_all, fields(context = context::RUNTIME))]
+async fn admin_set_log_level_route(
+    req: Request<hyper::body::Incoming>,
+    admin_api_key: &Option<String>,
+    max_request_body_size: usize,
+) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
+    let auth_header = req.headers().get(hyper::header::AUTHORIZATION);
+    if let Err(e) = validate_admin_auth(auth_header, admin_api_key).await {
+        return e.to_response();
+    }
+
+    let query_params: LogLevelQueryParams = req
+        .uri()
+        .query()
+        .map(|q| serde_urlencoded::from_str(q).unwrap_or_default())
+        .unwrap_or_default();
+
+    let mut body = String::new();
+    match to_reader(req, max_request_body_size).await {
+        Ok(mut reader) => {
+            if std::io::Read::read_to_string(&mut reader, &mut body).is_err() {
+                return IntegrationError::BadRequest("Body is not valid UTF-8".to_string())
+                    .to_response();
+            }
+        }
+        Err(response) => return Ok(response),
+    }
+
+    // Accept a bare level as well as a JSON string
+    let level = match crate::cli::logger::parse_log_level(body.trim().trim_matches('"')) {
+        Ok(level) => level,
+        Err(e) => return IntegrationError::BadRequest(e.to_string()).to_response(),
+    };
+    if let Err(e) = crate::cli::logger::set_log_level(level) {
+        return IntegrationError::InternalError(e.to_string()).to_response();
+    }
+    info!("Log level set to {}", level);
+
+    if query_params.persist {
+        if let Err(e) = super::settings::set_logger_level(&level.to_string()) {
+            return IntegrationError::InternalError(format!(
+                "Log level changed but not saved to the settings file: {e}"
+            ))
+            .to_response();
+        }
+    }
+    log_level_response(query_params.persist)
+}
+
 async fn workflows_history_route(
     req: Request<hyper::body::Incoming>,
     project: Arc<Project>,
@@ -985,6 +1183,8 @@ async fn live_route(project: &Project) -> Result<Response<Full<Bytes>>, hyper::h
 async fn health_route(
     project: &Project,
     redis_client: &Arc<RedisClient>,
+    shared_client: &Arc<SharedClient>,
+    metrics: &Metrics,
 ) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
     use std::time::Duration;
     use tokio::task::JoinSet;
@@ -1014,10 +1214,15 @@ async fn health_route(
 
     // Spawn ClickHouse check (if enabled)
     if project.features.olap {
-        let clickhouse_config = project.clickhouse_config.clone();
+        let shared_client = shared_client.clone();
         join_set.spawn(async move {
-            let olap_client = clickhouse::create_client(clickhouse_config);
-            match olap_client.client.query("SELECT 1").execute().await {
+            let check =
+                shared_client
+                    .run(|olap_client| async move {
+                        olap_client.client.query("SELECT 1").execute().await
+                    })
+                    .await;
+            match check {
                 Ok(_) => ("ClickHouse", true),
                 Err(e) => {
                     warn!("Health check: ClickHouse unavailable: {}", e);
@@ -1088,11 +1293,29 @@ async fn health_route(
     } else {
         StatusCode::SERVICE_UNAVAILABLE
     };
-    let json_response = serde_json::to_string_pretty(&serde_json::json!({
+    let fencing_token = redis_client.lock_fencing_token("leadership");
+    let redis_health = redis_client
+        .connection_health()
+        .snapshot(tokio::time::Instant::now());
+    let mut body = serde_json::json!({
         "healthy": healthy,
-        "unhealthy": unhealthy
-    }))
-    .unwrap_or_else(|_| String::from("{\"error\":\"Failed to serialize response\"}"));
+        "unhealthy": unhealthy,
+        "redis": redis_health,
+        "metrics_pipeline": metrics.pipeline_health(),
+        "leadership": {
+            "is_leader": fencing_token.is_some(),
+            "fencing_token": fencing_token
+        }
+    });
+    if project.features.workflows {
+        let degraded_reason = workflows::degraded_reason();
+        body["workflows"] = serde_json::json!({
+            "degraded": degraded_reason.is_some(),
+            "reason": degraded_reason
+        });
+    }
+    let json_response = serde_json::to_string_pretty(&body)
+        .unwrap_or_else(|_| String::from("{\"error\":\"Failed to serialize response\"}"));
 
     Response::builder()
         .status(status)
@@ -1111,6 +1334,7 @@ async fn health_route(
 async fn ready_route(
     project: &Project,
     redis_client: &Arc<RedisClient>,
+    shared_client: &SharedClient,
 ) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
     // This endpoint validates that backing services are not only reachable but their
     // connections are warmed/ready for immediate use.
@@ -1138,8 +1362,10 @@ async fn ready_route(
 
     // ClickHouse: run a small query using the configured client (ensures HTTP pool is ready)
     if project.features.olap {
-        let ch = clickhouse::create_client(project.clickhouse_config.clone());
-        match clickhouse::check_ready(&ch).await {
+        let check = shared_client
+            .run(|ch| async move { clickhouse::check_ready(&ch).await })
+            .await;
+        match check {
             Ok(_) => healthy.push("ClickHouse"),
             Err(e) => {
                 warn!("Ready check: ClickHouse not ready: {}", e);
@@ -1162,7 +1388,7 @@ async fn ready_route(
         match res {
             Ok(_) => healthy.push("Temporal"),
             Err(e) => {
-                warn!("Ready check: Temporal not ready: {:?}", e);
+                warn!("Ready check: Temporal not ready: {}", e);
                 unhealthy.push("Temporal")
             }
         }
@@ -1199,6 +1425,7 @@ async fn admin_reality_check_route(
     admin_api_key: &Option<String>,
     project: &Project,
     redis_client: &Arc<RedisClient>,
+    shared_client: &SharedClient,
 ) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
     let auth_header = req.headers().get(hyper::header::AUTHORIZATION);
 
@@ -1233,7 +1460,7 @@ async fn admin_reality_check_route(
     // Perform reality check (storage is guaranteed to be enabled at this point)
     let discrepancies = {
         // Create OLAP client and reality checker
-        let olap_client = clickhouse::create_client(project.clickhouse_config.clone());
+        let olap_client = clickhouse::create_client(shared_client.config());
         let reality_checker =
             crate::framework::core::infra_reality_checker::InfraRealityChecker::new(olap_client);
 
@@ -1300,6 +1527,17 @@ async fn metrics_log_route(
         .unwrap()
 }
 
+/// Where the analytics APIs with a latency budget stand, for `moose ls`.
+fn api_budgets_route() -> Result<Response<Full<Bytes>>, hyper::http::Error> {
+    let status = api_metrics::LATENCY_BUDGETS.status();
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(Full::new(Bytes::from(
+            serde_json::to_string(&status).unwrap_or_default(),
+        )))
+}
+
 async fn metrics_route(metrics: Arc<Metrics>) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
     let response = Response::builder()
         .status(StatusCode::OK)
@@ -1714,6 +1952,7 @@ fn get_env_var(s: &str) -> Option<String> {
 lazy_static! {
     static ref MOOSE_CONSUMPTION_API_KEY: Option<String> = get_env_var("MOOSE_CONSUMPTION_API_KEY");
     static ref MOOSE_INGEST_API_KEY: Option<String> = get_env_var("MOOSE_INGEST_API_KEY");
+    static ref MOOSE_MCP_API_KEY: Option<String> = get_env_var("MOOSE_MCP_API_KEY");
     static ref VERSION_PATTERN: Regex = Regex::new(r"^\d+(\.\d+)*$").unwrap();
 }
 
@@ -1841,6 +2080,7 @@ async fn router(
     request: RouterRequest,
     project: Arc<Project>,
     redis_client: Arc<RedisClient>,
+    shared_client: Arc<SharedClient>,
 ) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
     let now = Instant::now();
 
@@ -1949,6 +2189,7 @@ async fn router(
                 &project.authentication.admin_api_key,
                 &project,
                 &redis_client,
+                &shared_client,
                 project.http_server_config.max_request_body_size,
             )
             .await
@@ -1960,6 +2201,7 @@ async fn router(
                 &project.authentication.admin_api_key,
                 &redis_client,
                 &project,
+                &shared_client,
                 project.http_server_config.max_request_body_size,
             )
             .await
@@ -1970,6 +2212,7 @@ async fn router(
                 &project.authentication.admin_api_key,
                 &redis_client,
                 &project,
+                &shared_client,
             )
             .await
         }
@@ -1986,7 +2229,7 @@ async fn router(
                 host,
                 api_context,
                 is_prod,
-                project.http_server_config.proxy_port,
+                &project.http_server_config,
             )
             .await
             {
@@ -1999,15 +2242,39 @@ async fn router(
                 }
             }
         }
-        (_, &hyper::Method::GET, ["health"]) => health_route(&project, &redis_client).await,
+        (_, &hyper::Method::GET, ["health"]) => {
+            health_route(&project, &redis_client, &shared_client, &metrics).await
+        }
         (_, &hyper::Method::GET, ["liveness"]) => live_route(&project).await,
-        (_, &hyper::Method::GET, ["ready"]) => ready_route(&project, &redis_client).await,
+        (_, &hyper::Method::GET, ["ready"]) => {
+            ready_route(&project, &redis_client, &shared_client).await
+        }
+        (_, &hyper::Method::GET, ["admin", "log-level"]) => {
+            admin_get_log_level_route(req, &project.authentication.admin_api_key).await
+        }
+        (_, &hyper::Method::PUT, ["admin", "log-level"]) => {
+            admin_set_log_level_route(
+                req,
+                &project.authentication.admin_api_key,
+                project.http_server_config.max_request_body_size,
+            )
+            .await
+        }
+        (_, &hyper::Method::POST, ["admin", "reload-credentials"]) => {
+            admin_reload_credentials_route(
+                req,
+                &project.authentication.admin_api_key,
+                &shared_client,
+            )
+            .await
+        }
         (_, &hyper::Method::GET, ["admin", "reality-check"]) => {
             admin_reality_check_route(
                 req,
                 &project.authentication.admin_api_key,
                 &project,
                 &redis_client,
+                &shared_client,
             )
             .await
         }
@@ -2171,19 +2438,27 @@ async fn router(
 }
 
 const METRICS_LOGS_PATH: &str = "metrics-logs";
+pub const API_BUDGETS_PATH: &str = "api-budgets";
 
 pub trait InfraMapProvider {
     fn serialize(&self) -> impl Future<Output = serde_json::error::Result<String>> + Send;
     fn serialize_proto(&self) -> impl Future<Output = Vec<u8>> + Send;
+    /// The map kept up to date by the file watcher, in dev mode
+    fn live(&self) -> Option<&'static RwLock<InfrastructureMap>> {
+        None
+    }
 }
 
-impl InfraMapProvider for &RwLock<InfrastructureMap> {
+impl InfraMapProvider for &'static RwLock<InfrastructureMap> {
     async fn serialize(&self) -> serde_json::error::Result<String> {
         serde_json::to_string(self.read().await.deref())
     }
     async fn serialize_proto(&self) -> Vec<u8> {
         self.read().await.to_proto_bytes()
     }
+    fn live(&self) -> Option<&'static RwLock<InfrastructureMap>> {
+        Some(*self)
+    }
 }
 
 impl InfraMapProvider for &InfrastructureMap {
@@ -2234,6 +2509,7 @@ async fn management_router<I: InfraMapProvider>(
             Ok(metrics_log_route(req, metrics.clone(), max_request_body_size).await)
         }
         (&hyper::Method::GET, "metrics") => metrics_route(metrics.clone()).await,
+        (&hyper::Method::GET, API_BUDGETS_PATH) => api_budgets_route(),
         // TODO: changes from admin/integrate-changes should apply here
         (&hyper::Method::GET, "infra-map") => {
             if accept_header.contains("application/protobuf") {
@@ -2682,6 +2958,7 @@ impl Webserver {
         metrics: Arc<Metrics>,
         openapi_path: Option<PathBuf>,
         process_registry: Arc<RwLock<ProcessRegistries>>,
+        shared_client: Arc<SharedClient>,
         enable_mcp: bool,
         processing_coordinator: crate::cli::processing_coordinator::ProcessingCoordinator,
         watcher_shutdown_tx: Option<tokio::sync::watch::Sender<bool>>,
@@ -2779,9 +3056,11 @@ impl Webserver {
                 "moose-mcp-server".to_string(),
                 CLI_VERSION.to_string(),
                 redis_client_arc.clone(),
+                infra_map.live(),
                 project.clickhouse_config.clone(),
                 Arc::new(project.redpanda_config.clone()),
                 processing_coordinator.clone(),
+                project.clone(),
             );
             // Wrap the Tower service to make it compatible with Hyper
             Some(TowerToHyperService::new(tower_service))
@@ -2789,6 +3068,22 @@ impl Webserver {
             None
         };
 
+        let otlp_exporter = match &settings.telemetry.otlp {
+            Some(otlp) => match OtlpMetricsExporter::start(otlp, metrics.clone()).await {
+                Ok(exporter) => Some(exporter),
+                Err(e) => {
+                    warn!("Metrics will not be exported over OTLP: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        // In dev, SIGHUP keeps stopping the process when its terminal closes
+        if project.is_production && project.features.olap {
+            shared_client.clone().watch();
+        }
+
         let route_service = RouteService {
             host: self.host.clone(),
             path_prefix: project.http_server_config.normalized_path_prefix(),
@@ -2803,11 +3098,13 @@ impl Webserver {
             metrics: metrics.clone(),
             project: project.clone(),
             redis_client: redis_client_arc.clone(),
+            shared_client,
         };
 
         // Wrap route_service with ApiService to handle MCP routing at the top level
         let api_service = ApiService {
             route_service,
+            mcp_auth: McpAuth::new(&project),
             mcp_service,
         };
 
@@ -2964,6 +3261,10 @@ impl Webserver {
         // Producer Arc count should now be 1 (only producer_for_shutdown remains)
 
         // Now call shutdown to handle process cleanup and producer drop
+        if let Some(exporter) = otlp_exporter {
+            exporter.shutdown().await;
+        }
+
         shutdown(settings, &project, process_registry, producer_for_shutdown).await;
     }
 }
@@ -3390,6 +3691,7 @@ async fn admin_integrate_changes_route(
     admin_api_key: &Option<String>,
     project: &Project,
     redis_client: &Arc<RedisClient>,
+    shared_client: &SharedClient,
     max_request_body_size: usize,
 ) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
     debug!("Starting admin_integrate_changes_route");
@@ -3435,7 +3737,7 @@ async fn admin_integrate_changes_route(
     }
 
     // Get reality check
-    let olap_client = clickhouse::create_client(project.clickhouse_config.clone());
+    let olap_client = clickhouse::create_client(shared_client.config());
     let reality_checker =
         crate::framework::core::infra_reality_checker::InfraRealityChecker::new(olap_client);
 
@@ -3505,12 +3807,23 @@ pub struct PlanRequest {
     pub infra_map: InfrastructureMap,
 }
 
+/// Query parameters of the admin plan endpoint
+#[derive(Deserialize, Default)]
+struct PlanQueryParams {
+    /// Also return the SQL statements the OLAP changes would run
+    #[serde(default)]
+    dry_run: bool,
+}
+
 /// Response structure for the admin plan endpoint
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PlanResponse {
     pub status: String,
     // Changes that would be applied if the plan is executed
     pub changes: InfraChanges,
+    // With `dry_run`, the SQL statements of the OLAP changes, grouped by operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statements: Option<Vec<clickhouse::OperationStatements>>,
 }
 
 /// Response structure for the admin inframap endpoint
@@ -3538,14 +3851,14 @@ pub struct InfraMapResponse {
 async fn get_admin_reconciled_inframap(
     redis_client: &Arc<RedisClient>,
     project: &Project,
+    shared_client: &SharedClient,
 ) -> Result<InfrastructureMap, crate::framework::core::plan::PlanningError> {
     use crate::framework::core::state_storage::StateStorageBuilder;
-    use crate::infrastructure::olap::clickhouse;
 
     // Build state storage from project configuration.
     // This provides access to the persisted infrastructure map (stored in Redis or ClickHouse).
     let state_storage = StateStorageBuilder::from_config(project)
-        .clickhouse_config(Some(project.clickhouse_config.clone()))
+        .clickhouse_config(Some(shared_client.config()))
         .redis_client(Some(redis_client))
         .build()
         .await
@@ -3578,7 +3891,7 @@ async fn get_admin_reconciled_inframap(
     // may support reconciliation of other infrastructure types (e.g., Kafka topics).
     let reconciled_map = if project.features.olap {
         // Create the ClickHouse client for database introspection.
-        let clickhouse_client = clickhouse::create_client(project.clickhouse_config.clone());
+        let clickhouse_client = clickhouse::create_client(shared_client.config());
         crate::framework::core::plan::reconcile_with_reality(
             project,
             &current_map,
@@ -3614,6 +3927,7 @@ async fn admin_plan_route(
     admin_api_key: &Option<String>,
     redis_client: &Arc<RedisClient>,
     project: &Project,
+    shared_client: &SharedClient,
     max_request_body_size: usize,
 ) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
     // Validate admin authentication
@@ -3621,6 +3935,12 @@ async fn admin_plan_route(
     if let Err(e) = validate_admin_auth(auth_header, admin_api_key).await {
         return e.to_response();
     }
+    let query_params: PlanQueryParams = req
+        .uri()
+        .query()
+        .map(|q| serde_urlencoded::from_str(q).unwrap_or_default())
+        .unwrap_or_default();
+
     // Authentication successful, proceed with plan calculation
     // Use Limited to enforce size limit during streaming
     let limited_body = Limited::new(req.into_body(), max_request_body_size);
@@ -3666,21 +3986,22 @@ async fn admin_plan_route(
 
     // Get the reconciled infrastructure map (combines Redis + reality check for managed tables)
     // This ensures we're diffing against the true current state of managed infrastructure only
-    let current_infra_map = match get_admin_reconciled_inframap(redis_client, project).await {
-        Ok(infra_map) => infra_map,
-        Err(e) => {
-            error!("Failed to get reconciled infrastructure map: {}", e);
-            return Ok(Response::builder()
-                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                .body(Full::new(Bytes::from(format!(
-                    "Failed to get current infrastructure state: {e}"
-                ))))
-                .unwrap());
-        }
-    };
+    let current_infra_map =
+        match get_admin_reconciled_inframap(redis_client, project, shared_client).await {
+            Ok(infra_map) => infra_map,
+            Err(e) => {
+                error!("Failed to get reconciled infrastructure map: {}", e);
+                return Ok(Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Full::new(Bytes::from(format!(
+                        "Failed to get current infrastructure state: {e}"
+                    ))))
+                    .unwrap());
+            }
+        };
 
     // Normalize SQL in both maps before diffing to handle ClickHouse reformatting
-    let olap_client = clickhouse::create_client(project.clickhouse_config.clone());
+    let olap_client = clickhouse::create_client(shared_client.config());
     let current_normalized = crate::framework::core::plan::normalize_infra_map_for_comparison(
         &current_infra_map,
         &olap_client,
@@ -3700,18 +4021,44 @@ async fn admin_plan_route(
     } else {
         &[]
     };
-    let changes = current_normalized.diff_with_table_strategy(
+    let mut changes = current_normalized.diff_with_table_strategy(
         &target_normalized,
         &clickhouse_strategy,
         true,
         project.is_production,
         ignore_ops,
     );
+    if project.is_production {
+        drop_strategy::apply_drop_strategy(
+            &mut changes.olap_changes,
+            &project.migration_config,
+            &project.clickhouse_config.db_name,
+        );
+    }
+
+    // The statements come from the code executing the changes, run without sending them
+    let statements = if query_params.dry_run {
+        match crate::infrastructure::olap::dry_run_changes(project, &changes.olap_changes).await {
+            Ok(statements) => Some(statements),
+            Err(e) => {
+                error!("Failed to dry run the plan: {:?}", e);
+                return Ok(Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Full::new(Bytes::from(format!(
+                        "Failed to dry run the plan: {e}"
+                    ))))
+                    .unwrap());
+            }
+        }
+    } else {
+        None
+    };
 
     // Prepare the response
     let response = PlanResponse {
         status: "success".to_string(),
         changes,
+        statements,
     };
 
     // Serialize the response to JSON
@@ -3744,6 +4091,7 @@ async fn admin_inframap_route(
     admin_api_key: &Option<String>,
     redis_client: &Arc<RedisClient>,
     project: &Project,
+    shared_client: &SharedClient,
 ) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
     // Validate admin authentication
     let auth_header = req.headers().get(hyper::header::AUTHORIZATION);
@@ -3753,18 +4101,19 @@ async fn admin_inframap_route(
 
     // Get the reconciled infrastructure map (combines Redis + reality check for managed tables)
     // This ensures we return the true current state of managed infrastructure only
-    let current_infra_map = match get_admin_reconciled_inframap(redis_client, project).await {
-        Ok(infra_map) => infra_map,
-        Err(e) => {
-            error!("Failed to get reconciled infrastructure map: {}", e);
-            return Ok(Response::builder()
-                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                .body(Full::new(Bytes::from(format!(
-                    "Failed to get current infrastructure state: {e}"
-                ))))
-                .unwrap());
-        }
-    };
+    let current_infra_map =
+        match get_admin_reconciled_inframap(redis_client, project, shared_client).await {
+            Ok(infra_map) => infra_map,
+            Err(e) => {
+                error!("Failed to get reconciled infrastructure map: {}", e);
+                return Ok(Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Full::new(Bytes::from(format!(
+                        "Failed to get current infrastructure state: {e}"
+                    ))))
+                    .unwrap());
+            }
+        };
 
     // Check Accept header to determine response format
     let accept_header = req
@@ -3861,6 +4210,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }
     }
 
@@ -4078,4 +4428,79 @@ mod tests {
         // Leading slash edge case
         assert_eq!(find_api_name("/api/1", &apis), "/api/1");
     }
+
+    fn bearer(token: &str) -> HeaderValue {
+        HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_mcp_auth_tokens() {
+        use crate::cli::routines::auth::generate_hash_token;
+
+        let admin = generate_hash_token();
+        let mcp = generate_hash_token();
+        let other = generate_hash_token();
+        let auth = McpAuth {
+            admin_api_key: Some(admin.api_key_hash),
+            mcp_api_key: Some(mcp.api_key_hash),
+            allow_unauthenticated: false,
+        };
+
+        assert!(auth
+            .authorize(Some(&bearer(&admin.bearer_token)))
+            .await
+            .is_ok());
+        assert!(auth
+            .authorize(Some(&bearer(&mcp.bearer_token)))
+            .await
+            .is_ok());
+        assert!(auth
+            .authorize(Some(&bearer(&other.bearer_token)))
+            .await
+            .is_err());
+        assert!(auth.authorize(None).await.is_err());
+
+        let admin_only = McpAuth {
+            mcp_api_key: None,
+            ..auth
+        };
+        assert!(admin_only
+            .authorize(Some(&bearer(&mcp.bearer_token)))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mcp_auth_unauthenticated() {
+        let allowed = McpAuth {
+            admin_api_key: None,
+            mcp_api_key: None,
+            allow_unauthenticated: true,
+        };
+        assert!(allowed.authorize(None).await.is_ok());
+
+        let unconfigured = McpAuth {
+            allow_unauthenticated: false,
+            ..allowed
+        };
+        let message = unconfigured.authorize(None).await.unwrap_err();
+        assert!(message.contains("none is configured"), "{message}");
+    }
+
+    #[test]
+    fn test_mcp_auth_defaults_by_mode() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut project = Project::new(
+            temp_dir.path(),
+            "test-project".to_string(),
+            crate::framework::languages::SupportedLanguages::Typescript,
+        );
+        assert!(McpAuth::new(&project).allow_unauthenticated);
+
+        project.is_production = true;
+        assert!(!McpAuth::new(&project).allow_unauthenticated);
+
+        project.dev.mcp.allow_unauthenticated = Some(true);
+        assert!(McpAuth::new(&project).allow_unauthenticated);
+    }
 }
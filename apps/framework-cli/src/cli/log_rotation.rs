@@ -0,0 +1,495 @@
+//! Rotation and retention of the file logs in `~/.moose`.
+//!
+//! The active file is named after `log_file_date_format` (or
+//! [`UNDATED_LOG_FILE`] without daily rotation). Once it reaches
+//! `max_file_bytes` it is renamed to `<active>.<YYYYmmddTHHMMSSmmm>`, gzipped
+//! in the background and the oldest log files beyond `max_files`,
+//! `max_total_bytes` or [`MAX_LOG_AGE`] are deleted. Renaming and pruning
+//! only ever touch files other than the active one, under [`ROTATION_LOCK`],
+//! so the writer never loses a line to them.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Local, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Deserialize;
+use tracing::warn;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Active log file when daily rotation is off.
+pub const UNDATED_LOG_FILE: &str = "cli.log";
+
+/// Log files older than this are deleted regardless of the other limits.
+pub const MAX_LOG_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Delay before compressing a rotated file, for writers of other moose
+/// processes that opened it just before it was renamed.
+const COMPRESS_DELAY: Duration = Duration::from_secs(1);
+
+const ROTATED_SUFFIX_FORMAT: &str = "%Y%m%dT%H%M%S%3f";
+
+/// Serializes rotations and pruning within the process.
+static ROTATION_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LogRotationSettings {
+    /// Size at which the active file is rotated
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: u64,
+    /// Log files kept, the active one included
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+    /// Total size of the log files kept, the active one included
+    #[serde(default)]
+    pub max_total_bytes: Option<u64>,
+    /// Start a new file every day, named after `log_file_date_format`
+    #[serde(default = "default_true")]
+    pub daily: bool,
+    /// Gzip rotated files
+    #[serde(default = "default_true")]
+    pub compress: bool,
+}
+
+fn default_max_file_bytes() -> u64 {
+    100 * 1024 * 1024
+}
+
+fn default_max_files() -> usize {
+    10
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for LogRotationSettings {
+    fn default() -> Self {
+        LogRotationSettings {
+            max_file_bytes: default_max_file_bytes(),
+            max_files: default_max_files(),
+            max_total_bytes: None,
+            daily: default_true(),
+            compress: default_true(),
+        }
+    }
+}
+
+/// Name of the active log file at `now`.
+pub fn active_log_file_name(
+    date_format: &str,
+    settings: &LogRotationSettings,
+    now: DateTime<Local>,
+) -> String {
+    if settings.daily {
+        now.format(date_format).to_string()
+    } else {
+        UNDATED_LOG_FILE.to_string()
+    }
+}
+
+/// Whether `name` is a CLI log file: an active `cli.log` or `<date>-cli.log`
+/// file or a rotated one, possibly compressed. Other files of the directory,
+/// `.log` ones included, are left alone.
+fn is_log_file(name: &str) -> bool {
+    let is_active = |name: &str| {
+        name.strip_suffix(UNDATED_LOG_FILE)
+            .is_some_and(|date| date.is_empty() || date.ends_with('-'))
+    };
+    if is_active(name) {
+        return true;
+    }
+    let name = name.strip_suffix(".gz").unwrap_or(name);
+    match name.rsplit_once('.') {
+        Some((active, suffix)) => {
+            is_active(active)
+                && !suffix.is_empty()
+                && suffix.chars().all(|c| c.is_ascii_digit() || c == 'T')
+        }
+        None => false,
+    }
+}
+
+fn is_rotated_uncompressed(name: &str) -> bool {
+    is_log_file(name) && !name.ends_with(".log") && !name.ends_with(".gz")
+}
+
+struct LogFile {
+    path: PathBuf,
+    modified: SystemTime,
+    size: u64,
+}
+
+/// Log files in `dir` other than `active`, newest first.
+fn inactive_log_files(dir: &Path, active: &Path) -> io::Result<Vec<LogFile>> {
+    let mut files = Vec::new();
+    for entry in dir.read_dir()?.flatten() {
+        let path = entry.path();
+        let is_log = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(is_log_file);
+        if !is_log || path == active {
+            continue;
+        }
+        // Files deleted since listing the directory are skipped
+        if let Ok(metadata) = entry.metadata() {
+            if metadata.is_file() {
+                files.push(LogFile {
+                    path,
+                    modified: metadata.modified()?,
+                    size: metadata.len(),
+                });
+            }
+        }
+    }
+    files.sort_by(|a, b| b.modified.cmp(&a.modified));
+    Ok(files)
+}
+
+/// Deletes the oldest log files of `dir` beyond the retention limits and
+/// returns them. `active` is never deleted but counts toward the limits.
+pub fn prune_logs(
+    dir: &Path,
+    active: &Path,
+    settings: &LogRotationSettings,
+    now: SystemTime,
+) -> io::Result<Vec<PathBuf>> {
+    let guard = ROTATION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let cut_off = now - MAX_LOG_AGE;
+    let mut kept = 1;
+    let mut total = fs::metadata(active).map(|m| m.len()).unwrap_or(0);
+    let mut removed = Vec::new();
+    let mut failures = Vec::new();
+    for file in inactive_log_files(dir, active)? {
+        let within_limits = file.modified >= cut_off
+            && kept < settings.max_files
+            && settings
+                .max_total_bytes
+                .is_none_or(|max| total + file.size <= max);
+        if within_limits {
+            kept += 1;
+            total += file.size;
+            continue;
+        }
+        match fs::remove_file(&file.path) {
+            Ok(()) => removed.push(file.path),
+            Err(e) => failures.push((file.path, e)),
+        }
+    }
+
+    // Logging may need to rotate, which takes the lock
+    drop(guard);
+    for (path, e) in failures {
+        warn!("Failed to delete old log file {:?}. {}", path, e);
+    }
+    Ok(removed)
+}
+
+/// Gzips `path` into `<path>.gz` and deletes it.
+fn compress(path: &Path) -> io::Result<PathBuf> {
+    let mut compressed_name = path.as_os_str().to_owned();
+    compressed_name.push(".gz");
+    let compressed = PathBuf::from(compressed_name);
+
+    let original = File::open(path)?;
+    // Keep the time of the rotation, files are ordered by it
+    let modified = original.metadata()?.modified()?;
+    let mut encoder = GzEncoder::new(File::create(&compressed)?, Compression::default());
+    io::copy(&mut &original, &mut encoder)?;
+    let compressed_file = encoder.finish()?;
+    compressed_file.set_modified(modified)?;
+    compressed_file.sync_all()?;
+    fs::remove_file(path)?;
+    Ok(compressed)
+}
+
+/// Compresses the rotated files of `dir` left uncompressed, then prunes.
+fn housekeeping(dir: &Path, active: &Path, settings: &LogRotationSettings) {
+    if settings.compress {
+        match inactive_log_files(dir, active) {
+            Ok(files) => {
+                for file in files {
+                    let rotated = file
+                        .path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(is_rotated_uncompressed);
+                    if rotated {
+                        if let Err(e) = compress(&file.path) {
+                            warn!("Failed to compress log file {:?}. {}", file.path, e);
+                        }
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to read log directory {:?}. {}", dir, e),
+        }
+    }
+    if let Err(e) = prune_logs(dir, active, settings, SystemTime::now()) {
+        warn!("Failed to prune log directory {:?}. {}", dir, e);
+    }
+}
+
+/// Writes to the active log file of `dir`, rotating it when it grows past
+/// `max_file_bytes`.
+pub struct RotatingWriter {
+    dir: PathBuf,
+    date_format: String,
+    settings: LogRotationSettings,
+}
+
+impl RotatingWriter {
+    pub fn new(dir: PathBuf, date_format: String, settings: LogRotationSettings) -> Self {
+        Self {
+            dir,
+            date_format,
+            settings,
+        }
+    }
+
+    fn active_path(&self) -> PathBuf {
+        self.dir.join(active_log_file_name(
+            &self.date_format,
+            &self.settings,
+            Local::now(),
+        ))
+    }
+
+    /// Compresses and prunes in the background, on startup and after each
+    /// rotation.
+    pub fn spawn_housekeeping(&self, delay: Duration) {
+        let dir = self.dir.clone();
+        let active = self.active_path();
+        let settings = self.settings.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            housekeeping(&dir, &active, &settings);
+        });
+    }
+
+    /// Renames `active` out of the way if it is still over the limit.
+    fn rotate(&self, active: &Path) -> io::Result<()> {
+        {
+            let _guard = ROTATION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            // Another thread may have rotated it while we waited
+            if fs::metadata(active)?.len() < self.settings.max_file_bytes {
+                return Ok(());
+            }
+            let mut rotated = active.as_os_str().to_owned();
+            rotated.push(format!(".{}", Local::now().format(ROTATED_SUFFIX_FORMAT)));
+            fs::rename(active, PathBuf::from(rotated))?;
+        }
+        self.spawn_housekeeping(COMPRESS_DELAY);
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for RotatingWriter {
+    type Writer = File;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        let active = self.active_path();
+        let full = fs::metadata(&active).is_ok_and(|m| m.len() >= self.settings.max_file_bytes);
+        if full {
+            if let Err(e) = self.rotate(&active) {
+                eprintln!("Failed to rotate log file {active:?}: {e}");
+            }
+        }
+
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(active)
+            .expect("Failed to open log file")
+    }
+}
+
+/// Log files of `dir` other than `active` possibly holding entries since
+/// `since`, oldest first.
+pub fn log_files_since(dir: &Path, active: &Path, since: DateTime<Utc>) -> Vec<PathBuf> {
+    let since = SystemTime::from(since);
+    let mut files: Vec<_> = inactive_log_files(dir, active)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|file| file.modified >= since)
+        .map(|file| file.path)
+        .collect();
+    files.reverse();
+    files
+}
+
+/// Lines of a log file, decompressing rotated `.gz` files.
+pub fn read_log_lines(path: &Path) -> io::Result<Vec<String>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn Read> = if path.extension().is_some_and(|ext| ext == "gz") {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    BufReader::new(reader).lines().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_log(dir: &Path, name: &str, size: usize, age_secs: u64, now: SystemTime) -> PathBuf {
+        let path = dir.join(name);
+        let file = File::create(&path).unwrap();
+        (&file).write_all(&vec![b'x'; size]).unwrap();
+        file.set_modified(now - Duration::from_secs(age_secs))
+            .unwrap();
+        path
+    }
+
+    fn names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<_> = dir
+            .read_dir()
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_is_log_file() {
+        assert!(is_log_file("2025-11-25-cli.log"));
+        assert!(is_log_file("2025-11-25-cli.log.20251125T101500123"));
+        assert!(is_log_file("2025-11-25-cli.log.20251125T101500123.gz"));
+        assert!(!is_log_file("config.toml"));
+        assert!(!is_log_file("2025-11-25-cli.log.bak"));
+        assert!(!is_log_file("archive.gz"));
+        assert!(is_log_file("cli.log"));
+        assert!(!is_log_file("temporal.log"));
+        assert!(!is_log_file("mycli.log"));
+        assert!(!is_log_file("temporal.log.20251125T101500123.gz"));
+        assert!(is_rotated_uncompressed("cli.log.20251125T101500123"));
+        assert!(!is_rotated_uncompressed("cli.log.20251125T101500123.gz"));
+        assert!(!is_rotated_uncompressed("cli.log"));
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let active = write_log(dir.path(), "cli.log", 10, 0, now);
+        write_log(dir.path(), "cli.log.20251125T100000000.gz", 10, 10, now);
+        write_log(dir.path(), "cli.log.20251125T090000000.gz", 10, 20, now);
+        write_log(dir.path(), "cli.log.20251125T080000000", 10, 30, now);
+        write_log(dir.path(), "settings.toml", 10, 40, now);
+
+        let settings = LogRotationSettings {
+            max_files: 3,
+            ..LogRotationSettings::default()
+        };
+        let removed = prune_logs(dir.path(), &active, &settings, now).unwrap();
+
+        assert_eq!(removed, vec![dir.path().join("cli.log.20251125T080000000")]);
+        assert_eq!(
+            names(dir.path()),
+            [
+                "cli.log",
+                "cli.log.20251125T090000000.gz",
+                "cli.log.20251125T100000000.gz",
+                "settings.toml",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prune_by_total_size_and_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let active = write_log(dir.path(), "2025-11-25-cli.log", 40, 0, now);
+        write_log(dir.path(), "2025-11-24-cli.log", 30, 60, now);
+        write_log(dir.path(), "2025-11-23-cli.log", 40, 120, now);
+        write_log(
+            dir.path(),
+            "2025-11-01-cli.log",
+            1,
+            MAX_LOG_AGE.as_secs() + 1,
+            now,
+        );
+        // Not a CLI log file
+        write_log(
+            dir.path(),
+            "temporal.log",
+            1,
+            MAX_LOG_AGE.as_secs() + 1,
+            now,
+        );
+
+        let settings = LogRotationSettings {
+            max_total_bytes: Some(100),
+            ..LogRotationSettings::default()
+        };
+        prune_logs(dir.path(), &active, &settings, now).unwrap();
+
+        assert_eq!(
+            names(dir.path()),
+            ["2025-11-24-cli.log", "2025-11-25-cli.log", "temporal.log"]
+        );
+    }
+
+    #[test]
+    fn test_prune_never_deletes_active_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let active = write_log(dir.path(), "cli.log", 500, MAX_LOG_AGE.as_secs() * 2, now);
+        write_log(dir.path(), "cli.log.20251125T100000000.gz", 10, 10, now);
+
+        let settings = LogRotationSettings {
+            max_files: 1,
+            max_total_bytes: Some(100),
+            ..LogRotationSettings::default()
+        };
+        prune_logs(dir.path(), &active, &settings, now).unwrap();
+
+        assert_eq!(names(dir.path()), ["cli.log"]);
+    }
+
+    #[test]
+    fn test_rotation_compresses_and_reads_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = LogRotationSettings {
+            max_file_bytes: 10,
+            daily: false,
+            ..LogRotationSettings::default()
+        };
+        let writer = RotatingWriter::new(
+            dir.path().to_path_buf(),
+            "%Y-%m-%d-cli.log".to_string(),
+            settings.clone(),
+        );
+        let since = Utc::now() - chrono::Duration::minutes(1);
+
+        writer.make_writer().write_all(b"first line\n").unwrap();
+        writer.make_writer().write_all(b"second line\n").unwrap();
+
+        let active = dir.path().join(UNDATED_LOG_FILE);
+        housekeeping(dir.path(), &active, &settings);
+        let files = names(dir.path());
+        assert_eq!(files.len(), 2, "{files:?}");
+        assert!(files[1].starts_with("cli.log.") && files[1].ends_with(".gz"));
+
+        let mut files = log_files_since(dir.path(), &active, since);
+        files.push(active.clone());
+        let lines: Vec<String> = files
+            .iter()
+            .flat_map(|path| read_log_lines(path).unwrap())
+            .collect();
+        assert_eq!(lines, ["first line", "second line"]);
+
+        // Files last written before `since` are skipped
+        let later = Utc::now() + chrono::Duration::minutes(1);
+        assert!(log_files_since(dir.path(), &active, later).is_empty());
+    }
+}
@@ -15,6 +15,7 @@
 //! - `LoggerLevel`: An enumeration representing the different levels of logging: DEBUG, INFO, WARN, and ERROR.
 //! - `LoggerSettings`: A struct that holds the settings for the logger, including level and output options.
 //! - `setup_logging`: A function used to set up the logging system with the provided settings.
+//! - `set_log_level`: Changes the level of every local layer while the process runs.
 //!
 //! ## Features
 //!
@@ -66,12 +67,16 @@
 //! ```
 
 use serde::Deserialize;
-use std::sync::OnceLock;
-use std::time::{Duration, SystemTime};
-use tracing::warn;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 use tracing_subscriber::filter::LevelFilter;
-use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::format::{self, FormatEvent, FormatFields, JsonFields};
+use tracing_subscriber::fmt::{FmtContext, FormattedFields, MakeWriter};
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::reload;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
 
@@ -79,14 +84,18 @@ use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::logs::{BatchLogProcessor, SdkLoggerProvider};
 
-use crate::utilities::constants::NO_ANSI;
+use crate::utilities::constants::{JSON_LOGS, NO_ANSI};
 use std::sync::atomic::Ordering;
 
+use super::log_rotation::{LogRotationSettings, RotatingWriter};
 use super::settings::user_directory;
 
 /// Static storage for the OTLP log provider, used for shutdown.
 static LOG_PROVIDER: OnceLock<SdkLoggerProvider> = OnceLock::new();
 
+/// Level control of the layers installed by [`setup_logging`].
+static LOG_LEVEL: LogLevelControl = LogLevelControl::new();
+
 // # STRUCTURED LOGGING INSTRUMENTATION GUIDE
 //
 // This section explains how to instrument code with structured logging using span fields.
@@ -283,6 +292,8 @@ pub enum LoggerLevel {
     Warn,
     #[serde(alias = "ERROR", alias = "error")]
     Error,
+    #[serde(alias = "TRACE", alias = "trace")]
+    Trace,
 }
 
 impl LoggerLevel {
@@ -292,8 +303,119 @@ impl LoggerLevel {
             LoggerLevel::Info => LevelFilter::INFO,
             LoggerLevel::Warn => LevelFilter::WARN,
             LoggerLevel::Error => LevelFilter::ERROR,
+            LoggerLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LogLevelError {
+    #[error("'{0}' is not a log level, expected one of error, warn, info, debug or trace")]
+    InvalidLevel(String),
+
+    #[error("logging is not set up for runtime level changes")]
+    NotReloadable,
+}
+
+type LevelReloader = Box<dyn Fn(LevelFilter) -> Result<(), reload::Error> + Send + Sync>;
+
+/// Changes the level of the layers it made reloadable, without restarting.
+pub struct LogLevelControl {
+    reloaders: Mutex<Vec<LevelReloader>>,
+    level: Mutex<Option<LevelFilter>>,
+}
+
+impl LogLevelControl {
+    pub const fn new() -> Self {
+        LogLevelControl {
+            reloaders: Mutex::new(Vec::new()),
+            level: Mutex::new(None),
         }
     }
+
+    /// Wraps `filter` so that [`LogLevelControl::set_level`] can replace it.
+    pub fn reloadable<S>(&self, filter: EnvFilter) -> reload::Layer<EnvFilter, S>
+    where
+        S: tracing::Subscriber + 'static,
+    {
+        let (layer, handle) = reload::Layer::new(filter);
+        self.reloaders.lock().unwrap().push(Box::new(move |level| {
+            handle.reload(EnvFilter::new(level.to_string()))
+        }));
+        layer
+    }
+
+    /// Sets the level of every reloadable layer still in use. This replaces
+    /// their filters, `RUST_LOG` directives included.
+    pub fn set_level(&self, level: LevelFilter) -> Result<(), LogLevelError> {
+        let mut reloaders = self.reloaders.lock().unwrap();
+        // Layers of a dropped subscriber can't be reloaded anymore
+        reloaders.retain(|reload| !matches!(reload(level), Err(e) if e.is_dropped()));
+        if reloaders.is_empty() {
+            return Err(LogLevelError::NotReloadable);
+        }
+        *self.level.lock().unwrap() = Some(level);
+        Ok(())
+    }
+
+    /// The level last set, `None` before logging is set up.
+    pub fn level(&self) -> Option<LevelFilter> {
+        *self.level.lock().unwrap()
+    }
+
+    fn init_level(&self, level: LevelFilter) {
+        *self.level.lock().unwrap() = Some(level);
+    }
+}
+
+impl Default for LogLevelControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sets the level of the local log layers, see [`LogLevelControl::set_level`].
+pub fn set_log_level(level: LevelFilter) -> Result<(), LogLevelError> {
+    LOG_LEVEL.set_level(level)
+}
+
+/// The current level of the local log layers.
+pub fn current_log_level() -> Option<LevelFilter> {
+    LOG_LEVEL.level()
+}
+
+/// Parses `error`, `warn`, `info`, `debug` or `trace`, in any case.
+pub fn parse_log_level(level: &str) -> Result<LevelFilter, LogLevelError> {
+    match level.trim().to_lowercase().as_str() {
+        "error" => Ok(LevelFilter::ERROR),
+        "warn" => Ok(LevelFilter::WARN),
+        "info" => Ok(LevelFilter::INFO),
+        "debug" => Ok(LevelFilter::DEBUG),
+        "trace" => Ok(LevelFilter::TRACE),
+        _ => Err(LogLevelError::InvalidLevel(level.trim().to_string())),
+    }
+}
+
+/// The level after `level` in the cycle error, warn, info, debug, trace.
+pub fn next_log_level(level: LevelFilter) -> LevelFilter {
+    match level {
+        LevelFilter::ERROR => LevelFilter::WARN,
+        LevelFilter::WARN => LevelFilter::INFO,
+        LevelFilter::INFO => LevelFilter::DEBUG,
+        LevelFilter::DEBUG => LevelFilter::TRACE,
+        _ => LevelFilter::ERROR,
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoggerFormat {
+    /// Human-readable lines
+    #[default]
+    #[serde(alias = "TEXT", alias = "text")]
+    Text,
+    /// One JSON object per event, for log pipelines
+    #[serde(alias = "JSON", alias = "json")]
+    Json,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -306,6 +428,12 @@ pub struct LoggerSettings {
     pub stdout: bool,
     #[serde(default = "default_no_ansi")]
     pub no_ansi: bool,
+    /// Format of the file or stdout logs, `-v` console output stays text
+    #[serde(default)]
+    pub format: LoggerFormat,
+    /// Rotation and retention of the log files
+    #[serde(default)]
+    pub rotation: LogRotationSettings,
     /// OTLP gRPC endpoint for structured logs (e.g., "http://localhost:4317")
     /// When set, exports spans/logs to OTLP collector via gRPC with local logging
     #[serde(default)]
@@ -335,124 +463,284 @@ impl Default for LoggerSettings {
             level: default_log_level(),
             stdout: default_log_stdout(),
             no_ansi: default_no_ansi(),
+            format: LoggerFormat::default(),
+            rotation: LogRotationSettings::default(),
             otlp_endpoint: None,
         }
     }
 }
 
-// House-keeping: delete log files older than 7 days.
-//
-// Rationale for WARN vs INFO
-// --------------------------------
-// 1.  Any failure here (e.g. cannot read directory or metadata) prevents log-rotation
-//     which can silently fill disks.
-// 2.  According to our logging guidelines INFO is "things working as expected", while
-//     WARN is for unexpected situations that *might* become a problem.
-// 3.  Therefore we upgraded the two failure branches (`warn!`) below to highlight
-//     these issues in production without terminating execution.
-//
-// Errors are still swallowed so that logging setup never aborts the CLI, but we emit
-// WARN to make operators aware of the problem.
-fn clean_old_logs() {
-    let cut_off = SystemTime::now() - Duration::from_secs(7 * 24 * 60 * 60);
-
-    let dir_path = match user_directory() {
-        Ok(p) => p,
-        Err(_) => return,
-    };
-    if let Ok(dir) = dir_path.read_dir() {
-        for entry in dir.flatten() {
-            if entry.path().extension().is_some_and(|ext| ext == "log") {
-                match entry.metadata().and_then(|md| md.modified()) {
-                    // Smaller time means older than the cut_off
-                    Ok(t) if t < cut_off => {
-                        let _ = std::fs::remove_file(entry.path());
-                    }
-                    Ok(_) => {}
-                    // Escalated to WARN to surface unexpected FS errors encountered
-                    // during housekeeping.
-                    Err(e) => {
-                        // Escalated to warn! — inability to read file metadata may indicate FS issues
-                        warn!(
-                            "Failed to read modification time for {:?}. {}",
-                            entry.path(),
-                            e
-                        )
-                    }
-                }
-            }
-        }
-    } else {
-        // Directory unreadable: surface as warn instead of info so users notice
-        // Emitting WARN instead of INFO: inability to read the log directory means
-        // housekeeping could not run at all, which can later cause disk-space issues.
-        warn!("failed to read directory")
+// House-keeping: compress rotated log files and delete the ones beyond the
+// retention limits, in the background so that startup isn't slowed down.
+fn clean_old_logs(settings: &LoggerSettings) {
+    if let Ok(dir) = user_directory() {
+        RotatingWriter::new(
+            dir,
+            settings.log_file_date_format.clone(),
+            settings.rotation.clone(),
+        )
+        .spawn_housekeeping(Duration::ZERO);
     }
 }
 
-/// Custom MakeWriter that creates log files with user-specified date format
+/// Creates the rotating file appender for `~/.moose`
 ///
-/// This maintains backward compatibility with fern's DateBased rotation by allowing
-/// custom date format strings like "%Y-%m-%d-cli.log" to produce "2025-11-25-cli.log"
-struct DateBasedWriter {
-    date_format: String,
+/// The active file is named after the configured date format, e.g.
+/// "%Y-%m-%d-cli.log" produces "2025-11-25-cli.log", and is rotated according
+/// to the rotation settings.
+fn create_rolling_file_appender(settings: &LoggerSettings) -> RotatingWriter {
+    // HOME was already validated during CLI startup in setup_user_directory()
+    RotatingWriter::new(
+        user_directory().expect("HOME was validated at startup"),
+        settings.log_file_date_format.clone(),
+        settings.rotation.clone(),
+    )
+}
+
+/// Console log verbosity requested on the command line with `-v`/`-vv` or `--quiet`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConsoleVerbosity {
+    /// Number of `-v` flags
+    pub verbose: u8,
+    pub quiet: bool,
 }
 
-impl DateBasedWriter {
-    fn new(date_format: String) -> Self {
-        Self { date_format }
+/// Level of the console log layer for `verbosity`, or `None` when no flag was
+/// given and the configured logger settings apply unchanged.
+///
+/// `-v` shows debug logs, `-vv` (or more) trace logs and `--quiet` errors only.
+pub fn console_level(verbosity: ConsoleVerbosity) -> Option<LevelFilter> {
+    match (verbosity.quiet, verbosity.verbose) {
+        (true, _) => Some(LevelFilter::ERROR),
+        (false, 0) => None,
+        (false, 1) => Some(LevelFilter::DEBUG),
+        (false, _) => Some(LevelFilter::TRACE),
     }
 }
 
-impl<'a> MakeWriter<'a> for DateBasedWriter {
-    type Writer = std::fs::File;
-
-    fn make_writer(&'a self) -> Self::Writer {
-        let formatted_name = chrono::Local::now().format(&self.date_format).to_string();
-        // HOME was already validated during CLI startup in setup_user_directory()
-        let file_path = user_directory()
-            .expect("HOME was validated at startup")
-            .join(&formatted_name);
-
-        std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(file_path)
-            .expect("Failed to open log file")
+/// Builds the filter for the console layer. Flags given on the command line win
+/// over `RUST_LOG` and the configured level, which keep applying to file logs.
+pub fn console_filter(settings: &LoggerSettings, verbosity: ConsoleVerbosity) -> EnvFilter {
+    match console_level(verbosity) {
+        Some(level) => EnvFilter::new(level.to_string()),
+        None => configured_filter(settings),
     }
 }
 
-/// Creates a rolling file appender with custom date format
-///
-/// This function creates a file appender that respects the configured date format
-/// for log file naming, maintaining backward compatibility with fern's DateBased rotation.
-fn create_rolling_file_appender(date_format: &str) -> DateBasedWriter {
-    DateBasedWriter::new(date_format.to_string())
+/// Filter from `RUST_LOG`, falling back to the configured level.
+fn configured_filter(settings: &LoggerSettings) -> EnvFilter {
+    EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(settings.level.to_tracing_level().to_string()))
+}
+
+/// Console layer shown next to file logging, only when `-v` was given.
+fn stderr_console_layer<S>(
+    settings: &LoggerSettings,
+    verbosity: ConsoleVerbosity,
+) -> Option<impl tracing_subscriber::Layer<S>>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    use tracing_subscriber::Layer;
+
+    (verbosity.verbose > 0 && !verbosity.quiet).then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_target(true)
+            .with_level(true)
+            .with_ansi(!settings.no_ansi)
+            .compact()
+            .with_filter(LOG_LEVEL.reloadable(console_filter(settings, verbosity)))
+    })
 }
 
-pub fn setup_logging(settings: &LoggerSettings) {
-    clean_old_logs();
+/// File or stdout log layer writing to `writer` in the configured format.
+fn local_layer<S, W>(
+    settings: &LoggerSettings,
+    writer: W,
+    ansi: bool,
+) -> Box<dyn tracing_subscriber::Layer<S> + Send + Sync>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    use tracing_subscriber::Layer;
+
+    match settings.format {
+        LoggerFormat::Text => tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_target(true)
+            .with_level(true)
+            .with_ansi(ansi)
+            .compact()
+            .boxed(),
+        LoggerFormat::Json => tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(false)
+            .fmt_fields(JsonFields::new())
+            .event_format(JsonLines)
+            .boxed(),
+    }
+}
+
+/// Formats each event as one JSON object: `timestamp`, `level`, `target` and
+/// `message`, followed by the event fields and the fields of its spans. Event
+/// fields win over span fields, and inner spans over outer ones.
+struct JsonLines;
+
+impl<S, N> FormatEvent<S, N> for JsonLines
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: format::Writer<'_>,
+        event: &tracing::Event<'_>,
+    ) -> std::fmt::Result {
+        let metadata = event.metadata();
+        let mut fields = serde_json::Map::new();
+        event.record(&mut JsonFieldVisitor(&mut fields));
+
+        let mut object = serde_json::Map::new();
+        object.insert(
+            "timestamp".to_string(),
+            chrono::Utc::now()
+                .to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+                .into(),
+        );
+        object.insert("level".to_string(), metadata.level().as_str().into());
+        object.insert("target".to_string(), metadata.target().into());
+        object.insert(
+            "message".to_string(),
+            fields.remove("message").unwrap_or_else(|| "".into()),
+        );
+        for (name, value) in fields {
+            object.entry(name).or_insert(value);
+        }
+
+        // Spans from the innermost out, their fields were formatted by `JsonFields`
+        for span in ctx.event_scope().into_iter().flatten() {
+            let extensions = span.extensions();
+            let Some(span_fields) = extensions.get::<FormattedFields<N>>() else {
+                continue;
+            };
+            if let Ok(serde_json::Value::Object(span_fields)) =
+                serde_json::from_str::<serde_json::Value>(span_fields.as_str())
+            {
+                for (name, value) in span_fields {
+                    object.entry(name).or_insert(value);
+                }
+            }
+        }
+
+        writeln!(writer, "{}", serde_json::Value::Object(object))
+    }
+}
+
+/// Collects the fields of an event into a JSON object.
+struct JsonFieldVisitor<'a>(&'a mut serde_json::Map<String, serde_json::Value>);
+
+impl tracing::field::Visit for JsonFieldVisitor<'_> {
+    fn record_f64(&mut self, field: &tracing::field::Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &tracing::field::Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_error(
+        &mut self,
+        field: &tracing::field::Field,
+        value: &(dyn std::error::Error + 'static),
+    ) {
+        self.0
+            .insert(field.name().to_string(), value.to_string().into());
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}").into());
+    }
+}
+
+/// Logs panics as error events, so that they are JSON lines too, before
+/// handing them to the previous hook.
+fn log_panics() {
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let location = info
+            .location()
+            .map(|location| location.to_string())
+            .unwrap_or_default();
+        let backtrace = Backtrace::capture();
+        if backtrace.status() == BacktraceStatus::Captured {
+            tracing::error!(target: "panic", location = %location, backtrace = %backtrace, "{}", message);
+        } else {
+            tracing::error!(target: "panic", location = %location, "{}", message);
+        }
+        previous_hook(info);
+    }));
+}
+
+pub fn setup_logging(settings: &LoggerSettings, verbosity: ConsoleVerbosity) {
+    clean_old_logs(settings);
 
     // Set global NO_ANSI flag for terminal display functions
     NO_ANSI.store(settings.no_ansi, Ordering::Relaxed);
 
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new(settings.level.to_tracing_level().to_string()));
+    // JSON logs on stdout must not be interleaved with styled messages
+    JSON_LOGS.store(
+        settings.format == LoggerFormat::Json && settings.stdout,
+        Ordering::Relaxed,
+    );
+    if settings.format == LoggerFormat::Json {
+        log_panics();
+    }
+
+    LOG_LEVEL
+        .init_level(console_level(verbosity).unwrap_or_else(|| settings.level.to_tracing_level()));
 
     // When OTLP is enabled, set up both OTLP export AND local logging
     if let Some(endpoint) = &settings.otlp_endpoint {
-        setup_otlp_with_local_logging(settings, endpoint, env_filter);
+        setup_otlp_with_local_logging(settings, endpoint, verbosity);
         return;
     }
 
     // Default: use fmt layer for file/stdout output
-    setup_fmt_logging(settings, env_filter);
+    setup_fmt_logging(settings, verbosity);
 }
 
 /// Sets up OTLP export with local logging (stdout or file).
 ///
 /// Creates both an OTLP bridge layer for remote export and a fmt layer for local output.
-fn setup_otlp_with_local_logging(settings: &LoggerSettings, endpoint: &str, env_filter: EnvFilter) {
+fn setup_otlp_with_local_logging(
+    settings: &LoggerSettings,
+    endpoint: &str,
+    verbosity: ConsoleVerbosity,
+) {
+    use tracing_subscriber::Layer;
+
     // Create OTLP exporter
     let log_exporter = opentelemetry_otlp::LogExporter::builder()
         .with_tonic()
@@ -481,35 +769,28 @@ fn setup_otlp_with_local_logging(settings: &LoggerSettings, endpoint: &str, env_
         return;
     }
 
-    let otel_bridge = OpenTelemetryTracingBridge::new(&log_provider);
+    // The exporter keeps the configured level, console flags don't affect it
+    let otel_bridge =
+        OpenTelemetryTracingBridge::new(&log_provider).with_filter(configured_filter(settings));
 
     // Create local layer based on stdout setting
     if settings.stdout {
-        let local_layer = tracing_subscriber::fmt::layer()
-            .with_writer(std::io::stdout)
-            .with_target(true)
-            .with_level(true)
-            .with_ansi(!settings.no_ansi)
-            .compact();
+        let local_layer = local_layer(settings, std::io::stdout, !settings.no_ansi)
+            .with_filter(LOG_LEVEL.reloadable(console_filter(settings, verbosity)));
 
         tracing_subscriber::registry()
-            .with(env_filter)
             .with(otel_bridge)
             .with(local_layer)
             .init();
     } else {
-        let file_appender = create_rolling_file_appender(&settings.log_file_date_format);
-        let local_layer = tracing_subscriber::fmt::layer()
-            .with_writer(file_appender)
-            .with_target(true)
-            .with_level(true)
-            .with_ansi(false)
-            .compact();
+        let file_appender = create_rolling_file_appender(settings);
+        let local_layer = local_layer(settings, file_appender, false)
+            .with_filter(LOG_LEVEL.reloadable(configured_filter(settings)));
 
         tracing_subscriber::registry()
-            .with(env_filter)
             .with(otel_bridge)
             .with(local_layer)
+            .with(stderr_console_layer(settings, verbosity))
             .init();
     }
 
@@ -517,34 +798,29 @@ fn setup_otlp_with_local_logging(settings: &LoggerSettings, endpoint: &str, env_
 }
 
 /// Sets up standard fmt logging (file or stdout).
-fn setup_fmt_logging(settings: &LoggerSettings, env_filter: EnvFilter) {
+///
+/// When logging to stdout, that layer is the console and follows `verbosity`.
+/// When logging to a file, the file keeps the configured level and `-v` adds a
+/// separate stderr layer.
+fn setup_fmt_logging(settings: &LoggerSettings, verbosity: ConsoleVerbosity) {
+    use tracing_subscriber::Layer;
+
     if settings.stdout {
-        let layer = tracing_subscriber::fmt::layer()
-            .with_writer(std::io::stdout)
-            .with_target(true)
-            .with_level(true)
-            .with_ansi(!settings.no_ansi)
-            .compact();
+        let layer = local_layer(settings, std::io::stdout, !settings.no_ansi)
+            .with_filter(LOG_LEVEL.reloadable(console_filter(settings, verbosity)));
 
-        tracing_subscriber::registry()
-            .with(env_filter)
-            .with(layer)
-            .init();
+        tracing_subscriber::registry().with(layer).init();
     } else {
         // For file output, explicitly disable ANSI codes regardless of no_ansi setting.
         // Files are not terminals and don't render colors. tracing-subscriber defaults
         // to ANSI=true, so we must explicitly set it to false for file writers.
-        let file_appender = create_rolling_file_appender(&settings.log_file_date_format);
-        let layer = tracing_subscriber::fmt::layer()
-            .with_writer(file_appender)
-            .with_target(true)
-            .with_level(true)
-            .with_ansi(false)
-            .compact();
+        let file_appender = create_rolling_file_appender(settings);
+        let layer = local_layer(settings, file_appender, false)
+            .with_filter(LOG_LEVEL.reloadable(configured_filter(settings)));
 
         tracing_subscriber::registry()
-            .with(env_filter)
             .with(layer)
+            .with(stderr_console_layer(settings, verbosity))
             .init();
     }
 }
@@ -997,6 +1273,189 @@ mod tests {
         );
     }
 
+    fn json_lines(emit: impl FnOnce()) -> Vec<serde_json::Value> {
+        let mock_writer = MockWriter::new();
+        let settings = LoggerSettings {
+            format: LoggerFormat::Json,
+            ..LoggerSettings::default()
+        };
+        let subscriber =
+            tracing_subscriber::registry().with(local_layer(&settings, mock_writer.clone(), false));
+
+        tracing::subscriber::with_default(subscriber, emit);
+
+        mock_writer
+            .get_output()
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("log line is not JSON"))
+            .collect()
+    }
+
+    #[test]
+    fn test_json_format_flattens_span_fields() {
+        let lines = json_lines(|| {
+            let outer = tracing::info_span!("outer", request_id = "req-1", resource_name = "outer");
+            let _outer = outer.enter();
+            test_function_with_span("UserEvent");
+            tracing::warn!(retries = 3, "Without inner span");
+        });
+
+        assert_eq!(lines.len(), 2);
+        let entry = &lines[0];
+        assert!(entry["timestamp"]
+            .as_str()
+            .is_some_and(|ts| ts.ends_with('Z')));
+        assert_eq!(entry["level"], "INFO");
+        assert_eq!(entry["target"], "moose_cli::cli::logger::tests");
+        assert_eq!(entry["message"], "Processing request");
+        assert_eq!(entry["context"], "runtime");
+        assert_eq!(entry["resource_type"], "stream");
+        // The inner span wins
+        assert_eq!(entry["resource_name"], "UserEvent");
+        assert_eq!(entry["request_id"], "req-1");
+        assert!(entry.get("span").is_none());
+
+        let entry = &lines[1];
+        assert_eq!(entry["level"], "WARN");
+        assert_eq!(entry["message"], "Without inner span");
+        assert_eq!(entry["retries"], 3);
+        assert_eq!(entry["resource_name"], "outer");
+        assert!(entry.get("context").is_none());
+    }
+
+    #[test]
+    fn test_json_format_without_spans() {
+        let lines = json_lines(|| tracing::error!(error = %"boom", "Failed"));
+
+        assert_eq!(lines.len(), 1);
+        let entry = lines[0].as_object().unwrap();
+        let keys: Vec<_> = entry.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["timestamp", "level", "target", "message", "error"]);
+        assert_eq!(entry["error"], "boom");
+    }
+
+    #[test]
+    fn test_json_format_logs_display_messages() {
+        use crate::cli::display::message::MessageType;
+        use crate::cli::display::message_display::log_message;
+
+        let lines = json_lines(|| {
+            log_message(MessageType::Warning, " Deploy ", "took\nlong");
+            log_message(MessageType::Success, "Deploy", "done");
+        });
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["level"], "WARN");
+        assert_eq!(lines[0]["message"], "took long");
+        assert_eq!(lines[0]["action"], "Deploy");
+        assert_eq!(lines[0]["message_type"], "Warning");
+        assert_eq!(lines[1]["level"], "INFO");
+        assert_eq!(lines[1]["message_type"], "Success");
+    }
+
+    #[test]
+    fn test_logger_format_parsing() {
+        let parse = |format: &str| {
+            serde_json::from_value::<LoggerSettings>(serde_json::json!({ "format": format }))
+                .map(|settings| settings.format)
+                .ok()
+        };
+        assert_eq!(parse("json"), Some(LoggerFormat::Json));
+        assert_eq!(parse("Json"), Some(LoggerFormat::Json));
+        assert_eq!(parse("text"), Some(LoggerFormat::Text));
+        assert_eq!(parse("yaml"), None);
+        assert_eq!(LoggerSettings::default().format, LoggerFormat::Text);
+    }
+
+    #[test]
+    fn test_console_level_from_flags() {
+        let verbosity = |verbose, quiet| ConsoleVerbosity { verbose, quiet };
+
+        assert_eq!(console_level(ConsoleVerbosity::default()), None);
+        assert_eq!(console_level(verbosity(1, false)), Some(LevelFilter::DEBUG));
+        assert_eq!(console_level(verbosity(2, false)), Some(LevelFilter::TRACE));
+        assert_eq!(console_level(verbosity(5, false)), Some(LevelFilter::TRACE));
+        assert_eq!(console_level(verbosity(0, true)), Some(LevelFilter::ERROR));
+    }
+
+    #[test]
+    fn test_console_filter_uses_flag_level() {
+        let settings = LoggerSettings::default();
+
+        let filter = console_filter(
+            &settings,
+            ConsoleVerbosity {
+                verbose: 2,
+                quiet: false,
+            },
+        );
+        assert_eq!(filter.max_level_hint(), Some(LevelFilter::TRACE));
+
+        let filter = console_filter(
+            &settings,
+            ConsoleVerbosity {
+                verbose: 0,
+                quiet: true,
+            },
+        );
+        assert_eq!(filter.max_level_hint(), Some(LevelFilter::ERROR));
+    }
+
+    #[test]
+    fn test_reloadable_layer_follows_level_changes() {
+        use tracing_subscriber::Layer;
+
+        let control = LogLevelControl::new();
+        let mock_writer = MockWriter::new();
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(mock_writer.clone())
+            .with_ansi(false)
+            .with_filter(control.reloadable(EnvFilter::new("info")));
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("hidden debug");
+            tracing::info!("shown info");
+
+            control.set_level(LevelFilter::DEBUG).unwrap();
+            tracing::debug!("shown debug");
+
+            control.set_level(LevelFilter::ERROR).unwrap();
+            tracing::warn!("hidden warn");
+        });
+
+        let output = mock_writer.get_output();
+        assert!(!output.contains("hidden"), "{output}");
+        assert!(output.contains("shown info"), "{output}");
+        assert!(output.contains("shown debug"), "{output}");
+        assert_eq!(control.level(), Some(LevelFilter::ERROR));
+
+        // The subscriber is gone, nothing is left to reload
+        assert!(matches!(
+            control.set_level(LevelFilter::INFO),
+            Err(LogLevelError::NotReloadable)
+        ));
+        assert_eq!(control.level(), Some(LevelFilter::ERROR));
+    }
+
+    #[test]
+    fn test_log_level_parsing_and_cycle() {
+        assert_eq!(parse_log_level("DEBUG").unwrap(), LevelFilter::DEBUG);
+        assert_eq!(parse_log_level(" warn\n").unwrap(), LevelFilter::WARN);
+        assert!(matches!(
+            parse_log_level("verbose"),
+            Err(LogLevelError::InvalidLevel(level)) if level == "verbose"
+        ));
+
+        let mut level = LevelFilter::ERROR;
+        let mut cycle = vec![];
+        for _ in 0..5 {
+            level = next_log_level(level);
+            cycle.push(level.to_string());
+        }
+        assert_eq!(cycle, vec!["warn", "info", "debug", "trace", "error"]);
+    }
+
     #[test]
     fn test_p0_constants_exported() {
         // Verify context constants are accessible
@@ -11,6 +11,9 @@
 //! - MCP tools hold a read lock for the full tool execution
 //! - Multiple MCP tools can read concurrently when no processing is occurring
 //!
+//! It also tracks the file watcher's recomputes of the infrastructure: changes
+//! landing during a recompute queue exactly one follow-up recompute.
+//!
 //! ## Usage
 //!
 //! ```rust
@@ -24,6 +27,7 @@
 //! // Read infra state while watcher mutations are paused
 //! ```
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -40,6 +44,10 @@ pub struct ProcessingCoordinator {
     /// RwLock for synchronization
     /// Write lock held during processing, read lock held during MCP tool execution
     lock: Arc<RwLock<()>>,
+    /// Set while the file watcher recomputes the infrastructure
+    recomputing: Arc<AtomicBool>,
+    /// Set when changes land during a recompute
+    follow_up: Arc<AtomicBool>,
 }
 
 impl ProcessingCoordinator {
@@ -47,6 +55,8 @@ impl ProcessingCoordinator {
     pub fn new() -> Self {
         Self {
             lock: Arc::new(RwLock::new(())),
+            recomputing: Arc::new(AtomicBool::new(false)),
+            follow_up: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -92,6 +102,28 @@ impl ProcessingCoordinator {
     pub async fn wait_for_stable_state(&self) {
         let _guard = self.acquire_stable_state_guard().await;
     }
+
+    /// Mark the start of a recompute of the infrastructure by the file watcher,
+    /// returning a guard that marks its end when dropped.
+    pub fn begin_recompute(&self) -> RecomputeGuard {
+        self.recomputing.store(true, Ordering::SeqCst);
+        RecomputeGuard {
+            recomputing: self.recomputing.clone(),
+        }
+    }
+
+    /// Queue a follow-up recompute if one is in progress.
+    ///
+    /// Returns whether this call queued it: however many changes land during
+    /// a recompute, a single follow-up is queued.
+    pub fn queue_follow_up(&self) -> bool {
+        self.recomputing.load(Ordering::SeqCst) && !self.follow_up.swap(true, Ordering::SeqCst)
+    }
+
+    /// Take the queued follow-up recompute, if any.
+    pub fn take_follow_up(&self) -> bool {
+        self.follow_up.swap(false, Ordering::SeqCst)
+    }
 }
 
 impl Default for ProcessingCoordinator {
@@ -116,6 +148,18 @@ impl Drop for ProcessingGuard {
     }
 }
 
+/// RAII guard marking a recompute of the infrastructure as in progress.
+#[must_use]
+pub struct RecomputeGuard {
+    recomputing: Arc<AtomicBool>,
+}
+
+impl Drop for RecomputeGuard {
+    fn drop(&mut self) {
+        self.recomputing.store(false, Ordering::SeqCst);
+    }
+}
+
 /// RAII guard that holds a read lock while MCP tools execute.
 ///
 /// While this guard is alive, watchers cannot acquire the write lock for
@@ -417,4 +461,23 @@ mod tests {
         rx.recv().await.unwrap();
         drop(stable_guard);
     }
+
+    #[test]
+    fn test_changes_during_recompute_queue_one_follow_up() {
+        let coordinator = ProcessingCoordinator::new();
+
+        // No recompute in progress: changes are debounced by the watcher
+        assert!(!coordinator.queue_follow_up());
+        assert!(!coordinator.take_follow_up());
+
+        let guard = coordinator.begin_recompute();
+        assert!(coordinator.queue_follow_up());
+        assert!(!coordinator.clone().queue_follow_up());
+        assert!(!coordinator.queue_follow_up());
+        drop(guard);
+
+        assert!(coordinator.take_follow_up());
+        assert!(!coordinator.take_follow_up());
+        assert!(!coordinator.queue_follow_up());
+    }
 }
@@ -0,0 +1,314 @@
+//! Diagnostics of the ClickHouse tables for `moose check olap`.
+//!
+//! Runs the same diagnostic providers as the MCP `get_issues` tool on the
+//! tables of the stored infrastructure map, so that they can be checked on a
+//! server without an MCP client. Error issues make the command fail, which lets
+//! a deploy be gated on them.
+
+use std::collections::{BTreeSet, HashMap};
+
+use super::{RoutineFailure, RoutineSuccess};
+use crate::cli::display::{show_table, Message};
+use crate::framework::core::infrastructure_map::InfrastructureMap;
+use crate::framework::core::partial_infrastructure_map::LifeCycle;
+use crate::framework::core::state_storage::{StateStorage, StateStorageBuilder};
+use crate::infrastructure::olap::clickhouse::diagnostics::{
+    run_diagnostics, Component, DiagnosticOptions, DiagnosticOutput, DiagnosticRequest, Severity,
+};
+use crate::project::Project;
+
+/// Runs the diagnostics of `options` on `tables`, or on every table of the
+/// infrastructure map when empty, and prints the issues as a table or JSON
+/// document. Fails when an issue has the Error severity.
+pub async fn check_olap(
+    project: &Project,
+    tables: &[String],
+    options: DiagnosticOptions,
+    json: bool,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let state_storage = StateStorageBuilder::from_config(project)
+        .clickhouse_config(Some(project.clickhouse_config.clone()))
+        .redis_url(Some(project.redis_config.effective_url()))
+        .build()
+        .await
+        .map_err(|e| {
+            RoutineFailure::new(
+                Message::new(
+                    "State Storage".to_string(),
+                    "Failed to build state storage".to_string(),
+                ),
+                e,
+            )
+        })?;
+    let infra_map = state_storage
+        .load_infrastructure_map()
+        .await
+        .map_err(|e| {
+            RoutineFailure::new(
+                Message::new(
+                    "Failed".to_string(),
+                    "to load the current state".to_string(),
+                ),
+                e,
+            )
+        })?
+        .ok_or_else(|| {
+            RoutineFailure::error(Message::new(
+                "Failed".to_string(),
+                "No state found, deploy the project first".to_string(),
+            ))
+        })?;
+
+    let request = diagnostic_request(
+        &infra_map,
+        &project.clickhouse_config.db_name,
+        tables,
+        options,
+    )
+    .map_err(|details| RoutineFailure::error(Message::new("Check".to_string(), details)))?;
+    let output = run_diagnostics(request, &project.clickhouse_config)
+        .await
+        .map_err(|e| RoutineFailure::error(Message::new("Check".to_string(), format!("{e}"))))?;
+
+    render_output(&output, json)
+}
+
+/// The request diagnosing the tables named `tables`, or every table when
+/// empty. Externally managed tables are left out, Moose doesn't operate them.
+/// Unknown names are an error listing the available tables.
+fn diagnostic_request(
+    infra_map: &InfrastructureMap,
+    default_database: &str,
+    tables: &[String],
+    options: DiagnosticOptions,
+) -> Result<DiagnosticRequest, String> {
+    let managed_tables = || {
+        infra_map
+            .tables
+            .values()
+            .filter(|table| table.life_cycle != LifeCycle::ExternallyManaged)
+    };
+
+    let unknown: Vec<&str> = tables
+        .iter()
+        .filter(|name| !managed_tables().any(|table| &table.name == *name))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        let available: BTreeSet<&str> = managed_tables().map(|table| table.name.as_str()).collect();
+        return Err(format!(
+            "Unknown tables: {}. Available tables: {}",
+            unknown.join(", "),
+            available.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    let mut components: Vec<_> = managed_tables()
+        .filter(|table| tables.is_empty() || tables.contains(&table.name))
+        .map(|table| {
+            let database = table
+                .database
+                .clone()
+                .unwrap_or_else(|| default_database.to_string());
+            let component = Component {
+                component_type: "table".to_string(),
+                name: table.name.clone(),
+                metadata: HashMap::from([("database".to_string(), database)]),
+            };
+            (component, table.engine.clone())
+        })
+        .collect();
+    components.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+
+    Ok(DiagnosticRequest {
+        components,
+        options,
+    })
+}
+
+/// Prints the issues of `output` and returns the result of the check.
+fn render_output(output: &DiagnosticOutput, json: bool) -> Result<RoutineSuccess, RoutineFailure> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(output).unwrap());
+    } else if !output.issues.is_empty() {
+        show_table(
+            "OLAP issues".to_string(),
+            vec![
+                "Severity".to_string(),
+                "Table".to_string(),
+                "Source".to_string(),
+                "Issue".to_string(),
+                "Suggested action".to_string(),
+            ],
+            output
+                .issues
+                .iter()
+                .map(|issue| {
+                    vec![
+                        format!("{:?}", issue.severity).to_lowercase(),
+                        issue.component.name.clone(),
+                        issue.source.clone(),
+                        issue.message.clone(),
+                        issue.suggested_action.clone(),
+                    ]
+                })
+                .collect(),
+        );
+    }
+
+    check_result(output)
+}
+
+/// Counts the issues of `output` by severity, failing when one is an error.
+fn check_result(output: &DiagnosticOutput) -> Result<RoutineSuccess, RoutineFailure> {
+    let count = |severity: &str| {
+        output
+            .summary
+            .by_severity
+            .get(severity)
+            .copied()
+            .unwrap_or(0)
+    };
+    let summary = format!(
+        "{} errors, {} warnings, {} info",
+        count("error"),
+        count("warning"),
+        count("info")
+    );
+
+    if output
+        .issues
+        .iter()
+        .any(|issue| issue.severity == Severity::Error)
+    {
+        Err(RoutineFailure::error(Message::new(
+            "Check".to_string(),
+            summary,
+        )))
+    } else {
+        Ok(RoutineSuccess::success(Message::new(
+            "Check".to_string(),
+            summary,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::table::{OrderBy, Table};
+    use crate::framework::core::infrastructure_map::{PrimitiveSignature, PrimitiveTypes};
+    use crate::infrastructure::olap::clickhouse::diagnostics::{InfrastructureType, Issue};
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+    use serde_json::Map;
+
+    const DB: &str = "local";
+
+    fn table(name: &str, database: Option<&str>) -> Table {
+        Table {
+            name: name.to_string(),
+            columns: vec![],
+            order_by: OrderBy::Fields(vec![]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: database.map(String::from),
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    fn issue(severity: Severity) -> Issue {
+        Issue {
+            severity,
+            source: "system.mutations".to_string(),
+            component: Component {
+                component_type: "table".to_string(),
+                name: "events".to_string(),
+                metadata: HashMap::new(),
+            },
+            error_type: "stuck_mutation".to_string(),
+            message: "Mutation is not done".to_string(),
+            details: Map::new(),
+            suggested_action: "Wait for the mutation".to_string(),
+            related_queries: vec![],
+        }
+    }
+
+    #[test]
+    fn test_diagnostic_request_selects_tables() {
+        let mut infra_map = InfrastructureMap::default();
+        let mut external = table("legacy", None);
+        external.life_cycle = LifeCycle::ExternallyManaged;
+        for table in [
+            table("users", Some("analytics")),
+            table("events", None),
+            external,
+        ] {
+            infra_map.tables.insert(table.id(DB), table);
+        }
+
+        let request =
+            diagnostic_request(&infra_map, DB, &[], DiagnosticOptions::default()).unwrap();
+        let components: Vec<_> = request
+            .components
+            .iter()
+            .map(|(c, _)| (c.name.as_str(), c.metadata["database"].as_str()))
+            .collect();
+        assert_eq!(components, vec![("events", DB), ("users", "analytics")]);
+
+        let request = diagnostic_request(
+            &infra_map,
+            DB,
+            &["users".to_string()],
+            DiagnosticOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(request.components.len(), 1);
+        assert_eq!(request.components[0].0.name, "users");
+
+        let err = diagnostic_request(
+            &infra_map,
+            DB,
+            &["users".to_string(), "sessions".to_string()],
+            DiagnosticOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "Unknown tables: sessions. Available tables: events, users"
+        );
+    }
+
+    #[test]
+    fn test_error_issues_fail_the_check() {
+        let warnings = DiagnosticOutput::new(
+            InfrastructureType::ClickHouse,
+            vec![issue(Severity::Warning)],
+        );
+        let success = check_result(&warnings).unwrap();
+        assert_eq!(success.message.details, "0 errors, 1 warnings, 0 info");
+
+        let errors = DiagnosticOutput::new(
+            InfrastructureType::ClickHouse,
+            vec![issue(Severity::Warning), issue(Severity::Error)],
+        );
+        let failure = check_result(&errors).unwrap_err();
+        assert_eq!(failure.message.details, "1 errors, 1 warnings, 0 info");
+    }
+}
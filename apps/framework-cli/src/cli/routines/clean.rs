@@ -1,45 +1,908 @@
+use std::fmt;
+use std::io::IsTerminal;
+
+use chrono::{DateTime, Utc};
+
+use crate::framework::core::drop_strategy::expired_dropped_tables;
+use crate::framework::core::state_storage::{ClickHouseStateStorage, StateStorageBuilder};
+use crate::framework::scripts::executor::task_queue_name;
+use crate::infrastructure::olap::clickhouse::queries::drop_table_query;
+use crate::infrastructure::olap::clickhouse::{create_client, run_query};
+use crate::infrastructure::orchestration::temporal_client::{
+    OpenWorkflow, TemporalWorkflowService, WorkflowService, WorkflowServiceError,
+};
+use crate::infrastructure::stream::kafka::client::{delete_topics, fetch_topics};
 use crate::utilities::docker::DockerClient;
-use crate::{cli::display::Message, cli::settings::Settings, project::Project};
+use crate::{
+    cli::display::{show_message_wrapper, Message, MessageType},
+    cli::settings::Settings,
+    cli::{is_non_interactive, prompt_user, Decision},
+    project::Project,
+};
 use tracing::info;
 
+use super::truncate_table::{list_all_tables, truncate_all_rows};
 use super::util::ensure_docker_running;
 use super::{RoutineFailure, RoutineSuccess};
 
-pub fn clean_project(
+/// A kind of local resource `moose clean` can tear down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanTarget {
+    Olap,
+    /// Tables renamed by the `rename` drop strategy, past their retention
+    Dropped,
+    Streams,
+    State,
+    Containers,
+}
+
+impl CleanTarget {
+    fn flag(self) -> &'static str {
+        match self {
+            CleanTarget::Olap => "--olap",
+            CleanTarget::Dropped => "--dropped",
+            CleanTarget::Streams => "--streams",
+            CleanTarget::State => "--state",
+            CleanTarget::Containers => "--containers",
+        }
+    }
+
+    /// Targets that lose data which cannot be recreated from the project code.
+    fn guarded_in_production(self) -> bool {
+        matches!(self, CleanTarget::Olap | CleanTarget::State)
+    }
+}
+
+/// Flags passed to `moose clean`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CleanOptions {
+    pub olap: bool,
+    pub dropped: bool,
+    pub streams: bool,
+    pub state: bool,
+    pub containers: bool,
+    pub all: bool,
+    pub dry_run: bool,
+    pub yes: bool,
+    pub i_know_what_im_doing: bool,
+    /// Leave the project's running workflows alone
+    pub keep_workflows: bool,
+    /// Terminate the running workflows instead of cancelling them
+    pub terminate_workflows: bool,
+}
+
+/// Resolves the flags into the targets to clean, in teardown order.
+///
+/// Containers always come last so OLAP, streams and state are still reachable
+/// while they are cleaned. Without any target flag only the containers are
+/// stopped, which is what `moose clean` has always done.
+pub fn clean_targets(options: &CleanOptions) -> Vec<CleanTarget> {
+    let none_selected = !(options.olap
+        || options.dropped
+        || options.streams
+        || options.state
+        || options.containers);
+    if options.all {
+        return vec![
+            CleanTarget::Olap,
+            CleanTarget::Streams,
+            CleanTarget::State,
+            CleanTarget::Containers,
+        ];
+    }
+    if none_selected {
+        return vec![CleanTarget::Containers];
+    }
+
+    [
+        (options.olap, CleanTarget::Olap),
+        (options.dropped, CleanTarget::Dropped),
+        (options.streams, CleanTarget::Streams),
+        (options.state, CleanTarget::State),
+        (options.containers, CleanTarget::Containers),
+    ]
+    .into_iter()
+    .filter_map(|(selected, target)| selected.then_some(target))
+    .collect()
+}
+
+/// A single teardown step, resolved against the running infrastructure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CleanAction {
+    StopWorkflows {
+        workflows: Vec<OpenWorkflow>,
+        terminate: bool,
+    },
+    TruncateTable {
+        database: String,
+        table: String,
+    },
+    DropTable {
+        database: String,
+        table: String,
+    },
+    DeleteTopic(String),
+    ClearState {
+        backend: String,
+    },
+    StopContainers {
+        containers: Vec<String>,
+    },
+}
+
+impl fmt::Display for CleanAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CleanAction::StopWorkflows {
+                workflows,
+                terminate,
+            } => {
+                let verb = if *terminate { "terminate" } else { "cancel" };
+                let ids: Vec<&str> = workflows
+                    .iter()
+                    .map(|workflow| workflow.workflow_id.as_str())
+                    .collect();
+                write!(f, "{verb} running workflows: {}", ids.join(", "))
+            }
+            CleanAction::TruncateTable { database, table } => {
+                write!(f, "truncate table {database}.{table}")
+            }
+            CleanAction::DropTable { database, table } => {
+                write!(f, "drop table {database}.{table}")
+            }
+            CleanAction::DeleteTopic(topic) => write!(f, "delete topic {topic}"),
+            CleanAction::ClearState { backend } => {
+                write!(f, "clear stored infrastructure map ({backend})")
+            }
+            CleanAction::StopContainers { containers } if containers.is_empty() => {
+                write!(f, "stop and remove project containers")
+            }
+            CleanAction::StopContainers { containers } => {
+                write!(f, "stop and remove containers: {}", containers.join(", "))
+            }
+        }
+    }
+}
+
+/// Lists the actions one per line, as shown for `--dry-run` and before confirming.
+pub fn describe_actions(actions: &[CleanAction]) -> String {
+    if actions.is_empty() {
+        return "Nothing to clean".to_string();
+    }
+    actions
+        .iter()
+        .map(|action| format!("  - {action}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn check_production_guard(
+    project: &Project,
+    targets: &[CleanTarget],
+    options: &CleanOptions,
+) -> Result<(), RoutineFailure> {
+    if !project.is_production || options.i_know_what_im_doing {
+        return Ok(());
+    }
+
+    let guarded: Vec<&str> = targets
+        .iter()
+        .filter(|target| target.guarded_in_production())
+        .map(|target| target.flag())
+        .collect();
+
+    if guarded.is_empty() {
+        return Ok(());
+    }
+
+    Err(RoutineFailure::error(Message::new(
+        "Clean".to_string(),
+        format!(
+            "Refusing to run {} in production. Pass --i-know-what-im-doing to delete production data",
+            guarded.join(" and ")
+        ),
+    )))
+}
+
+/// Drops the tables renamed by the `rename` drop strategy more than
+/// `retention_days` ago.
+fn plan_purge_dropped(
+    database: &str,
+    tables: &[String],
+    retention_days: u32,
+    now: DateTime<Utc>,
+) -> Vec<CleanAction> {
+    expired_dropped_tables(tables, retention_days, now)
+        .into_iter()
+        .map(|table| CleanAction::DropTable {
+            database: database.to_string(),
+            table,
+        })
+        .collect()
+}
+
+/// The project's running workflows, which would keep retrying against the
+/// cleaned infrastructure. Temporal being unreachable only warns.
+async fn plan_stop_workflows(project: &Project, options: &CleanOptions) -> Option<CleanAction> {
+    if !project.features.workflows || options.keep_workflows {
+        return None;
+    }
+
+    let task_queue = task_queue_name(project.language);
+    let listed = match TemporalWorkflowService::new(&project.temporal_config) {
+        Ok(service) => service.list_open(task_queue).await,
+        Err(e) => Err(e.into()),
+    };
+    match listed {
+        Ok(workflows) if workflows.is_empty() => None,
+        Ok(workflows) => Some(CleanAction::StopWorkflows {
+            workflows,
+            terminate: options.terminate_workflows,
+        }),
+        Err(e) => {
+            show_message_wrapper(
+                MessageType::Warning,
+                Message::new(
+                    "Workflows".to_string(),
+                    format!("could not be listed, leaving them running: {e:#}"),
+                ),
+            );
+            None
+        }
+    }
+}
+
+async fn plan_actions(
     project: &Project,
     docker_client: &DockerClient,
-) -> Result<RoutineSuccess, RoutineFailure> {
-    ensure_docker_running(docker_client).map_err(|e| {
-        RoutineFailure::new(
+    targets: &[CleanTarget],
+    options: &CleanOptions,
+) -> Result<Vec<CleanAction>, RoutineFailure> {
+    // Workflows stop first, while what they use is still there
+    let mut actions: Vec<CleanAction> = plan_stop_workflows(project, options)
+        .await
+        .into_iter()
+        .collect();
+
+    for target in targets {
+        match target {
+            CleanTarget::Olap => {
+                let database = project.clickhouse_config.db_name.clone();
+                for table in list_all_tables(project).await? {
+                    if table != ClickHouseStateStorage::STATE_TABLE {
+                        actions.push(CleanAction::TruncateTable {
+                            database: database.clone(),
+                            table,
+                        });
+                    }
+                }
+            }
+            CleanTarget::Dropped => {
+                actions.extend(plan_purge_dropped(
+                    &project.clickhouse_config.db_name,
+                    &list_all_tables(project).await?,
+                    project.migration_config.dropped_table_retention_days,
+                    Utc::now(),
+                ));
+            }
+            CleanTarget::Streams => {
+                let topics = fetch_topics(&project.redpanda_config).await.map_err(|e| {
+                    RoutineFailure::new(
+                        Message::new("Failed".to_string(), "to list topics".to_string()),
+                        e,
+                    )
+                })?;
+                actions.extend(
+                    topics
+                        .into_iter()
+                        .map(|topic| CleanAction::DeleteTopic(topic.name)),
+                );
+            }
+            CleanTarget::State => actions.push(CleanAction::ClearState {
+                backend: project.state_config.storage.clone(),
+            }),
+            CleanTarget::Containers => {
+                // Get the settings to check if containers should be shut down
+                let settings = Settings::load().map_err(|e| {
+                    RoutineFailure::new(
+                        Message::new("Failed".to_string(), "to load settings".to_string()),
+                        e,
+                    )
+                })?;
+
+                if settings.should_shutdown_containers() {
+                    let containers = docker_client
+                        .list_containers(project)
+                        .map(|containers| containers.into_iter().map(|c| c.name).collect())
+                        .unwrap_or_default();
+                    actions.push(CleanAction::StopContainers { containers });
+                } else {
+                    info!(
+                        "Skipping container shutdown based on settings and environment variables"
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(actions)
+}
+
+/// Cancels or terminates `workflows`, returning how many were stopped. Those
+/// that completed in the meantime are not counted, and failures only warn.
+async fn stop_workflows(
+    service: &dyn WorkflowService,
+    workflows: &[OpenWorkflow],
+    terminate: bool,
+) -> usize {
+    let mut stopped = 0;
+    for workflow in workflows {
+        let result = if terminate {
+            service
+                .terminate(&workflow.workflow_id, &workflow.run_id)
+                .await
+        } else {
+            service
+                .request_cancel(&workflow.workflow_id, &workflow.run_id)
+                .await
+        };
+        match result {
+            Ok(()) => stopped += 1,
+            Err(WorkflowServiceError::AlreadyCompleted(_) | WorkflowServiceError::NotFound(_)) => {}
+            Err(e) => show_message_wrapper(
+                MessageType::Warning,
+                Message::new(
+                    "Workflows".to_string(),
+                    format!("could not stop '{}': {e}", workflow.workflow_id),
+                ),
+            ),
+        }
+    }
+    stopped
+}
+
+async fn execute_actions(
+    project: &Project,
+    docker_client: &DockerClient,
+    actions: &[CleanAction],
+) -> Result<(), RoutineFailure> {
+    for action in actions {
+        let CleanAction::StopWorkflows {
+            workflows,
+            terminate,
+        } = action
+        else {
+            continue;
+        };
+        let stopped = match TemporalWorkflowService::new(&project.temporal_config) {
+            Ok(service) => stop_workflows(&service, workflows, *terminate).await,
+            Err(e) => {
+                show_message_wrapper(
+                    MessageType::Warning,
+                    Message::new(
+                        "Workflows".to_string(),
+                        format!("could not be stopped: {e}"),
+                    ),
+                );
+                0
+            }
+        };
+        show_message_wrapper(
+            MessageType::Info,
             Message::new(
-                "Failed".to_string(),
-                "to ensure docker is running".to_string(),
+                if *terminate {
+                    "Terminated"
+                } else {
+                    "Cancelled"
+                }
+                .to_string(),
+                format!("{stopped} of {} running workflow(s)", workflows.len()),
             ),
-            e,
-        )
-    })?;
+        );
+    }
 
-    // Get the settings to check if containers should be shut down
-    let settings = Settings::load().map_err(|e| {
-        RoutineFailure::new(
-            Message::new("Failed".to_string(), "to load settings".to_string()),
-            e,
-        )
-    })?;
+    let tables: Vec<String> = actions
+        .iter()
+        .filter_map(|action| match action {
+            CleanAction::TruncateTable { table, .. } => Some(table.clone()),
+            _ => None,
+        })
+        .collect();
+    if !tables.is_empty() {
+        truncate_all_rows(project, &tables).await?;
+    }
 
-    if settings.should_shutdown_containers() {
+    let client = create_client(project.clickhouse_config.clone());
+    for action in actions {
+        let CleanAction::DropTable { database, table } = action else {
+            continue;
+        };
+        let query = drop_table_query(database, table, None).map_err(|e| {
+            RoutineFailure::new(
+                Message::new("Failed".to_string(), format!("to drop table {table}")),
+                e,
+            )
+        })?;
+        run_query(&query, &client).await.map_err(|e| {
+            RoutineFailure::new(
+                Message::new("Failed".to_string(), format!("to drop table {table}")),
+                e,
+            )
+        })?;
+    }
+
+    if actions
+        .iter()
+        .any(|action| matches!(action, CleanAction::DeleteTopic(_)))
+    {
+        let topics: Vec<_> = fetch_topics(&project.redpanda_config)
+            .await
+            .map_err(|e| {
+                RoutineFailure::new(
+                    Message::new("Failed".to_string(), "to list topics".to_string()),
+                    e,
+                )
+            })?
+            .into_iter()
+            .filter(|topic| actions.contains(&CleanAction::DeleteTopic(topic.name.clone())))
+            .collect();
+        if !topics.is_empty() {
+            delete_topics(&project.redpanda_config, topics.iter().collect())
+                .await
+                .map_err(|e| {
+                    RoutineFailure::new(
+                        Message::new("Failed".to_string(), "to delete topics".to_string()),
+                        e,
+                    )
+                })?;
+        }
+    }
+
+    if actions
+        .iter()
+        .any(|action| matches!(action, CleanAction::ClearState { .. }))
+    {
+        let state_storage = StateStorageBuilder::from_config(project)
+            .clickhouse_config(Some(project.clickhouse_config.clone()))
+            .redis_url(Some(project.redis_config.effective_url()))
+            .build()
+            .await
+            .map_err(|e| {
+                RoutineFailure::new(
+                    Message::new(
+                        "Failed".to_string(),
+                        "to connect to state storage".to_string(),
+                    ),
+                    e,
+                )
+            })?;
+        state_storage
+            .clear_infrastructure_map()
+            .await
+            .map_err(|e| {
+                RoutineFailure::new(
+                    Message::new("Failed".to_string(), "to clear state".to_string()),
+                    e,
+                )
+            })?;
+    }
+
+    if actions
+        .iter()
+        .any(|action| matches!(action, CleanAction::StopContainers { .. }))
+    {
         docker_client.stop_containers(project).map_err(|err| {
             RoutineFailure::new(
                 Message::new("Failed".to_string(), "to stop containers".to_string()),
                 err,
             )
         })?;
-    } else {
-        info!("Skipping container shutdown based on settings and environment variables");
     }
 
+    Ok(())
+}
+
+fn confirm(actions: &[CleanAction]) -> Result<bool, RoutineFailure> {
+    if !std::io::stdin().is_terminal() && !is_non_interactive() {
+        return Err(RoutineFailure::error(Message::new(
+            "Clean".to_string(),
+            "Refusing to clean without confirmation. Pass --yes to skip the prompt".to_string(),
+        )));
+    }
+
+    let answer = prompt_user(
+        Decision::confirmation("removing project resources", "--yes"),
+        &format!(
+            "The following will be removed:\n{}\nProceed? [y/N]",
+            describe_actions(actions)
+        ),
+        Some("n"),
+        None,
+    )?;
+    Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+}
+
+pub async fn clean_project(
+    project: &Project,
+    docker_client: &DockerClient,
+    options: &CleanOptions,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let targets = clean_targets(options);
+    check_production_guard(project, &targets, options)?;
+
+    if targets.contains(&CleanTarget::Containers) {
+        ensure_docker_running(docker_client).map_err(|e| {
+            RoutineFailure::new(
+                Message::new(
+                    "Failed".to_string(),
+                    "to ensure docker is running".to_string(),
+                ),
+                e,
+            )
+        })?;
+    }
+
+    let actions = plan_actions(project, docker_client, &targets, options).await?;
+
+    if options.dry_run {
+        show_message_wrapper(
+            MessageType::Info,
+            Message::new("Dry run".to_string(), describe_actions(&actions)),
+        );
+        return Ok(RoutineSuccess::success(Message::new(
+            "Dry run".to_string(),
+            format!("{} action(s) would be performed", actions.len()),
+        )));
+    }
+
+    // Stopping containers and workflows is the default and loses no data
+    let needs_confirmation = actions.iter().any(|action| {
+        !matches!(
+            action,
+            CleanAction::StopContainers { .. } | CleanAction::StopWorkflows { .. }
+        )
+    });
+    if needs_confirmation && !options.yes && !confirm(&actions)? {
+        return Ok(RoutineSuccess::success(Message::new(
+            "Clean".to_string(),
+            "cancelled".to_string(),
+        )));
+    }
+
+    execute_actions(project, docker_client, &actions).await?;
+
     Ok(RoutineSuccess::success(Message::new(
         "Cleaned".to_string(),
         "project".to_string(),
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use temporal_sdk_core_protos::temporal::api::enums::v1::WorkflowExecutionStatus;
+
+    fn open_workflow(workflow_id: &str) -> OpenWorkflow {
+        OpenWorkflow {
+            workflow_id: workflow_id.to_string(),
+            run_id: format!("{workflow_id}-run"),
+        }
+    }
+
+    /// Records the stopped workflows, failing for the given ones.
+    #[derive(Default)]
+    struct RecordingWorkflowService {
+        cancelled: Mutex<Vec<String>>,
+        terminated: Mutex<Vec<String>>,
+        completed: Vec<String>,
+        unreachable: Vec<String>,
+    }
+
+    impl RecordingWorkflowService {
+        fn result(&self, workflow_id: &str) -> Result<(), WorkflowServiceError> {
+            if self.completed.iter().any(|id| id == workflow_id) {
+                return Err(WorkflowServiceError::AlreadyCompleted(
+                    workflow_id.to_string(),
+                ));
+            }
+            if self.unreachable.iter().any(|id| id == workflow_id) {
+                return Err(WorkflowServiceError::Unavailable("refused".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl WorkflowService for RecordingWorkflowService {
+        async fn describe_status(
+            &self,
+            _workflow_id: &str,
+            _run_id: &str,
+        ) -> Result<WorkflowExecutionStatus, WorkflowServiceError> {
+            Ok(WorkflowExecutionStatus::Running)
+        }
+
+        async fn request_cancel(
+            &self,
+            workflow_id: &str,
+            _run_id: &str,
+        ) -> Result<(), WorkflowServiceError> {
+            self.result(workflow_id)?;
+            self.cancelled.lock().unwrap().push(workflow_id.to_string());
+            Ok(())
+        }
+
+        async fn terminate(
+            &self,
+            workflow_id: &str,
+            _run_id: &str,
+        ) -> Result<(), WorkflowServiceError> {
+            self.result(workflow_id)?;
+            self.terminated
+                .lock()
+                .unwrap()
+                .push(workflow_id.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stop_workflows_cancels_and_counts_stopped() {
+        let service = RecordingWorkflowService {
+            completed: vec!["finished".to_string()],
+            unreachable: vec!["flaky".to_string()],
+            ..Default::default()
+        };
+        let workflows = [
+            open_workflow("daily"),
+            open_workflow("finished"),
+            open_workflow("flaky"),
+            open_workflow("hourly"),
+        ];
+
+        let stopped = stop_workflows(&service, &workflows, false).await;
+
+        assert_eq!(stopped, 2);
+        assert_eq!(*service.cancelled.lock().unwrap(), ["daily", "hourly"]);
+        assert!(service.terminated.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stop_workflows_terminates_when_forced() {
+        let service = RecordingWorkflowService::default();
+
+        let stopped = stop_workflows(&service, &[open_workflow("daily")], true).await;
+
+        assert_eq!(stopped, 1);
+        assert_eq!(*service.terminated.lock().unwrap(), ["daily"]);
+        assert!(service.cancelled.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_workflows_kept_or_disabled_are_not_listed() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut project = Project::new(
+            temp_dir.path(),
+            "clean-test".to_string(),
+            crate::framework::languages::SupportedLanguages::Typescript,
+        );
+        project.features.workflows = false;
+        assert_eq!(
+            plan_stop_workflows(&project, &CleanOptions::default()).await,
+            None
+        );
+
+        project.features.workflows = true;
+        let keep = CleanOptions {
+            keep_workflows: true,
+            ..Default::default()
+        };
+        assert_eq!(plan_stop_workflows(&project, &keep).await, None);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires a running Temporal dev server
+    async fn test_clean_stops_running_workflow() {
+        use crate::framework::languages::SupportedLanguages;
+        use crate::framework::scripts::config::WorkflowConfig;
+        use crate::framework::scripts::executor::execute_workflow;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut project = Project::new(
+            temp_dir.path(),
+            "clean-test".to_string(),
+            SupportedLanguages::Typescript,
+        );
+        project.features.workflows = true;
+
+        // No worker polls the task queue, so the fixture runs until stopped
+        let workflow_id = format!("clean-fixture-{}", uuid::Uuid::new_v4());
+        let config = WorkflowConfig {
+            timeout: "1h".to_string(),
+            ..WorkflowConfig::new(workflow_id.clone())
+        };
+        execute_workflow(
+            &project.temporal_config,
+            SupportedLanguages::Typescript,
+            &workflow_id,
+            &config,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let options = CleanOptions {
+            terminate_workflows: true,
+            ..Default::default()
+        };
+        let Some(CleanAction::StopWorkflows { workflows, .. }) =
+            plan_stop_workflows(&project, &options).await
+        else {
+            panic!("Expected the fixture workflow to be listed");
+        };
+        let fixture: Vec<OpenWorkflow> = workflows
+            .into_iter()
+            .filter(|workflow| workflow.workflow_id == workflow_id)
+            .collect();
+        assert_eq!(fixture.len(), 1);
+
+        let service = TemporalWorkflowService::new(&project.temporal_config).unwrap();
+        assert_eq!(stop_workflows(&service, &fixture, true).await, 1);
+        assert_eq!(
+            service
+                .describe_status(&workflow_id, &fixture[0].run_id)
+                .await
+                .unwrap(),
+            WorkflowExecutionStatus::Terminated
+        );
+    }
+
+    #[test]
+    fn test_no_flags_only_stops_containers() {
+        let targets = clean_targets(&CleanOptions::default());
+        assert_eq!(targets, vec![CleanTarget::Containers]);
+    }
+
+    #[test]
+    fn test_flags_map_to_targets_in_teardown_order() {
+        let options = CleanOptions {
+            containers: true,
+            state: true,
+            olap: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            clean_targets(&options),
+            vec![
+                CleanTarget::Olap,
+                CleanTarget::State,
+                CleanTarget::Containers
+            ]
+        );
+
+        let streams_only = CleanOptions {
+            streams: true,
+            ..Default::default()
+        };
+        assert_eq!(clean_targets(&streams_only), vec![CleanTarget::Streams]);
+    }
+
+    #[test]
+    fn test_all_selects_every_target() {
+        let options = CleanOptions {
+            all: true,
+            streams: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            clean_targets(&options),
+            vec![
+                CleanTarget::Olap,
+                CleanTarget::Streams,
+                CleanTarget::State,
+                CleanTarget::Containers
+            ]
+        );
+    }
+
+    #[test]
+    fn test_production_guard() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut project = Project::new(
+            temp_dir.path(),
+            "clean-test".to_string(),
+            crate::framework::languages::SupportedLanguages::Typescript,
+        );
+        project.set_is_production_env(true);
+
+        let options = CleanOptions {
+            olap: true,
+            streams: true,
+            ..Default::default()
+        };
+        let err = check_production_guard(&project, &clean_targets(&options), &options).unwrap_err();
+        assert!(err.message.details.contains("--olap"));
+        assert!(!err.message.details.contains("--streams"));
+
+        let forced = CleanOptions {
+            i_know_what_im_doing: true,
+            ..options
+        };
+        assert!(check_production_guard(&project, &clean_targets(&forced), &forced).is_ok());
+
+        let streams_only = CleanOptions {
+            streams: true,
+            ..Default::default()
+        };
+        assert!(
+            check_production_guard(&project, &clean_targets(&streams_only), &streams_only).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_dry_run_output() {
+        let actions = vec![
+            CleanAction::TruncateTable {
+                database: "local".to_string(),
+                table: "Bar".to_string(),
+            },
+            CleanAction::DeleteTopic("Foo".to_string()),
+            CleanAction::StopWorkflows {
+                workflows: vec![open_workflow("daily"), open_workflow("hourly")],
+                terminate: false,
+            },
+            CleanAction::ClearState {
+                backend: "redis".to_string(),
+            },
+            CleanAction::StopContainers {
+                containers: vec!["app-clickhouse-1".to_string(), "app-redis-1".to_string()],
+            },
+        ];
+
+        assert_eq!(
+            describe_actions(&actions),
+            "  - truncate table local.Bar\n  \
+             - delete topic Foo\n  \
+             - cancel running workflows: daily, hourly\n  \
+             - clear stored infrastructure map (redis)\n  \
+             - stop and remove containers: app-clickhouse-1, app-redis-1"
+        );
+        assert_eq!(describe_actions(&[]), "Nothing to clean");
+    }
+
+    #[test]
+    fn test_dropped_selects_only_the_purge() {
+        let options = CleanOptions {
+            dropped: true,
+            ..Default::default()
+        };
+        assert_eq!(clean_targets(&options), vec![CleanTarget::Dropped]);
+    }
+
+    #[test]
+    fn test_purge_drops_expired_dropped_tables() {
+        let now = "2026-03-14T15:09:26Z".parse::<DateTime<Utc>>().unwrap();
+        let tables = vec![
+            "events".to_string(),
+            "events__dropped_20260301000000".to_string(),
+            "users__dropped_20260313000000".to_string(),
+        ];
+
+        let actions = plan_purge_dropped("local", &tables, 7, now);
+
+        assert_eq!(
+            actions,
+            vec![CleanAction::DropTable {
+                database: "local".to_string(),
+                table: "events__dropped_20260301000000".to_string(),
+            }]
+        );
+        assert_eq!(
+            describe_actions(&actions),
+            "  - drop table local.events__dropped_20260301000000"
+        );
+        assert_eq!(plan_purge_dropped("local", &tables, 0, now).len(), 2);
+    }
+}
@@ -1,13 +1,17 @@
 use crate::cli::display::{Message, MessageType};
 use crate::cli::routines::RoutineFailure;
-use crate::cli::{prompt_password, prompt_user};
+use crate::cli::{prompt_password, prompt_user, Decision};
+use crate::framework::core::infrastructure::sql_resource::SqlResource;
 use crate::framework::core::infrastructure::table::Table;
 use crate::framework::core::infrastructure_map::InfrastructureMap;
 use crate::framework::core::partial_infrastructure_map::LifeCycle;
 use crate::framework::languages::SupportedLanguages;
-use crate::framework::python::generate::tables_to_python;
-use crate::framework::typescript::generate::tables_to_typescript;
+use crate::framework::python::generate::{sql_resources_to_python, tables_to_python};
+use crate::framework::typescript::generate::{
+    sanitize_typescript_identifier, sql_resources_to_typescript, tables_to_typescript,
+};
 use crate::infrastructure::olap::clickhouse::remote::ClickHouseRemote;
+use crate::infrastructure::olap::clickhouse::sql_parser::is_create_dictionary;
 use crate::infrastructure::olap::clickhouse::{create_readonly_client, ConfiguredDBClient};
 use crate::infrastructure::olap::OlapOperations;
 use crate::project::Project;
@@ -17,20 +21,39 @@ use crate::utilities::constants::{
 use crate::utilities::git::create_code_generation_commit;
 use clickhouse::Client;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::env;
 use std::io::Write;
 use std::path::Path;
 use tracing::debug;
 
-pub fn prompt_user_for_remote_ch_http() -> Result<String, RoutineFailure> {
+/// Prompts for a remote ClickHouse connection and returns it as an HTTPS URL.
+///
+/// `flag` names the option that supplies the URL without prompting, for the
+/// error shown in non-interactive mode.
+pub fn prompt_user_for_remote_ch_http(flag: Option<&str>) -> Result<String, RoutineFailure> {
     let base = prompt_user(
+        Decision::new("the remote ClickHouse URL", flag),
         "Enter ClickHouse host and port",
         None,
         Some("Format: https://your-instance.boreal.cloud:8443\n  🔗 Get your URL: https://boreal.cloud/\n  📖 Troubleshooting: https://docs.fiveonefour.com/moose/getting-started/from-clickhouse#troubleshooting")
     )?.trim_end_matches('/').trim_start_matches("https://").to_string();
-    let user = prompt_user("Enter username", Some("default"), None)?;
-    let pass = prompt_password("Enter password")?;
-    let db = prompt_user("Enter database name", Some("default"), None)?;
+    let user = prompt_user(
+        Decision::new("the remote ClickHouse username", flag),
+        "Enter username",
+        Some("default"),
+        None,
+    )?;
+    let pass = prompt_password(
+        Decision::new("the remote ClickHouse password", flag),
+        "Enter password",
+    )?;
+    let db = prompt_user(
+        Decision::new("the remote ClickHouse database", flag),
+        "Enter database name",
+        Some("default"),
+        None,
+    )?;
 
     let mut url = reqwest::Url::parse(&format!("https://{base}")).map_err(|e| {
         RoutineFailure::new(
@@ -56,6 +79,134 @@ fn should_be_externally_managed(table: &Table) -> bool {
     table.columns.iter().any(|c| c.name.starts_with("_peerdb_"))
 }
 
+/// Key under which table and view names are compared for collisions: names that map
+/// to the same generated identifier cannot both be imported.
+fn collision_key(name: &str) -> String {
+    sanitize_typescript_identifier(name).to_lowercase()
+}
+
+/// Splits introspected views/MVs into those that can be generated and a description
+/// of each one whose name collides with a table.
+fn partition_name_collisions<'a>(
+    table_names: impl Iterator<Item = &'a str>,
+    resources: Vec<SqlResource>,
+) -> (Vec<SqlResource>, Vec<String>) {
+    let table_names: HashMap<String, &str> = table_names
+        .map(|name| (collision_key(name), name))
+        .collect();
+
+    let mut kept = Vec::new();
+    let mut collisions = Vec::new();
+    for resource in resources {
+        match table_names.get(&collision_key(&resource.name)) {
+            Some(table) => collisions.push(format!("{} (table '{table}')", resource.name)),
+            None => kept.push(resource),
+        }
+    }
+    (kept, collisions)
+}
+
+/// Orders resources so each one comes after the views it selects from. Cycles, which
+/// ClickHouse itself rejects, fall back to name order.
+fn order_by_dependencies(mut remaining: Vec<SqlResource>) -> Vec<SqlResource> {
+    remaining.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut ordered: Vec<SqlResource> = Vec::with_capacity(remaining.len());
+
+    while !remaining.is_empty() {
+        let ready = remaining.iter().position(|resource| {
+            resource.pulls_data_from.iter().all(|dep| {
+                dep.id() == resource.name || !remaining.iter().any(|other| other.name == dep.id())
+            })
+        });
+        ordered.push(remaining.remove(ready.unwrap_or(0)));
+    }
+
+    ordered
+}
+
+/// Whether `line` starts an import statement in TypeScript or Python.
+fn is_import_line(line: &str) -> bool {
+    line.starts_with("import ") || line.starts_with("from ")
+}
+
+/// Adds generated `code` to the end of `module`, moving the import statements `code`
+/// starts with into the import block at the top of `module`. Imports `module` already
+/// has are not repeated.
+fn merge_generated_code(module: &str, code: &str) -> String {
+    let code_lines: Vec<&str> = code.lines().collect();
+    let body_start = code_lines
+        .iter()
+        .position(|line| !is_import_line(line))
+        .unwrap_or(code_lines.len());
+    let (imports, body) = code_lines.split_at(body_start);
+
+    // The import block ends after the last import among the leading imports, comments
+    // and blank lines, or after the leading comments when there is no import. Imports
+    // spanning lines end once their brackets are closed.
+    let module_lines: Vec<&str> = module.lines().collect();
+    let mut block_end = 0;
+    let mut seen_import = false;
+    let mut open_brackets = 0;
+    for (i, line) in module_lines.iter().enumerate() {
+        if open_brackets == 0 {
+            let trimmed = line.trim();
+            if trimmed.starts_with("//") || trimmed.starts_with('#') {
+                if !seen_import {
+                    block_end = i + 1;
+                }
+                continue;
+            }
+            if trimmed.is_empty() {
+                continue;
+            }
+            if !is_import_line(line) {
+                break;
+            }
+            seen_import = true;
+        }
+        open_brackets += line.matches(['(', '{']).count();
+        open_brackets = open_brackets.saturating_sub(line.matches([')', '}']).count());
+        if open_brackets == 0 {
+            block_end = i + 1;
+        }
+    }
+
+    let new_imports = imports
+        .iter()
+        .filter(|import| !module_lines.contains(import))
+        .copied();
+    let mut lines: Vec<&str> = module_lines[..block_end].to_vec();
+    lines.extend(new_imports);
+    lines.extend(&module_lines[block_end..]);
+
+    let body = body.join("\n");
+    let body = body.trim();
+    let mut merged = lines.join("\n");
+    if !body.is_empty() {
+        merged.push_str("\n\n");
+        merged.push_str(body);
+    }
+    merged.push('\n');
+    merged
+}
+
+/// Adds generated `code` to the module at `path`, with its imports in the module's
+/// import block. `what` describes the code in error messages.
+fn add_generated_code(path: &str, code: &str, what: &str) -> Result<(), RoutineFailure> {
+    let module = std::fs::read_to_string(path).map_err(|e| {
+        RoutineFailure::new(
+            Message::new("Failure".to_string(), format!("reading {path}")),
+            e,
+        )
+    })?;
+    std::fs::write(path, merge_generated_code(&module, code)).map_err(|e| {
+        RoutineFailure::new(
+            Message::new("Failure".to_string(), format!("writing {what}")),
+            e,
+        )
+    })
+}
+
 // Shared helpers
 pub async fn create_client_and_db(
     remote_url: &str,
@@ -126,13 +277,13 @@ pub async fn create_client_and_db(
     Ok((create_readonly_client(config), db_name))
 }
 
-fn write_external_models_file(
+/// The external models file of the project, `file_path` when given.
+fn external_models_path<'a>(
     language: SupportedLanguages,
-    tables: &[Table],
-    file_path: Option<&str>,
+    file_path: Option<&'a str>,
     source_dir: &str,
-) -> Result<(), RoutineFailure> {
-    let file = match (language, file_path) {
+) -> Cow<'a, str> {
+    match (language, file_path) {
         (_, Some(path)) => Cow::Borrowed(path),
         (SupportedLanguages::Typescript, None) => {
             Cow::Owned(format!("{source_dir}/{TYPESCRIPT_EXTERNAL_FILE}"))
@@ -140,11 +291,25 @@ fn write_external_models_file(
         (SupportedLanguages::Python, None) => {
             Cow::Owned(format!("{source_dir}/{PYTHON_EXTERNAL_FILE}"))
         }
-    };
+    }
+}
+
+fn write_external_models_file(
+    language: SupportedLanguages,
+    tables: &[Table],
+    dictionaries: &[SqlResource],
+    file_path: Option<&str>,
+    source_dir: &str,
+) -> Result<(), RoutineFailure> {
+    let file = external_models_path(language, file_path, source_dir);
     match language {
         SupportedLanguages::Typescript => {
-            let table_definitions =
+            let mut table_definitions =
                 tables_to_typescript(tables, Some(LifeCycle::ExternallyManaged));
+            if !dictionaries.is_empty() {
+                table_definitions.push('\n');
+                table_definitions.push_str(&sql_resources_to_typescript(dictionaries, tables, &[]));
+            }
             let header = "// AUTO-GENERATED FILE. DO NOT EDIT.\n// This file will be replaced when you run `moose db pull`.";
             let mut file = std::fs::OpenOptions::new()
                 .create(true)
@@ -168,7 +333,12 @@ fn write_external_models_file(
             })?
         }
         SupportedLanguages::Python => {
-            let table_definitions = tables_to_python(tables, Some(LifeCycle::ExternallyManaged));
+            let mut table_definitions =
+                tables_to_python(tables, Some(LifeCycle::ExternallyManaged));
+            if !dictionaries.is_empty() {
+                table_definitions.push('\n');
+                table_definitions.push_str(&sql_resources_to_python(dictionaries, tables));
+            }
             let header = "# AUTO-GENERATED FILE. DO NOT EDIT.\n# This file will be replaced when you run `moose db pull`.";
             let mut file = std::fs::OpenOptions::new()
                 .create(true)
@@ -230,8 +400,6 @@ pub async fn db_to_dmv2(remote_url: &str, dir_path: &Path) -> Result<(), Routine
             e,
         )
     })?;
-    // TODO: Also call list_sql_resources to fetch Views/MVs and generate code for them.
-    // Currently we only generate code for Tables.
     show_message!(
         MessageType::Info,
         Message {
@@ -246,6 +414,23 @@ pub async fn db_to_dmv2(remote_url: &str, dir_path: &Path) -> Result<(), Routine
         )
     })?;
 
+    show_message!(
+        MessageType::Info,
+        Message {
+            action: "Introspecting".to_string(),
+            details: format!("views, materialized views and dictionaries in '{db}'..."),
+        }
+    );
+    let sql_resources = client.list_sql_resources(&db, &db).await.map_err(|e| {
+        RoutineFailure::new(
+            Message::new(
+                "Failure".to_string(),
+                "listing views, materialized views and dictionaries".to_string(),
+            ),
+            e,
+        )
+    })?;
+
     if tables.is_empty() && unsupported.is_empty() {
         return Err(RoutineFailure::error(Message::new(
             "No tables".to_string(),
@@ -281,6 +466,34 @@ pub async fn db_to_dmv2(remote_url: &str, dir_path: &Path) -> Result<(), Routine
         })
         .partition(should_be_externally_managed);
 
+    let sql_resources = sql_resources
+        .into_iter()
+        .map(|mut r| {
+            r.database = None;
+            r
+        })
+        .collect();
+    let (sql_resources, collisions) = partition_name_collisions(
+        externally_managed
+            .iter()
+            .chain(managed.iter())
+            .map(|t| t.name.as_str()),
+        sql_resources,
+    );
+    if !collisions.is_empty() {
+        show_message!(
+            MessageType::Highlight,
+            Message {
+                action: "Skipped".to_string(),
+                details: format!(
+                    "view(s) whose names collide with tables: {}",
+                    collisions.join(", ")
+                ),
+            }
+        );
+    }
+    let sql_resources = order_by_dependencies(sql_resources);
+
     match project.language {
         SupportedLanguages::Typescript => {
             if !externally_managed.is_empty() {
@@ -310,60 +523,29 @@ pub async fn db_to_dmv2(remote_url: &str, dir_path: &Path) -> Result<(), Routine
                         e,
                     )
                 })?;
-                let main_path = format!("{}/{TYPESCRIPT_MAIN_FILE}", project.source_dir);
-                let import_stmt = "import \"./externalModels\";";
-                let needs_import = match std::fs::read_to_string(&main_path) {
-                    Ok(contents) => !contents.contains(import_stmt),
-                    Err(_) => true,
-                };
-                if needs_import {
-                    let mut file = std::fs::OpenOptions::new()
-                        .append(true)
-                        .open(&main_path)
-                        .map_err(|e| {
-                            RoutineFailure::new(
-                                Message::new(
-                                    "Failure".to_string(),
-                                    format!("opening {TYPESCRIPT_MAIN_FILE}"),
-                                ),
-                                e,
-                            )
-                        })?;
-                    writeln!(file, "\n{import_stmt}").map_err(|e| {
-                        RoutineFailure::new(
-                            Message::new(
-                                "Failure".to_string(),
-                                "writing externalModels import".to_string(),
-                            ),
-                            e,
-                        )
-                    })?;
-                }
+                add_generated_code(
+                    &format!("{}/{TYPESCRIPT_MAIN_FILE}", project.source_dir),
+                    "import \"./externalModels\";",
+                    "externalModels import",
+                )?;
             }
 
             if !managed.is_empty() {
-                let table_definitions = tables_to_typescript(&managed, None);
-                let mut file = std::fs::OpenOptions::new()
-                    .append(true)
-                    .open(format!("{}/{TYPESCRIPT_MAIN_FILE}", project.source_dir))
-                    .map_err(|e| {
-                        RoutineFailure::new(
-                            Message::new(
-                                "Failure".to_string(),
-                                format!("opening {TYPESCRIPT_MAIN_FILE}"),
-                            ),
-                            e,
-                        )
-                    })?;
-                writeln!(file, "\n\n{table_definitions}").map_err(|e| {
-                    RoutineFailure::new(
-                        Message::new(
-                            "Failure".to_string(),
-                            "writing managed table definitions".to_string(),
-                        ),
-                        e,
-                    )
-                })?;
+                add_generated_code(
+                    &format!("{}/{TYPESCRIPT_MAIN_FILE}", project.source_dir),
+                    &tables_to_typescript(&managed, None),
+                    "managed table definitions",
+                )?;
+            }
+
+            if !sql_resources.is_empty() {
+                let resource_definitions =
+                    sql_resources_to_typescript(&sql_resources, &managed, &externally_managed);
+                add_generated_code(
+                    &format!("{}/{TYPESCRIPT_MAIN_FILE}", project.source_dir),
+                    &resource_definitions,
+                    "view, materialized view and dictionary definitions",
+                )?;
             }
         }
         SupportedLanguages::Python => {
@@ -394,59 +576,33 @@ pub async fn db_to_dmv2(remote_url: &str, dir_path: &Path) -> Result<(), Routine
                         e,
                     )
                 })?;
-                let main_path = format!("{}/{PYTHON_MAIN_FILE}", project.source_dir);
-                let import_stmt = "from .external_models import *";
-                let needs_import = match std::fs::read_to_string(&main_path) {
-                    Ok(contents) => !contents.contains(import_stmt),
-                    Err(_) => true,
-                };
-                if needs_import {
-                    let mut file = std::fs::OpenOptions::new()
-                        .append(true)
-                        .open(&main_path)
-                        .map_err(|e| {
-                            RoutineFailure::new(
-                                Message::new(
-                                    "Failure".to_string(),
-                                    format!("opening {PYTHON_MAIN_FILE}"),
-                                ),
-                                e,
-                            )
-                        })?;
-                    writeln!(file, "\n{import_stmt}").map_err(|e| {
-                        RoutineFailure::new(
-                            Message::new(
-                                "Failure".to_string(),
-                                "writing external_models import".to_string(),
-                            ),
-                            e,
-                        )
-                    })?;
-                }
+                add_generated_code(
+                    &format!("{}/{PYTHON_MAIN_FILE}", project.source_dir),
+                    "from .external_models import *",
+                    "external_models import",
+                )?;
             }
             if !managed.is_empty() {
-                let table_definitions = tables_to_python(&managed, None);
-                let mut file = std::fs::OpenOptions::new()
-                    .append(true)
-                    .open(format!("{}/{PYTHON_MAIN_FILE}", project.source_dir))
-                    .map_err(|e| {
-                        RoutineFailure::new(
-                            Message::new(
-                                "Failure".to_string(),
-                                format!("opening {PYTHON_MAIN_FILE}"),
-                            ),
-                            e,
-                        )
-                    })?;
-                writeln!(file, "\n\n{table_definitions}").map_err(|e| {
-                    RoutineFailure::new(
-                        Message::new(
-                            "Failure".to_string(),
-                            "writing managed table definitions".to_string(),
-                        ),
-                        e,
-                    )
-                })?;
+                add_generated_code(
+                    &format!("{}/{PYTHON_MAIN_FILE}", project.source_dir),
+                    &tables_to_python(&managed, None),
+                    "managed table definitions",
+                )?;
+            }
+
+            if !sql_resources.is_empty() {
+                let tables_in_scope: Vec<Table> = externally_managed
+                    .iter()
+                    .chain(managed.iter())
+                    .cloned()
+                    .collect();
+                let resource_definitions =
+                    sql_resources_to_python(&sql_resources, &tables_in_scope);
+                add_generated_code(
+                    &format!("{}/{PYTHON_MAIN_FILE}", project.source_dir),
+                    &resource_definitions,
+                    "view, materialized view and dictionary definitions",
+                )?;
             }
         }
     };
@@ -508,7 +664,7 @@ pub async fn db_pull_from_remote(
 
 /// Shared implementation for db pull operations.
 ///
-/// Introspects the remote ClickHouse, finds external/unknown tables,
+/// Introspects the remote ClickHouse, finds external/unknown tables and dictionaries,
 /// and regenerates the external models file.
 async fn db_pull_with_client(
     client: ConfiguredDBClient,
@@ -578,9 +734,41 @@ async fn db_pull_with_client(
     // Keep a stable ordering for deterministic output
     tables_for_external_file.sort_by(|a, b| a.name.cmp(&b.name));
 
+    // Dictionaries the project does not define, or only defines through the
+    // external models file being regenerated
+    let external_file = external_models_path(project.language, file_path, &project.source_dir);
+    let project_dictionary_names: std::collections::HashSet<&str> = infra_map
+        .sql_resources
+        .values()
+        .filter(|r| {
+            !r.source_file.as_deref().is_some_and(|source| {
+                Path::new(source).ends_with(&*external_file)
+                    || Path::new(&*external_file).ends_with(source)
+            })
+        })
+        .map(|r| r.name.as_str())
+        .collect();
+    let sql_resources = client.list_sql_resources(db, db).await.map_err(|e| {
+        RoutineFailure::new(
+            Message::new("Failure".to_string(), "listing dictionaries".to_string()),
+            e,
+        )
+    })?;
+    let mut dictionaries: Vec<SqlResource> = sql_resources
+        .into_iter()
+        .filter(|r| r.setup.iter().any(|sql| is_create_dictionary(sql)))
+        .filter(|r| !project_dictionary_names.contains(r.name.as_str()))
+        .map(|mut r| {
+            r.database = None;
+            r
+        })
+        .collect();
+    dictionaries.sort_by(|a, b| a.name.cmp(&b.name));
+
     write_external_models_file(
         project.language,
         &tables_for_external_file,
+        &dictionaries,
         file_path,
         &project.source_dir,
     )?;
@@ -589,9 +777,82 @@ async fn db_pull_with_client(
         MessageType::Info,
         Message {
             action: "External models".to_string(),
-            details: format!("refreshed ({} table(s))", tables_for_external_file.len()),
+            details: format!(
+                "refreshed ({} table(s), {} dictionary(ies))",
+                tables_for_external_file.len(),
+                dictionaries.len()
+            ),
         }
     );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::InfrastructureSignature;
+
+    fn view(name: &str, pulls_data_from: &[&str]) -> SqlResource {
+        SqlResource {
+            name: name.to_string(),
+            database: None,
+            source_file: None,
+            source_line: None,
+            source_column: None,
+            setup: vec![],
+            teardown: vec![],
+            pulls_data_from: pulls_data_from
+                .iter()
+                .map(|id| InfrastructureSignature::Table { id: id.to_string() })
+                .collect(),
+            pushes_data_to: vec![],
+            life_cycle: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_order_by_dependencies_puts_upstream_views_first() {
+        let ordered = order_by_dependencies(vec![
+            view("a_top", &["b_daily"]),
+            view("b_daily", &["c_mv"]),
+            view("c_mv", &["events"]),
+        ]);
+        let names: Vec<&str> = ordered.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["c_mv", "b_daily", "a_top"]);
+    }
+
+    #[test]
+    fn test_partition_name_collisions_reports_views_shadowing_tables() {
+        let (kept, collisions) = partition_name_collisions(
+            std::iter::once("user_events"),
+            vec![view("UserEvents", &[]), view("user_events_view", &[])],
+        );
+
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].name, "user_events_view");
+        assert_eq!(collisions, vec!["UserEvents (table 'user_events')"]);
+    }
+
+    #[test]
+    fn test_merge_generated_code_moves_imports_into_import_block() {
+        let module = "// Welcome\nimport {\n  OlapTable,\n} from \"@514labs/moose-lib\";\nimport typia from \"typia\";\n\nexport const a = 1;\n";
+        let code = "import typia from \"typia\";\nimport { SqlResource } from \"@514labs/moose-lib\";\n\nexport const b = 2;\n";
+
+        assert_eq!(
+            merge_generated_code(module, code),
+            "// Welcome\nimport {\n  OlapTable,\n} from \"@514labs/moose-lib\";\nimport typia from \"typia\";\nimport { SqlResource } from \"@514labs/moose-lib\";\n\nexport const a = 1;\n\nexport const b = 2;\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_generated_code_without_imports_in_module() {
+        let module = "# Welcome\n\nexample = 1\n";
+        let code = "from moose_lib import SqlResource\n\nexample_view = 2\n";
+
+        assert_eq!(
+            merge_generated_code(module, code),
+            "# Welcome\nfrom moose_lib import SqlResource\n\nexample = 1\n\nexample_view = 2\n"
+        );
+    }
+}
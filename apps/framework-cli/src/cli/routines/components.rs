@@ -25,8 +25,8 @@ use serde::Deserialize;
 
 use super::{display, templates, Message, MessageType};
 use crate::cli::commands::{AddArgs, AddComponent};
-use crate::cli::prompt_user;
 use crate::cli::routines::{RoutineFailure, RoutineSuccess};
+use crate::cli::{is_non_interactive, prompt_user, Decision};
 use crate::framework::languages::SupportedLanguages;
 use crate::project::Project;
 use crate::utilities::constants::CLI_VERSION;
@@ -480,11 +480,18 @@ fn confirm_plan(
         display::write_detail_lines(&conflicts);
     }
 
-    if yes || !std::io::stdin().is_terminal() {
+    // Without a terminal the files are written as before, unless prompts were
+    // explicitly disabled, in which case overwriting must be confirmed with --yes
+    if yes || (!std::io::stdin().is_terminal() && !is_non_interactive()) {
         return Ok(());
     }
 
-    let input = prompt_user("\nProceed? [y/N]", Some("N"), None)?;
+    let input = prompt_user(
+        Decision::confirmation("overwriting existing files", "--yes"),
+        "\nProceed? [y/N]",
+        Some("N"),
+        None,
+    )?;
     if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
         return Err(RoutineFailure::error(Message::new(
             "Cancelled".to_string(),
@@ -0,0 +1,497 @@
+//! `moose db diff`: compares the schemas of two ClickHouse databases directly,
+//! without reading any Moose state.
+//!
+//! Both sides are introspected with the same `list_tables`/`list_sql_resources`
+//! calls used for drift detection, so TTL, codec and settings normalization
+//! match what `moose plan` would report.
+
+use serde::Serialize;
+
+use super::code_generation::create_client_and_db;
+use super::{RoutineFailure, RoutineSuccess};
+use crate::cli::display::infrastructure::show_olap_changes;
+use crate::cli::display::{show_table, Message};
+use crate::cli::exit_code::CliExitCode;
+use crate::framework::core::infrastructure::sql_resource::SqlResource;
+use crate::framework::core::infrastructure::table::Table;
+use crate::framework::core::infrastructure_map::{InfraChanges, InfrastructureMap, OlapChange};
+use crate::framework::core::plan::infra_changes_to_operations;
+use crate::infrastructure::olap::clickhouse::diff_strategy::ClickHouseTableDiffStrategy;
+use crate::infrastructure::olap::clickhouse::{
+    normalize_table_for_diff, operation_sql_preview, IgnorableOperation, TableWithUnsupportedType,
+};
+use crate::infrastructure::olap::OlapOperations;
+use crate::project::Project;
+
+/// Schema version of the `moose db diff --json` document.
+///
+/// Bump this whenever a field is renamed, removed or changes meaning.
+pub const DB_DIFF_JSON_SCHEMA_VERSION: u32 = 1;
+
+pub struct DbDiffOptions<'a> {
+    pub from_url: &'a str,
+    pub to_url: &'a str,
+    /// Database to compare on both sides, overriding the one in each URL
+    pub database: Option<&'a str>,
+    pub json: bool,
+    pub show_sql: bool,
+    /// Differences left out of the comparison
+    pub ignore_operations: &'a [IgnorableOperation],
+}
+
+/// Side of the comparison a database belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Side {
+    From,
+    To,
+}
+
+impl Side {
+    fn label(self) -> &'static str {
+        match self {
+            Side::From => "from",
+            Side::To => "to",
+        }
+    }
+}
+
+/// Everything introspected from one database.
+struct DatabaseSchema {
+    database: String,
+    tables: Vec<Table>,
+    unsupported: Vec<TableWithUnsupportedType>,
+    sql_resources: Vec<SqlResource>,
+}
+
+/// A table that could not be compared because one of its columns uses a type
+/// Moose cannot represent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UnsupportedTable {
+    side: Side,
+    database: String,
+    table: String,
+    column: String,
+    column_type: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DbDiffReport {
+    version: u32,
+    from_database: String,
+    to_database: String,
+    changes: Vec<OlapChange>,
+    unsupported_tables: Vec<UnsupportedTable>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sql: Option<Vec<String>>,
+}
+
+async fn introspect(
+    url: &str,
+    database: Option<&str>,
+    project: &Project,
+    side: Side,
+) -> Result<DatabaseSchema, RoutineFailure> {
+    let (client, url_database) = create_client_and_db(url).await?;
+    let database = database.map_or(url_database, str::to_string);
+
+    let connectivity_failure = |what: &str, e: anyhow::Error| {
+        RoutineFailure::new(
+            Message::new(
+                "DB Diff".to_string(),
+                format!("Failed to list {what} in '{database}' ({})", side.label()),
+            ),
+            e,
+        )
+        .with_exit_code(CliExitCode::ConnectivityFailure)
+    };
+
+    let (tables, unsupported) = client
+        .list_tables(&database, project)
+        .await
+        .map_err(|e| connectivity_failure("tables", e.into()))?;
+    let sql_resources = client
+        .list_sql_resources(&database, &database)
+        .await
+        .map_err(|e| connectivity_failure("views", e.into()))?;
+
+    Ok(DatabaseSchema {
+        database,
+        tables,
+        unsupported,
+        sql_resources,
+    })
+}
+
+/// Builds an infrastructure map holding only the OLAP resources of `schema`.
+///
+/// Resources in the schema's own database are re-homed into
+/// `default_database`, so two databases with different names can be compared.
+fn schema_to_infra_map(
+    schema: &DatabaseSchema,
+    project: &Project,
+    default_database: &str,
+) -> InfrastructureMap {
+    let mut map = InfrastructureMap::empty_from_project(project);
+    map.default_database = default_database.to_string();
+
+    for table in &schema.tables {
+        let mut table = normalize_table_for_diff(table, &[]);
+        if table.database.as_deref() == Some(schema.database.as_str()) {
+            table.database = None;
+        }
+        map.tables.insert(table.id(default_database), table);
+    }
+
+    for resource in &schema.sql_resources {
+        let mut resource = resource.clone();
+        if resource.database.as_deref() == Some(schema.database.as_str()) {
+            resource.database = None;
+        }
+        map.sql_resources.insert(resource.name.clone(), resource);
+    }
+
+    map.canonicalize_tables()
+}
+
+/// Computes the OLAP changes that would turn `from` into `to`, leaving out
+/// the differences `ignore_ops` covers. Resources in each side's own database
+/// come back with `database: None`.
+fn diff_schemas(
+    from: &DatabaseSchema,
+    to: &DatabaseSchema,
+    project: &Project,
+    ignore_ops: &[IgnorableOperation],
+) -> Vec<OlapChange> {
+    let from_map = schema_to_infra_map(from, project, &to.database);
+    let to_map = schema_to_infra_map(to, project, &to.database);
+
+    from_map
+        .diff_with_table_strategy(
+            &to_map,
+            &ClickHouseTableDiffStrategy,
+            false,
+            false,
+            ignore_ops,
+        )
+        .olap_changes
+}
+
+fn unsupported_tables(from: &DatabaseSchema, to: &DatabaseSchema) -> Vec<UnsupportedTable> {
+    [(Side::From, from), (Side::To, to)]
+        .into_iter()
+        .flat_map(|(side, schema)| {
+            schema.unsupported.iter().map(move |t| UnsupportedTable {
+                side,
+                database: t.database.clone(),
+                table: t.name.clone(),
+                column: t.col_name.clone(),
+                column_type: t.col_type.clone(),
+            })
+        })
+        .collect()
+}
+
+fn changes_to_sql(
+    changes: &[OlapChange],
+    default_database: &str,
+) -> Result<Vec<String>, RoutineFailure> {
+    let infra_changes = InfraChanges {
+        olap_changes: changes.to_vec(),
+        ..Default::default()
+    };
+    let operations =
+        infra_changes_to_operations(&infra_changes, default_database).map_err(|e| {
+            RoutineFailure::new(
+                Message::new("DB Diff".to_string(), "Failed to order changes".to_string()),
+                e,
+            )
+        })?;
+
+    let mut statements = Vec::new();
+    for operation in &operations {
+        statements.extend(
+            operation_sql_preview(default_database, operation).map_err(|e| {
+                RoutineFailure::new(
+                    Message::new("DB Diff".to_string(), "Failed to render SQL".to_string()),
+                    e,
+                )
+            })?,
+        );
+    }
+    Ok(statements)
+}
+
+/// Compares the schemas of two ClickHouse databases and prints the changes
+/// needed to make `from` match `to`.
+pub async fn db_diff(
+    project: &Project,
+    options: DbDiffOptions<'_>,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let from = introspect(options.from_url, options.database, project, Side::From).await?;
+    let to = introspect(options.to_url, options.database, project, Side::To).await?;
+
+    let changes = diff_schemas(&from, &to, project, options.ignore_operations);
+    let unsupported = unsupported_tables(&from, &to);
+    let sql = if options.show_sql {
+        Some(changes_to_sql(&changes, &from.database)?)
+    } else {
+        None
+    };
+
+    if options.json {
+        let report = DbDiffReport {
+            version: DB_DIFF_JSON_SCHEMA_VERSION,
+            from_database: from.database,
+            to_database: to.database,
+            changes,
+            unsupported_tables: unsupported,
+            sql,
+        };
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return Ok(RoutineSuccess::success(Message::new(
+            "".to_string(),
+            "".to_string(),
+        )));
+    }
+
+    if !changes.is_empty() {
+        show_olap_changes(&changes, None);
+    }
+
+    if !unsupported.is_empty() {
+        show_table(
+            "Unsupported tables (not compared)".to_string(),
+            vec![
+                "Side".to_string(),
+                "Table".to_string(),
+                "Column".to_string(),
+                "Type".to_string(),
+            ],
+            unsupported
+                .iter()
+                .map(|t| {
+                    vec![
+                        t.side.label().to_string(),
+                        format!("{}.{}", t.database, t.table),
+                        t.column.clone(),
+                        t.column_type.clone(),
+                    ]
+                })
+                .collect(),
+        );
+    }
+
+    if let Some(statements) = sql.filter(|s| !s.is_empty()) {
+        println!();
+        for statement in statements {
+            println!("{statement};");
+        }
+        println!();
+    }
+
+    let summary = if changes.is_empty() {
+        format!(
+            "'{}' and '{}' have the same schema",
+            from.database, to.database
+        )
+    } else {
+        format!(
+            "{} change(s) between '{}' and '{}'",
+            changes.len(),
+            from.database,
+            to.database
+        )
+    };
+    Ok(RoutineSuccess::success(Message::new(
+        "DB Diff".to_string(),
+        summary,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::table::{Column, ColumnType, OrderBy};
+    use crate::framework::core::infrastructure_map::{
+        PrimitiveSignature, PrimitiveTypes, TableChange,
+    };
+    use crate::framework::core::partial_infrastructure_map::LifeCycle;
+    use crate::framework::languages::SupportedLanguages;
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+
+    fn column(name: &str) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: ColumnType::String,
+            required: true,
+            unique: false,
+            primary_key: false,
+            default: None,
+            annotations: vec![],
+            comment: None,
+            ttl: None,
+            codec: None,
+            materialized: None,
+            alias: None,
+        }
+    }
+
+    fn table(database: &str, name: &str, columns: &[&str]) -> Table {
+        Table {
+            name: name.to_string(),
+            database: Some(database.to_string()),
+            columns: columns.iter().map(|c| column(c)).collect(),
+            order_by: OrderBy::Fields(vec![columns[0].to_string()]),
+            partition_by: None,
+            sample_by: None,
+            version: None,
+            engine: ClickhouseEngine::MergeTree,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    fn schema(database: &str, tables: Vec<Table>) -> DatabaseSchema {
+        DatabaseSchema {
+            database: database.to_string(),
+            tables,
+            unsupported: vec![],
+            sql_resources: vec![],
+        }
+    }
+
+    fn test_project() -> (tempfile::TempDir, Project) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = Project::new(
+            dir.path(),
+            "db-diff".to_string(),
+            SupportedLanguages::Typescript,
+        );
+        (dir, project)
+    }
+
+    #[test]
+    fn test_identical_schemas_in_differently_named_databases_have_no_changes() {
+        let (_dir, project) = test_project();
+        let from = schema("staging", vec![table("staging", "events", &["id", "ts"])]);
+        let to = schema("prod", vec![table("prod", "events", &["id", "ts"])]);
+
+        assert!(diff_schemas(&from, &to, &project, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_added_removed_and_updated_tables() {
+        let (_dir, project) = test_project();
+        let from = schema(
+            "staging",
+            vec![
+                table("staging", "events", &["id", "ts"]),
+                table("staging", "legacy", &["id"]),
+            ],
+        );
+        let to = schema(
+            "prod",
+            vec![
+                table("prod", "events", &["id", "ts", "user_id"]),
+                table("prod", "sessions", &["id"]),
+            ],
+        );
+
+        let changes = diff_schemas(&from, &to, &project, &[]);
+
+        let mut added = vec![];
+        let mut removed = vec![];
+        let mut updated = vec![];
+        for change in &changes {
+            match change {
+                OlapChange::Table(TableChange::Added(t)) => added.push(t.name.clone()),
+                OlapChange::Table(TableChange::Removed(t)) => removed.push(t.name.clone()),
+                OlapChange::Table(TableChange::Updated { name, .. }) => updated.push(name.clone()),
+                other => panic!("unexpected change {other:?}"),
+            }
+        }
+        assert_eq!(added, vec!["sessions"]);
+        assert_eq!(removed, vec!["legacy"]);
+        assert_eq!(updated, vec!["events"]);
+    }
+
+    #[test]
+    fn test_ignored_operations_are_not_reported() {
+        let (_dir, project) = test_project();
+        let mut staging = table("staging", "events", &["id", "ts"]);
+        staging.table_ttl_setting = Some("ts + INTERVAL 7 DAY".to_string());
+        let mut prod = table("prod", "events", &["id", "ts"]);
+        prod.table_ttl_setting = Some("ts + INTERVAL 90 DAY".to_string());
+        let from = schema("staging", vec![staging]);
+        let to = schema("prod", vec![prod]);
+
+        assert_eq!(diff_schemas(&from, &to, &project, &[]).len(), 1);
+        assert!(
+            diff_schemas(&from, &to, &project, &[IgnorableOperation::ModifyTableTtl]).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_ignore_operations_are_parsed_by_config_name() {
+        assert_eq!(
+            "ModifyColumnTtl".parse::<IgnorableOperation>(),
+            Ok(IgnorableOperation::ModifyColumnTtl)
+        );
+        assert!("ModifyTtl"
+            .parse::<IgnorableOperation>()
+            .unwrap_err()
+            .contains("ModifyTableTtl"));
+    }
+
+    #[test]
+    fn test_unsupported_tables_are_listed_per_side() {
+        let mut from = schema("staging", vec![]);
+        from.unsupported.push(TableWithUnsupportedType {
+            database: "staging".to_string(),
+            name: "events_raw".to_string(),
+            col_name: "payload".to_string(),
+            col_type: "Dynamic".to_string(),
+        });
+        let to = schema("prod", vec![]);
+
+        assert_eq!(
+            unsupported_tables(&from, &to),
+            vec![UnsupportedTable {
+                side: Side::From,
+                database: "staging".to_string(),
+                table: "events_raw".to_string(),
+                column: "payload".to_string(),
+                column_type: "Dynamic".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_show_sql_renders_statements() {
+        let (_dir, project) = test_project();
+        let from = schema("staging", vec![table("staging", "events", &["id"])]);
+        let to = schema("prod", vec![]);
+
+        let changes = diff_schemas(&from, &to, &project, &[]);
+        let sql = changes_to_sql(&changes, &from.database).unwrap();
+
+        assert_eq!(sql, vec!["DROP TABLE IF EXISTS `staging`.`events`"]);
+    }
+}
@@ -0,0 +1,803 @@
+//! `moose table describe`: detailed view of a single table.
+//!
+//! By default the table is described as declared in the infrastructure map.
+//! With `--live` it is introspected from ClickHouse instead, and any
+//! differences from the map are listed alongside it.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::{RoutineFailure, RoutineSuccess};
+use crate::cli::display::{show_table, Message};
+use crate::cli::exit_code::CliExitCode;
+use crate::cli::{can_prompt, prompt_user, Decision};
+use crate::framework::core::infrastructure::table::{Column, Table, TableIndex};
+use crate::framework::core::infrastructure::{DataLineage, InfrastructureSignature};
+use crate::framework::core::infrastructure_map::{
+    compute_table_columns_diff, ColumnChange, InfrastructureMap,
+};
+use crate::framework::core::partial_infrastructure_map::LifeCycle;
+use crate::infrastructure::olap::clickhouse::{
+    check_ready, create_client, normalize_table_for_diff, IgnorableOperation,
+};
+use crate::infrastructure::olap::OlapOperations;
+use crate::project::Project;
+
+/// Schema version of the `moose table describe --json` document.
+///
+/// Bump this whenever a field is renamed, removed or changes meaning.
+pub const TABLE_DESCRIBE_JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Where the described table definition came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Source {
+    Map,
+    Live,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ColumnDescription {
+    name: String,
+    #[serde(rename = "type")]
+    column_type: String,
+    nullable: bool,
+    default: Option<String>,
+    codec: Option<String>,
+    ttl: Option<String>,
+    comment: Option<String>,
+    materialized: Option<String>,
+    alias: Option<String>,
+}
+
+impl From<&Column> for ColumnDescription {
+    fn from(column: &Column) -> Self {
+        ColumnDescription {
+            name: column.name.clone(),
+            column_type: column.data_type.to_string(),
+            nullable: !column.required,
+            default: column.default.clone(),
+            codec: column.codec.clone(),
+            ttl: column.ttl.clone(),
+            comment: column.comment.clone(),
+            materialized: column.materialized.clone(),
+            alias: column.alias.clone(),
+        }
+    }
+}
+
+/// A resource that reads from or writes to the described table.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LineageEntry {
+    kind: &'static str,
+    name: String,
+}
+
+/// A property whose value differs between the map and ClickHouse. `None` means
+/// the property (e.g. a column) is absent on that side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TableDifference {
+    field: String,
+    map: Option<String>,
+    live: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TableDescription {
+    version: u32,
+    source: Source,
+    database: String,
+    name: String,
+    columns: Vec<ColumnDescription>,
+    engine: String,
+    order_by: String,
+    primary_key: Option<String>,
+    partition_by: Option<String>,
+    sample_by: Option<String>,
+    ttl: Option<String>,
+    settings: BTreeMap<String, String>,
+    indexes: Vec<TableIndex>,
+    life_cycle: LifeCycle,
+    readers: Vec<LineageEntry>,
+    writers: Vec<LineageEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    differences: Option<Vec<TableDifference>>,
+}
+
+/// Outcome of looking a table name up in the infrastructure map.
+#[derive(Debug)]
+enum TableMatch<'a> {
+    Found(&'a Table),
+    /// The unqualified name exists in several databases; holds the
+    /// `db.table` names to choose from.
+    Ambiguous(Vec<String>),
+    NotFound,
+}
+
+fn qualified_name(table: &Table, default_database: &str) -> String {
+    format!(
+        "{}.{}",
+        table.database.as_deref().unwrap_or(default_database),
+        table.name
+    )
+}
+
+/// Finds `name` in the map. A `db.table` name only matches that database; a
+/// bare name matches the table in any database.
+fn find_table<'a>(map: &'a InfrastructureMap, name: &str) -> TableMatch<'a> {
+    let default_database = map.default_database.as_str();
+    let mut candidates: Vec<&Table> = match name.split_once('.') {
+        Some((database, table_name)) => map
+            .tables
+            .values()
+            .filter(|t| {
+                t.name == table_name
+                    && t.database.as_deref().unwrap_or(default_database) == database
+            })
+            .collect(),
+        None => map.tables.values().filter(|t| t.name == name).collect(),
+    };
+
+    match candidates.len() {
+        0 => TableMatch::NotFound,
+        1 => TableMatch::Found(candidates.remove(0)),
+        _ => {
+            let mut names: Vec<String> = candidates
+                .iter()
+                .map(|t| qualified_name(t, default_database))
+                .collect();
+            names.sort();
+            TableMatch::Ambiguous(names)
+        }
+    }
+}
+
+/// Resolves `name` to a single table, asking which database to use when the
+/// name is ambiguous and a terminal is attached.
+fn resolve_table<'a>(
+    map: &'a InfrastructureMap,
+    name: &str,
+    interactive: bool,
+) -> Result<&'a Table, RoutineFailure> {
+    match find_table(map, name) {
+        TableMatch::Found(table) => Ok(table),
+        TableMatch::NotFound => Err(RoutineFailure::error(Message::new(
+            "Table".to_string(),
+            format!("'{name}' not found in the infrastructure map"),
+        ))
+        .with_exit_code(CliExitCode::ValidationFailure)),
+        TableMatch::Ambiguous(names) if interactive => {
+            let answer = prompt_user(
+                Decision::new("the table's database", Some("a qualified db.table name")),
+                &format!(
+                    "'{name}' exists in several databases. Which one? ({})",
+                    names.join(", ")
+                ),
+                None,
+                Some("Enter a database name or a qualified db.table name"),
+            )?;
+            let answer = answer.trim();
+            let qualified = if answer.contains('.') {
+                answer.to_string()
+            } else {
+                format!("{answer}.{name}")
+            };
+            resolve_table(map, &qualified, false)
+        }
+        TableMatch::Ambiguous(names) => Err(RoutineFailure::error(Message::new(
+            "Table".to_string(),
+            format!(
+                "'{name}' exists in several databases, qualify it as one of: {}",
+                names.join(", ")
+            ),
+        ))
+        .with_exit_code(CliExitCode::ValidationFailure)),
+    }
+}
+
+/// Lists the resources that read from (`pulls_data_from`) and write to
+/// (`pushes_data_to`) `table`, based on the lineage signatures in the map.
+fn table_lineage(map: &InfrastructureMap, table: &Table) -> (Vec<LineageEntry>, Vec<LineageEntry>) {
+    let default_database = map.default_database.as_str();
+    let target = InfrastructureSignature::Table {
+        id: table.id(default_database),
+    };
+
+    let mut readers = Vec::new();
+    let mut writers = Vec::new();
+    let mut visit = |kind: &'static str, name: String, lineage: &dyn DataLineage| {
+        if lineage.pulls_data_from(default_database).contains(&target) {
+            readers.push(LineageEntry {
+                kind,
+                name: name.clone(),
+            });
+        }
+        if lineage.pushes_data_to(default_database).contains(&target) {
+            writers.push(LineageEntry { kind, name });
+        }
+    };
+
+    for process in map.topic_to_table_sync_processes.values() {
+        let topic_name = map
+            .topics
+            .get(&process.source_topic_id)
+            .map_or_else(|| process.source_topic_id.clone(), |t| t.name.clone());
+        visit("topic", topic_name, process);
+    }
+    for mv in map.materialized_views.values() {
+        visit("materializedView", mv.name.clone(), mv);
+    }
+    for view in map.views.values() {
+        visit("view", view.name.clone(), view);
+    }
+    for resource in map.sql_resources.values() {
+        visit("sqlResource", resource.name.clone(), resource);
+    }
+    for api in map.api_endpoints.values() {
+        visit("api", api.name.clone(), api);
+    }
+
+    readers.sort();
+    readers.dedup();
+    writers.sort();
+    writers.dedup();
+    (readers, writers)
+}
+
+fn primary_key(table: &Table) -> Option<String> {
+    if let Some(expr) = &table.primary_key_expression {
+        return Some(expr.clone());
+    }
+    let columns = table.primary_key_columns();
+    (!columns.is_empty()).then(|| columns.join(", "))
+}
+
+fn column_summary(column: &Column) -> String {
+    let mut summary = column.data_type.to_string();
+    if !column.required {
+        summary = format!("Nullable({summary})");
+    }
+    if let Some(default) = &column.default {
+        summary.push_str(&format!(" DEFAULT {default}"));
+    }
+    if let Some(codec) = &column.codec {
+        summary.push_str(&format!(" CODEC({codec})"));
+    }
+    if let Some(ttl) = &column.ttl {
+        summary.push_str(&format!(" TTL {ttl}"));
+    }
+    summary
+}
+
+/// Compares the map's definition of a table with the one introspected from
+/// ClickHouse, ignoring whatever `ignore_ops` tells migrations to ignore.
+fn table_differences(
+    map_table: &Table,
+    live_table: &Table,
+    ignore_ops: &[IgnorableOperation],
+) -> Vec<TableDifference> {
+    let map_table = normalize_table_for_diff(map_table, ignore_ops);
+    let live_table = normalize_table_for_diff(live_table, ignore_ops);
+
+    let mut differences: Vec<TableDifference> =
+        compute_table_columns_diff(&map_table, &live_table, ignore_ops)
+            .into_iter()
+            .map(|change| match change {
+                ColumnChange::Added { column, .. } => TableDifference {
+                    field: format!("column {}", column.name),
+                    map: None,
+                    live: Some(column_summary(&column)),
+                },
+                ColumnChange::Removed(column) => TableDifference {
+                    field: format!("column {}", column.name),
+                    map: Some(column_summary(&column)),
+                    live: None,
+                },
+                ColumnChange::Updated { before, after } => TableDifference {
+                    field: format!("column {}", before.name),
+                    map: Some(column_summary(&before)),
+                    live: Some(column_summary(&after)),
+                },
+            })
+            .collect();
+
+    let mut compare = |field: &str, map: Option<String>, live: Option<String>| {
+        if map != live {
+            differences.push(TableDifference {
+                field: field.to_string(),
+                map,
+                live,
+            });
+        }
+    };
+
+    if map_table.engine != live_table.engine {
+        compare(
+            "engine",
+            Some(map_table.engine.to_proto_string()),
+            Some(live_table.engine.to_proto_string()),
+        );
+    }
+    if !live_table.order_by_equals(&map_table) {
+        compare(
+            "order by",
+            Some(map_table.order_by.to_string()),
+            Some(live_table.order_by.to_string()),
+        );
+    }
+    if map_table.normalized_primary_key_expr() != live_table.normalized_primary_key_expr() {
+        compare(
+            "primary key",
+            primary_key(&map_table),
+            primary_key(&live_table),
+        );
+    }
+    compare(
+        "partition by",
+        map_table.partition_by.clone(),
+        live_table.partition_by.clone(),
+    );
+    compare(
+        "sample by",
+        map_table.sample_by.clone(),
+        live_table.sample_by.clone(),
+    );
+    compare(
+        "ttl",
+        map_table.table_ttl_setting.clone(),
+        live_table.table_ttl_setting.clone(),
+    );
+
+    differences
+}
+
+fn describe(
+    table: &Table,
+    default_database: &str,
+    source: Source,
+    life_cycle: LifeCycle,
+    lineage: (Vec<LineageEntry>, Vec<LineageEntry>),
+    differences: Option<Vec<TableDifference>>,
+) -> TableDescription {
+    let (readers, writers) = lineage;
+    TableDescription {
+        version: TABLE_DESCRIBE_JSON_SCHEMA_VERSION,
+        source,
+        database: table
+            .database
+            .clone()
+            .unwrap_or_else(|| default_database.to_string()),
+        name: table.name.clone(),
+        columns: table.columns.iter().map(ColumnDescription::from).collect(),
+        engine: table.engine.to_proto_string(),
+        order_by: table.order_by.to_string(),
+        primary_key: primary_key(table),
+        partition_by: table.partition_by.clone(),
+        sample_by: table.sample_by.clone(),
+        ttl: table.table_ttl_setting.clone(),
+        settings: table
+            .table_settings
+            .iter()
+            .flatten()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+        indexes: table.indexes.clone(),
+        life_cycle,
+        readers,
+        writers,
+        differences,
+    }
+}
+
+async fn fetch_live_table(
+    project: &Project,
+    database: &str,
+    name: &str,
+) -> Result<Table, RoutineFailure> {
+    let connectivity_failure = |e: anyhow::Error| {
+        RoutineFailure::new(
+            Message::new(
+                "Table".to_string(),
+                format!("Failed to introspect '{database}.{name}' from ClickHouse"),
+            ),
+            e,
+        )
+        .with_exit_code(CliExitCode::ConnectivityFailure)
+    };
+
+    let client = create_client(project.clickhouse_config.clone());
+    check_ready(&client)
+        .await
+        .map_err(|e| connectivity_failure(e.into()))?;
+    let (tables, unsupported) = client
+        .list_tables(database, project)
+        .await
+        .map_err(|e| connectivity_failure(e.into()))?;
+
+    if let Some(t) = unsupported.iter().find(|t| t.name == name) {
+        return Err(RoutineFailure::error(Message::new(
+            "Table".to_string(),
+            format!(
+                "'{database}.{name}' cannot be described: column '{}' has unsupported type {}",
+                t.col_name, t.col_type
+            ),
+        )));
+    }
+
+    tables.into_iter().find(|t| t.name == name).ok_or_else(|| {
+        RoutineFailure::error(Message::new(
+            "Table".to_string(),
+            format!("'{database}.{name}' is in the infrastructure map but not in ClickHouse"),
+        ))
+    })
+}
+
+fn optional(value: &Option<String>) -> String {
+    value.clone().unwrap_or_default()
+}
+
+fn show_description(description: &TableDescription) {
+    show_table(
+        format!("{}.{}", description.database, description.name),
+        vec![
+            "Column".to_string(),
+            "Type".to_string(),
+            "Nullable".to_string(),
+            "Default".to_string(),
+            "Codec".to_string(),
+            "TTL".to_string(),
+            "Comment".to_string(),
+        ],
+        description
+            .columns
+            .iter()
+            .map(|c| {
+                let default = match (&c.default, &c.materialized, &c.alias) {
+                    (Some(d), _, _) => d.clone(),
+                    (None, Some(m), _) => format!("MATERIALIZED {m}"),
+                    (None, None, Some(a)) => format!("ALIAS {a}"),
+                    (None, None, None) => String::new(),
+                };
+                vec![
+                    c.name.clone(),
+                    c.column_type.clone(),
+                    if c.nullable { "yes" } else { "no" }.to_string(),
+                    default,
+                    optional(&c.codec),
+                    optional(&c.ttl),
+                    optional(&c.comment),
+                ]
+            })
+            .collect(),
+    );
+
+    let mut properties = vec![
+        vec!["Engine".to_string(), description.engine.clone()],
+        vec!["ORDER BY".to_string(), description.order_by.clone()],
+    ];
+    for (label, value) in [
+        ("PRIMARY KEY", &description.primary_key),
+        ("PARTITION BY", &description.partition_by),
+        ("SAMPLE BY", &description.sample_by),
+        ("TTL", &description.ttl),
+    ] {
+        if let Some(value) = value {
+            properties.push(vec![label.to_string(), value.clone()]);
+        }
+    }
+    for (key, value) in &description.settings {
+        properties.push(vec![format!("SETTINGS {key}"), value.clone()]);
+    }
+    properties.push(vec![
+        "Lifecycle".to_string(),
+        format!("{:?}", description.life_cycle),
+    ]);
+    show_table(
+        "Properties".to_string(),
+        vec!["Property".to_string(), "Value".to_string()],
+        properties,
+    );
+
+    if !description.indexes.is_empty() {
+        show_table(
+            "Indexes".to_string(),
+            vec![
+                "Name".to_string(),
+                "Expression".to_string(),
+                "Type".to_string(),
+                "Granularity".to_string(),
+            ],
+            description
+                .indexes
+                .iter()
+                .map(|i| {
+                    let index_type = if i.arguments.is_empty() {
+                        i.index_type.clone()
+                    } else {
+                        format!("{}({})", i.index_type, i.arguments.join(", "))
+                    };
+                    vec![
+                        i.name.clone(),
+                        i.expression.clone(),
+                        index_type,
+                        i.granularity.to_string(),
+                    ]
+                })
+                .collect(),
+        );
+    }
+
+    let lineage: Vec<Vec<String>> = description
+        .readers
+        .iter()
+        .map(|r| ("reads", r))
+        .chain(description.writers.iter().map(|w| ("writes", w)))
+        .map(|(direction, entry)| {
+            vec![
+                entry.kind.to_string(),
+                entry.name.clone(),
+                direction.to_string(),
+            ]
+        })
+        .collect();
+    if !lineage.is_empty() {
+        show_table(
+            "Lineage".to_string(),
+            vec![
+                "Kind".to_string(),
+                "Name".to_string(),
+                "Direction".to_string(),
+            ],
+            lineage,
+        );
+    }
+
+    if let Some(differences) = description.differences.as_ref().filter(|d| !d.is_empty()) {
+        show_table(
+            "Differences from the infrastructure map".to_string(),
+            vec!["Field".to_string(), "Map".to_string(), "Live".to_string()],
+            differences
+                .iter()
+                .map(|d| {
+                    vec![
+                        d.field.clone(),
+                        d.map.clone().unwrap_or_else(|| "(missing)".to_string()),
+                        d.live.clone().unwrap_or_else(|| "(missing)".to_string()),
+                    ]
+                })
+                .collect(),
+        );
+    }
+}
+
+/// Describes a single table from the infrastructure map, or from ClickHouse
+/// when `live` is set.
+pub async fn describe_table(
+    project: &Project,
+    name: &str,
+    live: bool,
+    json: bool,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    // Don't resolve credentials - only the table structure is needed
+    let infra_map = InfrastructureMap::load_from_user_code(project, false)
+        .await
+        .map_err(|e| {
+            RoutineFailure::new(
+                Message::new("Load".to_string(), "Infrastructure".to_string()),
+                e,
+            )
+        })?;
+
+    let interactive = !json && can_prompt();
+    let table = resolve_table(&infra_map, name, interactive)?;
+    let default_database = infra_map.default_database.as_str();
+    let lineage = table_lineage(&infra_map, table);
+
+    let description = if live {
+        let database = table.database.as_deref().unwrap_or(default_database);
+        let live_table = fetch_live_table(project, database, &table.name).await?;
+        let differences = table_differences(
+            table,
+            &live_table,
+            &project.migration_config.ignore_operations,
+        );
+        describe(
+            &live_table,
+            default_database,
+            Source::Live,
+            table.life_cycle,
+            lineage,
+            Some(differences),
+        )
+    } else {
+        describe(
+            table,
+            default_database,
+            Source::Map,
+            table.life_cycle,
+            lineage,
+            None,
+        )
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&description).unwrap());
+        return Ok(RoutineSuccess::success(Message::new(
+            "".to_string(),
+            "".to_string(),
+        )));
+    }
+
+    show_description(&description);
+
+    let summary = match &description.differences {
+        Some(d) if d.is_empty() => "ClickHouse matches the infrastructure map".to_string(),
+        Some(d) => format!("{} difference(s) from the infrastructure map", d.len()),
+        None => format!("{} column(s)", description.columns.len()),
+    };
+    Ok(RoutineSuccess::success(Message::new(
+        "Table".to_string(),
+        summary,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::materialized_view::MaterializedView;
+    use crate::framework::core::infrastructure::table::{ColumnType, OrderBy};
+    use crate::framework::core::infrastructure_map::{PrimitiveSignature, PrimitiveTypes};
+    use crate::framework::languages::SupportedLanguages;
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+
+    fn column(name: &str) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: ColumnType::String,
+            required: true,
+            unique: false,
+            primary_key: false,
+            default: None,
+            annotations: vec![],
+            comment: None,
+            ttl: None,
+            codec: None,
+            materialized: None,
+            alias: None,
+        }
+    }
+
+    fn table(database: Option<&str>, name: &str) -> Table {
+        Table {
+            name: name.to_string(),
+            database: database.map(str::to_string),
+            columns: vec![column("id"), column("ts")],
+            order_by: OrderBy::Fields(vec!["id".to_string()]),
+            partition_by: None,
+            sample_by: None,
+            version: None,
+            engine: ClickhouseEngine::MergeTree,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    fn infra_map(tables: Vec<Table>) -> (tempfile::TempDir, InfrastructureMap) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = Project::new(
+            dir.path(),
+            "describe".to_string(),
+            SupportedLanguages::Typescript,
+        );
+        let mut map = InfrastructureMap::empty_from_project(&project);
+        map.default_database = "local".to_string();
+        for table in tables {
+            map.tables.insert(table.id("local"), table);
+        }
+        (dir, map)
+    }
+
+    #[test]
+    fn test_find_table_handles_qualified_and_ambiguous_names() {
+        let (_dir, map) = infra_map(vec![
+            table(None, "events"),
+            table(Some("analytics"), "events"),
+            table(Some("analytics"), "sessions"),
+        ]);
+
+        assert!(matches!(
+            find_table(&map, "sessions"),
+            TableMatch::Found(t) if t.name == "sessions"
+        ));
+        match find_table(&map, "events") {
+            TableMatch::Ambiguous(names) => {
+                assert_eq!(names, vec!["analytics.events", "local.events"])
+            }
+            other => panic!("expected an ambiguous match, got {other:?}"),
+        }
+        assert!(matches!(
+            find_table(&map, "local.events"),
+            TableMatch::Found(t) if t.database.is_none()
+        ));
+        assert!(matches!(
+            find_table(&map, "analytics.events"),
+            TableMatch::Found(t) if t.database.as_deref() == Some("analytics")
+        ));
+        assert!(matches!(find_table(&map, "missing"), TableMatch::NotFound));
+    }
+
+    #[test]
+    fn test_ambiguous_name_without_terminal_is_a_validation_failure() {
+        let (_dir, map) = infra_map(vec![
+            table(None, "events"),
+            table(Some("analytics"), "events"),
+        ]);
+
+        let failure = resolve_table(&map, "events", false).unwrap_err();
+        assert_eq!(failure.exit_code, CliExitCode::ValidationFailure);
+        assert!(failure.message.details.contains("analytics.events"));
+    }
+
+    #[test]
+    fn test_lineage_lists_materialized_view_readers_and_writers() {
+        let (_dir, mut map) = infra_map(vec![table(None, "events"), table(None, "daily")]);
+        let mv = MaterializedView::new(
+            "daily_mv",
+            "SELECT id, ts FROM events",
+            vec!["events".to_string()],
+            "daily",
+        );
+        map.materialized_views.insert(mv.name.clone(), mv);
+
+        let (readers, writers) = table_lineage(&map, map.find_table_by_name("events").unwrap());
+        assert_eq!(
+            readers,
+            vec![LineageEntry {
+                kind: "materializedView",
+                name: "daily_mv".to_string(),
+            }]
+        );
+        assert!(writers.is_empty());
+
+        let (readers, writers) = table_lineage(&map, map.find_table_by_name("daily").unwrap());
+        assert!(readers.is_empty());
+        assert_eq!(writers.len(), 1);
+    }
+
+    #[test]
+    fn test_table_differences_reports_columns_and_order_by() {
+        let map_table = table(None, "events");
+        let mut live_table = table(Some("local"), "events");
+        live_table.columns.push(column("extra"));
+        live_table.order_by = OrderBy::Fields(vec!["ts".to_string()]);
+
+        let differences = table_differences(&map_table, &live_table, &[]);
+        assert!(differences.contains(&TableDifference {
+            field: "column extra".to_string(),
+            map: None,
+            live: Some("String".to_string()),
+        }));
+        assert!(differences.iter().any(|d| d.field == "order by"));
+
+        assert!(table_differences(&map_table, &map_table, &[]).is_empty());
+    }
+}
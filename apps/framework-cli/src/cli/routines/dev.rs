@@ -8,14 +8,21 @@ use super::{
 use crate::cli::display::{
     show_message_wrapper, with_spinner_completion, with_timing, Message, MessageType,
 };
+use crate::cli::local_webserver::LocalWebserverConfig;
 use crate::cli::settings::Settings;
+use crate::cli::{can_prompt, prompt_user, Decision};
+use crate::framework::core::destructive_changes::{self, DestructiveChange};
+use crate::framework::core::plan::InfraPlan;
 use crate::framework::languages::SupportedLanguages;
+use crate::infrastructure::olap::clickhouse::describe_operation;
+use crate::infrastructure::olap::ddl_ordering::{order_olap_changes, AtomicOlapOperation};
 use crate::project::Project;
 use crate::utilities::constants::{CLI_PROJECT_INTERNAL_DIR, SHOW_TIMING};
 use crate::utilities::package_managers::{
     check_local_pnpm_version_warning, detect_pnpm_deploy_mode, find_pnpm_workspace_root,
     legacy_deploy_terminal_message, legacy_deploy_warning_message, PnpmDeployMode,
 };
+use crate::utilities::ports::{is_port_available, next_free_port, port_holder, PORT_SEARCH_RANGE};
 use crate::{cli::routines::util::ensure_docker_running, utilities::docker::DockerClient};
 use lazy_static::lazy_static;
 use std::sync::atomic::Ordering;
@@ -157,3 +164,240 @@ pub fn create_docker_compose_file(
         )),
     }
 }
+
+/// Port overrides passed to `moose dev` on the command line.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DevPortOptions {
+    pub port: Option<u16>,
+    pub management_port: Option<u16>,
+    pub auto_port: bool,
+}
+
+/// Applies the command-line port overrides to the webserver config, then makes
+/// sure the API and management ports can be bound before anything starts.
+///
+/// Ports given explicitly on the command line are never moved: if they are
+/// busy the command fails. Ports coming from the config are moved to the next
+/// free port either automatically (`--auto-port`) or after asking the user.
+/// Everything that formats URLs reads the config afterwards, so the chosen
+/// ports show up in the MCP message, the OpenAPI server list, etc.
+pub fn resolve_dev_ports(
+    config: &mut LocalWebserverConfig,
+    options: DevPortOptions,
+) -> Result<(), RoutineFailure> {
+    if let Some(port) = options.port {
+        config.port = port;
+    }
+    if let Some(management_port) = options.management_port {
+        config.management_port = management_port;
+    }
+
+    let host = config.host.clone();
+
+    config.port = resolve_port(
+        &host,
+        "API",
+        config.port,
+        &[config.management_port, config.proxy_port],
+        options.port.is_some(),
+        options.auto_port,
+        "--port",
+    )?;
+    config.management_port = resolve_port(
+        &host,
+        "Management",
+        config.management_port,
+        &[config.port, config.proxy_port],
+        options.management_port.is_some(),
+        options.auto_port,
+        "--management-port",
+    )?;
+
+    Ok(())
+}
+
+fn resolve_port(
+    host: &str,
+    label: &str,
+    port: u16,
+    reserved: &[u16],
+    explicit: bool,
+    auto_port: bool,
+    flag: &str,
+) -> Result<u16, RoutineFailure> {
+    if is_port_available(host, port) {
+        return Ok(port);
+    }
+
+    let holder = port_holder(port)
+        .map(|holder| format!(" by {holder}"))
+        .unwrap_or_default();
+    let busy = format!("{label} port {port} is already in use{holder}");
+
+    if explicit {
+        return Err(RoutineFailure::error(Message::new(
+            "Port".to_string(),
+            format!("{busy}. Choose another one with {flag} <PORT>"),
+        )));
+    }
+
+    let Some(next) = next_free_port(host, port, reserved) else {
+        return Err(RoutineFailure::error(Message::new(
+            "Port".to_string(),
+            format!(
+                "{busy} and no free port was found in the next {PORT_SEARCH_RANGE}. Choose one with {flag} <PORT>"
+            ),
+        )));
+    };
+
+    let accepted = if auto_port {
+        true
+    } else if can_prompt() {
+        let answer = prompt_user(
+            Decision::new("a free port", Some("--auto-port")),
+            &format!("{busy}. Use port {next} instead? [Y/n]"),
+            Some("y"),
+            None,
+        )?;
+        matches!(answer.to_lowercase().as_str(), "y" | "yes")
+    } else {
+        false
+    };
+
+    if !accepted {
+        return Err(RoutineFailure::error(Message::new(
+            "Port".to_string(),
+            format!("{busy}. Pass {flag} <PORT> or --auto-port to use a free port"),
+        )));
+    }
+
+    show_message_wrapper(
+        MessageType::Warning,
+        Message::new("Port".to_string(), format!("{busy}, using {next} instead")),
+    );
+    Ok(next)
+}
+
+/// Whether a plan is applied after [`confirm_destructive_changes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanConfirmation {
+    Apply,
+    Abort,
+}
+
+/// Lists the database operations of `plan`, in the order they run, when some
+/// of them lose data, and asks whether to apply them all, skip some of those
+/// or abort. Skipped operations are taken out of `plan` and proposed again by
+/// the next plan, see [`destructive_changes`].
+///
+/// Nothing is asked with `dev.confirm_destructive_changes` off (`--yes`) or
+/// when no prompt can be shown, as in CI. Callers ask before starting a
+/// spinner, which would draw over the prompt.
+pub async fn confirm_destructive_changes(
+    project: &Project,
+    plan: &mut InfraPlan,
+) -> Result<PlanConfirmation, RoutineFailure> {
+    if !project.dev.confirm_destructive_changes || !project.features.olap || !can_prompt() {
+        return Ok(PlanConfirmation::Apply);
+    }
+
+    let default_database = &project.clickhouse_config.db_name;
+    // A plan that cannot be ordered fails before anything runs, with the error
+    let Ok((teardown, setup)) = order_olap_changes(&plan.changes.olap_changes, default_database)
+    else {
+        return Ok(PlanConfirmation::Apply);
+    };
+    let operations: Vec<_> = teardown
+        .iter()
+        .map(|operation| ("Teardown", operation))
+        .chain(setup.iter().map(|operation| ("Setup", operation)))
+        .collect();
+    let destructive = destructive_changes::classify_operations(
+        &plan.changes.olap_changes,
+        operations.iter().map(|(_, operation)| *operation),
+        default_database,
+    );
+    if destructive.iter().all(Option::is_none) {
+        return Ok(PlanConfirmation::Apply);
+    }
+
+    for ((phase, operation), change) in operations.iter().zip(&destructive) {
+        show_message_wrapper(
+            if change.is_some() {
+                MessageType::Error
+            } else {
+                MessageType::Info
+            },
+            Message::new(phase.to_string(), describe(operation, change.as_ref())),
+        );
+    }
+
+    let decision = Decision::confirmation("destructive database changes", "--yes");
+    let answer = prompt_in_background(
+        decision,
+        "\nThe operations in red lose data. Apply all of them, skip some, or abort? [a/s/N]"
+            .to_string(),
+    )
+    .await?;
+    match answer.to_lowercase().as_str() {
+        "a" | "all" => return Ok(PlanConfirmation::Apply),
+        "s" | "skip" => {}
+        _ => {
+            show_message_wrapper(
+                MessageType::Warning,
+                Message::new(
+                    "Aborted".to_string(),
+                    "the database changes were not applied".to_string(),
+                ),
+            );
+            return Ok(PlanConfirmation::Abort);
+        }
+    }
+
+    let mut skipped = Vec::new();
+    for ((_, operation), change) in operations.iter().zip(&destructive) {
+        let Some(change) = change else {
+            continue;
+        };
+        let description = describe(operation, Some(change));
+        let answer =
+            prompt_in_background(decision, format!("Skip \"{description}\"? [y/N]")).await?;
+        if matches!(answer.to_lowercase().as_str(), "y" | "yes") {
+            show_message_wrapper(
+                MessageType::Warning,
+                Message::new(
+                    "Skipped".to_string(),
+                    format!("{description}, it is proposed again by the next plan"),
+                ),
+            );
+            skipped.push(change.clone());
+        }
+    }
+    destructive_changes::skip_changes(plan, &skipped, default_database);
+
+    Ok(PlanConfirmation::Apply)
+}
+
+/// Asks `prompt_text`, defaulting to no, while the runtime keeps serving the
+/// dev server
+async fn prompt_in_background(
+    decision: Decision<'static>,
+    prompt_text: String,
+) -> Result<String, RoutineFailure> {
+    tokio::task::spawn_blocking(move || prompt_user(decision, &prompt_text, Some("n"), None))
+        .await
+        .map_err(|e| {
+            RoutineFailure::new(
+                Message::new("Failed".to_string(), "to read the answer".to_string()),
+                e,
+            )
+        })?
+}
+
+fn describe(operation: &AtomicOlapOperation, change: Option<&DestructiveChange>) -> String {
+    let description = describe_operation(&operation.to_minimal());
+    match change {
+        Some(DestructiveChange::RecreateTable { .. }) => format!("{description} to re-create it"),
+        _ => description,
+    }
+}
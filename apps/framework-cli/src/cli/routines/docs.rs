@@ -114,8 +114,9 @@ pub fn resolve_language(
     }
 
     // 4. Interactive prompt (only if stdout is a terminal)
-    if std::io::stdout().is_terminal() {
+    if std::io::stdout().is_terminal() && !crate::cli::is_non_interactive() {
         let input = crate::cli::prompt_user(
+            crate::cli::Decision::new("the docs language", Some("--lang")),
             "Select language [1] TypeScript [2] Python",
             Some("1"),
             Some("This will be saved for future use"),
@@ -1503,7 +1504,12 @@ pub async fn browse_docs(
             );
         }
         println!();
-        let input = crate::cli::prompt_user("Enter number", Some("1"), None)?;
+        let input = crate::cli::prompt_user(
+            crate::cli::Decision::new("the documentation page", Some("a page slug")),
+            "Enter number",
+            Some("1"),
+            None,
+        )?;
         let idx: usize = input.trim().parse().unwrap_or(1);
         if idx == 0 || idx > all.len() {
             return Err(RoutineFailure::error(Message::new(
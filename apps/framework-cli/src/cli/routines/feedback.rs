@@ -9,6 +9,7 @@ use crate::utilities::capture::{
 use crate::utilities::constants::{
     CLI_USER_DIRECTORY, CLI_VERSION, GITHUB_ISSUES_URL, SLACK_COMMUNITY_URL, SUPPORT_EMAIL,
 };
+use crate::utilities::telemetry::telemetry_enabled;
 use std::collections::HashMap;
 
 /// Build a GitHub issue URL with pre-filled environment info, log paths, and optional description
@@ -139,7 +140,7 @@ pub async fn send_feedback(
     settings: &Settings,
     machine_id: String,
 ) -> Result<RoutineSuccess, RoutineFailure> {
-    if !settings.telemetry.enabled {
+    if !telemetry_enabled(settings) {
         return Err(RoutineFailure::error(Message::new(
             "Telemetry disabled".to_string(),
             format!(
@@ -1,43 +1,396 @@
-use std::{
-    io::{BufRead, BufReader},
-    process::{Command, Stdio},
-};
+//! Reading and filtering the Moose CLI log file for `moose logs`.
+//!
+//! Log lines are written by the compact `tracing-subscriber` formatter in
+//! [`crate::cli::logger`] and look like:
+//!
+//! ```text
+//! 2025-01-15T10:00:00.123456Z  INFO moose_cli::cli::routines: Starting dev server
+//! ```
+//!
+//! With `format = "json"`, each line is instead a JSON object with
+//! `timestamp`, `level`, `target` and `message` fields.
+//!
+//! Each line is parsed into a timestamp, level and target (module) so that
+//! `--level`, `--since` and `--source` can be applied. Lines that don't start
+//! with a timestamp (e.g. the continuation of a multi-line message) inherit the
+//! verdict of the entry they belong to.
+//!
+//! With `--since`, rotated (and compressed) log files written to since then are
+//! read before the active one.
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Deserialize;
 
 use crate::cli::display::{Message, MessageType};
+use crate::cli::log_rotation::read_log_lines;
 
 use super::{RoutineFailure, RoutineSuccess};
 
-pub fn show_logs(log_file_path: String, filter: String) -> Result<RoutineSuccess, RoutineFailure> {
-    let child = Command::new("tail")
-        .arg(log_file_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|err| {
-            RoutineFailure::new(
-                Message::new("Failed".to_string(), "to show logs".to_string()),
-                err,
-            )
-        })?;
-
-    let output = child.wait_with_output().map_err(|err| {
-        RoutineFailure::new(
-            Message::new("Failed".to_string(), "to show logs".to_string()),
-            err,
-        )
-    })?;
+/// How often the log file is polled for new lines in follow mode.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, thiserror::Error)]
+pub enum LogQueryError {
+    #[error("Unknown log level '{0}'. Expected one of: error, warn, info, debug, trace")]
+    UnknownLevel(String),
+
+    #[error("Invalid regex in --filter")]
+    InvalidPattern(#[from] regex::Error),
+
+    #[error("Invalid --since '{0}'. Expected a duration (e.g. 1h, 30m) or an RFC 3339 timestamp")]
+    InvalidSince(String),
+}
+
+/// Log levels in increasing order of severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "TRACE",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+}
+
+impl std::str::FromStr for LogLevel {
+    type Err = LogQueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "trace" => Ok(LogLevel::Trace),
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            _ => Err(LogQueryError::UnknownLevel(s.to_string())),
+        }
+    }
+}
+
+/// Text matcher for `--filter`: a plain substring, or a regex when prefixed with `re:`.
+#[derive(Debug)]
+pub enum LogPattern {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl LogPattern {
+    pub fn parse(filter: &str) -> Result<Self, LogQueryError> {
+        match filter.strip_prefix("re:") {
+            Some(pattern) => Ok(LogPattern::Regex(Regex::new(pattern)?)),
+            None => Ok(LogPattern::Substring(filter.to_string())),
+        }
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        match self {
+            LogPattern::Substring(s) => line.contains(s.as_str()),
+            LogPattern::Regex(re) => re.is_match(line),
+        }
+    }
+}
+
+/// Parses `--since` as either a duration relative to `now` or an RFC 3339 timestamp.
+pub fn parse_since(since: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, LogQueryError> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(since) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    let duration = humantime::parse_duration(since)
+        .map_err(|_| LogQueryError::InvalidSince(since.to_string()))?;
+    let duration = chrono::Duration::from_std(duration)
+        .map_err(|_| LogQueryError::InvalidSince(since.to_string()))?;
+    Ok(now - duration)
+}
+
+/// The structured prefix of a log line.
+#[derive(Debug, PartialEq)]
+struct LogEntryHeader<'a> {
+    timestamp: DateTime<Utc>,
+    level: LogLevel,
+    target: Option<&'a str>,
+    /// The message of a JSON line, text lines carry it after the header
+    message: Option<Cow<'a, str>>,
+}
+
+/// The fields of a JSON log line used by the filters.
+#[derive(Deserialize)]
+struct JsonLogLine<'a> {
+    timestamp: &'a str,
+    level: &'a str,
+    #[serde(default)]
+    target: Option<&'a str>,
+    #[serde(default, borrow)]
+    message: Option<Cow<'a, str>>,
+}
+
+/// Parses the `<timestamp> <LEVEL> [spans:] <target>: ` prefix of a log line,
+/// or the fields of a JSON line.
+///
+/// Returns `None` for lines that don't start a new entry.
+fn parse_header(line: &str) -> Option<LogEntryHeader<'_>> {
+    if line.starts_with('{') {
+        let json: JsonLogLine = serde_json::from_str(line).ok()?;
+        return Some(LogEntryHeader {
+            timestamp: DateTime::parse_from_rfc3339(json.timestamp)
+                .ok()?
+                .with_timezone(&Utc),
+            level: json.level.parse().ok()?,
+            target: json.target,
+            message: Some(json.message.unwrap_or_default()),
+        });
+    }
+
+    let mut tokens = line.split_whitespace();
+    let timestamp = DateTime::parse_from_rfc3339(tokens.next()?)
+        .ok()?
+        .with_timezone(&Utc);
+    let level = tokens.next()?.parse().ok()?;
+    // Spans render as `name{fields}:` and are skipped; the target is the
+    // first `path::like:` token.
+    let target = tokens
+        .find(|token| {
+            token.ends_with(':')
+                && token.len() > 1
+                && token[..token.len() - 1]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+        })
+        .map(|token| &token[..token.len() - 1]);
+    Some(LogEntryHeader {
+        timestamp,
+        level,
+        target,
+        message: None,
+    })
+}
+
+/// The message of the entry `line` starts, parsed into `header`.
+fn header_message<'a>(line: &'a str, header: &LogEntryHeader<'a>) -> Cow<'a, str> {
+    if let Some(message) = &header.message {
+        return message.clone();
+    }
+    if let Some((_, message)) = header
+        .target
+        .and_then(|target| line.split_once(&format!("{target}: ")))
+    {
+        return message.into();
+    }
+    // No target: skip the timestamp and level
+    let mut rest = line;
+    for _ in 0..2 {
+        rest = rest.trim_start();
+        rest = &rest[rest.find(char::is_whitespace).unwrap_or(rest.len())..];
+    }
+    rest.trim_start().into()
+}
+
+/// A log entry, continuation lines included in its message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub timestamp: DateTime<Utc>,
+    pub level: LogLevel,
+    pub target: Option<String>,
+    pub message: String,
+}
+
+/// Filters applied to each log entry. All set filters must match.
+#[derive(Debug, Default)]
+pub struct LogQuery {
+    pub level: Option<LogLevel>,
+    pub pattern: Option<LogPattern>,
+    pub since: Option<DateTime<Utc>>,
+    pub source: Option<String>,
+}
+
+impl LogQuery {
+    fn header_matches(&self, header: &LogEntryHeader) -> bool {
+        self.level.is_none_or(|min| header.level >= min)
+            && self.since.is_none_or(|since| header.timestamp >= since)
+            && self
+                .source
+                .as_deref()
+                .is_none_or(|source| header.target.is_some_and(|target| target.contains(source)))
+    }
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let lines = stdout.lines().filter(|line| line.contains(&filter));
+/// Applies a [`LogQuery`] to a stream of lines, tracking multi-line entries.
+struct LogLineFilter<'a> {
+    query: &'a LogQuery,
+    /// Whether the entry the next continuation line belongs to passed the header filters
+    current_entry_matches: bool,
+}
+
+impl<'a> LogLineFilter<'a> {
+    fn new(query: &'a LogQuery) -> Self {
+        let has_header_filters =
+            query.level.is_some() || query.since.is_some() || query.source.is_some();
+        Self {
+            query,
+            // Without header filters, leading lines that can't be parsed are still shown
+            current_entry_matches: !has_header_filters,
+        }
+    }
+
+    fn accepts(&mut self, line: &str) -> bool {
+        if let Some(header) = parse_header(line) {
+            self.current_entry_matches = self.query.header_matches(&header);
+        }
+        self.current_entry_matches
+            && self
+                .query
+                .pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(line))
+    }
+}
+
+/// Returns the last `tail` lines (or all lines when `None`) accepted by `query`.
+fn filter_lines<I>(lines: I, query: &LogQuery, tail: Option<usize>) -> Vec<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut filter = LogLineFilter::new(query);
+    let mut matched = VecDeque::new();
     for line in lines {
-        show_message!(
-            MessageType::Info,
-            Message {
-                action: "Log".to_string(),
-                details: line.to_string(),
-            },
-            true
-        );
+        if filter.accepts(&line) {
+            matched.push_back(line);
+            if tail.is_some_and(|n| matched.len() > n) {
+                matched.pop_front();
+            }
+        }
+    }
+    matched.into()
+}
+
+/// Groups `lines` into entries and returns the last `tail` accepted by
+/// `query`. Lines before the first entry header are dropped.
+pub fn tail_records<I>(lines: I, query: &LogQuery, tail: usize) -> Vec<LogRecord>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut matched = VecDeque::new();
+    let mut keep = |record: LogRecord, header_matches: bool| {
+        if header_matches
+            && query
+                .pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(&record.message))
+        {
+            matched.push_back(record);
+            if matched.len() > tail {
+                matched.pop_front();
+            }
+        }
+    };
+
+    let mut current: Option<(LogRecord, bool)> = None;
+    for line in lines {
+        match parse_header(&line) {
+            Some(header) => {
+                if let Some((record, header_matches)) = current.take() {
+                    keep(record, header_matches);
+                }
+                let record = LogRecord {
+                    timestamp: header.timestamp,
+                    level: header.level,
+                    target: header.target.map(str::to_string),
+                    message: header_message(&line, &header).into_owned(),
+                };
+                current = Some((record, query.header_matches(&header)));
+            }
+            None => {
+                if let Some((record, _)) = current.as_mut() {
+                    record.message.push('\n');
+                    record.message.push_str(&line);
+                }
+            }
+        }
+    }
+    if let Some((record, header_matches)) = current {
+        keep(record, header_matches);
+    }
+    matched.into()
+}
+
+fn print_log_line(line: &str) {
+    show_message!(
+        MessageType::Info,
+        Message {
+            action: "Log".to_string(),
+            details: line.to_string(),
+        },
+        true
+    );
+}
+
+fn open_failure(path: &Path, err: std::io::Error) -> RoutineFailure {
+    RoutineFailure::new(
+        Message::new("Failed".to_string(), format!("to open {}", path.display())),
+        err,
+    )
+}
+
+fn open_log_file(log_file_path: &Path) -> Result<File, RoutineFailure> {
+    File::open(log_file_path).map_err(|err| open_failure(log_file_path, err))
+}
+
+/// Lines of the rotated `log_files`, oldest first.
+fn rotated_lines(log_files: &[PathBuf]) -> Result<Vec<String>, RoutineFailure> {
+    let mut lines = Vec::new();
+    for path in log_files {
+        match read_log_lines(path) {
+            Ok(file_lines) => lines.extend(file_lines),
+            // Pruned since the directory was listed
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(open_failure(path, err)),
+        }
+    }
+    Ok(lines)
+}
+
+fn read_error(err: std::io::Error) -> RoutineFailure {
+    RoutineFailure::new(
+        Message::new("Failed".to_string(), "to read line from logs".to_string()),
+        err,
+    )
+}
+
+/// Prints the last `tail` log lines matching `query`, reading the `rotated`
+/// log files before the active `log_file_path`.
+pub fn show_logs(
+    log_file_path: PathBuf,
+    rotated: &[PathBuf],
+    query: &LogQuery,
+    tail: Option<usize>,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let file = open_log_file(&log_file_path)?;
+    let mut lines = rotated_lines(rotated)?;
+    for line in BufReader::new(file).lines() {
+        lines.push(line.map_err(read_error)?);
+    }
+
+    for line in filter_lines(lines, query, tail) {
+        print_log_line(&line);
     }
 
     Ok(RoutineSuccess::success(Message::new(
@@ -46,51 +399,290 @@ pub fn show_logs(log_file_path: String, filter: String) -> Result<RoutineSuccess
     )))
 }
 
+/// Prints the last `tail` log lines matching `query`, then keeps printing new
+/// matching lines as they are appended to the file.
 pub fn follow_logs(
-    log_file_path: String,
-    filter: String,
+    log_file_path: PathBuf,
+    rotated: &[PathBuf],
+    query: &LogQuery,
+    tail: Option<usize>,
 ) -> Result<RoutineSuccess, RoutineFailure> {
-    let child = Command::new("tail")
-        .arg("-f")
-        .arg(log_file_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|err| {
-            RoutineFailure::new(
-                Message::new("Failed".to_string(), "to show logs".to_string()),
-                err,
-            )
-        })?;
-
-    if let Some(out) = child.stdout {
-        let reader = BufReader::new(out);
-        for line_result in reader.lines() {
-            let line = match line_result {
-                Ok(line) => line,
-                Err(err) => {
-                    return Err(RoutineFailure::new(
-                        Message::new("Failed".to_string(), "to read line from logs".to_string()),
-                        err,
-                    ))
-                }
-            };
-
-            if line.contains(&filter) {
-                show_message!(
-                    MessageType::Info,
-                    Message {
-                        action: "Log".to_string(),
-                        details: line.clone(),
-                    },
-                    true
-                );
+    let file = open_log_file(&log_file_path)?;
+    let mut reader = BufReader::new(file);
+
+    // Replay the existing files through the same filter so multi-line entries
+    // that straddle the point where following starts are handled consistently.
+    let mut filter = LogLineFilter::new(query);
+    let mut backlog = VecDeque::new();
+    for line in rotated_lines(rotated)? {
+        if filter.accepts(&line) {
+            backlog.push_back(line);
+            if tail.is_some_and(|n| backlog.len() > n) {
+                backlog.pop_front();
+            }
+        }
+    }
+    let mut buf = String::new();
+    loop {
+        buf.clear();
+        if reader.read_line(&mut buf).map_err(read_error)? == 0 {
+            break;
+        }
+        let line = buf.trim_end_matches(['\n', '\r']);
+        if filter.accepts(line) {
+            backlog.push_back(line.to_string());
+            if tail.is_some_and(|n| backlog.len() > n) {
+                backlog.pop_front();
+            }
+        }
+    }
+    for line in backlog {
+        print_log_line(&line);
+    }
+
+    let mut position = reader.stream_position().map_err(read_error)?;
+    let mut partial = String::new();
+    loop {
+        buf.clear();
+        let read = reader.read_line(&mut buf).map_err(read_error)?;
+        if read == 0 {
+            // The file was truncated or replaced; start again from the top.
+            let len = std::fs::metadata(&log_file_path)
+                .map(|m| m.len())
+                .unwrap_or(position);
+            if len < position {
+                reader = BufReader::new(open_log_file(&log_file_path)?);
+                position = 0;
+                partial.clear();
+                continue;
             }
+            sleep(FOLLOW_POLL_INTERVAL);
+            reader.seek(SeekFrom::Start(position)).map_err(read_error)?;
+            continue;
+        }
+        position += read as u64;
+
+        // Only act on complete lines; a write may still be in progress.
+        partial.push_str(&buf);
+        if !partial.ends_with('\n') {
+            continue;
+        }
+        let line = partial.trim_end_matches(['\n', '\r']).to_string();
+        partial.clear();
+        if filter.accepts(&line) {
+            print_log_line(&line);
         }
     }
+}
 
-    Ok(RoutineSuccess::success(Message::new(
-        "".to_string(),
-        "".to_string(),
-    )))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../../../tests/fixtures/moose-cli.log");
+
+    fn run(query: &LogQuery, tail: Option<usize>) -> Vec<String> {
+        filter_lines(FIXTURE.lines().map(String::from), query, tail)
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-01-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let header = parse_header(
+            "2025-01-15T10:00:00.123456Z  INFO moose_cli::cli::routines: Starting dev server",
+        )
+        .unwrap();
+        assert_eq!(header.level, LogLevel::Info);
+        assert_eq!(header.target, Some("moose_cli::cli::routines"));
+
+        let header = parse_header(
+            "2025-01-15T10:00:00Z ERROR ingest{resource_name=Foo}: moose_cli::ingest: boom",
+        )
+        .unwrap();
+        assert_eq!(header.level, LogLevel::Error);
+        assert_eq!(header.target, Some("moose_cli::ingest"));
+
+        assert!(parse_header("    at some continuation line").is_none());
+    }
+
+    #[test]
+    fn test_no_filters_returns_everything() {
+        assert_eq!(
+            run(&LogQuery::default(), None).len(),
+            FIXTURE.lines().count()
+        );
+    }
+
+    #[test]
+    fn test_level_filter() {
+        let query = LogQuery {
+            level: Some(LogLevel::Warn),
+            ..Default::default()
+        };
+        let lines = run(&query, None);
+        assert_eq!(lines.len(), 4);
+        assert!(lines
+            .iter()
+            .all(|l| l.contains(" WARN ") || l.contains("ERROR") || l.starts_with("  ")));
+    }
+
+    #[test]
+    fn test_level_filter_keeps_continuation_lines() {
+        let query = LogQuery {
+            level: Some(LogLevel::Error),
+            ..Default::default()
+        };
+        let lines = run(&query, None);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("  Code: 60"));
+    }
+
+    #[test]
+    fn test_substring_and_regex_filter() {
+        let query = LogQuery {
+            pattern: Some(LogPattern::parse("ClickHouse").unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(run(&query, None).len(), 2);
+
+        let query = LogQuery {
+            pattern: Some(LogPattern::parse("re:Code: \\d+").unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(run(&query, None).len(), 1);
+
+        assert!(LogPattern::parse("re:(").is_err());
+    }
+
+    #[test]
+    fn test_since_filter() {
+        let query = LogQuery {
+            since: Some(parse_since("1h", now()).unwrap()),
+            ..Default::default()
+        };
+        let lines = run(&query, None);
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("2025-01-15T11:00"));
+
+        let query = LogQuery {
+            since: Some(parse_since("2025-01-15T11:45:00Z", now()).unwrap()),
+            ..Default::default()
+        };
+        // The continuation line belongs to the 11:30 entry and is dropped with it
+        assert_eq!(run(&query, None).len(), 1);
+
+        assert!(parse_since("yesterday-ish", now()).is_err());
+    }
+
+    #[test]
+    fn test_source_filter() {
+        let query = LogQuery {
+            source: Some("olap::clickhouse".to_string()),
+            ..Default::default()
+        };
+        let lines = run(&query, None);
+        assert_eq!(lines.len(), 4);
+        assert!(lines
+            .iter()
+            .filter(|l| parse_header(l).is_some())
+            .all(|l| l.contains("moose_cli::infrastructure::olap::clickhouse")));
+    }
+
+    #[test]
+    fn test_combined_filters_and_tail() {
+        let query = LogQuery {
+            level: Some(LogLevel::Warn),
+            source: Some("clickhouse".to_string()),
+            since: Some(parse_since("3h", now()).unwrap()),
+            pattern: Some(LogPattern::parse("re:(?i)table").unwrap()),
+        };
+        let lines = run(&query, None);
+        assert_eq!(lines.len(), 2);
+
+        let lines = run(&query, Some(1));
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("Table events does not exist"));
+    }
+
+    #[test]
+    fn test_tail_without_filters() {
+        let lines = run(&LogQuery::default(), Some(2));
+        assert_eq!(
+            lines,
+            FIXTURE
+                .lines()
+                .skip(FIXTURE.lines().count() - 2)
+                .map(String::from)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_tail_records() {
+        let query = LogQuery {
+            level: Some(LogLevel::Warn),
+            ..Default::default()
+        };
+        let records = tail_records(FIXTURE.lines().map(String::from), &query, 2);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].level, LogLevel::Warn);
+        assert_eq!(
+            records[0].target.as_deref(),
+            Some("moose_cli::cli::watcher")
+        );
+        assert_eq!(records[0].message, "File change detected while processing");
+        assert_eq!(
+            records[1].message,
+            "Failed to insert into ClickHouse\n  Code: 60. DB::Exception: Table events does not exist"
+        );
+
+        // The pattern also matches continuation lines
+        let query = LogQuery {
+            pattern: Some(LogPattern::parse("Code: 60").unwrap()),
+            ..Default::default()
+        };
+        let records = tail_records(FIXTURE.lines().map(String::from), &query, 10);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].level, LogLevel::Error);
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let lines = [
+            r#"{"timestamp":"2025-01-15T10:00:00.123456Z","level":"INFO","target":"moose_cli::cli::routines","message":"Starting dev server"}"#,
+            r#"{"timestamp":"2025-01-15T11:30:00.000000Z","level":"ERROR","target":"moose_cli::infrastructure::olap::clickhouse","message":"Table \"events\" does not exist","table":"events"}"#,
+        ];
+        let json_lines = || lines.iter().map(|l| l.to_string());
+
+        let header = parse_header(lines[1]).unwrap();
+        assert_eq!(header.level, LogLevel::Error);
+        assert_eq!(
+            header.target,
+            Some("moose_cli::infrastructure::olap::clickhouse")
+        );
+
+        let query = LogQuery {
+            level: Some(LogLevel::Warn),
+            source: Some("olap::clickhouse".to_string()),
+            since: Some(parse_since("1h", now()).unwrap()),
+            pattern: None,
+        };
+        assert_eq!(filter_lines(json_lines(), &query, None), vec![lines[1]]);
+
+        let records = tail_records(json_lines(), &query, 10);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message, "Table \"events\" does not exist");
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!("ERROR".parse::<LogLevel>().unwrap(), LogLevel::Error);
+        assert_eq!("warning".parse::<LogLevel>().unwrap(), LogLevel::Warn);
+        assert!("loud".parse::<LogLevel>().is_err());
+    }
 }
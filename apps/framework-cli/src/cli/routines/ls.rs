@@ -4,46 +4,97 @@
 //! and streaming resources (topics) based on the project configuration.
 
 use super::{RoutineFailure, RoutineSuccess};
+use crate::cli::local_webserver::API_BUDGETS_PATH;
+use crate::framework::consumption::api_metrics::BudgetStatus;
 use crate::framework::core::infrastructure::api_endpoint::{APIType, ApiEndpoint};
 use crate::framework::core::infrastructure::function_process::FunctionProcess;
+use crate::framework::core::infrastructure::table::Table;
 use crate::framework::core::infrastructure::topic::Topic;
 use crate::framework::core::infrastructure::topic_sync_process::TopicToTableSyncProcess;
 use crate::framework::core::infrastructure::web_app::WebApp;
 use crate::framework::core::infrastructure_map::InfrastructureMap;
+use crate::framework::core::partial_infrastructure_map::LifeCycle;
 use crate::framework::scripts::Workflow;
+use crate::infrastructure::orchestration::schedules::next_runs;
 use crate::{
     cli::display::{show_table, Message},
     project::Project,
 };
+use chrono::{DateTime, SecondsFormat, Utc};
 use itertools::{Either, Itertools};
 use serde::Serialize;
-use serde_json::Error;
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// Version of the `moose ls --json` output shape.
+///
+/// Bump this whenever a field is renamed, removed or changes type so that
+/// tooling consuming the output can detect the change.
+pub const LS_JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub data_type: String,
+    pub required: bool,
+}
 
 #[derive(Debug, Serialize)]
 pub struct TableInfo {
     pub name: String,
-    pub schema_fields: Vec<String>,
+    pub database: String,
+    pub engine: String,
+    pub columns: Vec<ColumnInfo>,
+    pub order_by: String,
+    pub life_cycle: LifeCycle,
+}
+
+impl TableInfo {
+    fn from_table(table: Table, default_database: &str) -> Self {
+        Self {
+            name: table.name.clone(),
+            database: table
+                .database
+                .clone()
+                .unwrap_or_else(|| default_database.to_string()),
+            engine: table.engine.to_proto_string(),
+            columns: table
+                .columns
+                .iter()
+                .map(|col| ColumnInfo {
+                    name: col.name.clone(),
+                    data_type: col.data_type.to_string(),
+                    required: col.required,
+                })
+                .collect(),
+            order_by: table.order_by.to_string(),
+            life_cycle: table.life_cycle,
+        }
+    }
 }
 
 impl ResourceInfo for Vec<TableInfo> {
     fn show(&self) {
         show_table(
             "Tables".to_string(),
-            vec!["name".to_string(), "schema_fields".to_string()],
+            vec![
+                "name".to_string(),
+                "database".to_string(),
+                "schema_fields".to_string(),
+            ],
             self.iter()
-                .map(|t| vec![t.name.clone(), t.schema_fields.iter().join(", ")])
+                .map(|t| {
+                    vec![
+                        t.name.clone(),
+                        t.database.clone(),
+                        t.columns.iter().map(|col| &col.name).join(", "),
+                    ]
+                })
                 .collect(),
         )
     }
-    fn to_json_string(&self) -> Result<String, Error> {
-        serde_json::to_string_pretty(&self)
-    }
 }
 
-// Note: From trait removed because Table::id() now requires default_database parameter.
-// TableInfo is constructed directly where needed with the appropriate default_database.
-
 #[derive(Debug, Serialize)]
 pub struct StreamInfo {
     pub name: String,
@@ -88,9 +139,6 @@ impl ResourceInfo for Vec<StreamInfo> {
                 .collect(),
         )
     }
-    fn to_json_string(&self) -> Result<String, Error> {
-        serde_json::to_string_pretty(&self)
-    }
 }
 
 #[derive(Debug, Serialize)]
@@ -123,6 +171,7 @@ fn to_info(endpoint: &ApiEndpoint) -> Either<IngestionApiInfo, ConsumptionApiInf
                 Some(version) => format!("api/{}/{}", endpoint.name, version),
                 None => format!("api/{}", endpoint.name),
             },
+            latency_budget: None,
         }),
     }
 }
@@ -137,9 +186,6 @@ impl ResourceInfo for Vec<IngestionApiInfo> {
                 .collect(),
         )
     }
-    fn to_json_string(&self) -> Result<String, Error> {
-        serde_json::to_string_pretty(&self)
-    }
 }
 
 #[derive(Debug, Serialize)]
@@ -157,9 +203,6 @@ impl ResourceInfo for Vec<SqlResourceInfo> {
                 .collect(),
         )
     }
-    fn to_json_string(&self) -> Result<String, Error> {
-        serde_json::to_string_pretty(&self)
-    }
 }
 
 #[derive(Debug, Serialize)]
@@ -167,26 +210,81 @@ pub struct ConsumptionApiInfo {
     pub name: String,
     pub params: Vec<String>,
     pub path: String,
+    /// Where the API stands against its latency budget in the running dev server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_budget: Option<BudgetStatus>,
+}
+
+impl ConsumptionApiInfo {
+    fn latency_budget_summary(&self) -> String {
+        match &self.latency_budget {
+            Some(status) if status.over_budget => format!(
+                "p95 {}ms / {}ms OVER BUDGET",
+                status.p95_ms, status.latency_budget_ms
+            ),
+            Some(status) => format!("p95 {}ms / {}ms", status.p95_ms, status.latency_budget_ms),
+            None => String::new(),
+        }
+    }
 }
 
 impl ResourceInfo for Vec<ConsumptionApiInfo> {
     fn show(&self) {
         show_table(
             "Analytics APIs".to_string(),
-            vec!["name".to_string(), "params".to_string(), "path".to_string()],
+            vec![
+                "name".to_string(),
+                "params".to_string(),
+                "path".to_string(),
+                "latency budget".to_string(),
+            ],
             self.iter()
                 .map(|api| {
                     vec![
                         api.name.clone(),
                         api.params.iter().join(", "),
                         api.path.clone(),
+                        api.latency_budget_summary(),
                     ]
                 })
                 .collect(),
         )
     }
-    fn to_json_string(&self) -> Result<String, Error> {
-        serde_json::to_string_pretty(&self)
+}
+
+/// Latency budget status of the analytics APIs, from the dev server when it
+/// is running. Empty when it can't be reached.
+async fn fetch_latency_budgets(project: &Project) -> HashMap<String, BudgetStatus> {
+    let url = format!(
+        "http://{}:{}/{}",
+        project.http_server_config.host,
+        project.http_server_config.management_port,
+        API_BUDGETS_PATH
+    );
+    let Ok(client) = reqwest::Client::builder()
+        .timeout(Duration::from_millis(500))
+        .build()
+    else {
+        return HashMap::new();
+    };
+    match client.get(url).send().await {
+        Ok(response) if response.status().is_success() => response.json().await.unwrap_or_default(),
+        _ => HashMap::new(),
+    }
+}
+
+/// Attaches to each API its budget status, found by versioned path or name.
+fn attach_latency_budgets(
+    apis: &mut [ConsumptionApiInfo],
+    budgets: &HashMap<String, BudgetStatus>,
+) {
+    for api in apis {
+        api.latency_budget = api
+            .path
+            .strip_prefix("api/")
+            .and_then(|path| budgets.get(path))
+            .or_else(|| budgets.get(&api.name))
+            .cloned();
     }
 }
 
@@ -211,9 +309,6 @@ impl ResourceInfo for Vec<StreamTransformationInfo> {
                 .collect(),
         )
     }
-    fn to_json_string(&self) -> Result<String, Error> {
-        serde_json::to_string_pretty(&self)
-    }
 }
 
 impl From<FunctionProcess> for StreamTransformationInfo {
@@ -229,21 +324,37 @@ impl From<FunctionProcess> for StreamTransformationInfo {
 pub struct WorkflowInfo {
     pub name: String,
     pub schedule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Next fire of the schedule, from Temporal when it is running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run: Option<DateTime<Utc>>,
 }
 
 impl ResourceInfo for Vec<WorkflowInfo> {
     fn show(&self) {
         show_table(
             "Workflows".to_string(),
-            vec!["name".to_string(), "schedule".to_string()],
+            vec![
+                "name".to_string(),
+                "schedule".to_string(),
+                "next run".to_string(),
+            ],
             self.iter()
-                .map(|workflow| vec![workflow.name.clone(), workflow.schedule.clone()])
+                .map(|workflow| {
+                    let schedule = match &workflow.timezone {
+                        Some(timezone) => format!("{} ({timezone})", workflow.schedule),
+                        None => workflow.schedule.clone(),
+                    };
+                    let next_run = workflow
+                        .next_run
+                        .map(|next_run| next_run.to_rfc3339_opts(SecondsFormat::Secs, true))
+                        .unwrap_or_else(|| "-".to_string());
+                    vec![workflow.name.clone(), schedule, next_run]
+                })
                 .collect(),
         )
     }
-    fn to_json_string(&self) -> Result<String, Error> {
-        serde_json::to_string_pretty(&self)
-    }
 }
 
 impl From<Workflow> for WorkflowInfo {
@@ -257,10 +368,37 @@ impl From<Workflow> for WorkflowInfo {
         Self {
             name: value.name().to_string(),
             schedule,
+            timezone: value.config().timezone.clone(),
+            next_run: None,
         }
     }
 }
 
+/// Attaches to each scheduled workflow the next fire of its Temporal schedule.
+/// Left empty when Temporal can't be reached in time.
+async fn attach_next_runs(project: &Project, workflows: &mut [WorkflowInfo]) {
+    if !project.features.workflows {
+        return;
+    }
+    let next_runs =
+        match tokio::time::timeout(Duration::from_secs(2), next_runs(&project.temporal_config))
+            .await
+        {
+            Ok(Ok(next_runs)) => next_runs,
+            Ok(Err(e)) => {
+                tracing::debug!("Could not list workflow schedules: {:#}", e);
+                return;
+            }
+            Err(_) => {
+                tracing::debug!("Timed out listing workflow schedules");
+                return;
+            }
+        };
+    for workflow in workflows {
+        workflow.next_run = next_runs.get(&workflow.name).copied();
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct WebAppInfo {
     pub name: String,
@@ -286,13 +424,9 @@ impl ResourceInfo for Vec<WebAppInfo> {
                 .collect(),
         )
     }
-
-    fn to_json_string(&self) -> Result<String, Error> {
-        serde_json::to_string_pretty(&self)
-    }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct ResourceListing {
     pub tables: Vec<TableInfo>,
     pub streams: Vec<StreamInfo>,
@@ -304,6 +438,87 @@ pub struct ResourceListing {
     pub web_apps: Vec<WebAppInfo>,
 }
 
+impl ResourceListing {
+    /// Builds the listing from an infrastructure map, keeping only resources whose
+    /// name contains `name` when provided.
+    fn from_infra_map(infra_map: InfrastructureMap, name: Option<&str>) -> Self {
+        let default_database = infra_map.default_database.clone();
+
+        let (ingestion_apis, consumption_apis): (Vec<_>, Vec<_>) = infra_map
+            .api_endpoints
+            .values()
+            .filter(|api| name.is_none_or(|name| api.name.contains(name)))
+            .partition_map(to_info);
+        ResourceListing {
+            tables: infra_map
+                .tables
+                .into_values()
+                .filter(|api| name.is_none_or(|name| api.name.contains(name)))
+                .map(|t| TableInfo::from_table(t, &default_database))
+                .collect(),
+            streams: infra_map
+                .topics
+                .into_values()
+                .filter(|api| name.is_none_or(|name| api.name.contains(name)))
+                .map(|t| StreamInfo::from_topic(t, &infra_map.topic_to_table_sync_processes))
+                .collect(),
+            ingestion_apis,
+            sql_resources: infra_map
+                .sql_resources
+                .into_values()
+                .filter(|api| name.is_none_or(|name| api.name.contains(name)))
+                .map(|resource| SqlResourceInfo {
+                    name: resource.name,
+                })
+                .collect(),
+            consumption_apis,
+            stream_transformations: infra_map
+                .function_processes
+                .into_values()
+                .filter(|api| name.is_none_or(|name| api.name.contains(name)))
+                .map(|p| p.into())
+                .collect(),
+            workflows: infra_map
+                .workflows
+                .into_values()
+                .filter(|api| name.is_none_or(|name| api.name().contains(name)))
+                .map(|w| w.into())
+                .collect(),
+            web_apps: infra_map
+                .web_apps
+                .into_values()
+                .filter(|app| name.is_none_or(|n| app.name.contains(n)))
+                .map(Into::into)
+                .collect(),
+        }
+    }
+
+    /// Keeps only the section selected by `--type`, emptying all others so the
+    /// JSON output keeps the same keys regardless of the filter.
+    fn retain_type(self, resource_type: &str) -> Option<Self> {
+        let mut filtered = ResourceListing::default();
+        match resource_type {
+            "tables" => filtered.tables = self.tables,
+            "streams" => filtered.streams = self.streams,
+            "ingestion" => filtered.ingestion_apis = self.ingestion_apis,
+            "sql_resource" => filtered.sql_resources = self.sql_resources,
+            "consumption" => filtered.consumption_apis = self.consumption_apis,
+            "workflows" => filtered.workflows = self.workflows,
+            "web_apps" => filtered.web_apps = self.web_apps,
+            _ => return None,
+        }
+        Some(filtered)
+    }
+}
+
+/// The `moose ls --json` document: the listing plus a schema version.
+#[derive(Debug, Serialize)]
+struct VersionedListing<'a> {
+    version: u32,
+    #[serde(flatten)]
+    listing: &'a ResourceListing,
+}
+
 impl ResourceInfo for ResourceListing {
     fn show(&self) {
         self.tables.show();
@@ -315,10 +530,6 @@ impl ResourceInfo for ResourceListing {
         self.workflows.show();
         self.web_apps.show();
     }
-
-    fn to_json_string(&self) -> Result<String, Error> {
-        serde_json::to_string_pretty(&self)
-    }
 }
 
 pub async fn ls(
@@ -340,77 +551,45 @@ pub async fn ls(
             )
         })?;
 
-    let default_database = infra_map.default_database.clone();
-
-    let (ingestion_apis, consumption_apis): (Vec<_>, Vec<_>) = infra_map
-        .api_endpoints
-        .values()
-        .filter(|api| name.is_none_or(|name| api.name.contains(name)))
-        .partition_map(to_info);
-    let resources = ResourceListing {
-        tables: infra_map
-            .tables
-            .into_values()
-            .filter(|api| name.is_none_or(|name| api.name.contains(name)))
-            .map(|t| TableInfo {
-                name: t.id(&default_database),
-                schema_fields: t.columns.iter().map(|col| col.name.clone()).collect(),
-            })
-            .collect(),
-        streams: infra_map
-            .topics
-            .into_values()
-            .filter(|api| name.is_none_or(|name| api.name.contains(name)))
-            .map(|t| StreamInfo::from_topic(t, &infra_map.topic_to_table_sync_processes))
-            .collect(),
-        ingestion_apis,
-        sql_resources: infra_map
-            .sql_resources
-            .into_values()
-            .filter(|api| name.is_none_or(|name| api.name.contains(name)))
-            .map(|resource| SqlResourceInfo {
-                name: resource.name,
-            })
-            .collect(),
-        consumption_apis,
-        stream_transformations: infra_map
-            .function_processes
-            .into_values()
-            .filter(|api| name.is_none_or(|name| api.name.contains(name)))
-            .map(|p| p.into())
-            .collect(),
-        workflows: infra_map
-            .workflows
-            .into_values()
-            .filter(|api| name.is_none_or(|name| api.name().contains(name)))
-            .map(|w| w.into())
-            .collect(),
-        web_apps: infra_map
-            .web_apps
-            .into_values()
-            .filter(|app| name.is_none_or(|n| app.name.contains(n)))
-            .map(Into::into)
-            .collect(),
-    };
-    let listing: &dyn ResourceInfo = match _type {
-        None => &resources,
-        Some("tables") => &resources.tables,
-        Some("streams") => &resources.streams,
-        Some("ingestion") => &resources.ingestion_apis,
-        Some("sql_resource") => &resources.sql_resources,
-        Some("consumption") => &resources.consumption_apis,
-        Some("workflows") => &resources.workflows,
-        Some("web_apps") => &resources.web_apps,
-        _ => {
-            return Err(RoutineFailure::error(Message::new(
+    let mut resources = ResourceListing::from_infra_map(infra_map, name);
+    if !resources.consumption_apis.is_empty() {
+        let budgets = fetch_latency_budgets(project).await;
+        attach_latency_budgets(&mut resources.consumption_apis, &budgets);
+    }
+    if resources
+        .workflows
+        .iter()
+        .any(|workflow| workflow.schedule != "None")
+    {
+        attach_next_runs(project, &mut resources.workflows).await;
+    }
+    let resources = match _type {
+        None => resources,
+        Some(resource_type) => resources.retain_type(resource_type).ok_or_else(|| {
+            RoutineFailure::error(Message::new(
                 "Unknown".to_string(),
-                "type".to_string(),
-            )))
-        }
+                format!("type {resource_type}"),
+            ))
+        })?,
     };
+
     if json {
-        println!("{}", listing.to_json_string().unwrap());
+        let output = VersionedListing {
+            version: LS_JSON_SCHEMA_VERSION,
+            listing: &resources,
+        };
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
     } else {
+        let listing: &dyn ResourceInfo = match _type {
+            None => &resources,
+            Some("tables") => &resources.tables,
+            Some("streams") => &resources.streams,
+            Some("ingestion") => &resources.ingestion_apis,
+            Some("sql_resource") => &resources.sql_resources,
+            Some("consumption") => &resources.consumption_apis,
+            Some("workflows") => &resources.workflows,
+            _ => &resources.web_apps,
+        };
         listing.show();
     }
 
@@ -422,5 +601,154 @@ pub async fn ls(
 
 trait ResourceInfo {
     fn show(&self);
-    fn to_json_string(&self) -> Result<String, serde_json::error::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::table::{Column, ColumnType, OrderBy};
+    use crate::framework::core::infrastructure_map::{PrimitiveSignature, PrimitiveTypes};
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+
+    fn test_table(name: &str) -> Table {
+        Table {
+            name: name.to_string(),
+            columns: vec![Column {
+                name: "id".to_string(),
+                data_type: ColumnType::String,
+                required: true,
+                unique: false,
+                primary_key: true,
+                default: None,
+                annotations: vec![],
+                comment: None,
+                ttl: None,
+                codec: None,
+                materialized: None,
+                alias: None,
+            }],
+            order_by: OrderBy::Fields(vec!["id".to_string()]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            indexes: vec![],
+            projections: vec![],
+            database: None,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    /// Snapshot of the `moose ls --json` shape. If this fails, the output
+    /// consumed by external tooling changed: update the snapshot and bump
+    /// `LS_JSON_SCHEMA_VERSION` if a field was renamed, removed or retyped.
+    #[test]
+    fn test_ls_json_schema_snapshot() {
+        let mut infra_map = InfrastructureMap::default();
+        infra_map.default_database = "local".to_string();
+        infra_map
+            .tables
+            .insert("events".to_string(), test_table("events"));
+
+        let listing = ResourceListing::from_infra_map(infra_map, None);
+        let output = serde_json::to_value(VersionedListing {
+            version: LS_JSON_SCHEMA_VERSION,
+            listing: &listing,
+        })
+        .unwrap();
+
+        assert_eq!(
+            output,
+            serde_json::json!({
+                "version": 1,
+                "tables": [{
+                    "name": "events",
+                    "database": "local",
+                    "engine": "MergeTree",
+                    "columns": [{"name": "id", "data_type": "String", "required": true}],
+                    "order_by": "id",
+                    "life_cycle": "FULLY_MANAGED",
+                }],
+                "streams": [],
+                "ingestion_apis": [],
+                "sql_resources": [],
+                "consumption_apis": [],
+                "stream_transformations": [],
+                "workflows": [],
+                "web_apps": [],
+            })
+        );
+    }
+
+    #[test]
+    fn test_ls_filters_by_name_and_type() {
+        let mut infra_map = InfrastructureMap::default();
+        infra_map
+            .tables
+            .insert("events".to_string(), test_table("events"));
+        infra_map
+            .tables
+            .insert("users".to_string(), test_table("users"));
+
+        let listing = ResourceListing::from_infra_map(infra_map, Some("even"));
+        assert_eq!(listing.tables.len(), 1);
+        assert_eq!(listing.tables[0].columns[0].name, "id");
+
+        let tables_only = listing.retain_type("tables").unwrap();
+        assert_eq!(tables_only.tables.len(), 1);
+        assert!(tables_only.streams.is_empty());
+
+        assert!(ResourceListing::default().retain_type("unknown").is_none());
+    }
+
+    #[test]
+    fn test_attach_latency_budgets() {
+        let api = |name: &str, path: &str| ConsumptionApiInfo {
+            name: name.to_string(),
+            params: vec![],
+            path: path.to_string(),
+            latency_budget: None,
+        };
+        let status = |p95_ms| BudgetStatus {
+            latency_budget_ms: 250,
+            p95_ms,
+            samples: 40,
+            over_budget: p95_ms > 250,
+        };
+        let mut apis = vec![
+            api("daily", "api/daily"),
+            api("weekly", "api/weekly/2"),
+            api("monthly", "api/monthly"),
+        ];
+        let budgets = HashMap::from([
+            ("daily".to_string(), status(400)),
+            ("weekly/2".to_string(), status(100)),
+        ]);
+
+        attach_latency_budgets(&mut apis, &budgets);
+
+        assert_eq!(
+            apis[0].latency_budget_summary(),
+            "p95 400ms / 250ms OVER BUDGET"
+        );
+        assert_eq!(apis[1].latency_budget_summary(), "p95 100ms / 250ms");
+        assert_eq!(apis[2].latency_budget, None);
+        assert!(serde_json::to_value(&apis[2])
+            .unwrap()
+            .get("latency_budget")
+            .is_none());
+    }
 }
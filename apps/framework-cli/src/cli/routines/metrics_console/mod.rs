@@ -2,10 +2,15 @@ use crate::cli::{display::Message, routines::RoutineSuccess};
 
 mod run_console;
 
+pub use run_console::AdminClient;
+
 use super::RoutineFailure;
 
-pub async fn run_console() -> Result<RoutineSuccess, RoutineFailure> {
-    let result = run_console::run_console().await;
+pub async fn run_console(
+    management_port: u16,
+    admin: AdminClient,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let result = run_console::run_console(management_port, admin).await;
 
     match result {
         Ok(_) => Ok(RoutineSuccess::success(Message::new(
@@ -1,7 +1,10 @@
 use prometheus_parse::HistogramCount;
 use ratatui::layout::Rect;
 
-use super::client::{parsing_histogram_data, ParsedMetricsData, PathMetricsData};
+use super::client::{
+    parsing_histogram_data, MigrationMetricsData, ParsedMetricsData, PathMetricsData,
+    SyncLatencyData,
+};
 use std::{collections::HashMap, error};
 
 pub type AppResult<T> = std::result::Result<T, Box<dyn error::Error>>;
@@ -44,6 +47,7 @@ pub struct AppKafkaClickHouseSyncMetrics {
     pub kafka_messages_out_per_sec: HashMap<String, (String, f64)>,
     pub kafka_bytes_out_total: HashMap<String, (String, u64)>,
     pub kafka_bytes_out_per_sec: HashMap<String, u64>,
+    pub sync_latency: HashMap<String, SyncLatencyData>,
 }
 
 pub struct AppStreamingFunctionsMetrics {
@@ -86,6 +90,9 @@ pub struct App {
     pub kafka_clikhouse_sync_metrics: AppKafkaClickHouseSyncMetrics,
     pub streaming_functions_metrics: AppStreamingFunctionsMetrics,
     pub overview_data: AppOverviewMetrics,
+    pub migration_metrics: MigrationMetricsData,
+    /// Log level of the dev server, or why it couldn't be read or changed
+    pub log_level: Result<String, String>,
 }
 
 impl Default for App {
@@ -116,6 +123,7 @@ impl Default for App {
                 kafka_messages_out_per_sec: HashMap::new(),
                 kafka_bytes_out_total: HashMap::new(),
                 kafka_bytes_out_per_sec: HashMap::new(),
+                sync_latency: HashMap::new(),
             },
             streaming_functions_metrics: AppStreamingFunctionsMetrics {
                 streaming_functions_in: HashMap::new(),
@@ -137,6 +145,8 @@ impl Default for App {
                     bytes_out_per_sec: 0,
                 },
             },
+            migration_metrics: MigrationMetricsData::default(),
+            log_level: Err("unknown".to_string()),
         }
     }
 }
@@ -155,6 +165,7 @@ impl App {
     pub fn set_metrics(&mut self, parsed_data: ParsedMetricsData) {
         self.overview_data.average = parsed_data.average_latency;
         self.overview_data.total_requests = parsed_data.total_requests;
+        self.migration_metrics = parsed_data.migrations;
         self.overview_data.summary = parsed_data.paths_data_vec;
         if !self.overview_data.summary.is_empty() {
             self.paths_data.path_detailed_data = parsing_histogram_data(
@@ -174,6 +185,7 @@ impl App {
         self.kafka_clikhouse_sync_metrics.kafka_messages_out_total =
             parsed_data.kafka_messages_out_total;
         self.kafka_clikhouse_sync_metrics.kafka_bytes_out_total = parsed_data.kafka_bytes_out_total;
+        self.kafka_clikhouse_sync_metrics.sync_latency = parsed_data.sync_latency;
         self.streaming_functions_metrics.streaming_functions_in =
             parsed_data.streaming_functions_in;
         self.streaming_functions_metrics.streaming_functions_out =
@@ -4,10 +4,11 @@ use prometheus_parse::{self, HistogramCount, Sample};
 use reqwest;
 
 use crate::metrics::{
-    CONSUMED_BYTES, HTTP_TO_TOPIC_EVENT_COUNT, INGESTED_BYTES, LATENCY,
+    CONSUMED_BYTES, HTTP_TO_TOPIC_EVENT_COUNT, INGESTED_BYTES, LATENCY, MIGRATION_OPERATIONS,
+    MIGRATION_OPERATION_DURATION, MIGRATION_PLAN_DURATION, MIGRATION_PLAN_OPERATIONS,
     STREAMING_FUNCTION_EVENT_INPUT_COUNT, STREAMING_FUNCTION_EVENT_OUPUT_COUNT,
-    STREAMING_FUNCTION_PROCESSED_BYTE_COUNT, TOPIC_TO_OLAP_BYTE_COUNT, TOPIC_TO_OLAP_EVENT_COUNT,
-    TOTAL_LATENCY,
+    STREAMING_FUNCTION_PROCESSED_BYTE_COUNT, SYNC_LATENCY, TOPIC_TO_OLAP_BYTE_COUNT,
+    TOPIC_TO_OLAP_EVENT_COUNT, TOTAL_LATENCY,
 };
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
@@ -18,6 +19,24 @@ pub struct PathMetricsData {
     pub path: String,
 }
 
+/// Totals of the OLAP migration operations run by the dev server.
+#[derive(Default)]
+pub struct MigrationMetricsData {
+    pub operations: f64,
+    pub failed_operations: f64,
+    pub operations_seconds: f64,
+    pub last_plan_seconds: f64,
+    pub last_plan_operations: f64,
+}
+
+/// Percentiles, in seconds, of the time records of a topic take to reach
+/// their table.
+pub struct SyncLatencyData {
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
 pub struct ParsedMetricsData {
     pub average_latency: f64,
     pub total_requests: f64,
@@ -32,10 +51,12 @@ pub struct ParsedMetricsData {
     pub streaming_functions_in: HashMap<String, f64>,
     pub streaming_functions_out: HashMap<String, f64>,
     pub streaming_functions_bytes: HashMap<String, u64>,
+    pub migrations: MigrationMetricsData,
+    pub sync_latency: HashMap<String, SyncLatencyData>,
 }
 
-pub async fn getting_metrics_data() -> Result<ParsedMetricsData> {
-    let body = reqwest::get("http://localhost:5001/metrics")
+pub async fn getting_metrics_data(management_port: u16) -> Result<ParsedMetricsData> {
+    let body = reqwest::get(format!("http://localhost:{management_port}/metrics"))
         .await
         .unwrap()
         .text();
@@ -62,6 +83,8 @@ pub async fn getting_metrics_data() -> Result<ParsedMetricsData> {
     let mut streaming_functions_in: HashMap<String, f64> = HashMap::new();
     let mut streaming_functions_out: HashMap<String, f64> = HashMap::new();
     let mut streaming_functions_bytes: HashMap<String, u64> = HashMap::new();
+    let mut migrations = MigrationMetricsData::default();
+    let mut sync_latency_buckets: HashMap<String, Vec<HistogramCount>> = HashMap::new();
 
     let mut i = 0;
     while i < metrics_vec.len() {
@@ -183,11 +206,68 @@ pub async fn getting_metrics_data() -> Result<ParsedMetricsData> {
                 metrics_vec[j].labels["function_name"].to_string(),
                 *value as u64,
             );
+        } else if metrics_vec[j].metric == format!("{MIGRATION_OPERATIONS}_total") {
+            let value = match &metrics_vec[j].value {
+                prometheus_parse::Value::Counter(v) => *v,
+                prometheus_parse::Value::Untyped(v) => *v,
+                _ => 0.0,
+            };
+            migrations.operations += value;
+            if metrics_vec[j].labels.get("outcome") == Some("failure") {
+                migrations.failed_operations += value;
+            }
+        } else if metrics_vec[j].metric == format!("{MIGRATION_OPERATION_DURATION}_sum") {
+            migrations.operations_seconds += match &metrics_vec[j].value {
+                prometheus_parse::Value::Histogram(v) => v[0].count,
+                prometheus_parse::Value::Untyped(v) => *v,
+                _ => 0.0,
+            };
+        } else if metrics_vec[j].metric == MIGRATION_PLAN_DURATION {
+            if let prometheus_parse::Value::Gauge(v) | prometheus_parse::Value::Untyped(v) =
+                &metrics_vec[j].value
+            {
+                migrations.last_plan_seconds = *v;
+            }
+        } else if metrics_vec[j].metric == MIGRATION_PLAN_OPERATIONS {
+            if let prometheus_parse::Value::Gauge(v) | prometheus_parse::Value::Untyped(v) =
+                &metrics_vec[j].value
+            {
+                migrations.last_plan_operations = *v;
+            }
+        } else if metrics_vec[j].metric == SYNC_LATENCY {
+            if let prometheus_parse::Value::Histogram(buckets) = &metrics_vec[j].value {
+                // A topic synced to several tables shows the latency of all of them
+                let topic = metrics_vec[j].labels["topic"].to_string();
+                match sync_latency_buckets.get_mut(&topic) {
+                    Some(merged) => {
+                        for (total, bucket) in merged.iter_mut().zip(buckets) {
+                            total.count += bucket.count;
+                        }
+                    }
+                    None => {
+                        sync_latency_buckets.insert(topic, buckets.clone());
+                    }
+                }
+            }
         }
 
         j += 1;
     }
 
+    let sync_latency = sync_latency_buckets
+        .into_iter()
+        .filter_map(|(topic, buckets)| {
+            Some((
+                topic,
+                SyncLatencyData {
+                    p50: histogram_quantile(0.5, &buckets)?,
+                    p95: histogram_quantile(0.95, &buckets)?,
+                    p99: histogram_quantile(0.99, &buckets)?,
+                },
+            ))
+        })
+        .collect();
+
     let parsed_data = ParsedMetricsData {
         average_latency,
         total_requests,
@@ -202,11 +282,87 @@ pub async fn getting_metrics_data() -> Result<ParsedMetricsData> {
         streaming_functions_in,
         streaming_functions_out,
         streaming_functions_bytes,
+        migrations,
+        sync_latency,
     };
 
     Ok(parsed_data)
 }
 
+/// Estimates the `q` quantile of cumulative histogram buckets, interpolating
+/// linearly within the bucket it falls in like Prometheus'
+/// `histogram_quantile`. `None` when nothing was observed.
+fn histogram_quantile(q: f64, buckets: &[HistogramCount]) -> Option<f64> {
+    let total = buckets.last()?.count;
+    if total <= 0.0 {
+        return None;
+    }
+    let rank = q * total;
+
+    let mut lower_bound = 0.0;
+    let mut lower_count = 0.0;
+    for bucket in buckets {
+        if bucket.count >= rank {
+            if bucket.less_than.is_infinite() {
+                // Past the last finite bucket, its bound is the best estimate
+                return Some(lower_bound);
+            }
+            let in_bucket = bucket.count - lower_count;
+            if in_bucket <= 0.0 {
+                return Some(bucket.less_than);
+            }
+            return Some(
+                lower_bound + (bucket.less_than - lower_bound) * (rank - lower_count) / in_bucket,
+            );
+        }
+        lower_bound = bucket.less_than;
+        lower_count = bucket.count;
+    }
+    Some(lower_bound)
+}
+
+/// The admin endpoints of the dev server, for changing its log level.
+pub struct AdminClient {
+    pub http_port: u16,
+    pub token: Option<String>,
+}
+
+impl AdminClient {
+    fn request(&self, method: reqwest::Method) -> reqwest::RequestBuilder {
+        let request = reqwest::Client::new().request(
+            method,
+            format!("http://localhost:{}/admin/log-level", self.http_port),
+        );
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn level_of(response: reqwest::Response) -> Result<String> {
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(format!("{status}: {body}").into());
+        }
+        let json: serde_json::Value = serde_json::from_str(&body)?;
+        Ok(json["level"].as_str().unwrap_or_default().to_string())
+    }
+
+    pub async fn log_level(&self) -> Result<String> {
+        Self::level_of(self.request(reqwest::Method::GET).send().await?).await
+    }
+
+    pub async fn set_log_level(&self, level: &str) -> Result<String> {
+        let response = self
+            .request(reqwest::Method::PUT)
+            .body(level.to_string())
+            .send()
+            .await?;
+        Self::level_of(response).await
+    }
+}
+
 pub fn parsing_histogram_data(
     path: String,
     metrics_vec: Vec<prometheus_parse::Sample>,
@@ -228,3 +384,37 @@ pub fn parsing_histogram_data(
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buckets(counts: &[(f64, f64)]) -> Vec<HistogramCount> {
+        counts
+            .iter()
+            .map(|(less_than, count)| HistogramCount {
+                less_than: *less_than,
+                count: *count,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_histogram_quantile() {
+        // 4 under 1s, 4 between 1s and 2s
+        let latency = buckets(&[(1.0, 4.0), (2.0, 8.0), (f64::INFINITY, 8.0)]);
+        assert_eq!(histogram_quantile(0.25, &latency), Some(0.5));
+        assert_eq!(histogram_quantile(0.75, &latency), Some(1.5));
+        assert_eq!(histogram_quantile(1.0, &latency), Some(2.0));
+    }
+
+    #[test]
+    fn test_histogram_quantile_edges() {
+        assert_eq!(histogram_quantile(0.5, &[]), None);
+        let empty = buckets(&[(1.0, 0.0), (f64::INFINITY, 0.0)]);
+        assert_eq!(histogram_quantile(0.5, &empty), None);
+        // Everything above the last finite bucket
+        let slow = buckets(&[(1.0, 0.0), (2.0, 0.0), (f64::INFINITY, 4.0)]);
+        assert_eq!(histogram_quantile(0.99, &slow), Some(2.0));
+    }
+}
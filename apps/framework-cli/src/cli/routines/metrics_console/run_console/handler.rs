@@ -1,10 +1,17 @@
+use crate::cli::logger::{next_log_level, parse_log_level};
 use crate::cli::routines::metrics_console::run_console::app::State;
 use crate::cli::routines::metrics_console::run_console::app::{App, AppResult};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tracing_subscriber::filter::LevelFilter;
 
 use super::app::TableState;
+use super::client::AdminClient;
 
-pub async fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+pub async fn handle_key_events(
+    key_event: KeyEvent,
+    app: &mut App,
+    admin: &AdminClient,
+) -> AppResult<()> {
     match key_event.code {
         KeyCode::Char('q') | KeyCode::Char('Q') => {
             app.quit();
@@ -15,6 +22,15 @@ pub async fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<
             }
         }
 
+        KeyCode::Char('l') | KeyCode::Char('L') => {
+            let current = match &app.log_level {
+                Ok(level) => parse_log_level(level).unwrap_or(LevelFilter::INFO),
+                Err(_) => LevelFilter::INFO,
+            };
+            let next = next_log_level(current).to_string();
+            app.log_level = admin.set_log_level(&next).await.map_err(|e| e.to_string());
+        }
+
         KeyCode::Down => match app.table_state {
             TableState::Endpoint => {
                 app.endpoint_down();
@@ -12,13 +12,15 @@ mod tui;
 mod ui;
 
 use app::App;
+pub use client::AdminClient;
 use client::ParsedMetricsData;
 use event::Event;
 use handler::handle_key_events;
 
-pub async fn run_console() -> app::AppResult<()> {
+pub async fn run_console(management_port: u16, admin: AdminClient) -> app::AppResult<()> {
     // Create an application.
     let mut app = App::new();
+    app.log_level = admin.log_level().await.map_err(|e| e.to_string());
 
     // Initialize the terminal user interface.
     let backend = CrosstermBackend::new(io::stderr());
@@ -31,7 +33,7 @@ pub async fn run_console() -> app::AppResult<()> {
 
     tokio::spawn(async move {
         loop {
-            let parsed_data = client::getting_metrics_data().await.unwrap();
+            let parsed_data = client::getting_metrics_data(management_port).await.unwrap();
             let _ = tx.send(parsed_data).await;
             tokio::time::sleep(time::Duration::from_millis(1000)).await;
         }
@@ -49,7 +51,7 @@ pub async fn run_console() -> app::AppResult<()> {
             // Handle events.
             event = tui.events.next() => { match event?{
                     Event::Tick => app.tick(),
-                    Event::Key(key_event) => handle_key_events(key_event, &mut app).await?,
+                    Event::Key(key_event) => handle_key_events(key_event, &mut app, &admin).await?,
                 }
             }
         }
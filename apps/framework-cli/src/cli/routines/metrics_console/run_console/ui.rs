@@ -8,9 +8,10 @@ use ratatui::{
 use ratatui::{prelude::*, widgets::*};
 
 use crate::cli::routines::metrics_console::run_console::app::{App, State, TableState};
+use crate::cli::routines::metrics_console::run_console::client::SyncLatencyData;
 
 const INFO_TEXT: &str =
-    "(Q) QUIT | (TAB) SWITCH TABLE | (↑) ROW UP | (↓) ROW DOWN | (ENTER) VIEW ENDPOINT DETAILS";
+    "(Q) QUIT | (TAB) SWITCH TABLE | (↑) ROW UP | (↓) ROW DOWN | (ENTER) VIEW ENDPOINT DETAILS | (L) LOG LEVEL";
 
 const ENDPOINT_TABLE_COLUMNS: [&str; 4] = [
     "PATH",
@@ -19,8 +20,14 @@ const ENDPOINT_TABLE_COLUMNS: [&str; 4] = [
     "# OF MESSAGES SENT TO KAFKA",
 ];
 
-const KAFKA_CLICKHOUSE_SYNC_TABLE_COLUMNS: [&str; 5] =
-    ["DATA MODEL", "MSG READ", "LAG", "MSG/SEC", "BYTES/SEC"];
+const KAFKA_CLICKHOUSE_SYNC_TABLE_COLUMNS: [&str; 6] = [
+    "DATA MODEL",
+    "MSG READ",
+    "LAG",
+    "MSG/SEC",
+    "BYTES/SEC",
+    "LATENCY P50/P95/P99 (ms)",
+];
 const STREAMING_FUNCTIONS_KAFKA_TABLE_COLUMNS: [&str; 6] = [
     "STREAMING PATH",
     "MSG IN",
@@ -70,7 +77,7 @@ pub fn render(app: &mut App, frame: &mut Frame) {
                 .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
                 .split(outer_layout[2]);
 
-            render_main_page_details(frame, &outer_layout);
+            render_main_page_details(app, frame, &outer_layout);
             render_overview_metrics(app, frame, &inner_layout);
             render_overview_bytes_data(app, frame, &bytes_overview_layout);
             render_endpoint_table(app, frame, outer_layout[3]);
@@ -375,6 +382,7 @@ fn render_passive_clickhouse_sync_table(app: &mut App, frame: &mut Frame, layout
                             .unwrap_or(&0) as f64
                     )
                 ),
+                format_sync_latency(app.kafka_clikhouse_sync_metrics.sync_latency.get(&item.0)),
             ])
             .not_bold()
             .white(),
@@ -382,11 +390,12 @@ fn render_passive_clickhouse_sync_table(app: &mut App, frame: &mut Frame, layout
     }
 
     let widths = [
-        Constraint::Percentage(25),
-        Constraint::Percentage(18),
-        Constraint::Percentage(18),
-        Constraint::Percentage(18),
-        Constraint::Percentage(18),
+        Constraint::Percentage(20),
+        Constraint::Percentage(12),
+        Constraint::Percentage(12),
+        Constraint::Percentage(12),
+        Constraint::Percentage(12),
+        Constraint::Percentage(28),
     ];
 
     let table = Table::new(rows, widths)
@@ -456,6 +465,7 @@ fn render_active_clickhouse_sync_table(app: &mut App, frame: &mut Frame, layout:
                             .unwrap_or(&0) as f64
                     )
                 ),
+                format_sync_latency(app.kafka_clikhouse_sync_metrics.sync_latency.get(&item.0)),
             ])
             .bold()
             .white(),
@@ -463,11 +473,12 @@ fn render_active_clickhouse_sync_table(app: &mut App, frame: &mut Frame, layout:
     }
 
     let widths = [
-        Constraint::Percentage(25),
-        Constraint::Percentage(18),
-        Constraint::Percentage(18),
-        Constraint::Percentage(18),
-        Constraint::Percentage(18),
+        Constraint::Percentage(20),
+        Constraint::Percentage(12),
+        Constraint::Percentage(12),
+        Constraint::Percentage(12),
+        Constraint::Percentage(12),
+        Constraint::Percentage(28),
     ];
     let mut table_state = ratatui::widgets::TableState::default();
     table_state.select(Some(app.table_scroll_data.kafka_starting_row));
@@ -687,6 +698,18 @@ fn render_passive_streaming_functions_messages_table(
     frame.render_widget(table, layout)
 }
 
+fn format_sync_latency(latency: Option<&SyncLatencyData>) -> String {
+    match latency {
+        Some(latency) => format!(
+            "{:.0} / {:.0} / {:.0}",
+            latency.p50 * 1000.0,
+            latency.p95 * 1000.0,
+            latency.p99 * 1000.0
+        ),
+        None => "NA".to_string(),
+    }
+}
+
 fn table_equals_path(path: String, table: String) -> bool {
     let mut path_vec: Vec<&str> = path.split(['/', '.']).collect();
     path_vec.remove(0);
@@ -781,14 +804,43 @@ fn render_overview_bytes_data(app: &mut App, frame: &mut Frame, layout: &Rc<[Rec
     frame.render_widget(bytes_out_per_sec_paragraph, layout[1]);
 }
 
-fn render_main_page_details(frame: &mut Frame, layout: &Rc<[Rect]>) {
-    let info_footer = Paragraph::new(Line::from(INFO_TEXT).white())
-        .centered()
-        .block(
-            Block::bordered()
-                .border_type(BorderType::Plain)
-                .border_style(Style::new().fg(Color::DarkGray)),
-        );
+fn render_main_page_details(app: &App, frame: &mut Frame, layout: &Rc<[Rect]>) {
+    let log_level = match &app.log_level {
+        Ok(level) => format!("LOG LEVEL: {}", level.to_uppercase()).white(),
+        Err(e) => format!("LOG LEVEL: {e}").red(),
+    };
+    let migrations = &app.migration_metrics;
+    let average_ms = if migrations.operations > 0.0 {
+        migrations.operations_seconds / migrations.operations * 1000.0
+    } else {
+        0.0
+    };
+    let migration_summary = format!(
+        "MIGRATIONS: {} ops, {} failed, avg {:.0}ms, last plan {} ops in {:.1}s",
+        migrations.operations,
+        migrations.failed_operations,
+        average_ms,
+        migrations.last_plan_operations,
+        migrations.last_plan_seconds,
+    );
+    let migration_summary = if migrations.failed_operations > 0.0 {
+        migration_summary.red()
+    } else {
+        migration_summary.white()
+    };
+    let info_footer = Paragraph::new(Line::from(vec![
+        Span::from(INFO_TEXT).white(),
+        Span::from(" | ").white(),
+        log_level,
+        Span::from(" | ").white(),
+        migration_summary,
+    ]))
+    .centered()
+    .block(
+        Block::bordered()
+            .border_type(BorderType::Plain)
+            .border_style(Style::new().fg(Color::DarkGray)),
+    );
 
     let endpoint_block = Block::new()
         .title("ENDPOINT METRICS")
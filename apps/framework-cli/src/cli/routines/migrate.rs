@@ -4,26 +4,68 @@ use crate::cli::display::Message;
 use crate::cli::routines::RoutineFailure;
 use crate::framework::core::infrastructure::table::Table;
 use crate::framework::core::infrastructure_map::InfrastructureMap;
-use crate::framework::core::migration_plan::MigrationPlan;
+use crate::framework::core::migration_plan::{
+    state_hash, strip_metadata_and_ignored_fields, MigrationPlan, MigrationSummary,
+};
 use crate::framework::core::plan::{reconcile_with_reality, ReconciliationFilter};
 use crate::framework::core::state_storage::{StateStorage, StateStorageBuilder};
 use crate::infrastructure::olap::clickhouse::config::{ClickHouseConfig, ClusterConfig};
+use crate::infrastructure::olap::clickhouse::writability;
 use crate::infrastructure::olap::clickhouse::IgnorableOperation;
 use crate::infrastructure::olap::clickhouse::{
-    check_ready, create_client, ConfiguredDBClient, SerializableOlapOperation,
+    check_ready, coalesce_column_operations, create_client, execute_column_batch,
+    statements_to_sql_script, ConfiguredDBClient, OperationStatements, SerializableOlapOperation,
+    StatementRecorder,
 };
 use crate::project::Project;
 use crate::utilities::constants::{
-    MIGRATION_AFTER_STATE_FILE, MIGRATION_BEFORE_STATE_FILE, MIGRATION_FILE,
+    MIGRATION_AFTER_STATE_FILE, MIGRATION_BEFORE_STATE_FILE, MIGRATION_FILE, MIGRATION_SUMMARY_FILE,
 };
 use anyhow::Result;
 use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// `moose migrate --dry-run`: the plan is recorded instead of executed
+pub struct DryRun {
+    /// File the SQL script is written to, stdout when unset
+    pub out: Option<PathBuf>,
+}
 
 /// Migration files loaded from disk
 struct MigrationFiles {
     plan: MigrationPlan,
     state_before: InfrastructureMap,
     state_after: InfrastructureMap,
+    /// Missing for plans generated by older CLIs
+    summary: Option<MigrationSummary>,
+}
+
+/// The remote changed since the plan was generated
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "The migration plan is stale: it was generated against state {expected}, but the live state is {found}.\n\
+     Regenerate it with `moose generate migration --save`, or pass --allow-stale to run it anyway"
+)]
+pub struct StalePlanError {
+    pub expected: String,
+    pub found: String,
+}
+
+/// Checks that the live tables are still the ones `summary` was generated against
+fn check_state_hash(
+    summary: &MigrationSummary,
+    current_tables: &HashMap<String, Table>,
+    ignore_operations: &[IgnorableOperation],
+) -> Result<(), StalePlanError> {
+    let found = state_hash(current_tables, ignore_operations);
+    if found == summary.before_state_hash {
+        Ok(())
+    } else {
+        Err(StalePlanError {
+            expected: summary.before_state_hash.clone(),
+            found,
+        })
+    }
 }
 
 /// Result of drift detection
@@ -83,32 +125,20 @@ fn load_migration_files() -> Result<MigrationFiles> {
     let after_content = std::fs::read_to_string(MIGRATION_AFTER_STATE_FILE)?;
     let state_after: InfrastructureMap = serde_json::from_str(&after_content)?;
 
+    let summary = match std::fs::read_to_string(MIGRATION_SUMMARY_FILE) {
+        Ok(content) => Some(serde_yaml::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
     Ok(MigrationFiles {
         plan,
         state_before,
         state_after,
+        summary,
     })
 }
 
-/// Strips both metadata and ignored fields from tables
-fn strip_metadata_and_ignored_fields(
-    tables: &HashMap<String, Table>,
-    ignore_ops: &[IgnorableOperation],
-) -> HashMap<String, Table> {
-    tables
-        .iter()
-        .map(|(name, table)| {
-            let mut table = table.clone();
-            table.metadata = None;
-            // Also strip ignored fields
-            let table = crate::infrastructure::olap::clickhouse::normalize_table_for_diff(
-                &table, ignore_ops,
-            );
-            (name.clone(), table)
-        })
-        .collect()
-}
-
 /// Detects drift by comparing three snapshots of table state.
 ///
 /// This function strips metadata (file paths) before comparison to avoid false positives
@@ -255,6 +285,14 @@ fn validate_table_databases_and_clusters(
             } => {
                 validate(database, cluster_name, table);
             }
+            SerializableOlapOperation::RenameTable {
+                table,
+                database,
+                cluster_name,
+                ..
+            } => {
+                validate(database, cluster_name, table);
+            }
             SerializableOlapOperation::AddTableColumn {
                 table,
                 database,
@@ -351,9 +389,27 @@ fn validate_table_databases_and_clusters(
             } => {
                 validate(database, cluster_name, table);
             }
+            SerializableOlapOperation::ModifyOrderBy {
+                table,
+                database,
+                cluster_name,
+                ..
+            } => {
+                validate(database, cluster_name, table);
+            }
             SerializableOlapOperation::RawSql { .. } => {
                 // RawSql doesn't reference specific tables/databases/clusters, skip validation
             }
+            SerializableOlapOperation::VerifyCopy {
+                table,
+                database,
+                source_table,
+                source_database,
+                ..
+            } => {
+                validate(source_database, &None, source_table);
+                validate(database, &None, table);
+            }
             SerializableOlapOperation::CreateMaterializedView { .. }
             | SerializableOlapOperation::DropMaterializedView { .. }
             | SerializableOlapOperation::CreateView { .. }
@@ -451,6 +507,7 @@ async fn execute_operations(
     project: &Project,
     migration_plan: &MigrationPlan,
     client: &ConfiguredDBClient,
+    state_storage: &dyn StateStorage,
 ) -> Result<()> {
     if migration_plan.operations.is_empty() {
         println!("\n✓ No operations to apply - database is already up to date");
@@ -482,26 +539,40 @@ async fn execute_operations(
         &project.clickhouse_config.clusters,
     )?;
 
+    let tables = writability::plan_tables(&migration_plan.operations, &client.config.db_name);
+    writability::ensure_writable(
+        client,
+        &tables,
+        project.migration_config.wait_for_writable(),
+    )
+    .await?;
+
     let is_dev = !project.is_production;
-    for (idx, operation) in migration_plan.operations.iter().enumerate() {
-        let description = crate::infrastructure::olap::clickhouse::describe_operation(operation);
-        println!(
-            "  [{}/{}] {}",
-            idx + 1,
-            migration_plan.operations.len(),
-            description
-        );
+    let operations = &migration_plan.operations;
+    // Column operations on a table run together, as they do outside of migrations
+    for batch in coalesce_column_operations(operations, &client.config.db_name) {
+        for idx in batch.clone() {
+            let description =
+                crate::infrastructure::olap::clickhouse::describe_operation(&operations[idx]);
+            println!("  [{}/{}] {}", idx + 1, operations.len(), description);
+        }
+
+        // Another instance took the migration lock over while this one stalled
+        if let Err(e) = state_storage.check_migration_lock().await {
+            report_partial_failure(batch.start, operations.len());
+            return Err(e);
+        }
 
         // Execute operation and provide detailed error context on failure
-        if let Err(e) = crate::infrastructure::olap::clickhouse::execute_atomic_operation(
+        if let Err(e) = execute_column_batch(
             &client.config.db_name,
-            operation,
+            &operations[batch.clone()],
             client,
             is_dev,
         )
         .await
         {
-            report_partial_failure(idx, migration_plan.operations.len());
+            report_partial_failure(batch.start, operations.len());
             return Err(e.into());
         }
     }
@@ -510,6 +581,47 @@ async fn execute_operations(
     Ok(())
 }
 
+/// Records the statements of the migration operations, going through the same
+/// code as `execute_operations` without sending anything to ClickHouse
+async fn record_operations(
+    project: &Project,
+    migration_plan: &MigrationPlan,
+    clickhouse_config: &ClickHouseConfig,
+) -> Result<Vec<OperationStatements>> {
+    validate_table_databases_and_clusters(
+        &migration_plan.operations,
+        &project.clickhouse_config.db_name,
+        &project.clickhouse_config.additional_databases,
+        &project.clickhouse_config.clusters,
+    )?;
+
+    let recorder = StatementRecorder::default();
+    let client = create_client(clickhouse_config.clone()).with_recorder(recorder.clone());
+    let is_dev = !project.is_production;
+    let operations = &migration_plan.operations;
+    for batch in coalesce_column_operations(operations, &client.config.db_name) {
+        execute_column_batch(&client.config.db_name, &operations[batch], &client, is_dev).await?;
+    }
+    Ok(recorder.take())
+}
+
+/// Writes the statements of a dry run as a SQL script
+fn write_dry_run(dry_run: &DryRun, operations: &[OperationStatements]) -> Result<()> {
+    let script = statements_to_sql_script(operations);
+    match &dry_run.out {
+        Some(path) => {
+            std::fs::write(path, script)?;
+            println!(
+                "\n✓ Wrote the SQL of {} operation(s) to {}",
+                operations.len(),
+                path.display()
+            );
+        }
+        None => print!("{script}"),
+    }
+    Ok(())
+}
+
 /// Report partial migration failure with recovery instructions
 fn report_partial_failure(succeeded_count: usize, total_count: usize) {
     let remaining = total_count - succeeded_count - 1;
@@ -551,9 +663,14 @@ fn report_partial_failure(succeeded_count: usize, total_count: usize) {
 }
 
 /// Execute migration plan from CLI (moose migrate command)
+///
+/// With `dry_run`, the SQL of the plan is written out instead, and the
+/// migration lock and the state are left untouched.
 pub async fn execute_migration(
     project: &Project,
     redis_url: Option<&str>,
+    dry_run: Option<&DryRun>,
+    allow_stale: bool,
 ) -> Result<(), RoutineFailure> {
     let clickhouse_config = &project.clickhouse_config;
 
@@ -574,15 +691,17 @@ pub async fn execute_migration(
         })?;
 
     // Acquire migration lock to prevent concurrent migrations
-    state_storage.acquire_migration_lock().await.map_err(|e| {
-        RoutineFailure::new(
-            Message::new(
-                "Lock".to_string(),
-                "Failed to acquire migration lock".to_string(),
-            ),
-            e,
-        )
-    })?;
+    if dry_run.is_none() {
+        state_storage.acquire_migration_lock().await.map_err(|e| {
+            RoutineFailure::new(
+                Message::new(
+                    "Lock".to_string(),
+                    "Failed to acquire migration lock".to_string(),
+                ),
+                e,
+            )
+        })?;
+    }
 
     // Wrap all operations to ensure lock cleanup on any error
     let result = async {
@@ -646,6 +765,8 @@ pub async fn execute_migration(
             current_tables,
             &target_infra_map,
             state_storage.as_ref(),
+            dry_run,
+            allow_stale,
         )
         .await
         .map_err(|e| {
@@ -662,8 +783,10 @@ pub async fn execute_migration(
 
     // Always release lock explicitly before returning
     // This ensures cleanup happens even if any operation above failed
-    if let Err(e) = state_storage.release_migration_lock().await {
-        tracing::warn!("Failed to release migration lock: {}", e);
+    if dry_run.is_none() {
+        if let Err(e) = state_storage.release_migration_lock().await {
+            tracing::warn!("Failed to release migration lock: {}", e);
+        }
     }
 
     result
@@ -672,13 +795,17 @@ pub async fn execute_migration(
 /// Execute pre-planned migration
 ///
 /// It validates the plan and executes it if valid. After successful execution,
-/// it saves the new infrastructure state.
+/// it saves the new infrastructure state. With `dry_run`, the valid plan is
+/// recorded and written out instead, and the state is not saved. With
+/// `allow_stale`, a plan whose remote changed since it was generated still runs.
 pub async fn execute_migration_plan(
     project: &Project,
     clickhouse_config: &ClickHouseConfig,
     current_tables: &HashMap<String, Table>,
     target_infra_map: &InfrastructureMap,
     state_storage: &dyn StateStorage,
+    dry_run: Option<&DryRun>,
+    allow_stale: bool,
 ) -> Result<()> {
     println!("Executing migration plan...");
 
@@ -698,14 +825,33 @@ pub async fn execute_migration_plan(
 
     // Validate migration plan
     println!("Validating migration plan...");
+    let ignore_operations = &project.migration_config.ignore_operations;
     let drift = detect_drift(
         current_tables,
         &files.state_before.tables,
         &target_infra_map.tables,
-        &project.migration_config.ignore_operations,
+        ignore_operations,
     );
 
-    match drift {
+    // A database already at the target state has had the plan applied, it is not stale
+    let mut stale = false;
+    match &files.summary {
+        _ if matches!(drift, DriftStatus::AlreadyAtTarget) => {}
+        Some(summary) => match check_state_hash(summary, current_tables, ignore_operations) {
+            Ok(()) => println!("  ✓ Current state hash = recorded before hash"),
+            Err(e) if allow_stale => {
+                println!("  ⚠ {e}");
+                println!("  ⚠ Running the stale plan because of --allow-stale");
+                stale = true;
+            }
+            Err(e) => return Err(e.into()),
+        },
+        None => println!(
+            "  • No {MIGRATION_SUMMARY_FILE}, the plan was generated by an older CLI: skipping the state hash check"
+        ),
+    }
+
+    let run_plan = match drift {
         DriftStatus::NoDrift => {
             println!("  ✓ Current = Expected (no drift detected)");
 
@@ -720,14 +866,15 @@ pub async fn execute_migration_plan(
                 );
             }
             println!("  ✓ Target = Code (plan is still valid)");
-
-            // Execute operations
-            let client = create_client(clickhouse_config.clone());
-            check_ready(&client).await?;
-            execute_operations(project, &files.plan, &client).await?;
+            true
         }
         DriftStatus::AlreadyAtTarget => {
             println!("  ✓ Database already matches target state - skipping migration");
+            false
+        }
+        DriftStatus::DriftDetected { .. } if stale => {
+            report_drift(&drift);
+            true
         }
         DriftStatus::DriftDetected { .. } => {
             report_drift(&drift);
@@ -743,6 +890,21 @@ pub async fn execute_migration_plan(
                  moose generate migration --clickhouse-url <url> --save\n"
             );
         }
+    };
+
+    if let Some(dry_run) = dry_run {
+        let operations = if run_plan {
+            record_operations(project, &files.plan, clickhouse_config).await?
+        } else {
+            Vec::new()
+        };
+        return write_dry_run(dry_run, &operations);
+    }
+
+    if run_plan {
+        let client = create_client(clickhouse_config.clone());
+        check_ready(&client).await?;
+        execute_operations(project, &files.plan, &client, state_storage).await?;
     }
 
     // Save the complete infrastructure state
@@ -757,7 +919,7 @@ pub async fn execute_migration_plan(
 mod tests {
     use super::*;
     use crate::framework::core::infrastructure::table::{
-        Column, ColumnType, OrderBy, TableProjection,
+        Column, ColumnType, Metadata, OrderBy, TableProjection,
     };
     use crate::framework::core::infrastructure_map::PrimitiveSignature;
     use crate::framework::core::partial_infrastructure_map::LifeCycle;
@@ -803,6 +965,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }
     }
 
@@ -857,6 +1020,38 @@ mod tests {
         assert!(matches!(result, DriftStatus::AlreadyAtTarget));
     }
 
+    #[test]
+    fn test_check_state_hash() {
+        let mut remote = HashMap::new();
+        remote.insert("users".to_string(), create_test_table("users"));
+        let summary = MigrationSummary {
+            generated_at: chrono::Utc::now(),
+            cli_version: "0.0.1".to_string(),
+            remote: "serverless:clickhouse".to_string(),
+            git: None,
+            operations: Default::default(),
+            before_state_hash: state_hash(&remote, &[]),
+            after_state_hash: state_hash(&HashMap::new(), &[]),
+        };
+
+        assert!(check_state_hash(&summary, &remote, &[]).is_ok());
+
+        // Metadata, such as the source location of the model, is not part of the state
+        let mut with_metadata = create_test_table("users");
+        with_metadata.metadata = Some(Metadata {
+            description: Some("Users".to_string()),
+            source: None,
+        });
+        remote.insert("users".to_string(), with_metadata);
+        assert!(check_state_hash(&summary, &remote, &[]).is_ok());
+
+        remote.insert("users".to_string(), create_modified_table("users"));
+        let err = check_state_hash(&summary, &remote, &[]).unwrap_err();
+        assert_eq!(err.expected, summary.before_state_hash);
+        assert_ne!(err.found, err.expected);
+        assert!(err.to_string().contains("--allow-stale"));
+    }
+
     #[test]
     fn test_detect_drift_with_extra_tables() {
         let mut current = HashMap::new();
@@ -1,7 +1,11 @@
+use super::{RoutineFailure, RoutineSuccess};
+use crate::cli::display::Message;
+use crate::cli::exit_code::CliExitCode;
 use crate::framework::consumption::model::ConsumptionQueryParam;
 use crate::framework::core::infrastructure::api_endpoint::{APIType, ApiEndpoint};
 use crate::framework::core::infrastructure::table::ColumnType;
 use crate::framework::core::infrastructure_map::InfrastructureMap;
+use crate::framework::core::plan::load_target_infrastructure;
 use crate::project::Project;
 use crate::utilities::constants::OPENAPI_FILE;
 
@@ -12,7 +16,8 @@ use serde_yaml;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
 
 #[derive(Serialize, Deserialize)]
@@ -91,21 +96,149 @@ struct Components {
 pub enum OpenAPIError {
     #[error("Failed to save OpenAPI spec to file: {0}")]
     Save(String),
+
+    #[error("Unknown OpenAPI format '{0}', expected json or yaml")]
+    UnknownFormat(String),
+}
+
+/// Serialization of a generated OpenAPI spec.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OpenAPIFormat {
+    #[default]
+    Yaml,
+    Json,
+}
+
+impl OpenAPIFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            OpenAPIFormat::Yaml => "yaml",
+            OpenAPIFormat::Json => "json",
+        }
+    }
 }
 
+impl FromStr for OpenAPIFormat {
+    type Err = OpenAPIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Ok(OpenAPIFormat::Yaml),
+            "json" => Ok(OpenAPIFormat::Json),
+            _ => Err(OpenAPIError::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// Writes the spec for `infra_map` to the project's internal directory, as dev
+/// mode does after every change.
 pub async fn openapi(
     project: &Arc<Project>,
     infra_map: &InfrastructureMap,
 ) -> Result<PathBuf, OpenAPIError> {
-    let openapi_spec = generate_openapi_spec(project, infra_map);
     let openapi_file = project.internal_dir().unwrap().join(OPENAPI_FILE);
-    save_openapi_to_file(&openapi_spec, &openapi_file.to_string_lossy())
-        .map_err(|e| OpenAPIError::Save(e.to_string()))?;
+    write_openapi(project, infra_map, &openapi_file, OpenAPIFormat::Yaml)?;
 
     Ok(openapi_file)
 }
 
-fn generate_openapi_spec(project: &Arc<Project>, infra_map: &InfrastructureMap) -> OpenAPI {
+fn write_openapi(
+    project: &Project,
+    infra_map: &InfrastructureMap,
+    path: &Path,
+    format: OpenAPIFormat,
+) -> Result<(), OpenAPIError> {
+    let openapi_spec = generate_openapi_spec(project, infra_map);
+    save_openapi_to_file(&openapi_spec, path, format).map_err(|e| OpenAPIError::Save(e.to_string()))
+}
+
+/// Explains why the project's APIs cannot be served with its current
+/// `[features]`, or `None` when the spec matches what dev mode would serve.
+fn disabled_features_error(project: &Project, infra_map: &InfrastructureMap) -> Option<String> {
+    if !project.features.apis && !project.features.streaming_engine {
+        return Some(
+            "Ingest and Analytics APIs are disabled. Enable them by setting [features].streaming_engine = true or [features].apis = true in moose.config.toml"
+                .to_string(),
+        );
+    }
+
+    let count = |ingress: bool| {
+        infra_map
+            .api_endpoints
+            .values()
+            .filter(|endpoint| matches!(endpoint.api_type, APIType::INGRESS { .. }) == ingress)
+            .count()
+    };
+    if !project.features.streaming_engine && count(true) > 0 {
+        return Some(format!(
+            "Streaming is disabled but {} ingest API(s) found. Enable it by setting [features].streaming_engine = true in moose.config.toml",
+            count(true)
+        ));
+    }
+    if !project.features.apis && count(false) > 0 {
+        return Some(format!(
+            "Analytics APIs feature is disabled but {} API endpoint(s) found. Enable it by setting [features].apis = true in moose.config.toml",
+            count(false)
+        ));
+    }
+    None
+}
+
+/// `moose openapi`: writes the spec dev mode generates, loading the
+/// infrastructure map from the project code without touching any infrastructure.
+pub async fn generate_openapi(
+    project: &Project,
+    out: Option<&Path>,
+    format: OpenAPIFormat,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let infra_map = load_target_infrastructure(project).await.map_err(|e| {
+        RoutineFailure::new(
+            Message::new(
+                "OpenAPI".to_string(),
+                "Failed to load the infrastructure map".to_string(),
+            ),
+            e,
+        )
+    })?;
+
+    if let Some(details) = disabled_features_error(project, &infra_map) {
+        return Err(
+            RoutineFailure::error(Message::new("OpenAPI".to_string(), details))
+                .with_exit_code(CliExitCode::ValidationFailure),
+        );
+    }
+
+    let path = out
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(format!("openapi.{}", format.extension())));
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            RoutineFailure::new(
+                Message::new(
+                    "OpenAPI".to_string(),
+                    format!("Failed to create {}", parent.display()),
+                ),
+                e,
+            )
+        })?;
+    }
+    write_openapi(project, &infra_map, &path, format).map_err(|e| {
+        RoutineFailure::new(
+            Message::new(
+                "OpenAPI".to_string(),
+                format!("Failed to write {}", path.display()),
+            ),
+            e,
+        )
+    })?;
+
+    Ok(RoutineSuccess::success(Message::new(
+        "OpenAPI".to_string(),
+        format!("spec written to {}", path.display()),
+    )))
+}
+
+fn generate_openapi_spec(project: &Project, infra_map: &InfrastructureMap) -> OpenAPI {
     let mut paths = HashMap::new();
     let mut schemas = HashMap::new();
 
@@ -294,21 +427,166 @@ fn map_query_param_type(data_type: &ColumnType) -> (String, Option<serde_json::V
     }
 }
 
-fn save_openapi_to_file(openapi_spec: &OpenAPI, file_path: &str) -> std::io::Result<()> {
-    let openapi_yaml = serde_yaml::to_string(openapi_spec).unwrap();
+fn save_openapi_to_file(
+    openapi_spec: &OpenAPI,
+    file_path: &Path,
+    format: OpenAPIFormat,
+) -> std::io::Result<()> {
     let mut file = File::create(file_path)?;
-    if openapi_spec.openapi.starts_with("3.1") {
-        file.write_all("# Use https://editor-next.swagger.io/ if the current version does not support 3.1 yet.\n".as_bytes())?;
+    match format {
+        OpenAPIFormat::Yaml => {
+            let openapi_yaml = serde_yaml::to_string(openapi_spec).unwrap();
+            if openapi_spec.openapi.starts_with("3.1") {
+                file.write_all("# Use https://editor-next.swagger.io/ if the current version does not support 3.1 yet.\n".as_bytes())?;
+            }
+            file.write_all(openapi_yaml.as_bytes())?;
+        }
+        OpenAPIFormat::Json => {
+            let openapi_json = serde_json::to_string_pretty(openapi_spec)?;
+            writeln!(file, "{openapi_json}")?;
+        }
     }
-    file.write_all(openapi_yaml.as_bytes())?;
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::framework::core::infrastructure::api_endpoint::Method;
+    use crate::framework::core::infrastructure::table::IntType;
+    use crate::framework::core::infrastructure_map::{PrimitiveSignature, PrimitiveTypes};
+    use crate::framework::languages::SupportedLanguages;
     use serde_json::json;
 
+    fn endpoint(name: &str, api_type: APIType) -> ApiEndpoint {
+        ApiEndpoint {
+            name: name.to_string(),
+            api_type,
+            path: PathBuf::from(name),
+            method: Method::POST,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::ConsumptionAPI,
+            },
+            metadata: None,
+            pulls_data_from: vec![],
+            pushes_data_to: vec![],
+        }
+    }
+
+    fn fixture_infra_map() -> InfrastructureMap {
+        let mut infra_map = InfrastructureMap::default();
+        let ingest = endpoint(
+            "ingest/Order",
+            APIType::INGRESS {
+                target_topic_id: "Order".to_string(),
+                data_model: None,
+                dead_letter_queue: None,
+                schema: json!({
+                    "type": "object",
+                    "properties": {"id": {"type": "string"}, "amount": {"type": "number"}}
+                })
+                .as_object()
+                .unwrap()
+                .clone(),
+            },
+        );
+        let api = endpoint(
+            "orders",
+            APIType::EGRESS {
+                query_params: vec![
+                    ConsumptionQueryParam {
+                        name: "limit".to_string(),
+                        data_type: ColumnType::Int(IntType::Int64),
+                        required: true,
+                    },
+                    ConsumptionQueryParam {
+                        name: "refunded".to_string(),
+                        data_type: ColumnType::Boolean,
+                        required: false,
+                    },
+                ],
+                output_schema: json!({"type": "array", "items": {"type": "object"}}),
+            },
+        );
+        infra_map.api_endpoints.insert(ingest.name.clone(), ingest);
+        infra_map.api_endpoints.insert(api.name.clone(), api);
+        infra_map
+    }
+
+    #[tokio::test]
+    async fn test_standalone_spec_matches_dev_mode_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = Arc::new(Project::new(
+            dir.path(),
+            "orders".to_string(),
+            SupportedLanguages::Typescript,
+        ));
+        let infra_map = fixture_infra_map();
+
+        let dev_file = openapi(&project, &infra_map).await.unwrap();
+        let dev_yaml = std::fs::read_to_string(&dev_file).unwrap();
+        let dev_spec: Value = serde_yaml::from_str(&dev_yaml).unwrap();
+
+        let yaml_file = dir.path().join("out.yaml");
+        write_openapi(&project, &infra_map, &yaml_file, OpenAPIFormat::Yaml).unwrap();
+        let yaml_spec: Value =
+            serde_yaml::from_str(&std::fs::read_to_string(&yaml_file).unwrap()).unwrap();
+        assert_eq!(yaml_spec, dev_spec);
+
+        let json_file = dir.path().join("out.json");
+        write_openapi(&project, &infra_map, &json_file, OpenAPIFormat::Json).unwrap();
+        let json_spec: Value =
+            serde_json::from_str(&std::fs::read_to_string(&json_file).unwrap()).unwrap();
+        assert_eq!(json_spec, dev_spec);
+
+        let limit = &json_spec["paths"]["/api/orders"]["get"]["parameters"][0];
+        assert_eq!(limit["name"], "limit");
+        assert_eq!(limit["schema"]["type"], "integer");
+        assert_eq!(
+            json_spec["paths"]["/ingest/Order"]["post"]["requestBody"]["content"]
+                ["application/json"]["schema"]["properties"]["amount"]["type"],
+            "number"
+        );
+    }
+
+    #[test]
+    fn test_disabled_features_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut project = Project::new(
+            dir.path(),
+            "orders".to_string(),
+            SupportedLanguages::Typescript,
+        );
+        let infra_map = fixture_infra_map();
+        assert_eq!(disabled_features_error(&project, &infra_map), None);
+
+        project.features.apis = false;
+        let error = disabled_features_error(&project, &infra_map).unwrap();
+        assert!(error.contains("1 API endpoint(s)"), "{error}");
+
+        project.features.streaming_engine = false;
+        let error = disabled_features_error(&project, &InfrastructureMap::default()).unwrap();
+        assert!(
+            error.starts_with("Ingest and Analytics APIs are disabled"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_openapi_format_from_str() {
+        assert_eq!(
+            "JSON".parse::<OpenAPIFormat>().unwrap(),
+            OpenAPIFormat::Json
+        );
+        assert_eq!("yml".parse::<OpenAPIFormat>().unwrap(), OpenAPIFormat::Yaml);
+        assert!(matches!(
+            "xml".parse::<OpenAPIFormat>(),
+            Err(OpenAPIError::UnknownFormat(_))
+        ));
+    }
+
     #[test]
     fn test_extract_component_schemas_with_enums() {
         // Test the exact enum pattern mentioned in the issue
@@ -1,25 +1,35 @@
 //! Module for examining data in the Moose framework.
 //!
 //! This module provides functionality to retrieve and display sample data from
-//! either database tables or streaming topics for debugging and exploration purposes.
+//! database tables, views, the target tables of materialized views or
+//! streaming topics for debugging and exploration purposes.
 
-use crate::cli::display::Message;
-use crate::framework::core::infrastructure::table::Table;
+use crate::cli::display::{render_table, Message};
+use crate::framework::core::infrastructure::materialized_view::MaterializedView;
+use crate::framework::core::infrastructure::table::{OrderBy, Table};
 use crate::framework::core::infrastructure::topic::Topic;
+use crate::framework::core::infrastructure::view::View;
 use crate::framework::core::infrastructure_map::InfrastructureMap;
 use crate::infrastructure::olap::clickhouse::mapper::std_table_to_clickhouse_table;
-use crate::infrastructure::olap::clickhouse_http_client::create_query_client;
+use crate::infrastructure::olap::clickhouse_http_client::{
+    create_query_client, query_as_json_stream,
+};
 use crate::project::Project;
 
 use super::{setup_redis_client, RoutineFailure, RoutineSuccess};
 
-use crate::infrastructure::olap::clickhouse::model::ClickHouseTable;
+use crate::infrastructure::olap::clickhouse::model::{
+    quote_ident, quote_qualified, wrap_and_join_column_names, ClickHouseTable,
+};
+use crate::infrastructure::olap::clickhouse::ConfiguredDBClient;
 use crate::infrastructure::stream::kafka::client::create_consumer;
 use futures::stream::BoxStream;
 use rdkafka::consumer::{Consumer, StreamConsumer};
 use rdkafka::{Message as KafkaMessage, Offset, TopicPartitionList};
 use serde_json::Value;
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::fs::File;
@@ -27,17 +37,119 @@ use tokio::io::AsyncWriteExt;
 use tokio_stream::StreamExt;
 use tracing::info;
 
+/// Output format for peeked rows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PeekFormat {
+    /// One JSON object per line
+    #[default]
+    Json,
+    /// RFC 4180 CSV with a header row
+    Csv,
+    /// Human readable table
+    Table,
+}
+
+impl FromStr for PeekFormat {
+    type Err = PeekQueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(PeekFormat::Json),
+            "csv" => Ok(PeekFormat::Csv),
+            "table" => Ok(PeekFormat::Table),
+            _ => Err(PeekQueryError::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// Options controlling which rows are peeked and how they are rendered.
+#[derive(Debug, Default, Clone)]
+pub struct PeekOptions {
+    pub limit: u8,
+    pub order_by: Option<String>,
+    pub desc: bool,
+    pub where_clause: Option<String>,
+    pub format: PeekFormat,
+    /// Poll interval when following the target table of a materialized view
+    pub follow: Option<Duration>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PeekQueryError {
+    #[error("Unknown output format '{0}'. Expected one of: json, csv, table")]
+    UnknownFormat(String),
+
+    #[error("Column '{column}' used in {clause} does not exist on table '{table}'. Available columns: {available}")]
+    UnknownColumn {
+        clause: &'static str,
+        column: String,
+        table: String,
+        available: String,
+    },
+
+    #[error("--where predicate must not contain semicolons")]
+    SemicolonInPredicate,
+
+    #[error("--where predicate must not be empty")]
+    EmptyPredicate,
+
+    #[error("--order-by and --where can only be used when peeking tables")]
+    TableOnlyOption,
+
+    #[error("'{name}' matches several resources: {candidates}. Qualify it with its database, e.g. 'database.{name}'")]
+    AmbiguousName { name: String, candidates: String },
+
+    #[error("--follow can only be used with materialized views, and {0} is not one")]
+    FollowRequiresMaterializedView(String),
+
+    #[error("--follow needs the rows of '{0}' to be ordered by columns. Pass one with --order-by")]
+    FollowWithoutOrderKey(String),
+}
+
+/// A table, view or materialized view a peeked name resolves to.
+#[derive(Debug)]
+enum PeekTarget<'a> {
+    Table(&'a Table),
+    View(&'a View),
+    MaterializedView(&'a MaterializedView),
+}
+
+impl PeekTarget<'_> {
+    /// e.g. "view `local.active_users`"
+    fn describe(&self, default_database: &str) -> String {
+        let (kind, database, name) = match self {
+            PeekTarget::Table(table) => ("table", &table.database, &table.name),
+            PeekTarget::View(view) => ("view", &view.database, &view.name),
+            PeekTarget::MaterializedView(mv) => ("materialized view", &mv.database, &mv.name),
+        };
+        format!(
+            "{kind} `{}.{name}`",
+            database.as_deref().unwrap_or(default_database)
+        )
+    }
+}
+
+/// Words that may appear in a `--where` predicate without referring to a column.
+const PREDICATE_KEYWORDS: &[&str] = &[
+    "AND", "OR", "NOT", "IN", "IS", "NULL", "LIKE", "ILIKE", "BETWEEN", "TRUE", "FALSE",
+    "INTERVAL", "SECOND", "MINUTE", "HOUR", "DAY", "WEEK", "MONTH", "QUARTER", "YEAR", "CASE",
+    "WHEN", "THEN", "ELSE", "END", "GLOBAL", "EXISTS", "AS", "DISTINCT", "NAN", "INF",
+];
+
 /// Retrieves and displays a sample of data from either a database table or streaming topic.
 ///
 /// Allows users to examine the actual data contents of resources in the Moose framework
 /// by querying either the ClickHouse database tables or Redpanda streaming topics.
+/// Views are queried directly and materialized views through their target table,
+/// which `--follow` keeps polling for new rows.
 /// Results can be displayed to the console or written to a file.
 ///
 /// # Arguments
 ///
 /// * `project` - The project configuration to use
-/// * `name` - Name of the table or stream to peek
-/// * `limit` - Maximum number of records to retrieve
+/// * `name` - Name of the table, view, materialized view or stream to peek
+/// * `options` - Row limit, ordering, filtering and output format
 /// * `file` - Optional file path to save the output instead of displaying to console
 /// * `is_stream` - Whether to peek at a stream/topic (true) or a table (false)
 ///
@@ -47,10 +159,21 @@ use tracing::info;
 pub async fn peek(
     project: Arc<Project>,
     name: &str,
-    limit: u8,
+    options: PeekOptions,
     file: Option<PathBuf>,
     is_stream: bool,
 ) -> Result<RoutineSuccess, RoutineFailure> {
+    if is_stream && (options.order_by.is_some() || options.where_clause.is_some()) {
+        return Err(peek_query_failure(PeekQueryError::TableOnlyOption));
+    }
+    if is_stream && options.follow.is_some() {
+        return Err(peek_query_failure(
+            PeekQueryError::FollowRequiresMaterializedView(format!("stream '{name}'")),
+        ));
+    }
+
+    let limit = options.limit;
+
     // Get HTTP-based ClickHouse client
     let client = create_query_client(&project.clickhouse_config);
 
@@ -77,9 +200,8 @@ pub async fn peek(
         })?;
 
     let consumer_ref: StreamConsumer;
-    let table_ref: ClickHouseTable;
 
-    let mut stream: BoxStream<anyhow::Result<Value>> = if is_stream {
+    let (columns, mut stream): (Vec<String>, BoxStream<anyhow::Result<Value>>) = if is_stream {
         let group_id = project.redpanda_config.prefix_with_namespace("peek");
 
         consumer_ref = create_consumer(&project.redpanda_config, &[("group.id", &group_id)]);
@@ -116,182 +238,615 @@ pub async fn peek(
                 )
             })?;
 
-        Box::pin(
-            consumer
-                .stream()
-                .map(|message| {
-                    Ok(serde_json::from_slice::<Value>(
-                        message?.payload().unwrap_or(&[]),
-                    )?)
-                })
-                .take(limit.into())
-                // ends the stream if the next message takes 1 second
-                // i.e. the kafka queue has less than `limit` records
-                .timeout(Duration::from_secs(1))
-                .take_while(Result::is_ok)
-                .map(Result::unwrap),
+        let columns = topic.columns.iter().map(|c| c.name.clone()).collect();
+
+        (
+            columns,
+            Box::pin(
+                consumer
+                    .stream()
+                    .map(|message| {
+                        Ok(serde_json::from_slice::<Value>(
+                            message?.payload().unwrap_or(&[]),
+                        )?)
+                    })
+                    .take(limit.into())
+                    // ends the stream if the next message takes 1 second
+                    // i.e. the kafka queue has less than `limit` records
+                    .timeout(Duration::from_secs(1))
+                    .take_while(Result::is_ok)
+                    .map(Result::unwrap),
+            ),
         )
     } else {
-        let table = find_table_by_name(&infra, name).ok_or_else(|| {
-            let available_tables: Vec<String> =
-                infra.tables.values().map(|t| t.name.clone()).collect();
-            RoutineFailure::error(Message::new(
-                "Failed".to_string(),
-                format!(
-                    "No matching table found: '{}'. Available tables: {}",
-                    name,
-                    available_tables.join(", ")
-                ),
-            ))
-        })?;
+        let target = resolve_peek_target(&infra, name)
+            .map_err(peek_query_failure)?
+            .ok_or_else(|| {
+                let available: Vec<String> = infra
+                    .tables
+                    .values()
+                    .map(|t| t.name.clone())
+                    .chain(infra.views.values().map(|v| v.name.clone()))
+                    .chain(infra.materialized_views.values().map(|mv| mv.name.clone()))
+                    .collect();
+                RoutineFailure::error(Message::new(
+                    "Failed".to_string(),
+                    format!(
+                        "No matching table, view or materialized view found: '{}'. Available: {}",
+                        name,
+                        available.join(", ")
+                    ),
+                ))
+            })?;
 
-        table_ref = std_table_to_clickhouse_table(table).map_err(|_| {
-            RoutineFailure::error(Message::new(
-                "Failed".to_string(),
-                "Error fetching table".to_string(),
-            ))
-        })?;
+        let default_database = &project.clickhouse_config.db_name;
+        if options.follow.is_some() && !matches!(target, PeekTarget::MaterializedView(_)) {
+            return Err(peek_query_failure(
+                PeekQueryError::FollowRequiresMaterializedView(target.describe(default_database)),
+            ));
+        }
 
-        // Build the SELECT query
-        let order_by = match &table_ref.order_by {
-            crate::framework::core::infrastructure::table::OrderBy::Fields(fields)
-                if !fields.is_empty() =>
-            {
-                format!(
-                    "ORDER BY {}",
-                    crate::infrastructure::olap::clickhouse::model::wrap_and_join_column_names(
-                        fields, ", "
-                    )
+        // Views are selected from directly, materialized views through the
+        // table they write to
+        let (database, relation, table) = match target {
+            PeekTarget::Table(table) => (table.database.clone(), table.name.clone(), Some(table)),
+            PeekTarget::View(view) => (view.database.clone(), view.name.clone(), None),
+            PeekTarget::MaterializedView(mv) => {
+                let database = mv.target_database.clone().or_else(|| mv.database.clone());
+                let target_table = format!(
+                    "{}.{}",
+                    database.as_deref().unwrap_or(&infra.default_database),
+                    mv.target_table
+                );
+                (
+                    database,
+                    mv.target_table.clone(),
+                    find_table_by_name(&infra, &target_table),
                 )
             }
-            crate::framework::core::infrastructure::table::OrderBy::SingleExpr(expr) => {
-                format!("ORDER BY {expr}")
-            }
-            _ => {
-                // Fall back to primary key
-                let key_columns: Vec<String> = table_ref
-                    .primary_key_columns()
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect();
+        };
 
-                if key_columns.is_empty() {
-                    "".to_string()
-                } else {
-                    format!(
-                        "ORDER BY {}",
-                        crate::infrastructure::olap::clickhouse::model::wrap_and_join_column_names(
-                            &key_columns,
-                            ", "
-                        )
-                    )
-                }
-            }
+        let table_ref = table
+            .map(std_table_to_clickhouse_table)
+            .transpose()
+            .map_err(|_| {
+                RoutineFailure::error(Message::new(
+                    "Failed".to_string(),
+                    "Error fetching table".to_string(),
+                ))
+            })?;
+        let relation = match &table_ref {
+            Some(table_ref) => table_ref.name.clone(),
+            None => relation,
         };
 
-        // Respect explicit table database, fallback to project default
-        let database = table
-            .database
-            .as_deref()
-            .unwrap_or(&project.clickhouse_config.db_name);
-        let query = format!(
-            "SELECT * FROM \"{}\".\"{}\" {} LIMIT {}",
-            database, table_ref.name, order_by, limit
-        );
+        // Respect explicit database, fallback to project default
+        let database = database.as_deref().unwrap_or(default_database);
+        let columns: Vec<String> = table
+            .map(|t| t.columns.iter().map(|c| c.name.clone()).collect())
+            .unwrap_or_default();
+
+        if let Some(interval) = options.follow {
+            let key = follow_key(table_ref.as_ref(), &options);
+            if key.is_empty() {
+                return Err(peek_query_failure(PeekQueryError::FollowWithoutOrderKey(
+                    relation,
+                )));
+            }
+            let query = build_follow_query(database, &relation, &columns, &key, &options)
+                .map_err(peek_query_failure)?;
+            info!("Peek follow query: {}", query);
+            return follow_rows(&client, &query, &key, options.format, &columns, interval).await;
+        }
+
+        let query = build_peek_query(
+            database,
+            &relation,
+            &columns,
+            &table_ref.as_ref().map(default_order_by).unwrap_or_default(),
+            &options,
+        )
+        .map_err(peek_query_failure)?;
 
         info!("Peek query: {}", query);
 
         // Execute query
-        let rows = crate::infrastructure::olap::clickhouse_http_client::query_as_json_stream(
-            &client, &query,
-        )
-        .await
-        .map_err(|e| {
-            RoutineFailure::error(Message::new(
-                "Peek".to_string(),
-                format!("ClickHouse query error: {}", e),
-            ))
-        })?;
+        let rows = query_as_json_stream(&client, &query)
+            .await
+            .map_err(clickhouse_query_failure)?;
 
         // Convert Vec to stream
-        Box::pin(tokio_stream::iter(rows.into_iter().map(anyhow::Ok)))
+        (
+            columns,
+            Box::pin(tokio_stream::iter(rows.into_iter().map(anyhow::Ok))),
+        )
     };
 
-    let mut success_count = 0;
-
-    let (mut file, success_message): (Option<File>, Box<dyn Fn(i32) -> String>) =
-        if let Some(file_path) = file {
-            (
-                Some(File::create(&file_path).await.map_err(|_| {
-                    RoutineFailure::error(Message::new(
-                        "Failed".to_string(),
-                        "Error creating file".to_string(),
-                    ))
-                })?),
-                Box::new(move |success_count| {
-                    format!("{success_count} rows written to {file_path:?}")
-                }),
-            )
-        } else {
-            (
-                None,
-                Box::new(|success_count| {
-                    // Just a newline for output cleanliness
-                    println!();
-                    format!("{success_count} rows")
-                }),
-            )
-        };
-
+    let mut rows = Vec::new();
     while let Some(result) = stream.next().await {
         match result {
-            Ok(value) => {
-                let json = serde_json::to_string(&value).unwrap();
-                match &mut file {
-                    None => {
-                        println!("{json}");
-                        info!("{}", json);
-                    }
-                    Some(ref mut file) => {
-                        file.write_all(format!("{json}\n").as_bytes())
-                            .await
-                            .map_err(|_| {
-                                RoutineFailure::error(Message::new(
-                                    "Failed".to_string(),
-                                    "Error writing to file".to_string(),
-                                ))
-                            })?;
-                    }
-                }
-                success_count += 1;
-            }
+            Ok(value) => rows.push(value),
             Err(e) => {
                 tracing::error!("Failed to read row {}", e);
             }
         }
     }
 
+    let output = render_rows(options.format, &columns, &rows).map_err(|e| {
+        RoutineFailure::new(
+            Message::new("Failed".to_string(), "Error rendering rows".to_string()),
+            e,
+        )
+    })?;
+
+    let success_message = match file {
+        Some(file_path) => {
+            let mut file = File::create(&file_path).await.map_err(|_| {
+                RoutineFailure::error(Message::new(
+                    "Failed".to_string(),
+                    "Error creating file".to_string(),
+                ))
+            })?;
+            file.write_all(output.as_bytes()).await.map_err(|_| {
+                RoutineFailure::error(Message::new(
+                    "Failed".to_string(),
+                    "Error writing to file".to_string(),
+                ))
+            })?;
+            format!("{} rows written to {file_path:?}", rows.len())
+        }
+        None => {
+            print!("{output}");
+            format!("{} rows", rows.len())
+        }
+    };
+
     Ok(RoutineSuccess::success(Message::new(
         "Peeked".to_string(),
-        success_message(success_count),
+        success_message,
     )))
 }
 
+fn peek_query_failure(e: PeekQueryError) -> RoutineFailure {
+    RoutineFailure::error(Message::new("Peek".to_string(), e.to_string()))
+}
+
+fn clickhouse_query_failure(e: Box<dyn std::error::Error + Send + Sync>) -> RoutineFailure {
+    RoutineFailure::error(Message::new(
+        "Peek".to_string(),
+        format!("ClickHouse query error: {}", e),
+    ))
+}
+
+/// Prints the rows of `query` every `interval` until interrupted, skipping
+/// those whose `key` was already printed. `query` returns the newest keys
+/// first; each batch is printed oldest first.
+async fn follow_rows(
+    client: &ConfiguredDBClient,
+    query: &str,
+    key: &[String],
+    format: PeekFormat,
+    columns: &[String],
+    interval: Duration,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let mut seen = HashSet::new();
+    let mut header_printed = false;
+    loop {
+        let mut rows: Vec<Value> = query_as_json_stream(client, query)
+            .await
+            .map_err(clickhouse_query_failure)?
+            .into_iter()
+            .filter(|row| seen.insert(row_key(row, key)))
+            .collect();
+        rows.reverse();
+
+        if !rows.is_empty() {
+            let output = render_rows(format, columns, &rows).map_err(|e| {
+                RoutineFailure::new(
+                    Message::new("Failed".to_string(), "Error rendering rows".to_string()),
+                    e,
+                )
+            })?;
+            // The CSV header is only printed with the first batch
+            match output.split_once('\n') {
+                Some((_, batch)) if format == PeekFormat::Csv && header_printed => {
+                    print!("{batch}")
+                }
+                _ => print!("{output}"),
+            }
+            header_printed = true;
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// The values of the `key` columns of `row`, as compact JSON
+fn row_key(row: &Value, key: &[String]) -> String {
+    Value::Array(
+        key.iter()
+            .map(|column| row.get(column).cloned().unwrap_or(Value::Null))
+            .collect(),
+    )
+    .to_string()
+}
+
+/// Columns telling the rows of a followed table apart: `--order-by` when
+/// given, else the table's `ORDER BY` columns or primary key. Empty when the
+/// table is ordered by an expression, or unknown.
+fn follow_key(table_ref: Option<&ClickHouseTable>, options: &PeekOptions) -> Vec<String> {
+    if let Some(column) = &options.order_by {
+        return vec![column.clone()];
+    }
+    let Some(table_ref) = table_ref else {
+        return vec![];
+    };
+    match &table_ref.order_by {
+        OrderBy::Fields(fields) if !fields.is_empty() => fields.clone(),
+        OrderBy::SingleExpr(_) => vec![],
+        _ => table_ref
+            .primary_key_columns()
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    }
+}
+
+/// Builds the query polled by `--follow`: the rows with the `limit` greatest
+/// values of `key`, newest first.
+fn build_follow_query(
+    database: &str,
+    table_name: &str,
+    columns: &[String],
+    key: &[String],
+    options: &PeekOptions,
+) -> Result<String, PeekQueryError> {
+    if let Some(column) = key
+        .iter()
+        .find(|column| !columns.is_empty() && !is_known_column(columns, column))
+    {
+        return Err(PeekQueryError::UnknownColumn {
+            clause: "--order-by",
+            column: column.clone(),
+            table: table_name.to_string(),
+            available: columns.join(", "),
+        });
+    }
+
+    let newest_first: Vec<String> = key
+        .iter()
+        .map(|column| format!("{} DESC", quote_ident(column)))
+        .collect();
+    build_peek_query(
+        database,
+        table_name,
+        columns,
+        &format!("ORDER BY {}", newest_first.join(", ")),
+        &PeekOptions {
+            order_by: None,
+            ..options.clone()
+        },
+    )
+}
+
+/// Builds the `ORDER BY` clause used when the user does not pass `--order-by`:
+/// the table's own ordering, falling back to its primary key.
+fn default_order_by(table_ref: &ClickHouseTable) -> String {
+    match &table_ref.order_by {
+        OrderBy::Fields(fields) if !fields.is_empty() => {
+            format!("ORDER BY {}", wrap_and_join_column_names(fields, ", "))
+        }
+        OrderBy::SingleExpr(expr) => format!("ORDER BY {expr}"),
+        _ => {
+            // Fall back to primary key
+            let key_columns: Vec<String> = table_ref
+                .primary_key_columns()
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+
+            if key_columns.is_empty() {
+                "".to_string()
+            } else {
+                format!(
+                    "ORDER BY {}",
+                    wrap_and_join_column_names(&key_columns, ", ")
+                )
+            }
+        }
+    }
+}
+
+/// Builds a bounded `SELECT` over the table's declared columns, validating any
+/// user supplied ordering column and predicate against them.
+fn build_peek_query(
+    database: &str,
+    table_name: &str,
+    columns: &[String],
+    default_order_by: &str,
+    options: &PeekOptions,
+) -> Result<String, PeekQueryError> {
+    let unknown_column = |clause: &'static str, column: &str| PeekQueryError::UnknownColumn {
+        clause,
+        column: column.to_string(),
+        table: table_name.to_string(),
+        available: columns.join(", "),
+    };
+
+    let mut query = format!(
+        "SELECT {} FROM {}",
+        if columns.is_empty() {
+            "*".to_string()
+        } else {
+            wrap_and_join_column_names(columns, ", ")
+        },
+        quote_qualified(database, table_name)
+    );
+
+    if let Some(predicate) = &options.where_clause {
+        let predicate = predicate.trim();
+        if predicate.is_empty() {
+            return Err(PeekQueryError::EmptyPredicate);
+        }
+        if predicate.contains(';') {
+            return Err(PeekQueryError::SemicolonInPredicate);
+        }
+        if !columns.is_empty() {
+            if let Some(column) = predicate_identifiers(predicate)
+                .into_iter()
+                .find(|ident| !is_known_column(columns, ident))
+            {
+                return Err(unknown_column("--where", &column));
+            }
+        }
+        query.push_str(&format!(" WHERE {predicate}"));
+    }
+
+    match &options.order_by {
+        Some(column) => {
+            if !columns.is_empty() && !is_known_column(columns, column) {
+                return Err(unknown_column("--order-by", column));
+            }
+            query.push_str(&format!(" ORDER BY {}", quote_ident(column)));
+            if options.desc {
+                query.push_str(" DESC");
+            }
+        }
+        None if !default_order_by.is_empty() => {
+            query.push(' ');
+            query.push_str(default_order_by);
+        }
+        None => {}
+    }
+
+    query.push_str(&format!(" LIMIT {}", options.limit));
+    Ok(query)
+}
+
+/// Whether `ident` names a column, either directly or as a nested/tuple path
+/// (`col.field`) rooted at one.
+fn is_known_column(columns: &[String], ident: &str) -> bool {
+    columns.iter().any(|c| c == ident)
+        || ident
+            .split_once('.')
+            .is_some_and(|(root, _)| columns.iter().any(|c| c == root))
+}
+
+/// Extracts the column references from a `--where` predicate, skipping string
+/// literals, numbers, function names and SQL keywords.
+fn predicate_identifiers(predicate: &str) -> Vec<String> {
+    let chars: Vec<char> = predicate.chars().collect();
+    let mut identifiers = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\'' {
+            i = read_quoted(&chars, i).0;
+        } else if c == '`' || c == '"' {
+            let (end, ident) = read_quoted(&chars, i);
+            identifiers.push(ident);
+            i = end;
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+            {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let is_call = chars[i..]
+                .iter()
+                .find(|c| !c.is_whitespace())
+                .is_some_and(|c| *c == '(');
+            let is_keyword = PREDICATE_KEYWORDS
+                .iter()
+                .any(|k| k.eq_ignore_ascii_case(&word));
+            if !is_call && !is_keyword {
+                identifiers.push(word);
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    identifiers
+}
+
+/// Reads a quoted token starting at `start`, honouring backslash escapes and
+/// doubled quotes. Returns the index just past the closing quote and the
+/// unquoted contents.
+fn read_quoted(chars: &[char], start: usize) -> (usize, String) {
+    let quote = chars[start];
+    let mut content = String::new();
+    let mut i = start + 1;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => {
+                if let Some(next) = chars.get(i + 1) {
+                    content.push(*next);
+                }
+                i += 2;
+            }
+            c if c == quote => {
+                if chars.get(i + 1) == Some(&quote) {
+                    content.push(quote);
+                    i += 2;
+                } else {
+                    return (i + 1, content);
+                }
+            }
+            c => {
+                content.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    (i, content)
+}
+
+/// Renders a single value as a CSV or table cell. Arrays, maps and JSON
+/// objects are emitted as compact JSON.
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Renders peeked rows in the requested format. When the resource declares no
+/// columns, the header is derived from the keys seen in the rows.
+fn render_rows(format: PeekFormat, columns: &[String], rows: &[Value]) -> anyhow::Result<String> {
+    if format == PeekFormat::Json {
+        let mut output = String::new();
+        for row in rows {
+            output.push_str(&serde_json::to_string(row)?);
+            output.push('\n');
+        }
+        return Ok(output);
+    }
+
+    let mut headers: Vec<String> = columns.to_vec();
+    if headers.is_empty() {
+        for key in rows
+            .iter()
+            .filter_map(Value::as_object)
+            .flat_map(|o| o.keys())
+        {
+            if !headers.contains(key) {
+                headers.push(key.clone());
+            }
+        }
+    }
+
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            headers
+                .iter()
+                .map(|h| row.get(h).map(cell_text).unwrap_or_default())
+                .collect()
+        })
+        .collect();
+
+    match format {
+        PeekFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(vec![]);
+            writer.write_record(&headers)?;
+            for row in &cells {
+                writer.write_record(row)?;
+            }
+            let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+            Ok(String::from_utf8_lossy(&bytes).into_owned())
+        }
+        _ => Ok(format!("{}\n", render_table(headers, cells))),
+    }
+}
+
+/// Whether `name` designates the resource `resource` of `database`, either
+/// plainly or qualified as `database.resource` (case-insensitive).
+fn names_resource(name: &str, resource: &str, database: Option<&str>, default_db: &str) -> bool {
+    name.eq_ignore_ascii_case(resource)
+        || name.split_once('.').is_some_and(|(db, rest)| {
+            db.eq_ignore_ascii_case(database.unwrap_or(default_db))
+                && rest.eq_ignore_ascii_case(resource)
+        })
+}
+
 /// Finds a table in the infrastructure map by name (case-insensitive).
 ///
 /// # Arguments
 ///
 /// * `infra` - The infrastructure map to search
-/// * `name` - The table name to find
+/// * `name` - The table name to find, optionally qualified by its database
 ///
 /// # Returns
 ///
 /// * `Option<&Table>` - The found table, or None if not found
 fn find_table_by_name<'a>(infra: &'a InfrastructureMap, name: &str) -> Option<&'a Table> {
-    infra
+    infra.tables.values().find(|table| {
+        names_resource(
+            name,
+            &table.name,
+            table.database.as_deref(),
+            &infra.default_database,
+        )
+    })
+}
+
+/// Resolves a peeked name to the table, view or materialized view it
+/// designates, failing when it designates several of them.
+///
+/// # Arguments
+///
+/// * `infra` - The infrastructure map to search
+/// * `name` - The name to resolve, optionally qualified by its database
+///
+/// # Returns
+///
+/// * `Result<Option<PeekTarget>, PeekQueryError>` - The resource, or None if
+///   nothing has that name
+fn resolve_peek_target<'a>(
+    infra: &'a InfrastructureMap,
+    name: &str,
+) -> Result<Option<PeekTarget<'a>>, PeekQueryError> {
+    let default_db = &infra.default_database;
+    let tables = infra
         .tables
         .values()
-        .find(|table| table.name.eq_ignore_ascii_case(name))
+        .filter(|t| names_resource(name, &t.name, t.database.as_deref(), default_db))
+        .map(PeekTarget::Table);
+    let views = infra
+        .views
+        .values()
+        .filter(|v| names_resource(name, &v.name, v.database.as_deref(), default_db))
+        .map(PeekTarget::View);
+    let materialized_views = infra
+        .materialized_views
+        .values()
+        .filter(|mv| names_resource(name, &mv.name, mv.database.as_deref(), default_db))
+        .map(PeekTarget::MaterializedView);
+
+    let mut candidates: Vec<PeekTarget> = tables.chain(views).chain(materialized_views).collect();
+    if candidates.len() > 1 {
+        let mut described: Vec<String> = candidates
+            .iter()
+            .map(|candidate| candidate.describe(default_db))
+            .collect();
+        described.sort();
+        return Err(PeekQueryError::AmbiguousName {
+            name: name.to_string(),
+            candidates: described.join(", "),
+        });
+    }
+    Ok(candidates.pop())
 }
 
 /// Finds a topic in the infrastructure map by name (case-insensitive).
@@ -313,11 +868,18 @@ fn find_topic_by_name<'a>(infra: &'a InfrastructureMap, name: &str) -> Option<&'
 
 #[cfg(test)]
 mod tests {
-    use super::{find_table_by_name, find_topic_by_name};
+    use super::{
+        build_follow_query, build_peek_query, find_table_by_name, find_topic_by_name,
+        predicate_identifiers, render_rows, resolve_peek_target, row_key, PeekFormat, PeekOptions,
+        PeekQueryError, PeekTarget,
+    };
+    use crate::framework::core::infrastructure::materialized_view::MaterializedView;
     use crate::framework::core::infrastructure::table::Table;
     use crate::framework::core::infrastructure::topic::Topic;
+    use crate::framework::core::infrastructure::view::View;
     use crate::framework::core::infrastructure_map::InfrastructureMap;
     use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+    use serde_json::json;
     use std::collections::HashMap;
     use std::time::Duration;
 
@@ -347,6 +909,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }
     }
 
@@ -421,6 +984,8 @@ mod tests {
             materialized_views: HashMap::new(),
             views: HashMap::new(),
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults: Default::default(),
         }
     }
 
@@ -535,4 +1100,222 @@ mod tests {
             "Should use default database when table.database is None"
         );
     }
+
+    #[test]
+    fn test_resolve_views_and_materialized_views() {
+        let mut infra = create_test_infra();
+        infra.views.insert(
+            "active_users".to_string(),
+            View::new(
+                "active_users",
+                "SELECT * FROM users",
+                vec!["users".to_string()],
+            ),
+        );
+        infra.materialized_views.insert(
+            "daily_orders_mv".to_string(),
+            MaterializedView::new(
+                "daily_orders_mv",
+                "SELECT toDate(at) AS day, count() AS orders FROM orders GROUP BY day",
+                vec!["orders".to_string()],
+                "daily_orders",
+            ),
+        );
+
+        assert!(matches!(
+            resolve_peek_target(&infra, "ACTIVE_USERS"),
+            Ok(Some(PeekTarget::View(view))) if view.name == "active_users"
+        ));
+        assert!(matches!(
+            resolve_peek_target(&infra, "daily_orders_mv"),
+            Ok(Some(PeekTarget::MaterializedView(mv))) if mv.target_table == "daily_orders"
+        ));
+        assert!(matches!(
+            resolve_peek_target(&infra, "local.orders"),
+            Ok(Some(PeekTarget::Table(table))) if table.name == "orders"
+        ));
+        assert!(matches!(
+            resolve_peek_target(&infra, "nonexistent"),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn test_name_collision_lists_candidates() {
+        let mut infra = create_test_infra();
+        let mut view = View::new("users", "SELECT * FROM local.users", vec![]);
+        view.database = Some("reporting".to_string());
+        infra.views.insert("reporting_users".to_string(), view);
+
+        assert_eq!(
+            resolve_peek_target(&infra, "users").unwrap_err(),
+            PeekQueryError::AmbiguousName {
+                name: "users".to_string(),
+                candidates: "table `local.users`, view `reporting.users`".to_string(),
+            }
+        );
+        assert!(matches!(
+            resolve_peek_target(&infra, "reporting.users"),
+            Ok(Some(PeekTarget::View(_)))
+        ));
+        assert!(matches!(
+            resolve_peek_target(&infra, "local.users"),
+            Ok(Some(PeekTarget::Table(_)))
+        ));
+    }
+
+    fn peek_columns() -> Vec<String> {
+        vec!["id".to_string(), "status".to_string(), "tags".to_string()]
+    }
+
+    #[test]
+    fn test_build_peek_query_defaults() {
+        let options = PeekOptions {
+            limit: 5,
+            ..Default::default()
+        };
+        let query = build_peek_query(
+            "local",
+            "events",
+            &peek_columns(),
+            "ORDER BY `id`",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            query,
+            "SELECT `id`, `status`, `tags` FROM `local`.`events` ORDER BY `id` LIMIT 5"
+        );
+    }
+
+    #[test]
+    fn test_build_peek_query_with_order_and_where() {
+        let options = PeekOptions {
+            limit: 20,
+            order_by: Some("status".to_string()),
+            desc: true,
+            where_clause: Some("status = 'failed' AND length(tags) > 0".to_string()),
+            format: PeekFormat::Csv,
+            follow: None,
+        };
+        let query = build_peek_query(
+            "local",
+            "events",
+            &peek_columns(),
+            "ORDER BY `id`",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            query,
+            "SELECT `id`, `status`, `tags` FROM `local`.`events` WHERE status = 'failed' AND length(tags) > 0 ORDER BY `status` DESC LIMIT 20"
+        );
+    }
+
+    #[test]
+    fn test_build_peek_query_rejects_unknown_columns() {
+        let options = PeekOptions {
+            order_by: Some("stauts".to_string()),
+            ..Default::default()
+        };
+        let err = build_peek_query("local", "events", &peek_columns(), "", &options).unwrap_err();
+        assert!(matches!(
+            err,
+            PeekQueryError::UnknownColumn { clause: "--order-by", ref column, .. } if column == "stauts"
+        ));
+
+        let options = PeekOptions {
+            where_clause: Some("statsu = 'ok'".to_string()),
+            ..Default::default()
+        };
+        let err = build_peek_query("local", "events", &peek_columns(), "", &options).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Available columns: id, status, tags"));
+    }
+
+    #[test]
+    fn test_build_peek_query_rejects_semicolons() {
+        let options = PeekOptions {
+            where_clause: Some("id = 1; DROP TABLE events".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            build_peek_query("local", "events", &peek_columns(), "", &options),
+            Err(PeekQueryError::SemicolonInPredicate)
+        );
+    }
+
+    #[test]
+    fn test_build_follow_query_orders_newest_keys_first() {
+        let options = PeekOptions {
+            limit: 10,
+            ..Default::default()
+        };
+        let key = vec!["id".to_string(), "status".to_string()];
+        let query = build_follow_query("local", "events", &peek_columns(), &key, &options).unwrap();
+        assert_eq!(
+            query,
+            "SELECT `id`, `status`, `tags` FROM `local`.`events` ORDER BY `id` DESC, `status` DESC LIMIT 10"
+        );
+
+        let err = build_follow_query(
+            "local",
+            "events",
+            &peek_columns(),
+            &["created".to_string()],
+            &options,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            PeekQueryError::UnknownColumn { ref column, .. } if column == "created"
+        ));
+
+        let row = json!({"id": 7, "status": "ok", "tags": []});
+        assert_eq!(row_key(&row, &key), "[7,\"ok\"]");
+    }
+
+    #[test]
+    fn test_predicate_identifiers_skip_literals_functions_and_keywords() {
+        let idents = predicate_identifiers(
+            "`status` IN ('a', 'it''s') AND toDate(created) >= today() - INTERVAL 1 DAY AND id > 1e3",
+        );
+        assert_eq!(idents, vec!["status", "created", "id"]);
+    }
+
+    #[test]
+    fn test_render_rows_complex_values_as_json() {
+        let rows = vec![
+            json!({"id": 1, "status": "ok, fine", "tags": ["a", "b"]}),
+            json!({"id": 2, "status": null, "tags": {"k": "v"}}),
+        ];
+
+        let csv = render_rows(PeekFormat::Csv, &peek_columns(), &rows).unwrap();
+        assert_eq!(
+            csv,
+            "id,status,tags\n1,\"ok, fine\",\"[\"\"a\"\",\"\"b\"\"]\"\n2,,\"{\"\"k\"\":\"\"v\"\"}\"\n"
+        );
+
+        let json_lines = render_rows(PeekFormat::Json, &peek_columns(), &rows).unwrap();
+        assert_eq!(
+            json_lines,
+            "{\"id\":1,\"status\":\"ok, fine\",\"tags\":[\"a\",\"b\"]}\n{\"id\":2,\"status\":null,\"tags\":{\"k\":\"v\"}}\n"
+        );
+
+        let table = render_rows(PeekFormat::Table, &[], &rows).unwrap();
+        assert!(table.contains("[\"a\",\"b\"]"));
+        assert!(table.contains("status"));
+    }
+
+    #[test]
+    fn test_peek_format_parsing() {
+        assert_eq!("CSV".parse::<PeekFormat>(), Ok(PeekFormat::Csv));
+        assert_eq!(
+            "xml".parse::<PeekFormat>(),
+            Err(PeekQueryError::UnknownFormat("xml".to_string()))
+        );
+    }
 }
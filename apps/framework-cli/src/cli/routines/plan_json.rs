@@ -0,0 +1,253 @@
+//! JSON document printed by `moose plan --json`.
+//!
+//! Next to the changes and the target infrastructure map, the document lists
+//! the OLAP operations of the plan in execution order, each flagged when it
+//! loses data, so that CI can reject a plan without parsing the text output.
+
+use serde::{Deserialize, Serialize};
+
+use crate::framework::core::destructive_changes::classify_operations;
+use crate::framework::core::infrastructure_map::{InfraChanges, InfrastructureMap};
+use crate::framework::core::plan::InfraPlan;
+use crate::infrastructure::olap::clickhouse::{describe_operation, SerializableOlapOperation};
+use crate::infrastructure::olap::ddl_ordering::{order_olap_changes, PlanOrderingError};
+
+/// Version of the `moose plan --json` document.
+///
+/// Bump this whenever a field is renamed, removed or changes type so that
+/// tooling consuming the output can detect the change.
+pub const PLAN_JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanDocument {
+    pub version: u32,
+    /// The OLAP operations of the plan, teardown first, in execution order
+    pub operations: Vec<PlannedOperation>,
+    pub changes: InfraChanges,
+    pub target_infra_map: InfrastructureMap,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedOperation {
+    /// Name of the `SerializableOlapOperation` variant, e.g. `DropTable`
+    pub kind: String,
+    /// The table, view or materialized view the operation applies to,
+    /// `None` for raw SQL
+    pub table: Option<String>,
+    /// `None` when the object is in the default database
+    pub database: Option<String>,
+    /// Whether the operation loses data: dropping a table, dropping it to
+    /// create it again or dropping a column
+    pub destructive: bool,
+    pub description: String,
+}
+
+impl PlanDocument {
+    pub fn new(plan: InfraPlan, default_database: &str) -> Result<Self, PlanOrderingError> {
+        let (teardown, setup) = order_olap_changes(&plan.changes.olap_changes, default_database)?;
+        let destructive = classify_operations(
+            &plan.changes.olap_changes,
+            teardown.iter().chain(&setup),
+            default_database,
+        );
+
+        let operations = teardown
+            .iter()
+            .chain(&setup)
+            .zip(destructive)
+            .map(|(operation, destructive)| {
+                let operation = operation.to_minimal();
+                let (table, database) = operation_target(&operation);
+                PlannedOperation {
+                    kind: operation_kind(&operation),
+                    table,
+                    database,
+                    destructive: destructive.is_some(),
+                    description: describe_operation(&operation),
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            version: PLAN_JSON_SCHEMA_VERSION,
+            operations,
+            changes: plan.changes,
+            target_infra_map: plan.target_infra_map,
+        })
+    }
+}
+
+/// The variant name, which serde uses as the tag of the operation
+fn operation_kind(operation: &SerializableOlapOperation) -> String {
+    match serde_json::to_value(operation) {
+        Ok(serde_json::Value::Object(tagged)) => tagged.keys().next().cloned().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+fn operation_target(operation: &SerializableOlapOperation) -> (Option<String>, Option<String>) {
+    use SerializableOlapOperation::*;
+
+    match operation {
+        CreateTable { table } => (Some(table.name.clone()), table.database.clone()),
+        DropTable {
+            table, database, ..
+        }
+        | RenameTable {
+            table, database, ..
+        }
+        | AddTableColumn {
+            table, database, ..
+        }
+        | DropTableColumn {
+            table, database, ..
+        }
+        | ModifyTableColumn {
+            table, database, ..
+        }
+        | RenameTableColumn {
+            table, database, ..
+        }
+        | ModifyTableSettings {
+            table, database, ..
+        }
+        | ModifyTableTtl {
+            table, database, ..
+        }
+        | AddTableIndex {
+            table, database, ..
+        }
+        | DropTableIndex {
+            table, database, ..
+        }
+        | AddTableProjection {
+            table, database, ..
+        }
+        | DropTableProjection {
+            table, database, ..
+        }
+        | ModifySampleBy {
+            table, database, ..
+        }
+        | RemoveSampleBy {
+            table, database, ..
+        }
+        | ModifyOrderBy {
+            table, database, ..
+        }
+        | VerifyCopy {
+            table, database, ..
+        } => (Some(table.clone()), database.clone()),
+        CreateMaterializedView { name, database, .. }
+        | DropMaterializedView { name, database }
+        | CreateView { name, database, .. }
+        | DropView { name, database } => (Some(name.clone()), database.clone()),
+        RawSql { .. } => (None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::table::{Column, ColumnType, OrderBy, Table};
+    use crate::framework::core::infrastructure_map::{
+        OlapChange, PrimitiveSignature, PrimitiveTypes, TableChange,
+    };
+    use crate::framework::core::partial_infrastructure_map::LifeCycle;
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+
+    const DB: &str = "local";
+
+    fn table(name: &str, order_by: &[&str]) -> Table {
+        Table {
+            name: name.to_string(),
+            columns: ["id", "at"]
+                .into_iter()
+                .map(|column| Column {
+                    name: column.to_string(),
+                    data_type: ColumnType::String,
+                    required: true,
+                    unique: false,
+                    primary_key: column == "id",
+                    default: None,
+                    annotations: vec![],
+                    comment: None,
+                    ttl: None,
+                    codec: None,
+                    materialized: None,
+                    alias: None,
+                })
+                .collect(),
+            order_by: OrderBy::Fields(order_by.iter().map(|field| field.to_string()).collect()),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_plan_document_round_trips_with_destructive_operations() {
+        // `logs` is dropped, `events` re-created for its new ORDER BY and
+        // `users` created
+        let plan = InfraPlan {
+            target_infra_map: InfrastructureMap {
+                default_database: DB.to_string(),
+                ..Default::default()
+            },
+            changes: InfraChanges {
+                olap_changes: vec![
+                    OlapChange::Table(TableChange::Removed(table("logs", &["id"]))),
+                    OlapChange::Table(TableChange::Removed(table("events", &["id"]))),
+                    OlapChange::Table(TableChange::Added(table("events", &["id", "at"]))),
+                    OlapChange::Table(TableChange::Added(table("users", &["id"]))),
+                ],
+                ..Default::default()
+            },
+        };
+
+        let document = PlanDocument::new(plan, DB).unwrap();
+        let json = serde_json::to_string_pretty(&document).unwrap();
+        let parsed: PlanDocument = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.version, PLAN_JSON_SCHEMA_VERSION);
+        assert_eq!(parsed.operations, document.operations);
+
+        let mut operations: Vec<_> = parsed
+            .operations
+            .iter()
+            .map(|o| (o.kind.as_str(), o.table.as_deref().unwrap(), o.destructive))
+            .collect();
+        operations.sort();
+        assert_eq!(
+            operations,
+            vec![
+                ("CreateTable", "events", false),
+                ("CreateTable", "users", false),
+                ("DropTable", "events", true),
+                ("DropTable", "logs", true),
+            ]
+        );
+        assert!(parsed
+            .operations
+            .iter()
+            .all(|o| o.database.is_none() && !o.description.is_empty()));
+    }
+}
@@ -0,0 +1,541 @@
+//! Preflight diagnosis for `moose check preflight`.
+//!
+//! Validates the project configuration file, probes every enabled backing
+//! service with a timeout, verifies the state storage backend and validates
+//! the current plan without executing it. Each step yields a pass/warn/fail
+//! line so the whole report can be rendered as a table or JSON document.
+
+use std::future::Future;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::{RoutineFailure, RoutineSuccess};
+use crate::cli::display::{show_table, Message};
+use crate::cli::exit_code::CliExitCode;
+use crate::framework::core::plan::plan_changes;
+use crate::framework::core::plan_validator;
+use crate::framework::core::state_storage::{StateStorage, StateStorageBuilder};
+use crate::infrastructure::olap::clickhouse::writability::check_writable;
+use crate::infrastructure::olap::clickhouse::{check_ready, create_client};
+use crate::infrastructure::orchestration::temporal_client::{
+    probe_temporal_namespace, TemporalClientManager,
+};
+use crate::infrastructure::redis::connection_info::RedisConnectionError;
+use crate::infrastructure::stream::kafka::client::fetch_topics;
+use crate::project::Project;
+use crate::utilities::constants::{OLD_PROJECT_CONFIG_FILE, PROJECT_CONFIG_FILE};
+
+/// Version of the `moose check preflight --json` document. Bump when the shape changes.
+pub const PREFLIGHT_JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl std::fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckStatus::Pass => write!(f, "pass"),
+            CheckStatus::Warn => write!(f, "warn"),
+            CheckStatus::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// Outcome of a single preflight step.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+impl CheckResult {
+    fn new(name: &str, status: CheckStatus, detail: impl Into<String>, elapsed: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+            duration_ms: elapsed.as_millis() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreflightReport {
+    pub version: u32,
+    pub checks: Vec<CheckResult>,
+}
+
+impl PreflightReport {
+    pub fn has_failures(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Fail)
+    }
+
+    /// Config and plan failures are validation failures; any other failed
+    /// check means a service could not be reached.
+    pub fn exit_code(&self) -> CliExitCode {
+        let failed = |name: &str| {
+            self.checks
+                .iter()
+                .any(|c| c.name == name && c.status == CheckStatus::Fail)
+        };
+        if failed("config") || failed("plan") {
+            CliExitCode::ValidationFailure
+        } else if self.has_failures() {
+            CliExitCode::ConnectivityFailure
+        } else {
+            CliExitCode::Success
+        }
+    }
+
+    fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+}
+
+/// Validates the raw project config file against the `Project` schema, without
+/// environment variable overrides, reporting the location of the first error.
+pub fn validate_config_contents(file_name: &str, content: &str) -> CheckResult {
+    let start = Instant::now();
+    match toml::from_str::<Project>(content) {
+        Ok(_) => CheckResult::new("config", CheckStatus::Pass, file_name, start.elapsed()),
+        Err(e) => {
+            let location = e
+                .line_col()
+                .map(|(line, col)| format!("{file_name}:{}:{}", line + 1, col + 1))
+                .unwrap_or_else(|| file_name.to_string());
+            // toml appends its own "at line X column Y" suffix; keep only the message
+            let message = e.to_string();
+            let message = message
+                .split(" at line ")
+                .next()
+                .unwrap_or(&message)
+                .to_string();
+            CheckResult::new(
+                "config",
+                CheckStatus::Fail,
+                format!("{location}: {message}"),
+                start.elapsed(),
+            )
+        }
+    }
+}
+
+fn validate_config_file(project_dir: &Path) -> CheckResult {
+    let file_name = if project_dir.join(PROJECT_CONFIG_FILE).exists() {
+        PROJECT_CONFIG_FILE
+    } else {
+        OLD_PROJECT_CONFIG_FILE
+    };
+
+    match std::fs::read_to_string(project_dir.join(file_name)) {
+        Ok(content) => validate_config_contents(file_name, &content),
+        Err(e) => CheckResult::new(
+            "config",
+            CheckStatus::Fail,
+            format!("{file_name}: {e}"),
+            Duration::ZERO,
+        ),
+    }
+}
+
+/// Runs a probe under `timeout`. Probes that succeed but take more than half
+/// the timeout are reported as warnings.
+async fn probe<F>(name: &str, timeout: Duration, fut: F) -> CheckResult
+where
+    F: Future<Output = anyhow::Result<String>>,
+{
+    let start = Instant::now();
+    match tokio::time::timeout(timeout, fut).await {
+        Ok(Ok(detail)) if start.elapsed() > timeout / 2 => CheckResult::new(
+            name,
+            CheckStatus::Warn,
+            format!("{detail} (slow response)"),
+            start.elapsed(),
+        ),
+        Ok(Ok(detail)) => CheckResult::new(name, CheckStatus::Pass, detail, start.elapsed()),
+        Ok(Err(e)) => CheckResult::new(name, CheckStatus::Fail, format!("{e:#}"), start.elapsed()),
+        Err(_) => CheckResult::new(
+            name,
+            CheckStatus::Fail,
+            format!("timed out after {}s", timeout.as_secs()),
+            start.elapsed(),
+        ),
+    }
+}
+
+/// Runs every preflight step for `project`.
+pub async fn run_preflight(project: &Project, timeout: Duration) -> PreflightReport {
+    let mut checks = vec![validate_config_file(&project.project_location)];
+
+    let clickhouse = if project.features.olap {
+        let result = probe("clickhouse", timeout, async {
+            let client = create_client(project.clickhouse_config.clone());
+            check_ready(&client).await?;
+            anyhow::Ok(format!(
+                "{}:{}",
+                project.clickhouse_config.host, project.clickhouse_config.host_port
+            ))
+        })
+        .await;
+        let ok = result.status != CheckStatus::Fail;
+        checks.push(result);
+        Some(ok)
+    } else {
+        None
+    };
+
+    checks.push(
+        probe("redis", timeout, async {
+            let redis_config = &project.redis_config;
+            let client = redis_config.open_client().await?;
+            let mut conn = client
+                .get_multiplexed_async_connection()
+                .await
+                .map_err(|e| RedisConnectionError::new(redis_config.target_description(), e))?;
+            redis::cmd("PING").query_async::<String>(&mut conn).await?;
+            anyhow::Ok("responded to PING".to_string())
+        })
+        .await,
+    );
+
+    if project.features.streaming_engine {
+        checks.push(
+            probe("kafka", timeout, async {
+                let topics = fetch_topics(&project.redpanda_config).await?;
+                anyhow::Ok(format!(
+                    "{} ({} topics)",
+                    project.redpanda_config.broker,
+                    topics.len()
+                ))
+            })
+            .await,
+        );
+    }
+
+    if project.features.workflows {
+        checks.push(
+            probe("temporal", timeout, async {
+                let manager = TemporalClientManager::new_validate(&project.temporal_config, true)?;
+                let namespace = project.temporal_config.get_temporal_namespace();
+                probe_temporal_namespace(&manager, namespace.clone()).await?;
+                anyhow::Ok(format!("namespace '{namespace}'"))
+            })
+            .await,
+        );
+    }
+
+    let storage_start = Instant::now();
+    let state_storage = tokio::time::timeout(
+        timeout,
+        StateStorageBuilder::from_config(project)
+            .clickhouse_config(Some(project.clickhouse_config.clone()))
+            .redis_url(Some(project.redis_config.effective_url()))
+            .build(),
+    )
+    .await;
+    let state_storage = match state_storage {
+        Ok(Ok(storage)) => match storage.load_infrastructure_map().await {
+            Ok(_) => {
+                checks.push(CheckResult::new(
+                    "state storage",
+                    CheckStatus::Pass,
+                    project.state_config.storage.clone(),
+                    storage_start.elapsed(),
+                ));
+                Some(storage)
+            }
+            Err(e) => {
+                checks.push(CheckResult::new(
+                    "state storage",
+                    CheckStatus::Fail,
+                    format!("{}: {e:#}", project.state_config.storage),
+                    storage_start.elapsed(),
+                ));
+                None
+            }
+        },
+        Ok(Err(e)) => {
+            checks.push(CheckResult::new(
+                "state storage",
+                CheckStatus::Fail,
+                format!("{}: {e:#}", project.state_config.storage),
+                storage_start.elapsed(),
+            ));
+            None
+        }
+        Err(_) => {
+            checks.push(CheckResult::new(
+                "state storage",
+                CheckStatus::Fail,
+                format!("timed out after {}s", timeout.as_secs()),
+                storage_start.elapsed(),
+            ));
+            None
+        }
+    };
+
+    let plan_start = Instant::now();
+    // Tables of the target state, whose replicas must accept DDL
+    let mut target_tables = vec![];
+    match (state_storage, clickhouse) {
+        (Some(storage), Some(true) | None) => {
+            let result = match plan_changes(&*storage, project).await {
+                Ok((_, plan)) => match plan_validator::validate(project, &plan) {
+                    Ok(()) => {
+                        target_tables = plan
+                            .target_infra_map
+                            .tables
+                            .values()
+                            .map(|table| {
+                                (
+                                    table.database.clone().unwrap_or_else(|| {
+                                        project.clickhouse_config.db_name.clone()
+                                    }),
+                                    table.name.clone(),
+                                )
+                            })
+                            .collect();
+                        CheckResult::new(
+                            "plan",
+                            CheckStatus::Pass,
+                            "current plan is valid",
+                            plan_start.elapsed(),
+                        )
+                    }
+                    Err(e) => CheckResult::new(
+                        "plan",
+                        CheckStatus::Fail,
+                        e.to_string(),
+                        plan_start.elapsed(),
+                    ),
+                },
+                Err(e) => CheckResult::new(
+                    "plan",
+                    CheckStatus::Fail,
+                    format!("could not compute plan: {e}"),
+                    plan_start.elapsed(),
+                ),
+            };
+            checks.push(result);
+        }
+        _ => checks.push(CheckResult::new(
+            "plan",
+            CheckStatus::Warn,
+            "skipped: requires reachable ClickHouse and state storage",
+            Duration::ZERO,
+        )),
+    }
+
+    if clickhouse == Some(true) {
+        checks.push(
+            probe("clickhouse writable", timeout, async {
+                let client = create_client(project.clickhouse_config.clone());
+                match check_writable(&client, &target_tables).await? {
+                    None => anyhow::Ok("accepts DDL".to_string()),
+                    Some(reason) => Err(anyhow::anyhow!(
+                        "{reason}: plans would fail until it accepts DDL"
+                    )),
+                }
+            })
+            .await,
+        );
+    }
+
+    PreflightReport {
+        version: PREFLIGHT_JSON_SCHEMA_VERSION,
+        checks,
+    }
+}
+
+/// Runs the preflight checks and prints them as a table or JSON document.
+/// Returns a failure when any check failed so the process exits non-zero.
+pub async fn preflight(
+    project: &Project,
+    timeout: Duration,
+    json: bool,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let report = run_preflight(project, timeout).await;
+    render_report(&report, json)
+}
+
+/// Reports on the config file when the project itself failed to load. If the
+/// config file is valid the original load error is returned unchanged.
+pub fn preflight_without_project(
+    project_dir: &Path,
+    json: bool,
+    load_error: RoutineFailure,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let config = validate_config_file(project_dir);
+    if config.status != CheckStatus::Fail {
+        return Err(load_error);
+    }
+
+    let report = PreflightReport {
+        version: PREFLIGHT_JSON_SCHEMA_VERSION,
+        checks: vec![config],
+    };
+    render_report(&report, json)
+}
+
+fn render_report(report: &PreflightReport, json: bool) -> Result<RoutineSuccess, RoutineFailure> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+    } else {
+        show_table(
+            "Preflight".to_string(),
+            vec![
+                "Check".to_string(),
+                "Status".to_string(),
+                "Detail".to_string(),
+                "Time".to_string(),
+            ],
+            report
+                .checks
+                .iter()
+                .map(|c| {
+                    vec![
+                        c.name.clone(),
+                        c.status.to_string(),
+                        c.detail.clone(),
+                        format!("{}ms", c.duration_ms),
+                    ]
+                })
+                .collect(),
+        );
+    }
+
+    let summary = format!(
+        "{} passed, {} warnings, {} failed",
+        report.count(CheckStatus::Pass),
+        report.count(CheckStatus::Warn),
+        report.count(CheckStatus::Fail)
+    );
+
+    if report.has_failures() {
+        Err(
+            RoutineFailure::error(Message::new("Preflight".to_string(), summary))
+                .with_exit_code(report.exit_code()),
+        )
+    } else {
+        Ok(RoutineSuccess::success(Message::new(
+            "Preflight".to_string(),
+            summary,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_config_contents_reports_location() {
+        let content =
+            "language = \"Typescript\"\n\n[clickhouse_config]\nhost_port = \"not-a-port\"\n";
+        let result = validate_config_contents("moose.config.toml", content);
+
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(
+            result.detail.starts_with("moose.config.toml:"),
+            "unexpected detail: {}",
+            result.detail
+        );
+        assert!(!result.detail.contains(" at line "));
+    }
+
+    #[test]
+    fn test_validate_config_contents_rejects_syntax_errors() {
+        let result = validate_config_contents("moose.config.toml", "language = \n");
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.detail.starts_with("moose.config.toml:1:"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_statuses() {
+        let timeout = Duration::from_millis(200);
+
+        let pass = probe("ok", timeout, async { anyhow::Ok("fine".to_string()) }).await;
+        assert_eq!(pass.status, CheckStatus::Pass);
+
+        let fail = probe("err", timeout, async {
+            Err::<String, _>(anyhow::anyhow!("refused"))
+        })
+        .await;
+        assert_eq!(fail.status, CheckStatus::Fail);
+        assert_eq!(fail.detail, "refused");
+
+        let slow = probe("slow", timeout, async {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            anyhow::Ok("eventually".to_string())
+        })
+        .await;
+        assert_eq!(slow.status, CheckStatus::Warn);
+
+        let hung = probe("hung", timeout, async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            anyhow::Ok(String::new())
+        })
+        .await;
+        assert_eq!(hung.status, CheckStatus::Fail);
+        assert!(hung.detail.starts_with("timed out"));
+    }
+
+    #[test]
+    fn test_report_serialization() {
+        let report = PreflightReport {
+            version: PREFLIGHT_JSON_SCHEMA_VERSION,
+            checks: vec![CheckResult::new(
+                "redis",
+                CheckStatus::Warn,
+                "responded to PING (slow response)",
+                Duration::from_millis(12),
+            )],
+        };
+        assert!(!report.has_failures());
+        assert_eq!(report.exit_code(), CliExitCode::Success);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "version": 1,
+                "checks": [{
+                    "name": "redis",
+                    "status": "warn",
+                    "detail": "responded to PING (slow response)",
+                    "duration_ms": 12
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_report_exit_code_by_failed_check() {
+        let check = |name: &str, status| CheckResult::new(name, status, "", Duration::ZERO);
+
+        let connectivity = PreflightReport {
+            version: PREFLIGHT_JSON_SCHEMA_VERSION,
+            checks: vec![
+                check("config", CheckStatus::Pass),
+                check("clickhouse", CheckStatus::Fail),
+            ],
+        };
+        assert_eq!(connectivity.exit_code(), CliExitCode::ConnectivityFailure);
+
+        let validation = PreflightReport {
+            version: PREFLIGHT_JSON_SCHEMA_VERSION,
+            checks: vec![
+                check("redis", CheckStatus::Fail),
+                check("plan", CheckStatus::Fail),
+            ],
+        };
+        assert_eq!(validation.exit_code(), CliExitCode::ValidationFailure);
+    }
+}
@@ -3,22 +3,103 @@ use std::{
     sync::Arc,
 };
 
+use serde::Serialize;
 use tracing::error;
 
 use crate::{
     cli::display::{show_table, Message},
+    cli::exit_code::CliExitCode,
     project::Project,
+    utilities::docker::{docker_socket_path, ContainerInspect, DockerClient},
 };
 
 use super::{RoutineFailure, RoutineSuccess};
 
+/// Version of the `moose ps --json` document.
+///
+/// Bump this whenever a field is renamed, removed or changes type so that
+/// tooling consuming the output can detect the change.
+pub const PS_JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
 pub struct MooseProcess {
     pub name: String,
     pub pid: u32,
     pub port: u16,
+    #[serde(skip)]
     pub config: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PortMapping {
+    pub host_ip: String,
+    pub host_port: u16,
+    pub container_port: u16,
+    pub protocol: String,
+}
+
+impl std::fmt::Display for PortMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}->{}/{}",
+            self.host_ip, self.host_port, self.container_port, self.protocol
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContainerStatus {
+    pub name: String,
+    pub image: String,
+    pub state: String,
+    /// Health-check status, `None` when the container defines no health check
+    pub health: Option<String>,
+    pub restart_count: u32,
+    pub ports: Vec<PortMapping>,
+}
+
+impl From<ContainerInspect> for ContainerStatus {
+    fn from(inspect: ContainerInspect) -> Self {
+        let mut ports: Vec<PortMapping> = inspect
+            .network_settings
+            .ports
+            .iter()
+            .flat_map(|(container_port, bindings)| {
+                let (port, protocol) = container_port
+                    .split_once('/')
+                    .unwrap_or((container_port.as_str(), "tcp"));
+                let container_port = port.parse::<u16>().ok();
+                bindings.iter().flatten().filter_map(move |binding| {
+                    Some(PortMapping {
+                        host_ip: binding.host_ip.clone(),
+                        host_port: binding.host_port.parse().ok()?,
+                        container_port: container_port?,
+                        protocol: protocol.to_string(),
+                    })
+                })
+            })
+            .collect();
+        ports.sort_by(|a, b| (a.container_port, &a.host_ip).cmp(&(b.container_port, &b.host_ip)));
+
+        Self {
+            name: inspect.name.trim_start_matches('/').to_string(),
+            image: inspect.config.image,
+            state: inspect.state.status,
+            health: inspect.state.health.map(|h| h.status),
+            restart_count: inspect.restart_count,
+            ports,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PsListing<'a> {
+    version: u32,
+    processes: &'a [MooseProcess],
+    containers: &'a [ContainerStatus],
+}
+
 impl MooseProcess {
     pub fn new(name: String, pid: u32, port: u16, config: String) -> Self {
         Self {
@@ -30,18 +111,39 @@ impl MooseProcess {
     }
 }
 
-pub fn show_processes(project: Arc<Project>) -> Result<RoutineSuccess, RoutineFailure> {
-    let processes = vec![
+pub fn show_processes(
+    project: Arc<Project>,
+    docker_client: &DockerClient,
+    json: bool,
+    all: bool,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let processes: Vec<MooseProcess> = [
         get_webserver_process(&project),
         get_clickhouse_process(&project),
         get_redpanda_process(&project),
-    ];
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let containers = get_containers(&project, docker_client, all)?;
+
+    if json {
+        let listing = PsListing {
+            version: PS_JSON_SCHEMA_VERSION,
+            processes: &processes,
+            containers: &containers,
+        };
+        println!("{}", serde_json::to_string_pretty(&listing).unwrap());
+        return Ok(RoutineSuccess::success(Message::new(
+            "".to_string(),
+            "".to_string(),
+        )));
+    }
 
     let data: Vec<Vec<String>> = processes
         .into_iter()
-        .filter_map(|process| {
-            process.map(|p| vec![p.name, p.pid.to_string(), p.port.to_string(), p.config])
-        })
+        .map(|p| vec![p.name, p.pid.to_string(), p.port.to_string(), p.config])
         .collect();
 
     show_table(
@@ -55,12 +157,99 @@ pub fn show_processes(project: Arc<Project>) -> Result<RoutineSuccess, RoutineFa
         data,
     );
 
+    if !containers.is_empty() {
+        show_table(
+            "Containers".to_string(),
+            vec![
+                "Name".to_string(),
+                "Image".to_string(),
+                "State".to_string(),
+                "Health".to_string(),
+                "Restarts".to_string(),
+                "Ports".to_string(),
+            ],
+            containers
+                .into_iter()
+                .map(|c| {
+                    vec![
+                        c.name,
+                        c.image,
+                        c.state,
+                        c.health.unwrap_or_else(|| "-".to_string()),
+                        c.restart_count.to_string(),
+                        c.ports
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ]
+                })
+                .collect(),
+        );
+    }
+
     Ok(RoutineSuccess::success(Message::new(
         "".to_string(),
         "".to_string(),
     )))
 }
 
+/// Gathers the status of this project's containers. Stopped containers are
+/// only included with `all`.
+fn get_containers(
+    project: &Project,
+    docker_client: &DockerClient,
+    all: bool,
+) -> Result<Vec<ContainerStatus>, RoutineFailure> {
+    docker_client.check_status().map_err(|e| {
+        RoutineFailure::new(
+            Message::new(
+                "Docker".to_string(),
+                format!(
+                    "is not reachable at {}. Make sure Docker is running",
+                    docker_socket_path()
+                ),
+            ),
+            e,
+        )
+        .with_exit_code(CliExitCode::ConnectivityFailure)
+    })?;
+
+    // No compose file means the dev infrastructure has never been started
+    let compose_file_exists = project
+        .internal_dir()
+        .map(|dir| dir.join("docker-compose.yml").exists())
+        .unwrap_or(false);
+    if !compose_file_exists {
+        return Ok(Vec::new());
+    }
+
+    let names: Vec<String> = docker_client
+        .list_containers(project)
+        .map_err(|e| {
+            RoutineFailure::new(
+                Message::new("Failed".to_string(), "to list containers".to_string()),
+                e,
+            )
+        })?
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+
+    let inspected = docker_client.inspect_containers(&names).map_err(|e| {
+        RoutineFailure::new(
+            Message::new("Failed".to_string(), "to inspect containers".to_string()),
+            e,
+        )
+    })?;
+
+    Ok(inspected
+        .into_iter()
+        .map(ContainerStatus::from)
+        .filter(|c| all || c.state == "running")
+        .collect())
+}
+
 fn get_webserver_process(project: &Arc<Project>) -> Option<MooseProcess> {
     get_process_by_port(project.http_server_config.port, "moose", None, "N/A")
 }
@@ -166,3 +355,122 @@ fn get_process_by_port(
         config.to_string(),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSPECT_FIXTURE: &str = r#"[
+        {
+            "Id": "3f1c",
+            "Name": "/my-app-clickhousedb-1",
+            "RestartCount": 2,
+            "State": {
+                "Status": "running",
+                "Running": true,
+                "Health": { "Status": "healthy", "FailingStreak": 0, "Log": [] }
+            },
+            "Config": { "Image": "docker.io/clickhouse/clickhouse-server:25.6" },
+            "NetworkSettings": {
+                "Ports": {
+                    "9000/tcp": null,
+                    "8123/tcp": [
+                        { "HostIp": "0.0.0.0", "HostPort": "18123" },
+                        { "HostIp": "::", "HostPort": "18123" }
+                    ]
+                }
+            }
+        },
+        {
+            "Id": "9a2b",
+            "Name": "/my-app-redis-1",
+            "RestartCount": 0,
+            "State": { "Status": "exited", "Running": false },
+            "Config": { "Image": "redis:latest" },
+            "NetworkSettings": { "Ports": {} }
+        }
+    ]"#;
+
+    fn fixture_containers() -> Vec<ContainerStatus> {
+        serde_json::from_str::<Vec<ContainerInspect>>(INSPECT_FIXTURE)
+            .unwrap()
+            .into_iter()
+            .map(ContainerStatus::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_container_status_from_inspect() {
+        let containers = fixture_containers();
+
+        assert_eq!(
+            containers[0],
+            ContainerStatus {
+                name: "my-app-clickhousedb-1".to_string(),
+                image: "docker.io/clickhouse/clickhouse-server:25.6".to_string(),
+                state: "running".to_string(),
+                health: Some("healthy".to_string()),
+                restart_count: 2,
+                ports: vec![
+                    PortMapping {
+                        host_ip: "0.0.0.0".to_string(),
+                        host_port: 18123,
+                        container_port: 8123,
+                        protocol: "tcp".to_string(),
+                    },
+                    PortMapping {
+                        host_ip: "::".to_string(),
+                        host_port: 18123,
+                        container_port: 8123,
+                        protocol: "tcp".to_string(),
+                    },
+                ],
+            }
+        );
+        assert_eq!(
+            containers[0].ports[0].to_string(),
+            "0.0.0.0:18123->8123/tcp"
+        );
+
+        assert_eq!(containers[1].state, "exited");
+        assert_eq!(containers[1].health, None);
+        assert!(containers[1].ports.is_empty());
+    }
+
+    /// Snapshot of the `moose ps --json` shape. If this fails, the output
+    /// consumed by external tooling changed: update the snapshot and bump
+    /// `PS_JSON_SCHEMA_VERSION` if a field was renamed, removed or retyped.
+    #[test]
+    fn test_ps_json_schema_snapshot() {
+        let processes = vec![MooseProcess::new(
+            "moose".to_string(),
+            4242,
+            4000,
+            "secret config".to_string(),
+        )];
+        let containers = fixture_containers();
+
+        let output = serde_json::to_value(PsListing {
+            version: PS_JSON_SCHEMA_VERSION,
+            processes: &processes,
+            containers: &containers[1..],
+        })
+        .unwrap();
+
+        assert_eq!(
+            output,
+            serde_json::json!({
+                "version": 1,
+                "processes": [{ "name": "moose", "pid": 4242, "port": 4000 }],
+                "containers": [{
+                    "name": "my-app-redis-1",
+                    "image": "redis:latest",
+                    "state": "exited",
+                    "health": null,
+                    "restart_count": 0,
+                    "ports": []
+                }]
+            })
+        );
+    }
+}
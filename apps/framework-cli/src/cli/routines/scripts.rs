@@ -1,11 +1,15 @@
 use anyhow::Result;
 use std::convert::TryFrom;
+use std::time::Duration;
 
-use crate::cli::display::{show_table, Message};
+use crate::cli::display::{show_message_wrapper, show_table, Message, MessageType};
+use crate::cli::exit_code::CliExitCode;
 use crate::cli::routines::{RoutineFailure, RoutineSuccess};
 use crate::framework::core::infrastructure_map::InfrastructureMap;
 use crate::framework::scripts::executor::WorkflowStartInfo;
-use crate::infrastructure::orchestration::temporal_client::TemporalClientManager;
+use crate::infrastructure::orchestration::temporal_client::{
+    TemporalClientManager, TemporalWorkflowService, WorkflowService, WorkflowServiceError,
+};
 use crate::project::Project;
 use crate::utilities::decode_object::decode_base64_to_json;
 use chrono::{DateTime, Utc};
@@ -13,10 +17,12 @@ use temporal_sdk_core_protos::temporal::api::common::v1::WorkflowExecution;
 use temporal_sdk_core_protos::temporal::api::enums::v1::WorkflowExecutionStatus;
 use temporal_sdk_core_protos::temporal::api::workflowservice::v1::{
     DescribeWorkflowExecutionRequest, GetWorkflowExecutionHistoryRequest,
-    ListWorkflowExecutionsRequest, RequestCancelWorkflowExecutionRequest,
-    SignalWorkflowExecutionRequest, TerminateWorkflowExecutionRequest,
+    ListWorkflowExecutionsRequest, SignalWorkflowExecutionRequest,
 };
 
+/// How often `moose workflow run --follow` polls Temporal for status changes.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct WorkflowInfo {
     pub name: String,
@@ -103,6 +109,8 @@ pub async fn run_workflow(
     project: &Project,
     name: &str,
     input: Option<String>,
+    follow: bool,
+    json: bool,
 ) -> Result<RoutineSuccess, RoutineFailure> {
     let namespace = project.temporal_config.get_temporal_namespace();
 
@@ -110,12 +118,82 @@ pub async fn run_workflow(
 
     let dashboard_url = temporal_dashboard_url(&namespace, &info.workflow_id, &info.run_id);
 
-    Ok(RoutineSuccess::success(Message {
-        action: "Workflow".to_string(),
-        details: format!(
-            "'{name}' started successfully.\nView it in the Temporal dashboard: {dashboard_url}\n",
-        ),
-    }))
+    let final_status = if follow {
+        if !json {
+            show_message_wrapper(
+                MessageType::Info,
+                Message {
+                    action: "Workflow".to_string(),
+                    details: format!(
+                        "'{name}' started (workflow id: {}, run id: {})",
+                        info.workflow_id, info.run_id
+                    ),
+                },
+            );
+        }
+        let service = temporal_service(project)?;
+        let status = follow_workflow(
+            &service,
+            &info.workflow_id,
+            &info.run_id,
+            FOLLOW_POLL_INTERVAL,
+            |status| {
+                if !json {
+                    show_message_wrapper(
+                        MessageType::Info,
+                        Message {
+                            action: "Workflow".to_string(),
+                            details: format!("'{name}' is {}", status.as_str_name()),
+                        },
+                    );
+                }
+            },
+        )
+        .await
+        .map_err(workflow_failure)?;
+        Some(status)
+    } else {
+        None
+    };
+
+    if json {
+        let mut output = serde_json::json!({
+            "workflowId": info.workflow_id,
+            "runId": info.run_id,
+            "dashboardUrl": dashboard_url,
+        });
+        if let Some(status) = final_status {
+            output["status"] = serde_json::json!(status.as_str_name());
+        }
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    }
+
+    match final_status {
+        None if json => Ok(RoutineSuccess::success(Message::new(
+            "".to_string(),
+            "".to_string(),
+        ))),
+        None => Ok(RoutineSuccess::success(Message {
+            action: "Workflow".to_string(),
+            details: format!(
+                "'{name}' started successfully (workflow id: {}, run id: {}).\nView it in the Temporal dashboard: {dashboard_url}\n",
+                info.workflow_id, info.run_id
+            ),
+        })),
+        Some(WorkflowExecutionStatus::Completed) => Ok(RoutineSuccess::success(Message {
+            action: "Workflow".to_string(),
+            details: format!("'{name}' completed successfully\n"),
+        })),
+        Some(status) => Err(RoutineFailure::error(Message {
+            action: "Workflow".to_string(),
+            details: format!(
+                "'{name}' finished with status {}.\nSee `moose workflow status {} --id {} --verbose` or {dashboard_url}\n",
+                status.as_str_name(),
+                info.workflow_id,
+                info.run_id
+            ),
+        })),
+    }
 }
 
 pub async fn run_workflow_and_get_run_ids(
@@ -278,57 +356,50 @@ pub async fn list_workflows_history(
     )))
 }
 
+/// Maps a workflow service error to a failure, flagging Temporal outages as
+/// connectivity failures so scripts can tell them apart from bad ids.
+fn workflow_failure(error: WorkflowServiceError) -> RoutineFailure {
+    let exit_code = match error {
+        WorkflowServiceError::Unavailable(_) | WorkflowServiceError::Timeout(_) => {
+            CliExitCode::ConnectivityFailure
+        }
+        WorkflowServiceError::PermissionDenied(_) => CliExitCode::AuthFailure,
+        _ => CliExitCode::Error,
+    };
+    RoutineFailure::error(Message {
+        action: "Workflow".to_string(),
+        details: format!("{error}\n"),
+    })
+    .with_exit_code(exit_code)
+}
+
+fn temporal_service(project: &Project) -> Result<TemporalWorkflowService, RoutineFailure> {
+    TemporalWorkflowService::new(&project.temporal_config).map_err(|e| {
+        RoutineFailure::error(Message {
+            action: "Temporal".to_string(),
+            details: format!("Failed to create client manager: {e}"),
+        })
+    })
+}
+
 // Terminate is a hard stop. Temporal updates its server states
-// but does not notify workers. Command is hidden for now because we probably
-// want to use cancel instead, with a flag to force kill.
+// but does not notify workers. Prefer cancel unless the workflow is stuck.
 pub async fn terminate_workflow(
     project: &Project,
     name: &str,
 ) -> Result<RoutineSuccess, RoutineFailure> {
-    let client_manager = TemporalClientManager::new_validate(&project.temporal_config, true)
-        .map_err(|e| {
-            RoutineFailure::error(Message {
-                action: "Temporal".to_string(),
-                details: format!("Failed to create client manager: {e}"),
-            })
-        })?;
-    let namespace = project.temporal_config.get_temporal_namespace();
-
-    let request = TerminateWorkflowExecutionRequest {
-        namespace,
-        workflow_execution: Some(
-            temporal_sdk_core_protos::temporal::api::common::v1::WorkflowExecution {
-                workflow_id: name.to_string(),
-                run_id: "".to_string(),
-            },
-        ),
-        reason: "Terminated by user request".to_string(),
-        ..Default::default()
-    };
+    let service = temporal_service(project)?;
+    terminate_workflow_with(&service, name).await
+}
 
-    client_manager
-        .execute(|mut client| async move {
-            client
-                .terminate_workflow_execution(request)
-                .await
-                .map_err(|e| anyhow::Error::msg(e.to_string()))
-        })
+async fn terminate_workflow_with(
+    service: &dyn WorkflowService,
+    name: &str,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    service
+        .terminate(name, "")
         .await
-        .map_err(|e| {
-            let error_message = if e
-                .to_string()
-                .contains("workflow execution already completed")
-            {
-                format!("Workflow '{name}' has already completed")
-            } else {
-                format!("Could not terminate workflow '{name}': {e}")
-            };
-
-            RoutineFailure::error(Message {
-                action: "Workflow".to_string(),
-                details: format!("{error_message}\n"),
-            })
-        })?;
+        .map_err(workflow_failure)?;
 
     Ok(RoutineSuccess::success(Message {
         action: "Workflow".to_string(),
@@ -341,48 +412,18 @@ pub async fn cancel_workflow(
     project: &Project,
     name: &str,
 ) -> Result<RoutineSuccess, RoutineFailure> {
-    let client_manager = TemporalClientManager::new_validate(&project.temporal_config, true)
-        .map_err(|e| {
-            RoutineFailure::error(Message {
-                action: "Temporal".to_string(),
-                details: format!("Failed to create client manager: {e}"),
-            })
-        })?;
-    let namespace = project.temporal_config.get_temporal_namespace();
-
-    let request = RequestCancelWorkflowExecutionRequest {
-        namespace,
-        workflow_execution: Some(WorkflowExecution {
-            workflow_id: name.to_string(),
-            run_id: "".to_string(),
-        }),
-        reason: "Cancelled by user request".to_string(),
-        ..Default::default()
-    };
+    let service = temporal_service(project)?;
+    cancel_workflow_with(&service, name).await
+}
 
-    client_manager
-        .execute(|mut client| async move {
-            client
-                .request_cancel_workflow_execution(request)
-                .await
-                .map_err(|e| anyhow::Error::msg(e.to_string()))
-        })
+async fn cancel_workflow_with(
+    service: &dyn WorkflowService,
+    name: &str,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    service
+        .request_cancel(name, "")
         .await
-        .map_err(|e| {
-            let error_message = if e
-                .to_string()
-                .contains("workflow execution already completed")
-            {
-                format!("Workflow '{name}' has already completed")
-            } else {
-                format!("Could not cancel workflow '{name}': {e}")
-            };
-
-            RoutineFailure::error(Message {
-                action: "Workflow".to_string(),
-                details: format!("{error_message}\n"),
-            })
-        })?;
+        .map_err(workflow_failure)?;
 
     Ok(RoutineSuccess::success(Message {
         action: "Workflow".to_string(),
@@ -390,6 +431,35 @@ pub async fn cancel_workflow(
     }))
 }
 
+/// Polls a workflow run until it closes, calling `on_transition` every time
+/// its status changes (including the first status observed).
+async fn follow_workflow<F>(
+    service: &dyn WorkflowService,
+    workflow_id: &str,
+    run_id: &str,
+    poll_interval: Duration,
+    mut on_transition: F,
+) -> Result<WorkflowExecutionStatus, WorkflowServiceError>
+where
+    F: FnMut(WorkflowExecutionStatus),
+{
+    let mut last_status = None;
+    loop {
+        let status = service.describe_status(workflow_id, run_id).await?;
+        if last_status != Some(status) {
+            on_transition(status);
+            last_status = Some(status);
+        }
+        if !matches!(
+            status,
+            WorkflowExecutionStatus::Running | WorkflowExecutionStatus::Unspecified
+        ) {
+            return Ok(status);
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
 pub async fn pause_workflow(
     project: &Project,
     name: &str,
@@ -997,3 +1067,165 @@ pub async fn get_workflow_status(
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Workflow service that replays a scripted sequence of statuses and
+    /// fails cancel/terminate with a canned error when one is set.
+    struct MockWorkflowService {
+        statuses: Mutex<Vec<WorkflowExecutionStatus>>,
+        control_error: Mutex<Option<WorkflowServiceError>>,
+        describe_calls: Mutex<usize>,
+    }
+
+    impl MockWorkflowService {
+        fn with_statuses(statuses: Vec<WorkflowExecutionStatus>) -> Self {
+            Self {
+                statuses: Mutex::new(statuses),
+                control_error: Mutex::new(None),
+                describe_calls: Mutex::new(0),
+            }
+        }
+
+        fn failing_with(error: WorkflowServiceError) -> Self {
+            let service = Self::with_statuses(vec![]);
+            *service.control_error.lock().unwrap() = Some(error);
+            service
+        }
+
+        fn control_result(&self) -> Result<(), WorkflowServiceError> {
+            match self.control_error.lock().unwrap().take() {
+                Some(error) => Err(error),
+                None => Ok(()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl WorkflowService for MockWorkflowService {
+        async fn describe_status(
+            &self,
+            workflow_id: &str,
+            _run_id: &str,
+        ) -> Result<WorkflowExecutionStatus, WorkflowServiceError> {
+            *self.describe_calls.lock().unwrap() += 1;
+            let mut statuses = self.statuses.lock().unwrap();
+            if statuses.is_empty() {
+                return Err(WorkflowServiceError::NotFound(workflow_id.to_string()));
+            }
+            Ok(statuses.remove(0))
+        }
+
+        async fn request_cancel(
+            &self,
+            _workflow_id: &str,
+            _run_id: &str,
+        ) -> Result<(), WorkflowServiceError> {
+            self.control_result()
+        }
+
+        async fn terminate(
+            &self,
+            _workflow_id: &str,
+            _run_id: &str,
+        ) -> Result<(), WorkflowServiceError> {
+            self.control_result()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_follow_workflow_reports_each_transition_once() {
+        let service = MockWorkflowService::with_statuses(vec![
+            WorkflowExecutionStatus::Running,
+            WorkflowExecutionStatus::Running,
+            WorkflowExecutionStatus::Running,
+            WorkflowExecutionStatus::Completed,
+        ]);
+
+        let mut transitions = Vec::new();
+        let status = follow_workflow(&service, "daily", "run-1", Duration::ZERO, |s| {
+            transitions.push(s)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(status, WorkflowExecutionStatus::Completed);
+        assert_eq!(
+            transitions,
+            vec![
+                WorkflowExecutionStatus::Running,
+                WorkflowExecutionStatus::Completed
+            ]
+        );
+        assert_eq!(*service.describe_calls.lock().unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_follow_workflow_stops_on_failure() {
+        let service = MockWorkflowService::with_statuses(vec![
+            WorkflowExecutionStatus::Running,
+            WorkflowExecutionStatus::Failed,
+            WorkflowExecutionStatus::Running,
+        ]);
+
+        let status = follow_workflow(&service, "daily", "run-1", Duration::ZERO, |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(status, WorkflowExecutionStatus::Failed);
+        assert_eq!(*service.describe_calls.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_follow_workflow_propagates_not_found() {
+        let service = MockWorkflowService::with_statuses(vec![]);
+
+        let error = follow_workflow(&service, "missing", "", Duration::ZERO, |_| {})
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, WorkflowServiceError::NotFound(id) if id == "missing"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_already_completed_workflow_is_friendly() {
+        let service = MockWorkflowService::failing_with(WorkflowServiceError::AlreadyCompleted(
+            "daily".to_string(),
+        ));
+
+        let failure = cancel_workflow_with(&service, "daily").await.unwrap_err();
+
+        assert_eq!(
+            failure.message.details,
+            "Workflow 'daily' has already completed\n"
+        );
+        assert_eq!(failure.exit_code, CliExitCode::Error);
+    }
+
+    #[tokio::test]
+    async fn test_terminate_maps_unavailable_to_connectivity_failure() {
+        let service = MockWorkflowService::failing_with(WorkflowServiceError::Unavailable(
+            "connection refused".to_string(),
+        ));
+
+        let failure = terminate_workflow_with(&service, "daily")
+            .await
+            .unwrap_err();
+
+        assert_eq!(failure.exit_code, CliExitCode::ConnectivityFailure);
+        assert!(!failure.message.details.contains("status:"));
+    }
+
+    #[tokio::test]
+    async fn test_terminate_succeeds() {
+        let service = MockWorkflowService::with_statuses(vec![]);
+
+        let success = terminate_workflow_with(&service, "daily").await.unwrap();
+
+        assert_eq!(success.message.details, "'daily' terminated successfully\n");
+    }
+}
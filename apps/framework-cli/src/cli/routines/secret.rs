@@ -0,0 +1,118 @@
+//! `moose secret set/get/list/delete`: named secrets of the project.
+//!
+//! `get` and `list` print bare values so they can be used in scripts, e.g.
+//! `export TOKEN=$(moose secret get api_token)`.
+
+use std::io::{IsTerminal, Read};
+
+use super::{RoutineFailure, RoutineSuccess};
+use crate::cli::display::Message;
+use crate::cli::exit_code::CliExitCode;
+use crate::cli::{prompt_password, Decision};
+use crate::utilities::keyring::SecretRepository;
+use crate::utilities::secret_store::{SecretStore, SecretStoreError};
+
+fn store_failure(details: String, error: SecretStoreError) -> RoutineFailure {
+    let exit_code = match error {
+        SecretStoreError::InvalidName(_) => CliExitCode::ValidationFailure,
+        _ => CliExitCode::Error,
+    };
+    RoutineFailure::new(Message::new("Secret".to_string(), details), error)
+        .with_exit_code(exit_code)
+}
+
+/// Stores `name`, reading the value from stdin when it is not a terminal.
+pub fn set_secret<R: SecretRepository>(
+    secrets: &SecretStore<R>,
+    name: &str,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let value = if std::io::stdin().is_terminal() {
+        prompt_password(
+            Decision::new(&format!("the value of secret '{name}'"), None),
+            &format!("Enter the value of secret '{name}'"),
+        )?
+    } else {
+        let mut value = String::new();
+        std::io::stdin().read_to_string(&mut value).map_err(|e| {
+            RoutineFailure::new(
+                Message::new("Secret".to_string(), "Failed to read stdin".to_string()),
+                e,
+            )
+        })?;
+        value.trim_end_matches(['\n', '\r']).to_string()
+    };
+
+    if value.is_empty() {
+        return Err(RoutineFailure::error(Message::new(
+            "Secret".to_string(),
+            format!("The value of secret '{name}' cannot be empty"),
+        ))
+        .with_exit_code(CliExitCode::ValidationFailure));
+    }
+
+    secrets
+        .set(name, &value)
+        .map_err(|e| store_failure(format!("Failed to store secret '{name}'"), e))?;
+
+    Ok(RoutineSuccess::success(Message::new(
+        "Secret".to_string(),
+        format!("Stored '{name}'"),
+    )))
+}
+
+pub fn get_secret<R: SecretRepository>(
+    secrets: &SecretStore<R>,
+    name: &str,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let value = secrets
+        .get(name)
+        .map_err(|e| store_failure(format!("Failed to read secret '{name}'"), e))?
+        .ok_or_else(|| {
+            RoutineFailure::error(Message::new(
+                "Secret".to_string(),
+                format!("No secret named '{name}'"),
+            ))
+        })?;
+
+    println!("{value}");
+    Ok(RoutineSuccess::success(Message::new(
+        "".to_string(),
+        "".to_string(),
+    )))
+}
+
+pub fn list_secrets<R: SecretRepository>(
+    secrets: &SecretStore<R>,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let names = secrets
+        .list()
+        .map_err(|e| store_failure("Failed to list secrets".to_string(), e))?;
+
+    for name in names {
+        println!("{name}");
+    }
+    Ok(RoutineSuccess::success(Message::new(
+        "".to_string(),
+        "".to_string(),
+    )))
+}
+
+pub fn delete_secret<R: SecretRepository>(
+    secrets: &SecretStore<R>,
+    name: &str,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let deleted = secrets
+        .delete(name)
+        .map_err(|e| store_failure(format!("Failed to delete secret '{name}'"), e))?;
+
+    if !deleted {
+        return Err(RoutineFailure::error(Message::new(
+            "Secret".to_string(),
+            format!("No secret named '{name}'"),
+        )));
+    }
+    Ok(RoutineSuccess::success(Message::new(
+        "Secret".to_string(),
+        format!("Deleted '{name}'"),
+    )))
+}
@@ -2,6 +2,7 @@ use crate::cli::commands::SeedSubcommands;
 use crate::cli::display;
 use crate::cli::display::status::{format_error, format_success, format_warning};
 use crate::cli::display::{with_spinner_completion_async, Message, MessageType};
+use crate::cli::routines::seed_file;
 use crate::cli::routines::RoutineFailure;
 use crate::cli::routines::RoutineSuccess;
 use crate::framework::core::infrastructure::table::Table;
@@ -11,14 +12,19 @@ use crate::infrastructure::olap::clickhouse::config::{
     parse_clickhouse_connection_string, ClickHouseConfig,
 };
 use crate::infrastructure::olap::clickhouse::mapper::std_table_to_clickhouse_table;
+use crate::infrastructure::olap::clickhouse::model::quote_qualified;
 use crate::infrastructure::olap::clickhouse::queries::create_table_query;
 use crate::infrastructure::olap::clickhouse::remote::ClickHouseRemote;
 use crate::project::Project;
-use crate::utilities::constants::{DEFAULT_SEED_LIMIT, KEY_REMOTE_CLICKHOUSE_URL};
-use crate::utilities::keyring::{KeyringSecretRepository, SecretRepository};
+use crate::utilities::constants::{
+    DEFAULT_SEED_LIMIT, KEY_REMOTE_CLICKHOUSE_URL, REMOTE_CLICKHOUSE_URL_SECRET,
+};
+use crate::utilities::secret_store::project_secrets;
 
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use std::cmp::min;
 use std::collections::HashSet;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 /// How many rows to copy per table.
@@ -35,6 +41,17 @@ pub enum SeedLimit {
     Unspecified,
 }
 
+/// Which tables and rows to copy from the remote.
+#[derive(Debug, Default)]
+pub struct SeedSelection {
+    /// `--table`: seed exactly this table
+    pub table_name: Option<String>,
+    /// `--tables`: glob patterns the table name must match
+    pub table_patterns: Vec<String>,
+    /// `--where`: predicate applied to every remote SELECT
+    pub where_clause: Option<String>,
+}
+
 /// Resolves the effective row limit for a single table.
 ///
 /// Precedence: `--all` > `--limit N` > `seedFilter.limit` > [`DEFAULT_SEED_LIMIT`].
@@ -136,8 +153,8 @@ struct SeedingQueryParams<'a> {
 /// Builds the seeding SQL query for a specific table
 fn build_seeding_query(params: &SeedingQueryParams) -> String {
     format!(
-        "INSERT INTO `{local_db}`.`{table_name}` SELECT * FROM remoteSecure('{remote_host_and_port}', '{remote_db}', '{table_name}', '{remote_user}', '{remote_password}') {where_clause} {order_by_clause} LIMIT {limit} OFFSET {offset}",
-        local_db = params.local_db,
+        "INSERT INTO {local_table} SELECT * FROM remoteSecure('{remote_host_and_port}', '{remote_db}', '{table_name}', '{remote_user}', '{remote_password}') {where_clause} {order_by_clause} LIMIT {limit} OFFSET {offset}",
+        local_table = quote_qualified(params.local_db, params.table_name),
         table_name = params.table_name,
         remote_host_and_port = params.remote_host_and_port,
         remote_db = params.remote_db,
@@ -259,6 +276,65 @@ async fn get_remote_table_count(
     })
 }
 
+/// Rows copied per INSERT ... SELECT batch
+const SEED_BATCH_SIZE: usize = 50_000;
+
+/// Rejects predicates that could smuggle a second statement into the remote SELECT.
+///
+/// The predicate is otherwise inserted verbatim, which is fine because the CLI
+/// runs against the user's own databases.
+fn validate_where_predicate(predicate: &str) -> Result<(), RoutineFailure> {
+    if predicate.contains(';') || predicate.contains("--") || predicate.contains("/*") {
+        return Err(RoutineFailure::error(Message::new(
+            "Seed".to_string(),
+            format!(
+                "--where must be a single predicate without semicolons or comments: {predicate}"
+            ),
+        )));
+    }
+    if predicate.trim().is_empty() {
+        return Err(RoutineFailure::error(Message::new(
+            "Seed".to_string(),
+            "--where cannot be empty".to_string(),
+        )));
+    }
+    Ok(())
+}
+
+/// Combines the per-table seedFilter.where with the CLI `--where` predicate.
+fn build_where_clause(seed_filter_where: Option<&str>, cli_where: Option<&str>) -> String {
+    match (seed_filter_where, cli_where) {
+        (None, None) => String::new(),
+        (Some(w), None) | (None, Some(w)) => format!("WHERE {w}"),
+        (Some(a), Some(b)) => format!("WHERE ({a}) AND ({b})"),
+    }
+}
+
+/// Number of rows to request in the next batch, never overshooting `total_rows`.
+fn next_batch_limit(total_rows: usize, copied: usize, batch_size: usize) -> usize {
+    min(batch_size, total_rows.saturating_sub(copied))
+}
+
+/// Estimates the remaining time from the average throughput so far.
+fn estimate_remaining(copied: usize, total: usize, elapsed: Duration) -> Option<Duration> {
+    if copied == 0 || copied >= total {
+        return None;
+    }
+    Some(elapsed.mul_f64((total - copied) as f64 / copied as f64))
+}
+
+fn format_seed_progress(table: &str, copied: usize, total: usize, elapsed: Duration) -> String {
+    let elapsed_secs = Duration::from_secs(elapsed.as_secs());
+    let eta = match estimate_remaining(copied, total, elapsed) {
+        Some(eta) => humantime::format_duration(Duration::from_secs(eta.as_secs())).to_string(),
+        None => "-".to_string(),
+    };
+    format!(
+        "{table}: {copied}/{total} rows, elapsed {}, ETA {eta}",
+        humantime::format_duration(elapsed_secs)
+    )
+}
+
 /// Seeds a single table with batched copying
 async fn seed_single_table(
     local_clickhouse: &ClickHouseClient,
@@ -266,20 +342,15 @@ async fn seed_single_table(
     table: &Table,
     limit: Option<usize>,
     order_by: Option<&str>,
+    cli_where: Option<&str>,
 ) -> Result<String, RoutineFailure> {
     let remote_host_and_port = format!("{}:{}", remote_config.host, remote_config.native_port);
     let db = table.database.as_deref();
     let local_db = db.unwrap_or(&local_clickhouse.config().db_name);
-    let batch_size: usize = 50_000;
 
     // User-provided config inserted verbatim
     // safe here because the CLI runs against the user's own databases.
-    let where_clause = table
-        .seed_filter
-        .where_clause
-        .as_deref()
-        .map(|w| format!("WHERE {w}"))
-        .unwrap_or_default();
+    let where_clause = build_where_clause(table.seed_filter.where_clause.as_deref(), cli_where);
 
     // Get total row count (with seed filter WHERE applied)
     let remote_total = get_remote_table_count(
@@ -309,17 +380,15 @@ async fn seed_single_table(
         Some(l) => min(remote_total, l),
     };
 
-    let order_by_clause = build_order_by_clause(table, order_by, total_rows, batch_size)?;
+    let order_by_clause = build_order_by_clause(table, order_by, total_rows, SEED_BATCH_SIZE)?;
 
+    let started = Instant::now();
     let mut copied_total: usize = 0;
     let mut i: usize = 0;
 
     while copied_total < total_rows {
         i += 1;
-        let batch_limit = match limit {
-            None => batch_size,
-            Some(l) => min(l - copied_total, batch_size),
-        };
+        let batch_limit = next_batch_limit(total_rows, copied_total, SEED_BATCH_SIZE);
 
         let sql = build_seeding_query(&SeedingQueryParams {
             local_db,
@@ -343,21 +412,69 @@ async fn seed_single_table(
             Ok(_) => {
                 copied_total += batch_limit;
                 debug!("{}: copied batch {i}", table.name);
+                display::show_message_wrapper(
+                    MessageType::Info,
+                    Message::new(
+                        "Seeding".to_string(),
+                        format_seed_progress(
+                            &table.name,
+                            copied_total,
+                            total_rows,
+                            started.elapsed(),
+                        ),
+                    ),
+                );
             }
             Err(e) => {
                 return Err(RoutineFailure::error(Message::new(
                     "SeedSingleTable".to_string(),
-                    format!("Failed to copy batch for {}: {e}", table.name),
+                    format!(
+                        "Failed to copy batch for {} after {copied_total} of {total_rows} rows landed: {e}. Re-run with --where to resume from the rows already copied",
+                        table.name
+                    ),
                 )));
             }
         }
     }
 
-    Ok(format!("✓ {}: copied from remote", table.name))
+    Ok(format!(
+        "✓ {}: copied {copied_total} rows from remote",
+        table.name
+    ))
+}
+
+/// Builds a matcher for `--tables` glob patterns, or `None` when no patterns were given.
+fn build_table_matcher(patterns: &[String]) -> Result<Option<GlobSet>, RoutineFailure> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| {
+            RoutineFailure::new(
+                Message::new(
+                    "Seed".to_string(),
+                    format!("Invalid --tables pattern {pattern}"),
+                ),
+                e,
+            )
+        })?;
+        builder.add(glob);
+    }
+    builder.build().map(Some).map_err(|e| {
+        RoutineFailure::new(
+            Message::new("Seed".to_string(), "Invalid --tables patterns".to_string()),
+            e,
+        )
+    })
 }
 
 /// Gets the list of tables to seed based on parameters
-fn get_tables_to_seed(infra_map: &InfrastructureMap, table_name: Option<String>) -> Vec<&Table> {
+fn get_tables_to_seed<'a>(
+    infra_map: &'a InfrastructureMap,
+    table_name: Option<String>,
+    table_matcher: Option<&GlobSet>,
+) -> Vec<&'a Table> {
     let table_list: Vec<_> = infra_map
         .tables
         .values()
@@ -365,6 +482,7 @@ fn get_tables_to_seed(infra_map: &InfrastructureMap, table_name: Option<String>)
             None => !table.name.starts_with("_MOOSE"),
             Some(name) => &table.name == name,
         })
+        .filter(|table| table_matcher.is_none_or(|m| m.is_match(&table.name)))
         .collect();
     info!(
         "Seeding {} tables (excluding internal Moose tables)",
@@ -379,10 +497,14 @@ fn get_tables_to_seed(infra_map: &InfrastructureMap, table_name: Option<String>)
 async fn seed_clickhouse_operation(
     project: &Project,
     clickhouse_url: &str,
-    table: Option<String>,
+    selection: SeedSelection,
     limit: SeedLimit,
     order_by: Option<&str>,
 ) -> Result<(String, String, Vec<String>), RoutineFailure> {
+    if let Some(predicate) = &selection.where_clause {
+        validate_where_predicate(predicate)?;
+    }
+
     // Load infrastructure map
     let infra_map = load_infrastructure_map(project).await?;
 
@@ -418,7 +540,7 @@ async fn seed_clickhouse_operation(
         &infra_map,
         &local_clickhouse,
         &remote_config,
-        table,
+        &selection,
         limit,
         order_by,
     )
@@ -519,14 +641,17 @@ pub async fn handle_seed_command(
             limit,
             all,
             table,
+            tables,
+            where_clause,
             order_by,
             report,
         }) => {
             let resolved_clickhouse_url = match clickhouse_url {
                 Some(s) => s.clone(),
                 None => {
-                    let repo = KeyringSecretRepository;
-                    match repo.get(&project.name(), KEY_REMOTE_CLICKHOUSE_URL) {
+                    match project_secrets(&project.name())
+                        .get_or_migrate(REMOTE_CLICKHOUSE_URL_SECRET, KEY_REMOTE_CLICKHOUSE_URL)
+                    {
                         Ok(Some(s)) => s,
                         Ok(None) => {
                             return Err(RoutineFailure::error(Message::new(
@@ -552,7 +677,11 @@ pub async fn handle_seed_command(
                 seed_clickhouse_operation(
                     project,
                     &resolved_clickhouse_url,
-                    table.clone(),
+                    SeedSelection {
+                        table_name: table.clone(),
+                        table_patterns: tables.clone(),
+                        where_clause: where_clause.clone(),
+                    },
                     match (all, limit) {
                         (true, _) => SeedLimit::All,
                         (false, Some(n)) => SeedLimit::Count(*n),
@@ -587,6 +716,15 @@ pub async fn handle_seed_command(
                 ),
             )))
         }
+        Some(SeedSubcommands::Data {
+            file,
+            table,
+            truncate_first,
+            limit,
+        }) => {
+            info!("Running seed data command from file: {}", file.display());
+            seed_file::seed_from_file(project, file, table, *truncate_first, *limit).await
+        }
         None => Err(RoutineFailure::error(Message {
             action: "Seed".to_string(),
             details: "No subcommand provided".to_string(),
@@ -599,21 +737,26 @@ pub async fn seed_clickhouse_tables(
     infra_map: &InfrastructureMap,
     local_clickhouse: &ClickHouseClient,
     remote_config: &ClickHouseConfig,
-    table_name: Option<String>,
+    selection: &SeedSelection,
     limit: SeedLimit,
     order_by: Option<&str>,
 ) -> Result<Vec<String>, RoutineFailure> {
     let mut summary = Vec::new();
 
     // Get the list of tables to seed
-    let tables = get_tables_to_seed(infra_map, table_name.clone());
+    let table_matcher = build_table_matcher(&selection.table_patterns)?;
+    let tables = get_tables_to_seed(
+        infra_map,
+        selection.table_name.clone(),
+        table_matcher.as_ref(),
+    );
     let other_dbs: Vec<&str> = tables
         .iter()
         .filter_map(|t| t.database.as_deref())
         .collect();
 
     // Get available remote tables for validation (unless specific table is requested)
-    let remote_tables = if let Some(name) = table_name {
+    let remote_tables = if let Some(name) = &selection.table_name {
         if tables.is_empty() {
             return Err(RoutineFailure::error(Message::new(
                 "Table".to_string(),
@@ -664,6 +807,7 @@ pub async fn seed_clickhouse_tables(
             table,
             effective_limit,
             order_by,
+            selection.where_clause.as_deref(),
         )
         .await
         {
@@ -994,6 +1138,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }
     }
 
@@ -1016,6 +1161,8 @@ mod tests {
             materialized_views: HashMap::new(),
             views: HashMap::new(),
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults: Default::default(),
         }
     }
 
@@ -1195,7 +1342,7 @@ mod tests {
 
         let infra_map = create_test_infra_map(tables);
 
-        let result = get_tables_to_seed(&infra_map, Some("specific_table".to_string()));
+        let result = get_tables_to_seed(&infra_map, Some("specific_table".to_string()), None);
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].name, "specific_table");
         assert_eq!(result[0].database, None);
@@ -1205,10 +1352,92 @@ mod tests {
     fn test_get_tables_to_seed_all_tables_empty() {
         let infra_map = InfrastructureMap::default();
 
-        let result = get_tables_to_seed(&infra_map, None);
+        let result = get_tables_to_seed(&infra_map, None, None);
         assert_eq!(result.len(), 0); // Default map has no tables
     }
 
+    #[test]
+    fn test_get_tables_to_seed_with_glob_patterns() {
+        let mut tables = HashMap::new();
+        for name in ["events_2024", "events_2025", "users", "_MOOSE_STATE"] {
+            tables.insert(name.to_string(), create_test_table(name, None));
+        }
+        let infra_map = create_test_infra_map(tables);
+
+        let matcher = build_table_matcher(&["events_*".to_string(), "users".to_string()])
+            .unwrap()
+            .unwrap();
+        let mut names: Vec<_> = get_tables_to_seed(&infra_map, None, Some(&matcher))
+            .into_iter()
+            .map(|t| t.name.clone())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["events_2024", "events_2025", "users"]);
+
+        assert!(build_table_matcher(&[]).unwrap().is_none());
+        assert!(build_table_matcher(&["events_[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_next_batch_limit_never_overshoots() {
+        assert_eq!(next_batch_limit(120_000, 0, 50_000), 50_000);
+        assert_eq!(next_batch_limit(120_000, 100_000, 50_000), 20_000);
+        assert_eq!(next_batch_limit(120_000, 120_000, 50_000), 0);
+        assert_eq!(next_batch_limit(10, 0, 50_000), 10);
+
+        let mut copied = 0;
+        let mut batches = 0;
+        while copied < 120_000 {
+            copied += next_batch_limit(120_000, copied, 50_000);
+            batches += 1;
+        }
+        assert_eq!(copied, 120_000);
+        assert_eq!(batches, 3);
+    }
+
+    #[test]
+    fn test_estimate_remaining() {
+        assert_eq!(estimate_remaining(0, 100, Duration::from_secs(5)), None);
+        assert_eq!(estimate_remaining(100, 100, Duration::from_secs(5)), None);
+        assert_eq!(
+            estimate_remaining(25, 100, Duration::from_secs(10)),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn test_format_seed_progress() {
+        assert_eq!(
+            format_seed_progress("events", 50, 100, Duration::from_millis(10_400)),
+            "events: 50/100 rows, elapsed 10s, ETA 10s"
+        );
+        assert_eq!(
+            format_seed_progress("events", 100, 100, Duration::from_secs(20)),
+            "events: 100/100 rows, elapsed 20s, ETA -"
+        );
+    }
+
+    #[test]
+    fn test_validate_where_predicate_rejects_injection() {
+        assert!(validate_where_predicate("timestamp > now() - INTERVAL 7 DAY").is_ok());
+        assert!(validate_where_predicate("name = 'a'").is_ok());
+        assert!(validate_where_predicate("1 = 1; DROP TABLE users").is_err());
+        assert!(validate_where_predicate("1 = 1 -- trailing").is_err());
+        assert!(validate_where_predicate("1 = 1 /* hidden */").is_err());
+        assert!(validate_where_predicate("   ").is_err());
+    }
+
+    #[test]
+    fn test_build_where_clause_combines_predicates() {
+        assert_eq!(build_where_clause(None, None), "");
+        assert_eq!(build_where_clause(Some("a = 1"), None), "WHERE a = 1");
+        assert_eq!(build_where_clause(None, Some("b = 2")), "WHERE b = 2");
+        assert_eq!(
+            build_where_clause(Some("a = 1 OR c = 3"), Some("b = 2")),
+            "WHERE (a = 1 OR c = 3) AND (b = 2)"
+        );
+    }
+
     // Test for the bug fix: ensure batch counting is accurate
     #[test]
     fn test_batch_counting_logic() {
@@ -0,0 +1,671 @@
+//! Seeding ClickHouse tables from local CSV and Parquet files.
+//!
+//! Files are read in batches of rows, each sent to ClickHouse over the HTTP
+//! interface as the body of an `INSERT INTO ... FORMAT Parquet` /
+//! `FORMAT CSVWithNames` statement. Before anything is inserted, the file's
+//! column names (Parquet schema or CSV header) are checked against the target
+//! table so that unmapped columns are reported up front instead of surfacing
+//! as a ClickHouse parse error halfway through.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use hyper::body::Bytes;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::errors::ParquetError;
+use tracing::{debug, info};
+
+use crate::cli::display::{self, Message, MessageType};
+use crate::cli::routines::{RoutineFailure, RoutineSuccess};
+use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
+use crate::infrastructure::olap::clickhouse::errors::validate_clickhouse_identifier;
+use crate::infrastructure::olap::clickhouse::model::{quote_ident, quote_qualified, quote_string};
+use crate::project::Project;
+
+/// Number of CSV rows sent per INSERT request. Each batch prints a progress line.
+const CSV_BATCH_ROWS: usize = 100_000;
+
+/// Number of Parquet rows sent per INSERT request. Each batch prints a progress line.
+const PARQUET_BATCH_ROWS: usize = 100_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SeedFileError {
+    #[error("Unsupported file type for {path}: expected a .csv or .parquet file")]
+    UnsupportedFormat { path: PathBuf },
+
+    #[error("Failed to read {path}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid Parquet file: {0}")]
+    Parquet(#[from] ParquetError),
+
+    #[error("Failed to read CSV")]
+    Csv(#[from] csv::Error),
+}
+
+/// File formats supported by `moose seed data`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedFileFormat {
+    Csv,
+    Parquet,
+}
+
+impl SeedFileFormat {
+    /// Detects the format from the file extension.
+    pub fn from_path(path: &Path) -> Result<Self, SeedFileError> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("csv") => Ok(SeedFileFormat::Csv),
+            Some("parquet") | Some("pq") => Ok(SeedFileFormat::Parquet),
+            _ => Err(SeedFileError::UnsupportedFormat {
+                path: path.to_path_buf(),
+            }),
+        }
+    }
+
+    /// The ClickHouse input format name used in the `FORMAT` clause.
+    pub fn clickhouse_format(&self) -> &'static str {
+        match self {
+            SeedFileFormat::Csv => "CSVWithNames",
+            SeedFileFormat::Parquet => "Parquet",
+        }
+    }
+}
+
+/// Opens a Parquet file, reading its footer.
+fn open_parquet(path: &Path) -> Result<ParquetRecordBatchReaderBuilder<File>, SeedFileError> {
+    let file = File::open(path).map_err(|source| SeedFileError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ok(ParquetRecordBatchReaderBuilder::try_new(file)?)
+}
+
+/// Reads the top-level column names of a Parquet file from its footer.
+pub fn read_parquet_columns(path: &Path) -> Result<Vec<String>, SeedFileError> {
+    Ok(open_parquet(path)?
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .collect())
+}
+
+/// Reads the header row of a CSV file.
+pub fn read_csv_header(path: &Path) -> Result<Vec<String>, SeedFileError> {
+    let mut reader = csv::Reader::from_path(path)?;
+    Ok(reader.headers()?.iter().map(|h| h.to_string()).collect())
+}
+
+/// How the columns of a file line up with the columns of the target table.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ColumnMapping {
+    /// File columns present in the table, as `(name, clickhouse_type)`, in file order
+    pub mapped: Vec<(String, String)>,
+    /// File columns with no matching table column
+    pub unmapped: Vec<String>,
+    /// Table columns not present in the file; these get their default values
+    pub missing: Vec<String>,
+}
+
+/// Matches file columns to table columns by exact name.
+pub fn map_columns(file_columns: &[String], table_columns: &[(String, String)]) -> ColumnMapping {
+    let mut mapping = ColumnMapping::default();
+    for file_column in file_columns {
+        match table_columns.iter().find(|(name, _)| name == file_column) {
+            Some((name, column_type)) => mapping.mapped.push((name.clone(), column_type.clone())),
+            None => mapping.unmapped.push(file_column.clone()),
+        }
+    }
+    mapping.missing = table_columns
+        .iter()
+        .filter(|(name, _)| !file_columns.contains(name))
+        .map(|(name, _)| name.clone())
+        .collect();
+    mapping
+}
+
+/// Builds the INSERT statement whose body is a batch of rows of the file.
+pub fn build_file_insert_query(
+    database: &str,
+    table: &str,
+    columns: &[(String, String)],
+    format: SeedFileFormat,
+) -> String {
+    let column_list = columns
+        .iter()
+        .map(|(name, _)| quote_ident(name))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "INSERT INTO {} ({column_list}) FORMAT {}",
+        quote_qualified(database, table),
+        format.clickhouse_format()
+    )
+}
+
+/// Fetches `(name, type)` for every column of the target table.
+async fn get_table_columns(
+    client: &ClickHouseClient,
+    database: &str,
+    table: &str,
+) -> Result<Vec<(String, String)>, RoutineFailure> {
+    let sql = format!(
+        "SELECT name, type FROM system.columns WHERE database = {} AND table = {} ORDER BY position",
+        quote_string(database),
+        quote_string(table)
+    );
+    let result = client.execute_sql(&sql).await.map_err(|e| {
+        RoutineFailure::new(
+            Message::new(
+                "SeedData".to_string(),
+                format!("Failed to read columns of {table}"),
+            ),
+            e,
+        )
+    })?;
+
+    Ok(result
+        .lines()
+        .filter_map(|line| {
+            let (name, column_type) = line.split_once('\t')?;
+            Some((name.to_string(), column_type.to_string()))
+        })
+        .collect())
+}
+
+fn show_progress(table: &str, rows: u64, bytes: usize) {
+    display::show_message_wrapper(
+        MessageType::Info,
+        Message::new(
+            "Seeding".to_string(),
+            format!("{table}: {rows} rows ({bytes} bytes) inserted"),
+        ),
+    );
+}
+
+fn file_failure(e: SeedFileError) -> RoutineFailure {
+    RoutineFailure::new(Message::new("SeedData".to_string(), e.to_string()), e)
+}
+
+/// Inserts the contents of a local CSV or Parquet file into a table.
+pub async fn seed_from_file(
+    project: &Project,
+    file: &Path,
+    table: &str,
+    truncate_first: bool,
+    limit: Option<u64>,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let format = SeedFileFormat::from_path(file).map_err(file_failure)?;
+    validate_clickhouse_identifier(table, "Table name").map_err(|e| {
+        RoutineFailure::new(
+            Message::new(
+                "SeedData".to_string(),
+                format!("Invalid table name {table}"),
+            ),
+            e,
+        )
+    })?;
+
+    let client = ClickHouseClient::new(&project.clickhouse_config).map_err(|e| {
+        RoutineFailure::new(
+            Message::new(
+                "SeedData".to_string(),
+                "Failed to create ClickHouse client".to_string(),
+            ),
+            e,
+        )
+    })?;
+    let database = client.config().db_name.clone();
+
+    let table_columns = get_table_columns(&client, &database, table).await?;
+    if table_columns.is_empty() {
+        return Err(RoutineFailure::error(Message::new(
+            "SeedData".to_string(),
+            format!("Table {database}.{table} not found"),
+        )));
+    }
+
+    let file_columns = match format {
+        SeedFileFormat::Csv => read_csv_header(file).map_err(file_failure)?,
+        SeedFileFormat::Parquet => read_parquet_columns(file).map_err(file_failure)?,
+    };
+
+    let mapping = map_columns(&file_columns, &table_columns);
+    if !mapping.unmapped.is_empty() {
+        return Err(RoutineFailure::error(Message::new(
+            "SeedData".to_string(),
+            format!(
+                "Columns in {} do not exist on table {table}: {}",
+                file.display(),
+                mapping.unmapped.join(", ")
+            ),
+        )));
+    }
+    if !mapping.missing.is_empty() {
+        info!(
+            "Columns missing from {} will use defaults: {}",
+            file.display(),
+            mapping.missing.join(", ")
+        );
+    }
+
+    if truncate_first {
+        debug!("Truncating {database}.{table} before seeding");
+        client
+            .execute_sql(&format!(
+                "TRUNCATE TABLE {}",
+                quote_qualified(&database, table)
+            ))
+            .await
+            .map_err(|e| {
+                RoutineFailure::new(
+                    Message::new(
+                        "SeedData".to_string(),
+                        format!("Failed to truncate {table}"),
+                    ),
+                    e,
+                )
+            })?;
+    }
+
+    let (rows, bytes) = match format {
+        SeedFileFormat::Parquet => {
+            insert_parquet(
+                &client,
+                file,
+                &database,
+                table,
+                &mapping.mapped,
+                limit,
+                PARQUET_BATCH_ROWS,
+            )
+            .await?
+        }
+        SeedFileFormat::Csv => {
+            insert_csv(&client, file, &database, table, &mapping.mapped, limit).await?
+        }
+    };
+
+    Ok(RoutineSuccess::success(Message::new(
+        "Seeded".to_string(),
+        format!(
+            "{table} with {rows} rows ({bytes} bytes) from {}",
+            file.display()
+        ),
+    )))
+}
+
+/// Inserts the rows of a Parquet file in batches of `batch_rows` rows, each
+/// re-encoded as a Parquet file of its own. The file is read a batch at a time.
+async fn insert_parquet(
+    client: &ClickHouseClient,
+    file: &Path,
+    database: &str,
+    table: &str,
+    columns: &[(String, String)],
+    limit: Option<u64>,
+    batch_rows: usize,
+) -> Result<(u64, usize), RoutineFailure> {
+    let sql = build_file_insert_query(database, table, columns, SeedFileFormat::Parquet);
+    let mut builder = open_parquet(file)
+        .map_err(file_failure)?
+        .with_batch_size(batch_rows);
+    if let Some(limit) = limit {
+        builder = builder.with_limit(limit as usize);
+    }
+    let batches = builder
+        .build()
+        .map_err(SeedFileError::from)
+        .map_err(file_failure)?;
+
+    let mut rows: u64 = 0;
+    let mut bytes: usize = 0;
+    for batch in batches {
+        let (batch_len, body) = batch
+            .map_err(ParquetError::from)
+            .and_then(|batch| {
+                let mut writer = ArrowWriter::try_new(Vec::new(), batch.schema(), None)?;
+                writer.write(&batch)?;
+                Ok((batch.num_rows(), writer.into_inner()?))
+            })
+            .map_err(SeedFileError::from)
+            .map_err(file_failure)?;
+        let body_len = body.len();
+
+        client
+            .insert_raw(&sql, Bytes::from(body))
+            .await
+            .map_err(|e| {
+                RoutineFailure::new(
+                    Message::new(
+                        "SeedData".to_string(),
+                        format!("Failed to insert into {table} after {rows} rows"),
+                    ),
+                    e,
+                )
+            })?;
+
+        rows += batch_len as u64;
+        bytes += body_len;
+        show_progress(table, rows, bytes);
+    }
+
+    Ok((rows, bytes))
+}
+
+async fn insert_csv(
+    client: &ClickHouseClient,
+    file: &Path,
+    database: &str,
+    table: &str,
+    columns: &[(String, String)],
+    limit: Option<u64>,
+) -> Result<(u64, usize), RoutineFailure> {
+    let sql = build_file_insert_query(database, table, columns, SeedFileFormat::Csv);
+    let mut reader = csv::Reader::from_path(file)
+        .map_err(SeedFileError::from)
+        .map_err(file_failure)?;
+    let headers = reader
+        .headers()
+        .map_err(SeedFileError::from)
+        .map_err(file_failure)?
+        .clone();
+
+    let mut records = reader.records();
+    let mut rows: u64 = 0;
+    let mut bytes: usize = 0;
+
+    loop {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer
+            .write_record(&headers)
+            .map_err(SeedFileError::from)
+            .map_err(file_failure)?;
+
+        let mut batch_rows: usize = 0;
+        while batch_rows < CSV_BATCH_ROWS && limit.is_none_or(|l| rows + (batch_rows as u64) < l) {
+            match records.next() {
+                Some(record) => {
+                    let record = record.map_err(SeedFileError::from).map_err(file_failure)?;
+                    writer
+                        .write_record(&record)
+                        .map_err(SeedFileError::from)
+                        .map_err(file_failure)?;
+                    batch_rows += 1;
+                }
+                None => break,
+            }
+        }
+
+        if batch_rows == 0 {
+            break;
+        }
+
+        let body = writer.into_inner().map_err(|e| {
+            RoutineFailure::new(
+                Message::new(
+                    "SeedData".to_string(),
+                    "Failed to encode CSV batch".to_string(),
+                ),
+                e.into_error(),
+            )
+        })?;
+        let body_len = body.len();
+
+        client
+            .insert_raw(&sql, Bytes::from(body))
+            .await
+            .map_err(|e| {
+                RoutineFailure::new(
+                    Message::new(
+                        "SeedData".to_string(),
+                        format!("Failed to insert into {table} after {rows} rows"),
+                    ),
+                    e,
+                )
+            })?;
+
+        rows += batch_rows as u64;
+        bytes += body_len;
+        show_progress(table, rows, bytes);
+
+        if batch_rows < CSV_BATCH_ROWS {
+            break;
+        }
+    }
+
+    Ok((rows, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::languages::SupportedLanguages;
+    use arrow_array::{Array, ArrayRef, Int64Array, RecordBatch, StringArray};
+    use http_body_util::{BodyExt, Full};
+    use hyper::body::Incoming;
+    use hyper::service::service_fn;
+    use hyper::{Request, Response};
+    use hyper_util::rt::TokioIo;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// Writes the events `0..rows` to a Parquet file of Snappy-compressed row
+    /// groups of 30 000 rows.
+    fn write_parquet(path: &Path, rows: i64) {
+        let ids = Int64Array::from_iter_values(0..rows);
+        let names = StringArray::from_iter_values((0..rows).map(|id| format!("event-{id}")));
+        let batch = RecordBatch::try_from_iter([
+            ("id", Arc::new(ids) as ArrayRef),
+            ("name", Arc::new(names) as ArrayRef),
+        ])
+        .unwrap();
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .set_max_row_group_size(30_000)
+            .build();
+        let mut writer = ArrowWriter::try_new(
+            File::create(path).unwrap(),
+            batch.schema(),
+            Some(properties),
+        )
+        .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    /// Starts a ClickHouse HTTP interface serving an `events (id Int64, name String)`
+    /// table and forwarding the query and body of each INSERT
+    async fn start_clickhouse() -> (u16, mpsc::UnboundedReceiver<(String, Bytes)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |req: Request<Incoming>| {
+                        let tx = tx.clone();
+                        async move {
+                            let params: Vec<(String, String)> =
+                                serde_urlencoded::from_str(req.uri().query().unwrap_or_default())
+                                    .unwrap();
+                            let query = params
+                                .into_iter()
+                                .find(|(name, _)| name == "query")
+                                .map(|(_, query)| query)
+                                .unwrap_or_default();
+                            let body = req.into_body().collect().await?.to_bytes();
+
+                            let response = if query.contains("system.columns") {
+                                "id\tInt64\nname\tString\n"
+                            } else {
+                                if query.starts_with("INSERT") {
+                                    let _ = tx.send((query, body));
+                                }
+                                ""
+                            };
+                            Ok::<_, hyper::Error>(Response::new(Full::new(Bytes::from(response))))
+                        }
+                    });
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        (port, rx)
+    }
+
+    /// The number of rows and the first id of each batch inserted
+    fn inserted_batches(
+        inserts: &mut mpsc::UnboundedReceiver<(String, Bytes)>,
+        expected_query: &str,
+    ) -> Vec<(usize, i64)> {
+        let mut batches = Vec::new();
+        while let Ok((query, body)) = inserts.try_recv() {
+            assert_eq!(query, expected_query);
+            let batch = ParquetRecordBatchReaderBuilder::try_new(body)
+                .unwrap()
+                .build()
+                .unwrap()
+                .next()
+                .unwrap()
+                .unwrap();
+            let ids = batch
+                .column_by_name("id")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap();
+            batches.push((batch.num_rows(), ids.value(0)));
+        }
+        batches
+    }
+
+    #[tokio::test]
+    async fn test_seed_parquet_file_in_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("events.parquet");
+        let batch = PARQUET_BATCH_ROWS as i64;
+        write_parquet(&file, 2 * batch + 5);
+
+        let (port, mut inserts) = start_clickhouse().await;
+        let mut project = Project::new(
+            dir.path(),
+            "seed".to_string(),
+            SupportedLanguages::Typescript,
+        );
+        project.clickhouse_config.host = "127.0.0.1".to_string();
+        project.clickhouse_config.host_port = port as i32;
+        project.clickhouse_config.use_ssl = false;
+        let expected_query = format!(
+            "INSERT INTO `{}`.`events` (`id`, `name`) FORMAT Parquet",
+            project.clickhouse_config.db_name
+        );
+
+        let success = seed_from_file(&project, &file, "events", false, None)
+            .await
+            .unwrap();
+        assert!(success
+            .message
+            .details
+            .starts_with(&format!("events with {} rows", 2 * batch + 5)));
+        assert_eq!(
+            inserted_batches(&mut inserts, &expected_query),
+            vec![
+                (PARQUET_BATCH_ROWS, 0),
+                (PARQUET_BATCH_ROWS, batch),
+                (5, 2 * batch)
+            ]
+        );
+
+        seed_from_file(&project, &file, "events", false, Some(batch as u64 + 10))
+            .await
+            .unwrap();
+        assert_eq!(
+            inserted_batches(&mut inserts, &expected_query),
+            vec![(PARQUET_BATCH_ROWS, 0), (10, batch)]
+        );
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            SeedFileFormat::from_path(Path::new("a/events.parquet")).unwrap(),
+            SeedFileFormat::Parquet
+        );
+        assert_eq!(
+            SeedFileFormat::from_path(Path::new("events.CSV")).unwrap(),
+            SeedFileFormat::Csv
+        );
+        assert!(SeedFileFormat::from_path(Path::new("events.json")).is_err());
+    }
+
+    #[test]
+    fn test_read_parquet_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("events.parquet");
+        write_parquet(&file, 3);
+        assert_eq!(read_parquet_columns(&file).unwrap(), vec!["id", "name"]);
+
+        let csv = dir.path().join("events.csv");
+        std::fs::write(&csv, "id,name\n1,a\n").unwrap();
+        assert!(matches!(
+            read_parquet_columns(&csv),
+            Err(SeedFileError::Parquet(_))
+        ));
+    }
+
+    #[test]
+    fn test_map_columns_reports_unmapped_and_missing() {
+        let table = vec![
+            ("id".to_string(), "Int64".to_string()),
+            ("name".to_string(), "String".to_string()),
+            ("ts".to_string(), "DateTime".to_string()),
+        ];
+        let file = vec!["id".to_string(), "name".to_string(), "extra".to_string()];
+        let mapping = map_columns(&file, &table);
+        assert_eq!(
+            mapping.mapped,
+            vec![
+                ("id".to_string(), "Int64".to_string()),
+                ("name".to_string(), "String".to_string())
+            ]
+        );
+        assert_eq!(mapping.unmapped, vec!["extra"]);
+        assert_eq!(mapping.missing, vec!["ts"]);
+    }
+
+    #[test]
+    fn test_build_file_insert_query() {
+        let columns = vec![
+            ("id".to_string(), "Int64".to_string()),
+            ("kind".to_string(), "Enum8('a' = 1)".to_string()),
+        ];
+        assert_eq!(
+            build_file_insert_query("db", "events", &columns, SeedFileFormat::Parquet),
+            "INSERT INTO `db`.`events` (`id`, `kind`) FORMAT Parquet"
+        );
+        assert_eq!(
+            build_file_insert_query("db", "events", &columns, SeedFileFormat::Csv),
+            "INSERT INTO `db`.`events` (`id`, `kind`) FORMAT CSVWithNames"
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//! `moose config show`: prints the effective project configuration.
+//!
+//! The output is the loaded [`Project`] serialized back to TOML, i.e.
+//! `moose.config.toml` after interpolation, the selected profile, `MOOSE_*`
+//! environment variables and `--set` overrides. Secrets, and the passwords of
+//! URLs, are redacted.
+
+use super::{RoutineFailure, RoutineSuccess};
+use crate::cli::display::Message;
+use crate::project::config_interpolation::redact_config;
+use crate::project::config_profiles::selected_profile;
+use crate::project::Project;
+
+pub fn show_config(project: &Project) -> Result<RoutineSuccess, RoutineFailure> {
+    let mut config = toml::Value::try_from(project).map_err(|e| {
+        RoutineFailure::new(
+            Message::new("Config".to_string(), "Failed to serialize".to_string()),
+            e,
+        )
+    })?;
+    redact_config(&mut config);
+
+    let rendered = toml::to_string_pretty(&config).map_err(|e| {
+        RoutineFailure::new(
+            Message::new("Config".to_string(), "Failed to serialize".to_string()),
+            e,
+        )
+    })?;
+    println!("{rendered}");
+
+    let details = match selected_profile() {
+        Some(profile) => format!("Effective configuration with profile '{profile}'"),
+        None => "Effective configuration, no profile selected".to_string(),
+    };
+    Ok(RoutineSuccess::success(Message::new(
+        "Config".to_string(),
+        details,
+    )))
+}
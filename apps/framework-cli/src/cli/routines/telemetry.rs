@@ -0,0 +1,98 @@
+//! `moose config telemetry [on|off|status]`: opt in or out of usage telemetry.
+
+use std::collections::HashMap;
+
+use super::{RoutineFailure, RoutineSuccess};
+use crate::cli::display::Message;
+use crate::cli::settings::{read_settings, set_telemetry_enabled, Settings};
+use crate::utilities::capture::{posthog_event, usage_event, ActivityType, USAGE_EVENT_NAME};
+use crate::utilities::machine_id::{get_machine_id_path, read_machine_id};
+use crate::utilities::telemetry::{telemetry_status, TelemetryStatus, TELEMETRY_ENV_VAR};
+
+/// PostHog capture endpoint the usage events are sent to.
+const TELEMETRY_ENDPOINT: &str = "https://us.i.posthog.com/capture/";
+
+/// Writes `telemetry.enabled` to the CLI settings.
+pub fn set_telemetry(enabled: bool) -> Result<RoutineSuccess, RoutineFailure> {
+    set_telemetry_enabled(enabled).map_err(|e| {
+        RoutineFailure::new(
+            Message::new(
+                "Telemetry".to_string(),
+                "Failed to update the CLI settings".to_string(),
+            ),
+            e,
+        )
+    })?;
+
+    if !enabled {
+        return Ok(RoutineSuccess::success(Message::new(
+            "Telemetry".to_string(),
+            "Disabled".to_string(),
+        )));
+    }
+
+    // Still off when the environment disables it
+    let details = match read_settings().map(|settings| telemetry_status(&settings)) {
+        Ok(TelemetryStatus::Enabled) | Err(_) => "Enabled".to_string(),
+        Ok(status) => format!("Enabled in the CLI settings, but {status}"),
+    };
+    Ok(RoutineSuccess::success(Message::new(
+        "Telemetry".to_string(),
+        details,
+    )))
+}
+
+/// Prints whether telemetry is sent, an example event and its destination.
+pub fn show_telemetry_status(
+    settings: &Settings,
+    project_name: Option<String>,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let status = telemetry_status(settings);
+    let machine_id_path = get_machine_id_path();
+    let machine_id = read_machine_id();
+
+    println!("Status: {status}");
+    println!("Opt out: `moose config telemetry off`, or {TELEMETRY_ENV_VAR}=0 for a single run");
+    println!("Destination: PostHog, {TELEMETRY_ENDPOINT}");
+    match &machine_id {
+        Some(id) => println!("Machine ID: {id} (from {})", machine_id_path.display()),
+        None => println!(
+            "Machine ID: none yet, a random ID is created in {} when telemetry is first sent",
+            machine_id_path.display()
+        ),
+    }
+    if settings.telemetry.export_metrics {
+        println!(
+            "Metrics export: {}",
+            if status.is_enabled() {
+                "runtime metrics are also sent to the endpoints in metric.endpoints"
+            } else {
+                "off while telemetry is disabled"
+            }
+        );
+    }
+
+    let event = usage_event(
+        ActivityType::ConfigTelemetryCommand,
+        project_name,
+        settings,
+        HashMap::new(),
+    );
+    let event = posthog_event(&event, machine_id.as_deref().unwrap_or("<machine id>"));
+    let rendered = serde_json::to_string_pretty(&event).map_err(|e| {
+        RoutineFailure::new(
+            Message::new("Telemetry".to_string(), "Failed to serialize".to_string()),
+            e,
+        )
+    })?;
+    println!("\nEach command sends one '{USAGE_EVENT_NAME}' event like this one:\n{rendered}");
+
+    Ok(RoutineSuccess::success(Message::new(
+        "Telemetry".to_string(),
+        if status.is_enabled() {
+            "Enabled".to_string()
+        } else {
+            "Nothing is sent".to_string()
+        },
+    )))
+}
@@ -2,11 +2,13 @@ use flate2::read::GzDecoder;
 use futures::StreamExt;
 use home::home_dir;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::str::FromStr;
 use tar::Archive;
 use toml::Value;
 use tracing::warn;
@@ -14,16 +16,21 @@ use tracing::warn;
 use super::RoutineFailure;
 use super::RoutineSuccess;
 use crate::cli::display::{Message, MessageType};
+use crate::cli::exit_code::CliExitCode;
 use crate::cli::settings::user_directory;
+use crate::cli::{can_prompt, prompt_user, Decision};
 use crate::framework::languages::SupportedLanguages;
 use crate::project::Project;
 use crate::utilities::constants::CLI_VERSION;
-use crate::utilities::git::is_git_repo;
+use crate::utilities::git::{create_init_commit, is_git_repo};
 
 const TEMPLATE_REGISTRY_URL: &str = "https://templates.514.dev";
 const DOWNLOAD_DIR: &str = "templates";
 const LOCAL_TEMPLATE_DIR: &str = "template-packages";
 
+/// Version of the `moose template list --json` document. Bump when the shape changes.
+pub const TEMPLATE_LIST_JSON_SCHEMA_VERSION: u32 = 1;
+
 // Add a new struct to represent template config
 #[derive(Debug)]
 pub struct TemplateConfig {
@@ -31,10 +38,17 @@ pub struct TemplateConfig {
     pub description: String,
     pub post_install_print: String,
     pub visible: bool,
+    pub min_moose_version: Option<String>,
+    pub variables: Vec<TemplateVariable>,
 }
 
 impl TemplateConfig {
     fn from_toml(value: &Value) -> Option<Self> {
+        let variables = match value.get("variables") {
+            Some(variables) => variables.clone().try_into().ok()?,
+            None => Vec::new(),
+        };
+
         Some(TemplateConfig {
             language: value.get("language")?.as_str()?.to_string(),
             description: value.get("description")?.as_str()?.to_string(),
@@ -43,10 +57,202 @@ impl TemplateConfig {
                 .get("visible")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(true),
+            min_moose_version: value
+                .get("min_moose_version")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            variables,
         })
     }
 }
 
+/// A parameter declared in a template's `[[variables]]` table. Every `{{name}}`
+/// in the generated files is replaced with its value.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct TemplateVariable {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub default: Option<String>,
+    /// Regex the whole value has to match
+    pub validation: Option<String>,
+}
+
+impl TemplateVariable {
+    fn validate(&self, value: &str) -> Result<(), TemplateVariableError> {
+        let Some(pattern) = &self.validation else {
+            return Ok(());
+        };
+        let regex = Regex::new(&format!("^(?:{pattern})$")).map_err(|source| {
+            TemplateVariableError::InvalidPattern {
+                name: self.name.clone(),
+                source,
+            }
+        })?;
+        if regex.is_match(value) {
+            Ok(())
+        } else {
+            Err(TemplateVariableError::InvalidValue {
+                name: self.name.clone(),
+                value: value.to_string(),
+                pattern: pattern.clone(),
+            })
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum TemplateVariableError {
+    #[error("expected <name>=<value>, got '{0}'")]
+    MissingValue(String),
+
+    #[error("unknown template variable '{name}', expected one of: {expected}")]
+    Unknown { name: String, expected: String },
+
+    #[error("'{value}' is not a valid value for '{name}', it must match {pattern}")]
+    InvalidValue {
+        name: String,
+        value: String,
+        pattern: String,
+    },
+
+    #[error("template variable '{name}' has an invalid validation pattern")]
+    InvalidPattern {
+        name: String,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// A template variable value passed as `--var <name>=<value>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateVar {
+    pub name: String,
+    pub value: String,
+}
+
+impl FromStr for TemplateVar {
+    type Err = TemplateVariableError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s
+            .split_once('=')
+            .ok_or_else(|| TemplateVariableError::MissingValue(s.to_string()))?;
+        Ok(TemplateVar {
+            name: name.trim().to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+/// A template entry in the `moose template list --json` document.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct TemplateSummary {
+    pub name: String,
+    pub language: String,
+    pub description: String,
+    /// Templates are published per CLI release, so unless a template declares
+    /// `min_moose_version` this is the registry version it was listed from.
+    pub required_moose_version: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TemplateListing<'a> {
+    version: u32,
+    template_version: &'a str,
+    templates: &'a [TemplateSummary],
+}
+
+fn language_matches(template_language: &str, language: SupportedLanguages) -> bool {
+    match language {
+        SupportedLanguages::Typescript => matches!(template_language, "typescript" | "ts"),
+        SupportedLanguages::Python => matches!(template_language, "python" | "py"),
+    }
+}
+
+/// Visible templates from the manifest's `templates` table, sorted by name and
+/// optionally restricted to a single language.
+fn visible_templates(
+    templates: &Value,
+    template_version: &str,
+    language: Option<SupportedLanguages>,
+) -> Vec<TemplateSummary> {
+    let mut summaries: Vec<TemplateSummary> = templates
+        .as_table()
+        .map(|table| {
+            table
+                .iter()
+                .filter_map(|(name, config)| {
+                    let config = TemplateConfig::from_toml(config)?;
+                    // Filter out templates with visible=false
+                    if !config.visible
+                        || language.is_some_and(|l| !language_matches(&config.language, l))
+                    {
+                        return None;
+                    }
+                    Some(TemplateSummary {
+                        name: name.clone(),
+                        language: config.language,
+                        description: config.description,
+                        required_moose_version: config
+                            .min_moose_version
+                            .unwrap_or_else(|| template_version.to_string()),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    summaries.sort_by(|a, b| a.name.cmp(&b.name));
+    summaries
+}
+
+/// Lists the file paths contained in a gzipped template archive, skipping the
+/// archive root and macOS metadata files.
+fn archive_paths(tar_gz: impl Read) -> anyhow::Result<Vec<String>> {
+    let mut archive = Archive::new(GzDecoder::new(tar_gz));
+    let mut paths = Vec::new();
+
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let path = entry.path()?.to_string_lossy().to_string();
+        if path.contains("/._") || path.starts_with("._") {
+            continue;
+        }
+        let path = path.trim_start_matches("./").trim_end_matches('/');
+        if path.is_empty() || path == "." {
+            continue;
+        }
+        if entry.header().entry_type().is_dir() {
+            paths.push(format!("{path}/"));
+        } else {
+            paths.push(path.to_string());
+        }
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+/// Renders archive paths as an indented tree, one entry per line.
+fn render_file_tree(paths: &[String]) -> String {
+    paths
+        .iter()
+        .map(|path| {
+            let is_dir = path.ends_with('/');
+            let components: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+            format!(
+                "{}{}{}",
+                "  ".repeat(components.len() - 1),
+                components[components.len() - 1],
+                if is_dir { "/" } else { "" }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 // TODO - no need to download every time, once cached once, use the cached version
 fn templates_download_dir() -> std::io::Result<PathBuf> {
     let mut path = user_directory()?;
@@ -283,6 +489,8 @@ pub async fn get_template_config(
 
 pub async fn list_available_templates(
     template_version: &str,
+    language: Option<SupportedLanguages>,
+    json: bool,
 ) -> Result<RoutineSuccess, RoutineFailure> {
     let manifest = get_template_manifest(template_version).await.map_err(|e| {
         RoutineFailure::error(Message {
@@ -298,27 +506,25 @@ pub async fn list_available_templates(
         })
     })?;
 
-    let available_templates: Vec<String> = templates
-        .as_table()
-        .map(|table| {
-            table
-                .iter()
-                .filter_map(|(name, config)| {
-                    TemplateConfig::from_toml(config).and_then(|config| {
-                        // Filter out templates with visible=false
-                        if config.visible {
-                            Some(format!(
-                                "  - {} ({}) - {}",
-                                name, config.language, config.description
-                            ))
-                        } else {
-                            None
-                        }
-                    })
-                })
-                .collect()
-        })
-        .unwrap_or_default();
+    let summaries = visible_templates(templates, template_version, language);
+
+    if json {
+        let listing = TemplateListing {
+            version: TEMPLATE_LIST_JSON_SCHEMA_VERSION,
+            template_version,
+            templates: &summaries,
+        };
+        println!("{}", serde_json::to_string_pretty(&listing).unwrap());
+        return Ok(RoutineSuccess::success(Message::new(
+            "Templates".to_string(),
+            format!("{} template(s) listed", summaries.len()),
+        )));
+    }
+
+    let available_templates: Vec<String> = summaries
+        .iter()
+        .map(|t| format!("  - {} ({}) - {}", t.name, t.language, t.description))
+        .collect();
 
     let output = format!(
         "Available templates for version {}:
@@ -336,14 +542,183 @@ pub async fn list_available_templates(
     )))
 }
 
+async fn template_files(
+    template_name: &str,
+    template_version: &str,
+) -> anyhow::Result<Vec<String>> {
+    download(template_name, template_version).await?;
+    let archive = File::open(template_file_archive(template_name, template_version)?)?;
+    archive_paths(archive)
+}
+
+/// Shows a template's description, file tree and post-init instructions without
+/// creating a project.
+pub async fn show_template(
+    template_name: &str,
+    template_version: &str,
+) -> Result<RoutineSuccess, RoutineFailure> {
+    let config = get_template_config(template_name, template_version).await?;
+
+    let files = template_files(template_name, template_version)
+        .await
+        .map_err(|e| {
+            RoutineFailure::error(Message {
+                action: "Template".to_string(),
+                details: format!("Failed to read template '{template_name}': {e:?}"),
+            })
+        })?;
+
+    let output = format!(
+        "{} ({})\n{}\n\nFiles:\n{}\n\nAfter init:\n{}",
+        template_name,
+        config.language,
+        config.description,
+        render_file_tree(&files),
+        config
+            .post_install_print
+            .replace("{project_dir}", "<project-dir>")
+            .trim_end()
+    );
+
+    Ok(RoutineSuccess::success(Message::new(
+        "Template".to_string(),
+        output,
+    )))
+}
+
+fn variable_failure(error: TemplateVariableError) -> RoutineFailure {
+    RoutineFailure::error(Message::new("Template".to_string(), error.to_string()))
+        .with_exit_code(CliExitCode::ValidationFailure)
+}
+
+/// Values of the template's variables, from `--var` when given and otherwise
+/// asked for with the variable's default. `--var` for a variable the template
+/// does not declare is rejected.
+fn resolve_variables(
+    variables: &[TemplateVariable],
+    vars: &[TemplateVar],
+) -> Result<BTreeMap<String, String>, RoutineFailure> {
+    if let Some(unknown) = vars
+        .iter()
+        .find(|var| !variables.iter().any(|v| v.name == var.name))
+    {
+        let expected = if variables.is_empty() {
+            "none".to_string()
+        } else {
+            variables
+                .iter()
+                .map(|v| v.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        return Err(variable_failure(TemplateVariableError::Unknown {
+            name: unknown.name.clone(),
+            expected,
+        }));
+    }
+
+    let mut values = BTreeMap::new();
+    for variable in variables {
+        // The last --var wins when a variable is given twice
+        let value = match vars.iter().rev().find(|var| var.name == variable.name) {
+            Some(var) => {
+                variable.validate(&var.value).map_err(variable_failure)?;
+                var.value.clone()
+            }
+            None => prompt_variable(variable)?,
+        };
+        values.insert(variable.name.clone(), value);
+    }
+    Ok(values)
+}
+
+/// Asks for `variable` until the answer passes validation. Without a terminal
+/// the first invalid answer fails.
+fn prompt_variable(variable: &TemplateVariable) -> Result<String, RoutineFailure> {
+    let decision_name = format!("template variable '{}'", variable.name);
+    let flag = format!("--var {}=<value>", variable.name);
+    let prompt = if variable.description.is_empty() {
+        variable.name.clone()
+    } else {
+        format!("{} ({})", variable.description, variable.name)
+    };
+    let hint = variable
+        .validation
+        .as_ref()
+        .map(|pattern| format!("Must match {pattern}"));
+
+    loop {
+        let value = prompt_user(
+            Decision::new(&decision_name, Some(&flag)),
+            &prompt,
+            variable.default.as_deref(),
+            hint.as_deref(),
+        )?;
+        match variable.validate(&value) {
+            Ok(()) => return Ok(value),
+            Err(e @ TemplateVariableError::InvalidValue { .. }) if can_prompt() => {
+                show_message!(
+                    MessageType::Error,
+                    Message::new("Template".to_string(), e.to_string())
+                );
+            }
+            Err(e) => return Err(variable_failure(e)),
+        }
+    }
+}
+
+fn substitute_variables(content: &str, values: &BTreeMap<String, String>) -> String {
+    values
+        .iter()
+        .fold(content.to_string(), |content, (name, value)| {
+            content.replace(&format!("{{{{{name}}}}}"), value)
+        })
+}
+
+/// Substitutes `{{name}}` placeholders in the files at `paths`, relative to
+/// `dir`. Directories and files that are not UTF-8 text are left untouched.
+fn render_variables(
+    dir: &Path,
+    paths: &[String],
+    values: &BTreeMap<String, String>,
+) -> std::io::Result<()> {
+    for path in paths.iter().filter(|path| !path.ends_with('/')) {
+        let path = dir.join(path);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => continue,
+            Err(e) => return Err(e),
+        };
+        let rendered = substitute_variables(&content, values);
+        if rendered != content {
+            std::fs::write(&path, rendered)?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders the variables into the files unpacked from the template archive.
+fn render_template_files(
+    template_name: &str,
+    dir_path: &Path,
+    values: &BTreeMap<String, String>,
+) -> anyhow::Result<()> {
+    let archive = File::open(template_file_archive(template_name, CLI_VERSION)?)?;
+    render_variables(dir_path, &archive_paths(archive)?, values)?;
+    Ok(())
+}
+
 pub async fn create_project_from_template(
     template: &str,
     name: &str,
     dir_path: &Path,
     no_fail_already_exists: bool,
     custom_dockerfile: bool,
+    vars: &[TemplateVar],
 ) -> Result<String, RoutineFailure> {
     let template_config = get_template_config(template, CLI_VERSION).await?;
+    // Resolved before anything is written so an invalid value leaves no directory behind
+    let values = resolve_variables(&template_config.variables, vars)?;
 
     // Special case: If name is ".", use current directory without creating a new folder
     let is_current_dir = name == ".";
@@ -383,6 +758,14 @@ pub async fn create_project_from_template(
     };
 
     generate_template(template, CLI_VERSION, dir_path).await?;
+    if !values.is_empty() {
+        render_template_files(template, dir_path, &values).map_err(|e| {
+            RoutineFailure::error(Message {
+                action: "Template".to_string(),
+                details: format!("Failed to substitute template variables: {e:?}"),
+            })
+        })?;
+    }
 
     // For current directory case, use the directory name as the project name if name is "."
     let project_name = if is_current_dir {
@@ -395,7 +778,6 @@ pub async fn create_project_from_template(
     };
 
     let project = Project::new(dir_path, project_name.clone(), language);
-    let project_arc = Arc::new(project);
 
     // Update project configuration based on language
     match language {
@@ -475,26 +857,58 @@ pub async fn create_project_from_template(
         setup_custom_dockerfile(dir_path, language)?;
     }
 
-    maybe_create_git_repo(dir_path, project_arc, is_current_dir);
+    maybe_create_git_repo(dir_path, &project, is_current_dir);
 
-    Ok(template_config
-        .post_install_print
-        .replace("{project_dir}", &dir_path.to_string_lossy()))
+    Ok(substitute_variables(
+        &template_config
+            .post_install_print
+            .replace("{project_dir}", &dir_path.to_string_lossy()),
+        &values,
+    ))
 }
 
-fn maybe_create_git_repo(dir_path: &Path, project_arc: Arc<Project>, is_current_dir: bool) {
-    let is_git_repo = is_git_repo(dir_path).expect("Failed to check if directory is a git repo");
+/// Creates a repository with an initial commit unless the project is already
+/// inside one. Init still succeeds when this fails, only the repository is skipped.
+fn create_git_repo(dir_path: &Path, project: &Project) {
+    match is_git_repo(dir_path) {
+        Ok(true) => {
+            show_message!(
+                MessageType::Info,
+                Message {
+                    action: "Init".to_string(),
+                    details: "Already inside a git repository, skipped git init".to_string(),
+                }
+            );
+            return;
+        }
+        Ok(false) => {}
+        Err(e) => warn!(
+            "Failed to check if {} is a git repo: {}",
+            dir_path.display(),
+            e
+        ),
+    }
 
-    if !is_git_repo {
-        crate::utilities::git::create_init_commit(project_arc, dir_path);
-        show_message!(
+    match create_init_commit(project, dir_path) {
+        Ok(()) => show_message!(
             MessageType::Info,
             Message {
                 action: "Init".to_string(),
                 details: "Created a new git repository".to_string(),
             }
-        );
+        ),
+        Err(e) => show_message!(
+            MessageType::Warning,
+            Message {
+                action: "Init".to_string(),
+                details: format!("Skipped creating a git repository: {e}"),
+            }
+        ),
     }
+}
+
+fn maybe_create_git_repo(dir_path: &Path, project: &Project, is_current_dir: bool) {
+    create_git_repo(dir_path, project);
 
     {
         let message = if is_current_dir {
@@ -626,7 +1040,7 @@ mod tests {
     async fn test_list_available_templates_local() {
         ensure_test_environment();
         // Use version "0.0.1" to test against the local manifest
-        let result = list_available_templates("0.0.1").await;
+        let result = list_available_templates("0.0.1", None, false).await;
 
         assert!(
             result.is_ok(),
@@ -689,4 +1103,233 @@ mod tests {
         assert!(templates_table.contains_key("typescript"));
         assert!(templates_table.contains_key("python"));
     }
+
+    const FIXTURE_MANIFEST: &str = r#"
+[templates.typescript]
+language = "typescript"
+description = "Default TypeScript project"
+post_install_print = "cd {project_dir}"
+
+[templates.python-empty]
+language = "python"
+description = "Empty Python project"
+post_install_print = "cd {project_dir}"
+min_moose_version = "0.6.0"
+
+[templates.hidden]
+language = "python"
+description = "Internal test template"
+post_install_print = ""
+visible = false
+"#;
+
+    fn fixture_templates() -> Value {
+        let manifest: Value = toml::from_str(FIXTURE_MANIFEST).unwrap();
+        manifest["templates"].clone()
+    }
+
+    #[test]
+    fn test_visible_templates_filters_by_language_and_visibility() {
+        let templates = fixture_templates();
+
+        let all = visible_templates(&templates, "1.2.3", None);
+        let names: Vec<&str> = all.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["python-empty", "typescript"]);
+
+        let python = visible_templates(&templates, "1.2.3", Some(SupportedLanguages::Python));
+        assert_eq!(python.len(), 1);
+        assert_eq!(python[0].name, "python-empty");
+
+        let typescript =
+            visible_templates(&templates, "1.2.3", Some(SupportedLanguages::Typescript));
+        assert_eq!(typescript.len(), 1);
+        assert_eq!(typescript[0].name, "typescript");
+    }
+
+    #[test]
+    fn test_template_listing_json_shape() {
+        let templates = visible_templates(&fixture_templates(), "1.2.3", None);
+        let listing = TemplateListing {
+            version: TEMPLATE_LIST_JSON_SCHEMA_VERSION,
+            template_version: "1.2.3",
+            templates: &templates,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&listing).unwrap(),
+            serde_json::json!({
+                "version": 1,
+                "template_version": "1.2.3",
+                "templates": [
+                    {
+                        "name": "python-empty",
+                        "language": "python",
+                        "description": "Empty Python project",
+                        "required_moose_version": "0.6.0"
+                    },
+                    {
+                        "name": "typescript",
+                        "language": "typescript",
+                        "description": "Default TypeScript project",
+                        "required_moose_version": "1.2.3"
+                    }
+                ]
+            })
+        );
+    }
+
+    const VARIABLES_MANIFEST: &str = r#"
+[templates.parameterized]
+language = "typescript"
+description = "Template with variables"
+post_install_print = "cd {project_dir} and open {{dashboard_title}}"
+
+[[templates.parameterized.variables]]
+name = "db_name"
+description = "ClickHouse database"
+default = "local"
+validation = "[a-z_][a-z0-9_]*"
+
+[[templates.parameterized.variables]]
+name = "dashboard_title"
+default = "Moose"
+"#;
+
+    fn fixture_variables() -> Vec<TemplateVariable> {
+        let manifest: Value = toml::from_str(VARIABLES_MANIFEST).unwrap();
+        TemplateConfig::from_toml(&manifest["templates"]["parameterized"])
+            .unwrap()
+            .variables
+    }
+
+    fn var(s: &str) -> TemplateVar {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_template_config_reads_variables() {
+        let variables = fixture_variables();
+        assert_eq!(variables.len(), 2);
+        assert_eq!(variables[0].name, "db_name");
+        assert_eq!(variables[0].default.as_deref(), Some("local"));
+        assert_eq!(variables[1].description, "");
+        assert_eq!(variables[1].validation, None);
+
+        let config = TemplateConfig::from_toml(&fixture_templates()["typescript"]).unwrap();
+        assert!(config.variables.is_empty());
+    }
+
+    #[test]
+    fn test_render_fixture_template_with_overrides() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let files = [
+            (
+                "moose.config.toml",
+                "[clickhouse_config]\ndb_name = \"{{db_name}}\"\n",
+            ),
+            (
+                "package.json",
+                r#"{"description": "{{dashboard_title}} on {{db_name}}", "x": "{{other}}"}"#,
+            ),
+            ("app/index.ts", "// {{ db_name }} is not a placeholder\n"),
+        ];
+        std::fs::create_dir(dir.path().join("app")).unwrap();
+        for (path, contents) in files {
+            std::fs::write(dir.path().join(path), contents).unwrap();
+        }
+        std::fs::write(dir.path().join("logo.png"), [0xff, 0xfe, 0x7b, 0x7b]).unwrap();
+
+        let values = resolve_variables(
+            &fixture_variables(),
+            &[
+                var("db_name=analytics"),
+                var("dashboard_title=Orders"),
+                var("db_name=shop"),
+            ],
+        )
+        .unwrap();
+        let mut paths: Vec<String> = files.iter().map(|(path, _)| path.to_string()).collect();
+        paths.extend(["app/".to_string(), "logo.png".to_string()]);
+        render_variables(dir.path(), &paths, &values).unwrap();
+
+        let read = |path: &str| std::fs::read_to_string(dir.path().join(path)).unwrap();
+        assert_eq!(
+            read("moose.config.toml"),
+            "[clickhouse_config]\ndb_name = \"shop\"\n"
+        );
+        assert_eq!(
+            read("package.json"),
+            r#"{"description": "Orders on shop", "x": "{{other}}"}"#
+        );
+        assert_eq!(read("app/index.ts"), files[2].1);
+        assert_eq!(
+            substitute_variables("open {{dashboard_title}}", &values),
+            "open Orders"
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_and_unknown_variables() {
+        let variables = fixture_variables();
+
+        let failure = resolve_variables(
+            &variables,
+            &[var("db_name=Not-Valid"), var("dashboard_title=x")],
+        )
+        .unwrap_err();
+        assert_eq!(failure.exit_code, CliExitCode::ValidationFailure);
+        assert!(
+            failure
+                .message
+                .details
+                .contains("'Not-Valid' is not a valid value for 'db_name'"),
+            "{}",
+            failure.message.details
+        );
+
+        let failure = resolve_variables(&variables, &[var("db=shop")]).unwrap_err();
+        assert_eq!(
+            failure.message.details,
+            "unknown template variable 'db', expected one of: db_name, dashboard_title"
+        );
+
+        assert!(matches!(
+            "db_name".parse::<TemplateVar>(),
+            Err(TemplateVariableError::MissingValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_archive_paths_and_file_tree() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        for (path, contents) in [
+            ("./package.json", "{}"),
+            ("./app/index.ts", ""),
+            ("./._package.json", ""),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, contents.as_bytes())
+                .unwrap();
+        }
+        let mut dir_header = tar::Header::new_gnu();
+        dir_header.set_entry_type(tar::EntryType::Directory);
+        dir_header.set_size(0);
+        dir_header.set_mode(0o755);
+        dir_header.set_cksum();
+        builder
+            .append_data(&mut dir_header, "./app/", std::io::empty())
+            .unwrap();
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+
+        let paths = archive_paths(bytes.as_slice()).unwrap();
+        assert_eq!(paths, vec!["app/", "app/index.ts", "package.json"]);
+        assert_eq!(render_file_tree(&paths), "app/\n  index.ts\npackage.json");
+    }
 }
@@ -1,16 +1,22 @@
-use crate::cli::display::Message;
+use crate::cli::display::{show_message_wrapper, Message, MessageType};
 use crate::cli::routines::{RoutineFailure, RoutineSuccess};
+use crate::cli::{is_non_interactive, prompt_user, Decision};
+use crate::framework::core::infrastructure::sql_resource::SqlResource;
+use crate::framework::core::infrastructure::InfrastructureSignature;
+use crate::framework::core::infrastructure_map::InfrastructureMap;
+use crate::framework::core::partial_infrastructure_map::LifeCycle;
+use crate::infrastructure::olap::clickhouse::model::{quote_ident, quote_qualified};
 use crate::infrastructure::olap::clickhouse::{
-    check_ready, create_client, extract_order_by_from_create_query, run_query,
+    check_ready, create_client, extract_order_by_from_create_query,
+    extract_version_from_table_name, run_query,
 };
+use crate::infrastructure::olap::OlapOperations;
 use crate::project::Project;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::io::IsTerminal;
 use tracing::{info, warn};
 
-fn escape_ident(ident: &str) -> String {
-    ident.replace('`', "``")
-}
-
-async fn list_all_tables(project: &Project) -> Result<Vec<String>, RoutineFailure> {
+pub(super) async fn list_all_tables(project: &Project) -> Result<Vec<String>, RoutineFailure> {
     let client = create_client(project.clickhouse_config.clone());
     check_ready(&client).await.map_err(|e| {
         RoutineFailure::error(Message::new(
@@ -40,7 +46,10 @@ async fn list_all_tables(project: &Project) -> Result<Vec<String>, RoutineFailur
     Ok(rows)
 }
 
-async fn truncate_all_rows(project: &Project, tables: &[String]) -> Result<(), RoutineFailure> {
+pub(super) async fn truncate_all_rows(
+    project: &Project,
+    tables: &[String],
+) -> Result<(), RoutineFailure> {
     let client = create_client(project.clickhouse_config.clone());
     check_ready(&client).await.map_err(|e| {
         RoutineFailure::error(Message::new(
@@ -51,8 +60,7 @@ async fn truncate_all_rows(project: &Project, tables: &[String]) -> Result<(), R
 
     let db_name = &client.config.db_name;
     for t in tables {
-        let table = escape_ident(t);
-        let sql = format!("TRUNCATE TABLE `{}`.`{}`", db_name, table);
+        let sql = format!("TRUNCATE TABLE {}", quote_qualified(db_name, t));
         info!("Truncating table {}.{}", db_name, t);
         run_query(&sql, &client).await.map_err(|e| {
             RoutineFailure::error(Message::new(
@@ -96,7 +104,7 @@ async fn delete_last_n_rows(
 
         // Build ORDER BY clause and projection for IN subquery
         let proj = if order_by.len() == 1 {
-            format!("`{}`", escape_ident(&order_by[0]))
+            quote_ident(&order_by[0])
         } else if order_by.is_empty() {
             return Err(RoutineFailure::error(Message::new(
                 "Ordering".to_string(),
@@ -105,21 +113,21 @@ async fn delete_last_n_rows(
         } else {
             let cols = order_by
                 .iter()
-                .map(|c| format!("`{}`", escape_ident(c)))
+                .map(|c| quote_ident(c))
                 .collect::<Vec<_>>()
                 .join(", ");
             format!("({})", cols)
         };
         let ord = order_by
             .iter()
-            .map(|c| format!("`{}` DESC", escape_ident(c)))
+            .map(|c| format!("{} DESC", quote_ident(c)))
             .collect::<Vec<_>>()
             .join(", ");
 
-        let table = escape_ident(t);
+        let table = quote_qualified(db_name, t);
         let sql = format!(
-            "ALTER TABLE `{db_name}`.`{table}` DELETE WHERE {proj} > (\
-            SELECT {proj} FROM `{db_name}`.`{table}` ORDER BY {ord} LIMIT 1 OFFSET {n}\
+            "ALTER TABLE {table} DELETE WHERE {proj} > (\
+            SELECT {proj} FROM {table} ORDER BY {ord} LIMIT 1 OFFSET {n}\
             ) SETTINGS mutations_sync=1"
         );
         warn!(
@@ -137,13 +145,217 @@ async fn delete_last_n_rows(
     Ok(())
 }
 
+/// Tables fed, through materialized views, by the truncated ones
+#[derive(Debug, PartialEq)]
+struct Cascade {
+    /// Downstream tables, each after the tables feeding it
+    tables: Vec<String>,
+    /// Downstream tables managed outside of Moose, left untouched
+    skipped: Vec<String>,
+}
+
+/// The tables of the database a lineage id of `list_sql_resources` refers
+/// to. The ids drop the version suffix, which only matters when no table has
+/// the exact name.
+fn lineage_tables<'a>(id: &str, tables: &'a [String]) -> Vec<&'a str> {
+    if let Some(table) = tables.iter().find(|t| *t == id) {
+        return vec![table.as_str()];
+    }
+    tables
+        .iter()
+        .filter(|t| extract_version_from_table_name(t).0 == id)
+        .map(String::as_str)
+        .collect()
+}
+
+/// Walks the materialized views of `resources` from `roots` down to the tables
+/// they feed. An externally managed table keeps its data, so the walk stops
+/// there. Fails with the tables of a cycle when the views feed one back.
+fn cascade(
+    roots: &[String],
+    tables: &[String],
+    resources: &[SqlResource],
+    externally_managed: &HashSet<String>,
+) -> Result<Cascade, Vec<String>> {
+    let table_ids = |signatures: &[InfrastructureSignature]| -> Vec<&str> {
+        signatures
+            .iter()
+            .filter(|s| matches!(s, InfrastructureSignature::Table { .. }))
+            .flat_map(|s| lineage_tables(s.id(), tables))
+            .collect()
+    };
+
+    let mut feeds: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for resource in resources {
+        let targets = table_ids(&resource.pushes_data_to);
+        for source in table_ids(&resource.pulls_data_from) {
+            feeds.entry(source).or_default().extend(&targets);
+        }
+    }
+
+    let roots: BTreeSet<&str> = roots.iter().map(String::as_str).collect();
+    let mut reached: BTreeSet<&str> = roots.clone();
+    let mut skipped = BTreeSet::new();
+    let mut queue: VecDeque<&str> = roots.iter().copied().collect();
+    while let Some(table) = queue.pop_front() {
+        for &target in feeds.get(table).into_iter().flatten() {
+            if externally_managed.contains(target) && !roots.contains(target) {
+                skipped.insert(target);
+            } else if reached.insert(target) {
+                queue.push_back(target);
+            }
+        }
+    }
+
+    // Kahn's algorithm over the reached tables, a table is truncated once all
+    // the reached tables feeding it are
+    let mut fed_by: BTreeMap<&str, usize> = reached.iter().map(|t| (*t, 0)).collect();
+    for source in &reached {
+        for target in feeds.get(source).into_iter().flatten() {
+            if let Some(count) = fed_by.get_mut(target) {
+                *count += 1;
+            }
+        }
+    }
+    let mut ready: VecDeque<&str> = fed_by
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(t, _)| *t)
+        .collect();
+    let mut ordered = Vec::new();
+    while let Some(table) = ready.pop_front() {
+        ordered.push(table);
+        for target in feeds.get(table).into_iter().flatten() {
+            if let Some(count) = fed_by.get_mut(target) {
+                *count -= 1;
+                if *count == 0 {
+                    ready.push_back(*target);
+                }
+            }
+        }
+    }
+    if ordered.len() < reached.len() {
+        let ordered: HashSet<&str> = ordered.into_iter().collect();
+        return Err(reached
+            .into_iter()
+            .filter(|t| !ordered.contains(t))
+            .map(str::to_string)
+            .collect());
+    }
+
+    Ok(Cascade {
+        tables: ordered
+            .into_iter()
+            .filter(|t| !roots.contains(t))
+            .map(str::to_string)
+            .collect(),
+        skipped: skipped.into_iter().map(str::to_string).collect(),
+    })
+}
+
+/// Finds the tables fed by `roots` through materialized views and asks to
+/// truncate them too, unless `yes` is set.
+async fn cascade_tables(
+    project: &Project,
+    roots: &[String],
+    yes: bool,
+) -> Result<Vec<String>, RoutineFailure> {
+    let client = create_client(project.clickhouse_config.clone());
+    let db_name = &client.config.db_name;
+    let resources = client
+        .list_sql_resources(db_name, db_name)
+        .await
+        .map_err(|e| {
+            RoutineFailure::new(
+                Message::new(
+                    "Truncate".to_string(),
+                    "listing materialized views".to_string(),
+                ),
+                e,
+            )
+        })?;
+    let tables = list_all_tables(project).await?;
+
+    let infra_map = InfrastructureMap::load_from_user_code(project, false)
+        .await
+        .map_err(|e| {
+            RoutineFailure::error(Message::new(
+                "Failure".to_string(),
+                format!("loading infra map: {e:?}"),
+            ))
+        })?;
+    let externally_managed: HashSet<String> = infra_map
+        .tables
+        .values()
+        .filter(|t| t.life_cycle == LifeCycle::ExternallyManaged)
+        .filter(|t| t.database.as_deref().is_none_or(|db| db == db_name))
+        .map(|t| t.name.clone())
+        .collect();
+
+    let cascade = cascade(roots, &tables, &resources, &externally_managed).map_err(|cycle| {
+        RoutineFailure::error(Message::new(
+            "Truncate".to_string(),
+            format!(
+                "Materialized views feed these tables into each other: {}. Truncate them without --cascade",
+                cycle.join(", ")
+            ),
+        ))
+    })?;
+
+    if !cascade.skipped.is_empty() {
+        show_message_wrapper(
+            MessageType::Warning,
+            Message::new(
+                "Skipped".to_string(),
+                format!(
+                    "externally managed table(s) fed by materialized views: {}",
+                    cascade.skipped.join(", ")
+                ),
+            ),
+        );
+    }
+    if cascade.tables.is_empty() || yes {
+        return Ok(cascade.tables);
+    }
+
+    if !std::io::stdin().is_terminal() && !is_non_interactive() {
+        return Err(RoutineFailure::error(Message::new(
+            "Truncate".to_string(),
+            "Refusing to cascade without confirmation. Pass --yes to skip the prompt".to_string(),
+        )));
+    }
+    let answer = prompt_user(
+        Decision::confirmation("truncating the tables fed by materialized views", "--yes"),
+        &format!(
+            "Materialized views feed the following tables, which will be truncated too:\n{}\nProceed? [y/N]",
+            cascade
+                .tables
+                .iter()
+                .map(|t| format!("  - {t}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+        Some("n"),
+        None,
+    )?;
+    if !matches!(answer.to_lowercase().as_str(), "y" | "yes") {
+        return Err(RoutineFailure::error(Message::new(
+            "Truncate".to_string(),
+            "Cancelled, no table was truncated".to_string(),
+        )));
+    }
+    Ok(cascade.tables)
+}
+
 pub async fn truncate_tables(
     project: &Project,
     tables: Vec<String>,
     all: bool,
     rows: Option<u64>,
+    cascade: bool,
+    yes: bool,
 ) -> Result<RoutineSuccess, RoutineFailure> {
-    let target_tables = if all {
+    let mut target_tables = if all {
         list_all_tables(project).await?
     } else if tables.is_empty() {
         return Err(RoutineFailure::error(Message::new(
@@ -161,6 +373,11 @@ pub async fn truncate_tables(
         )));
     }
 
+    if cascade {
+        let downstream = cascade_tables(project, &target_tables, yes).await?;
+        target_tables.extend(downstream);
+    }
+
     match rows {
         None => truncate_all_rows(project, &target_tables).await?,
         Some(n) => delete_last_n_rows(project, &target_tables, n).await?,
@@ -177,3 +394,91 @@ pub async fn truncate_tables(
         },
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mv(name: &str, source: &str, target: &str) -> SqlResource {
+        SqlResource {
+            name: name.to_string(),
+            database: None,
+            source_file: None,
+            source_line: None,
+            source_column: None,
+            setup: vec![],
+            teardown: vec![],
+            pulls_data_from: vec![InfrastructureSignature::Table {
+                id: source.to_string(),
+            }],
+            pushes_data_to: vec![InfrastructureSignature::Table {
+                id: target.to_string(),
+            }],
+            life_cycle: Default::default(),
+        }
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_cascade_orders_downstream_tables() {
+        let tables = names(&["events_1_0", "hourly", "daily", "users"]);
+        let resources = vec![
+            mv("daily_mv", "hourly", "daily"),
+            mv("hourly_mv", "events", "hourly"),
+            mv("events_by_user_mv", "events", "daily"),
+        ];
+
+        let cascade = cascade(
+            &names(&["events_1_0"]),
+            &tables,
+            &resources,
+            &HashSet::new(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            cascade,
+            Cascade {
+                tables: names(&["hourly", "daily"]),
+                skipped: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_cascade_stops_at_externally_managed_tables() {
+        let tables = names(&["events", "mirror", "daily"]);
+        let resources = vec![
+            mv("mirror_mv", "events", "mirror"),
+            mv("daily_mv", "mirror", "daily"),
+        ];
+
+        let cascade = cascade(
+            &names(&["events"]),
+            &tables,
+            &resources,
+            &HashSet::from(["mirror".to_string()]),
+        )
+        .unwrap();
+
+        assert!(cascade.tables.is_empty());
+        assert_eq!(cascade.skipped, names(&["mirror"]));
+    }
+
+    #[test]
+    fn test_cascade_rejects_cycles() {
+        let tables = names(&["events", "a", "b"]);
+        let resources = vec![
+            mv("to_a", "events", "a"),
+            mv("a_to_b", "a", "b"),
+            mv("b_to_a", "b", "a"),
+        ];
+
+        let cycle = cascade(&names(&["events"]), &tables, &resources, &HashSet::new());
+
+        assert_eq!(cycle, Err(names(&["a", "b"])));
+    }
+}
@@ -0,0 +1,134 @@
+//! `moose config validate`: strict validation of the project configuration.
+//!
+//! Reports keys of `moose.config.toml` that the CLI ignores, with the nearest
+//! valid key, inconsistent settings, and ON CLUSTER references of tables to
+//! clusters missing from `clickhouse_config.clusters`. Any issue fails the
+//! command with the validation exit code so CI can gate on it.
+
+use super::{RoutineFailure, RoutineSuccess};
+use crate::cli::display::{show_table, Message};
+use crate::cli::exit_code::CliExitCode;
+use crate::framework::core::plan::load_target_infrastructure;
+use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::project::config_validation::ConfigIssue;
+use crate::project::Project;
+
+pub async fn validate_config(project: &Project) -> Result<RoutineSuccess, RoutineFailure> {
+    let mut issues = project.config_issues().map_err(|e| {
+        RoutineFailure::new(
+            Message::new(
+                "Config".to_string(),
+                "Failed to read the project config".to_string(),
+            ),
+            e,
+        )
+        .with_exit_code(CliExitCode::ValidationFailure)
+    })?;
+
+    let infra_map = load_target_infrastructure(project).await.map_err(|e| {
+        RoutineFailure::new(
+            Message::new(
+                "Config".to_string(),
+                "Failed to load the infrastructure map".to_string(),
+            ),
+            e,
+        )
+    })?;
+    issues.extend(missing_clusters(
+        &project.clickhouse_config,
+        infra_map.tables.values().filter_map(|table| {
+            table
+                .cluster_name
+                .as_deref()
+                .map(|cluster| (table.name.as_str(), cluster))
+        }),
+    ));
+
+    if issues.is_empty() {
+        return Ok(RoutineSuccess::success(Message::new(
+            "Config".to_string(),
+            "No issues found".to_string(),
+        )));
+    }
+
+    show_table(
+        "Config issues".to_string(),
+        vec!["Key".to_string(), "Issue".to_string()],
+        issues
+            .iter()
+            .map(|issue| vec![issue.path.clone(), issue.message.clone()])
+            .collect(),
+    );
+    Err(RoutineFailure::error(Message::new(
+        "Config".to_string(),
+        format!("{} issue(s) found", issues.len()),
+    ))
+    .with_exit_code(CliExitCode::ValidationFailure))
+}
+
+/// Clusters referenced by tables, as `(table, cluster)`, that the config does
+/// not define.
+fn missing_clusters<'a>(
+    config: &ClickHouseConfig,
+    references: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<ConfigIssue> {
+    let defined: Vec<&str> = config
+        .clusters
+        .iter()
+        .flatten()
+        .map(|cluster| cluster.name.as_str())
+        .collect();
+    let defined_list = if defined.is_empty() {
+        "none are defined".to_string()
+    } else {
+        format!("defined: {}", defined.join(", "))
+    };
+
+    let mut missing: Vec<(&str, &str)> = references
+        .into_iter()
+        .filter(|(_, cluster)| !defined.contains(cluster))
+        .collect();
+    missing.sort();
+
+    missing
+        .into_iter()
+        .map(|(table, cluster)| ConfigIssue {
+            path: "clickhouse_config.clusters".to_string(),
+            message: format!(
+                "cluster '{cluster}' used by table '{table}' is not configured ({defined_list})"
+            ),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::olap::clickhouse::config::ClusterConfig;
+
+    #[test]
+    fn test_missing_clusters() {
+        let mut config = ClickHouseConfig::default();
+        let references = [("events", "main"), ("audit", "backup"), ("users", "main")];
+
+        let issues = missing_clusters(&config, references);
+        assert_eq!(issues.len(), 3);
+        assert_eq!(
+            issues[0].message,
+            "cluster 'backup' used by table 'audit' is not configured (none are defined)"
+        );
+
+        config.clusters = Some(vec![ClusterConfig {
+            name: "main".to_string(),
+        }]);
+        let issues = missing_clusters(&config, references);
+        assert_eq!(
+            issues,
+            vec![ConfigIssue {
+                path: "clickhouse_config.clusters".to_string(),
+                message: "cluster 'backup' used by table 'audit' is not configured (defined: main)"
+                    .to_string(),
+            }]
+        );
+    }
+}
@@ -24,6 +24,9 @@
 //! machine_id = "uuid-here"
 //! export_metrics = false
 //!
+//! [telemetry.otlp]
+//! endpoint = "http://localhost:4318"
+//!
 //! [features]
 //! metrics_v2 = false
 //! scripts = true
@@ -32,6 +35,7 @@
 use config::{Config, ConfigError, Environment, File};
 use home::home_dir;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use toml_edit::{table, value, DocumentMut, Entry, Item};
 use tracing::warn;
@@ -49,6 +53,39 @@ pub struct MetricLabels {
     pub labels: Option<String>,
     /// Custom endpoints for metric submission
     pub endpoints: Option<String>,
+    /// Spilling of metric batches while their sink is failing
+    #[serde(default)]
+    pub spill: MetricSpillSettings,
+}
+
+/// Local spill file for metric batches the sink rejects, `[metric.spill]`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MetricSpillSettings {
+    /// Consecutive failed sends after which batches are spilled
+    #[serde(default = "MetricSpillSettings::default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Size of the spill file beyond which the oldest batches are dropped
+    #[serde(default = "MetricSpillSettings::default_max_bytes")]
+    pub max_bytes: u64,
+}
+
+impl Default for MetricSpillSettings {
+    fn default() -> Self {
+        Self {
+            failure_threshold: Self::default_failure_threshold(),
+            max_bytes: Self::default_max_bytes(),
+        }
+    }
+}
+
+impl MetricSpillSettings {
+    fn default_failure_threshold() -> u32 {
+        3
+    }
+
+    fn default_max_bytes() -> u64 {
+        50 * 1024 * 1024
+    }
 }
 
 /// Telemetry configuration for usage tracking and metrics
@@ -62,6 +99,9 @@ pub struct Telemetry {
     /// Flag indicating if the user is a Moose developer
     #[serde(default)]
     pub is_moose_developer: bool,
+    /// Export of the webserver metrics to an OpenTelemetry collector
+    #[serde(default)]
+    pub otlp: Option<OtlpSettings>,
 }
 
 impl Default for Telemetry {
@@ -70,10 +110,46 @@ impl Default for Telemetry {
             enabled: true,
             is_moose_developer: false,
             export_metrics: false,
+            otlp: None,
         }
     }
 }
 
+/// OTLP/HTTP metrics export configuration, `[telemetry.otlp]`
+#[derive(Deserialize, Debug, Clone)]
+pub struct OtlpSettings {
+    /// Base URL of the collector's OTLP/HTTP receiver, such as `http://localhost:4318`
+    pub endpoint: String,
+    /// Headers sent with each export, such as an API key
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Seconds between exports
+    #[serde(default = "OtlpSettings::default_interval_secs")]
+    pub interval_secs: u64,
+    /// Attributes added to the exported resource, next to the project name
+    /// and machine id
+    #[serde(default)]
+    pub resource_attributes: HashMap<String, String>,
+    #[serde(default)]
+    pub protocol: OtlpProtocol,
+}
+
+impl OtlpSettings {
+    fn default_interval_secs() -> u64 {
+        60
+    }
+}
+
+/// Encoding of the exported metrics
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OtlpProtocol {
+    #[default]
+    #[serde(rename = "http/protobuf")]
+    HttpProtobuf,
+    #[serde(rename = "http/json")]
+    HttpJson,
+}
+
 /// Feature flag configuration for enabling/disabling functionality
 #[derive(Deserialize, Debug, Clone)]
 pub struct Features {
@@ -513,6 +589,77 @@ pub fn set_docs_default_language(language: &str) -> Result<(), std::io::Error> {
     Ok(())
 }
 
+/// Updates the global CLI config (~/.moose/config.toml) to set the
+/// telemetry.enabled flag using toml_edit. Creates the [telemetry] table if missing.
+pub fn set_telemetry_enabled(enabled: bool) -> Result<(), std::io::Error> {
+    let path = config_path()?;
+    let contents = std::fs::read_to_string(&path)?;
+    let mut doc: DocumentMut = contents
+        .parse()
+        .map_err(|_| std::io::Error::other("Failed to parse CLI config"))?;
+
+    let telemetry_table = match doc.get_mut("telemetry") {
+        Some(Item::Table(t)) => t,
+        Some(_) => {
+            return Err(std::io::Error::other("telemetry in config is not a table."));
+        }
+        None => {
+            doc["telemetry"] = table();
+            doc["telemetry"].as_table_mut().unwrap()
+        }
+    };
+
+    match telemetry_table.entry("enabled") {
+        Entry::Occupied(entry) => *entry.into_mut() = value(enabled),
+        Entry::Vacant(entry) => {
+            entry.insert(value(enabled));
+        }
+    }
+
+    // Unlike other settings, an opt-out must not be silently dropped
+    std::fs::write(&path, doc.to_string())
+}
+
+/// Updates the global CLI config (~/.moose/config.toml) to set the
+/// logger.level value using toml_edit. Creates the [logger] table if missing.
+pub fn set_logger_level(level: &str) -> Result<(), std::io::Error> {
+    let path = config_path()?;
+    let contents = std::fs::read_to_string(&path)?;
+    let mut doc: DocumentMut = contents
+        .parse()
+        .map_err(|_| std::io::Error::other("Failed to parse CLI config"))?;
+
+    let logger_table = match doc.get_mut("logger") {
+        Some(Item::Table(t)) => t,
+        Some(_) => {
+            return Err(std::io::Error::other("logger in config is not a table."));
+        }
+        None => {
+            doc["logger"] = table();
+            doc["logger"].as_table_mut().unwrap()
+        }
+    };
+
+    match logger_table.entry("level") {
+        Entry::Occupied(entry) => *entry.into_mut() = value(level),
+        Entry::Vacant(entry) => {
+            entry.insert(value(level));
+        }
+    }
+
+    if let Err(e) = std::fs::write(&path, doc.to_string()) {
+        if e.kind() == std::io::ErrorKind::PermissionDenied {
+            warn!(
+                "Config file {} is read-only (externally managed); skipping write",
+                path.display()
+            );
+            return Ok(());
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::{DevSettings, Settings};
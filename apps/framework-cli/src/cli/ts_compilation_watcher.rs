@@ -26,6 +26,7 @@
 /// - `InitialCompileHandle`: Handle returned by initial compilation, passed to start()
 use crate::framework;
 use crate::framework::core::infrastructure_map::{ApiChange, InfrastructureMap};
+use crate::framework::core::plan::InfraPlan;
 use display::with_timing_async;
 use serde::Deserialize;
 use std::io::{BufRead, BufReader};
@@ -38,6 +39,7 @@ use super::display::{self, with_spinner_completion_async, Message, MessageType};
 use super::processing_coordinator::ProcessingCoordinator;
 use super::settings::Settings;
 
+use crate::cli::routines::dev::{confirm_destructive_changes, PlanConfirmation};
 use crate::cli::routines::openapi::openapi;
 use crate::framework::core::state_storage::StateStorage;
 use crate::infrastructure::processes::process_registry::ProcessRegistries;
@@ -422,11 +424,11 @@ async fn watch(
                                     display_compilation_success(&event);
 
                                     let project_clone = project.clone();
-                                    let result: anyhow::Result<()> = with_spinner_completion_async(
-                                        "Processing infrastructure changes",
-                                        "Infrastructure changes processed successfully",
-                                        async {
-                                            let plan_result = with_timing_async("Planning", async {
+                                    // Planned and confirmed before the spinner starts, which
+                                    // would draw over the prompt
+                                    let planned: anyhow::Result<Option<InfraPlan>> = async {
+                                        let (_, mut plan_result) =
+                                            with_timing_async("Planning", async {
                                                 // IS_DEV_MODE is set, so ensure_typescript_compiled is a no-op
                                                 // (moose-tspc --watch already compiled)
                                                 framework::core::plan::plan_changes(
@@ -435,91 +437,102 @@ async fn watch(
                                                 )
                                                 .await
                                             })
-                                            .await;
-
-                                            match plan_result {
-                                                Ok((_, plan_result)) => {
-                                                    with_timing_async("Validation", async {
-                                                        framework::core::plan_validator::validate(
-                                                            &project_clone,
-                                                            &plan_result,
-                                                        )
-                                                    })
-                                                    .await?;
+                                            .await?;
+
+                                        with_timing_async("Validation", async {
+                                            framework::core::plan_validator::validate(
+                                                &project_clone,
+                                                &plan_result,
+                                            )
+                                        })
+                                        .await?;
+
+                                        display::show_changes(&plan_result);
+                                        Ok(
+                                            match confirm_destructive_changes(
+                                                &project_clone,
+                                                &mut plan_result,
+                                            )
+                                            .await?
+                                            {
+                                                PlanConfirmation::Apply => Some(plan_result),
+                                                PlanConfirmation::Abort => None,
+                                            },
+                                        )
+                                    }
+                                    .await;
 
-                                                    display::show_changes(&plan_result);
+                                    let result: anyhow::Result<bool> = match planned {
+                                        Ok(Some(plan_result)) => {
+                                            with_spinner_completion_async(
+                                                "Processing infrastructure changes",
+                                                "Infrastructure changes processed successfully",
+                                                async {
                                                     // Hold the mutation guard only for execution/persist steps.
                                                     let _processing_guard =
                                                         processing_coordinator.begin_processing().await;
                                                     let mut project_registries =
                                                         project_registries.write().await;
 
-                                                    let execution_result =
-                                                        with_timing_async("Execution", async {
-                                                            framework::core::execute::execute_online_change(
-                                                                &project_clone,
-                                                                &plan_result,
-                                                                route_update_channel.clone(),
-                                                                webapp_update_channel.clone(),
-                                                                &mut project_registries,
-                                                                metrics.clone(),
-                                                                &settings,
+                                                    with_timing_async("Execution", async {
+                                                        framework::core::execute::execute_online_change(
+                                                            &project_clone,
+                                                            &plan_result,
+                                                            route_update_channel.clone(),
+                                                            webapp_update_channel.clone(),
+                                                            &mut project_registries,
+                                                            metrics.clone(),
+                                                            &settings,
+                                                        )
+                                                        .await
+                                                    })
+                                                    .await?;
+
+                                                    with_timing_async("Persist State", async {
+                                                        state_storage
+                                                            .store_infrastructure_map(
+                                                                &plan_result.target_infra_map,
                                                             )
                                                             .await
-                                                        })
-                                                        .await;
-
-                                                    match execution_result {
-                                                        Ok(_) => {
-                                                            with_timing_async("Persist State", async {
-                                                                state_storage
-                                                                    .store_infrastructure_map(
-                                                                        &plan_result.target_infra_map,
-                                                                    )
-                                                                    .await
-                                                            })
-                                                            .await?;
-
-                                                            with_timing_async("OpenAPI Gen", async {
-                                                                openapi(
-                                                                    &project_clone,
-                                                                    &plan_result.target_infra_map,
-                                                                )
-                                                                .await
-                                                            })
-                                                            .await?;
-
-                                                            let mut infra_ptr =
-                                                                infrastructure_map.write().await;
-                                                            *infra_ptr = plan_result.target_infra_map;
-                                                            Ok(())
-                                                        }
-                                                        Err(e) => {
-                                                            Err(e.into())
-                                                        }
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    Err(e.into())
-                                                }
-                                            }
-                                        },
-                                        {
-                                            use crate::utilities::constants::SHOW_TIMING;
-                                            use std::sync::atomic::Ordering;
-                                            !project.is_production
-                                                && !SHOW_TIMING.load(Ordering::Relaxed)
-                                        },
-                                    )
-                                    .await;
+                                                    })
+                                                    .await?;
+
+                                                    with_timing_async("OpenAPI Gen", async {
+                                                        openapi(
+                                                            &project_clone,
+                                                            &plan_result.target_infra_map,
+                                                        )
+                                                        .await
+                                                    })
+                                                    .await?;
+
+                                                    let mut infra_ptr =
+                                                        infrastructure_map.write().await;
+                                                    *infra_ptr = plan_result.target_infra_map;
+                                                    Ok(true)
+                                                },
+                                                {
+                                                    use crate::utilities::constants::SHOW_TIMING;
+                                                    use std::sync::atomic::Ordering;
+                                                    !project.is_production
+                                                        && !SHOW_TIMING.load(Ordering::Relaxed)
+                                                },
+                                            )
+                                            .await
+                                        }
+                                        // Aborted at the prompt, which reported it
+                                        Ok(None) => Ok(false),
+                                        Err(e) => Err(e),
+                                    };
 
                                     match result {
-                                        Ok(()) => {
+                                        Ok(true) => {
                                             project
                                                 .http_server_config
                                                 .run_after_dev_server_reload_script()
                                                 .await;
                                         }
+                                        Ok(false) => {}
                                         Err(e) => {
                                             show_message!(MessageType::Error, {
                                                 Message {
@@ -10,17 +10,21 @@
 /// ## Main Components:
 /// - `FileWatcher`: The main struct that initializes and starts the file watching process
 /// - `EventListener`: Handles file system events and forwards them to the processing pipeline
-/// - `EventBuckets`: Tracks changes in the app directory with debouncing
+/// - `EventBuckets`: Tracks changes in the watched directories
+/// - `Debouncer`: Coalesces bursts of changes into one recompute
 /// - `WatcherConfig`: Configuration for ignore patterns to prevent infinite loops
+/// - `DevWatcherConfig`: The `[dev.watcher]` debounce, ignore patterns and watch paths
 ///
 /// ## Process Flow:
-/// 1. The watcher monitors the project directory for file changes
+/// 1. The watcher monitors the app directory, or the configured watch paths, for file changes
 /// 2. When changes are detected, they are tracked in EventBuckets
 /// 3. Paths matching ignore patterns are filtered out
-/// 4. After a short delay (debouncing), changes are processed to update the infrastructure
+/// 4. Once no change arrived for the debounce window, changes are processed to update the infrastructure
 /// 5. The updated infrastructure is applied to the system
+/// 6. Changes that arrived while processing queue exactly one more run
 use crate::framework;
 use crate::framework::core::infrastructure_map::{ApiChange, InfrastructureMap};
+use crate::framework::core::plan::InfraPlan;
 use display::with_timing_async;
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use notify::event::ModifyKind;
@@ -32,12 +36,14 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::{io::Error, path::PathBuf};
 use tokio::sync::RwLock;
+use tokio::time::Instant;
 use tracing::info;
 
 use super::display::{self, with_spinner_completion_async, Message, MessageType};
 use super::processing_coordinator::ProcessingCoordinator;
 use super::settings::Settings;
 
+use crate::cli::routines::dev::{confirm_destructive_changes, PlanConfirmation};
 use crate::cli::routines::openapi::openapi;
 use crate::framework::core::state_storage::StateStorage;
 use crate::infrastructure::processes::process_registry::ProcessRegistries;
@@ -54,6 +60,45 @@ pub struct WatcherConfig {
     pub ignore_patterns: Vec<String>,
 }
 
+/// Dev file watcher configuration, `[dev.watcher]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevWatcherConfig {
+    /// How long the watcher waits without changes before recomputing
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: u64,
+    /// Glob patterns for paths to ignore, relative to the watched directory.
+    /// Added to the `[watcher_config]` ones.
+    #[serde(default = "default_ignore_patterns")]
+    pub ignore_patterns: Vec<String>,
+    /// Directories to watch, relative to the project directory. The app
+    /// directory when empty.
+    #[serde(default)]
+    pub watch_paths: Vec<PathBuf>,
+}
+
+fn default_debounce_ms() -> u64 {
+    300
+}
+
+fn default_ignore_patterns() -> Vec<String> {
+    vec![
+        "**/.git/**".to_string(),
+        "**/node_modules/**".to_string(),
+        "**/__pycache__/**".to_string(),
+        "**/*.swp".to_string(),
+    ]
+}
+
+impl Default for DevWatcherConfig {
+    fn default() -> Self {
+        DevWatcherConfig {
+            debounce_ms: default_debounce_ms(),
+            ignore_patterns: default_ignore_patterns(),
+            watch_paths: Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum WatcherConfigError {
     #[error("Invalid glob pattern '{pattern}': {source}")]
@@ -88,6 +133,7 @@ impl WatcherConfig {
 /// It uses a watch channel to communicate with the main processing loop.
 struct EventListener {
     tx: tokio::sync::watch::Sender<EventBuckets>,
+    processing_coordinator: ProcessingCoordinator,
 }
 
 impl EventHandler for EventListener {
@@ -95,10 +141,10 @@ impl EventHandler for EventListener {
         tracing::debug!("Received Watcher event: {:?}", event);
         match event {
             Ok(event) => {
-                self.tx.send_if_modified(|events| {
-                    events.insert(event);
-                    !events.is_empty()
-                });
+                let inserted = self.tx.send_if_modified(|events| events.insert(event));
+                if inserted && self.processing_coordinator.queue_follow_up() {
+                    tracing::debug!("Changes detected during a recompute, queued a follow-up run");
+                }
             }
             Err(e) => {
                 tracing::error!("Watcher Error: {:?}", e);
@@ -107,22 +153,22 @@ impl EventHandler for EventListener {
     }
 }
 
-/// Container for tracking file system events in the app directory.
-/// Implements debouncing by tracking changes until they are processed.
+/// Container for tracking file system events in the watched directories.
+/// Tracks changes until they are processed.
 /// Supports ignore patterns to filter out paths that shouldn't trigger hot-reloads.
 #[derive(Debug)]
 struct EventBuckets {
     changes: HashSet<PathBuf>,
     ignore_matcher: Option<Arc<GlobSet>>,
-    app_dir: PathBuf,
+    watch_dirs: Arc<[PathBuf]>,
 }
 
 impl EventBuckets {
-    pub fn new(ignore_matcher: Option<Arc<GlobSet>>, app_dir: PathBuf) -> Self {
+    pub fn new(ignore_matcher: Option<Arc<GlobSet>>, watch_dirs: Arc<[PathBuf]>) -> Self {
         Self {
             changes: HashSet::new(),
             ignore_matcher,
-            app_dir,
+            watch_dirs,
         }
     }
 
@@ -132,10 +178,14 @@ impl EventBuckets {
     }
 
     /// Checks if a path should be ignored based on configured patterns.
-    /// Patterns are matched against the path relative to the app directory.
+    /// Patterns are matched against the path relative to the watched directory.
     fn is_ignored(&self, path: &Path) -> bool {
         if let Some(ref matcher) = self.ignore_matcher {
-            if let Ok(relative) = path.strip_prefix(&self.app_dir) {
+            if let Some(relative) = self
+                .watch_dirs
+                .iter()
+                .find_map(|dir| path.strip_prefix(dir).ok())
+            {
                 let is_match = matcher.is_match(relative);
                 if is_match {
                     tracing::debug!(
@@ -153,9 +203,11 @@ impl EventBuckets {
     /// Processes a file system event and tracks it if it's relevant.
     /// Only processes events that are relevant (create, modify, remove) and
     /// ignores metadata changes, access events, and paths matching ignore patterns.
-    pub fn insert(&mut self, event: Event) {
+    ///
+    /// Returns whether a change was tracked.
+    pub fn insert(&mut self, event: Event) -> bool {
         match event.kind {
-            EventKind::Access(_) | EventKind::Modify(ModifyKind::Metadata(_)) => return,
+            EventKind::Access(_) | EventKind::Modify(ModifyKind::Metadata(_)) => return false,
             EventKind::Any
             | EventKind::Create(_)
             | EventKind::Modify(_)
@@ -163,6 +215,7 @@ impl EventBuckets {
             | EventKind::Other => {}
         };
 
+        let mut inserted = false;
         for path in event.paths {
             if !path.ext_is_supported_lang() {
                 continue;
@@ -170,12 +223,53 @@ impl EventBuckets {
             if self.is_ignored(&path) {
                 continue;
             }
-            self.changes.insert(path);
+            inserted |= self.changes.insert(path);
         }
 
-        if !self.changes.is_empty() {
+        if inserted {
             info!("App directory changes detected: {:?}", self.changes);
         }
+        inserted
+    }
+}
+
+/// Coalesces bursts of changes: a recompute is due once no change was
+/// recorded for the debounce window.
+#[derive(Debug)]
+struct Debouncer {
+    window: Duration,
+    deadline: Option<Instant>,
+}
+
+impl Debouncer {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            deadline: None,
+        }
+    }
+
+    /// Records a change made at `now`, postponing the recompute
+    fn record(&mut self, now: Instant) {
+        self.deadline = Some(now + self.window);
+    }
+
+    /// When the recompute is due, if changes are pending
+    fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Clears the pending changes when the recompute starts
+    fn reset(&mut self) {
+        self.deadline = None;
+    }
+}
+
+/// Waits until `deadline`, or forever when there is none
+async fn wait_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
     }
 }
 
@@ -184,7 +278,7 @@ impl EventBuckets {
 ///
 /// This function runs in a loop, waiting for file system events, then waits for a period
 /// of inactivity (debouncing) before processing the changes to update the infrastructure
-/// map and apply changes to the system.
+/// map and apply changes to the system. Changes made while processing run it once more.
 ///
 /// # Arguments
 /// * `project` - The project configuration
@@ -197,6 +291,8 @@ impl EventBuckets {
 /// * `settings` - CLI settings configuration
 /// * `processing_coordinator` - Coordinator for synchronizing with MCP tools
 /// * `shutdown_rx` - Receiver to listen for shutdown signal
+/// * `ignore_matcher` - Paths not triggering a recompute
+/// * `watch_dirs` - The directories watched
 #[allow(clippy::too_many_arguments)]
 async fn watch(
     project: Arc<Project>,
@@ -212,35 +308,36 @@ async fn watch(
     processing_coordinator: ProcessingCoordinator,
     mut shutdown_rx: tokio::sync::watch::Receiver<bool>,
     ignore_matcher: Option<Arc<GlobSet>>,
-    app_dir: PathBuf,
+    watch_dirs: Arc<[PathBuf]>,
 ) -> Result<(), anyhow::Error> {
-    tracing::debug!(
-        "Starting file watcher for project: {:?}",
-        project.app_dir().display()
-    );
-
-    if ignore_matcher.is_some() {
-        info!(
-            "File watcher configured with {} ignore pattern(s): {:?}",
-            project.watcher_config.ignore_patterns.len(),
-            project.watcher_config.ignore_patterns
-        );
-    }
+    tracing::debug!("Starting file watcher for project: {:?}", watch_dirs);
 
-    let (tx, mut rx) =
-        tokio::sync::watch::channel(EventBuckets::new(ignore_matcher.clone(), app_dir.clone()));
+    let (tx, mut rx) = tokio::sync::watch::channel(EventBuckets::new(
+        ignore_matcher.clone(),
+        watch_dirs.clone(),
+    ));
     let receiver_ack = tx.clone();
 
-    let mut watcher = RecommendedWatcher::new(EventListener { tx }, notify::Config::default())
-        .map_err(|e| Error::other(format!("Failed to create file watcher: {e}")))?;
-
-    watcher
-        .watch(app_dir.as_ref(), RecursiveMode::Recursive)
-        .map_err(|e| Error::other(format!("Failed to watch file: {e}")))?;
+    let mut watcher = RecommendedWatcher::new(
+        EventListener {
+            tx,
+            processing_coordinator: processing_coordinator.clone(),
+        },
+        notify::Config::default(),
+    )
+    .map_err(|e| Error::other(format!("Failed to create file watcher: {e}")))?;
+
+    for dir in watch_dirs.iter() {
+        watcher
+            .watch(dir, RecursiveMode::Recursive)
+            .map_err(|e| Error::other(format!("Failed to watch {}: {e}", dir.display())))?;
+    }
 
     tracing::debug!("Watcher setup complete, entering main loop");
 
+    let mut debouncer = Debouncer::new(Duration::from_millis(project.dev.watcher.debounce_ms));
     loop {
+        let deadline = debouncer.deadline();
         tokio::select! {
             _ = shutdown_rx.changed() => {
                 info!("Watcher received shutdown signal, stopping file monitoring");
@@ -248,8 +345,10 @@ async fn watch(
             }
             Ok(()) = rx.changed() => {
                 tracing::debug!("Received change notification, current changes: {:?}", rx.borrow().changes);
+                debouncer.record(Instant::now());
             }
-            _ = tokio::time::sleep(Duration::from_secs(1)) => {
+            _ = wait_until(deadline) => {
+                debouncer.reset();
                 let should_process = {
                     let current_changes = rx.borrow();
                     !current_changes.is_empty()
@@ -257,102 +356,104 @@ async fn watch(
 
                 if should_process {
                     tracing::debug!("Debounce period elapsed, processing changes");
-                    receiver_ack.send_replace(EventBuckets::new(ignore_matcher.clone(), app_dir.clone()));
+                    let recompute_guard = processing_coordinator.begin_recompute();
+                    receiver_ack.send_replace(EventBuckets::new(ignore_matcher.clone(), watch_dirs.clone()));
                     rx.mark_unchanged();
 
-                    let result: anyhow::Result<()> = with_spinner_completion_async(
-                        "Processing Infrastructure changes from file watcher",
-                        "Infrastructure changes processed successfully",
-                        async {
-                            let plan_result = with_timing_async("Planning", async {
-                                framework::core::plan::plan_changes(&**state_storage, &project).await
-                            })
-                            .await;
-
-                            match plan_result {
-                                Ok((_, plan_result)) => {
-                                    with_timing_async("Validation", async {
-                                        framework::core::plan_validator::validate(&project, &plan_result)
-                                    })
-                                    .await?;
-
-                                    display::show_changes(&plan_result);
-                                    // Hold the mutation guard only for execution/persist steps.
-                                    let _processing_guard = processing_coordinator.begin_processing().await;
-                                    let mut project_registries = project_registries.write().await;
-
-                                    let execution_result = with_timing_async("Execution", async {
-                                        framework::core::execute::execute_online_change(
-                                            &project,
-                                            &plan_result,
-                                            route_update_channel.clone(),
-                                            webapp_update_channel.clone(),
-                                            &mut project_registries,
-                                            metrics.clone(),
-                                            &settings,
-                                        )
-                                        .await
-                                    })
-                                    .await;
-
-                                    match execution_result {
-                                        Ok(_) => {
-                                            with_timing_async("Persist State", async {
-                                                state_storage
-                                                    .store_infrastructure_map(&plan_result.target_infra_map)
-                                                    .await
-                                            })
-                                            .await?;
-
-                                            with_timing_async("OpenAPI Gen", async {
-                                                openapi(&project, &plan_result.target_infra_map).await
-                                            })
-                                            .await?;
-
-                                            let mut infra_ptr = infrastructure_map.write().await;
-                                            *infra_ptr = plan_result.target_infra_map
-                                        }
-                                        Err(e) => {
-                                            let error: anyhow::Error = e.into();
-                                            show_message!(MessageType::Error, {
-                                                Message {
-                                                    action: "\nFailed".to_string(),
-                                                    details: format!(
-                                                        "Executing changes to the infrastructure failed:\n{error:?}"
-                                                    ),
-                                                }
-                                            });
-                                        }
+                    // Planned and confirmed before the spinner starts, which would
+                    // draw over the prompt
+                    let planned: anyhow::Result<Option<InfraPlan>> = async {
+                        let (_, mut plan_result) = with_timing_async("Planning", async {
+                            framework::core::plan::plan_changes(&**state_storage, &project).await
+                        })
+                        .await?;
+
+                        with_timing_async("Validation", async {
+                            framework::core::plan_validator::validate(&project, &plan_result)
+                        })
+                        .await?;
+
+                        display::show_changes(&plan_result);
+                        Ok(match confirm_destructive_changes(&project, &mut plan_result).await? {
+                            PlanConfirmation::Apply => Some(plan_result),
+                            PlanConfirmation::Abort => None,
+                        })
+                    }
+                    .await;
+
+                    let result: anyhow::Result<bool> = match planned {
+                        Ok(Some(plan_result)) => with_spinner_completion_async(
+                            "Processing Infrastructure changes from file watcher",
+                            "Infrastructure changes processed successfully",
+                            async {
+                                // Hold the mutation guard only for execution/persist steps.
+                                let _processing_guard = processing_coordinator.begin_processing().await;
+                                let mut project_registries = project_registries.write().await;
+
+                                let execution_result = with_timing_async("Execution", async {
+                                    framework::core::execute::execute_online_change(
+                                        &project,
+                                        &plan_result,
+                                        route_update_channel.clone(),
+                                        webapp_update_channel.clone(),
+                                        &mut project_registries,
+                                        metrics.clone(),
+                                        &settings,
+                                    )
+                                    .await
+                                })
+                                .await;
+
+                                match execution_result {
+                                    Ok(_) => {
+                                        with_timing_async("Persist State", async {
+                                            state_storage
+                                                .store_infrastructure_map(&plan_result.target_infra_map)
+                                                .await
+                                        })
+                                        .await?;
+
+                                        with_timing_async("OpenAPI Gen", async {
+                                            openapi(&project, &plan_result.target_infra_map).await
+                                        })
+                                        .await?;
+
+                                        let mut infra_ptr = infrastructure_map.write().await;
+                                        *infra_ptr = plan_result.target_infra_map
+                                    }
+                                    Err(e) => {
+                                        let error: anyhow::Error = e.into();
+                                        show_message!(MessageType::Error, {
+                                            Message {
+                                                action: "\nFailed".to_string(),
+                                                details: format!(
+                                                    "Executing changes to the infrastructure failed:\n{error:?}"
+                                                ),
+                                            }
+                                        });
                                     }
                                 }
-                                Err(e) => {
-                                    let error: anyhow::Error = e.into();
-                                    show_message!(MessageType::Error, {
-                                        Message {
-                                            action: "\nFailed".to_string(),
-                                            details: format!(
-                                                "Planning changes to the infrastructure failed:\n{error:?}"
-                                            ),
-                                        }
-                                    });
-                                }
-                            }
-                            Ok(())
-                        },
-                        {
-                            use crate::utilities::constants::SHOW_TIMING;
-                            use std::sync::atomic::Ordering;
-                            !project.is_production && !SHOW_TIMING.load(Ordering::Relaxed)
-                        },
-                    )
-                    .await;
+                                Ok(true)
+                            },
+                            {
+                                use crate::utilities::constants::SHOW_TIMING;
+                                use std::sync::atomic::Ordering;
+                                !project.is_production && !SHOW_TIMING.load(Ordering::Relaxed)
+                            },
+                        )
+                        .await,
+                        // Aborted at the prompt, which reported it
+                        Ok(None) => Ok(false),
+                        Err(e) => Err(e),
+                    };
                     match result {
-                        Ok(()) => {
+                        Ok(true) => {
                             project
                                 .http_server_config
                                 .run_after_dev_server_reload_script()
                                 .await;
                         }
+                        Ok(false) => {}
                         Err(e) => {
                             show_message!(MessageType::Error, {
                                 Message {
@@ -362,6 +463,11 @@ async fn watch(
                             });
                         }
                     }
+
+                    drop(recompute_guard);
+                    if processing_coordinator.take_follow_up() {
+                        debouncer.record(Instant::now());
+                    }
                 }
             }
         }
@@ -411,21 +517,50 @@ impl FileWatcher {
         processing_coordinator: ProcessingCoordinator,
         shutdown_rx: tokio::sync::watch::Receiver<bool>,
     ) -> Result<(), Error> {
+        // `[watcher_config]` patterns apply on top of the `[dev.watcher]` ones
+        let ignore_config = WatcherConfig {
+            ignore_patterns: project
+                .dev
+                .watcher
+                .ignore_patterns
+                .iter()
+                .chain(&project.watcher_config.ignore_patterns)
+                .cloned()
+                .collect(),
+        };
         // Validate ignore patterns early so errors are shown to the user
-        let ignore_matcher = project
-            .watcher_config
+        let ignore_matcher = ignore_config
             .build_ignore_matcher()
             .map_err(|e| Error::other(format!("Invalid watcher ignore pattern: {e}")))?
             .map(Arc::new);
+        if ignore_matcher.is_some() {
+            info!(
+                "File watcher configured with {} ignore pattern(s): {:?}",
+                ignore_config.ignore_patterns.len(),
+                ignore_config.ignore_patterns
+            );
+        }
 
-        let app_dir = project.app_dir();
+        let watch_dirs: Arc<[PathBuf]> = if project.dev.watcher.watch_paths.is_empty() {
+            Arc::new([project.app_dir()])
+        } else {
+            project
+                .dev
+                .watcher
+                .watch_paths
+                .iter()
+                .map(|path| project.project_location.join(path))
+                .collect()
+        };
 
-        show_message!(MessageType::Info, {
-            Message {
-                action: "Watching".to_string(),
-                details: format!("{:?}", app_dir.display()),
-            }
-        });
+        for dir in watch_dirs.iter() {
+            show_message!(MessageType::Info, {
+                Message {
+                    action: "Watching".to_string(),
+                    details: format!("{:?}", dir.display()),
+                }
+            });
+        }
 
         // Move everything into the spawned task to avoid Send issues
         let watch_task = async move {
@@ -441,7 +576,7 @@ impl FileWatcher {
                 processing_coordinator,
                 shutdown_rx,
                 ignore_matcher,
-                app_dir,
+                watch_dirs,
             )
             .await
         };
@@ -485,7 +620,7 @@ mod tests {
 
     #[test]
     fn test_no_patterns_means_nothing_ignored() {
-        let buckets = EventBuckets::new(None, PathBuf::from("/app"));
+        let buckets = EventBuckets::new(None, Arc::new([PathBuf::from("/app")]));
         assert!(!buckets.is_ignored(Path::new("/app/sdk/client.ts")));
     }
 
@@ -495,7 +630,7 @@ mod tests {
             ignore_patterns: vec!["test?.ts".to_string()],
         };
         let matcher = config.build_ignore_matcher().unwrap().map(Arc::new);
-        let app_dir = PathBuf::from("/project/app");
+        let app_dir: Arc<[PathBuf]> = Arc::new([PathBuf::from("/project/app")]);
         let buckets = EventBuckets::new(matcher, app_dir);
 
         assert!(buckets.is_ignored(Path::new("/project/app/test1.ts")));
@@ -511,7 +646,7 @@ mod tests {
             ignore_patterns: vec!["sdk/**".to_string()],
         };
         let matcher = config.build_ignore_matcher().unwrap().map(Arc::new);
-        let app_dir = PathBuf::from("/project/app");
+        let app_dir: Arc<[PathBuf]> = Arc::new([PathBuf::from("/project/app")]);
         let buckets = EventBuckets::new(matcher, app_dir);
 
         assert!(!buckets.is_ignored(Path::new("/other/path/sdk/client.ts")));
@@ -527,7 +662,7 @@ mod tests {
             ],
         };
         let matcher = config.build_ignore_matcher().unwrap().map(Arc::new);
-        let app_dir = PathBuf::from("/project/app");
+        let app_dir: Arc<[PathBuf]> = Arc::new([PathBuf::from("/project/app")]);
         let buckets = EventBuckets::new(matcher, app_dir);
 
         assert!(buckets.is_ignored(Path::new("/project/app/sdk/client.ts")));
@@ -537,4 +672,120 @@ mod tests {
 
         assert!(!buckets.is_ignored(Path::new("/project/app/datamodels/user.ts")));
     }
+
+    fn modified(path: &str) -> Event {
+        Event::new(EventKind::Modify(ModifyKind::Data(
+            notify::event::DataChange::Content,
+        )))
+        .add_path(PathBuf::from(path))
+    }
+
+    /// Times, in milliseconds, at which recomputes start for changes made at
+    /// `changes`, with a debounce window of `window` milliseconds
+    fn recompute_times(window: u64, changes: &[u64]) -> Vec<u64> {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut debouncer = Debouncer::new(Duration::from_millis(window));
+        let mut runs = Vec::new();
+
+        for &change in changes {
+            if let Some(deadline) = debouncer.deadline().filter(|d| *d <= at(change)) {
+                runs.push((deadline - start).as_millis() as u64);
+                debouncer.reset();
+            }
+            debouncer.record(at(change));
+        }
+        if let Some(deadline) = debouncer.deadline() {
+            runs.push((deadline - start).as_millis() as u64);
+        }
+        runs
+    }
+
+    #[test]
+    fn test_debouncer_coalesces_bursts() {
+        // A burst of saves recomputes once, after the last one
+        assert_eq!(recompute_times(300, &[0, 50, 100, 250]), vec![550]);
+        // Saves further apart than the window recompute separately
+        assert_eq!(recompute_times(300, &[0, 400, 450]), vec![300, 750]);
+        assert_eq!(recompute_times(300, &[0, 300]), vec![300, 600]);
+        assert!(recompute_times(300, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_dev_watcher_config_defaults() {
+        let config: DevWatcherConfig = toml::from_str("debounce_ms = 50").unwrap();
+        assert_eq!(config.debounce_ms, 50);
+        assert_eq!(config.ignore_patterns, default_ignore_patterns());
+        assert!(config.watch_paths.is_empty());
+
+        let config: DevWatcherConfig = toml::from_str(
+            r#"
+            ignore_patterns = ["generated/**"]
+            watch_paths = ["app", "shared"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.debounce_ms, 300);
+        assert_eq!(config.ignore_patterns, vec!["generated/**"]);
+        assert_eq!(
+            config.watch_paths,
+            vec![PathBuf::from("app"), PathBuf::from("shared")]
+        );
+    }
+
+    #[test]
+    fn test_default_ignore_patterns_filter_event_stream() {
+        let config = WatcherConfig {
+            ignore_patterns: DevWatcherConfig::default().ignore_patterns,
+        };
+        let matcher = config.build_ignore_matcher().unwrap().map(Arc::new);
+        let watch_dirs: Arc<[PathBuf]> = Arc::new([
+            PathBuf::from("/project/app"),
+            PathBuf::from("/project/shared"),
+        ]);
+        let mut buckets = EventBuckets::new(matcher, watch_dirs);
+
+        let tracked: Vec<bool> = [
+            modified("/project/app/.git/hooks/pre-commit.py"),
+            modified("/project/app/node_modules/pkg/index.ts"),
+            modified("/project/shared/__pycache__/models.py"),
+            modified("/project/app/.models.py.swp"),
+            modified("/project/app/models.py"),
+            modified("/project/app/models.py"),
+            modified("/project/shared/node_modules/pkg/index.ts"),
+            modified("/project/shared/types.ts"),
+        ]
+        .into_iter()
+        .map(|event| buckets.insert(event))
+        .collect();
+
+        assert_eq!(
+            tracked,
+            vec![false, false, false, false, true, false, false, true]
+        );
+        assert_eq!(
+            buckets.changes,
+            HashSet::from([
+                PathBuf::from("/project/app/models.py"),
+                PathBuf::from("/project/shared/types.ts"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_metadata_and_access_events_are_not_tracked() {
+        let mut buckets = EventBuckets::new(None, Arc::new([PathBuf::from("/app")]));
+
+        assert!(!buckets.insert(
+            Event::new(EventKind::Access(notify::event::AccessKind::Any))
+                .add_path(PathBuf::from("/app/models.py"))
+        ));
+        assert!(!buckets.insert(
+            Event::new(EventKind::Modify(ModifyKind::Metadata(
+                notify::event::MetadataKind::Any
+            )))
+            .add_path(PathBuf::from("/app/models.py"))
+        ));
+        assert!(buckets.is_empty());
+    }
 }
@@ -0,0 +1,377 @@
+//! Latency metrics and budgets of the analytics APIs.
+//!
+//! Every request proxied to an analytics API is timed into
+//! `moose_api_latency_seconds`, labelled with the API name. The API runners
+//! report the time spent in ClickHouse queries in a `Server-Timing: db;dur=<ms>`
+//! response header, which splits that latency into
+//! `moose_api_query_duration_seconds` and the handler overhead,
+//! `moose_api_handler_duration_seconds`.
+//!
+//! APIs given a `latency_budget_ms` in `[http_server_config.api_budgets.<name>]`
+//! are checked against the p95 of their latencies over the last
+//! [`BUDGET_WINDOW`]: a warning is logged when the p95 goes over the budget,
+//! and [`LatencyBudgets::status`] reports it to `moose ls`.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::{exponential_buckets, Histogram};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// How far back the p95 compared to a budget looks.
+pub const BUDGET_WINDOW: Duration = Duration::from_secs(5 * 60);
+/// Requests needed in the window before a budget is checked, so that a
+/// single slow request after startup doesn't flag an API.
+pub const MIN_BUDGET_SAMPLES: usize = 20;
+/// Latencies kept per API, whatever the request rate.
+const MAX_WINDOW_SAMPLES: usize = 10_000;
+
+/// The `Server-Timing` metric carrying the ClickHouse time of a request.
+const QUERY_TIMING_METRIC: &str = "db";
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct ApiLabels {
+    pub api: String,
+}
+
+lazy_static! {
+    /// Seconds taken by each request, from 1ms to about 30 seconds.
+    pub static ref API_LATENCY: Family<ApiLabels, Histogram> =
+        Family::new_with_constructor(|| Histogram::new(exponential_buckets(0.001, 2.0, 16)));
+    /// Seconds of each request spent in ClickHouse queries.
+    pub static ref API_QUERY_DURATION: Family<ApiLabels, Histogram> =
+        Family::new_with_constructor(|| Histogram::new(exponential_buckets(0.001, 2.0, 16)));
+    /// Seconds of each request spent outside of ClickHouse queries.
+    pub static ref API_HANDLER_DURATION: Family<ApiLabels, Histogram> =
+        Family::new_with_constructor(|| Histogram::new(exponential_buckets(0.001, 2.0, 16)));
+    /// Whether the p95 latency of an API is over its budget (1) or not (0).
+    pub static ref API_OVER_BUDGET: Family<ApiLabels, Gauge> = Family::default();
+}
+
+/// Budgets of the analytics APIs checked by the local webserver.
+pub static LATENCY_BUDGETS: LazyLock<LatencyBudgets> = LazyLock::new(LatencyBudgets::default);
+
+/// Latency budget of an analytics API, from the project configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiBudgetConfig {
+    /// Highest acceptable p95 latency, in milliseconds
+    pub latency_budget_ms: u64,
+}
+
+/// Where an API stands against its budget, as served to `moose ls`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BudgetStatus {
+    pub latency_budget_ms: u64,
+    pub p95_ms: u64,
+    pub samples: usize,
+    pub over_budget: bool,
+}
+
+#[derive(Default)]
+struct BudgetWindow {
+    latencies: VecDeque<(Instant, Duration)>,
+    budget: Duration,
+    over_budget: bool,
+}
+
+impl BudgetWindow {
+    fn p95(&self) -> Duration {
+        let mut latencies: Vec<Duration> = self.latencies.iter().map(|(_, l)| *l).collect();
+        latencies.sort_unstable();
+        let rank = (latencies.len() as f64 * 0.95).ceil() as usize;
+        latencies
+            .get(rank.saturating_sub(1))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Rolling windows of the latencies of the APIs that have a budget.
+#[derive(Default)]
+pub struct LatencyBudgets {
+    windows: Mutex<HashMap<String, BudgetWindow>>,
+}
+
+impl LatencyBudgets {
+    /// Adds a request of `api` that took `latency` at `now`, and checks the
+    /// p95 of the window against `budget`. Returns the status when the API
+    /// just went over its budget.
+    pub fn record(
+        &self,
+        api: &str,
+        budget: Duration,
+        latency: Duration,
+        now: Instant,
+    ) -> Option<BudgetStatus> {
+        let mut windows = self.windows.lock().unwrap();
+        let window = windows.entry(api.to_string()).or_default();
+        window.budget = budget;
+        window.latencies.push_back((now, latency));
+        while window.latencies.len() > MAX_WINDOW_SAMPLES
+            || window
+                .latencies
+                .front()
+                .is_some_and(|(at, _)| now.duration_since(*at) > BUDGET_WINDOW)
+        {
+            window.latencies.pop_front();
+        }
+
+        if window.latencies.len() < MIN_BUDGET_SAMPLES {
+            return None;
+        }
+        let p95 = window.p95();
+        let over_budget = p95 > budget;
+        let breached = over_budget && !window.over_budget;
+        let recovered = !over_budget && window.over_budget;
+        window.over_budget = over_budget;
+        API_OVER_BUDGET
+            .get_or_create(&ApiLabels {
+                api: api.to_string(),
+            })
+            .set(over_budget as i64);
+
+        let status = BudgetStatus {
+            latency_budget_ms: budget.as_millis() as u64,
+            p95_ms: p95.as_millis() as u64,
+            samples: window.latencies.len(),
+            over_budget,
+        };
+        if recovered {
+            info!(
+                api,
+                p95_ms = status.p95_ms,
+                latency_budget_ms = status.latency_budget_ms,
+                "Analytics API {} is back within its latency budget",
+                api
+            );
+        }
+        breached.then_some(status)
+    }
+
+    /// Status of every API with a budget that received requests.
+    pub fn status(&self) -> HashMap<String, BudgetStatus> {
+        self.windows
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(api, window)| {
+                (
+                    api.clone(),
+                    BudgetStatus {
+                        latency_budget_ms: window.budget.as_millis() as u64,
+                        p95_ms: window.p95().as_millis() as u64,
+                        samples: window.latencies.len(),
+                        over_budget: window.over_budget,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// The ClickHouse time reported in a `Server-Timing` header, summing the
+/// `db` entries. `None` when the header has none.
+pub fn query_duration(server_timing: &str) -> Option<Duration> {
+    let mut total: Option<f64> = None;
+    for entry in server_timing.split(',') {
+        let mut params = entry.split(';').map(str::trim);
+        if params.next() != Some(QUERY_TIMING_METRIC) {
+            continue;
+        }
+        let duration_ms = params
+            .filter_map(|param| param.strip_prefix("dur="))
+            .find_map(|ms| ms.parse::<f64>().ok())
+            .filter(|ms| ms.is_finite() && *ms >= 0.0);
+        if let Some(ms) = duration_ms {
+            *total.get_or_insert(0.0) += ms;
+        }
+    }
+    total.map(|ms| Duration::from_nanos((ms * 1_000_000.0).round() as u64))
+}
+
+/// Runs the `request` of `api`, which returns its result and the ClickHouse
+/// time reported for it, recording its latency and checking it against
+/// `budget` in `budgets`.
+pub async fn observe_request<T, E>(
+    budgets: &LatencyBudgets,
+    api: &str,
+    budget: Option<&ApiBudgetConfig>,
+    request: impl Future<Output = Result<(T, Option<Duration>), E>>,
+) -> Result<T, E> {
+    let started = Instant::now();
+    let (response, query_time) = request.await?;
+    let latency = started.elapsed();
+
+    let labels = ApiLabels {
+        api: api.to_string(),
+    };
+    API_LATENCY
+        .get_or_create(&labels)
+        .observe(latency.as_secs_f64());
+    if let Some(query_time) = query_time {
+        API_QUERY_DURATION
+            .get_or_create(&labels)
+            .observe(query_time.as_secs_f64());
+        API_HANDLER_DURATION
+            .get_or_create(&labels)
+            .observe(latency.saturating_sub(query_time).as_secs_f64());
+    }
+
+    if let Some(budget) = budget {
+        let budget = Duration::from_millis(budget.latency_budget_ms);
+        if let Some(status) = budgets.record(api, budget, latency, Instant::now()) {
+            warn!(
+                api,
+                p95_ms = status.p95_ms,
+                latency_budget_ms = status.latency_budget_ms,
+                samples = status.samples,
+                "Analytics API {} p95 latency of {}ms is over its {}ms budget",
+                api,
+                status.p95_ms,
+                status.latency_budget_ms
+            );
+        }
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus_client::encoding::text::encode;
+    use prometheus_client::registry::Registry;
+
+    /// An API taking `latency`, `query_time` of which in ClickHouse.
+    async fn slow_handler(
+        latency: Duration,
+        query_time: Duration,
+    ) -> Result<(&'static str, Option<Duration>), String> {
+        tokio::time::sleep(latency).await;
+        Ok(("ok", Some(query_time)))
+    }
+
+    fn budget(ms: u64) -> ApiBudgetConfig {
+        ApiBudgetConfig {
+            latency_budget_ms: ms,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_slow_handler_breaches_budget() {
+        let budgets = LatencyBudgets::default();
+        let budget = budget(250);
+
+        for _ in 0..MIN_BUDGET_SAMPLES {
+            let response = observe_request(
+                &budgets,
+                "api_metrics_slow",
+                Some(&budget),
+                slow_handler(Duration::from_millis(100), Duration::from_millis(60)),
+            )
+            .await;
+            assert_eq!(response, Ok("ok"));
+        }
+        assert!(!budgets.status()["api_metrics_slow"].over_budget);
+
+        // Enough slow requests to move the p95
+        for _ in 0..2 {
+            observe_request(
+                &budgets,
+                "api_metrics_slow",
+                Some(&budget),
+                slow_handler(Duration::from_millis(400), Duration::from_millis(300)),
+            )
+            .await
+            .unwrap();
+        }
+
+        let status = &budgets.status()["api_metrics_slow"];
+        assert!(status.over_budget, "{status:?}");
+        assert_eq!(status.p95_ms, 400);
+        assert_eq!(status.samples, MIN_BUDGET_SAMPLES + 2);
+        let labels = ApiLabels {
+            api: "api_metrics_slow".to_string(),
+        };
+        assert_eq!(API_OVER_BUDGET.get_or_create(&labels).get(), 1);
+        let mut registry = Registry::default();
+        registry.register("handler", "", API_HANDLER_DURATION.clone());
+        let mut exposition = String::new();
+        encode(&mut exposition, &registry).unwrap();
+        let handler_sum = exposition
+            .lines()
+            .find(|line| line.starts_with("handler_sum{api=\"api_metrics_slow\"}"))
+            .expect("no handler duration recorded");
+        // 20 x 40ms + 2 x 100ms outside of ClickHouse
+        let seconds: f64 = handler_sum.rsplit(' ').next().unwrap().parse().unwrap();
+        assert!((1.0..1.01).contains(&seconds), "{handler_sum}");
+
+        // Slow requests age out of the window
+        tokio::time::advance(BUDGET_WINDOW).await;
+        for _ in 0..MIN_BUDGET_SAMPLES {
+            observe_request(
+                &budgets,
+                "api_metrics_slow",
+                Some(&budget),
+                slow_handler(Duration::from_millis(100), Duration::from_millis(60)),
+            )
+            .await
+            .unwrap();
+        }
+        assert!(!budgets.status()["api_metrics_slow"].over_budget);
+        assert_eq!(API_OVER_BUDGET.get_or_create(&labels).get(), 0);
+    }
+
+    #[test]
+    fn test_budget_needs_enough_samples() {
+        let budgets = LatencyBudgets::default();
+        let now = Instant::now();
+        for _ in 0..MIN_BUDGET_SAMPLES - 1 {
+            let breach = budgets.record(
+                "api_metrics_few",
+                Duration::from_millis(10),
+                Duration::from_secs(1),
+                now,
+            );
+            assert_eq!(breach, None);
+        }
+        let breach = budgets.record(
+            "api_metrics_few",
+            Duration::from_millis(10),
+            Duration::from_secs(1),
+            now,
+        );
+        assert!(breach.is_some_and(|status| status.over_budget));
+        // Only reported when going over the budget
+        let breach = budgets.record(
+            "api_metrics_few",
+            Duration::from_millis(10),
+            Duration::from_secs(1),
+            now,
+        );
+        assert_eq!(breach, None);
+    }
+
+    #[test]
+    fn test_query_duration() {
+        assert_eq!(
+            query_duration("db;dur=12.5"),
+            Some(Duration::from_micros(12_500))
+        );
+        assert_eq!(
+            query_duration("cache;desc=\"hit\", db;dur=10, db; dur=5"),
+            Some(Duration::from_millis(15))
+        );
+        assert_eq!(query_duration("total;dur=10"), None);
+        assert_eq!(query_duration("db;dur=-1"), None);
+        assert_eq!(query_duration(""), None);
+    }
+}
@@ -1 +1,2 @@
+pub mod api_metrics;
 pub mod model;
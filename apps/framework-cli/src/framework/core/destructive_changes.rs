@@ -0,0 +1,321 @@
+//! Operations of a plan that lose data, which `moose dev` asks to confirm
+//!
+//! Dropping a table, dropping it to re-create it (e.g. for an ORDER BY change)
+//! and dropping a column lose the data they hold. Before `moose dev` applies a
+//! plan containing such operations, the user can skip some of them.
+//!
+//! A skipped operation is taken out of the plan, and the table it applies to
+//! is kept in the target infrastructure map as it still is in the database.
+//! Once that map is stored, the next plan is computed against it and proposes
+//! the skipped operation again, instead of forgetting it.
+
+use std::collections::HashSet;
+
+use crate::framework::core::infrastructure_map::{ColumnChange, OlapChange, TableChange};
+use crate::framework::core::plan::InfraPlan;
+use crate::infrastructure::olap::ddl_ordering::AtomicOlapOperation;
+
+/// An operation losing data, identified by the id of its table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DestructiveChange {
+    /// A table removed from the code is dropped
+    DropTable { table_id: String },
+    /// A table is dropped to be created again with its new definition
+    RecreateTable { table_id: String },
+    /// A column removed from the code is dropped
+    DropColumn { table_id: String, column: String },
+}
+
+/// The destructive change each of `operations`, ordered from `olap_changes`,
+/// makes, `None` for the operations keeping the data.
+pub fn classify_operations<'a>(
+    olap_changes: &[OlapChange],
+    operations: impl IntoIterator<Item = &'a AtomicOlapOperation>,
+    default_database: &str,
+) -> Vec<Option<DestructiveChange>> {
+    let table_ids = |added: bool| -> HashSet<String> {
+        olap_changes
+            .iter()
+            .filter_map(|change| match change {
+                OlapChange::Table(TableChange::Added(table)) if added => {
+                    Some(table.id(default_database))
+                }
+                OlapChange::Table(TableChange::Removed(table)) if !added => {
+                    Some(table.id(default_database))
+                }
+                _ => None,
+            })
+            .collect()
+    };
+    let added = table_ids(true);
+    let removed = table_ids(false);
+
+    operations
+        .into_iter()
+        .map(|operation| match operation {
+            AtomicOlapOperation::DropTable { table, .. } => {
+                let table_id = table.id(default_database);
+                if !removed.contains(&table_id) {
+                    None
+                } else if added.contains(&table_id) {
+                    Some(DestructiveChange::RecreateTable { table_id })
+                } else {
+                    Some(DestructiveChange::DropTable { table_id })
+                }
+            }
+            AtomicOlapOperation::DropTableColumn {
+                table, column_name, ..
+            } => Some(DestructiveChange::DropColumn {
+                table_id: table.id(default_database),
+                column: column_name.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Takes `skipped` out of the changes of `plan` and keeps their tables in its
+/// target map as they are in the database.
+pub fn skip_changes(plan: &mut InfraPlan, skipped: &[DestructiveChange], default_database: &str) {
+    for change in skipped {
+        match change {
+            DestructiveChange::DropTable { table_id }
+            | DestructiveChange::RecreateTable { table_id } => {
+                let mut kept = None;
+                plan.changes.olap_changes.retain(|change| match change {
+                    OlapChange::Table(TableChange::Removed(table))
+                        if table.id(default_database) == *table_id =>
+                    {
+                        kept = Some(table.clone());
+                        false
+                    }
+                    OlapChange::Table(TableChange::Added(table)) => {
+                        table.id(default_database) != *table_id
+                    }
+                    _ => true,
+                });
+                if let Some(table) = kept {
+                    plan.target_infra_map.tables.insert(table_id.clone(), table);
+                }
+            }
+            DestructiveChange::DropColumn { table_id, column } => {
+                for change in plan.changes.olap_changes.iter_mut() {
+                    let OlapChange::Table(TableChange::Updated {
+                        column_changes,
+                        before,
+                        after,
+                        ..
+                    }) = change
+                    else {
+                        continue;
+                    };
+                    if after.id(default_database) != *table_id {
+                        continue;
+                    }
+
+                    let mut kept = None;
+                    column_changes.retain(|column_change| match column_change {
+                        ColumnChange::Removed(removed) if removed.name == *column => {
+                            kept = Some(removed.clone());
+                            false
+                        }
+                        _ => true,
+                    });
+                    let Some(kept) = kept else {
+                        continue;
+                    };
+
+                    // Back after the column it followed, when that one is kept too
+                    let position = before
+                        .columns
+                        .iter()
+                        .position(|c| c.name == kept.name)
+                        .and_then(|index| index.checked_sub(1))
+                        .and_then(|index| {
+                            after
+                                .columns
+                                .iter()
+                                .position(|c| c.name == before.columns[index].name)
+                        })
+                        .map_or(0, |index| index + 1);
+                    after.columns.insert(position, kept);
+                    plan.target_infra_map
+                        .tables
+                        .insert(table_id.clone(), after.clone());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::table::{Column, ColumnType, OrderBy, Table};
+    use crate::framework::core::infrastructure_map::{
+        InfraChanges, InfrastructureMap, OrderByChange, PartitionByChange, PrimitiveSignature,
+        PrimitiveTypes,
+    };
+    use crate::framework::core::partial_infrastructure_map::LifeCycle;
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+    use crate::infrastructure::olap::ddl_ordering::order_olap_changes;
+
+    const DB: &str = "local";
+
+    fn column(name: &str) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: ColumnType::String,
+            required: true,
+            unique: false,
+            primary_key: name == "id",
+            default: None,
+            annotations: vec![],
+            comment: None,
+            ttl: None,
+            codec: None,
+            materialized: None,
+            alias: None,
+        }
+    }
+
+    fn table(name: &str, columns: &[&str], order_by: &[&str]) -> Table {
+        Table {
+            name: name.to_string(),
+            columns: columns.iter().map(|name| column(name)).collect(),
+            order_by: OrderBy::Fields(order_by.iter().map(|field| field.to_string()).collect()),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    /// A plan dropping `logs`, re-creating `events` with a new ORDER BY and
+    /// dropping the `email` column of `users`
+    fn destructive_plan() -> InfraPlan {
+        let users_before = table("users", &["id", "email", "name"], &["id"]);
+        let users_after = table("users", &["id", "name"], &["id"]);
+        let events_after = table("events", &["id", "at"], &["id", "at"]);
+
+        let mut target_infra_map = InfrastructureMap {
+            default_database: DB.to_string(),
+            ..Default::default()
+        };
+        for table in [&users_after, &events_after] {
+            target_infra_map
+                .tables
+                .insert(table.id(DB), (*table).clone());
+        }
+
+        InfraPlan {
+            target_infra_map,
+            changes: InfraChanges {
+                olap_changes: vec![
+                    OlapChange::Table(TableChange::Removed(table("logs", &["id"], &["id"]))),
+                    OlapChange::Table(TableChange::Removed(table(
+                        "events",
+                        &["id", "at"],
+                        &["id"],
+                    ))),
+                    OlapChange::Table(TableChange::Added(events_after)),
+                    OlapChange::Table(TableChange::Updated {
+                        name: "users".to_string(),
+                        column_changes: vec![ColumnChange::Removed(column("email"))],
+                        order_by_change: OrderByChange {
+                            before: users_before.order_by.clone(),
+                            after: users_after.order_by.clone(),
+                        },
+                        partition_by_change: PartitionByChange {
+                            before: None,
+                            after: None,
+                        },
+                        before: users_before,
+                        after: users_after,
+                    }),
+                ],
+                ..Default::default()
+            },
+        }
+    }
+
+    fn destructive_changes(plan: &InfraPlan) -> Vec<DestructiveChange> {
+        let (teardown, setup) = order_olap_changes(&plan.changes.olap_changes, DB).unwrap();
+        classify_operations(
+            &plan.changes.olap_changes,
+            teardown.iter().chain(&setup),
+            DB,
+        )
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    #[test]
+    fn test_drops_and_recreations_are_destructive() {
+        let mut changes = destructive_changes(&destructive_plan());
+        changes.sort_by_key(|change| format!("{change:?}"));
+
+        assert_eq!(
+            changes,
+            vec![
+                DestructiveChange::DropColumn {
+                    table_id: "local_users".to_string(),
+                    column: "email".to_string(),
+                },
+                DestructiveChange::DropTable {
+                    table_id: "local_logs".to_string(),
+                },
+                DestructiveChange::RecreateTable {
+                    table_id: "local_events".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skipped_changes_are_proposed_again() {
+        let mut plan = destructive_plan();
+        let skipped = destructive_changes(&plan);
+
+        skip_changes(&mut plan, &skipped, DB);
+
+        assert!(destructive_changes(&plan).is_empty());
+        assert!(!plan.changes.olap_changes.iter().any(|change| matches!(
+            change,
+            OlapChange::Table(TableChange::Added(_) | TableChange::Removed(_))
+        )));
+
+        // The stored map holds the tables as they are in the database, so the
+        // next plan drops them again
+        let tables = &plan.target_infra_map.tables;
+        assert!(tables.contains_key("local_logs"));
+        assert_eq!(
+            tables["local_events"].order_by,
+            OrderBy::Fields(vec!["id".to_string()])
+        );
+        let users: Vec<_> = tables["local_users"]
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(users, vec!["id", "email", "name"]);
+    }
+}
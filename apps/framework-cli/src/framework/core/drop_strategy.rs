@@ -0,0 +1,283 @@
+//! Drop strategies for tables removed from the code
+//!
+//! `migration_config.drop_strategy` decides what production plans do with a
+//! table whose model was removed. The `Removed` change is replaced here, in the
+//! plan, so that `moose plan` and generated migrations show the operation that
+//! will actually run:
+//! - `drop` keeps the `DROP TABLE`
+//! - `truncate_only` empties the table and leaves it in the database
+//! - `rename` renames it to `<name>__dropped_<timestamp>`, which
+//!   `moose clean --dropped` purges once the retention has passed
+//!
+//! Only removals are converted. A table dropped to be re-created by the same
+//! plan (e.g. an ORDER BY change) is still dropped, and so are the tables of
+//! engines that hold no data of their own, which are not MergeTree tables.
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use std::collections::HashSet;
+
+use crate::framework::core::infrastructure_map::{OlapChange, TableChange};
+use crate::project::{DropStrategy, MigrationConfig};
+
+/// Separates the original table name from the time it was dropped
+const DROPPED_TABLE_MARKER: &str = "__dropped_";
+const DROPPED_TIMESTAMP_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// Replaces the removals of `olap_changes` according to the configured drop
+/// strategy.
+pub fn apply_drop_strategy(
+    olap_changes: &mut [OlapChange],
+    config: &MigrationConfig,
+    default_database: &str,
+) {
+    apply_drop_strategy_at(
+        olap_changes,
+        config.drop_strategy,
+        default_database,
+        Utc::now(),
+    );
+}
+
+fn apply_drop_strategy_at(
+    olap_changes: &mut [OlapChange],
+    strategy: DropStrategy,
+    default_database: &str,
+    now: DateTime<Utc>,
+) {
+    if strategy == DropStrategy::Drop {
+        return;
+    }
+
+    let recreated: HashSet<String> = olap_changes
+        .iter()
+        .filter_map(|change| match change {
+            OlapChange::Table(TableChange::Added(table)) => Some(table.id(default_database)),
+            _ => None,
+        })
+        .collect();
+
+    for change in olap_changes.iter_mut() {
+        let OlapChange::Table(TableChange::Removed(table)) = change else {
+            continue;
+        };
+        if recreated.contains(&table.id(default_database)) || !table.engine.is_merge_tree_family() {
+            continue;
+        }
+
+        let table = table.clone();
+        *change = OlapChange::Table(match strategy {
+            DropStrategy::Drop => TableChange::Removed(table),
+            DropStrategy::TruncateOnly => TableChange::Truncated(table),
+            DropStrategy::Rename => TableChange::Renamed {
+                new_name: dropped_table_name(&table.name, now),
+                before: table,
+            },
+        });
+    }
+}
+
+/// Name a table dropped at `dropped_at` is renamed to by the `rename` strategy
+pub fn dropped_table_name(table_name: &str, dropped_at: DateTime<Utc>) -> String {
+    format!(
+        "{table_name}{DROPPED_TABLE_MARKER}{}",
+        dropped_at.format(DROPPED_TIMESTAMP_FORMAT)
+    )
+}
+
+/// When the table was dropped, if it was renamed by the `rename` strategy
+pub fn dropped_at(table_name: &str) -> Option<DateTime<Utc>> {
+    let (original_name, timestamp) = table_name.rsplit_once(DROPPED_TABLE_MARKER)?;
+    if original_name.is_empty() {
+        return None;
+    }
+    NaiveDateTime::parse_from_str(timestamp, DROPPED_TIMESTAMP_FORMAT)
+        .ok()
+        .map(|dropped_at| dropped_at.and_utc())
+}
+
+/// The tables renamed by the `rename` strategy more than `retention_days` ago
+pub fn expired_dropped_tables(
+    table_names: &[String],
+    retention_days: u32,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let retention = Duration::days(retention_days.into());
+    table_names
+        .iter()
+        .filter(|name| dropped_at(name).is_some_and(|dropped_at| now - dropped_at >= retention))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::table::{Column, ColumnType, OrderBy, Table};
+    use crate::framework::core::infrastructure_map::{PrimitiveSignature, PrimitiveTypes};
+    use crate::framework::core::partial_infrastructure_map::LifeCycle;
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+    use chrono::TimeZone;
+
+    fn table(name: &str, engine: ClickhouseEngine) -> Table {
+        Table {
+            name: name.to_string(),
+            columns: vec![Column {
+                name: "id".to_string(),
+                data_type: ColumnType::String,
+                required: true,
+                unique: false,
+                primary_key: true,
+                default: None,
+                annotations: vec![],
+                comment: None,
+                ttl: None,
+                codec: None,
+                materialized: None,
+                alias: None,
+            }],
+            order_by: OrderBy::Fields(vec!["id".to_string()]),
+            partition_by: None,
+            sample_by: None,
+            engine,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 14, 15, 9, 26).unwrap()
+    }
+
+    /// A removed table, a table dropped to be re-created and a removed Kafka
+    /// table, with their changes after applying `strategy`
+    fn plan_with(strategy: DropStrategy) -> Vec<OlapChange> {
+        let mut changes = vec![
+            OlapChange::Table(TableChange::Removed(table(
+                "events",
+                ClickhouseEngine::MergeTree,
+            ))),
+            OlapChange::Table(TableChange::Removed(table(
+                "users",
+                ClickhouseEngine::MergeTree,
+            ))),
+            OlapChange::Table(TableChange::Added(table(
+                "users",
+                ClickhouseEngine::MergeTree,
+            ))),
+            OlapChange::Table(TableChange::Removed(table(
+                "ingest",
+                ClickhouseEngine::Kafka {
+                    broker_list: "localhost:9092".to_string(),
+                    topic_list: "ingest".to_string(),
+                    group_name: "moose".to_string(),
+                    format: "JSONEachRow".to_string(),
+                },
+            ))),
+        ];
+        apply_drop_strategy_at(&mut changes, strategy, "local", now());
+        changes
+    }
+
+    /// Only the first change differs between strategies
+    fn assert_others_unchanged(changes: &[OlapChange]) {
+        assert!(matches!(
+            &changes[1],
+            OlapChange::Table(TableChange::Removed(t)) if t.name == "users"
+        ));
+        assert!(matches!(
+            &changes[2],
+            OlapChange::Table(TableChange::Added(t)) if t.name == "users"
+        ));
+        assert!(matches!(
+            &changes[3],
+            OlapChange::Table(TableChange::Removed(t)) if t.name == "ingest"
+        ));
+    }
+
+    #[test]
+    fn test_drop_strategy_keeps_removals() {
+        let changes = plan_with(DropStrategy::Drop);
+
+        assert!(matches!(
+            &changes[0],
+            OlapChange::Table(TableChange::Removed(t)) if t.name == "events"
+        ));
+        assert_others_unchanged(&changes);
+    }
+
+    #[test]
+    fn test_truncate_only_strategy_truncates_removed_tables() {
+        let changes = plan_with(DropStrategy::TruncateOnly);
+
+        assert!(matches!(
+            &changes[0],
+            OlapChange::Table(TableChange::Truncated(t)) if t.name == "events"
+        ));
+        assert_others_unchanged(&changes);
+    }
+
+    #[test]
+    fn test_rename_strategy_renames_removed_tables() {
+        let changes = plan_with(DropStrategy::Rename);
+
+        match &changes[0] {
+            OlapChange::Table(TableChange::Renamed { before, new_name }) => {
+                assert_eq!(before.name, "events");
+                assert_eq!(new_name, "events__dropped_20260314150926");
+            }
+            other => panic!("Expected Renamed, got {other:?}"),
+        }
+        assert_others_unchanged(&changes);
+    }
+
+    #[test]
+    fn test_dropped_at_parses_renamed_table_names() {
+        assert_eq!(
+            dropped_at(&dropped_table_name("events", now())),
+            Some(now())
+        );
+        assert_eq!(
+            dropped_at("a__dropped_b__dropped_20260314150926"),
+            Some(now())
+        );
+        assert_eq!(dropped_at("events"), None);
+        assert_eq!(dropped_at("events__dropped_yesterday"), None);
+        assert_eq!(dropped_at("__dropped_20260314150926"), None);
+    }
+
+    #[test]
+    fn test_expired_dropped_tables() {
+        let tables = vec![
+            "events".to_string(),
+            dropped_table_name("old", now() - Duration::days(8)),
+            dropped_table_name("boundary", now() - Duration::days(7)),
+            dropped_table_name("recent", now() - Duration::days(6)),
+            "notes__dropped_soon".to_string(),
+        ];
+
+        assert_eq!(
+            expired_dropped_tables(&tables, 7, now()),
+            vec![
+                "old__dropped_20260306150926".to_string(),
+                "boundary__dropped_20260307150926".to_string(),
+            ]
+        );
+        assert_eq!(expired_dropped_tables(&tables, 0, now()).len(), 3);
+    }
+}
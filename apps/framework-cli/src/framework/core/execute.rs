@@ -17,11 +17,15 @@ use tokio::sync::mpsc::Sender;
 use super::{
     infrastructure_map::{ApiChange, InfrastructureMap},
     plan::InfraPlan,
+    state_storage::StateStorage,
 };
 use crate::{
     infrastructure::{
         api,
-        olap::{self, OlapChangesError},
+        olap::{
+            self, clickhouse::credentials::SharedClient, plan_fingerprint::PlanFingerprintContext,
+            OlapChangesError,
+        },
         orchestration::workflows,
         processes::{
             self, kafka_clickhouse_sync::SyncingProcessesRegistry,
@@ -62,10 +66,16 @@ pub struct ExecutionContext<'a> {
     pub project: &'a Project,
     pub settings: &'a Settings,
     pub plan: &'a InfraPlan,
+    /// The infrastructure map the plan was computed from
+    pub current_infra_map: &'a InfrastructureMap,
+    /// Keeps the fingerprint of the last applied plan, so it is not applied twice
+    pub state_storage: &'a dyn StateStorage,
     pub skip_olap: bool,
     pub api_changes_channel: Sender<(InfrastructureMap, ApiChange)>,
     pub webapp_changes_channel: Sender<super::infrastructure_map::WebAppChange>,
     pub metrics: Arc<Metrics>,
+    /// ClickHouse client the started processes use
+    pub clickhouse_client: Arc<SharedClient>,
 }
 
 /// Executes the initial infrastructure changes when the system starts up.
@@ -94,7 +104,16 @@ pub async fn execute_initial_infra_change(
     } else {
         // Only execute OLAP changes if OLAP is enabled and not bypassed
         if ctx.project.features.olap && !ctx.skip_olap {
-            olap::execute_changes(ctx.project, &ctx.plan.changes.olap_changes).await?;
+            olap::execute_changes(
+                ctx.project,
+                &ctx.plan.changes.olap_changes,
+                Some(PlanFingerprintContext {
+                    state_storage: ctx.state_storage,
+                    source_infra_map: ctx.current_infra_map,
+                    target_infra_map: &ctx.plan.target_infra_map,
+                }),
+            )
+            .await?;
         }
         // Only execute streaming changes if streaming engine is enabled and not bypassed
         if ctx.project.features.streaming_engine {
@@ -122,10 +141,14 @@ pub async fn execute_initial_infra_change(
 
     let syncing_processes_registry = SyncingProcessesRegistry::new(
         ctx.project.redpanda_config.clone(),
-        ctx.project.clickhouse_config.clone(),
+        ctx.clickhouse_client.clone(),
+    );
+    let mut process_registries = ProcessRegistries::new(
+        ctx.project,
+        ctx.settings,
+        syncing_processes_registry,
+        ctx.clickhouse_client.clone(),
     );
-    let mut process_registries =
-        ProcessRegistries::new(ctx.project, ctx.settings, syncing_processes_registry);
 
     // Execute changes that are allowed on any instance
     let changes = ctx.plan.target_infra_map.init_processes(ctx.project);
@@ -180,7 +203,7 @@ pub async fn execute_online_change(
     } else {
         // Only execute OLAP changes if OLAP is enabled and not bypassed
         if project.features.olap {
-            olap::execute_changes(project, &plan.changes.olap_changes).await?;
+            olap::execute_changes(project, &plan.changes.olap_changes, None).await?;
         }
         // Only execute streaming changes if streaming engine is enabled and not bypassed
         if project.features.streaming_engine {
@@ -97,6 +97,19 @@ impl InfraDiscrepancies {
             && self.missing_views.is_empty()
             && self.mismatched_views.is_empty()
     }
+
+    /// Returns true if every object of the map exists as mapped. Objects only
+    /// found in reality, like tables managed outside Moose, are not considered.
+    pub fn is_map_applied(&self) -> bool {
+        self.missing_tables.is_empty()
+            && self.mismatched_tables.is_empty()
+            && self.missing_sql_resources.is_empty()
+            && self.mismatched_sql_resources.is_empty()
+            && self.missing_materialized_views.is_empty()
+            && self.mismatched_materialized_views.is_empty()
+            && self.missing_views.is_empty()
+            && self.mismatched_views.is_empty()
+    }
 }
 
 /// Normalizes a database reference for comparison.
@@ -881,6 +894,8 @@ mod tests {
                 host_data_path: None,
                 additional_databases: Vec::new(),
                 clusters: None,
+                ddl_parallelism: 4,
+                insert_error_threshold: 10,
             },
             http_server_config: LocalWebserverConfig {
                 proxy_port: crate::cli::local_webserver::default_proxy_port(),
@@ -908,6 +923,8 @@ mod tests {
             docker_config: crate::project::DockerConfig::default(),
             watcher_config: crate::cli::watcher::WatcherConfig::default(),
             dev: crate::project::DevConfig::default(),
+            databases: Default::default(),
+            retries: Default::default(),
         }
     }
 
@@ -949,6 +966,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }
     }
 
@@ -984,6 +1002,8 @@ mod tests {
             materialized_views: HashMap::new(),
             views: HashMap::new(),
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults: Default::default(),
         };
 
         // Create reality checker
@@ -1058,6 +1078,8 @@ mod tests {
             materialized_views: HashMap::new(),
             views: HashMap::new(),
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults: Default::default(),
         };
 
         infra_map
@@ -1138,6 +1160,8 @@ mod tests {
             materialized_views: HashMap::new(),
             views: HashMap::new(),
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults: Default::default(),
         };
 
         infra_map
@@ -1208,6 +1232,8 @@ mod tests {
             materialized_views: HashMap::new(),
             views: HashMap::new(),
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults: Default::default(),
         };
 
         infra_map
@@ -1280,6 +1306,8 @@ mod tests {
             materialized_views: HashMap::new(),
             views: HashMap::new(),
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults: Default::default(),
         };
 
         infra_map
@@ -1332,6 +1360,7 @@ mod tests {
             teardown: vec!["DROP VIEW test_view".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         let infra_resource = SqlResource {
@@ -1344,6 +1373,7 @@ mod tests {
             teardown: vec!["DROP VIEW test_view".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         let mock_client = MockOlapClient {
@@ -1368,6 +1398,8 @@ mod tests {
             materialized_views: HashMap::new(),
             views: HashMap::new(),
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults: Default::default(),
         };
 
         infra_map
@@ -1573,6 +1605,8 @@ mod tests {
             materialized_views: HashMap::new(),
             views: HashMap::new(),
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults: Default::default(),
         };
 
         infra_map
@@ -1640,6 +1674,8 @@ mod tests {
             materialized_views: HashMap::new(),
             views: HashMap::new(),
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults: Default::default(),
         };
 
         infra_map
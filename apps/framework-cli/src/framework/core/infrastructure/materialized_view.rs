@@ -17,6 +17,7 @@ use protobuf::MessageField;
 use serde::{Deserialize, Serialize};
 
 use crate::framework::core::partial_infrastructure_map::LifeCycle;
+use crate::infrastructure::olap::clickhouse::model::{quote_ident, quote_qualified};
 use crate::proto::infrastructure_map::LifeCycle as ProtoLifeCycle;
 use crate::proto::infrastructure_map::{
     MaterializedView as ProtoMaterializedView, SelectQuery as ProtoSelectQuery,
@@ -64,8 +65,8 @@ impl TableReference {
     /// Returns the quoted identifier for use in SQL
     pub fn quoted(&self) -> String {
         match &self.database {
-            Some(db) => format!("`{}`.`{}`", db, self.table),
-            None => format!("`{}`", self.table),
+            Some(db) => quote_qualified(db, &self.table),
+            None => quote_ident(&self.table),
         }
     }
 
@@ -163,16 +164,16 @@ impl MaterializedView {
     /// Returns the quoted view name for SQL
     pub fn quoted_name(&self) -> String {
         match &self.database {
-            Some(db) => format!("`{}`.`{}`", db, self.name),
-            None => format!("`{}`", self.name),
+            Some(db) => quote_qualified(db, &self.name),
+            None => quote_ident(&self.name),
         }
     }
 
     /// Returns the quoted target table name for SQL
     pub fn quoted_target_table(&self) -> String {
         match &self.target_database {
-            Some(db) => format!("`{}`.`{}`", db, self.target_table),
-            None => format!("`{}`", self.target_table),
+            Some(db) => quote_qualified(db, &self.target_table),
+            None => quote_ident(&self.target_table),
         }
     }
 
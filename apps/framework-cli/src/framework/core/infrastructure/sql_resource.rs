@@ -1,7 +1,10 @@
+use crate::framework::core::partial_infrastructure_map::LifeCycle;
 use crate::infrastructure::olap::clickhouse::sql_parser::normalize_sql_for_comparison;
+use crate::proto::infrastructure_map::LifeCycle as ProtoLifeCycle;
 use crate::proto::infrastructure_map::SqlResource as ProtoSqlResource;
 use serde::{Deserialize, Serialize};
 
+use super::table::deserialize_nullable_as_default;
 use super::DataLineage;
 use super::InfrastructureSignature;
 
@@ -48,6 +51,15 @@ pub struct SqlResource {
     /// Signatures of infrastructure components to which this SQL resource pushes data.
     #[serde(alias = "pushesDataTo")]
     pub pushes_data_to: Vec<InfrastructureSignature>,
+
+    /// Lifecycle management policy for the SQL resource.
+    /// Controls whether Moose can run its setup and teardown scripts automatically.
+    #[serde(
+        default,
+        alias = "lifeCycle",
+        deserialize_with = "deserialize_nullable_as_default"
+    )]
+    pub life_cycle: LifeCycle,
 }
 
 impl SqlResource {
@@ -77,6 +89,11 @@ impl SqlResource {
             special_fields: Default::default(),
             pulls_data_from: self.pulls_data_from.iter().map(|s| s.to_proto()).collect(),
             pushes_data_to: self.pushes_data_to.iter().map(|s| s.to_proto()).collect(),
+            life_cycle: match self.life_cycle {
+                LifeCycle::FullyManaged => ProtoLifeCycle::FULLY_MANAGED.into(),
+                LifeCycle::DeletionProtected => ProtoLifeCycle::DELETION_PROTECTED.into(),
+                LifeCycle::ExternallyManaged => ProtoLifeCycle::EXTERNALLY_MANAGED.into(),
+            },
         }
     }
 
@@ -104,6 +121,11 @@ impl SqlResource {
                 .into_iter()
                 .map(InfrastructureSignature::from_proto)
                 .collect(),
+            life_cycle: match proto.life_cycle.enum_value_or_default() {
+                ProtoLifeCycle::FULLY_MANAGED => LifeCycle::FullyManaged,
+                ProtoLifeCycle::DELETION_PROTECTED => LifeCycle::DeletionProtected,
+                ProtoLifeCycle::EXTERNALLY_MANAGED => LifeCycle::ExternallyManaged,
+            },
         }
     }
 }
@@ -189,6 +211,7 @@ mod tests {
             teardown: teardown.into_iter().map(String::from).collect(),
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         }
     }
 
@@ -337,6 +360,7 @@ mod tests {
             teardown: vec![],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
         assert_eq!(resource_with_db.id("default"), "custom_MyView");
 
@@ -351,6 +375,7 @@ mod tests {
             teardown: vec![],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
         assert_eq!(resource_no_db.id("default"), "default_MyView");
     }
@@ -370,6 +395,7 @@ mod tests {
             teardown: vec!["DROP VIEW IF EXISTS MyView".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         let resource_with_db = SqlResource {
@@ -382,6 +408,7 @@ mod tests {
             teardown: vec!["DROP VIEW IF EXISTS MyView".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         // These should be equal because database is not compared in PartialEq
@@ -403,6 +430,7 @@ mod tests {
             teardown: vec!["DROP VIEW IF EXISTS `TestView`".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         let resource_compact = SqlResource {
@@ -417,9 +445,32 @@ mod tests {
             teardown: vec!["DROP VIEW IF EXISTS `TestView`".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         // These should be equal after SQL normalization
         assert_eq!(resource_formatted, resource_compact);
     }
+
+    #[test]
+    fn test_sql_resource_life_cycle_round_trip() {
+        let json = r#"{
+            "name": "MyView",
+            "setup": [],
+            "teardown": [],
+            "pullsDataFrom": [],
+            "pushesDataTo": [],
+            "lifeCycle": "EXTERNALLY_MANAGED"
+        }"#;
+        let resource: SqlResource = serde_json::from_str(json).unwrap();
+        assert_eq!(resource.life_cycle, LifeCycle::ExternallyManaged);
+
+        let restored = SqlResource::from_proto(resource.to_proto());
+        assert_eq!(restored.life_cycle, LifeCycle::ExternallyManaged);
+
+        // Python SDK emits "lifeCycle": null when life_cycle is unset
+        let json = json.replace("\"EXTERNALLY_MANAGED\"", "null");
+        let resource: SqlResource = serde_json::from_str(&json).unwrap();
+        assert_eq!(resource.life_cycle, LifeCycle::FullyManaged);
+    }
 }
@@ -56,19 +56,54 @@ pub const METADATA_PREFIX: &str = "[MOOSE_METADATA:DO_NOT_MODIFY] ";
 /// This allows for future format changes while maintaining backward compatibility.
 pub const METADATA_VERSION: u32 = 1;
 
+/// Maximum length, in bytes, of the metadata Moose appends to a column comment.
+/// Every comment is part of the `CREATE TABLE` query, which ClickHouse caps at
+/// `max_query_size` (256 KiB by default) for all columns together.
+pub const MAX_METADATA_COMMENT_LEN: usize = 32 * 1024;
+
+/// Splits a column comment into the user comment and the metadata appended
+/// after it.
+///
+/// The metadata starts at the first occurrence of `METADATA_PREFIX` followed by
+/// valid metadata JSON up to the end of the comment. Newlines and occurrences
+/// of the prefix that are not followed by metadata stay in the user comment.
+pub fn split_column_comment(comment: &str) -> (&str, Option<ColumnMetadata>) {
+    for (position, _) in comment.match_indices(METADATA_PREFIX) {
+        let json = &comment[position + METADATA_PREFIX.len()..];
+        if let Ok(metadata) = serde_json::from_str::<ColumnMetadata>(json.trim()) {
+            return (comment[..position].trim(), Some(metadata));
+        }
+    }
+    (comment, None)
+}
+
 /// Root structure for column metadata stored in ClickHouse column comments.
 ///
 /// This metadata preserves the original TypeScript enum definitions to solve
 /// the false positive diff issue where TypeScript string enums (e.g., `TEXT = 'text'`)
-/// get converted to ClickHouse integer enums (e.g., `'text' = 1`).
+/// get converted to ClickHouse integer enums (e.g., `'text' = 1`), and the
+/// comments of nested fields, which ClickHouse does not store.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ColumnMetadata {
     /// Version of the metadata format
     pub version: u32,
-    /// Enum definition (currently the only supported metadata type)
-    #[serde(rename = "enum")]
-    pub enum_def: EnumMetadata,
-    // Future fields can be added here with #[serde(skip_serializing_if = "Option::is_none")]
+    /// Enum definition, for enum columns
+    #[serde(rename = "enum", skip_serializing_if = "Option::is_none", default)]
+    pub enum_def: Option<EnumMetadata>,
+    /// Comments of the fields of nested columns
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub fields: Vec<NestedFieldMetadata>,
+}
+
+/// Comment of a field of a nested column
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NestedFieldMetadata {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub comment: Option<String>,
+    /// Comments of the fields of the field, when it is nested too
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub fields: Vec<NestedFieldMetadata>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -395,6 +430,10 @@ pub struct Table {
         deserialize_with = "deserialize_nullable_as_default"
     )]
     pub seed_filter: SeedFilter,
+    /// Free-form key/value data carried through the proto `extensions` field, so
+    /// that properties added by newer CLIs survive a read and rewrite by this one
+    #[serde(skip_serializing_if = "std::collections::HashMap::is_empty", default)]
+    pub extensions: std::collections::HashMap<String, String>,
 }
 
 impl Table {
@@ -760,6 +799,7 @@ impl Table {
                     special_fields: Default::default(),
                 })
             }),
+            extensions: self.extensions.clone(),
             special_fields: Default::default(),
         }
     }
@@ -892,6 +932,7 @@ impl Table {
                     where_clause: sf.where_clause,
                 })
                 .unwrap_or_default(),
+            extensions: proto.extensions,
         }
     }
 }
@@ -1667,6 +1708,62 @@ impl ColumnType {
             T::FixedString(length) => ColumnType::FixedString { length },
         }
     }
+
+    /// Comments of the fields of the nested columns of this type, which
+    /// ClickHouse does not keep in the type. Fields without comments are left out.
+    pub fn nested_field_comments(&self) -> Vec<NestedFieldMetadata> {
+        match self {
+            ColumnType::Nested(nested) => nested
+                .columns
+                .iter()
+                .filter_map(|column| {
+                    let fields = column.data_type.nested_field_comments();
+                    (column.comment.is_some() || !fields.is_empty()).then(|| NestedFieldMetadata {
+                        name: column.name.clone(),
+                        comment: column.comment.clone(),
+                        fields,
+                    })
+                })
+                .collect(),
+            ColumnType::Array { element_type, .. } | ColumnType::Nullable(element_type) => {
+                element_type.nested_field_comments()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Replaces the comments of the fields of the nested columns of this type
+    /// with `comments`, as returned by [`ColumnType::nested_field_comments`].
+    pub fn set_nested_field_comments(&mut self, comments: &[NestedFieldMetadata]) {
+        match self {
+            ColumnType::Nested(nested) => {
+                for column in &mut nested.columns {
+                    let field = comments.iter().find(|field| field.name == column.name);
+                    column.comment = field.and_then(|field| field.comment.clone());
+                    column
+                        .data_type
+                        .set_nested_field_comments(field.map_or(&[], |field| &field.fields));
+                }
+            }
+            ColumnType::Array { element_type, .. } | ColumnType::Nullable(element_type) => {
+                element_type.set_nested_field_comments(comments)
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether this type equals `other` when the comments of nested fields are
+    /// ignored, since changing them does not change the type in ClickHouse.
+    pub fn eq_ignoring_field_comments(&self, other: &ColumnType) -> bool {
+        if self == other {
+            return true;
+        }
+        let mut this = self.clone();
+        this.set_nested_field_comments(&[]);
+        let mut other = other.clone();
+        other.set_nested_field_comments(&[]);
+        this == other
+    }
 }
 
 impl DataEnum {
@@ -1984,6 +2081,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
         assert_eq!(table1.id(DEFAULT_DATABASE_NAME), "local_users");
 
@@ -2091,6 +2189,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Target table from code: explicit order_by that matches primary key
@@ -2118,6 +2217,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // These should be equal because:
@@ -2240,6 +2340,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let canonicalized = table.canonicalize();
@@ -2314,6 +2415,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let canonicalized = table.canonicalize();
@@ -2386,6 +2488,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let canonicalized = table.canonicalize();
@@ -2465,6 +2568,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let first_canonicalize = table.clone().canonicalize();
@@ -2528,6 +2632,7 @@ mod tests {
             cluster_name: Some("clickhouse".to_string()),
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Serialize to proto
@@ -2599,6 +2704,7 @@ mod tests {
             cluster_name: Some("clickhouse".to_string()),
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Serialize to proto
@@ -2766,6 +2872,7 @@ mod tests {
                 limit: Some(100),
                 where_clause: Some("user_id = 10".to_string()),
             },
+            extensions: Default::default(),
         };
 
         let proto = table.to_proto();
@@ -2816,6 +2923,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let proto = table.to_proto();
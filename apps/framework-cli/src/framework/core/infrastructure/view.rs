@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::framework::data_model::model::DataModel;
 use crate::framework::versions::Version;
+use crate::infrastructure::olap::clickhouse::model::{quote_ident, quote_qualified};
 use crate::proto::infrastructure_map::dmv1view;
 use crate::proto::infrastructure_map::Dmv1View as ProtoDmv1View;
 use crate::proto::infrastructure_map::SelectQuery as ProtoSelectQuery;
@@ -86,8 +87,8 @@ impl View {
     /// Returns the quoted view name for SQL
     pub fn quoted_name(&self) -> String {
         match &self.database {
-            Some(db) => format!("`{}`.`{}`", db, self.name),
-            None => format!("`{}`", self.name),
+            Some(db) => quote_qualified(db, &self.name),
+            None => quote_ident(&self.name),
         }
     }
 
@@ -45,7 +45,7 @@ use super::infrastructure::view::{Dmv1View, View};
 use super::infrastructure::web_app::web_apps_equal_ignore_metadata;
 use super::infrastructure::InfrastructureSignature;
 use super::partial_infrastructure_map::LifeCycle;
-use super::partial_infrastructure_map::PartialInfrastructureMap;
+use super::partial_infrastructure_map::{AppliedDatabaseDefaults, PartialInfrastructureMap};
 use crate::cli::display::{show_message_wrapper, Message, MessageType};
 use crate::framework::core::infra_reality_checker::find_table_from_infra_map;
 use crate::framework::core::infrastructure::materialized_view::MaterializedView;
@@ -53,13 +53,14 @@ use crate::framework::core::infrastructure_map::Change::Added;
 use crate::framework::core::lifecycle_filter;
 use crate::framework::languages::SupportedLanguages;
 use crate::framework::python::datamodel_config::load_main_py;
+use crate::framework::scripts::config::WorkflowRetryPolicy;
 use crate::framework::scripts::Workflow;
 use crate::framework::typescript::parser::ensure_typescript_compiled;
 use crate::framework::versions::Version;
 use crate::infrastructure::olap::clickhouse::codec_expressions_are_equivalent;
 use crate::infrastructure::olap::clickhouse::config::DEFAULT_DATABASE_NAME;
 use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
-use crate::infrastructure::olap::clickhouse::IgnorableOperation;
+use crate::infrastructure::olap::clickhouse::{revert_ignored_changes, IgnorableOperation};
 use crate::infrastructure::redis::redis_client::RedisClient;
 use crate::project::Project;
 use crate::proto::infrastructure_map::InfrastructureMap as ProtoInfrastructureMap;
@@ -144,9 +145,14 @@ impl TableDiffStrategy for DefaultTableDiffStrategy {
 #[error("Failed to convert infrastructure map from proto")]
 #[non_exhaustive]
 pub enum InfraMapProtoError {
-    /// Error occurred during protobuf parsing
-    #[error("Failed to parse proto message")]
-    ProtoParseError(#[from] protobuf::Error),
+    /// The bytes could not be decoded as an infrastructure map
+    #[error("Failed to decode the infrastructure map: expected {expected}, found {found}")]
+    Decode {
+        expected: &'static str,
+        found: String,
+        #[source]
+        source: protobuf::Error,
+    },
 
     /// A required field was missing in the protobuf message
     #[error("Missing required field: {field_name}")]
@@ -167,6 +173,25 @@ pub enum InfraMapError {
     #[error("Table {table_id} not found in the infrastructure map")]
     TableNotFound { table_id: String },
 }
+
+/// Describes bytes that are not a valid infrastructure map, hinting at what
+/// they look like instead
+fn describe_undecodable_map(bytes: &[u8]) -> String {
+    let text = bytes.trim_ascii();
+    let is_base64 = |b: &u8| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=');
+    if text.starts_with(b"{") {
+        "JSON text, not protobuf".to_string()
+    } else if bytes.starts_with(&[0x1f, 0x8b]) {
+        "gzip-compressed data that must be decompressed first".to_string()
+    } else if !text.is_empty() && text.len() % 4 == 0 && text.iter().all(is_base64) {
+        "base64 text that must be decoded first".to_string()
+    } else {
+        format!(
+            "{} bytes of malformed protobuf, truncated or written with an incompatible schema",
+            bytes.len()
+        )
+    }
+}
 /// Types of primitives that can be represented in the infrastructure
 ///
 /// These represent the core building blocks of the system that can be
@@ -304,6 +329,15 @@ pub enum TableChange {
     Added(Table),
     /// An existing table has been removed
     Removed(Table),
+    /// A removed table is emptied but kept (`truncate_only` drop strategy)
+    Truncated(Table),
+    /// A removed table is renamed so its data can be recovered (`rename` drop strategy)
+    Renamed {
+        /// The removed table
+        before: Table,
+        /// Name the table is renamed to
+        new_name: String,
+    },
     /// An existing table has been modified
     Updated {
         /// Name of the table that was updated
@@ -595,6 +629,17 @@ pub struct InfrastructureMap {
     /// None for maps created by older CLI versions (pre-version-tracking).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub moose_version: Option<String>,
+
+    /// Free-form key/value data carried through the proto `extensions` field, so
+    /// that map-level properties added by newer CLIs survive a rewrite by this one
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extensions: HashMap<String, String>,
+
+    /// The `[databases.<name>]` defaults each table was built with, indexed by table ID.
+    /// Only known for a map built from the user code, where the plan shows which values
+    /// came from the project config; never stored.
+    #[serde(skip)]
+    pub applied_database_defaults: HashMap<String, AppliedDatabaseDefaults>,
 }
 
 impl InfrastructureMap {
@@ -633,6 +678,8 @@ impl InfrastructureMap {
             materialized_views: Default::default(),
             views: Default::default(),
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults: Default::default(),
         }
     }
 
@@ -874,6 +921,8 @@ impl InfrastructureMap {
             &self.sql_resources,
             &target_map.sql_resources,
             &mut changes.olap_changes,
+            &mut changes.filtered_olap_changes,
+            respect_life_cycle,
         );
         let sql_resource_changes = changes.olap_changes.len() - olap_changes_len_before;
         tracing::info!("SQL Resource changes detected: {}", sql_resource_changes);
@@ -1576,10 +1625,14 @@ impl InfrastructureMap {
     /// * `self_sql_resources` - HashMap of source SQL resources to compare from
     /// * `target_sql_resources` - HashMap of target SQL resources to compare against
     /// * `olap_changes` - Mutable vector to collect the identified changes
+    /// * `filtered_changes` - Mutable vector to collect the changes blocked by lifecycle
+    /// * `respect_life_cycle` - Whether to block changes to protected resources
     fn diff_sql_resources(
         self_sql_resources: &HashMap<String, SqlResource>,
         target_sql_resources: &HashMap<String, SqlResource>,
         olap_changes: &mut Vec<OlapChange>,
+        filtered_changes: &mut Vec<FilteredChange>,
+        respect_life_cycle: bool,
     ) {
         tracing::info!(
             "Analyzing SQL resource differences between {} source resources and {} target resources",
@@ -1596,11 +1649,29 @@ impl InfrastructureMap {
                 if sql_resource != target_sql_resource {
                     // TODO: if only the teardown code changed, we should not need to execute any changes
                     tracing::debug!("SQL resource '{}' has differences", id);
-                    sql_resource_updates += 1;
-                    olap_changes.push(OlapChange::SqlResource(Change::Updated {
+                    let change = OlapChange::SqlResource(Change::Updated {
                         before: Box::new(sql_resource.clone()),
                         after: Box::new(target_sql_resource.clone()),
-                    }));
+                    });
+                    // SQL resource update = teardown + setup. Block if drop-protected.
+                    // Check the AFTER lifecycle (target) to handle transitions.
+                    if respect_life_cycle && target_sql_resource.life_cycle.is_drop_protected() {
+                        tracing::warn!(
+                            "Blocking update of {:?} SQL resource '{}' (update requires teardown+setup)",
+                            target_sql_resource.life_cycle,
+                            id
+                        );
+                        filtered_changes.push(FilteredChange {
+                            reason: format!(
+                                "SqlResource '{}' has {:?} lifecycle - UPDATE (teardown+setup) blocked",
+                                sql_resource.name, target_sql_resource.life_cycle
+                            ),
+                            change,
+                        });
+                    } else {
+                        sql_resource_updates += 1;
+                        olap_changes.push(change);
+                    }
                     // Note: MV population check is no longer needed here.
                     // Moose-lib generated MVs are now emitted as structured MaterializedView types.
                     // Old SqlResource MVs are converted by normalize() to MaterializedView.
@@ -1608,20 +1679,51 @@ impl InfrastructureMap {
                 }
             } else {
                 tracing::debug!("SQL resource '{}' removed", id);
-                sql_resource_removals += 1;
-                olap_changes.push(OlapChange::SqlResource(Change::Removed(Box::new(
-                    sql_resource.clone(),
-                ))));
+                let change =
+                    OlapChange::SqlResource(Change::Removed(Box::new(sql_resource.clone())));
+                // Block removal if drop-protected
+                if respect_life_cycle && sql_resource.life_cycle.is_drop_protected() {
+                    tracing::warn!(
+                        "Blocking removal of {:?} SQL resource '{}'",
+                        sql_resource.life_cycle,
+                        id
+                    );
+                    filtered_changes.push(FilteredChange {
+                        reason: format!(
+                            "SqlResource '{}' has {:?} lifecycle - teardown blocked",
+                            sql_resource.name, sql_resource.life_cycle
+                        ),
+                        change,
+                    });
+                } else {
+                    sql_resource_removals += 1;
+                    olap_changes.push(change);
+                }
             }
         }
 
         for (id, sql_resource) in target_sql_resources {
             if !self_sql_resources.contains_key(id) {
                 tracing::debug!("SQL resource '{}' added", id);
-                sql_resource_additions += 1;
-                olap_changes.push(OlapChange::SqlResource(Change::Added(Box::new(
-                    sql_resource.clone(),
-                ))));
+                let change = OlapChange::SqlResource(Change::Added(Box::new(sql_resource.clone())));
+                // Block creation if externally managed
+                if respect_life_cycle && sql_resource.life_cycle.is_any_modification_protected() {
+                    tracing::warn!(
+                        "Blocking creation of {:?} SQL resource '{}'",
+                        sql_resource.life_cycle,
+                        id
+                    );
+                    filtered_changes.push(FilteredChange {
+                        reason: format!(
+                            "SqlResource '{}' has {:?} lifecycle - setup blocked",
+                            sql_resource.name, sql_resource.life_cycle
+                        ),
+                        change,
+                    });
+                } else {
+                    sql_resource_additions += 1;
+                    olap_changes.push(change);
+                }
                 // Note: MV population check is no longer needed here.
                 // Moose-lib generated MVs are now emitted as structured MaterializedView types.
                 // Old SqlResource MVs are converted by normalize() to MaterializedView.
@@ -2025,6 +2127,10 @@ impl InfrastructureMap {
                             ),
                         });
                     } else {
+                        // Leave the settings and indexes whose changes are
+                        // ignored as the table has them
+                        let target_table = &revert_ignored_changes(table, target_table, ignore_ops);
+
                         // Compute the basic diff components
                         let column_changes =
                             compute_table_columns_diff(table, target_table, ignore_ops);
@@ -2729,6 +2835,7 @@ impl InfrastructureMap {
                 .map(|(k, v)| (k.clone(), v.to_proto()))
                 .collect(),
             moose_version: self.moose_version.clone().unwrap_or_default(),
+            extensions: self.extensions.clone(),
             special_fields: Default::default(),
         }
     }
@@ -2749,7 +2856,13 @@ impl InfrastructureMap {
     /// # Returns
     /// A Result containing either the deserialized map or a proto error
     pub fn from_proto(bytes: Vec<u8>) -> Result<Self, InfraMapProtoError> {
-        let proto = ProtoInfrastructureMap::parse_from_bytes(&bytes)?;
+        let proto = ProtoInfrastructureMap::parse_from_bytes(&bytes).map_err(|source| {
+            InfraMapProtoError::Decode {
+                expected: "a protobuf-encoded moose.proto.InfrastructureMap",
+                found: describe_undecodable_map(&bytes),
+                source,
+            }
+        })?;
         let default_database = proto.default_database.clone();
 
         // Load sql_resources first, then migrate any that are MVs/Views to new format
@@ -2883,6 +2996,8 @@ impl InfrastructureMap {
             } else {
                 Some(proto.moose_version)
             },
+            extensions: proto.extensions,
+            applied_database_defaults: HashMap::new(),
         })
     }
 
@@ -2945,7 +3060,7 @@ impl InfrastructureMap {
             target_table: parsed.target_table,
             target_database: parsed.target_database,
             metadata,
-            life_cycle: crate::framework::core::partial_infrastructure_map::LifeCycle::FullyManaged,
+            life_cycle: sql_resource.life_cycle,
         })
     }
 
@@ -3281,12 +3396,14 @@ impl InfrastructureMap {
             );
         }
 
-        let mut infra_map = partial.into_infra_map(
-            project.language,
-            &project.main_file(),
-            &project.clickhouse_config.db_name,
-            &project.project_location,
-        )?;
+        let mut infra_map = partial
+            .with_database_defaults(project.databases.clone())
+            .into_infra_map(
+                project.language,
+                &project.main_file(),
+                &project.clickhouse_config.db_name,
+                &project.project_location,
+            )?;
 
         // Resolve runtime credentials at runtime if requested
         if resolve_credentials {
@@ -3574,7 +3691,8 @@ fn topics_equal_ignore_metadata(a: &Topic, b: &Topic) -> bool {
 
 /// Check if two tables are equal, ignoring metadata
 ///
-/// Metadata changes (like source file location) should not trigger redeployments.
+/// Metadata changes (like source file location) should not trigger redeployments,
+/// nor should extensions, which code never declares.
 ///
 /// # Arguments
 /// * `a` - The first table to compare
@@ -3589,6 +3707,8 @@ fn tables_equal_ignore_metadata(a: &Table, b: &Table) -> bool {
     b.metadata = None;
     a.seed_filter = Default::default();
     b.seed_filter = Default::default();
+    a.extensions.clear();
+    b.extensions.clear();
     a == b
 }
 
@@ -3636,8 +3756,11 @@ fn api_endpoints_equal_ignore_metadata(a: &ApiEndpoint, b: &ApiEndpoint) -> bool
 #[derive(PartialEq)]
 struct WorkflowConfigComparableForDiff<'a> {
     schedule: &'a str,
+    timezone: Option<&'a str>,
     retries: u32,
     timeout: &'a str,
+    execution_timeout: Option<&'a str>,
+    retry_policy: Option<&'a WorkflowRetryPolicy>,
     pulls_data_from: HashSet<&'a InfrastructureSignature>,
     pushes_data_to: HashSet<&'a InfrastructureSignature>,
 }
@@ -3646,8 +3769,11 @@ impl<'a> From<&'a Workflow> for WorkflowConfigComparableForDiff<'a> {
     fn from(workflow: &'a Workflow) -> Self {
         Self {
             schedule: workflow.config().schedule.as_str(),
+            timezone: workflow.config().timezone.as_deref(),
             retries: workflow.config().retries,
             timeout: workflow.config().timeout.as_str(),
+            execution_timeout: workflow.config().execution_timeout.as_deref(),
+            retry_policy: workflow.config().retry_policy.as_ref(),
             pulls_data_from: workflow.pulls_data_from().iter().collect(),
             pushes_data_to: workflow.pushes_data_to().iter().collect(),
         }
@@ -3656,7 +3782,7 @@ impl<'a> From<&'a Workflow> for WorkflowConfigComparableForDiff<'a> {
 
 /// Check if two workflow configurations are equal
 ///
-/// Compares the schedule, retries, timeout, and lineage settings between two workflows.
+/// Compares the schedule, retries, timeouts, retry policy and lineage settings between two workflows.
 /// These are the values that affect how Temporal runs the workflow and how
 /// downstream lineage should be represented.
 ///
@@ -3766,6 +3892,8 @@ impl Default for InfrastructureMap {
             materialized_views: HashMap::new(),
             views: HashMap::new(),
             moose_version: None, // Not set until storage
+            extensions: HashMap::new(),
+            applied_database_defaults: HashMap::new(),
         }
     }
 }
@@ -3802,6 +3930,8 @@ impl serde::Serialize for InfrastructureMap {
             views: &'a HashMap<String, super::infrastructure::view::View>,
             #[serde(skip_serializing_if = "Option::is_none")]
             moose_version: &'a Option<String>,
+            #[serde(skip_serializing_if = "HashMap::is_empty")]
+            extensions: &'a HashMap<String, String>,
         }
 
         // Mask credentials before serialization (for JSON migration files)
@@ -3825,6 +3955,7 @@ impl serde::Serialize for InfrastructureMap {
             materialized_views: &masked_inframap.materialized_views,
             views: &masked_inframap.views,
             moose_version: &masked_inframap.moose_version,
+            extensions: &masked_inframap.extensions,
         };
 
         // Serialize to JSON value, sort keys, then serialize that
@@ -3921,6 +4052,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let after = Table {
@@ -3990,6 +4122,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let diff = compute_table_columns_diff(&before, &after, &[]);
@@ -4512,6 +4645,7 @@ mod diff_tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }
     }
 
@@ -6225,6 +6359,74 @@ mod diff_tests {
             .count();
         assert_eq!(ttl_not_ignored, 1, "TTL should be detected");
     }
+
+    #[test]
+    fn test_ignore_settings_and_index_operations() {
+        use crate::framework::core::infrastructure::table::TableIndex;
+
+        let index = |name: &str, expression: &str| TableIndex {
+            name: name.to_string(),
+            expression: expression.to_string(),
+            index_type: "minmax".to_string(),
+            arguments: vec![],
+            granularity: 1,
+        };
+
+        // The remote table has a setting and an index added by hand, the code
+        // a new index
+        let mut remote = create_test_table("events", "1.0");
+        remote.table_settings = Some(HashMap::from([(
+            "index_granularity_bytes".to_string(),
+            "0".to_string(),
+        )]));
+        remote.indexes = vec![index("idx_manual", "user_id")];
+        let mut code = create_test_table("events", "1.0");
+        code.indexes = vec![index("idx_ts", "ts")];
+
+        let mut map1 = InfrastructureMap::default();
+        let mut map2 = InfrastructureMap::default();
+        map1.tables
+            .insert(remote.id(DEFAULT_DATABASE_NAME), remote.clone());
+        map2.tables.insert(code.id(DEFAULT_DATABASE_NAME), code);
+        let diff = |ignore_ops: &[IgnorableOperation]| {
+            map1.diff_with_table_strategy(
+                &map2,
+                &DefaultTableDiffStrategy,
+                false,
+                false,
+                ignore_ops,
+            )
+            .olap_changes
+        };
+
+        let changes = diff(&[
+            IgnorableOperation::ModifyTableSettings,
+            IgnorableOperation::DropTableIndex,
+        ]);
+        assert_eq!(changes.len(), 1);
+        match &changes[0] {
+            OlapChange::Table(TableChange::Updated { after, .. }) => {
+                assert_eq!(after.table_settings, remote.table_settings);
+                assert_eq!(
+                    after.indexes,
+                    vec![index("idx_manual", "user_id"), index("idx_ts", "ts")]
+                );
+            }
+            other => panic!("Expected an update of the table, got {other:?}"),
+        }
+
+        let ignore_all = [
+            IgnorableOperation::ModifyTableSettings,
+            IgnorableOperation::AddTableIndex,
+            IgnorableOperation::DropTableIndex,
+        ];
+        assert!(diff(&ignore_all).is_empty());
+
+        let normalized =
+            crate::infrastructure::olap::clickhouse::normalize_table_for_diff(&remote, &ignore_all);
+        assert_eq!(normalized.table_settings, None);
+        assert!(normalized.indexes.is_empty());
+    }
 }
 
 #[cfg(test)]
@@ -6244,6 +6446,7 @@ mod diff_sql_resources_tests {
             teardown: teardown.iter().map(|s| s.to_string()).collect(),
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         }
     }
 
@@ -6257,6 +6460,8 @@ mod diff_sql_resources_tests {
             &self_resources,
             &target_resources,
             &mut olap_changes,
+            &mut vec![],
+            true,
         );
         assert!(olap_changes.is_empty());
     }
@@ -6275,6 +6480,8 @@ mod diff_sql_resources_tests {
             &self_resources,
             &target_resources,
             &mut olap_changes,
+            &mut vec![],
+            true,
         );
         assert!(olap_changes.is_empty());
     }
@@ -6291,6 +6498,8 @@ mod diff_sql_resources_tests {
             &self_resources,
             &target_resources,
             &mut olap_changes,
+            &mut vec![],
+            true,
         );
 
         assert_eq!(olap_changes.len(), 1);
@@ -6314,6 +6523,8 @@ mod diff_sql_resources_tests {
             &self_resources,
             &target_resources,
             &mut olap_changes,
+            &mut vec![],
+            true,
         );
 
         assert_eq!(olap_changes.len(), 1);
@@ -6341,6 +6552,8 @@ mod diff_sql_resources_tests {
             &self_resources,
             &target_resources,
             &mut olap_changes,
+            &mut vec![],
+            true,
         );
 
         assert_eq!(olap_changes.len(), 1);
@@ -6357,6 +6570,46 @@ mod diff_sql_resources_tests {
         }
     }
 
+    #[test]
+    fn test_externally_managed_resources_are_not_changed() {
+        let mut added = create_sql_resource("added", vec!["setup"], vec!["teardown"]);
+        added.life_cycle = LifeCycle::ExternallyManaged;
+        let mut removed = create_sql_resource("removed", vec!["setup"], vec!["teardown"]);
+        removed.life_cycle = LifeCycle::ExternallyManaged;
+        let before = create_sql_resource("updated", vec!["old_setup"], vec!["teardown"]);
+        let mut after = create_sql_resource("updated", vec!["new_setup"], vec!["teardown"]);
+        after.life_cycle = LifeCycle::ExternallyManaged;
+
+        let self_resources = HashMap::from([
+            (removed.name.clone(), removed),
+            (before.name.clone(), before),
+        ]);
+        let target_resources =
+            HashMap::from([(added.name.clone(), added), (after.name.clone(), after)]);
+
+        let mut olap_changes = Vec::new();
+        let mut filtered_changes = Vec::new();
+        InfrastructureMap::diff_sql_resources(
+            &self_resources,
+            &target_resources,
+            &mut olap_changes,
+            &mut filtered_changes,
+            true,
+        );
+        assert!(olap_changes.is_empty());
+        assert_eq!(filtered_changes.len(), 3);
+
+        let mut olap_changes = Vec::new();
+        InfrastructureMap::diff_sql_resources(
+            &self_resources,
+            &target_resources,
+            &mut olap_changes,
+            &mut vec![],
+            false,
+        );
+        assert_eq!(olap_changes.len(), 3);
+    }
+
     #[test]
     fn test_update_resource_teardown() {
         let before_resource = create_sql_resource("res1", vec!["setup1"], vec!["old_teardown"]);
@@ -6373,6 +6626,8 @@ mod diff_sql_resources_tests {
             &self_resources,
             &target_resources,
             &mut olap_changes,
+            &mut vec![],
+            true,
         );
 
         assert_eq!(olap_changes.len(), 1);
@@ -6414,6 +6669,8 @@ mod diff_sql_resources_tests {
             &self_resources,
             &target_resources,
             &mut olap_changes,
+            &mut vec![],
+            true,
         );
 
         assert_eq!(olap_changes.len(), 3); // 1 Update, 1 Remove, 1 Add
@@ -6471,6 +6728,7 @@ mod diff_sql_resources_tests {
             pushes_data_to: vec![InfrastructureSignature::Table {
                 id: "events_summary_table".to_string(),
             }],
+            life_cycle: Default::default(),
         };
 
         let mv_after = SqlResource {
@@ -6487,6 +6745,7 @@ mod diff_sql_resources_tests {
             pushes_data_to: vec![InfrastructureSignature::Table {
                 id: "events_summary_table".to_string(),
             }],
+            life_cycle: Default::default(),
         };
 
         let mut self_resources = HashMap::new();
@@ -6501,6 +6760,8 @@ mod diff_sql_resources_tests {
             &self_resources,
             &target_resources,
             &mut olap_changes,
+            &mut vec![],
+            true,
         );
 
         // Only the Update change is expected - population is handled elsewhere
@@ -7536,6 +7797,7 @@ mod diff_orchestration_worker_tests {
             life_cycle: LifeCycle::FullyManaged,
             database: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let mut kafka_settings = std::collections::HashMap::new();
@@ -7572,6 +7834,7 @@ mod diff_orchestration_worker_tests {
             life_cycle: LifeCycle::FullyManaged,
             database: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         map.tables.insert("s3queue_test".to_string(), s3queue_table);
@@ -7662,6 +7925,7 @@ mod diff_orchestration_worker_tests {
             table_ttl_setting: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let table_without_low_cardinality = Table {
@@ -7717,6 +7981,7 @@ mod diff_orchestration_worker_tests {
             table_ttl_setting: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Test 1: Without ignore flag, should detect difference
@@ -7799,6 +8064,7 @@ mod normalize_tests {
             pushes_data_to: vec![InfrastructureSignature::Table {
                 id: "events_summary".to_string(),
             }],
+            life_cycle: Default::default(),
         };
 
         map.sql_resources
@@ -7857,6 +8123,7 @@ mod normalize_tests {
                 id: "users".to_string(),
             }],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         map.sql_resources
@@ -7905,6 +8172,7 @@ mod normalize_tests {
             teardown: vec!["DROP FUNCTION my_func".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         map.sql_resources
@@ -7946,6 +8214,7 @@ mod normalize_tests {
             teardown: vec!["DROP TABLE fake_mv".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         map.sql_resources
@@ -7983,6 +8252,7 @@ mod normalize_tests {
             teardown: vec!["DROP VIEW IF EXISTS lowercase_mv".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         map.sql_resources
@@ -8021,6 +8291,7 @@ mod normalize_tests {
             teardown: vec!["DROP VIEW IF EXISTS multi_mv".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         map.sql_resources
@@ -8058,6 +8329,7 @@ mod normalize_tests {
             teardown: vec!["DROP VIEW IF EXISTS no_to_mv".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         map.sql_resources.insert("no_to_mv".to_string(), missing_to);
@@ -8223,6 +8495,119 @@ mod diff_workflow_tests {
         }
     }
 
+    #[test]
+    fn test_workflow_retry_policy_change_triggers_update() {
+        let mut current: HashMap<String, Workflow> = HashMap::new();
+        let mut target: HashMap<String, Workflow> = HashMap::new();
+
+        let policy = WorkflowRetryPolicy {
+            initial_interval: Some("1s".to_string()),
+            max_attempts: Some(5),
+            ..Default::default()
+        };
+        let workflow_v1 = create_test_workflow("my_workflow", "1h", 3, "30s")
+            .with_execution_options(Some(policy.clone()), None);
+        let workflow_v2 = create_test_workflow("my_workflow", "1h", 3, "30s")
+            .with_execution_options(
+                Some(WorkflowRetryPolicy {
+                    backoff_coefficient: Some(3.0),
+                    ..policy.clone()
+                }),
+                None,
+            );
+
+        current.insert("my_workflow".to_string(), workflow_v1.clone());
+        target.insert("my_workflow".to_string(), workflow_v2);
+
+        let mut changes = vec![];
+        InfrastructureMap::diff_workflows(&current, &target, &mut changes);
+
+        assert_eq!(changes.len(), 1);
+        match &changes[0] {
+            WorkflowChange::Workflow(Change::Updated { before, after }) => {
+                assert_eq!(before.config().retry_policy.as_ref(), Some(&policy));
+                assert_eq!(
+                    after
+                        .config()
+                        .retry_policy
+                        .as_ref()
+                        .unwrap()
+                        .backoff_coefficient,
+                    Some(3.0)
+                );
+            }
+            _ => panic!("Expected Updated change"),
+        }
+
+        // Removing the policy is an update too
+        target.insert(
+            "my_workflow".to_string(),
+            create_test_workflow("my_workflow", "1h", 3, "30s"),
+        );
+        let mut changes = vec![];
+        InfrastructureMap::diff_workflows(&current, &target, &mut changes);
+        assert_eq!(changes.len(), 1);
+
+        // And an identical policy is not
+        target.insert("my_workflow".to_string(), workflow_v1);
+        let mut changes = vec![];
+        InfrastructureMap::diff_workflows(&current, &target, &mut changes);
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_workflow_execution_timeout_change_triggers_update() {
+        let mut current: HashMap<String, Workflow> = HashMap::new();
+        let mut target: HashMap<String, Workflow> = HashMap::new();
+
+        let workflow_v1 = create_test_workflow("my_workflow", "1h", 3, "30s");
+        let workflow_v2 = create_test_workflow("my_workflow", "1h", 3, "30s")
+            .with_execution_options(None, Some("2h".to_string()));
+
+        current.insert("my_workflow".to_string(), workflow_v1);
+        target.insert("my_workflow".to_string(), workflow_v2);
+
+        let mut changes = vec![];
+        InfrastructureMap::diff_workflows(&current, &target, &mut changes);
+
+        assert_eq!(changes.len(), 1);
+        match &changes[0] {
+            WorkflowChange::Workflow(Change::Updated { before, after }) => {
+                assert_eq!(before.config().execution_timeout, None);
+                assert_eq!(after.config().execution_timeout.as_deref(), Some("2h"));
+            }
+            _ => panic!("Expected Updated change"),
+        }
+    }
+
+    #[test]
+    fn test_workflow_timezone_change_triggers_update() {
+        let mut current: HashMap<String, Workflow> = HashMap::new();
+        let mut target: HashMap<String, Workflow> = HashMap::new();
+
+        current.insert(
+            "my_workflow".to_string(),
+            create_test_workflow("my_workflow", "1h", 3, "30s"),
+        );
+        target.insert(
+            "my_workflow".to_string(),
+            create_test_workflow("my_workflow", "1h", 3, "30s")
+                .with_timezone(Some("America/New_York".to_string())),
+        );
+
+        let mut changes = vec![];
+        InfrastructureMap::diff_workflows(&current, &target, &mut changes);
+
+        assert_eq!(changes.len(), 1);
+        match &changes[0] {
+            WorkflowChange::Workflow(Change::Updated { before, after }) => {
+                assert_eq!(before.config().timezone, None);
+                assert_eq!(after.config().timezone.as_deref(), Some("America/New_York"));
+            }
+            _ => panic!("Expected Updated change"),
+        }
+    }
+
     #[test]
     fn test_workflow_no_change_when_identical() {
         let mut current: HashMap<String, Workflow> = HashMap::new();
@@ -8513,6 +8898,290 @@ mod version_tests {
     }
 }
 
+/// Compatibility of persisted infrastructure maps across CLI versions.
+///
+/// The fixtures in `tests/fixtures/infra_map` are maps in the shapes older CLIs
+/// wrote, and a newer one. A change that breaks one of these tests breaks users
+/// upgrading with existing state. A new schema shape gets a new fixture.
+///
+/// Each fixture is the encoding of its message in [`fixture_maps`], which sets
+/// only the fields `packages/protobuf/infrastructure_map.proto` had when the
+/// shape was written. Field numbers never change, so the current generated
+/// types encode these messages as the releases did. To add or regenerate a
+/// fixture, edit [`fixture_maps`] and run
+/// `cargo test -p moose-cli regenerate_infra_map_fixtures -- --ignored`.
+#[cfg(test)]
+mod proto_compatibility_tests {
+    use super::*;
+    use crate::framework::core::infrastructure::table::ColumnType;
+    use crate::proto::infrastructure_map::{
+        column_type, Column as ProtoColumn, ColumnType as ProtoColumnType,
+        PrimitiveSignature as ProtoPrimitiveSignature, SimpleColumnType, Table as ProtoTable,
+    };
+    use protobuf::well_known_types::wrappers::StringValue;
+    use protobuf::MessageField;
+    use std::path::PathBuf;
+
+    /// Tables without `engine`, one with the deprecated `deduplicate` flag.
+    const ENGINE_UNSET: &[u8] = include_bytes!("../../../tests/fixtures/infra_map/engine_unset.pb");
+    /// Tables with the `order_by` field list and no `order_by2`.
+    const LEGACY_ORDER_BY: &[u8] =
+        include_bytes!("../../../tests/fixtures/infra_map/legacy_order_by.pb");
+    /// A versioned table without `database`, in a map without `moose_version`.
+    const UNVERSIONED_MAP: &[u8] =
+        include_bytes!("../../../tests/fixtures/infra_map/unversioned_map.pb");
+    /// A map of version 99.0.0 with fields this CLI doesn't know. No release
+    /// wrote it, it stands for a future one.
+    const NEWER_WRITER: &[u8] = include_bytes!("../../../tests/fixtures/infra_map/newer_writer.pb");
+
+    /// A table as written before `engine` existed, with a `String` primary key
+    /// `id` and a `DateTime` `ts`
+    fn proto_table(name: &str, order_by: &[&str]) -> ProtoTable {
+        let column = |name: &str, data_type: SimpleColumnType, primary_key: bool| ProtoColumn {
+            name: name.to_string(),
+            data_type: MessageField::some(ProtoColumnType {
+                t: Some(column_type::T::Simple(data_type.into())),
+                special_fields: Default::default(),
+            }),
+            required: true,
+            primary_key,
+            ..Default::default()
+        };
+        ProtoTable {
+            name: name.to_string(),
+            columns: vec![
+                column("id", SimpleColumnType::STRING, true),
+                column("ts", SimpleColumnType::DATETIME, false),
+            ],
+            order_by: order_by.iter().map(|field| field.to_string()).collect(),
+            source_primitive: MessageField::some(ProtoPrimitiveSignature {
+                name: name.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn merge_tree(mut table: ProtoTable) -> ProtoTable {
+        table.engine = MessageField::some(StringValue {
+            value: "MergeTree".to_string(),
+            special_fields: Default::default(),
+        });
+        table
+    }
+
+    fn proto_map(
+        default_database: &str,
+        tables: Vec<(&str, ProtoTable)>,
+    ) -> ProtoInfrastructureMap {
+        ProtoInfrastructureMap {
+            tables: tables
+                .into_iter()
+                .map(|(key, table)| (key.to_string(), table))
+                .collect(),
+            default_database: default_database.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The messages encoded by the fixtures, by file name
+    fn fixture_maps() -> Vec<(&'static str, ProtoInfrastructureMap)> {
+        let engine_unset = proto_map(
+            "local",
+            vec![
+                ("local_events", proto_table("events", &["id"])),
+                (
+                    "local_dedup",
+                    ProtoTable {
+                        deduplicate: true,
+                        ..proto_table("dedup", &["id"])
+                    },
+                ),
+            ],
+        );
+
+        let legacy_order_by = proto_map(
+            "local",
+            vec![
+                (
+                    "local_unordered",
+                    merge_tree(proto_table("unordered", &["tuple()"])),
+                ),
+                (
+                    "local_events",
+                    merge_tree(proto_table("events", &["id", "ts"])),
+                ),
+            ],
+        );
+
+        let unversioned_map = proto_map(
+            "",
+            vec![(
+                "events_1_0",
+                ProtoTable {
+                    version: Some("1.0".to_string()),
+                    ..merge_tree(proto_table("events", &["id"]))
+                },
+            )],
+        );
+
+        let mut events = ProtoTable {
+            database: Some("local".to_string()),
+            extensions: HashMap::from([("retention_class".to_string(), "hot".to_string())]),
+            ..merge_tree(proto_table("events", &["id"]))
+        };
+        events
+            .special_fields
+            .mut_unknown_fields()
+            .add_length_delimited(99, b"unknown table field".to_vec());
+        let mut newer_writer = ProtoInfrastructureMap {
+            moose_version: "99.0.0".to_string(),
+            extensions: HashMap::from([("owner".to_string(), "data-platform".to_string())]),
+            ..proto_map("local", vec![("local_events", events)])
+        };
+        newer_writer
+            .special_fields
+            .mut_unknown_fields()
+            .add_varint(98, 7);
+
+        vec![
+            ("engine_unset.pb", engine_unset),
+            ("legacy_order_by.pb", legacy_order_by),
+            ("unversioned_map.pb", unversioned_map),
+            ("newer_writer.pb", newer_writer),
+        ]
+    }
+
+    fn fixture_path(file: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/infra_map")
+            .join(file)
+    }
+
+    #[test]
+    fn test_fixtures_encode_their_definitions() {
+        for (file, expected) in fixture_maps() {
+            let bytes = fs::read(fixture_path(file)).unwrap();
+            let actual = ProtoInfrastructureMap::parse_from_bytes(&bytes).unwrap();
+            assert_eq!(actual, expected, "{file} differs from its definition");
+        }
+    }
+
+    /// Writes the fixtures from [`fixture_maps`]
+    #[test]
+    #[ignore] // Rewrites tests/fixtures/infra_map
+    fn regenerate_infra_map_fixtures() {
+        for (file, map) in fixture_maps() {
+            fs::write(fixture_path(file), map.write_to_bytes().unwrap()).unwrap();
+        }
+    }
+
+    fn load(fixture: &[u8]) -> InfrastructureMap {
+        InfrastructureMap::from_proto(fixture.to_vec()).expect("fixture should load")
+    }
+
+    #[test]
+    fn test_maps_without_table_engines_must_load() {
+        let map = load(ENGINE_UNSET);
+
+        let events = &map.tables["local_events"];
+        assert_eq!(events.engine, ClickhouseEngine::MergeTree);
+        assert_eq!(events.columns.len(), 2);
+        assert!(events.columns[0].primary_key);
+        assert_eq!(
+            events.columns[1].data_type,
+            ColumnType::DateTime { precision: None }
+        );
+        // The deprecated `deduplicate` flag stood for ReplacingMergeTree
+        assert_eq!(
+            map.tables["local_dedup"].engine,
+            ClickhouseEngine::ReplacingMergeTree {
+                ver: None,
+                is_deleted: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_maps_with_legacy_order_by_must_load() {
+        let map = load(LEGACY_ORDER_BY);
+
+        assert_eq!(
+            map.tables["local_unordered"].order_by,
+            OrderBy::SingleExpr("tuple()".to_string())
+        );
+        assert_eq!(
+            map.tables["local_events"].order_by,
+            OrderBy::Fields(vec!["id".to_string(), "ts".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_maps_without_database_or_version_must_load() {
+        let map = load(UNVERSIONED_MAP);
+
+        assert_eq!(map.moose_version, None);
+        let events = &map.tables["events_1_0"];
+        assert_eq!(events.database, None);
+        assert_eq!(
+            events.version,
+            Some(Version::from_string("1.0".to_string()))
+        );
+        assert!(events.extensions.is_empty());
+    }
+
+    #[test]
+    fn test_maps_from_newer_clis_must_load_ignoring_unknown_fields() {
+        let map = load(NEWER_WRITER);
+
+        assert_eq!(map.moose_version.as_deref(), Some("99.0.0"));
+        assert_eq!(map.extensions["owner"], "data-platform");
+        let events = &map.tables["local_events"];
+        assert_eq!(events.database.as_deref(), Some("local"));
+        assert_eq!(events.extensions["retention_class"], "hot");
+    }
+
+    #[test]
+    fn test_extensions_survive_a_rewrite() {
+        let rewritten = load(&load(NEWER_WRITER).to_proto_bytes());
+
+        assert_eq!(rewritten.extensions["owner"], "data-platform");
+        assert_eq!(
+            rewritten.tables["local_events"].extensions["retention_class"],
+            "hot"
+        );
+    }
+
+    #[test]
+    fn test_extensions_do_not_change_tables() {
+        let map = load(NEWER_WRITER);
+        let mut without_extensions = map.tables["local_events"].clone();
+        without_extensions.extensions.clear();
+
+        assert!(tables_equal_ignore_metadata(
+            &map.tables["local_events"],
+            &without_extensions
+        ));
+    }
+
+    #[test]
+    fn test_corrupted_maps_report_what_was_found() {
+        let found = |bytes: &[u8]| match InfrastructureMap::from_proto(bytes.to_vec()) {
+            Err(InfraMapProtoError::Decode {
+                expected, found, ..
+            }) => {
+                assert!(expected.contains("moose.proto.InfrastructureMap"));
+                found
+            }
+            other => panic!("expected a decode error, got {other:?}"),
+        };
+
+        let truncated = &ENGINE_UNSET[..ENGINE_UNSET.len() - 10];
+        assert!(found(truncated).contains("malformed protobuf"));
+        assert!(found(br#"{"tables": {}}"#).contains("JSON"));
+    }
+}
+
 #[cfg(test)]
 mod mirrorable_external_tables_tests {
     use super::*;
@@ -8562,6 +9231,7 @@ mod mirrorable_external_tables_tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // 2. ExternallyManaged table with Kafka engine (write-only) - should NOT be returned
@@ -8607,6 +9277,7 @@ mod mirrorable_external_tables_tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // 3. FullyManaged table with MergeTree (supports SELECT but wrong lifecycle) - should NOT be returned
@@ -0,0 +1,328 @@
+//! Skip indexes and table settings changed by hand on dev tables
+//!
+//! In dev, a skip index added or a setting changed from clickhouse-client is
+//! not in the code, so the next plan would revert it. At startup,
+//! `moose dev` finds these changes on fully managed tables by comparing the
+//! reconciled state (what ClickHouse has) with the code, and lets the user keep
+//! them. Kept changes are applied to the code's tables in every plan of the
+//! session, until the snippets generated for them are pasted in the model.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{LazyLock, Mutex};
+
+use crate::framework::core::infrastructure::table::{Table, TableIndex};
+use crate::framework::core::infrastructure_map::InfrastructureMap;
+use crate::framework::core::partial_infrastructure_map::LifeCycle;
+use crate::framework::languages::SupportedLanguages;
+use crate::framework::{python, typescript};
+use crate::infrastructure::olap::clickhouse::diff_strategy::READONLY_SETTINGS;
+
+/// Changes kept for the rest of the session
+static KEPT: LazyLock<Mutex<Vec<ManualTableChanges>>> = LazyLock::new(Default::default);
+
+/// Indexes and settings of a live table that are not in the code
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManualTableChanges {
+    /// Id of the table in the infrastructure maps
+    pub table_id: String,
+    pub table_name: String,
+    /// Live indexes not declared in the code. A live index replaces a declared
+    /// index of the same name.
+    pub indexes: Vec<TableIndex>,
+    /// Live value of the settings that differ from the code
+    pub settings: BTreeMap<String, String>,
+}
+
+impl ManualTableChanges {
+    /// Declarations to paste in the table config of the model, which is
+    /// `code_table` with the changes applied.
+    pub fn snippet(&self, language: SupportedLanguages, code_table: &Table) -> String {
+        let mut table = code_table.clone();
+        self.apply(&mut table);
+        let settings = table.table_settings.unwrap_or_default();
+
+        let mut snippet = String::new();
+        match language {
+            SupportedLanguages::Typescript => {
+                if !self.indexes.is_empty() {
+                    snippet.push_str("indexes: [\n");
+                    for index in &self.indexes {
+                        let declaration = typescript::generate::index_declaration(index);
+                        snippet.push_str(&format!("    {declaration},\n"));
+                    }
+                    snippet.push_str("],\n");
+                }
+                if !self.settings.is_empty() {
+                    let declaration = typescript::generate::settings_declaration(&settings);
+                    snippet.push_str(&format!("settings: {declaration},\n"));
+                }
+            }
+            SupportedLanguages::Python => {
+                if !self.indexes.is_empty() {
+                    snippet.push_str("indexes=[\n");
+                    for index in &self.indexes {
+                        let declaration = python::generate::index_declaration(index);
+                        snippet.push_str(&format!("    {declaration},\n"));
+                    }
+                    snippet.push_str("],\n");
+                }
+                if !self.settings.is_empty() {
+                    let declaration = python::generate::settings_declaration(&settings);
+                    snippet.push_str(&format!("settings={declaration},\n"));
+                }
+            }
+        }
+        snippet
+    }
+
+    fn apply(&self, table: &mut Table) {
+        for index in &self.indexes {
+            table.indexes.retain(|declared| declared.name != index.name);
+            table.indexes.push(index.clone());
+        }
+        if !self.settings.is_empty() {
+            let settings = table.table_settings.get_or_insert_with(HashMap::new);
+            settings.extend(self.settings.clone());
+            table.table_settings_hash = table.compute_table_settings_hash();
+        }
+    }
+}
+
+/// Finds the indexes and settings of the fully managed tables of `current`,
+/// the state reconciled with ClickHouse, that `target`, the code, would revert.
+pub fn detect_manual_changes(
+    current: &InfrastructureMap,
+    target: &InfrastructureMap,
+) -> Vec<ManualTableChanges> {
+    let mut changes: Vec<ManualTableChanges> = target
+        .tables
+        .iter()
+        .filter(|(_, table)| table.life_cycle == LifeCycle::FullyManaged)
+        .filter_map(|(id, code_table)| {
+            let live_table = current.tables.get(id)?;
+            let indexes: Vec<TableIndex> = live_table
+                .indexes
+                .iter()
+                .filter(|index| !code_table.indexes.contains(index))
+                .cloned()
+                .collect();
+            let settings = changed_settings(live_table, code_table);
+            if indexes.is_empty() && settings.is_empty() {
+                return None;
+            }
+            Some(ManualTableChanges {
+                table_id: id.clone(),
+                table_name: code_table.name.clone(),
+                indexes,
+                settings,
+            })
+        })
+        .collect();
+    changes.sort_by(|a, b| a.table_id.cmp(&b.table_id));
+    changes
+}
+
+/// Live settings whose value differs from the code. A readonly setting
+/// missing from the code has its default value.
+fn changed_settings(live_table: &Table, code_table: &Table) -> BTreeMap<String, String> {
+    let Some(live_settings) = &live_table.table_settings else {
+        return BTreeMap::new();
+    };
+    let code_settings = code_table.table_settings.clone().unwrap_or_default();
+    live_settings
+        .iter()
+        .filter(|(key, live_value)| {
+            let code_value = code_settings.get(*key).map(String::as_str).or_else(|| {
+                READONLY_SETTINGS
+                    .iter()
+                    .find(|(setting, _)| *setting == key.as_str())
+                    .map(|(_, default)| *default)
+            });
+            code_value != Some(live_value.as_str())
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Keeps `changes` for the rest of the session, see [`apply_kept_changes`].
+pub fn keep_changes(changes: Vec<ManualTableChanges>) {
+    KEPT.lock().unwrap().extend(changes);
+}
+
+/// Applies the kept changes to the tables of `target`, the code, so that
+/// plans do not revert them.
+pub fn apply_kept_changes(target: &mut InfrastructureMap) {
+    for change in KEPT.lock().unwrap().iter() {
+        if let Some(table) = target.tables.get_mut(&change.table_id) {
+            change.apply(table);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::table::{Column, ColumnType, OrderBy};
+    use crate::framework::versions::Version;
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+
+    fn table(indexes: Vec<TableIndex>, settings: &[(&str, &str)]) -> Table {
+        Table {
+            name: "events".to_string(),
+            columns: vec![Column {
+                name: "id".to_string(),
+                data_type: ColumnType::String,
+                required: true,
+                unique: false,
+                primary_key: true,
+                default: None,
+                annotations: vec![],
+                comment: None,
+                ttl: None,
+                codec: None,
+                materialized: None,
+                alias: None,
+            }],
+            order_by: OrderBy::Fields(vec!["id".to_string()]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: Some(Version::from_string("1.0".to_string())),
+            source_primitive: crate::framework::core::infrastructure_map::PrimitiveSignature {
+                name: "events".to_string(),
+                primitive_type:
+                    crate::framework::core::infrastructure_map::PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: (!settings.is_empty()).then(|| {
+                settings
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect()
+            }),
+            indexes,
+            projections: vec![],
+            database: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    fn bloom_filter() -> TableIndex {
+        TableIndex {
+            name: "idx_user".to_string(),
+            expression: "user_id".to_string(),
+            index_type: "bloom_filter".to_string(),
+            arguments: vec!["0.01".to_string()],
+            granularity: 4,
+        }
+    }
+
+    fn maps(live: Table, code: Table) -> (InfrastructureMap, InfrastructureMap) {
+        let mut current = InfrastructureMap::default();
+        current.tables.insert("events_1_0".to_string(), live);
+        let mut target = InfrastructureMap::default();
+        target.tables.insert("events_1_0".to_string(), code);
+        (current, target)
+    }
+
+    #[test]
+    fn test_detects_added_index() {
+        let (current, target) = maps(
+            table(vec![bloom_filter()], &[("index_granularity", "8192")]),
+            table(vec![], &[]),
+        );
+
+        let changes = detect_manual_changes(&current, &target);
+        assert_eq!(
+            changes,
+            vec![ManualTableChanges {
+                table_id: "events_1_0".to_string(),
+                table_name: "events".to_string(),
+                indexes: vec![bloom_filter()],
+                // The default of a readonly setting is not a change
+                settings: BTreeMap::new(),
+            }]
+        );
+
+        let typescript =
+            changes[0].snippet(SupportedLanguages::Typescript, &target.tables["events_1_0"]);
+        assert_eq!(
+            typescript,
+            "indexes: [\n    { name: \"idx_user\", expression: \"user_id\", type: \"bloom_filter\", arguments: [\"0.01\"], granularity: 4 },\n],\n"
+        );
+        let python = changes[0].snippet(SupportedLanguages::Python, &target.tables["events_1_0"]);
+        assert_eq!(
+            python,
+            "indexes=[\n    OlapConfig.TableIndex(name=\"idx_user\", expression=\"user_id\", type=\"bloom_filter\", arguments=[\"0.01\"], granularity=4),\n],\n"
+        );
+    }
+
+    #[test]
+    fn test_detects_changed_setting() {
+        let (current, target) = maps(
+            table(
+                vec![],
+                &[
+                    ("merge_with_ttl_timeout", "3600"),
+                    ("ttl_only_drop_parts", "1"),
+                ],
+            ),
+            table(
+                vec![],
+                &[
+                    ("merge_with_ttl_timeout", "14400"),
+                    ("ttl_only_drop_parts", "1"),
+                ],
+            ),
+        );
+
+        let changes = detect_manual_changes(&current, &target);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].indexes.is_empty());
+        assert_eq!(
+            changes[0].settings,
+            BTreeMap::from([("merge_with_ttl_timeout".to_string(), "3600".to_string())])
+        );
+
+        // The snippets hold all the settings of the table, as they replace the declared ones
+        let code_table = &target.tables["events_1_0"];
+        assert_eq!(
+            changes[0].snippet(SupportedLanguages::Typescript, code_table),
+            "settings: { merge_with_ttl_timeout: \"3600\", ttl_only_drop_parts: \"1\" },\n"
+        );
+        assert_eq!(
+            changes[0].snippet(SupportedLanguages::Python, code_table),
+            "settings={\"merge_with_ttl_timeout\": \"3600\", \"ttl_only_drop_parts\": \"1\"},\n"
+        );
+    }
+
+    #[test]
+    fn test_ignores_tables_not_fully_managed() {
+        let mut code = table(vec![], &[]);
+        code.life_cycle = LifeCycle::DeletionProtected;
+        let (current, target) = maps(table(vec![bloom_filter()], &[]), code);
+
+        assert!(detect_manual_changes(&current, &target).is_empty());
+    }
+
+    #[test]
+    fn test_kept_changes_are_no_longer_detected() {
+        let (current, mut target) = maps(
+            table(vec![bloom_filter()], &[("merge_with_ttl_timeout", "3600")]),
+            table(vec![], &[]),
+        );
+        let changes = detect_manual_changes(&current, &target);
+
+        for change in &changes {
+            change.apply(target.tables.get_mut(&change.table_id).unwrap());
+        }
+        assert!(detect_manual_changes(&current, &target).is_empty());
+    }
+}
@@ -1,9 +1,15 @@
+use crate::framework::core::infrastructure::table::Table;
 use crate::framework::core::infrastructure_map::{InfraChanges, InfrastructureMap};
-use crate::infrastructure::olap::clickhouse::SerializableOlapOperation;
+use crate::infrastructure::olap::clickhouse::{
+    normalize_table_for_diff, IgnorableOperation, SerializableOlapOperation,
+};
 use crate::infrastructure::olap::ddl_ordering::PlanOrderingError;
+use crate::utilities::git::GitRevision;
 use crate::utilities::json;
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 
 /// A comprehensive migration plan that can be reviewed, approved, and executed
 ///
@@ -86,6 +92,87 @@ pub struct MigrationPlanWithBeforeAfter {
     pub db_migration: MigrationPlan,
 }
 
+impl MigrationPlanWithBeforeAfter {
+    /// The summary of this plan, generated against `remote`
+    pub fn summary(
+        &self,
+        remote: String,
+        git: Option<GitRevision>,
+        ignore_operations: &[IgnorableOperation],
+    ) -> MigrationSummary {
+        let mut operations: BTreeMap<String, usize> = BTreeMap::new();
+        for operation in &self.db_migration.operations {
+            *operations.entry(operation.kind()).or_default() += 1;
+        }
+        MigrationSummary {
+            generated_at: self.db_migration.created_at,
+            cli_version: crate::utilities::constants::CLI_VERSION.to_string(),
+            remote,
+            git,
+            operations,
+            before_state_hash: state_hash(&self.remote_state.tables, ignore_operations),
+            after_state_hash: state_hash(&self.local_infra_map.tables, ignore_operations),
+        }
+    }
+}
+
+/// Context of a migration plan for its reviewers, saved next to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationSummary {
+    pub generated_at: DateTime<Utc>,
+    pub cli_version: String,
+    /// URL of the remote with its password redacted, or `serverless:clickhouse`
+    pub remote: String,
+    /// Commit of the code, when it is in a git repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitRevision>,
+    /// Number of operations of each kind
+    pub operations: BTreeMap<String, usize>,
+    /// [`state_hash`] of the remote tables the plan was generated against
+    pub before_state_hash: String,
+    /// [`state_hash`] of the tables of the code
+    pub after_state_hash: String,
+}
+
+impl MigrationSummary {
+    pub fn to_yaml(&self) -> anyhow::Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+}
+
+/// Strips both metadata and ignored fields from tables
+pub fn strip_metadata_and_ignored_fields(
+    tables: &HashMap<String, Table>,
+    ignore_ops: &[IgnorableOperation],
+) -> HashMap<String, Table> {
+    tables
+        .iter()
+        .map(|(name, table)| {
+            let mut table = table.clone();
+            table.metadata = None;
+            // Also strip ignored fields
+            let table = normalize_table_for_diff(&table, ignore_ops);
+            (name.clone(), table)
+        })
+        .collect()
+}
+
+/// Hash of the schemas of `tables`. Like the drift detection of `moose
+/// migrate`, it ignores table metadata and the ignored operations.
+pub fn state_hash(tables: &HashMap<String, Table>, ignore_ops: &[IgnorableOperation]) -> String {
+    let tables = strip_metadata_and_ignored_fields(tables, ignore_ops);
+    let sorted: BTreeMap<&String, serde_json::Value> = tables
+        .iter()
+        .map(|(id, table)| {
+            let value = serde_json::to_value(table).unwrap_or(serde_json::Value::Null);
+            (id, json::sort_json_keys(value))
+        })
+        .collect();
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_vec(&sorted).unwrap_or_default());
+    format!("{:x}", hasher.finalize())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -21,11 +21,14 @@
 /// └──────────────┘                     └──────────────┘
 ///
 pub mod check;
+pub mod destructive_changes;
+pub mod drop_strategy;
 pub mod execute;
 pub mod infra_reality_checker;
 pub mod infrastructure;
 pub mod infrastructure_map;
 pub mod lifecycle_filter;
+pub mod manual_changes;
 pub mod migration_plan;
 pub mod partial_infrastructure_map;
 pub mod plan;
@@ -35,7 +35,7 @@
 //! ```
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
 };
 
@@ -60,10 +60,16 @@ use super::{
 };
 use crate::framework::core::infrastructure::table::{OrderBy, SeedFilter, TableProjection};
 use crate::infrastructure::olap::clickhouse::queries::BufferEngine;
+use crate::project::DatabaseDefaults;
 use crate::{
     framework::{
-        consumption::model::ConsumptionQueryParam, languages::SupportedLanguages,
-        scripts::Workflow, versions::Version,
+        consumption::model::ConsumptionQueryParam,
+        languages::SupportedLanguages,
+        scripts::{
+            config::{WorkflowConfigError, WorkflowRetryPolicy},
+            Workflow,
+        },
+        versions::Version,
     },
     infrastructure::olap::clickhouse::queries::ClickhouseEngine,
     utilities::{constants, normalize_path_string},
@@ -314,6 +320,9 @@ enum EngineConfig {
         #[serde(alias = "tablesRegexp")]
         tables_regexp: String,
     },
+
+    #[serde(rename = "Null")]
+    Null {},
 }
 
 #[derive(Debug, Deserialize)]
@@ -500,6 +509,13 @@ pub struct PartialWorkflow {
     pub retries: Option<u32>,
     pub timeout: Option<String>,
     pub schedule: Option<String>,
+    /// IANA time zone of the schedule, UTC when unset
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub retry_policy: Option<WorkflowRetryPolicy>,
+    #[serde(default)]
+    pub execution_timeout: Option<String>,
     /// Infrastructure components this workflow reads data from (lineage).
     #[serde(default)]
     pub pulls_data_from: Vec<InfrastructureSignature>,
@@ -529,6 +545,13 @@ pub enum DmV2LoadingError {
     /// JSON parsing errors
     JsonParsing(#[from] serde_json::Error),
 
+    /// Invalid schedule, retry or timeout settings of a workflow
+    #[error("Invalid configuration for workflow '{workflow}': {source}")]
+    Workflow {
+        workflow: String,
+        source: WorkflowConfigError,
+    },
+
     /// Runtime environment variable resolution errors
     #[error("Failed to resolve runtime environment variable for table '{table_name}' field '{field}': {error}")]
     RuntimeEnvResolution {
@@ -542,6 +565,23 @@ pub enum DmV2LoadingError {
     Other { message: String },
 }
 
+/// ClickHouse only accepts the `is_deleted` column of a ReplacingMergeTree
+/// after a version column: `ReplacingMergeTree(ver, is_deleted)`.
+fn check_is_deleted_has_ver(
+    table_name: &str,
+    ver: &Option<String>,
+    is_deleted: &Option<String>,
+) -> Result<(), DmV2LoadingError> {
+    match (ver, is_deleted) {
+        (None, Some(is_deleted)) => Err(DmV2LoadingError::Other {
+            message: format!(
+                "Table '{table_name}': the ReplacingMergeTree is_deleted column '{is_deleted}' requires a ver column"
+            ),
+        }),
+        _ => Ok(()),
+    }
+}
+
 /// Represents a partial infrastructure map loaded from user code.
 ///
 /// This structure is the main entry point for loading and converting infrastructure
@@ -597,6 +637,18 @@ pub struct PartialInfrastructureMap {
     /// File paths should be relative to the project root.
     #[serde(default, rename = "unloadedFiles")]
     pub unloaded_files: Vec<String>,
+    /// Table defaults by database, `[databases.<name>]` in the project config
+    #[serde(skip)]
+    database_defaults: BTreeMap<String, DatabaseDefaults>,
+}
+
+/// The `[databases.<name>]` defaults a table was built with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedDatabaseDefaults {
+    /// Database whose defaults were used
+    pub database: String,
+    /// Keys taken from the defaults, e.g. `life_cycle` or `table_settings.storage_policy`
+    pub keys: Vec<String>,
 }
 
 impl PartialInfrastructureMap {
@@ -679,6 +731,16 @@ impl PartialInfrastructureMap {
         }
     }
 
+    /// Sets the table defaults by database, used for the lifecycle, cluster and
+    /// MergeTree settings that a table leaves unset.
+    pub fn with_database_defaults(
+        mut self,
+        database_defaults: BTreeMap<String, DatabaseDefaults>,
+    ) -> Self {
+        self.database_defaults = database_defaults;
+        self
+    }
+
     /// Converts this partial infrastructure map into a complete [`InfrastructureMap`].
     ///
     /// This method performs the final transformation of user-defined infrastructure components
@@ -708,13 +770,13 @@ impl PartialInfrastructureMap {
         default_database: &str,
         project_root: &Path,
     ) -> Result<InfrastructureMap, DmV2LoadingError> {
-        let tables = self.convert_tables(default_database)?;
+        let (tables, applied_database_defaults) = self.convert_tables(default_database)?;
         let topics = self.convert_topics();
         let api_endpoints = self.convert_api_endpoints(main_file, &topics);
         let topic_to_table_sync_processes =
             self.create_topic_to_table_sync_processes(&tables, &topics, default_database);
         let function_processes = self.create_function_processes(main_file, language, &topics);
-        let workflows = self.convert_workflows(language);
+        let workflows = self.convert_workflows(language)?;
         let web_apps = self.convert_web_apps();
 
         // Why does dmv1 InfrastructureMap::new do this?
@@ -741,6 +803,8 @@ impl PartialInfrastructureMap {
             materialized_views: self.materialized_views,
             views: self.views,
             moose_version: None,
+            extensions: Default::default(),
+            applied_database_defaults,
         };
 
         normalize_all_metadata_paths(&mut infra_map, project_root);
@@ -751,7 +815,8 @@ impl PartialInfrastructureMap {
     /// Converts partial table definitions into complete [`Table`] instances.
     ///
     /// This method handles versioning and naming of tables, ensuring that versioned tables
-    /// have appropriate suffixes in their names.
+    /// have appropriate suffixes in their names. Alongside the tables, it returns the
+    /// `[databases.<name>]` defaults each table was built with, by table ID.
     ///
     /// # Errors
     ///
@@ -761,98 +826,160 @@ impl PartialInfrastructureMap {
     fn convert_tables(
         &self,
         default_database: &str,
-    ) -> Result<HashMap<String, Table>, DmV2LoadingError> {
-        self.tables
-            .values()
-            .map(|partial_table| {
-                let version: Option<Version> = partial_table
-                    .version
-                    .as_ref()
-                    .map(|v_str| Version::from_string(v_str.clone()));
+    ) -> Result<
+        (
+            HashMap<String, Table>,
+            HashMap<String, AppliedDatabaseDefaults>,
+        ),
+        DmV2LoadingError,
+    > {
+        let mut tables = HashMap::new();
+        let mut applied_database_defaults = HashMap::new();
+        for partial_table in self.tables.values() {
+            let version: Option<Version> = partial_table
+                .version
+                .as_ref()
+                .map(|v_str| Version::from_string(v_str.clone()));
+
+            let name = version
+                .as_ref()
+                .map_or(partial_table.name.clone(), |version| {
+                    format!("{}_{}", partial_table.name, version.as_suffix())
+                });
 
-                let engine = self.parse_engine(partial_table, default_database)?;
-                let engine_params_hash = Some(engine.non_alterable_params_hash());
-
-                // S3Queue settings should come directly from table_settings in the user code
-                let mut table_settings = partial_table.table_settings.clone().unwrap_or_default();
-
-                // Apply ClickHouse default settings for MergeTree family engines
-                // This ensures our internal representation matches what ClickHouse actually has
-                // and prevents unnecessary diffs
-                let should_apply_mergetree_defaults = engine.is_merge_tree_family();
-
-                if should_apply_mergetree_defaults {
-                    // Apply MergeTree defaults if not explicitly set by user
-                    // These are the most common defaults that appear in system.tables
-
-                    // Index granularity settings (readonly after table creation)
-                    table_settings
-                        .entry("index_granularity".to_string())
-                        .or_insert("8192".to_string());
-                    table_settings
-                        .entry("index_granularity_bytes".to_string())
-                        .or_insert("10485760".to_string()); // 10 * 1024 * 1024
-
-                    // In ClickHouse 19.11+, this defaults to true (readonly after creation)
-                    table_settings
-                        .entry("enable_mixed_granularity_parts".to_string())
-                        .or_insert("1".to_string()); // true = 1 in ClickHouse settings
-
-                    // Note: We don't set other defaults like:
-                    // - min_bytes_for_wide_part (defaults to 10485760 but is modifiable)
-                    // - min_rows_for_wide_part (defaults to 0 but is modifiable)
-                    // - merge_max_block_size (defaults to 8192 but is modifiable)
-                    // Because they are modifiable and won't cause issues if not set
+            // Values left unset by the table come from its database's defaults
+            let database = partial_table
+                .database
+                .as_deref()
+                .unwrap_or(default_database);
+
+            let engine = self
+                .parse_engine(partial_table, default_database)?
+                .expand_replication_macros(database, &name);
+            let engine_params_hash = Some(engine.non_alterable_params_hash());
+
+            // S3Queue settings should come directly from table_settings in the user code
+            let mut table_settings = partial_table.table_settings.clone().unwrap_or_default();
+
+            // Apply ClickHouse default settings for MergeTree family engines
+            // This ensures our internal representation matches what ClickHouse actually has
+            // and prevents unnecessary diffs
+            let should_apply_mergetree_defaults = engine.is_merge_tree_family();
+
+            let database_defaults = self.database_defaults.get(database);
+            let mut applied_defaults = Vec::new();
+
+            let life_cycle = match (
+                partial_table.life_cycle,
+                database_defaults.and_then(|defaults| defaults.life_cycle),
+            ) {
+                (Some(life_cycle), _) => life_cycle,
+                (None, Some(life_cycle)) => {
+                    applied_defaults.push("life_cycle".to_string());
+                    life_cycle
+                }
+                (None, None) => LifeCycle::FullyManaged,
+            };
+            let cluster_name = match (
+                &partial_table.cluster,
+                database_defaults.and_then(|defaults| defaults.cluster.as_ref()),
+            ) {
+                (Some(cluster), _) => Some(cluster.clone()),
+                (None, Some(cluster)) => {
+                    applied_defaults.push("cluster".to_string());
+                    Some(cluster.clone())
                 }
+                (None, None) => None,
+            };
+            if let (true, Some(defaults)) = (should_apply_mergetree_defaults, database_defaults) {
+                for (name, value) in &defaults.table_settings {
+                    if !table_settings.contains_key(name) {
+                        table_settings.insert(name.clone(), value.clone());
+                        applied_defaults.push(format!("table_settings.{name}"));
+                    }
+                }
+            }
 
-                // Extract table-level TTL from partial table
-                let table_ttl_setting = partial_table.ttl.clone();
+            if should_apply_mergetree_defaults {
+                // Apply MergeTree defaults if not explicitly set by user
+                // These are the most common defaults that appear in system.tables
+
+                // Index granularity settings (readonly after table creation)
+                table_settings
+                    .entry("index_granularity".to_string())
+                    .or_insert("8192".to_string());
+                table_settings
+                    .entry("index_granularity_bytes".to_string())
+                    .or_insert("10485760".to_string()); // 10 * 1024 * 1024
+
+                // In ClickHouse 19.11+, this defaults to true (readonly after creation)
+                table_settings
+                    .entry("enable_mixed_granularity_parts".to_string())
+                    .or_insert("1".to_string()); // true = 1 in ClickHouse settings
+
+                // Note: We don't set other defaults like:
+                // - min_bytes_for_wide_part (defaults to 10485760 but is modifiable)
+                // - min_rows_for_wide_part (defaults to 0 but is modifiable)
+                // - merge_max_block_size (defaults to 8192 but is modifiable)
+                // Because they are modifiable and won't cause issues if not set
+            }
 
-                // Construct the table with raw values from partial_table.
-                // Canonicalization (order_by fallback, array nullability, primary_key clearing)
-                // is handled by Table::canonicalize() below.
-                let table = Table {
-                    name: version
-                        .as_ref()
-                        .map_or(partial_table.name.clone(), |version| {
-                            format!("{}_{}", partial_table.name, version.as_suffix())
-                        }),
-                    columns: partial_table.columns.clone(),
-                    order_by: partial_table.order_by.clone(),
-                    partition_by: partial_table.partition_by.clone(),
-                    sample_by: partial_table.sample_by.clone(),
-                    engine,
-                    version,
-                    source_primitive: PrimitiveSignature {
-                        name: partial_table.name.clone(),
-                        primitive_type: PrimitiveTypes::DataModel,
-                    },
-                    metadata: partial_table.metadata.clone(),
-                    life_cycle: partial_table.life_cycle.unwrap_or(LifeCycle::FullyManaged),
-                    engine_params_hash,
-                    table_settings: if table_settings.is_empty() {
-                        None
-                    } else {
-                        Some(table_settings.clone())
-                    },
-                    table_settings_hash: None, // Will be computed below
-                    indexes: partial_table.indexes.clone(),
-                    projections: partial_table.projections.clone(),
-                    table_ttl_setting,
-                    database: partial_table.database.clone(),
-                    cluster_name: partial_table.cluster.clone(),
-                    primary_key_expression: partial_table.primary_key_expression.clone(),
-                    seed_filter: partial_table.seed_filter.clone(),
-                };
+            // Extract table-level TTL from partial table
+            let table_ttl_setting = partial_table.ttl.clone();
+
+            // Construct the table with raw values from partial_table.
+            // Canonicalization (order_by fallback, array nullability, primary_key clearing)
+            // is handled by Table::canonicalize() below.
+            let table = Table {
+                name,
+                columns: partial_table.columns.clone(),
+                order_by: partial_table.order_by.clone(),
+                partition_by: partial_table.partition_by.clone(),
+                sample_by: partial_table.sample_by.clone(),
+                engine,
+                version,
+                source_primitive: PrimitiveSignature {
+                    name: partial_table.name.clone(),
+                    primitive_type: PrimitiveTypes::DataModel,
+                },
+                metadata: partial_table.metadata.clone(),
+                life_cycle,
+                engine_params_hash,
+                table_settings: if table_settings.is_empty() {
+                    None
+                } else {
+                    Some(table_settings.clone())
+                },
+                table_settings_hash: None, // Will be computed below
+                indexes: partial_table.indexes.clone(),
+                projections: partial_table.projections.clone(),
+                table_ttl_setting,
+                database: partial_table.database.clone(),
+                cluster_name,
+                primary_key_expression: partial_table.primary_key_expression.clone(),
+                seed_filter: partial_table.seed_filter.clone(),
+                extensions: Default::default(),
+            };
 
-                // Compute table_settings_hash for change detection, then canonicalize
-                let mut table = table;
-                table.table_settings_hash = table.compute_table_settings_hash();
-                let table = table.canonicalize();
+            // Compute table_settings_hash for change detection, then canonicalize
+            let mut table = table;
+            table.table_settings_hash = table.compute_table_settings_hash();
+            let table = table.canonicalize();
+
+            let id = table.id(default_database);
+            if !applied_defaults.is_empty() {
+                applied_database_defaults.insert(
+                    id.clone(),
+                    AppliedDatabaseDefaults {
+                        database: database.to_string(),
+                        keys: applied_defaults,
+                    },
+                );
+            }
+            tables.insert(id, table);
+        }
 
-                Ok((table.id(default_database), table))
-            })
-            .collect()
+        Ok((tables, applied_database_defaults))
     }
 
     /// Parses the engine configuration from a partial table using the discriminated union approach.
@@ -874,6 +1001,7 @@ impl PartialInfrastructureMap {
             Some(EngineConfig::MergeTree {}) => Ok(ClickhouseEngine::MergeTree),
 
             Some(EngineConfig::ReplacingMergeTree { ver, is_deleted }) => {
+                check_is_deleted_has_ver(&partial_table.name, ver, is_deleted)?;
                 Ok(ClickhouseEngine::ReplacingMergeTree {
                     ver: ver.clone(),
                     is_deleted: is_deleted.clone(),
@@ -914,12 +1042,15 @@ impl PartialInfrastructureMap {
                 replica_name,
                 ver,
                 is_deleted,
-            }) => Ok(ClickhouseEngine::ReplicatedReplacingMergeTree {
-                keeper_path: keeper_path.clone(),
-                replica_name: replica_name.clone(),
-                ver: ver.clone(),
-                is_deleted: is_deleted.clone(),
-            }),
+            }) => {
+                check_is_deleted_has_ver(&partial_table.name, ver, is_deleted)?;
+                Ok(ClickhouseEngine::ReplicatedReplacingMergeTree {
+                    keeper_path: keeper_path.clone(),
+                    replica_name: replica_name.clone(),
+                    ver: ver.clone(),
+                    is_deleted: is_deleted.clone(),
+                })
+            }
 
             Some(EngineConfig::ReplicatedAggregatingMergeTree {
                 keeper_path,
@@ -1045,6 +1176,8 @@ impl PartialInfrastructureMap {
                 })
             }
 
+            Some(EngineConfig::Null {}) => Ok(ClickhouseEngine::Null),
+
             None => Ok(ClickhouseEngine::MergeTree),
         }
     }
@@ -1442,7 +1575,14 @@ impl PartialInfrastructureMap {
     /// # Arguments
     ///
     /// * `language` - The programming language of the user's code
-    fn convert_workflows(&self, language: SupportedLanguages) -> HashMap<String, Workflow> {
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DmV2LoadingError::Workflow`] if a retry policy or timeout is invalid
+    fn convert_workflows(
+        &self,
+        language: SupportedLanguages,
+    ) -> Result<HashMap<String, Workflow>, DmV2LoadingError> {
         self.workflows
             .values()
             .map(|partial_workflow| {
@@ -1454,8 +1594,20 @@ impl PartialInfrastructureMap {
                     partial_workflow.schedule.clone(),
                     partial_workflow.pulls_data_from.clone(),
                     partial_workflow.pushes_data_to.clone(),
-                );
-                (partial_workflow.name.clone(), workflow)
+                )
+                .with_execution_options(
+                    partial_workflow.retry_policy.clone(),
+                    partial_workflow.execution_timeout.clone(),
+                )
+                .with_timezone(partial_workflow.timezone.clone());
+                workflow
+                    .config()
+                    .validate()
+                    .map_err(|source| DmV2LoadingError::Workflow {
+                        workflow: partial_workflow.name.clone(),
+                        source,
+                    })?;
+                Ok((partial_workflow.name.clone(), workflow))
             })
             .collect()
     }
@@ -1557,7 +1709,9 @@ mod tests {
 
         let partial: PartialInfrastructureMap =
             serde_json::from_value(payload).expect("payload should deserialize");
-        let workflows = partial.convert_workflows(SupportedLanguages::Typescript);
+        let workflows = partial
+            .convert_workflows(SupportedLanguages::Typescript)
+            .unwrap();
         let workflow = workflows
             .get("lineageWorkflow")
             .expect("workflow should be converted");
@@ -1576,6 +1730,96 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deserializes_workflow_retry_policy_from_camel_case_fields() {
+        let payload = json!({
+            "workflows": {
+                "flakyApi": {
+                    "name": "flakyApi",
+                    "executionTimeout": "6h",
+                    "retryPolicy": {
+                        "initialInterval": "2s",
+                        "backoffCoefficient": 3.0,
+                        "maxAttempts": 8,
+                        "nonRetryableErrorTypes": ["ValidationError"]
+                    }
+                }
+            }
+        });
+
+        let partial: PartialInfrastructureMap =
+            serde_json::from_value(payload).expect("payload should deserialize");
+        let workflows = partial
+            .convert_workflows(SupportedLanguages::Python)
+            .unwrap();
+        let config = workflows["flakyApi"].config();
+
+        assert_eq!(config.execution_timeout.as_deref(), Some("6h"));
+        assert_eq!(
+            config.retry_policy,
+            Some(WorkflowRetryPolicy {
+                initial_interval: Some("2s".to_string()),
+                backoff_coefficient: Some(3.0),
+                maximum_interval: None,
+                max_attempts: Some(8),
+                non_retryable_error_types: vec!["ValidationError".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_workflow_retrying_forever() {
+        let payload = json!({
+            "workflows": {
+                "flakyApi": {
+                    "name": "flakyApi",
+                    "retryPolicy": { "maxAttempts": 0 }
+                }
+            }
+        });
+
+        let partial: PartialInfrastructureMap =
+            serde_json::from_value(payload).expect("payload should deserialize");
+        let err = partial
+            .convert_workflows(SupportedLanguages::Typescript)
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            DmV2LoadingError::Workflow {
+                ref workflow,
+                source: WorkflowConfigError::UnboundedRetries,
+            } if workflow == "flakyApi"
+        ));
+    }
+
+    #[test]
+    fn rejects_workflow_with_invalid_schedule() {
+        let payload = json!({
+            "workflows": {
+                "nightly": {
+                    "name": "nightly",
+                    "schedule": "0 2 * *",
+                    "timezone": "Europe/Paris"
+                }
+            }
+        });
+
+        let partial: PartialInfrastructureMap =
+            serde_json::from_value(payload).expect("payload should deserialize");
+        let err = partial
+            .convert_workflows(SupportedLanguages::Typescript)
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            DmV2LoadingError::Workflow {
+                ref workflow,
+                source: WorkflowConfigError::InvalidSchedule { .. },
+            } if workflow == "nightly"
+        ));
+    }
+
     #[test]
     fn deserializes_api_lineage_from_camel_case_fields() {
         let payload = json!({
@@ -1770,4 +2014,120 @@ mod tests {
         assert_eq!(sf.limit, Some(20));
         assert_eq!(sf.where_clause, None);
     }
+
+    fn analytics_defaults() -> BTreeMap<String, DatabaseDefaults> {
+        BTreeMap::from([(
+            "analytics".to_string(),
+            DatabaseDefaults {
+                life_cycle: Some(LifeCycle::DeletionProtected),
+                cluster: Some("main".to_string()),
+                table_settings: BTreeMap::from([
+                    ("storage_policy".to_string(), "s3".to_string()),
+                    ("index_granularity".to_string(), "4096".to_string()),
+                ]),
+            },
+        )])
+    }
+
+    #[test]
+    fn tables_inherit_database_defaults() {
+        let payload = json!({ "tables": {
+            "inherits": { "name": "inherits", "columns": [], "orderBy": ["id"] },
+            "other_db": {
+                "name": "other_db", "columns": [], "orderBy": ["id"], "database": "raw"
+            }
+        }});
+        let partial: PartialInfrastructureMap = serde_json::from_value(payload).unwrap();
+        let (tables, applied) = partial
+            .with_database_defaults(analytics_defaults())
+            .convert_tables("analytics")
+            .unwrap();
+
+        let table = &tables["analytics_inherits"];
+        assert_eq!(table.life_cycle, LifeCycle::DeletionProtected);
+        assert_eq!(table.cluster_name.as_deref(), Some("main"));
+        let settings = table.table_settings.as_ref().unwrap();
+        assert_eq!(settings["storage_policy"], "s3");
+        assert_eq!(settings["index_granularity"], "4096");
+        assert_eq!(
+            applied.get("analytics_inherits"),
+            Some(&AppliedDatabaseDefaults {
+                database: "analytics".to_string(),
+                keys: vec![
+                    "life_cycle".to_string(),
+                    "cluster".to_string(),
+                    "table_settings.index_granularity".to_string(),
+                    "table_settings.storage_policy".to_string(),
+                ],
+            })
+        );
+
+        // Tables of other databases are left alone
+        let table = &tables["raw_other_db"];
+        assert_eq!(table.life_cycle, LifeCycle::FullyManaged);
+        assert_eq!(table.cluster_name, None);
+        assert_eq!(applied.get("raw_other_db"), None);
+    }
+
+    #[test]
+    fn table_values_override_database_defaults() {
+        let payload = json!({ "tables": { "overrides": {
+            "name": "overrides",
+            "columns": [],
+            "orderBy": ["id"],
+            "database": "analytics",
+            "lifeCycle": "EXTERNALLY_MANAGED",
+            "cluster": "replica",
+            "tableSettings": { "storage_policy": "local" }
+        }}});
+        let partial: PartialInfrastructureMap = serde_json::from_value(payload).unwrap();
+        let (tables, applied) = partial
+            .with_database_defaults(analytics_defaults())
+            .convert_tables("local")
+            .unwrap();
+
+        let table = &tables["analytics_overrides"];
+        assert_eq!(table.life_cycle, LifeCycle::ExternallyManaged);
+        assert_eq!(table.cluster_name.as_deref(), Some("replica"));
+        let settings = table.table_settings.as_ref().unwrap();
+        assert_eq!(settings["storage_policy"], "local");
+        assert_eq!(settings["index_granularity"], "4096");
+        assert_eq!(
+            applied["analytics_overrides"].keys,
+            vec!["table_settings.index_granularity".to_string()]
+        );
+    }
+
+    #[test]
+    fn rejects_is_deleted_without_ver() {
+        let payload = json!({ "tables": { "users": {
+            "name": "users",
+            "columns": [],
+            "orderBy": ["id"],
+            "engineConfig": { "engine": "ReplacingMergeTree", "isDeleted": "deleted" }
+        }}});
+        let partial: PartialInfrastructureMap = serde_json::from_value(payload).unwrap();
+        let err = partial.convert_tables("local").unwrap_err();
+        assert!(err.to_string().contains("requires a ver column"));
+
+        let payload = json!({ "tables": { "users": {
+            "name": "users",
+            "columns": [],
+            "orderBy": ["id"],
+            "engineConfig": {
+                "engine": "ReplacingMergeTree",
+                "ver": "updated_at",
+                "isDeleted": "deleted"
+            }
+        }}});
+        let partial: PartialInfrastructureMap = serde_json::from_value(payload).unwrap();
+        let (tables, _) = partial.convert_tables("local").unwrap();
+        assert_eq!(
+            tables["local_users"].engine,
+            ClickhouseEngine::ReplacingMergeTree {
+                ver: Some("updated_at".to_string()),
+                is_deleted: Some("deleted".to_string()),
+            }
+        );
+    }
 }
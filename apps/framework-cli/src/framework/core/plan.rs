@@ -12,10 +12,12 @@
 /// 5. Creating a plan that describes the changes to be applied
 ///
 /// The resulting plan is then used by the execution module to apply the changes.
+use crate::framework::core::drop_strategy;
 use crate::framework::core::infra_reality_checker::{InfraRealityChecker, RealityCheckError};
 use crate::framework::core::infrastructure_map::{
     Change, InfraChanges, InfrastructureMap, OlapChange, TableChange,
 };
+use crate::framework::core::manual_changes;
 use crate::framework::core::state_storage::StateStorage;
 use crate::infrastructure::olap::clickhouse;
 #[cfg(test)]
@@ -311,7 +313,10 @@ pub async fn reconcile_with_reality<T: OlapOperations + Sync>(
                         }
                     }
 
-                    TableChange::Added(_) | TableChange::Removed(_) => {
+                    TableChange::Added(_)
+                    | TableChange::Removed(_)
+                    | TableChange::Truncated(_)
+                    | TableChange::Renamed { .. } => {
                         // Add/Remove are already handled by unmapped/missing
                         debug!("Skipping table change: {:?}", table_change);
                     }
@@ -716,20 +721,40 @@ pub async fn plan_changes(
     project: &Project,
 ) -> Result<(InfrastructureMap, InfraPlan), PlanningError> {
     // Load target state from project code
-    let target_infra_map = load_target_infrastructure(project).await?;
+    let mut target_infra_map = load_target_infrastructure(project).await?;
+    // Changes made by hand that `moose dev` was told to keep
+    manual_changes::apply_kept_changes(&mut target_infra_map);
+
+    plan_changes_to_target(state_storage, project, target_infra_map, || {
+        clickhouse::create_client(project.clickhouse_config.clone())
+    })
+    .await
+}
 
+/// Plans the changes turning the reconciled current state into `target_infra_map`.
+///
+/// ClickHouse is only read, through the [`OlapOperations`] clients returned by
+/// `olap_client`, and nothing is written to `state_storage`.
+///
+/// # Returns
+/// * `Result<(InfrastructureMap, InfraPlan), PlanningError>` - The current state and infrastructure plan, or an error
+pub async fn plan_changes_to_target<T: OlapOperations + Sync>(
+    state_storage: &dyn StateStorage,
+    project: &Project,
+    target_infra_map: InfrastructureMap,
+    olap_client: impl Fn() -> T,
+) -> Result<(InfrastructureMap, InfraPlan), PlanningError> {
     // Load and reconcile current state
-    let olap_client = clickhouse::create_client(project.clickhouse_config.clone());
     let filter = ReconciliationFilter::from_infra_map(&target_infra_map);
 
     let reconciled_map =
-        load_reconciled_infrastructure(project, state_storage, olap_client, &filter).await?;
+        load_reconciled_infrastructure(project, state_storage, olap_client(), &filter).await?;
 
     // Normalize SQL in both maps for comparison only (don't mutate originals).
     // We normalize at comparison time rather than storing normalized SQL because
     // ClickHouse's formatQuerySingleLine behavior could change between versions.
     // The original user SQL is preserved in target_infra_map for storage.
-    let normalization_client = clickhouse::create_client(project.clickhouse_config.clone());
+    let normalization_client = olap_client();
     let reconciled_normalized =
         normalize_infra_map_for_comparison(&reconciled_map, &normalization_client).await;
     let target_normalized =
@@ -745,13 +770,20 @@ pub async fn plan_changes(
         &[]
     };
 
-    let changes = reconciled_normalized.diff_with_table_strategy(
+    let mut changes = reconciled_normalized.diff_with_table_strategy(
         &target_normalized,
         &clickhouse_strategy,
         true,
         project.is_production,
         ignore_ops,
     );
+    if project.is_production {
+        drop_strategy::apply_drop_strategy(
+            &mut changes.olap_changes,
+            &project.migration_config,
+            &project.clickhouse_config.db_name,
+        );
+    }
 
     // Note: changes contain normalized SQL (via ClickHouse's formatQuerySingleLine).
     // This is fine because ClickHouse reformats SQL anyway when storing.
@@ -863,6 +895,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }
     }
 
@@ -882,6 +915,8 @@ mod tests {
                 host_data_path: None,
                 additional_databases: Vec::new(),
                 clusters: None,
+                ddl_parallelism: 4,
+                insert_error_threshold: 10,
             },
             http_server_config: crate::cli::local_webserver::LocalWebserverConfig::default(),
             redis_config: crate::infrastructure::redis::redis_client::RedisConfig::default(),
@@ -906,6 +941,8 @@ mod tests {
             docker_config: crate::project::DockerConfig::default(),
             watcher_config: crate::cli::watcher::WatcherConfig::default(),
             dev: crate::project::DevConfig::default(),
+            databases: Default::default(),
+            retries: Default::default(),
         }
     }
 
@@ -1507,6 +1544,7 @@ mod tests {
             teardown: vec!["DROP VIEW IF EXISTS unmapped_view".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         let mock_client = MockOlapClient {
@@ -1545,6 +1583,7 @@ mod tests {
             teardown: vec!["DROP VIEW IF EXISTS view_a".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         let view_b = SqlResource {
@@ -1557,6 +1596,7 @@ mod tests {
             teardown: vec!["DROP VIEW IF EXISTS view_b".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         let mock_client = MockOlapClient {
@@ -1600,6 +1640,7 @@ mod tests {
             teardown: vec!["DROP VIEW IF EXISTS existing_view".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         // Reality has a different version (mismatched)
@@ -1613,6 +1654,7 @@ mod tests {
             teardown: vec!["DROP VIEW IF EXISTS existing_view".to_string()],
             pulls_data_from: vec![],
             pushes_data_to: vec![],
+            life_cycle: Default::default(),
         };
 
         let mock_client = MockOlapClient {
@@ -1,6 +1,23 @@
-use crate::{infrastructure::stream, project::Project};
-
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    cli::display::{show_message_wrapper, Message, MessageType},
+    infrastructure::{
+        olap::clickhouse::{
+            create_client,
+            diff_strategy::{format_key_column_change_error, key_column_type_changes},
+            fetch_cluster_names,
+            mapper::{nullable_key_columns, validate_column_comments, validate_key_nullability},
+            sql_parser::extract_ttl_columns,
+        },
+        stream,
+    },
+    project::{config_validation::database_cluster_issues, Project},
+};
+
+use super::infrastructure::table::Table;
 use super::infrastructure_map::{OlapChange, TableChange};
+use super::partial_infrastructure_map::LifeCycle;
 use super::plan::InfraPlan;
 
 #[derive(Debug, thiserror::Error)]
@@ -13,6 +30,9 @@ pub enum ValidationError {
 
     #[error("Cluster validation failed: {0}")]
     ClusterValidation(String),
+
+    #[error("Database validation failed: {0}")]
+    DatabaseValidation(String),
 }
 
 /// Validates that all tables with cluster_name reference clusters defined in the config
@@ -66,11 +86,211 @@ fn validate_cluster_references(project: &Project, plan: &InfraPlan) -> Result<()
     Ok(())
 }
 
+/// Validates that every database a table of the plan is in is created by Moose,
+/// being `db_name` or one of `additional_databases`. Rows synced to a table
+/// elsewhere would fail to insert. Externally managed tables are in databases
+/// Moose does not create.
+fn validate_database_references(
+    project: &Project,
+    plan: &InfraPlan,
+) -> Result<(), ValidationError> {
+    let config = &project.clickhouse_config;
+    let mut missing: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for table in plan
+        .target_infra_map
+        .tables
+        .values()
+        .filter(|table| table.life_cycle != LifeCycle::ExternallyManaged)
+    {
+        if let Some(database) = table.database.as_deref() {
+            if database != config.db_name
+                && !config.additional_databases.iter().any(|db| db == database)
+            {
+                missing.entry(database).or_default().insert(&table.name);
+            }
+        }
+    }
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let tables: Vec<String> = missing
+        .iter()
+        .map(|(database, tables)| {
+            let tables: Vec<&str> = tables.iter().copied().collect();
+            format!("  - '{database}', used by tables: {}", tables.join(", "))
+        })
+        .collect();
+    let databases: Vec<String> = config
+        .additional_databases
+        .iter()
+        .map(String::as_str)
+        .chain(missing.keys().copied())
+        .map(|database| format!("\"{database}\""))
+        .collect();
+    Err(ValidationError::DatabaseValidation(format!(
+        "These databases are not configured in moose.config.toml:\n{}\n\
+        \n\
+        To fix this, add them to your config:\n\
+        \n\
+        [clickhouse_config]\n\
+        additional_databases = [{}]\n",
+        tables.join("\n"),
+        databases.join(", ")
+    )))
+}
+
+/// Validates that `[databases.<name>]` defaults only name clusters defined in the config
+fn validate_database_defaults(project: &Project) -> Result<(), ValidationError> {
+    match database_cluster_issues(project).first() {
+        Some(issue) => Err(ValidationError::ClusterValidation(format!(
+            "{issue}.\n\
+            Add the cluster to [[clickhouse_config.clusters]] in moose.config.toml \
+            or remove it from [databases.<name>]"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Clusters referenced by the config or by tables of the plan, each with the
+/// tables using it
+fn referenced_clusters(project: &Project, plan: &InfraPlan) -> BTreeMap<String, BTreeSet<String>> {
+    let mut clusters: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for cluster in project.clickhouse_config.clusters.iter().flatten() {
+        clusters.entry(cluster.name.clone()).or_default();
+    }
+    for cluster in project
+        .databases
+        .values()
+        .filter_map(|defaults| defaults.cluster.as_ref())
+    {
+        clusters.entry(cluster.clone()).or_default();
+    }
+    for table in plan.target_infra_map.tables.values() {
+        if let Some(cluster) = &table.cluster_name {
+            clusters
+                .entry(cluster.clone())
+                .or_default()
+                .insert(table.name.clone());
+        }
+    }
+    clusters
+}
+
+/// Validates that every referenced cluster is one of `available`, the clusters
+/// the ClickHouse server knows
+fn validate_clusters_exist(
+    project: &Project,
+    plan: &InfraPlan,
+    available: &[String],
+) -> Result<(), ValidationError> {
+    let missing: Vec<String> = referenced_clusters(project, plan)
+        .into_iter()
+        .filter(|(cluster, _)| !available.contains(cluster))
+        .map(|(cluster, tables)| {
+            if tables.is_empty() {
+                format!("  - '{cluster}', defined in moose.config.toml")
+            } else {
+                let tables: Vec<String> = tables.into_iter().collect();
+                format!("  - '{cluster}', used by tables: {}", tables.join(", "))
+            }
+        })
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let available = if available.is_empty() {
+        "none".to_string()
+    } else {
+        available.join(", ")
+    };
+    Err(ValidationError::ClusterValidation(format!(
+        "These clusters do not exist on the ClickHouse server:\n{}\n\
+        Available clusters (system.clusters): {available}\n\
+        Check the names in [[clickhouse_config.clusters]] and the tables' cluster settings.",
+        missing.join("\n")
+    )))
+}
+
+/// Validates the clusters referenced by the config or the plan against
+/// `system.clusters` of the configured ClickHouse server, before any ON CLUSTER
+/// DDL runs. When the server cannot be queried, e.g. when planning offline, the
+/// check is skipped with a warning.
+pub async fn validate_clusters_on_server(
+    project: &Project,
+    plan: &InfraPlan,
+) -> Result<(), ValidationError> {
+    if referenced_clusters(project, plan).is_empty() {
+        return Ok(());
+    }
+
+    let client = create_client(project.clickhouse_config.clone());
+    match fetch_cluster_names(&client).await {
+        Ok(available) => validate_clusters_exist(project, plan, &available),
+        Err(e) => {
+            show_message_wrapper(
+                MessageType::Warning,
+                Message {
+                    action: "Clusters".to_string(),
+                    details: format!(
+                        "Skipping the check of clusters against system.clusters, ClickHouse could not be queried: {e}"
+                    ),
+                },
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Validates that the TTL expressions of `table` only refer to its columns,
+/// which ClickHouse otherwise reports once the plan is running. Expressions
+/// that cannot be parsed are left to ClickHouse.
+fn validate_ttl_columns(table: &Table) -> Result<(), ValidationError> {
+    let table_ttl = table
+        .table_ttl_setting
+        .as_deref()
+        .map(|ttl| ("the TTL".to_string(), ttl));
+    let column_ttls = table.columns.iter().filter_map(|column| {
+        let ttl = column.ttl.as_deref()?;
+        Some((format!("the TTL of column '{}'", column.name), ttl))
+    });
+
+    for (owner, ttl) in table_ttl.into_iter().chain(column_ttls) {
+        let identifiers = match extract_ttl_columns(ttl) {
+            Ok(identifiers) => identifiers,
+            Err(e) => {
+                tracing::debug!("Not validating TTL `{ttl}` of table {}: {e}", table.name);
+                continue;
+            }
+        };
+        // Subcolumns of nested columns are written `column.field`
+        let unknown = identifiers.into_iter().find(|identifier| {
+            let parent = identifier.split('.').next().unwrap_or(identifier);
+            !table
+                .columns
+                .iter()
+                .any(|column| column.name == *identifier || column.name == parent)
+        });
+        if let Some(unknown) = unknown {
+            return Err(ValidationError::TableValidation(format!(
+                "Table '{}': {owner} `{ttl}` refers to column '{unknown}', which the table does not have.\n\
+                If the expression is valid, set skip_ttl_validation = true under [migration_config] in moose.config.toml",
+                table.name
+            )));
+        }
+    }
+    Ok(())
+}
+
 pub fn validate(project: &Project, plan: &InfraPlan) -> Result<(), ValidationError> {
     stream::validate_changes(project, &plan.changes.streaming_engine_changes)?;
 
     // Validate cluster references
+    validate_database_defaults(project)?;
     validate_cluster_references(project, plan)?;
+    validate_database_references(project, plan)?;
 
     // Check for validation errors in OLAP changes
     for change in &plan.changes.olap_changes {
@@ -79,6 +299,75 @@ pub fn validate(project: &Project, plan: &InfraPlan) -> Result<(), ValidationErr
         }
     }
 
+    // Column comments are generated while the plan runs, check them beforehand
+    for change in &plan.changes.olap_changes {
+        let table = match change {
+            OlapChange::Table(TableChange::Added(table)) => table,
+            OlapChange::Table(TableChange::Updated { after, .. }) => after,
+            _ => continue,
+        };
+        if let Err(e) = validate_column_comments(&table.columns) {
+            return Err(ValidationError::TableValidation(format!(
+                "Table {}: {e}",
+                table.name
+            )));
+        }
+    }
+
+    // Nullable key columns fail CREATE TABLE, after a rebuild already dropped the
+    // previous table
+    for change in &plan.changes.olap_changes {
+        let OlapChange::Table(TableChange::Added(table)) = change else {
+            continue;
+        };
+        validate_key_nullability(table)
+            .map_err(|e| ValidationError::TableValidation(e.to_string()))?;
+        let columns = nullable_key_columns(table);
+        if !columns.is_empty() {
+            show_message_wrapper(
+                MessageType::Warning,
+                Message {
+                    action: "Nullable".to_string(),
+                    details: format!(
+                        "key column(s) {} of table '{}', allowed by allow_nullable_key = 1",
+                        columns.join(", "),
+                        table.name
+                    ),
+                },
+            );
+        }
+    }
+
+    // TTLs naming missing columns fail the CREATE or ALTER of the table
+    if !project.migration_config.skip_ttl_validation {
+        for change in &plan.changes.olap_changes {
+            let table = match change {
+                OlapChange::Table(TableChange::Added(table)) => table,
+                OlapChange::Table(TableChange::Updated { after, .. }) => after,
+                _ => continue,
+            };
+            validate_ttl_columns(table)?;
+        }
+    }
+
+    // Type changes of key columns fail in ClickHouse once the plan is running
+    for change in &plan.changes.olap_changes {
+        if let OlapChange::Table(TableChange::Updated {
+            name,
+            column_changes,
+            before,
+            ..
+        }) = change
+        {
+            let key_columns = key_column_type_changes(before, column_changes);
+            if !key_columns.is_empty() {
+                return Err(ValidationError::TableValidation(
+                    format_key_column_change_error(name, &key_columns),
+                ));
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -115,6 +404,8 @@ mod tests {
                 host_data_path: None,
                 additional_databases: vec![],
                 clusters,
+                ddl_parallelism: 4,
+                insert_error_threshold: 10,
             },
             http_server_config: crate::cli::local_webserver::LocalWebserverConfig::default(),
             redis_config: crate::infrastructure::redis::redis_client::RedisConfig::default(),
@@ -137,6 +428,8 @@ mod tests {
             docker_config: crate::project::DockerConfig::default(),
             watcher_config: crate::cli::watcher::WatcherConfig::default(),
             dev: crate::project::DevConfig::default(),
+            databases: Default::default(),
+            retries: Default::default(),
         }
     }
 
@@ -178,6 +471,7 @@ mod tests {
             cluster_name,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }
     }
 
@@ -205,6 +499,8 @@ mod tests {
                 materialized_views: HashMap::new(),
                 views: HashMap::new(),
                 moose_version: None,
+                extensions: Default::default(),
+                applied_database_defaults: Default::default(),
             },
             changes: Default::default(),
         }
@@ -269,6 +565,30 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validate_table_in_unconfigured_database() {
+        let mut project = create_test_project(None);
+        let mut events = create_test_table("events", None);
+        events.database = Some("analytics".to_string());
+        let mut archived = create_test_table("archived_events", None);
+        archived.database = Some("archive".to_string());
+        let plan = create_test_plan(vec![events, archived]);
+
+        project.clickhouse_config.additional_databases = vec!["archive".to_string()];
+        match validate(&project, &plan) {
+            Err(ValidationError::DatabaseValidation(msg)) => {
+                assert!(msg.contains("'analytics', used by tables: events"));
+                assert!(!msg.contains("'archive'"));
+                assert!(msg.contains(r#"additional_databases = ["archive", "analytics"]"#));
+            }
+            other => panic!("Expected DatabaseValidation error, got {other:?}"),
+        }
+
+        project.clickhouse_config.additional_databases =
+            vec!["archive".to_string(), "analytics".to_string()];
+        assert!(validate(&project, &plan).is_ok());
+    }
+
     #[test]
     fn test_validate_table_with_no_cluster_is_allowed() {
         let project = create_test_project(Some(vec![ClusterConfig {
@@ -362,6 +682,7 @@ mod tests {
             cluster_name,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }
     }
 
@@ -375,4 +696,245 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_validate_database_defaults_reference_undefined_cluster() {
+        let mut project = create_test_project(Some(vec![ClusterConfig {
+            name: "cluster_a".to_string(),
+        }]));
+        project.databases.insert(
+            "analytics".to_string(),
+            crate::project::DatabaseDefaults {
+                cluster: Some("cluster_b".to_string()),
+                ..Default::default()
+            },
+        );
+        let plan = create_test_plan(vec![]);
+
+        match validate(&project, &plan) {
+            Err(ValidationError::ClusterValidation(msg)) => {
+                assert!(msg.starts_with(
+                    "databases.analytics.cluster: cluster 'cluster_b' is not defined"
+                ));
+            }
+            other => panic!("Expected ClusterValidation error, got {other:?}"),
+        }
+
+        project.databases.get_mut("analytics").unwrap().cluster = Some("cluster_a".to_string());
+        assert!(validate(&project, &plan).is_ok());
+    }
+
+    #[test]
+    fn test_validate_clusters_exist_on_server() {
+        let project = create_test_project(Some(vec![
+            ClusterConfig {
+                name: "cluster_a".to_string(),
+            },
+            ClusterConfig {
+                name: "cluster_b".to_string(),
+            },
+        ]));
+        let plan = create_test_plan(vec![
+            create_test_table("events", Some("cluster_a".to_string())),
+            create_test_table("audit", Some("cluster_a".to_string())),
+        ]);
+
+        // Result of `SELECT DISTINCT cluster FROM system.clusters`
+        let available = vec!["cluster_a".to_string(), "cluster_b".to_string()];
+        assert!(validate_clusters_exist(&project, &plan, &available).is_ok());
+
+        let available = vec!["cluster_b".to_string(), "default".to_string()];
+        match validate_clusters_exist(&project, &plan, &available) {
+            Err(ValidationError::ClusterValidation(msg)) => {
+                assert!(msg.contains("'cluster_a', used by tables: audit, events"));
+                assert!(msg.contains("Available clusters (system.clusters): cluster_b, default"));
+                assert!(!msg.contains("'cluster_b'"));
+            }
+            other => panic!("Expected ClusterValidation error, got {other:?}"),
+        }
+
+        // Clusters only defined in the config are checked too
+        let plan = create_test_plan(vec![]);
+        match validate_clusters_exist(&project, &plan, &[]) {
+            Err(ValidationError::ClusterValidation(msg)) => {
+                assert!(msg.contains("'cluster_a', defined in moose.config.toml"));
+                assert!(msg.contains("'cluster_b', defined in moose.config.toml"));
+                assert!(msg.contains("Available clusters (system.clusters): none"));
+            }
+            other => panic!("Expected ClusterValidation error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cluster_check_without_references_skips_query() {
+        // No clusters anywhere: the unreachable server is never queried
+        let mut project = create_test_project(None);
+        project.clickhouse_config.host_port = 1;
+        let plan = create_test_plan(vec![create_test_table("events", None)]);
+
+        assert!(validate_clusters_on_server(&project, &plan).await.is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_key_column_type_change() {
+        use crate::framework::core::infrastructure_map::{
+            ColumnChange, OrderByChange, PartitionByChange,
+        };
+
+        let project = create_test_project(None);
+        let before = create_test_table("events", None);
+        let mut key_change = before.clone();
+        key_change.columns[0].data_type = ColumnType::Uuid;
+
+        let updated = |before: Table, after: Table| {
+            let mut plan = create_test_plan(vec![after.clone()]);
+            plan.changes.olap_changes = vec![OlapChange::Table(TableChange::Updated {
+                name: before.name.clone(),
+                column_changes: vec![ColumnChange::Updated {
+                    before: before.columns[0].clone(),
+                    after: after.columns[0].clone(),
+                }],
+                order_by_change: OrderByChange {
+                    before: before.order_by.clone(),
+                    after: after.order_by.clone(),
+                },
+                partition_by_change: PartitionByChange {
+                    before: None,
+                    after: None,
+                },
+                before,
+                after,
+            })];
+            plan
+        };
+
+        match validate(&project, &updated(before.clone(), key_change)) {
+            Err(ValidationError::TableValidation(msg)) => {
+                assert!(msg.contains("key column(s) id"));
+                assert!(msg.contains("ALTER_OF_COLUMN_IS_FORBIDDEN"));
+            }
+            other => panic!("Expected TableValidation error, got {other:?}"),
+        }
+
+        // `payload` is not part of the key
+        let mut before = before;
+        before.columns[0].name = "payload".to_string();
+        before.columns[0].primary_key = false;
+        before.order_by = OrderBy::Fields(vec![]);
+        let mut after = before.clone();
+        after.columns[0].data_type = ColumnType::Uuid;
+        assert!(validate(&project, &updated(before, after)).is_ok());
+    }
+
+    /// `events` with a nullable `user_id` column and the given ORDER BY
+    fn table_with_nullable_column(order_by: &[&str]) -> Table {
+        let mut table = create_test_table("events", None);
+        table.columns.push(Column {
+            name: "user_id".to_string(),
+            data_type: ColumnType::String,
+            required: false,
+            primary_key: false,
+            ..table.columns[0].clone()
+        });
+        table.order_by = OrderBy::Fields(order_by.iter().map(|f| f.to_string()).collect());
+        table
+    }
+
+    fn added(table: Table) -> InfraPlan {
+        let mut plan = create_test_plan(vec![table.clone()]);
+        plan.changes.olap_changes = vec![OlapChange::Table(TableChange::Added(table))];
+        plan
+    }
+
+    #[test]
+    fn test_validate_rejects_nullable_order_by_column() {
+        let project = create_test_project(None);
+
+        match validate(
+            &project,
+            &added(table_with_nullable_column(&["id", "user_id"])),
+        ) {
+            Err(ValidationError::TableValidation(msg)) => {
+                assert!(msg.contains("Nullable column(s) user_id of table 'events'"));
+                assert!(msg.contains("allow_nullable_key = 1"));
+            }
+            other => panic!("Expected TableValidation error, got {other:?}"),
+        }
+        assert!(validate(&project, &added(table_with_nullable_column(&["id"]))).is_ok());
+    }
+
+    #[test]
+    fn test_validate_resolves_columns_of_key_expressions() {
+        let project = create_test_project(None);
+
+        let hashed = table_with_nullable_column(&["id", "cityHash64(user_id)"]);
+        assert!(matches!(
+            validate(&project, &added(hashed)),
+            Err(ValidationError::TableValidation(_))
+        ));
+
+        let mut primary_key = table_with_nullable_column(&["id"]);
+        primary_key.primary_key_expression = Some("(id, user_id)".to_string());
+        assert!(matches!(
+            validate(&project, &added(primary_key)),
+            Err(ValidationError::TableValidation(_))
+        ));
+
+        // Expressions referencing no column are not checked
+        let unresolved = table_with_nullable_column(&["id", "rand()"]);
+        assert!(validate(&project, &added(unresolved)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_allows_nullable_key_with_setting() {
+        let project = create_test_project(None);
+        let mut table = table_with_nullable_column(&["id", "user_id"]);
+        table.table_settings = Some(HashMap::from([(
+            "allow_nullable_key".to_string(),
+            "1".to_string(),
+        )]));
+
+        assert_eq!(nullable_key_columns(&table), vec!["user_id".to_string()]);
+        assert!(validate(&project, &added(table)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_ttl_referring_to_missing_column() {
+        let mut project = create_test_project(None);
+        let mut table = create_test_table("events", None);
+        table.columns.push(Column {
+            name: "createdAt".to_string(),
+            data_type: ColumnType::DateTime { precision: None },
+            primary_key: false,
+            ..table.columns[0].clone()
+        });
+
+        let mut typo = table.clone();
+        typo.table_ttl_setting = Some("created_at + INTERVAL 30 DAY".to_string());
+        match validate(&project, &added(typo.clone())) {
+            Err(ValidationError::TableValidation(msg)) => {
+                assert!(msg.contains("Table 'events'"));
+                assert!(msg.contains("column 'created_at'"));
+            }
+            other => panic!("Expected TableValidation error, got {other:?}"),
+        }
+
+        let mut column_typo = table.clone();
+        column_typo.columns[0].ttl = Some("toDate(created) + toIntervalDay(1)".to_string());
+        match validate(&project, &added(column_typo)) {
+            Err(ValidationError::TableValidation(msg)) => {
+                assert!(msg.contains("the TTL of column 'id'"));
+                assert!(msg.contains("column 'created'"));
+            }
+            other => panic!("Expected TableValidation error, got {other:?}"),
+        }
+
+        let mut functions = table.clone();
+        functions.table_ttl_setting =
+            Some("toStartOfDay(createdAt) + INTERVAL 30 DAY DELETE".to_string());
+        assert!(validate(&project, &added(functions)).is_ok());
+
+        project.migration_config.skip_ttl_validation = true;
+        assert!(validate(&project, &added(typo)).is_ok());
+    }
 }
@@ -5,6 +5,7 @@
 
 use crate::framework::core::infrastructure_map::InfrastructureMap;
 use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::model::quote_qualified;
 use crate::infrastructure::olap::clickhouse::ConfiguredDBClient;
 use crate::infrastructure::olap::clickhouse::{check_ready, create_client};
 use crate::infrastructure::redis::redis_client::RedisClient;
@@ -34,6 +35,9 @@ pub trait StateStorage: Send + Sync {
     /// Load the infrastructure map
     async fn load_infrastructure_map(&self) -> Result<Option<InfrastructureMap>>;
 
+    /// Delete the stored infrastructure map, so the next plan starts from scratch
+    async fn clear_infrastructure_map(&self) -> Result<()>;
+
     /// Try to acquire migration lock
     /// Must be manually released with release_migration_lock()
     /// Lock automatically expires after 5 minutes as a safety fallback
@@ -41,6 +45,23 @@ pub trait StateStorage: Send + Sync {
 
     /// Release migration lock
     async fn release_migration_lock(&self) -> Result<()>;
+
+    /// Fails when the migration lock taken by this instance has since been
+    /// taken by another one, renewing it otherwise. Checked before each
+    /// migration operation.
+    async fn check_migration_lock(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Store the fingerprint of the last DDL plan fully applied
+    async fn store_applied_plan_fingerprint(&self, _fingerprint: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Load the fingerprint of the last DDL plan fully applied
+    async fn load_applied_plan_fingerprint(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
 }
 
 /// Redis-based state storage
@@ -50,6 +71,7 @@ pub struct RedisStateStorage {
 
 impl RedisStateStorage {
     const LOCK_KEY: &'static str = "migration_lock";
+    const PLAN_FINGERPRINT_KEY: &'static str = "applied_plan_fingerprint";
     const LOCK_TIMEOUT_SECS: i64 = 300; // 5 minutes
 
     pub fn new(client: Arc<RedisClient>) -> Self {
@@ -71,6 +93,13 @@ impl StateStorage for RedisStateStorage {
         InfrastructureMap::load_from_last_redis_prefix(&self.client).await
     }
 
+    async fn clear_infrastructure_map(&self) -> Result<()> {
+        self.client
+            .delete_with_service_prefix("infrastructure_map")
+            .await
+            .context("Failed to delete InfrastructureMap from Redis")
+    }
+
     async fn acquire_migration_lock(&self) -> Result<()> {
         // Use LeadershipManager's atomic lock acquisition
         // Add key_prefix for multi-tenancy isolation (different projects can migrate in parallel)
@@ -79,7 +108,7 @@ impl StateStorage for RedisStateStorage {
             .client
             .leadership_manager
             .attempt_lock(
-                self.client.connection_manager.connection.clone(),
+                self.client.connection_manager.connection(),
                 &lock_key,
                 Self::LOCK_TIMEOUT_SECS,
             )
@@ -117,7 +146,7 @@ impl StateStorage for RedisStateStorage {
         self.client
             .leadership_manager
             .release_lock(
-                self.client.connection_manager.connection.clone(),
+                self.client.connection_manager.connection(),
                 &lock_key,
                 &self.client.instance_id,
             )
@@ -126,6 +155,39 @@ impl StateStorage for RedisStateStorage {
         info!("Released migration lock {}", lock_key);
         Ok(())
     }
+
+    async fn check_migration_lock(&self) -> Result<()> {
+        let lock_key = self.client.service_prefix(&[Self::LOCK_KEY]);
+        let leadership = &self.client.leadership_manager;
+
+        // Not locked by this instance (e.g. a plan applied on startup): nothing to fence
+        let Some(token) = leadership.fencing_token(&lock_key) else {
+            return Ok(());
+        };
+        leadership
+            .check_and_renew_with_token(
+                self.client.connection_manager.connection(),
+                &lock_key,
+                token,
+                Self::LOCK_TIMEOUT_SECS,
+            )
+            .await
+            .context("Aborting the migration")
+    }
+
+    async fn store_applied_plan_fingerprint(&self, fingerprint: &str) -> Result<()> {
+        self.client
+            .set_with_service_prefix(Self::PLAN_FINGERPRINT_KEY, fingerprint)
+            .await
+            .context("Failed to store the applied plan fingerprint in Redis")
+    }
+
+    async fn load_applied_plan_fingerprint(&self) -> Result<Option<String>> {
+        self.client
+            .get_with_service_prefix(Self::PLAN_FINGERPRINT_KEY)
+            .await
+            .context("Failed to load the applied plan fingerprint from Redis")
+    }
 }
 
 /// ClickHouse-based state storage (for serverless/CLI-only deployments)
@@ -135,8 +197,9 @@ pub struct ClickHouseStateStorage {
 }
 
 impl ClickHouseStateStorage {
-    const STATE_TABLE: &'static str = "_MOOSE_STATE";
+    pub const STATE_TABLE: &'static str = "_MOOSE_STATE";
     const LOCK_KEY: &'static str = "migration_lock";
+    const PLAN_FINGERPRINT_KEY: &'static str = "applied_plan_fingerprint";
     const LOCK_TIMEOUT_SECS: i64 = 300; // 5 minutes
 
     pub fn new(client: ConfiguredDBClient, db_name: String) -> Self {
@@ -152,7 +215,7 @@ impl ClickHouseStateStorage {
         // 4. Already configured in dev mode. Available in Clickhouse Cloud
         let create_table_sql = format!(
             r#"
-            CREATE TABLE IF NOT EXISTS `{}`.`{}`
+            CREATE TABLE IF NOT EXISTS {}
             (
                 key String,
                 value String,
@@ -161,8 +224,7 @@ impl ClickHouseStateStorage {
             ENGINE = KeeperMap('/{}/{}')
             PRIMARY KEY key
             "#,
-            self.db_name,
-            Self::STATE_TABLE,
+            quote_qualified(&self.db_name, Self::STATE_TABLE),
             self.db_name,
             Self::STATE_TABLE
         );
@@ -201,9 +263,8 @@ impl StateStorage for ClickHouseStateStorage {
 
         // Insert with timestamp key (creates audit history)
         let insert_sql = format!(
-            "INSERT INTO `{}`.`{}` (key, value) VALUES ('{}', '{}')",
-            self.db_name,
-            Self::STATE_TABLE,
+            "INSERT INTO {} (key, value) VALUES ('{}', '{}')",
+            quote_qualified(&self.db_name, Self::STATE_TABLE),
             key,
             encoded_base64
         );
@@ -233,13 +294,12 @@ impl StateStorage for ClickHouseStateStorage {
         let query_sql = format!(
             r#"
             SELECT value
-            FROM `{}`.`{}`
+            FROM {}
             WHERE key LIKE 'infra_map_%'
             ORDER BY created_at DESC
             LIMIT 1
             "#,
-            self.db_name,
-            Self::STATE_TABLE
+            quote_qualified(&self.db_name, Self::STATE_TABLE)
         );
 
         info!("Loading infrastructure map from database: {}", self.db_name);
@@ -279,6 +339,27 @@ impl StateStorage for ClickHouseStateStorage {
         Ok(Some(infra_map))
     }
 
+    async fn clear_infrastructure_map(&self) -> Result<()> {
+        self.ensure_state_table().await?;
+
+        // Only the map history is removed; lock rows are left to expire on their own
+        let delete_sql = format!(
+            "DELETE FROM {} WHERE key LIKE 'infra_map_%'",
+            quote_qualified(&self.db_name, Self::STATE_TABLE)
+        );
+
+        self.client
+            .client
+            .query(&delete_sql)
+            .execute()
+            .await
+            .context("Failed to delete infrastructure map from ClickHouse")?;
+
+        info!("Cleared infrastructure map from ClickHouse");
+
+        Ok(())
+    }
+
     async fn acquire_migration_lock(&self) -> Result<()> {
         self.ensure_state_table().await?;
 
@@ -292,9 +373,8 @@ impl StateStorage for ClickHouseStateStorage {
 
         // Check if lock exists
         let existing_lock_query = format!(
-            "SELECT value FROM `{}`.`{}` WHERE key = '{}'",
-            self.db_name,
-            Self::STATE_TABLE,
+            "SELECT value FROM {} WHERE key = '{}'",
+            quote_qualified(&self.db_name, Self::STATE_TABLE),
             Self::LOCK_KEY
         );
 
@@ -322,9 +402,8 @@ impl StateStorage for ClickHouseStateStorage {
             if existing_lock.expires_at < Utc::now() {
                 // Stale lock - delete it
                 let delete_sql = format!(
-                    "DELETE FROM `{}`.`{}` WHERE key = '{}'",
-                    self.db_name,
-                    Self::STATE_TABLE,
+                    "DELETE FROM {} WHERE key = '{}'",
+                    quote_qualified(&self.db_name, Self::STATE_TABLE),
                     Self::LOCK_KEY
                 );
 
@@ -372,9 +451,8 @@ impl StateStorage for ClickHouseStateStorage {
         );
 
         let insert_sql = format!(
-            "INSERT INTO `{}`.`{}` (key, value) VALUES ('{}', '{}')",
-            self.db_name,
-            Self::STATE_TABLE,
+            "INSERT INTO {} (key, value) VALUES ('{}', '{}')",
+            quote_qualified(&self.db_name, Self::STATE_TABLE),
             Self::LOCK_KEY,
             lock_json_base64
         );
@@ -396,9 +474,8 @@ impl StateStorage for ClickHouseStateStorage {
 
     async fn release_migration_lock(&self) -> Result<()> {
         let delete_sql = format!(
-            "DELETE FROM `{}`.`{}` WHERE key = '{}'",
-            self.db_name,
-            Self::STATE_TABLE,
+            "DELETE FROM {} WHERE key = '{}'",
+            quote_qualified(&self.db_name, Self::STATE_TABLE),
             Self::LOCK_KEY
         );
 
@@ -412,6 +489,48 @@ impl StateStorage for ClickHouseStateStorage {
         info!("Released migration lock");
         Ok(())
     }
+
+    async fn store_applied_plan_fingerprint(&self, fingerprint: &str) -> Result<()> {
+        self.ensure_state_table().await?;
+
+        // Fingerprints are hex digests, safe to inline. Without strict mode,
+        // KeeperMap replaces the previous value of the key.
+        let insert_sql = format!(
+            "INSERT INTO {} (key, value) VALUES ('{}', '{}')",
+            quote_qualified(&self.db_name, Self::STATE_TABLE),
+            Self::PLAN_FINGERPRINT_KEY,
+            fingerprint
+        );
+
+        self.client
+            .client
+            .query(&insert_sql)
+            .execute()
+            .await
+            .context("Failed to store the applied plan fingerprint in ClickHouse")
+    }
+
+    async fn load_applied_plan_fingerprint(&self) -> Result<Option<String>> {
+        self.ensure_state_table().await?;
+
+        let query_sql = format!(
+            "SELECT value FROM {} WHERE key = '{}'",
+            quote_qualified(&self.db_name, Self::STATE_TABLE),
+            Self::PLAN_FINGERPRINT_KEY
+        );
+
+        let mut cursor = self
+            .client
+            .client
+            .query(&query_sql)
+            .fetch::<String>()
+            .context("Failed to query state table")?;
+
+        cursor
+            .next()
+            .await
+            .context("Failed to load the applied plan fingerprint from ClickHouse")
+    }
 }
 
 /// Builder for creating state storage based on project configuration.
@@ -486,9 +605,16 @@ impl<'a> StateStorageBuilder<'a> {
                 use crate::infrastructure::redis::redis_client::{RedisClient, RedisConfig};
                 use std::sync::Arc;
 
+                // Credentials and TLS settings still come from the project
                 let redis_config = RedisConfig {
                     url: redis_url,
                     key_prefix: self.project.redis_config.key_prefix.clone(),
+                    last_key_prefix: self.project.redis_config.last_key_prefix.clone(),
+                    tls: self.project.redis_config.tls,
+                    tls_ca_path: self.project.redis_config.tls_ca_path.clone(),
+                    tls_insecure_skip_verify: self.project.redis_config.tls_insecure_skip_verify,
+                    username: self.project.redis_config.username.clone(),
+                    password: self.project.redis_config.password.clone(),
                     ..Default::default()
                 };
                 let redis_client = Arc::new(
@@ -79,6 +79,7 @@ impl DataModel {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Compute hash that includes both engine params and database
@@ -1,6 +1,9 @@
+use crate::framework::core::infrastructure::sql_resource::SqlResource;
 use crate::framework::core::infrastructure::table::{
     ColumnType, DataEnum, EnumValue, FloatType, IntType, JsonOptions, Nested, OrderBy, Table,
+    TableIndex,
 };
+use crate::framework::core::infrastructure::InfrastructureSignature;
 use crate::framework::core::partial_infrastructure_map::LifeCycle;
 use crate::utilities::identifiers as ident;
 use convert_case::{Case, Casing};
@@ -534,6 +537,33 @@ fn collect_types<'a>(
     }
 }
 
+/// An entry of the `indexes` list of an `OlapConfig`
+pub fn index_declaration(idx: &TableIndex) -> String {
+    // arguments optional
+    let arguments = if idx.arguments.is_empty() {
+        String::new()
+    } else {
+        format!(
+            ", arguments=[{}]",
+            idx.arguments.iter().map(|a| format!("{:?}", a)).join(", ")
+        )
+    };
+    format!(
+        "OlapConfig.TableIndex(name={:?}, expression={:?}, type={:?}{arguments}, granularity={})",
+        idx.name, idx.expression, idx.index_type, idx.granularity
+    )
+}
+
+/// The `settings` dict of an `OlapConfig`, sorted by name
+pub fn settings_declaration(settings: &HashMap<String, String>) -> String {
+    let entries = settings
+        .iter()
+        .sorted()
+        .map(|(key, value)| format!("{:?}: {:?}", key, value))
+        .join(", ");
+    format!("{{{entries}}}")
+}
+
 pub fn tables_to_python(tables: &[Table], life_cycle: Option<LifeCycle>) -> String {
     let mut output = String::new();
 
@@ -589,7 +619,7 @@ pub fn tables_to_python(tables: &[Table], life_cycle: Option<LifeCycle>) -> Stri
     .unwrap();
     writeln!(
         output,
-        "from moose_lib.blocks import MergeTreeEngine, ReplacingMergeTreeEngine, AggregatingMergeTreeEngine, SummingMergeTreeEngine, CollapsingMergeTreeEngine, VersionedCollapsingMergeTreeEngine, S3QueueEngine, KafkaEngine, ReplicatedMergeTreeEngine, ReplicatedReplacingMergeTreeEngine, ReplicatedAggregatingMergeTreeEngine, ReplicatedSummingMergeTreeEngine, ReplicatedCollapsingMergeTreeEngine, ReplicatedVersionedCollapsingMergeTreeEngine, BufferEngine, DistributedEngine, MergeEngine"
+        "from moose_lib.blocks import MergeTreeEngine, ReplacingMergeTreeEngine, AggregatingMergeTreeEngine, SummingMergeTreeEngine, CollapsingMergeTreeEngine, VersionedCollapsingMergeTreeEngine, S3QueueEngine, KafkaEngine, ReplicatedMergeTreeEngine, ReplicatedReplacingMergeTreeEngine, ReplicatedAggregatingMergeTreeEngine, ReplicatedSummingMergeTreeEngine, ReplicatedCollapsingMergeTreeEngine, ReplicatedVersionedCollapsingMergeTreeEngine, BufferEngine, DistributedEngine, MergeEngine, NullEngine"
     )
     .unwrap();
     writeln!(output).unwrap();
@@ -1089,6 +1119,9 @@ pub fn tables_to_python(tables: &[Table], life_cycle: Option<LifeCycle>) -> Stri
                 writeln!(output, "        tables_regexp={:?},", tables_regexp).unwrap();
                 writeln!(output, "    ),").unwrap();
             }
+            crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine::Null => {
+                writeln!(output, "    engine=NullEngine(),").unwrap();
+            }
         }
         // Skip version for externally managed tables — the infra map appends
         // `_{version}` to the table name, which would corrupt external table names
@@ -1101,43 +1134,14 @@ pub fn tables_to_python(tables: &[Table], life_cycle: Option<LifeCycle>) -> Stri
         // Add table settings if present (includes mode for S3Queue)
         if let Some(settings) = &table.table_settings {
             if !settings.is_empty() {
-                write!(output, "    settings={{").unwrap();
-                for (i, (key, value)) in settings.iter().enumerate() {
-                    if i > 0 {
-                        write!(output, ", ").unwrap();
-                    }
-                    write!(output, "{:?}: {:?}", key, value).unwrap();
-                }
-                writeln!(output, "}},").unwrap();
+                writeln!(output, "    settings={},", settings_declaration(settings)).unwrap();
             }
         }
 
         if !table.indexes.is_empty() {
             writeln!(output, "    indexes=[").unwrap();
             for idx in &table.indexes {
-                // arguments optional
-                if idx.arguments.is_empty() {
-                    writeln!(
-                        output,
-                        "        OlapConfig.TableIndex(name={:?}, expression={:?}, type={:?}, granularity={}),",
-                        idx.name, idx.expression, idx.index_type, idx.granularity
-                    )
-                    .unwrap();
-                } else {
-                    write!(
-                        output,
-                        "        OlapConfig.TableIndex(name={:?}, expression={:?}, type={:?}, arguments=[",
-                        idx.name, idx.expression, idx.index_type
-                    )
-                    .unwrap();
-                    for (i, a) in idx.arguments.iter().enumerate() {
-                        if i > 0 {
-                            write!(output, ", ").unwrap();
-                        }
-                        write!(output, "{:?}", a).unwrap();
-                    }
-                    writeln!(output, "], granularity={}),", idx.granularity).unwrap();
-                }
+                writeln!(output, "        {},", index_declaration(idx)).unwrap();
             }
             writeln!(output, "    ],").unwrap();
         }
@@ -1172,6 +1176,122 @@ pub fn tables_to_python(tables: &[Table], life_cycle: Option<LifeCycle>) -> Stri
     output
 }
 
+/// Whether a lineage id produced by SQL resource introspection refers to `table`.
+fn table_matches_lineage_id(table: &Table, id: &str) -> bool {
+    table.name == id || extract_version_from_table_name(&table.name).0 == id
+}
+
+/// A Python string literal for `value`, for embedding SQL and names verbatim.
+fn python_string_literal(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => write!(literal, "\\u{:04x}", c as u32).unwrap(),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Generates `SqlResource` definitions for views, materialized views and dictionaries
+/// introspected from a remote database.
+///
+/// The resources already exist in the database, so they are marked externally managed.
+/// Resources are expected in dependency order and `tables` must already be in scope of
+/// the generated code. Dependencies that cannot be resolved are listed in a comment
+/// above the resource.
+pub fn sql_resources_to_python(resources: &[SqlResource], tables: &[Table]) -> String {
+    let mut output = String::new();
+    writeln!(output, "from moose_lib import LifeCycle, SqlResource").unwrap();
+    writeln!(output).unwrap();
+
+    for (i, resource) in resources.iter().enumerate() {
+        let mut unresolved = Vec::new();
+        let mut resolve = |deps: &[InfrastructureSignature]| -> Vec<String> {
+            let mut vars = Vec::new();
+            for dep in deps {
+                let id = dep.id();
+                if let Some(table) = tables.iter().find(|t| table_matches_lineage_id(t, id)) {
+                    vars.push(format!(
+                        "{}_table",
+                        map_to_python_snake_identifier(&table.name)
+                    ));
+                } else if let Some(upstream) = resources[..i].iter().find(|r| r.name == id) {
+                    vars.push(format!(
+                        "{}_sql_resource",
+                        map_to_python_snake_identifier(&upstream.name)
+                    ));
+                } else {
+                    unresolved.push(id.to_string());
+                }
+            }
+            vars
+        };
+        let pulls_data_from = resolve(&resource.pulls_data_from);
+        let pushes_data_to = resolve(&resource.pushes_data_to);
+
+        if !unresolved.is_empty() {
+            writeln!(
+                output,
+                "# Depends on resources not defined in this project: {}",
+                unresolved.join(", ")
+            )
+            .unwrap();
+        }
+        writeln!(
+            output,
+            "{}_sql_resource = SqlResource(",
+            map_to_python_snake_identifier(&resource.name)
+        )
+        .unwrap();
+        writeln!(output, "    {},", python_string_literal(&resource.name)).unwrap();
+        writeln!(
+            output,
+            "    setup=[{}],",
+            resource
+                .setup
+                .iter()
+                .map(|s| python_string_literal(s))
+                .join(", ")
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "    teardown=[{}],",
+            resource
+                .teardown
+                .iter()
+                .map(|s| python_string_literal(s))
+                .join(", ")
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "    pulls_data_from=[{}],",
+            pulls_data_from.join(", ")
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "    pushes_data_to=[{}],",
+            pushes_data_to.join(", ")
+        )
+        .unwrap();
+        writeln!(output, "    life_cycle=LifeCycle.EXTERNALLY_MANAGED,").unwrap();
+        writeln!(output, ")").unwrap();
+        writeln!(output).unwrap();
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1226,6 +1346,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }
     }
 
@@ -1260,7 +1381,7 @@ from moose_lib import Key, IngestPipeline, IngestPipelineConfig, OlapTable, Olap
 from moose_lib.data_models import ClickHouseJson
 from moose_lib import Point, Ring, LineString, MultiLineString, Polygon, MultiPolygon, FixedString
 from moose_lib import clickhouse_default, ClickHouseCodec, ClickHouseMaterialized, ClickHouseAlias, LifeCycle, ClickHouseTTL
-from moose_lib.blocks import MergeTreeEngine, ReplacingMergeTreeEngine, AggregatingMergeTreeEngine, SummingMergeTreeEngine, CollapsingMergeTreeEngine, VersionedCollapsingMergeTreeEngine, S3QueueEngine, KafkaEngine, ReplicatedMergeTreeEngine, ReplicatedReplacingMergeTreeEngine, ReplicatedAggregatingMergeTreeEngine, ReplicatedSummingMergeTreeEngine, ReplicatedCollapsingMergeTreeEngine, ReplicatedVersionedCollapsingMergeTreeEngine, BufferEngine, DistributedEngine, MergeEngine
+from moose_lib.blocks import MergeTreeEngine, ReplacingMergeTreeEngine, AggregatingMergeTreeEngine, SummingMergeTreeEngine, CollapsingMergeTreeEngine, VersionedCollapsingMergeTreeEngine, S3QueueEngine, KafkaEngine, ReplicatedMergeTreeEngine, ReplicatedReplacingMergeTreeEngine, ReplicatedAggregatingMergeTreeEngine, ReplicatedSummingMergeTreeEngine, ReplicatedCollapsingMergeTreeEngine, ReplicatedVersionedCollapsingMergeTreeEngine, BufferEngine, DistributedEngine, MergeEngine, NullEngine
 
 class Foo(BaseModel):
     primary_key: Key[str]
@@ -1352,6 +1473,7 @@ foo_table = OlapTable[Foo]("Foo", OlapConfig(
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_python(&tables, None);
@@ -1493,6 +1615,7 @@ nested_array_table = OlapTable[NestedArray]("NestedArray", OlapConfig(
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_python(&tables, None);
@@ -1647,6 +1770,20 @@ user_table = OlapTable[User]("User", OlapConfig(
         assert!(result.contains("tables_regexp=\"^events_.*$\""));
     }
 
+    #[test]
+    fn test_null_engine() {
+        let tables = vec![test_table(
+            "RawEvents",
+            vec![test_column("id", ColumnType::String)],
+            ClickhouseEngine::Null,
+        )];
+
+        let result = tables_to_python(&tables, None);
+
+        assert!(result.contains("engine=NullEngine(),"));
+        assert!(!result.contains("order_by_"));
+    }
+
     #[test]
     fn test_table_settings_all_engines() {
         let tables = vec![Table {
@@ -1830,6 +1967,7 @@ user_table = OlapTable[User]("User", OlapConfig(
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_python(&tables, None);
@@ -1902,6 +2040,7 @@ user_table = OlapTable[User]("User", OlapConfig(
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_python(&tables, None);
@@ -1977,6 +2116,7 @@ user_table = OlapTable[User]("User", OlapConfig(
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_python(&tables, None);
@@ -2038,6 +2178,7 @@ user_table = OlapTable[User]("User", OlapConfig(
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_python(&tables, None);
@@ -2113,6 +2254,7 @@ user_table = OlapTable[User]("User", OlapConfig(
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_python(&tables, None);
@@ -2178,6 +2320,7 @@ user_table = OlapTable[User]("User", OlapConfig(
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_python(&tables, None);
@@ -2318,4 +2461,61 @@ user_table = OlapTable[User]("User", OlapConfig(
             result
         );
     }
+
+    fn test_sql_resource(
+        name: &str,
+        pulls_data_from: &[&str],
+        pushes_data_to: &[&str],
+    ) -> SqlResource {
+        SqlResource {
+            name: name.to_string(),
+            database: None,
+            source_file: None,
+            source_line: None,
+            source_column: None,
+            setup: vec![format!("CREATE VIEW IF NOT EXISTS {name} AS SELECT 1")],
+            teardown: vec![format!("DROP VIEW IF EXISTS `{name}`")],
+            pulls_data_from: pulls_data_from
+                .iter()
+                .map(|id| InfrastructureSignature::Table { id: id.to_string() })
+                .collect(),
+            pushes_data_to: pushes_data_to
+                .iter()
+                .map(|id| InfrastructureSignature::Table { id: id.to_string() })
+                .collect(),
+            life_cycle: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_sql_resources_to_python_resolves_dependencies() {
+        let tables = vec![
+            test_table("raw_events", vec![], ClickhouseEngine::MergeTree),
+            test_table("daily_counts", vec![], ClickhouseEngine::MergeTree),
+        ];
+        let resources = vec![
+            test_sql_resource("events_mv", &["raw_events"], &["daily_counts"]),
+            test_sql_resource("daily_view", &["events_mv", "other_db_table"], &[]),
+        ];
+
+        let code = sql_resources_to_python(&resources, &tables);
+
+        assert!(code.starts_with("from moose_lib import LifeCycle, SqlResource\n"));
+        assert!(code.contains("events_mv_sql_resource = SqlResource(\n    \"events_mv\",\n"));
+        assert!(code.contains("    pulls_data_from=[raw_events_table],\n"));
+        assert!(code.contains("    pushes_data_to=[daily_counts_table],\n"));
+        assert!(code.contains("    pulls_data_from=[events_mv_sql_resource],\n"));
+        assert!(code.contains("# Depends on resources not defined in this project: other_db_table"));
+        assert!(code.contains(
+            "    teardown=[\"DROP VIEW IF EXISTS `daily_view`\"],\n    pulls_data_from=[events_mv_sql_resource],\n    pushes_data_to=[],\n    life_cycle=LifeCycle.EXTERNALLY_MANAGED,\n"
+        ));
+    }
+
+    #[test]
+    fn test_python_string_literal_escapes_sql() {
+        assert_eq!(
+            python_string_literal("SELECT 'a\\b', \"c\"\n\tFROM t -- é\u{1}"),
+            r#""SELECT 'a\\b', \"c\"\n\tFROM t -- é\u0001""#
+        );
+    }
 }
@@ -358,6 +358,7 @@ def handler_with_client(moose_client):
                     if jwt_payload is not None:
                         args.append(jwt_payload)
                     print(f"[API] | Executing API: {user_api.name}")
+                    moose_client.query.query_seconds = 0.0
                     response = user_api.query_function(*args)
                     # Convert Pydantic model to dict before JSON serialization
                     if isinstance(response, BaseModel):
@@ -389,6 +390,11 @@ def handler_with_client(moose_client):
                         "utf-8",
                     )
 
+                # Lets the webserver tell the ClickHouse time from the handler's
+                self.send_header(
+                    "Server-Timing",
+                    f"db;dur={moose_client.query.query_seconds * 1000:.1f}",
+                )
                 self.end_headers()
                 self.wfile.write(response_message)
 
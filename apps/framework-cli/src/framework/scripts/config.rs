@@ -1,17 +1,78 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use super::utils::{is_timezone_name, parse_schedule, parse_timeout_to_seconds, validate_cron};
+
+/// Retry policy of a workflow. Unset fields keep Temporal's defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowRetryPolicy {
+    /// Delay before the first retry, e.g. "1s"
+    #[serde(default)]
+    pub initial_interval: Option<String>,
+    /// Multiplier applied to the delay after each retry
+    #[serde(default)]
+    pub backoff_coefficient: Option<f64>,
+    /// Upper bound of the delay between retries, e.g. "5m"
+    #[serde(default)]
+    pub maximum_interval: Option<String>,
+    /// Total attempts including the first one, 0 retrying until a timeout.
+    /// Takes precedence over `retries`.
+    #[serde(default)]
+    pub max_attempts: Option<u32>,
+    /// Error types that fail the workflow without retrying
+    #[serde(default)]
+    pub non_retryable_error_types: Vec<String>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum WorkflowConfigError {
+    #[error("{field} '{value}' is not a positive duration such as 30s, 5m or 1h")]
+    InvalidDuration { field: &'static str, value: String },
+
+    #[error("retry_policy.backoff_coefficient must be at least 1, got {0}")]
+    InvalidBackoffCoefficient(f64),
+
+    #[error("retry_policy.maximum_interval must not be shorter than initial_interval")]
+    MaximumIntervalTooShort,
+
+    #[error("retry_policy.non_retryable_error_types contains an empty error type")]
+    EmptyErrorType,
+
+    #[error("retry_policy.max_attempts 0 retries forever, set an execution_timeout")]
+    UnboundedRetries,
+
+    #[error("schedule '{schedule}' is not a valid cron expression: {reason}")]
+    InvalidSchedule { schedule: String, reason: String },
+
+    #[error("timezone '{0}' is not a time zone name such as UTC or America/New_York")]
+    InvalidTimezone(String),
+
+    #[error("timezone is set but the workflow has no schedule")]
+    TimezoneWithoutSchedule,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WorkflowConfig {
     // Basic workflow configuration
     pub name: String,
     #[serde(default = "default_schedule")]
     pub schedule: String,
+    /// IANA time zone the schedule is read in, UTC when unset
+    #[serde(default)]
+    pub timezone: Option<String>,
     #[serde(default = "default_retries")]
     pub retries: u32,
     #[serde(default = "default_timeout")]
     pub timeout: String,
 
+    /// Limit of the whole execution across runs and retries, unlike
+    /// `timeout` which limits each run
+    #[serde(default)]
+    pub execution_timeout: Option<String>,
+    #[serde(default)]
+    pub retry_policy: Option<WorkflowRetryPolicy>,
+
     // Optional tasks configuration
     #[serde(default)]
     pub tasks: Option<Vec<String>>,
@@ -22,8 +83,11 @@ impl WorkflowConfig {
         WorkflowConfig {
             name,
             schedule: default_schedule(),
+            timezone: None,
             retries: default_retries(),
             timeout: default_timeout(),
+            execution_timeout: None,
+            retry_policy: None,
             tasks: None,
         }
     }
@@ -53,6 +117,70 @@ impl WorkflowConfig {
         config
     }
 
+    /// Rejects schedules, retry and timeout settings Temporal would refuse or
+    /// that would let a failing workflow retry forever.
+    pub fn validate(&self) -> Result<(), WorkflowConfigError> {
+        if !self.schedule.is_empty() {
+            validate_cron(&parse_schedule(&self.schedule)).map_err(|reason| {
+                WorkflowConfigError::InvalidSchedule {
+                    schedule: self.schedule.clone(),
+                    reason,
+                }
+            })?;
+        }
+        if let Some(timezone) = &self.timezone {
+            if self.schedule.is_empty() {
+                return Err(WorkflowConfigError::TimezoneWithoutSchedule);
+            }
+            if !is_timezone_name(timezone) {
+                return Err(WorkflowConfigError::InvalidTimezone(timezone.clone()));
+            }
+        }
+        if let Some(execution_timeout) = &self.execution_timeout {
+            positive_seconds("execution_timeout", execution_timeout)?;
+        }
+        let Some(policy) = &self.retry_policy else {
+            return Ok(());
+        };
+
+        let initial = policy
+            .initial_interval
+            .as_deref()
+            .map(|interval| positive_seconds("retry_policy.initial_interval", interval))
+            .transpose()?;
+        let maximum = policy
+            .maximum_interval
+            .as_deref()
+            .map(|interval| positive_seconds("retry_policy.maximum_interval", interval))
+            .transpose()?;
+        if let (Some(initial), Some(maximum)) = (initial, maximum) {
+            if maximum < initial {
+                return Err(WorkflowConfigError::MaximumIntervalTooShort);
+            }
+        }
+
+        if let Some(coefficient) = policy.backoff_coefficient {
+            if coefficient.is_nan() || coefficient < 1.0 {
+                return Err(WorkflowConfigError::InvalidBackoffCoefficient(coefficient));
+            }
+        }
+
+        if policy
+            .non_retryable_error_types
+            .iter()
+            .any(|error_type| error_type.trim().is_empty())
+        {
+            return Err(WorkflowConfigError::EmptyErrorType);
+        }
+
+        // Each retry is a new run, so only the execution timeout bounds them
+        if policy.max_attempts == Some(0) && self.execution_timeout.is_none() {
+            return Err(WorkflowConfigError::UnboundedRetries);
+        }
+
+        Ok(())
+    }
+
     pub fn save(&self, path: PathBuf) -> std::io::Result<()> {
         let content = toml::to_string_pretty(self).map_err(std::io::Error::other)?;
         std::fs::write(path, content)
@@ -65,6 +193,16 @@ impl WorkflowConfig {
     }
 }
 
+fn positive_seconds(field: &'static str, value: &str) -> Result<i64, WorkflowConfigError> {
+    match parse_timeout_to_seconds(value) {
+        Ok(seconds) if seconds > 0 => Ok(seconds),
+        _ => Err(WorkflowConfigError::InvalidDuration {
+            field,
+            value: value.to_string(),
+        }),
+    }
+}
+
 // Default values functions
 fn default_schedule() -> String {
     "".to_string() // Empty string means no schedule
@@ -122,4 +260,139 @@ mod tests {
         assert_eq!(config.name, "test");
         assert_eq!(config.schedule, "0 0 * * *");
     }
+
+    #[test]
+    fn test_validate_retry_policy() {
+        let config = |retry_policy: WorkflowRetryPolicy| WorkflowConfig {
+            retry_policy: Some(retry_policy),
+            ..WorkflowConfig::new("etl".to_string())
+        };
+
+        let valid = config(WorkflowRetryPolicy {
+            initial_interval: Some("1s".to_string()),
+            backoff_coefficient: Some(2.0),
+            maximum_interval: Some("5m".to_string()),
+            max_attempts: Some(10),
+            non_retryable_error_types: vec!["ValidationError".to_string()],
+        });
+        assert_eq!(valid.validate(), Ok(()));
+
+        let invalid_interval = config(WorkflowRetryPolicy {
+            initial_interval: Some("soon".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            invalid_interval.validate(),
+            Err(WorkflowConfigError::InvalidDuration {
+                field: "retry_policy.initial_interval",
+                value: "soon".to_string(),
+            })
+        );
+
+        let zero_interval = config(WorkflowRetryPolicy {
+            initial_interval: Some("0s".to_string()),
+            ..Default::default()
+        });
+        assert!(zero_interval.validate().is_err());
+
+        let inverted = config(WorkflowRetryPolicy {
+            initial_interval: Some("1m".to_string()),
+            maximum_interval: Some("30s".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            inverted.validate(),
+            Err(WorkflowConfigError::MaximumIntervalTooShort)
+        );
+
+        for coefficient in [0.5, f64::NAN] {
+            let shrinking = config(WorkflowRetryPolicy {
+                backoff_coefficient: Some(coefficient),
+                ..Default::default()
+            });
+            assert!(matches!(
+                shrinking.validate(),
+                Err(WorkflowConfigError::InvalidBackoffCoefficient(_))
+            ));
+        }
+
+        let empty_type = config(WorkflowRetryPolicy {
+            non_retryable_error_types: vec![" ".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(
+            empty_type.validate(),
+            Err(WorkflowConfigError::EmptyErrorType)
+        );
+    }
+
+    #[test]
+    fn test_validate_unbounded_retries() {
+        let config = WorkflowConfig {
+            retry_policy: Some(WorkflowRetryPolicy {
+                max_attempts: Some(0),
+                ..Default::default()
+            }),
+            ..WorkflowConfig::new("etl".to_string())
+        };
+        assert_eq!(
+            config.validate(),
+            Err(WorkflowConfigError::UnboundedRetries)
+        );
+
+        let invalid_timeout = WorkflowConfig {
+            execution_timeout: Some("1d".to_string()),
+            ..config.clone()
+        };
+        assert!(matches!(
+            invalid_timeout.validate(),
+            Err(WorkflowConfigError::InvalidDuration {
+                field: "execution_timeout",
+                ..
+            })
+        ));
+
+        let bounded = WorkflowConfig {
+            execution_timeout: Some("24h".to_string()),
+            ..config
+        };
+        assert_eq!(bounded.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_schedule() {
+        let config = |schedule: &str, timezone: Option<&str>| WorkflowConfig {
+            schedule: schedule.to_string(),
+            timezone: timezone.map(str::to_string),
+            ..WorkflowConfig::new("etl".to_string())
+        };
+
+        assert_eq!(config("", None).validate(), Ok(()));
+        assert_eq!(config("5m", None).validate(), Ok(()));
+        assert_eq!(
+            config("0 2 * * MON-FRI", Some("Europe/Paris")).validate(),
+            Ok(())
+        );
+        assert_eq!(
+            config("0 25 * * *", None).validate(),
+            Err(WorkflowConfigError::InvalidSchedule {
+                schedule: "0 25 * * *".to_string(),
+                reason: "hour 25 is out of range 0-23".to_string(),
+            })
+        );
+        assert!(matches!(
+            config("1d", None).validate(),
+            Err(WorkflowConfigError::InvalidSchedule { .. })
+        ));
+        assert_eq!(
+            config("@daily", Some("Paris France")).validate(),
+            Err(WorkflowConfigError::InvalidTimezone(
+                "Paris France".to_string()
+            ))
+        );
+        assert_eq!(
+            config("", Some("UTC")).validate(),
+            Err(WorkflowConfigError::TimezoneWithoutSchedule)
+        );
+    }
 }
@@ -7,7 +7,7 @@ use tracing::info;
 use super::config::WorkflowConfig;
 use crate::framework::{
     languages::SupportedLanguages,
-    scripts::utils::{parse_schedule, parse_timeout_to_seconds, TemporalExecutionError},
+    scripts::utils::{parse_timeout_to_seconds, TemporalExecutionError},
 };
 use crate::infrastructure::orchestration::temporal::TemporalConfig;
 use crate::infrastructure::orchestration::temporal_client::TemporalClientManager;
@@ -22,6 +22,7 @@ use temporal_sdk_core::protos::temporal::api::enums::v1::{
 };
 
 use temporal_sdk_core::protos::temporal::api::taskqueue::v1::TaskQueue;
+use temporal_sdk_core::protos::temporal::api::workflow::v1::NewWorkflowExecutionInfo;
 use temporal_sdk_core::protos::temporal::api::workflowservice::v1::StartWorkflowExecutionRequest;
 
 #[derive(Debug, thiserror::Error)]
@@ -55,32 +56,54 @@ pub(crate) async fn execute_workflow(
     config: &WorkflowConfig,
     input: Option<String>,
 ) -> Result<WorkflowStartInfo, WorkflowExecutionError> {
+    let params = WorkflowExecutionParams {
+        temporal_config,
+        workflow_id,
+        config,
+        input,
+        task_queue_name: task_queue_name(language),
+    };
+    let info = execute_workflow_for_language(params, temporal_config).await?;
+    Ok(info)
+}
+
+pub(crate) fn task_queue_name(language: SupportedLanguages) -> &'static str {
     match language {
-        SupportedLanguages::Python => {
-            let params = WorkflowExecutionParams {
-                temporal_config,
-                workflow_id,
-                config,
-                input,
-                task_queue_name: PYTHON_TASK_QUEUE,
-            };
-            let info = execute_workflow_for_language(params, temporal_config).await?;
-            Ok(info)
-        }
-        SupportedLanguages::Typescript => {
-            let params = WorkflowExecutionParams {
-                temporal_config,
-                workflow_id,
-                config,
-                input,
-                task_queue_name: TYPESCRIPT_TASK_QUEUE,
-            };
-            let info = execute_workflow_for_language(params, temporal_config).await?;
-            Ok(info)
-        }
+        SupportedLanguages::Python => PYTHON_TASK_QUEUE,
+        SupportedLanguages::Typescript => TYPESCRIPT_TASK_QUEUE,
     }
 }
 
+/// The workflow a schedule starts at each fire, the same as a manual run
+/// without input. Temporal appends the fire time to the workflow ID.
+pub(crate) fn scheduled_workflow_info(
+    temporal_config: &TemporalConfig,
+    language: SupportedLanguages,
+    workflow_id: &str,
+    config: &WorkflowConfig,
+) -> Result<NewWorkflowExecutionInfo, TemporalExecutionError> {
+    let params = WorkflowExecutionParams {
+        temporal_config,
+        workflow_id,
+        config,
+        input: None,
+        task_queue_name: task_queue_name(language),
+    };
+    let request =
+        create_workflow_execution_request(temporal_config.get_temporal_namespace(), &params)?;
+
+    Ok(NewWorkflowExecutionInfo {
+        workflow_id: request.workflow_id,
+        workflow_type: request.workflow_type,
+        task_queue: request.task_queue,
+        input: request.input,
+        workflow_execution_timeout: request.workflow_execution_timeout,
+        workflow_run_timeout: request.workflow_run_timeout,
+        retry_policy: request.retry_policy,
+        ..Default::default()
+    })
+}
+
 async fn execute_workflow_for_language(
     params: WorkflowExecutionParams<'_>,
     temporal_config: &TemporalConfig,
@@ -172,26 +195,171 @@ fn create_workflow_execution_request(
             if params.config.timeout == "never" {
                 None
             } else {
-                Some(prost_wkt_types::Duration {
-                    seconds: parse_timeout_to_seconds(&params.config.timeout)?,
-                    nanos: 0,
-                })
+                Some(duration(&params.config.timeout)?)
             }
         },
+        workflow_execution_timeout: params
+            .config
+            .execution_timeout
+            .as_deref()
+            .map(duration)
+            .transpose()?,
         identity: MOOSE_CLI_NAME.to_string(),
         request_id: uuid::Uuid::new_v4().to_string(),
         // Allow duplicate doesn't actually allow concurrent runs of the same workflow ID
         // It allows reuse of that workflow ID after the previous run has completed
         workflow_id_reuse_policy: WorkflowIdReusePolicy::AllowDuplicate as i32,
-        retry_policy: Some(RetryPolicy {
-            // Temporal's maximum_attempts = total attempts (initial + retries)
-            // User-facing "retries" = number of retries after initial failure
-            maximum_attempts: (params.config.retries + 1) as i32,
-            ..Default::default()
-        }),
-        cron_schedule: parse_schedule(&params.config.schedule),
+        retry_policy: Some(retry_policy(params.config)?),
         workflow_id_conflict_policy: WorkflowIdConflictPolicy::Unspecified as i32,
         request_eager_execution: false,
         ..Default::default()
     })
 }
+
+fn duration(value: &str) -> Result<prost_wkt_types::Duration, TemporalExecutionError> {
+    Ok(prost_wkt_types::Duration {
+        seconds: parse_timeout_to_seconds(value)?,
+        nanos: 0,
+    })
+}
+
+/// Maps the workflow's retry settings to Temporal's retry policy. Fields left
+/// unset keep Temporal's defaults.
+fn retry_policy(config: &WorkflowConfig) -> Result<RetryPolicy, TemporalExecutionError> {
+    let mut retry_policy = RetryPolicy {
+        // Temporal's maximum_attempts = total attempts (initial + retries)
+        // User-facing "retries" = number of retries after initial failure
+        maximum_attempts: (config.retries + 1) as i32,
+        ..Default::default()
+    };
+
+    if let Some(policy) = &config.retry_policy {
+        if let Some(max_attempts) = policy.max_attempts {
+            retry_policy.maximum_attempts = i32::try_from(max_attempts).unwrap_or(i32::MAX);
+        }
+        retry_policy.initial_interval = policy
+            .initial_interval
+            .as_deref()
+            .map(duration)
+            .transpose()?;
+        retry_policy.maximum_interval = policy
+            .maximum_interval
+            .as_deref()
+            .map(duration)
+            .transpose()?;
+        retry_policy.backoff_coefficient = policy.backoff_coefficient.unwrap_or_default();
+        retry_policy.non_retryable_error_types = policy.non_retryable_error_types.clone();
+    }
+
+    Ok(retry_policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::scripts::config::WorkflowRetryPolicy;
+
+    fn request(config: &WorkflowConfig) -> StartWorkflowExecutionRequest {
+        let temporal_config = TemporalConfig::default();
+        let params = WorkflowExecutionParams {
+            temporal_config: &temporal_config,
+            workflow_id: &config.name,
+            config,
+            input: None,
+            task_queue_name: TYPESCRIPT_TASK_QUEUE,
+        };
+        create_workflow_execution_request("default".to_string(), &params).unwrap()
+    }
+
+    fn seconds(seconds: i64) -> Option<prost_wkt_types::Duration> {
+        Some(prost_wkt_types::Duration { seconds, nanos: 0 })
+    }
+
+    #[test]
+    fn test_scheduled_workflow_is_started_once() {
+        let config = WorkflowConfig {
+            schedule: "0 2 * * *".to_string(),
+            ..WorkflowConfig::new("nightly".to_string())
+        };
+
+        // The Temporal schedule starts it on schedule, not a cron workflow
+        assert_eq!(request(&config).cron_schedule, "");
+
+        let info = scheduled_workflow_info(
+            &TemporalConfig::default(),
+            SupportedLanguages::Typescript,
+            "nightly",
+            &config,
+        )
+        .unwrap();
+        assert_eq!(info.workflow_id, "nightly");
+        assert_eq!(info.cron_schedule, "");
+        assert_eq!(info.task_queue.unwrap().name, TYPESCRIPT_TASK_QUEUE);
+        assert_eq!(info.workflow_run_timeout, seconds(3600));
+        assert_eq!(info.retry_policy.unwrap().maximum_attempts, 4);
+    }
+
+    #[test]
+    fn test_default_options() {
+        let request = request(&WorkflowConfig::new("etl".to_string()));
+
+        assert_eq!(request.workflow_run_timeout, seconds(3600));
+        assert_eq!(request.workflow_execution_timeout, None);
+        assert_eq!(
+            request.retry_policy,
+            Some(RetryPolicy {
+                maximum_attempts: 4,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_retry_policy_and_timeouts_options() {
+        let config = WorkflowConfig {
+            timeout: "never".to_string(),
+            execution_timeout: Some("6h".to_string()),
+            retry_policy: Some(WorkflowRetryPolicy {
+                initial_interval: Some("2s".to_string()),
+                backoff_coefficient: Some(1.5),
+                maximum_interval: Some("10m".to_string()),
+                max_attempts: Some(0),
+                non_retryable_error_types: vec!["ValidationError".to_string()],
+            }),
+            ..WorkflowConfig::new("flaky_api".to_string())
+        };
+        let request = request(&config);
+
+        assert_eq!(request.workflow_run_timeout, None);
+        assert_eq!(request.workflow_execution_timeout, seconds(6 * 3600));
+        assert_eq!(
+            request.retry_policy,
+            Some(RetryPolicy {
+                initial_interval: seconds(2),
+                backoff_coefficient: 1.5,
+                maximum_interval: seconds(600),
+                // max_attempts wins over retries, 0 meaning unlimited
+                maximum_attempts: 0,
+                non_retryable_error_types: vec!["ValidationError".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn test_partial_retry_policy_keeps_temporal_defaults() {
+        let config = WorkflowConfig {
+            retries: 2,
+            retry_policy: Some(WorkflowRetryPolicy {
+                non_retryable_error_types: vec!["AuthError".to_string()],
+                ..Default::default()
+            }),
+            ..WorkflowConfig::new("etl".to_string())
+        };
+        let retry_policy = request(&config).retry_policy.unwrap();
+
+        assert_eq!(retry_policy.maximum_attempts, 3);
+        assert_eq!(retry_policy.initial_interval, None);
+        assert_eq!(retry_policy.backoff_coefficient, 0.0);
+        assert_eq!(retry_policy.non_retryable_error_types, ["AuthError"]);
+    }
+}
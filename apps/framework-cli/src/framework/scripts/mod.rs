@@ -7,10 +7,12 @@ pub mod config;
 pub mod executor;
 pub mod utils;
 
-use crate::framework::scripts::config::WorkflowConfig;
+use crate::framework::scripts::config::{WorkflowConfig, WorkflowRetryPolicy};
 use crate::infrastructure::orchestration::temporal::TemporalConfig;
 use crate::proto::infrastructure_map::Workflow as ProtoWorkflow;
+use crate::proto::infrastructure_map::WorkflowRetryPolicy as ProtoWorkflowRetryPolicy;
 use anyhow::Result;
+use protobuf::MessageField;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +51,23 @@ impl Workflow {
         }
     }
 
+    /// Sets the retry policy and execution timeout declared with the workflow.
+    pub fn with_execution_options(
+        mut self,
+        retry_policy: Option<WorkflowRetryPolicy>,
+        execution_timeout: Option<String>,
+    ) -> Self {
+        self.config.retry_policy = retry_policy;
+        self.config.execution_timeout = execution_timeout;
+        self
+    }
+
+    /// Sets the time zone the schedule is read in.
+    pub fn with_timezone(mut self, timezone: Option<String>) -> Self {
+        self.config.timezone = timezone;
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -57,6 +76,10 @@ impl Workflow {
         &self.config
     }
 
+    pub fn language(&self) -> SupportedLanguages {
+        self.language
+    }
+
     /// Returns lineage sources this workflow reads from.
     pub fn pulls_data_from(&self) -> &[InfrastructureSignature] {
         &self.pulls_data_from
@@ -92,6 +115,18 @@ impl Workflow {
             language: self.language.to_string(),
             pulls_data_from: self.pulls_data_from.iter().map(|s| s.to_proto()).collect(),
             pushes_data_to: self.pushes_data_to.iter().map(|s| s.to_proto()).collect(),
+            retry_policy: MessageField::from_option(self.config.retry_policy.as_ref().map(
+                |policy| ProtoWorkflowRetryPolicy {
+                    initial_interval: policy.initial_interval.clone(),
+                    backoff_coefficient: policy.backoff_coefficient,
+                    maximum_interval: policy.maximum_interval.clone(),
+                    max_attempts: policy.max_attempts,
+                    non_retryable_error_types: policy.non_retryable_error_types.clone(),
+                    special_fields: Default::default(),
+                },
+            )),
+            execution_timeout: self.config.execution_timeout.clone(),
+            timezone: self.config.timezone.clone(),
             special_fields: Default::default(),
         }
     }
@@ -100,8 +135,20 @@ impl Workflow {
         let config = WorkflowConfig {
             name: proto.name.clone(),
             schedule: proto.schedule,
+            timezone: proto.timezone,
             retries: proto.retries,
             timeout: proto.timeout,
+            execution_timeout: proto.execution_timeout,
+            retry_policy: proto
+                .retry_policy
+                .into_option()
+                .map(|policy| WorkflowRetryPolicy {
+                    initial_interval: policy.initial_interval,
+                    backoff_coefficient: policy.backoff_coefficient,
+                    maximum_interval: policy.maximum_interval,
+                    max_attempts: policy.max_attempts,
+                    non_retryable_error_types: policy.non_retryable_error_types,
+                }),
             tasks: None,
         };
 
@@ -156,5 +203,35 @@ mod tests {
         assert_eq!(workflow.config().timeout, restored.config().timeout);
         assert_eq!(restored.pulls_data_from(), expected_pulls);
         assert_eq!(restored.pushes_data_to(), expected_pushes);
+        assert_eq!(restored.config().retry_policy, None);
+        assert_eq!(restored.config().execution_timeout, None);
+        assert_eq!(restored.config().timezone, None);
+    }
+
+    #[test]
+    fn test_workflow_proto_roundtrip_with_retry_policy() {
+        let retry_policy = WorkflowRetryPolicy {
+            initial_interval: Some("1s".to_string()),
+            backoff_coefficient: Some(1.5),
+            maximum_interval: None,
+            max_attempts: Some(0),
+            non_retryable_error_types: vec!["ValidationError".to_string()],
+        };
+        let workflow = Workflow::from_user_code(
+            "flaky_api".to_string(),
+            SupportedLanguages::Python,
+            None,
+            None,
+            None,
+            vec![],
+            vec![],
+        )
+        .with_execution_options(Some(retry_policy.clone()), Some("6h".to_string()))
+        .with_timezone(Some("Europe/Paris".to_string()));
+
+        let restored = Workflow::from_proto(workflow.to_proto());
+        assert_eq!(restored.config().retry_policy, Some(retry_policy));
+        assert_eq!(restored.config().execution_timeout.as_deref(), Some("6h"));
+        assert_eq!(restored.config().timezone.as_deref(), Some("Europe/Paris"));
     }
 }
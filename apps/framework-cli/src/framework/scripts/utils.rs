@@ -75,3 +75,267 @@ pub fn parse_timeout_to_seconds(timeout: &str) -> Result<i64, TemporalExecutionE
 
     Ok(seconds as i64)
 }
+
+/// Shortcuts Temporal accepts in place of the five cron fields
+const CRON_SHORTCUTS: [&str; 7] = [
+    "@yearly",
+    "@annually",
+    "@monthly",
+    "@weekly",
+    "@daily",
+    "@midnight",
+    "@hourly",
+];
+
+struct CronField {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    /// Names of the values from `min`, e.g. JAN for the month 1
+    names: &'static [&'static str],
+    /// Whether `?` can stand for any value
+    allows_any: bool,
+}
+
+const CRON_FIELDS: [CronField; 5] = [
+    CronField {
+        name: "minute",
+        min: 0,
+        max: 59,
+        names: &[],
+        allows_any: false,
+    },
+    CronField {
+        name: "hour",
+        min: 0,
+        max: 23,
+        names: &[],
+        allows_any: false,
+    },
+    CronField {
+        name: "day of month",
+        min: 1,
+        max: 31,
+        names: &[],
+        allows_any: true,
+    },
+    CronField {
+        name: "month",
+        min: 1,
+        max: 12,
+        names: &[
+            "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+        ],
+        allows_any: false,
+    },
+    // 7 is Sunday too
+    CronField {
+        name: "day of week",
+        min: 0,
+        max: 7,
+        names: &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"],
+        allows_any: true,
+    },
+];
+
+impl CronField {
+    fn validate(&self, field: &str) -> Result<(), String> {
+        for item in field.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+            if let Some(step) = step {
+                if !step.parse::<u32>().is_ok_and(|step| step > 0) {
+                    return Err(format!("invalid step '{step}' in {} '{field}'", self.name));
+                }
+            }
+            if range == "*" || (range == "?" && self.allows_any) {
+                continue;
+            }
+            match range.split_once('-') {
+                Some((start, end)) => {
+                    if self.value(start, field)? > self.value(end, field)? {
+                        return Err(format!("reversed range '{range}' in {}", self.name));
+                    }
+                }
+                None => {
+                    self.value(range, field)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn value(&self, token: &str, field: &str) -> Result<u32, String> {
+        let value = token.parse::<u32>().ok().or_else(|| {
+            self.names
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(token))
+                .map(|index| self.min + index as u32)
+        });
+        match value {
+            Some(value) if (self.min..=self.max).contains(&value) => Ok(value),
+            Some(value) => Err(format!(
+                "{} {value} is out of range {}-{}",
+                self.name, self.min, self.max
+            )),
+            None => Err(format!(
+                "'{token}' is not a valid {} in '{field}'",
+                self.name
+            )),
+        }
+    }
+}
+
+/// Whether `interval` is a sequence of numbers with a unit, e.g. `1h30m`,
+/// the units being s, m, h and d.
+fn is_interval(interval: &str) -> bool {
+    let mut parts = 0;
+    let mut digits = String::new();
+    for c in interval.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            's' | 'm' | 'h' | 'd' if !digits.is_empty() => {
+                if digits.parse::<u64>().is_ok_and(|value| value > 0) {
+                    parts += 1;
+                }
+                digits.clear();
+            }
+            _ => return false,
+        }
+    }
+    parts > 0 && digits.is_empty()
+}
+
+/// Checks a cron expression, as produced by [`parse_schedule`], the way
+/// Temporal Schedules read it, returning why it is invalid.
+///
+/// # Formats Supported
+/// * Five fields: minute, hour, day of month, month and day of week, with
+///   `*`, lists, ranges, steps and month or day names
+/// * Shortcuts such as `@daily` or `@hourly`
+/// * `@every <interval>`, e.g. `@every 90m` or `@every 1h30m`
+pub fn validate_cron(expression: &str) -> Result<(), String> {
+    let expression = expression.trim();
+    if let Some(every) = expression.strip_prefix("@every ") {
+        let every = every.trim();
+        return if is_interval(every) {
+            Ok(())
+        } else {
+            Err(format!(
+                "'{every}' is not an interval such as 30s, 5m, 1h30m or 7d"
+            ))
+        };
+    }
+    if expression.starts_with('@') {
+        return if CRON_SHORTCUTS.contains(&expression.to_lowercase().as_str()) {
+            Ok(())
+        } else {
+            Err(format!("unknown shortcut '{expression}'"))
+        };
+    }
+
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    if fields.len() != CRON_FIELDS.len() {
+        return Err(format!(
+            "expected 5 fields (minute hour day-of-month month day-of-week), found {}",
+            fields.len()
+        ));
+    }
+    fields
+        .iter()
+        .zip(CRON_FIELDS.iter())
+        .try_for_each(|(field, spec)| spec.validate(field))
+}
+
+/// Whether `name` looks like an IANA time zone name, e.g. `Europe/Paris`.
+/// Temporal checks that the zone exists when the schedule is created.
+pub fn is_timezone_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('/').all(|part| {
+            !part.is_empty()
+                && part != ".."
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+' | '.'))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_cron_accepts_temporal_expressions() {
+        for expression in [
+            "0 2 * * *",
+            "*/15 * * * *",
+            "0 9-17 * * MON-FRI",
+            "30 6 1,15 * ?",
+            "0 0 1 jan,jul *",
+            "@daily",
+            "@every 90m",
+            "@every 1h30m",
+            "@every 7d",
+            "0 0 * * 7",
+            // From the shorthand formats of parse_schedule
+            &parse_schedule("5m"),
+            &parse_schedule("2h"),
+        ] {
+            assert_eq!(validate_cron(expression), Ok(()), "{expression}");
+        }
+    }
+
+    #[test]
+    fn test_validate_cron_explains_invalid_expressions() {
+        assert_eq!(
+            validate_cron("0 2 * *"),
+            Err(
+                "expected 5 fields (minute hour day-of-month month day-of-week), found 4"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            validate_cron("0 24 * * *"),
+            Err("hour 24 is out of range 0-23".to_string())
+        );
+        assert_eq!(
+            validate_cron("0 0 * FOO *"),
+            Err("'FOO' is not a valid month in 'FOO'".to_string())
+        );
+        assert_eq!(
+            validate_cron("*/0 * * * *"),
+            Err("invalid step '0' in minute '*/0'".to_string())
+        );
+        assert_eq!(
+            validate_cron("0 0 * * FRI-MON"),
+            Err("reversed range 'FRI-MON' in day of week".to_string())
+        );
+        assert_eq!(
+            validate_cron("? * * * *"),
+            Err("'?' is not a valid minute in '?'".to_string())
+        );
+        assert_eq!(
+            validate_cron("@nightly"),
+            Err("unknown shortcut '@nightly'".to_string())
+        );
+        assert!(validate_cron("@every soon").is_err());
+        assert!(validate_cron("@every 1hm").is_err());
+        assert!(validate_cron("@every 0s").is_err());
+        assert!(validate_cron("@every 90").is_err());
+        // Unrecognized shorthand is passed through by parse_schedule
+        assert!(validate_cron(&parse_schedule("1d")).is_err());
+    }
+
+    #[test]
+    fn test_is_timezone_name() {
+        assert!(is_timezone_name("UTC"));
+        assert!(is_timezone_name("America/New_York"));
+        assert!(is_timezone_name("Etc/GMT+5"));
+        assert!(!is_timezone_name(""));
+        assert!(!is_timezone_name("Europe/"));
+        assert!(!is_timezone_name("../etc/passwd"));
+        assert!(!is_timezone_name("New York"));
+    }
+}
@@ -1,6 +1,8 @@
+use crate::framework::core::infrastructure::sql_resource::SqlResource;
 use crate::framework::core::infrastructure::table::{
-    ColumnType, DataEnum, EnumValue, FloatType, JsonOptions, Nested, OrderBy, Table,
+    ColumnType, DataEnum, EnumValue, FloatType, JsonOptions, Nested, OrderBy, Table, TableIndex,
 };
+use crate::framework::core::infrastructure::InfrastructureSignature;
 use crate::framework::core::partial_infrastructure_map::LifeCycle;
 use crate::utilities::identifiers as ident;
 use convert_case::{Case, Casing};
@@ -320,6 +322,32 @@ fn generate_json_inner_interface(
     interface
 }
 
+/// An entry of the `indexes` array of an `OlapTable` config
+pub fn index_declaration(idx: &TableIndex) -> String {
+    let args_list = format!(
+        "[{}]",
+        idx.arguments
+            .iter()
+            .map(|a| format!("{:?}", a))
+            .collect::<Vec<String>>()
+            .join(", ")
+    );
+    format!(
+        "{{ name: {:?}, expression: {:?}, type: {:?}, arguments: {}, granularity: {} }}",
+        idx.name, idx.expression, idx.index_type, args_list, idx.granularity
+    )
+}
+
+/// The `settings` object of an `OlapTable` config, sorted by name
+pub fn settings_declaration(settings: &HashMap<String, String>) -> String {
+    let entries = settings
+        .iter()
+        .sorted()
+        .map(|(key, value)| format!(" {}: {:?}", quote_name_if_needed(key), value))
+        .join(",");
+    format!("{{{entries} }}")
+}
+
 pub fn tables_to_typescript(tables: &[Table], life_cycle: Option<LifeCycle>) -> String {
     let mut output = String::new();
 
@@ -965,6 +993,9 @@ pub fn tables_to_typescript(tables: &[Table], life_cycle: Option<LifeCycle>) ->
                 writeln!(output, "    sourceDatabase: {:?},", source_database).unwrap();
                 writeln!(output, "    tablesRegexp: {:?},", tables_regexp).unwrap();
             }
+            crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine::Null => {
+                writeln!(output, "    engine: ClickHouseEngines.Null,").unwrap();
+            }
         }
         // Skip version for externally managed tables — the infra map appends
         // `_{version}` to the table name, which would corrupt external table names
@@ -977,14 +1008,7 @@ pub fn tables_to_typescript(tables: &[Table], life_cycle: Option<LifeCycle>) ->
         // Add table settings if present (works for all engines)
         if let Some(settings) = &table.table_settings {
             if !settings.is_empty() {
-                write!(output, "    settings: {{").unwrap();
-                for (i, (key, value)) in settings.iter().enumerate() {
-                    if i > 0 {
-                        write!(output, ",").unwrap();
-                    }
-                    write!(output, " {}: {:?}", key, value).unwrap();
-                }
-                writeln!(output, " }},").unwrap();
+                writeln!(output, "    settings: {},", settings_declaration(settings)).unwrap();
             }
         }
         if let Some(life_cycle) = life_cycle {
@@ -1001,28 +1025,7 @@ pub fn tables_to_typescript(tables: &[Table], life_cycle: Option<LifeCycle>) ->
         if !table.indexes.is_empty() {
             writeln!(output, "    indexes: [").unwrap();
             for idx in &table.indexes {
-                let args_list = if idx.arguments.is_empty() {
-                    String::from("[]")
-                } else {
-                    format!(
-                        "[{}]",
-                        idx.arguments
-                            .iter()
-                            .map(|a| format!("{:?}", a))
-                            .collect::<Vec<String>>()
-                            .join(", ")
-                    )
-                };
-                writeln!(
-                    output,
-                    "        {{ name: {:?}, expression: {:?}, type: {:?}, arguments: {}, granularity: {} }},",
-                    idx.name,
-                    idx.expression,
-                    idx.index_type,
-                    args_list,
-                    idx.granularity
-                )
-                .unwrap();
+                writeln!(output, "        {},", index_declaration(idx)).unwrap();
             }
             writeln!(output, "    ],").unwrap();
         }
@@ -1056,6 +1059,156 @@ pub fn tables_to_typescript(tables: &[Table], life_cycle: Option<LifeCycle>) ->
     output
 }
 
+/// Whether a lineage id produced by SQL resource introspection refers to `table`.
+fn table_matches_lineage_id(table: &Table, id: &str) -> bool {
+    table.name == id || extract_version_from_table_name(&table.name).0 == id
+}
+
+/// A TypeScript string literal for `value`, for embedding SQL and names verbatim.
+fn typescript_string_literal(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            // Line and paragraph separators end lines in older JavaScript engines
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                write!(literal, "\\u{:04x}", c as u32).unwrap()
+            }
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Generates `SqlResource` definitions for views, materialized views and dictionaries
+/// introspected from a remote database.
+///
+/// The resources already exist in the database, so they are marked externally managed.
+/// Resources are expected in dependency order. `tables` are defined in the same module
+/// as the generated code, while `external_tables` live in the externally managed models
+/// file and are imported by name when a resource depends on them. Dependencies that
+/// cannot be resolved are listed in a comment above the resource.
+pub fn sql_resources_to_typescript(
+    resources: &[SqlResource],
+    tables: &[Table],
+    external_tables: &[Table],
+) -> String {
+    let mut output = String::new();
+    let mut body = String::new();
+    let mut external_imports: Vec<String> = Vec::new();
+
+    for (i, resource) in resources.iter().enumerate() {
+        let mut unresolved = Vec::new();
+        let mut resolve = |deps: &[InfrastructureSignature]| -> Vec<String> {
+            let mut vars = Vec::new();
+            for dep in deps {
+                let id = dep.id();
+                if let Some(table) = tables.iter().find(|t| table_matches_lineage_id(t, id)) {
+                    vars.push(format!(
+                        "{}Table",
+                        sanitize_typescript_identifier(&table.name)
+                    ));
+                } else if let Some(table) = external_tables
+                    .iter()
+                    .find(|t| table_matches_lineage_id(t, id))
+                {
+                    let var = format!("{}Table", sanitize_typescript_identifier(&table.name));
+                    if !external_imports.contains(&var) {
+                        external_imports.push(var.clone());
+                    }
+                    vars.push(var);
+                } else if let Some(upstream) = resources[..i].iter().find(|r| r.name == id) {
+                    vars.push(format!(
+                        "{}SqlResource",
+                        sanitize_typescript_identifier(&upstream.name)
+                    ));
+                } else {
+                    unresolved.push(id.to_string());
+                }
+            }
+            vars
+        };
+        let pulls_data_from = resolve(&resource.pulls_data_from);
+        let pushes_data_to = resolve(&resource.pushes_data_to);
+
+        if !unresolved.is_empty() {
+            writeln!(
+                body,
+                "// Depends on resources not defined in this project: {}",
+                unresolved.join(", ")
+            )
+            .unwrap();
+        }
+        writeln!(
+            body,
+            "export const {}SqlResource = new SqlResource(",
+            sanitize_typescript_identifier(&resource.name)
+        )
+        .unwrap();
+        writeln!(body, "    {},", typescript_string_literal(&resource.name)).unwrap();
+        writeln!(
+            body,
+            "    [{}],",
+            resource
+                .setup
+                .iter()
+                .map(|s| typescript_string_literal(s))
+                .join(", ")
+        )
+        .unwrap();
+        writeln!(
+            body,
+            "    [{}],",
+            resource
+                .teardown
+                .iter()
+                .map(|s| typescript_string_literal(s))
+                .join(", ")
+        )
+        .unwrap();
+        writeln!(body, "    {{").unwrap();
+        writeln!(
+            body,
+            "        pullsDataFrom: [{}],",
+            pulls_data_from.join(", ")
+        )
+        .unwrap();
+        writeln!(
+            body,
+            "        pushesDataTo: [{}],",
+            pushes_data_to.join(", ")
+        )
+        .unwrap();
+        writeln!(body, "        lifeCycle: LifeCycle.EXTERNALLY_MANAGED,").unwrap();
+        writeln!(body, "    }},").unwrap();
+        writeln!(body, ");").unwrap();
+        writeln!(body).unwrap();
+    }
+
+    writeln!(
+        output,
+        "import {{ LifeCycle, SqlResource }} from \"@514labs/moose-lib\";"
+    )
+    .unwrap();
+    if !external_imports.is_empty() {
+        writeln!(
+            output,
+            "import {{ {} }} from \"./externalModels\";",
+            external_imports.join(", ")
+        )
+        .unwrap();
+    }
+    writeln!(output).unwrap();
+    output.push_str(&body);
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1187,6 +1340,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -1279,6 +1433,7 @@ export const UserTable = new OlapTable<User>("User", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -1337,6 +1492,7 @@ export const UserTable = new OlapTable<User>("User", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -1420,6 +1576,7 @@ export const UserTable = new OlapTable<User>("User", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -1472,6 +1629,7 @@ export const UserTable = new OlapTable<User>("User", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -1562,6 +1720,7 @@ export const UserTable = new OlapTable<User>("User", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -1632,6 +1791,7 @@ export const UserTable = new OlapTable<User>("User", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -1713,6 +1873,7 @@ export const UserTable = new OlapTable<User>("User", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -1804,6 +1965,7 @@ export const TaskTable = new OlapTable<Task>("Task", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -1881,6 +2043,7 @@ export const TaskTable = new OlapTable<Task>("Task", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -1936,6 +2099,7 @@ export const TaskTable = new OlapTable<Task>("Task", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -2011,6 +2175,7 @@ export const TaskTable = new OlapTable<Task>("Task", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -2078,6 +2243,7 @@ export const TaskTable = new OlapTable<Task>("Task", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, Some(LifeCycle::ExternallyManaged));
@@ -2152,6 +2318,7 @@ export const TaskTable = new OlapTable<Task>("Task", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -2241,6 +2408,7 @@ export const TaskTable = new OlapTable<Task>("Task", {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }];
 
         let result = tables_to_typescript(&tables, None);
@@ -2261,4 +2429,94 @@ export const TaskTable = new OlapTable<Task>("Task", {
             result
         );
     }
+
+    fn test_lineage_table(name: &str) -> Table {
+        Table {
+            name: name.to_string(),
+            columns: vec![],
+            order_by: OrderBy::Fields(vec![]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_sql_resources_to_typescript_imports_external_dependencies() {
+        let managed = vec![test_lineage_table("daily_counts")];
+        let external = vec![test_lineage_table("raw_events")];
+        let resources = vec![
+            SqlResource {
+                name: "events_mv".to_string(),
+                database: None,
+                source_file: None,
+                source_line: None,
+                source_column: None,
+                setup: vec![
+                    "CREATE MATERIALIZED VIEW IF NOT EXISTS events_mv TO daily_counts AS SELECT * FROM raw_events".to_string(),
+                ],
+                teardown: vec!["DROP VIEW IF EXISTS `events_mv`".to_string()],
+                pulls_data_from: vec![InfrastructureSignature::Table {
+                    id: "raw_events".to_string(),
+                }],
+                pushes_data_to: vec![InfrastructureSignature::Table {
+                    id: "daily_counts".to_string(),
+                }],
+                life_cycle: Default::default(),
+            },
+            SqlResource {
+                name: "top_counts".to_string(),
+                database: None,
+                source_file: None,
+                source_line: None,
+                source_column: None,
+                setup: vec!["CREATE VIEW IF NOT EXISTS top_counts AS SELECT * FROM events_mv".to_string()],
+                teardown: vec!["DROP VIEW IF EXISTS `top_counts`".to_string()],
+                pulls_data_from: vec![InfrastructureSignature::Table {
+                    id: "events_mv".to_string(),
+                }],
+                pushes_data_to: vec![],
+                life_cycle: Default::default(),
+            },
+        ];
+
+        let code = sql_resources_to_typescript(&resources, &managed, &external);
+
+        assert!(code.contains("import { LifeCycle, SqlResource } from \"@514labs/moose-lib\";"));
+        assert!(code.contains("import { RawEventsTable } from \"./externalModels\";"));
+        assert!(code
+            .contains("export const EventsMvSqlResource = new SqlResource(\n    \"events_mv\",\n"));
+        assert!(code.contains(
+            "        pullsDataFrom: [RawEventsTable],\n        pushesDataTo: [DailyCountsTable],\n"
+        ));
+        assert!(code.contains(
+            "        pullsDataFrom: [EventsMvSqlResource],\n        pushesDataTo: [],\n        lifeCycle: LifeCycle.EXTERNALLY_MANAGED,\n"
+        ));
+    }
+
+    #[test]
+    fn test_typescript_string_literal_escapes_sql() {
+        assert_eq!(
+            typescript_string_literal("SELECT 'a\\b', \"c\"\n\tFROM t -- é\u{2028}\u{1}"),
+            r#""SELECT 'a\\b', \"c\"\n\tFROM t -- é\u2028\u0001""#
+        );
+    }
 }
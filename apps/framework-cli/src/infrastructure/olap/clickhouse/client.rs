@@ -2,19 +2,19 @@ use base64::prelude::*;
 use http_body_util::BodyExt;
 use http_body_util::Full;
 
-use async_recursion::async_recursion;
 use hyper::body::Bytes;
 use hyper::{Request, Response, Uri};
 use hyper_tls::HttpsConnector;
 use hyper_util::client::legacy::{connect::HttpConnector, Client};
-use tokio::time::{sleep, Duration};
+use tokio::time::Duration;
 use tracing::debug;
 
 use super::config::ClickHouseConfig;
 use super::errors::{validate_clickhouse_identifier, ClickhouseError};
-use super::model::{wrap_and_join_column_names, ClickHouseRecord};
+use super::model::{quote_qualified, wrap_and_join_column_names, ClickHouseRecord};
 use super::queries::drop_table_query;
 use super::remote::ClickHouseRemote;
+use crate::utilities::retry::{self, RetryPolicy, RetryTarget};
 
 use tracing::error;
 
@@ -27,9 +27,14 @@ pub struct ClickHouseClient {
 }
 
 // Considering Clickhouse could take 30s to wake up, we need to have a backoff strategy
-const BACKOFF_START_MILLIS: u64 = 1000;
-const MAX_RETRIES: u8 = 10;
-// Retries will be 1s, 2s, 4s, 8s, 16s, 32s, 64s, 128s, 256s, 512s
+// Unless `[retries.clickhouse]` is configured, retries will be 1s, 2s, 4s, 8s, 16s, 32s, 64s, 128s, 256s, 512s
+const DEFAULT_RETRY_POLICY: RetryPolicy = RetryPolicy {
+    max_attempts: Some(11),
+    base_delay: Duration::from_secs(1),
+    max_delay: Duration::from_secs(512),
+    jitter: 0.0,
+    max_elapsed: None,
+};
 
 // TODO - investigate if we need to change basic auth
 impl ClickHouseClient {
@@ -50,34 +55,23 @@ impl ClickHouseClient {
         &self.config
     }
 
-    #[async_recursion]
     async fn request(
         &self,
         req: Request<Full<Bytes>>,
-        retries: u8,
-        backoff_millis: u64,
     ) -> Result<Response<hyper::body::Incoming>, hyper_util::client::legacy::Error> {
-        let res = if self.config.use_ssl {
-            self.ssl_client.request(req.clone()).await
-        } else {
-            self.client.request(req.clone()).await
-        };
-
-        match res {
-            Ok(res) => Ok(res),
-            Err(e) => {
-                if e.is_connect() {
-                    if retries > 0 {
-                        sleep(Duration::from_millis(backoff_millis)).await;
-                        self.request(req, retries - 1, backoff_millis * 2).await
-                    } else {
-                        Err(e)
-                    }
+        let policy = retry::policy(RetryTarget::ClickHouse, DEFAULT_RETRY_POLICY);
+        retry::retry(
+            || async {
+                if self.config.use_ssl {
+                    self.ssl_client.request(req.clone()).await
                 } else {
-                    Err(e)
+                    self.client.request(req.clone()).await
                 }
-            }
-        }
+            },
+            |e| e.is_connect(),
+            &policy,
+        )
+        .await
     }
 
     pub async fn ping(&mut self) -> anyhow::Result<()> {
@@ -88,8 +82,7 @@ impl ClickHouseClient {
             .uri("/ping")
             .body(Full::new(empty_body))?;
 
-        let res: Response<hyper::body::Incoming> =
-            self.request(req, MAX_RETRIES, BACKOFF_START_MILLIS).await?;
+        let res: Response<hyper::body::Incoming> = self.request(req).await?;
 
         assert_eq!(res.status(), 200);
         Ok(())
@@ -148,9 +141,8 @@ impl ClickHouseClient {
         columns: &[String],
         records: &[ClickHouseRecord],
     ) -> anyhow::Result<()> {
-        let target_db = database.unwrap_or(&self.config.db_name);
         // TODO - this could be optimized with RowBinary instead
-        let insert_query = build_insert_query(target_db, table_name, columns);
+        let insert_query = self.insert_query(table_name, database, columns);
 
         debug!("Inserting into clickhouse: {}", insert_query);
 
@@ -171,7 +163,7 @@ impl ClickHouseClient {
             .header("Content-Length", bytes.len())
             .body(Full::new(bytes))?;
 
-        let res = self.request(req, MAX_RETRIES, BACKOFF_START_MILLIS).await?;
+        let res = self.request(req).await?;
 
         let status = res.status();
 
@@ -192,6 +184,54 @@ impl ClickHouseClient {
         }
     }
 
+    /// Builds the INSERT into `table_name` of `database`, or of the
+    /// configured database when `None`
+    fn insert_query(&self, table_name: &str, database: Option<&str>, columns: &[String]) -> String {
+        build_insert_query(
+            database.unwrap_or(&self.config.db_name),
+            table_name,
+            columns,
+        )
+    }
+
+    /// Executes an `INSERT ... FORMAT <fmt>` statement with a raw request body.
+    ///
+    /// The body is sent as-is, so it must already be encoded in the format
+    /// named by the statement (e.g. Parquet bytes or CSVWithNames text).
+    ///
+    /// # Arguments
+    /// * `sql` - The INSERT statement, ending with a `FORMAT` clause
+    /// * `body` - The encoded rows
+    pub async fn insert_raw(&self, sql: &str, body: Bytes) -> anyhow::Result<()> {
+        let query: String = query_param(sql, None)?;
+        let uri = self.uri(format!("/?{query}"))?;
+
+        debug!("Inserting raw body ({} bytes): {}", body.len(), sql);
+
+        let req = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("Host", self.host())
+            .header("Authorization", self.auth_header())
+            .header("Content-Length", body.len())
+            .body(Full::new(body))?;
+
+        let res = self.request(req).await?;
+        let status = res.status();
+
+        if status != 200 {
+            let body = res.collect().await?.to_bytes().to_vec();
+            let body_str = String::from_utf8(body)?;
+            error!("Failed to insert raw body: Res {} - {}", &status, body_str);
+            Err(anyhow::anyhow!(
+                "Failed to insert into clickhouse: {}",
+                body_str
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
     /// Executes a SQL statement without a body (e.g., INSERT...SELECT, CREATE TABLE, etc.)
     ///
     /// # Arguments
@@ -211,7 +251,7 @@ impl ClickHouseClient {
             .header("Authorization", self.auth_header())
             .header("Content-Length", 0)
             .body(Full::new(Bytes::new()))?;
-        let res = self.request(req, MAX_RETRIES, BACKOFF_START_MILLIS).await?;
+        let res = self.request(req).await?;
         let status = res.status();
         let response_body = res.collect().await?.to_bytes().to_vec();
         let body_str = String::from_utf8(response_body)?;
@@ -303,30 +343,32 @@ impl ClickHouseClient {
         let col_list = if columns.is_empty() {
             "*".to_string()
         } else {
-            columns
-                .iter()
-                .map(|c| format!("`{}`", c))
-                .collect::<Vec<_>>()
-                .join(", ")
+            wrap_and_join_column_names(columns, ", ")
         };
 
         // FORMAT is required here: it tells the *remote* server to return JSONEachRow.
         // The format param in query_function_with_format tells the *local* url() how to parse it.
         let select_query = format!(
-            "SELECT {} FROM `{}`.`{}` LIMIT {} FORMAT JSONEachRow",
-            col_list, remote_database, table_name, limit
+            "SELECT {} FROM {} LIMIT {} FORMAT JSONEachRow",
+            col_list,
+            quote_qualified(remote_database, table_name),
+            limit
         );
         let remote_func = remote.query_function_with_format(&select_query, "JSONEachRow");
 
         let insert_sql = if columns.is_empty() {
             format!(
-                "INSERT INTO `{}`.`{}` SELECT * FROM {}",
-                local_database, table_name, remote_func
+                "INSERT INTO {} SELECT * FROM {}",
+                quote_qualified(local_database, table_name),
+                remote_func
             )
         } else {
             format!(
-                "INSERT INTO `{}`.`{}` ({}) SELECT {} FROM {}",
-                local_database, table_name, col_list, col_list, remote_func
+                "INSERT INTO {} ({}) SELECT {} FROM {}",
+                quote_qualified(local_database, table_name),
+                col_list,
+                col_list,
+                remote_func
             )
         };
 
@@ -349,12 +391,11 @@ const DDL_COMMANDS: &[&str] = &["INSERT", "CREATE", "ALTER", "DROP", "TRUNCATE"]
 /// * `columns` - The column names to insert
 ///
 /// # Returns
-/// A formatted INSERT query string like: `INSERT INTO "db"."table" ("col1","col2") VALUES`
+/// A formatted INSERT query string like: ``INSERT INTO `db`.`table` (`col1`,`col2`) VALUES``
 fn build_insert_query(database: &str, table_name: &str, columns: &[String]) -> String {
     format!(
-        "INSERT INTO \"{}\".\"{}\" ({}) VALUES",
-        database,
-        table_name,
+        "INSERT INTO {} ({}) VALUES",
+        quote_qualified(database, table_name),
         wrap_and_join_column_names(columns, ","),
     )
 }
@@ -366,14 +407,17 @@ fn build_insert_query(database: &str, table_name: &str, columns: &[String]) -> S
 /// * `table_name` - The table name (must be a valid identifier)
 ///
 /// # Returns
-/// A formatted EXISTS TABLE query string like: `EXISTS TABLE "db"."table"`
+/// A formatted EXISTS TABLE query string like: ``EXISTS TABLE `db`.`table` ``
 ///
 /// # Errors
 /// Returns an error if database or table_name contains invalid characters
 fn build_exists_table_query(database: &str, table_name: &str) -> Result<String, ClickhouseError> {
     validate_clickhouse_identifier(database, "Database name")?;
     validate_clickhouse_identifier(table_name, "Table name")?;
-    Ok(format!("EXISTS TABLE \"{}\".\"{}\"", database, table_name))
+    Ok(format!(
+        "EXISTS TABLE {}",
+        quote_qualified(database, table_name)
+    ))
 }
 
 fn query_param(query: &str, database: Option<&str>) -> anyhow::Result<String> {
@@ -552,7 +596,7 @@ mod tests {
         let columns = vec!["id".to_string(), "name".to_string()];
         let result = build_insert_query("custom_db", "my_table", &columns);
         assert_eq!(
-            result, "INSERT INTO \"custom_db\".\"my_table\" (`id`,`name`) VALUES",
+            result, "INSERT INTO `custom_db`.`my_table` (`id`,`name`) VALUES",
             "Should build INSERT query with correct database and table"
         );
     }
@@ -562,7 +606,7 @@ mod tests {
         let columns = vec!["col1".to_string()];
         let result = build_insert_query("local", "test_table", &columns);
         assert!(
-            result.contains(r#""local"."test_table""#),
+            result.contains("`local`.`test_table`"),
             "Should use provided database in query"
         );
     }
@@ -572,17 +616,39 @@ mod tests {
         let columns = vec!["user_id".to_string(), "event_time".to_string()];
         let result = build_insert_query("analytics_db", "user_events", &columns);
         assert_eq!(
-            result, "INSERT INTO \"analytics_db\".\"user_events\" (`user_id`,`event_time`) VALUES",
+            result, "INSERT INTO `analytics_db`.`user_events` (`user_id`,`event_time`) VALUES",
             "Should handle underscores in database and table names"
         );
     }
 
+    #[tokio::test]
+    async fn test_insert_query_targets_the_database_of_the_table() {
+        let client = ClickHouseClient::new(&ClickHouseConfig {
+            db_name: "local".to_string(),
+            additional_databases: vec!["analytics".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        let columns = vec!["id".to_string()];
+
+        // `Table.database` of the sync process target
+        let database = Some("analytics".to_string());
+        assert_eq!(
+            client.insert_query("events", database.as_deref(), &columns),
+            "INSERT INTO `analytics`.`events` (`id`) VALUES"
+        );
+        assert_eq!(
+            client.insert_query("events", None, &columns),
+            "INSERT INTO `local`.`events` (`id`) VALUES"
+        );
+    }
+
     #[test]
     fn test_build_exists_table_query() {
         let result = build_exists_table_query("test_db", "my_table").unwrap();
         assert_eq!(
-            result, "EXISTS TABLE \"test_db\".\"my_table\"",
-            "Should build EXISTS TABLE query with double-quoted identifiers"
+            result, "EXISTS TABLE `test_db`.`my_table`",
+            "Should build EXISTS TABLE query with backtick-quoted identifiers"
         );
     }
 
@@ -590,7 +656,7 @@ mod tests {
     fn test_build_exists_table_query_with_special_characters() {
         let result = build_exists_table_query("analytics_db", "user_events").unwrap();
         assert_eq!(
-            result, "EXISTS TABLE \"analytics_db\".\"user_events\"",
+            result, "EXISTS TABLE `analytics_db`.`user_events`",
             "Should handle underscores in database and table names"
         );
     }
@@ -0,0 +1,295 @@
+//! Checks the hosts of the clusters a plan runs `ON CLUSTER` DDL on.
+//!
+//! An `ON CLUSTER` statement waits for every host of the cluster to run it,
+//! up to `distributed_ddl_task_timeout`. With a host down, it hangs that long
+//! and then fails with TIMEOUT_EXCEEDED, aborting the plan. The hosts are
+//! checked before the first operation instead, and a statement timing out
+//! reports how far each host got with it.
+
+use async_trait::async_trait;
+
+use super::errors::clickhouse_error_code;
+use super::ConfiguredDBClient;
+
+/// Code of the error an `ON CLUSTER` statement fails with once
+/// `distributed_ddl_task_timeout` has passed
+const TIMEOUT_EXCEEDED: u32 = 159;
+
+/// The status of an `ON CLUSTER` statement on a host, from
+/// `system.distributed_ddl_queue`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DdlHostStatus {
+    /// `host:port`
+    pub host: String,
+    /// `Inactive`, `Active`, `Finished`, `Removing` or `Unknown`
+    pub status: String,
+    pub exception: Option<String>,
+}
+
+impl std::fmt::Display for DdlHostStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is {}", self.host, self.status)?;
+        if let Some(exception) = &self.exception {
+            write!(f, " ({exception})")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ClusterHealthError {
+    #[error("Hosts of cluster '{cluster}' are unreachable: {}. No operation was run, as ON CLUSTER DDL would wait for them until distributed_ddl_task_timeout", .hosts.join(", "))]
+    Unreachable { cluster: String, hosts: Vec<String> },
+
+    #[error("Failed to check the hosts of cluster '{cluster}'")]
+    Check {
+        cluster: String,
+        #[source]
+        error: clickhouse::error::Error,
+    },
+}
+
+/// The queries telling how the hosts of a cluster are doing
+#[async_trait]
+pub trait ClusterProbe: Sync {
+    /// `host:port` of the hosts of `cluster`, in the order of its shards and
+    /// replicas
+    async fn cluster_hosts(&self, cluster: &str) -> Result<Vec<String>, clickhouse::error::Error>;
+
+    /// Positions, from 1, of the hosts of `cluster` answering a query
+    async fn answering_hosts(&self, cluster: &str) -> Result<Vec<u32>, clickhouse::error::Error>;
+
+    /// The status on each host of the latest `ON CLUSTER` statement on
+    /// `table` sent to `cluster`
+    async fn ddl_statuses(
+        &self,
+        cluster: &str,
+        table: &str,
+    ) -> Result<Vec<DdlHostStatus>, clickhouse::error::Error>;
+}
+
+#[derive(clickhouse::Row, serde::Deserialize)]
+struct HostRow {
+    host_name: String,
+    port: u16,
+}
+
+#[derive(clickhouse::Row, serde::Deserialize)]
+struct DdlStatusRow {
+    entry: String,
+    host_port: String,
+    status_name: String,
+    exception: String,
+}
+
+#[async_trait]
+impl ClusterProbe for ConfiguredDBClient {
+    async fn cluster_hosts(&self, cluster: &str) -> Result<Vec<String>, clickhouse::error::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT host_name, port FROM system.clusters WHERE cluster = ? \
+                 ORDER BY shard_num, replica_num",
+            )
+            .bind(cluster)
+            .fetch_all::<HostRow>()
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| format!("{}:{}", row.host_name, row.port))
+            .collect())
+    }
+
+    async fn answering_hosts(&self, cluster: &str) -> Result<Vec<u32>, clickhouse::error::Error> {
+        // clusterAllReplicas numbers each replica as a shard of its own, in
+        // the order of system.clusters, and skips those it cannot reach
+        self.client
+            .query(
+                "SELECT DISTINCT _shard_num FROM clusterAllReplicas(?, system.one) \
+                 SETTINGS skip_unavailable_shards = 1",
+            )
+            .bind(cluster)
+            .fetch_all::<u32>()
+            .await
+    }
+
+    async fn ddl_statuses(
+        &self,
+        cluster: &str,
+        table: &str,
+    ) -> Result<Vec<DdlHostStatus>, clickhouse::error::Error> {
+        let rows = self
+            .client
+            .query(
+                "SELECT entry, \
+                 concat(ifNull(host, ''), ':', toString(ifNull(port, 0))) AS host_port, \
+                 toString(status) AS status_name, ifNull(exception_text, '') AS exception \
+                 FROM system.distributed_ddl_queue \
+                 WHERE cluster = ? AND position(query, ?) > 0 \
+                 ORDER BY entry DESC, host_port",
+            )
+            .bind(cluster)
+            .bind(table)
+            .fetch_all::<DdlStatusRow>()
+            .await?;
+
+        let latest = rows.first().map(|row| row.entry.clone());
+        Ok(rows
+            .into_iter()
+            .take_while(|row| Some(&row.entry) == latest.as_ref())
+            .map(|row| DdlHostStatus {
+                host: row.host_port,
+                status: row.status_name,
+                exception: Some(row.exception).filter(|exception| !exception.is_empty()),
+            })
+            .collect())
+    }
+}
+
+/// Fails when hosts of `clusters` don't answer, naming them.
+pub async fn ensure_reachable(
+    probe: &dyn ClusterProbe,
+    clusters: &[&str],
+) -> Result<(), ClusterHealthError> {
+    for cluster in clusters {
+        let check = |error| ClusterHealthError::Check {
+            cluster: cluster.to_string(),
+            error,
+        };
+        let hosts = probe.cluster_hosts(cluster).await.map_err(check)?;
+        let answering = probe.answering_hosts(cluster).await.map_err(check)?;
+
+        let unreachable: Vec<String> = hosts
+            .into_iter()
+            .zip(1..)
+            .filter(|(_, position)| !answering.contains(position))
+            .map(|(host, _)| host)
+            .collect();
+        if !unreachable.is_empty() {
+            return Err(ClusterHealthError::Unreachable {
+                cluster: cluster.to_string(),
+                hosts: unreachable,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The status on each host of the `ON CLUSTER` statement on `table` that
+/// failed with `message`, when it timed out waiting for them. `None` for other
+/// errors, or when the status cannot be read.
+pub async fn timed_out_ddl_statuses(
+    probe: &dyn ClusterProbe,
+    cluster: &str,
+    table: &str,
+    message: &str,
+) -> Option<Vec<DdlHostStatus>> {
+    if clickhouse_error_code(message) != Some(TIMEOUT_EXCEEDED) {
+        return None;
+    }
+    match probe.ddl_statuses(cluster, table).await {
+        Ok(statuses) if !statuses.is_empty() => Some(statuses),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to read the status of the DDL on '{table}' from system.distributed_ddl_queue: {e}"
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cluster `main` has three hosts, the second one down
+    struct MockProbe;
+
+    #[async_trait]
+    impl ClusterProbe for MockProbe {
+        async fn cluster_hosts(
+            &self,
+            cluster: &str,
+        ) -> Result<Vec<String>, clickhouse::error::Error> {
+            Ok(match cluster {
+                "main" => vec!["ch-1:9000", "ch-2:9000", "ch-3:9000"],
+                _ => vec!["ch-1:9000"],
+            }
+            .into_iter()
+            .map(String::from)
+            .collect())
+        }
+
+        async fn answering_hosts(
+            &self,
+            cluster: &str,
+        ) -> Result<Vec<u32>, clickhouse::error::Error> {
+            Ok(match cluster {
+                "main" => vec![3, 1],
+                _ => vec![1],
+            })
+        }
+
+        async fn ddl_statuses(
+            &self,
+            _cluster: &str,
+            _table: &str,
+        ) -> Result<Vec<DdlHostStatus>, clickhouse::error::Error> {
+            Ok(vec![
+                DdlHostStatus {
+                    host: "ch-1:9000".to_string(),
+                    status: "Finished".to_string(),
+                    exception: None,
+                },
+                DdlHostStatus {
+                    host: "ch-2:9000".to_string(),
+                    status: "Inactive".to_string(),
+                    exception: None,
+                },
+            ])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_hosts_are_named() {
+        assert!(ensure_reachable(&MockProbe, &["backup"]).await.is_ok());
+
+        let err = ensure_reachable(&MockProbe, &["backup", "main"])
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            ClusterHealthError::Unreachable { cluster, hosts }
+                if cluster == "main" && hosts == &["ch-2:9000".to_string()]
+        ));
+        assert!(err.to_string().contains("No operation was run"));
+    }
+
+    #[tokio::test]
+    async fn test_timeouts_report_the_status_on_each_host() {
+        let statuses = timed_out_ddl_statuses(
+            &MockProbe,
+            "main",
+            "events",
+            "Code: 159. DB::Exception: Distributed DDL task is not finished on 1 of 3 hosts. (TIMEOUT_EXCEEDED)",
+        )
+        .await
+        .unwrap();
+        let statuses: Vec<String> = statuses.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            statuses,
+            vec!["ch-1:9000 is Finished", "ch-2:9000 is Inactive"]
+        );
+
+        let other = timed_out_ddl_statuses(
+            &MockProbe,
+            "main",
+            "events",
+            "Code: 57. DB::Exception: Table local.events already exists. (TABLE_ALREADY_EXISTS)",
+        )
+        .await;
+        assert_eq!(other, None);
+    }
+}
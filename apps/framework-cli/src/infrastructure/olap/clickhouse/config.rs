@@ -9,6 +9,8 @@ use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use crate::infrastructure::olap::ddl_execution::DEFAULT_DDL_PARALLELISM;
+
 /// Default database name used by ClickHouse when not otherwise specified.
 /// This is used as the default value for ClickHouseConfig::db_name and for
 /// normalizing table IDs when table.database is None.
@@ -18,6 +20,17 @@ fn default_native_port() -> i32 {
     9000
 }
 
+fn default_ddl_parallelism() -> usize {
+    DEFAULT_DDL_PARALLELISM
+}
+
+/// Default of [`ClickHouseConfig::insert_error_threshold`]
+pub const DEFAULT_INSERT_ERROR_THRESHOLD: u64 = 10;
+
+fn default_insert_error_threshold() -> u64 {
+    DEFAULT_INSERT_ERROR_THRESHOLD
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ClusterConfig {
     pub name: String,
@@ -45,6 +58,13 @@ pub struct ClickHouseConfig {
     /// Optional cluster configurations for ON CLUSTER support
     #[serde(default)]
     pub clusters: Option<Vec<ClusterConfig>>,
+    /// Maximum number of independent DDL operations run at once
+    #[serde(default = "default_ddl_parallelism")]
+    pub ddl_parallelism: usize,
+    /// Failed inserts into a table above which diagnostics report an error
+    /// rather than a warning
+    #[serde(default = "default_insert_error_threshold")]
+    pub insert_error_threshold: u64,
 }
 
 impl Default for ClickHouseConfig {
@@ -60,6 +80,8 @@ impl Default for ClickHouseConfig {
             host_data_path: None,
             additional_databases: Vec::new(),
             clusters: None,
+            ddl_parallelism: default_ddl_parallelism(),
+            insert_error_threshold: default_insert_error_threshold(),
         }
     }
 }
@@ -191,6 +213,8 @@ pub fn parse_clickhouse_connection_string_with_metadata(
         host_data_path: None,
         additional_databases: Vec::new(),
         clusters: None,
+        ddl_parallelism: default_ddl_parallelism(),
+        insert_error_threshold: default_insert_error_threshold(),
     };
 
     // Create display URL (HTTP(S) protocol with masked password)
@@ -1,14 +1,15 @@
 //! Remote ClickHouse configuration resolution
 //!
 //! Resolves remote ClickHouse connection configuration from moose.config.toml
-//! and credentials from OS keychain.
+//! and credentials from OS keychain, or from secret files where there is no
+//! keychain (see [`default_secret_repository`]).
 
 use crate::cli::display::{self, Message, MessageType};
 use crate::cli::routines::RoutineFailure;
-use crate::cli::{prompt_password, prompt_user};
+use crate::cli::{prompt_password, prompt_user, Decision};
 use crate::project::{ClickHouseProtocol, Project, RemoteClickHouseConfig};
 use crate::utilities::constants::{KEY_REMOTE_CLICKHOUSE_PASSWORD, KEY_REMOTE_CLICKHOUSE_USER};
-use crate::utilities::keyring::{KeyringSecretRepository, SecretRepository};
+use crate::utilities::keyring::{default_secret_repository, SecretRepository};
 use tracing::debug;
 
 use super::remote::{ClickHouseRemote, Protocol};
@@ -49,7 +50,7 @@ pub fn resolve_remote_clickhouse(
 
     let port = config.effective_port();
     let project_name = project.name();
-    let repo = KeyringSecretRepository;
+    let repo = default_secret_repository();
 
     let (user, password) = match get_stored_credentials(&repo, &project_name)? {
         Some((u, p)) => (u, p),
@@ -90,8 +91,16 @@ fn prompt_for_credentials(
         ),
     );
 
-    let user = prompt_user("Enter username", Some("default"), None)?;
-    let password = prompt_password("Enter password")?;
+    let user = prompt_user(
+        Decision::new("the remote ClickHouse username", None),
+        "Enter username",
+        Some("default"),
+        None,
+    )?;
+    let password = prompt_password(
+        Decision::new("the remote ClickHouse password", None),
+        "Enter password",
+    )?;
 
     if password.is_empty() {
         return Err(RoutineFailure::error(Message::new(
@@ -152,7 +161,7 @@ pub fn store_remote_clickhouse_credentials(
             "Username and password must not be empty".to_string(),
         )));
     }
-    let repo = KeyringSecretRepository;
+    let repo = default_secret_repository();
     store_credentials(&repo, project_name, user, password)
 }
 
@@ -0,0 +1,313 @@
+//! Checks that the rows copied into a table, e.g. by a `RawSql` backfill of a
+//! migration plan, all made it.
+//!
+//! A `VerifyCopy` operation compares `count()` and `sum(cityHash64(...))`
+//! over some or all of the columns between the source and the copy. When
+//! they diverge, it fails and the migration stops before its next operation,
+//! typically the one dropping the source table.
+
+use async_trait::async_trait;
+use tracing::info;
+
+use super::model::{quote_ident, quote_qualified};
+use super::{build_query, run_query, ConfiguredDBClient};
+
+/// The row count and checksum of a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clickhouse::Row, serde::Deserialize)]
+pub struct TableDigest {
+    pub rows: u64,
+    /// Sum of the hashes of the rows, wrapping around
+    pub checksum: u64,
+}
+
+impl std::fmt::Display for TableDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} rows (checksum {})", self.rows, self.checksum)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum CopyVerificationError {
+    #[error("The copy of {from} into {to} diverges beyond the tolerance of {tolerance} rows: {expected} in the source, {actual} in the copy")]
+    Diverged {
+        from: String,
+        to: String,
+        expected: TableDigest,
+        actual: TableDigest,
+        tolerance: u64,
+    },
+
+    #[error("Failed to read the row count and checksum of {table}")]
+    Digest {
+        table: String,
+        #[source]
+        error: clickhouse::error::Error,
+    },
+}
+
+/// Runs the query computing the digest of a table
+#[async_trait]
+pub trait DigestProbe: Sync {
+    async fn digest(&self, query: &str) -> Result<TableDigest, clickhouse::error::Error>;
+}
+
+#[async_trait]
+impl DigestProbe for ConfiguredDBClient {
+    async fn digest(&self, query: &str) -> Result<TableDigest, clickhouse::error::Error> {
+        build_query(&self.client, query)
+            .fetch_one::<TableDigest>()
+            .await
+    }
+}
+
+/// The query computing the [`TableDigest`] of `database.table`, hashing
+/// `columns`, or every column when empty.
+pub fn digest_query(database: &str, table: &str, columns: &[String]) -> String {
+    let hashed = if columns.is_empty() {
+        "*".to_string()
+    } else {
+        columns
+            .iter()
+            .map(|column| quote_ident(column))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    format!(
+        "SELECT count() AS rows, sum(cityHash64({hashed})) AS checksum FROM {}",
+        quote_qualified(database, table)
+    )
+}
+
+/// Compares the `(database, table)` `destination` rows were copied into with
+/// `source`. Fails when the row counts differ by more than `tolerance`, or,
+/// without a tolerance, when the checksums differ.
+pub async fn verify_copy(
+    probe: &dyn DigestProbe,
+    source: (&str, &str),
+    destination: (&str, &str),
+    columns: &[String],
+    tolerance: u64,
+) -> Result<(), CopyVerificationError> {
+    let digest = |(database, table): (&str, &str)| async move {
+        probe
+            .digest(&digest_query(database, table, columns))
+            .await
+            .map_err(|error| CopyVerificationError::Digest {
+                table: format!("{database}.{table}"),
+                error,
+            })
+    };
+    let expected = digest(source).await?;
+    let actual = digest(destination).await?;
+
+    let from = format!("{}.{}", source.0, source.1);
+    let to = format!("{}.{}", destination.0, destination.1);
+    let diverged = expected.rows.abs_diff(actual.rows) > tolerance
+        || (tolerance == 0 && expected.checksum != actual.checksum);
+    if diverged {
+        return Err(CopyVerificationError::Diverged {
+            from,
+            to,
+            expected,
+            actual,
+            tolerance,
+        });
+    }
+    info!("Verified the copy of {from} into {to}: {expected} in the source, {actual} in the copy");
+    Ok(())
+}
+
+/// Runs a `VerifyCopy` operation, only recording its queries in a dry run.
+pub(super) async fn execute_verify_copy(
+    client: &ConfiguredDBClient,
+    source: (&str, &str),
+    destination: (&str, &str),
+    columns: &[String],
+    tolerance: u64,
+) -> Result<(), super::ClickhouseChangesError> {
+    if client.recorder.is_some() {
+        for (database, table) in [source, destination] {
+            run_query(&digest_query(database, table, columns), client)
+                .await
+                .map_err(|error| super::ClickhouseChangesError::ClickhouseClient {
+                    error,
+                    resource: Some(table.to_string()),
+                })?;
+        }
+        return Ok(());
+    }
+    verify_copy(client, source, destination, columns, tolerance)
+        .await
+        .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::olap::clickhouse::{
+        create_client, execute_atomic_operation, ClickHouseConfig, ClickhouseChangesError,
+        SerializableOlapOperation, StatementRecorder,
+    };
+    use std::collections::HashMap;
+
+    /// Answers with the digest of the table the query reads
+    struct MockProbe(HashMap<&'static str, TableDigest>);
+
+    #[async_trait]
+    impl DigestProbe for MockProbe {
+        async fn digest(&self, query: &str) -> Result<TableDigest, clickhouse::error::Error> {
+            let (_, table) = query.rsplit_once(" FROM ").unwrap();
+            Ok(self.0[table])
+        }
+    }
+
+    fn digest(rows: u64, checksum: u64) -> TableDigest {
+        TableDigest { rows, checksum }
+    }
+
+    async fn verify(
+        source: TableDigest,
+        copy: TableDigest,
+        tolerance: u64,
+    ) -> Result<(), CopyVerificationError> {
+        let probe = MockProbe(HashMap::from([
+            ("`local`.`events`", source),
+            ("`local`.`events_v2`", copy),
+        ]));
+        verify_copy(
+            &probe,
+            ("local", "events"),
+            ("local", "events_v2"),
+            &[],
+            tolerance,
+        )
+        .await
+    }
+
+    #[test]
+    fn test_digest_query() {
+        assert_eq!(
+            digest_query("local", "events", &[]),
+            "SELECT count() AS rows, sum(cityHash64(*)) AS checksum FROM `local`.`events`"
+        );
+        assert_eq!(
+            digest_query("local", "events", &["id".to_string(), "ts".to_string()]),
+            "SELECT count() AS rows, sum(cityHash64(`id`, `ts`)) AS checksum FROM `local`.`events`"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_identical_copy() {
+        assert!(verify(digest(100, 42), digest(100, 42), 0).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_filtered_backfill_diverges() {
+        let err = verify(digest(100, 42), digest(90, 7), 0).await.unwrap_err();
+        assert!(matches!(
+            err,
+            CopyVerificationError::Diverged { expected, actual, .. }
+                if expected.rows == 100 && actual.rows == 90
+        ));
+        assert_eq!(
+            err.to_string(),
+            "The copy of local.events into local.events_v2 diverges beyond the tolerance of 0 rows: 100 rows (checksum 42) in the source, 90 rows (checksum 7) in the copy"
+        );
+
+        // Same count, different rows
+        assert!(verify(digest(100, 42), digest(100, 7), 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tolerance() {
+        // A tolerance only bounds the difference of the row counts
+        assert!(verify(digest(100, 42), digest(95, 7), 5).await.is_ok());
+        assert!(verify(digest(100, 42), digest(94, 7), 5).await.is_err());
+    }
+
+    fn verify_copy_operation() -> SerializableOlapOperation {
+        SerializableOlapOperation::VerifyCopy {
+            table: "verify_copy_destination".to_string(),
+            database: None,
+            source_table: "verify_copy_source".to_string(),
+            source_database: None,
+            columns: vec![],
+            tolerance: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_records_the_digest_queries() {
+        let recorder = StatementRecorder::default();
+        let client = create_client(ClickHouseConfig::default()).with_recorder(recorder.clone());
+
+        execute_atomic_operation("local", &verify_copy_operation(), &client, false)
+            .await
+            .unwrap();
+
+        let recorded = recorder.take();
+        assert_eq!(
+            recorded[0].description,
+            "Verifying the copy of table 'verify_copy_source' into 'verify_copy_destination'"
+        );
+        assert_eq!(
+            recorded[0].statements,
+            [
+                digest_query("local", "verify_copy_source", &[]),
+                digest_query("local", "verify_copy_destination", &[]),
+            ]
+        );
+    }
+
+    /// A backfill filtering out rows fails the plan at `VerifyCopy`, before the
+    /// source table is dropped.
+    #[tokio::test]
+    #[ignore] // Requires running ClickHouse instance
+    async fn test_filtered_backfill_keeps_the_source_table() {
+        let client = create_client(ClickHouseConfig::default());
+        let raw_sql = |sql: &[&str]| SerializableOlapOperation::RawSql {
+            sql: sql.iter().map(|s| s.to_string()).collect(),
+            description: "Backfilling".to_string(),
+        };
+        let plan = [
+            raw_sql(&[
+                "DROP TABLE IF EXISTS verify_copy_source",
+                "DROP TABLE IF EXISTS verify_copy_destination",
+                "CREATE TABLE verify_copy_source (id UInt64) ENGINE = MergeTree ORDER BY id",
+                "INSERT INTO verify_copy_source SELECT number FROM numbers(100)",
+                "CREATE TABLE verify_copy_destination AS verify_copy_source",
+                "INSERT INTO verify_copy_destination SELECT * FROM verify_copy_source WHERE id % 10 != 0",
+            ]),
+            verify_copy_operation(),
+            SerializableOlapOperation::DropTable {
+                table: "verify_copy_source".to_string(),
+                database: None,
+                cluster_name: None,
+            },
+        ];
+
+        let db_name = client.config.db_name.clone();
+        let mut result = Ok(());
+        for operation in &plan {
+            result = execute_atomic_operation(&db_name, operation, &client, false).await;
+            if result.is_err() {
+                break;
+            }
+        }
+
+        assert!(matches!(
+            result,
+            Err(ClickhouseChangesError::CopyVerification(
+                CopyVerificationError::Diverged { expected, actual, .. }
+            )) if expected.rows == 100 && actual.rows == 90
+        ));
+        let exists = client
+            .client
+            .query("EXISTS TABLE verify_copy_source")
+            .fetch_one::<u8>()
+            .await
+            .unwrap();
+        assert_eq!(exists, 1);
+    }
+}
@@ -0,0 +1,501 @@
+//! ClickHouse credentials reloaded without restarting the process.
+//!
+//! The server holds one [`SharedClient`] for its ClickHouse operations. When
+//! the password is rotated, the client is rebuilt from the credentials read
+//! again from where they are configured (`moose.config.toml`, `MOOSE_*`
+//! variables, `*_FILE` files, named secrets). A reload is triggered
+//!
+//! - by an operation failing with code 516 (authentication failed), which is
+//!   then retried once with the new client
+//! - by `POST /admin/reload-credentials`
+//! - in production, by `SIGHUP` and by a change of the file named by
+//!   `MOOSE_CLICKHOUSE_CONFIG__PASSWORD_FILE`
+//!
+//! Operations hold the client they started with, so in-flight operations finish
+//! on the old client while new ones use the new client. The sync processes
+//! insert through a [`SharedInsertClient`], and the analytics API process is
+//! started with the current credentials.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use tracing::{info, warn};
+
+use super::client::{ClickHouseClient, ClickHouseClientTrait};
+use super::config::ClickHouseConfig;
+use super::errors::clickhouse_error_code;
+use super::model::ClickHouseRecord;
+use super::{create_client, ConfiguredDBClient};
+use crate::project::config_secrets::file_env_var;
+use crate::project::Project;
+use crate::utilities::dotenv::MooseEnvironment;
+
+/// ClickHouse error code of a rejected user or password
+pub const AUTHENTICATION_FAILED: u32 = 516;
+
+/// How often the password file is checked for changes
+const FILE_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The ClickHouse client shared by the server's operations
+pub type SharedClient = ReloadableClient<ConfiguredDBClient>;
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum CredentialsError {
+    #[error("cannot read the ClickHouse credentials: {0}")]
+    Source(String),
+
+    #[error("ClickHouse rejected the credentials (code 516), and they have not changed since the client was created. Update the ClickHouse user or password where it is configured, then reload the credentials")]
+    Unchanged,
+}
+
+/// Error of an operation run with [`ReloadableClient::run`]
+#[derive(Debug, thiserror::Error)]
+pub enum ReloadError<E> {
+    #[error("{0}")]
+    Operation(E),
+    #[error(transparent)]
+    Credentials(#[from] CredentialsError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub user: String,
+    pub password: String,
+}
+
+/// Where the ClickHouse credentials are read from on reload
+#[async_trait]
+pub trait CredentialSource: Send + Sync {
+    async fn read(&self) -> Result<Credentials, CredentialsError>;
+
+    /// File holding the password, watched for changes
+    fn watched_file(&self) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// Reads the credentials by loading the project configuration again
+pub struct ProjectCredentialSource {
+    directory: PathBuf,
+    environment: MooseEnvironment,
+}
+
+impl ProjectCredentialSource {
+    pub fn new(project: &Project) -> Self {
+        Self {
+            directory: project.project_location.clone(),
+            environment: if project.is_production {
+                MooseEnvironment::Production
+            } else {
+                MooseEnvironment::Development
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialSource for ProjectCredentialSource {
+    async fn read(&self) -> Result<Credentials, CredentialsError> {
+        // Loading the project reads files and may resolve secrets
+        let directory = self.directory.clone();
+        let environment = self.environment;
+        let project = tokio::task::spawn_blocking(move || Project::load(&directory, environment))
+            .await
+            .map_err(|e| CredentialsError::Source(e.to_string()))?
+            .map_err(|e| CredentialsError::Source(e.to_string()))?;
+        Ok(Credentials {
+            user: project.clickhouse_config.user,
+            password: project.clickhouse_config.password,
+        })
+    }
+
+    fn watched_file(&self) -> Option<PathBuf> {
+        std::env::var(file_env_var("clickhouse_config.password"))
+            .ok()
+            .filter(|path| !path.is_empty())
+            .map(|path| self.directory.join(path))
+    }
+}
+
+struct Current<C> {
+    /// Incremented on each swap
+    generation: u64,
+    config: ClickHouseConfig,
+    client: Arc<C>,
+}
+
+/// A client rebuilt with new credentials when they change
+pub struct ReloadableClient<C> {
+    source: Box<dyn CredentialSource>,
+    factory: Box<dyn Fn(ClickHouseConfig) -> C + Send + Sync>,
+    current: RwLock<Current<C>>,
+    /// Held while reloading, so that concurrent reloads swap the client once
+    reloading: tokio::sync::Mutex<()>,
+}
+
+impl<C> ReloadableClient<C> {
+    pub fn new(
+        config: ClickHouseConfig,
+        source: Box<dyn CredentialSource>,
+        factory: Box<dyn Fn(ClickHouseConfig) -> C + Send + Sync>,
+    ) -> Self {
+        let client = Arc::new(factory(config.clone()));
+        Self {
+            source,
+            factory,
+            current: RwLock::new(Current {
+                generation: 0,
+                config,
+                client,
+            }),
+            reloading: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn client(&self) -> Arc<C> {
+        self.current.read().unwrap().client.clone()
+    }
+
+    /// The configuration with the current credentials
+    pub fn config(&self) -> ClickHouseConfig {
+        self.current.read().unwrap().config.clone()
+    }
+
+    fn lease(&self) -> (u64, Arc<C>) {
+        let current = self.current.read().unwrap();
+        (current.generation, current.client.clone())
+    }
+
+    /// Reads the credentials again and swaps the client if they changed.
+    /// Returns whether the client was swapped.
+    pub async fn reload(&self) -> Result<bool, CredentialsError> {
+        let _reloading = self.reloading.lock().await;
+        self.swap_if_changed().await
+    }
+
+    /// Runs `operation` with the current client. If ClickHouse rejects the
+    /// credentials, reloads them and runs `operation` once more with the new
+    /// client.
+    pub async fn run<T, E, F, Fut>(&self, operation: F) -> Result<T, ReloadError<E>>
+    where
+        F: Fn(Arc<C>) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: std::fmt::Display,
+    {
+        let (generation, client) = self.lease();
+        match operation(client).await {
+            Err(e) if clickhouse_error_code(&e.to_string()) == Some(AUTHENTICATION_FAILED) => {
+                self.reload_after_auth_failure(generation).await?;
+                operation(self.client())
+                    .await
+                    .map_err(ReloadError::Operation)
+            }
+            result => result.map_err(ReloadError::Operation),
+        }
+    }
+
+    /// Reloads the credentials after the client of `generation` was rejected.
+    /// The client is not swapped again if another operation already did.
+    async fn reload_after_auth_failure(&self, generation: u64) -> Result<(), CredentialsError> {
+        let _reloading = self.reloading.lock().await;
+        if self.current.read().unwrap().generation != generation {
+            return Ok(());
+        }
+        if self.swap_if_changed().await? {
+            Ok(())
+        } else {
+            Err(CredentialsError::Unchanged)
+        }
+    }
+
+    async fn swap_if_changed(&self) -> Result<bool, CredentialsError> {
+        let credentials = self.source.read().await?;
+        let mut config = self.config();
+        if config.user == credentials.user && config.password == credentials.password {
+            return Ok(false);
+        }
+
+        config.user = credentials.user;
+        config.password = credentials.password;
+        let client = Arc::new((self.factory)(config.clone()));
+        let mut current = self.current.write().unwrap();
+        current.generation += 1;
+        current.config = config;
+        current.client = client;
+        info!(
+            "Reloaded the ClickHouse credentials for user '{}'",
+            current.config.user
+        );
+        Ok(true)
+    }
+}
+
+impl<C: Send + Sync + 'static> ReloadableClient<C> {
+    /// Reloads the credentials on `SIGHUP` and when the password file changes.
+    pub fn watch(self: Arc<Self>) {
+        let mut sighup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        {
+            Ok(sighup) => sighup,
+            Err(e) => {
+                warn!("Cannot reload the ClickHouse credentials on SIGHUP: {e}");
+                return;
+            }
+        };
+        let watched_file = self.source.watched_file();
+
+        tokio::spawn(async move {
+            let mut modified = watched_file.as_deref().and_then(modified_time);
+            let mut poll = tokio::time::interval(FILE_POLL_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = sighup.recv() => {
+                        info!("SIGHUP received, reloading the ClickHouse credentials");
+                    }
+                    _ = poll.tick(), if watched_file.is_some() => {
+                        let now = watched_file.as_deref().and_then(modified_time);
+                        if now == modified {
+                            continue;
+                        }
+                        modified = now;
+                        info!("ClickHouse password file changed, reloading the credentials");
+                    }
+                }
+                if let Err(e) = self.reload().await {
+                    warn!("Failed to reload the ClickHouse credentials: {e}");
+                }
+            }
+        });
+    }
+}
+
+fn modified_time(path: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// The shared client of `project`, reading its credentials from the project
+/// configuration on reload
+pub fn shared_client(project: &Project) -> Arc<SharedClient> {
+    Arc::new(ReloadableClient::new(
+        project.clickhouse_config.clone(),
+        Box::new(ProjectCredentialSource::new(project)),
+        Box::new(create_client),
+    ))
+}
+
+/// Inserts with the current credentials of a [`SharedClient`], so that the
+/// sync processes keep inserting after the password is rotated
+pub struct SharedInsertClient {
+    shared: Arc<SharedClient>,
+    /// HTTP client of the credentials it was created with, reused until they
+    /// change
+    http: Mutex<Option<(Credentials, Arc<ClickHouseClient>)>>,
+}
+
+impl SharedInsertClient {
+    pub fn new(shared: Arc<SharedClient>) -> Self {
+        Self {
+            shared,
+            http: Mutex::new(None),
+        }
+    }
+
+    fn http_client(&self, config: &ClickHouseConfig) -> anyhow::Result<Arc<ClickHouseClient>> {
+        let credentials = Credentials {
+            user: config.user.clone(),
+            password: config.password.clone(),
+        };
+        let mut http = self.http.lock().unwrap();
+        if let Some((cached, client)) = http.as_ref() {
+            if *cached == credentials {
+                return Ok(client.clone());
+            }
+        }
+        let client = Arc::new(ClickHouseClient::new(config)?);
+        *http = Some((credentials, client.clone()));
+        Ok(client)
+    }
+}
+
+#[async_trait]
+impl ClickHouseClientTrait for SharedInsertClient {
+    async fn insert(
+        &self,
+        table: &str,
+        database: Option<&str>,
+        columns: &[String],
+        records: &[ClickHouseRecord],
+    ) -> anyhow::Result<()> {
+        self.shared
+            .run(|client| async move {
+                self.http_client(&client.config)?
+                    .insert(table, database, columns, records)
+                    .await
+            })
+            .await
+            .map_err(|e| match e {
+                ReloadError::Operation(e) => e,
+                ReloadError::Credentials(e) => e.into(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const AUTH_FAILED: &str =
+        "Code: 516. DB::Exception: default: Authentication failed. (AUTHENTICATION_FAILED)";
+
+    /// Credentials stored where the project configures them
+    struct MockSource(Arc<Mutex<String>>);
+
+    #[async_trait]
+    impl CredentialSource for MockSource {
+        async fn read(&self) -> Result<Credentials, CredentialsError> {
+            Ok(Credentials {
+                user: "default".to_string(),
+                password: self.0.lock().unwrap().clone(),
+            })
+        }
+    }
+
+    struct MockClient {
+        password: String,
+    }
+
+    struct Fixture {
+        client: ReloadableClient<MockClient>,
+        /// Password at the source
+        source: Arc<Mutex<String>>,
+        /// Password ClickHouse accepts
+        server: Arc<Mutex<String>>,
+        clients_created: Arc<AtomicUsize>,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let source = Arc::new(Mutex::new("old".to_string()));
+            let clients_created = Arc::new(AtomicUsize::new(0));
+            let created = clients_created.clone();
+            let config = ClickHouseConfig {
+                user: "default".to_string(),
+                password: "old".to_string(),
+                ..ClickHouseConfig::default()
+            };
+            let client = ReloadableClient::new(
+                config,
+                Box::new(MockSource(source.clone())),
+                Box::new(move |config: ClickHouseConfig| {
+                    created.fetch_add(1, Ordering::SeqCst);
+                    MockClient {
+                        password: config.password,
+                    }
+                }),
+            );
+            Fixture {
+                client,
+                source,
+                server: Arc::new(Mutex::new("old".to_string())),
+                clients_created,
+            }
+        }
+
+        fn rotate(&self, password: &str) {
+            *self.server.lock().unwrap() = password.to_string();
+            *self.source.lock().unwrap() = password.to_string();
+        }
+
+        fn swaps(&self) -> usize {
+            self.clients_created.load(Ordering::SeqCst) - 1
+        }
+
+        /// Runs a query, returning the password it was accepted with
+        async fn query(&self) -> Result<String, ReloadError<String>> {
+            self.client
+                .run(|client| async move {
+                    // Lets concurrent queries take their client before any reload
+                    tokio::task::yield_now().await;
+                    if client.password == *self.server.lock().unwrap() {
+                        Ok(client.password.clone())
+                    } else {
+                        Err(AUTH_FAILED.to_string())
+                    }
+                })
+                .await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_is_swapped_once_per_rotation() {
+        let fixture = Fixture::new();
+        assert_eq!(fixture.query().await.unwrap(), "old");
+
+        fixture.rotate("new");
+        let (a, b, c) = tokio::join!(fixture.query(), fixture.query(), fixture.query());
+        for result in [a, b, c] {
+            assert_eq!(result.unwrap(), "new");
+        }
+        assert_eq!(fixture.swaps(), 1);
+        assert_eq!(fixture.query().await.unwrap(), "new");
+        assert_eq!(fixture.swaps(), 1);
+
+        fixture.rotate("newer");
+        let (a, b) = tokio::join!(fixture.query(), fixture.query());
+        assert_eq!(a.unwrap(), "newer");
+        assert_eq!(b.unwrap(), "newer");
+        assert_eq!(fixture.swaps(), 2);
+        assert_eq!(fixture.client.config().password, "newer");
+    }
+
+    #[tokio::test]
+    async fn test_repeated_auth_failures_without_new_secret_are_reported() {
+        let fixture = Fixture::new();
+        // ClickHouse changed the password, the source was not updated
+        *fixture.server.lock().unwrap() = "new".to_string();
+
+        for _ in 0..3 {
+            assert!(matches!(
+                fixture.query().await,
+                Err(ReloadError::Credentials(CredentialsError::Unchanged))
+            ));
+        }
+        assert_eq!(fixture.swaps(), 0);
+
+        // Updating the source recovers
+        *fixture.source.lock().unwrap() = "new".to_string();
+        assert_eq!(fixture.query().await.unwrap(), "new");
+        assert_eq!(fixture.swaps(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_explicit_reload_keeps_in_flight_client() {
+        let fixture = Fixture::new();
+        assert!(!fixture.client.reload().await.unwrap());
+
+        let in_flight = fixture.client.client();
+        fixture.rotate("new");
+        assert!(fixture.client.reload().await.unwrap());
+        assert!(!fixture.client.reload().await.unwrap());
+
+        assert_eq!(in_flight.password, "old");
+        assert_eq!(fixture.client.client().password, "new");
+        assert_eq!(fixture.swaps(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_do_not_reload() {
+        let fixture = Fixture::new();
+        let result: Result<(), _> = fixture
+            .client
+            .run(|_| async { Err("Code: 60. DB::Exception: Table does not exist".to_string()) })
+            .await;
+
+        assert!(matches!(result, Err(ReloadError::Operation(_))));
+        assert_eq!(fixture.swaps(), 0);
+    }
+}
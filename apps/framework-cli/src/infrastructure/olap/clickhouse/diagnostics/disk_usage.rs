@@ -0,0 +1,196 @@
+//! Diagnostic provider for checking the disks the managed tables are stored on
+
+use serde_json::{json, Map, Value};
+use tracing::debug;
+
+use super::{json_u64, Component, DiagnosticError, DiagnosticProvider, Issue, Severity};
+use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
+use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::model::quote_string;
+use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+
+/// Query timeout for diagnostic checks (30 seconds)
+const DIAGNOSTIC_QUERY_TIMEOUT_SECS: u64 = 30;
+
+/// Percentage of a disk used above which a warning is reported
+const WARNING_USED_PERCENT: f64 = 80.0;
+
+/// Percentage of a disk used above which an error is reported
+const ERROR_USED_PERCENT: f64 = 90.0;
+
+/// Diagnostic provider for checking the disks of the storage policies of the managed tables
+///
+/// Use `DiskUsageDiagnostic::new()` or `Default::default()` to construct.
+#[derive(Default)]
+pub struct DiskUsageDiagnostic(());
+
+impl DiskUsageDiagnostic {
+    /// Create a new DiskUsageDiagnostic provider
+    pub const fn new() -> Self {
+        Self(())
+    }
+
+    /// Parse the ClickHouse JSON response and extract issues for the disks filling up
+    ///
+    /// Each row is a disk with its `free_space`, its `total_space` and the `tables`
+    /// whose storage policy includes it.
+    ///
+    /// # Arguments
+    /// * `json_response` - The raw JSON string from ClickHouse
+    /// * `component` - The component being diagnosed (used for system-wide context)
+    ///
+    /// # Returns
+    /// Vector of issues found in the response
+    pub fn parse_response(
+        json_response: &str,
+        component: &Component,
+    ) -> Result<Vec<Issue>, DiagnosticError> {
+        let json_value: Value = serde_json::from_str(json_response)
+            .map_err(|e| DiagnosticError::ParseError(format!("{}", e)))?;
+
+        let data = json_value
+            .get("data")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| {
+                DiagnosticError::ParseError("Missing 'data' field in response".to_string())
+            })?;
+
+        let mut issues = Vec::new();
+
+        for row in data {
+            let disk = row
+                .get("disk")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown");
+            let free_space = row.get("free_space").and_then(json_u64).unwrap_or(0);
+            let total_space = row.get("total_space").and_then(json_u64).unwrap_or(0);
+            let tables: Vec<&str> = row
+                .get("tables")
+                .and_then(|v| v.as_array())
+                .map(|tables| tables.iter().filter_map(|t| t.as_str()).collect())
+                .unwrap_or_default();
+
+            // Disks without a known size, e.g. some object storages, can't fill up
+            if total_space == 0 {
+                continue;
+            }
+
+            let used_percent =
+                total_space.saturating_sub(free_space) as f64 * 100.0 / total_space as f64;
+            let severity = if used_percent > ERROR_USED_PERCENT {
+                Severity::Error
+            } else if used_percent > WARNING_USED_PERCENT {
+                Severity::Warning
+            } else {
+                continue;
+            };
+
+            let mut details = Map::new();
+            details.insert("disk".to_string(), json!(disk));
+            details.insert("free_bytes".to_string(), json!(free_space));
+            details.insert("total_bytes".to_string(), json!(total_space));
+            details.insert(
+                "used_percent".to_string(),
+                json!((used_percent * 10.0).round() / 10.0),
+            );
+            details.insert("tables".to_string(), json!(tables));
+
+            issues.push(Issue {
+                severity,
+                source: "system.disks".to_string(),
+                component: component.clone(),
+                error_type: "disk_space_low".to_string(),
+                message: format!(
+                    "Disk '{}' is {:.1}% full ({} of {} bytes free), storing {}",
+                    disk,
+                    used_percent,
+                    free_space,
+                    total_space,
+                    tables.join(", ")
+                ),
+                details,
+                suggested_action: format!(
+                    "Free up space on disk '{}', e.g. by dropping old partitions or setting a TTL on the tables stored on it, or add a disk to their storage policy. Inserts and merges fail once the disk is full.",
+                    disk
+                ),
+                related_queries: vec![
+                    format!(
+                        "SELECT name, path, free_space, total_space FROM system.disks WHERE name = {}",
+                        quote_string(disk)
+                    ),
+                    format!(
+                        "SELECT database, table, sum(bytes_on_disk) AS bytes FROM system.parts WHERE disk_name = {} AND active = 1 GROUP BY database, table ORDER BY bytes DESC LIMIT 10",
+                        quote_string(disk)
+                    ),
+                ],
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+#[async_trait::async_trait]
+impl DiagnosticProvider for DiskUsageDiagnostic {
+    fn name(&self) -> &str {
+        "DiskUsageDiagnostic"
+    }
+
+    fn applicable_to(&self, _component: &Component, _engine: Option<&ClickhouseEngine>) -> bool {
+        // Disks are shared by all the tables of a storage policy
+        // This should be run separately outside the component loop
+        false
+    }
+
+    fn is_system_wide(&self) -> bool {
+        true
+    }
+
+    async fn diagnose(
+        &self,
+        component: &Component,
+        _engine: Option<&ClickhouseEngine>,
+        config: &ClickHouseConfig,
+        _since: Option<&str>,
+    ) -> Result<Vec<Issue>, DiagnosticError> {
+        let client = ClickHouseClient::new(config)
+            .map_err(|e| DiagnosticError::ConnectionFailed(format!("{}", e)))?;
+
+        let databases = std::iter::once(&config.db_name)
+            .chain(&config.additional_databases)
+            .map(|database| quote_string(database))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // The disks of the storage policies of the tables in the managed databases
+        let query = format!(
+            "SELECT
+                d.name AS disk,
+                d.free_space AS free_space,
+                d.total_space AS total_space,
+                groupArray(concat(t.database, '.', t.name)) AS tables
+             FROM system.tables AS t
+             INNER JOIN (
+                SELECT policy_name, arrayJoin(disks) AS disk_name
+                FROM system.storage_policies
+             ) AS p ON t.storage_policy = p.policy_name
+             INNER JOIN system.disks AS d ON d.name = p.disk_name
+             WHERE t.database IN ({})
+             GROUP BY d.name, d.free_space, d.total_space
+             FORMAT JSON",
+            databases
+        );
+
+        debug!("Executing disk usage query: {}", query);
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(DIAGNOSTIC_QUERY_TIMEOUT_SECS),
+            client.execute_sql(&query),
+        )
+        .await
+        .map_err(|_| DiagnosticError::QueryTimeout(DIAGNOSTIC_QUERY_TIMEOUT_SECS))?
+        .map_err(|e| DiagnosticError::QueryFailed(format!("{}", e)))?;
+
+        Self::parse_response(&result, component)
+    }
+}
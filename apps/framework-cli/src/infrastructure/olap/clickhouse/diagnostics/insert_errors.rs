@@ -0,0 +1,191 @@
+//! Diagnostic provider for checking failed inserts into a table
+
+use serde_json::{json, Map, Value};
+use tracing::debug;
+
+use super::{json_u64, Component, DiagnosticError, DiagnosticProvider, Issue, Severity};
+use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
+use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::model::quote_string;
+use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+
+/// Query timeout for diagnostic checks (30 seconds)
+const DIAGNOSTIC_QUERY_TIMEOUT_SECS: u64 = 30;
+
+/// Window of `system.query_log` checked when no `since` is given
+const DEFAULT_SINCE: &str = "-1d";
+
+/// Diagnostic provider for checking failed inserts into a table
+///
+/// Use `InsertErrorsDiagnostic::new()` or `Default::default()` to construct.
+#[derive(Default)]
+pub struct InsertErrorsDiagnostic(());
+
+impl InsertErrorsDiagnostic {
+    /// Create a new InsertErrorsDiagnostic provider
+    pub const fn new() -> Self {
+        Self(())
+    }
+
+    /// Parse the ClickHouse JSON response and extract failed insert issues
+    ///
+    /// # Arguments
+    /// * `json_response` - The raw JSON string from ClickHouse
+    /// * `component` - The component being diagnosed
+    /// * `db_name` - Database of the table, for generating related queries
+    /// * `error_threshold` - Failed inserts above which an error rather than a warning is reported
+    ///
+    /// # Returns
+    /// Vector of issues found in the response
+    pub fn parse_response(
+        json_response: &str,
+        component: &Component,
+        db_name: &str,
+        error_threshold: u64,
+    ) -> Result<Vec<Issue>, DiagnosticError> {
+        let json_value: Value = serde_json::from_str(json_response)
+            .map_err(|e| DiagnosticError::ParseError(format!("{}", e)))?;
+
+        let data = json_value
+            .get("data")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| {
+                DiagnosticError::ParseError("Missing 'data' field in response".to_string())
+            })?;
+
+        let mut issues = Vec::new();
+
+        for row in data {
+            let failed_inserts = row.get("failed_inserts").and_then(json_u64).unwrap_or(0);
+            let last_exception = row
+                .get("last_exception")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+
+            // Skip if no failures
+            if failed_inserts == 0 {
+                continue;
+            }
+
+            let severity = if failed_inserts > error_threshold {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+
+            let mut details = Map::new();
+            details.insert("failed_inserts".to_string(), json!(failed_inserts));
+            details.insert(
+                "last_failure_time".to_string(),
+                row.get("last_failure_time").cloned().unwrap_or(json!("")),
+            );
+            details.insert("last_exception".to_string(), json!(last_exception));
+            details.insert("error_threshold".to_string(), json!(error_threshold));
+
+            issues.push(Issue {
+                severity,
+                source: "system.query_log".to_string(),
+                component: component.clone(),
+                error_type: "failed_inserts".to_string(),
+                message: format!(
+                    "{} inserts into '{}' failed. Last: {}",
+                    failed_inserts, component.name, last_exception
+                ),
+                details,
+                suggested_action: "Check that the data sent matches the schema of the table, and review the failing statements. Rows of failed inserts were not written.".to_string(),
+                related_queries: vec![format!(
+                    "SELECT event_time, query, exception FROM system.query_log WHERE type = 'ExceptionWhileProcessing' AND query_kind = 'Insert' AND has(tables, {}) ORDER BY event_time DESC LIMIT 20",
+                    quote_string(&format!("{}.{}", db_name, component.name))
+                )],
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// The condition on `event_time` keeping the entries since `since`, either a
+/// relative time such as `-1h`, `-30m` or `-1d`, or an RFC 3339 timestamp
+fn since_condition(since: &str) -> Result<String, DiagnosticError> {
+    if let Some(relative) = since.strip_prefix('-') {
+        let amount = relative.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        let unit = match &relative[amount.len()..] {
+            "s" => Some("SECOND"),
+            "m" => Some("MINUTE"),
+            "h" => Some("HOUR"),
+            "d" => Some("DAY"),
+            _ => None,
+        };
+        if let (Ok(amount), Some(unit)) = (amount.parse::<u32>(), unit) {
+            return Ok(format!("event_time > now() - INTERVAL {} {}", amount, unit));
+        }
+    } else if chrono::DateTime::parse_from_rfc3339(since).is_ok() {
+        return Ok(format!(
+            "event_time > parseDateTimeBestEffort({})",
+            quote_string(since)
+        ));
+    }
+
+    Err(DiagnosticError::InvalidParameter(format!(
+        "Invalid since '{}', expected e.g. '-1h', '-30m', '-1d' or an RFC 3339 timestamp",
+        since
+    )))
+}
+
+#[async_trait::async_trait]
+impl DiagnosticProvider for InsertErrorsDiagnostic {
+    fn name(&self) -> &str {
+        "InsertErrorsDiagnostic"
+    }
+
+    fn applicable_to(&self, component: &Component, _engine: Option<&ClickhouseEngine>) -> bool {
+        component.component_type == "table"
+    }
+
+    async fn diagnose(
+        &self,
+        component: &Component,
+        _engine: Option<&ClickhouseEngine>,
+        config: &ClickHouseConfig,
+        since: Option<&str>,
+    ) -> Result<Vec<Issue>, DiagnosticError> {
+        let since_condition = since_condition(since.unwrap_or(DEFAULT_SINCE))?;
+        let db_name = component
+            .metadata
+            .get("database")
+            .unwrap_or(&config.db_name);
+
+        let client = ClickHouseClient::new(config)
+            .map_err(|e| DiagnosticError::ConnectionFailed(format!("{}", e)))?;
+
+        // `tables` lists the tables of a query as `database.table`
+        let query = format!(
+            "SELECT
+                table_name,
+                count() AS failed_inserts,
+                max(event_time) AS last_failure_time,
+                argMax(exception, event_time) AS last_exception
+             FROM system.query_log
+             ARRAY JOIN tables AS table_name
+             WHERE type = 'ExceptionWhileProcessing' AND query_kind = 'Insert'
+             AND table_name = {}
+             AND {}
+             GROUP BY table_name
+             FORMAT JSON",
+            quote_string(&format!("{}.{}", db_name, component.name)),
+            since_condition
+        );
+
+        debug!("Executing insert errors query: {}", query);
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(DIAGNOSTIC_QUERY_TIMEOUT_SECS),
+            client.execute_sql(&query),
+        )
+        .await
+        .map_err(|_| DiagnosticError::QueryTimeout(DIAGNOSTIC_QUERY_TIMEOUT_SECS))?
+        .map_err(|e| DiagnosticError::QueryFailed(format!("{}", e)))?;
+
+        Self::parse_response(&result, component, db_name, config.insert_error_threshold)
+    }
+}
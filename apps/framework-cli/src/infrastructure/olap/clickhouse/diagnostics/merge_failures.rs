@@ -6,6 +6,7 @@ use tracing::debug;
 use super::{Component, DiagnosticError, DiagnosticProvider, Issue, Severity};
 use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
 use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::model::quote_string;
 use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
 
 /// Query timeout for diagnostic checks (30 seconds)
@@ -75,8 +76,8 @@ impl MergeFailureDiagnostic {
                     "SELECT * FROM system.errors WHERE name LIKE '%Merge%' ORDER BY last_error_time DESC LIMIT 10".to_string(),
                     "SELECT * FROM system.metrics WHERE metric LIKE '%Merge%'".to_string(),
                     format!(
-                        "SELECT * FROM system.merges WHERE database = '{}' AND table = '{}'",
-                        db_name, component.name
+                        "SELECT * FROM system.merges WHERE database = {} AND table = {}",
+                        quote_string(db_name), quote_string(&component.name)
                     ),
                 ],
             });
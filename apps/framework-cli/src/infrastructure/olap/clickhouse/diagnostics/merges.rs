@@ -6,6 +6,7 @@ use tracing::debug;
 use super::{Component, DiagnosticError, DiagnosticProvider, Issue, Severity};
 use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
 use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::model::{quote_qualified, quote_string};
 use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
 
 /// Query timeout for diagnostic checks (30 seconds)
@@ -86,10 +87,10 @@ impl MergeDiagnostic {
                 suggested_action: "Monitor merge progress. If stuck, check server resources (CPU, disk I/O, memory). Consider stopping merge with SYSTEM STOP MERGES if necessary.".to_string(),
                 related_queries: vec![
                     format!(
-                        "SELECT * FROM system.merges WHERE database = '{}' AND table = '{}'",
-                        db_name, component.name
+                        "SELECT * FROM system.merges WHERE database = {} AND table = {}",
+                        quote_string(db_name), quote_string(&component.name)
                     ),
-                    format!("SYSTEM STOP MERGES {}.{}", db_name, component.name),
+                    format!("SYSTEM STOP MERGES {}", quote_qualified(db_name, &component.name)),
                 ],
             });
         }
@@ -127,11 +128,12 @@ impl DiagnosticProvider for MergeDiagnostic {
                 result_part_name,
                 total_size_bytes_compressed
              FROM system.merges
-             WHERE database = '{}' AND table = '{}'
+             WHERE database = {} AND table = {}
              AND elapsed > 300
              ORDER BY elapsed DESC
              FORMAT JSON",
-            config.db_name, component.name
+            quote_string(&config.db_name),
+            quote_string(&component.name)
         );
 
         debug!("Executing merges query: {}", query);
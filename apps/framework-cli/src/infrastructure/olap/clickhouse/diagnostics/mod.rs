@@ -52,16 +52,29 @@
 //! Identifies manually stopped operations.
 //! - **Sources**: `system.parts`, `system.merges`, `system.replicas`
 //! - **Thresholds**: Error (stopped replication), Warning (stopped merges)
+//!
+//! ### 9. DiskUsageDiagnostic
+//! Detects disks of the storage policies of the managed tables filling up.
+//! - **Sources**: `system.disks`, `system.storage_policies`
+//! - **Thresholds**: Error (>90% used), Warning (>80% used)
+//!
+//! ### 10. InsertErrorsDiagnostic
+//! Detects inserts into a table failing within the `since` window (default: last day).
+//! - **Source**: `system.query_log`
+//! - **Thresholds**: Error (more than `insert_error_threshold` failures), Warning (any failure)
 
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
 use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
 
 // Module declarations for diagnostic providers
+mod disk_usage;
 mod errors;
+mod insert_errors;
 mod merge_failures;
 mod merges;
 mod mutations;
@@ -71,7 +84,9 @@ mod s3queue;
 mod stopped_operations;
 
 // Re-export diagnostic providers
+pub use disk_usage::DiskUsageDiagnostic;
 pub use errors::ErrorStatsDiagnostic;
+pub use insert_errors::InsertErrorsDiagnostic;
 pub use merge_failures::MergeFailureDiagnostic;
 pub use merges::MergeDiagnostic;
 pub use mutations::MutationDiagnostic;
@@ -99,6 +114,13 @@ pub enum DiagnosticError {
     InvalidParameter(String),
 }
 
+/// A UInt64 of a `FORMAT JSON` row, which ClickHouse quotes by default
+fn json_u64(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
 /// Severity level for issues
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -265,22 +287,11 @@ pub fn create_all_providers() -> Vec<Box<dyn DiagnosticProvider>> {
         Box::new(ReplicationDiagnostic::new()),
         Box::new(MergeFailureDiagnostic::new()),
         Box::new(StoppedOperationsDiagnostic::new()),
+        Box::new(DiskUsageDiagnostic::new()),
+        Box::new(InsertErrorsDiagnostic::new()),
     ]
 }
 
-/// Get a specific diagnostic provider by name
-///
-/// # Arguments
-/// * `name` - The name of the provider to retrieve
-///
-/// # Returns
-/// Some(provider) if found, None otherwise
-pub fn get_provider(name: &str) -> Option<Box<dyn DiagnosticProvider>> {
-    create_all_providers()
-        .into_iter()
-        .find(|p| p.name() == name)
-}
-
 /// Run diagnostics on the provided components
 ///
 /// This is the main orchestration function that:
@@ -301,12 +312,20 @@ pub async fn run_diagnostics(
     request: DiagnosticRequest,
     config: &ClickHouseConfig,
 ) -> Result<DiagnosticOutput, DiagnosticError> {
-    use tokio::task::JoinSet;
+    let providers = create_all_providers().into_iter().map(Arc::from).collect();
+    run_diagnostics_with_providers(request, config, providers).await
+}
 
-    let all_providers = create_all_providers();
+/// [`run_diagnostics`] with the given providers instead of all the built-in ones
+pub async fn run_diagnostics_with_providers(
+    request: DiagnosticRequest,
+    config: &ClickHouseConfig,
+    all_providers: Vec<Arc<dyn DiagnosticProvider>>,
+) -> Result<DiagnosticOutput, DiagnosticError> {
+    use tokio::task::JoinSet;
 
     // Filter providers by requested diagnostic names (empty = all)
-    let providers: Vec<Box<dyn DiagnosticProvider>> = if request.options.diagnostic_names.is_empty()
+    let providers: Vec<Arc<dyn DiagnosticProvider>> = if request.options.diagnostic_names.is_empty()
     {
         all_providers
     } else {
@@ -368,9 +387,6 @@ pub async fn run_diagnostics(
     }
 
     // Spawn component-specific providers as concurrent tasks
-    // We need to collect (component, provider) pairs to spawn since we can't borrow provider
-    let mut tasks_to_spawn = Vec::new();
-
     for (component, engine) in request.components {
         for provider in &component_specific {
             // Check if provider is applicable to this component
@@ -378,37 +394,21 @@ pub async fn run_diagnostics(
                 continue;
             }
 
-            tasks_to_spawn.push((
-                component.clone(),
-                engine.clone(),
-                provider.name().to_string(),
-            ));
-        }
-    }
-
-    // Now spawn tasks with recreated providers for each task
-    for (component, engine, provider_name) in tasks_to_spawn {
-        let config = config.clone();
-        let since = since.clone();
-
-        // Get a fresh provider instance for this task
-        let provider = get_provider(&provider_name);
+            let provider = provider.clone();
+            let component = component.clone();
+            let engine = engine.clone();
+            let config = config.clone();
+            let since = since.clone();
+            let provider_name = provider.name().to_string();
 
-        join_set.spawn(async move {
-            let result = if let Some(provider) = provider {
-                provider
+            join_set.spawn(async move {
+                let result = provider
                     .diagnose(&component, Some(&engine), &config, since.as_deref())
-                    .await
-            } else {
-                // This shouldn't happen since we just got the name from a valid provider
-                Err(DiagnosticError::InvalidParameter(format!(
-                    "Provider {} not found",
-                    provider_name
-                )))
-            };
-
-            (provider_name, result)
-        });
+                    .await;
+
+                (provider_name, result)
+            });
+        }
     }
 
     // Collect results as they complete
@@ -579,6 +579,8 @@ mod tests {
             host_data_path: None,
             additional_databases: Vec::new(),
             clusters: None,
+            ddl_parallelism: 4,
+            insert_error_threshold: 10,
         };
 
         let component = Component {
@@ -613,6 +615,8 @@ mod tests {
             host_data_path: None,
             additional_databases: Vec::new(),
             clusters: None,
+            ddl_parallelism: 4,
+            insert_error_threshold: 10,
         };
 
         let component = Component {
@@ -837,6 +841,8 @@ mod tests {
             host_data_path: None,
             additional_databases: Vec::new(),
             clusters: None,
+            ddl_parallelism: 4,
+            insert_error_threshold: 10,
         };
 
         // Note: This test demonstrates the concurrent execution pattern,
@@ -873,6 +879,86 @@ mod tests {
         assert_eq!(fast_order.load(Ordering::SeqCst), 1);
     }
 
+    #[test]
+    fn test_disk_usage_thresholds() {
+        let component = Component {
+            component_type: "table".to_string(),
+            name: "events".to_string(),
+            metadata: HashMap::new(),
+        };
+        // 70%, 85% and 95% used, the first two as ClickHouse quotes UInt64,
+        // and a disk without a known size
+        let response = r#"{
+            "data": [
+                {"disk": "default", "free_space": "300", "total_space": "1000", "tables": ["local.events"]},
+                {"disk": "hot", "free_space": "150", "total_space": "1000", "tables": ["local.events", "local.users"]},
+                {"disk": "cold", "free_space": 50, "total_space": 1000, "tables": ["archive.logs"]},
+                {"disk": "s3", "free_space": "0", "total_space": "0", "tables": ["archive.logs"]}
+            ]
+        }"#;
+
+        let issues = DiskUsageDiagnostic::parse_response(response, &component).unwrap();
+        let severities: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.details["disk"].as_str().unwrap(), &issue.severity))
+            .collect();
+        assert_eq!(
+            severities,
+            vec![("hot", &Severity::Warning), ("cold", &Severity::Error)]
+        );
+
+        let hot = &issues[0].details;
+        assert_eq!(hot["free_bytes"], 150);
+        assert_eq!(hot["total_bytes"], 1000);
+        assert_eq!(
+            hot["tables"],
+            serde_json::json!(["local.events", "local.users"])
+        );
+        assert!(issues[0].message.contains("85.0% full"));
+
+        assert!(matches!(
+            DiskUsageDiagnostic::parse_response("{}", &component),
+            Err(DiagnosticError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_insert_errors_thresholds() {
+        let component = Component {
+            component_type: "table".to_string(),
+            name: "events".to_string(),
+            metadata: HashMap::from([("database".to_string(), "analytics".to_string())]),
+        };
+        let response = |failed_inserts: &str| {
+            format!(
+                r#"{{"data": [{{"table_name": "analytics.events", "failed_inserts": "{}", "last_failure_time": "2025-01-15 11:00:00", "last_exception": "Code: 27. DB::Exception: Cannot parse input"}}]}}"#,
+                failed_inserts
+            )
+        };
+
+        let issues =
+            InsertErrorsDiagnostic::parse_response(&response("3"), &component, "analytics", 10)
+                .unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[0].details["failed_inserts"], 3);
+        assert!(issues[0].details["last_exception"]
+            .as_str()
+            .unwrap()
+            .contains("Cannot parse input"));
+        assert!(issues[0].related_queries[0].contains("has(tables, 'analytics.events')"));
+
+        let issues =
+            InsertErrorsDiagnostic::parse_response(&response("11"), &component, "analytics", 10)
+                .unwrap();
+        assert_eq!(issues[0].severity, Severity::Error);
+
+        let issues =
+            InsertErrorsDiagnostic::parse_response(r#"{"data": []}"#, &component, "analytics", 10)
+                .unwrap();
+        assert!(issues.is_empty());
+    }
+
     #[tokio::test]
     async fn test_invalid_diagnostic_names_return_error() {
         let config = ClickHouseConfig {
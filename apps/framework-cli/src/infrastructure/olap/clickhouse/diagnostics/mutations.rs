@@ -6,6 +6,7 @@ use tracing::debug;
 use super::{Component, DiagnosticError, DiagnosticProvider, Issue, Severity};
 use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
 use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::model::quote_string;
 use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
 
 /// Query timeout for diagnostic checks (30 seconds)
@@ -91,22 +92,22 @@ impl MutationDiagnostic {
 
             let suggested_action = if !latest_fail_reason.is_empty() {
                 format!(
-                    "Review the failure reason and consider killing the mutation with: KILL MUTATION WHERE mutation_id = '{}'",
-                    mutation_id
+                    "Review the failure reason and consider killing the mutation with: KILL MUTATION WHERE mutation_id = {}",
+                    quote_string(mutation_id)
                 )
             } else {
                 format!(
-                    "Check mutation progress. If stuck, kill it with: KILL MUTATION WHERE mutation_id = '{}'",
-                    mutation_id
+                    "Check mutation progress. If stuck, kill it with: KILL MUTATION WHERE mutation_id = {}",
+                    quote_string(mutation_id)
                 )
             };
 
             let related_queries = vec![
                 format!(
-                    "SELECT * FROM system.mutations WHERE database = '{}' AND table = '{}' AND mutation_id = '{}'",
-                    db_name, component.name, mutation_id
+                    "SELECT * FROM system.mutations WHERE database = {} AND table = {} AND mutation_id = {}",
+                    quote_string(db_name), quote_string(&component.name), quote_string(mutation_id)
                 ),
-                format!("KILL MUTATION WHERE mutation_id = '{}'", mutation_id),
+                format!("KILL MUTATION WHERE mutation_id = {}", quote_string(mutation_id)),
             ];
 
             issues.push(Issue {
@@ -156,11 +157,12 @@ impl DiagnosticProvider for MutationDiagnostic {
                 latest_fail_time,
                 latest_fail_reason
              FROM system.mutations
-             WHERE database = '{}' AND table = '{}'
+             WHERE database = {} AND table = {}
              AND (is_done = 0 OR latest_fail_reason != '')
              ORDER BY create_time DESC
              FORMAT JSON",
-            config.db_name, component.name
+            quote_string(&config.db_name),
+            quote_string(&component.name)
         );
 
         debug!("Executing mutations query: {}", query);
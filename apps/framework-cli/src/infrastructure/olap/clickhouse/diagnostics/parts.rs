@@ -6,6 +6,7 @@ use tracing::debug;
 use super::{Component, DiagnosticError, DiagnosticProvider, Issue, Severity};
 use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
 use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::model::{quote_qualified, quote_string};
 use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
 
 /// Query timeout for diagnostic checks (30 seconds)
@@ -85,17 +86,17 @@ impl PartsDiagnostic {
                 ),
                 details,
                 suggested_action: format!(
-                    "Run OPTIMIZE TABLE to merge parts: OPTIMIZE TABLE {}.{} PARTITION '{}'",
-                    db_name, component.name, partition
+                    "Run OPTIMIZE TABLE to merge parts: OPTIMIZE TABLE {} PARTITION {}",
+                    quote_qualified(db_name, &component.name), quote_string(partition)
                 ),
                 related_queries: vec![
                     format!(
-                        "SELECT * FROM system.parts WHERE database = '{}' AND table = '{}' AND partition = '{}' AND active = 1",
-                        db_name, component.name, partition
+                        "SELECT * FROM system.parts WHERE database = {} AND table = {} AND partition = {} AND active = 1",
+                        quote_string(db_name), quote_string(&component.name), quote_string(partition)
                     ),
                     format!(
-                        "OPTIMIZE TABLE {}.{} PARTITION '{}'",
-                        db_name, component.name, partition
+                        "OPTIMIZE TABLE {} PARTITION {}",
+                        quote_qualified(db_name, &component.name), quote_string(partition)
                     ),
                 ],
             });
@@ -134,12 +135,13 @@ impl DiagnosticProvider for PartsDiagnostic {
                 sum(rows) as total_rows,
                 sum(bytes_on_disk) as total_bytes
              FROM system.parts
-             WHERE database = '{}' AND table = '{}' AND active = 1
+             WHERE database = {} AND table = {} AND active = 1
              GROUP BY partition
              HAVING part_count > 100
              ORDER BY part_count DESC
              FORMAT JSON",
-            config.db_name, component.name
+            quote_string(&config.db_name),
+            quote_string(&component.name)
         );
 
         debug!("Executing parts query: {}", query);
@@ -6,6 +6,7 @@ use tracing::debug;
 use super::{Component, DiagnosticError, DiagnosticProvider, Issue, Severity};
 use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
 use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::model::{quote_qualified, quote_string};
 use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
 
 /// Query timeout for diagnostic checks (30 seconds)
@@ -66,14 +67,17 @@ impl ReplicationDiagnostic {
                 suggested_action: "Check if replication is stopped with 'SELECT * FROM system.replicas'. Consider restarting replication with 'SYSTEM START REPLICATION QUEUES' if stopped.".to_string(),
                 related_queries: vec![
                     format!(
-                        "SELECT * FROM system.replication_queue WHERE database = '{}' AND table = '{}'",
-                        db_name, component.name
+                        "SELECT * FROM system.replication_queue WHERE database = {} AND table = {}",
+                        quote_string(db_name), quote_string(&component.name)
                     ),
                     format!(
-                        "SELECT * FROM system.replicas WHERE database = '{}' AND table = '{}'",
-                        db_name, component.name
+                        "SELECT * FROM system.replicas WHERE database = {} AND table = {}",
+                        quote_string(db_name), quote_string(&component.name)
+                    ),
+                    format!(
+                        "SYSTEM START REPLICATION QUEUES {}",
+                        quote_qualified(db_name, &component.name)
                     ),
-                    format!("SYSTEM START REPLICATION QUEUES {}.{}", db_name, component.name),
                 ],
             });
         }
@@ -150,12 +154,12 @@ impl ReplicationDiagnostic {
                 suggested_action: "Check ZooKeeper/ClickHouse Keeper connectivity. Verify replica is active and reachable. Review ClickHouse server logs for replication errors.".to_string(),
                 related_queries: vec![
                     format!(
-                        "SELECT * FROM system.replication_queue WHERE database = '{}' AND table = '{}'",
-                        db_name, component.name
+                        "SELECT * FROM system.replication_queue WHERE database = {} AND table = {}",
+                        quote_string(db_name), quote_string(&component.name)
                     ),
                     format!(
-                        "SELECT * FROM system.replicas WHERE database = '{}' AND table = '{}'",
-                        db_name, component.name
+                        "SELECT * FROM system.replicas WHERE database = {} AND table = {}",
+                        quote_string(db_name), quote_string(&component.name)
                     ),
                 ],
             });
@@ -243,10 +247,13 @@ impl ReplicationDiagnostic {
                         suggested_action: "Check ZooKeeper/ClickHouse Keeper connectivity. Verify network connectivity between replicas. Consider using SYSTEM RESTART REPLICA if session expired.".to_string(),
                         related_queries: vec![
                             format!(
-                                "SELECT * FROM system.replicas WHERE database = '{}' AND table = '{}'",
-                                db_name, component.name
+                                "SELECT * FROM system.replicas WHERE database = {} AND table = {}",
+                                quote_string(db_name), quote_string(&component.name)
+                            ),
+                            format!(
+                                "SYSTEM RESTART REPLICA {}",
+                                quote_qualified(db_name, &component.name)
                             ),
-                            format!("SYSTEM RESTART REPLICA {}.{}", db_name, component.name),
                         ],
                     });
                 }
@@ -290,9 +297,10 @@ impl DiagnosticProvider for ReplicationDiagnostic {
         let queue_size_query = format!(
             "SELECT count() as queue_size
              FROM system.replication_queue
-             WHERE database = '{}' AND table = '{}'
+             WHERE database = {} AND table = {}
              FORMAT JSON",
-            config.db_name, component.name
+            quote_string(&config.db_name),
+            quote_string(&component.name)
         );
 
         debug!(
@@ -323,12 +331,13 @@ impl DiagnosticProvider for ReplicationDiagnostic {
                 num_tries,
                 last_exception
              FROM system.replication_queue
-             WHERE database = '{}' AND table = '{}'
+             WHERE database = {} AND table = {}
              AND (num_tries > 3 OR last_exception != '')
              ORDER BY create_time ASC
              LIMIT 20
              FORMAT JSON",
-            config.db_name, component.name
+            quote_string(&config.db_name),
+            quote_string(&component.name)
         );
 
         debug!("Executing replication queue query: {}", queue_query);
@@ -359,9 +368,10 @@ impl DiagnosticProvider for ReplicationDiagnostic {
                 merges_in_queue,
                 absolute_delay
              FROM system.replicas
-             WHERE database = '{}' AND table = '{}'
+             WHERE database = {} AND table = {}
              FORMAT JSON",
-            config.db_name, component.name
+            quote_string(&config.db_name),
+            quote_string(&component.name)
         );
 
         debug!("Executing replicas query: {}", replica_query);
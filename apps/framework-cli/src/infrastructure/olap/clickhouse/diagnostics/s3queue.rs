@@ -6,6 +6,7 @@ use tracing::debug;
 use super::{Component, DiagnosticError, DiagnosticProvider, Issue, Severity};
 use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
 use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::model::quote_string;
 use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
 
 /// Query timeout for diagnostic checks (30 seconds)
@@ -83,12 +84,12 @@ impl S3QueueDiagnostic {
                 suggested_action: "Check S3 bucket permissions, file format, and schema compatibility. Review S3Queue settings and keeper_path configuration.".to_string(),
                 related_queries: vec![
                     format!(
-                        "SELECT * FROM system.s3queue_log WHERE database = '{}' AND table = '{}' ORDER BY processing_start_time DESC LIMIT 50",
-                        db_name, component.name
+                        "SELECT * FROM system.s3queue_log WHERE database = {} AND table = {} ORDER BY processing_start_time DESC LIMIT 50",
+                        quote_string(db_name), quote_string(&component.name)
                     ),
                     format!(
-                        "SELECT * FROM system.s3queue WHERE database = '{}' AND table = '{}'",
-                        db_name, component.name
+                        "SELECT * FROM system.s3queue WHERE database = {} AND table = {}",
+                        quote_string(db_name), quote_string(&component.name)
                     ),
                 ],
             });
@@ -128,12 +129,13 @@ impl DiagnosticProvider for S3QueueDiagnostic {
                 processing_end_time,
                 exception
              FROM system.s3queue_log
-             WHERE database = '{}' AND table = '{}'
+             WHERE database = {} AND table = {}
              AND status IN ('Failed', 'ProcessingFailed')
              ORDER BY processing_start_time DESC
              LIMIT 20
              FORMAT JSON",
-            config.db_name, component.name
+            quote_string(&config.db_name),
+            quote_string(&component.name)
         );
 
         debug!("Executing S3Queue query: {}", query);
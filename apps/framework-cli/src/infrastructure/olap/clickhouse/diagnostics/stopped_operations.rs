@@ -6,6 +6,7 @@ use tracing::debug;
 use super::{Component, DiagnosticError, DiagnosticProvider, Issue, Severity};
 use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
 use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::model::{quote_qualified, quote_string};
 use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
 
 /// Query timeout for diagnostic checks (30 seconds)
@@ -82,17 +83,17 @@ impl StoppedOperationsDiagnostic {
                     ),
                     details,
                     suggested_action: format!(
-                        "Check if merges were manually stopped with 'SELECT * FROM system.settings WHERE name LIKE \"%merge%\"'. Start merges if needed: 'SYSTEM START MERGES {}.{}'",
-                        db_name, component.name
+                        "Check if merges were manually stopped with 'SELECT * FROM system.settings WHERE name LIKE \"%merge%\"'. Start merges if needed: 'SYSTEM START MERGES {}'",
+                        quote_qualified(db_name, &component.name)
                     ),
                     related_queries: vec![
                         format!(
-                            "SELECT * FROM system.parts WHERE database = '{}' AND table = '{}' AND active = 1 ORDER BY modification_time DESC LIMIT 20",
-                            db_name, component.name
+                            "SELECT * FROM system.parts WHERE database = {} AND table = {} AND active = 1 ORDER BY modification_time DESC LIMIT 20",
+                            quote_string(db_name), quote_string(&component.name)
                         ),
                         format!(
-                            "SYSTEM START MERGES {}.{}",
-                            db_name, component.name
+                            "SYSTEM START MERGES {}",
+                            quote_qualified(db_name, &component.name)
                         ),
                     ],
                 });
@@ -143,17 +144,17 @@ impl StoppedOperationsDiagnostic {
                         ),
                         details,
                         suggested_action: format!(
-                            "Investigate why replica is read-only. Try restarting replication: 'SYSTEM START REPLICATION QUEUES {}.{}'",
-                            db_name, component.name
+                            "Investigate why replica is read-only. Try restarting replication: 'SYSTEM START REPLICATION QUEUES {}'",
+                            quote_qualified(db_name, &component.name)
                         ),
                         related_queries: vec![
                             format!(
-                                "SELECT * FROM system.replicas WHERE database = '{}' AND table = '{}'",
-                                db_name, component.name
+                                "SELECT * FROM system.replicas WHERE database = {} AND table = {}",
+                                quote_string(db_name), quote_string(&component.name)
                             ),
                             format!(
-                                "SYSTEM START REPLICATION QUEUES {}.{}",
-                                db_name, component.name
+                                "SYSTEM START REPLICATION QUEUES {}",
+                                quote_qualified(db_name, &component.name)
                             ),
                         ],
                     });
@@ -193,9 +194,10 @@ impl DiagnosticProvider for StoppedOperationsDiagnostic {
         let parts_count_query = format!(
             "SELECT count() as part_count
              FROM system.parts
-             WHERE database = '{}' AND table = '{}' AND active = 1
+             WHERE database = {} AND table = {} AND active = 1
              FORMAT JSON",
-            config.db_name, component.name
+            quote_string(&config.db_name),
+            quote_string(&component.name)
         );
 
         debug!("Executing parts count query: {}", parts_count_query);
@@ -211,9 +213,10 @@ impl DiagnosticProvider for StoppedOperationsDiagnostic {
         let merges_query = format!(
             "SELECT count() as merge_count
              FROM system.merges
-             WHERE database = '{}' AND table = '{}'
+             WHERE database = {} AND table = {}
              FORMAT JSON",
-            config.db_name, component.name
+            quote_string(&config.db_name),
+            quote_string(&component.name)
         );
 
         debug!("Executing merges query: {}", merges_query);
@@ -246,9 +249,10 @@ impl DiagnosticProvider for StoppedOperationsDiagnostic {
             let replicas_query = format!(
                 "SELECT is_readonly, queue_size
                      FROM system.replicas
-                     WHERE database = '{}' AND table = '{}'
+                     WHERE database = {} AND table = {}
                      FORMAT JSON",
-                config.db_name, component.name
+                quote_string(&config.db_name),
+                quote_string(&component.name)
             );
 
             debug!("Executing replicas query: {}", replicas_query);
@@ -6,15 +6,28 @@
 
 use crate::framework::core::infrastructure::sql_resource::SqlResource;
 use crate::framework::core::infrastructure::table::{
-    Column, ColumnType, DataEnum, EnumValue, JsonOptions, Nested, Table,
+    Column, ColumnType, DataEnum, EnumValue, JsonOptions, Nested, OrderBy, Table,
 };
 use crate::framework::core::infrastructure_map::{
     ColumnChange, OlapChange, OrderByChange, PartitionByChange, TableChange, TableDiffStrategy,
 };
+use crate::framework::core::partial_infrastructure_map::LifeCycle;
 use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
 use std::collections::HashMap;
 use std::mem::discriminant;
 
+/// Readonly settings that cannot be modified after table creation, with their
+/// defaults. A missing readonly setting has its default value.
+/// Source: ClickHouse/src/Storages/MergeTree/MergeTreeSettings.cpp::isReadonlySetting
+pub const READONLY_SETTINGS: &[(&str, &str)] = &[
+    ("index_granularity", "8192"),
+    ("index_granularity_bytes", "10485760"),
+    ("enable_mixed_granularity_parts", "1"),
+    ("add_minmax_index_for_numeric_columns", "0"),
+    ("add_minmax_index_for_string_columns", "0"),
+    ("table_disk", "0"),
+];
+
 /// Generates a formatted error message for database field changes.
 ///
 /// This function creates a user-friendly error message explaining that database field
@@ -433,6 +446,86 @@ fn is_only_required_change_for_special_column_type(before: &Column, after: &Colu
     }
 }
 
+/// Whether `expr` references `column` as a whole identifier
+pub fn expression_references_column(expr: &str, column: &str) -> bool {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+    expr.match_indices(column).any(|(start, _)| {
+        let before = expr[..start].chars().next_back();
+        let after = expr[start + column.len()..].chars().next();
+        !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+    })
+}
+
+/// Names of the columns whose type changes although they are part of the
+/// table's ORDER BY, PRIMARY KEY or PARTITION BY.
+///
+/// ClickHouse refuses to `MODIFY COLUMN` these (error 524,
+/// ALTER_OF_COLUMN_IS_FORBIDDEN, or 44 for partition keys), so such changes
+/// can only be applied by rebuilding the table.
+pub fn key_column_type_changes(table: &Table, column_changes: &[ColumnChange]) -> Vec<String> {
+    if !table.engine.is_merge_tree_family() {
+        return Vec::new();
+    }
+
+    let mut key_expressions = vec![
+        table.order_by_with_fallback().to_expr().into_owned(),
+        table.normalized_primary_key_expr(),
+    ];
+    key_expressions.extend(table.partition_by.clone());
+
+    column_changes
+        .iter()
+        .filter_map(|change| match change {
+            ColumnChange::Updated { before, after }
+                if !before
+                    .data_type
+                    .eq_ignoring_field_comments(&after.data_type)
+                    || before.required != after.required =>
+            {
+                Some(&before.name)
+            }
+            _ => None,
+        })
+        .filter(|column| {
+            key_expressions
+                .iter()
+                .any(|expr| expression_references_column(expr, column))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Generates the error message for type changes of key columns that cannot be
+/// applied by rebuilding the table.
+///
+/// # Arguments
+/// * `table_name` - The name of the table being changed
+/// * `columns` - The key columns whose type changed
+///
+/// # Returns
+/// A formatted string explaining the ClickHouse restriction and how to rebuild
+pub fn format_key_column_change_error(table_name: &str, columns: &[String]) -> String {
+    format!(
+        "\n\n\
+        ERROR: Type change of key column(s) {} detected for table '{}'\n\
+        \n\
+        ClickHouse cannot ALTER the type of a column used in the ORDER BY,\n\
+        PRIMARY KEY or PARTITION BY of a table (error 524 ALTER_OF_COLUMN_IS_FORBIDDEN).\n\
+        The table has to be rebuilt instead, and Moose only rebuilds FULLY_MANAGED tables.\n\
+        \n\
+        To apply this change, either:\n\
+        \n\
+        1. Set the table's life cycle to FULLY_MANAGED so Moose drops and re-creates\n\
+           it (this deletes the table's data), or\n\
+        2. Create a new table with the new column type, copy the data with\n\
+           INSERT INTO <new_table> SELECT * FROM {}\n\
+           and delete the old table definition from your code.\n",
+        columns.join(", "),
+        table_name,
+        table_name
+    )
+}
+
 impl ClickHouseTableDiffStrategy {
     /// Check if a table uses the S3Queue engine
     ///
@@ -523,6 +616,44 @@ impl ClickHouseTableDiffStrategy {
     }
 }
 
+/// Whether the ORDER BY change of a table appends columns added by the same
+/// change to the sorting key, which `ALTER TABLE ... MODIFY ORDER BY` can do
+/// without rewriting the data, since existing rows are still sorted.
+///
+/// ClickHouse rejects appending existing columns, and added columns with a
+/// DEFAULT, MATERIALIZED or ALIAS expression. The primary key stays the old
+/// sorting key, so the table must declare it for the result to match the code.
+fn is_sort_key_extension(before: &Table, after: &Table, column_changes: &[ColumnChange]) -> bool {
+    let (OrderBy::Fields(before_fields), OrderBy::Fields(after_fields)) =
+        (&before.order_by, &after.order_by)
+    else {
+        return false;
+    };
+    let primary_key = before.normalized_primary_key_expr();
+    if !after.engine.is_merge_tree_family()
+        || before_fields.is_empty()
+        || after_fields.len() <= before_fields.len()
+        || !after_fields.starts_with(before_fields)
+        || primary_key.is_empty()
+        || primary_key != after.normalized_primary_key_expr()
+    {
+        return false;
+    }
+
+    after_fields[before_fields.len()..].iter().all(|field| {
+        column_changes.iter().any(|change| {
+            matches!(
+                change,
+                ColumnChange::Added { column, .. }
+                    if &column.name == field
+                        && column.default.is_none()
+                        && column.materialized.is_none()
+                        && column.alias.is_none()
+            )
+        })
+    })
+}
+
 impl TableDiffStrategy for ClickHouseTableDiffStrategy {
     /// This function is only called when there are actual changes to the table
     /// (column changes, ORDER BY changes, PARTITION BY changes, or deduplication changes).
@@ -537,9 +668,27 @@ impl TableDiffStrategy for ClickHouseTableDiffStrategy {
         partition_by_change: PartitionByChange,
         default_database: &str,
     ) -> Vec<OlapChange> {
-        // Check if ORDER BY has changed
+        // A Null table stores no rows, so switching a table to it drops the rows
+        // the table holds. Checked first as the ORDER BY, which Null tables
+        // don't take, usually changes too.
+        if matches!(after.engine, ClickhouseEngine::Null)
+            && !matches!(before.engine, ClickhouseEngine::Null)
+        {
+            tracing::warn!(
+                "ClickHouse: table '{}' switches from {} to the Null engine, requiring drop+create, its rows will be lost",
+                before.name,
+                before.engine.to_proto_string()
+            );
+            return vec![
+                OlapChange::Table(TableChange::Removed(before.clone())),
+                OlapChange::Table(TableChange::Added(after.clone())),
+            ];
+        }
+
+        // Check if ORDER BY has changed. Only extending it with added columns
+        // can be done via ALTER TABLE
         let order_by_changed = order_by_change.before != order_by_change.after;
-        if order_by_changed {
+        if order_by_changed && !is_sort_key_extension(before, after, &column_changes) {
             tracing::warn!(
                 "ClickHouse: ORDER BY changed for table '{}', requiring drop+create",
                 before.name
@@ -650,17 +799,6 @@ impl TableDiffStrategy for ClickHouseTableDiffStrategy {
         }
         let mut changes = Vec::new();
 
-        // List of readonly settings that cannot be modified after table creation
-        // Source: ClickHouse/src/Storages/MergeTree/MergeTreeSettings.cpp::isReadonlySetting
-        const READONLY_SETTINGS: &[(&str, &str)] = &[
-            ("index_granularity", "8192"),
-            ("index_granularity_bytes", "10485760"),
-            ("enable_mixed_granularity_parts", "1"),
-            ("add_minmax_index_for_numeric_columns", "0"),
-            ("add_minmax_index_for_string_columns", "0"),
-            ("table_disk", "0"),
-        ];
-
         // Compare table_settings using hashes when available (for tables with sensitive settings).
         // This allows detecting actual changes without comparing masked credential values.
         // When comparing directly, treat missing readonly settings as having their default values
@@ -792,10 +930,36 @@ impl TableDiffStrategy for ClickHouseTableDiffStrategy {
             })
             .collect();
 
+        // ClickHouse cannot change the type of key columns in place
+        let key_columns = key_column_type_changes(before, &column_changes);
+        if !key_columns.is_empty() {
+            if after.life_cycle == LifeCycle::FullyManaged {
+                tracing::warn!(
+                    "ClickHouse: Type of key column(s) {} changed for table '{}', requiring drop+create",
+                    key_columns.join(", "),
+                    before.name
+                );
+                return vec![
+                    OlapChange::Table(TableChange::Removed(before.clone())),
+                    OlapChange::Table(TableChange::Added(after.clone())),
+                ];
+            }
+
+            let error_message = format_key_column_change_error(&before.name, &key_columns);
+            tracing::error!("{}", error_message);
+            return vec![OlapChange::Table(TableChange::ValidationError {
+                table_name: before.name.clone(),
+                message: error_message,
+                before: Box::new(before.clone()),
+                after: Box::new(after.clone()),
+            })];
+        }
+
         // For other changes, ClickHouse can handle them via ALTER TABLE.
-        // If there are no column/index/sample_by changes, return an empty vector.
+        // If there are no column/index/sample_by/order_by changes, return an empty vector.
         let sample_by_changed = before.sample_by != after.sample_by;
         if !column_changes.is_empty()
+            || order_by_changed
             || before.indexes != after.indexes
             || before.projections != after.projections
             || sample_by_changed
@@ -819,9 +983,10 @@ mod tests {
     use super::*;
     use crate::framework::core::infrastructure::table::{Column, ColumnType, EnumMember, OrderBy};
     use crate::framework::core::infrastructure_map::{PrimitiveSignature, PrimitiveTypes};
-    use crate::framework::core::partial_infrastructure_map::LifeCycle;
     use crate::framework::versions::Version;
     use crate::infrastructure::olap::clickhouse::sql_parser::parse_create_materialized_view;
+    use crate::infrastructure::olap::clickhouse::SerializableOlapOperation;
+    use crate::infrastructure::olap::ddl_ordering::order_olap_changes;
 
     fn create_test_table(name: &str, order_by: Vec<String>, deduplicate: bool) -> Table {
         Table {
@@ -884,6 +1049,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         }
     }
 
@@ -928,6 +1094,129 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_switch_to_null_engine_requires_drop_create() {
+        let strategy = ClickHouseTableDiffStrategy;
+
+        let before = create_test_table("test", vec!["id".to_string()], false);
+        let mut after = create_test_table("test", vec![], false);
+        after.engine = ClickhouseEngine::Null;
+
+        let changes = strategy.diff_table_update(
+            &before,
+            &after,
+            vec![],
+            OrderByChange {
+                before: before.order_by.clone(),
+                after: after.order_by.clone(),
+            },
+            PartitionByChange {
+                before: None,
+                after: None,
+            },
+            "local",
+        );
+
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(
+            &changes[0],
+            OlapChange::Table(TableChange::Removed(Table {
+                engine: ClickhouseEngine::MergeTree,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            &changes[1],
+            OlapChange::Table(TableChange::Added(Table {
+                engine: ClickhouseEngine::Null,
+                ..
+            }))
+        ));
+    }
+
+    fn added_region_column(default: Option<&str>) -> Column {
+        Column {
+            name: "region".to_string(),
+            data_type: ColumnType::String,
+            required: true,
+            unique: false,
+            primary_key: false,
+            default: default.map(str::to_string),
+            annotations: vec![],
+            comment: None,
+            ttl: None,
+            codec: None,
+            materialized: None,
+            alias: None,
+        }
+    }
+
+    fn extend_sort_key_with_region(region: Column) -> Vec<OlapChange> {
+        let before = create_test_table("test", vec!["id".to_string()], false);
+        let mut after =
+            create_test_table("test", vec!["id".to_string(), "region".to_string()], false);
+        after.columns.push(region.clone());
+
+        let order_by_change = OrderByChange {
+            before: before.order_by.clone(),
+            after: after.order_by.clone(),
+        };
+        let partition_by_change = PartitionByChange {
+            before: None,
+            after: None,
+        };
+
+        ClickHouseTableDiffStrategy.diff_table_update(
+            &before,
+            &after,
+            vec![ColumnChange::Added {
+                column: region,
+                position_after: Some("timestamp".to_string()),
+            }],
+            order_by_change,
+            partition_by_change,
+            "local",
+        )
+    }
+
+    #[test]
+    fn test_sort_key_extended_with_added_column_is_altered() {
+        let changes = extend_sort_key_with_region(added_region_column(None));
+
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(
+            changes[0],
+            OlapChange::Table(TableChange::Updated { .. })
+        ));
+
+        let (teardown, setup) = order_olap_changes(&changes, "local").unwrap();
+        assert!(teardown.is_empty());
+        let setup: Vec<_> = setup.iter().map(|op| op.to_minimal()).collect();
+        assert!(matches!(
+            setup.as_slice(),
+            [
+                SerializableOlapOperation::AddTableColumn { column, .. },
+                SerializableOlapOperation::ModifyOrderBy { table, expression, .. },
+            ] if column.name == "region" && table == "test" && expression == "(id, region)"
+        ));
+    }
+
+    #[test]
+    fn test_sort_key_extended_with_defaulted_column_requires_drop_create() {
+        // ClickHouse rejects a sorting key using an added column with a default
+        let changes = extend_sort_key_with_region(added_region_column(Some("'eu'")));
+
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(
+            changes[0],
+            OlapChange::Table(TableChange::Removed(_))
+        ));
+        assert!(matches!(
+            changes[1],
+            OlapChange::Table(TableChange::Added(_))
+        ));
+    }
+
     #[test]
     fn test_deduplication_change_requires_drop_create() {
         let strategy = ClickHouseTableDiffStrategy;
@@ -1879,6 +2168,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Test legacy helper method
@@ -3108,4 +3398,166 @@ mod tests {
             true
         ));
     }
+
+    /// Changes the type of `column` in a copy of `before`, returning the
+    /// updated table and the column change
+    fn change_column_type(
+        before: &Table,
+        column: &str,
+        data_type: ColumnType,
+    ) -> (Table, Vec<ColumnChange>) {
+        let mut after = before.clone();
+        let after_column = after.columns.iter_mut().find(|c| c.name == column).unwrap();
+        after_column.data_type = data_type;
+        let column_changes = vec![ColumnChange::Updated {
+            before: before
+                .columns
+                .iter()
+                .find(|c| c.name == column)
+                .unwrap()
+                .clone(),
+            after: after_column.clone(),
+        }];
+        (after, column_changes)
+    }
+
+    fn diff_column_changes(
+        before: &Table,
+        after: &Table,
+        column_changes: Vec<ColumnChange>,
+    ) -> Vec<OlapChange> {
+        ClickHouseTableDiffStrategy.diff_table_update(
+            before,
+            after,
+            column_changes,
+            OrderByChange {
+                before: before.order_by.clone(),
+                after: after.order_by.clone(),
+            },
+            PartitionByChange {
+                before: before.partition_by.clone(),
+                after: after.partition_by.clone(),
+            },
+            "local",
+        )
+    }
+
+    #[test]
+    fn test_key_column_type_changes() {
+        let mut table = create_test_table("test", vec!["id".to_string()], false);
+        table.partition_by = Some("toYYYYMM(timestamp)".to_string());
+
+        let (_, id_change) = change_column_type(&table, "id", ColumnType::Uuid);
+        assert_eq!(key_column_type_changes(&table, &id_change), vec!["id"]);
+
+        let (_, partition_change) = change_column_type(
+            &table,
+            "timestamp",
+            ColumnType::DateTime { precision: None },
+        );
+        assert_eq!(
+            key_column_type_changes(&table, &partition_change),
+            vec!["timestamp"]
+        );
+
+        // `id` is only a prefix of `identifier`
+        table.partition_by = None;
+        table.order_by = OrderBy::SingleExpr("(identifier, cityHash64(timestamp))".to_string());
+        table.primary_key_expression = Some("identifier".to_string());
+        assert!(key_column_type_changes(&table, &id_change).is_empty());
+        assert_eq!(
+            key_column_type_changes(&table, &partition_change),
+            vec!["timestamp"]
+        );
+    }
+
+    #[test]
+    fn test_key_column_type_change_requires_drop_create() {
+        let before = create_test_table("test", vec!["id".to_string()], false);
+        let (after, column_changes) = change_column_type(&before, "id", ColumnType::Uuid);
+
+        let changes = diff_column_changes(&before, &after, column_changes);
+
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(
+            changes[0],
+            OlapChange::Table(TableChange::Removed(_))
+        ));
+        assert!(matches!(
+            changes[1],
+            OlapChange::Table(TableChange::Added(_))
+        ));
+    }
+
+    #[test]
+    fn test_non_key_column_type_change_uses_alter() {
+        let before = create_test_table("test", vec!["id".to_string()], false);
+        let (after, column_changes) = change_column_type(
+            &before,
+            "timestamp",
+            ColumnType::DateTime { precision: None },
+        );
+
+        let changes = diff_column_changes(&before, &after, column_changes);
+
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(
+            &changes[0],
+            OlapChange::Table(TableChange::Updated { column_changes, .. })
+                if column_changes.len() == 1
+        ));
+    }
+
+    #[test]
+    fn test_key_column_type_change_rejected_when_table_cannot_be_rebuilt() {
+        for life_cycle in [LifeCycle::DeletionProtected, LifeCycle::ExternallyManaged] {
+            let mut before = create_test_table("test", vec!["id".to_string()], false);
+            before.life_cycle = life_cycle;
+            let (after, column_changes) = change_column_type(&before, "id", ColumnType::Uuid);
+
+            let changes = diff_column_changes(&before, &after, column_changes);
+
+            assert_eq!(changes.len(), 1);
+            match &changes[0] {
+                OlapChange::Table(TableChange::ValidationError {
+                    table_name,
+                    message,
+                    ..
+                }) => {
+                    assert_eq!(table_name, "test");
+                    assert!(message.contains("key column(s) id"));
+                    assert!(message.contains("ALTER_OF_COLUMN_IS_FORBIDDEN"));
+                    assert!(message.contains("FULLY_MANAGED"));
+                    assert!(message.contains("INSERT INTO <new_table> SELECT * FROM test"));
+                }
+                other => panic!("Expected ValidationError for {life_cycle:?}, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_externally_managed_key_column_type_change_is_filtered() {
+        use crate::framework::core::infrastructure_map::InfrastructureMap;
+
+        let mut before = create_test_table("test", vec!["id".to_string()], false);
+        before.life_cycle = LifeCycle::ExternallyManaged;
+        let (after, _) = change_column_type(&before, "id", ColumnType::Uuid);
+
+        let mut current = InfrastructureMap::default();
+        current.tables.insert(before.id("local"), before);
+        let mut target = InfrastructureMap::default();
+        target.tables.insert(after.id("local"), after);
+
+        let changes = current.diff_with_table_strategy(
+            &target,
+            &ClickHouseTableDiffStrategy,
+            true,
+            false,
+            &[],
+        );
+
+        // Moose never alters or rebuilds tables it does not manage
+        assert!(changes.olap_changes.is_empty());
+        assert_eq!(changes.filtered_olap_changes.len(), 1);
+    }
 }
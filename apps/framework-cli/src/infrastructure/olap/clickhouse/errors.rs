@@ -70,3 +70,167 @@ pub fn validate_clickhouse_identifier(
         reason: reason.to_string(),
     })
 }
+
+/// What a ClickHouse error code means and what to do about it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorGuidance {
+    pub code: u32,
+    pub explanation: &'static str,
+    pub action: &'static str,
+}
+
+impl std::fmt::Display for ErrorGuidance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Code {}: {}. {}",
+            self.code, self.explanation, self.action
+        )
+    }
+}
+
+const ERROR_GUIDANCE: &[ErrorGuidance] = &[
+    ErrorGuidance {
+        code: 44,
+        explanation: "the column is used in the table key and cannot be modified in place",
+        action: "Rebuild the table with the new column definition, or drop the column from ORDER BY/PARTITION BY first",
+    },
+    ErrorGuidance {
+        code: 57,
+        explanation: "the table already exists",
+        action: "Drop or rename the existing table, or run `moose db pull` to adopt it",
+    },
+    ErrorGuidance {
+        code: 60,
+        explanation: "the table does not exist",
+        action: "Check the database set in `clickhouse_config` and that the table was created",
+    },
+    ErrorGuidance {
+        code: 81,
+        explanation: "the database does not exist",
+        action: "Check `db_name` in `clickhouse_config`, or create the database",
+    },
+    ErrorGuidance {
+        code: 241,
+        explanation: "the query exceeded the ClickHouse memory limit",
+        action: "Raise `max_memory_usage`, or run the operation on less data at a time",
+    },
+    ErrorGuidance {
+        code: 252,
+        explanation: "the table has too many active parts",
+        action: "Insert in larger, less frequent batches, and wait for merges to catch up before retrying",
+    },
+    ErrorGuidance {
+        code: 253,
+        explanation: "a replica with this path already exists in Keeper",
+        action: "Drop the stale replica with `SYSTEM DROP REPLICA`, or use a different replication path",
+    },
+    ErrorGuidance {
+        code: 516,
+        explanation: "ClickHouse rejected the credentials",
+        action: "Check `user` and `password` in `clickhouse_config`",
+    },
+    ErrorGuidance {
+        code: 524,
+        explanation: "ClickHouse cannot alter this column, usually because it is part of the table key",
+        action: "Rebuild the table with the new column definition",
+    },
+];
+
+/// Parses the numeric code out of a ClickHouse error message, e.g.
+/// `Code: 60. DB::Exception: ...`.
+pub fn clickhouse_error_code(message: &str) -> Option<u32> {
+    let start = message.find("Code: ")? + "Code: ".len();
+    let digits: String = message[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+/// Guidance for the ClickHouse error in `message`, for the common codes.
+pub fn error_guidance(message: &str) -> Option<ErrorGuidance> {
+    let code = clickhouse_error_code(message)?;
+    ERROR_GUIDANCE
+        .iter()
+        .find(|guidance| guidance.code == code)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_guidance_from_error_messages() {
+        let cases = [
+            (
+                "bad response: Code: 44. DB::Exception: Trying to ALTER key column id. (ILLEGAL_COLUMN)",
+                Some(44),
+            ),
+            (
+                "bad response: Code: 57. DB::Exception: Table local.users already exists. (TABLE_ALREADY_EXISTS) (version 25.3.1.1)",
+                Some(57),
+            ),
+            (
+                "Code: 60. DB::Exception: Table local.events does not exist. (UNKNOWN_TABLE)",
+                Some(60),
+            ),
+            (
+                "Code: 81. DB::Exception: Database analytics does not exist. (UNKNOWN_DATABASE)",
+                Some(81),
+            ),
+            (
+                "Code: 241. DB::Exception: Memory limit (total) exceeded. (MEMORY_LIMIT_EXCEEDED)",
+                Some(241),
+            ),
+            (
+                "Code: 252. DB::Exception: Too many parts (300). (TOO_MANY_PARTS)",
+                Some(252),
+            ),
+            (
+                "Code: 253. DB::Exception: Replica /clickhouse/tables/events/replicas/r1 already exists. (REPLICA_ALREADY_EXISTS)",
+                Some(253),
+            ),
+            (
+                "Code: 516. DB::Exception: default: Authentication failed. (AUTHENTICATION_FAILED)",
+                Some(516),
+            ),
+            (
+                "Code: 524. DB::Exception: ALTER of key column ts from type DateTime to type DateTime64(3) is not safe because it can change the representation of primary key. (ALTER_OF_COLUMN_IS_FORBIDDEN)",
+                Some(524),
+            ),
+            // Codes without guidance and messages without a code
+            (
+                "Code: 62. DB::Exception: Syntax error. (SYNTAX_ERROR)",
+                None,
+            ),
+            ("network error: connection refused", None),
+            ("Code: abc", None),
+        ];
+
+        for (message, expected) in cases {
+            assert_eq!(
+                error_guidance(message).map(|guidance| guidance.code),
+                expected,
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_clickhouse_error_code() {
+        assert_eq!(clickhouse_error_code("Code: 62. DB::Exception"), Some(62));
+        assert_eq!(clickhouse_error_code("Code: 1000"), Some(1000));
+        assert_eq!(clickhouse_error_code("no code"), None);
+    }
+
+    #[test]
+    fn test_error_guidance_display() {
+        let guidance = error_guidance("Code: 81. DB::Exception").unwrap();
+        assert_eq!(
+            guidance.to_string(),
+            "Code 81: the database does not exist. Check `db_name` in `clickhouse_config`, or create the database"
+        );
+    }
+}
@@ -7,6 +7,7 @@
 //! - Tracking Kafka partition offsets for each batch
 //! - Committing offsets after successful inserts
 //! - Handling transient failures during insertion
+//! - Recording the sync latency, batch size and insert duration metrics
 //!
 //! The primary components are:
 //!
@@ -31,13 +32,15 @@
 //!         // Commit the offset to Kafka
 //!         Ok(())
 //!     }),
+//!     "my_topic",
 //!     "my_table".to_string(),
+//!     None,
 //!     vec!["column1".to_string(), "column2".to_string()],
 //! );
 //!
-//! // Insert records
+//! // Insert records, with the timestamp of their Kafka message
 //! let record = ClickHouseRecord::new();
-//! inserter.insert(record, 0, 100);
+//! inserter.insert(record, 0, 100, Some(1700000000000));
 //!
 //! // Flush records to ClickHouse
 //! inserter.flush().await;
@@ -45,9 +48,11 @@
 
 use crate::infrastructure::olap::clickhouse::client::ClickHouseClientTrait;
 use crate::infrastructure::olap::clickhouse::model::ClickHouseRecord;
+use crate::infrastructure::olap::clickhouse::sync_metrics::SyncMetrics;
 use std::collections::{HashMap, VecDeque};
 
 use rdkafka::error::KafkaError;
+use tokio::time::Instant;
 use tracing::{info, warn};
 
 /// Represents a Kafka partition identifier
@@ -65,6 +70,7 @@ type PartitionSizes = HashMap<Partition, i64>;
 /// - A collection of ClickHouse records
 /// - The highest offset for each Kafka partition in the batch
 /// - The number of messages from each partition in the batch
+/// - The Kafka timestamps of its records
 #[derive(Default)]
 pub struct Batch {
     /// Collection of ClickHouse records to be inserted
@@ -73,20 +79,36 @@ pub struct Batch {
     pub partition_offsets: PartitionOffsets,
     /// Maps partitions to the number of messages in this batch
     pub messages_sizes: PartitionSizes,
+    /// Kafka timestamps of the records, in milliseconds since the epoch
+    pub kafka_timestamps: Vec<i64>,
 }
 
 impl Batch {
-    /// Updates the offset tracking for a partition.
+    /// Adds a record and updates the offset tracking for its partition.
     ///
     /// This method:
     /// 1. Updates the highest offset for the partition (if the new offset is higher)
     /// 2. Increments the message count for the partition
+    /// 3. Keeps the Kafka timestamp of the record, if it has one
     ///
     /// # Arguments
     ///
+    /// * `record` - The ClickHouse record to add
     /// * `partition` - The Kafka partition ID
     /// * `offset` - The message offset in the partition
-    fn update_offset(&mut self, partition: i32, offset: i64) {
+    /// * `kafka_timestamp` - The message timestamp in milliseconds since the epoch
+    fn push(
+        &mut self,
+        record: ClickHouseRecord,
+        partition: i32,
+        offset: i64,
+        kafka_timestamp: Option<i64>,
+    ) {
+        self.records.push(record);
+        if let Some(timestamp) = kafka_timestamp {
+            self.kafka_timestamps.push(timestamp);
+        }
+
         self.partition_offsets
             .entry(partition)
             .and_modify(|e| *e = (*e).max(offset))
@@ -169,6 +191,8 @@ pub struct Inserter<C: ClickHouseClientTrait + 'static> {
     database: Option<String>,
     /// Column names for the target table
     columns: Vec<String>,
+    /// Latency, batch size and insert duration histograms of this sync
+    metrics: SyncMetrics,
 }
 
 impl<C: ClickHouseClientTrait + 'static> Inserter<C> {
@@ -179,6 +203,7 @@ impl<C: ClickHouseClientTrait + 'static> Inserter<C> {
     /// * `client` - A ClickHouse client for performing inserts
     /// * `batch_size` - Maximum number of records in a batch
     /// * `commit_callback` - Function to call for committing offsets
+    /// * `topic` - Source Kafka topic name, used to label the metrics
     /// * `table` - Target ClickHouse table name
     /// * `database` - Optional target database name. If None, uses client's default database
     /// * `columns` - Column names for the target table
@@ -190,11 +215,13 @@ impl<C: ClickHouseClientTrait + 'static> Inserter<C> {
         client: C,
         batch_size: usize,
         commit_callback: OffsetCommitCallback,
+        topic: &str,
         table: String,
         database: Option<String>,
         columns: Vec<String>,
     ) -> Self {
         let queue = VecDeque::from([Batch::default()]);
+        let metrics = SyncMetrics::new(topic, &table);
 
         Self {
             queue,
@@ -204,6 +231,7 @@ impl<C: ClickHouseClientTrait + 'static> Inserter<C> {
             table,
             database,
             columns,
+            metrics,
         }
     }
 
@@ -235,7 +263,15 @@ impl<C: ClickHouseClientTrait + 'static> Inserter<C> {
     /// * `record` - The ClickHouse record to insert
     /// * `partition` - The Kafka partition the record came from
     /// * `offset` - The offset of the record in the Kafka partition
-    pub fn insert(&mut self, record: ClickHouseRecord, partition: i32, offset: i64) {
+    /// * `kafka_timestamp` - The timestamp of the Kafka message, in milliseconds
+    ///   since the epoch, to measure the sync latency of the record
+    pub fn insert(
+        &mut self,
+        record: ClickHouseRecord,
+        partition: i32,
+        offset: i64,
+        kafka_timestamp: Option<i64>,
+    ) {
         let current_batch = self.queue.back_mut();
 
         match current_batch {
@@ -243,18 +279,15 @@ impl<C: ClickHouseClientTrait + 'static> Inserter<C> {
                 if batch.records.len() >= self.batch_size {
                     self.queue.push_back(Batch::default());
                     let new_batch = self.queue.back_mut().unwrap();
-                    new_batch.records.push(record);
-                    new_batch.update_offset(partition, offset);
+                    new_batch.push(record, partition, offset, kafka_timestamp);
                 } else {
-                    batch.records.push(record);
-                    batch.update_offset(partition, offset);
+                    batch.push(record, partition, offset, kafka_timestamp);
                 }
             }
             None => {
                 self.queue.push_back(Batch::default());
                 let new_batch = self.queue.back_mut().unwrap();
-                new_batch.records.push(record);
-                new_batch.update_offset(partition, offset);
+                new_batch.push(record, partition, offset, kafka_timestamp);
             }
         }
     }
@@ -264,7 +297,8 @@ impl<C: ClickHouseClientTrait + 'static> Inserter<C> {
     /// This method:
     /// 1. Takes the first batch from the queue
     /// 2. Attempts to insert it into ClickHouse
-    /// 3. On success, commits offsets and removes the batch from the queue
+    /// 3. On success, records the sync metrics, commits offsets and removes the
+    ///    batch from the queue
     /// 4. On failure, logs a warning and leaves the batch in the queue for retry
     ///
     /// If the queue is empty or the first batch has no records, this method does nothing.
@@ -279,6 +313,7 @@ impl<C: ClickHouseClientTrait + 'static> Inserter<C> {
         }
 
         if let Some(batch) = self.queue.front() {
+            let started = Instant::now();
             match self
                 .client
                 .insert(
@@ -290,6 +325,12 @@ impl<C: ClickHouseClientTrait + 'static> Inserter<C> {
                 .await
             {
                 Ok(_) => {
+                    self.metrics.record_flush(
+                        batch.records.len(),
+                        &batch.kafka_timestamps,
+                        started.elapsed(),
+                        chrono::Utc::now().timestamp_millis(),
+                    );
                     info!(
                         "Batch Insert records - table='{}';insert_sizes='{}';offsets='{}'",
                         self.table,
@@ -321,6 +362,25 @@ impl<C: ClickHouseClientTrait + 'static> Inserter<C> {
             }
         }
     }
+
+    /// Flushes every batch in the queue, oldest first.
+    ///
+    /// Stops at the first failed insert, which stays queued with the batches
+    /// after it. Returns whether the queue was emptied.
+    pub async fn flush_all(&mut self) -> bool {
+        while self
+            .queue
+            .front()
+            .is_some_and(|batch| !batch.records.is_empty())
+        {
+            let queued = self.queue.len();
+            self.flush().await;
+            if self.queue.len() == queued {
+                return false;
+            }
+        }
+        true
+    }
 }
 
 #[cfg(test)]
@@ -382,6 +442,7 @@ mod tests {
             mock_client,
             1,
             Box::new(|_, _| Ok(())),
+            "test_topic",
             "test_table".to_string(),
             None, // default database
             vec!["test".to_string()],
@@ -389,7 +450,7 @@ mod tests {
 
         // Insert 2 records with batch size 1
         for i in 0..2 {
-            inserter.insert(create_test_record(i as i64), 0, i as i64);
+            inserter.insert(create_test_record(i as i64), 0, i as i64, None);
         }
 
         assert_eq!(inserter.queue.len(), 2, "Should have created two batches");
@@ -414,13 +475,14 @@ mod tests {
             mock_client,
             100,
             Box::new(|_, _| Ok(())),
+            "test_topic",
             "test_table".to_string(),
             None, // default database
             vec!["test".to_string()],
         );
 
-        inserter.insert(create_test_record(1), 0, 100);
-        inserter.insert(create_test_record(2), 1, 200);
+        inserter.insert(create_test_record(1), 0, 100, None);
+        inserter.insert(create_test_record(2), 1, 200, None);
 
         // Manually flush instead of waiting for interval
         inserter.flush().await;
@@ -443,6 +505,61 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_flush_all() {
+        let mock_client = MockClickHouseClient::new(false);
+        let insert_calls = mock_client.insert_calls.clone();
+        let committed = Arc::new(Mutex::new(Vec::new()));
+        let committed_clone = committed.clone();
+
+        let mut inserter = Inserter::new(
+            mock_client,
+            1,
+            Box::new(move |partition, offset| {
+                committed_clone.lock().unwrap().push((partition, offset));
+                Ok(())
+            }),
+            "test_topic",
+            "test_table".to_string(),
+            None,
+            vec!["test".to_string()],
+        );
+        for i in 0..3 {
+            inserter.insert(create_test_record(i), 0, i, None);
+        }
+
+        assert!(inserter.flush_all().await);
+        assert_eq!(insert_calls.load(Ordering::SeqCst), 3);
+        assert!(inserter.is_empty());
+        assert_eq!(*committed.lock().unwrap(), vec![(0, 0), (0, 1), (0, 2)]);
+
+        // Nothing queued: nothing to do
+        assert!(inserter.flush_all().await);
+        assert_eq!(insert_calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_flush_all_stops_on_failure() {
+        let mock_client = MockClickHouseClient::new(true);
+        let insert_calls = mock_client.insert_calls.clone();
+        let mut inserter = Inserter::new(
+            mock_client,
+            1,
+            Box::new(|_, _| Ok(())),
+            "test_topic",
+            "test_table".to_string(),
+            None,
+            vec!["test".to_string()],
+        );
+        for i in 0..3 {
+            inserter.insert(create_test_record(i), 0, i, None);
+        }
+
+        assert!(!inserter.flush_all().await);
+        assert_eq!(insert_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(inserter.len(), 3, "Failed batches should stay queued");
+    }
+
     #[tokio::test]
     async fn test_offset_tracking_per_partition() {
         let mock_client = MockClickHouseClient::new(false);
@@ -450,15 +567,16 @@ mod tests {
             mock_client,
             100,
             Box::new(|_, _| Ok(())),
+            "test_topic",
             "test_table".to_string(),
             None, // default database
             vec!["test".to_string()],
         );
 
-        inserter.insert(create_test_record(1), 0, 100);
-        inserter.insert(create_test_record(2), 1, 200);
-        inserter.insert(create_test_record(3), 0, 150);
-        inserter.insert(create_test_record(4), 1, 175);
+        inserter.insert(create_test_record(1), 0, 100, None);
+        inserter.insert(create_test_record(2), 1, 200, None);
+        inserter.insert(create_test_record(3), 0, 150, None);
+        inserter.insert(create_test_record(4), 1, 175, None);
 
         let batch = inserter.queue.front().unwrap();
 
@@ -474,6 +592,36 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_kafka_timestamps_tracked_per_batch() {
+        let mock_client = MockClickHouseClient::new(false);
+        let mut inserter = Inserter::new(
+            mock_client,
+            2,
+            Box::new(|_, _| Ok(())),
+            "test_topic",
+            "test_table".to_string(),
+            None,
+            vec!["test".to_string()],
+        );
+
+        inserter.insert(create_test_record(1), 0, 100, Some(1_000));
+        inserter.insert(create_test_record(2), 0, 101, None);
+        inserter.insert(create_test_record(3), 0, 102, Some(3_000));
+
+        assert_eq!(
+            inserter.queue.front().unwrap().kafka_timestamps,
+            vec![1_000]
+        );
+        assert_eq!(inserter.queue.back().unwrap().kafka_timestamps, vec![3_000]);
+
+        inserter.flush().await;
+        assert_eq!(
+            inserter.queue.front().unwrap().kafka_timestamps,
+            vec![3_000]
+        );
+    }
+
     #[tokio::test]
     async fn test_flush_with_default_database() {
         let mock_client = MockClickHouseClient::new(false);
@@ -483,12 +631,13 @@ mod tests {
             mock_client,
             100,
             Box::new(|_, _| Ok(())),
+            "test_topic",
             "test_table".to_string(),
             None, // No database specified - should use client default
             vec!["test".to_string()],
         );
 
-        inserter.insert(create_test_record(1), 0, 100);
+        inserter.insert(create_test_record(1), 0, 100, None);
         inserter.flush().await;
 
         // Verify None was passed to the client (meaning use default)
@@ -509,12 +658,13 @@ mod tests {
             mock_client,
             100,
             Box::new(|_, _| Ok(())),
+            "test_topic",
             "test_table".to_string(),
             Some("custom_db".to_string()), // Custom database
             vec!["test".to_string()],
         );
 
-        inserter.insert(create_test_record(1), 0, 100);
+        inserter.insert(create_test_record(1), 0, 100, None);
         inserter.flush().await;
 
         // Verify the custom database was passed
@@ -1,54 +1,96 @@
 use crate::framework::core::infrastructure::table::{
-    Column, ColumnMetadata, ColumnType, DataEnum, EnumMemberMetadata, EnumMetadata, EnumValue,
-    EnumValueMetadata, FloatType, IntType, JsonOptions, Table, METADATA_PREFIX, METADATA_VERSION,
+    split_column_comment, Column, ColumnMetadata, ColumnType, DataEnum, EnumMemberMetadata,
+    EnumMetadata, EnumValue, EnumValueMetadata, FloatType, IntType, JsonOptions,
+    NestedFieldMetadata, OrderBy, Table, MAX_METADATA_COMMENT_LEN, METADATA_PREFIX,
+    METADATA_VERSION,
 };
 use serde_json::Value;
+use tracing::debug;
 
 use crate::infrastructure::olap::clickhouse::model::{
     AggregationFunction, ClickHouseColumn, ClickHouseColumnType, ClickHouseFloat, ClickHouseIndex,
     ClickHouseInt, ClickHouseProjection, ClickHouseTable, DefaultExpressionKind,
 };
 
+use super::diff_strategy::expression_references_column;
 use super::errors::ClickhouseError;
 
-/// Generates a column comment, preserving any existing user comment and adding/updating metadata for enums
+/// Generates a column comment, preserving any existing user comment and adding/updating metadata
+/// for enums and the comments of nested fields
 fn generate_column_comment(column: &Column) -> Result<Option<String>, ClickhouseError> {
-    if let ColumnType::Enum(ref data_enum) = column.data_type {
-        let metadata_comment = build_enum_metadata_comment(data_enum)?;
-
-        // Extract user comment from existing comment (if any)
-        // The existing comment might be:
-        // 1. Just a user comment
-        // 2. Just metadata (starts with METADATA_PREFIX)
-        // 3. User comment + metadata
-        let user_comment = match &column.comment {
-            Some(existing) => {
-                if let Some(metadata_pos) = existing.find(METADATA_PREFIX) {
-                    // Has metadata - extract the user comment part before it
-                    let user_part = existing[..metadata_pos].trim();
-                    if !user_part.is_empty() {
-                        Some(user_part.to_string())
-                    } else {
-                        None
-                    }
-                } else if !existing.is_empty() {
-                    // No metadata, entire comment is user comment
-                    Some(existing.clone())
-                } else {
-                    None
-                }
+    // The existing comment might be:
+    // 1. Just a user comment
+    // 2. Just metadata (starts with METADATA_PREFIX)
+    // 3. User comment + metadata
+    let user_comment = column
+        .comment
+        .as_deref()
+        .map(|existing| split_column_comment(existing).0)
+        .filter(|user_text| !user_text.trim().is_empty());
+
+    // Metadata is read back from the end of the comment, a user comment holding
+    // the prefix would be mistaken for it
+    if user_comment.is_some_and(|user_text| user_text.contains(METADATA_PREFIX)) {
+        return Err(ClickhouseError::InvalidParameters {
+            message: format!(
+                "The comment of column '{}' contains '{}', which is reserved for the metadata Moose stores in column comments. Remove it from the comment.",
+                column.name,
+                METADATA_PREFIX.trim_end()
+            ),
+        });
+    }
+
+    let fields = column.data_type.nested_field_comments();
+    let metadata_comment = match &column.data_type {
+        ColumnType::Enum(data_enum) => {
+            let metadata_comment = build_metadata_comment(enum_metadata(data_enum), fields)?;
+            if metadata_comment.len() > MAX_METADATA_COMMENT_LEN {
+                return Err(ClickhouseError::InvalidParameters {
+                    message: format!(
+                        "The metadata of enum '{}' in column '{}' takes {} bytes, more than the {MAX_METADATA_COMMENT_LEN} bytes allowed in a column comment. \
+                         Store the column as a String (or LowCardinality(String)) instead, or split the enum into smaller Enum8/Enum16 enums.",
+                        data_enum.name,
+                        column.name,
+                        metadata_comment.len()
+                    ),
+                });
             }
-            None => None,
-        };
+            metadata_comment
+        }
+        _ if !fields.is_empty() => {
+            let metadata_comment = build_metadata_comment(None, fields)?;
+            if metadata_comment.len() > MAX_METADATA_COMMENT_LEN {
+                return Err(ClickhouseError::InvalidParameters {
+                    message: format!(
+                        "The comments of the nested fields of column '{}' take {} bytes, more than the {MAX_METADATA_COMMENT_LEN} bytes allowed in a column comment. Shorten them.",
+                        column.name,
+                        metadata_comment.len()
+                    ),
+                });
+            }
+            metadata_comment
+        }
+        // Pass through the user comment for columns without metadata
+        _ => return Ok(user_comment.map(str::to_string)),
+    };
 
-        // Combine user comment with new metadata
-        Ok(match user_comment {
-            Some(user_text) => Some(format!("{user_text} {metadata_comment}")),
-            None => Some(metadata_comment),
-        })
-    } else {
-        Ok(column.comment.clone()) // Pass through any existing comment for non-enum types
+    // Combine user comment with new metadata
+    Ok(match user_comment {
+        Some(user_text) => Some(format!("{} {metadata_comment}", user_text.trim())),
+        None => Some(metadata_comment),
+    })
+}
+
+/// Checks the comments of `columns`, including nested columns, so that the
+/// comments that cannot be stored fail the plan rather than its execution.
+pub fn validate_column_comments(columns: &[Column]) -> Result<(), ClickhouseError> {
+    for column in columns {
+        generate_column_comment(column)?;
+        if let ColumnType::Nested(nested) = &column.data_type {
+            validate_column_comments(&nested.columns)?;
+        }
     }
+    Ok(())
 }
 
 pub fn std_column_to_clickhouse_column(
@@ -125,27 +167,39 @@ pub fn std_column_to_clickhouse_column(
 }
 
 pub fn build_enum_metadata_comment(data_enum: &DataEnum) -> Result<String, ClickhouseError> {
+    build_metadata_comment(enum_metadata(data_enum), Vec::new())
+}
+
+fn enum_metadata(data_enum: &DataEnum) -> Option<EnumMetadata> {
+    Some(EnumMetadata {
+        name: data_enum.name.clone(),
+        members: data_enum
+            .values
+            .iter()
+            .map(|m| EnumMemberMetadata {
+                name: m.name.clone(),
+                value: match &m.value {
+                    EnumValue::String(s) => EnumValueMetadata::String(s.clone()),
+                    EnumValue::Int(i) => EnumValueMetadata::Int(*i),
+                },
+            })
+            .collect(),
+    })
+}
+
+fn build_metadata_comment(
+    enum_def: Option<EnumMetadata>,
+    fields: Vec<NestedFieldMetadata>,
+) -> Result<String, ClickhouseError> {
     let metadata = ColumnMetadata {
         version: METADATA_VERSION,
-        enum_def: EnumMetadata {
-            name: data_enum.name.clone(),
-            members: data_enum
-                .values
-                .iter()
-                .map(|m| EnumMemberMetadata {
-                    name: m.name.clone(),
-                    value: match &m.value {
-                        EnumValue::String(s) => EnumValueMetadata::String(s.clone()),
-                        EnumValue::Int(i) => EnumValueMetadata::Int(*i),
-                    },
-                })
-                .collect(),
-        },
+        enum_def,
+        fields,
     };
 
     let json =
         serde_json::to_string(&metadata).map_err(|e| ClickhouseError::InvalidParameters {
-            message: format!("Failed to serialize enum metadata: {e}"),
+            message: format!("Failed to serialize column metadata: {e}"),
         })?;
     Ok(format!("{METADATA_PREFIX}{json}"))
 }
@@ -360,7 +414,83 @@ pub fn std_columns_to_clickhouse_columns(
         .collect()
 }
 
+/// Whether `column` is created as Nullable, see [`std_column_to_clickhouse_column`]
+fn is_nullable_column(column: &Column) -> bool {
+    match &column.data_type {
+        ColumnType::Nullable(_) => true,
+        ColumnType::Array { .. } | ColumnType::Nested(_) => false,
+        _ => !column.required,
+    }
+}
+
+/// Whether the settings of `table` set `allow_nullable_key`
+pub fn allows_nullable_key(table: &Table) -> bool {
+    table
+        .table_settings
+        .as_ref()
+        .and_then(|settings| settings.get("allow_nullable_key"))
+        .is_some_and(|value| matches!(value.trim(), "1" | "true"))
+}
+
+/// Nullable columns in the ORDER BY or PRIMARY KEY of a MergeTree `table`.
+///
+/// An entry that is not a column, like `cityHash64(user_id)`, stands for the
+/// columns it references. Entries referencing no column are skipped.
+pub fn nullable_key_columns(table: &Table) -> Vec<String> {
+    if !table.engine.is_merge_tree_family() {
+        return Vec::new();
+    }
+
+    let mut key_entries: Vec<&str> = match &table.order_by {
+        OrderBy::Fields(fields) => fields.iter().map(String::as_str).collect(),
+        OrderBy::SingleExpr(expr) => vec![expr.as_str()],
+    };
+    key_entries.extend(table.primary_key_expression.as_deref());
+
+    let mut nullable: Vec<String> = Vec::new();
+    for entry in key_entries {
+        let referenced: Vec<&Column> = match table.columns.iter().find(|c| c.name == entry) {
+            Some(column) => vec![column],
+            None => table
+                .columns
+                .iter()
+                .filter(|c| expression_references_column(entry, &c.name))
+                .collect(),
+        };
+        if referenced.is_empty() {
+            debug!(
+                "Skipping the nullability check of key expression '{entry}' of table {}, it references no column",
+                table.name
+            );
+        }
+        for column in referenced {
+            if is_nullable_column(column) && !nullable.contains(&column.name) {
+                nullable.push(column.name.clone());
+            }
+        }
+    }
+    nullable
+}
+
+/// Fails when the keys of `table` hold Nullable columns, which ClickHouse only
+/// accepts with `allow_nullable_key = 1` and otherwise rejects at CREATE TABLE.
+pub fn validate_key_nullability(table: &Table) -> Result<(), ClickhouseError> {
+    let columns = nullable_key_columns(table);
+    if columns.is_empty() || allows_nullable_key(table) {
+        return Ok(());
+    }
+    Err(ClickhouseError::InvalidParameters {
+        message: format!(
+            "Nullable column(s) {} of table '{}' are in its ORDER BY or PRIMARY KEY, which ClickHouse rejects (ILLEGAL_COLUMN). \
+             Make them required, or add allow_nullable_key = 1 to the table settings",
+            columns.join(", "),
+            table.name
+        ),
+    })
+}
+
 pub fn std_table_to_clickhouse_table(table: &Table) -> Result<ClickHouseTable, ClickhouseError> {
+    validate_key_nullability(table)?;
     let columns = std_columns_to_clickhouse_columns(&table.columns)?;
 
     let clickhouse_engine = table.engine.clone();
@@ -438,12 +568,13 @@ mod tests {
 
         // Verify the metadata
         assert_eq!(metadata.version, METADATA_VERSION);
-        assert_eq!(metadata.enum_def.name, "RecordType");
-        assert_eq!(metadata.enum_def.members.len(), 3);
+        let enum_metadata = metadata.enum_def.unwrap();
+        assert_eq!(enum_metadata.name, "RecordType");
+        assert_eq!(enum_metadata.members.len(), 3);
 
         // Verify first member
-        assert_eq!(metadata.enum_def.members[0].name, "TEXT");
-        match &metadata.enum_def.members[0].value {
+        assert_eq!(enum_metadata.members[0].name, "TEXT");
+        match &enum_metadata.members[0].value {
             EnumValueMetadata::String(s) => assert_eq!(s, "text"),
             _ => panic!("Expected string value"),
         }
@@ -514,7 +645,7 @@ mod tests {
         let metadata_start = comment.find(METADATA_PREFIX).unwrap();
         let json_str = &comment[metadata_start + METADATA_PREFIX.len()..];
         let metadata: ColumnMetadata = serde_json::from_str(json_str.trim()).unwrap();
-        assert_eq!(metadata.enum_def.name, "RecordType"); // New enum name, not "OldEnum"
+        assert_eq!(metadata.enum_def.unwrap().name, "RecordType"); // New enum name, not "OldEnum"
 
         // Test 3: Existing metadata only (no user comment)
         let column_metadata_only = Column {
@@ -539,7 +670,7 @@ mod tests {
         assert!(comment.starts_with(METADATA_PREFIX));
         let metadata: ColumnMetadata =
             serde_json::from_str(comment.strip_prefix(METADATA_PREFIX).unwrap().trim()).unwrap();
-        assert_eq!(metadata.enum_def.name, "RecordType");
+        assert_eq!(metadata.enum_def.unwrap().name, "RecordType");
     }
 
     #[test]
@@ -616,6 +747,163 @@ mod tests {
         }
     }
 
+    fn enum_column(data_enum: DataEnum, comment: Option<&str>) -> Column {
+        Column {
+            name: "record_type".to_string(),
+            data_type: ColumnType::Enum(data_enum),
+            required: true,
+            unique: false,
+            primary_key: false,
+            default: None,
+            annotations: vec![],
+            comment: comment.map(str::to_string),
+            ttl: None,
+            codec: None,
+            materialized: None,
+            alias: None,
+        }
+    }
+
+    fn enum_with_members(count: usize, name_len: usize) -> DataEnum {
+        DataEnum {
+            name: "Large".to_string(),
+            values: (0..count)
+                .map(|i| EnumMember {
+                    name: format!("{i:0>name_len$}"),
+                    value: EnumValue::String(format!("value_{i}")),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_comment_roundtrip_with_adversarial_user_comments() {
+        let enum_def = DataEnum {
+            name: "RecordType".to_string(),
+            values: vec![EnumMember {
+                name: "TEXT".to_string(),
+                value: EnumValue::String("text".to_string()),
+            }],
+        };
+        let user_comments = [
+            "First line\nsecond line",
+            "Ends with a newline\n",
+            "Mentions [MOOSE_METADATA but not the full prefix",
+            "JSON-looking text {\"version\":1}",
+            "Brackets [like] {these}",
+        ];
+
+        for user_comment in user_comments {
+            let comment =
+                std_column_to_clickhouse_column(enum_column(enum_def.clone(), Some(user_comment)))
+                    .unwrap()
+                    .comment
+                    .unwrap();
+
+            let (user_part, metadata) = split_column_comment(&comment);
+            assert_eq!(user_part, user_comment.trim());
+            assert_eq!(metadata.unwrap().enum_def.unwrap().name, "RecordType");
+
+            // Generating the comment again from what was read back is stable
+            let regenerated =
+                std_column_to_clickhouse_column(enum_column(enum_def.clone(), Some(&comment)))
+                    .unwrap()
+                    .comment
+                    .unwrap();
+            assert_eq!(regenerated, comment);
+        }
+
+        // Metadata preceded by a newline and followed by trailing whitespace
+        let metadata = build_enum_metadata_comment(&enum_def).unwrap();
+        let (user_part, parsed) =
+            split_column_comment(&format!("Line one\nline two\n{metadata}\n"));
+        assert_eq!(user_part, "Line one\nline two");
+        assert_eq!(parsed.unwrap().enum_def.unwrap().name, "RecordType");
+
+        // Only the prefix followed by metadata up to the end starts the metadata
+        let (user_part, parsed) =
+            split_column_comment(&format!("{METADATA_PREFIX}not json {metadata}"));
+        assert_eq!(user_part, format!("{METADATA_PREFIX}not json"));
+        assert!(parsed.is_some());
+
+        let (user_part, parsed) = split_column_comment("Only a user comment\n");
+        assert_eq!(user_part, "Only a user comment\n");
+        assert!(parsed.is_none());
+    }
+
+    #[test]
+    fn test_user_comment_containing_metadata_prefix_is_rejected() {
+        let enum_def = enum_with_members(1, 4);
+        let comment = format!("See {METADATA_PREFIX}for details");
+
+        let mut string_column = enum_column(enum_def.clone(), Some(&comment));
+        string_column.data_type = ColumnType::String;
+
+        for column in [enum_column(enum_def, Some(&comment)), string_column] {
+            match std_column_to_clickhouse_column(column) {
+                Err(ClickhouseError::InvalidParameters { message }) => {
+                    assert!(message.contains("record_type"));
+                    assert!(message.contains("reserved"));
+                }
+                other => panic!("Expected InvalidParameters, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_enum_metadata_roundtrip() {
+        let enum_def = enum_with_members(300, 8);
+        let comment = std_column_to_clickhouse_column(enum_column(
+            enum_def.clone(),
+            Some("Three hundred members"),
+        ))
+        .unwrap()
+        .comment
+        .unwrap();
+
+        let (user_part, metadata) = split_column_comment(&comment);
+        assert_eq!(user_part, "Three hundred members");
+        let members = metadata.unwrap().enum_def.unwrap().members;
+        assert_eq!(members.len(), 300);
+        assert_eq!(members[299].name, "00000299");
+        assert_eq!(
+            members[299].value,
+            EnumValueMetadata::String("value_299".to_string())
+        );
+    }
+
+    #[test]
+    fn test_oversized_enum_metadata_is_rejected() {
+        let columns = vec![Column {
+            name: "details".to_string(),
+            data_type: ColumnType::Nested(Nested {
+                name: "Details".to_string(),
+                columns: vec![enum_column(enum_with_members(300, 128), None)],
+                jwt: false,
+            }),
+            required: true,
+            unique: false,
+            primary_key: false,
+            default: None,
+            annotations: vec![],
+            comment: None,
+            ttl: None,
+            codec: None,
+            materialized: None,
+            alias: None,
+        }];
+
+        match validate_column_comments(&columns) {
+            Err(ClickhouseError::InvalidParameters { message }) => {
+                assert!(message.contains("enum 'Large' in column 'record_type'"));
+                assert!(message.contains("String"));
+                assert!(message.contains("Enum16"));
+            }
+            other => panic!("Expected InvalidParameters, got {other:?}"),
+        }
+        assert!(validate_column_comments(&[enum_column(enum_with_members(300, 8), None)]).is_ok());
+    }
+
     #[test]
     fn test_enum_metadata_with_int_values() {
         // Create a test enum with integer values
@@ -641,11 +929,12 @@ mod tests {
         let metadata: ColumnMetadata = serde_json::from_str(json_str).unwrap();
 
         // Verify the metadata
-        assert_eq!(metadata.enum_def.name, "Status");
-        assert_eq!(metadata.enum_def.members.len(), 2);
+        let enum_metadata = metadata.enum_def.unwrap();
+        assert_eq!(enum_metadata.name, "Status");
+        assert_eq!(enum_metadata.members.len(), 2);
 
         // Verify integer values
-        match &metadata.enum_def.members[0].value {
+        match &enum_metadata.members[0].value {
             EnumValueMetadata::Int(i) => assert_eq!(*i, 1),
             _ => panic!("Expected int value"),
         }
@@ -835,6 +1124,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let ch_table = std_table_to_clickhouse_table(&table).unwrap();
@@ -890,6 +1180,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let ch_table = std_table_to_clickhouse_table(&table).unwrap();
@@ -32,9 +32,14 @@
 
 use clickhouse::Client;
 
-use errors::{validate_clickhouse_identifier, ClickhouseError};
+use cluster_health::{ClusterHealthError, DdlHostStatus};
+use copy_verification::CopyVerificationError;
+use errors::{error_guidance, validate_clickhouse_identifier, ClickhouseError};
 use mapper::{std_column_to_clickhouse_column, std_table_to_clickhouse_table};
-use model::{ClickHouseColumn, ColumnPropertyRemovals, DefaultExpressionKind};
+use model::{
+    on_cluster_clause, quote_ident, quote_qualified, quote_string, ClickHouseColumn,
+    ColumnPropertyRemovals, DefaultExpressionKind,
+};
 use queries::ClickhouseEngine;
 use queries::{
     alter_table_modify_settings_query, alter_table_reset_settings_query,
@@ -42,23 +47,28 @@ use queries::{
 };
 use serde::{Deserialize, Serialize};
 use sql_parser::{
-    extract_engine_from_create_table, extract_indexes_from_create_table,
-    extract_primary_key_from_create_table, extract_projections_from_create_table,
-    extract_sample_by_from_create_table, extract_source_tables_from_query,
-    extract_source_tables_from_query_regex, extract_table_settings_from_create_table,
-    normalize_sql_for_comparison, split_qualified_name,
+    extract_dictionary_source_tables, extract_engine_from_create_table,
+    extract_indexes_from_create_table, extract_primary_key_from_create_table,
+    extract_projections_from_create_table, extract_sample_by_from_create_table,
+    extract_source_tables_from_query, extract_source_tables_from_query_regex,
+    extract_table_settings_from_create_table, normalize_sql_for_comparison, split_qualified_name,
+    TableReference,
 };
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::LazyLock;
+use std::time::Instant;
 use tracing::{debug, info, instrument, warn};
+use writability::WritabilityError;
 
 use crate::cli::logger::{context, resource_type};
 
 use self::model::ClickHouseSystemTable;
 use crate::framework::core::infrastructure::sql_resource::SqlResource;
 use crate::framework::core::infrastructure::table::{
-    Column, ColumnMetadata, ColumnType, DataEnum, EnumMember, EnumValue, EnumValueMetadata,
-    OrderBy, Table, TableIndex, TableProjection, METADATA_PREFIX,
+    split_column_comment, Column, ColumnMetadata, ColumnType, DataEnum, EnumMember, EnumValue,
+    EnumValueMetadata, OrderBy, Table, TableIndex, TableProjection, METADATA_PREFIX,
 };
 use crate::framework::core::infrastructure::InfrastructureSignature;
 use crate::framework::core::infrastructure_map::{PrimitiveSignature, PrimitiveTypes};
@@ -66,25 +76,35 @@ use crate::framework::core::partial_infrastructure_map::LifeCycle;
 use crate::framework::versions::Version;
 use crate::infrastructure::olap::clickhouse::model::ClickHouseSystemTableRow;
 use crate::infrastructure::olap::{OlapChangesError, OlapOperations};
+use crate::project::config_validation::closest_key;
 use crate::project::Project;
+use crate::utilities::retry::{self, RetryPolicy, RetryTarget};
 
 pub mod client;
+pub mod cluster_health;
 pub mod config;
 pub mod config_resolver;
+pub mod copy_verification;
+pub mod credentials;
 pub mod diagnostics;
 pub mod diff_strategy;
 pub mod errors;
 pub mod inserter;
 pub mod mapper;
+pub mod migration_metrics;
 pub mod model;
 pub mod queries;
 pub mod remote;
 pub mod sql_parser;
+pub mod sync_metrics;
 pub mod type_parser;
+pub mod writability;
 
 pub use config::ClickHouseConfig;
 
-use super::ddl_ordering::AtomicOlapOperation;
+use super::ddl_execution::execute_concurrently;
+use super::ddl_ordering::{execution_dependencies, AtomicOlapOperation};
+use super::ddl_progress::{DdlProgress, DdlProgressEvent, NoProgress};
 
 /// Type alias for query strings to improve readability
 pub type QueryString = String;
@@ -97,7 +117,11 @@ pub enum ClickhouseChangesError {
     Clickhouse(#[from] ClickhouseError),
 
     /// Error from the ClickHouse client library
-    #[error("Error interacting with Clickhouse{}", .resource.as_ref().map(|t| format!(" for '{t}'")).unwrap_or_default())]
+    #[error(
+        "Error interacting with Clickhouse{}{}",
+        .resource.as_ref().map(|t| format!(" for '{t}'")).unwrap_or_default(),
+        error_guidance(&.error.to_string()).map(|g| format!(" ({g})")).unwrap_or_default()
+    )]
     ClickhouseClient {
         #[source]
         error: clickhouse::error::Error,
@@ -107,6 +131,39 @@ pub enum ClickhouseChangesError {
     /// Error for unsupported operations
     #[error("Not Supported {0}")]
     NotSupported(String),
+
+    /// Several operations of a plan failed
+    #[error(
+        "{} operations failed: {}",
+        .0.len(),
+        .0.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ")
+    )]
+    OperationsFailed(Vec<ClickhouseChangesError>),
+
+    /// ClickHouse cannot accept DDL, no operation was run
+    #[error(transparent)]
+    NotWritable(#[from] WritabilityError),
+
+    /// A `VerifyCopy` operation found rows missing from a copy
+    #[error(transparent)]
+    CopyVerification(#[from] CopyVerificationError),
+
+    /// Hosts of a cluster the plan runs DDL on are down, no operation was run
+    #[error(transparent)]
+    ClusterUnhealthy(#[from] ClusterHealthError),
+
+    /// An `ON CLUSTER` statement timed out waiting for hosts of the cluster
+    #[error(
+        "Timed out waiting for the hosts of cluster '{cluster}' to run the DDL on '{resource}': {}",
+        .hosts.iter().map(|h| h.to_string()).collect::<Vec<_>>().join(", ")
+    )]
+    DistributedDdlTimeout {
+        #[source]
+        error: clickhouse::error::Error,
+        cluster: String,
+        resource: String,
+        hosts: Vec<DdlHostStatus>,
+    },
 }
 
 /// Represents atomic DDL operations for OLAP resources.
@@ -128,6 +185,17 @@ pub enum SerializableOlapOperation {
         /// Optional cluster name for ON CLUSTER support
         cluster_name: Option<String>,
     },
+    /// Rename an existing table
+    RenameTable {
+        /// The table to rename
+        table: String,
+        /// Name the table is renamed to
+        new_name: String,
+        /// The database containing the table (None means use primary database)
+        database: Option<String>,
+        /// Optional cluster name for ON CLUSTER support
+        cluster_name: Option<String>,
+    },
     /// Add a column to a table
     AddTableColumn {
         /// The table to add the column to
@@ -249,6 +317,16 @@ pub enum SerializableOlapOperation {
         /// Optional cluster name for ON CLUSTER support
         cluster_name: Option<String>,
     },
+    /// Extend the sorting key of a MergeTree-family table with columns added
+    /// right before, in the same `ALTER TABLE` statement.
+    ModifyOrderBy {
+        table: String,
+        expression: String,
+        /// The database containing the table (None means use primary database)
+        database: Option<String>,
+        /// Optional cluster name for ON CLUSTER support
+        cluster_name: Option<String>,
+    },
     /// Create a materialized view
     CreateMaterializedView {
         /// Name of the materialized view
@@ -290,24 +368,184 @@ pub enum SerializableOlapOperation {
         sql: Vec<String>,
         description: String,
     },
+    /// Compare a table with the table its rows were copied from, e.g. by a
+    /// `RawSql` backfill, failing when they diverge
+    VerifyCopy {
+        /// The table the rows were copied into
+        table: String,
+        /// Database of `table` (None = default database)
+        database: Option<String>,
+        /// The table the rows were copied from
+        source_table: String,
+        /// Database of `source_table` (None = default database)
+        source_database: Option<String>,
+        /// Columns the checksum covers, all of them when empty
+        #[serde(default)]
+        columns: Vec<String>,
+        /// Rows the copy may miss or add. With a tolerance, the checksums
+        /// are not compared.
+        #[serde(default)]
+        tolerance: u64,
+    },
+}
+
+impl SerializableOlapOperation {
+    /// The variant name, such as `DropTable`
+    pub fn kind(&self) -> String {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(tagged)) => tagged.keys().next().cloned(),
+            _ => None,
+        }
+        .unwrap_or_else(|| "Unknown".to_string())
+    }
+
+    /// The `database.name` (or `name`) of the table or view the operation
+    /// targets, none for raw SQL
+    pub fn target(&self) -> Option<String> {
+        let (name, database) = self.target_table()?;
+        Some(match database {
+            Some(database) => format!("{}.{}", database, name),
+            None => name.clone(),
+        })
+    }
+
+    /// The name and database of the table or view the operation targets,
+    /// none for raw SQL
+    pub fn target_table(&self) -> Option<(&String, &Option<String>)> {
+        Some(match self {
+            SerializableOlapOperation::CreateTable { table } => (&table.name, &table.database),
+            SerializableOlapOperation::DropTable {
+                table, database, ..
+            }
+            | SerializableOlapOperation::RenameTable {
+                table, database, ..
+            }
+            | SerializableOlapOperation::AddTableColumn {
+                table, database, ..
+            }
+            | SerializableOlapOperation::DropTableColumn {
+                table, database, ..
+            }
+            | SerializableOlapOperation::ModifyTableColumn {
+                table, database, ..
+            }
+            | SerializableOlapOperation::RenameTableColumn {
+                table, database, ..
+            }
+            | SerializableOlapOperation::ModifyTableSettings {
+                table, database, ..
+            }
+            | SerializableOlapOperation::ModifyTableTtl {
+                table, database, ..
+            }
+            | SerializableOlapOperation::AddTableIndex {
+                table, database, ..
+            }
+            | SerializableOlapOperation::DropTableIndex {
+                table, database, ..
+            }
+            | SerializableOlapOperation::AddTableProjection {
+                table, database, ..
+            }
+            | SerializableOlapOperation::DropTableProjection {
+                table, database, ..
+            }
+            | SerializableOlapOperation::ModifySampleBy {
+                table, database, ..
+            }
+            | SerializableOlapOperation::RemoveSampleBy {
+                table, database, ..
+            }
+            | SerializableOlapOperation::ModifyOrderBy {
+                table, database, ..
+            }
+            | SerializableOlapOperation::VerifyCopy {
+                table, database, ..
+            } => (table, database),
+            SerializableOlapOperation::CreateMaterializedView { name, database, .. }
+            | SerializableOlapOperation::DropMaterializedView { name, database }
+            | SerializableOlapOperation::CreateView { name, database, .. }
+            | SerializableOlapOperation::DropView { name, database } => (name, database),
+            SerializableOlapOperation::RawSql { .. } => return None,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", try_from = "String")]
 pub enum IgnorableOperation {
     ModifyTableTtl,
     ModifyColumnTtl,
     ModifyPartitionBy,
     IgnoreStringLowCardinalityDifferences,
+    ModifyTableSettings,
+    AddTableIndex,
+    DropTableIndex,
+}
+
+impl std::str::FromStr for IgnorableOperation {
+    type Err = String;
+
+    /// Parses the name used in `ignore_operations` of the migration config
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let names: Vec<String> = Self::ALL.iter().map(Self::name).collect();
+        match names.iter().position(|name| name == s) {
+            Some(position) => Ok(Self::ALL[position]),
+            None => {
+                let suggestion = closest_key(s, names.iter())
+                    .map(|name| format!("did you mean '{name}'? "))
+                    .unwrap_or_default();
+                Err(format!(
+                    "unknown operation '{s}', {suggestion}expected one of {}",
+                    names.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for IgnorableOperation {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
 }
 
 impl IgnorableOperation {
+    const ALL: [Self; 7] = [
+        Self::ModifyTableTtl,
+        Self::ModifyColumnTtl,
+        Self::ModifyPartitionBy,
+        Self::IgnoreStringLowCardinalityDifferences,
+        Self::ModifyTableSettings,
+        Self::AddTableIndex,
+        Self::DropTableIndex,
+    ];
+
+    /// The name used in `ignore_operations` of the migration config
+    fn name(&self) -> String {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(name)) => name,
+            _ => format!("{self:?}"),
+        }
+    }
+
     pub fn matches(&self, op: &SerializableOlapOperation) -> bool {
         matches!(
             (self, op),
             (
                 Self::ModifyTableTtl,
                 SerializableOlapOperation::ModifyTableTtl { .. }
+            ) | (
+                Self::ModifyTableSettings,
+                SerializableOlapOperation::ModifyTableSettings { .. }
+            ) | (
+                Self::AddTableIndex,
+                SerializableOlapOperation::AddTableIndex { .. }
+            ) | (
+                Self::DropTableIndex,
+                SerializableOlapOperation::DropTableIndex { .. }
             )
         )
     }
@@ -351,6 +589,20 @@ pub fn normalize_table_for_diff(table: &Table, ignore_ops: &[IgnorableOperation]
         }
     }
 
+    // Strip table settings if ignored
+    if ignore_ops.contains(&IgnorableOperation::ModifyTableSettings) {
+        normalized.table_settings = None;
+        normalized.table_settings_hash = None;
+    }
+
+    // Strip indexes when neither adding nor dropping them is diffed, see
+    // `revert_ignored_changes` for ignoring only one of them
+    if ignore_ops.contains(&IgnorableOperation::AddTableIndex)
+        && ignore_ops.contains(&IgnorableOperation::DropTableIndex)
+    {
+        normalized.indexes = vec![];
+    }
+
     // Strip LowCardinality annotations if ignored (only for String-typed columns)
     if ignore_ops.contains(&IgnorableOperation::IgnoreStringLowCardinalityDifferences) {
         for column in &mut normalized.columns {
@@ -365,6 +617,51 @@ pub fn normalize_table_for_diff(table: &Table, ignore_ops: &[IgnorableOperation]
     normalized
 }
 
+/// Returns `after` with the settings and indexes changes from `before` that
+/// `ignore_ops` leaves out reverted, so that diffing `before` with it
+/// generates none of the ignored operations.
+///
+/// With `DropTableIndex` ignored, the indexes only `before` has are kept;
+/// with `AddTableIndex` ignored, those only `after` has are left out. An index
+/// changed in place, dropped then added again, keeps its `before` definition
+/// when either is ignored.
+pub fn revert_ignored_changes(
+    before: &Table,
+    after: &Table,
+    ignore_ops: &[IgnorableOperation],
+) -> Table {
+    let mut reverted = after.clone();
+
+    if ignore_ops.contains(&IgnorableOperation::ModifyTableSettings) {
+        reverted.table_settings = before.table_settings.clone();
+        reverted.table_settings_hash = before.table_settings_hash.clone();
+    }
+
+    let ignore_add = ignore_ops.contains(&IgnorableOperation::AddTableIndex);
+    let ignore_drop = ignore_ops.contains(&IgnorableOperation::DropTableIndex);
+    if ignore_add || ignore_drop {
+        // In the order of `before`, so that kept indexes compare equal
+        let mut indexes: Vec<TableIndex> = before
+            .indexes
+            .iter()
+            .filter(|index| ignore_drop || after.indexes.iter().any(|a| a.name == index.name))
+            .cloned()
+            .collect();
+        if !ignore_add {
+            indexes.extend(
+                after
+                    .indexes
+                    .iter()
+                    .filter(|index| !before.indexes.iter().any(|b| b.name == index.name))
+                    .cloned(),
+            );
+        }
+        reverted.indexes = indexes;
+    }
+
+    reverted
+}
+
 /// Extracts the cluster name from an atomic OLAP operation, if present.
 fn extract_cluster_name(op: &AtomicOlapOperation) -> Option<&str> {
     match op {
@@ -380,7 +677,8 @@ fn extract_cluster_name(op: &AtomicOlapOperation) -> Option<&str> {
         | AtomicOlapOperation::AddTableProjection { table, .. }
         | AtomicOlapOperation::DropTableProjection { table, .. }
         | AtomicOlapOperation::ModifySampleBy { table, .. }
-        | AtomicOlapOperation::RemoveSampleBy { table, .. } => table.cluster_name.as_deref(),
+        | AtomicOlapOperation::RemoveSampleBy { table, .. }
+        | AtomicOlapOperation::ModifyOrderBy { table, .. } => table.cluster_name.as_deref(),
         AtomicOlapOperation::PopulateMaterializedView { .. }
         | AtomicOlapOperation::CreateDmv1View { .. }
         | AtomicOlapOperation::DropDmv1View { .. }
@@ -400,6 +698,7 @@ fn extract_cluster_name(op: &AtomicOlapOperation) -> Option<&str> {
 /// * `project` - The Project configuration containing ClickHouse connection details
 /// * `teardown_plan` - A slice of AtomicOlapOperation representing the teardown plan
 /// * `setup_plan` - A slice of AtomicOlapOperation representing the setup plan
+/// * `progress` - Receives the progress of the plans as operations start and end
 ///
 /// # Returns
 ///
@@ -416,6 +715,9 @@ fn extract_cluster_name(op: &AtomicOlapOperation) -> Option<&str> {
 ///
 /// Will retry certain operations that return specific ClickHouse error codes indicating retry is possible.
 ///
+/// Sets the migration plan duration and operation count gauges once the plan
+/// has run, whether it succeeded or not.
+///
 /// # Example
 /// ```rust
 /// let changes = vec![OlapChange::Table(TableChange::Added(table))];
@@ -425,9 +727,15 @@ pub async fn execute_changes(
     project: &Project,
     teardown_plan: &[AtomicOlapOperation],
     setup_plan: &[AtomicOlapOperation],
+    progress: &dyn DdlProgress,
 ) -> Result<(), ClickhouseChangesError> {
     // Setup the client
-    let client = create_client(project.clickhouse_config.clone());
+    let mut client = create_client(project.clickhouse_config.clone());
+    if let Some(timeout) = project.migration_config.distributed_ddl_timeout_seconds {
+        client.client = client
+            .client
+            .with_option("distributed_ddl_task_timeout", timeout.to_string());
+    }
     check_ready(&client)
         .await
         .map_err(|e| ClickhouseChangesError::ClickhouseClient {
@@ -435,7 +743,70 @@ pub async fn execute_changes(
             resource: None,
         })?;
 
-    let db_name = &project.clickhouse_config.db_name;
+    // Abort before the first operation rather than failing each one on a readonly server
+    let operations: Vec<SerializableOlapOperation> = teardown_plan
+        .iter()
+        .chain(setup_plan)
+        .map(AtomicOlapOperation::to_minimal)
+        .collect();
+    let tables = writability::plan_tables(&operations, &client.config.db_name);
+    writability::ensure_writable(
+        &client,
+        &tables,
+        project.migration_config.wait_for_writable(),
+    )
+    .await?;
+
+    let mut clusters: Vec<&str> = teardown_plan
+        .iter()
+        .chain(setup_plan)
+        .filter_map(extract_cluster_name)
+        .collect();
+    clusters.sort();
+    clusters.dedup();
+    cluster_health::ensure_reachable(&client, &clusters).await?;
+
+    apply_changes(
+        &client,
+        !project.is_production,
+        teardown_plan,
+        setup_plan,
+        progress,
+    )
+    .await
+}
+
+/// Goes through `execute_changes` without running anything: the statements it
+/// would send to ClickHouse are recorded instead, and returned in the order
+/// they would run, grouped by operation.
+pub async fn dry_run_changes(
+    project: &Project,
+    teardown_plan: &[AtomicOlapOperation],
+    setup_plan: &[AtomicOlapOperation],
+) -> Result<Vec<OperationStatements>, ClickhouseChangesError> {
+    let recorder = StatementRecorder::default();
+    let client = create_client(project.clickhouse_config.clone()).with_recorder(recorder.clone());
+    apply_changes(
+        &client,
+        !project.is_production,
+        teardown_plan,
+        setup_plan,
+        &NoProgress,
+    )
+    .await?;
+    Ok(recorder.take())
+}
+
+/// Creates the configured databases, then runs the plans with `client`.
+async fn apply_changes(
+    client: &ConfiguredDBClient,
+    is_dev: bool,
+    teardown_plan: &[AtomicOlapOperation],
+    setup_plan: &[AtomicOlapOperation],
+    progress: &dyn DdlProgress,
+) -> Result<(), ClickhouseChangesError> {
+    let config = &client.config;
+    let db_name = &config.db_name;
 
     // Validate all cluster names before executing any SQL
     for op in teardown_plan.iter().chain(setup_plan.iter()) {
@@ -464,18 +835,25 @@ pub async fn execute_changes(
 
     // Create all configured databases
     let mut all_databases = vec![db_name.clone()];
-    all_databases.extend(project.clickhouse_config.additional_databases.clone());
+    all_databases.extend(config.additional_databases.clone());
 
     for database in &all_databases {
+        if let Some(recorder) = &client.recorder {
+            recorder.begin_operation(format!("Creating database '{database}'"));
+        }
         if let Some(clusters) = db_to_clusters.get(database) {
             // Database has tables with clusters - create on each cluster
+            // (sorted, so that the statements of dry runs are stable)
+            let mut clusters: Vec<&String> = clusters.iter().collect();
+            clusters.sort();
             for cluster in clusters {
                 let create_db_query = format!(
-                    "CREATE DATABASE IF NOT EXISTS `{}` ON CLUSTER `{}`",
-                    database, cluster
+                    "CREATE DATABASE IF NOT EXISTS {}{}",
+                    quote_ident(database),
+                    on_cluster_clause(Some(cluster.as_str()))
                 );
                 info!("Creating database {} on cluster {}", database, cluster);
-                run_query(&create_db_query, &client).await.map_err(|e| {
+                run_query(&create_db_query, client).await.map_err(|e| {
                     ClickhouseChangesError::ClickhouseClient {
                         error: e,
                         resource: Some(format!("database:{}@cluster:{}", database, cluster)),
@@ -484,9 +862,10 @@ pub async fn execute_changes(
             }
         } else {
             // No clusters for this database - create normally
-            let create_db_query = format!("CREATE DATABASE IF NOT EXISTS `{}`", database);
+            let create_db_query =
+                format!("CREATE DATABASE IF NOT EXISTS {}", quote_ident(database));
             info!("Creating database: {}", database);
-            run_query(&create_db_query, &client).await.map_err(|e| {
+            run_query(&create_db_query, client).await.map_err(|e| {
                 ClickhouseChangesError::ClickhouseClient {
                     error: e,
                     resource: Some(format!("database:{}", database)),
@@ -495,112 +874,610 @@ pub async fn execute_changes(
         }
     }
 
-    // Execute Teardown Plan
+    // Operations of a dry run are recorded one after the other, in plan order
+    let parallelism = if client.recorder.is_some() {
+        1
+    } else {
+        config.ddl_parallelism
+    };
+    let started = tokio::time::Instant::now();
+    let result = apply_plans(
+        teardown_plan,
+        setup_plan,
+        db_name,
+        parallelism,
+        progress,
+        |operations| async move {
+            let result = match operations {
+                [operation] => {
+                    execute_atomic_operation(db_name, &operation.to_minimal(), client, is_dev).await
+                }
+                _ => {
+                    let operations: Vec<SerializableOlapOperation> = operations
+                        .iter()
+                        .map(AtomicOlapOperation::to_minimal)
+                        .collect();
+                    execute_column_batch(db_name, &operations, client, is_dev).await
+                }
+            };
+            match (result, extract_cluster_name(&operations[0])) {
+                (Err(e), Some(cluster)) => Err(explain_ddl_timeout(e, cluster, client).await),
+                (result, _) => result,
+            }
+        },
+    )
+    .await;
+    if client.recorder.is_none() {
+        migration_metrics::record_plan(started.elapsed(), teardown_plan.len() + setup_plan.len());
+    }
+    result?;
+
+    info!("OLAP Change execution complete");
+    Ok(())
+}
+
+/// Turns the failure of an `ON CLUSTER` statement timing out into an error
+/// listing the status of the statement on each host of `cluster`.
+async fn explain_ddl_timeout(
+    e: ClickhouseChangesError,
+    cluster: &str,
+    client: &ConfiguredDBClient,
+) -> ClickhouseChangesError {
+    match e {
+        ClickhouseChangesError::ClickhouseClient {
+            error,
+            resource: Some(resource),
+        } => {
+            let message = error.to_string();
+            match cluster_health::timed_out_ddl_statuses(client, cluster, &resource, &message).await
+            {
+                Some(hosts) => ClickhouseChangesError::DistributedDdlTimeout {
+                    error,
+                    cluster: cluster.to_string(),
+                    resource,
+                    hosts,
+                },
+                None => ClickhouseChangesError::ClickhouseClient {
+                    error,
+                    resource: Some(resource),
+                },
+            }
+        }
+        e => e,
+    }
+}
+
+/// Runs the teardown plan then, when it succeeded, the setup plan, with
+/// `run` executing each batch of operations (see [`coalesce_column_operations`]).
+async fn apply_plans<'a, F, Fut>(
+    teardown_plan: &'a [AtomicOlapOperation],
+    setup_plan: &'a [AtomicOlapOperation],
+    db_name: &str,
+    parallelism: usize,
+    progress: &dyn DdlProgress,
+    mut run: F,
+) -> Result<(), ClickhouseChangesError>
+where
+    F: FnMut(&'a [AtomicOlapOperation]) -> Fut,
+    Fut: Future<Output = Result<(), ClickhouseChangesError>>,
+{
+    progress.on_event(DdlProgressEvent::PlanStarted {
+        teardown_operations: teardown_plan.len(),
+        setup_operations: setup_plan.len(),
+    });
+    let started = Instant::now();
+    let succeeded = AtomicUsize::new(0);
+
     info!(
         "Executing OLAP Teardown Plan with {} operations",
         teardown_plan.len()
     );
     debug!("Ordered Teardown plan: {:?}", teardown_plan);
-    for op in teardown_plan {
-        debug!("Teardown operation: {:?}", op);
-        execute_atomic_operation(db_name, &op.to_minimal(), &client, !project.is_production)
-            .await?;
+    let mut errors = execute_plan(
+        teardown_plan,
+        true,
+        db_name,
+        parallelism,
+        progress,
+        &succeeded,
+        &mut run,
+    )
+    .await;
+
+    if errors.is_empty() {
+        info!(
+            "Executing OLAP Setup Plan with {} operations",
+            setup_plan.len()
+        );
+        debug!("Ordered Setup plan: {:?}", setup_plan);
+        errors = execute_plan(
+            setup_plan,
+            false,
+            db_name,
+            parallelism,
+            progress,
+            &succeeded,
+            &mut run,
+        )
+        .await;
     }
 
-    // Execute Setup Plan
-    info!(
-        "Executing OLAP Setup Plan with {} operations",
-        setup_plan.len()
+    progress.on_event(DdlProgressEvent::PlanFinished {
+        succeeded: succeeded.load(Ordering::Relaxed),
+        failed: errors.len(),
+        duration: started.elapsed(),
+    });
+
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0)),
+        _ => Err(ClickhouseChangesError::OperationsFailed(errors)),
+    }
+}
+
+/// Executes an ordered plan, running up to `parallelism` batches of operations
+/// that do not depend on each other at once, and returns the errors of those
+/// that failed.
+async fn execute_plan<'a, F, Fut>(
+    plan: &'a [AtomicOlapOperation],
+    is_teardown: bool,
+    db_name: &str,
+    parallelism: usize,
+    progress: &dyn DdlProgress,
+    succeeded: &AtomicUsize,
+    run: &mut F,
+) -> Vec<ClickhouseChangesError>
+where
+    F: FnMut(&'a [AtomicOlapOperation]) -> Fut,
+    Fut: Future<Output = Result<(), ClickhouseChangesError>>,
+{
+    let minimal: Vec<SerializableOlapOperation> =
+        plan.iter().map(AtomicOlapOperation::to_minimal).collect();
+    let batches = coalesce_column_operations(&minimal, db_name);
+    let dependencies = batch_dependencies(
+        &execution_dependencies(plan, is_teardown, db_name),
+        &batches,
     );
-    debug!("Ordered Setup plan: {:?}", setup_plan);
-    for op in setup_plan {
-        debug!("Setup operation: {:?}", op);
-        execute_atomic_operation(db_name, &op.to_minimal(), &client, !project.is_production)
-            .await?;
+    let slow_threshold = progress.slow_operation_threshold();
+    execute_concurrently(&batches, &dependencies, parallelism, |batch| {
+        let operations = &plan[batch.clone()];
+        debug!("Operations: {:?}", operations);
+        let description = describe_operations(&minimal[batch.clone()]);
+        let table = minimal[batch.start].target();
+        progress.on_event(DdlProgressEvent::OperationStarted {
+            description: description.clone(),
+        });
+        let operation = run(operations);
+        async move {
+            let started = Instant::now();
+            tokio::pin!(operation);
+            let result = match slow_threshold {
+                Some(threshold) => match tokio::time::timeout(threshold, &mut operation).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        progress.on_event(DdlProgressEvent::OperationSlow {
+                            description: description.clone(),
+                            elapsed: threshold,
+                        });
+                        operation.await
+                    }
+                },
+                None => operation.await,
+            };
+            let duration = started.elapsed();
+            match &result {
+                Ok(()) => {
+                    succeeded.fetch_add(operations.len(), Ordering::Relaxed);
+                    progress.on_event(DdlProgressEvent::OperationSucceeded {
+                        description,
+                        table,
+                        duration,
+                    });
+                }
+                Err(e) => progress.on_event(DdlProgressEvent::OperationFailed {
+                    description,
+                    table,
+                    duration,
+                    error: e.to_string(),
+                }),
+            }
+            result
+        }
+    })
+    .await
+}
+
+/// Groups the consecutive column operations of `plan` on the same table that
+/// can run as a single `ALTER TABLE` statement with comma-separated actions,
+/// such as `ALTER TABLE t ADD COLUMN a ..., DROP COLUMN b`, and returns the
+/// ranges of operations that run together, in plan order. Other operations
+/// are alone in their range.
+///
+/// ClickHouse applies the actions of a statement in order but rejects one
+/// changing the same column twice, so an operation on a column an earlier
+/// operation of the batch changed, such as modifying a renamed column, starts
+/// a new batch. So does adding a column after one the batch dropped or
+/// renamed. Modifications that need several statements, like removing a
+/// default before changing the type, run alone. Extending the sorting key
+/// joins the batch, as ClickHouse only accepts appending columns to it in the
+/// statement adding them.
+pub fn coalesce_column_operations(
+    plan: &[SerializableOlapOperation],
+    db_name: &str,
+) -> Vec<std::ops::Range<usize>> {
+    let mut batches: Vec<std::ops::Range<usize>> = Vec::new();
+    let mut current: Option<ColumnBatch> = None;
+    for (i, operation) in plan.iter().enumerate() {
+        let alteration = column_alteration(db_name, operation);
+        if let (Some(alteration), Some(batch), Some(range)) =
+            (&alteration, &mut current, batches.last_mut())
+        {
+            if batch.accepts(&alteration.prefix, operation) {
+                batch.add(operation);
+                range.end = i + 1;
+                continue;
+            }
+        }
+        batches.push(i..i + 1);
+        current = alteration.map(|alteration| {
+            let mut batch = ColumnBatch {
+                prefix: alteration.prefix,
+                changed: HashSet::new(),
+                removed: HashSet::new(),
+            };
+            batch.add(operation);
+            batch
+        });
     }
+    batches
+}
 
-    info!("OLAP Change execution complete");
-    Ok(())
+/// The columns changed by the operations of a batch being built
+struct ColumnBatch {
+    /// The `ALTER TABLE <table> [ON CLUSTER <cluster>]` the batch shares
+    prefix: String,
+    /// Columns added, dropped, modified or renamed, under both names
+    changed: HashSet<String>,
+    /// Columns dropped or renamed
+    removed: HashSet<String>,
 }
 
-/// Returns a human-readable description of an operation for logging/display
-pub fn describe_operation(operation: &SerializableOlapOperation) -> String {
+impl ColumnBatch {
+    fn accepts(&self, prefix: &str, operation: &SerializableOlapOperation) -> bool {
+        let (changed, _) = changed_columns(operation);
+        let after_removed = match operation {
+            SerializableOlapOperation::AddTableColumn {
+                after_column: Some(after),
+                ..
+            } => self.removed.contains(after),
+            _ => false,
+        };
+        prefix == self.prefix
+            && !after_removed
+            && changed.iter().all(|column| !self.changed.contains(*column))
+    }
+
+    fn add(&mut self, operation: &SerializableOlapOperation) {
+        let (changed, removed) = changed_columns(operation);
+        self.changed.extend(changed.into_iter().cloned());
+        self.removed.extend(removed.into_iter().cloned());
+    }
+}
+
+/// The columns a column operation changes, and those it removes from the table
+fn changed_columns(operation: &SerializableOlapOperation) -> (Vec<&String>, Vec<&String>) {
     match operation {
-        SerializableOlapOperation::CreateTable { table } => {
-            format!("Creating table '{}'", table.name)
-        }
-        SerializableOlapOperation::DropTable { table, .. } => {
-            format!("Dropping table '{}'", table)
-        }
-        SerializableOlapOperation::AddTableColumn { table, column, .. } => {
-            format!("Adding column '{}' to table '{}'", column.name, table)
-        }
-        SerializableOlapOperation::DropTableColumn {
-            table, column_name, ..
-        } => {
-            format!("Dropping column '{}' from table '{}'", column_name, table)
+        SerializableOlapOperation::AddTableColumn { column, .. } => (vec![&column.name], vec![]),
+        SerializableOlapOperation::DropTableColumn { column_name, .. } => {
+            (vec![column_name], vec![column_name])
         }
         SerializableOlapOperation::ModifyTableColumn {
-            table,
+            before_column,
             after_column,
             ..
-        } => {
-            format!(
-                "Modifying column '{}' in table '{}'",
-                after_column.name, table
-            )
-        }
+        } => (vec![&before_column.name, &after_column.name], vec![]),
         SerializableOlapOperation::RenameTableColumn {
-            table,
             before_column_name,
             after_column_name,
             ..
-        } => {
-            format!(
-                "Renaming column '{}' to '{}' in table '{}'",
-                before_column_name, after_column_name, table
-            )
-        }
-        SerializableOlapOperation::ModifyTableSettings { table, .. } => {
-            format!("Modifying settings for table '{}'", table)
-        }
-        SerializableOlapOperation::AddTableIndex { table, index, .. } => {
-            format!("Adding index '{}' to table '{}'", index.name, table)
-        }
-        SerializableOlapOperation::DropTableIndex {
-            table, index_name, ..
-        } => {
-            format!("Dropping index '{}' from table '{}'", index_name, table)
+        } => (
+            vec![before_column_name, after_column_name],
+            vec![before_column_name],
+        ),
+        _ => (vec![], vec![]),
+    }
+}
+
+/// The part of a column operation that can share an `ALTER TABLE` statement
+struct ColumnAlteration {
+    /// `ALTER TABLE <table> [ON CLUSTER <cluster>]`
+    prefix: String,
+    /// The action of the operation, such as `ADD COLUMN ...`
+    action: String,
+}
+
+/// The `ALTER TABLE` action of a column operation, none for other operations
+/// and for those that need several statements.
+fn column_alteration(
+    db_name: &str,
+    operation: &SerializableOlapOperation,
+) -> Option<ColumnAlteration> {
+    let (table, database, cluster_name) = match operation {
+        SerializableOlapOperation::AddTableColumn {
+            table,
+            database,
+            cluster_name,
+            ..
         }
-        SerializableOlapOperation::AddTableProjection {
-            table, projection, ..
-        } => {
-            format!(
-                "Adding projection '{}' to table '{}'",
-                projection.name, table
-            )
+        | SerializableOlapOperation::DropTableColumn {
+            table,
+            database,
+            cluster_name,
+            ..
         }
-        SerializableOlapOperation::DropTableProjection {
+        | SerializableOlapOperation::ModifyTableColumn {
             table,
-            projection_name,
+            database,
+            cluster_name,
             ..
-        } => {
-            format!(
-                "Dropping projection '{}' from table '{}'",
-                projection_name, table
-            )
         }
-        SerializableOlapOperation::ModifySampleBy {
-            table, expression, ..
-        } => {
-            format!(
-                "Modifying SAMPLE BY to '{}' for table '{}'",
-                expression, table
-            )
+        | SerializableOlapOperation::RenameTableColumn {
+            table,
+            database,
+            cluster_name,
+            ..
+        }
+        | SerializableOlapOperation::ModifyOrderBy {
+            table,
+            database,
+            cluster_name,
+            ..
+        } => (table, database, cluster_name),
+        _ => return None,
+    };
+    let target_db = database.as_deref().unwrap_or(db_name);
+    let statements = match operation {
+        SerializableOlapOperation::AddTableColumn {
+            column,
+            after_column,
+            ..
+        } => vec![add_table_column_query(
+            target_db,
+            table,
+            column,
+            after_column,
+            cluster_name.as_deref(),
+        )
+        .ok()?],
+        SerializableOlapOperation::DropTableColumn { column_name, .. } => {
+            vec![drop_table_column_query(
+                target_db,
+                table,
+                column_name,
+                cluster_name.as_deref(),
+            )]
+        }
+        SerializableOlapOperation::ModifyTableColumn {
+            before_column,
+            after_column,
+            ..
+        } => modify_table_column_queries(
+            target_db,
+            table,
+            before_column,
+            after_column,
+            cluster_name.as_deref(),
+        )
+        .ok()?,
+        SerializableOlapOperation::RenameTableColumn {
+            before_column_name,
+            after_column_name,
+            ..
+        } => vec![rename_table_column_query(
+            target_db,
+            table,
+            before_column_name,
+            after_column_name,
+            cluster_name.as_deref(),
+        )],
+        SerializableOlapOperation::ModifyOrderBy { expression, .. } => vec![modify_order_by_query(
+            target_db,
+            table,
+            expression,
+            cluster_name.as_deref(),
+        )],
+        _ => return None,
+    };
+    let [statement] = statements.as_slice() else {
+        return None;
+    };
+
+    let prefix = format!(
+        "ALTER TABLE {}{}",
+        quote_qualified(target_db, table),
+        on_cluster_clause(cluster_name.as_deref())
+    );
+    let action = statement.strip_prefix(&prefix)?.trim().to_string();
+    Some(ColumnAlteration { prefix, action })
+}
+
+/// For each batch, the indices of the earlier batches it waits for, from the
+/// `dependencies` between the operations of the plan
+fn batch_dependencies(
+    dependencies: &[Vec<usize>],
+    batches: &[std::ops::Range<usize>],
+) -> Vec<Vec<usize>> {
+    let mut batch_of = vec![0; dependencies.len()];
+    for (index, batch) in batches.iter().enumerate() {
+        for operation in batch.clone() {
+            batch_of[operation] = index;
+        }
+    }
+    batches
+        .iter()
+        .enumerate()
+        .map(|(index, batch)| {
+            let mut waits_for: Vec<usize> = batch
+                .clone()
+                .flat_map(|operation| dependencies[operation].iter().map(|&j| batch_of[j]))
+                .filter(|&waited| waited != index)
+                .collect();
+            waits_for.sort();
+            waits_for.dedup();
+            waits_for
+        })
+        .collect()
+}
+
+/// Runs a batch of operations from [`coalesce_column_operations`], as a
+/// single `ALTER TABLE` statement when it holds several column operations.
+pub async fn execute_column_batch(
+    db_name: &str,
+    operations: &[SerializableOlapOperation],
+    client: &ConfiguredDBClient,
+    is_dev: bool,
+) -> Result<(), ClickhouseChangesError> {
+    let alterations: Vec<ColumnAlteration> = operations
+        .iter()
+        .filter_map(|operation| column_alteration(db_name, operation))
+        .collect();
+    let Some(first) = alterations
+        .first()
+        .filter(|_| operations.len() > 1 && alterations.len() == operations.len())
+    else {
+        // Not a batch of column operations: run them one by one
+        for operation in operations {
+            execute_atomic_operation(db_name, operation, client, is_dev).await?;
+        }
+        return Ok(());
+    };
+
+    let actions: Vec<&str> = alterations
+        .iter()
+        .map(|alteration| alteration.action.as_str())
+        .collect();
+    let query = format!("{} {}", first.prefix, actions.join(", "));
+    let execution = async {
+        info!(
+            "Executing {} column operations as one statement",
+            operations.len()
+        );
+        debug!("Altering columns: {}", query);
+        run_query(&query, client)
+            .await
+            .map_err(|e| ClickhouseChangesError::ClickhouseClient {
+                error: e,
+                resource: operations[0].target(),
+            })
+    };
+    if let Some(recorder) = &client.recorder {
+        recorder.begin_operation(describe_operations(operations));
+        return execution.await;
+    }
+    // The batch is observed as its first operation, all of them targeting the same table
+    migration_metrics::observe_operation(&operations[0], execution).await
+}
+
+/// Describes operations that run together
+fn describe_operations(operations: &[SerializableOlapOperation]) -> String {
+    operations
+        .iter()
+        .map(describe_operation)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Returns a human-readable description of an operation for logging/display
+pub fn describe_operation(operation: &SerializableOlapOperation) -> String {
+    match operation {
+        SerializableOlapOperation::CreateTable { table } => {
+            format!("Creating table '{}'", table.name)
+        }
+        SerializableOlapOperation::DropTable { table, .. } => {
+            format!("Dropping table '{}'", table)
+        }
+        SerializableOlapOperation::RenameTable {
+            table, new_name, ..
+        } => {
+            format!("Renaming table '{}' to '{}'", table, new_name)
+        }
+        SerializableOlapOperation::AddTableColumn { table, column, .. } => {
+            format!("Adding column '{}' to table '{}'", column.name, table)
+        }
+        SerializableOlapOperation::DropTableColumn {
+            table, column_name, ..
+        } => {
+            format!("Dropping column '{}' from table '{}'", column_name, table)
+        }
+        SerializableOlapOperation::ModifyTableColumn {
+            table,
+            after_column,
+            ..
+        } => {
+            format!(
+                "Modifying column '{}' in table '{}'",
+                after_column.name, table
+            )
+        }
+        SerializableOlapOperation::RenameTableColumn {
+            table,
+            before_column_name,
+            after_column_name,
+            ..
+        } => {
+            format!(
+                "Renaming column '{}' to '{}' in table '{}'",
+                before_column_name, after_column_name, table
+            )
+        }
+        SerializableOlapOperation::ModifyTableSettings { table, .. } => {
+            format!("Modifying settings for table '{}'", table)
+        }
+        SerializableOlapOperation::AddTableIndex { table, index, .. } => {
+            format!("Adding index '{}' to table '{}'", index.name, table)
+        }
+        SerializableOlapOperation::DropTableIndex {
+            table, index_name, ..
+        } => {
+            format!("Dropping index '{}' from table '{}'", index_name, table)
+        }
+        SerializableOlapOperation::AddTableProjection {
+            table, projection, ..
+        } => {
+            format!(
+                "Adding projection '{}' to table '{}'",
+                projection.name, table
+            )
+        }
+        SerializableOlapOperation::DropTableProjection {
+            table,
+            projection_name,
+            ..
+        } => {
+            format!(
+                "Dropping projection '{}' from table '{}'",
+                projection_name, table
+            )
+        }
+        SerializableOlapOperation::ModifySampleBy {
+            table, expression, ..
+        } => {
+            format!(
+                "Modifying SAMPLE BY to '{}' for table '{}'",
+                expression, table
+            )
         }
         SerializableOlapOperation::RemoveSampleBy { table, .. } => {
             format!("Removing SAMPLE BY from table '{}'", table)
         }
+        SerializableOlapOperation::ModifyOrderBy {
+            table, expression, ..
+        } => {
+            format!(
+                "Modifying ORDER BY to '{}' for table '{}'",
+                expression, table
+            )
+        }
         SerializableOlapOperation::ModifyTableTtl { table, after, .. } => {
             if after.is_some() {
                 format!("Modifying table TTL for '{}'", table)
@@ -626,15 +1503,142 @@ pub fn describe_operation(operation: &SerializableOlapOperation) -> String {
             format!("Dropping custom view '{}'", name)
         }
         SerializableOlapOperation::RawSql { description, .. } => description.clone(),
+        SerializableOlapOperation::VerifyCopy {
+            table,
+            source_table,
+            ..
+        } => {
+            format!(
+                "Verifying the copy of table '{}' into '{}'",
+                source_table, table
+            )
+        }
     }
 }
 
-/// Executes a single atomic OLAP operation.
+/// Returns the SQL an operation would run, for previews such as `moose db diff --show-sql`.
+///
+/// Operations whose statements are only assembled at execution time (column
+/// modifications, indexes, projections, views) are rendered as a SQL comment
+/// describing the operation instead.
+pub fn operation_sql_preview(
+    db_name: &str,
+    operation: &SerializableOlapOperation,
+) -> Result<Vec<String>, ClickhouseChangesError> {
+    let statements = match operation {
+        SerializableOlapOperation::CreateTable { table } => {
+            let target_database = table.database.as_deref().unwrap_or(db_name);
+            let clickhouse_table = std_table_to_clickhouse_table(table)?;
+            vec![create_table_query(
+                target_database,
+                clickhouse_table,
+                false,
+            )?]
+        }
+        SerializableOlapOperation::DropTable {
+            table,
+            database,
+            cluster_name,
+        } => vec![drop_table_query(
+            database.as_deref().unwrap_or(db_name),
+            table,
+            cluster_name.as_deref(),
+        )?],
+        SerializableOlapOperation::RenameTable {
+            table,
+            new_name,
+            database,
+            cluster_name,
+        } => vec![rename_table_query(
+            database.as_deref().unwrap_or(db_name),
+            table,
+            new_name,
+            cluster_name.as_deref(),
+        )],
+        SerializableOlapOperation::AddTableColumn {
+            table,
+            column,
+            after_column,
+            database,
+            cluster_name,
+        } => vec![add_table_column_query(
+            database.as_deref().unwrap_or(db_name),
+            table,
+            column,
+            after_column,
+            cluster_name.as_deref(),
+        )?],
+        SerializableOlapOperation::DropTableColumn {
+            table,
+            column_name,
+            database,
+            cluster_name,
+        } => vec![drop_table_column_query(
+            database.as_deref().unwrap_or(db_name),
+            table,
+            column_name,
+            cluster_name.as_deref(),
+        )],
+        SerializableOlapOperation::ModifyTableTtl {
+            table,
+            after,
+            database,
+            cluster_name,
+            ..
+        } => vec![modify_table_ttl_query(
+            database.as_deref().unwrap_or(db_name),
+            table,
+            after.as_deref(),
+            cluster_name.as_deref(),
+        )],
+        SerializableOlapOperation::RawSql { sql, .. } => sql.clone(),
+        SerializableOlapOperation::VerifyCopy {
+            table,
+            database,
+            source_table,
+            source_database,
+            columns,
+            ..
+        } => vec![
+            copy_verification::digest_query(
+                source_database.as_deref().unwrap_or(db_name),
+                source_table,
+                columns,
+            ),
+            copy_verification::digest_query(database.as_deref().unwrap_or(db_name), table, columns),
+        ],
+        other => vec![format!("-- {}", describe_operation(other))],
+    };
+    Ok(statements
+        .into_iter()
+        .map(|statement| statement.trim().trim_end_matches(';').to_string())
+        .collect())
+}
+
+/// Executes a single atomic OLAP operation, recording its duration and outcome
+/// in the migration metrics.
 pub async fn execute_atomic_operation(
     db_name: &str,
     operation: &SerializableOlapOperation,
     client: &ConfiguredDBClient,
     is_dev: bool,
+) -> Result<(), ClickhouseChangesError> {
+    if let Some(recorder) = &client.recorder {
+        recorder.begin_operation(describe_operation(operation));
+        return run_atomic_operation(db_name, operation, client, is_dev).await;
+    }
+    migration_metrics::observe_operation(
+        operation,
+        run_atomic_operation(db_name, operation, client, is_dev),
+    )
+    .await
+}
+
+async fn run_atomic_operation(
+    db_name: &str,
+    operation: &SerializableOlapOperation,
+    client: &ConfiguredDBClient,
+    is_dev: bool,
 ) -> Result<(), ClickhouseChangesError> {
     match operation {
         SerializableOlapOperation::CreateTable { table } => {
@@ -654,6 +1658,16 @@ pub async fn execute_atomic_operation(
             )
             .await?;
         }
+        SerializableOlapOperation::RenameTable {
+            table,
+            new_name,
+            database,
+            cluster_name,
+        } => {
+            let target_db = database.as_deref().unwrap_or(db_name);
+            execute_rename_table(target_db, table, new_name, cluster_name.as_deref(), client)
+                .await?;
+        }
         SerializableOlapOperation::AddTableColumn {
             table,
             column,
@@ -750,22 +1764,8 @@ pub async fn execute_atomic_operation(
             cluster_name,
         } => {
             let target_db = database.as_deref().unwrap_or(db_name);
-            // Build ALTER TABLE ... [REMOVE TTL | MODIFY TTL expr]
-            let cluster_clause = cluster_name
-                .as_ref()
-                .map(|c| format!(" ON CLUSTER `{}`", c))
-                .unwrap_or_default();
-            let sql = if let Some(expr) = after {
-                format!(
-                    "ALTER TABLE `{}`.`{}`{} MODIFY TTL {}",
-                    target_db, table, cluster_clause, expr
-                )
-            } else {
-                format!(
-                    "ALTER TABLE `{}`.`{}`{} REMOVE TTL",
-                    target_db, table, cluster_clause
-                )
-            };
+            let sql =
+                modify_table_ttl_query(target_db, table, after.as_deref(), cluster_name.as_deref());
             run_query(&sql, client).await.map_err(|e| {
                 ClickhouseChangesError::ClickhouseClient {
                     error: e,
@@ -855,6 +1855,22 @@ pub async fn execute_atomic_operation(
             let target_db = database.as_deref().unwrap_or(db_name);
             execute_remove_sample_by(target_db, table, cluster_name.as_deref(), client).await?;
         }
+        SerializableOlapOperation::ModifyOrderBy {
+            table,
+            expression,
+            database,
+            cluster_name,
+        } => {
+            let target_db = database.as_deref().unwrap_or(db_name);
+            execute_modify_order_by(
+                target_db,
+                table,
+                expression,
+                cluster_name.as_deref(),
+                client,
+            )
+            .await?;
+        }
         SerializableOlapOperation::CreateMaterializedView {
             name,
             database,
@@ -889,6 +1905,23 @@ pub async fn execute_atomic_operation(
         SerializableOlapOperation::RawSql { sql, description } => {
             execute_raw_sql(sql, description, client).await?;
         }
+        SerializableOlapOperation::VerifyCopy {
+            table,
+            database,
+            source_table,
+            source_database,
+            columns,
+            tolerance,
+        } => {
+            copy_verification::execute_verify_copy(
+                client,
+                (source_database.as_deref().unwrap_or(db_name), source_table),
+                (database.as_deref().unwrap_or(db_name), table),
+                columns,
+                *tolerance,
+            )
+            .await?;
+        }
     }
     Ok(())
 }
@@ -934,15 +1967,12 @@ async fn execute_add_table_index(
     } else {
         format!("({})", index.arguments.join(", "))
     };
-    let cluster_clause = cluster_name
-        .map(|c| format!(" ON CLUSTER `{}`", c))
-        .unwrap_or_default();
+    let cluster_clause = on_cluster_clause(cluster_name);
     let sql = format!(
-        "ALTER TABLE `{}`.`{}`{} ADD INDEX `{}` {} TYPE {}{} GRANULARITY {}",
-        db_name,
-        table_name,
+        "ALTER TABLE {}{} ADD INDEX {} {} TYPE {}{} GRANULARITY {}",
+        quote_qualified(db_name, table_name),
         cluster_clause,
-        index.name,
+        quote_ident(&index.name),
         index.expression,
         index.index_type,
         args,
@@ -963,12 +1993,12 @@ async fn execute_drop_table_index(
     cluster_name: Option<&str>,
     client: &ConfiguredDBClient,
 ) -> Result<(), ClickhouseChangesError> {
-    let cluster_clause = cluster_name
-        .map(|c| format!(" ON CLUSTER `{}`", c))
-        .unwrap_or_default();
+    let cluster_clause = on_cluster_clause(cluster_name);
     let sql = format!(
-        "ALTER TABLE `{}`.`{}`{} DROP INDEX `{}`",
-        db_name, table_name, cluster_clause, index_name
+        "ALTER TABLE {}{} DROP INDEX {}",
+        quote_qualified(db_name, table_name),
+        cluster_clause,
+        quote_ident(index_name)
     );
     run_query(&sql, client)
         .await
@@ -991,19 +2021,36 @@ async fn execute_add_table_projection(
         .map_err(ClickhouseChangesError::Clickhouse)?;
     validate_clickhouse_identifier(&projection.name, "Projection name")
         .map_err(ClickhouseChangesError::Clickhouse)?;
-    let cluster_clause = cluster_name
-        .map(|c| format!(" ON CLUSTER `{}`", c))
-        .unwrap_or_default();
-    let sql = format!(
-        "ALTER TABLE `{}`.`{}`{} ADD PROJECTION IF NOT EXISTS `{}` ({})",
-        db_name, table_name, cluster_clause, projection.name, projection.body
-    );
-    run_query(&sql, client)
-        .await
-        .map_err(|e| ClickhouseChangesError::ClickhouseClient {
-            error: e,
-            resource: Some(table_name.to_string()),
-        })
+    for sql in add_table_projection_queries(db_name, table_name, projection, cluster_name) {
+        run_query(&sql, client)
+            .await
+            .map_err(|e| ClickhouseChangesError::ClickhouseClient {
+                error: e,
+                resource: Some(table_name.to_string()),
+            })?;
+    }
+    Ok(())
+}
+
+/// Adds the projection, then builds it for the parts already in the table:
+/// ClickHouse only maintains a new projection for the parts inserted after it
+/// is added. Materializing runs as a background mutation.
+fn add_table_projection_queries(
+    db_name: &str,
+    table_name: &str,
+    projection: &TableProjection,
+    cluster_name: Option<&str>,
+) -> Vec<String> {
+    let table = quote_qualified(db_name, table_name);
+    let cluster_clause = on_cluster_clause(cluster_name);
+    let name = quote_ident(&projection.name);
+    vec![
+        format!(
+            "ALTER TABLE {table}{cluster_clause} ADD PROJECTION IF NOT EXISTS {name} ({})",
+            projection.body
+        ),
+        format!("ALTER TABLE {table}{cluster_clause} MATERIALIZE PROJECTION {name}"),
+    ]
 }
 
 async fn execute_drop_table_projection(
@@ -1019,12 +2066,12 @@ async fn execute_drop_table_projection(
         .map_err(ClickhouseChangesError::Clickhouse)?;
     validate_clickhouse_identifier(projection_name, "Projection name")
         .map_err(ClickhouseChangesError::Clickhouse)?;
-    let cluster_clause = cluster_name
-        .map(|c| format!(" ON CLUSTER `{}`", c))
-        .unwrap_or_default();
+    let cluster_clause = on_cluster_clause(cluster_name);
     let sql = format!(
-        "ALTER TABLE `{}`.`{}`{} DROP PROJECTION IF EXISTS `{}`",
-        db_name, table_name, cluster_clause, projection_name
+        "ALTER TABLE {}{} DROP PROJECTION IF EXISTS {}",
+        quote_qualified(db_name, table_name),
+        cluster_clause,
+        quote_ident(projection_name)
     );
     run_query(&sql, client)
         .await
@@ -1041,12 +2088,12 @@ async fn execute_modify_sample_by(
     cluster_name: Option<&str>,
     client: &ConfiguredDBClient,
 ) -> Result<(), ClickhouseChangesError> {
-    let cluster_clause = cluster_name
-        .map(|c| format!(" ON CLUSTER `{}`", c))
-        .unwrap_or_default();
+    let cluster_clause = on_cluster_clause(cluster_name);
     let sql = format!(
-        "ALTER TABLE `{}`.`{}`{} MODIFY SAMPLE BY {}",
-        db_name, table_name, cluster_clause, expression
+        "ALTER TABLE {}{} MODIFY SAMPLE BY {}",
+        quote_qualified(db_name, table_name),
+        cluster_clause,
+        expression
     );
     run_query(&sql, client)
         .await
@@ -1056,18 +2103,50 @@ async fn execute_modify_sample_by(
         })
 }
 
+async fn execute_modify_order_by(
+    db_name: &str,
+    table_name: &str,
+    expression: &str,
+    cluster_name: Option<&str>,
+    client: &ConfiguredDBClient,
+) -> Result<(), ClickhouseChangesError> {
+    let sql = modify_order_by_query(db_name, table_name, expression, cluster_name);
+    run_query(&sql, client)
+        .await
+        .map_err(|e| ClickhouseChangesError::ClickhouseClient {
+            error: e,
+            resource: Some(table_name.to_string()),
+        })
+}
+
+/// Builds ALTER TABLE ... MODIFY ORDER BY expr. ClickHouse only accepts it when
+/// the columns appended to the sorting key are added by the same statement.
+fn modify_order_by_query(
+    db_name: &str,
+    table_name: &str,
+    expression: &str,
+    cluster_name: Option<&str>,
+) -> String {
+    let cluster_clause = on_cluster_clause(cluster_name);
+    format!(
+        "ALTER TABLE {}{} MODIFY ORDER BY {}",
+        quote_qualified(db_name, table_name),
+        cluster_clause,
+        expression
+    )
+}
+
 async fn execute_remove_sample_by(
     db_name: &str,
     table_name: &str,
     cluster_name: Option<&str>,
     client: &ConfiguredDBClient,
 ) -> Result<(), ClickhouseChangesError> {
-    let cluster_clause = cluster_name
-        .map(|c| format!(" ON CLUSTER `{}`", c))
-        .unwrap_or_default();
+    let cluster_clause = on_cluster_clause(cluster_name);
     let sql = format!(
-        "ALTER TABLE `{}`.`{}`{} REMOVE SAMPLE BY",
-        db_name, table_name, cluster_clause
+        "ALTER TABLE {}{} REMOVE SAMPLE BY",
+        quote_qualified(db_name, table_name),
+        cluster_clause
     );
     run_query(&sql, client)
         .await
@@ -1106,11 +2185,116 @@ async fn execute_drop_table(
     Ok(())
 }
 
+fn rename_table_query(
+    db_name: &str,
+    table_name: &str,
+    new_name: &str,
+    cluster_name: Option<&str>,
+) -> String {
+    let cluster_clause = on_cluster_clause(cluster_name);
+    format!(
+        "RENAME TABLE {} TO {}{cluster_clause}",
+        quote_qualified(db_name, table_name),
+        quote_qualified(db_name, new_name)
+    )
+}
+
+async fn execute_rename_table(
+    db_name: &str,
+    table_name: &str,
+    new_name: &str,
+    cluster_name: Option<&str>,
+    client: &ConfiguredDBClient,
+) -> Result<(), ClickhouseChangesError> {
+    tracing::info!(
+        "Executing RenameTable: {}.{} → {}",
+        db_name,
+        table_name,
+        new_name
+    );
+    let rename_query = rename_table_query(db_name, table_name, new_name, cluster_name);
+    run_query(&rename_query, client).await.map_err(|e| {
+        ClickhouseChangesError::ClickhouseClient {
+            error: e,
+            resource: Some(table_name.to_string()),
+        }
+    })?;
+    Ok(())
+}
+
 // Note: The nullable wrapping logic has been moved to std_column_to_clickhouse_column
 // in mapper.rs to ensure consistent handling across all uses.
 // TODO: Future refactoring opportunity - Consider eliminating the `required` boolean field
 // from ClickHouseColumn and rely solely on the Nullable type wrapper.
 
+fn add_table_column_query(
+    db_name: &str,
+    table_name: &str,
+    column: &Column,
+    after_column: &Option<String>,
+    cluster_name: Option<&str>,
+) -> Result<String, ClickhouseChangesError> {
+    let clickhouse_column = std_column_to_clickhouse_column(column.clone())?;
+    let column_type_string = basic_field_type_to_string(&clickhouse_column.column_type)?;
+
+    let cluster_clause = on_cluster_clause(cluster_name);
+
+    let property_clauses = build_column_property_clauses(&clickhouse_column);
+
+    let position_clause = match after_column {
+        None => "FIRST".to_string(),
+        Some(after_col) => format!("AFTER {}", quote_ident(after_col)),
+    };
+
+    Ok(format!(
+        "ALTER TABLE {}{} ADD COLUMN {} {}{}  {}",
+        quote_qualified(db_name, table_name),
+        cluster_clause,
+        quote_ident(&clickhouse_column.name),
+        column_type_string,
+        property_clauses,
+        position_clause
+    ))
+}
+
+fn drop_table_column_query(
+    db_name: &str,
+    table_name: &str,
+    column_name: &str,
+    cluster_name: Option<&str>,
+) -> String {
+    let cluster_clause = on_cluster_clause(cluster_name);
+    format!(
+        "ALTER TABLE {}{} DROP COLUMN IF EXISTS {}",
+        quote_qualified(db_name, table_name),
+        cluster_clause,
+        quote_ident(column_name)
+    )
+}
+
+/// Builds ALTER TABLE ... [REMOVE TTL | MODIFY TTL expr]
+fn modify_table_ttl_query(
+    db_name: &str,
+    table_name: &str,
+    ttl: Option<&str>,
+    cluster_name: Option<&str>,
+) -> String {
+    let cluster_clause = on_cluster_clause(cluster_name);
+    match ttl {
+        Some(expr) => format!(
+            "ALTER TABLE {}{} MODIFY TTL {}",
+            quote_qualified(db_name, table_name),
+            cluster_clause,
+            expr
+        ),
+        None => format!(
+            "ALTER TABLE {}{} REMOVE TTL",
+            quote_qualified(db_name, table_name),
+            cluster_clause
+        ),
+    }
+}
+
 #[instrument(
     name = "add_column",
     skip_all,
@@ -1135,30 +2319,8 @@ async fn execute_add_table_column(
         column.name,
         after_column
     );
-    let clickhouse_column = std_column_to_clickhouse_column(column.clone())?;
-    let column_type_string = basic_field_type_to_string(&clickhouse_column.column_type)?;
-
-    let cluster_clause = cluster_name
-        .map(|c| format!(" ON CLUSTER `{}`", c))
-        .unwrap_or_default();
-
-    let property_clauses = build_column_property_clauses(&clickhouse_column);
-
-    let position_clause = match after_column {
-        None => "FIRST".to_string(),
-        Some(after_col) => format!("AFTER `{after_col}`"),
-    };
-
-    let add_column_query = format!(
-        "ALTER TABLE `{}`.`{}`{} ADD COLUMN `{}` {}{}  {}",
-        db_name,
-        table_name,
-        cluster_clause,
-        clickhouse_column.name,
-        column_type_string,
-        property_clauses,
-        position_clause
-    );
+    let add_column_query =
+        add_table_column_query(db_name, table_name, column, after_column, cluster_name)?;
     tracing::debug!("Adding column: {}", add_column_query);
     run_query(&add_column_query, client).await.map_err(|e| {
         ClickhouseChangesError::ClickhouseClient {
@@ -1191,13 +2353,7 @@ async fn execute_drop_table_column(
         table_name,
         column_name
     );
-    let cluster_clause = cluster_name
-        .map(|c| format!(" ON CLUSTER `{}`", c))
-        .unwrap_or_default();
-    let drop_column_query = format!(
-        "ALTER TABLE `{}`.`{}`{} DROP COLUMN IF EXISTS `{}`",
-        db_name, table_name, cluster_clause, column_name
-    );
+    let drop_column_query = drop_table_column_query(db_name, table_name, column_name, cluster_name);
     tracing::debug!("Dropping column: {}", drop_column_query);
     run_query(&drop_column_query, client).await.map_err(|e| {
         ClickhouseChangesError::ClickhouseClient {
@@ -1210,10 +2366,8 @@ async fn execute_drop_table_column(
 
 /// Execute a ModifyTableColumn operation
 ///
-/// This function handles column modifications, including type changes and comment-only changes.
-/// When only the comment has changed (e.g., when enum metadata is added or user documentation
-/// is updated), it uses a more efficient comment-only modification instead of recreating
-/// the entire column definition.
+/// This function handles column modifications, including type changes and comment-only changes,
+/// see [`modify_table_column_queries`].
 #[instrument(
     name = "modify_column",
     skip_all,
@@ -1231,13 +2385,60 @@ async fn execute_modify_table_column(
     cluster_name: Option<&str>,
     client: &ConfiguredDBClient,
 ) -> Result<(), ClickhouseChangesError> {
-    // Check if only the comment has changed
-    let data_type_changed = before_column.data_type != after_column.data_type;
+    tracing::info!(
+        "Executing ModifyTableColumn for table: {}, column: {} ({}→{})",
+        table_name,
+        after_column.name,
+        before_column.data_type,
+        after_column.data_type
+    );
+
+    let queries = modify_table_column_queries(
+        db_name,
+        table_name,
+        before_column,
+        after_column,
+        cluster_name,
+    )?;
+
+    // Execute all statements in order
+    for query in queries {
+        tracing::debug!("Modifying column: {}", query);
+        run_query(&query, client)
+            .await
+            .map_err(|e| ClickhouseChangesError::ClickhouseClient {
+                error: e,
+                resource: Some(table_name.to_string()),
+            })?;
+    }
+
+    Ok(())
+}
+
+/// The statements of a ModifyTableColumn operation, in execution order.
+///
+/// When only the comment has changed (e.g., when enum metadata is added or user
+/// documentation is updated), it uses a more efficient comment-only modification
+/// instead of recreating the entire column definition.
+fn modify_table_column_queries(
+    db_name: &str,
+    table_name: &str,
+    before_column: &Column,
+    after_column: &Column,
+    cluster_name: Option<&str>,
+) -> Result<Vec<String>, ClickhouseChangesError> {
+    // Check if only the comment has changed. The comments of nested fields are
+    // stored in the column comment, not in the type
+    let data_type_changed = !before_column
+        .data_type
+        .eq_ignoring_field_comments(&after_column.data_type);
     let default_changed = before_column.default != after_column.default;
     let materialized_changed = before_column.materialized != after_column.materialized;
     let alias_changed = before_column.alias != after_column.alias;
     let required_changed = before_column.required != after_column.required;
-    let comment_changed = before_column.comment != after_column.comment;
+    let comment_changed = before_column.comment != after_column.comment
+        || before_column.data_type.nested_field_comments()
+            != after_column.data_type.nested_field_comments();
     let ttl_changed = before_column.ttl != after_column.ttl;
     let codec_changed = before_column.codec != after_column.codec;
 
@@ -1252,47 +2453,22 @@ async fn execute_modify_table_column(
         && !codec_changed
         && comment_changed
     {
-        tracing::info!(
-            "Executing comment-only modification for table: {}, column: {}",
-            table_name,
-            after_column.name
-        );
-
         // Get the ClickHouse column to generate the proper comment (with metadata if needed)
         let clickhouse_column = std_column_to_clickhouse_column(after_column.clone())?;
 
-        if let Some(ref comment) = clickhouse_column.comment {
-            execute_modify_column_comment(
-                db_name,
-                table_name,
-                after_column,
-                comment,
-                cluster_name,
-                client,
-            )
-            .await?;
-        } else {
-            // If the new comment is None, we still need to update to remove the old comment
-            execute_modify_column_comment(
-                db_name,
-                table_name,
-                after_column,
-                "",
-                cluster_name,
-                client,
-            )
-            .await?;
-        }
-        return Ok(());
+        // If the new comment is None, we still need to update to remove the old comment
+        let comment = clickhouse_column.comment.as_deref().unwrap_or("");
+        return Ok(vec![build_modify_column_comment_sql(
+            db_name,
+            table_name,
+            &after_column.name,
+            comment,
+            cluster_name,
+        )?]);
     }
 
-    tracing::info!(
-        "Executing ModifyTableColumn for table: {}, column: {} ({}→{})\
-data_type_changed: {data_type_changed}, default_changed: {default_changed}, materialized_changed: {materialized_changed}, alias_changed: {alias_changed}, required_changed: {required_changed}, comment_changed: {comment_changed}, ttl_changed: {ttl_changed}, codec_changed: {codec_changed}",
-        table_name,
-        after_column.name,
-        before_column.data_type,
-        after_column.data_type
+    debug!(
+        "data_type_changed: {data_type_changed}, default_changed: {default_changed}, materialized_changed: {materialized_changed}, alias_changed: {alias_changed}, required_changed: {required_changed}, comment_changed: {comment_changed}, ttl_changed: {ttl_changed}, codec_changed: {codec_changed}"
     );
 
     // Full column modification including type change
@@ -1310,57 +2486,13 @@ data_type_changed: {data_type_changed}, default_changed: {default_changed}, mate
         ttl: before_column.ttl.is_some() && after_column.ttl.is_none(),
         codec: before_column.codec.is_some() && after_column.codec.is_none(),
     };
-    let queries = build_modify_column_sql(
+    build_modify_column_sql(
         db_name,
         table_name,
         &clickhouse_column,
         &removals,
         cluster_name,
-    )?;
-
-    // Execute all statements in order
-    for query in queries {
-        tracing::debug!("Modifying column: {}", query);
-        run_query(&query, client)
-            .await
-            .map_err(|e| ClickhouseChangesError::ClickhouseClient {
-                error: e,
-                resource: Some(table_name.to_string()),
-            })?;
-    }
-
-    Ok(())
-}
-
-/// Execute a ModifyColumnComment operation
-///
-/// This is used to add or update metadata comments on columns, particularly
-/// for enum columns that need to store their original TypeScript definition.
-async fn execute_modify_column_comment(
-    db_name: &str,
-    table_name: &str,
-    column: &Column,
-    comment: &str,
-    cluster_name: Option<&str>,
-    client: &ConfiguredDBClient,
-) -> Result<(), ClickhouseChangesError> {
-    tracing::info!(
-        "Executing ModifyColumnComment for table: {}, column: {}",
-        table_name,
-        column.name
-    );
-
-    let modify_comment_query =
-        build_modify_column_comment_sql(db_name, table_name, &column.name, comment, cluster_name)?;
-
-    tracing::debug!("Modifying column comment: {}", modify_comment_query);
-    run_query(&modify_comment_query, client)
-        .await
-        .map_err(|e| ClickhouseChangesError::ClickhouseClient {
-            error: e,
-            resource: Some(table_name.to_string()),
-        })?;
-    Ok(())
+    )
 }
 
 /// Extracts the default expression kind from a core `Column` struct.
@@ -1389,10 +2521,7 @@ fn build_column_property_clauses(col: &ClickHouseColumn) -> String {
     let comment_clause = col
         .comment
         .as_ref()
-        .map(|c| {
-            let escaped = c.replace('\\', "\\\\").replace('\'', "''");
-            format!(" COMMENT '{}'", escaped)
-        })
+        .map(|c| format!(" COMMENT {}", quote_string(c)))
         .unwrap_or_default();
 
     let codec_clause = col
@@ -1422,9 +2551,9 @@ fn build_modify_column_sql(
 ) -> Result<Vec<String>, ClickhouseChangesError> {
     let column_type_string = basic_field_type_to_string(&ch_col.column_type)?;
 
-    let cluster_clause = cluster_name
-        .map(|c| format!(" ON CLUSTER `{}`", c))
-        .unwrap_or_default();
+    let table = quote_qualified(db_name, table_name);
+    let column = quote_ident(&ch_col.name);
+    let cluster_clause = on_cluster_clause(cluster_name);
 
     let mut statements = vec![];
 
@@ -1432,30 +2561,26 @@ fn build_modify_column_sql(
     // so REMOVE statements must be separate ALTER TABLE statements.
     if let Some(kind) = removals.default_expression {
         statements.push(format!(
-            "ALTER TABLE `{}`.`{}`{} MODIFY COLUMN `{}` REMOVE {}",
-            db_name, table_name, cluster_clause, ch_col.name, kind
+            "ALTER TABLE {table}{cluster_clause} MODIFY COLUMN {column} REMOVE {kind}"
         ));
     }
 
     if removals.ttl {
         statements.push(format!(
-            "ALTER TABLE `{}`.`{}`{} MODIFY COLUMN `{}` REMOVE TTL",
-            db_name, table_name, cluster_clause, ch_col.name
+            "ALTER TABLE {table}{cluster_clause} MODIFY COLUMN {column} REMOVE TTL"
         ));
     }
 
     if removals.codec {
         statements.push(format!(
-            "ALTER TABLE `{}`.`{}`{} MODIFY COLUMN `{}` REMOVE CODEC",
-            db_name, table_name, cluster_clause, ch_col.name
+            "ALTER TABLE {table}{cluster_clause} MODIFY COLUMN {column} REMOVE CODEC"
         ));
     }
 
     let property_clauses = build_column_property_clauses(ch_col);
 
     let main_sql = format!(
-        "ALTER TABLE `{}`.`{}`{} MODIFY COLUMN IF EXISTS `{}` {}{}",
-        db_name, table_name, cluster_clause, ch_col.name, column_type_string, property_clauses
+        "ALTER TABLE {table}{cluster_clause} MODIFY COLUMN IF EXISTS {column} {column_type_string}{property_clauses}"
     );
     statements.push(main_sql);
 
@@ -1469,14 +2594,13 @@ fn build_modify_column_comment_sql(
     comment: &str,
     cluster_name: Option<&str>,
 ) -> Result<String, ClickhouseChangesError> {
-    // Escape for ClickHouse SQL: backslashes first, then single quotes
-    let escaped_comment = comment.replace('\\', "\\\\").replace('\'', "''");
-    let cluster_clause = cluster_name
-        .map(|c| format!(" ON CLUSTER `{}`", c))
-        .unwrap_or_default();
+    let cluster_clause = on_cluster_clause(cluster_name);
     Ok(format!(
-        "ALTER TABLE `{}`.`{}`{} MODIFY COLUMN `{}` COMMENT '{}'",
-        db_name, table_name, cluster_clause, column_name, escaped_comment
+        "ALTER TABLE {}{} MODIFY COLUMN {} COMMENT {}",
+        quote_qualified(db_name, table_name),
+        cluster_clause,
+        quote_ident(column_name),
+        quote_string(comment)
     ))
 }
 
@@ -1571,11 +2695,12 @@ async fn execute_rename_table_column(
         before_column_name,
         after_column_name
     );
-    let cluster_clause = cluster_name
-        .map(|c| format!(" ON CLUSTER `{}`", c))
-        .unwrap_or_default();
-    let rename_column_query = format!(
-        "ALTER TABLE `{db_name}`.`{table_name}`{cluster_clause} RENAME COLUMN `{before_column_name}` TO `{after_column_name}`"
+    let rename_column_query = rename_table_column_query(
+        db_name,
+        table_name,
+        before_column_name,
+        after_column_name,
+        cluster_name,
     );
     tracing::debug!("Renaming column: {}", rename_column_query);
     run_query(&rename_column_query, client).await.map_err(|e| {
@@ -1587,6 +2712,22 @@ async fn execute_rename_table_column(
     Ok(())
 }
 
+fn rename_table_column_query(
+    db_name: &str,
+    table_name: &str,
+    before_column_name: &str,
+    after_column_name: &str,
+    cluster_name: Option<&str>,
+) -> String {
+    let cluster_clause = on_cluster_clause(cluster_name);
+    format!(
+        "ALTER TABLE {}{cluster_clause} RENAME COLUMN {} TO {}",
+        quote_qualified(db_name, table_name),
+        quote_ident(before_column_name),
+        quote_ident(after_column_name)
+    )
+}
+
 /// Execute raw SQL statements
 async fn execute_raw_sql(
     sql_statements: &[String],
@@ -1642,12 +2783,14 @@ async fn execute_create_materialized_view(
     // Strip any existing backticks from target_table to avoid double-backticks
     let clean_target_table = strip_backticks(target_table);
     let to_target = match target_database {
-        Some(tdb) => format!("`{}`.`{}`", tdb, clean_target_table),
-        None => format!("`{}`.`{}`", target_db, clean_target_table),
+        Some(tdb) => quote_qualified(tdb, &clean_target_table),
+        None => quote_qualified(target_db, &clean_target_table),
     };
     let sql = format!(
-        "CREATE MATERIALIZED VIEW IF NOT EXISTS `{}`.`{}` TO {} AS {}",
-        target_db, view_name, to_target, select_sql
+        "CREATE MATERIALIZED VIEW IF NOT EXISTS {} TO {} AS {}",
+        quote_qualified(target_db, view_name),
+        to_target,
+        select_sql
     );
     tracing::info!("Creating materialized view: {}.{}", target_db, view_name);
     tracing::debug!("MV SQL: {}", sql);
@@ -1679,8 +2822,9 @@ async fn execute_create_view(
 ) -> Result<(), ClickhouseChangesError> {
     let target_db = view_database.unwrap_or(db_name);
     let sql = format!(
-        "CREATE VIEW IF NOT EXISTS `{}`.`{}` AS {}",
-        target_db, view_name, select_sql
+        "CREATE VIEW IF NOT EXISTS {} AS {}",
+        quote_qualified(target_db, view_name),
+        select_sql
     );
     tracing::info!("Creating custom view: {}.{}", target_db, view_name);
     tracing::debug!("View SQL: {}", sql);
@@ -1701,7 +2845,10 @@ async fn execute_drop_view_inner(
     client: &ConfiguredDBClient,
 ) -> Result<(), ClickhouseChangesError> {
     let target_db = view_database.unwrap_or(db_name);
-    let sql = format!("DROP VIEW IF EXISTS `{}`.`{}`", target_db, view_name);
+    let sql = format!(
+        "DROP VIEW IF EXISTS {}",
+        quote_qualified(target_db, view_name)
+    );
     tracing::info!("Dropping view: {}.{}", target_db, view_name);
     run_query(&sql, client)
         .await
@@ -1750,6 +2897,10 @@ async fn execute_drop_view(
     execute_drop_view_inner(db_name, view_name, view_database, client).await
 }
 
+/// Fewest and most underscore-separated numeric parts read as a table version
+const MIN_VERSION_PARTS: usize = 2;
+const MAX_VERSION_PARTS: usize = 4;
+
 /// Extracts version information from a table name
 ///
 /// # Arguments
@@ -1761,7 +2912,10 @@ async fn execute_drop_view(
 ///
 /// # Format
 /// For tables following the naming convention: {name}_{version}
-/// where version is in the format x_y_z (e.g., 1_0_0)
+/// where version is the trailing run of 2 to 4 numeric parts (e.g., 1_0_0).
+/// Numeric parts before it belong to the base name, so `iot_2024_events_1_0_0`
+/// is `iot_2024_events` at version 1.0.0. A longer run keeps its first parts
+/// in the base name.
 /// For tables not following the convention: returns the full name and default_version
 ///
 /// Empty segments produced by consecutive underscores (e.g., `foo__1_0`) are
@@ -1793,7 +2947,8 @@ pub fn extract_version_from_table_name(table_name: &str) -> (String, Option<Vers
         return (table_name.to_string(), None);
     }
 
-    let parts: Vec<&str> = table_name.split('_').collect();
+    // Empty segments from consecutive, leading or trailing underscores are dropped
+    let parts: Vec<&str> = table_name.split('_').filter(|p| !p.is_empty()).collect();
     debug!("Split table name into parts: {:?}", parts);
 
     if parts.len() < 2 {
@@ -1802,59 +2957,106 @@ pub fn extract_version_from_table_name(table_name: &str) -> (String, Option<Vers
         return (table_name.to_string(), None);
     }
 
-    // Find the first numeric part - this marks the start of the version
-    let mut version_start_idx = None;
-    for (i, part) in parts.iter().enumerate() {
-        if !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) {
-            version_start_idx = Some(i);
-            debug!("Found version start at index {}: {}", i, part);
-            break;
-        }
+    // The version is the trailing run of numeric parts, so that numeric parts
+    // earlier in the name (e.g. `iot_2024_events_1_0_0`) stay in the base name
+    let trailing_numeric = parts
+        .iter()
+        .rev()
+        .take_while(|part| part.chars().all(|c| c.is_ascii_digit()))
+        .count();
+    debug!("Found {} trailing numeric parts", trailing_numeric);
+
+    if trailing_numeric < MIN_VERSION_PARTS {
+        debug!("No version parts found");
+        return (table_name.to_string(), None);
     }
 
-    match version_start_idx {
-        Some(idx) => {
-            // Filter out empty parts when joining base name
-            let base_parts: Vec<&str> = parts[..idx]
-                .iter()
-                .filter(|p| !p.is_empty())
-                .copied()
-                .collect();
-            let base_name = base_parts.join("_");
-            debug!(
-                "Base parts: {:?}, joined base name: {}",
-                base_parts, base_name
-            );
+    let version_start_idx = parts.len() - trailing_numeric.min(MAX_VERSION_PARTS);
+    let base_name = parts[..version_start_idx].join("_");
+    let version_str = parts[version_start_idx..].join(".");
+    debug!(
+        "Base name: {}, created version string: {}",
+        base_name, version_str
+    );
 
-            // Filter out empty parts when joining version
-            let version_parts: Vec<&str> = parts[idx..]
-                .iter()
-                .filter(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
-                .copied()
-                .collect();
-            debug!("Version parts: {:?}", version_parts);
+    (base_name, Some(Version::from_string(version_str)))
+}
 
-            // If we have no valid version parts, return the original name and default version
-            if version_parts.is_empty() {
-                debug!("No valid version parts found.");
-                return (table_name.to_string(), None);
-            }
+pub struct ConfiguredDBClient {
+    pub client: Client,
+    pub config: ClickHouseConfig,
+    /// Set for dry runs: `run_query` records the statements instead of
+    /// sending them
+    recorder: Option<StatementRecorder>,
+}
+
+impl ConfiguredDBClient {
+    /// Makes `run_query` record the statements in `recorder` instead of
+    /// sending them to ClickHouse.
+    pub fn with_recorder(mut self, recorder: StatementRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+}
 
-            let version_str = version_parts.join(".");
-            debug!("Created version string: {}", version_str);
+/// The statements sent for an operation of a plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationStatements {
+    pub description: String,
+    pub statements: Vec<String>,
+}
+
+/// Statements recorded by dry runs instead of being sent, grouped by the
+/// operation that sent them.
+#[derive(Debug, Clone, Default)]
+pub struct StatementRecorder(std::sync::Arc<std::sync::Mutex<Vec<OperationStatements>>>);
+
+impl StatementRecorder {
+    /// Starts the group of the statements of the next operation
+    fn begin_operation(&self, description: String) {
+        self.0.lock().unwrap().push(OperationStatements {
+            description,
+            statements: vec![],
+        });
+    }
 
-            (base_name, Some(Version::from_string(version_str)))
+    fn record(&self, statement: &str) {
+        let mut operations = self.0.lock().unwrap();
+        if operations.is_empty() {
+            operations.push(OperationStatements {
+                description: String::new(),
+                statements: vec![],
+            });
         }
-        None => {
-            debug!("No version parts found");
-            (table_name.to_string(), None)
+        if let Some(operation) = operations.last_mut() {
+            operation.statements.push(statement.to_string());
         }
     }
+
+    /// The recorded operations, without those that sent no statement
+    pub fn take(&self) -> Vec<OperationStatements> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+            .into_iter()
+            .filter(|operation| !operation.statements.is_empty())
+            .collect()
+    }
 }
 
-pub struct ConfiguredDBClient {
-    pub client: Client,
-    pub config: ClickHouseConfig,
+/// Renders recorded statements as a SQL script, each operation preceded by
+/// its description.
+pub fn statements_to_sql_script(operations: &[OperationStatements]) -> String {
+    operations
+        .iter()
+        .map(|operation| {
+            let statements: String = operation
+                .statements
+                .iter()
+                .map(|statement| format!("{};\n", statement.trim().trim_end_matches(';')))
+                .collect();
+            format!("-- {}\n{statements}", operation.description)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 /// Creates a configured ClickHouse client with the provided configuration
@@ -1891,6 +3093,7 @@ pub fn create_client(clickhouse_config: ClickHouseConfig) -> ConfiguredDBClient
     ConfiguredDBClient {
         client,
         config: clickhouse_config,
+        recorder: None,
     }
 }
 
@@ -1900,6 +3103,7 @@ pub fn create_readonly_client(clickhouse_config: ClickHouseConfig) -> Configured
     ConfiguredDBClient {
         client: create_base_client(&clickhouse_config),
         config: clickhouse_config,
+        recorder: None,
     }
 }
 
@@ -1944,6 +3148,11 @@ pub async fn run_query(
     query: &str,
     configured_client: &ConfiguredDBClient,
 ) -> Result<(), clickhouse::error::Error> {
+    if let Some(recorder) = &configured_client.recorder {
+        debug!("Recording query: {:?}", query);
+        recorder.record(query);
+        return Ok(());
+    }
     debug!("Running query: {:?}", query);
     build_query(&configured_client.client, query)
         .execute()
@@ -2032,7 +3241,7 @@ pub async fn normalize_sql_via_clickhouse(
 /// - Executes a simple version query
 /// - Implements retry logic for common connection issues
 /// - Handles temporary network failures
-/// - Maximum 20 retries with 200ms delay
+/// - Retries with the `clickhouse` policy, by default 20 times with 200ms delay
 ///
 /// # Retries
 /// Retries on the following conditions:
@@ -2044,31 +3253,36 @@ pub async fn check_ready(
     configured_client: &ConfiguredDBClient,
 ) -> Result<(), clickhouse::error::Error> {
     let dummy_query = "SELECT version()".to_owned();
-    crate::utilities::retry::retry(
+    let policy = retry::policy(
+        RetryTarget::ClickHouse,
+        RetryPolicy::fixed(21, tokio::time::Duration::from_millis(200)),
+    );
+    retry::retry_with(
         || run_query(&dummy_query, configured_client),
-        |i, e| {
-            i < 20
-                && match e {
-                    clickhouse::error::Error::Network(v) => {
-                        let err_string = v.to_string();
-                        debug!("Network error is {}", err_string);
-                        err_string.contains("connection closed before message completed")
-                            || err_string.contains("connection error: Connection reset by peer")
-                            || err_string
-                                .contains("operation was canceled: connection was not ready")
-                            || err_string.contains("channel closed")
-                    }
-                    _ => {
-                        debug!("Error is {} instead of network error. Will not retry.", e);
-                        false
-                    }
-                }
-        },
-        tokio::time::Duration::from_millis(200),
+        |e| std::future::ready(is_connection_not_ready(e)),
+        &policy,
     )
     .await
 }
 
+/// Whether `e` is a network error of a server that is still starting up.
+fn is_connection_not_ready(e: &clickhouse::error::Error) -> bool {
+    match e {
+        clickhouse::error::Error::Network(v) => {
+            let err_string = v.to_string();
+            debug!("Network error is {}", err_string);
+            err_string.contains("connection closed before message completed")
+                || err_string.contains("connection error: Connection reset by peer")
+                || err_string.contains("operation was canceled: connection was not ready")
+                || err_string.contains("channel closed")
+        }
+        _ => {
+            debug!("Error is {} instead of network error. Will not retry.", e);
+            false
+        }
+    }
+}
+
 /// Fetches tables matching a specific version pattern
 ///
 /// # Arguments
@@ -2104,6 +3318,24 @@ pub async fn fetch_tables_with_version(
     Ok(tables)
 }
 
+#[derive(clickhouse::Row, serde::Deserialize)]
+struct ClusterRow {
+    cluster: String,
+}
+
+/// Fetches the names of the clusters known to the server, from `system.clusters`
+pub async fn fetch_cluster_names(
+    configured_client: &ConfiguredDBClient,
+) -> Result<Vec<String>, clickhouse::error::Error> {
+    let rows = configured_client
+        .client
+        .query("SELECT DISTINCT cluster FROM system.clusters ORDER BY cluster")
+        .fetch_all::<ClusterRow>()
+        .await?;
+
+    Ok(rows.into_iter().map(|row| row.cluster).collect())
+}
+
 pub struct TableWithUnsupportedType {
     pub database: String,
     pub name: String,
@@ -2113,31 +3345,18 @@ pub struct TableWithUnsupportedType {
 
 /// Parses column metadata from a comment string
 fn parse_column_metadata(comment: &str) -> Option<ColumnMetadata> {
-    // Check if metadata exists in the comment (could be at the beginning or after user comment)
-    let metadata_start = comment.find(METADATA_PREFIX)?;
-
-    // Extract the JSON part starting from the metadata prefix
-    let json_part = &comment[metadata_start + METADATA_PREFIX.len()..];
-
-    // The metadata JSON should be everything from the prefix to the end
-    // or to the next space if there's content after it (though that shouldn't happen)
-    let json_str = json_part.trim();
-
-    match serde_json::from_str::<ColumnMetadata>(json_str) {
-        Ok(metadata) => Some(metadata),
-        Err(e) => {
-            tracing::warn!("Failed to parse column metadata JSON: {}", e);
-            None
-        }
+    let (_, metadata) = split_column_comment(comment);
+    if metadata.is_none() && comment.contains(METADATA_PREFIX) {
+        tracing::warn!("Failed to parse column metadata from comment: {comment}");
     }
+    metadata
 }
 
 /// Parses an enum definition from metadata comment
 fn parse_enum_from_metadata(comment: &str) -> Option<DataEnum> {
-    let metadata = parse_column_metadata(comment)?;
+    let enum_def = parse_column_metadata(comment)?.enum_def?;
 
-    let values = metadata
-        .enum_def
+    let values = enum_def
         .members
         .into_iter()
         .map(|member| {
@@ -2154,11 +3373,39 @@ fn parse_enum_from_metadata(comment: &str) -> Option<DataEnum> {
         .collect();
 
     Some(DataEnum {
-        name: metadata.enum_def.name,
+        name: enum_def.name,
         values,
     })
 }
 
+/// A row of `system.columns`, as read by `list_tables`
+#[derive(Debug, Clone, PartialEq, clickhouse::Row, serde::Deserialize)]
+struct SystemColumnRow {
+    table: String,
+    name: String,
+    #[serde(rename = "type")]
+    column_type: String,
+    comment: String,
+    is_in_primary_key: u8,
+    is_in_sorting_key: u8,
+    default_kind: String,
+    default_expression: String,
+    compression_codec: String,
+    position: u64,
+}
+
+/// Groups the columns of a database by table, each table's in position order
+fn group_columns_by_table(rows: Vec<SystemColumnRow>) -> HashMap<String, Vec<SystemColumnRow>> {
+    let mut by_table: HashMap<String, Vec<SystemColumnRow>> = HashMap::new();
+    for row in rows {
+        by_table.entry(row.table.clone()).or_default().push(row);
+    }
+    for columns in by_table.values_mut() {
+        columns.sort_by_key(|column| column.position);
+    }
+    by_table
+}
+
 #[async_trait::async_trait]
 impl OlapOperations for ConfiguredDBClient {
     /// Retrieves all tables from the ClickHouse database and converts them to framework Table objects
@@ -2174,7 +3421,7 @@ impl OlapOperations for ConfiguredDBClient {
     /// This implementation:
     /// 1. Queries system.tables for basic table information
     /// 2. Extracts version information from table names
-    /// 3. Queries system.columns for column metadata
+    /// 3. Queries system.columns once for the column metadata of all the tables
     /// 4. Converts ClickHouse types to framework types
     /// 5. Creates Table objects with proper versioning and source primitives
     ///
@@ -2210,24 +3457,52 @@ impl OlapOperations for ConfiguredDBClient {
         );
         debug!("Executing table query: {}", query);
 
-        let mut cursor = self
+        let table_rows = self
             .client
             .query(&query)
-            .fetch::<(String, String, String, String, String)>()
+            .fetch_all::<(String, String, String, String, String)>()
+            .await
             .map_err(|e| {
                 debug!("Error fetching tables: {}", e);
                 OlapChangesError::DatabaseError(e.to_string())
             })?;
 
+        // Get the columns of all the tables at once: a query per table takes
+        // minutes on databases with hundreds of tables
+        let columns_query = format!(
+            r#"
+            SELECT
+                table,
+                name,
+                type,
+                comment,
+                is_in_primary_key,
+                is_in_sorting_key,
+                default_kind,
+                default_expression,
+                compression_codec,
+                position
+            FROM system.columns
+            WHERE database = '{db_name}'
+            ORDER BY table, position
+            "#
+        );
+        debug!("Executing columns query: {}", columns_query);
+        let column_rows = self
+            .client
+            .query(&columns_query)
+            .fetch_all::<SystemColumnRow>()
+            .await
+            .map_err(|e| {
+                debug!("Error fetching columns: {}", e);
+                OlapChangesError::DatabaseError(e.to_string())
+            })?;
+        let mut columns_by_table = group_columns_by_table(column_rows);
+
         let mut tables = Vec::new();
         let mut unsupported_tables = Vec::new();
 
-        'table_loop: while let Some((table_name, database, engine, create_query, partition_key)) =
-            cursor
-                .next()
-                .await
-                .map_err(|e| OlapChangesError::DatabaseError(e.to_string()))?
-        {
+        'table_loop: for (table_name, database, engine, create_query, partition_key) in table_rows {
             debug!("Processing table: {}", table_name);
             debug!("Table engine: {}", engine);
             debug!("Create query: {}", create_query);
@@ -2247,55 +3522,29 @@ impl OlapOperations for ConfiguredDBClient {
                 table_name, has_explicit_primary_key
             );
 
-            // Get column information for each table
-            let columns_query = format!(
-                r#"
-                SELECT
-                    name,
-                    type,
-                    comment,
-                    is_in_primary_key,
-                    is_in_sorting_key,
-                    default_kind,
-                    default_expression,
-                    compression_codec
-                FROM system.columns
-                WHERE database = '{db_name}'
-                AND table = '{table_name}'
-                ORDER BY position
-                "#
-            );
-            debug!(
-                "Executing columns query for table {}: {}",
-                table_name, columns_query
-            );
-
-            let mut columns_cursor = self
-                .client
-                .query(&columns_query)
-                .fetch::<(String, String, String, u8, u8, String, String, String)>()
-                .map_err(|e| {
-                    debug!("Error fetching columns for table {}: {}", table_name, e);
-                    OlapChangesError::DatabaseError(e.to_string())
-                })?;
+            let Some(column_rows) = columns_by_table.remove(&table_name) else {
+                debug!(
+                    "No columns for table {}, it was dropped while listing",
+                    table_name
+                );
+                continue;
+            };
 
             let mut columns = Vec::new();
 
             let column_ttls =
                 extract_column_ttls_from_create_query(&create_query).unwrap_or_default();
-            while let Some((
-                col_name,
-                col_type,
+            for SystemColumnRow {
+                name: col_name,
+                column_type: col_type,
                 comment,
-                is_primary,
-                is_sorting,
+                is_in_primary_key: is_primary,
+                is_in_sorting_key: is_sorting,
                 default_kind,
                 default_expression,
                 compression_codec,
-            )) = columns_cursor
-                .next()
-                .await
-                .map_err(|e| OlapChangesError::DatabaseError(e.to_string()))?
+                ..
+            } in column_rows
             {
                 debug!(
                     "Processing column: {} (type: {}, comment: {}, primary: {}, sorting: {})",
@@ -2303,7 +3552,7 @@ impl OlapOperations for ConfiguredDBClient {
                 );
 
                 // Try to parse enum from metadata comment first if it's an enum type
-                let (data_type, is_nullable) =
+                let (mut data_type, is_nullable) =
                     if col_type.starts_with("Enum") && !comment.is_empty() {
                         // Try to parse from metadata comment
                         if let Some(enum_def) = parse_enum_from_metadata(&comment) {
@@ -2352,6 +3601,14 @@ impl OlapOperations for ConfiguredDBClient {
                         }
                     };
 
+                // ClickHouse does not keep the comments of nested fields, they are
+                // restored from the metadata
+                if let (_, Some(metadata)) = split_column_comment(&comment) {
+                    if !metadata.fields.is_empty() {
+                        data_type.set_nested_field_comments(&metadata.fields);
+                    }
+                }
+
                 // Only set primary_key=true if there's an explicit PRIMARY KEY clause
                 // When only ORDER BY is specified (no PRIMARY KEY), ClickHouse internally
                 // treats ORDER BY columns as primary key, but we shouldn't mark them as such
@@ -2359,22 +3616,9 @@ impl OlapOperations for ConfiguredDBClient {
                 let is_actual_primary_key = has_explicit_primary_key && is_primary == 1;
 
                 // Preserve user comments (strip metadata if present)
-                let column_comment = if !comment.is_empty() {
-                    if let Some(metadata_pos) = comment.find(METADATA_PREFIX) {
-                        // Extract the user comment part (before metadata)
-                        let user_comment = comment[..metadata_pos].trim();
-                        if !user_comment.is_empty() {
-                            Some(user_comment.to_string())
-                        } else {
-                            None
-                        }
-                    } else {
-                        // No metadata, entire comment is user comment
-                        Some(comment.clone())
-                    }
-                } else {
-                    None
-                };
+                let column_comment = Some(split_column_comment(&comment).0)
+                    .filter(|user_comment| !user_comment.is_empty())
+                    .map(str::to_string);
 
                 let (default, materialized, alias) = match default_kind.parse() {
                     Ok(DefaultExpressionKind::Default) => {
@@ -2648,6 +3892,7 @@ impl OlapOperations for ConfiguredDBClient {
                 cluster_name: None,
                 primary_key_expression: final_primary_key_expression,
                 seed_filter: Default::default(),
+                extensions: Default::default(),
             };
             debug!("Created table object: {:?}", table);
 
@@ -2661,7 +3906,8 @@ impl OlapOperations for ConfiguredDBClient {
         Ok((tables, unsupported_tables))
     }
 
-    /// Retrieves all SQL resources (views and materialized views) from the ClickHouse database
+    /// Retrieves all SQL resources (views, materialized views and dictionaries) from the
+    /// ClickHouse database
     ///
     /// # Arguments
     /// * `db_name` - The name of the database to list SQL resources from
@@ -2672,7 +3918,7 @@ impl OlapOperations for ConfiguredDBClient {
     ///
     /// # Details
     /// This implementation:
-    /// 1. Queries system.tables for views and materialized views
+    /// 1. Queries system.tables for views, materialized views and dictionaries
     /// 2. Parses the CREATE statements to extract dependencies
     /// 3. Reconstructs SqlResource objects with setup and teardown scripts
     /// 4. Extracts data lineage (pulls_data_from and pushes_data_to)
@@ -2699,7 +3945,7 @@ impl OlapOperations for ConfiguredDBClient {
                 as_select
             FROM system.tables
             WHERE database = '{}'
-            AND engine IN ('View', 'MaterializedView')
+            AND engine IN ('View', 'MaterializedView', 'Dictionary')
             AND NOT name LIKE '.%'
             ORDER BY name
             "#,
@@ -2738,6 +3984,12 @@ impl OlapOperations for ConfiguredDBClient {
                 "View" => {
                     reconstruct_sql_resource_from_view(name, as_select, database, default_database)?
                 }
+                "Dictionary" => reconstruct_sql_resource_from_dictionary(
+                    name,
+                    create_query,
+                    database,
+                    default_database,
+                )?,
                 _ => {
                     warn!("Unexpected engine type for SQL resource: {}", engine);
                     continue;
@@ -2885,6 +4137,82 @@ fn reconstruct_sql_resource_from_view(
     )
 }
 
+static DICTIONARY_DEFINITION_PATTERN: LazyLock<regex::Regex> = LazyLock::new(|| {
+    // Pattern to extract what follows the name in CREATE DICTIONARY
+    regex::Regex::new(r"(?is)^\s*CREATE\s+DICTIONARY\s+(?:IF\s+NOT\s+EXISTS\s+)?[^\s(]+\s*(.*)$")
+        .expect("DICTIONARY_DEFINITION_PATTERN regex should compile")
+});
+
+/// Reconstructs a SqlResource from a dictionary's CREATE statement
+///
+/// # Arguments
+/// * `name` - The name of the dictionary
+/// * `create_query` - The CREATE DICTIONARY statement from ClickHouse
+/// * `database` - The database where the dictionary is located
+/// * `default_database` - The default database for resolving unqualified table references
+///
+/// # Returns
+/// * `Result<SqlResource, OlapChangesError>` - The reconstructed SqlResource, pulling
+///   data from the source table of the dictionary when it is read from ClickHouse
+fn reconstruct_sql_resource_from_dictionary(
+    name: String,
+    create_query: String,
+    database: String,
+    default_database: &str,
+) -> Result<SqlResource, OlapChangesError> {
+    let definition = DICTIONARY_DEFINITION_PATTERN
+        .captures(&create_query)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().trim())
+        .ok_or_else(|| {
+            OlapChangesError::DatabaseError(format!(
+                "Could not parse the definition of dictionary: {}",
+                name
+            ))
+        })?;
+
+    // Reconstruct without the database, as for views
+    let setup_raw = format!("CREATE DICTIONARY IF NOT EXISTS {} {}", name, definition);
+    let setup = normalize_sql_for_comparison(&setup_raw, default_database);
+    let teardown = format!("DROP DICTIONARY IF EXISTS {}", quote_ident(&name));
+
+    let pulls_data_from = extract_dictionary_source_tables(&create_query)
+        .into_iter()
+        .map(|table_ref| lineage_table_signature(table_ref, default_database))
+        .collect();
+
+    Ok(SqlResource {
+        name,
+        database: Some(database),
+        source_file: None, // Introspected from database, not from user code
+        source_line: None,
+        source_column: None,
+        setup: vec![setup],
+        teardown: vec![teardown],
+        pulls_data_from,
+        pushes_data_to: vec![],
+        life_cycle: Default::default(),
+    })
+}
+
+/// The lineage signature of a table read by an introspected resource: its
+/// name without version suffix, prefixed by its database outside the default one
+fn lineage_table_signature(
+    table_ref: TableReference,
+    default_database: &str,
+) -> InfrastructureSignature {
+    // Get the table name, strip version suffix if present
+    let (base_name, _version) = extract_version_from_table_name(&table_ref.table);
+
+    // Use database from table reference if available, otherwise use default
+    let qualified_id = match table_ref.database {
+        Some(db) if db != default_database => format!("{}_{}", db, base_name),
+        _ => base_name,
+    };
+
+    InfrastructureSignature::Table { id: qualified_id }
+}
+
 /// Common logic for reconstructing SqlResource from MV or View
 fn reconstruct_sql_resource_common(
     name: String,
@@ -2898,7 +4226,7 @@ fn reconstruct_sql_resource_common(
     let setup = normalize_sql_for_comparison(&setup_raw, default_database);
 
     // Generate teardown script
-    let teardown = format!("DROP VIEW IF EXISTS `{}`", name);
+    let teardown = format!("DROP VIEW IF EXISTS {}", quote_ident(&name));
 
     // Parse as_select to get source tables (lineage)
     // Try standard SQL parser first, but fall back to regex if it fails
@@ -2921,24 +4249,7 @@ fn reconstruct_sql_resource_common(
     // Extract pulls_data_from (source tables)
     let pulls_data_from = source_tables
         .into_iter()
-        .map(|table_ref| {
-            // Get the table name, strip version suffix if present
-            let table_name = table_ref.table;
-            let (base_name, _version) = extract_version_from_table_name(&table_name);
-
-            // Use database from table reference if available, otherwise use default
-            let qualified_id = if let Some(db) = table_ref.database {
-                if db == default_database {
-                    base_name
-                } else {
-                    format!("{}_{}", db, base_name)
-                }
-            } else {
-                base_name
-            };
-
-            InfrastructureSignature::Table { id: qualified_id }
-        })
+        .map(|table_ref| lineage_table_signature(table_ref, default_database))
         .collect();
 
     Ok(SqlResource {
@@ -2951,6 +4262,7 @@ fn reconstruct_sql_resource_common(
         teardown: vec![teardown],
         pulls_data_from,
         pushes_data_to,
+        life_cycle: Default::default(),
     })
 }
 
@@ -3152,125 +4464,827 @@ pub fn normalize_ttl_expression(expr: &str) -> String {
 
 use sql_parser::{find_regex_outside_quotes, RE_ENGINE_KEYWORD};
 
-/// Extract column-level TTL expressions from the CREATE TABLE column list.
-/// Returns a map of column name to TTL expression (without leading 'TTL').
-pub fn extract_column_ttls_from_create_query(
-    create_query: &str,
-) -> Option<HashMap<String, String>> {
-    let upper = create_query.to_uppercase();
-    // Columns section is between the first '(' after CREATE TABLE and the closing ')' before ENGINE
-    let open_paren = upper.find('(')?;
-    let engine_pos =
-        find_regex_outside_quotes(create_query, &RE_ENGINE_KEYWORD).map(|m| m.start())?;
-    if engine_pos <= open_paren {
-        return None;
+/// Extract column-level TTL expressions from the CREATE TABLE column list.
+/// Returns a map of column name to TTL expression (without leading 'TTL').
+pub fn extract_column_ttls_from_create_query(
+    create_query: &str,
+) -> Option<HashMap<String, String>> {
+    let upper = create_query.to_uppercase();
+    // Columns section is between the first '(' after CREATE TABLE and the closing ')' before ENGINE
+    let open_paren = upper.find('(')?;
+    let engine_pos =
+        find_regex_outside_quotes(create_query, &RE_ENGINE_KEYWORD).map(|m| m.start())?;
+    if engine_pos <= open_paren {
+        return None;
+    }
+    let columns_block = &create_query[open_paren + 1..engine_pos];
+    let mut map = HashMap::new();
+
+    // Split columns by top-level commas (not inside parentheses or single quotes)
+    let mut col_defs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut depth: i32 = 0;
+    let mut in_string = false;
+    let mut prev: Option<char> = None;
+    for ch in columns_block.chars() {
+        if ch == '\'' && prev != Some('\\') {
+            in_string = !in_string;
+        }
+        if !in_string {
+            if ch == '(' {
+                depth += 1;
+            } else if ch == ')' {
+                if depth > 0 {
+                    depth -= 1;
+                }
+            } else if ch == ',' && depth == 0 {
+                let trimmed = current.trim();
+                if !trimmed.is_empty() {
+                    col_defs.push(trimmed.to_string());
+                }
+                current.clear();
+                prev = Some(ch);
+                continue;
+            }
+        }
+        current.push(ch);
+        prev = Some(ch);
+    }
+    let trimmed = current.trim();
+    if !trimmed.is_empty() {
+        col_defs.push(trimmed.to_string());
+    }
+
+    for def in col_defs {
+        let line_trim = def.trim();
+        // Expect defs like: `col` Type ... [TTL expr] ...
+        if !line_trim.starts_with('`') {
+            continue;
+        }
+        // Extract column name between the first pair of backticks
+        let first_bt = 0; // starts with backtick
+        let second_bt = match line_trim[1..].find('`') {
+            Some(pos) => 1 + pos,
+            None => continue,
+        };
+        let col_name = &line_trim[first_bt + 1..second_bt];
+
+        // Find TTL clause within this column definition, ignoring
+        // occurrences of " TTL " inside single-quoted COMMENT strings.
+        static RE_TTL: LazyLock<regex::Regex> =
+            LazyLock::new(|| regex::Regex::new(r"(?i) TTL ").unwrap());
+        static RE_DEFAULT_OR_COMMENT: LazyLock<regex::Regex> =
+            LazyLock::new(|| regex::Regex::new(r"(?i) (?:DEFAULT\s|COMMENT\s*')").unwrap());
+
+        if let Some(m) = find_regex_outside_quotes(line_trim, &RE_TTL) {
+            let after = &line_trim[m.end()..];
+            let mut cut = after.len();
+
+            if let Some(m2) = find_regex_outside_quotes(after, &RE_DEFAULT_OR_COMMENT) {
+                cut = cut.min(m2.start());
+            }
+
+            // Find the closing parenthesis at depth 0 (the one that ends the column list)
+            let mut depth = 0;
+            for (i, ch) in after.char_indices() {
+                if i >= cut {
+                    break;
+                }
+                match ch {
+                    '(' => depth += 1,
+                    ')' => {
+                        if depth == 0 {
+                            // This is the closing parenthesis of the column list
+                            cut = cut.min(i);
+                            break;
+                        }
+                        depth -= 1;
+                    }
+                    _ => {}
+                }
+            }
+
+            let expr = after[..cut].trim();
+            if !expr.is_empty() {
+                map.insert(col_name.to_string(), expr.to_string());
+            }
+        }
+    }
+
+    if map.is_empty() {
+        None
+    } else {
+        Some(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::olap::clickhouse::model::{ClickHouseColumnType, ClickHouseInt};
+    use crate::infrastructure::olap::clickhouse::sql_parser::tests::NESTED_OBJECTS_SQL;
+
+    #[derive(Default)]
+    struct CollectingProgress {
+        events: std::sync::Mutex<Vec<DdlProgressEvent>>,
+        slow_threshold: Option<std::time::Duration>,
+    }
+
+    impl DdlProgress for CollectingProgress {
+        fn on_event(&self, event: DdlProgressEvent) {
+            self.events.lock().unwrap().push(event);
+        }
+
+        fn slow_operation_threshold(&self) -> Option<std::time::Duration> {
+            self.slow_threshold
+        }
+    }
+
+    impl CollectingProgress {
+        /// The events without their durations
+        fn summary(&self) -> Vec<String> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|event| match event {
+                    DdlProgressEvent::PlanStarted {
+                        teardown_operations,
+                        setup_operations,
+                    } => format!("plan started {teardown_operations}+{setup_operations}"),
+                    DdlProgressEvent::OperationStarted { description } => {
+                        format!("started {description}")
+                    }
+                    DdlProgressEvent::OperationSlow { description, .. } => {
+                        format!("slow {description}")
+                    }
+                    DdlProgressEvent::OperationSucceeded { description, .. } => {
+                        format!("succeeded {description}")
+                    }
+                    DdlProgressEvent::OperationFailed {
+                        description, error, ..
+                    } => format!("failed {description}: {error}"),
+                    DdlProgressEvent::PlanFinished {
+                        succeeded, failed, ..
+                    } => format!("plan finished {succeeded} ok {failed} failed"),
+                })
+                .collect()
+        }
+    }
+
+    fn view_operation(name: &str, create: bool) -> AtomicOlapOperation {
+        let view =
+            crate::framework::core::infrastructure::view::View::new(name, "SELECT 1", vec![]);
+        let dependency_info = Default::default();
+        if create {
+            AtomicOlapOperation::CreateView {
+                view,
+                dependency_info,
+            }
+        } else {
+            AtomicOlapOperation::DropView {
+                view,
+                dependency_info,
+            }
+        }
+    }
+
+    /// Fails the operations on the view `failing`
+    fn run_failing_on(
+        failing: &'static str,
+    ) -> impl FnMut(&[AtomicOlapOperation]) -> std::future::Ready<Result<(), ClickhouseChangesError>>
+    {
+        move |operations| {
+            let fails = operations.iter().any(|op| {
+                matches!(
+                    op,
+                    AtomicOlapOperation::CreateView { view, .. }
+                        | AtomicOlapOperation::DropView { view, .. } if view.name == failing
+                )
+            });
+            std::future::ready(if fails {
+                Err(ClickhouseChangesError::NotSupported("boom".to_string()))
+            } else {
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_plans_reports_progress() {
+        let teardown = [view_operation("old", false)];
+        let setup = [
+            view_operation("a", true),
+            view_operation("b", true),
+            view_operation("c", true),
+        ];
+        let progress = CollectingProgress::default();
+
+        let result = apply_plans(
+            &teardown,
+            &setup,
+            "local",
+            1,
+            &progress,
+            run_failing_on("b"),
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(ClickhouseChangesError::NotSupported(_))
+        ));
+        assert_eq!(
+            progress.summary(),
+            [
+                "plan started 1+3",
+                "started Dropping custom view 'old'",
+                "succeeded Dropping custom view 'old'",
+                "started Creating custom view 'a'",
+                "succeeded Creating custom view 'a'",
+                "started Creating custom view 'b'",
+                "failed Creating custom view 'b': Not Supported boom",
+                "started Creating custom view 'c'",
+                "succeeded Creating custom view 'c'",
+                "plan finished 3 ok 1 failed",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_plans_skips_setup_after_failed_teardown() {
+        let teardown = [view_operation("old", false)];
+        let setup = [view_operation("a", true)];
+        let progress = CollectingProgress::default();
+
+        let result = apply_plans(
+            &teardown,
+            &setup,
+            "local",
+            4,
+            &progress,
+            run_failing_on("old"),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(
+            progress.summary(),
+            [
+                "plan started 1+1",
+                "started Dropping custom view 'old'",
+                "failed Dropping custom view 'old': Not Supported boom",
+                "plan finished 0 ok 1 failed",
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_apply_plans_reports_slow_operations() {
+        let setup = [view_operation("fast", true), view_operation("slow", true)];
+        let progress = CollectingProgress {
+            slow_threshold: Some(std::time::Duration::from_secs(30)),
+            ..Default::default()
+        };
+
+        // The view `slow` takes a minute to create
+        apply_plans(&[], &setup, "local", 1, &progress, |operations| {
+            let slow = matches!(
+                &operations[0],
+                AtomicOlapOperation::CreateView { view, .. } if view.name == "slow"
+            );
+            async move {
+                let seconds = if slow { 60 } else { 1 };
+                tokio::time::sleep(std::time::Duration::from_secs(seconds)).await;
+                Ok(())
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            progress.summary(),
+            [
+                "plan started 0+2",
+                "started Creating custom view 'fast'",
+                "succeeded Creating custom view 'fast'",
+                "started Creating custom view 'slow'",
+                "slow Creating custom view 'slow'",
+                "succeeded Creating custom view 'slow'",
+                "plan finished 2 ok 0 failed",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_console_progress_keeps_timings_slowest_first() {
+        let progress = crate::infrastructure::olap::ddl_progress::ConsoleProgress::new(None);
+        for (description, millis, succeeded) in [("a", 20, true), ("b", 50, false), ("c", 10, true)]
+        {
+            let duration = std::time::Duration::from_millis(millis);
+            progress.on_event(if succeeded {
+                DdlProgressEvent::OperationSucceeded {
+                    description: description.to_string(),
+                    table: Some(format!("local.{description}")),
+                    duration,
+                }
+            } else {
+                DdlProgressEvent::OperationFailed {
+                    description: description.to_string(),
+                    table: None,
+                    duration,
+                    error: "boom".to_string(),
+                }
+            });
+        }
+
+        let timings = progress.timings();
+        let order: Vec<(&str, bool)> = timings
+            .iter()
+            .map(|timing| (timing.description.as_str(), timing.succeeded))
+            .collect();
+        assert_eq!(order, [("b", false), ("a", true), ("c", true)]);
+        assert_eq!(timings[1].table.as_deref(), Some("local.a"));
+    }
+
+    fn fixture_table(name: &str) -> Table {
+        let column = |name: &str, data_type: ColumnType, default: Option<&str>| Column {
+            name: name.to_string(),
+            data_type,
+            required: true,
+            unique: false,
+            primary_key: name == "id",
+            default: default.map(str::to_string),
+            annotations: vec![],
+            comment: None,
+            ttl: None,
+            codec: None,
+            materialized: None,
+            alias: None,
+        };
+        Table {
+            name: name.to_string(),
+            columns: vec![
+                column("id", ColumnType::String, None),
+                column(
+                    "count",
+                    ColumnType::Int(crate::framework::core::infrastructure::table::IntType::Int32),
+                    Some("0"),
+                ),
+            ],
+            order_by: OrderBy::Fields(vec!["id".to_string()]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    /// Plans creating databases, tables, and running column changes made of
+    /// several statements
+    fn fixture_plans() -> (Vec<AtomicOlapOperation>, Vec<AtomicOlapOperation>) {
+        let table = fixture_table("events");
+        let before_column = table.columns[1].clone();
+        let after_column = Column {
+            default: None,
+            ..before_column.clone()
+        };
+        let mut added_column = before_column.clone();
+        added_column.name = "total".to_string();
+
+        let teardown = vec![AtomicOlapOperation::DropTable {
+            table: fixture_table("old_events"),
+            dependency_info: Default::default(),
+        }];
+        let setup = vec![
+            AtomicOlapOperation::CreateTable {
+                table: table.clone(),
+                dependency_info: Default::default(),
+            },
+            AtomicOlapOperation::ModifyTableColumn {
+                table: table.clone(),
+                before_column,
+                after_column,
+                dependency_info: Default::default(),
+            },
+            AtomicOlapOperation::AddTableColumn {
+                table,
+                column: added_column,
+                after_column: Some("count".to_string()),
+                dependency_info: Default::default(),
+            },
+        ];
+        (teardown, setup)
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_records_statements_sent_by_execution() {
+        let config = ClickHouseConfig {
+            additional_databases: vec!["archive".to_string()],
+            ..Default::default()
+        };
+        let (teardown, setup) = fixture_plans();
+
+        let recorder = StatementRecorder::default();
+        let dry_run_client = create_client(config.clone()).with_recorder(recorder.clone());
+        apply_changes(&dry_run_client, false, &teardown, &setup, &NoProgress)
+            .await
+            .unwrap();
+        let recorded = recorder.take();
+
+        let descriptions: Vec<&str> = recorded
+            .iter()
+            .map(|operation| operation.description.as_str())
+            .collect();
+        assert_eq!(
+            descriptions,
+            [
+                format!("Creating database '{}'", config.db_name).as_str(),
+                "Creating database 'archive'",
+                "Dropping table 'old_events'",
+                "Creating table 'events'",
+                "Modifying column 'count' in table 'events'",
+                "Adding column 'total' to table 'events'",
+            ]
+        );
+        // Removing the default is a statement of its own
+        assert_eq!(recorded[4].statements.len(), 2);
+        assert!(recorded[4].statements[0].ends_with("REMOVE DEFAULT"));
+
+        // A real execution sends the same statements, in the same order
+        let statements: Vec<String> = recorded
+            .iter()
+            .flat_map(|operation| operation.statements.clone())
+            .collect();
+        let mock = clickhouse::test::Mock::new();
+        let controls: Vec<_> = statements
+            .iter()
+            .map(|_| mock.add(clickhouse::test::handlers::record_ddl()))
+            .collect();
+        let client = ConfiguredDBClient {
+            client: Client::default().with_url(mock.url()),
+            config,
+            recorder: None,
+        };
+        apply_changes(&client, false, &teardown, &setup, &NoProgress)
+            .await
+            .unwrap();
+
+        let mut sent = vec![];
+        for control in controls {
+            sent.push(control.query().await.trim().to_string());
+        }
+        assert_eq!(sent, statements);
+    }
+
+    #[tokio::test]
+    async fn test_column_changes_of_a_table_run_as_one_statement() {
+        let config = ClickHouseConfig::default();
+        let table = fixture_table("events");
+        let count = table.columns[1].clone();
+        let mut total = count.clone();
+        total.name = "total".to_string();
+        let plan = vec![
+            AtomicOlapOperation::AddTableColumn {
+                table: table.clone(),
+                column: total,
+                after_column: Some("count".to_string()),
+                dependency_info: Default::default(),
+            },
+            AtomicOlapOperation::DropTableColumn {
+                table: table.clone(),
+                column_name: "legacy".to_string(),
+                dependency_info: Default::default(),
+            },
+            AtomicOlapOperation::ModifyTableColumn {
+                table: table.clone(),
+                before_column: count.clone(),
+                after_column: Column {
+                    comment: Some("Number of events".to_string()),
+                    ..count
+                },
+                dependency_info: Default::default(),
+            },
+            AtomicOlapOperation::DropTableColumn {
+                table: fixture_table("users"),
+                column_name: "legacy".to_string(),
+                dependency_info: Default::default(),
+            },
+        ];
+
+        let recorder = StatementRecorder::default();
+        let client = create_client(config.clone()).with_recorder(recorder.clone());
+        apply_changes(&client, false, &[], &plan, &NoProgress)
+            .await
+            .unwrap();
+        let recorded = recorder.take();
+
+        let events = format!("`{}`.`events`", config.db_name);
+        let batch = recorded
+            .iter()
+            .find(|operation| operation.statements[0].contains(&events))
+            .unwrap();
+        assert_eq!(
+            batch.description,
+            "Adding column 'total' to table 'events'; Dropping column 'legacy' from table 'events'; Modifying column 'count' in table 'events'"
+        );
+        assert_eq!(batch.statements.len(), 1);
+        let statement = &batch.statements[0];
+        assert!(statement.starts_with(&format!("ALTER TABLE {events} ADD COLUMN `total`")));
+        assert!(statement.contains(", DROP COLUMN IF EXISTS `legacy`, MODIFY COLUMN `count`"));
+        assert_eq!(statement.matches("ALTER TABLE").count(), 1);
+
+        // Another table is altered by a statement of its own
+        assert!(recorded.iter().any(|operation| operation.statements
+            == [format!(
+                "ALTER TABLE `{}`.`users` DROP COLUMN IF EXISTS `legacy`",
+                config.db_name
+            )]));
+    }
+
+    #[test]
+    fn test_coalesce_column_operations() {
+        let table = fixture_table("events");
+        let count = table.columns[1].clone();
+        let add = |name: &str, after: &str| {
+            let mut column = count.clone();
+            column.name = name.to_string();
+            SerializableOlapOperation::AddTableColumn {
+                table: "events".to_string(),
+                column,
+                after_column: Some(after.to_string()),
+                database: None,
+                cluster_name: None,
+            }
+        };
+        let rename = |before: &str, after: &str| SerializableOlapOperation::RenameTableColumn {
+            table: "events".to_string(),
+            before_column_name: before.to_string(),
+            after_column_name: after.to_string(),
+            database: None,
+            cluster_name: None,
+        };
+        let modify = |name: &str, default: Option<&str>| {
+            let before = Column {
+                name: name.to_string(),
+                ..count.clone()
+            };
+            SerializableOlapOperation::ModifyTableColumn {
+                table: "events".to_string(),
+                after_column: Column {
+                    data_type: ColumnType::String,
+                    default: default.map(str::to_string),
+                    ..before.clone()
+                },
+                before_column: before,
+                database: None,
+                cluster_name: None,
+            }
+        };
+
+        let plan = vec![
+            add("a", "id"),
+            rename("b", "c"),
+            // Changes the renamed column
+            modify("c", Some("0")),
+            add("d", "id"),
+            // Needs removing the default first
+            modify("e", None),
+            rename("x", "y"),
+            // Added after a column the batch renamed
+            add("g", "x"),
+            SerializableOlapOperation::DropTable {
+                table: "users".to_string(),
+                database: None,
+                cluster_name: None,
+            },
+            add("h", "id"),
+            // Same table name in another database
+            SerializableOlapOperation::AddTableColumn {
+                database: Some("archive".to_string()),
+                ..add("i", "id")
+            },
+        ];
+        assert_eq!(
+            coalesce_column_operations(&plan, "local"),
+            vec![0..2, 2..4, 4..5, 5..6, 6..7, 7..8, 8..9, 9..10]
+        );
     }
-    let columns_block = &create_query[open_paren + 1..engine_pos];
-    let mut map = HashMap::new();
 
-    // Split columns by top-level commas (not inside parentheses or single quotes)
-    let mut col_defs: Vec<String> = Vec::new();
-    let mut current = String::new();
-    let mut depth: i32 = 0;
-    let mut in_string = false;
-    let mut prev: Option<char> = None;
-    for ch in columns_block.chars() {
-        if ch == '\'' && prev != Some('\\') {
-            in_string = !in_string;
-        }
-        if !in_string {
-            if ch == '(' {
-                depth += 1;
-            } else if ch == ')' {
-                if depth > 0 {
-                    depth -= 1;
-                }
-            } else if ch == ',' && depth == 0 {
-                let trimmed = current.trim();
-                if !trimmed.is_empty() {
-                    col_defs.push(trimmed.to_string());
-                }
-                current.clear();
-                prev = Some(ch);
-                continue;
-            }
-        }
-        current.push(ch);
-        prev = Some(ch);
+    #[test]
+    fn test_batch_dependencies() {
+        let dependencies = vec![vec![], vec![0], vec![], vec![1, 2], vec![0, 3]];
+        assert_eq!(
+            batch_dependencies(&dependencies, &[0..1, 1..3, 3..5]),
+            vec![vec![], vec![0], vec![0, 1]]
+        );
     }
-    let trimmed = current.trim();
-    if !trimmed.is_empty() {
-        col_defs.push(trimmed.to_string());
+
+    #[test]
+    fn test_group_columns_by_table() {
+        let row = |table: &str, name: &str, position: u64| SystemColumnRow {
+            table: table.to_string(),
+            name: name.to_string(),
+            column_type: "String".to_string(),
+            comment: String::new(),
+            is_in_primary_key: 0,
+            is_in_sorting_key: 0,
+            default_kind: String::new(),
+            default_expression: String::new(),
+            compression_codec: String::new(),
+            position,
+        };
+        let grouped = group_columns_by_table(vec![
+            row("users", "name", 2),
+            row("events", "id", 1),
+            row("users", "id", 1),
+            row("events", "ts", 2),
+        ]);
+
+        let names = |table: &str| -> Vec<&str> {
+            grouped[table]
+                .iter()
+                .map(|column| column.name.as_str())
+                .collect()
+        };
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(names("events"), ["id", "ts"]);
+        assert_eq!(names("users"), ["id", "name"]);
     }
 
-    for def in col_defs {
-        let line_trim = def.trim();
-        // Expect defs like: `col` Type ... [TTL expr] ...
-        if !line_trim.starts_with('`') {
-            continue;
-        }
-        // Extract column name between the first pair of backticks
-        let first_bt = 0; // starts with backtick
-        let second_bt = match line_trim[1..].find('`') {
-            Some(pos) => 1 + pos,
-            None => continue,
+    #[tokio::test]
+    async fn test_added_projection_is_materialized() {
+        let recorder = StatementRecorder::default();
+        let client = create_client(ClickHouseConfig::default()).with_recorder(recorder.clone());
+        let operation = SerializableOlapOperation::AddTableProjection {
+            table: "events".to_string(),
+            projection: TableProjection {
+                name: "by_user".to_string(),
+                body: "SELECT _part_offset ORDER BY user_id".to_string(),
+            },
+            database: None,
+            cluster_name: Some("main".to_string()),
         };
-        let col_name = &line_trim[first_bt + 1..second_bt];
 
-        // Find TTL clause within this column definition, ignoring
-        // occurrences of " TTL " inside single-quoted COMMENT strings.
-        static RE_TTL: LazyLock<regex::Regex> =
-            LazyLock::new(|| regex::Regex::new(r"(?i) TTL ").unwrap());
-        static RE_DEFAULT_OR_COMMENT: LazyLock<regex::Regex> =
-            LazyLock::new(|| regex::Regex::new(r"(?i) (?:DEFAULT\s|COMMENT\s*')").unwrap());
+        execute_atomic_operation("local", &operation, &client, false)
+            .await
+            .unwrap();
 
-        if let Some(m) = find_regex_outside_quotes(line_trim, &RE_TTL) {
-            let after = &line_trim[m.end()..];
-            let mut cut = after.len();
+        let recorded = recorder.take();
+        assert_eq!(
+            recorded[0].statements,
+            [
+                "ALTER TABLE `local`.`events` ON CLUSTER `main` ADD PROJECTION IF NOT EXISTS `by_user` (SELECT _part_offset ORDER BY user_id)",
+                "ALTER TABLE `local`.`events` ON CLUSTER `main` MATERIALIZE PROJECTION `by_user`",
+            ]
+        );
+    }
 
-            if let Some(m2) = find_regex_outside_quotes(after, &RE_DEFAULT_OR_COMMENT) {
-                cut = cut.min(m2.start());
-            }
+    #[tokio::test]
+    async fn test_sort_key_is_extended_in_the_statement_adding_its_column() {
+        let config = ClickHouseConfig::default();
+        let mut table = fixture_table("events");
+        let mut region = table.columns[0].clone();
+        region.name = "region".to_string();
+        region.primary_key = false;
+        table.columns.push(region.clone());
+        table.order_by = OrderBy::Fields(vec!["id".to_string(), "region".to_string()]);
+        let plan = vec![
+            AtomicOlapOperation::AddTableColumn {
+                table: table.clone(),
+                column: region,
+                after_column: Some("count".to_string()),
+                dependency_info: Default::default(),
+            },
+            AtomicOlapOperation::ModifyOrderBy {
+                table: table.clone(),
+                expression: "(id, region)".to_string(),
+                dependency_info: Default::default(),
+            },
+        ];
 
-            // Find the closing parenthesis at depth 0 (the one that ends the column list)
-            let mut depth = 0;
-            for (i, ch) in after.char_indices() {
-                if i >= cut {
-                    break;
-                }
-                match ch {
-                    '(' => depth += 1,
-                    ')' => {
-                        if depth == 0 {
-                            // This is the closing parenthesis of the column list
-                            cut = cut.min(i);
-                            break;
-                        }
-                        depth -= 1;
-                    }
-                    _ => {}
-                }
-            }
+        let recorder = StatementRecorder::default();
+        let client = create_client(config.clone()).with_recorder(recorder.clone());
+        apply_changes(&client, false, &[], &plan, &NoProgress)
+            .await
+            .unwrap();
+        let recorded = recorder.take();
 
-            let expr = after[..cut].trim();
-            if !expr.is_empty() {
-                map.insert(col_name.to_string(), expr.to_string());
-            }
-        }
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(
+            recorded[0].description,
+            "Adding column 'region' to table 'events'; Modifying ORDER BY to '(id, region)' for table 'events'"
+        );
+        let statement = &recorded[0].statements[0];
+        assert!(statement.starts_with(&format!(
+            "ALTER TABLE `{}`.`events` ADD COLUMN `region`",
+            config.db_name
+        )));
+        assert!(statement.ends_with(", MODIFY ORDER BY (id, region)"));
+
+        assert_eq!(
+            modify_order_by_query("local", "events", "(id, region)", Some("main")),
+            "ALTER TABLE `local`.`events` ON CLUSTER `main` MODIFY ORDER BY (id, region)"
+        );
     }
 
-    if map.is_empty() {
-        None
-    } else {
-        Some(map)
+    #[tokio::test]
+    async fn test_dry_run_records_raw_sql_verbatim() {
+        let recorder = StatementRecorder::default();
+        let client = create_client(ClickHouseConfig::default()).with_recorder(recorder.clone());
+        let operation = SerializableOlapOperation::RawSql {
+            sql: vec![
+                "CREATE VIEW v AS SELECT * FROM events".to_string(),
+                "  ".to_string(),
+                "INSERT INTO totals SELECT count() FROM events".to_string(),
+            ],
+            description: "Creating the totals".to_string(),
+        };
+
+        execute_atomic_operation("local", &operation, &client, false)
+            .await
+            .unwrap();
+
+        let recorded = recorder.take();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].description, "Creating the totals");
+        // Blank statements are not run
+        assert_eq!(
+            recorded[0].statements,
+            [
+                "CREATE VIEW v AS SELECT * FROM events",
+                "INSERT INTO totals SELECT count() FROM events"
+            ]
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::infrastructure::olap::clickhouse::model::{ClickHouseColumnType, ClickHouseInt};
-    use crate::infrastructure::olap::clickhouse::sql_parser::tests::NESTED_OBJECTS_SQL;
+    #[test]
+    fn test_statements_to_sql_script() {
+        let operations = [
+            OperationStatements {
+                description: "Creating table 'events'".to_string(),
+                statements: vec!["CREATE TABLE events (id String)".to_string()],
+            },
+            OperationStatements {
+                description: "Modifying column 'count' in table 'events'".to_string(),
+                statements: vec![
+                    "ALTER TABLE events MODIFY COLUMN count REMOVE DEFAULT;".to_string(),
+                    "ALTER TABLE events MODIFY COLUMN count Int32\n".to_string(),
+                ],
+            },
+        ];
+
+        assert_eq!(
+            statements_to_sql_script(&operations),
+            "-- Creating table 'events'\n\
+             CREATE TABLE events (id String);\n\
+             \n\
+             -- Modifying column 'count' in table 'events'\n\
+             ALTER TABLE events MODIFY COLUMN count REMOVE DEFAULT;\n\
+             ALTER TABLE events MODIFY COLUMN count Int32;\n"
+        );
+        assert_eq!(statements_to_sql_script(&[]), "");
+    }
+
+    #[test]
+    fn test_clickhouse_client_error_includes_guidance() {
+        let error = ClickhouseChangesError::ClickhouseClient {
+            error: clickhouse::error::Error::BadResponse(
+                "Code: 57. DB::Exception: Table local.users already exists. (TABLE_ALREADY_EXISTS)"
+                    .to_string(),
+            ),
+            resource: Some("users".to_string()),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Error interacting with Clickhouse for 'users' (Code 57: the table already exists. \
+             Drop or rename the existing table, or run `moose db pull` to adopt it)"
+        );
+
+        // Codes without guidance keep the plain message
+        let error = ClickhouseChangesError::ClickhouseClient {
+            error: clickhouse::error::Error::BadResponse("Code: 62. Syntax error".to_string()),
+            resource: None,
+        };
+        assert_eq!(error.to_string(), "Error interacting with Clickhouse");
+    }
 
     #[test]
     fn test_extract_version_from_table_name() {
@@ -3346,14 +5360,39 @@ mod tests {
         assert_eq!(base_name, "Bar");
         assert!(version.is_none());
 
-        // PeerDB-style table names with UUIDs: digit-only segments are treated as versions.
-        // The leading underscore is lost because empty split parts are filtered out.
-        // This is why externally managed tables skip version extraction entirely in generate.rs.
+        // PeerDB-style table names with UUIDs: digit-only segments inside the name
+        // are not a version, as the name does not end with numeric parts.
         let (base_name, version) = extract_version_from_table_name(
             "_peerdb_raw_mirror_a1b2c3d4_e5f6_7890_abcd_ef1234567890",
         );
-        assert_eq!(base_name, "peerdb_raw_mirror_a1b2c3d4_e5f6");
-        assert_eq!(version.unwrap().to_string(), "7890");
+        assert_eq!(
+            base_name,
+            "_peerdb_raw_mirror_a1b2c3d4_e5f6_7890_abcd_ef1234567890"
+        );
+        assert!(version.is_none());
+
+        // Numeric parts before the version belong to the base name
+        let (base_name, version) = extract_version_from_table_name("iot_2024_events_1_0_0");
+        assert_eq!(base_name, "iot_2024_events");
+        assert_eq!(version.unwrap().to_string(), "1.0.0");
+
+        let (base_name, version) = extract_version_from_table_name("metrics_5m");
+        assert_eq!(base_name, "metrics_5m");
+        assert!(version.is_none());
+
+        let (base_name, version) = extract_version_from_table_name("t_10_2_3");
+        assert_eq!(base_name, "t");
+        assert_eq!(version.unwrap().to_string(), "10.2.3");
+
+        // A single trailing number is part of the name, not a version
+        let (base_name, version) = extract_version_from_table_name("events_2024");
+        assert_eq!(base_name, "events_2024");
+        assert!(version.is_none());
+
+        // At most 4 parts are read as the version
+        let (base_name, version) = extract_version_from_table_name("logs_2024_1_0_0_1");
+        assert_eq!(base_name, "logs_2024");
+        assert_eq!(version.unwrap().to_string(), "1.0.0.1");
     }
 
     #[test]
@@ -3584,6 +5623,58 @@ SETTINGS enable_mixed_granularity_parts = 1, index_granularity = 8192, index_gra
         );
     }
 
+    #[test]
+    fn test_nested_field_comment_change_is_comment_only() {
+        use crate::framework::core::infrastructure::table::Nested;
+
+        let field = |name: &str, comment: Option<&str>| Column {
+            name: name.to_string(),
+            data_type: ColumnType::String,
+            required: true,
+            unique: false,
+            primary_key: false,
+            default: None,
+            annotations: vec![],
+            comment: comment.map(str::to_string),
+            ttl: None,
+            codec: None,
+            materialized: None,
+            alias: None,
+        };
+        let address = |city_comment: &str| Column {
+            data_type: ColumnType::Nested(Nested {
+                name: "Address".to_string(),
+                columns: vec![field("street", None), field("city", Some(city_comment))],
+                jwt: false,
+            }),
+            ..field("address", Some("Shipping address"))
+        };
+        let before = address("City name");
+        let after = address("City or town");
+
+        let sqls = modify_table_column_queries("db", "table", &before, &after, None).unwrap();
+        assert_eq!(
+            sqls,
+            [format!(
+                "ALTER TABLE `db`.`table` MODIFY COLUMN `address` COMMENT 'Shipping address {METADATA_PREFIX}{{\"version\":1,\"fields\":[{{\"name\":\"city\",\"comment\":\"City or town\"}}]}}'"
+            )]
+        );
+
+        // The comments are restored on the type read back from ClickHouse
+        let comment = std_column_to_clickhouse_column(after.clone())
+            .unwrap()
+            .comment
+            .unwrap();
+        let (user_comment, metadata) = split_column_comment(&comment);
+        assert_eq!(user_comment, "Shipping address");
+        let mut data_type = after.data_type.clone();
+        data_type.set_nested_field_comments(&[]);
+        assert_ne!(data_type, after.data_type);
+        assert!(data_type.eq_ignoring_field_comments(&after.data_type));
+        data_type.set_nested_field_comments(&metadata.unwrap().fields);
+        assert_eq!(data_type, after.data_type);
+    }
+
     #[test]
     fn test_modify_nullable_column_with_default() {
         use crate::framework::core::infrastructure::table::Column;
@@ -4290,6 +6381,7 @@ SETTINGS enable_mixed_granularity_parts = 1, index_granularity = 8192, index_gra
             table_ttl_setting: Some("created_at + INTERVAL 30 DAY".to_string()),
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let ignore_ops = vec![
@@ -4363,6 +6455,7 @@ SETTINGS enable_mixed_granularity_parts = 1, index_granularity = 8192, index_gra
             table_ttl_setting: Some("created_at + INTERVAL 30 DAY".to_string()),
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let ignore_ops = vec![];
@@ -4459,6 +6552,7 @@ SETTINGS enable_mixed_granularity_parts = 1, index_granularity = 8192, index_gra
             table_ttl_setting: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let ignore_ops = vec![IgnorableOperation::IgnoreStringLowCardinalityDifferences];
@@ -4499,6 +6593,42 @@ SETTINGS enable_mixed_granularity_parts = 1, index_granularity = 8192, index_gra
         assert_eq!(normalized.order_by, table.order_by);
     }
 
+    #[test]
+    fn test_ignorable_operations_parse_with_suggestions() {
+        let parsed: Vec<IgnorableOperation> =
+            serde_json::from_str(r#"["ModifyTableSettings", "AddTableIndex", "DropTableIndex"]"#)
+                .unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                IgnorableOperation::ModifyTableSettings,
+                IgnorableOperation::AddTableIndex,
+                IgnorableOperation::DropTableIndex,
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&IgnorableOperation::DropTableIndex).unwrap(),
+            r#""DropTableIndex""#
+        );
+
+        let err = serde_json::from_str::<Vec<IgnorableOperation>>(r#"["ModifyTableSetings"]"#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("did you mean 'ModifyTableSettings'?"),
+            "unexpected error: {err}"
+        );
+
+        let drop_index = SerializableOlapOperation::DropTableIndex {
+            table: "events".to_string(),
+            index_name: "idx_manual".to_string(),
+            database: None,
+            cluster_name: None,
+        };
+        assert!(IgnorableOperation::DropTableIndex.matches(&drop_index));
+        assert!(!IgnorableOperation::AddTableIndex.matches(&drop_index));
+    }
+
     #[test]
     fn test_reconstruct_sql_resource_from_mv_with_standard_sql() {
         let create_query =
@@ -4606,6 +6736,33 @@ SETTINGS enable_mixed_granularity_parts = 1, index_granularity = 8192, index_gra
         }
     }
 
+    #[test]
+    fn test_reconstruct_sql_resource_from_dictionary() {
+        let create_query = "CREATE DICTIONARY local.products_dict (`id` UInt64, `name` String) PRIMARY KEY id SOURCE(CLICKHOUSE(TABLE 'products' DB 'local')) LIFETIME(MIN 0 MAX 300) LAYOUT(HASHED())".to_string();
+
+        let result = reconstruct_sql_resource_from_dictionary(
+            "products_dict".to_string(),
+            create_query,
+            "local".to_string(),
+            "local",
+        )
+        .unwrap();
+
+        assert_eq!(
+            result.teardown,
+            vec!["DROP DICTIONARY IF EXISTS `products_dict`"]
+        );
+        assert!(result.setup[0].starts_with("CREATE DICTIONARY IF NOT EXISTS products_dict"));
+        assert!(result.setup[0].contains("HASHED"));
+        assert_eq!(
+            result.pulls_data_from,
+            vec![InfrastructureSignature::Table {
+                id: "products".to_string()
+            }]
+        );
+        assert!(result.pushes_data_to.is_empty());
+    }
+
     #[test]
     fn test_codec_wrapper_stripping() {
         let test_cases = vec![
@@ -4802,4 +6959,118 @@ SETTINGS enable_mixed_granularity_parts = 1, index_granularity = 8192, index_gra
             "query without `?` should be unchanged"
         );
     }
+
+    #[test]
+    fn test_operation_sql_preview() {
+        let drop = SerializableOlapOperation::DropTableColumn {
+            table: "events".to_string(),
+            column_name: "legacy".to_string(),
+            database: None,
+            cluster_name: None,
+        };
+        assert_eq!(
+            super::operation_sql_preview("local", &drop).unwrap(),
+            vec!["ALTER TABLE `local`.`events` DROP COLUMN IF EXISTS `legacy`"]
+        );
+
+        let ttl = SerializableOlapOperation::ModifyTableTtl {
+            table: "events".to_string(),
+            before: None,
+            after: Some("ts + toIntervalDay(30)".to_string()),
+            database: Some("analytics".to_string()),
+            cluster_name: None,
+        };
+        assert_eq!(
+            super::operation_sql_preview("local", &ttl).unwrap(),
+            vec!["ALTER TABLE `analytics`.`events` MODIFY TTL ts + toIntervalDay(30)"]
+        );
+
+        let view = SerializableOlapOperation::DropView {
+            name: "top_paths".to_string(),
+            database: None,
+        };
+        let preview = super::operation_sql_preview("local", &view).unwrap();
+        assert_eq!(preview.len(), 1);
+        assert!(preview[0].starts_with("-- "));
+
+        let rename = SerializableOlapOperation::RenameTable {
+            table: "events".to_string(),
+            new_name: "events__dropped_20260314150926".to_string(),
+            database: None,
+            cluster_name: Some("main".to_string()),
+        };
+        assert_eq!(
+            super::operation_sql_preview("local", &rename).unwrap(),
+            vec!["RENAME TABLE `local`.`events` TO `local`.`events__dropped_20260314150926` ON CLUSTER `main`"]
+        );
+    }
+
+    /// Identifiers that only survive proper quoting and escaping
+    const HOSTILE_IDENTIFIERS: [&str; 6] = [
+        "we`ird",
+        "``",
+        "it's \"quoted\"",
+        "événements_日本",
+        "1st_table",
+        "select",
+    ];
+
+    #[test]
+    fn test_hostile_identifiers_round_trip() {
+        use crate::infrastructure::olap::clickhouse::sql_parser::tests::quoted_identifiers;
+
+        for name in HOSTILE_IDENTIFIERS {
+            let cluster = Some(name);
+            // Each statement with the number of identifiers it quotes
+            let statements = [
+                (drop_table_column_query(name, name, name, cluster), 4),
+                (rename_table_query(name, name, name, cluster), 5),
+                (modify_table_ttl_query(name, name, Some("ts"), cluster), 3),
+                (modify_table_ttl_query(name, name, None, cluster), 3),
+                (
+                    build_modify_column_comment_sql(name, name, name, "it's", cluster).unwrap(),
+                    4,
+                ),
+                (drop_table_query(name, name, cluster).unwrap(), 3),
+                (
+                    queries::alter_table_reset_settings_query(
+                        name,
+                        name,
+                        &["ttl_only_drop_parts".to_string()],
+                        cluster,
+                    )
+                    .unwrap(),
+                    3,
+                ),
+                (
+                    queries::create_view_query(name, name, "SELECT 1").unwrap(),
+                    2,
+                ),
+            ];
+            for (sql, count) in statements {
+                assert_eq!(
+                    quoted_identifiers(&sql),
+                    vec![name.to_string(); count],
+                    "identifiers of {sql} should round-trip"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_hostile_identifiers_parse() {
+        use sqlparser::dialect::ClickHouseDialect;
+        use sqlparser::parser::Parser;
+
+        for name in HOSTILE_IDENTIFIERS {
+            for sql in [
+                drop_table_column_query(name, name, name, Some(name)),
+                queries::create_view_query(name, name, "SELECT 1").unwrap(),
+            ] {
+                let statements = Parser::parse_sql(&ClickHouseDialect {}, &sql)
+                    .unwrap_or_else(|e| panic!("{sql} should parse: {e}"));
+                assert_eq!(statements.len(), 1, "{sql}");
+            }
+        }
+    }
 }
@@ -384,6 +384,24 @@ mod tests {
             _ => panic!("Failed to parse JSON options with mixed configuration"),
         }
     }
+
+    #[test]
+    fn test_quote_ident_escapes_backticks_and_backslashes() {
+        assert_eq!(quote_ident("events"), "`events`");
+        assert_eq!(quote_ident("we`ird"), "`we``ird`");
+        assert_eq!(quote_ident("a\\`b"), "`a\\\\``b`");
+        assert_eq!(quote_ident("1st événement"), "`1st événement`");
+        assert_eq!(quote_qualified("db`", "t"), "`db```.`t`");
+        assert_eq!(on_cluster_clause(Some("main")), " ON CLUSTER `main`");
+        assert_eq!(on_cluster_clause(None), "");
+    }
+
+    #[test]
+    fn test_quote_string_escapes_quotes_and_backslashes() {
+        assert_eq!(quote_string("events"), "'events'");
+        assert_eq!(quote_string("it's"), "'it\\'s'");
+        assert_eq!(quote_string("a\\'b"), "'a\\\\\\'b'");
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -760,16 +778,47 @@ impl ClickHouseTable {
     }
 }
 
-/// Wraps a column name in backticks for safe use in ClickHouse SQL queries
-pub fn wrap_column_name(name: &str) -> String {
-    format!("`{name}`")
+/// Quotes an identifier (database, table, column, cluster, index...) for
+/// ClickHouse SQL. The name is always wrapped in backticks, so keywords, leading
+/// digits and unicode are accepted, and embedded backticks and backslashes are
+/// escaped the way the ClickHouse lexer reads them back.
+pub fn quote_ident(name: &str) -> String {
+    let mut quoted = String::with_capacity(name.len() + 2);
+    quoted.push('`');
+    for c in name.chars() {
+        match c {
+            '`' => quoted.push_str("``"),
+            '\\' => quoted.push_str("\\\\"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('`');
+    quoted
+}
+
+/// Quotes a `database`.`name` pair for ClickHouse SQL
+pub fn quote_qualified(database: &str, name: &str) -> String {
+    format!("{}.{}", quote_ident(database), quote_ident(name))
+}
+
+/// Quotes `value` as a ClickHouse string literal, e.g. to compare it with the
+/// `database` or `table` columns of the system tables
+pub fn quote_string(value: &str) -> String {
+    format!("'{}'", escape_ch_string(value))
+}
+
+/// The ` ON CLUSTER` clause of a statement, empty without a cluster
+pub fn on_cluster_clause(cluster_name: Option<&str>) -> String {
+    cluster_name
+        .map(|cluster| format!(" ON CLUSTER {}", quote_ident(cluster)))
+        .unwrap_or_default()
 }
 
 /// Wraps multiple column names in backticks and joins them with the specified separator
 pub fn wrap_and_join_column_names(names: &[String], separator: &str) -> String {
     names
         .iter()
-        .map(|name| wrap_column_name(name))
+        .map(|name| quote_ident(name))
         .collect::<Vec<String>>()
         .join(separator)
 }
@@ -9,8 +9,8 @@ use super::model::ClickHouseColumn;
 use crate::framework::core::infrastructure::table::{EnumValue, OrderBy};
 use crate::infrastructure::olap::clickhouse::build_column_property_clauses;
 use crate::infrastructure::olap::clickhouse::model::{
-    wrap_and_join_column_names, AggregationFunction, ClickHouseColumnType, ClickHouseFloat,
-    ClickHouseInt, ClickHouseTable,
+    on_cluster_clause, quote_ident, quote_qualified, wrap_and_join_column_names,
+    AggregationFunction, ClickHouseColumnType, ClickHouseFloat, ClickHouseInt, ClickHouseTable,
 };
 
 /// Format a ClickHouse setting value with proper quoting.
@@ -37,7 +37,7 @@ pub fn format_clickhouse_setting_value(value: &str) -> String {
 
 // Unclear if we need to add flatten_nested to the views setting as well
 static CREATE_ALIAS_TEMPLATE: &str = r#"
-CREATE VIEW IF NOT EXISTS `{{db_name}}`.`{{alias_name}}` AS SELECT * FROM `{{db_name}}`.`{{source_table_name}}`;
+CREATE VIEW IF NOT EXISTS {{{alias}}} AS SELECT * FROM {{{source_table}}};
 "#;
 
 fn create_alias_query(
@@ -49,16 +49,15 @@ fn create_alias_query(
     reg.register_escape_fn(no_escape);
 
     let context = json!({
-        "db_name": db_name,
-        "alias_name": alias_name,
-        "source_table_name": source_table_name,
+        "alias": quote_qualified(db_name, alias_name),
+        "source_table": quote_qualified(db_name, source_table_name),
     });
 
     Ok(reg.render_template(CREATE_ALIAS_TEMPLATE, &context)?)
 }
 
 static CREATE_VIEW_TEMPLATE: &str = r#"
-CREATE VIEW IF NOT EXISTS `{{db_name}}`.`{{view_name}}` AS {{view_query}};
+CREATE VIEW IF NOT EXISTS {{{view}}} AS {{view_query}};
 "#;
 
 pub fn create_view_query(
@@ -69,8 +68,7 @@ pub fn create_view_query(
     let reg = Handlebars::new();
 
     let context = json!({
-        "db_name": db_name,
-        "view_name": view_name,
+        "view": quote_qualified(db_name, view_name),
         "view_query": view_query,
     });
 
@@ -78,22 +76,21 @@ pub fn create_view_query(
 }
 
 static DROP_VIEW_TEMPLATE: &str = r#"
-DROP VIEW `{{db_name}}`.`{{view_name}}`;
+DROP VIEW {{{view}}};
 "#;
 
 pub fn drop_view_query(db_name: &str, view_name: &str) -> Result<String, ClickhouseError> {
     let reg = Handlebars::new();
 
     let context = json!({
-        "db_name": db_name,
-        "view_name": view_name,
+        "view": quote_qualified(db_name, view_name),
     });
 
     Ok(reg.render_template(DROP_VIEW_TEMPLATE, &context)?)
 }
 
 static UPDATE_VIEW_TEMPLATE: &str = r#"
-CREATE OR REPLACE VIEW `{{db_name}}`.`{{view_name}}` AS {{view_query}};
+CREATE OR REPLACE VIEW {{{view}}} AS {{view_query}};
 "#;
 
 pub fn update_view_query(
@@ -105,8 +102,7 @@ pub fn update_view_query(
     reg.register_escape_fn(no_escape);
 
     let context = json!({
-        "db_name": db_name,
-        "view_name": view_name,
+        "view": quote_qualified(db_name, view_name),
         "view_query": view_query,
     });
 
@@ -122,10 +118,10 @@ pub fn create_alias_for_table(
 }
 
 static CREATE_TABLE_TEMPLATE: &str = r#"
-CREATE TABLE IF NOT EXISTS `{{db_name}}`.`{{table_name}}`{{#if cluster_name}}
-ON CLUSTER `{{cluster_name}}`{{/if}}
+CREATE TABLE IF NOT EXISTS {{{table}}}{{#if cluster_name}}
+ON CLUSTER {{{cluster_name}}}{{/if}}
 (
-{{#each fields}} `{{field_name}}` {{{field_type}}} {{field_nullable}}{{{field_properties}}}{{#unless @last}},
+{{#each fields}} {{{field_name}}} {{{field_type}}} {{field_nullable}}{{{field_properties}}}{{#unless @last}},
 {{/unless}}{{/each}}{{#if has_indexes}}, {{#each indexes}}{{this}}{{#unless @last}}, {{/unless}}{{/each}}{{/if}}{{#if has_projections}}, {{#each projections}}{{this}}{{#unless @last}}, {{/unless}}{{/each}}{{/if}}
 )
 ENGINE = {{engine}}{{#if primary_key_string}}
@@ -380,6 +376,9 @@ pub enum ClickhouseEngine {
         // Regex pattern to match table names in the source database
         tables_regexp: String,
     },
+    // Discards the rows inserted into it, materialized views selecting from
+    // the table still receive them
+    Null,
 }
 
 // The implementation is not symetric between TryFrom and Into so we
@@ -516,6 +515,7 @@ impl Into<String> for ClickhouseEngine {
                 source_database,
                 tables_regexp,
             } => Self::serialize_merge(&source_database, &tables_regexp),
+            ClickhouseEngine::Null => "Null".to_string(),
             // this might sound obvious, but when you edit this function
             // please check if you have changed the parsing side (try_from) as well
             // especially if you're an LLM
@@ -620,8 +620,8 @@ impl ClickhouseEngine {
             let (keeper_path, replica_name) = Self::extract_replication_params(&params);
 
             // Optional 3rd param is ver, optional 4th is is_deleted
-            let ver = params.get(2).cloned();
-            let is_deleted = params.get(3).cloned();
+            let ver = params.get(2).map(|p| p.trim_matches('`').to_string());
+            let is_deleted = params.get(3).map(|p| p.trim_matches('`').to_string());
 
             Ok(ClickhouseEngine::ReplicatedReplacingMergeTree {
                 keeper_path,
@@ -639,8 +639,8 @@ impl ClickhouseEngine {
 
             // Skip the first two params (keeper_path and replica_name) for Shared engines
             // Optional 3rd param is ver, optional 4th is is_deleted
-            let ver = params.get(2).cloned();
-            let is_deleted = params.get(3).cloned();
+            let ver = params.get(2).map(|p| p.trim_matches('`').to_string());
+            let is_deleted = params.get(3).map(|p| p.trim_matches('`').to_string());
 
             // SharedReplacingMergeTree normalizes to ReplacingMergeTree
             Ok(ClickhouseEngine::ReplacingMergeTree { ver, is_deleted })
@@ -666,6 +666,56 @@ impl ClickhouseEngine {
         }
     }
 
+    /// Unfolds the `{database}` and `{table}` macros of the replication params,
+    /// which ClickHouse does when it creates the table, so the definition read
+    /// back from `system.tables` matches. `{shard}`, `{replica}` and `{uuid}`
+    /// are kept as written.
+    pub fn expand_replication_macros(mut self, database: &str, table: &str) -> Self {
+        let expand = |param: &mut Option<String>| {
+            if let Some(value) = param {
+                *value = value
+                    .replace("{database}", database)
+                    .replace("{table}", table);
+            }
+        };
+
+        match &mut self {
+            ClickhouseEngine::ReplicatedMergeTree {
+                keeper_path,
+                replica_name,
+            }
+            | ClickhouseEngine::ReplicatedReplacingMergeTree {
+                keeper_path,
+                replica_name,
+                ..
+            }
+            | ClickhouseEngine::ReplicatedAggregatingMergeTree {
+                keeper_path,
+                replica_name,
+            }
+            | ClickhouseEngine::ReplicatedSummingMergeTree {
+                keeper_path,
+                replica_name,
+                ..
+            }
+            | ClickhouseEngine::ReplicatedCollapsingMergeTree {
+                keeper_path,
+                replica_name,
+                ..
+            }
+            | ClickhouseEngine::ReplicatedVersionedCollapsingMergeTree {
+                keeper_path,
+                replica_name,
+                ..
+            } => {
+                expand(keeper_path);
+                expand(replica_name);
+            }
+            _ => {}
+        }
+        self
+    }
+
     /// Extract and normalize replication params from parsed CSV parameters
     /// Returns normalized (keeper_path, replica_name) tuple
     fn extract_replication_params(params: &[String]) -> (Option<String>, Option<String>) {
@@ -1018,6 +1068,7 @@ impl ClickhouseEngine {
                 Self::parse_regular_replacing_merge_tree(s, value)
             }
             "AggregatingMergeTree" => Ok(ClickhouseEngine::AggregatingMergeTree),
+            "Null" => Ok(ClickhouseEngine::Null),
             "SummingMergeTree" => Ok(ClickhouseEngine::SummingMergeTree { columns: None }),
             s if s.starts_with("SummingMergeTree(") => {
                 Self::parse_regular_summing_merge_tree(s, value)
@@ -1351,6 +1402,7 @@ impl ClickhouseEngine {
 
     /// Parse regular Distributed with parameters
     /// Format: Distributed('cluster', 'database', 'table'[, sharding_key][, 'policy'])
+    /// The cluster, database, table and policy may also be unquoted or in backticks.
     fn parse_regular_distributed<'a>(
         engine_name: &str,
         original_value: &'a str,
@@ -1359,30 +1411,23 @@ impl ClickhouseEngine {
             .strip_prefix("Distributed(")
             .and_then(|s| s.strip_suffix(")"))
         {
-            let params = parse_quoted_csv(content);
+            // The sharding key is an expression that can hold commas, e.g. cityHash64(a, b)
+            let params = split_engine_args(content);
 
-            // Need at least 3 parameters (cluster, database, table)
-            if params.len() < 3 {
+            // Need 3 to 5 parameters (cluster, database, table, sharding_key, policy)
+            if params.len() < 3 || params.len() > 5 {
                 return Err(original_value);
             }
 
-            let cluster = params[0].clone();
-            let target_database = params[1].clone();
-            let target_table = params[2].clone();
+            let cluster = unquote_engine_arg(&params[0]);
+            let target_database = unquote_engine_arg(&params[1]);
+            let target_table = unquote_engine_arg(&params[2]);
 
             // Parse optional sharding_key (4th parameter, not quoted - it's an expression)
-            let sharding_key = if params.len() > 3 {
-                Some(params[3].clone())
-            } else {
-                None
-            };
+            let sharding_key = params.get(3).cloned();
 
             // Parse optional policy_name (5th parameter, quoted)
-            let policy_name = if params.len() > 4 {
-                Some(params[4].clone())
-            } else {
-                None
-            };
+            let policy_name = params.get(4).map(|policy| unquote_engine_arg(policy));
 
             Ok(ClickhouseEngine::Distributed {
                 cluster,
@@ -1454,6 +1499,60 @@ fn parse_quoted_csv(content: &str) -> Vec<String> {
     parts
 }
 
+/// Split engine arguments on the commas that are outside quotes and
+/// parentheses, keeping each argument as written, trimmed
+fn split_engine_args(content: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut escape_next = false;
+    let mut depth = 0usize;
+
+    for ch in content.chars() {
+        if escape_next {
+            escape_next = false;
+        } else if let Some(q) = quote {
+            if ch == '\\' {
+                escape_next = true;
+            } else if ch == q {
+                quote = None;
+            }
+        } else {
+            match ch {
+                '\'' | '`' | '"' => quote = Some(ch),
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    args.push(current.trim().to_string());
+                    current.clear();
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        current.push(ch);
+    }
+    if !current.trim().is_empty() || !args.is_empty() {
+        args.push(current.trim().to_string());
+    }
+    args
+}
+
+/// An identifier or string engine argument without its single quotes or
+/// backticks
+fn unquote_engine_arg(arg: &str) -> String {
+    if let Some(inner) = arg
+        .strip_prefix('\'')
+        .and_then(|arg| arg.strip_suffix('\''))
+    {
+        return inner.replace("\\'", "'").replace("\\\\", "\\");
+    }
+    arg.strip_prefix('`')
+        .and_then(|arg| arg.strip_suffix('`'))
+        .unwrap_or(arg)
+        .to_string()
+}
+
 impl ClickhouseEngine {
     /// Check if this engine is part of the MergeTree family
     pub fn is_merge_tree_family(&self) -> bool {
@@ -1476,7 +1575,7 @@ impl ClickhouseEngine {
 
     /// Returns true if this engine supports ORDER BY clause
     /// MergeTree family and S3 support ORDER BY
-    /// Buffer, S3Queue, Distributed, Kafka, and IcebergS3 do NOT support ORDER BY
+    /// Buffer, S3Queue, Distributed, Kafka, IcebergS3, Merge and Null do NOT support ORDER BY
     pub fn supports_order_by(&self) -> bool {
         self.is_merge_tree_family() || matches!(self, ClickhouseEngine::S3 { .. })
     }
@@ -1618,6 +1717,7 @@ impl ClickhouseEngine {
                 source_database,
                 tables_regexp,
             } => Self::serialize_merge(source_database, tables_regexp),
+            ClickhouseEngine::Null => "Null".to_string(),
         }
     }
 
@@ -2184,9 +2284,16 @@ impl ClickhouseEngine {
     }
 
     /// Parse ReplacingMergeTree engine from serialized string format
-    /// Expected format: ReplacingMergeTree('ver'[, 'is_deleted'])
+    /// Expected format: ReplacingMergeTree('ver'[, 'is_deleted']), or the
+    /// unquoted or backquoted identifiers of the CREATE TABLE of ClickHouse
     fn parse_replacing_merge_tree(content: &str) -> Result<ClickhouseEngine, &str> {
-        let parts = parse_quoted_csv(content);
+        let parts: Vec<String> = parse_quoted_csv(content)
+            .into_iter()
+            .map(|part| part.trim_matches('`').to_string())
+            .collect();
+        if parts.len() > 2 {
+            return Err(content);
+        }
 
         let ver = if !parts.is_empty() && parts[0] != "null" {
             Some(parts[0].clone())
@@ -2809,6 +2916,9 @@ impl ClickhouseEngine {
                 hasher.update(source_database.as_bytes());
                 hasher.update(tables_regexp.as_bytes());
             }
+            ClickhouseEngine::Null => {
+                hasher.update("Null".as_bytes());
+            }
         }
 
         format!("{:x}", hasher.finalize())
@@ -2836,10 +2946,10 @@ fn build_replacing_merge_tree_ddl(
 
     let mut params = vec![];
     if let Some(ver_col) = ver {
-        params.push(format!("`{}`", ver_col));
+        params.push(quote_ident(ver_col));
     }
     if let Some(is_deleted_col) = is_deleted {
-        params.push(format!("`{}`", is_deleted_col));
+        params.push(quote_ident(is_deleted_col));
     }
 
     Ok(if params.is_empty() {
@@ -2853,11 +2963,7 @@ fn build_replacing_merge_tree_ddl(
 fn build_summing_merge_tree_ddl(columns: &Option<Vec<String>>) -> String {
     if let Some(cols) = columns {
         if !cols.is_empty() {
-            let col_list = cols
-                .iter()
-                .map(|c| format!("`{}`", c))
-                .collect::<Vec<_>>()
-                .join(", ");
+            let col_list = wrap_and_join_column_names(cols, ", ");
             return format!("SummingMergeTree({})", col_list);
         }
     }
@@ -2866,12 +2972,16 @@ fn build_summing_merge_tree_ddl(columns: &Option<Vec<String>>) -> String {
 
 /// Generate DDL for CollapsingMergeTree engine
 fn build_collapsing_merge_tree_ddl(sign: &str) -> String {
-    format!("CollapsingMergeTree(`{}`)", sign)
+    format!("CollapsingMergeTree({})", quote_ident(sign))
 }
 
 /// Generate DDL for VersionedCollapsingMergeTree engine
 fn build_versioned_collapsing_merge_tree_ddl(sign: &str, version: &str) -> String {
-    format!("VersionedCollapsingMergeTree(`{}`, `{}`)", sign, version)
+    format!(
+        "VersionedCollapsingMergeTree({}, {})",
+        quote_ident(sign),
+        quote_ident(version)
+    )
 }
 
 /// Build replication parameters for replicated engines
@@ -2974,10 +3084,10 @@ fn build_replicated_replacing_merge_tree_ddl(
     )?;
 
     if let Some(ver_col) = ver {
-        params.push(format!("`{}`", ver_col));
+        params.push(quote_ident(ver_col));
     }
     if let Some(is_deleted_col) = is_deleted {
-        params.push(format!("`{}`", is_deleted_col));
+        params.push(quote_ident(is_deleted_col));
     }
 
     Ok(format!(
@@ -3028,11 +3138,7 @@ fn build_replicated_summing_merge_tree_ddl(
 
     if let Some(cols) = columns {
         if !cols.is_empty() {
-            let col_list = cols
-                .iter()
-                .map(|c| format!("`{}`", c))
-                .collect::<Vec<_>>()
-                .join(", ");
+            let col_list = wrap_and_join_column_names(cols, ", ");
             params.push(format!("({})", col_list));
         }
     }
@@ -3058,7 +3164,7 @@ fn build_replicated_collapsing_merge_tree_ddl(
         is_dev,
     )?;
 
-    params.push(format!("`{}`", sign));
+    params.push(quote_ident(sign));
 
     Ok(format!(
         "ReplicatedCollapsingMergeTree({})",
@@ -3085,8 +3191,8 @@ fn build_replicated_versioned_collapsing_merge_tree_ddl(
         is_dev,
     )?;
 
-    params.push(format!("`{}`", sign));
-    params.push(format!("`{}`", version));
+    params.push(quote_ident(sign));
+    params.push(quote_ident(version));
 
     Ok(format!(
         "ReplicatedVersionedCollapsingMergeTree({})",
@@ -3376,6 +3482,7 @@ pub fn create_table_query(
             source_database,
             tables_regexp,
         } => ClickhouseEngine::serialize_merge(source_database, tables_regexp),
+        ClickhouseEngine::Null => "Null".to_string(),
     };
 
     // Format settings from table.table_settings
@@ -3436,7 +3543,11 @@ pub fn create_table_query(
             };
             items.push(format!(
                 "INDEX {} {} TYPE {}{} GRANULARITY {}",
-                idx.name, idx.expression, idx.index_type, args_part, idx.granularity
+                quote_ident(&idx.name),
+                idx.expression,
+                idx.index_type,
+                args_part,
+                idx.granularity
             ));
         }
         (true, items)
@@ -3451,7 +3562,7 @@ pub fn create_table_query(
             let items: Vec<String> = table
                 .projections
                 .iter()
-                .map(|p| format!("PROJECTION {} ({})", p.name, p.body))
+                .map(|p| format!("PROJECTION {} ({})", quote_ident(&p.name), p.body))
                 .collect();
             (true, items)
         };
@@ -3459,7 +3570,7 @@ pub fn create_table_query(
     // Different engines support different clauses:
     // - MergeTree family: Supports all clauses (ORDER BY, PRIMARY KEY, PARTITION BY, SAMPLE BY)
     // - S3: Supports PARTITION BY and SETTINGS, but not ORDER BY, PRIMARY KEY, or SAMPLE BY
-    // - S3Queue, Buffer, Distributed: Don't support any of these clauses
+    // - S3Queue, Buffer, Distributed, Null: Don't support any of these clauses
 
     let supports_order_by = table.engine.supports_order_by();
     let supports_primary_key = table.engine.supports_order_by();
@@ -3482,9 +3593,8 @@ pub fn create_table_query(
     );
 
     let template_context = json!({
-        "db_name": db_name,
-        "table_name": table.name,
-        "cluster_name": table.cluster_name.as_deref(),
+        "table": quote_qualified(db_name, &table.name),
+        "cluster_name": table.cluster_name.as_deref().map(quote_ident),
         "fields":  builds_field_context(&table.columns)?,
         "has_fields": !table.columns.is_empty(),
         "has_indexes": has_indexes,
@@ -3528,7 +3638,7 @@ pub fn create_table_query(
 }
 
 pub static DROP_TABLE_TEMPLATE: &str = r#"
-DROP TABLE IF EXISTS `{{db_name}}`.`{{table_name}}`{{#if cluster_name}} ON CLUSTER `{{cluster_name}}` SYNC{{/if}};
+DROP TABLE IF EXISTS {{{table}}}{{{cluster_clause}}}{{#if cluster_clause}} SYNC{{/if}};
 "#;
 
 pub fn drop_table_query(
@@ -3540,21 +3650,20 @@ pub fn drop_table_query(
     reg.register_escape_fn(no_escape);
 
     let context = json!({
-        "db_name": db_name,
-        "table_name": table_name,
-        "cluster_name": cluster_name,
+        "table": quote_qualified(db_name, table_name),
+        "cluster_clause": on_cluster_clause(cluster_name),
     });
 
     Ok(reg.render_template(DROP_TABLE_TEMPLATE, &context)?)
 }
 
 pub static ALTER_TABLE_MODIFY_SETTINGS_TEMPLATE: &str = r#"
-ALTER TABLE `{{db_name}}`.`{{table_name}}`{{#if cluster_name}} ON CLUSTER `{{cluster_name}}`{{/if}}
+ALTER TABLE {{{table}}}{{{cluster_clause}}}
 MODIFY SETTING {{settings}};
 "#;
 
 pub static ALTER_TABLE_RESET_SETTINGS_TEMPLATE: &str = r#"
-ALTER TABLE `{{db_name}}`.`{{table_name}}`{{#if cluster_name}} ON CLUSTER `{{cluster_name}}`{{/if}}
+ALTER TABLE {{{table}}}{{{cluster_clause}}}
 RESET SETTING {{settings}};
 "#;
 
@@ -3587,10 +3696,9 @@ pub fn alter_table_modify_settings_query(
         .join(", ");
 
     let context = json!({
-        "db_name": db_name,
-        "table_name": table_name,
+        "table": quote_qualified(db_name, table_name),
         "settings": settings_str,
-        "cluster_name": cluster_name,
+        "cluster_clause": on_cluster_clause(cluster_name),
     });
 
     Ok(reg.render_template(ALTER_TABLE_MODIFY_SETTINGS_TEMPLATE, &context)?)
@@ -3615,10 +3723,9 @@ pub fn alter_table_reset_settings_query(
     let settings_str = setting_names.join(", ");
 
     let context = json!({
-        "db_name": db_name,
-        "table_name": table_name,
+        "table": quote_qualified(db_name, table_name),
         "settings": settings_str,
-        "cluster_name": cluster_name,
+        "cluster_clause": on_cluster_clause(cluster_name),
     });
 
     Ok(reg.render_template(ALTER_TABLE_RESET_SETTINGS_TEMPLATE, &context)?)
@@ -3789,7 +3896,7 @@ fn builds_field_context(columns: &[ClickHouseColumn]) -> Result<Vec<Value>, Clic
             let field_properties = build_column_property_clauses(column);
 
             Ok(json!({
-                "field_name": column.name,
+                "field_name": quote_ident(&column.name),
                 "field_type": field_type,
                 "field_nullable": if let ClickHouseColumnType::Nullable(_) = column.column_type {
                     // if type is Nullable, do not add extra specifier
@@ -4533,6 +4640,51 @@ ORDER BY (`id`) "#;
         );
     }
 
+    #[test]
+    fn test_replacing_merge_tree_is_deleted_from_clickhouse() {
+        use crate::infrastructure::olap::clickhouse::sql_parser::extract_engine_from_create_table;
+
+        let expected = ClickhouseEngine::ReplacingMergeTree {
+            ver: Some("updated_at".to_string()),
+            is_deleted: Some("is_deleted".to_string()),
+        };
+        // As listed by ClickHouse, and as created by Moose
+        for arguments in ["updated_at, is_deleted", "`updated_at`, `is_deleted`"] {
+            let create_query = format!(
+                "CREATE TABLE local.users (`id` String, `updated_at` DateTime, `is_deleted` UInt8) \
+                 ENGINE = ReplacingMergeTree({arguments}) ORDER BY id SETTINGS index_granularity = 8192"
+            );
+            let engine = extract_engine_from_create_table(&create_query).unwrap();
+            assert_eq!(
+                ClickhouseEngine::try_from(engine.as_str()),
+                Ok(expected.clone())
+            );
+        }
+
+        assert_eq!(
+            ClickhouseEngine::try_from(
+                "ReplicatedReplacingMergeTree('/clickhouse/tables/{uuid}/{shard}', '{replica}', updated_at, is_deleted)"
+            ),
+            Ok(ClickhouseEngine::ReplicatedReplacingMergeTree {
+                keeper_path: None,
+                replica_name: None,
+                ver: Some("updated_at".to_string()),
+                is_deleted: Some("is_deleted".to_string()),
+            })
+        );
+        assert!(ClickhouseEngine::try_from("ReplacingMergeTree(a, b, c)").is_err());
+
+        // Adding is_deleted recreates the table, it cannot be altered
+        let without_is_deleted = ClickhouseEngine::ReplacingMergeTree {
+            ver: Some("updated_at".to_string()),
+            is_deleted: None,
+        };
+        assert_ne!(
+            expected.non_alterable_params_hash(),
+            without_is_deleted.non_alterable_params_hash()
+        );
+    }
+
     #[test]
     fn test_create_table_query_complex() {
         let table = ClickHouseTable {
@@ -5546,6 +5698,47 @@ ENGINE = S3Queue('s3://my-bucket/data/*.csv', NOSIGN, 'CSV')"#;
         }
     }
 
+    #[test]
+    fn test_replicated_engines_keep_explicit_replication_params() {
+        let engine = ClickhouseEngine::try_from(
+            "ReplicatedReplacingMergeTree('/clickhouse/tables/{shard}/local/events', '{replica}', updated_at)",
+        )
+        .unwrap();
+        assert_eq!(
+            engine,
+            ClickhouseEngine::ReplicatedReplacingMergeTree {
+                keeper_path: Some("/clickhouse/tables/{shard}/local/events".to_string()),
+                replica_name: Some("{replica}".to_string()),
+                ver: Some("updated_at".to_string()),
+                is_deleted: None,
+            }
+        );
+
+        // ClickHouse stores the path with {database} and {table} unfolded, so
+        // the table written with them matches the one read back
+        let declared = ClickhouseEngine::ReplicatedReplacingMergeTree {
+            keeper_path: Some("/clickhouse/tables/{shard}/{database}/{table}".to_string()),
+            replica_name: Some("{replica}".to_string()),
+            ver: Some("updated_at".to_string()),
+            is_deleted: None,
+        }
+        .expand_replication_macros("local", "events");
+        assert_eq!(declared, engine);
+        assert_eq!(
+            declared.non_alterable_params_hash(),
+            engine.non_alterable_params_hash()
+        );
+
+        let bare = ClickhouseEngine::ReplicatedMergeTree {
+            keeper_path: None,
+            replica_name: None,
+        };
+        assert_eq!(
+            bare.clone().expand_replication_macros("local", "events"),
+            bare
+        );
+    }
+
     #[test]
     fn test_replicated_aggregating_merge_tree_engine_parsing() {
         // Test ReplicatedAggregatingMergeTree without parameters - should return ReplicatedAggregatingMergeTree with None parameters
@@ -6606,6 +6799,44 @@ ENGINE = S3Queue('s3://my-bucket/data/*.csv', NOSIGN, 'CSV')"#;
         }
     }
 
+    #[test]
+    fn test_distributed_engine_from_clickhouse() {
+        let distributed =
+            |sharding_key: Option<&str>, policy_name: Option<&str>| ClickhouseEngine::Distributed {
+                cluster: "my_cluster".to_string(),
+                target_database: "db".to_string(),
+                target_table: "events_local".to_string(),
+                sharding_key: sharding_key.map(str::to_string),
+                policy_name: policy_name.map(str::to_string),
+            };
+
+        // A sharding key with commas is a single argument
+        assert_eq!(
+            ClickhouseEngine::try_from(
+                "Distributed('my_cluster', 'db', 'events_local', cityHash64(user_id, 'a,b'), 'hot')"
+            )
+            .unwrap(),
+            distributed(Some("cityHash64(user_id, 'a,b')"), Some("hot"))
+        );
+        // Unquoted and backticked arguments
+        assert_eq!(
+            ClickhouseEngine::try_from("Distributed(my_cluster, `db`, events_local, rand())")
+                .unwrap(),
+            distributed(Some("rand()"), None)
+        );
+        assert!(ClickhouseEngine::try_from("Distributed('my_cluster', 'db')").is_err());
+
+        // Pointing at another table recreates the Distributed table
+        let mut other_table = distributed(None, None);
+        if let ClickhouseEngine::Distributed { target_table, .. } = &mut other_table {
+            *target_table = "events_archive".to_string();
+        }
+        assert_ne!(
+            distributed(None, None).non_alterable_params_hash(),
+            other_table.non_alterable_params_hash()
+        );
+    }
+
     #[test]
     fn test_icebergs3_hash_consistency() {
         // Test that identical engines produce identical hashes
@@ -7456,6 +7687,64 @@ ORDER BY (`event_time`)
         let _result = ClickhouseEngine::try_from("Merge(', '^test')");
     }
 
+    #[test]
+    fn test_null_engine_roundtrip() {
+        let engine = ClickhouseEngine::try_from("Null").unwrap();
+        assert_eq!(engine, ClickhouseEngine::Null);
+        assert_eq!(engine.to_proto_string(), "Null");
+        let serialized: String = engine.clone().into();
+        assert_eq!(serialized, "Null");
+
+        assert!(!engine.is_merge_tree_family());
+        assert!(!engine.supports_order_by());
+        assert_ne!(
+            engine.non_alterable_params_hash(),
+            ClickhouseEngine::MergeTree.non_alterable_params_hash()
+        );
+    }
+
+    #[test]
+    fn test_create_table_query_null_engine() {
+        let table = ClickHouseTable {
+            version: None,
+            name: "raw_events".to_string(),
+            columns: vec![ClickHouseColumn {
+                name: "id".to_string(),
+                column_type: ClickHouseColumnType::String,
+                required: true,
+                primary_key: true,
+                unique: false,
+                default: None,
+                comment: None,
+                ttl: None,
+                codec: None,
+                materialized: None,
+                alias: None,
+            }],
+            order_by: OrderBy::Fields(vec!["id".to_string()]),
+            partition_by: Some("id".to_string()),
+            sample_by: None,
+            engine: ClickhouseEngine::Null,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+        };
+
+        let query = create_table_query("test_db", table, false).unwrap();
+        // Null tables take no ORDER BY, PRIMARY KEY or PARTITION BY
+        let expected = r#"
+CREATE TABLE IF NOT EXISTS `test_db`.`raw_events`
+(
+ `id` String NOT NULL
+)
+ENGINE = Null
+"#;
+        assert_eq!(query.trim(), expected.trim());
+    }
+
     #[test]
     fn test_create_table_query_with_projection_mergetree() {
         use crate::infrastructure::olap::clickhouse::model::ClickHouseProjection;
@@ -7508,12 +7797,84 @@ ORDER BY (`event_time`)
 
         let query = create_table_query("test_db", table, false).unwrap();
         assert!(
-            query.contains("PROJECTION proj_by_user (SELECT * ORDER BY user_id)"),
+            query.contains("PROJECTION `proj_by_user` (SELECT * ORDER BY user_id)"),
             "MergeTree DDL should contain the projection. Got: {}",
             query
         );
     }
 
+    #[test]
+    fn test_create_table_query_quotes_hostile_identifiers() {
+        use crate::infrastructure::olap::clickhouse::sql_parser::tests::quoted_identifiers;
+        use sqlparser::dialect::ClickHouseDialect;
+        use sqlparser::parser::Parser;
+
+        let column =
+            |name: &str, column_type: ClickHouseColumnType, primary_key: bool| ClickHouseColumn {
+                name: name.to_string(),
+                column_type,
+                required: true,
+                primary_key,
+                unique: false,
+                default: None,
+                comment: None,
+                ttl: None,
+                codec: None,
+                materialized: None,
+                alias: None,
+            };
+        let table = ClickHouseTable {
+            version: None,
+            name: "1st `events`".to_string(),
+            columns: vec![
+                column(
+                    "id`",
+                    ClickHouseColumnType::ClickhouseInt(ClickHouseInt::Int64),
+                    true,
+                ),
+                column("événement 'type'", ClickHouseColumnType::String, false),
+                column(
+                    "sign",
+                    ClickHouseColumnType::ClickhouseInt(ClickHouseInt::Int8),
+                    false,
+                ),
+            ],
+            order_by: OrderBy::Fields(vec!["id`".to_string()]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::CollapsingMergeTree {
+                sign: "sign".to_string(),
+            },
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            table_ttl_setting: None,
+            cluster_name: Some("clüster \"main\"".to_string()),
+            primary_key_expression: None,
+        };
+
+        let query = create_table_query("db`", table, false).unwrap();
+        assert_eq!(
+            quoted_identifiers(&query),
+            vec![
+                "db`",
+                "1st `events`",
+                "clüster \"main\"",
+                "id`",
+                "événement 'type'",
+                "sign",
+                "sign",
+                "id`",
+                "id`",
+            ],
+            "identifiers of {query} should round-trip"
+        );
+        assert!(
+            Parser::parse_sql(&ClickHouseDialect {}, &query).is_ok(),
+            "{query} should parse"
+        );
+    }
+
     #[test]
     fn test_create_table_query_drops_projection_for_non_mergetree() {
         use crate::infrastructure::olap::clickhouse::model::ClickHouseProjection;
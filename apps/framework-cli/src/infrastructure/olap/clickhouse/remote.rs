@@ -22,8 +22,9 @@
 
 use std::fmt;
 
-use super::config::ClickHouseConfig;
+use super::config::{ClickHouseConfig, DEFAULT_INSERT_ERROR_THRESHOLD};
 use super::{create_readonly_client, ConfiguredDBClient};
+use crate::infrastructure::olap::ddl_execution::DEFAULT_DDL_PARALLELISM;
 use urlencoding::encode;
 
 /// Escapes a string for use in a SQL string literal.
@@ -176,6 +177,8 @@ impl ClickHouseRemote {
             host_data_path: None,
             additional_databases: vec![],
             clusters: None,
+            ddl_parallelism: DEFAULT_DDL_PARALLELISM,
+            insert_error_threshold: DEFAULT_INSERT_ERROR_THRESHOLD,
         };
 
         let client = create_readonly_client(config);
@@ -339,6 +342,8 @@ mod tests {
             host_data_path: None,
             additional_databases: vec![],
             clusters: None,
+            ddl_parallelism: 4,
+            insert_error_threshold: 10,
         }
     }
 
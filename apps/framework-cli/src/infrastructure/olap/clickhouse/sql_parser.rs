@@ -5,8 +5,9 @@
 
 use crate::infrastructure::olap::clickhouse::model::ClickHouseIndex;
 use sqlparser::ast::{
-    CreateTableOptions, Expr, ObjectName, ObjectNamePart, Query, Select, SelectItem, SetExpr,
-    SqlOption, Statement, TableFactor, TableWithJoins, ToSql, VisitMut, VisitorMut,
+    visit_expressions, CreateTableOptions, Expr, ObjectName, ObjectNamePart, Query, Select,
+    SelectItem, SetExpr, SqlOption, Statement, TableFactor, TableWithJoins, ToSql, VisitMut,
+    VisitorMut,
 };
 use sqlparser::dialect::ClickHouseDialect;
 use sqlparser::keywords::Keyword;
@@ -842,6 +843,12 @@ impl<'a> VisitorMut for Normalizer<'a> {
                 ident.quote_style = None;
                 ident.value = ident.value.replace('`', "");
             }
+            Expr::CompoundIdentifier(idents) => {
+                for ident in idents {
+                    ident.quote_style = None;
+                    ident.value = ident.value.replace('`', "");
+                }
+            }
             Expr::Function(func) => {
                 // Uppercase function names (e.g. count -> COUNT)
                 if let Some(ObjectNamePart::Identifier(ident)) = func.name.0.last_mut() {
@@ -941,20 +948,65 @@ pub fn normalize_sql_for_comparison(sql: &str, default_database: &str) -> String
             // 3. Convert back to string using dialect-aware serialization
             ast[0].to_sql(&dialect)
         }
-        Err(_e) => {
-            // Fallback if parsing fails: rudimentary string replacement
-            let mut result = sql.to_string();
-            if !default_database.is_empty() {
-                let prefix_pattern = format!("{}.", default_database);
-                result = result.replace(&prefix_pattern, "");
+        Err(_e) => match normalize_sql_tokens(sql, default_database) {
+            Some(normalized) => normalized,
+            None => {
+                // Fallback if tokenizing fails too: rudimentary string replacement
+                let mut result = sql.to_string();
+                if !default_database.is_empty() {
+                    let prefix_pattern = format!("{}.", default_database);
+                    result = result.replace(&prefix_pattern, "");
+                }
+                result
             }
-            result
-        }
+        },
     };
 
     intermediate.trim().to_string()
 }
 
+/// Token-level normalization for SQL the parser does not understand.
+///
+/// Comments are dropped and tokens are joined by single spaces, so only the
+/// whitespace and comments outside string literals change. Unquoted keywords
+/// are uppercased, backticks are removed and the default database prefix is
+/// stripped from qualified names.
+fn normalize_sql_tokens(sql: &str, default_database: &str) -> Option<String> {
+    let tokens: Vec<Token> = Tokenizer::new(&ClickHouseDialect {}, sql)
+        .tokenize()
+        .ok()?
+        .into_iter()
+        .filter(|token| !matches!(token, Token::Whitespace(_) | Token::EOF))
+        .collect();
+
+    let mut normalized = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        if let Token::Word(word) = &tokens[i] {
+            let is_default_database = !default_database.is_empty()
+                && word.value.eq_ignore_ascii_case(default_database)
+                && !matches!(i.checked_sub(1).map(|j| &tokens[j]), Some(Token::Period))
+                && matches!(tokens.get(i + 1), Some(Token::Period))
+                && matches!(tokens.get(i + 2), Some(Token::Word(_)));
+            if is_default_database {
+                i += 2;
+                continue;
+            }
+
+            if word.quote_style.is_none() && word.keyword != Keyword::NoKeyword {
+                normalized.push(word.value.to_uppercase());
+            } else {
+                normalized.push(word.value.clone());
+            }
+        } else {
+            normalized.push(tokens[i].to_string());
+        }
+        i += 1;
+    }
+
+    Some(normalized.join(" "))
+}
+
 pub fn parse_create_materialized_view(
     sql: &str,
 ) -> Result<MaterializedViewStatement, SqlParseError> {
@@ -1055,6 +1107,165 @@ pub fn is_materialized_view(sql: &str) -> bool {
     parse_create_materialized_view(sql).is_ok()
 }
 
+/// The columns a TTL clause refers to, e.g. `created_at` for
+/// `created_at + INTERVAL 30 DAY DELETE`.
+///
+/// Each comma separated rule is read up to its action (`DELETE`, `TO DISK`,
+/// `WHERE`, ...). Function names and the parameters of lambdas are not columns.
+pub fn extract_ttl_columns(ttl: &str) -> Result<Vec<String>, SqlParseError> {
+    let dialect = ClickHouseDialect {};
+    let mut parser = Parser::new(&dialect).try_with_sql(ttl)?;
+    let mut identifiers = Vec::new();
+    let mut lambda_params = HashSet::new();
+
+    loop {
+        let expr = parser.parse_expr()?;
+        let _ = visit_expressions(&expr, |expr| {
+            match expr {
+                Expr::Identifier(ident) => identifiers.push(ident.value.clone()),
+                Expr::CompoundIdentifier(idents) => identifiers.push(
+                    idents
+                        .iter()
+                        .map(|ident| ident.value.as_str())
+                        .collect::<Vec<_>>()
+                        .join("."),
+                ),
+                Expr::Lambda(lambda) => lambda_params.extend(
+                    lambda
+                        .params
+                        .to_string()
+                        .trim_matches(|c| c == '(' || c == ')')
+                        .split(',')
+                        .map(|param| param.trim().to_string()),
+                ),
+                _ => {}
+            }
+            ControlFlow::<()>::Continue(())
+        });
+
+        // Skip the action of the rule, up to the next one
+        let mut depth = 0usize;
+        loop {
+            match parser.next_token().token {
+                Token::EOF => {
+                    let mut seen = HashSet::new();
+                    identifiers.retain(|identifier| {
+                        !lambda_params.contains(identifier) && seen.insert(identifier.clone())
+                    });
+                    return Ok(identifiers);
+                }
+                Token::LParen => depth += 1,
+                Token::RParen => depth = depth.saturating_sub(1),
+                Token::Comma if depth == 0 => break,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Non-whitespace tokens of `sql`, `None` when it cannot be tokenized.
+fn significant_tokens(sql: &str) -> Option<Vec<Token>> {
+    let tokens = Tokenizer::new(&ClickHouseDialect {}, sql).tokenize().ok()?;
+    Some(
+        tokens
+            .into_iter()
+            .filter(|token| !matches!(token, Token::Whitespace(_)))
+            .collect(),
+    )
+}
+
+/// Checks whether the token is the given word, which the parser may not know
+/// as a keyword.
+fn is_word(token: &Token, word: &str) -> bool {
+    matches!(token, Token::Word(w) if w.value.eq_ignore_ascii_case(word))
+}
+
+/// Whether `tokens` start a `CREATE [OR REPLACE] DICTIONARY` statement.
+fn starts_create_dictionary(tokens: &[Token]) -> bool {
+    match tokens {
+        [create, or, replace, dictionary, ..]
+            if is_keyword(create, Keyword::CREATE)
+                && is_keyword(or, Keyword::OR)
+                && is_keyword(replace, Keyword::REPLACE) =>
+        {
+            is_word(dictionary, "DICTIONARY")
+        }
+        [create, dictionary, ..] => {
+            is_keyword(create, Keyword::CREATE) && is_word(dictionary, "DICTIONARY")
+        }
+        _ => false,
+    }
+}
+
+pub fn is_create_dictionary(sql: &str) -> bool {
+    significant_tokens(sql).is_some_and(|tokens| starts_create_dictionary(&tokens))
+}
+
+/// Extracts the tables a `CREATE DICTIONARY` statement reads from when its
+/// source is ClickHouse: the `TABLE` of `SOURCE(CLICKHOUSE(...))`, in its `DB`
+/// when given, or the tables of its `QUERY`.
+///
+/// Other statements and sources (files, HTTP, other databases) read no table
+/// and return an empty list.
+pub fn extract_dictionary_source_tables(sql: &str) -> Vec<TableReference> {
+    let Some(tokens) = significant_tokens(sql) else {
+        return Vec::new();
+    };
+    if !starts_create_dictionary(&tokens) {
+        return Vec::new();
+    }
+    let Some(source) = tokens.windows(4).position(|window| {
+        is_word(&window[0], "SOURCE")
+            && matches!(window[1], Token::LParen)
+            && is_word(&window[2], "CLICKHOUSE")
+            && matches!(window[3], Token::LParen)
+    }) else {
+        return Vec::new();
+    };
+
+    // The arguments are `KEY value` pairs up to the closing parenthesis
+    let (mut table, mut database, mut query) = (None, None, None);
+    let arguments = tokens[source + 4..]
+        .iter()
+        .take_while(|token| !matches!(token, Token::RParen))
+        .cloned()
+        .collect::<Vec<_>>();
+    for pair in arguments.windows(2) {
+        let [Token::Word(key), value] = pair else {
+            continue;
+        };
+        let value = match value {
+            Token::SingleQuotedString(value) | Token::DoubleQuotedString(value) => value.clone(),
+            Token::Word(word) => word.value.clone(),
+            _ => continue,
+        };
+        match key.value.to_uppercase().as_str() {
+            "TABLE" => table = Some(value),
+            "DB" => database = Some(value),
+            "QUERY" => query = Some(value),
+            _ => {}
+        }
+    }
+
+    match (table, query) {
+        (Some(table), _) => vec![TableReference {
+            database,
+            table,
+            alias: None,
+        }],
+        // Unqualified tables of the query are read from the source database
+        (None, Some(query)) => extract_source_tables_from_query(&query)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|table_ref| TableReference {
+                database: table_ref.database.or_else(|| database.clone()),
+                ..table_ref
+            })
+            .collect(),
+        (None, None) => Vec::new(),
+    }
+}
+
 fn object_name_to_string(name: &ObjectName) -> String {
     // Use Display trait and strip backticks
     // Note: ObjectName is just an identifier, not a type, so Display is appropriate
@@ -1336,6 +1547,19 @@ pub mod tests {
 
     pub const NESTED_OBJECTS_SQL: &str = "CREATE TABLE local.NestedObjects (`id` String, `timestamp` DateTime('UTC'), `address` Nested(street String, city String, coordinates Nested(lat Float64, lng Float64)), `metadata` Nested(tags Array(String), priority Int64, config Nested(enabled Bool, settings Nested(theme String, notifications Bool)))) ENGINE = MergeTree PRIMARY KEY id ORDER BY id SETTINGS enable_mixed_granularity_parts = 1, index_granularity = 8192, index_granularity_bytes = 10485760";
 
+    /// The backtick-quoted identifiers of `sql`, unescaped the way the lexer reads them
+    pub fn quoted_identifiers(sql: &str) -> Vec<String> {
+        Tokenizer::new(&ClickHouseDialect {}, sql)
+            .tokenize()
+            .expect("SQL should tokenize")
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Word(word) if word.quote_style == Some('`') => Some(word.value),
+                _ => None,
+            })
+            .collect()
+    }
+
     // Tests for extract_engine_from_create_table
     #[test]
     fn test_extract_simple_merge_tree() {
@@ -2375,6 +2599,88 @@ pub mod tests {
         assert!(!normalized_ch.contains("AS `table`"));
     }
 
+    /// User-authored SQL paired with what ClickHouse shows for it.
+    const EQUIVALENT_SQL_PAIRS: &[(&str, &str)] = &[
+        (
+            "CREATE MATERIALIZED VIEW BarAggregated_MV TO BarAggregated AS\n\
+             -- one row per day\n\
+             SELECT\n    toDayOfMonth(utcTimestamp) as dayOfMonth,\n    count(primaryKey) as totalRows\n\
+             FROM Bar /* raw events */\nGROUP BY toDayOfMonth(utcTimestamp)",
+            "CREATE MATERIALIZED VIEW local.BarAggregated_MV TO local.BarAggregated AS SELECT toDayOfMonth(utcTimestamp) AS dayOfMonth, count(primaryKey) AS totalRows FROM local.Bar GROUP BY toDayOfMonth(utcTimestamp)",
+        ),
+        (
+            "SELECT\n\t`u`.`id`,   `u`.`name`\n  FROM users AS u\n  INNER JOIN orders AS o ON u.id = o.user_id -- only buyers\n  WHERE o.status = 'paid'",
+            "SELECT u.id, u.name FROM local.users AS u INNER JOIN local.orders AS o ON u.id = o.user_id WHERE o.status = 'paid'",
+        ),
+        (
+            "/* daily totals */ select date, sum(amount) as total from `local`.`Sales` group by date",
+            "SELECT date, sum(amount) AS total FROM local.Sales GROUP BY date",
+        ),
+        (
+            "SELECT id FROM events WHERE note = 'keep -- this' /* and\nthis comment goes */",
+            "SELECT id FROM local.events WHERE note = 'keep -- this'",
+        ),
+    ];
+
+    /// Pairs that differ in more than layout and must not compare equal.
+    const DIFFERENT_SQL_PAIRS: &[(&str, &str)] = &[
+        (
+            "SELECT id FROM events WHERE note = 'a  b'",
+            "SELECT id FROM events WHERE note = 'a b'",
+        ),
+        (
+            "SELECT id FROM events WHERE note = '-- x'",
+            "SELECT id FROM events WHERE note = ''",
+        ),
+        ("SELECT id FROM local.events", "SELECT id FROM other.events"),
+        ("SELECT id, name FROM users", "SELECT name, id FROM users"),
+        ("SELECT Id FROM users", "SELECT id FROM users"),
+        (
+            "SELECT count(id) AS total FROM users",
+            "SELECT count(id) AS total FROM users WHERE active",
+        ),
+    ];
+
+    #[test]
+    fn test_normalize_sql_regression_corpus() {
+        for (user_sql, ch_sql) in EQUIVALENT_SQL_PAIRS {
+            assert_eq!(
+                normalize_sql_for_comparison(user_sql, "local"),
+                normalize_sql_for_comparison(ch_sql, "local"),
+                "Expected equal:\n{user_sql}\n{ch_sql}"
+            );
+        }
+        for (left, right) in DIFFERENT_SQL_PAIRS {
+            assert_ne!(
+                normalize_sql_for_comparison(left, "local"),
+                normalize_sql_for_comparison(right, "local"),
+                "Expected different:\n{left}\n{right}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_sql_tokens() {
+        // Used when the parser rejects the statement
+        let user_sql = "SELECT arrayMap(x -> x * 2, `readings`) -- doubled\nFROM local.sensor_data\n/* a\nb */ SETTINGS  max_threads = 1";
+        let ch_sql =
+            "select arrayMap(x -> x * 2, readings) from sensor_data settings max_threads = 1";
+
+        let normalized = normalize_sql_tokens(user_sql, "local").unwrap();
+        assert_eq!(normalized, normalize_sql_tokens(ch_sql, "local").unwrap());
+        assert!(!normalized.contains("doubled"));
+        assert!(!normalized.contains("local"));
+        assert!(normalized.starts_with("SELECT "));
+
+        // Strings are kept verbatim, even when they look like comments
+        let normalized = normalize_sql_tokens("SELECT '/* x */  -- y' FROM t", "").unwrap();
+        assert!(normalized.contains("'/* x */  -- y'"));
+
+        // Only a leading database qualifier is the default database
+        let normalized = normalize_sql_tokens("SELECT t.local.c FROM t", "local").unwrap();
+        assert!(normalized.contains("local"));
+    }
+
     #[test]
     fn test_extract_source_tables_with_standard_sql() {
         let sql = "SELECT a.id, b.name FROM users a JOIN orders b ON a.id = b.user_id";
@@ -2912,4 +3218,60 @@ ORDER BY id"#;
             "Raw body should be trimmed but internal whitespace preserved"
         );
     }
+
+    #[test]
+    fn test_extract_dictionary_source_tables() {
+        let sql = "CREATE DICTIONARY local.products_dict (`id` UInt64, `name` String) \
+                   PRIMARY KEY id \
+                   SOURCE(CLICKHOUSE(HOST 'localhost' PORT 9000 USER 'default' TABLE 'products' PASSWORD '[HIDDEN]' DB 'shop')) \
+                   LIFETIME(MIN 0 MAX 300) LAYOUT(HASHED())";
+        assert!(is_create_dictionary(sql));
+        assert_eq!(
+            extract_dictionary_source_tables(sql),
+            vec![TableReference::with_database(
+                "shop".to_string(),
+                "products".to_string()
+            )]
+        );
+
+        let query_source = "CREATE DICTIONARY IF NOT EXISTS names (id UInt64, name String) \
+                            PRIMARY KEY id \
+                            SOURCE(CLICKHOUSE(QUERY 'SELECT id, name FROM users')) \
+                            LIFETIME(300) LAYOUT(FLAT())";
+        assert_eq!(
+            extract_dictionary_source_tables(query_source),
+            vec![TableReference::new("users".to_string())]
+        );
+
+        let file_source = "CREATE DICTIONARY regions (id UInt64, name String) PRIMARY KEY id \
+                           SOURCE(FILE(path './regions.csv' format 'CSV')) \
+                           LIFETIME(0) LAYOUT(FLAT())";
+        assert!(extract_dictionary_source_tables(file_source).is_empty());
+        assert!(!is_create_dictionary(
+            "CREATE VIEW v AS SELECT * FROM products"
+        ));
+    }
+
+    #[test]
+    fn test_extract_ttl_columns() {
+        assert_eq!(
+            extract_ttl_columns("created_at + INTERVAL 30 DAY").unwrap(),
+            vec!["created_at"]
+        );
+        assert_eq!(
+            extract_ttl_columns(
+                "toStartOfDay(`createdAt`) + toIntervalDay(30) DELETE, toDate(updated_at) + INTERVAL 1 WEEK TO VOLUME 'cold'"
+            )
+            .unwrap(),
+            vec!["createdAt", "updated_at"]
+        );
+        assert_eq!(
+            extract_ttl_columns("ts + INTERVAL 1 DAY DELETE WHERE arrayExists(x -> x > 1, scores)")
+                .unwrap(),
+            vec!["ts"]
+        );
+        assert!(extract_ttl_columns("now() + INTERVAL 1 DAY")
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -0,0 +1,130 @@
+//! Latency and batch metrics of the topic to table syncs.
+//!
+//! Records consumed by a sync carry the timestamp of their Kafka message.
+//! When a batch is written, [`SyncMetrics::record_flush`] observes how long
+//! each of its records took from the topic to the table into
+//! `moose_sync_latency_seconds`, along with the batch size and the duration of
+//! the insert, labelled with the source topic and the target table.
+
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::histogram::{exponential_buckets, Histogram};
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct SyncLabels {
+    pub topic: String,
+    pub table: String,
+}
+
+lazy_static! {
+    /// Seconds from the Kafka message timestamp to the end of the insert,
+    /// from 1ms to about 9 minutes.
+    pub static ref SYNC_LATENCY: Family<SyncLabels, Histogram> =
+        Family::new_with_constructor(|| Histogram::new(exponential_buckets(0.001, 2.0, 20)));
+    /// Records per inserted batch, from 1 to 131072.
+    pub static ref SYNC_BATCH_SIZE: Family<SyncLabels, Histogram> =
+        Family::new_with_constructor(|| Histogram::new(exponential_buckets(1.0, 2.0, 18)));
+    /// Seconds taken by each insert, from 1ms to about 30 seconds.
+    pub static ref SYNC_FLUSH_DURATION: Family<SyncLabels, Histogram> =
+        Family::new_with_constructor(|| Histogram::new(exponential_buckets(0.001, 2.0, 16)));
+}
+
+/// Seconds between a Kafka message timestamp and `completed_at`, both in
+/// milliseconds since the epoch.
+///
+/// Producer clocks can be ahead of ours, so a timestamp in the future counts
+/// as no latency rather than a negative one.
+pub fn latency_seconds(kafka_timestamp_ms: i64, completed_at_ms: i64) -> f64 {
+    completed_at_ms.saturating_sub(kafka_timestamp_ms).max(0) as f64 / 1000.0
+}
+
+/// The histograms of one sync, resolved once instead of at every flush.
+pub struct SyncMetrics {
+    latency: Histogram,
+    batch_size: Histogram,
+    flush_duration: Histogram,
+}
+
+impl SyncMetrics {
+    pub fn new(topic: &str, table: &str) -> Self {
+        let labels = SyncLabels {
+            topic: topic.to_string(),
+            table: table.to_string(),
+        };
+        Self {
+            latency: SYNC_LATENCY.get_or_create(&labels).clone(),
+            batch_size: SYNC_BATCH_SIZE.get_or_create(&labels).clone(),
+            flush_duration: SYNC_FLUSH_DURATION.get_or_create(&labels).clone(),
+        }
+    }
+
+    /// Records a batch of `records` written in `duration` and completed at
+    /// `completed_at_ms`, with the Kafka timestamps of the records that had one.
+    pub fn record_flush(
+        &self,
+        records: usize,
+        kafka_timestamps: &[i64],
+        duration: Duration,
+        completed_at_ms: i64,
+    ) {
+        self.batch_size.observe(records as f64);
+        self.flush_duration.observe(duration.as_secs_f64());
+        for timestamp in kafka_timestamps {
+            self.latency
+                .observe(latency_seconds(*timestamp, completed_at_ms));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus_client::encoding::text::encode;
+    use prometheus_client::registry::Registry;
+
+    #[test]
+    fn test_latency_seconds() {
+        assert_eq!(latency_seconds(1_000, 3_500), 2.5);
+        assert_eq!(latency_seconds(1_000, 1_000), 0.0);
+    }
+
+    #[test]
+    fn test_latency_seconds_clamps_clock_skew() {
+        // Producer clock ahead of ours
+        assert_eq!(latency_seconds(5_000, 3_000), 0.0);
+        assert_eq!(latency_seconds(i64::MAX, i64::MIN), 0.0);
+    }
+
+    #[test]
+    fn test_record_flush() {
+        let metrics = SyncMetrics::new("sync_metrics_topic", "sync_metrics_table");
+        metrics.record_flush(3, &[1_000, 2_000, 9_000], Duration::from_millis(250), 4_000);
+
+        let mut registry = Registry::default();
+        registry.register("latency", "", SYNC_LATENCY.clone());
+        registry.register("batch", "", SYNC_BATCH_SIZE.clone());
+        registry.register("flush", "", SYNC_FLUSH_DURATION.clone());
+        let mut exposition = String::new();
+        encode(&mut exposition, &registry).unwrap();
+
+        let value = |name: &str| -> f64 {
+            let line = exposition
+                .lines()
+                .find(|line| {
+                    line.starts_with(&format!("{name}{{"))
+                        && line.contains("table=\"sync_metrics_table\"")
+                })
+                .unwrap_or_else(|| panic!("no {name} in {exposition}"));
+            line.rsplit(' ').next().unwrap().parse().unwrap()
+        };
+        // 3s + 2s + 0s, the last record being stamped in the future
+        assert_eq!(value("latency_sum"), 5.0);
+        assert_eq!(value("latency_count"), 3.0);
+        assert_eq!(value("batch_sum"), 3.0);
+        assert_eq!(value("batch_count"), 1.0);
+        assert_eq!(value("flush_sum"), 0.25);
+    }
+}
@@ -0,0 +1,311 @@
+//! Checks that ClickHouse accepts DDL before a plan runs.
+//!
+//! A server in readonly mode, during replica recovery or cloud maintenance,
+//! rejects every DDL of a plan one by one with READONLY errors, leaving it half
+//! applied. The plan is aborted before its first operation instead, or runs
+//! once the server is writable again when a wait is configured.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::time::Instant;
+use tracing::info;
+
+use super::{ConfiguredDBClient, SerializableOlapOperation};
+
+/// Delay between two checks while waiting for the server to be writable
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Why ClickHouse cannot accept DDL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotWritable {
+    /// The `readonly` setting of the session is not 0
+    ReadonlyServer { level: String },
+    /// Replicas of tables of the plan are readonly, as `database.table`
+    ReadonlyReplicas(Vec<String>),
+}
+
+impl std::fmt::Display for NotWritable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotWritable::ReadonlyServer { level } => {
+                write!(f, "the server is in readonly mode (readonly = {level})")
+            }
+            NotWritable::ReadonlyReplicas(tables) => {
+                write!(f, "the replicas of {} are readonly", tables.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum WritabilityError {
+    #[error("ClickHouse cannot accept DDL: {0}. No operation was run. Retry once it is writable, or pass --wait-for-writable <duration> to wait for it")]
+    NotWritable(NotWritable),
+
+    #[error("ClickHouse was still not writable after waiting {}: {reason}. No operation was run", humantime::format_duration(*.waited))]
+    Timeout {
+        waited: Duration,
+        reason: NotWritable,
+    },
+
+    #[error("Failed to check whether ClickHouse accepts DDL")]
+    Check(#[from] clickhouse::error::Error),
+}
+
+/// The queries telling whether ClickHouse accepts DDL
+#[async_trait]
+pub trait WritabilityProbe: Sync {
+    /// Value of the `readonly` setting
+    async fn readonly_setting(&self) -> Result<String, clickhouse::error::Error>;
+
+    /// `(database, table)` of the readonly replicas
+    async fn readonly_replicas(&self) -> Result<Vec<(String, String)>, clickhouse::error::Error>;
+}
+
+#[derive(clickhouse::Row, serde::Deserialize)]
+struct ReplicaRow {
+    database: String,
+    table: String,
+}
+
+#[async_trait]
+impl WritabilityProbe for ConfiguredDBClient {
+    async fn readonly_setting(&self) -> Result<String, clickhouse::error::Error> {
+        self.client
+            .query("SELECT value FROM system.settings WHERE name = 'readonly'")
+            .fetch_one::<String>()
+            .await
+    }
+
+    async fn readonly_replicas(&self) -> Result<Vec<(String, String)>, clickhouse::error::Error> {
+        let rows = self
+            .client
+            .query("SELECT database, table FROM system.replicas WHERE is_readonly")
+            .fetch_all::<ReplicaRow>()
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.database, row.table))
+            .collect())
+    }
+}
+
+/// `(database, table)` of the tables and views `operations` touch
+pub fn plan_tables<'a>(
+    operations: impl IntoIterator<Item = &'a SerializableOlapOperation>,
+    default_database: &str,
+) -> Vec<(String, String)> {
+    let mut tables: Vec<(String, String)> = operations
+        .into_iter()
+        .filter_map(|operation| operation.target_table())
+        .map(|(name, database)| {
+            (
+                database.as_deref().unwrap_or(default_database).to_string(),
+                name.clone(),
+            )
+        })
+        .collect();
+    tables.sort();
+    tables.dedup();
+    tables
+}
+
+/// Checks whether the server accepts DDL for `tables`, returning why not.
+/// Only replicated tables can have readonly replicas.
+pub async fn check_writable(
+    probe: &dyn WritabilityProbe,
+    tables: &[(String, String)],
+) -> Result<Option<NotWritable>, clickhouse::error::Error> {
+    let level = probe.readonly_setting().await?;
+    if level.trim() != "0" {
+        return Ok(Some(NotWritable::ReadonlyServer {
+            level: level.trim().to_string(),
+        }));
+    }
+    if tables.is_empty() {
+        return Ok(None);
+    }
+
+    let mut readonly: Vec<String> = probe
+        .readonly_replicas()
+        .await?
+        .into_iter()
+        .filter(|replica| tables.contains(replica))
+        .map(|(database, table)| format!("{database}.{table}"))
+        .collect();
+    if readonly.is_empty() {
+        return Ok(None);
+    }
+    readonly.sort();
+    Ok(Some(NotWritable::ReadonlyReplicas(readonly)))
+}
+
+/// Fails unless the server accepts DDL for `tables`. With `wait`, polls until
+/// it does, failing once `wait` has passed.
+pub async fn ensure_writable(
+    probe: &dyn WritabilityProbe,
+    tables: &[(String, String)],
+    wait: Option<Duration>,
+) -> Result<(), WritabilityError> {
+    let start = Instant::now();
+    loop {
+        let Some(reason) = check_writable(probe, tables).await? else {
+            return Ok(());
+        };
+        let Some(wait) = wait else {
+            return Err(WritabilityError::NotWritable(reason));
+        };
+        let waited = start.elapsed();
+        if waited >= wait {
+            return Err(WritabilityError::Timeout { waited, reason });
+        }
+        info!("ClickHouse cannot accept DDL yet: {reason}. Waiting for it to be writable");
+        tokio::time::sleep(POLL_INTERVAL.min(wait - waited)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Answers the queries with scripted responses, the last one repeating
+    struct MockProbe {
+        settings: Mutex<Vec<&'static str>>,
+        replicas: Vec<(String, String)>,
+    }
+
+    impl MockProbe {
+        fn new(settings: &[&'static str], replicas: &[(&str, &str)]) -> Self {
+            Self {
+                settings: Mutex::new(settings.iter().rev().copied().collect()),
+                replicas: replicas
+                    .iter()
+                    .map(|(database, table)| (database.to_string(), table.to_string()))
+                    .collect(),
+            }
+        }
+
+        fn remaining(&self) -> usize {
+            self.settings.lock().unwrap().len()
+        }
+    }
+
+    #[async_trait]
+    impl WritabilityProbe for MockProbe {
+        async fn readonly_setting(&self) -> Result<String, clickhouse::error::Error> {
+            let mut settings = self.settings.lock().unwrap();
+            let setting = if settings.len() > 1 {
+                settings.pop().unwrap()
+            } else {
+                settings[0]
+            };
+            Ok(setting.to_string())
+        }
+
+        async fn readonly_replicas(
+            &self,
+        ) -> Result<Vec<(String, String)>, clickhouse::error::Error> {
+            Ok(self.replicas.clone())
+        }
+    }
+
+    fn tables(tables: &[(&str, &str)]) -> Vec<(String, String)> {
+        tables
+            .iter()
+            .map(|(database, table)| (database.to_string(), table.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_writable_server() {
+        let probe = MockProbe::new(&["0"], &[("local", "other")]);
+        let plan = tables(&[("local", "events")]);
+
+        assert_eq!(check_writable(&probe, &plan).await.unwrap(), None);
+        assert!(ensure_writable(&probe, &plan, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_readonly_server() {
+        let probe = MockProbe::new(&["1"], &[]);
+
+        let err = ensure_writable(&probe, &[], None).await.unwrap_err();
+        assert!(matches!(
+            &err,
+            WritabilityError::NotWritable(NotWritable::ReadonlyServer { level }) if level == "1"
+        ));
+        assert!(err.to_string().contains("--wait-for-writable"));
+    }
+
+    #[tokio::test]
+    async fn test_readonly_replicas_of_the_plan() {
+        let probe = MockProbe::new(&["0"], &[("local", "events"), ("local", "other")]);
+
+        assert_eq!(
+            check_writable(&probe, &tables(&[("local", "events"), ("local", "users")]))
+                .await
+                .unwrap(),
+            Some(NotWritable::ReadonlyReplicas(vec![
+                "local.events".to_string()
+            ]))
+        );
+        // Replicas of tables the plan does not touch do not block it
+        assert_eq!(
+            check_writable(&probe, &tables(&[("local", "users")]))
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_waits_until_writable() {
+        let probe = MockProbe::new(&["1", "1", "0"], &[]);
+        let start = Instant::now();
+
+        ensure_writable(&probe, &[], Some(Duration::from_secs(60)))
+            .await
+            .unwrap();
+        assert_eq!(probe.remaining(), 1);
+        assert_eq!(start.elapsed(), POLL_INTERVAL * 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_times_out() {
+        let probe = MockProbe::new(&["1"], &[]);
+
+        let err = ensure_writable(&probe, &[], Some(Duration::from_secs(12)))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            WritabilityError::Timeout { waited, .. } if waited == Duration::from_secs(12)
+        ));
+    }
+
+    #[test]
+    fn test_plan_tables() {
+        let drop = |table: &str, database: Option<&str>| SerializableOlapOperation::DropTable {
+            table: table.to_string(),
+            database: database.map(str::to_string),
+            cluster_name: None,
+        };
+        let operations = [
+            drop("users", None),
+            drop("events", Some("archive")),
+            drop("users", Some("local")),
+            SerializableOlapOperation::RawSql {
+                sql: vec!["SELECT 1".to_string()],
+                description: "raw".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            plan_tables(&operations, "local"),
+            tables(&[("archive", "events"), ("local", "users")])
+        );
+    }
+}
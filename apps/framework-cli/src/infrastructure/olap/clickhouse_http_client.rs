@@ -94,6 +94,8 @@ mod tests {
             host_data_path: None,
             additional_databases: vec![],
             clusters: None,
+            ddl_parallelism: 4,
+            insert_error_threshold: 10,
         };
 
         let client = create_query_client(&config);
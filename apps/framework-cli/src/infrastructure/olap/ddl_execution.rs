@@ -0,0 +1,224 @@
+//! Concurrent execution of ordered DDL plans.
+//!
+//! Operations run as soon as the operations they depend on (see
+//! `ddl_ordering::execution_dependencies`) have succeeded, with at most
+//! `parallelism` of them in flight. When one fails, the operations that depend
+//! on it, directly or not, are not started; independent ones still run, and
+//! every failure is returned.
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::collections::BTreeSet;
+use std::future::Future;
+
+/// Default number of DDL operations run at once
+pub const DEFAULT_DDL_PARALLELISM: usize = 4;
+
+/// Runs `execute` on each operation, honoring `dependencies` and running at
+/// most `parallelism` operations at once. Ready operations start in plan
+/// order, so a parallelism of 1 runs the plan sequentially.
+///
+/// # Arguments
+/// * `operations` - The operations, in plan order
+/// * `dependencies` - For each operation, the indices of the earlier operations it waits for
+/// * `parallelism` - Maximum number of operations in flight, at least 1
+/// * `execute` - Runs one operation
+///
+/// # Returns
+/// The errors of the failed operations, in the order they failed
+pub async fn execute_concurrently<'a, T, E, F, Fut>(
+    operations: &'a [T],
+    dependencies: &[Vec<usize>],
+    parallelism: usize,
+    mut execute: F,
+) -> Vec<E>
+where
+    F: FnMut(&'a T) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    let parallelism = parallelism.max(1);
+
+    let mut waiting_on: Vec<usize> = dependencies.iter().map(Vec::len).collect();
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); operations.len()];
+    for (i, deps) in dependencies.iter().enumerate() {
+        for &dep in deps {
+            dependents[dep].push(i);
+        }
+    }
+
+    let mut cancelled = vec![false; operations.len()];
+    let mut ready: BTreeSet<usize> = (0..operations.len())
+        .filter(|&i| waiting_on[i] == 0)
+        .collect();
+    let mut in_flight = FuturesUnordered::new();
+    let mut errors = Vec::new();
+
+    loop {
+        while in_flight.len() < parallelism {
+            let Some(index) = ready.pop_first() else {
+                break;
+            };
+            let operation = execute(&operations[index]);
+            in_flight.push(async move { (index, operation.await) });
+        }
+
+        let Some((index, result)) = in_flight.next().await else {
+            break;
+        };
+
+        match result {
+            Ok(()) => {
+                for &dependent in &dependents[index] {
+                    waiting_on[dependent] -= 1;
+                    if waiting_on[dependent] == 0 && !cancelled[dependent] {
+                        ready.insert(dependent);
+                    }
+                }
+            }
+            Err(e) => {
+                errors.push(e);
+                let mut to_cancel = dependents[index].clone();
+                while let Some(dependent) = to_cancel.pop() {
+                    if !cancelled[dependent] {
+                        cancelled[dependent] = true;
+                        to_cancel.extend(&dependents[dependent]);
+                    }
+                }
+            }
+        }
+    }
+
+    let skipped = cancelled.iter().filter(|&&c| c).count();
+    if skipped > 0 {
+        tracing::warn!(
+            "Skipped {} operation(s) depending on failed operations",
+            skipped
+        );
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// Records when each operation starts and ends, and how many run at once.
+    #[derive(Default)]
+    struct MockExecutor {
+        events: Mutex<Vec<(usize, &'static str)>>,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl MockExecutor {
+        async fn run(&self, operation: usize, fails: bool) -> Result<(), String> {
+            self.events.lock().unwrap().push((operation, "start"));
+            let running = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(running, Ordering::SeqCst);
+
+            // Later operations finish first, to shake out ordering bugs
+            tokio::time::sleep(Duration::from_millis(20 - operation as u64 % 20)).await;
+
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            self.events.lock().unwrap().push((operation, "end"));
+            if fails {
+                Err(format!("operation {operation} failed"))
+            } else {
+                Ok(())
+            }
+        }
+
+        fn position(&self, operation: usize, event: &str) -> Option<usize> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .position(|&(op, e)| op == operation && e == event)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dependencies_finish_before_dependents_start() {
+        // 0 -> 2 -> 4, 1 -> 3, 1 -> 4, and 5..12 independent
+        let mut dependencies = vec![vec![], vec![], vec![0], vec![1], vec![1, 2]];
+        dependencies.extend((5..12).map(|_| vec![]));
+        let operations: Vec<usize> = (0..dependencies.len()).collect();
+        let executor = MockExecutor::default();
+
+        let errors =
+            execute_concurrently(&operations, &dependencies, 4, |&op| executor.run(op, false))
+                .await;
+
+        assert!(errors.is_empty());
+        for (operation, deps) in dependencies.iter().enumerate() {
+            let start = executor.position(operation, "start").unwrap();
+            for &dep in deps {
+                let end = executor.position(dep, "end").unwrap();
+                assert!(end < start, "{operation} started before {dep} ended");
+            }
+        }
+        assert!(executor.max_in_flight.load(Ordering::SeqCst) > 1);
+    }
+
+    #[tokio::test]
+    async fn test_parallelism_bounds_in_flight_operations() {
+        let operations: Vec<usize> = (0..20).collect();
+        let dependencies = vec![vec![]; operations.len()];
+
+        for parallelism in [1, 3, 8] {
+            let executor = MockExecutor::default();
+            let errors = execute_concurrently(&operations, &dependencies, parallelism, |&op| {
+                executor.run(op, false)
+            })
+            .await;
+
+            assert!(errors.is_empty());
+            assert_eq!(
+                executor.max_in_flight.load(Ordering::SeqCst),
+                parallelism,
+                "parallelism {parallelism}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parallelism_of_one_runs_in_plan_order() {
+        let operations: Vec<usize> = (0..6).collect();
+        let dependencies = vec![vec![], vec![], vec![1], vec![], vec![2], vec![]];
+        let executor = MockExecutor::default();
+
+        execute_concurrently(&operations, &dependencies, 1, |&op| executor.run(op, false)).await;
+
+        let starts: Vec<usize> = executor
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, event)| *event == "start")
+            .map(|(op, _)| *op)
+            .collect();
+        assert_eq!(starts, operations);
+    }
+
+    #[tokio::test]
+    async fn test_failures_cancel_dependents_and_are_all_reported() {
+        // 0 fails, cancelling 2 and, through it, 3. 1 fails too, 4 still runs.
+        let operations: Vec<usize> = (0..5).collect();
+        let dependencies = vec![vec![], vec![], vec![0], vec![2], vec![]];
+        let executor = MockExecutor::default();
+
+        let mut errors = execute_concurrently(&operations, &dependencies, 4, |&op| {
+            executor.run(op, op <= 1)
+        })
+        .await;
+
+        errors.sort();
+        assert_eq!(errors, ["operation 0 failed", "operation 1 failed"]);
+        assert!(executor.position(2, "start").is_none());
+        assert!(executor.position(3, "start").is_none());
+        assert!(executor.position(4, "end").is_some());
+    }
+}
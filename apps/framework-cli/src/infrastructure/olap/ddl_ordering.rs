@@ -6,11 +6,15 @@ use crate::framework::core::infrastructure::InfrastructureSignature;
 use crate::framework::core::infrastructure_map::{Change, ColumnChange, OlapChange, TableChange};
 #[cfg(test)]
 use crate::infrastructure::olap::clickhouse::config::DEFAULT_DATABASE_NAME;
+use crate::infrastructure::olap::clickhouse::model::{
+    on_cluster_clause, quote_ident, quote_qualified,
+};
+use crate::infrastructure::olap::clickhouse::sql_parser::extract_dictionary_source_tables;
 use crate::infrastructure::olap::clickhouse::SerializableOlapOperation;
 use petgraph::algo::toposort;
 use petgraph::graph::{DiGraph, NodeIndex};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Represents a dependency edge between two resources
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -49,6 +53,22 @@ pub enum AtomicOlapOperation {
         /// Dependency information
         dependency_info: DependencyInfo,
     },
+    /// Empty a table, keeping it in the database
+    TruncateTable {
+        /// The table to truncate
+        table: Table,
+        /// Dependency information
+        dependency_info: DependencyInfo,
+    },
+    /// Rename a table
+    RenameTable {
+        /// The table to rename
+        table: Table,
+        /// Name the table is renamed to
+        new_name: String,
+        /// Dependency information
+        dependency_info: DependencyInfo,
+    },
     /// Add a column to a table
     AddTableColumn {
         /// The table to add the column to
@@ -133,6 +153,12 @@ pub enum AtomicOlapOperation {
         table: Table,
         dependency_info: DependencyInfo,
     },
+    /// Extend the ORDER BY of a table with newly added columns
+    ModifyOrderBy {
+        table: Table,
+        expression: String,
+        dependency_info: DependencyInfo,
+    },
     /// Populate a materialized view with initial data
     PopulateMaterializedView {
         /// Name of the materialized view
@@ -223,6 +249,25 @@ impl AtomicOlapOperation {
                 database: table.database.clone(),
                 cluster_name: table.cluster_name.clone(),
             },
+            AtomicOlapOperation::TruncateTable { table, .. } => {
+                let target = match &table.database {
+                    Some(database) => quote_qualified(database, &table.name),
+                    None => quote_ident(&table.name),
+                };
+                let cluster_clause = on_cluster_clause(table.cluster_name.as_deref());
+                SerializableOlapOperation::RawSql {
+                    sql: vec![format!("TRUNCATE TABLE {target}{cluster_clause}")],
+                    description: format!("Truncating table {} instead of dropping it", table.name),
+                }
+            }
+            AtomicOlapOperation::RenameTable {
+                table, new_name, ..
+            } => SerializableOlapOperation::RenameTable {
+                table: table.name.clone(),
+                new_name: new_name.clone(),
+                database: table.database.clone(),
+                cluster_name: table.cluster_name.clone(),
+            },
             AtomicOlapOperation::AddTableColumn {
                 table,
                 column,
@@ -330,6 +375,14 @@ impl AtomicOlapOperation {
                     cluster_name: table.cluster_name.clone(),
                 }
             }
+            AtomicOlapOperation::ModifyOrderBy {
+                table, expression, ..
+            } => SerializableOlapOperation::ModifyOrderBy {
+                table: table.name.clone(),
+                expression: expression.clone(),
+                database: table.database.clone(),
+                cluster_name: table.cluster_name.clone(),
+            },
             AtomicOlapOperation::PopulateMaterializedView {
                 view_name: _,
                 target_table,
@@ -342,20 +395,25 @@ impl AtomicOlapOperation {
 
                 if *should_truncate {
                     let truncate_sql = if let Some(database) = target_database {
-                        format!("TRUNCATE TABLE `{}`.`{}`", database, target_table)
+                        format!("TRUNCATE TABLE {}", quote_qualified(database, target_table))
                     } else {
-                        format!("TRUNCATE TABLE `{}`", target_table)
+                        format!("TRUNCATE TABLE {}", quote_ident(target_table))
                     };
                     sqls.push(truncate_sql);
                 }
 
                 let insert_sql = if let Some(database) = target_database {
                     format!(
-                        "INSERT INTO `{}`.`{}` {}",
-                        database, target_table, select_statement
+                        "INSERT INTO {} {}",
+                        quote_qualified(database, target_table),
+                        select_statement
                     )
                 } else {
-                    format!("INSERT INTO `{}` {}", target_table, select_statement)
+                    format!(
+                        "INSERT INTO {} {}",
+                        quote_ident(target_table),
+                        select_statement
+                    )
                 };
                 sqls.push(insert_sql);
 
@@ -381,8 +439,9 @@ impl AtomicOlapOperation {
                     ..
                 } = view;
                 let query = format!(
-                    "CREATE VIEW IF NOT EXISTS `{}` AS SELECT * FROM `{source_table_name}`;",
-                    view.id(),
+                    "CREATE VIEW IF NOT EXISTS {} AS SELECT * FROM {};",
+                    quote_ident(&view.id()),
+                    quote_ident(source_table_name),
                 );
                 SerializableOlapOperation::RawSql {
                     sql: vec![query],
@@ -446,6 +505,12 @@ impl AtomicOlapOperation {
             AtomicOlapOperation::DropTable { table, .. } => InfrastructureSignature::Table {
                 id: table.id(default_database),
             },
+            AtomicOlapOperation::TruncateTable { table, .. } => InfrastructureSignature::Table {
+                id: table.id(default_database),
+            },
+            AtomicOlapOperation::RenameTable { table, .. } => InfrastructureSignature::Table {
+                id: table.id(default_database),
+            },
             AtomicOlapOperation::AddTableColumn { table, .. } => InfrastructureSignature::Table {
                 id: table.id(default_database),
             },
@@ -487,6 +552,9 @@ impl AtomicOlapOperation {
             AtomicOlapOperation::RemoveSampleBy { table, .. } => InfrastructureSignature::Table {
                 id: table.id(default_database),
             },
+            AtomicOlapOperation::ModifyOrderBy { table, .. } => InfrastructureSignature::Table {
+                id: table.id(default_database),
+            },
             AtomicOlapOperation::PopulateMaterializedView { view_name, .. } => {
                 InfrastructureSignature::SqlResource {
                     id: view_name.clone(),
@@ -536,6 +604,12 @@ impl AtomicOlapOperation {
             | AtomicOlapOperation::DropTable {
                 dependency_info, ..
             }
+            | AtomicOlapOperation::TruncateTable {
+                dependency_info, ..
+            }
+            | AtomicOlapOperation::RenameTable {
+                dependency_info, ..
+            }
             | AtomicOlapOperation::AddTableColumn {
                 dependency_info, ..
             }
@@ -569,6 +643,9 @@ impl AtomicOlapOperation {
             | AtomicOlapOperation::RemoveSampleBy {
                 dependency_info, ..
             }
+            | AtomicOlapOperation::ModifyOrderBy {
+                dependency_info, ..
+            }
             | AtomicOlapOperation::PopulateMaterializedView {
                 dependency_info, ..
             }
@@ -819,6 +896,21 @@ fn handle_table_remove(table: &Table) -> OperationPlan {
     OperationPlan::teardown(vec![drop_table_operation(table)])
 }
 
+fn handle_table_truncate(table: &Table) -> OperationPlan {
+    OperationPlan::teardown(vec![AtomicOlapOperation::TruncateTable {
+        table: table.clone(),
+        dependency_info: create_empty_dependency_info(),
+    }])
+}
+
+fn handle_table_rename(table: &Table, new_name: &str) -> OperationPlan {
+    OperationPlan::teardown(vec![AtomicOlapOperation::RenameTable {
+        table: table.clone(),
+        new_name: new_name.to_string(),
+        dependency_info: create_empty_dependency_info(),
+    }])
+}
+
 /// Handles updating a table operation
 ///
 /// Process column-level changes and settings changes for a table update.
@@ -965,6 +1057,24 @@ fn handle_table_update(
     column_changes: &[ColumnChange],
 ) -> OperationPlan {
     let mut plan = handle_table_column_updates(before, after, column_changes);
+    // The diff strategy only keeps ORDER BY changes that extend the sorting key
+    // with added columns, which must directly follow their addition to run in
+    // the same ALTER TABLE statement
+    if before.order_by != after.order_by {
+        let position = plan
+            .setup_ops
+            .iter()
+            .rposition(|op| matches!(op, AtomicOlapOperation::AddTableColumn { .. }))
+            .map_or(plan.setup_ops.len(), |i| i + 1);
+        plan.setup_ops.insert(
+            position,
+            AtomicOlapOperation::ModifyOrderBy {
+                table: after.clone(),
+                expression: after.order_by.to_expr().into_owned(),
+                dependency_info: create_empty_dependency_info(),
+            },
+        );
+    }
     plan.combine(process_index_changes(before, after));
     plan.combine(process_projection_changes(before, after));
     // SAMPLE BY changes are handled via ALTER TABLE
@@ -1138,10 +1248,32 @@ fn handle_dmv1_view_update(
     plan
 }
 
+/// What a SQL resource pulls data from: its declared lineage, plus the source
+/// tables of the dictionaries it creates, so that they exist before them
+fn sql_resource_pulls_from(
+    resource: &SqlResource,
+    default_database: &str,
+) -> Vec<InfrastructureSignature> {
+    let mut pulls_from = resource.pulls_data_from(default_database);
+    for table_ref in resource
+        .setup
+        .iter()
+        .flat_map(|sql| extract_dictionary_source_tables(sql))
+    {
+        let signature = InfrastructureSignature::Table {
+            id: parse_table_reference_to_id(&table_ref.qualified_name(), default_database),
+        };
+        if !pulls_from.contains(&signature) {
+            pulls_from.push(signature);
+        }
+    }
+    pulls_from
+}
+
 /// Handles adding a SQL resource operation
 /// Handle adding a SQL resource
 fn handle_sql_resource_add(resource: &SqlResource, default_database: &str) -> OperationPlan {
-    let pulls_from = resource.pulls_data_from(default_database);
+    let pulls_from = sql_resource_pulls_from(resource, default_database);
     let pushes_to = resource.pushes_data_to(default_database);
     let setup_op = run_setup_sql_operation(resource, pulls_from, pushes_to);
     OperationPlan::setup(vec![setup_op])
@@ -1149,7 +1281,7 @@ fn handle_sql_resource_add(resource: &SqlResource, default_database: &str) -> Op
 
 /// Handles removing a SQL resource operation
 fn handle_sql_resource_remove(resource: &SqlResource, default_database: &str) -> OperationPlan {
-    let pulls_from = resource.pulls_data_from(default_database);
+    let pulls_from = sql_resource_pulls_from(resource, default_database);
     let pushes_to = resource.pushes_data_to(default_database);
     let teardown_op = run_teardown_sql_operation(resource, pulls_from, pushes_to);
     OperationPlan::teardown(vec![teardown_op])
@@ -1162,11 +1294,11 @@ fn handle_sql_resource_update(
     after: &SqlResource,
     default_database: &str,
 ) -> OperationPlan {
-    let before_pulls = before.pulls_data_from(default_database);
+    let before_pulls = sql_resource_pulls_from(before, default_database);
     let before_pushes = before.pushes_data_to(default_database);
     let teardown_op = run_teardown_sql_operation(before, before_pulls, before_pushes);
 
-    let after_pulls = after.pulls_data_from(default_database);
+    let after_pulls = sql_resource_pulls_from(after, default_database);
     let after_pushes = after.pushes_data_to(default_database);
     let setup_op = run_setup_sql_operation(after, after_pulls, after_pushes);
 
@@ -1326,7 +1458,9 @@ pub fn order_olap_changes(
                 TableChange::Updated { after, .. } => {
                     tables.insert(after.name.clone(), after.clone());
                 }
-                TableChange::Removed(table) => {
+                TableChange::Removed(table)
+                | TableChange::Truncated(table)
+                | TableChange::Renamed { before: table, .. } => {
                     // Keep removed tables for context during teardown
                     tables.insert(table.name.clone(), table.clone());
                 }
@@ -1354,6 +1488,10 @@ pub fn order_olap_changes(
         let change_plan = match change {
             OlapChange::Table(TableChange::Added(table)) => handle_table_add(table),
             OlapChange::Table(TableChange::Removed(table)) => handle_table_remove(table),
+            OlapChange::Table(TableChange::Truncated(table)) => handle_table_truncate(table),
+            OlapChange::Table(TableChange::Renamed { before, new_name }) => {
+                handle_table_rename(before, new_name)
+            }
             OlapChange::Table(TableChange::Updated {
                 before,
                 after,
@@ -1610,6 +1748,74 @@ fn order_operations_by_dependencies(
     Ok(sorted_operations)
 }
 
+/// Returns, for each operation of an ordered plan, the indices of the earlier
+/// operations it has to wait for when operations run concurrently.
+///
+/// An operation waits for the earlier operations on the same resource and for
+/// those it has a dependency edge with. Raw SQL resources and materialized view
+/// backfills may touch resources their lineage does not declare, so they wait
+/// for everything before them and everything after waits for them.
+///
+/// # Arguments
+/// * `plan` - Operations in execution order, as returned by `order_olap_changes`
+/// * `is_teardown` - Whether `plan` is a teardown plan
+/// * `default_database` - The default database name to use for table IDs
+pub fn execution_dependencies(
+    plan: &[AtomicOlapOperation],
+    is_teardown: bool,
+    default_database: &str,
+) -> Vec<Vec<usize>> {
+    let signatures: Vec<InfrastructureSignature> = plan
+        .iter()
+        .map(|op| op.resource_signature(default_database))
+        .collect();
+
+    let edges: HashSet<(InfrastructureSignature, InfrastructureSignature)> = plan
+        .iter()
+        .flat_map(|op| {
+            if is_teardown {
+                op.get_teardown_edges(default_database)
+            } else {
+                op.get_setup_edges(default_database)
+            }
+        })
+        .map(|edge| (edge.dependency, edge.dependent))
+        .collect();
+
+    let is_barrier = |op: &AtomicOlapOperation| {
+        matches!(
+            op,
+            AtomicOlapOperation::RunSetupSql { .. }
+                | AtomicOlapOperation::RunTeardownSql { .. }
+                | AtomicOlapOperation::PopulateMaterializedView { .. }
+        )
+    };
+
+    let mut last_barrier: Option<usize> = None;
+    let mut dependencies = Vec::with_capacity(plan.len());
+    for (i, op) in plan.iter().enumerate() {
+        let waits_for: Vec<usize> = if is_barrier(op) {
+            (0..i).collect()
+        } else {
+            (0..i)
+                .filter(|&j| {
+                    Some(j) == last_barrier
+                        || signatures[j] == signatures[i]
+                        || edges.contains(&(signatures[j].clone(), signatures[i].clone()))
+                        // Edges dropped to break a cycle still keep the plan order
+                        || edges.contains(&(signatures[i].clone(), signatures[j].clone()))
+                })
+                .collect()
+        };
+        if is_barrier(op) {
+            last_barrier = Some(i);
+        }
+        dependencies.push(waits_for);
+    }
+
+    dependencies
+}
+
 /// Helper function to detect if a path exists from start to end in the graph
 fn path_exists(graph: &DiGraph<usize, ()>, start: NodeIndex, end: NodeIndex) -> bool {
     use petgraph::algo::has_path_connecting;
@@ -1657,6 +1863,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create some atomic operations
@@ -1739,6 +1946,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create table B - depends on table A
@@ -1766,6 +1974,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create view C - depends on table B
@@ -1865,6 +2074,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create table B - target for materialized view
@@ -1892,6 +2102,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create view C - depends on table B
@@ -2011,6 +2222,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let view = Dmv1View {
@@ -2175,6 +2387,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let table_b = Table {
@@ -2201,6 +2414,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let table_c = Table {
@@ -2227,6 +2441,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Test operations
@@ -2322,6 +2537,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let table_b = Table {
@@ -2348,6 +2564,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let table_c = Table {
@@ -2374,6 +2591,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let table_d = Table {
@@ -2400,6 +2618,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let table_e = Table {
@@ -2426,6 +2645,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let op_create_a = AtomicOlapOperation::CreateTable {
@@ -2584,6 +2804,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create table B - target for materialized view
@@ -2611,6 +2832,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create SQL resource for a materialized view
@@ -2631,6 +2853,7 @@ mod tests {
             pushes_data_to: vec![InfrastructureSignature::Table {
                 id: table_b.id(DEFAULT_DATABASE_NAME),
             }],
+            life_cycle: Default::default(),
         };
 
         // Create operations
@@ -2741,6 +2964,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create table B - target for materialized view
@@ -2768,6 +2992,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create SQL resource for a materialized view
@@ -2788,6 +3013,7 @@ mod tests {
             pushes_data_to: vec![InfrastructureSignature::Table {
                 id: table_b.id(DEFAULT_DATABASE_NAME),
             }],
+            life_cycle: Default::default(),
         };
 
         // MV - no dependencies for teardown (it should be removed first)
@@ -2903,6 +3129,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let table_b = Table {
@@ -2929,6 +3156,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create SQL resource for materialized view
@@ -2949,6 +3177,7 @@ mod tests {
             pushes_data_to: vec![InfrastructureSignature::Table {
                 id: table_b.id(DEFAULT_DATABASE_NAME),
             }],
+            life_cycle: Default::default(),
         };
 
         // Create setup operations
@@ -3144,6 +3373,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create a column
@@ -3261,6 +3491,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create operations with signatures that work with the current implementation
@@ -3391,6 +3622,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let after_table = Table {
@@ -3446,6 +3678,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         // Create column changes (remove old_column, add new_column)
@@ -3559,6 +3792,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
 
         let mut after = before.clone();
@@ -3607,6 +3841,7 @@ mod tests {
             cluster_name: None,
             primary_key_expression: None,
             seed_filter: Default::default(),
+            extensions: Default::default(),
         };
         before.projections = vec![TableProjection {
             name: "proj_by_user".to_string(),
@@ -3658,6 +3893,7 @@ mod tests {
             table_ttl_setting: None,
             cluster_name: None,
             primary_key_expression: None,
+            extensions: Default::default(),
         };
         before.projections = vec![TableProjection {
             name: "proj_by_user".to_string(),
@@ -3687,6 +3923,124 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_sort_key_extension_follows_added_column() {
+        let column = |name: &str| Column {
+            name: name.to_string(),
+            data_type: ColumnType::String,
+            required: true,
+            unique: false,
+            primary_key: name == "id",
+            default: None,
+            annotations: vec![],
+            comment: None,
+            ttl: None,
+            codec: None,
+            materialized: None,
+            alias: None,
+        };
+        let before = Table {
+            name: "test_table".to_string(),
+            columns: vec![column("id")],
+            order_by: OrderBy::Fields(vec!["id".to_string()]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: "test".to_string(),
+                primitive_type: PrimitiveTypes::DBBlock,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        };
+        let mut after = before.clone();
+        after.columns = vec![column("id"), column("region"), column("note")];
+        after.order_by = OrderBy::Fields(vec!["id".to_string(), "region".to_string()]);
+        after.indexes = vec![TableIndex {
+            name: "idx_note".to_string(),
+            expression: "note".to_string(),
+            index_type: "bloom_filter".to_string(),
+            arguments: vec![],
+            granularity: 1,
+        }];
+
+        let column_changes = vec![
+            ColumnChange::Added {
+                column: column("region"),
+                position_after: Some("id".to_string()),
+            },
+            ColumnChange::Added {
+                column: column("note"),
+                position_after: Some("region".to_string()),
+            },
+        ];
+        let plan = handle_table_update(&before, &after, &column_changes);
+
+        assert_eq!(plan.setup_ops.len(), 4);
+        assert!(matches!(
+            &plan.setup_ops[2],
+            AtomicOlapOperation::ModifyOrderBy { expression, .. } if expression == "(id, region)"
+        ));
+        assert!(matches!(
+            &plan.setup_ops[3],
+            AtomicOlapOperation::AddTableIndex { .. }
+        ));
+    }
+
+    #[test]
+    fn test_dictionary_is_created_after_its_source_table() {
+        let dictionary = SqlResource {
+            name: "products_dict".to_string(),
+            database: None,
+            source_file: None,
+            source_line: None,
+            source_column: None,
+            setup: vec![
+                "CREATE DICTIONARY products_dict (id UInt64, name String) PRIMARY KEY id \
+                 SOURCE(CLICKHOUSE(TABLE 'products')) LIFETIME(300) LAYOUT(HASHED())"
+                    .to_string(),
+            ],
+            teardown: vec!["DROP DICTIONARY IF EXISTS products_dict".to_string()],
+            pulls_data_from: vec![],
+            pushes_data_to: vec![],
+            life_cycle: Default::default(),
+        };
+        let source = InfrastructureSignature::Table {
+            id: format!("{DEFAULT_DATABASE_NAME}_products"),
+        };
+
+        let plan = handle_sql_resource_add(&dictionary, DEFAULT_DATABASE_NAME);
+        assert!(matches!(
+            &plan.setup_ops[0],
+            AtomicOlapOperation::RunSetupSql { dependency_info, .. }
+                if dependency_info.pulls_data_from == vec![source.clone()]
+        ));
+
+        // A declared source is not repeated
+        let declared = SqlResource {
+            pulls_data_from: vec![source.clone()],
+            ..dictionary
+        };
+        let plan = handle_sql_resource_remove(&declared, DEFAULT_DATABASE_NAME);
+        assert!(matches!(
+            &plan.teardown_ops[0],
+            AtomicOlapOperation::RunTeardownSql { dependency_info, .. }
+                if dependency_info.pulls_data_from == vec![source]
+        ));
+    }
+
     #[test]
     fn test_populate_materialized_view_includes_truncate() {
         let test_cases = vec![
@@ -3722,4 +4076,140 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_truncated_and_renamed_tables_are_torn_down() {
+        let table = |name: &str| Table {
+            name: name.to_string(),
+            columns: vec![],
+            order_by: OrderBy::Fields(vec![]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: "test".to_string(),
+                primitive_type: PrimitiveTypes::DBBlock,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: Some("analytics".to_string()),
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        };
+        let changes = vec![
+            OlapChange::Table(TableChange::Truncated(table("events"))),
+            OlapChange::Table(TableChange::Renamed {
+                before: table("users"),
+                new_name: "users__dropped_20260314150926".to_string(),
+            }),
+        ];
+
+        let (teardown, setup) = order_olap_changes(&changes, DEFAULT_DATABASE_NAME).unwrap();
+
+        assert!(setup.is_empty());
+        let operations: Vec<SerializableOlapOperation> = teardown
+            .iter()
+            .map(AtomicOlapOperation::to_minimal)
+            .collect();
+        assert_eq!(operations.len(), 2);
+        assert!(operations.contains(&SerializableOlapOperation::RawSql {
+            sql: vec!["TRUNCATE TABLE `analytics`.`events`".to_string()],
+            description: "Truncating table events instead of dropping it".to_string(),
+        }));
+        assert!(
+            operations.contains(&SerializableOlapOperation::RenameTable {
+                table: "users".to_string(),
+                new_name: "users__dropped_20260314150926".to_string(),
+                database: Some("analytics".to_string()),
+                cluster_name: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_execution_dependencies() {
+        let table = |name: &str| Table {
+            name: name.to_string(),
+            columns: vec![],
+            order_by: OrderBy::Fields(vec![]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: "test".to_string(),
+                primitive_type: PrimitiveTypes::DBBlock,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        };
+        let create = |name: &str, pulls_from: &[&str]| AtomicOlapOperation::CreateTable {
+            table: table(name),
+            dependency_info: DependencyInfo {
+                pulls_data_from: pulls_from
+                    .iter()
+                    .map(|source| InfrastructureSignature::Table {
+                        id: table(source).id(DEFAULT_DATABASE_NAME),
+                    })
+                    .collect(),
+                pushes_data_to: vec![],
+            },
+        };
+        let populate = AtomicOlapOperation::PopulateMaterializedView {
+            view_name: "mv".to_string(),
+            target_table: "d".to_string(),
+            target_database: None,
+            select_statement: "SELECT * FROM a".to_string(),
+            should_truncate: false,
+            dependency_info: create_empty_dependency_info(),
+        };
+
+        let plan = vec![
+            create("a", &[]),
+            create("b", &[]),
+            create("c", &["a"]),
+            create("a", &[]),
+            create("d", &[]),
+            populate,
+            create("e", &[]),
+        ];
+
+        let dependencies = execution_dependencies(&plan, false, DEFAULT_DATABASE_NAME);
+        assert_eq!(
+            dependencies,
+            vec![
+                vec![],
+                vec![],
+                // Pulls from a
+                vec![0],
+                // Same table as the first operation, and pulled from by c
+                vec![0, 2],
+                vec![],
+                // Backfills wait for everything before them
+                vec![0, 1, 2, 3, 4],
+                // and everything after waits for them
+                vec![5],
+            ]
+        );
+    }
 }
@@ -0,0 +1,270 @@
+//! Progress of DDL plans while they run.
+//!
+//! `clickhouse::execute_changes` reports each operation as it
+//! starts and ends, so that a long migration does not look hung.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::cli::display::{show_message_wrapper, show_table, Message, MessageType};
+
+/// Plans with fewer operations are already listed by the change summary, so
+/// only their failures are shown.
+const CONSOLE_MIN_OPERATIONS: usize = 10;
+
+/// Operations listed by the timing summary of a plan
+const SLOWEST_OPERATIONS_SHOWN: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DdlProgressEvent {
+    /// The plans are about to run
+    PlanStarted {
+        teardown_operations: usize,
+        setup_operations: usize,
+    },
+    OperationStarted {
+        description: String,
+    },
+    /// The operation has been running for the slow operation threshold
+    OperationSlow {
+        description: String,
+        elapsed: Duration,
+    },
+    OperationSucceeded {
+        description: String,
+        /// The `database.table` the operation changed, none for raw SQL
+        table: Option<String>,
+        duration: Duration,
+    },
+    OperationFailed {
+        description: String,
+        table: Option<String>,
+        duration: Duration,
+        error: String,
+    },
+    /// The plans ran. Operations neither succeeded nor failed were skipped.
+    PlanFinished {
+        succeeded: usize,
+        failed: usize,
+        duration: Duration,
+    },
+}
+
+/// Receives the progress of DDL plans. Called concurrently while operations
+/// run in parallel.
+pub trait DdlProgress: Send + Sync {
+    fn on_event(&self, event: DdlProgressEvent);
+
+    /// How long an operation runs before an `OperationSlow` event is sent
+    fn slow_operation_threshold(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// How long an operation of a plan took
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationTiming {
+    pub description: String,
+    /// The `database.table` the operation changed, none for raw SQL
+    pub table: Option<String>,
+    pub duration: Duration,
+    pub succeeded: bool,
+}
+
+/// Ignores the progress, for dry runs which execute nothing.
+pub struct NoProgress;
+
+impl DdlProgress for NoProgress {
+    fn on_event(&self, _event: DdlProgressEvent) {}
+}
+
+/// Shows the progress of large plans, every failure and slow operation, on
+/// the console, and keeps how long each operation took.
+#[derive(Default)]
+pub struct ConsoleProgress {
+    total: AtomicUsize,
+    completed: AtomicUsize,
+    slow_threshold: Option<Duration>,
+    timings: Mutex<Vec<OperationTiming>>,
+}
+
+impl ConsoleProgress {
+    /// Warns about the operations running longer than `slow_threshold`
+    pub fn new(slow_threshold: Option<Duration>) -> Self {
+        Self {
+            slow_threshold,
+            ..Default::default()
+        }
+    }
+
+    /// The operations run so far, slowest first
+    pub fn timings(&self) -> Vec<OperationTiming> {
+        let mut timings = self.timings.lock().unwrap().clone();
+        timings.sort_by(|a, b| b.duration.cmp(&a.duration));
+        timings
+    }
+
+    fn record(&self, description: &str, table: Option<String>, duration: Duration, ok: bool) {
+        self.timings.lock().unwrap().push(OperationTiming {
+            description: description.to_string(),
+            table,
+            duration,
+            succeeded: ok,
+        });
+    }
+
+    /// Logs how long every operation took, and shows the slowest ones of
+    /// large plans
+    fn show_timings(&self, total: usize) {
+        let timings = self.timings();
+        for timing in &timings {
+            tracing::info!(
+                duration_ms = timing.duration.as_millis() as u64,
+                table = timing.table.as_deref().unwrap_or_default(),
+                succeeded = timing.succeeded,
+                "{}",
+                timing.description
+            );
+        }
+        if total < CONSOLE_MIN_OPERATIONS || timings.is_empty() {
+            return;
+        }
+        show_table(
+            "Slowest database operations".to_string(),
+            vec![
+                "Duration".to_string(),
+                "Operation".to_string(),
+                "Outcome".to_string(),
+            ],
+            timings
+                .iter()
+                .take(SLOWEST_OPERATIONS_SHOWN)
+                .map(|timing| {
+                    vec![
+                        format!("{:.1}s", timing.duration.as_secs_f64()),
+                        timing.description.clone(),
+                        if timing.succeeded { "ok" } else { "failed" }.to_string(),
+                    ]
+                })
+                .collect(),
+        );
+    }
+}
+
+impl DdlProgress for ConsoleProgress {
+    fn on_event(&self, event: DdlProgressEvent) {
+        let total = self.total.load(Ordering::Relaxed);
+        match event {
+            DdlProgressEvent::PlanStarted {
+                teardown_operations,
+                setup_operations,
+            } => {
+                let total = teardown_operations + setup_operations;
+                self.total.store(total, Ordering::Relaxed);
+                self.completed.store(0, Ordering::Relaxed);
+                if total >= CONSOLE_MIN_OPERATIONS {
+                    show_message_wrapper(
+                        MessageType::Info,
+                        Message {
+                            action: "Applying".to_string(),
+                            details: format!("{total} database operations"),
+                        },
+                    );
+                }
+            }
+            DdlProgressEvent::OperationStarted { description } => {
+                tracing::debug!("{description}");
+            }
+            DdlProgressEvent::OperationSlow {
+                description,
+                elapsed,
+            } => {
+                tracing::warn!(
+                    "{description} still running after {:.0}s",
+                    elapsed.as_secs_f64()
+                );
+                show_message_wrapper(
+                    MessageType::Warning,
+                    Message {
+                        action: "Slow".to_string(),
+                        details: format!(
+                            "{description} still running after {:.0}s",
+                            elapsed.as_secs_f64()
+                        ),
+                    },
+                );
+            }
+            DdlProgressEvent::OperationSucceeded {
+                description,
+                table,
+                duration,
+            } => {
+                self.record(&description, table, duration, true);
+                let completed = self.completed.fetch_add(1, Ordering::Relaxed) + 1;
+                if total >= CONSOLE_MIN_OPERATIONS {
+                    show_message_wrapper(
+                        MessageType::Info,
+                        Message {
+                            action: format!("[{completed}/{total}]"),
+                            details: format!("{description} ({:.1}s)", duration.as_secs_f64()),
+                        },
+                    );
+                }
+            }
+            DdlProgressEvent::OperationFailed {
+                description,
+                table,
+                duration,
+                error,
+            } => {
+                self.record(&description, table, duration, false);
+                let completed = self.completed.fetch_add(1, Ordering::Relaxed) + 1;
+                show_message_wrapper(
+                    MessageType::Error,
+                    Message {
+                        action: format!("[{completed}/{total}]"),
+                        details: format!(
+                            "{description} failed after {:.1}s: {error}",
+                            duration.as_secs_f64()
+                        ),
+                    },
+                );
+            }
+            DdlProgressEvent::PlanFinished {
+                succeeded,
+                failed,
+                duration,
+            } => {
+                self.show_timings(total);
+                if total >= CONSOLE_MIN_OPERATIONS && failed == 0 {
+                    show_message_wrapper(
+                        MessageType::Success,
+                        Message {
+                            action: "Applied".to_string(),
+                            details: format!(
+                                "{succeeded} database operations in {:.1}s",
+                                duration.as_secs_f64()
+                            ),
+                        },
+                    );
+                } else if failed > 0 {
+                    show_message_wrapper(
+                        MessageType::Error,
+                        Message {
+                            action: "Applied".to_string(),
+                            details: format!(
+                                "{succeeded} of {total} database operations, {failed} failed, {} skipped",
+                                total.saturating_sub(succeeded + failed)
+                            ),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn slow_operation_threshold(&self) -> Option<Duration> {
+        self.slow_threshold
+    }
+}
@@ -1,17 +1,25 @@
 use clickhouse::sql_parser::normalize_sql_for_comparison;
 use clickhouse::ClickhouseChangesError;
 
+use crate::framework::core::infra_reality_checker::InfraRealityChecker;
 use crate::framework::core::infrastructure::sql_resource::SqlResource;
 use crate::framework::core::lifecycle_filter::{self, LifecycleViolation};
 use crate::infrastructure::olap::clickhouse::TableWithUnsupportedType;
 use crate::{
-    framework::core::infrastructure::table::Table, framework::core::infrastructure_map::OlapChange,
+    cli::display::{show_message_wrapper, Message, MessageType},
+    framework::core::infrastructure::table::Table,
+    framework::core::infrastructure_map::OlapChange,
     project::Project,
 };
+use ddl_ordering::AtomicOlapOperation;
+use plan_fingerprint::{PlanFingerprintContext, PlanRun};
 
 pub mod clickhouse;
 pub mod clickhouse_http_client;
+pub mod ddl_execution;
 pub mod ddl_ordering;
+pub mod ddl_progress;
+pub mod plan_fingerprint;
 
 #[derive(Debug, thiserror::Error)]
 pub enum OlapChangesError {
@@ -70,7 +78,7 @@ pub trait OlapOperations {
         project: &Project,
     ) -> Result<(Vec<Table>, Vec<TableWithUnsupportedType>), OlapChangesError>;
 
-    /// Retrieves all SQL resources (views and materialized views) from the database
+    /// Retrieves all SQL resources (views, materialized views and dictionaries) from the database
     ///
     /// # Arguments
     ///
@@ -134,10 +142,99 @@ pub trait OlapOperations {
 /// pipeline should have already blocked protected operations, but this guard
 /// ensures that even if a bug allows a violation through, it will be caught here
 /// before any changes reach the database.
+///
+/// # Re-runs
+/// With a `fingerprint` context, a plan identical to the last one applied is
+/// skipped when ClickHouse already is in its target state, see `plan_fingerprint`.
+///
+/// Returns how long each operation took, slowest first, none when skipped.
 pub async fn execute_changes(
     project: &Project,
     changes: &[OlapChange],
-) -> Result<(), OlapChangesError> {
+    fingerprint: Option<PlanFingerprintContext<'_>>,
+) -> Result<Vec<ddl_progress::OperationTiming>, OlapChangesError> {
+    let (teardown_plan, setup_plan) = ordered_plans(project, changes)?;
+
+    let progress =
+        ddl_progress::ConsoleProgress::new(project.migration_config.warn_slow_operation());
+    let execute = || async {
+        clickhouse::execute_changes(project, &teardown_plan, &setup_plan, &progress).await
+    };
+
+    let fingerprinted = fingerprint
+        .filter(|_| !teardown_plan.is_empty() || !setup_plan.is_empty())
+        .and_then(|context| {
+            match plan_fingerprint::plan_fingerprint(
+                &teardown_plan,
+                &setup_plan,
+                context.source_infra_map,
+                context.target_infra_map,
+            ) {
+                Ok(fingerprint) => Some((context, fingerprint)),
+                Err(e) => {
+                    tracing::warn!("Failed to fingerprint the plan: {e:?}");
+                    None
+                }
+            }
+        });
+    let Some((context, fingerprint)) = fingerprinted else {
+        execute().await?;
+        return Ok(progress.timings());
+    };
+
+    let target_confirmed = || async {
+        let checker =
+            InfraRealityChecker::new(clickhouse::create_client(project.clickhouse_config.clone()));
+        match checker
+            .check_reality(project, context.target_infra_map)
+            .await
+        {
+            Ok(discrepancies) => discrepancies.is_map_applied(),
+            Err(e) => {
+                tracing::warn!("Failed to check the database against the plan target: {e:?}");
+                false
+            }
+        }
+    };
+
+    let run = plan_fingerprint::run_once(
+        context.state_storage,
+        &fingerprint,
+        target_confirmed,
+        execute,
+    )
+    .await?;
+    if run == PlanRun::AlreadyApplied {
+        show_message_wrapper(
+            MessageType::Info,
+            Message {
+                action: "Skipped".to_string(),
+                details: format!(
+                    "database changes, plan {} was already applied",
+                    &fingerprint[..12]
+                ),
+            },
+        );
+    }
+    Ok(progress.timings())
+}
+
+/// The statements `execute_changes` would send to ClickHouse for `changes`,
+/// grouped by operation, without executing anything.
+pub async fn dry_run_changes(
+    project: &Project,
+    changes: &[OlapChange],
+) -> Result<Vec<clickhouse::OperationStatements>, OlapChangesError> {
+    let (teardown_plan, setup_plan) = ordered_plans(project, changes)?;
+    Ok(clickhouse::dry_run_changes(project, &teardown_plan, &setup_plan).await?)
+}
+
+/// Checks the lifecycle of `changes` and orders them into the teardown and
+/// setup plans.
+fn ordered_plans(
+    project: &Project,
+    changes: &[OlapChange],
+) -> Result<(Vec<AtomicOlapOperation>, Vec<AtomicOlapOperation>), OlapChangesError> {
     // LIFECYCLE GUARD: Final safety check before execution
     // This catches any lifecycle violations that may have slipped through the
     // diff/filter pipeline. A violation here indicates a bug that should be fixed.
@@ -150,12 +247,10 @@ pub async fn execute_changes(
     };
 
     // Order changes based on dependencies, including database context for SQL resources
-    let (teardown_plan, setup_plan) =
-        ddl_ordering::order_olap_changes(changes, &project.clickhouse_config.db_name)?;
-
-    // Execute the ordered changes
-    clickhouse::execute_changes(project, &teardown_plan, &setup_plan).await?;
-    Ok(())
+    Ok(ddl_ordering::order_olap_changes(
+        changes,
+        &project.clickhouse_config.db_name,
+    )?)
 }
 
 #[cfg(test)]
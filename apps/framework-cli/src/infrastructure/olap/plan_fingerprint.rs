@@ -0,0 +1,308 @@
+//! Detection of DDL plans that were already applied.
+//!
+//! Running the same plan twice, when retrying after a transient failure or
+//! when two instances start at once, fails on operations like adding a column
+//! that already exists, although the database is in the target state. Each
+//! plan has a fingerprint, stored once the plan has fully applied: a plan
+//! matching the stored fingerprint is skipped, provided ClickHouse is confirmed
+//! to be in the target state.
+//!
+//! A plan that only partially applied never stores its fingerprint, so it
+//! always runs again.
+
+use std::future::Future;
+
+use sha2::{Digest, Sha256};
+
+use crate::framework::core::infrastructure_map::InfrastructureMap;
+use crate::framework::core::state_storage::StateStorage;
+use crate::infrastructure::olap::clickhouse::SerializableOlapOperation;
+use crate::infrastructure::olap::ddl_ordering::AtomicOlapOperation;
+use crate::utilities::json::sort_json_keys;
+
+/// What `olap::execute_changes` needs to skip plans already applied
+pub struct PlanFingerprintContext<'a> {
+    pub state_storage: &'a dyn StateStorage,
+    /// The map the plan was computed from
+    pub source_infra_map: &'a InfrastructureMap,
+    /// The map the plan leads to
+    pub target_infra_map: &'a InfrastructureMap,
+}
+
+/// How `run_once` handled a plan
+#[derive(Debug, PartialEq, Eq)]
+pub enum PlanRun {
+    Applied,
+    /// The plan had already been applied, it was not run again
+    AlreadyApplied,
+}
+
+/// Fingerprint of a plan: a hash of its operations and of the maps it goes
+/// from and to.
+///
+/// Operations are hashed in a canonical order within each phase, since plans
+/// computed from the same maps may list independent operations in any order.
+pub fn plan_fingerprint(
+    teardown_plan: &[AtomicOlapOperation],
+    setup_plan: &[AtomicOlapOperation],
+    source_infra_map: &InfrastructureMap,
+    target_infra_map: &InfrastructureMap,
+) -> serde_json::Result<String> {
+    let minimal = |plan: &[AtomicOlapOperation]| -> Vec<SerializableOlapOperation> {
+        plan.iter().map(AtomicOlapOperation::to_minimal).collect()
+    };
+    fingerprint(
+        &minimal(teardown_plan),
+        &minimal(setup_plan),
+        source_infra_map,
+        target_infra_map,
+    )
+}
+
+fn fingerprint(
+    teardown_plan: &[SerializableOlapOperation],
+    setup_plan: &[SerializableOlapOperation],
+    source_infra_map: &InfrastructureMap,
+    target_infra_map: &InfrastructureMap,
+) -> serde_json::Result<String> {
+    let mut hasher = Sha256::new();
+    for phase in [teardown_plan, setup_plan] {
+        let mut operations = phase
+            .iter()
+            .map(|operation| {
+                serde_json::to_value(operation).map(|value| sort_json_keys(value).to_string())
+            })
+            .collect::<serde_json::Result<Vec<String>>>()?;
+        operations.sort();
+        hasher.update(operations.len().to_be_bytes());
+        for operation in operations {
+            hasher.update(operation.as_bytes());
+            hasher.update([0]);
+        }
+    }
+    // The map serialization sorts its keys
+    hasher.update(serde_json::to_vec(source_infra_map)?);
+    hasher.update(serde_json::to_vec(target_infra_map)?);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Runs `execute` unless the plan with `fingerprint` is the last one applied
+/// and `target_confirmed` checks that the database is in its target state.
+/// The fingerprint is stored once `execute` succeeds.
+///
+/// Failing to read or store the fingerprint only loses the detection, so it
+/// is logged rather than returned.
+pub async fn run_once<E, P, PFut, F, Fut>(
+    state_storage: &dyn StateStorage,
+    fingerprint: &str,
+    target_confirmed: P,
+    execute: F,
+) -> Result<PlanRun, E>
+where
+    P: FnOnce() -> PFut,
+    PFut: Future<Output = bool>,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    match state_storage.load_applied_plan_fingerprint().await {
+        Ok(Some(applied)) if applied == fingerprint => {
+            if target_confirmed().await {
+                return Ok(PlanRun::AlreadyApplied);
+            }
+            tracing::info!(
+                "Plan {fingerprint} was already applied, but the database is not in its target state. Applying it again"
+            );
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to load the last applied plan fingerprint: {e:?}"),
+    }
+
+    execute().await?;
+
+    if let Err(e) = state_storage
+        .store_applied_plan_fingerprint(fingerprint)
+        .await
+    {
+        tracing::warn!("Failed to store the applied plan fingerprint: {e:?}");
+    }
+    Ok(PlanRun::Applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// A state storage only keeping the applied plan fingerprint
+    #[derive(Default)]
+    struct FingerprintStorage {
+        fingerprint: Mutex<Option<String>>,
+    }
+
+    #[async_trait]
+    impl StateStorage for FingerprintStorage {
+        async fn store_infrastructure_map(&self, _: &InfrastructureMap) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn load_infrastructure_map(&self) -> anyhow::Result<Option<InfrastructureMap>> {
+            Ok(None)
+        }
+
+        async fn clear_infrastructure_map(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn acquire_migration_lock(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn release_migration_lock(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn store_applied_plan_fingerprint(&self, fingerprint: &str) -> anyhow::Result<()> {
+            *self.fingerprint.lock().unwrap() = Some(fingerprint.to_string());
+            Ok(())
+        }
+
+        async fn load_applied_plan_fingerprint(&self) -> anyhow::Result<Option<String>> {
+            Ok(self.fingerprint.lock().unwrap().clone())
+        }
+    }
+
+    fn raw_sql(sql: &str) -> SerializableOlapOperation {
+        SerializableOlapOperation::RawSql {
+            sql: vec![sql.to_string()],
+            description: sql.to_string(),
+        }
+    }
+
+    /// Runs a plan with `fingerprint`, counting its executions
+    async fn run(
+        storage: &FingerprintStorage,
+        fingerprint: &str,
+        target_confirmed: bool,
+        fails: bool,
+        executions: &AtomicUsize,
+    ) -> Result<PlanRun, String> {
+        run_once(
+            storage,
+            fingerprint,
+            || async move { target_confirmed },
+            || async {
+                executions.fetch_add(1, Ordering::SeqCst);
+                if fails {
+                    Err("column already exists".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+        )
+        .await
+    }
+
+    #[test]
+    fn test_fingerprint_identifies_plans() {
+        let source = InfrastructureMap::default();
+        let mut target = InfrastructureMap::default();
+        target.default_database = "analytics".to_string();
+        let setup = [raw_sql("CREATE TABLE a"), raw_sql("CREATE TABLE b")];
+        let reordered = [raw_sql("CREATE TABLE b"), raw_sql("CREATE TABLE a")];
+
+        let fingerprint_of = |teardown: &[SerializableOlapOperation],
+                              setup: &[SerializableOlapOperation],
+                              target: &InfrastructureMap| {
+            fingerprint(teardown, setup, &source, target).unwrap()
+        };
+        let plan = fingerprint_of(&[], &setup, &target);
+
+        assert_eq!(plan.len(), 64);
+        assert_eq!(plan, fingerprint_of(&[], &setup, &target));
+        assert_eq!(plan, fingerprint_of(&[], &reordered, &target));
+        // Same operations in another phase, other operations, another target
+        assert_ne!(plan, fingerprint_of(&setup, &[], &target));
+        assert_ne!(plan, fingerprint_of(&[], &setup[..1], &target));
+        assert_ne!(plan, fingerprint_of(&[], &setup, &source));
+    }
+
+    #[tokio::test]
+    async fn test_applied_plan_is_skipped() {
+        let storage = FingerprintStorage::default();
+        let executions = AtomicUsize::new(0);
+
+        assert_eq!(
+            run(&storage, "plan", true, false, &executions).await,
+            Ok(PlanRun::Applied)
+        );
+        assert_eq!(
+            run(&storage, "plan", true, false, &executions).await,
+            Ok(PlanRun::AlreadyApplied)
+        );
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_other_plan_runs() {
+        let storage = FingerprintStorage::default();
+        storage
+            .store_applied_plan_fingerprint("previous")
+            .await
+            .unwrap();
+        let executions = AtomicUsize::new(0);
+
+        assert_eq!(
+            run(&storage, "plan", true, false, &executions).await,
+            Ok(PlanRun::Applied)
+        );
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            storage.load_applied_plan_fingerprint().await.unwrap(),
+            Some("plan".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_applied_plan_runs_again_when_target_is_not_confirmed() {
+        let storage = FingerprintStorage::default();
+        storage
+            .store_applied_plan_fingerprint("plan")
+            .await
+            .unwrap();
+        let executions = AtomicUsize::new(0);
+
+        assert_eq!(
+            run(&storage, "plan", false, false, &executions).await,
+            Ok(PlanRun::Applied)
+        );
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_partially_applied_plan_is_not_recorded() {
+        let storage = FingerprintStorage::default();
+        storage
+            .store_applied_plan_fingerprint("previous")
+            .await
+            .unwrap();
+        let executions = AtomicUsize::new(0);
+
+        // Some operations of the plan ran before one failed
+        assert!(run(&storage, "plan", true, true, &executions)
+            .await
+            .is_err());
+        assert_eq!(
+            storage.load_applied_plan_fingerprint().await.unwrap(),
+            Some("previous".to_string())
+        );
+
+        // The retry runs the plan again, even with the target state confirmed
+        assert_eq!(
+            run(&storage, "plan", true, false, &executions).await,
+            Ok(PlanRun::Applied)
+        );
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+    }
+}
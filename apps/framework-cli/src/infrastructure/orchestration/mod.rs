@@ -1,3 +1,4 @@
+pub mod schedules;
 pub mod temporal;
 pub mod temporal_client;
 pub mod workflows;
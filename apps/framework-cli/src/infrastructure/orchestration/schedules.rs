@@ -0,0 +1,279 @@
+//! Temporal Schedules of the cron-scheduled workflows.
+//!
+//! A workflow with a `schedule` gets a Temporal Schedule whose ID is the
+//! workflow name. It is created or updated when the workflow is added or
+//! changed and deleted with it. A fire is skipped while the previous run is
+//! still going, and fires missed while Temporal was down are not caught up.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use temporal_sdk_core_protos::temporal::api::enums::v1::ScheduleOverlapPolicy;
+use temporal_sdk_core_protos::temporal::api::schedule::v1::{
+    schedule_action, Schedule, ScheduleAction, SchedulePolicies, ScheduleSpec,
+};
+use temporal_sdk_core_protos::temporal::api::workflowservice::v1::{
+    CreateScheduleRequest, DeleteScheduleRequest, DescribeScheduleRequest, ListSchedulesRequest,
+    UpdateScheduleRequest,
+};
+
+use crate::framework::scripts::executor::scheduled_workflow_info;
+use crate::framework::scripts::utils::{parse_schedule, TemporalExecutionError};
+use crate::framework::scripts::Workflow;
+use crate::infrastructure::orchestration::temporal::TemporalConfig;
+use crate::infrastructure::orchestration::temporal_client::TemporalClientManager;
+use crate::utilities::constants::MOOSE_CLI_NAME;
+
+/// How late a fire can still start, e.g. after Temporal restarts
+const CATCHUP_WINDOW_SECONDS: i64 = 60;
+
+const LIST_PAGE_SIZE: i32 = 1000;
+
+/// The schedule starting `workflow` on its cron schedule.
+fn schedule_for(
+    temporal_config: &TemporalConfig,
+    workflow: &Workflow,
+) -> Result<Schedule, TemporalExecutionError> {
+    let config = workflow.config();
+    let start_workflow = scheduled_workflow_info(
+        temporal_config,
+        workflow.language(),
+        workflow.name(),
+        config,
+    )?;
+
+    Ok(Schedule {
+        spec: Some(ScheduleSpec {
+            cron_string: vec![parse_schedule(&config.schedule)],
+            timezone_name: config.timezone.clone().unwrap_or_default(),
+            ..Default::default()
+        }),
+        action: Some(ScheduleAction {
+            action: Some(schedule_action::Action::StartWorkflow(start_workflow)),
+        }),
+        policies: Some(SchedulePolicies {
+            overlap_policy: ScheduleOverlapPolicy::Skip as i32,
+            catchup_window: Some(prost_wkt_types::Duration {
+                seconds: CATCHUP_WINDOW_SECONDS,
+                nanos: 0,
+            }),
+            ..Default::default()
+        }),
+        state: None,
+    })
+}
+
+fn is_not_found(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<tonic::Status>()
+        .is_some_and(|status| status.code() == tonic::Code::NotFound)
+}
+
+/// Creates the schedule of `workflow`, or replaces it when it exists.
+pub async fn upsert_schedule(temporal_config: &TemporalConfig, workflow: &Workflow) -> Result<()> {
+    let schedule = schedule_for(temporal_config, workflow)?;
+    let namespace = temporal_config.get_temporal_namespace();
+    let schedule_id = workflow.name().to_string();
+
+    TemporalClientManager::new_validate(temporal_config, true)?
+        .execute(|mut client| async move {
+            let existing = client
+                .describe_schedule(DescribeScheduleRequest {
+                    namespace: namespace.clone(),
+                    schedule_id: schedule_id.clone(),
+                })
+                .await;
+            match existing {
+                Ok(response) => {
+                    client
+                        .update_schedule(UpdateScheduleRequest {
+                            namespace,
+                            schedule_id,
+                            schedule: Some(schedule),
+                            conflict_token: response.into_inner().conflict_token,
+                            identity: MOOSE_CLI_NAME.to_string(),
+                            request_id: uuid::Uuid::new_v4().to_string(),
+                            ..Default::default()
+                        })
+                        .await?;
+                }
+                Err(e) if is_not_found(&e) => {
+                    client
+                        .create_schedule(CreateScheduleRequest {
+                            namespace,
+                            schedule_id,
+                            schedule: Some(schedule),
+                            identity: MOOSE_CLI_NAME.to_string(),
+                            request_id: uuid::Uuid::new_v4().to_string(),
+                            ..Default::default()
+                        })
+                        .await?;
+                }
+                Err(e) => return Err(e),
+            }
+            Ok(())
+        })
+        .await
+}
+
+/// Deletes the schedule of the workflow `name`. Runs it already started are
+/// left alone, and a missing schedule is not an error.
+pub async fn delete_schedule(temporal_config: &TemporalConfig, name: &str) -> Result<()> {
+    let request = DeleteScheduleRequest {
+        namespace: temporal_config.get_temporal_namespace(),
+        schedule_id: name.to_string(),
+        identity: MOOSE_CLI_NAME.to_string(),
+    };
+
+    TemporalClientManager::new_validate(temporal_config, true)?
+        .execute(|mut client| async move {
+            match client.delete_schedule(request).await {
+                Err(e) if !is_not_found(&e) => Err(e),
+                _ => Ok(()),
+            }
+        })
+        .await
+}
+
+/// Next fire time of each schedule, by workflow name. Paused schedules and
+/// those that will not fire again are left out.
+pub async fn next_runs(temporal_config: &TemporalConfig) -> Result<HashMap<String, DateTime<Utc>>> {
+    let namespace = temporal_config.get_temporal_namespace();
+
+    TemporalClientManager::new_validate(temporal_config, true)?
+        .execute(|mut client| async move {
+            let mut next_runs = HashMap::new();
+            let mut next_page_token = Vec::new();
+            loop {
+                let response = client
+                    .list_schedules(ListSchedulesRequest {
+                        namespace: namespace.clone(),
+                        maximum_page_size: LIST_PAGE_SIZE,
+                        next_page_token,
+                        ..Default::default()
+                    })
+                    .await?
+                    .into_inner();
+
+                for entry in response.schedules {
+                    let next_run = entry
+                        .info
+                        .filter(|info| !info.paused)
+                        .and_then(|info| info.future_action_times.into_iter().next())
+                        .and_then(|time| DateTime::from_timestamp(time.seconds, time.nanos as u32));
+                    if let Some(next_run) = next_run {
+                        next_runs.insert(entry.schedule_id, next_run);
+                    }
+                }
+
+                if response.next_page_token.is_empty() {
+                    return Ok(next_runs);
+                }
+                next_page_token = response.next_page_token;
+            }
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::languages::SupportedLanguages;
+    use crate::utilities::constants::{PYTHON_TASK_QUEUE, WORKFLOW_TYPE};
+
+    fn workflow(schedule: &str, timezone: Option<&str>) -> Workflow {
+        Workflow::from_user_code(
+            "nightly_etl".to_string(),
+            SupportedLanguages::Python,
+            Some(2),
+            Some("30m".to_string()),
+            Some(schedule.to_string()),
+            vec![],
+            vec![],
+        )
+        .with_timezone(timezone.map(str::to_string))
+    }
+
+    #[test]
+    fn test_schedule_for_workflow() {
+        let schedule = schedule_for(
+            &TemporalConfig::default(),
+            &workflow("0 2 * * *", Some("Europe/Paris")),
+        )
+        .unwrap();
+
+        let spec = schedule.spec.unwrap();
+        assert_eq!(spec.cron_string, ["0 2 * * *"]);
+        assert_eq!(spec.timezone_name, "Europe/Paris");
+
+        let policies = schedule.policies.unwrap();
+        assert_eq!(policies.overlap_policy, ScheduleOverlapPolicy::Skip as i32);
+
+        let Some(schedule_action::Action::StartWorkflow(start)) = schedule.action.unwrap().action
+        else {
+            panic!("Expected a start workflow action");
+        };
+        assert_eq!(start.workflow_id, "nightly_etl");
+        assert_eq!(start.workflow_type.unwrap().name, WORKFLOW_TYPE);
+        assert_eq!(start.task_queue.unwrap().name, PYTHON_TASK_QUEUE);
+        assert_eq!(start.workflow_run_timeout.unwrap().seconds, 1800);
+        assert_eq!(start.retry_policy.unwrap().maximum_attempts, 3);
+    }
+
+    #[test]
+    fn test_schedule_for_workflow_shorthand_in_utc() {
+        let schedule = schedule_for(&TemporalConfig::default(), &workflow("15m", None)).unwrap();
+
+        let spec = schedule.spec.unwrap();
+        assert_eq!(spec.cron_string, ["*/15 * * * *"]);
+        // Temporal reads an empty time zone as UTC
+        assert_eq!(spec.timezone_name, "");
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires a running Temporal dev server
+    async fn test_schedule_fires_on_temporal() {
+        let temporal_config = TemporalConfig::default();
+        let workflow = workflow("@every 1s", None);
+        let namespace = temporal_config.get_temporal_namespace();
+
+        upsert_schedule(&temporal_config, &workflow).await.unwrap();
+        // Updating it in place works too
+        upsert_schedule(&temporal_config, &workflow).await.unwrap();
+
+        let mut fired = false;
+        for _ in 0..20 {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            let request = DescribeScheduleRequest {
+                namespace: namespace.clone(),
+                schedule_id: workflow.name().to_string(),
+            };
+            let info = TemporalClientManager::new(&temporal_config)
+                .unwrap()
+                .execute(|mut client| async move { client.describe_schedule(request).await })
+                .await
+                .unwrap()
+                .into_inner()
+                .info
+                .unwrap();
+            if info.action_count > 0 {
+                fired = true;
+                break;
+            }
+        }
+        assert!(next_runs(&temporal_config)
+            .await
+            .unwrap()
+            .contains_key(workflow.name()));
+
+        delete_schedule(&temporal_config, workflow.name())
+            .await
+            .unwrap();
+        // Deleting twice is not an error
+        delete_schedule(&temporal_config, workflow.name())
+            .await
+            .unwrap();
+        assert!(fired, "the schedule did not start the workflow");
+    }
+}
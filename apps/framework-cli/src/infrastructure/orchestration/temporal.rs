@@ -50,7 +50,7 @@ impl TryFrom<&str> for TemporalScheme {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct TemporalConfig {
     #[serde(default = "default_db_user")]
     pub db_user: String,
@@ -82,14 +82,60 @@ pub struct TemporalConfig {
     pub config_path: String,
     #[serde(default = "default_postgresql_version")]
     pub postgresql_version: String,
+    /// Path to the PEM client certificate for mTLS.
     #[serde(default = "default_client_cert")]
     pub client_cert: String,
+    /// Path to the PEM private key of `client_cert`.
     #[serde(default = "default_client_key")]
     pub client_key: String,
+    /// Path to the PEM CA certificate trusted for the server, the system
+    /// roots being trusted when empty.
     #[serde(default = "default_ca_cert")]
     pub ca_cert: String,
     #[serde(default = "default_api_key")]
     pub api_key: String,
+    /// Seconds a readiness probe waits for Temporal.
+    #[serde(default = "default_probe_timeout_secs")]
+    pub probe_timeout_secs: u64,
+    /// Seconds any other call, such as starting a workflow, waits for Temporal.
+    #[serde(default = "default_operation_timeout_secs")]
+    pub operation_timeout_secs: u64,
+}
+
+/// The API key is redacted.
+impl std::fmt::Debug for TemporalConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TemporalConfig")
+            .field("db_user", &self.db_user)
+            .field("db_password", &self.db_password)
+            .field("db_port", &self.db_port)
+            .field("namespace", &self.namespace)
+            .field("temporal_host", &self.temporal_host)
+            .field("temporal_port", &self.temporal_port)
+            .field("temporal_scheme", &self.temporal_scheme)
+            .field("temporal_version", &self.temporal_version)
+            .field("temporal_region", &self.temporal_region)
+            .field("admin_tools_version", &self.admin_tools_version)
+            .field("ui_version", &self.ui_version)
+            .field("ui_port", &self.ui_port)
+            .field("ui_cors_origins", &self.ui_cors_origins)
+            .field("config_path", &self.config_path)
+            .field("postgresql_version", &self.postgresql_version)
+            .field("client_cert", &self.client_cert)
+            .field("client_key", &self.client_key)
+            .field("ca_cert", &self.ca_cert)
+            .field(
+                "api_key",
+                &if self.api_key.is_empty() {
+                    ""
+                } else {
+                    "[REDACTED]"
+                },
+            )
+            .field("probe_timeout_secs", &self.probe_timeout_secs)
+            .field("operation_timeout_secs", &self.operation_timeout_secs)
+            .finish()
+    }
 }
 
 fn default_namespace() -> String {
@@ -165,6 +211,14 @@ fn default_api_key() -> String {
     "".to_string()
 }
 
+fn default_probe_timeout_secs() -> u64 {
+    5
+}
+
+fn default_operation_timeout_secs() -> u64 {
+    30
+}
+
 fn default_temporal_region() -> String {
     // Default Temporal Cloud region
     "us-west1".to_string()
@@ -289,6 +343,8 @@ impl Default for TemporalConfig {
             ca_cert: default_ca_cert(),
             api_key: default_api_key(),
             temporal_region: default_temporal_region(),
+            probe_timeout_secs: default_probe_timeout_secs(),
+            operation_timeout_secs: default_operation_timeout_secs(),
         }
     }
 }
@@ -451,4 +507,15 @@ mod tests {
             "us-east1.gcp.api.temporal.io"
         );
     }
+
+    #[test]
+    fn test_debug_redacts_api_key() {
+        let config = TemporalConfig {
+            api_key: "tmprl-s3cr3t".to_string(),
+            ..Default::default()
+        };
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("tmprl-s3cr3t"));
+        assert!(debug.contains("api_key: \"[REDACTED]\""));
+    }
 }
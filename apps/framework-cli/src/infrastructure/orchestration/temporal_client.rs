@@ -1,28 +1,200 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
 use anyhow::{Error, Result};
+use async_trait::async_trait;
+use temporal_sdk_core_protos::temporal::api::common::v1::WorkflowExecution;
+use temporal_sdk_core_protos::temporal::api::enums::v1::WorkflowExecutionStatus;
 use temporal_sdk_core_protos::temporal::api::workflowservice::v1::workflow_service_client::WorkflowServiceClient;
 use temporal_sdk_core_protos::temporal::api::workflowservice::v1::{
-    DescribeNamespaceRequest, DescribeNamespaceResponse, DescribeWorkflowExecutionRequest,
-    DescribeWorkflowExecutionResponse, GetWorkflowExecutionHistoryRequest,
-    GetWorkflowExecutionHistoryResponse, ListWorkflowExecutionsRequest,
-    ListWorkflowExecutionsResponse, RequestCancelWorkflowExecutionRequest,
-    RequestCancelWorkflowExecutionResponse, SignalWorkflowExecutionRequest,
-    SignalWorkflowExecutionResponse, StartWorkflowExecutionRequest,
-    TerminateWorkflowExecutionRequest, TerminateWorkflowExecutionResponse,
+    CreateScheduleRequest, CreateScheduleResponse, DeleteScheduleRequest, DeleteScheduleResponse,
+    DescribeNamespaceRequest, DescribeNamespaceResponse, DescribeScheduleRequest,
+    DescribeScheduleResponse, DescribeWorkflowExecutionRequest, DescribeWorkflowExecutionResponse,
+    GetWorkflowExecutionHistoryRequest, GetWorkflowExecutionHistoryResponse, ListSchedulesRequest,
+    ListSchedulesResponse, ListWorkflowExecutionsRequest, ListWorkflowExecutionsResponse,
+    RequestCancelWorkflowExecutionRequest, RequestCancelWorkflowExecutionResponse,
+    SignalWorkflowExecutionRequest, SignalWorkflowExecutionResponse, StartWorkflowExecutionRequest,
+    TerminateWorkflowExecutionRequest, TerminateWorkflowExecutionResponse, UpdateScheduleRequest,
+    UpdateScheduleResponse,
 };
 use tonic::service::interceptor::InterceptedService;
-use tonic::transport::{Channel, Uri};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity};
 use tracing::{info, warn};
 
 use crate::infrastructure::orchestration::temporal::{InvalidTemporalSchemeError, TemporalConfig};
+use crate::project::config_interpolation::REDACTED;
 use crate::project::Project;
 
 pub struct TemporalClientManager {
-    config: TemporalConfig,
-    temporal_url: String,
-    ca_cert: String,
-    client_cert: String,
-    client_key: String,
-    api_key: String,
+    options: ConnectionOptions,
+    probe_timeout: Duration,
+    operation_timeout: Duration,
+}
+
+/// A Temporal call, connection included, that did not complete in time.
+#[derive(Debug, thiserror::Error)]
+#[error("Temporal did not answer within {}s", .timeout.as_secs_f64())]
+pub struct TemporalTimeoutError {
+    pub timeout: Duration,
+}
+
+/// How the client reaches Temporal, derived from [`TemporalConfig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionOptions {
+    pub endpoint: String,
+    pub tls: Option<TlsOptions>,
+    pub api_key: Option<ApiKey>,
+    pub namespace: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsOptions {
+    pub domain_name: String,
+    /// CA certificate trusted for the server, the system roots when unset.
+    pub ca_cert: Option<PathBuf>,
+    /// Client certificate and key paths for mTLS.
+    pub identity: Option<(PathBuf, PathBuf)>,
+}
+
+/// A Temporal Cloud API key, redacted in `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ApiKey(String);
+
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ApiKey({REDACTED})")
+    }
+}
+
+impl ApiKey {
+    /// `text` with the key replaced by `[REDACTED]`.
+    fn redact(&self, text: &str) -> String {
+        text.replace(self.0.as_str(), REDACTED)
+    }
+}
+
+fn non_empty(value: &str) -> Option<PathBuf> {
+    (!value.is_empty()).then(|| PathBuf::from(value))
+}
+
+impl ConnectionOptions {
+    /// Maps the configuration to connection options:
+    /// - a client certificate and key connect with mTLS to the configured host,
+    /// - otherwise an API key connects with TLS to the regional Temporal Cloud
+    ///   endpoint of `temporal_region`,
+    /// - otherwise TLS is used when `ca_cert` is set or the scheme is https.
+    pub fn from_config(
+        config: &TemporalConfig,
+        validate: bool,
+    ) -> Result<Self, InvalidTemporalSchemeError> {
+        let url = config.temporal_url_with_scheme_validate(validate)?;
+        let ca_cert = non_empty(&config.ca_cert);
+        let identity = non_empty(&config.client_cert).zip(non_empty(&config.client_key));
+        // mTLS authenticates the client on its own
+        let api_key = (!config.api_key.is_empty() && identity.is_none())
+            .then(|| ApiKey(config.api_key.clone()));
+
+        let (endpoint, tls) = if api_key.is_some() {
+            let tls = TlsOptions {
+                domain_name: config.get_temporal_api_key_domain(),
+                ca_cert,
+                identity: None,
+            };
+            (config.get_temporal_api_key_endpoint(), Some(tls))
+        } else if identity.is_some() || ca_cert.is_some() || url.starts_with("https://") {
+            let tls = TlsOptions {
+                domain_name: config.get_temporal_domain_name(),
+                ca_cert,
+                identity,
+            };
+            (format!("https://{}", config.temporal_url()), Some(tls))
+        } else {
+            (url, None)
+        };
+
+        Ok(Self {
+            endpoint,
+            tls,
+            api_key,
+            namespace: config.get_temporal_namespace(),
+        })
+    }
+
+    /// Lists the running workflow executions of `task_queue`.
+    pub async fn list_open(&self, task_queue: &str) -> Result<Vec<OpenWorkflow>, Error> {
+        let query = format!("ExecutionStatus = 'Running' AND TaskQueue = '{task_queue}'");
+        let mut open = Vec::new();
+        let mut next_page_token = Vec::new();
+        loop {
+            let request = ListWorkflowExecutionsRequest {
+                namespace: self.namespace.clone(),
+                query: query.clone(),
+                page_size: 100,
+                next_page_token,
+            };
+            let response = self
+                .manager
+                .execute(|mut client| async move { client.list_workflow_executions(request).await })
+                .await?
+                .into_inner();
+
+            open.extend(
+                response
+                    .executions
+                    .into_iter()
+                    .filter_map(|execution| execution.execution)
+                    .map(|execution| OpenWorkflow {
+                        workflow_id: execution.workflow_id,
+                        run_id: execution.run_id,
+                    }),
+            );
+            if response.next_page_token.is_empty() {
+                return Ok(open);
+            }
+            next_page_token = response.next_page_token;
+        }
+    }
+}
+
+/// A running workflow execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenWorkflow {
+    pub workflow_id: String,
+    pub run_id: String,
+}
+
+/// A TLS certificate or key that could not be read.
+#[derive(Debug, thiserror::Error)]
+#[error("cannot read {} {}: {source}", .kind, .path.display())]
+struct TlsFileError {
+    kind: &'static str,
+    path: PathBuf,
+    source: std::io::Error,
+}
+
+fn read_pem(kind: &'static str, path: &Path) -> Result<Vec<u8>, TlsFileError> {
+    std::fs::read(path).map_err(|source| TlsFileError {
+        kind,
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+impl TlsOptions {
+    fn client_tls_config(&self) -> Result<ClientTlsConfig, TlsFileError> {
+        let mut tls = ClientTlsConfig::new().domain_name(self.domain_name.clone());
+        tls = match &self.ca_cert {
+            Some(path) => tls.ca_certificate(Certificate::from_pem(read_pem("ca_cert", path)?)),
+            None => tls.with_native_roots(),
+        };
+        if let Some((cert, key)) = &self.identity {
+            tls = tls.identity(Identity::from_pem(
+                read_pem("client_cert", cert)?,
+                read_pem("client_key", key)?,
+            ));
+        }
+        Ok(tls)
+    }
 }
 
 pub enum TemporalClient {
@@ -31,7 +203,7 @@ pub enum TemporalClient {
 }
 
 pub struct ApiKeyInterceptor {
-    api_key: String,
+    api_key: ApiKey,
     namespace: String,
 }
 
@@ -42,7 +214,7 @@ impl tonic::service::Interceptor for ApiKeyInterceptor {
     ) -> Result<tonic::Request<()>, tonic::Status> {
         request.metadata_mut().insert(
             "authorization",
-            tonic::metadata::MetadataValue::try_from(format!("Bearer {}", self.api_key))
+            tonic::metadata::MetadataValue::try_from(format!("Bearer {}", self.api_key.0))
                 .map_err(|_| tonic::Status::internal("Invalid metadata value"))?,
         );
         request.metadata_mut().insert(
@@ -64,120 +236,98 @@ impl TemporalClientManager {
         validate: bool,
     ) -> Result<Self, InvalidTemporalSchemeError> {
         Ok(Self {
-            config: config.clone(),
-            temporal_url: config.temporal_url_with_scheme_validate(validate)?,
-            ca_cert: config.ca_cert.clone(),
-            client_cert: config.client_cert.clone(),
-            client_key: config.client_key.clone(),
-            api_key: config.api_key.clone(),
+            options: ConnectionOptions::from_config(config, validate)?,
+            probe_timeout: Duration::from_secs(config.probe_timeout_secs),
+            operation_timeout: Duration::from_secs(config.operation_timeout_secs),
         })
     }
 
+    /// Runs `operation` on a new client, failing with [`TemporalTimeoutError`]
+    /// when connecting and the operation take longer than the operation timeout.
     pub async fn execute<F, Fut, R>(&self, operation: F) -> Result<R>
     where
         F: FnOnce(TemporalClient) -> Fut,
         Fut: std::future::Future<Output = Result<R>>,
     {
-        let client = self.get_client().await?;
-        operation(client).await
-    }
-
-    async fn get_client(&self) -> Result<TemporalClient> {
-        info!("Getting client for Temporal URL: {}", self.temporal_url);
-
-        if !self.ca_cert.is_empty() && !self.client_cert.is_empty() && !self.client_key.is_empty() {
-            info!("Choosing client with mTLS");
-            let client = self.get_temporal_client_mtls().await?;
-            Ok(TemporalClient::Standard(client))
-        } else if !self.ca_cert.is_empty() && !self.api_key.is_empty() {
-            info!("Choosing client with API key");
-            let client = self.get_temporal_client_api_key().await?;
-            Ok(TemporalClient::WithInterceptor(client))
-        } else {
-            info!("Choosing client with no authentication");
-            let client = self.get_temporal_client().await?;
-            Ok(TemporalClient::Standard(client))
-        }
+        self.execute_within(self.operation_timeout, operation).await
     }
 
-    async fn get_temporal_client(&self) -> Result<WorkflowServiceClient<Channel>> {
-        let endpoint: Uri = self.temporal_url.parse().unwrap();
-        WorkflowServiceClient::connect(endpoint).await.map_err(|e| {
-            warn!("Failed to connect to Temporal: {}", e);
-            Error::msg(format!(
-                r#"Could not connect to Temporal: {e}
-
-Please ensure the Temporal server is running.
-Is the Moose development server running? Start it with `moose dev`."#
-            ))
-        })
+    /// Like [`Self::execute`], within the shorter probe timeout.
+    pub async fn execute_probe<F, Fut, R>(&self, operation: F) -> Result<R>
+    where
+        F: FnOnce(TemporalClient) -> Fut,
+        Fut: std::future::Future<Output = Result<R>>,
+    {
+        self.execute_within(self.probe_timeout, operation).await
     }
 
-    async fn get_temporal_client_mtls(&self) -> Result<WorkflowServiceClient<Channel>> {
-        let ca_cert_path = self.ca_cert.clone();
-        let client_cert_path = self.client_cert.clone();
-        let client_key_path = self.client_key.clone();
-
-        let domain_name = self.config.get_temporal_domain_name();
-
-        let client_identity = tonic::transport::Identity::from_pem(
-            std::fs::read(client_cert_path).map_err(|e| Error::msg(e.to_string()))?,
-            std::fs::read(client_key_path).map_err(|e| Error::msg(e.to_string()))?,
-        );
-
-        let ca_certificate = tonic::transport::Certificate::from_pem(
-            std::fs::read(ca_cert_path).map_err(|e| Error::msg(e.to_string()))?,
-        );
-
-        let tls_config = tonic::transport::ClientTlsConfig::new()
-            .identity(client_identity)
-            .ca_certificate(ca_certificate)
-            .domain_name(domain_name);
-
-        let endpoint = tonic::transport::Channel::from_shared(self.temporal_url.to_string())
-            .map_err(|e| Error::msg(e.to_string()))?;
-
-        let client = WorkflowServiceClient::new(endpoint.tls_config(tls_config)?.connect().await?);
-
-        Ok(client)
+    async fn execute_within<F, Fut, R>(&self, timeout: Duration, operation: F) -> Result<R>
+    where
+        F: FnOnce(TemporalClient) -> Fut,
+        Fut: std::future::Future<Output = Result<R>>,
+    {
+        let call = async {
+            let client = self.get_client().await?;
+            operation(client).await
+        };
+        match tokio::time::timeout(timeout, call).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!(
+                    "Temporal at {} did not answer within {:?}",
+                    self.options.endpoint, timeout
+                );
+                Err(TemporalTimeoutError { timeout }.into())
+            }
+        }
     }
 
-    async fn get_temporal_client_api_key(
-        &self,
-    ) -> Result<WorkflowServiceClient<InterceptedService<Channel, ApiKeyInterceptor>>> {
-        let ca_cert_path = self.ca_cert.clone();
-        let api_key = self.api_key.clone();
-
-        let namespace = self.config.get_temporal_namespace();
-
-        let ca_certificate = tonic::transport::Certificate::from_pem(
-            std::fs::read(ca_cert_path).map_err(|e| Error::msg(e.to_string()))?,
-        );
-
-        let regional_endpoint = self.config.get_temporal_api_key_endpoint();
-        let domain_name = self.config.get_temporal_api_key_domain();
-        info!(
-            "Temporal API key mode: namespace='{}', endpoint='{}'",
-            namespace, regional_endpoint
-        );
-
-        let endpoint = tonic::transport::Channel::from_shared(regional_endpoint)
-            .map_err(|e| Error::msg(e.to_string()))?
-            .tls_config(
-                tonic::transport::ClientTlsConfig::new()
-                    .domain_name(domain_name)
-                    .ca_certificate(ca_certificate),
-            )
-            .map_err(|e| Error::msg(e.to_string()))?;
+    async fn get_client(&self) -> Result<TemporalClient> {
+        let options = &self.options;
+        info!("Getting client for Temporal URL: {}", options.endpoint);
 
-        let interceptor = ApiKeyInterceptor {
-            api_key: api_key.to_string(),
-            namespace: namespace.clone(),
-        };
+        let mut endpoint = Channel::from_shared(options.endpoint.clone())?;
+        if let Some(tls) = &options.tls {
+            endpoint = endpoint.tls_config(tls.client_tls_config()?)?;
+        }
 
-        let client = WorkflowServiceClient::with_interceptor(endpoint.connect_lazy(), interceptor);
+        match &options.api_key {
+            Some(api_key) => {
+                info!(
+                    "Choosing client with API key: namespace='{}', endpoint='{}'",
+                    options.namespace, options.endpoint
+                );
+                let interceptor = ApiKeyInterceptor {
+                    api_key: api_key.clone(),
+                    namespace: options.namespace.clone(),
+                };
+                Ok(TemporalClient::WithInterceptor(
+                    WorkflowServiceClient::with_interceptor(endpoint.connect_lazy(), interceptor),
+                ))
+            }
+            None => {
+                match &options.tls {
+                    Some(TlsOptions {
+                        identity: Some(_), ..
+                    }) => info!("Choosing client with mTLS"),
+                    Some(_) => info!("Choosing client with TLS and no authentication"),
+                    None => info!("Choosing client with no authentication"),
+                }
+                let channel = endpoint.connect().await.map_err(|e| {
+                    warn!("Failed to connect to Temporal: {}", e);
+                    let message = format!(
+                        r#"Could not connect to Temporal: {e}
 
-        Ok(client)
+Please ensure the Temporal server is running.
+Is the Moose development server running? Start it with `moose dev`."#
+                    );
+                    Error::new(e).context(message)
+                })?;
+                Ok(TemporalClient::Standard(WorkflowServiceClient::new(
+                    channel,
+                )))
+            }
+        }
     }
 }
 
@@ -190,26 +340,108 @@ pub fn manager_from_project_if_enabled(project: &Project) -> Option<TemporalClie
     TemporalClientManager::new_validate(&project.temporal_config, true).ok()
 }
 
+/// Why Temporal could not be probed, told apart so `moose check` can point at
+/// the setting to fix.
+#[derive(Debug, thiserror::Error)]
+pub enum TemporalProbeError {
+    #[error("TLS connection to Temporal at {endpoint} failed: {reason} (check ca_cert, client_cert and client_key)")]
+    Tls { endpoint: String, reason: String },
+    #[error("Temporal at {endpoint} rejected the credentials: {reason} (check api_key or the client certificate)")]
+    Auth { endpoint: String, reason: String },
+    #[error("Temporal namespace '{namespace}' was not found at {endpoint}")]
+    NamespaceNotFound { endpoint: String, namespace: String },
+    #[error("Could not reach Temporal at {endpoint}: {reason}")]
+    Unavailable { endpoint: String, reason: String },
+    #[error("Temporal at {endpoint} did not answer within {timeout_secs}s (check that the endpoint is right and reachable)")]
+    Timeout { endpoint: String, timeout_secs: f64 },
+    #[error("Temporal probe failed: {0}")]
+    Other(String),
+}
+
+/// Fragments of transport errors raised while negotiating TLS.
+const TLS_ERROR_MARKERS: [&str; 6] = [
+    "certificate",
+    "handshake",
+    "tls",
+    "ssl",
+    "x509",
+    "unknownissuer",
+];
+
+impl TemporalProbeError {
+    /// Classifies an error returned by [`TemporalClientManager::execute`] for
+    /// a probe of `namespace` through `options`, whose API key is redacted.
+    pub fn classify(error: &Error, options: &ConnectionOptions, namespace: &str) -> Self {
+        let endpoint = options.endpoint.clone();
+        let redact = |text: &str| match &options.api_key {
+            Some(api_key) => api_key.redact(text),
+            None => text.to_string(),
+        };
+        let reason = redact(&format!("{error:#}"));
+
+        if error.chain().any(|cause| cause.is::<TlsFileError>()) {
+            return TemporalProbeError::Tls { endpoint, reason };
+        }
+        if let Some(timeout) = error.downcast_ref::<TemporalTimeoutError>() {
+            return TemporalProbeError::Timeout {
+                endpoint,
+                timeout_secs: timeout.timeout.as_secs_f64(),
+            };
+        }
+
+        let status = error.downcast_ref::<tonic::Status>();
+        match status.map(tonic::Status::code) {
+            Some(tonic::Code::Unauthenticated | tonic::Code::PermissionDenied) => {
+                return TemporalProbeError::Auth {
+                    endpoint,
+                    reason: redact(status.map_or("", |s| s.message())),
+                }
+            }
+            Some(tonic::Code::NotFound) => {
+                return TemporalProbeError::NamespaceNotFound {
+                    endpoint,
+                    namespace: namespace.to_string(),
+                }
+            }
+            _ => {}
+        }
+
+        let tls_failure = error.chain().any(|cause| {
+            let message = cause.to_string().to_lowercase();
+            TLS_ERROR_MARKERS
+                .iter()
+                .any(|marker| message.contains(marker))
+        });
+        match status.map(tonic::Status::code) {
+            _ if tls_failure => TemporalProbeError::Tls { endpoint, reason },
+            None | Some(tonic::Code::Unavailable | tonic::Code::DeadlineExceeded) => {
+                TemporalProbeError::Unavailable { endpoint, reason }
+            }
+            Some(_) => TemporalProbeError::Other(reason),
+        }
+    }
+}
+
 /// Perform a lightweight probe against Temporal to establish/validate readiness.
 /// The `label` is embedded in the query to allow distinguishing warmup vs ready calls.
 pub async fn probe_temporal(
     manager: &TemporalClientManager,
     namespace: String,
     label: &str,
-) -> Result<()> {
+) -> Result<(), TemporalProbeError> {
     let query = format!("WorkflowType!='__{}__'", label);
+    let request = ListWorkflowExecutionsRequest {
+        namespace: namespace.clone(),
+        query,
+        page_size: 1,
+        ..Default::default()
+    };
     manager
-        .execute(move |mut c| async move {
-            c.list_workflow_executions(ListWorkflowExecutionsRequest {
-                namespace,
-                query,
-                page_size: 1,
-                ..Default::default()
-            })
-            .await
-            .map(|_| ())
-        })
+        .execute_probe(
+            move |mut c| async move { c.list_workflow_executions(request).await.map(|_| ()) },
+        )
         .await
+        .map_err(|e| TemporalProbeError::classify(&e, &manager.options, &namespace))
 }
 
 /// Probe Temporal readiness by calling DescribeNamespace, which requires only
@@ -217,18 +449,174 @@ pub async fn probe_temporal(
 pub async fn probe_temporal_namespace(
     manager: &TemporalClientManager,
     namespace: String,
-) -> Result<()> {
+) -> Result<(), TemporalProbeError> {
     info!("Probing Temporal namespace: '{}'", namespace);
+    let request = DescribeNamespaceRequest {
+        namespace: namespace.clone(),
+        ..Default::default()
+    };
     manager
-        .execute(move |mut c| async move {
-            c.describe_namespace(DescribeNamespaceRequest {
-                namespace,
-                ..Default::default()
-            })
+        .execute_probe(move |mut c| async move { c.describe_namespace(request).await.map(|_| ()) })
+        .await
+        .map_err(|e| TemporalProbeError::classify(&e, &manager.options, &namespace))
+}
+
+/// Errors from workflow management calls, mapped from the raw gRPC status
+/// Temporal returns so they can be shown to users as-is.
+#[derive(Debug, thiserror::Error)]
+pub enum WorkflowServiceError {
+    #[error("Workflow '{0}' was not found in this project's namespace")]
+    NotFound(String),
+    #[error("Workflow '{0}' has already completed")]
+    AlreadyCompleted(String),
+    #[error("Could not reach Temporal: {0}")]
+    Unavailable(String),
+    #[error("{0}")]
+    Timeout(String),
+    #[error("Temporal rejected the request: {0}")]
+    PermissionDenied(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl WorkflowServiceError {
+    pub fn from_status(workflow_id: &str, status: &tonic::Status) -> Self {
+        match status.code() {
+            tonic::Code::NotFound
+                if status
+                    .message()
+                    .contains("workflow execution already completed") =>
+            {
+                WorkflowServiceError::AlreadyCompleted(workflow_id.to_string())
+            }
+            tonic::Code::NotFound => WorkflowServiceError::NotFound(workflow_id.to_string()),
+            tonic::Code::Unavailable | tonic::Code::DeadlineExceeded => {
+                WorkflowServiceError::Unavailable(status.message().to_string())
+            }
+            tonic::Code::PermissionDenied | tonic::Code::Unauthenticated => {
+                WorkflowServiceError::PermissionDenied(status.message().to_string())
+            }
+            _ => WorkflowServiceError::Other(status.message().to_string()),
+        }
+    }
+
+    /// Maps an error returned by [`TemporalClientManager::execute`]. Errors
+    /// that are not gRPC statuses come from establishing the connection.
+    pub fn from_error(workflow_id: &str, error: &Error) -> Self {
+        if error.is::<TemporalTimeoutError>() {
+            return WorkflowServiceError::Timeout(error.to_string());
+        }
+        match error.downcast_ref::<tonic::Status>() {
+            Some(status) => Self::from_status(workflow_id, status),
+            None => WorkflowServiceError::Unavailable(error.to_string()),
+        }
+    }
+}
+
+/// Workflow management operations used by `moose workflow`, kept behind a
+/// trait so the CLI routines can be exercised without a Temporal server.
+#[async_trait]
+pub trait WorkflowService: Send + Sync {
+    /// Returns the status of a workflow execution. An empty `run_id` targets
+    /// the latest run of `workflow_id`.
+    async fn describe_status(
+        &self,
+        workflow_id: &str,
+        run_id: &str,
+    ) -> Result<WorkflowExecutionStatus, WorkflowServiceError>;
+
+    async fn request_cancel(
+        &self,
+        workflow_id: &str,
+        run_id: &str,
+    ) -> Result<(), WorkflowServiceError>;
+
+    async fn terminate(&self, workflow_id: &str, run_id: &str) -> Result<(), WorkflowServiceError>;
+}
+
+/// [`WorkflowService`] backed by a Temporal server, scoped to the project's namespace.
+pub struct TemporalWorkflowService {
+    manager: TemporalClientManager,
+    namespace: String,
+}
+
+impl TemporalWorkflowService {
+    pub fn new(config: &TemporalConfig) -> Result<Self, InvalidTemporalSchemeError> {
+        Ok(Self {
+            manager: TemporalClientManager::new_validate(config, true)?,
+            namespace: config.get_temporal_namespace(),
+        })
+    }
+}
+
+fn workflow_execution(workflow_id: &str, run_id: &str) -> Option<WorkflowExecution> {
+    Some(WorkflowExecution {
+        workflow_id: workflow_id.to_string(),
+        run_id: run_id.to_string(),
+    })
+}
+
+#[async_trait]
+impl WorkflowService for TemporalWorkflowService {
+    async fn describe_status(
+        &self,
+        workflow_id: &str,
+        run_id: &str,
+    ) -> Result<WorkflowExecutionStatus, WorkflowServiceError> {
+        let request = DescribeWorkflowExecutionRequest {
+            namespace: self.namespace.clone(),
+            execution: workflow_execution(workflow_id, run_id),
+        };
+
+        let response = self
+            .manager
+            .execute(|mut client| async move { client.describe_workflow_execution(request).await })
+            .await
+            .map_err(|e| WorkflowServiceError::from_error(workflow_id, &e))?;
+
+        let status = response
+            .into_inner()
+            .workflow_execution_info
+            .map_or(0, |info| info.status);
+        Ok(WorkflowExecutionStatus::try_from(status)
+            .unwrap_or(WorkflowExecutionStatus::Unspecified))
+    }
+
+    async fn request_cancel(
+        &self,
+        workflow_id: &str,
+        run_id: &str,
+    ) -> Result<(), WorkflowServiceError> {
+        let request = RequestCancelWorkflowExecutionRequest {
+            namespace: self.namespace.clone(),
+            workflow_execution: workflow_execution(workflow_id, run_id),
+            reason: "Cancelled by user request".to_string(),
+            ..Default::default()
+        };
+
+        self.manager
+            .execute(
+                |mut client| async move { client.request_cancel_workflow_execution(request).await },
+            )
             .await
             .map(|_| ())
-        })
-        .await
+            .map_err(|e| WorkflowServiceError::from_error(workflow_id, &e))
+    }
+
+    async fn terminate(&self, workflow_id: &str, run_id: &str) -> Result<(), WorkflowServiceError> {
+        let request = TerminateWorkflowExecutionRequest {
+            namespace: self.namespace.clone(),
+            workflow_execution: workflow_execution(workflow_id, run_id),
+            reason: "Terminated by user request".to_string(),
+            ..Default::default()
+        };
+
+        self.manager
+            .execute(|mut client| async move { client.terminate_workflow_execution(request).await })
+            .await
+            .map(|_| ())
+            .map_err(|e| WorkflowServiceError::from_error(workflow_id, &e))
+    }
 }
 
 impl TemporalClient {
@@ -383,4 +771,370 @@ impl TemporalClient {
                 .map_err(Error::from),
         }
     }
+
+    pub async fn create_schedule(
+        &mut self,
+        request: CreateScheduleRequest,
+    ) -> Result<tonic::Response<CreateScheduleResponse>> {
+        match self {
+            TemporalClient::Standard(client) => {
+                client.create_schedule(request).await.map_err(Error::from)
+            }
+            TemporalClient::WithInterceptor(client) => {
+                client.create_schedule(request).await.map_err(Error::from)
+            }
+        }
+    }
+
+    pub async fn update_schedule(
+        &mut self,
+        request: UpdateScheduleRequest,
+    ) -> Result<tonic::Response<UpdateScheduleResponse>> {
+        match self {
+            TemporalClient::Standard(client) => {
+                client.update_schedule(request).await.map_err(Error::from)
+            }
+            TemporalClient::WithInterceptor(client) => {
+                client.update_schedule(request).await.map_err(Error::from)
+            }
+        }
+    }
+
+    pub async fn delete_schedule(
+        &mut self,
+        request: DeleteScheduleRequest,
+    ) -> Result<tonic::Response<DeleteScheduleResponse>> {
+        match self {
+            TemporalClient::Standard(client) => {
+                client.delete_schedule(request).await.map_err(Error::from)
+            }
+            TemporalClient::WithInterceptor(client) => {
+                client.delete_schedule(request).await.map_err(Error::from)
+            }
+        }
+    }
+
+    pub async fn describe_schedule(
+        &mut self,
+        request: DescribeScheduleRequest,
+    ) -> Result<tonic::Response<DescribeScheduleResponse>> {
+        match self {
+            TemporalClient::Standard(client) => {
+                client.describe_schedule(request).await.map_err(Error::from)
+            }
+            TemporalClient::WithInterceptor(client) => {
+                client.describe_schedule(request).await.map_err(Error::from)
+            }
+        }
+    }
+
+    pub async fn list_schedules(
+        &mut self,
+        request: ListSchedulesRequest,
+    ) -> Result<tonic::Response<ListSchedulesResponse>> {
+        match self {
+            TemporalClient::Standard(client) => {
+                client.list_schedules(request).await.map_err(Error::from)
+            }
+            TemporalClient::WithInterceptor(client) => {
+                client.list_schedules(request).await.map_err(Error::from)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::orchestration::temporal::TemporalScheme;
+
+    #[test]
+    fn test_workflow_service_error_from_status() {
+        let completed = tonic::Status::not_found("workflow execution already completed");
+        assert!(matches!(
+            WorkflowServiceError::from_status("daily", &completed),
+            WorkflowServiceError::AlreadyCompleted(id) if id == "daily"
+        ));
+
+        let missing = tonic::Status::not_found("workflow not found for ID: daily");
+        assert_eq!(
+            WorkflowServiceError::from_status("daily", &missing).to_string(),
+            "Workflow 'daily' was not found in this project's namespace"
+        );
+
+        let unavailable = tonic::Status::unavailable("connection refused");
+        assert!(matches!(
+            WorkflowServiceError::from_status("daily", &unavailable),
+            WorkflowServiceError::Unavailable(_)
+        ));
+    }
+
+    #[test]
+    fn test_workflow_service_error_from_connection_error() {
+        let error = Error::msg("Could not connect to Temporal");
+        assert!(matches!(
+            WorkflowServiceError::from_error("daily", &error),
+            WorkflowServiceError::Unavailable(_)
+        ));
+
+        let error = Error::from(tonic::Status::not_found("not found"));
+        assert!(matches!(
+            WorkflowServiceError::from_error("daily", &error),
+            WorkflowServiceError::NotFound(_)
+        ));
+
+        let error = Error::from(TemporalTimeoutError {
+            timeout: Duration::from_secs(30),
+        });
+        assert!(matches!(
+            WorkflowServiceError::from_error("daily", &error),
+            WorkflowServiceError::Timeout(message) if message == "Temporal did not answer within 30s"
+        ));
+    }
+
+    #[test]
+    fn test_connection_options_local_plaintext() {
+        let options = ConnectionOptions::from_config(&TemporalConfig::default(), true).unwrap();
+        assert_eq!(
+            options,
+            ConnectionOptions {
+                endpoint: "http://localhost:7233".to_string(),
+                tls: None,
+                api_key: None,
+                namespace: "default".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_connection_options_api_key() {
+        let config = TemporalConfig {
+            temporal_host: "my-ns.a1b2c.tmprl.cloud".to_string(),
+            temporal_region: "us-east1".to_string(),
+            api_key: "tmprl-key".to_string(),
+            ..Default::default()
+        };
+        let options = ConnectionOptions::from_config(&config, true).unwrap();
+        assert_eq!(
+            options.endpoint,
+            "https://us-east1.gcp.api.temporal.io:7233"
+        );
+        assert_eq!(
+            options.tls,
+            Some(TlsOptions {
+                domain_name: "us-east1.gcp.api.temporal.io".to_string(),
+                ca_cert: None,
+                identity: None,
+            })
+        );
+        assert_eq!(options.api_key, Some(ApiKey("tmprl-key".to_string())));
+        assert_eq!(options.namespace, "my-ns.a1b2c");
+        assert!(!format!("{options:?}").contains("tmprl-key"));
+    }
+
+    #[test]
+    fn test_connection_options_mtls() {
+        let config = TemporalConfig {
+            temporal_host: "my-ns.a1b2c.tmprl.cloud".to_string(),
+            client_cert: "certs/client.pem".to_string(),
+            client_key: "certs/client.key".to_string(),
+            ca_cert: "certs/ca.pem".to_string(),
+            api_key: "tmprl-key".to_string(),
+            ..Default::default()
+        };
+        let options = ConnectionOptions::from_config(&config, true).unwrap();
+        assert_eq!(options.endpoint, "https://my-ns.a1b2c.tmprl.cloud:7233");
+        assert_eq!(
+            options.tls,
+            Some(TlsOptions {
+                domain_name: "my-ns.a1b2c.tmprl.cloud".to_string(),
+                ca_cert: Some(PathBuf::from("certs/ca.pem")),
+                identity: Some((
+                    PathBuf::from("certs/client.pem"),
+                    PathBuf::from("certs/client.key")
+                )),
+            })
+        );
+        // The certificate authenticates the client, the key is not sent
+        assert_eq!(options.api_key, None);
+    }
+
+    #[test]
+    fn test_connection_options_tls_without_client_auth() {
+        // A CA certificate alone upgrades to TLS, even on localhost
+        let config = TemporalConfig {
+            ca_cert: "ca.pem".to_string(),
+            ..Default::default()
+        };
+        let options = ConnectionOptions::from_config(&config, true).unwrap();
+        assert_eq!(options.endpoint, "https://localhost:7233");
+        assert_eq!(options.tls.unwrap().ca_cert, Some(PathBuf::from("ca.pem")));
+
+        // So does an https scheme, trusting the system roots
+        let config = TemporalConfig {
+            temporal_host: "temporal.internal".to_string(),
+            ..Default::default()
+        };
+        let options = ConnectionOptions::from_config(&config, true).unwrap();
+        assert_eq!(options.endpoint, "https://temporal.internal:7233");
+        assert_eq!(
+            options.tls,
+            Some(TlsOptions {
+                domain_name: "temporal.internal".to_string(),
+                ca_cert: None,
+                identity: None,
+            })
+        );
+        assert_eq!(options.api_key, None);
+    }
+
+    fn classify(error: Error) -> TemporalProbeError {
+        let options = ConnectionOptions {
+            endpoint: "https://temporal:7233".to_string(),
+            tls: None,
+            api_key: Some(ApiKey("tmprl-probe-s3cr3t".to_string())),
+            namespace: "analytics".to_string(),
+        };
+        TemporalProbeError::classify(&error, &options, "analytics")
+    }
+
+    #[test]
+    fn test_probe_error_auth() {
+        let error = classify(Error::from(tonic::Status::unauthenticated(
+            "Request unauthorized.",
+        )));
+        assert!(matches!(error, TemporalProbeError::Auth { .. }), "{error}");
+
+        let error = classify(Error::from(tonic::Status::permission_denied(
+            "Request unauthorized.",
+        )));
+        assert!(matches!(error, TemporalProbeError::Auth { .. }), "{error}");
+    }
+
+    #[test]
+    fn test_probe_error_auth_redacts_api_key() {
+        let error = classify(Error::from(tonic::Status::unauthenticated(
+            "invalid key tmprl-probe-s3cr3t",
+        )));
+        assert!(!error.to_string().contains("tmprl-probe-s3cr3t"), "{error}");
+    }
+
+    #[test]
+    fn test_probe_error_namespace_not_found() {
+        let error = classify(Error::from(tonic::Status::not_found(
+            "Namespace analytics is not found.",
+        )));
+        assert_eq!(
+            error.to_string(),
+            "Temporal namespace 'analytics' was not found at https://temporal:7233"
+        );
+    }
+
+    #[test]
+    fn test_probe_error_tls_from_transport() {
+        // Lazy channels surface handshake failures as a status wrapping the transport error
+        let transport = std::io::Error::other("invalid peer certificate: UnknownIssuer");
+        let error = classify(Error::from(tonic::Status::from_error(Box::new(transport))));
+        assert!(matches!(error, TemporalProbeError::Tls { .. }), "{error}");
+
+        // Eager connections fail before any status
+        let transport = std::io::Error::other("received fatal alert: HandshakeFailure");
+        let error = classify(Error::new(transport).context("Could not connect to Temporal"));
+        assert!(matches!(error, TemporalProbeError::Tls { .. }), "{error}");
+    }
+
+    #[test]
+    fn test_probe_error_tls_from_unreadable_certificate() {
+        let tls = TlsOptions {
+            domain_name: "temporal".to_string(),
+            ca_cert: Some(PathBuf::from("/nonexistent/ca.pem")),
+            identity: None,
+        };
+        let error = classify(Error::from(tls.client_tls_config().unwrap_err()));
+        assert!(matches!(error, TemporalProbeError::Tls { .. }), "{error}");
+        assert!(error
+            .to_string()
+            .contains("cannot read ca_cert /nonexistent/ca.pem"));
+    }
+
+    #[test]
+    fn test_probe_error_unavailable() {
+        let refused = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+        let error = classify(Error::new(refused).context("Could not connect to Temporal"));
+        assert!(
+            matches!(error, TemporalProbeError::Unavailable { .. }),
+            "{error}"
+        );
+
+        let error = classify(Error::from(tonic::Status::unavailable("connection reset")));
+        assert!(
+            matches!(error, TemporalProbeError::Unavailable { .. }),
+            "{error}"
+        );
+
+        let error = classify(Error::from(tonic::Status::internal("boom")));
+        assert!(matches!(error, TemporalProbeError::Other(_)), "{error}");
+    }
+
+    #[test]
+    fn test_probe_error_timeout() {
+        let error = classify(Error::from(TemporalTimeoutError {
+            timeout: Duration::from_millis(2500),
+        }));
+        assert_eq!(
+            error.to_string(),
+            "Temporal at https://temporal:7233 did not answer within 2.5s (check that the endpoint is right and reachable)"
+        );
+    }
+
+    /// Accepts connections and never answers, like a firewall dropping packets.
+    async fn black_hole() -> u16 {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((connection, _)) = listener.accept().await {
+                connections.push(connection);
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_probe_times_out_on_unresponsive_temporal() {
+        let config = TemporalConfig {
+            temporal_scheme: Some(TemporalScheme::Http),
+            temporal_host: "127.0.0.1".to_string(),
+            temporal_port: black_hole().await,
+            probe_timeout_secs: 1,
+            ..Default::default()
+        };
+        let manager = TemporalClientManager::new(&config).unwrap();
+
+        let started = std::time::Instant::now();
+        let error = probe_temporal_namespace(&manager, "default".to_string())
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(error, TemporalProbeError::Timeout { .. }),
+            "{error}"
+        );
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+
+    #[tokio::test]
+    async fn test_operation_times_out_on_unresponsive_temporal() {
+        let config = TemporalConfig {
+            temporal_scheme: Some(TemporalScheme::Http),
+            temporal_host: "127.0.0.1".to_string(),
+            temporal_port: black_hole().await,
+            operation_timeout_secs: 1,
+            ..Default::default()
+        };
+        let service = TemporalWorkflowService::new(&config).unwrap();
+
+        let error = service.terminate("daily", "").await.unwrap_err();
+        assert!(matches!(error, WorkflowServiceError::Timeout(_)), "{error}");
+    }
 }
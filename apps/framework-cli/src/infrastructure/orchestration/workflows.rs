@@ -1,16 +1,53 @@
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+
+use crate::cli::display::{show_message_wrapper, Message, MessageType};
 use crate::cli::routines::scripts::terminate_workflow;
 use crate::framework::core::infrastructure_map::{Change, WorkflowChange};
 use crate::framework::scripts::Workflow;
+use crate::infrastructure::orchestration::schedules::{delete_schedule, upsert_schedule};
+use crate::infrastructure::orchestration::temporal_client::{
+    probe_temporal_namespace, TemporalClientManager,
+};
 use crate::project::Project;
 
+lazy_static! {
+    /// Why the last workflow changes were skipped, while Temporal could not
+    /// be reached.
+    static ref DEGRADED: RwLock<Option<String>> = RwLock::new(None);
+}
+
+/// Why workflows are degraded, `None` when the last changes were applied.
+pub fn degraded_reason() -> Option<String> {
+    DEGRADED.read().unwrap().clone()
+}
+
+fn set_degraded(reason: Option<String>) {
+    *DEGRADED.write().unwrap() = reason;
+}
+
+/// Checks that Temporal answers within the probe timeout, so that a hung
+/// endpoint doesn't hold up every change after it.
+async fn probe(project: &Project) -> Result<(), String> {
+    let manager = TemporalClientManager::new_validate(&project.temporal_config, true)
+        .map_err(|e| e.to_string())?;
+    probe_temporal_namespace(&manager, project.temporal_config.get_temporal_namespace())
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Executes workflow changes based on the diff between current and target infrastructure.
 ///
 /// Change handling:
-/// - Added: terminate + register if scheduled
-/// - Removed: terminate
-/// - Updated: terminate + register if scheduled
+/// - Added: terminate runs left by older versions + create the schedule if scheduled
+/// - Removed: delete the schedule + terminate
+/// - Updated: update the schedule, or delete it when the schedule was removed
 /// - No change: no action needed
 ///
+/// When Temporal can't be probed, the changes are skipped and workflows are
+/// reported as degraded instead of blocking startup.
+///
 /// Shutdown behavior:
 /// - Workers stop, but workflow definitions and schedules persist in Temporal
 /// - On restart, workers reconnect and resume orchestration
@@ -25,6 +62,22 @@ pub async fn execute_changes(project: &Project, changes: &[WorkflowChange]) {
         return;
     }
 
+    if let Err(reason) = probe(project).await {
+        show_message_wrapper(
+            MessageType::Warning,
+            Message {
+                action: "Workflows".to_string(),
+                details: format!(
+                    "degraded, skipping {} workflow change(s): {reason}",
+                    changes.len()
+                ),
+            },
+        );
+        set_degraded(Some(reason));
+        return;
+    }
+    set_degraded(None);
+
     tracing::info!("Executing {} workflow change(s)", changes.len());
 
     for change in changes {
@@ -43,6 +96,7 @@ pub async fn execute_changes(project: &Project, changes: &[WorkflowChange]) {
 }
 
 async fn handle_workflow_added(project: &Project, workflow: &Workflow) {
+    // Earlier versions started scheduled workflows as cron workflows
     terminate(project, workflow.name()).await;
 
     if workflow.config().schedule.is_empty() {
@@ -53,23 +107,25 @@ async fn handle_workflow_added(project: &Project, workflow: &Workflow) {
         return;
     }
 
-    start(project, workflow).await;
+    schedule(project, workflow).await;
 }
 
 async fn handle_workflow_removed(project: &Project, workflow: &Workflow) {
+    unschedule(project, workflow.name()).await;
     terminate(project, workflow.name()).await;
 }
 
 async fn handle_workflow_updated(project: &Project, before: &Workflow, after: &Workflow) {
-    terminate(project, before.name()).await;
-
     if after.config().schedule.is_empty() {
-        tracing::info!(
-            "Workflow '{}' schedule removed, workflow stopped",
-            after.name()
-        );
+        if !before.config().schedule.is_empty() {
+            unschedule(project, before.name()).await;
+            tracing::info!(
+                "Workflow '{}' schedule removed, available for manual trigger only",
+                after.name()
+            );
+        }
     } else {
-        start(project, after).await;
+        schedule(project, after).await;
     }
 }
 
@@ -79,30 +135,99 @@ async fn terminate(project: &Project, name: &str) {
             tracing::info!("Terminated workflow '{}'", name);
         }
         Err(e) => {
-            // May not be running or on schedule
+            // May not be running
             tracing::debug!("Could not terminate workflow '{}': {:?}", name, e);
         }
     }
 }
 
-async fn start(project: &Project, workflow: &Workflow) {
-    let schedule = &workflow.config().schedule;
-    match workflow.start(&project.temporal_config, None).await {
-        Ok(info) => {
+async fn schedule(project: &Project, workflow: &Workflow) {
+    let config = workflow.config();
+    let timezone = config.timezone.as_deref().unwrap_or("UTC");
+    match upsert_schedule(&project.temporal_config, workflow).await {
+        Ok(()) => {
             tracing::info!(
-                "Started workflow '{}' with schedule '{}' (run_id: {})",
+                "Scheduled workflow '{}' on '{}' ({})",
                 workflow.name(),
-                schedule,
-                info.run_id
+                config.schedule,
+                timezone
             );
         }
         Err(e) => {
             tracing::error!(
-                "Failed to start workflow '{}' with schedule '{}': {}",
+                "Failed to schedule workflow '{}' on '{}' ({}): {:#}",
                 workflow.name(),
-                schedule,
+                config.schedule,
+                timezone,
                 e
             );
         }
     }
 }
+
+async fn unschedule(project: &Project, name: &str) {
+    match delete_schedule(&project.temporal_config, name).await {
+        Ok(()) => {
+            tracing::info!("Deleted the schedule of workflow '{}'", name);
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete the schedule of workflow '{}': {:#}",
+                name,
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::languages::SupportedLanguages;
+    use crate::infrastructure::orchestration::temporal::TemporalScheme;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_unresponsive_temporal_degrades_workflows() {
+        // Accepts connections and never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((connection, _)) = listener.accept().await {
+                connections.push(connection);
+            }
+        });
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut project = Project::new(
+            temp_dir.path(),
+            "degraded".to_string(),
+            SupportedLanguages::Typescript,
+        );
+        project.features.workflows = true;
+        project.temporal_config.temporal_scheme = Some(TemporalScheme::Http);
+        project.temporal_config.temporal_host = "127.0.0.1".to_string();
+        project.temporal_config.temporal_port = port;
+        project.temporal_config.probe_timeout_secs = 1;
+
+        let workflow = Workflow::from_user_code(
+            "nightly".to_string(),
+            SupportedLanguages::Typescript,
+            None,
+            None,
+            Some("@daily".to_string()),
+            vec![],
+            vec![],
+        );
+        let changes = [WorkflowChange::Workflow(Change::Added(Box::new(workflow)))];
+
+        let started = Instant::now();
+        execute_changes(&project, &changes).await;
+
+        assert!(started.elapsed() < Duration::from_secs(3));
+        let reason = degraded_reason().expect("workflows should be degraded");
+        assert!(reason.contains("did not answer within 1s"), "{reason}");
+    }
+}
@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use tracing::{info, instrument};
 
@@ -6,7 +7,7 @@ use crate::cli::logger::{context, resource_type};
 use crate::utilities::system::{RestartPolicy, RestartingProcess, StartChildFn};
 use crate::{
     framework::{languages::SupportedLanguages, python, typescript},
-    infrastructure::olap::clickhouse::config::ClickHouseConfig,
+    infrastructure::olap::clickhouse::credentials::SharedClient,
     project::{JwtConfig, Project, ProjectFileError},
     utilities::system::KillProcessError,
 };
@@ -26,7 +27,8 @@ pub enum ConsumptionError {
 
 pub struct ConsumptionProcessRegistry {
     api_process: Option<RestartingProcess>,
-    clickhouse_config: ClickHouseConfig,
+    /// Its current credentials are passed to the process on each (re)start
+    clickhouse_client: Arc<SharedClient>,
     language: SupportedLanguages,
     project_path: PathBuf,
     jwt_config: Option<JwtConfig>,
@@ -37,7 +39,7 @@ pub struct ConsumptionProcessRegistry {
 impl ConsumptionProcessRegistry {
     pub fn new(
         language: SupportedLanguages,
-        clickhouse_config: ClickHouseConfig,
+        clickhouse_client: Arc<SharedClient>,
         jwt_config: Option<JwtConfig>,
         project_path: PathBuf,
         project: Project,
@@ -47,7 +49,7 @@ impl ConsumptionProcessRegistry {
         Self {
             api_process: Option::None,
             language,
-            clickhouse_config,
+            clickhouse_client,
             project_path,
             jwt_config,
             project,
@@ -68,7 +70,7 @@ impl ConsumptionProcessRegistry {
         info!("Starting analytics api...");
 
         let project = self.project.clone();
-        let clickhouse_config = self.clickhouse_config.clone();
+        let clickhouse_client = self.clickhouse_client.clone();
         let jwt_config = self.jwt_config.clone();
         let proxy_port = self.proxy_port;
 
@@ -76,7 +78,7 @@ impl ConsumptionProcessRegistry {
             SupportedLanguages::Python => Box::new(move || {
                 python::consumption::run(
                     &project,
-                    &clickhouse_config,
+                    &clickhouse_client.config(),
                     &jwt_config,
                     proxy_port,
                     project.is_production,
@@ -87,7 +89,7 @@ impl ConsumptionProcessRegistry {
                 Box::new(move || {
                     typescript::consumption::run(
                         &project,
-                        &clickhouse_config,
+                        &clickhouse_client.config(),
                         &jwt_config,
                         &project_path,
                         proxy_port,
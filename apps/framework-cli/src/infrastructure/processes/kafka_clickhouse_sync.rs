@@ -9,7 +9,7 @@
 //! batching, back pressure, and error handling mechanisms.
 
 use futures::TryFutureExt;
-use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
 use rdkafka::producer::{DeliveryFuture, Producer};
 use rdkafka::Message;
 use serde_json::Value;
@@ -24,13 +24,13 @@ use crate::cli::logger::{context, resource_type};
 
 use crate::framework::core::infrastructure::table::Column;
 use crate::framework::core::infrastructure::table::ColumnType;
-use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
-use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::credentials::{SharedClient, SharedInsertClient};
 use crate::infrastructure::olap::clickhouse::errors::ClickhouseError;
 use crate::infrastructure::olap::clickhouse::inserter::Inserter;
 use crate::infrastructure::olap::clickhouse::model::{
     ClickHouseColumn, ClickHouseRecord, ClickHouseRuntimeEnum, ClickHouseValue,
 };
+use crate::infrastructure::processes::write_pause::{wait_until_resumed, WritePause};
 use crate::infrastructure::stream::kafka::client::create_subscriber;
 use crate::infrastructure::stream::kafka::client::{create_producer, send_with_back_pressure};
 use crate::infrastructure::stream::kafka::models::KafkaConfig;
@@ -86,8 +86,8 @@ pub struct SyncingProcessesRegistry {
     to_topic_registry: HashMap<String, TopicSyncProcess>,
     /// Kafka configuration
     kafka_config: KafkaConfig,
-    /// ClickHouse configuration
-    clickhouse_config: ClickHouseConfig,
+    /// ClickHouse client, whose credentials are reloaded when rotated
+    clickhouse_client: Arc<SharedClient>,
 }
 
 impl SyncingProcessesRegistry {
@@ -95,13 +95,13 @@ impl SyncingProcessesRegistry {
     ///
     /// # Arguments
     /// * `kafka_config` - Configuration for Kafka/Redpanda connection
-    /// * `clickhouse_config` - Configuration for ClickHouse connection
-    pub fn new(kafka_config: KafkaConfig, clickhouse_config: ClickHouseConfig) -> Self {
+    /// * `clickhouse_client` - Shared ClickHouse client the processes insert with
+    pub fn new(kafka_config: KafkaConfig, clickhouse_client: Arc<SharedClient>) -> Self {
         Self {
             to_table_registry: HashMap::new(),
             to_topic_registry: HashMap::new(),
             kafka_config,
-            clickhouse_config,
+            clickhouse_client,
         }
     }
 
@@ -190,7 +190,7 @@ impl SyncingProcessesRegistry {
 
         let TableSyncProcess { handle, cancel_tx } = spawn_sync_process_core(
             self.kafka_config.clone(),
-            self.clickhouse_config.clone(),
+            self.clickhouse_client.clone(),
             source_topic_name,
             source_topic_columns,
             target_table_name,
@@ -375,7 +375,7 @@ impl SyncingProcessesRegistry {
 ///
 /// # Arguments
 /// * `kafka_config` - Kafka/Redpanda configuration
-/// * `clickhouse_config` - ClickHouse configuration
+/// * `clickhouse_client` - Shared ClickHouse client
 /// * `source_topic_name` - Source Kafka topic name
 /// * `source_topic_columns` - Schema definition of the source topic
 /// * `target_table_name` - Target ClickHouse table name
@@ -388,7 +388,7 @@ impl SyncingProcessesRegistry {
 #[allow(clippy::too_many_arguments)]
 fn spawn_sync_process_core(
     kafka_config: KafkaConfig,
-    clickhouse_config: ClickHouseConfig,
+    clickhouse_client: Arc<SharedClient>,
     source_topic_name: String,
     source_topic_columns: Vec<Column>,
     target_table_name: String,
@@ -404,7 +404,7 @@ fn spawn_sync_process_core(
     let handle = tokio::spawn(
         sync_kafka_to_clickhouse(
             kafka_config,
-            clickhouse_config,
+            clickhouse_client,
             source_topic_name.clone(),
             source_topic_columns,
             target_table_name.clone(),
@@ -561,7 +561,7 @@ async fn sync_kafka_to_kafka(
 ///
 /// # Arguments
 /// * `kafka_config` - Kafka/Redpanda configuration
-/// * `clickhouse_config` - ClickHouse configuration
+/// * `clickhouse_client` - Shared ClickHouse client
 /// * `source_topic_name` - Source Kafka topic name
 /// * `source_topic_columns` - Schema definition of the source topic
 /// * `target_table_name` - Target ClickHouse table name
@@ -574,7 +574,7 @@ async fn sync_kafka_to_kafka(
 #[allow(clippy::too_many_arguments)]
 async fn sync_kafka_to_clickhouse(
     kafka_config: KafkaConfig,
-    clickhouse_config: ClickHouseConfig,
+    clickhouse_client: Arc<SharedClient>,
     source_topic_name: String,
     source_topic_columns: Vec<Column>,
     target_table_name: String,
@@ -604,13 +604,13 @@ async fn sync_kafka_to_clickhouse(
     let subscriber_clone = subscriber.clone();
     let table_clone = target_table_name.clone();
 
-    let client = ClickHouseClient::new(&clickhouse_config).unwrap();
-    let mut inserter = Inserter::<ClickHouseClient>::new(
-        client,
+    let mut inserter = Inserter::<SharedInsertClient>::new(
+        SharedInsertClient::new(clickhouse_client),
         MAX_BATCH_SIZE,
         Box::new(move |partition, offset| {
             subscriber_clone.store_offset(&topic_clone, partition, offset)
         }),
+        &source_topic_name,
         target_table_name,
         target_database,
         clickhouse_columns,
@@ -627,7 +627,42 @@ async fn sync_kafka_to_clickhouse(
 
     // This should also not be broken, otherwise, the subscriber will stop receiving messages
 
+    // Held so that the pause sender outlives this task
+    let write_pause = WritePause::global().clone();
+    let mut paused = write_pause.subscribe();
+
     loop {
+        // Another instance is migrating: write out what was consumed and stop
+        // consuming until it is done
+        if *paused.borrow_and_update() {
+            if !inserter.flush_all().await {
+                warn!(
+                    "Could not flush all batches for {} before pausing for a migration",
+                    table_clone
+                );
+            }
+            if let Err(e) = subscriber.commit_consumer_state(CommitMode::Async) {
+                debug!(
+                    "Nothing committed for {} before pausing: {}",
+                    source_topic_name, e
+                );
+            }
+            info!(
+                "Paused kafka-clickhouse sync {} -> {} during a migration",
+                source_topic_name, table_clone
+            );
+
+            select! {
+                _ = &mut cancel_rx => {
+                    info!("Received cancellation signal for kafka-clickhouse sync: {} -> {}", source_topic_name, table_clone);
+                    return Ok(());
+                }
+                _ = wait_until_resumed(&mut paused) => {
+                    info!("Resumed kafka-clickhouse sync {} -> {}", source_topic_name, table_clone);
+                }
+            }
+        }
+
         // if we have a full batch, we flush it
         if !inserter.is_empty() && inserter.len() > 1 {
             inserter.flush().await;
@@ -641,6 +676,10 @@ async fn sync_kafka_to_clickhouse(
                 inserter.flush().await;
                 return Ok(());
             }
+            // Pause requested, handled at the top of the loop
+            _ = paused.changed() => {
+                continue;
+            }
             // This is here to ensure that if we don't have new messages to process, we still flush
             // the inserter at the end of the interval.
             _ = interval_clock.tick() => {
@@ -685,6 +724,7 @@ async fn sync_kafka_to_clickhouse(
                                                 clickhouse_record,
                                                 message.partition(),
                                                 message.offset(),
+                                                message.timestamp().to_millis(),
                                             );
                                         }
                                     }
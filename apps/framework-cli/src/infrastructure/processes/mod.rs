@@ -20,6 +20,7 @@ pub mod functions_registry;
 pub mod kafka_clickhouse_sync;
 pub mod orchestration_workers_registry;
 pub mod process_registry;
+pub mod write_pause;
 
 #[derive(Debug, thiserror::Error)]
 pub enum SyncProcessChangesError {
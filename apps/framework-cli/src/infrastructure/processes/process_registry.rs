@@ -4,7 +4,10 @@
 //! It coordinates the lifecycle of function processes, consumption processes,
 //! and orchestration worker processes.
 
+use std::sync::Arc;
+
 use crate::cli::settings::Settings;
+use crate::infrastructure::olap::clickhouse::credentials::SharedClient;
 use crate::project::Project;
 
 use super::consumption_registry::{ConsumptionError, ConsumptionProcessRegistry};
@@ -58,15 +61,21 @@ impl ProcessRegistries {
     /// * `project` - Project configuration containing paths and settings for processes
     /// * `settings` - Global application settings
     /// * `syncing` - Syncing processes registry for Kafka to ClickHouse and topic-to-topic synchronization
+    /// * `clickhouse_client` - Shared ClickHouse client, whose credentials the analytics api is started with
     ///
     /// # Returns
     /// * `Self` - A new ProcessRegistries instance
-    pub fn new(project: &Project, settings: &Settings, syncing: SyncingProcessesRegistry) -> Self {
+    pub fn new(
+        project: &Project,
+        settings: &Settings,
+        syncing: SyncingProcessesRegistry,
+        clickhouse_client: Arc<SharedClient>,
+    ) -> Self {
         let functions = FunctionProcessRegistry::new(project.clone());
 
         let consumption = ConsumptionProcessRegistry::new(
             project.language,
-            project.clickhouse_config.clone(),
+            clickhouse_client,
             project.jwt.clone(),
             project.project_location.clone(),
             project.clone(),
@@ -0,0 +1,363 @@
+//! Pausing the Kafka to ClickHouse sync while another instance migrates.
+//!
+//! The instance applying a plan broadcasts a `migration_start`
+//! [`MigrationMessage`] before touching the schema and a `migration_end` one
+//! after. The other instances pause their sync tasks in between: each task
+//! writes out and commits what it has already consumed, then stops consuming
+//! until the pause is lifted, so no insert races an `ALTER`.
+//!
+//! A migration whose end never arrives (the migrating instance died) stops
+//! pausing writes after [`PAUSE_TIMEOUT`], with a warning.
+
+use std::collections::HashSet;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+/// How long writes stay paused without a `migration_end`. Kept below the
+/// Kafka `max.poll.interval.ms` default (5 minutes), past which a consumer
+/// that stopped polling is removed from its group.
+pub const PAUSE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long the migrating instance waits after `migration_start`, for the
+/// others to flush their pending batches.
+pub const PAUSE_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// Messages sent before migration messages were JSON.
+const LEGACY_START_MARKER: &str = "<migration_start>";
+const LEGACY_END_MARKER: &str = "<migration_end>";
+/// Plan id standing for the migration announced by a legacy marker
+const LEGACY_PLAN_ID: &str = "legacy";
+
+static GLOBAL: LazyLock<WritePause> = LazyLock::new(WritePause::new);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationPhase {
+    MigrationStart,
+    MigrationEnd,
+}
+
+/// A migration announcement on the pub/sub channel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationMessage {
+    #[serde(rename = "type")]
+    pub phase: MigrationPhase,
+    /// The fields below are missing from legacy markers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan_id: Option<String>,
+    /// Instance applying the plan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl MigrationMessage {
+    pub fn new(phase: MigrationPhase, plan_id: &str, instance_id: &str) -> Self {
+        Self {
+            phase,
+            plan_id: Some(plan_id.to_string()),
+            instance_id: Some(instance_id.to_string()),
+            timestamp: Some(Utc::now()),
+        }
+    }
+
+    /// Parses a pub/sub message, `None` when it isn't about a migration.
+    pub fn parse(message: &str) -> Option<Self> {
+        if let Ok(parsed) = serde_json::from_str::<MigrationMessage>(message) {
+            return Some(parsed);
+        }
+
+        let phase = if message.contains(LEGACY_START_MARKER) {
+            MigrationPhase::MigrationStart
+        } else if message.contains(LEGACY_END_MARKER) {
+            MigrationPhase::MigrationEnd
+        } else {
+            return None;
+        };
+        Some(Self {
+            phase,
+            plan_id: None,
+            instance_id: None,
+            timestamp: None,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        // Serializing a struct of strings can't fail
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn plan_id(&self) -> &str {
+        self.plan_id.as_deref().unwrap_or(LEGACY_PLAN_ID)
+    }
+}
+
+/// Whether ClickHouse writes are paused, shared by the sync tasks of an instance.
+///
+/// Writes stay paused while any migration is in progress.
+#[derive(Clone)]
+pub struct WritePause {
+    /// Plan ids of the migrations in progress
+    active: Arc<Mutex<HashSet<String>>>,
+    paused: Arc<watch::Sender<bool>>,
+}
+
+impl Default for WritePause {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WritePause {
+    pub fn new() -> Self {
+        Self {
+            active: Arc::new(Mutex::new(HashSet::new())),
+            paused: Arc::new(watch::channel(false).0),
+        }
+    }
+
+    /// The pause state of this process.
+    pub fn global() -> &'static WritePause {
+        &GLOBAL
+    }
+
+    /// Changes to the pause state, `true` while paused.
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.paused.subscribe()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Pauses writes until [`WritePause::resume`] is called for `plan_id`, or
+    /// for at most `timeout`.
+    pub fn pause(&self, plan_id: &str, timeout: Duration) {
+        if !self.update(|active| active.insert(plan_id.to_string())) {
+            return;
+        }
+        info!(
+            "<WritePause> Pausing ClickHouse writes during migration {}",
+            plan_id
+        );
+
+        let pause = self.clone();
+        let plan_id = plan_id.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            if pause.update(|active| active.remove(&plan_id)) {
+                warn!(
+                    "<WritePause> Migration {} did not end within {}s, no longer pausing ClickHouse writes for it",
+                    plan_id,
+                    timeout.as_secs()
+                );
+            }
+        });
+    }
+
+    /// Ends the pause for `plan_id`. Writes resume once no other migration is in progress.
+    pub fn resume(&self, plan_id: &str) {
+        if self.update(|active| active.remove(plan_id)) {
+            info!(
+                "<WritePause> Migration {} ended{}",
+                plan_id,
+                if self.is_paused() {
+                    ", another one is still in progress"
+                } else {
+                    ", resuming ClickHouse writes"
+                }
+            );
+        }
+    }
+
+    /// Pauses or resumes for `message`, received by the instance `instance_id`.
+    ///
+    /// Messages sent by this instance are ignored, it is the one migrating.
+    /// Legacy markers don't say who sent them: the leader, which used to be
+    /// the only instance migrating, ignores them. Returns whether the message
+    /// was applied.
+    pub fn handle_message(
+        &self,
+        message: &MigrationMessage,
+        instance_id: &str,
+        is_leader: bool,
+    ) -> bool {
+        let from_self = match &message.instance_id {
+            Some(sender) => sender == instance_id,
+            None => is_leader,
+        };
+        if from_self {
+            return false;
+        }
+
+        match message.phase {
+            MigrationPhase::MigrationStart => self.pause(message.plan_id(), PAUSE_TIMEOUT),
+            MigrationPhase::MigrationEnd => self.resume(message.plan_id()),
+        }
+        true
+    }
+
+    /// Applies `change` to the migrations in progress, returning what it returned.
+    fn update(&self, change: impl FnOnce(&mut HashSet<String>) -> bool) -> bool {
+        let mut active = self
+            .active
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let changed = change(&mut active);
+        let now_paused = !active.is_empty();
+        self.paused.send_if_modified(|paused| {
+            let modified = *paused != now_paused;
+            *paused = now_paused;
+            modified
+        });
+        changed
+    }
+}
+
+/// Waits until `paused` turns `false`. Returns immediately when not paused.
+pub async fn wait_until_resumed(paused: &mut watch::Receiver<bool>) {
+    while *paused.borrow_and_update() {
+        if paused.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_messages() {
+        let message = MigrationMessage::new(MigrationPhase::MigrationStart, "plan-1", "instance-a");
+        let json = message.to_json();
+        assert!(json.contains(r#""type":"migration_start""#), "{json}");
+        assert_eq!(MigrationMessage::parse(&json), Some(message));
+
+        let legacy = MigrationMessage::parse("<migration_end>").unwrap();
+        assert_eq!(legacy.phase, MigrationPhase::MigrationEnd);
+        assert_eq!(legacy.instance_id, None);
+        assert_eq!(legacy.plan_id(), LEGACY_PLAN_ID);
+
+        assert_eq!(MigrationMessage::parse("leader.new"), None);
+        assert_eq!(MigrationMessage::parse(r#"{"type":"other"}"#), None);
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume() {
+        let pause = WritePause::new();
+        let mut paused = pause.subscribe();
+
+        pause.pause("plan-1", PAUSE_TIMEOUT);
+        pause.pause("plan-2", PAUSE_TIMEOUT);
+        assert!(*paused.borrow_and_update());
+
+        // Still paused while plan-2 is running
+        pause.resume("plan-1");
+        assert!(pause.is_paused());
+        pause.resume("plan-2");
+        assert!(!pause.is_paused());
+        wait_until_resumed(&mut paused).await;
+    }
+
+    #[tokio::test]
+    async fn test_pause_times_out() {
+        let pause = WritePause::new();
+        pause.pause("plan-1", Duration::from_millis(20));
+        assert!(pause.is_paused());
+
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            wait_until_resumed(&mut pause.subscribe()),
+        )
+        .await
+        .expect("the pause should have timed out");
+        assert!(!pause.is_paused());
+    }
+
+    #[test]
+    fn test_legacy_markers_ignored_by_leader() {
+        let legacy = MigrationMessage::parse("<migration_start>").unwrap();
+
+        let leader = WritePause::new();
+        assert!(!leader.handle_message(&legacy, "leader", true));
+        assert!(!leader.is_paused());
+    }
+
+    /// Two instances on one channel: `a` migrates while `b` keeps writing
+    /// records from its topic, and none of `b`'s writes may land between the
+    /// start and the end of the migration.
+    #[tokio::test]
+    async fn test_two_instances() {
+        let instances = [("a", WritePause::new()), ("b", WritePause::new())];
+        let broadcast = |message: &MigrationMessage| {
+            let json = message.to_json();
+            for (instance_id, pause) in &instances {
+                let received = MigrationMessage::parse(&json).unwrap();
+                // Both instances consider `a` the leader
+                pause.handle_message(&received, instance_id, *instance_id == "a");
+            }
+        };
+        let b_pause = instances[1].1.clone();
+
+        // Instance b's sync task
+        let (records_tx, mut records_rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let written_clone = written.clone();
+        let mut paused = b_pause.subscribe();
+        let sync_task = tokio::spawn(async move {
+            while let Some(record) = records_rx.recv().await {
+                wait_until_resumed(&mut paused).await;
+                written_clone.lock().unwrap().push(record);
+            }
+        });
+        let written_count = || written.lock().unwrap().len();
+
+        records_tx.send(1).unwrap();
+        wait_for(|| written_count() == 1).await;
+
+        let plan_id = "plan-1";
+        broadcast(&MigrationMessage::new(
+            MigrationPhase::MigrationStart,
+            plan_id,
+            "a",
+        ));
+        assert!(
+            !instances[0].1.is_paused(),
+            "the migrating instance ignores its own message"
+        );
+        assert!(b_pause.is_paused());
+
+        records_tx.send(2).unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(written_count(), 1, "no write during the migration");
+
+        broadcast(&MigrationMessage::new(
+            MigrationPhase::MigrationEnd,
+            plan_id,
+            "a",
+        ));
+        assert!(!b_pause.is_paused());
+        wait_for(|| written_count() == 2).await;
+
+        drop(records_tx);
+        sync_task.await.unwrap();
+        assert_eq!(*written.lock().unwrap(), vec![1, 2]);
+    }
+
+    async fn wait_for(condition: impl Fn() -> bool) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !condition() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("condition not met in time");
+    }
+}
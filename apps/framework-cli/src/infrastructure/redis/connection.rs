@@ -1,11 +1,25 @@
 use redis::aio::ConnectionManager;
 use redis::{Client, RedisError};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use tokio::time;
 
+use super::connection_info::{connection_info, RedisConnectionError};
+use super::health::{reconnect_with_backoff, ConnectionHealth};
 use super::redis_client::RedisConfig;
+use crate::utilities::retry::{self, RetryPolicy, RetryTarget};
+
+/// Reconnection backoff unless `[retries.redis]` is configured: 5s doubling
+/// up to 60s, without limit. The jitter keeps instances that lost Redis
+/// together from reconnecting all at once.
+pub(super) const DEFAULT_RECONNECT_POLICY: RetryPolicy = RetryPolicy {
+    max_attempts: None,
+    base_delay: Duration::from_secs(5),
+    max_delay: Duration::from_secs(60),
+    jitter: 0.2,
+    max_elapsed: None,
+};
 
 /// Represents the possible states of a Redis connection.
 ///
@@ -26,16 +40,26 @@ pub enum ConnectionState {
 /// This struct provides a wrapper around the Redis ConnectionManager that
 /// ensures thread safety and adds features like connection health
 /// monitoring and automatic reconnection with exponential backoff.
+///
+/// Clones share the underlying connections, so a reconnection performed by
+/// the connection monitor (possibly to a new master after a Sentinel
+/// failover) is picked up by every holder of the wrapper.
 #[derive(Clone)]
 pub struct ConnectionManagerWrapper {
+    connections: Arc<RwLock<Connections>>,
+    /// Atomic flag indicating the connection state.
+    pub state: Arc<AtomicBool>, // true = connected
+    /// Failures and reconnections, for metrics and `/health`
+    health: ConnectionHealth,
+}
+
+struct Connections {
     /// The main connection used for regular Redis commands.
-    pub connection: ConnectionManager,
+    connection: ConnectionManager,
     /// A separate connection dedicated to pub/sub operations.
-    pub pub_sub: ConnectionManager,
+    pub_sub: ConnectionManager,
     /// Client used to create new connections
-    client: Arc<Client>,
-    /// Atomic flag indicating the connection state.
-    pub state: Arc<AtomicBool>, // true = connected
+    client: Client,
 }
 
 impl ConnectionManagerWrapper {
@@ -68,13 +92,68 @@ impl ConnectionManagerWrapper {
         let pub_sub_conn = Self::create_connection_with_retry(client).await?;
 
         Ok(ConnectionManagerWrapper {
-            connection: conn,
-            pub_sub: pub_sub_conn,
-            client: Arc::new(client.clone()),
+            connections: Arc::new(RwLock::new(Connections {
+                connection: conn,
+                pub_sub: pub_sub_conn,
+                client: client.clone(),
+            })),
             state: Arc::new(AtomicBool::new(true)),
+            health: ConnectionHealth::new(),
         })
     }
 
+    /// Failures and reconnections of the connection.
+    pub fn health(&self) -> &ConnectionHealth {
+        &self.health
+    }
+
+    /// The main connection used for regular Redis commands.
+    pub fn connection(&self) -> ConnectionManager {
+        self.read().connection.clone()
+    }
+
+    /// The connection dedicated to pub/sub operations.
+    pub fn pub_sub(&self) -> ConnectionManager {
+        self.read().pub_sub.clone()
+    }
+
+    /// Whether the connections point at the server `url` refers to.
+    pub fn is_connected_to(&self, config: &RedisConfig, url: &str) -> bool {
+        match connection_info(config, url) {
+            Ok(info) => self.read().client.get_connection_info().addr == info.addr,
+            Err(_) => false,
+        }
+    }
+
+    /// Whether the sentinels now report another master than the one connected to.
+    ///
+    /// A demoted master keeps answering PING as a replica, so a failover is
+    /// not noticed by [`Self::ping`]. Always false without `sentinel`.
+    pub async fn master_moved(&self, config: &RedisConfig) -> bool {
+        if config.sentinel.is_none() {
+            return false;
+        }
+        match config.resolve_url().await {
+            Ok(url) => !self.is_connected_to(config, &url),
+            Err(e) => {
+                tracing::warn!("<RedisConnection> Failed to check the Redis master: {}", e);
+                false
+            }
+        }
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Connections> {
+        self.connections
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, Connections> {
+        self.connections
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// Creates a new ConnectionManager with retry logic.
     ///
     /// This helper method attempts to create a new connection with retry logic
@@ -129,39 +208,38 @@ impl ConnectionManagerWrapper {
         }))
     }
 
-    /// Gets a fresh connection, either by cloning the existing one or creating a new one.
+    /// A single connection attempt, for reconnections: the retries are
+    /// spaced out by the reconnection backoff.
+    async fn create_connection(client: &Client) -> Result<ConnectionManager, RedisError> {
+        match time::timeout(Duration::from_secs(5), client.get_connection_manager()).await {
+            Ok(result) => result,
+            Err(_) => Err(RedisError::from(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "Timeout creating Redis connection",
+            ))),
+        }
+    }
+
+    /// Gets the connection for an operation.
     ///
-    /// This method is used to get a connection for operations, with built-in
-    /// recovery if the connection state is not healthy.
+    /// While disconnected this is the existing connection, whose commands
+    /// fail until the connection monitor has reconnected. Connecting here
+    /// would retry on every operation, ignoring the reconnection backoff.
     ///
     /// # Returns
     ///
     /// - `ConnectionManager` - A Redis connection manager ready for operations
     pub async fn get_connection(&self) -> ConnectionManager {
-        if self.state.load(Ordering::SeqCst) {
-            self.connection.clone()
-        } else {
-            match Self::create_connection_with_retry(&self.client).await {
-                Ok(conn) => conn,
-                Err(_) => self.connection.clone(), // Fall back to existing connection
-            }
-        }
+        self.connection()
     }
 
-    /// Gets a fresh pub_sub connection, either by cloning the existing one or creating a new one.
+    /// Gets the pub_sub connection, see [`Self::get_connection`].
     ///
     /// # Returns
     ///
     /// - `ConnectionManager` - A Redis connection manager for pub/sub operations
     pub async fn get_pubsub_connection(&self) -> ConnectionManager {
-        if self.state.load(Ordering::SeqCst) {
-            self.pub_sub.clone()
-        } else {
-            match Self::create_connection_with_retry(&self.client).await {
-                Ok(conn) => conn,
-                Err(_) => self.pub_sub.clone(), // Fall back to existing connection
-            }
-        }
+        self.pub_sub()
     }
 
     /// Checks the health of the Redis connection by sending a PING command.
@@ -176,37 +254,40 @@ impl ConnectionManagerWrapper {
     /// - `bool` - true if the connection is healthy, false otherwise
     pub async fn ping(&mut self) -> bool {
         // Get a fresh connection by cloning the existing one
-        let mut conn = self.connection.clone();
+        let mut conn = self.connection();
 
         // Try to ping with connection using cmd() method
         let cmd = redis::cmd("PING");
         let ping_future = cmd.query_async::<String>(&mut conn);
         let timeout_future = time::timeout(Duration::from_secs(2), ping_future);
 
-        match timeout_future.await {
-            Ok(Ok(_response)) => true,
-            Ok(Err(e)) => {
-                tracing::warn!("<RedisConnection> Redis ping failed: {:?}", e);
-                self.state.store(false, Ordering::SeqCst);
-                false
-            }
-            Err(e) => {
-                tracing::warn!("<RedisConnection> Redis ping timed out: {:?}", e);
-                self.state.store(false, Ordering::SeqCst);
-                false
-            }
+        let error = match timeout_future.await {
+            Ok(Ok(_response)) => return true,
+            Ok(Err(e)) => format!("ping failed: {e}"),
+            Err(_) => "ping timed out".to_string(),
+        };
+        let now = time::Instant::now();
+        if self.health.should_warn(now) {
+            tracing::warn!("<RedisConnection> Redis {}", error);
         }
+        self.health.record_failure(error, now);
+        self.state.store(false, Ordering::SeqCst);
+        false
     }
 
     /// Attempts to reconnect to Redis with exponential backoff.
     ///
     /// This method is called when a connection failure is detected. It
-    /// attempts to reestablish the connection with an exponential backoff
-    /// strategy:
+    /// attempts to reestablish the connection with the `redis` retry policy,
+    /// which by default:
     ///
-    /// 1. Initial backoff of 5 seconds
+    /// 1. Waits about 5 seconds before the first attempt
     /// 2. Doubles the backoff time after each failed attempt
-    /// 3. Caps the maximum backoff at 60 seconds
+    /// 3. Caps the maximum backoff at 60 seconds, shortened by up to 20% jitter
+    /// 4. Keeps trying until the connection is back
+    ///
+    /// With `redis_config.sentinel` set, the sentinels are asked for the
+    /// current master before every attempt, so a failover is followed.
     ///
     /// Once a connection is successfully established, it updates the
     /// connection state to connected (true) and returns.
@@ -214,60 +295,44 @@ impl ConnectionManagerWrapper {
     /// # Parameters
     ///
     /// - `config` - A reference to the Redis configuration
-    pub async fn attempt_reconnection(&mut self, config: &RedisConfig) {
-        let mut backoff = 5;
-        while !self.state.load(Ordering::SeqCst) {
-            tracing::info!(
-                "<RedisConnection> Attempting to reconnect to Redis at {} (backoff: {}s)",
-                config.effective_url(),
-                backoff
-            );
-            time::sleep(Duration::from_secs(backoff)).await;
-
-            // Attempt to create a new client and connections
-            let client_result = Client::open(config.effective_url());
-            match client_result {
-                Ok(client) => {
-                    // Try to create both connections
-                    match Self::create_connection_with_retry(&client).await {
-                        Ok(new_conn) => {
-                            self.connection = new_conn;
-
-                            // Try to get a new pub_sub connection as well
-                            match Self::create_connection_with_retry(&client).await {
-                                Ok(new_pubsub) => {
-                                    self.pub_sub = new_pubsub;
-                                    // Store the new client for future connection creation
-                                    self.client = Arc::new(client);
-                                    tracing::info!("<RedisConnection> Successfully reconnected both Redis connections");
-                                }
-                                Err(e) => {
-                                    tracing::warn!("<RedisConnection> Reconnected main connection but failed to reconnect pub_sub: {}", e);
-                                    // Still mark as reconnected since the main connection succeeded
-                                }
-                            }
-
-                            self.state.store(true, Ordering::SeqCst);
-                            break;
-                        }
-                        Err(err) => {
-                            tracing::warn!(
-                                "<RedisConnection> Failed to reconnect to Redis: {}",
-                                err
-                            );
-                            backoff = std::cmp::min(backoff * 2, 60);
-                        }
-                    }
-                }
-                Err(err) => {
-                    tracing::warn!(
-                        "<RedisConnection> Failed to create Redis client for reconnection: {}",
-                        err
-                    );
-                    backoff = std::cmp::min(backoff * 2, 60);
-                }
+    pub async fn attempt_reconnection(&self, config: &RedisConfig) {
+        if self.state.load(Ordering::SeqCst) {
+            return;
+        }
+        tracing::info!(
+            "<RedisConnection> Reconnecting to Redis at {}",
+            config.target_description()
+        );
+
+        let policy = retry::policy(RetryTarget::Redis, DEFAULT_RECONNECT_POLICY);
+        let reconnected = reconnect_with_backoff(&policy, &self.health, || async {
+            let client = config.open_client().await.map_err(|e| format!("{e:#}"))?;
+            let connection = Self::create_connection(&client).await.map_err(|e| {
+                RedisConnectionError::new(config.target_description(), e).to_string()
+            })?;
+            Ok::<_, String>((client, connection))
+        })
+        .await;
+
+        let Some((client, connection)) = reconnected else {
+            return;
+        };
+        let pub_sub = Self::create_connection(&client).await;
+        {
+            let mut connections = self.write();
+            connections.connection = connection;
+            // Store the new client for future connection creation
+            connections.client = client;
+            match pub_sub {
+                Ok(pub_sub) => connections.pub_sub = pub_sub,
+                // Still reconnected since the main connection succeeded
+                Err(e) => tracing::warn!(
+                    "<RedisConnection> Reconnected main connection but failed to reconnect pub_sub: {}",
+                    e
+                ),
             }
         }
+        self.state.store(true, Ordering::SeqCst);
     }
 
     /// Gracefully shuts down Redis connections by sending QUIT commands.
@@ -278,8 +343,8 @@ impl ConnectionManagerWrapper {
         tracing::info!("<RedisConnection> Shutting down Redis connections");
 
         // Send QUIT command to both connection managers
-        let mut conn = self.connection.clone();
-        let mut pub_sub = self.pub_sub.clone();
+        let mut conn = self.connection();
+        let mut pub_sub = self.pub_sub();
 
         let _ = redis::cmd("QUIT").query_async::<()>(&mut conn).await;
         let _ = redis::cmd("QUIT").query_async::<()>(&mut pub_sub).await;
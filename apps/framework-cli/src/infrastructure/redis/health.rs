@@ -0,0 +1,336 @@
+//! Health of the Redis connection.
+//!
+//! The connection monitor reports every failure and reconnection here. This
+//! feeds the `moose_redis_connected` and `moose_redis_reconnect_count`
+//! metrics and the `redis` section of `/health`, and opens a circuit once
+//! Redis has been unreachable for [`CIRCUIT_OPEN_AFTER`]: features needing
+//! leadership are then skipped with a warning per
+//! [`UNAVAILABLE_WARNING_INTERVAL`] instead of failing on every tick.
+
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::gauge::Gauge;
+use serde::Serialize;
+use tokio::time::{self, Instant};
+
+use crate::utilities::retry::RetryPolicy;
+
+/// How long Redis must be unreachable before the circuit opens.
+pub const CIRCUIT_OPEN_AFTER: Duration = Duration::from_secs(30);
+
+/// Minimum time between two warnings about Redis being unavailable.
+pub const UNAVAILABLE_WARNING_INTERVAL: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    /// 1 while connected to Redis, 0 otherwise.
+    pub static ref REDIS_CONNECTED: Gauge = {
+        let gauge = Gauge::default();
+        gauge.set(1);
+        gauge
+    };
+    /// Reconnections to Redis since startup.
+    pub static ref REDIS_RECONNECT_COUNT: Counter = Counter::default();
+}
+
+/// Whether features depending on Redis should run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Redis is reachable, or hasn't been unreachable for long
+    Closed,
+    /// Redis has been unreachable for [`CIRCUIT_OPEN_AFTER`] or more
+    Open,
+}
+
+/// The connection health at one point in time, as shown by `/health`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthSnapshot {
+    pub connected: bool,
+    pub reconnect_count: u64,
+    pub last_error: Option<String>,
+    pub circuit: CircuitState,
+    /// Seconds since the connection was lost, while disconnected
+    pub disconnected_for_secs: Option<u64>,
+}
+
+/// Connection health shared by the clones of a connection manager.
+#[derive(Clone, Default)]
+pub struct ConnectionHealth {
+    state: Arc<Mutex<HealthState>>,
+}
+
+#[derive(Default)]
+struct HealthState {
+    disconnected_since: Option<Instant>,
+    last_error: Option<String>,
+    reconnect_count: u64,
+    last_warning: Option<Instant>,
+}
+
+impl ConnectionHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a failed ping or reconnection attempt.
+    pub fn record_failure(&self, error: impl Display, now: Instant) {
+        let mut state = self.lock();
+        state.disconnected_since.get_or_insert(now);
+        state.last_error = Some(error.to_string());
+        REDIS_CONNECTED.set(0);
+    }
+
+    /// Records that the connection is back after a failure.
+    pub fn record_reconnected(&self) {
+        let mut state = self.lock();
+        if state.disconnected_since.take().is_some() {
+            state.reconnect_count += 1;
+            state.last_warning = None;
+            REDIS_RECONNECT_COUNT.inc();
+        }
+        REDIS_CONNECTED.set(1);
+    }
+
+    pub fn circuit(&self, now: Instant) -> CircuitState {
+        match self.lock().disconnected_since {
+            Some(since) if now.duration_since(since) >= CIRCUIT_OPEN_AFTER => CircuitState::Open,
+            _ => CircuitState::Closed,
+        }
+    }
+
+    /// Whether to warn about Redis being unavailable: at most once per
+    /// [`UNAVAILABLE_WARNING_INTERVAL`].
+    pub fn should_warn(&self, now: Instant) -> bool {
+        let mut state = self.lock();
+        match state.last_warning {
+            Some(last) if now.duration_since(last) < UNAVAILABLE_WARNING_INTERVAL => false,
+            _ => {
+                state.last_warning = Some(now);
+                true
+            }
+        }
+    }
+
+    pub fn snapshot(&self, now: Instant) -> HealthSnapshot {
+        let circuit = self.circuit(now);
+        let state = self.lock();
+        HealthSnapshot {
+            connected: state.disconnected_since.is_none(),
+            reconnect_count: state.reconnect_count,
+            last_error: state.last_error.clone(),
+            circuit,
+            disconnected_for_secs: state
+                .disconnected_since
+                .map(|since| now.duration_since(since).as_secs()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HealthState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Calls `connect` until it succeeds, waiting `policy` delays (jitter
+/// included) before each attempt. Returns `None` once `policy` allows no
+/// more attempts.
+///
+/// Every failure is recorded in `health`, and only logged when a warning is
+/// due, so an outage doesn't flood the logs.
+pub async fn reconnect_with_backoff<T, E, F, Fut>(
+    policy: &RetryPolicy,
+    health: &ConnectionHealth,
+    mut connect: F,
+) -> Option<T>
+where
+    E: Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let start = Instant::now();
+    let mut failures = 0;
+    loop {
+        let backoff = policy.delay(failures);
+        tracing::debug!(
+            "<RedisConnection> Reconnecting to Redis in {:?} (attempt {})",
+            backoff,
+            failures + 1
+        );
+        time::sleep(backoff).await;
+
+        match connect().await {
+            Ok(connection) => {
+                health.record_reconnected();
+                tracing::info!(
+                    "<RedisConnection> Reconnected to Redis after {} failed attempts",
+                    failures
+                );
+                return Some(connection);
+            }
+            Err(e) => {
+                let now = Instant::now();
+                if health.should_warn(now) {
+                    tracing::warn!(
+                        "<RedisConnection> Redis still unavailable after {} attempts: {}",
+                        failures + 1,
+                        e
+                    );
+                }
+                health.record_failure(e, now);
+            }
+        }
+
+        failures += 1;
+        if !policy.allows_retry(failures, start.elapsed()) {
+            tracing::error!(
+                "<RedisConnection> Giving up reconnecting to Redis after {} attempts",
+                failures
+            );
+            return None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: RetryPolicy = RetryPolicy {
+        max_attempts: None,
+        base_delay: Duration::from_secs(5),
+        max_delay: Duration::from_secs(60),
+        jitter: 0.0,
+        max_elapsed: None,
+    };
+
+    /// A connection failing `failures` times, then connecting.
+    fn flaky_connection(
+        failures: u32,
+        attempts: Arc<Mutex<Vec<Instant>>>,
+    ) -> impl FnMut() -> std::future::Ready<Result<&'static str, String>> {
+        move || {
+            let mut attempts = attempts.lock().unwrap();
+            attempts.push(Instant::now());
+            std::future::ready(if attempts.len() as u32 <= failures {
+                Err(format!("connection refused ({})", attempts.len()))
+            } else {
+                Ok("connected")
+            })
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reconnect_backoff_and_counters() {
+        let health = ConnectionHealth::new();
+        let start = Instant::now();
+        health.record_failure("ping timed out", start);
+        let reconnects_before = REDIS_RECONNECT_COUNT.get();
+
+        let attempts = Arc::new(Mutex::new(Vec::new()));
+        let connection =
+            reconnect_with_backoff(&POLICY, &health, flaky_connection(4, attempts.clone())).await;
+        assert_eq!(connection, Some("connected"));
+
+        // 5s, doubling up to the 60s cap
+        let offsets: Vec<u64> = attempts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|at| at.duration_since(start).as_secs())
+            .collect();
+        assert_eq!(offsets, vec![5, 15, 35, 75, 135]);
+
+        let snapshot = health.snapshot(Instant::now());
+        assert!(snapshot.connected);
+        assert_eq!(snapshot.reconnect_count, 1);
+        assert_eq!(snapshot.circuit, CircuitState::Closed);
+        assert_eq!(
+            snapshot.last_error.as_deref(),
+            Some("connection refused (4)")
+        );
+        assert!(REDIS_RECONNECT_COUNT.get() > reconnects_before);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_jittered_backoff_stays_within_bounds() {
+        let policy = RetryPolicy {
+            jitter: 0.5,
+            ..POLICY
+        };
+        let attempts = Arc::new(Mutex::new(Vec::new()));
+        let start = Instant::now();
+        reconnect_with_backoff(
+            &policy,
+            &ConnectionHealth::new(),
+            flaky_connection(2, attempts.clone()),
+        )
+        .await
+        .unwrap();
+
+        let attempts = attempts.lock().unwrap();
+        let mut previous = start;
+        for (retry, at) in attempts.iter().enumerate() {
+            let waited = at.duration_since(previous);
+            let backoff = POLICY.backoff(retry as u32);
+            assert!(
+                waited >= backoff / 2 && waited <= backoff,
+                "{waited:?} for {backoff:?}"
+            );
+            previous = *at;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_gives_up_with_limited_policy() {
+        let policy = RetryPolicy {
+            max_attempts: Some(3),
+            ..POLICY
+        };
+        let health = ConnectionHealth::new();
+        let attempts = Arc::new(Mutex::new(Vec::new()));
+        let connection =
+            reconnect_with_backoff(&policy, &health, flaky_connection(10, attempts.clone())).await;
+        assert_eq!(connection, None);
+        assert_eq!(attempts.lock().unwrap().len(), 3);
+
+        let snapshot = health.snapshot(Instant::now());
+        assert!(!snapshot.connected);
+        assert_eq!(snapshot.reconnect_count, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_circuit_opens_and_warnings_are_throttled() {
+        let health = ConnectionHealth::new();
+        let start = Instant::now();
+        assert_eq!(health.circuit(start), CircuitState::Closed);
+
+        // Failures every 5 seconds, as the connection monitor reports them
+        for tick in 0..=6 {
+            health.record_failure("connection refused", start + Duration::from_secs(tick * 5));
+        }
+        assert_eq!(
+            health.circuit(start + Duration::from_secs(29)),
+            CircuitState::Closed
+        );
+        let opened = start + CIRCUIT_OPEN_AFTER;
+        assert_eq!(health.circuit(opened), CircuitState::Open);
+        assert_eq!(health.snapshot(opened).disconnected_for_secs, Some(30));
+
+        assert!(health.should_warn(opened));
+        assert!(!health.should_warn(opened + Duration::from_secs(5)));
+        assert!(!health.should_warn(opened + Duration::from_secs(59)));
+        assert!(health.should_warn(opened + UNAVAILABLE_WARNING_INTERVAL));
+
+        health.record_reconnected();
+        assert_eq!(
+            health.circuit(opened + Duration::from_secs(90)),
+            CircuitState::Closed
+        );
+    }
+}
@@ -1,4 +1,10 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use redis::aio::ConnectionManager;
+use redis::RedisError;
+use tokio::time::{Interval, MissedTickBehavior};
 
 use super::redis_client::RedisConfig;
 
@@ -6,12 +12,136 @@ use super::redis_client::RedisConfig;
 ///
 /// This struct provides methods to acquire, verify, renew, and release
 /// distributed locks across multiple service instances using Redis.
+///
+/// Every new acquisition of a lock increments a counter stored next to it
+/// (`<lock key>::fencing`). The value is the fencing token of the holder:
+/// an operation guarded by the lock presents it through
+/// [`LeadershipManager::check_and_renew_with_token`] and aborts when a newer
+/// token was issued, which happens when this instance stalled past the TTL
+/// and another one took the lock over.
 #[derive(Clone)]
 pub struct LeadershipManager {
     /// Unique identifier for this service instance.
     pub instance_id: String,
     /// Redis configuration for connecting to the Redis server.
     pub redis_config: RedisConfig,
+    /// Fencing tokens of the locks held by this instance, by lock key.
+    tokens: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+/// A lock-guarded operation that must not go on.
+#[derive(Debug, thiserror::Error)]
+pub enum FencingError {
+    #[error("Lock '{lock_key}' was taken over by another instance (fencing token {current}, ours is {token})")]
+    Superseded {
+        lock_key: String,
+        token: u64,
+        current: u64,
+    },
+
+    #[error("Lock '{lock_key}' is no longer held by this instance (fencing token {token})")]
+    LockLost { lock_key: String, token: u64 },
+
+    #[error("Failed to check the fencing token of lock '{lock_key}': {source}")]
+    Redis {
+        lock_key: String,
+        #[source]
+        source: RedisError,
+    },
+}
+
+/// More acquisitions than this within [`FLAP_WINDOW`] means leadership is flapping.
+pub const FLAP_THRESHOLD: usize = 5;
+pub const FLAP_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Ticks every `period` for lock renewals, the first tick being immediate.
+///
+/// A renewal that runs late pushes the next ones back instead of being
+/// followed by a burst of catch-up renewals.
+pub fn renewal_interval(period: Duration) -> Interval {
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval
+}
+
+/// Counts the lock acquisitions of this instance to notice flapping: the
+/// lock repeatedly expiring and being taken again.
+#[derive(Debug)]
+pub struct FlapDetector {
+    threshold: usize,
+    window: Duration,
+    acquisitions: VecDeque<Instant>,
+}
+
+impl Default for FlapDetector {
+    fn default() -> Self {
+        Self::new(FLAP_THRESHOLD, FLAP_WINDOW)
+    }
+}
+
+impl FlapDetector {
+    pub fn new(threshold: usize, window: Duration) -> Self {
+        Self {
+            threshold,
+            window,
+            acquisitions: VecDeque::new(),
+        }
+    }
+
+    /// Records an acquisition at `now`. Returns the number of acquisitions
+    /// within the window once it exceeds the threshold, then starts counting
+    /// afresh so that the warning isn't repeated on every acquisition.
+    pub fn record(&mut self, now: Instant) -> Option<usize> {
+        while self
+            .acquisitions
+            .front()
+            .is_some_and(|first| now.duration_since(*first) > self.window)
+        {
+            self.acquisitions.pop_front();
+        }
+        self.acquisitions.push_back(now);
+
+        if self.acquisitions.len() > self.threshold {
+            let count = self.acquisitions.len();
+            self.acquisitions.clear();
+            Some(count)
+        } else {
+            None
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+}
+
+/// Key of the fencing token counter of `lock_key`.
+fn fencing_key(lock_key: &str) -> String {
+    format!("{lock_key}::fencing")
+}
+
+/// Outcome of a token check: `renewed` when the lock is still ours with
+/// `token`, `current` being the last token issued.
+fn fencing_verdict(
+    lock_key: &str,
+    token: u64,
+    renewed: bool,
+    current: u64,
+) -> Result<(), FencingError> {
+    if renewed {
+        Ok(())
+    } else if current > token {
+        Err(FencingError::Superseded {
+            lock_key: lock_key.to_string(),
+            token,
+            current,
+        })
+    } else {
+        Err(FencingError::LockLost {
+            lock_key: lock_key.to_string(),
+            token,
+        })
+    }
 }
 
 impl LeadershipManager {
@@ -30,9 +160,21 @@ impl LeadershipManager {
         LeadershipManager {
             instance_id,
             redis_config: config,
+            tokens: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// Fencing token of `lock_key`, when this instance holds it.
+    pub fn fencing_token(&self, lock_key: &str) -> Option<u64> {
+        self.lock_tokens().get(lock_key).copied()
+    }
+
+    fn lock_tokens(&self) -> std::sync::MutexGuard<'_, HashMap<String, u64>> {
+        self.tokens
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// Attempts to acquire a distributed lock.
     ///
     /// This method tries to acquire a lock with the following logic:
@@ -40,7 +182,9 @@ impl LeadershipManager {
     /// - If the lock exists and is owned by this instance, the TTL is refreshed
     /// - If the lock exists and is owned by another instance, acquisition fails
     ///
-    /// The operation is performed atomically using a Lua script.
+    /// A new acquisition issues the next fencing token, see
+    /// [`LeadershipManager::fencing_token`]. The operation is performed
+    /// atomically using a Lua script.
     ///
     /// # Parameters
     ///
@@ -65,36 +209,43 @@ impl LeadershipManager {
             if not current then
                 redis.call('SET', KEYS[1], ARGV[1])
                 redis.call('EXPIRE', KEYS[1], ARGV[2])
-                return 2  -- New acquisition
-            elseif current == ARGV[1] then
+                return {2, redis.call('INCR', KEYS[2])}  -- New acquisition
+            end
+            local token = tonumber(redis.call('GET', KEYS[2]) or '0')
+            if current == ARGV[1] then
                 redis.call('EXPIRE', KEYS[1], ARGV[2])
-                return 1  -- Renewal
+                return {1, token}  -- Renewal
             else
-                return 0  -- Failed to acquire
+                return {0, token}  -- Failed to acquire
             end
             "#,
         );
 
         match script
             .key(lock_key)
+            .key(fencing_key(lock_key))
             .arg(instance_id)
             .arg(ttl)
-            .invoke_async::<i64>(&mut conn)
+            .invoke_async::<(i64, u64)>(&mut conn)
             .await
         {
-            Ok(2) => {
-                tracing::debug!(
-                    "<RedisLeadership> Lock acquired: {} by instance {}",
+            Ok((2, token)) => {
+                tracing::info!(
+                    "<RedisLeadership> Lock acquired: {} by instance {} with fencing token {}",
                     lock_key,
-                    instance_id
+                    instance_id,
+                    token
                 );
+                self.lock_tokens().insert(lock_key.to_string(), token);
                 (true, true) // has_lock, is_new_acquisition
             }
-            Ok(1) => {
+            Ok((1, token)) => {
                 // Don't log renewals to reduce noise
+                self.lock_tokens().insert(lock_key.to_string(), token);
                 (true, false) // has_lock and not_new_acquisition
             }
             Ok(_) => {
+                self.lock_tokens().remove(lock_key);
                 (false, false) // doesn't have lock and not new acquisition
             }
             Err(e) => {
@@ -104,6 +255,59 @@ impl LeadershipManager {
         }
     }
 
+    /// Checks that `token` is still the fencing token of `lock_key` and that
+    /// this instance holds the lock, extending its TTL if so.
+    ///
+    /// Called before each operation guarded by the lock: a leader that
+    /// stalled past the TTL finds a newer token there and must abort.
+    ///
+    /// # Errors
+    ///
+    /// - [`FencingError::Superseded`] when a newer token was issued
+    /// - [`FencingError::LockLost`] when the lock expired without being taken
+    /// - [`FencingError::Redis`] when the check itself failed
+    pub async fn check_and_renew_with_token(
+        &self,
+        mut conn: ConnectionManager,
+        lock_key: &str,
+        token: u64,
+        ttl: i64,
+    ) -> Result<(), FencingError> {
+        let script = redis::Script::new(
+            r#"
+            local current = tonumber(redis.call('GET', KEYS[2]) or '0')
+            if redis.call('GET', KEYS[1]) ~= ARGV[1] or current ~= tonumber(ARGV[3]) then
+                return {0, current}
+            end
+            redis.call('EXPIRE', KEYS[1], ARGV[2])
+            return {1, current}
+            "#,
+        );
+
+        let (renewed, current) = script
+            .key(lock_key)
+            .key(fencing_key(lock_key))
+            .arg(&self.instance_id)
+            .arg(ttl)
+            .arg(token)
+            .invoke_async::<(i64, u64)>(&mut conn)
+            .await
+            .map_err(|source| FencingError::Redis {
+                lock_key: lock_key.to_string(),
+                source,
+            })?;
+
+        let verdict = fencing_verdict(lock_key, token, renewed == 1, current);
+        if let Err(e) = &verdict {
+            tracing::warn!("<RedisLeadership> {}", e);
+            let mut tokens = self.lock_tokens();
+            if tokens.get(lock_key) == Some(&token) {
+                tokens.remove(lock_key);
+            }
+        }
+        verdict
+    }
+
     /// Renews an existing lock by extending its TTL.
     ///
     /// This method extends the TTL of a lock only if it's currently owned
@@ -120,7 +324,8 @@ impl LeadershipManager {
     /// # Returns
     ///
     /// - `anyhow::Result<bool>` - Ok(true) if the lock was renewed,
-    ///   Ok(false) if not owned by this instance
+    ///   Ok(false) if not owned by this instance, in which case its fencing
+    ///   token is forgotten
     ///
     /// # Errors
     ///
@@ -165,6 +370,7 @@ impl LeadershipManager {
                     lock_key,
                     instance_id
                 );
+                self.lock_tokens().remove(lock_key);
                 Ok(false)
             }
             Ok(_) => {
@@ -173,6 +379,7 @@ impl LeadershipManager {
                     lock_key,
                     instance_id
                 );
+                self.lock_tokens().remove(lock_key);
                 Ok(false)
             }
             Err(e) => {
@@ -215,6 +422,7 @@ impl LeadershipManager {
             .arg(instance_id)
             .invoke_async(&mut conn)
             .await?;
+        self.lock_tokens().remove(lock_key);
         Ok(())
     }
 
@@ -255,3 +463,129 @@ impl LeadershipManager {
         Ok(result == 1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fencing_verdict() {
+        assert!(fencing_verdict("MS::leadership::lock", 3, true, 3).is_ok());
+        assert!(matches!(
+            fencing_verdict("MS::leadership::lock", 3, false, 4),
+            Err(FencingError::Superseded {
+                token: 3,
+                current: 4,
+                ..
+            })
+        ));
+        assert!(matches!(
+            fencing_verdict("MS::leadership::lock", 3, false, 3),
+            Err(FencingError::LockLost { token: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_flap_detector() {
+        let start = Instant::now();
+        let mut detector = FlapDetector::new(3, Duration::from_secs(60));
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(detector.record(at(0)), None);
+        assert_eq!(detector.record(at(10)), None);
+        assert_eq!(detector.record(at(20)), None);
+        assert_eq!(detector.record(at(30)), Some(4));
+
+        // Counting restarts after a warning, and old acquisitions fall out of the window
+        assert_eq!(detector.record(at(100)), None);
+        assert_eq!(detector.record(at(200)), None);
+        assert_eq!(detector.record(at(300)), None);
+        assert_eq!(detector.record(at(400)), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_renewal_schedule() {
+        let start = tokio::time::Instant::now();
+        let mut interval = renewal_interval(Duration::from_secs(2));
+
+        interval.tick().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        interval.tick().await;
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+
+        // A renewal stalls past the next tick: renew right away, then every
+        // period from there, without catching up on the missed ticks
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        interval.tick().await;
+        assert_eq!(start.elapsed(), Duration::from_secs(7));
+        interval.tick().await;
+        assert_eq!(start.elapsed(), Duration::from_secs(9));
+    }
+
+    /// A leader stalls past the TTL, another instance takes the lock over,
+    /// and the stalled leader's operations are rejected when it resumes.
+    /// Needs a Redis on 127.0.0.1:6379.
+    #[tokio::test]
+    #[ignore] // Requires a running Redis
+    async fn test_resumed_leader_is_fenced() {
+        let config = RedisConfig::default();
+        let client = redis::Client::open(config.url.clone()).unwrap();
+        let conn = ConnectionManager::new(client).await.unwrap();
+        let lock_key = format!("fencing-test::{}::lock", uuid::Uuid::new_v4());
+
+        let old_leader = LeadershipManager::new("old".to_string(), config.clone());
+        let new_leader = LeadershipManager::new("new".to_string(), config);
+
+        assert_eq!(
+            old_leader.attempt_lock(conn.clone(), &lock_key, 1).await,
+            (true, true)
+        );
+        let old_token = old_leader.fencing_token(&lock_key).unwrap();
+        old_leader
+            .check_and_renew_with_token(conn.clone(), &lock_key, old_token, 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            new_leader.attempt_lock(conn.clone(), &lock_key, 1).await,
+            (false, false)
+        );
+
+        // The old leader stalls past the TTL
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+        assert!(matches!(
+            old_leader
+                .check_and_renew_with_token(conn.clone(), &lock_key, old_token, 1)
+                .await,
+            Err(FencingError::LockLost { .. })
+        ));
+
+        assert_eq!(
+            new_leader.attempt_lock(conn.clone(), &lock_key, 10).await,
+            (true, true)
+        );
+        let new_token = new_leader.fencing_token(&lock_key).unwrap();
+        assert!(new_token > old_token);
+
+        // Resumed, the old leader still believes it holds the lock
+        assert!(matches!(
+            old_leader
+                .check_and_renew_with_token(conn.clone(), &lock_key, old_token, 10)
+                .await,
+            Err(FencingError::Superseded { current, .. }) if current == new_token
+        ));
+        assert_eq!(old_leader.fencing_token(&lock_key), None);
+        new_leader
+            .check_and_renew_with_token(conn.clone(), &lock_key, new_token, 10)
+            .await
+            .unwrap();
+
+        new_leader
+            .release_lock(conn.clone(), &lock_key, "new")
+            .await
+            .unwrap();
+        assert_eq!(new_leader.fencing_token(&lock_key), None);
+        let _: () = redis::AsyncCommands::del(&mut conn.clone(), fencing_key(&lock_key))
+            .await
+            .unwrap();
+    }
+}
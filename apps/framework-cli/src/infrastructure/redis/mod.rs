@@ -9,16 +9,18 @@
 ///
 /// - **RedisClient**: The main entry point that coordinates all Redis operations
 /// - **ConnectionManager**: Handles connection establishment, monitoring, and recovery
-/// - **LeadershipManager**: Implements distributed locks for leader election
+/// - **Health**: Tracks connection failures and reconnections for metrics and `/health`
+/// - **LeadershipManager**: Implements distributed locks for leader election, with fencing tokens
 /// - **PresenceManager**: Maintains service instance health and discoverability
 /// - **MessagingManager**: Facilitates pub/sub and queue-based messaging
 /// - **MockRedisClient**: Provides local in-memory fallback when Redis is unavailable
+/// - **Sentinel**: Discovers the current master when Redis runs behind Redis Sentinel
 ///
 /// ## Key Features
 ///
 /// - **High Availability**: Graceful degradation when Redis is unavailable
 /// - **Thread Safety**: All components are designed for concurrent access
-/// - **Automatic Recovery**: Connection monitoring and reconnection
+/// - **Automatic Recovery**: Connection monitoring and reconnection, following Sentinel failovers
 /// - **Performance Optimized**: Using RwLock instead of Mutex where appropriate
 ///
 /// ## Usage Example
@@ -55,11 +57,14 @@
 /// - **Non-critical operations** (messaging, presence) fail gracefully with logged warnings
 /// - **Fallback mode** activates automatically when Redis is unavailable
 pub mod connection;
+pub mod connection_info;
+pub mod health;
 pub mod leadership;
 pub mod messaging;
 pub mod mock;
 pub mod presence;
 pub mod redis_client;
+pub mod sentinel;
 
 /// Re-exports the primary types for easier imports.
 ///
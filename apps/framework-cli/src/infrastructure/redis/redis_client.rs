@@ -54,30 +54,33 @@
 /// to acquire a write lock when we're adding or removing locks.
 ///
 use futures::StreamExt;
+use redis::aio::PubSub;
 use redis::{AsyncCommands, Client};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{oneshot, RwLock};
 use tokio::task::JoinHandle;
 
 use crate::cli::display::{Message, MessageType};
+use crate::utilities::retry::{self, RetryTarget};
 
 // Import the modules
-use super::connection::ConnectionManagerWrapper;
-use super::leadership::LeadershipManager;
+use super::connection::{ConnectionManagerWrapper, DEFAULT_RECONNECT_POLICY};
+use super::connection_info::{open_client, redact_url, RedisConnectionError, REDACTED};
+use super::health::ConnectionHealth;
+use super::leadership::{FencingError, LeadershipManager};
 use super::messaging::MessagingManager;
 use super::mock::MockRedisClient;
 use super::presence::PresenceManager;
+use super::sentinel::{self, SentinelConfig};
 
 // Type alias for complex callback types
 type MessageCallback = Arc<dyn Fn(String) + Send + Sync>;
 
-// Default TTL for leadership locks (15 seconds)
-const LEADERSHIP_LOCK_TTL: i64 = 15;
-
 /// Represents a distributed lock in Redis
 ///
 /// This struct encapsulates the data needed to represent a lock in Redis
@@ -101,9 +104,17 @@ pub struct RedisLock {
 /// - `url`: The Redis connection URL in the format: `redis://[username:password@]host[:port][/db]`.
 ///   Defaults to `redis://127.0.0.1:6379` (local Redis server on default port).
 ///
-/// - `key_prefix`: A namespace prefix added to all Redis keys to prevent
-///   collisions when multiple services share the same Redis instance.
-///   Defaults to `"MS"`.
+/// - `key_prefix`: A namespace prefix added to all Redis keys and channels
+///   to prevent collisions when multiple projects share the same Redis
+///   instance. Defaults to the project name, see
+///   [`RedisConfig::resolve_key_prefixes`].
+///
+/// - `sentinel`: When set, the master is discovered through Redis Sentinel
+///   and `url` is ignored. See [`SentinelConfig`].
+///
+/// - `username`, `password`, `tls_ca_path`, `tls_insecure_skip_verify`: ACL
+///   credentials and TLS settings applied on top of the URL, see
+///   [`connection_info`](super::connection_info::connection_info).
 ///
 /// ## Usage
 ///
@@ -131,14 +142,17 @@ pub struct RedisLock {
 /// The Redis configuration can be loaded from environment variables or
 /// configuration files using the `config` crate's deserialization
 /// capabilities, as the struct implements `Serialize` and `Deserialize`.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct RedisConfig {
     /// Legacy URL field for backwards compatibility
     #[serde(default = "RedisConfig::default_url")]
     pub url: String,
-    #[serde(default = "RedisConfig::default_key_prefix")]
+    /// Left out of the config, the sanitized project name
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub key_prefix: String,
-    #[serde(default = "RedisConfig::default_key_prefix")]
+    /// Prefix the infrastructure map of the previous deployment is loaded
+    /// from. Left out of the config, `key_prefix`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub last_key_prefix: String,
     #[serde(default = "RedisConfig::default_port")]
     pub port: u16,
@@ -148,6 +162,90 @@ pub struct RedisConfig {
     /// Redis hostname
     #[serde(default = "RedisConfig::default_hostname")]
     pub hostname: String,
+    /// CA certificate (PEM) to verify the server with, instead of the system roots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_ca_path: Option<PathBuf>,
+    /// Accept any server certificate. For development only.
+    #[serde(default)]
+    pub tls_insecure_skip_verify: bool,
+    /// ACL username, overriding the one in `url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Password, overriding the one in `url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Discover the master through Redis Sentinel instead of using `url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentinel: Option<SentinelConfig>,
+    /// Seconds before the leadership lock expires when it isn't renewed
+    #[serde(default = "RedisConfig::default_leadership_lock_ttl_secs")]
+    pub leadership_lock_ttl_secs: u64,
+    /// Seconds between renewals of the leadership lock
+    #[serde(default = "RedisConfig::default_leadership_renewal_interval_secs")]
+    pub leadership_renewal_interval_secs: u64,
+}
+
+/// `name` usable as a key prefix: characters other than ASCII letters,
+/// digits, `-` and `_` (such as the `@` and `/` of scoped package names, or
+/// the `:` separating key parts) become `_`.
+pub fn sanitize_key_prefix(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let sanitized = sanitized.trim_matches('_');
+    if sanitized.is_empty() {
+        RedisConfig::default_key_prefix()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+/// Leadership lock timings that would make leadership change hands on a
+/// single late renewal.
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum LeadershipConfigError {
+    #[error("redis_config.leadership_renewal_interval_secs must be at least 1")]
+    ZeroRenewalInterval,
+
+    #[error("redis_config.leadership_lock_ttl_secs ({ttl}) must be at least 3 times leadership_renewal_interval_secs ({interval})")]
+    TtlTooShort { ttl: u64, interval: u64 },
+}
+
+/// Credentials are redacted.
+impl std::fmt::Debug for RedisConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redacted = |secret: &Option<String>| secret.as_ref().map(|_| REDACTED);
+        f.debug_struct("RedisConfig")
+            .field("url", &redact_url(&self.url))
+            .field("key_prefix", &self.key_prefix)
+            .field("last_key_prefix", &self.last_key_prefix)
+            .field("port", &self.port)
+            .field("tls", &self.tls)
+            .field("hostname", &self.hostname)
+            .field("tls_ca_path", &self.tls_ca_path)
+            .field("tls_insecure_skip_verify", &self.tls_insecure_skip_verify)
+            .field("username", &self.username)
+            .field("password", &redacted(&self.password))
+            .field(
+                "sentinel",
+                &self.sentinel.as_ref().map(|sentinel| SentinelConfig {
+                    password: sentinel.password.as_ref().map(|_| REDACTED.to_string()),
+                    sentinel_password: sentinel
+                        .sentinel_password
+                        .as_ref()
+                        .map(|_| REDACTED.to_string()),
+                    ..sentinel.clone()
+                }),
+            )
+            .finish()
+    }
 }
 
 impl RedisConfig {
@@ -198,6 +296,92 @@ impl RedisConfig {
         false
     }
 
+    /// The defaults, with the keys prefixed by the sanitized `project_name`.
+    pub fn for_project(project_name: &str) -> Self {
+        let key_prefix = sanitize_key_prefix(project_name);
+        RedisConfig {
+            last_key_prefix: key_prefix.clone(),
+            key_prefix,
+            ..Default::default()
+        }
+    }
+
+    /// Fills in the prefixes left out of the config: `key_prefix` with the
+    /// sanitized `project_name`, then `last_key_prefix` with `key_prefix`.
+    pub fn resolve_key_prefixes(&mut self, project_name: &str) {
+        if self.key_prefix.is_empty() {
+            self.key_prefix = sanitize_key_prefix(project_name);
+        }
+        if self.last_key_prefix.is_empty() {
+            self.last_key_prefix = self.key_prefix.clone();
+        }
+    }
+
+    /// `<key_prefix>::<parts joined with ::>`. Every key and channel Moose
+    /// uses is built from this.
+    pub fn prefixed_key(&self, parts: &[&str]) -> String {
+        format!("{}::{}", self.key_prefix, parts.join("::"))
+    }
+
+    /// Key of the distributed lock `name`.
+    pub fn lock_key(&self, name: &str) -> String {
+        self.prefixed_key(&[name, "lock"])
+    }
+
+    /// Channel every instance listens on.
+    pub fn broadcast_channel(&self) -> String {
+        self.prefixed_key(&["msgchannel"])
+    }
+
+    /// Channel for messages to the instance `instance_id` only.
+    pub fn instance_channel(&self, instance_id: &str) -> String {
+        self.prefixed_key(&[instance_id, "msgchannel"])
+    }
+
+    /// Key refreshed while the instance `instance_id` is alive.
+    pub fn presence_key(&self, instance_id: &str) -> String {
+        self.prefixed_key(&[instance_id, "presence"])
+    }
+
+    /// Key of the message queue `queue`, per feature when `feature_name` is set.
+    pub fn queue_key(&self, feature_name: Option<&str>, queue: &str) -> String {
+        match feature_name {
+            Some(name) => self.prefixed_key(&[name, queue]),
+            None => self.prefixed_key(&[queue]),
+        }
+    }
+
+    /// Returns the default leadership lock TTL: 15 seconds.
+    pub fn default_leadership_lock_ttl_secs() -> u64 {
+        15
+    }
+
+    /// Returns the default leadership lock renewal interval: 5 seconds.
+    pub fn default_leadership_renewal_interval_secs() -> u64 {
+        5
+    }
+
+    /// Checks that the leadership lock outlives a few renewal intervals, so
+    /// that one late renewal doesn't hand leadership to another instance.
+    pub fn validate_leadership_timings(&self) -> Result<(), LeadershipConfigError> {
+        let ttl = self.leadership_lock_ttl_secs;
+        let interval = self.leadership_renewal_interval_secs;
+        if interval == 0 {
+            Err(LeadershipConfigError::ZeroRenewalInterval)
+        } else if ttl < interval.saturating_mul(3) {
+            Err(LeadershipConfigError::TtlTooShort { ttl, interval })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Whether the leadership lock timings differ from the defaults.
+    pub fn has_custom_leadership_timings(&self) -> bool {
+        self.leadership_lock_ttl_secs != Self::default_leadership_lock_ttl_secs()
+            || self.leadership_renewal_interval_secs
+                != Self::default_leadership_renewal_interval_secs()
+    }
+
     /// Shows configuration warnings for mixed Redis configuration scenarios.
     ///
     /// This displays a warning when both URL and individual fields are set,
@@ -266,6 +450,33 @@ impl RedisConfig {
             self.url.clone()
         }
     }
+
+    /// Returns the URL to connect to: the current master as reported by the
+    /// sentinels when `sentinel` is set, [`Self::effective_url`] otherwise.
+    pub async fn resolve_url(&self) -> anyhow::Result<String> {
+        match &self.sentinel {
+            Some(sentinel) => Ok(sentinel::resolve_master_url(sentinel, self.tls).await?),
+            None => Ok(self.effective_url()),
+        }
+    }
+
+    /// Opens a client for [`Self::resolve_url`] with the TLS and ACL settings applied.
+    pub async fn open_client(&self) -> anyhow::Result<Client> {
+        let url = self.resolve_url().await?;
+        Ok(open_client(self, &url)?)
+    }
+
+    /// Where the client connects to, for messages and logs. Credentials are redacted.
+    pub fn target_description(&self) -> String {
+        match &self.sentinel {
+            Some(sentinel) => format!(
+                "the master '{}' from sentinels {}",
+                sentinel.master_name,
+                sentinel.addresses.join(", ")
+            ),
+            None => redact_url(&self.effective_url()),
+        }
+    }
 }
 
 /// Implements the Default trait for RedisConfig.
@@ -282,6 +493,14 @@ impl Default for RedisConfig {
             port: RedisConfig::default_port(),
             tls: RedisConfig::default_tls(),
             hostname: RedisConfig::default_hostname(),
+            tls_ca_path: None,
+            tls_insecure_skip_verify: false,
+            username: None,
+            password: None,
+            sentinel: None,
+            leadership_lock_ttl_secs: RedisConfig::default_leadership_lock_ttl_secs(),
+            leadership_renewal_interval_secs: RedisConfig::default_leadership_renewal_interval_secs(
+            ),
         }
     }
 }
@@ -390,13 +609,18 @@ impl RedisClient {
     pub async fn new(service_name: String, config: RedisConfig) -> anyhow::Result<Self> {
         let instance_id = uuid::Uuid::new_v4().to_string();
 
-        // Create Redis client using the effective URL
-        let effective_url = config.effective_url();
-        let client_result = Client::open(effective_url.clone());
+        // Create Redis client using the effective URL, or the master's with Sentinel
+        let client_result = config.open_client().await.inspect_err(|e| {
+            tracing::warn!("<RedisClient> Failed to create the Redis client: {}", e);
+        });
         let (connection_manager, fallback) = match client_result {
             Ok(c) => match ConnectionManagerWrapper::new(&c).await {
                 Ok(cm) => (cm, None),
-                Err(_) => {
+                Err(e) => {
+                    tracing::warn!(
+                        "<RedisClient> {}",
+                        RedisConnectionError::new(config.target_description(), e)
+                    );
                     // If we can't connect to Redis, use the mock client
                     let dummy_client = Client::open("redis://localhost:6379").unwrap();
                     let cm = ConnectionManagerWrapper::new(&dummy_client).await?;
@@ -446,11 +670,8 @@ impl RedisClient {
     }
 
     async fn start_message_listener(&self) -> anyhow::Result<()> {
-        let instance_channel = format!(
-            "{}::{}::msgchannel",
-            self.config.key_prefix, self.instance_id
-        );
-        let broadcast_channel = format!("{}::msgchannel", self.config.key_prefix);
+        let instance_channel = self.config.instance_channel(&self.instance_id);
+        let broadcast_channel = self.config.broadcast_channel();
 
         tracing::info!(
             "<RedisClient> Starting message listener on channels: {} and {}",
@@ -459,45 +680,52 @@ impl RedisClient {
         );
 
         let callbacks = self.message_callbacks.clone();
-        let config_url = self.config.effective_url();
+        let config = self.config.clone();
 
         let instance_channel_clone = instance_channel.clone();
         let broadcast_channel_clone = broadcast_channel.clone();
 
+        // Subscribes again whenever the connection drops, re-resolving the
+        // master so subscriptions follow a Sentinel failover. The handlers
+        // live in `message_callbacks` and keep receiving messages.
         let listener = tokio::spawn(async move {
-            // Obtain a pubsub connection
-            let client = Client::open(config_url).expect("Failed to open client for listener");
-            let mut pubsub = match client.get_async_pubsub().await {
-                Ok(pubsub) => pubsub,
-                Err(e) => {
-                    tracing::error!("<RedisClient> Failed to get pubsub connection: {}", e);
-                    return;
-                }
-            };
-
-            if let Err(e) = pubsub
-                .subscribe(&[&instance_channel_clone, &broadcast_channel_clone])
-                .await
-            {
-                tracing::error!("<RedisClient> Failed to subscribe to channels: {}", e);
-                return;
-            }
-
+            let policy = retry::policy(RetryTarget::Redis, DEFAULT_RECONNECT_POLICY);
+            let mut failures = 0;
             loop {
-                let msg = pubsub.on_message().next().await;
-                if let Some(msg) = msg {
-                    if let Ok(payload) = msg.get_payload::<String>() {
-                        tracing::info!("<RedisClient> Received message: {}", payload);
-                        let handlers = callbacks.read().await.clone();
-                        for handler in handlers.iter() {
-                            handler(payload.clone());
+                let subscription = Self::subscribe(
+                    &config,
+                    &[&instance_channel_clone, &broadcast_channel_clone],
+                )
+                .await;
+                match subscription {
+                    Ok(mut pubsub) => {
+                        failures = 0;
+                        let mut messages = pubsub.on_message();
+                        while let Some(msg) = messages.next().await {
+                            if let Ok(payload) = msg.get_payload::<String>() {
+                                tracing::info!("<RedisClient> Received message: {}", payload);
+                                let handlers = callbacks.read().await.clone();
+                                for handler in handlers.iter() {
+                                    handler(payload.clone());
+                                }
+                            } else {
+                                tracing::warn!("<RedisClient> Failed to decode message payload");
+                            }
                         }
-                    } else {
-                        tracing::warn!("<RedisClient> Failed to decode message payload");
+                        tracing::warn!(
+                            "<RedisClient> Message subscription closed, subscribing again"
+                        );
+                    }
+                    Err(e) => {
+                        tracing::warn!("<RedisClient> Failed to subscribe to channels: {}", e);
+                        failures += 1;
                     }
-                } else {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
                 }
+                let delay = match failures {
+                    0 => Duration::from_secs(1),
+                    _ => policy.delay(failures - 1),
+                };
+                tokio::time::sleep(delay).await;
             }
         });
 
@@ -511,6 +739,16 @@ impl RedisClient {
         Ok(())
     }
 
+    async fn subscribe(config: &RedisConfig, channels: &[&str]) -> anyhow::Result<PubSub> {
+        let client = config.open_client().await?;
+        let mut pubsub = client
+            .get_async_pubsub()
+            .await
+            .map_err(|e| RedisConnectionError::new(config.target_description(), e))?;
+        pubsub.subscribe(channels).await?;
+        Ok(pubsub)
+    }
+
     // -----------------
     // Queue Operations
     // -----------------
@@ -519,13 +757,10 @@ impl RedisClient {
         message: &str,
         feature_name: Option<&str>,
     ) -> anyhow::Result<()> {
-        let queue = match feature_name {
-            Some(name) => format!("{}::{}::mqrecieved", self.config.key_prefix, name),
-            None => format!("{}::mqrecieved", self.config.key_prefix),
-        };
+        let queue = self.config.queue_key(feature_name, "mqrecieved");
 
         if self.fallback.is_none() {
-            let mut conn = self.connection_manager.connection.clone();
+            let mut conn = self.connection_manager.connection();
             let _: () = redis::cmd("RPUSH")
                 .arg(&queue)
                 .arg(message)
@@ -541,16 +776,10 @@ impl RedisClient {
         &self,
         feature_name: Option<&str>,
     ) -> anyhow::Result<Option<String>> {
-        let source_queue = match feature_name {
-            Some(name) => format!("{}::{}::mqrecieved", self.config.key_prefix, name),
-            None => format!("{}::mqrecieved", self.config.key_prefix),
-        };
-        let destination_queue = match feature_name {
-            Some(name) => format!("{}::{}::mqprocess", self.config.key_prefix, name),
-            None => format!("{}::mqprocess", self.config.key_prefix),
-        };
+        let source_queue = self.config.queue_key(feature_name, "mqrecieved");
+        let destination_queue = self.config.queue_key(feature_name, "mqprocess");
         if self.fallback.is_none() {
-            let mut conn = self.connection_manager.connection.clone();
+            let mut conn = self.connection_manager.connection();
             let msg: Option<String> = redis::cmd("RPOPLPUSH")
                 .arg(&source_queue)
                 .arg(&destination_queue)
@@ -571,16 +800,10 @@ impl RedisClient {
         success: bool,
         feature_name: Option<&str>,
     ) -> anyhow::Result<()> {
-        let in_progress_queue = match feature_name {
-            Some(name) => format!("{}::{}::mqprocess", self.config.key_prefix, name),
-            None => format!("{}::mqprocess", self.config.key_prefix),
-        };
-        let incomplete_queue = match feature_name {
-            Some(name) => format!("{}::{}::mqincomplete", self.config.key_prefix, name),
-            None => format!("{}::mqincomplete", self.config.key_prefix),
-        };
+        let in_progress_queue = self.config.queue_key(feature_name, "mqprocess");
+        let incomplete_queue = self.config.queue_key(feature_name, "mqincomplete");
         if self.fallback.is_none() {
-            let mut conn = self.connection_manager.connection.clone();
+            let mut conn = self.connection_manager.connection();
             if success {
                 let _: i32 = redis::cmd("LREM")
                     .arg(&in_progress_queue)
@@ -602,24 +825,24 @@ impl RedisClient {
     }
 
     pub async fn attempt_lock(&self, name: &str) -> anyhow::Result<bool> {
-        let lock_key = format!("{}::{}::lock", self.config.key_prefix, name);
-        let ttl = LEADERSHIP_LOCK_TTL; // Use the configured TTL instead of hardcoded value
+        let lock_key = self.config.lock_key(name);
+        let ttl = self.config.leadership_lock_ttl_secs as i64;
 
         let (has_lock, _) = self
             .leadership_manager
-            .attempt_lock(self.connection_manager.connection.clone(), &lock_key, ttl)
+            .attempt_lock(self.connection_manager.connection(), &lock_key, ttl)
             .await;
         Ok(has_lock)
     }
 
     pub async fn renew_lock(&self, name: &str) -> anyhow::Result<bool> {
-        let lock_key = format!("{}::{}::lock", self.config.key_prefix, name);
-        let ttl = LEADERSHIP_LOCK_TTL; // Use the configured TTL instead of hardcoded value
+        let lock_key = self.config.lock_key(name);
+        let ttl = self.config.leadership_lock_ttl_secs as i64;
 
         let result = self
             .leadership_manager
             .renew_lock(
-                self.connection_manager.connection.clone(),
+                self.connection_manager.connection(),
                 &lock_key,
                 &self.instance_id,
                 ttl,
@@ -630,23 +853,23 @@ impl RedisClient {
     }
 
     pub async fn check_and_renew_lock(&self, name: &str) -> anyhow::Result<(bool, bool)> {
-        let lock_key = format!("{}::{}::lock", self.config.key_prefix, name);
-        let ttl = LEADERSHIP_LOCK_TTL;
+        let lock_key = self.config.lock_key(name);
+        let ttl = self.config.leadership_lock_ttl_secs as i64;
 
         let (has_lock, is_new_acquisition) = self
             .leadership_manager
-            .attempt_lock(self.connection_manager.connection.clone(), &lock_key, ttl)
+            .attempt_lock(self.connection_manager.connection(), &lock_key, ttl)
             .await;
 
         Ok((has_lock, is_new_acquisition))
     }
 
     pub async fn release_lock(&self, name: &str) -> anyhow::Result<()> {
-        let lock_key = format!("{}::{}::lock", self.config.key_prefix, name);
+        let lock_key = self.config.lock_key(name);
 
         self.leadership_manager
             .release_lock(
-                self.connection_manager.connection.clone(),
+                self.connection_manager.connection(),
                 &lock_key,
                 &self.instance_id,
             )
@@ -654,12 +877,12 @@ impl RedisClient {
     }
 
     pub async fn has_lock(&self, name: &str) -> anyhow::Result<bool> {
-        let lock_key = format!("{}::{}::lock", self.config.key_prefix, name);
+        let lock_key = self.config.lock_key(name);
 
         let result = self
             .leadership_manager
             .has_lock(
-                self.connection_manager.connection.clone(),
+                self.connection_manager.connection(),
                 &lock_key,
                 &self.instance_id,
             )
@@ -668,27 +891,58 @@ impl RedisClient {
         Ok(result)
     }
 
+    /// Fencing token of the lock `name`, when this instance holds it.
+    pub fn lock_fencing_token(&self, name: &str) -> Option<u64> {
+        let lock_key = self.config.lock_key(name);
+        self.leadership_manager.fencing_token(&lock_key)
+    }
+
+    /// Fails when `token` is no longer the fencing token of the lock `name`,
+    /// renewing the lock otherwise. Operations guarded by the lock call this
+    /// before acting.
+    pub async fn check_lock_token(&self, name: &str, token: u64) -> Result<(), FencingError> {
+        let lock_key = self.config.lock_key(name);
+        self.leadership_manager
+            .check_and_renew_with_token(
+                self.connection_manager.connection(),
+                &lock_key,
+                token,
+                self.config.leadership_lock_ttl_secs as i64,
+            )
+            .await
+    }
+
     /// Starts a background task that periodically renews a lock to prevent expiration.
     ///
     /// This method spawns a task that periodically attempts to renew the specified
     /// lock at an interval that is approximately 1/3 of the lock's TTL to ensure
     /// continuous ownership.
     ///
+    /// When the lock turns out not to be owned by this instance anymore, the
+    /// task gives it up rather than taking it back: another instance may hold
+    /// it already. The fencing token is forgotten, the renewals stop and the
+    /// returned receiver completes, so that the caller stops acting as the
+    /// owner.
+    ///
     /// # Parameters
     ///
     /// - `name` - Name of the lock to renew
     ///
     /// # Returns
     ///
-    /// - `anyhow::Result<()>` - Ok if the renewal task was started successfully
-    pub async fn start_lock_renewal_task(&self, name: &str) -> anyhow::Result<()> {
-        let lock_key = format!("{}::{}::lock", self.config.key_prefix, name);
-        let ttl = LEADERSHIP_LOCK_TTL; // Use the configured TTL instead of hardcoded value
+    /// - `anyhow::Result<oneshot::Receiver<()>>` - Completes when the lock is lost
+    pub async fn start_lock_renewal_task(
+        &self,
+        name: &str,
+    ) -> anyhow::Result<oneshot::Receiver<()>> {
+        let lock_key = self.config.lock_key(name);
+        let ttl = self.config.leadership_lock_ttl_secs as i64;
 
         // Clone required data for the task
         let instance_id = self.instance_id.clone();
-        let connection = self.connection_manager.connection.clone();
+        let connection_manager = self.connection_manager.clone();
         let leadership_manager = self.leadership_manager.clone();
+        let (lost_tx, lost_rx) = oneshot::channel();
 
         // Determine renewal interval (1/3 of TTL)
         let renewal_interval = std::cmp::max((ttl / 3) as u64, 1); // At least 1 second
@@ -698,8 +952,14 @@ impl RedisClient {
             let mut interval = tokio::time::interval(Duration::from_secs(renewal_interval));
             loop {
                 interval.tick().await;
+                // Fetched on every tick so renewals follow a reconnection
                 match leadership_manager
-                    .renew_lock(connection.clone(), &lock_key, &instance_id, ttl)
+                    .renew_lock(
+                        connection_manager.connection(),
+                        &lock_key,
+                        &instance_id,
+                        ttl,
+                    )
                     .await
                 {
                     Ok(true) => {
@@ -707,11 +967,14 @@ impl RedisClient {
                         tracing::debug!("<RedisClient> Lock '{}' renewed successfully", &lock_key);
                     }
                     Ok(false) => {
-                        // Lock not owned by this instance anymore
+                        // Lock not owned by this instance anymore, e.g. lost in a
+                        // failover to a replica that hadn't received it yet
                         tracing::warn!(
-                            "<RedisClient> Failed to renew lock '{}': not owned by this instance",
+                            "<RedisClient> Failed to renew lock '{}': not owned by this instance, giving it up",
                             &lock_key
                         );
+                        // The caller may have stopped listening
+                        let _ = lost_tx.send(());
                         break; // Stop renewing
                     }
                     Err(e) => {
@@ -723,7 +986,7 @@ impl RedisClient {
             }
         });
 
-        Ok(())
+        Ok(lost_rx)
     }
 
     // --------------------------------------
@@ -732,13 +995,15 @@ impl RedisClient {
     pub fn start_periodic_tasks(&self) {
         // Spawn a periodic presence update task
         let connection_manager = self.connection_manager.clone();
-        let key_prefix = self.config.key_prefix.clone();
-        let instance_id = self.instance_id.clone();
+        let key = self.config.presence_key(&self.instance_id);
         let presence_task = tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1));
             loop {
                 interval.tick().await;
-                let key = format!("{key_prefix}::{instance_id}::presence");
+                // The connection monitor reports the outage
+                if !connection_manager.state.load(Ordering::SeqCst) {
+                    continue;
+                }
                 let now = std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .map(|d| d.as_secs())
@@ -779,7 +1044,7 @@ impl RedisClient {
             loop {
                 interval.tick().await;
                 if let Err(e) = presence_manager
-                    .update_presence(connection_manager.connection.clone())
+                    .update_presence(connection_manager.connection())
                     .await
                 {
                     tracing::error!("<RedisClient> Error updating presence: {}", e);
@@ -809,6 +1074,10 @@ impl RedisClient {
                 if !connection_manager.ping().await {
                     tracing::warn!("<RedisClient> Redis connection lost, attempting reconnection");
                     connection_manager.attempt_reconnection(&config).await;
+                } else if connection_manager.master_moved(&config).await {
+                    tracing::warn!("<RedisClient> Redis master changed, reconnecting");
+                    connection_manager.state.store(false, Ordering::SeqCst);
+                    connection_manager.attempt_reconnection(&config).await;
                 }
             }
         })
@@ -831,7 +1100,8 @@ impl RedisClient {
     }
 
     pub async fn check_connection(&mut self) -> anyhow::Result<()> {
-        let is_connected = self.connection_manager.ping().await;
+        let is_connected = self.connection_manager.ping().await
+            && !self.connection_manager.master_moved(&self.config).await;
         self.connection_manager
             .state
             .store(is_connected, Ordering::SeqCst);
@@ -849,13 +1119,18 @@ impl RedisClient {
         self.connection_manager.state.load(Ordering::SeqCst)
     }
 
+    /// Failures and reconnections of the Redis connection.
+    pub fn connection_health(&self) -> &ConnectionHealth {
+        self.connection_manager.health()
+    }
+
     pub async fn set_with_service_prefix<V: redis::ToRedisArgs + Send + Sync>(
         &self,
         key: &str,
         value: V,
     ) -> anyhow::Result<()> {
         let prefixed_key = self.service_prefix(&[key]);
-        let mut conn = self.connection_manager.connection.clone();
+        let mut conn = self.connection_manager.connection();
         conn.set::<_, _, ()>(&prefixed_key, value).await?;
         Ok(())
     }
@@ -865,18 +1140,25 @@ impl RedisClient {
         key: &str,
     ) -> anyhow::Result<Option<V>> {
         let prefixed_key = self.service_prefix(&[key]);
-        let mut conn = self.connection_manager.connection.clone();
+        let mut conn = self.connection_manager.connection();
         let result = conn.get(&prefixed_key).await?;
         Ok(result)
     }
 
+    pub async fn delete_with_service_prefix(&self, key: &str) -> anyhow::Result<()> {
+        let prefixed_key = self.service_prefix(&[key]);
+        let mut conn = self.connection_manager.connection();
+        conn.del::<_, ()>(&prefixed_key).await?;
+        Ok(())
+    }
+
     pub async fn get_with_explicit_prefix<V: redis::FromRedisValue + Send + Sync>(
         &self,
         explicit_prefix: &str,
         key: &str,
     ) -> anyhow::Result<Option<V>> {
         let prefixed_key = self.service_prefix_from(explicit_prefix, &[key]);
-        let mut conn = self.connection_manager.connection.clone();
+        let mut conn = self.connection_manager.connection();
         let result = conn.get(&prefixed_key).await?;
         Ok(result)
     }
@@ -889,7 +1171,7 @@ impl RedisClient {
             Some(feature) => self.service_prefix(&["queue", feature]),
             None => self.service_prefix(&["queue"]),
         };
-        let mut conn = self.connection_manager.connection.clone();
+        let mut conn = self.connection_manager.connection();
         let result: Option<String> = conn.lpop(&queue_key, None).await?;
         Ok(result)
     }
@@ -906,49 +1188,14 @@ impl RedisClient {
             None => self.service_prefix(&["queue", "history", status]),
         };
 
-        let mut conn = self.connection_manager.connection.clone();
+        let mut conn = self.connection_manager.connection();
         conn.rpush::<_, _, ()>(&queue_history_key, message).await?;
         Ok(())
     }
 
+    /// Publishes `message` on the broadcast channel, same as [`Self::broadcast_message`].
     pub async fn publish_message(&self, message: &str) -> anyhow::Result<()> {
-        // When Redis is unavailable (indicated by fallback existence), we silently succeed
-        // rather than failing the operation. This prevents application crashes when Redis
-        // is temporarily down or unreachable.
-        //
-        // Messaging is considered a non-critical operation that can be skipped in degraded mode.
-        if let Some(ref _mock_client) = self.fallback {
-            // Note: We're not actually using the mock client's functionality here,
-            // just returning success. This could be extended to implement proper mocking
-            // if needed for testing or specific fallback behavior.
-            return Ok(());
-        }
-
-        let target = "msgchannel"; // Broadcast channel
-
-        // Handle potential Redis errors gracefully by:
-        // 1. Catching and logging errors
-        // 2. Returning Ok() to prevent error propagation
-        //
-        // This prevents application crashes from Redis-related issues
-        match self
-            .messaging_manager
-            .publish_message(
-                self.connection_manager.get_connection().await,
-                target,
-                message,
-            )
-            .await
-        {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                tracing::warn!(
-                    "<RedisClient> Failed to publish message (Redis may be unavailable): {}",
-                    e
-                );
-                Ok(()) // Return Ok to prevent propagating errors
-            }
-        }
+        self.broadcast_message(message).await
     }
 
     pub async fn broadcast_message(&self, message: &str) -> anyhow::Result<()> {
@@ -964,7 +1211,7 @@ impl RedisClient {
             return Ok(());
         }
 
-        let channel = format!("{}::msgchannel", self.config.key_prefix);
+        let channel = self.config.broadcast_channel();
 
         // Handle potential Redis errors gracefully by:
         // 1. Catching and logging errors
@@ -994,11 +1241,10 @@ impl RedisClient {
         message: &str,
         target_instance_id: &str,
     ) -> anyhow::Result<()> {
-        let channel = format!("{}::{}", self.config.key_prefix, target_instance_id);
         self.messaging_manager
             .publish_message(
-                self.connection_manager.connection.clone(),
-                &channel,
+                self.connection_manager.connection(),
+                target_instance_id,
                 message,
             )
             .await
@@ -1031,8 +1277,40 @@ impl Drop for RedisClient {
 }
 
 impl RedisClient {
+    /// The former default prefix `MS` when Moose state is found under it but
+    /// not under `key_prefix`: the deployment ran with `MS` before, and this
+    /// instance won't see its leader, instances or infrastructure map.
+    ///
+    /// Nothing is reported when `last_key_prefix` differs from `key_prefix`,
+    /// as the state is then being carried over on purpose.
+    pub async fn abandoned_key_prefix(&self) -> Option<String> {
+        let legacy = RedisConfig::default_key_prefix();
+        if self.fallback.is_some()
+            || self.config.key_prefix == legacy
+            || self.config.last_key_prefix != self.config.key_prefix
+        {
+            return None;
+        }
+
+        let mut conn = self.connection_manager.connection();
+        let legacy_config = RedisConfig {
+            key_prefix: legacy.clone(),
+            ..RedisConfig::default()
+        };
+        let current_map = self.service_prefix(&["infrastructure_map"]);
+        if conn.exists::<_, bool>(&current_map).await.unwrap_or(true) {
+            return None;
+        }
+        let legacy_keys = [
+            legacy_config.lock_key("leadership"),
+            legacy_config.prefixed_key(&["infrastructure_map"]),
+        ];
+        let found = conn.exists::<_, usize>(&legacy_keys[..]).await.unwrap_or(0) > 0;
+        found.then_some(legacy)
+    }
+
     pub fn service_prefix(&self, keys: &[&str]) -> String {
-        format!("{}::{}", self.config.key_prefix, keys.join("::"))
+        self.config.prefixed_key(keys)
     }
 
     pub fn service_prefix_from(&self, explicit_prefix: &str, keys: &[&str]) -> String {
@@ -1045,7 +1323,7 @@ impl RedisClient {
 
     pub async fn presence_update(&mut self) -> anyhow::Result<()> {
         self.presence_manager
-            .update_presence(self.connection_manager.connection.clone())
+            .update_presence(self.connection_manager.connection())
             .await
     }
 
@@ -1069,6 +1347,98 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_leadership_timings() {
+        let config = RedisConfig::default();
+        assert_eq!(config.validate_leadership_timings(), Ok(()));
+        assert!(!config.has_custom_leadership_timings());
+
+        let config: RedisConfig =
+            toml::from_str("leadership_lock_ttl_secs = 3\nleadership_renewal_interval_secs = 1")
+                .unwrap();
+        assert_eq!(config.validate_leadership_timings(), Ok(()));
+        assert!(config.has_custom_leadership_timings());
+
+        let config = RedisConfig {
+            leadership_lock_ttl_secs: 14,
+            ..RedisConfig::default()
+        };
+        assert_eq!(
+            config.validate_leadership_timings(),
+            Err(LeadershipConfigError::TtlTooShort {
+                ttl: 14,
+                interval: 5
+            })
+        );
+
+        let config = RedisConfig {
+            leadership_renewal_interval_secs: 0,
+            ..RedisConfig::default()
+        };
+        assert_eq!(
+            config.validate_leadership_timings(),
+            Err(LeadershipConfigError::ZeroRenewalInterval)
+        );
+    }
+
+    #[test]
+    fn test_key_prefix_defaults_to_project_name() {
+        assert_eq!(sanitize_key_prefix("my-app"), "my-app");
+        assert_eq!(sanitize_key_prefix("@acme/ingest app"), "acme_ingest_app");
+        assert_eq!(sanitize_key_prefix("a::b"), "a__b");
+        assert_eq!(sanitize_key_prefix("@@"), "MS");
+
+        let config = RedisConfig::for_project("@acme/ingest");
+        assert_eq!(config.key_prefix, "acme_ingest");
+        assert_eq!(config.last_key_prefix, "acme_ingest");
+
+        let mut config: RedisConfig = toml::from_str("").unwrap();
+        config.resolve_key_prefixes("ingest");
+        assert_eq!(config.key_prefix, "ingest");
+        assert_eq!(config.last_key_prefix, "ingest");
+
+        // Explicit prefixes are kept
+        let mut config: RedisConfig = toml::from_str("key_prefix = \"MS\"").unwrap();
+        config.resolve_key_prefixes("ingest");
+        assert_eq!(config.key_prefix, "MS");
+        assert_eq!(config.last_key_prefix, "MS");
+
+        let mut config: RedisConfig =
+            toml::from_str("key_prefix = \"new\"\nlast_key_prefix = \"MS\"").unwrap();
+        config.resolve_key_prefixes("ingest");
+        assert_eq!(config.key_prefix, "new");
+        assert_eq!(config.last_key_prefix, "MS");
+    }
+
+    #[test]
+    fn test_every_key_is_prefixed() {
+        let config = RedisConfig::for_project("app-a");
+        let lock_key = config.lock_key("leadership");
+        let keys = [
+            lock_key.clone(),
+            format!("{lock_key}::fencing"),
+            config.broadcast_channel(),
+            config.instance_channel("instance-1"),
+            config.presence_key("instance-1"),
+            config.queue_key(None, "jobs"),
+            config.queue_key(Some("feature"), "jobs"),
+            config.prefixed_key(&["infrastructure_map"]),
+            MessagingManager::new(config.key_prefix.clone()).channel_for("instance-1"),
+        ];
+        for key in &keys {
+            assert!(key.starts_with("app-a::"), "{key}");
+        }
+        assert_eq!(
+            config.instance_channel("instance-1"),
+            MessagingManager::new(config.key_prefix.clone()).channel_for("instance-1")
+        );
+
+        // Two projects on one Redis don't share any key
+        let other = RedisConfig::for_project("app-b");
+        assert_ne!(other.lock_key("leadership"), lock_key);
+        assert_ne!(other.broadcast_channel(), config.broadcast_channel());
+    }
+
     #[test]
     fn test_effective_port_default() {
         assert_eq!(RedisConfig::default().effective_port(), 6379);
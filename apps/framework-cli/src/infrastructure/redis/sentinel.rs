@@ -0,0 +1,540 @@
+//! Redis Sentinel master discovery.
+//!
+//! With `[redis_config.sentinel]` set, Moose asks the sentinels for the
+//! address of the current master instead of connecting to `url`, and asks
+//! again whenever the connection is lost so that a failover is followed.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use prometheus_client::metrics::counter::Counter;
+use redis::Client;
+use reqwest::Url;
+use serde::{Deserialize, Deserializer, Serialize};
+use tokio::time;
+
+/// How long a single sentinel gets to answer before the next one is tried.
+const SENTINEL_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+lazy_static! {
+    /// Master changes observed since startup.
+    pub static ref REDIS_FAILOVER_COUNT: Counter = Counter::default();
+    /// Last master address seen per master name.
+    static ref KNOWN_MASTERS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// `[redis_config.sentinel]`: where to find the sentinels and which master to ask for.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SentinelConfig {
+    /// Sentinel addresses as `host:port`. A comma-separated string is also
+    /// accepted, for `MOOSE_REDIS_CONFIG__SENTINEL__ADDRESSES`.
+    #[serde(deserialize_with = "deserialize_addresses")]
+    pub addresses: Vec<String>,
+    /// Name the sentinels monitor the master under
+    pub master_name: String,
+    /// Credentials for the master
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Credentials for the sentinels, when they require auth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentinel_username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentinel_password: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SentinelError {
+    #[error("redis_config.sentinel.addresses is empty")]
+    NoAddresses,
+
+    #[error("No sentinel returned a master named '{master_name}': {}", errors.join("; "))]
+    MasterNotFound {
+        master_name: String,
+        errors: Vec<String>,
+    },
+
+    #[error("Invalid Redis address '{0}'")]
+    InvalidAddress(String),
+}
+
+fn deserialize_addresses<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Addresses {
+        List(Vec<String>),
+        CommaSeparated(String),
+    }
+
+    let addresses = match Addresses::deserialize(deserializer)? {
+        Addresses::List(list) => list,
+        Addresses::CommaSeparated(joined) => joined.split(',').map(str::to_string).collect(),
+    };
+    Ok(addresses
+        .into_iter()
+        .map(|address| address.trim().to_string())
+        .filter(|address| !address.is_empty())
+        .collect())
+}
+
+impl SentinelConfig {
+    /// URL of the sentinel at `address`.
+    fn sentinel_url(&self, address: &str, tls: bool) -> Result<String, SentinelError> {
+        let (host, port) = address
+            .rsplit_once(':')
+            .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)))
+            .ok_or_else(|| SentinelError::InvalidAddress(address.to_string()))?;
+        redis_url(
+            host.trim_start_matches('[').trim_end_matches(']'),
+            port,
+            tls,
+            self.sentinel_username.as_deref(),
+            self.sentinel_password.as_deref(),
+        )
+    }
+
+    /// URL of the master at `host:port`, with the master credentials.
+    fn master_url(&self, host: &str, port: u16, tls: bool) -> Result<String, SentinelError> {
+        redis_url(
+            host,
+            port,
+            tls,
+            self.username.as_deref(),
+            self.password.as_deref(),
+        )
+    }
+}
+
+fn redis_url(
+    host: &str,
+    port: u16,
+    tls: bool,
+    username: Option<&str>,
+    password: Option<&str>,
+) -> Result<String, SentinelError> {
+    let scheme = if tls { "rediss" } else { "redis" };
+    let host = if host.contains(':') {
+        format!("[{host}]")
+    } else {
+        host.to_string()
+    };
+    let invalid = || SentinelError::InvalidAddress(format!("{host}:{port}"));
+
+    let mut url = Url::parse(&format!("{scheme}://{host}:{port}")).map_err(|_| invalid())?;
+    if let Some(username) = username {
+        url.set_username(username).map_err(|_| invalid())?;
+    }
+    if password.is_some() {
+        url.set_password(password).map_err(|_| invalid())?;
+    }
+    Ok(url.to_string())
+}
+
+/// Asks each sentinel in turn for the current master and returns its URL.
+///
+/// A master address different from the last one seen for the same master
+/// name is a failover: it's logged and counted in `moose_redis_failover_count`.
+pub async fn resolve_master_url(
+    config: &SentinelConfig,
+    tls: bool,
+) -> Result<String, SentinelError> {
+    if config.addresses.is_empty() {
+        return Err(SentinelError::NoAddresses);
+    }
+
+    let mut errors = Vec::new();
+    for address in &config.addresses {
+        match query_master(config, address, tls).await {
+            Ok(Some((host, port))) => {
+                record_master(&config.master_name, &format!("{host}:{port}"));
+                return config.master_url(&host, port, tls);
+            }
+            Ok(None) => errors.push(format!("{address}: unknown master")),
+            Err(e) => errors.push(format!("{address}: {e}")),
+        }
+    }
+
+    Err(SentinelError::MasterNotFound {
+        master_name: config.master_name.clone(),
+        errors,
+    })
+}
+
+async fn query_master(
+    config: &SentinelConfig,
+    address: &str,
+    tls: bool,
+) -> anyhow::Result<Option<(String, u16)>> {
+    let client = Client::open(config.sentinel_url(address, tls)?)?;
+    let mut conn = time::timeout(
+        SENTINEL_QUERY_TIMEOUT,
+        client.get_multiplexed_async_connection(),
+    )
+    .await??;
+    let master = time::timeout(
+        SENTINEL_QUERY_TIMEOUT,
+        redis::cmd("SENTINEL")
+            .arg("get-master-addr-by-name")
+            .arg(&config.master_name)
+            .query_async::<Option<(String, u16)>>(&mut conn),
+    )
+    .await??;
+    Ok(master)
+}
+
+/// Remembers `address` as the master of `master_name`, returning whether it moved.
+fn record_master(master_name: &str, address: &str) -> bool {
+    let mut known = KNOWN_MASTERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match known.insert(master_name.to_string(), address.to_string()) {
+        Some(previous) if previous != address => {
+            tracing::warn!(
+                "<RedisSentinel> Failover of '{}': master moved from {} to {}",
+                master_name,
+                previous,
+                address
+            );
+            REDIS_FAILOVER_COUNT.inc();
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::redis::redis_client::RedisConfig;
+    use std::sync::Arc;
+
+    fn sentinel_config() -> SentinelConfig {
+        SentinelConfig {
+            addresses: vec!["10.0.0.1:26379".to_string()],
+            master_name: "mymaster".to_string(),
+            username: None,
+            password: None,
+            sentinel_username: None,
+            sentinel_password: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_sentinel_config() {
+        let config: RedisConfig = toml::from_str(
+            r#"
+            key_prefix = "MS"
+            [sentinel]
+            addresses = ["10.0.0.1:26379", " 10.0.0.2:26379 "]
+            master_name = "mymaster"
+            password = "secret"
+            "#,
+        )
+        .unwrap();
+        let sentinel = config.sentinel.unwrap();
+        assert_eq!(sentinel.addresses, vec!["10.0.0.1:26379", "10.0.0.2:26379"]);
+        assert_eq!(sentinel.master_name, "mymaster");
+        assert_eq!(sentinel.password.as_deref(), Some("secret"));
+        assert_eq!(sentinel.sentinel_password, None);
+
+        // As set from MOOSE_REDIS_CONFIG__SENTINEL__ADDRESSES
+        let sentinel: SentinelConfig = toml::from_str(
+            r#"
+            addresses = "10.0.0.1:26379,10.0.0.2:26379,"
+            master_name = "mymaster"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(sentinel.addresses, vec!["10.0.0.1:26379", "10.0.0.2:26379"]);
+
+        let config: RedisConfig = toml::from_str("key_prefix = \"MS\"").unwrap();
+        assert!(config.sentinel.is_none());
+    }
+
+    #[test]
+    fn test_urls() {
+        let mut config = sentinel_config();
+        assert_eq!(
+            config.sentinel_url("10.0.0.1:26379", false).unwrap(),
+            "redis://10.0.0.1:26379"
+        );
+        assert_eq!(
+            config.sentinel_url("[::1]:26379", false).unwrap(),
+            "redis://[::1]:26379"
+        );
+        assert!(matches!(
+            config.sentinel_url("10.0.0.1", false),
+            Err(SentinelError::InvalidAddress(_))
+        ));
+
+        config.password = Some("p@ss word".to_string());
+        config.sentinel_password = Some("sentinel".to_string());
+        assert_eq!(
+            config.master_url("10.0.0.5", 6379, true).unwrap(),
+            "rediss://:p%40ss%20word@10.0.0.5:6379"
+        );
+        assert_eq!(
+            config.sentinel_url("10.0.0.1:26379", false).unwrap(),
+            "redis://:sentinel@10.0.0.1:26379"
+        );
+    }
+
+    #[test]
+    fn test_record_master_counts_failovers() {
+        let before = REDIS_FAILOVER_COUNT.get();
+        assert!(!record_master("test-record-master", "10.0.0.1:6379"));
+        assert!(!record_master("test-record-master", "10.0.0.1:6379"));
+        assert!(record_master("test-record-master", "10.0.0.2:6379"));
+        assert!(REDIS_FAILOVER_COUNT.get() > before);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_without_addresses() {
+        let mut config = sentinel_config();
+        config.addresses.clear();
+        assert!(matches!(
+            resolve_master_url(&config, false).await,
+            Err(SentinelError::NoAddresses)
+        ));
+    }
+
+    /// Answers `SENTINEL get-master-addr-by-name` for the master named
+    /// `master_name` with the address in `master`, and `OK` to anything else
+    /// the client sends on connection.
+    async fn fake_sentinel(master_name: &str, master: Arc<Mutex<(String, u16)>>) -> u16 {
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let master_name = master_name.to_string();
+        tokio::spawn(async move {
+            while let Ok((connection, _)) = listener.accept().await {
+                let master_name = master_name.clone();
+                let master = master.clone();
+                tokio::spawn(async move {
+                    let mut connection = BufReader::new(connection);
+                    let mut line = String::new();
+                    // Commands come as arrays of bulk strings: `*<n>` then `$<len>` and data
+                    while connection.read_line(&mut line).await.unwrap_or(0) > 0 {
+                        let arg_count: usize = line.trim()[1..].parse().unwrap();
+                        let mut args = Vec::new();
+                        for _ in 0..arg_count {
+                            line.clear();
+                            connection.read_line(&mut line).await.unwrap();
+                            let len: usize = line.trim()[1..].parse().unwrap();
+                            let mut arg = vec![0; len + 2];
+                            connection.read_exact(&mut arg).await.unwrap();
+                            args.push(String::from_utf8_lossy(&arg[..len]).to_uppercase());
+                        }
+                        line.clear();
+
+                        let reply = if args[0] != "SENTINEL" {
+                            "+OK\r\n".to_string()
+                        } else if args[2] == master_name.to_uppercase() {
+                            let (host, port) = master.lock().unwrap().clone();
+                            let port = port.to_string();
+                            format!(
+                                "*2\r\n${}\r\n{}\r\n${}\r\n{}\r\n",
+                                host.len(),
+                                host,
+                                port.len(),
+                                port
+                            )
+                        } else {
+                            "*-1\r\n".to_string()
+                        };
+                        connection.write_all(reply.as_bytes()).await.unwrap();
+                    }
+                });
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_resolve_through_fake_sentinel() {
+        let master = Arc::new(Mutex::new(("10.0.0.7".to_string(), 6379)));
+        let port = fake_sentinel("test-fake-sentinel", master.clone()).await;
+        let mut config = SentinelConfig {
+            addresses: vec![
+                "127.0.0.1:1".to_string(), // unreachable, the next one answers
+                format!("127.0.0.1:{port}"),
+            ],
+            master_name: "test-fake-sentinel".to_string(),
+            password: Some("secret".to_string()),
+            ..sentinel_config()
+        };
+
+        assert_eq!(
+            resolve_master_url(&config, false).await.unwrap(),
+            "redis://:secret@10.0.0.7:6379"
+        );
+
+        // The sentinels promoted a replica
+        let failovers = REDIS_FAILOVER_COUNT.get();
+        *master.lock().unwrap() = ("10.0.0.8".to_string(), 6380);
+        assert_eq!(
+            resolve_master_url(&config, false).await.unwrap(),
+            "redis://:secret@10.0.0.8:6380"
+        );
+        assert!(REDIS_FAILOVER_COUNT.get() > failovers);
+
+        config.master_name = "unknown".to_string();
+        match resolve_master_url(&config, false).await {
+            Err(SentinelError::MasterNotFound { errors, .. }) => {
+                assert_eq!(errors.len(), 2);
+                assert_eq!(errors[1], format!("127.0.0.1:{port}: unknown master"));
+            }
+            other => panic!("Expected MasterNotFound, got {other:?}"),
+        }
+    }
+
+    /// Needs a sentinel on 127.0.0.1:26379 monitoring a master named `mymaster`,
+    /// with a replica to fail over to. On the host network, the addresses the
+    /// sentinel reports are reachable from the tests:
+    ///
+    /// ```sh
+    /// docker run -d --name redis-master --network host redis redis-server --port 6379
+    /// docker run -d --name redis-replica --network host redis \
+    ///   redis-server --port 6380 --replicaof 127.0.0.1 6379
+    /// docker run -d --name redis-sentinel --network host redis sh -c 'printf \
+    ///   "port 26379\nsentinel monitor mymaster 127.0.0.1 6379 1\nsentinel down-after-milliseconds mymaster 1000\nsentinel failover-timeout mymaster 5000\n" \
+    ///   > /s.conf && redis-sentinel /s.conf'
+    /// ```
+    #[tokio::test]
+    #[ignore] // Requires a running Redis Sentinel
+    async fn test_client_through_sentinel() {
+        use crate::infrastructure::redis::redis_client::RedisClient;
+
+        let config = RedisConfig {
+            sentinel: Some(SentinelConfig {
+                addresses: vec![
+                    "127.0.0.1:1".to_string(), // unreachable, the next one answers
+                    "127.0.0.1:26379".to_string(),
+                ],
+                ..sentinel_config()
+            }),
+            ..Default::default()
+        };
+        let url = config.resolve_url().await.expect("Sentinel should answer");
+        assert!(url.starts_with("redis://"));
+
+        let client = RedisClient::new("sentinel-test".to_string(), config.clone())
+            .await
+            .unwrap();
+        assert!(client.fallback.is_none());
+        assert!(client.connection_manager.is_connected_to(&config, &url));
+        client
+            .set_with_service_prefix("sentinel-test", "ok")
+            .await
+            .unwrap();
+        let value: Option<String> = client
+            .get_with_service_prefix("sentinel-test")
+            .await
+            .unwrap();
+        assert_eq!(value.as_deref(), Some("ok"));
+    }
+
+    /// Publishes `message` until the handler sending to `received` gets it, the
+    /// subscription being (re-)established in the background.
+    async fn expect_message(
+        client: &crate::infrastructure::redis::redis_client::RedisClient,
+        received: &mut tokio::sync::mpsc::UnboundedReceiver<String>,
+        message: &str,
+    ) {
+        loop {
+            client.broadcast_message(message).await.unwrap();
+            let wait = tokio::time::timeout(std::time::Duration::from_secs(1), received.recv());
+            match wait.await {
+                Ok(Some(payload)) if payload == message => return,
+                Ok(Some(_)) | Err(_) => continue,
+                Ok(None) => panic!("The message handler was dropped"),
+            }
+        }
+    }
+
+    /// After a `SENTINEL FAILOVER`, the client reconnects to the promoted
+    /// replica, keeps the leadership lock and receives messages through the
+    /// handler registered before. Needs the setup of
+    /// [`test_client_through_sentinel`].
+    #[tokio::test]
+    #[ignore] // Requires a running Redis Sentinel with a replica
+    async fn test_client_follows_sentinel_failover() {
+        use crate::infrastructure::redis::redis_client::RedisClient;
+        use std::time::Duration;
+
+        const TIMEOUT: Duration = Duration::from_secs(30);
+
+        let config = RedisConfig {
+            key_prefix: format!("failover-test-{}", uuid::Uuid::new_v4()),
+            // Outlasts the failover
+            leadership_lock_ttl_secs: 60,
+            sentinel: Some(SentinelConfig {
+                addresses: vec!["127.0.0.1:26379".to_string()],
+                ..sentinel_config()
+            }),
+            ..Default::default()
+        };
+        let mut client = RedisClient::new("failover-test".to_string(), config.clone())
+            .await
+            .unwrap();
+        assert!(client.fallback.is_none());
+        let (sender, mut received) = tokio::sync::mpsc::unbounded_channel();
+        client
+            .register_message_handler(Arc::new(move |message: String| {
+                let _ = sender.send(message);
+            }))
+            .await;
+        assert!(client.attempt_lock("failover-test").await.unwrap());
+
+        tokio::time::timeout(TIMEOUT, expect_message(&client, &mut received, "before"))
+            .await
+            .expect("The message should be received before the failover");
+
+        let old_master = config.resolve_url().await.unwrap();
+        let failovers = REDIS_FAILOVER_COUNT.get();
+        let sentinel = redis::Client::open("redis://127.0.0.1:26379").unwrap();
+        let mut sentinel = sentinel.get_multiplexed_async_connection().await.unwrap();
+        redis::cmd("SENTINEL")
+            .arg("FAILOVER")
+            .arg("mymaster")
+            .query_async::<()>(&mut sentinel)
+            .await
+            .unwrap();
+
+        let new_master = tokio::time::timeout(TIMEOUT, async {
+            loop {
+                let url = config.resolve_url().await.unwrap();
+                if url != old_master {
+                    return url;
+                }
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+        })
+        .await
+        .expect("The sentinel should promote the replica");
+        assert!(REDIS_FAILOVER_COUNT.get() > failovers);
+
+        client.check_connection().await.unwrap();
+        assert!(client.is_connected());
+        assert!(client
+            .connection_manager
+            .is_connected_to(&config, &new_master));
+
+        // The lock was replicated to the promoted master
+        assert!(client.renew_lock("failover-test").await.unwrap());
+
+        tokio::time::timeout(TIMEOUT, expect_message(&client, &mut received, "after"))
+            .await
+            .expect("The message should be received after the failover");
+
+        client.release_lock("failover-test").await.unwrap();
+        client.stop_periodic_tasks().await.unwrap();
+    }
+}
@@ -13,6 +13,7 @@ use crate::infrastructure::stream::kafka::constants::{
     DEFAULT_MAX_MESSAGE_BYTES, KAFKA_MAX_MESSAGE_BYTES_CONFIG_KEY, KAFKA_RETENTION_CONFIG_KEY,
 };
 use crate::project::Project;
+use crate::utilities::retry::{self, RetryPolicy, RetryTarget};
 use rdkafka::admin::{AlterConfig, NewPartitions, ResourceSpecifier};
 use rdkafka::config::RDKafkaLogLevel;
 use rdkafka::consumer::stream_consumer::StreamConsumer;
@@ -565,8 +566,17 @@ pub async fn check_topic_size(topic: &str, config: &KafkaConfig) -> Result<i64,
 /// * Returns error if client creation fails
 /// * Returns error if metadata fetch fails
 /// * Returns error if describe configs fails for any topic
+///
+/// Failures are retried with the `kafka` retry policy, by default not at all.
 pub async fn fetch_topics(
     config: &KafkaConfig,
+) -> Result<Vec<KafkaStreamConfig>, rdkafka::error::KafkaError> {
+    let policy = retry::policy(RetryTarget::Kafka, RetryPolicy::NO_RETRY);
+    retry::retry(|| fetch_topics_once(config), |_| true, &policy).await
+}
+
+async fn fetch_topics_once(
+    config: &KafkaConfig,
 ) -> Result<Vec<KafkaStreamConfig>, rdkafka::error::KafkaError> {
     let rdkafka_config = build_rdkafka_client_config(config);
     let client: BaseConsumer = rdkafka_config.create()?;
@@ -5,6 +5,7 @@ pub mod infrastructure;
 pub mod mcp;
 pub mod metrics;
 pub mod metrics_inserter;
+pub mod metrics_otlp;
 pub mod project;
 pub mod utilities;
 
@@ -105,6 +106,18 @@ fn main() -> ExitCode {
         std::env::set_var("RUST_LIB_BACKTRACE", "1");
     }
 
+    // Must happen before any `Project` is loaded
+    project::config_overrides::set_config_overrides(cli_result.config_overrides.clone());
+    project::config_profiles::set_profile(cli_result.profile.clone());
+
+    if cli_result.quiet {
+        utilities::constants::SUPPRESS_DISPLAY.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    if cli_result.non_interactive || cli::non_interactive_from_env() {
+        utilities::constants::NON_INTERACTIVE.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
     // Clone logger settings before moving config into async block
     let logger_settings = config.logger.clone();
 
@@ -117,10 +130,21 @@ fn main() -> ExitCode {
     // Run inside runtime context so OTLP batch exporter can initialize properly
     let result = runtime.block_on(async {
         // Setup logging (inside runtime context for OTLP batch exporter)
-        cli::logger::setup_logging(&logger_settings);
+        cli::logger::setup_logging(
+            &logger_settings,
+            cli::logger::ConsoleVerbosity {
+                verbose: cli_result.verbose,
+                quiet: cli_result.quiet,
+            },
+        );
 
-        // Get machine ID (after logging setup so warnings are visible)
-        let machine_id = utilities::machine_id::get_or_create_machine_id();
+        // Get machine ID (after logging setup so warnings are visible). It only
+        // identifies telemetry, so none is created when telemetry is off.
+        let machine_id = if utilities::telemetry::telemetry_enabled(&config) {
+            utilities::machine_id::get_or_create_machine_id()
+        } else {
+            String::new()
+        };
 
         // Run the async command handler
         cli::top_command_handler(config, &cli_result.command, machine_id).await
@@ -134,7 +158,7 @@ fn main() -> ExitCode {
                 show_message!(s.message_type, s.message);
             }
             ensure_terminal_cleanup();
-            ExitCode::from(0)
+            ExitCode::from(s.exit_code)
         }
         Err(e) => {
             show_message!(e.message_type, e.message);
@@ -142,7 +166,7 @@ fn main() -> ExitCode {
                 eprintln!("{err:?}");
             }
             ensure_terminal_cleanup();
-            ExitCode::from(1)
+            ExitCode::from(e.exit_code)
         }
     };
 
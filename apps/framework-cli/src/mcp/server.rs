@@ -10,13 +10,19 @@ use rmcp::{
     ErrorData, RoleServer, ServerHandler,
 };
 use std::sync::Arc;
+use tokio::sync::RwLock;
 use tracing::info;
 
-use super::tools::{create_error_result, infra_issues, infra_map, logs, query_olap, sample_stream};
+use super::tools::{
+    create_error_result, diagnose_table, get_plan, infra_issues, infra_map, list_infrastructure,
+    logs, query_olap, query_table, sample_stream, tail_logs,
+};
 use crate::cli::processing_coordinator::ProcessingCoordinator;
+use crate::framework::core::infrastructure_map::InfrastructureMap;
 use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
 use crate::infrastructure::redis::redis_client::RedisClient;
 use crate::infrastructure::stream::kafka::models::KafkaConfig;
+use crate::project::Project;
 
 /// Handler for the MCP server that implements the Model Context Protocol
 #[derive(Clone)]
@@ -24,9 +30,12 @@ pub struct MooseMcpHandler {
     server_name: String,
     server_version: String,
     redis_client: Arc<RedisClient>,
+    /// The map kept up to date by the file watcher, absent outside dev mode
+    infra_map: Option<&'static RwLock<InfrastructureMap>>,
     clickhouse_config: ClickHouseConfig,
     kafka_config: Arc<KafkaConfig>,
     processing_coordinator: ProcessingCoordinator,
+    project: Arc<Project>,
 }
 
 impl MooseMcpHandler {
@@ -35,17 +44,21 @@ impl MooseMcpHandler {
         server_name: String,
         server_version: String,
         redis_client: Arc<RedisClient>,
+        infra_map: Option<&'static RwLock<InfrastructureMap>>,
         clickhouse_config: ClickHouseConfig,
         kafka_config: Arc<KafkaConfig>,
         processing_coordinator: ProcessingCoordinator,
+        project: Arc<Project>,
     ) -> Self {
         Self {
             server_name,
             server_version,
             redis_client,
+            infra_map,
             clickhouse_config,
             kafka_config,
             processing_coordinator,
+            project,
         }
     }
 }
@@ -67,7 +80,7 @@ impl ServerHandler for MooseMcpHandler {
                 website_url: None,
             },
             instructions: Some(
-                "Moose MCP Server - Access dev server logs, infrastructure map, diagnose infrastructure issues, query the OLAP database, and sample streaming topics"
+                "Moose MCP Server - Access dev server logs, infrastructure map, list infrastructure resources, diagnose infrastructure issues and individual tables, query the OLAP database and individual tables, sample streaming topics, and show the deployment plan"
                     .to_string(),
             ),
         }
@@ -78,15 +91,23 @@ impl ServerHandler for MooseMcpHandler {
         _pagination: Option<PaginatedRequestParams>,
         _context: RequestContext<RoleServer>,
     ) -> Result<ListToolsResult, ErrorData> {
+        let mut tools = vec![
+            logs::tool_definition(),
+            tail_logs::tool_definition(),
+            infra_map::tool_definition(),
+            list_infrastructure::tool_definition(),
+            infra_issues::tool_definition(),
+            diagnose_table::tool_definition(),
+            query_olap::tool_definition(),
+            sample_stream::tool_definition(),
+            get_plan::tool_definition(),
+            query_table::tool_definition(),
+        ];
+        tools.retain(|tool| self.project.dev.mcp.tool_enabled(&tool.name));
+
         Ok(ListToolsResult {
             meta: None,
-            tools: vec![
-                logs::tool_definition(),
-                infra_map::tool_definition(),
-                infra_issues::tool_definition(),
-                query_olap::tool_definition(),
-                sample_stream::tool_definition(),
-            ],
+            tools,
             next_cursor: None,
         })
     }
@@ -103,30 +124,70 @@ impl ServerHandler for MooseMcpHandler {
             .acquire_stable_state_guard()
             .await;
 
+        if !self.project.dev.mcp.tool_enabled(&param.name) {
+            return Ok(create_error_result(format!(
+                "The {} tool is disabled in [dev.mcp] of moose.config.toml",
+                param.name
+            )));
+        }
+
         match param.name.as_ref() {
             "get_logs" => Ok(logs::handle_call(param.arguments.as_ref())),
+            "tail_logs" => Ok(tail_logs::handle_call(
+                param.arguments.as_ref(),
+                &self.project,
+            )),
             "get_infra_map" => Ok(infra_map::handle_call(
                 param.arguments.as_ref(),
                 self.redis_client.clone(),
             )
             .await),
+            "list_infrastructure" => match self.infra_map {
+                Some(infra_map) => Ok(list_infrastructure::handle_call(
+                    param.arguments.as_ref(),
+                    infra_map,
+                    &self.project,
+                )
+                .await),
+                None => Ok(create_error_result(
+                    "The list_infrastructure tool is only available in dev mode".to_string(),
+                )),
+            },
             "get_issues" => Ok(infra_issues::handle_call(
                 param.arguments.as_ref(),
                 self.redis_client.clone(),
                 &self.clickhouse_config,
             )
             .await),
+            "diagnose_table" => Ok(diagnose_table::handle_call(
+                param.arguments.as_ref(),
+                self.redis_client.clone(),
+                &self.clickhouse_config,
+            )
+            .await),
             "query_olap" => Ok(query_olap::handle_call(
                 &self.clickhouse_config,
                 param.arguments.as_ref(),
             )
             .await),
+            "query_table" => Ok(query_table::handle_call(
+                param.arguments.as_ref(),
+                self.redis_client.clone(),
+                &self.clickhouse_config,
+            )
+            .await),
             "get_stream_sample" => Ok(sample_stream::handle_call(
                 param.arguments.as_ref(),
                 self.redis_client.clone(),
                 self.kafka_config.clone(),
             )
             .await),
+            "get_plan" => Ok(get_plan::handle_call(
+                param.arguments.as_ref(),
+                self.redis_client.clone(),
+                &self.project,
+            )
+            .await),
             _ => Ok(create_error_result(format!("Unknown tool: {}", param.name))),
         }
     }
@@ -138,9 +199,11 @@ impl ServerHandler for MooseMcpHandler {
 /// * `server_name` - Name of the MCP server
 /// * `server_version` - Version of the MCP server
 /// * `redis_client` - Redis client for accessing infrastructure state
+/// * `infra_map` - The infrastructure map kept up to date by the file watcher, in dev mode
 /// * `clickhouse_config` - ClickHouse configuration for database access
 /// * `kafka_config` - Kafka configuration for streaming operations
 /// * `processing_coordinator` - Coordinator for synchronizing with file watcher
+/// * `project` - The project, for planning and the optional tools to expose
 ///
 /// # Returns
 /// * `StreamableHttpService` - HTTP service that can handle MCP requests
@@ -148,9 +211,11 @@ pub fn create_mcp_http_service(
     server_name: String,
     server_version: String,
     redis_client: Arc<RedisClient>,
+    infra_map: Option<&'static RwLock<InfrastructureMap>>,
     clickhouse_config: ClickHouseConfig,
     kafka_config: Arc<KafkaConfig>,
     processing_coordinator: ProcessingCoordinator,
+    project: Arc<Project>,
 ) -> StreamableHttpService<MooseMcpHandler, LocalSessionManager> {
     info!(
         "[MCP] Creating MCP HTTP service: {} v{}",
@@ -173,9 +238,11 @@ pub fn create_mcp_http_service(
                 server_name.clone(),
                 server_version.clone(),
                 redis_client.clone(),
+                infra_map,
                 clickhouse_config.clone(),
                 kafka_config.clone(),
                 processing_coordinator.clone(),
+                project.clone(),
             ))
         },
         session_manager,
@@ -207,16 +274,26 @@ mod tests {
         let infra_issues_tool = infra_issues::tool_definition();
         let olap_tool = query_olap::tool_definition();
         let stream_tool = sample_stream::tool_definition();
+        let diagnose_table_tool = diagnose_table::tool_definition();
+        let query_table_tool = query_table::tool_definition();
+        let plan_tool = get_plan::tool_definition();
+        let tail_logs_tool = tail_logs::tool_definition();
+        let list_infrastructure_tool = list_infrastructure::tool_definition();
 
-        // Ensure we have 5 tools
+        // Ensure we have 10 tools
         let all_tools = vec![
             &logs_tool,
             &infra_tool,
             &infra_issues_tool,
             &olap_tool,
             &stream_tool,
+            &diagnose_table_tool,
+            &query_table_tool,
+            &plan_tool,
+            &tail_logs_tool,
+            &list_infrastructure_tool,
         ];
-        assert_eq!(all_tools.len(), 5);
+        assert_eq!(all_tools.len(), 10);
 
         // Verify each tool has required fields
         for tool in all_tools {
@@ -235,6 +312,11 @@ mod tests {
             "query_olap",
             "get_stream_sample",
             "get_issues",
+            "diagnose_table",
+            "query_table",
+            "get_plan",
+            "tail_logs",
+            "list_infrastructure",
         ];
 
         let logs_tool = logs::tool_definition();
@@ -242,11 +324,21 @@ mod tests {
         let olap_tool = query_olap::tool_definition();
         let stream_tool = sample_stream::tool_definition();
         let infra_issues_tool = infra_issues::tool_definition();
+        let diagnose_table_tool = diagnose_table::tool_definition();
+        let query_table_tool = query_table::tool_definition();
+        let plan_tool = get_plan::tool_definition();
+        let tail_logs_tool = tail_logs::tool_definition();
+        let list_infrastructure_tool = list_infrastructure::tool_definition();
 
         assert_eq!(logs_tool.name, expected_tools[0]);
         assert_eq!(infra_tool.name, expected_tools[1]);
         assert_eq!(olap_tool.name, expected_tools[2]);
         assert_eq!(stream_tool.name, expected_tools[3]);
         assert_eq!(infra_issues_tool.name, expected_tools[4]);
+        assert_eq!(diagnose_table_tool.name, expected_tools[5]);
+        assert_eq!(query_table_tool.name, expected_tools[6]);
+        assert_eq!(plan_tool.name, expected_tools[7]);
+        assert_eq!(tail_logs_tool.name, expected_tools[8]);
+        assert_eq!(list_infrastructure_tool.name, expected_tools[9]);
     }
 }
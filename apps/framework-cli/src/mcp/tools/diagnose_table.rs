@@ -0,0 +1,395 @@
+//! # Diagnose Table Tool
+//!
+//! This module implements the MCP tool answering "why is my table slow or
+//! broken": it runs the ClickHouse diagnostics on a single table, resolving
+//! the table's database and engine from the live infrastructure map.
+//!
+//! Diagnostics go through the shared
+//! `crate::infrastructure::olap::clickhouse::diagnostics` orchestration, so
+//! the same per-query timeouts apply as for `get_issues`, and the call runs
+//! under the stable state guard the MCP server holds for every tool.
+
+use rmcp::model::{CallToolResult, Tool};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tracing::info;
+
+use super::infra_issues::DiagnoseError;
+use super::{create_error_result, create_success_result};
+use crate::framework::core::infrastructure_map::InfrastructureMap;
+use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::diagnostics::{
+    create_all_providers, run_diagnostics_with_providers, Component, DiagnosticOptions,
+    DiagnosticOutput, DiagnosticProvider, DiagnosticRequest, Severity,
+};
+use crate::infrastructure::redis::redis_client::RedisClient;
+
+/// Error types for the diagnose_table tool
+#[derive(Debug, thiserror::Error)]
+pub enum DiagnoseTableError {
+    #[error(transparent)]
+    Params(#[from] DiagnoseError),
+
+    #[error("Table '{name}' not found in the infrastructure map. Available tables: {}", available.join(", "))]
+    UnknownTable {
+        name: String,
+        available: Vec<String>,
+    },
+
+    #[error("Unknown diagnostic names: {}. Available diagnostics: {}", names.join(", "), available.join(", "))]
+    UnknownDiagnostics {
+        names: Vec<String>,
+        available: Vec<String>,
+    },
+
+    #[error("Failed to execute diagnostics: {0}")]
+    DiagnosticFailed(String),
+}
+
+/// Parameters for the diagnose_table tool
+#[derive(Debug)]
+struct DiagnoseTableParams {
+    /// Name of the table, as in the infrastructure map
+    table: String,
+    /// Diagnostics to run, all applicable ones when empty
+    diagnostics: Vec<String>,
+    /// Minimum severity level to report
+    severity: Severity,
+    /// Optional time filter (e.g., "-1h" for last hour)
+    since: Option<String>,
+}
+
+/// Returns the tool definition for the MCP server
+pub fn tool_definition() -> Tool {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "table": {
+                "type": "string",
+                "description": "Name of the table to diagnose, as defined in the project"
+            },
+            "diagnostics": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Diagnostics to run (e.g., 'MutationDiagnostic', 'PartsDiagnostic'). All applicable ones when omitted"
+            },
+            "severity": {
+                "type": "string",
+                "description": "Minimum severity level to report",
+                "enum": ["error", "warning", "info", "all"],
+                "default": "all"
+            },
+            "since": {
+                "type": "string",
+                "description": "Optional time filter for issues (e.g., '-1h' for last hour, '-30m' for last 30 minutes)",
+                "examples": ["-1h", "-30m", "-1d", "2024-01-01T00:00:00Z"]
+            }
+        },
+        "required": ["table"]
+    });
+
+    Tool {
+        name: "diagnose_table".into(),
+        description: Some(
+            "Diagnose a single ClickHouse table: stuck mutations, too many parts, long merges, replication or S3Queue problems. Use when a specific table is slow, not receiving data or failing. Returns the issues found as JSON, with remediation suggestions.".into()
+        ),
+        input_schema: Arc::new(schema.as_object().unwrap().clone()),
+        annotations: None,
+        execution: None,
+        icons: None,
+        meta: None,
+        output_schema: None,
+        title: Some("Diagnose Table".into()),
+    }
+}
+
+/// Parse and validate parameters from MCP arguments
+fn parse_params(
+    arguments: Option<&Map<String, Value>>,
+) -> Result<DiagnoseTableParams, DiagnoseError> {
+    let args = arguments
+        .ok_or_else(|| DiagnoseError::InvalidParameter("No arguments provided".to_string()))?;
+
+    let table = args
+        .get("table")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| DiagnoseError::InvalidParameter("table parameter is required".to_string()))?
+        .trim()
+        .to_string();
+
+    let diagnostics = match args.get("diagnostics") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(names)) => names
+            .iter()
+            .map(|name| {
+                name.as_str().map(str::to_string).ok_or_else(|| {
+                    DiagnoseError::InvalidParameter(
+                        "diagnostics must be an array of strings".to_string(),
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => {
+            return Err(DiagnoseError::InvalidParameter(
+                "diagnostics must be an array of strings".to_string(),
+            ))
+        }
+    };
+
+    let severity = args
+        .get("severity")
+        .and_then(|v| v.as_str())
+        .map(Severity::from_str)
+        .transpose()?
+        .unwrap_or(Severity::Info);
+
+    let since = args
+        .get("since")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    Ok(DiagnoseTableParams {
+        table,
+        diagnostics,
+        severity,
+        since,
+    })
+}
+
+/// Handle the MCP tool call with the given arguments
+pub async fn handle_call(
+    arguments: Option<&Map<String, Value>>,
+    redis_client: Arc<RedisClient>,
+    clickhouse_config: &ClickHouseConfig,
+) -> CallToolResult {
+    let infra_map = match InfrastructureMap::load_from_redis(&redis_client).await {
+        Ok(Some(infra_map)) => infra_map,
+        Ok(None) => {
+            return create_error_result(
+                "No infrastructure map found. The dev server may not be running.".to_string(),
+            )
+        }
+        Err(e) => return create_error_result(format!("Failed to load infrastructure map: {}", e)),
+    };
+
+    let providers = create_all_providers().into_iter().map(Arc::from).collect();
+    diagnose(arguments, &infra_map, clickhouse_config, providers).await
+}
+
+/// Runs the diagnostics of `providers` on the table named in `arguments`
+async fn diagnose(
+    arguments: Option<&Map<String, Value>>,
+    infra_map: &InfrastructureMap,
+    clickhouse_config: &ClickHouseConfig,
+    providers: Vec<Arc<dyn DiagnosticProvider>>,
+) -> CallToolResult {
+    match execute_diagnose_table(arguments, infra_map, clickhouse_config, providers).await {
+        Ok(output) => match serde_json::to_string_pretty(&output) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(format!("Failed to convert output to JSON: {}", e)),
+        },
+        Err(e) => create_error_result(format!("Table diagnostics error: {}", e)),
+    }
+}
+
+async fn execute_diagnose_table(
+    arguments: Option<&Map<String, Value>>,
+    infra_map: &InfrastructureMap,
+    clickhouse_config: &ClickHouseConfig,
+    providers: Vec<Arc<dyn DiagnosticProvider>>,
+) -> Result<DiagnosticOutput, DiagnoseTableError> {
+    let params = parse_params(arguments)?;
+
+    let available: Vec<String> = providers.iter().map(|p| p.name().to_string()).collect();
+    let unknown: Vec<String> = params
+        .diagnostics
+        .iter()
+        .filter(|name| !available.contains(name))
+        .cloned()
+        .collect();
+    if !unknown.is_empty() {
+        return Err(DiagnoseTableError::UnknownDiagnostics {
+            names: unknown,
+            available,
+        });
+    }
+
+    let table = infra_map.find_table_by_name(&params.table).ok_or_else(|| {
+        DiagnoseTableError::UnknownTable {
+            name: params.table.clone(),
+            available: infra_map
+                .tables
+                .values()
+                .map(|table| table.name.clone())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        }
+    })?;
+
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        "database".to_string(),
+        table
+            .database
+            .clone()
+            .unwrap_or_else(|| clickhouse_config.db_name.clone()),
+    );
+    let component = Component {
+        component_type: "table".to_string(),
+        name: table.name.clone(),
+        metadata,
+    };
+
+    info!(
+        "Running diagnostics on table {} with severity filter: {:?}",
+        table.name, params.severity
+    );
+    let request = DiagnosticRequest {
+        components: vec![(component, table.engine.clone())],
+        options: DiagnosticOptions {
+            diagnostic_names: params.diagnostics,
+            min_severity: params.severity,
+            since: params.since,
+        },
+    };
+
+    run_diagnostics_with_providers(request, clickhouse_config, providers)
+        .await
+        .map_err(|e| DiagnoseTableError::DiagnosticFailed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::table::{OrderBy, Table};
+    use crate::framework::core::infrastructure_map::{PrimitiveSignature, PrimitiveTypes};
+    use crate::framework::core::partial_infrastructure_map::LifeCycle;
+    use crate::infrastructure::olap::clickhouse::diagnostics::test_providers::MockDiagnostic;
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+    use rmcp::model::RawContent;
+
+    fn table(name: &str) -> Table {
+        Table {
+            name: name.to_string(),
+            columns: vec![],
+            order_by: OrderBy::Fields(vec![]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    fn infra_map() -> InfrastructureMap {
+        let mut infra_map = InfrastructureMap::default();
+        for name in ["events", "users"] {
+            infra_map.tables.insert(name.to_string(), table(name));
+        }
+        infra_map
+    }
+
+    fn providers() -> Vec<Arc<dyn DiagnosticProvider>> {
+        vec![
+            Arc::new(MockDiagnostic::with_error("events")),
+            Arc::new(MockDiagnostic::with_warning("events")),
+        ]
+    }
+
+    async fn call(arguments: Value) -> (bool, String) {
+        let result = diagnose(
+            arguments.as_object(),
+            &infra_map(),
+            &ClickHouseConfig::default(),
+            providers(),
+        )
+        .await;
+        let text = match &result.content[0].raw {
+            RawContent::Text(text) => text.text.clone(),
+            other => panic!("unexpected content: {other:?}"),
+        };
+        (result.is_error == Some(true), text)
+    }
+
+    #[tokio::test]
+    async fn test_returns_diagnostic_output() {
+        let (is_error, text) = call(json!({ "table": "events" })).await;
+        assert!(!is_error, "{text}");
+
+        let output: DiagnosticOutput = serde_json::from_str(&text).unwrap();
+        assert_eq!(output.summary.total_issues, 2);
+        assert_eq!(output.summary.by_component.get("events"), Some(&2));
+    }
+
+    #[tokio::test]
+    async fn test_severity_and_diagnostic_filters() {
+        let (_, text) = call(json!({ "table": "events", "severity": "error" })).await;
+        let output: DiagnosticOutput = serde_json::from_str(&text).unwrap();
+        assert_eq!(output.issues.len(), 1);
+        assert_eq!(output.issues[0].severity, Severity::Error);
+
+        let (_, text) = call(json!({ "table": "events", "diagnostics": ["mock_warning"] })).await;
+        let output: DiagnosticOutput = serde_json::from_str(&text).unwrap();
+        assert_eq!(output.issues.len(), 1);
+        assert_eq!(output.issues[0].error_type, "mock_warning");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_table_lists_tables() {
+        let (is_error, text) = call(json!({ "table": "evnts" })).await;
+        assert!(is_error);
+        assert!(text.contains("Table 'evnts' not found"), "{text}");
+        assert!(text.contains("Available tables: events, users"), "{text}");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_diagnostic_lists_diagnostics() {
+        let (is_error, text) = call(json!({
+            "table": "events",
+            "diagnostics": ["mock_warning", "slow_queries"]
+        }))
+        .await;
+        assert!(is_error);
+        assert!(
+            text.contains("Unknown diagnostic names: slow_queries."),
+            "{text}"
+        );
+        assert!(
+            text.contains("Available diagnostics: mock_diagnostic, mock_warning"),
+            "{text}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_parameters() {
+        for arguments in [
+            json!({}),
+            json!({ "table": " " }),
+            json!({ "table": "events", "severity": "critical" }),
+            json!({ "table": "events", "diagnostics": "mock_warning" }),
+        ] {
+            let (is_error, text) = call(arguments.clone()).await;
+            assert!(is_error, "{arguments}");
+            assert!(text.contains("Invalid parameter"), "{text}");
+        }
+    }
+}
@@ -0,0 +1,587 @@
+//! # Get Plan Tool
+//!
+//! This module implements the MCP tool answering "what would deploying now
+//! change?". It plans like `moose plan`: against the remote ClickHouse when a
+//! remote URL is stored for the project (`moose secret`), against the local
+//! state otherwise. Plans are never executed: ClickHouse is only read through
+//! [`OlapOperations`], and nothing is written to the state storage.
+//!
+//! Planning loads the project code and inspects ClickHouse, so it is bounded
+//! by [`PLAN_TIMEOUT`], and its result reused for [`PLAN_CACHE_TTL`] to
+//! absorb repeated calls.
+
+use rmcp::model::{CallToolResult, Tool};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use super::{create_error_result, create_success_result};
+use crate::cli::routines::get_remote_inframap_serverless;
+use crate::framework::core::drop_strategy;
+use crate::framework::core::infrastructure_map::{InfraChanges, InfrastructureMap};
+use crate::framework::core::plan::{
+    infra_changes_to_operations, load_target_infrastructure, normalize_infra_map_for_comparison,
+    plan_changes_to_target, PlanningError, ReconciliationFilter,
+};
+use crate::framework::core::state_storage::{StateStorage, StateStorageBuilder};
+use crate::infrastructure::olap::clickhouse::diff_strategy::ClickHouseTableDiffStrategy;
+use crate::infrastructure::olap::clickhouse::{
+    self, describe_operation, operation_sql_preview, ClickhouseChangesError,
+    SerializableOlapOperation,
+};
+use crate::infrastructure::olap::ddl_ordering::PlanOrderingError;
+use crate::infrastructure::olap::OlapOperations;
+use crate::infrastructure::redis::redis_client::RedisClient;
+use crate::project::Project;
+use crate::utilities::constants::{KEY_REMOTE_CLICKHOUSE_URL, REMOTE_CLICKHOUSE_URL_SECRET};
+use crate::utilities::secret_store::project_secrets;
+
+/// Maximum time spent computing a plan
+const PLAN_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long a computed plan is returned to later calls
+const PLAN_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// The last computed plan. Held while planning so that concurrent calls wait
+/// for the plan in progress instead of computing their own.
+static PLAN_CACHE: LazyLock<Mutex<Option<(Instant, PlanOutput)>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// Error types for the get_plan tool
+#[derive(Debug, thiserror::Error)]
+pub enum GetPlanError {
+    #[error("Failed to plan changes: {0}")]
+    Planning(#[from] PlanningError),
+
+    #[error("Failed to plan changes: {0:#}")]
+    Other(#[from] anyhow::Error),
+
+    #[error("Failed to order operations: {0}")]
+    Ordering(#[from] PlanOrderingError),
+
+    #[error("Failed to render SQL: {0}")]
+    Rendering(#[from] ClickhouseChangesError),
+
+    #[error("Planning did not finish within {} seconds", PLAN_TIMEOUT.as_secs())]
+    Timeout,
+}
+
+/// What the project code was compared with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PlanSource {
+    /// The state of the local dev environment
+    Local,
+    /// The ClickHouse of the remote URL stored for the project
+    Remote,
+}
+
+/// One database operation of the plan
+#[derive(Debug, Clone, Serialize)]
+struct PlannedOperation {
+    kind: String,
+    /// The table or view operated on, `database.name` outside the default database
+    target: Option<String>,
+    description: String,
+    /// Whether the operation may lose data
+    destructive: bool,
+    sql: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+struct PlanSummary {
+    total_operations: usize,
+    destructive_operations: usize,
+    by_kind: BTreeMap<String, usize>,
+    /// Database changes blocked by table lifecycles
+    blocked_changes: usize,
+    streaming_changes: usize,
+    api_changes: usize,
+    web_app_changes: usize,
+    function_changes: usize,
+    workflow_changes: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PlanOutput {
+    source: PlanSource,
+    has_changes: bool,
+    summary: PlanSummary,
+    operations: Vec<PlannedOperation>,
+}
+
+/// Returns the tool definition for the MCP server
+pub fn tool_definition() -> Tool {
+    let schema = json!({
+        "type": "object",
+        "properties": {}
+    });
+
+    Tool {
+        name: "get_plan".into(),
+        description: Some(
+            "Show what deploying the project now would change, without changing anything. Compares the project code with the deployed ClickHouse when a remote URL is stored for the project, with the local dev state otherwise. Returns each database operation with its kind, target table or view, whether it may lose data, and its SQL, plus a summary including streaming, API and workflow changes.".into()
+        ),
+        input_schema: Arc::new(schema.as_object().unwrap().clone()),
+        annotations: None,
+        execution: None,
+        icons: None,
+        meta: None,
+        output_schema: None,
+        title: Some("Get Deployment Plan".into()),
+    }
+}
+
+/// Whether `operation` may lose data
+fn is_destructive(operation: &SerializableOlapOperation) -> bool {
+    match operation {
+        SerializableOlapOperation::DropTable { .. }
+        | SerializableOlapOperation::DropTableColumn { .. }
+        | SerializableOlapOperation::RawSql { .. } => true,
+        SerializableOlapOperation::ModifyTableColumn {
+            before_column,
+            after_column,
+            ..
+        } => !before_column
+            .data_type
+            .eq_ignoring_field_comments(&after_column.data_type),
+        // A TTL deletes the rows it expires
+        SerializableOlapOperation::ModifyTableTtl { after, .. } => after.is_some(),
+        _ => false,
+    }
+}
+
+fn build_output(
+    source: PlanSource,
+    changes: &InfraChanges,
+    default_database: &str,
+) -> Result<PlanOutput, GetPlanError> {
+    let operations = infra_changes_to_operations(changes, default_database)?
+        .iter()
+        .map(|operation| {
+            Ok(PlannedOperation {
+                kind: operation.kind(),
+                target: operation.target(),
+                description: describe_operation(operation),
+                destructive: is_destructive(operation),
+                sql: operation_sql_preview(default_database, operation)?,
+            })
+        })
+        .collect::<Result<Vec<_>, GetPlanError>>()?;
+
+    let mut summary = PlanSummary {
+        total_operations: operations.len(),
+        destructive_operations: operations.iter().filter(|op| op.destructive).count(),
+        blocked_changes: changes.filtered_olap_changes.len(),
+        streaming_changes: changes.streaming_engine_changes.len(),
+        api_changes: changes.api_changes.len(),
+        web_app_changes: changes.web_app_changes.len(),
+        function_changes: changes.processes_changes.len(),
+        workflow_changes: changes.workflow_changes.len(),
+        ..Default::default()
+    };
+    for operation in &operations {
+        *summary.by_kind.entry(operation.kind.clone()).or_default() += 1;
+    }
+
+    Ok(PlanOutput {
+        source,
+        has_changes: !changes.is_empty(),
+        summary,
+        operations,
+    })
+}
+
+/// Plans against the local state, reading ClickHouse through `olap_client`.
+async fn plan_local<T: OlapOperations + Sync>(
+    state_storage: &dyn StateStorage,
+    project: &Project,
+    target_infra_map: InfrastructureMap,
+    olap_client: impl Fn() -> T,
+) -> Result<PlanOutput, GetPlanError> {
+    let (_, plan) =
+        plan_changes_to_target(state_storage, project, target_infra_map, olap_client).await?;
+    build_output(
+        PlanSource::Local,
+        &plan.changes,
+        &project.clickhouse_config.db_name,
+    )
+}
+
+/// Plans against the remote ClickHouse at `clickhouse_url`, with the
+/// production settings `moose plan --clickhouse-url` uses.
+async fn plan_remote(
+    project: &Project,
+    target_infra_map: InfrastructureMap,
+    clickhouse_url: &str,
+) -> Result<PlanOutput, GetPlanError> {
+    let filter = ReconciliationFilter::from_infra_map(&target_infra_map);
+    let remote_infra_map =
+        get_remote_inframap_serverless(project, clickhouse_url, None, &filter).await?;
+
+    let olap_client = clickhouse::create_client(project.clickhouse_config.clone());
+    let remote_normalized =
+        normalize_infra_map_for_comparison(&remote_infra_map, &olap_client).await;
+    let target_normalized =
+        normalize_infra_map_for_comparison(&target_infra_map, &olap_client).await;
+
+    let mut changes = remote_normalized.diff_with_table_strategy(
+        &target_normalized,
+        &ClickHouseTableDiffStrategy,
+        true, // respect_lifecycle
+        true, // is_production
+        &project.migration_config.ignore_operations,
+    );
+    drop_strategy::apply_drop_strategy(
+        &mut changes.olap_changes,
+        &project.migration_config,
+        &project.clickhouse_config.db_name,
+    );
+    build_output(
+        PlanSource::Remote,
+        &changes,
+        &project.clickhouse_config.db_name,
+    )
+}
+
+async fn compute_plan(
+    project: &Project,
+    redis_client: &Arc<RedisClient>,
+) -> Result<PlanOutput, GetPlanError> {
+    let target_infra_map = load_target_infrastructure(project).await?;
+
+    let remote_url = project_secrets(&project.name())
+        .get_or_migrate(REMOTE_CLICKHOUSE_URL_SECRET, KEY_REMOTE_CLICKHOUSE_URL)
+        .unwrap_or_else(|e| {
+            debug!("Could not read the remote ClickHouse URL: {:?}", e);
+            None
+        });
+
+    match remote_url {
+        Some(url) => plan_remote(project, target_infra_map, &url).await,
+        None => {
+            let state_storage = StateStorageBuilder::from_config(project)
+                .redis_client(Some(redis_client))
+                .build()
+                .await?;
+            plan_local(&*state_storage, project, target_infra_map, || {
+                clickhouse::create_client(project.clickhouse_config.clone())
+            })
+            .await
+        }
+    }
+}
+
+/// Returns the cached plan while fresh, computes one otherwise. The boolean
+/// tells whether the plan came from the cache.
+async fn cached_plan<F>(compute: F) -> Result<(PlanOutput, bool), GetPlanError>
+where
+    F: std::future::Future<Output = Result<PlanOutput, GetPlanError>>,
+{
+    let mut cache = PLAN_CACHE.lock().await;
+    if let Some((computed_at, plan)) = cache.as_ref() {
+        if computed_at.elapsed() < PLAN_CACHE_TTL {
+            return Ok((plan.clone(), true));
+        }
+    }
+
+    let plan = tokio::time::timeout(PLAN_TIMEOUT, compute)
+        .await
+        .map_err(|_| GetPlanError::Timeout)??;
+    *cache = Some((Instant::now(), plan.clone()));
+    Ok((plan, false))
+}
+
+/// Handle the MCP tool call with the given arguments
+pub async fn handle_call(
+    _arguments: Option<&Map<String, Value>>,
+    redis_client: Arc<RedisClient>,
+    project: &Project,
+) -> CallToolResult {
+    match cached_plan(compute_plan(project, &redis_client)).await {
+        Ok((plan, cached)) => {
+            info!(
+                "Planned {} operations against the {:?} state (cached: {})",
+                plan.summary.total_operations, plan.source, cached
+            );
+            let mut output = match serde_json::to_value(&plan) {
+                Ok(output) => output,
+                Err(e) => {
+                    return create_error_result(format!("Failed to convert output to JSON: {}", e))
+                }
+            };
+            output["cached"] = Value::Bool(cached);
+            match serde_json::to_string_pretty(&output) {
+                Ok(json) => create_success_result(json),
+                Err(e) => create_error_result(format!("Failed to convert output to JSON: {}", e)),
+            }
+        }
+        Err(e) => create_error_result(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::sql_resource::SqlResource;
+    use crate::framework::core::infrastructure::table::{Column, ColumnType, OrderBy, Table};
+    use crate::framework::core::infrastructure_map::{PrimitiveSignature, PrimitiveTypes};
+    use crate::framework::core::partial_infrastructure_map::LifeCycle;
+    use crate::framework::languages::SupportedLanguages;
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+    use crate::infrastructure::olap::clickhouse::TableWithUnsupportedType;
+    use crate::infrastructure::olap::OlapChangesError;
+    use async_trait::async_trait;
+    use std::sync::Mutex as StdMutex;
+    use tempfile::TempDir;
+
+    /// An OLAP client listing `tables` and recording every call
+    #[derive(Clone)]
+    struct RecordingOlapClient {
+        tables: Vec<Table>,
+        calls: Arc<StdMutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl OlapOperations for RecordingOlapClient {
+        async fn list_tables(
+            &self,
+            _db_name: &str,
+            _project: &Project,
+        ) -> Result<(Vec<Table>, Vec<TableWithUnsupportedType>), OlapChangesError> {
+            self.calls.lock().unwrap().push("list_tables");
+            Ok((self.tables.clone(), vec![]))
+        }
+
+        async fn list_sql_resources(
+            &self,
+            _db_name: &str,
+            _default_database: &str,
+        ) -> Result<Vec<SqlResource>, OlapChangesError> {
+            self.calls.lock().unwrap().push("list_sql_resources");
+            Ok(vec![])
+        }
+
+        async fn normalize_sql(
+            &self,
+            sql: &str,
+            _default_database: &str,
+        ) -> Result<String, OlapChangesError> {
+            self.calls.lock().unwrap().push("normalize_sql");
+            Ok(sql.to_string())
+        }
+    }
+
+    /// A state storage holding `infra_map` and recording every call
+    struct RecordingStorage {
+        infra_map: InfrastructureMap,
+        calls: StdMutex<Vec<&'static str>>,
+    }
+
+    #[async_trait]
+    impl StateStorage for RecordingStorage {
+        async fn store_infrastructure_map(&self, _: &InfrastructureMap) -> anyhow::Result<()> {
+            self.calls.lock().unwrap().push("store_infrastructure_map");
+            Ok(())
+        }
+
+        async fn load_infrastructure_map(&self) -> anyhow::Result<Option<InfrastructureMap>> {
+            self.calls.lock().unwrap().push("load_infrastructure_map");
+            Ok(Some(self.infra_map.clone()))
+        }
+
+        async fn clear_infrastructure_map(&self) -> anyhow::Result<()> {
+            self.calls.lock().unwrap().push("clear_infrastructure_map");
+            Ok(())
+        }
+
+        async fn acquire_migration_lock(&self) -> anyhow::Result<()> {
+            self.calls.lock().unwrap().push("acquire_migration_lock");
+            Ok(())
+        }
+
+        async fn release_migration_lock(&self) -> anyhow::Result<()> {
+            self.calls.lock().unwrap().push("release_migration_lock");
+            Ok(())
+        }
+    }
+
+    fn table(name: &str) -> Table {
+        Table {
+            name: name.to_string(),
+            columns: vec![Column {
+                name: "id".to_string(),
+                data_type: ColumnType::String,
+                required: true,
+                unique: false,
+                primary_key: true,
+                default: None,
+                annotations: vec![],
+                comment: None,
+                ttl: None,
+                codec: None,
+                materialized: None,
+                alias: None,
+            }],
+            order_by: OrderBy::Fields(vec!["id".to_string()]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: name.to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    fn infra_map(project: &Project, tables: &[&str]) -> InfrastructureMap {
+        let mut infra_map = InfrastructureMap::empty_from_project(project);
+        for name in tables {
+            let table = table(name);
+            infra_map
+                .tables
+                .insert(table.id(&infra_map.default_database), table);
+        }
+        infra_map
+    }
+
+    #[tokio::test]
+    async fn test_plan_only_reads() {
+        let dir = TempDir::new().unwrap();
+        let project = Project::new(
+            dir.path(),
+            "test-project".to_string(),
+            SupportedLanguages::Typescript,
+        );
+
+        // `legacy` is deployed and removed from the code, `events` is new
+        let storage = RecordingStorage {
+            infra_map: infra_map(&project, &["legacy"]),
+            calls: StdMutex::new(vec![]),
+        };
+        let olap_client = RecordingOlapClient {
+            tables: vec![table("legacy")],
+            calls: Arc::new(StdMutex::new(vec![])),
+        };
+
+        let plan = plan_local(&storage, &project, infra_map(&project, &["events"]), || {
+            olap_client.clone()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(plan.source, PlanSource::Local);
+        assert!(plan.has_changes);
+        assert_eq!(plan.summary.total_operations, 2);
+        assert_eq!(plan.summary.destructive_operations, 1);
+
+        let drop = &plan.operations[0];
+        assert_eq!(drop.kind, "DropTable");
+        assert_eq!(drop.target.as_deref(), Some("legacy"));
+        assert!(drop.destructive);
+        assert!(drop.sql[0].starts_with("DROP TABLE"), "{:?}", drop.sql);
+
+        let create = &plan.operations[1];
+        assert_eq!(create.kind, "CreateTable");
+        assert_eq!(create.target.as_deref(), Some("events"));
+        assert!(!create.destructive);
+        assert!(create.sql[0].contains("CREATE TABLE"), "{:?}", create.sql);
+
+        // Planning must never reach ClickHouse DDL nor change the state
+        let olap_calls = olap_client.calls.lock().unwrap();
+        assert!(!olap_calls.is_empty());
+        assert!(
+            olap_calls
+                .iter()
+                .all(|call| ["list_tables", "list_sql_resources", "normalize_sql"].contains(call)),
+            "{olap_calls:?}"
+        );
+        assert_eq!(
+            *storage.calls.lock().unwrap(),
+            vec!["load_infrastructure_map"]
+        );
+    }
+
+    #[test]
+    fn test_destructive_operations() {
+        let column = table("t").columns.remove(0);
+        let retyped = Column {
+            data_type: ColumnType::Boolean,
+            ..column.clone()
+        };
+        let modify = |after_column: Column| SerializableOlapOperation::ModifyTableColumn {
+            table: "t".to_string(),
+            before_column: column.clone(),
+            after_column,
+            database: None,
+            cluster_name: None,
+        };
+        let ttl = |after: Option<&str>| SerializableOlapOperation::ModifyTableTtl {
+            table: "t".to_string(),
+            before: None,
+            after: after.map(str::to_string),
+            database: Some("analytics".to_string()),
+            cluster_name: None,
+        };
+
+        assert!(is_destructive(&modify(retyped)));
+        assert!(!is_destructive(&modify(Column {
+            comment: Some("described".to_string()),
+            ..column.clone()
+        })));
+        assert!(is_destructive(&ttl(Some("ts + INTERVAL 1 DAY"))));
+        assert!(!is_destructive(&ttl(None)));
+        assert!(!is_destructive(&SerializableOlapOperation::DropView {
+            name: "v".to_string(),
+            database: None,
+        }));
+
+        assert_eq!(ttl(None).kind(), "ModifyTableTtl");
+        assert_eq!(ttl(None).target().as_deref(), Some("analytics.t"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_plan_is_cached_briefly() {
+        let plan = || PlanOutput {
+            source: PlanSource::Local,
+            has_changes: false,
+            summary: PlanSummary::default(),
+            operations: vec![],
+        };
+        *PLAN_CACHE.lock().await = None;
+
+        let (_, cached) = cached_plan(async { Ok(plan()) }).await.unwrap();
+        assert!(!cached);
+        // A fresh plan is returned without planning again
+        let (_, cached) = cached_plan(async { Err(GetPlanError::Timeout) })
+            .await
+            .unwrap();
+        assert!(cached);
+
+        tokio::time::advance(PLAN_CACHE_TTL).await;
+        let result = cached_plan(async {
+            tokio::time::sleep(PLAN_TIMEOUT * 2).await;
+            Ok(plan())
+        })
+        .await;
+        assert!(matches!(result, Err(GetPlanError::Timeout)));
+    }
+}
@@ -72,7 +72,7 @@ pub struct DiagnoseInfraParams {
 }
 
 impl Severity {
-    fn from_str(s: &str) -> Result<Self, DiagnoseError> {
+    pub(crate) fn from_str(s: &str) -> Result<Self, DiagnoseError> {
         match s.to_lowercase().as_str() {
             "error" => Ok(Severity::Error),
             "warning" => Ok(Severity::Warning),
@@ -139,7 +139,7 @@ pub fn tool_definition() -> Tool {
     Tool {
         name: "get_issues".into(),
         description: Some(
-            "Proactively scan for health issues (stuck mutations, replication errors, S3Queue failures, merge problems). Auto-checks relevant diagnostics based on infrastructure type. Use when investigating errors or performance issues. Returns actionable problems with remediation suggestions.".into()
+            "Proactively scan for health issues (stuck mutations, replication errors, S3Queue failures, merge problems, disks filling up, failed inserts). Auto-checks relevant diagnostics based on infrastructure type. Use when investigating errors or performance issues. Returns actionable problems with remediation suggestions.".into()
         ),
         input_schema: Arc::new(schema.as_object().unwrap().clone()),
         annotations: None,
@@ -315,7 +315,13 @@ async fn diagnose_clickhouse(
         .iter()
         .map(|(_map_key, table)| {
             let mut metadata = HashMap::new();
-            metadata.insert("database".to_string(), clickhouse_config.db_name.clone());
+            metadata.insert(
+                "database".to_string(),
+                table
+                    .database
+                    .clone()
+                    .unwrap_or_else(|| clickhouse_config.db_name.clone()),
+            );
 
             let component = Component {
                 component_type: "table".to_string(),
@@ -0,0 +1,605 @@
+//! # List Infrastructure Tool
+//!
+//! This module implements the MCP tool returning an inventory of the project's
+//! infrastructure, one compact summary per resource grouped by type. It reads
+//! the infrastructure map held by the dev server, so the inventory reflects
+//! hot-reloaded changes.
+
+use rmcp::model::{CallToolResult, Tool};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::{create_error_result, create_success_result};
+use crate::framework::core::infrastructure::api_endpoint::APIType;
+use crate::framework::core::infrastructure_map::InfrastructureMap;
+use crate::framework::core::partial_infrastructure_map::LifeCycle;
+use crate::project::Project;
+
+/// Resource types the tool can be filtered by
+const RESOURCE_TYPES: [&str; 8] = [
+    "tables",
+    "topics",
+    "ingest_apis",
+    "consumption_apis",
+    "sql_resources",
+    "materialized_views",
+    "views",
+    "workflows",
+];
+
+/// Error types for the list_infrastructure tool
+#[derive(Debug, thiserror::Error)]
+pub enum ListInfrastructureError {
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
+}
+
+/// Parameters for the list_infrastructure tool
+#[derive(Debug, Default)]
+struct ListInfrastructureParams {
+    /// Only this resource type, one of [`RESOURCE_TYPES`]
+    resource_type: Option<String>,
+    /// Only resources whose name contains this, case-insensitively
+    name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TableSummary {
+    name: String,
+    database: String,
+    engine: String,
+    column_count: usize,
+    life_cycle: LifeCycle,
+}
+
+#[derive(Debug, Serialize)]
+struct TopicSummary {
+    name: String,
+    partition_count: usize,
+    retention_seconds: u64,
+    life_cycle: LifeCycle,
+}
+
+#[derive(Debug, Serialize)]
+struct IngestApiSummary {
+    name: String,
+    path: String,
+    target_topic: String,
+    dead_letter_queue: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConsumptionApiSummary {
+    name: String,
+    path: String,
+    query_params: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SqlResourceSummary {
+    name: String,
+    database: String,
+    pulls_data_from: usize,
+    pushes_data_to: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct MaterializedViewSummary {
+    name: String,
+    database: String,
+    source_tables: Vec<String>,
+    target_table: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ViewSummary {
+    name: String,
+    database: String,
+    source_tables: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct WorkflowSummary {
+    name: String,
+    schedule: Option<String>,
+}
+
+/// The inventory returned by the tool. Types excluded by the filter are omitted.
+#[derive(Debug, Default, Serialize)]
+struct InfrastructureSummary {
+    version: String,
+    default_database: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tables: Option<Vec<TableSummary>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    topics: Option<Vec<TopicSummary>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ingest_apis: Option<Vec<IngestApiSummary>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    consumption_apis: Option<Vec<ConsumptionApiSummary>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sql_resources: Option<Vec<SqlResourceSummary>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    materialized_views: Option<Vec<MaterializedViewSummary>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    views: Option<Vec<ViewSummary>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workflows: Option<Vec<WorkflowSummary>>,
+}
+
+/// Returns the tool definition for the MCP server
+pub fn tool_definition() -> Tool {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "type": {
+                "type": "string",
+                "description": "Only list resources of this type",
+                "enum": RESOURCE_TYPES
+            },
+            "name": {
+                "type": "string",
+                "description": "Only list resources whose name contains this (case-insensitive)"
+            }
+        }
+    });
+
+    Tool {
+        name: "list_infrastructure".into(),
+        description: Some(
+            "List the project's infrastructure as a compact inventory grouped by type: tables (database, engine, column count, lifecycle), topics, ingest APIs, consumption APIs, SQL resources, materialized views, views and workflows. Includes the project version and default database. Reflects code changes as soon as the dev server reloads them. Use get_infra_map for full definitions.".into()
+        ),
+        input_schema: Arc::new(schema.as_object().unwrap().clone()),
+        annotations: None,
+        execution: None,
+        icons: None,
+        meta: None,
+        output_schema: None,
+        title: Some("List Infrastructure".into()),
+    }
+}
+
+/// Parse and validate parameters from MCP arguments
+fn parse_params(
+    arguments: Option<&Map<String, Value>>,
+) -> Result<ListInfrastructureParams, ListInfrastructureError> {
+    let mut params = ListInfrastructureParams::default();
+    let Some(args) = arguments else {
+        return Ok(params);
+    };
+
+    match args.get("type") {
+        None | Some(Value::Null) => {}
+        Some(Value::String(resource_type)) if RESOURCE_TYPES.contains(&resource_type.as_str()) => {
+            params.resource_type = Some(resource_type.clone());
+        }
+        Some(value) => {
+            return Err(ListInfrastructureError::InvalidParameter(format!(
+                "type must be one of {}, got {}",
+                RESOURCE_TYPES.join(", "),
+                value
+            )))
+        }
+    }
+
+    match args.get("name") {
+        None | Some(Value::Null) => {}
+        Some(Value::String(name)) => params.name = Some(name.to_lowercase()),
+        Some(_) => {
+            return Err(ListInfrastructureError::InvalidParameter(
+                "name must be a string".to_string(),
+            ))
+        }
+    }
+
+    Ok(params)
+}
+
+/// Summarizes the resources of `infra_map` selected by `params`, sorted by name.
+fn summarize(
+    infra_map: &InfrastructureMap,
+    version: String,
+    params: &ListInfrastructureParams,
+) -> InfrastructureSummary {
+    let wanted = |resource_type: &str| {
+        params
+            .resource_type
+            .as_deref()
+            .is_none_or(|wanted| wanted == resource_type)
+    };
+    let matches = |name: &str| {
+        params
+            .name
+            .as_deref()
+            .is_none_or(|needle| name.to_lowercase().contains(needle))
+    };
+    let database = |database: &Option<String>| {
+        database
+            .clone()
+            .unwrap_or_else(|| infra_map.default_database.clone())
+    };
+    fn sorted<T>(mut items: Vec<T>, name: impl Fn(&T) -> &str) -> Vec<T> {
+        items.sort_by(|a, b| name(a).cmp(name(b)));
+        items
+    }
+
+    let mut summary = InfrastructureSummary {
+        version,
+        default_database: infra_map.default_database.clone(),
+        ..Default::default()
+    };
+
+    if wanted("tables") {
+        let tables = infra_map
+            .tables
+            .values()
+            .filter(|table| matches(&table.name))
+            .map(|table| TableSummary {
+                name: table.name.clone(),
+                database: database(&table.database),
+                engine: table.engine.to_proto_string(),
+                column_count: table.columns.len(),
+                life_cycle: table.life_cycle,
+            })
+            .collect();
+        summary.tables = Some(sorted(tables, |t| t.name.as_str()));
+    }
+
+    if wanted("topics") {
+        let topics = infra_map
+            .topics
+            .values()
+            .filter(|topic| matches(&topic.name))
+            .map(|topic| TopicSummary {
+                name: topic.name.clone(),
+                partition_count: topic.partition_count,
+                retention_seconds: topic.retention_period.as_secs(),
+                life_cycle: topic.life_cycle,
+            })
+            .collect();
+        summary.topics = Some(sorted(topics, |t| t.name.as_str()));
+    }
+
+    if wanted("ingest_apis") || wanted("consumption_apis") {
+        let mut ingest_apis = Vec::new();
+        let mut consumption_apis = Vec::new();
+        for endpoint in infra_map.api_endpoints.values() {
+            if !matches(&endpoint.name) {
+                continue;
+            }
+            let path = endpoint.path.to_string_lossy().to_string();
+            match &endpoint.api_type {
+                APIType::INGRESS {
+                    target_topic_id,
+                    dead_letter_queue,
+                    ..
+                } => ingest_apis.push(IngestApiSummary {
+                    name: endpoint.name.clone(),
+                    path,
+                    target_topic: target_topic_id.clone(),
+                    dead_letter_queue: dead_letter_queue.clone(),
+                }),
+                APIType::EGRESS { query_params, .. } => {
+                    consumption_apis.push(ConsumptionApiSummary {
+                        name: endpoint.name.clone(),
+                        path,
+                        query_params: query_params.iter().map(|p| p.name.clone()).collect(),
+                    })
+                }
+            }
+        }
+        if wanted("ingest_apis") {
+            summary.ingest_apis = Some(sorted(ingest_apis, |a| a.name.as_str()));
+        }
+        if wanted("consumption_apis") {
+            summary.consumption_apis = Some(sorted(consumption_apis, |a| a.name.as_str()));
+        }
+    }
+
+    if wanted("sql_resources") {
+        let sql_resources = infra_map
+            .sql_resources
+            .values()
+            .filter(|resource| matches(&resource.name))
+            .map(|resource| SqlResourceSummary {
+                name: resource.name.clone(),
+                database: database(&resource.database),
+                pulls_data_from: resource.pulls_data_from.len(),
+                pushes_data_to: resource.pushes_data_to.len(),
+            })
+            .collect();
+        summary.sql_resources = Some(sorted(sql_resources, |r| r.name.as_str()));
+    }
+
+    if wanted("materialized_views") {
+        let materialized_views = infra_map
+            .materialized_views
+            .values()
+            .filter(|mv| matches(&mv.name))
+            .map(|mv| MaterializedViewSummary {
+                name: mv.name.clone(),
+                database: database(&mv.database),
+                source_tables: mv.source_tables.clone(),
+                target_table: mv.target_table.clone(),
+            })
+            .collect();
+        summary.materialized_views = Some(sorted(materialized_views, |v| v.name.as_str()));
+    }
+
+    if wanted("views") {
+        let views = infra_map
+            .views
+            .values()
+            .filter(|view| matches(&view.name))
+            .map(|view| ViewSummary {
+                name: view.name.clone(),
+                database: database(&view.database),
+                source_tables: view.source_tables.clone(),
+            })
+            .collect();
+        summary.views = Some(sorted(views, |v| v.name.as_str()));
+    }
+
+    if wanted("workflows") {
+        let workflows = infra_map
+            .workflows
+            .values()
+            .filter(|workflow| matches(workflow.name()))
+            .map(|workflow| WorkflowSummary {
+                name: workflow.name().to_string(),
+                schedule: Some(workflow.config().schedule.clone())
+                    .filter(|schedule| !schedule.is_empty()),
+            })
+            .collect();
+        summary.workflows = Some(sorted(workflows, |w| w.name.as_str()));
+    }
+
+    summary
+}
+
+/// Handle the MCP tool call with the given arguments
+pub async fn handle_call(
+    arguments: Option<&Map<String, Value>>,
+    infra_map: &RwLock<InfrastructureMap>,
+    project: &Project,
+) -> CallToolResult {
+    let params = match parse_params(arguments) {
+        Ok(params) => params,
+        Err(e) => return create_error_result(format!("Parameter validation error: {}", e)),
+    };
+
+    let summary = summarize(
+        &*infra_map.read().await,
+        project.cur_version().to_string(),
+        &params,
+    );
+    match serde_json::to_string_pretty(&summary) {
+        Ok(json) => create_success_result(json),
+        Err(e) => create_error_result(format!("Failed to convert output to JSON: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::api_endpoint::{ApiEndpoint, Method};
+    use crate::framework::core::infrastructure::table::{Column, ColumnType, OrderBy, Table};
+    use crate::framework::core::infrastructure::topic::Topic;
+    use crate::framework::core::infrastructure_map::{PrimitiveSignature, PrimitiveTypes};
+    use crate::framework::languages::SupportedLanguages;
+    use crate::framework::scripts::Workflow;
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+    use rmcp::model::RawContent;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn column(name: &str) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: ColumnType::String,
+            required: true,
+            unique: false,
+            primary_key: false,
+            default: None,
+            annotations: vec![],
+            comment: None,
+            ttl: None,
+            codec: None,
+            materialized: None,
+            alias: None,
+        }
+    }
+
+    fn signature(name: &str) -> PrimitiveSignature {
+        PrimitiveSignature {
+            name: name.to_string(),
+            primitive_type: PrimitiveTypes::DataModel,
+        }
+    }
+
+    fn table(name: &str, database: Option<&str>, life_cycle: LifeCycle) -> Table {
+        Table {
+            name: name.to_string(),
+            columns: vec![column("id"), column("ts")],
+            order_by: OrderBy::Fields(vec!["id".to_string()]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: signature(name),
+            metadata: None,
+            life_cycle,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: database.map(str::to_string),
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        }
+    }
+
+    fn endpoint(name: &str, api_type: APIType) -> ApiEndpoint {
+        ApiEndpoint {
+            name: name.to_string(),
+            api_type,
+            path: PathBuf::from(format!("ingest/{name}")),
+            method: Method::POST,
+            version: None,
+            source_primitive: signature(name),
+            metadata: None,
+            pulls_data_from: vec![],
+            pushes_data_to: vec![],
+        }
+    }
+
+    fn fixture() -> InfrastructureMap {
+        let mut infra_map = InfrastructureMap {
+            default_database: "local".to_string(),
+            ..Default::default()
+        };
+        infra_map.tables.insert(
+            "Events".to_string(),
+            table("Events", None, LifeCycle::FullyManaged),
+        );
+        infra_map.tables.insert(
+            "archive_PageViews".to_string(),
+            table("PageViews", Some("archive"), LifeCycle::ExternallyManaged),
+        );
+        infra_map.topics.insert(
+            "Events".to_string(),
+            Topic {
+                version: None,
+                name: "Events".to_string(),
+                retention_period: Duration::from_secs(3600),
+                partition_count: 2,
+                max_message_bytes: 1024,
+                columns: vec![column("id")],
+                source_primitive: signature("Events"),
+                metadata: None,
+                life_cycle: LifeCycle::FullyManaged,
+                schema_config: None,
+            },
+        );
+        infra_map.api_endpoints.insert(
+            "INGRESS_Events".to_string(),
+            endpoint(
+                "Events",
+                APIType::INGRESS {
+                    target_topic_id: "Events".to_string(),
+                    data_model: None,
+                    dead_letter_queue: None,
+                    schema: Default::default(),
+                },
+            ),
+        );
+        infra_map.api_endpoints.insert(
+            "EGRESS_topEvents".to_string(),
+            endpoint(
+                "topEvents",
+                APIType::EGRESS {
+                    query_params: vec![],
+                    output_schema: Value::Null,
+                },
+            ),
+        );
+        infra_map.workflows.insert(
+            "nightly".to_string(),
+            Workflow::from_user_code(
+                "nightly".to_string(),
+                SupportedLanguages::Typescript,
+                None,
+                None,
+                Some("@daily".to_string()),
+                vec![],
+                vec![],
+            ),
+        );
+        infra_map
+    }
+
+    fn list(arguments: Value) -> Value {
+        let params = parse_params(arguments.as_object()).unwrap();
+        serde_json::to_value(summarize(&fixture(), "1.2.0".to_string(), &params)).unwrap()
+    }
+
+    #[test]
+    fn test_summarizes_every_type() {
+        let summary = list(json!({}));
+
+        assert_eq!(summary["version"], "1.2.0");
+        assert_eq!(summary["default_database"], "local");
+        assert_eq!(
+            summary["tables"],
+            json!([
+                {
+                    "name": "Events",
+                    "database": "local",
+                    "engine": "MergeTree",
+                    "column_count": 2,
+                    "life_cycle": "FULLY_MANAGED"
+                },
+                {
+                    "name": "PageViews",
+                    "database": "archive",
+                    "engine": "MergeTree",
+                    "column_count": 2,
+                    "life_cycle": "EXTERNALLY_MANAGED"
+                }
+            ])
+        );
+        assert_eq!(summary["topics"][0]["retention_seconds"], 3600);
+        assert_eq!(summary["ingest_apis"][0]["target_topic"], "Events");
+        assert_eq!(summary["consumption_apis"][0]["name"], "topEvents");
+        assert_eq!(summary["workflows"][0]["schedule"], "@daily");
+        assert_eq!(summary["sql_resources"], json!([]));
+    }
+
+    #[test]
+    fn test_filters() {
+        let summary = list(json!({ "type": "tables", "name": "view" }));
+        let object = summary.as_object().unwrap();
+        assert_eq!(object.len(), 3);
+        assert!(object.contains_key("default_database") && object.contains_key("version"));
+        assert_eq!(summary["tables"][0]["name"], "PageViews");
+        assert_eq!(summary["tables"].as_array().unwrap().len(), 1);
+
+        let summary = list(json!({ "name": "EVENTS" }));
+        assert_eq!(summary["tables"].as_array().unwrap().len(), 1);
+        assert_eq!(summary["consumption_apis"][0]["name"], "topEvents");
+        assert_eq!(summary["workflows"], json!([]));
+
+        for arguments in [json!({ "type": "dashboards" }), json!({ "name": 3 })] {
+            assert!(parse_params(arguments.as_object()).is_err(), "{arguments}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_call_reads_live_map() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project = Project::new(
+            temp_dir.path(),
+            "test-project".to_string(),
+            SupportedLanguages::Typescript,
+        );
+        let infra_map = RwLock::new(fixture());
+        let arguments = json!({ "type": "workflows" });
+
+        infra_map.write().await.workflows.clear();
+        let result = handle_call(arguments.as_object(), &infra_map, &project).await;
+
+        assert_ne!(result.is_error, Some(true));
+        let RawContent::Text(text) = &result.content[0].raw else {
+            panic!("expected text content");
+        };
+        let summary: Value = serde_json::from_str(&text.text).unwrap();
+        assert_eq!(summary["workflows"], json!([]));
+        assert_eq!(summary["version"], project.cur_version().to_string());
+    }
+}
@@ -77,7 +77,7 @@ struct GetLogsParams {
 }
 
 /// Gets the path to the current day's log file using the shared format from logger module
-fn get_log_file_path() -> std::io::Result<PathBuf> {
+pub(super) fn get_log_file_path() -> std::io::Result<PathBuf> {
     let formatted_date = Local::now().format(DEFAULT_LOG_FILE_FORMAT).to_string();
     let mut path = user_directory()?;
     path.push(formatted_date);
@@ -1,8 +1,13 @@
+pub mod diagnose_table;
+pub mod get_plan;
 pub mod infra_issues;
 pub mod infra_map;
+pub mod list_infrastructure;
 pub mod logs;
 pub mod query_olap;
+pub mod query_table;
 pub mod sample_stream;
+pub mod tail_logs;
 
 use rmcp::model::{Annotated, CallToolResult, RawContent, RawTextContent};
 
@@ -0,0 +1,740 @@
+//! # Query Table Tool
+//!
+//! This module implements the MCP tool returning rows of a project table
+//! from structured inputs instead of SQL. The inputs are compiled into a
+//! single SELECT against the table's known columns: unknown columns and
+//! operators are rejected, values are escaped, and the row count is capped.
+//! The generated SQL is returned along with the rows.
+//!
+//! The tool can be turned off with `query_table = false` under `[dev.mcp]`.
+
+use rmcp::model::{CallToolResult, Tool};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::{create_error_result, create_success_result};
+use crate::framework::core::infrastructure::table::Table;
+use crate::framework::core::infrastructure_map::InfrastructureMap;
+use crate::infrastructure::olap::clickhouse::client::ClickHouseClient;
+use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+use crate::infrastructure::olap::clickhouse::model::{quote_ident, quote_qualified, quote_string};
+use crate::infrastructure::redis::redis_client::RedisClient;
+
+const DEFAULT_LIMIT: u64 = 100;
+/// Hard cap on the rows returned, whatever the requested limit
+const MAX_LIMIT: u64 = 1000;
+const QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Error types for the query_table tool
+#[derive(Debug, thiserror::Error)]
+pub enum QueryTableError {
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
+
+    #[error("Table '{name}' not found in the infrastructure map. Available tables: {}", available.join(", "))]
+    UnknownTable {
+        name: String,
+        available: Vec<String>,
+    },
+
+    #[error("Unknown column '{column}' in table '{table}'. Available columns: {}", available.join(", "))]
+    UnknownColumn {
+        column: String,
+        table: String,
+        available: Vec<String>,
+    },
+
+    #[error("Unsupported operator '{0}'. Supported operators: {}", Operator::NAMES.join(", "))]
+    UnsupportedOperator(String),
+
+    #[error("Invalid value for {column} {op}: {reason}")]
+    InvalidValue {
+        column: String,
+        op: &'static str,
+        reason: &'static str,
+    },
+
+    #[error("Failed to execute query: {0}")]
+    ExecutionError(String),
+}
+
+/// Comparison operators of filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    NotEq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Like,
+    In,
+    NotIn,
+    IsNull,
+    IsNotNull,
+}
+
+impl Operator {
+    const NAMES: [&'static str; 11] = [
+        "=",
+        "!=",
+        "<",
+        "<=",
+        ">",
+        ">=",
+        "like",
+        "in",
+        "not_in",
+        "is_null",
+        "is_not_null",
+    ];
+
+    fn parse(op: &str) -> Result<Self, QueryTableError> {
+        match op.trim().to_lowercase().as_str() {
+            "=" | "==" | "eq" => Ok(Operator::Eq),
+            "!=" | "<>" | "ne" => Ok(Operator::NotEq),
+            "<" | "lt" => Ok(Operator::Lt),
+            "<=" | "lte" => Ok(Operator::Lte),
+            ">" | "gt" => Ok(Operator::Gt),
+            ">=" | "gte" => Ok(Operator::Gte),
+            "like" => Ok(Operator::Like),
+            "in" => Ok(Operator::In),
+            "not_in" => Ok(Operator::NotIn),
+            "is_null" => Ok(Operator::IsNull),
+            "is_not_null" => Ok(Operator::IsNotNull),
+            _ => Err(QueryTableError::UnsupportedOperator(op.to_string())),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Operator::Eq => "=",
+            Operator::NotEq => "!=",
+            Operator::Lt => "<",
+            Operator::Lte => "<=",
+            Operator::Gt => ">",
+            Operator::Gte => ">=",
+            Operator::Like => "like",
+            Operator::In => "in",
+            Operator::NotIn => "not_in",
+            Operator::IsNull => "is_null",
+            Operator::IsNotNull => "is_not_null",
+        }
+    }
+
+    fn sql(&self) -> &'static str {
+        match self {
+            Operator::Eq => "=",
+            Operator::NotEq => "!=",
+            Operator::Lt => "<",
+            Operator::Lte => "<=",
+            Operator::Gt => ">",
+            Operator::Gte => ">=",
+            Operator::Like => "LIKE",
+            Operator::In => "IN",
+            Operator::NotIn => "NOT IN",
+            Operator::IsNull => "IS NULL",
+            Operator::IsNotNull => "IS NOT NULL",
+        }
+    }
+}
+
+/// A `column op value` condition
+#[derive(Debug)]
+struct Filter {
+    column: String,
+    op: Operator,
+    value: Option<Value>,
+}
+
+/// Parameters for the query_table tool
+#[derive(Debug)]
+struct QueryTableParams {
+    table: String,
+    /// Columns to return, all when empty
+    columns: Vec<String>,
+    filters: Vec<Filter>,
+    /// Columns to sort by, `true` for descending
+    order_by: Vec<(String, bool)>,
+    /// Maximum number of rows, already capped to [`MAX_LIMIT`]
+    limit: u64,
+}
+
+/// Returns the tool definition for the MCP server
+pub fn tool_definition() -> Tool {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "table": {
+                "type": "string",
+                "description": "Name of the table, as defined in the project"
+            },
+            "columns": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Columns to return. All columns when omitted"
+            },
+            "filters": {
+                "type": "array",
+                "description": "Conditions the rows must all match",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "column": { "type": "string" },
+                        "op": { "type": "string", "enum": Operator::NAMES },
+                        "value": {
+                            "description": "A string, number or boolean. An array for in and not_in. Omitted for is_null and is_not_null"
+                        }
+                    },
+                    "required": ["column", "op"]
+                }
+            },
+            "order_by": {
+                "type": "array",
+                "description": "Columns to sort by",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "column": { "type": "string" },
+                        "direction": { "type": "string", "enum": ["asc", "desc"], "default": "asc" }
+                    },
+                    "required": ["column"]
+                }
+            },
+            "limit": {
+                "type": "number",
+                "description": format!("Maximum number of rows to return (default: {}, capped at {})", DEFAULT_LIMIT, MAX_LIMIT),
+                "minimum": 1,
+                "default": DEFAULT_LIMIT
+            }
+        },
+        "required": ["table"]
+    });
+
+    Tool {
+        name: "query_table".into(),
+        description: Some(
+            "Read rows from a project table without writing SQL: pick columns, filter with column/operator/value conditions, sort, and limit. Use to sample data or look up specific records. Returns the rows as JSON with the generated SQL. Read-only, row count capped.".into()
+        ),
+        input_schema: Arc::new(schema.as_object().unwrap().clone()),
+        annotations: None,
+        execution: None,
+        icons: None,
+        meta: None,
+        output_schema: None,
+        title: Some("Query Table".into()),
+    }
+}
+
+fn string_array(value: Option<&Value>, name: &str) -> Result<Vec<String>, QueryTableError> {
+    let invalid =
+        || QueryTableError::InvalidParameter(format!("{name} must be an array of strings"));
+    match value {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| item.as_str().map(str::to_string).ok_or_else(invalid))
+            .collect(),
+        Some(_) => Err(invalid()),
+    }
+}
+
+fn object_array<'a>(
+    value: Option<&'a Value>,
+    name: &str,
+) -> Result<Vec<&'a Map<String, Value>>, QueryTableError> {
+    let invalid =
+        || QueryTableError::InvalidParameter(format!("{name} must be an array of objects"));
+    match value {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| item.as_object().ok_or_else(invalid))
+            .collect(),
+        Some(_) => Err(invalid()),
+    }
+}
+
+fn required_str<'a>(
+    object: &'a Map<String, Value>,
+    key: &str,
+    context: &str,
+) -> Result<&'a str, QueryTableError> {
+    object
+        .get(key)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| QueryTableError::InvalidParameter(format!("{context}.{key} is required")))
+}
+
+/// Parse and validate parameters from MCP arguments
+fn parse_params(
+    arguments: Option<&Map<String, Value>>,
+) -> Result<QueryTableParams, QueryTableError> {
+    let args = arguments
+        .ok_or_else(|| QueryTableError::InvalidParameter("No arguments provided".to_string()))?;
+
+    let table = args
+        .get("table")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| {
+            QueryTableError::InvalidParameter("table parameter is required".to_string())
+        })?
+        .trim()
+        .to_string();
+
+    let columns = string_array(args.get("columns"), "columns")?;
+
+    let filters = object_array(args.get("filters"), "filters")?
+        .into_iter()
+        .map(|filter| {
+            Ok(Filter {
+                column: required_str(filter, "column", "filters")?.to_string(),
+                op: Operator::parse(required_str(filter, "op", "filters")?)?,
+                value: filter.get("value").cloned(),
+            })
+        })
+        .collect::<Result<_, QueryTableError>>()?;
+
+    let order_by = object_array(args.get("order_by"), "order_by")?
+        .into_iter()
+        .map(|order| {
+            let column = required_str(order, "column", "order_by")?.to_string();
+            let descending = match order.get("direction").and_then(|v| v.as_str()) {
+                None => false,
+                Some(direction) => match direction.to_lowercase().as_str() {
+                    "asc" => false,
+                    "desc" => true,
+                    _ => {
+                        return Err(QueryTableError::InvalidParameter(format!(
+                            "order_by.direction must be 'asc' or 'desc', got '{}'",
+                            direction
+                        )))
+                    }
+                },
+            };
+            Ok((column, descending))
+        })
+        .collect::<Result<_, QueryTableError>>()?;
+
+    let limit = match args.get("limit") {
+        None | Some(Value::Null) => DEFAULT_LIMIT,
+        Some(value) => match value.as_u64() {
+            Some(limit) if limit >= 1 => limit.min(MAX_LIMIT),
+            _ => {
+                return Err(QueryTableError::InvalidParameter(format!(
+                    "limit must be a positive integer, got {}",
+                    value
+                )))
+            }
+        },
+    };
+
+    Ok(QueryTableParams {
+        table,
+        columns,
+        filters,
+        order_by,
+        limit,
+    })
+}
+
+fn compile_filter(filter: &Filter) -> Result<String, QueryTableError> {
+    let invalid = |reason| QueryTableError::InvalidValue {
+        column: filter.column.clone(),
+        op: filter.op.name(),
+        reason,
+    };
+    let column = quote_ident(&filter.column);
+    // A SQL literal for a JSON string, number or boolean
+    let literal = |value: &Value| match value {
+        Value::String(s) => Some(quote_string(s)),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    };
+
+    let operand = match (filter.op, &filter.value) {
+        (Operator::IsNull | Operator::IsNotNull, None | Some(Value::Null)) => None,
+        (Operator::IsNull | Operator::IsNotNull, Some(_)) => {
+            return Err(invalid("takes no value"));
+        }
+        (Operator::In | Operator::NotIn, Some(Value::Array(values))) => {
+            if values.is_empty() {
+                return Err(invalid("expects a non-empty array"));
+            }
+            let values = values
+                .iter()
+                .map(literal)
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| invalid("expects an array of strings, numbers or booleans"))?;
+            Some(format!("({})", values.join(", ")))
+        }
+        (Operator::In | Operator::NotIn, _) => {
+            return Err(invalid("expects an array of strings, numbers or booleans"));
+        }
+        (Operator::Like, Some(value @ Value::String(_))) => literal(value),
+        (Operator::Like, _) => return Err(invalid("expects a string pattern")),
+        (_, None | Some(Value::Null)) => {
+            return Err(invalid(
+                "expects a value, use is_null or is_not_null to match NULL",
+            ));
+        }
+        (_, Some(value)) => {
+            Some(literal(value).ok_or_else(|| invalid("expects a string, number or boolean"))?)
+        }
+    };
+
+    Ok(match operand {
+        Some(operand) => format!("{} {} {}", column, filter.op.sql(), operand),
+        None => format!("{} {}", column, filter.op.sql()),
+    })
+}
+
+/// Compiles `params` into a SELECT on `table`, checking every column
+/// against the table's columns.
+fn compile_query(
+    params: &QueryTableParams,
+    table: &Table,
+    default_database: &str,
+) -> Result<String, QueryTableError> {
+    let known: Vec<String> = table.columns.iter().map(|c| c.name.clone()).collect();
+    let check = |column: &str| {
+        if known.iter().any(|known| known == column) {
+            Ok(())
+        } else {
+            Err(QueryTableError::UnknownColumn {
+                column: column.to_string(),
+                table: table.name.clone(),
+                available: known.clone(),
+            })
+        }
+    };
+
+    let columns = if params.columns.is_empty() {
+        "*".to_string()
+    } else {
+        params
+            .columns
+            .iter()
+            .map(|column| check(column).map(|_| quote_ident(column)))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(", ")
+    };
+
+    let mut sql = format!(
+        "SELECT {} FROM {}",
+        columns,
+        quote_qualified(
+            table.database.as_deref().unwrap_or(default_database),
+            &table.name
+        )
+    );
+
+    if !params.filters.is_empty() {
+        let conditions = params
+            .filters
+            .iter()
+            .map(|filter| {
+                check(&filter.column)?;
+                compile_filter(filter)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+
+    if !params.order_by.is_empty() {
+        let order = params
+            .order_by
+            .iter()
+            .map(|(column, descending)| {
+                check(column)?;
+                Ok(format!(
+                    "{} {}",
+                    quote_ident(column),
+                    if *descending { "DESC" } else { "ASC" }
+                ))
+            })
+            .collect::<Result<Vec<_>, QueryTableError>>()?;
+        sql.push_str(" ORDER BY ");
+        sql.push_str(&order.join(", "));
+    }
+
+    sql.push_str(&format!(" LIMIT {}", params.limit));
+    Ok(sql)
+}
+
+/// Resolves the table of `params` in `infra_map` and compiles the query.
+fn prepare_query(
+    params: &QueryTableParams,
+    infra_map: &InfrastructureMap,
+    default_database: &str,
+) -> Result<String, QueryTableError> {
+    let table = infra_map.find_table_by_name(&params.table).ok_or_else(|| {
+        QueryTableError::UnknownTable {
+            name: params.table.clone(),
+            available: infra_map
+                .tables
+                .values()
+                .map(|table| table.name.clone())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        }
+    })?;
+    compile_query(params, table, default_database)
+}
+
+async fn execute_query(config: &ClickHouseConfig, sql: &str) -> Result<Value, QueryTableError> {
+    let client = ClickHouseClient::new(config)
+        .map_err(|e| QueryTableError::ExecutionError(format!("Failed to create client: {}", e)))?;
+
+    debug!("Executing table query: {}", sql);
+    let result = tokio::time::timeout(
+        QUERY_TIMEOUT,
+        client.execute_sql_with_database(&format!("{} FORMAT JSON", sql), Some(&config.db_name)),
+    )
+    .await
+    .map_err(|_| {
+        QueryTableError::ExecutionError(format!(
+            "Query timeout after {} seconds",
+            QUERY_TIMEOUT.as_secs()
+        ))
+    })?
+    .map_err(|e| QueryTableError::ExecutionError(format!("Query failed: {}", e)))?;
+
+    let mut response: Value = serde_json::from_str(&result).map_err(|e| {
+        QueryTableError::ExecutionError(format!("Failed to parse ClickHouse response: {}", e))
+    })?;
+    Ok(response
+        .get_mut("data")
+        .map(Value::take)
+        .unwrap_or_else(|| Value::Array(Vec::new())))
+}
+
+/// Handle the MCP tool call with the given arguments
+pub async fn handle_call(
+    arguments: Option<&Map<String, Value>>,
+    redis_client: Arc<RedisClient>,
+    clickhouse_config: &ClickHouseConfig,
+) -> CallToolResult {
+    let params = match parse_params(arguments) {
+        Ok(params) => params,
+        Err(e) => return create_error_result(format!("Parameter validation error: {}", e)),
+    };
+
+    let infra_map = match InfrastructureMap::load_from_redis(&redis_client).await {
+        Ok(Some(infra_map)) => infra_map,
+        Ok(None) => {
+            return create_error_result(
+                "No infrastructure map found. The dev server may not be running.".to_string(),
+            )
+        }
+        Err(e) => return create_error_result(format!("Failed to load infrastructure map: {}", e)),
+    };
+
+    let sql = match prepare_query(&params, &infra_map, &clickhouse_config.db_name) {
+        Ok(sql) => sql,
+        Err(e) => return create_error_result(format!("Parameter validation error: {}", e)),
+    };
+    info!(
+        "Querying table {} with limit {}",
+        params.table, params.limit
+    );
+
+    match execute_query(clickhouse_config, &sql).await {
+        Ok(rows) => {
+            let row_count = rows.as_array().map_or(0, Vec::len);
+            let output = json!({
+                "sql": sql,
+                "row_count": row_count,
+                "limit": params.limit,
+                "rows": rows,
+            });
+            match serde_json::to_string_pretty(&output) {
+                Ok(json) => create_success_result(json),
+                Err(e) => create_error_result(format!("Failed to convert output to JSON: {}", e)),
+            }
+        }
+        Err(e) => create_error_result(format!("{}\nSQL: {}", e, sql)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::core::infrastructure::table::{Column, ColumnType, OrderBy};
+    use crate::framework::core::infrastructure_map::{PrimitiveSignature, PrimitiveTypes};
+    use crate::framework::core::partial_infrastructure_map::LifeCycle;
+    use crate::infrastructure::olap::clickhouse::queries::ClickhouseEngine;
+
+    fn column(name: &str) -> Column {
+        Column {
+            name: name.to_string(),
+            data_type: ColumnType::String,
+            required: true,
+            unique: false,
+            primary_key: false,
+            default: None,
+            annotations: vec![],
+            comment: None,
+            ttl: None,
+            codec: None,
+            materialized: None,
+            alias: None,
+        }
+    }
+
+    fn infra_map() -> InfrastructureMap {
+        let table = Table {
+            name: "events".to_string(),
+            columns: vec![column("id"), column("user_id"), column("ts")],
+            order_by: OrderBy::Fields(vec!["id".to_string()]),
+            partition_by: None,
+            sample_by: None,
+            engine: ClickhouseEngine::MergeTree,
+            version: None,
+            source_primitive: PrimitiveSignature {
+                name: "events".to_string(),
+                primitive_type: PrimitiveTypes::DataModel,
+            },
+            metadata: None,
+            life_cycle: LifeCycle::FullyManaged,
+            engine_params_hash: None,
+            table_settings_hash: None,
+            table_settings: None,
+            indexes: vec![],
+            projections: vec![],
+            database: None,
+            table_ttl_setting: None,
+            cluster_name: None,
+            primary_key_expression: None,
+            seed_filter: Default::default(),
+            extensions: Default::default(),
+        };
+        let mut infra_map = InfrastructureMap::default();
+        infra_map.tables.insert("events".to_string(), table);
+        infra_map
+    }
+
+    fn compile(arguments: Value) -> Result<String, QueryTableError> {
+        let params = parse_params(arguments.as_object())?;
+        prepare_query(&params, &infra_map(), "local")
+    }
+
+    #[test]
+    fn test_compile_filters() {
+        let sql = compile(json!({
+            "table": "events",
+            "columns": ["id", "ts"],
+            "filters": [
+                { "column": "user_id", "op": "=", "value": "o'brien\\" },
+                { "column": "ts", "op": ">=", "value": 1700000000 },
+                { "column": "id", "op": "in", "value": ["a", "b"] },
+                { "column": "user_id", "op": "is_not_null" },
+                { "column": "id", "op": "LIKE", "value": "evt_%" }
+            ],
+            "order_by": [{ "column": "ts", "direction": "desc" }, { "column": "id" }],
+            "limit": 10
+        }))
+        .unwrap();
+        assert_eq!(
+            sql,
+            "SELECT `id`, `ts` FROM `local`.`events` \
+             WHERE `user_id` = 'o\\'brien\\\\' AND `ts` >= 1700000000 AND `id` IN ('a', 'b') \
+             AND `user_id` IS NOT NULL AND `id` LIKE 'evt_%' \
+             ORDER BY `ts` DESC, `id` ASC LIMIT 10"
+        );
+
+        assert_eq!(
+            compile(json!({ "table": "events" })).unwrap(),
+            "SELECT * FROM `local`.`events` LIMIT 100"
+        );
+
+        let params = parse_params(json!({ "table": "events" }).as_object()).unwrap();
+        assert_eq!(
+            prepare_query(&params, &infra_map(), "back\\slash`db").unwrap(),
+            "SELECT * FROM `back\\\\slash``db`.`events` LIMIT 100"
+        );
+    }
+
+    #[test]
+    fn test_limit_is_capped() {
+        let sql = compile(json!({ "table": "events", "limit": 1_000_000 })).unwrap();
+        assert!(sql.ends_with(" LIMIT 1000"), "{sql}");
+
+        for limit in [json!(0), json!(-5), json!("10")] {
+            assert!(matches!(
+                compile(json!({ "table": "events", "limit": limit })),
+                Err(QueryTableError::InvalidParameter(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_rejects_unknown_columns() {
+        for arguments in [
+            json!({ "table": "events", "columns": ["id", "password"] }),
+            json!({ "table": "events", "filters": [{ "column": "password", "op": "=", "value": 1 }] }),
+            json!({ "table": "events", "order_by": [{ "column": "1; DROP TABLE events" }] }),
+        ] {
+            let err = compile(arguments).unwrap_err();
+            assert!(
+                matches!(err, QueryTableError::UnknownColumn { .. }),
+                "{err}"
+            );
+            assert!(
+                err.to_string()
+                    .contains("Available columns: id, user_id, ts"),
+                "{err}"
+            );
+        }
+
+        let err = compile(json!({ "table": "users" })).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Table 'users' not found in the infrastructure map. Available tables: events"
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_operators_and_values() {
+        let err = compile(json!({
+            "table": "events",
+            "filters": [{ "column": "id", "op": "= 1 OR 1 =", "value": 1 }]
+        }))
+        .unwrap_err();
+        assert!(matches!(err, QueryTableError::UnsupportedOperator(_)));
+        assert!(err.to_string().contains("Supported operators: =, !="));
+
+        for (op, value) in [
+            ("=", json!(null)),
+            ("=", json!({ "nested": true })),
+            ("in", json!("a")),
+            ("in", json!([])),
+            ("in", json!([["a"]])),
+            ("like", json!(5)),
+            ("is_null", json!("a")),
+        ] {
+            let result = compile(json!({
+                "table": "events",
+                "filters": [{ "column": "id", "op": op, "value": value }]
+            }));
+            assert!(
+                matches!(result, Err(QueryTableError::InvalidValue { .. })),
+                "{op} {value}: {result:?}"
+            );
+        }
+
+        assert!(matches!(
+            compile(
+                json!({ "table": "events", "order_by": [{ "column": "id", "direction": "up" }] })
+            ),
+            Err(QueryTableError::InvalidParameter(_))
+        ));
+    }
+}
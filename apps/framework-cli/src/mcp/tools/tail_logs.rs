@@ -0,0 +1,362 @@
+//! # Tail Logs Tool
+//!
+//! This module implements the MCP tool returning the most recent entries of
+//! the Moose log file as structured entries, filtered like `moose logs`.
+//! Only the end of the file is read. The values of the secret fields of the
+//! project configuration are redacted, and the response size is capped.
+
+use chrono::Utc;
+use rmcp::model::{CallToolResult, Tool};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::logs::get_log_file_path;
+use super::{create_error_result, create_success_result};
+use crate::cli::routines::logs::{parse_since, tail_records, LogLevel, LogQuery, LogQueryError};
+use crate::project::config_interpolation::REDACTED;
+use crate::project::config_secrets::secret_values;
+use crate::project::Project;
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+/// How much of the end of the log file is read
+const MAX_READ_BYTES: u64 = 8 * 1024 * 1024;
+/// Longer messages are cut
+const MAX_MESSAGE_CHARS: usize = 4000;
+/// Oldest entries are dropped until the entries fit
+const MAX_RESPONSE_BYTES: usize = 256 * 1024;
+
+/// Error types for the tail_logs tool
+#[derive(Debug, thiserror::Error)]
+pub enum TailLogsError {
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
+
+    #[error(transparent)]
+    Query(#[from] LogQueryError),
+
+    #[error("Failed to read log file: {0}")]
+    FileRead(#[from] std::io::Error),
+}
+
+/// A log entry as returned by the tool
+#[derive(Debug, Serialize)]
+struct TailEntry {
+    timestamp: String,
+    level: &'static str,
+    source: Option<String>,
+    message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TailOutput {
+    entries: Vec<TailEntry>,
+    /// Whether entries were dropped or messages cut to fit the response
+    truncated: bool,
+}
+
+/// Returns the tool definition for the MCP server
+pub fn tool_definition() -> Tool {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "level": {
+                "type": "string",
+                "description": "Minimum level of the entries",
+                "enum": ["error", "warn", "info", "debug", "trace"]
+            },
+            "source": {
+                "type": "string",
+                "description": "Only entries logged by modules whose path contains this, such as 'clickhouse' or 'kafka'"
+            },
+            "limit": {
+                "type": "number",
+                "description": format!("Number of most recent entries to return (default: {}, max: {})", DEFAULT_LIMIT, MAX_LIMIT),
+                "minimum": 1,
+                "maximum": MAX_LIMIT
+            },
+            "since": {
+                "type": "string",
+                "description": "Only entries since then, a duration such as '15m' or '1h', or an RFC 3339 timestamp"
+            }
+        }
+    });
+
+    Tool {
+        name: "tail_logs".into(),
+        description: Some(
+            "Get the most recent Moose log entries as structured JSON (timestamp, level, source module, message). Filter by minimum level, source module and time. Use to see what the process logged while debugging. Secrets are redacted.".into()
+        ),
+        input_schema: Arc::new(schema.as_object().unwrap().clone()),
+        annotations: None,
+        execution: None,
+        icons: None,
+        meta: None,
+        output_schema: None,
+        title: Some("Tail Logs".into()),
+    }
+}
+
+fn str_param<'a>(
+    arguments: Option<&'a Map<String, Value>>,
+    name: &str,
+) -> Result<Option<&'a str>, TailLogsError> {
+    match arguments.and_then(|args| args.get(name)) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.as_str())),
+        Some(_) => Err(TailLogsError::InvalidParameter(format!(
+            "{name} must be a string"
+        ))),
+    }
+}
+
+/// Parse and validate parameters from MCP arguments
+fn parse_params(
+    arguments: Option<&Map<String, Value>>,
+) -> Result<(LogQuery, usize), TailLogsError> {
+    let query = LogQuery {
+        level: str_param(arguments, "level")?
+            .map(str::parse::<LogLevel>)
+            .transpose()?,
+        pattern: None,
+        since: str_param(arguments, "since")?
+            .map(|since| parse_since(since, Utc::now()))
+            .transpose()?,
+        source: str_param(arguments, "source")?.map(str::to_string),
+    };
+
+    let limit = match arguments.and_then(|args| args.get("limit")) {
+        None | Some(Value::Null) => DEFAULT_LIMIT,
+        Some(value) => match value.as_u64() {
+            Some(limit) if (1..=MAX_LIMIT as u64).contains(&limit) => limit as usize,
+            _ => {
+                return Err(TailLogsError::InvalidParameter(format!(
+                    "limit must be between 1 and {}, got {}",
+                    MAX_LIMIT, value
+                )))
+            }
+        },
+    };
+
+    Ok((query, limit))
+}
+
+/// Reads the lines of the last [`MAX_READ_BYTES`] of the file at `path`.
+fn read_tail(path: &Path) -> Result<Vec<String>, TailLogsError> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(MAX_READ_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+
+    let mut reader = BufReader::new(file);
+    if start > 0 {
+        // Skip the line cut by the start of the read
+        reader.read_until(b'\n', &mut Vec::new())?;
+    }
+
+    let mut lines = Vec::new();
+    let mut buf = Vec::new();
+    while reader.read_until(b'\n', &mut buf)? > 0 {
+        lines.push(
+            String::from_utf8_lossy(&buf)
+                .trim_end_matches(['\n', '\r'])
+                .to_string(),
+        );
+        buf.clear();
+    }
+    Ok(lines)
+}
+
+/// The last `limit` entries of `lines` matching `query`, with `secrets`
+/// redacted and capped to [`MAX_RESPONSE_BYTES`].
+fn tail_entries(
+    lines: Vec<String>,
+    query: &LogQuery,
+    limit: usize,
+    mut secrets: Vec<String>,
+) -> TailOutput {
+    // Longest first so a secret containing another one is redacted whole
+    secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+
+    let mut truncated = false;
+    let mut entries: Vec<TailEntry> = tail_records(lines, query, limit)
+        .into_iter()
+        .map(|record| {
+            let mut message = secrets.iter().fold(record.message, |message, secret| {
+                message.replace(secret.as_str(), REDACTED)
+            });
+            if let Some((cut, _)) = message.char_indices().nth(MAX_MESSAGE_CHARS) {
+                message.truncate(cut);
+                message.push_str("… [truncated]");
+                truncated = true;
+            }
+            TailEntry {
+                timestamp: record.timestamp.to_rfc3339(),
+                level: record.level.as_str(),
+                source: record.target,
+                message,
+            }
+        })
+        .collect();
+
+    // Keep the most recent entries that fit
+    let mut size = 0;
+    let kept = entries
+        .iter()
+        .rev()
+        .take_while(|entry| {
+            // Each entry is followed by a separator
+            size += serde_json::to_string(entry).map_or(0, |json| json.len()) + 1;
+            size <= MAX_RESPONSE_BYTES
+        })
+        .count();
+    if kept < entries.len() {
+        entries.drain(..entries.len() - kept);
+        truncated = true;
+    }
+
+    TailOutput { entries, truncated }
+}
+
+/// Handle the MCP tool call with the given arguments
+pub fn handle_call(arguments: Option<&Map<String, Value>>, project: &Project) -> CallToolResult {
+    let (query, limit) = match parse_params(arguments) {
+        Ok(params) => params,
+        Err(e) => return create_error_result(format!("Parameter validation error: {}", e)),
+    };
+
+    let log_file_path = match get_log_file_path() {
+        Ok(path) => path,
+        Err(e) => return create_error_result(format!("Failed to locate log file: {}", e)),
+    };
+    if !log_file_path.exists() {
+        return create_error_result(format!(
+            "No log file found at {}. Moose may not be running or no logs have been written yet.",
+            log_file_path.display()
+        ));
+    }
+
+    let lines = match read_tail(&log_file_path) {
+        Ok(lines) => lines,
+        Err(e) => return create_error_result(e.to_string()),
+    };
+
+    let output = tail_entries(lines, &query, limit, secret_values(project));
+    match serde_json::to_string_pretty(&output) {
+        Ok(json) => create_success_result(json),
+        Err(e) => create_error_result(format!("Failed to convert output to JSON: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const FIXTURE: &str = include_str!("../../../tests/fixtures/moose-cli.log");
+
+    fn lines() -> Vec<String> {
+        FIXTURE.lines().map(String::from).collect()
+    }
+
+    fn query(arguments: Value) -> (LogQuery, usize) {
+        parse_params(arguments.as_object()).unwrap()
+    }
+
+    #[test]
+    fn test_filters() {
+        let (query_all, limit) = query(json!({}));
+        assert_eq!(limit, DEFAULT_LIMIT);
+        assert_eq!(
+            tail_entries(lines(), &query_all, limit, vec![])
+                .entries
+                .len(),
+            7
+        );
+
+        let (warn, limit) = query(json!({ "level": "warn", "source": "clickhouse" }));
+        let output = tail_entries(lines(), &warn, limit, vec![]);
+        let levels: Vec<_> = output.entries.iter().map(|e| e.level).collect();
+        assert_eq!(levels, vec!["WARN", "ERROR"]);
+        assert_eq!(
+            output.entries[1].source.as_deref(),
+            Some("moose_cli::infrastructure::olap::clickhouse")
+        );
+        assert!(output.entries[1]
+            .message
+            .ends_with("Table events does not exist"));
+
+        let (since, _) = query(json!({ "since": "2025-01-15T11:00:00Z" }));
+        let output = tail_entries(lines(), &since, 2, vec![]);
+        assert_eq!(output.entries.len(), 2);
+        assert_eq!(output.entries[1].message, "Ingest request handled");
+        assert!(!output.truncated);
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        for arguments in [
+            json!({ "level": "loud" }),
+            json!({ "since": "yesterday-ish" }),
+            json!({ "limit": 0 }),
+            json!({ "limit": MAX_LIMIT + 1 }),
+            json!({ "source": 5 }),
+        ] {
+            assert!(parse_params(arguments.as_object()).is_err(), "{arguments}");
+        }
+    }
+
+    #[test]
+    fn test_secrets_are_redacted() {
+        let lines = vec![
+            "2025-01-15T08:00:00.000000Z  INFO moose_cli::cli: Connecting with password tail-logs-s3cr3t".to_string(),
+            "  token=tail-logs-s3cr3t".to_string(),
+        ];
+        let output = tail_entries(
+            lines,
+            &LogQuery::default(),
+            10,
+            vec!["s3cr3t".to_string(), "tail-logs-s3cr3t".to_string()],
+        );
+        assert_eq!(
+            output.entries[0].message,
+            "Connecting with password [REDACTED]\n  token=[REDACTED]"
+        );
+    }
+
+    #[test]
+    fn test_response_is_capped() {
+        let long = "x".repeat(MAX_MESSAGE_CHARS * 2);
+        let lines: Vec<String> = (0..200)
+            .map(|i| format!("2025-01-15T08:00:00.000000Z  INFO moose_cli::cli: {i} {long}"))
+            .collect();
+        let output = tail_entries(lines, &LogQuery::default(), MAX_LIMIT, vec![]);
+
+        assert!(output.truncated);
+        assert!(serde_json::to_string(&output.entries).unwrap().len() <= MAX_RESPONSE_BYTES + 2);
+        assert!(output.entries[0].message.ends_with("… [truncated]"));
+        assert!(output.entries.last().unwrap().message.starts_with("199 "));
+    }
+
+    #[test]
+    fn test_read_tail_skips_cut_line() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let filler = "y".repeat(MAX_READ_BYTES as usize);
+        writeln!(file, "{filler}").unwrap();
+        writeln!(
+            file,
+            "2025-01-15T08:00:00.000000Z  INFO moose_cli::cli: last"
+        )
+        .unwrap();
+
+        let lines = read_tail(file.path()).unwrap();
+        assert_eq!(
+            lines,
+            vec!["2025-01-15T08:00:00.000000Z  INFO moose_cli::cli: last"]
+        );
+    }
+}
@@ -12,8 +12,12 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 
+use crate::cli::settings::{user_directory, MetricSpillSettings};
+use crate::framework::consumption::api_metrics;
+use crate::infrastructure::olap::clickhouse::{migration_metrics, sync_metrics};
 use crate::infrastructure::redis::redis_client::RedisClient;
-use crate::metrics_inserter::MetricsInserter;
+use crate::infrastructure::redis::{health, sentinel};
+use crate::metrics_inserter::{MetricsInserter, PipelineHealthSnapshot};
 use crate::utilities::decode_object;
 use chrono::{DateTime, Utc};
 use tracing::trace;
@@ -31,6 +35,20 @@ pub const STREAMING_FUNCTION_EVENT_OUPUT_COUNT: &str =
     "moose_streaming_functions_events_output_count";
 pub const STREAMING_FUNCTION_PROCESSED_BYTE_COUNT: &str =
     "moose_streaming_functions_processed_byte_count";
+pub const REDIS_FAILOVER_COUNT: &str = "moose_redis_failover_count";
+pub const REDIS_CONNECTED: &str = "moose_redis_connected";
+pub const REDIS_RECONNECT_COUNT: &str = "moose_redis_reconnect_count";
+pub const MIGRATION_OPERATION_DURATION: &str = "moose_migration_operation_duration_seconds";
+pub const MIGRATION_OPERATIONS: &str = "moose_migration_operations";
+pub const MIGRATION_PLAN_DURATION: &str = "moose_migration_plan_duration_seconds";
+pub const MIGRATION_PLAN_OPERATIONS: &str = "moose_migration_plan_operations";
+pub const SYNC_LATENCY: &str = "moose_sync_latency_seconds";
+pub const SYNC_BATCH_SIZE: &str = "moose_sync_batch_size";
+pub const SYNC_FLUSH_DURATION: &str = "moose_sync_flush_duration_seconds";
+pub const API_LATENCY: &str = "moose_api_latency_seconds";
+pub const API_QUERY_DURATION: &str = "moose_api_query_duration_seconds";
+pub const API_HANDLER_DURATION: &str = "moose_api_handler_duration_seconds";
+pub const API_OVER_BUDGET: &str = "moose_api_over_latency_budget";
 
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
@@ -85,6 +103,7 @@ pub struct TelemetryMetadata {
     pub is_production: bool,
     pub project_name: String,
     pub export_metrics: bool,
+    pub metric_spill: MetricSpillSettings,
 }
 
 #[derive(Clone)]
@@ -171,7 +190,18 @@ impl Metrics {
         let metrics = Metrics {
             tx_events,
             telemetry_metadata: telemetry_metadata.clone(),
-            metrics_inserter: MetricsInserter::new(metric_labels, metric_endpoints, redis_client),
+            metrics_inserter: MetricsInserter::new(
+                metric_labels,
+                metric_endpoints,
+                redis_client,
+                &telemetry_metadata.metric_spill,
+                user_directory().ok().map(|dir| {
+                    dir.join(format!(
+                        "metrics-spill-{}.jsonl",
+                        telemetry_metadata.project_name
+                    ))
+                }),
+            ),
             registry: Arc::new(Mutex::new(Registry::default())),
         };
         (metrics, rx_events)
@@ -181,6 +211,15 @@ impl Metrics {
         let _ = self.tx_events.send(data).await;
     }
 
+    pub fn telemetry_metadata(&self) -> &TelemetryMetadata {
+        &self.telemetry_metadata
+    }
+
+    /// Health of the pipeline sending metric events to their sink.
+    pub fn pipeline_health(&self) -> PipelineHealthSnapshot {
+        self.metrics_inserter.health()
+    }
+
     pub async fn get_metrics_registry_as_string(&self) -> String {
         let registry = self.registry.lock().await;
         formatted_registry(&registry)
@@ -305,6 +344,76 @@ impl Metrics {
             "Bytes sent from one data model to another using kafka stream",
             data.streaming_functions_processed_bytes_count.clone(),
         );
+        registry.register(
+            REDIS_FAILOVER_COUNT,
+            "Redis master changes followed through Sentinel",
+            sentinel::REDIS_FAILOVER_COUNT.clone(),
+        );
+        registry.register(
+            REDIS_CONNECTED,
+            "Whether the Redis connection is up (1) or down (0)",
+            health::REDIS_CONNECTED.clone(),
+        );
+        registry.register(
+            REDIS_RECONNECT_COUNT,
+            "Reconnections to Redis after the connection was lost",
+            health::REDIS_RECONNECT_COUNT.clone(),
+        );
+        registry.register(
+            MIGRATION_OPERATION_DURATION,
+            "Duration of OLAP migration operations by kind, table and outcome",
+            migration_metrics::MIGRATION_OPERATION_DURATION.clone(),
+        );
+        registry.register(
+            MIGRATION_OPERATIONS,
+            "OLAP migration operations by kind, table and outcome",
+            migration_metrics::MIGRATION_OPERATIONS.clone(),
+        );
+        registry.register(
+            MIGRATION_PLAN_DURATION,
+            "Duration of the last OLAP migration plan",
+            migration_metrics::MIGRATION_PLAN_DURATION.clone(),
+        );
+        registry.register(
+            MIGRATION_PLAN_OPERATIONS,
+            "Operations in the last OLAP migration plan",
+            migration_metrics::MIGRATION_PLAN_OPERATIONS.clone(),
+        );
+        registry.register(
+            SYNC_LATENCY,
+            "Time from the Kafka message timestamp to the end of its insert, by topic and table",
+            sync_metrics::SYNC_LATENCY.clone(),
+        );
+        registry.register(
+            SYNC_BATCH_SIZE,
+            "Records per batch inserted by the topic to table syncs",
+            sync_metrics::SYNC_BATCH_SIZE.clone(),
+        );
+        registry.register(
+            SYNC_FLUSH_DURATION,
+            "Duration of the batch inserts of the topic to table syncs",
+            sync_metrics::SYNC_FLUSH_DURATION.clone(),
+        );
+        registry.register(
+            API_LATENCY,
+            "Latency of the analytics API requests by API",
+            api_metrics::API_LATENCY.clone(),
+        );
+        registry.register(
+            API_QUERY_DURATION,
+            "Time analytics API requests spent in ClickHouse queries by API",
+            api_metrics::API_QUERY_DURATION.clone(),
+        );
+        registry.register(
+            API_HANDLER_DURATION,
+            "Time analytics API requests spent outside of ClickHouse queries by API",
+            api_metrics::API_HANDLER_DURATION.clone(),
+        );
+        registry.register(
+            API_OVER_BUDGET,
+            "Whether the p95 latency of an analytics API is over its budget (1) or not (0)",
+            api_metrics::API_OVER_BUDGET.clone(),
+        );
 
         let metrics_inserter = self.metrics_inserter.clone();
         let export_metrics = self.telemetry_metadata.export_metrics;
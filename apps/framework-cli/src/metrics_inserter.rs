@@ -1,21 +1,41 @@
+//! Batches metric events and sends them to the metrics sink, Redis with
+//! `metrics_v2` or the configured HTTP endpoints otherwise.
+//!
+//! Failed sends are tracked in [`PipelineHealth`], shown in the `metrics_pipeline`
+//! section of `/health`. After `failure_threshold` consecutive failures the
+//! batches are spilled to a local file, capped at `max_bytes` by evicting the
+//! oldest batches, and replayed in order once the sink accepts them again.
+
+use crate::cli::settings::MetricSpillSettings;
 use crate::infrastructure::redis::redis_client::RedisClient;
 use crate::metrics::MetricEvent;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
-use tokio::time;
-use tracing::error;
+use tokio::time::{self, Instant};
+use tracing::{error, info, warn};
 
 const MAX_FLUSH_INTERVAL_SECONDS: u64 = 10;
 const MAX_BATCH_SIZE: usize = 1000;
 
+/// Minimum time between two warnings about the sink failing.
+const FAILURE_WARNING_INTERVAL: Duration = Duration::from_secs(60);
+
 pub type BatchEvents = Arc<Mutex<Vec<MetricEvent>>>;
 
 #[derive(Clone)]
 pub struct MetricsInserter {
     buffer: BatchEvents,
+    health: PipelineHealth,
 }
 
 impl MetricsInserter {
@@ -23,17 +43,39 @@ impl MetricsInserter {
         metric_labels: Option<serde_json::Map<String, serde_json::Value>>,
         metric_endpoints: Option<serde_json::Map<String, serde_json::Value>>,
         redis_client: Option<Arc<RedisClient>>,
+        spill_settings: &MetricSpillSettings,
+        spill_path: Option<PathBuf>,
     ) -> Self {
         let buffer = Arc::new(Mutex::new(Vec::new()));
+        let health = PipelineHealth::default();
+
+        let sink: Box<dyn MetricsSink> = match redis_client {
+            Some(redis_client) => Box::new(RedisSink(redis_client)),
+            None => Box::new(HttpSink(Client::new())),
+        };
+        let pipeline = Pipeline {
+            sink,
+            health: health.clone(),
+            spill: spill_path.map(|path| SpillFile {
+                path,
+                max_bytes: spill_settings.max_bytes,
+            }),
+            failure_threshold: spill_settings.failure_threshold,
+        };
+        // Batches spilled by a previous run are replayed on the first flush
+        if let Some(Ok(lines)) = pipeline.spill.as_ref().map(SpillFile::load) {
+            let bytes = lines.iter().map(|line| line.len() as u64 + 1).sum();
+            health.set_spilled(lines.len(), bytes);
+        }
 
         tokio::spawn(flush(
             buffer.clone(),
-            metric_labels.clone(),
-            metric_endpoints.clone(),
-            redis_client.clone(),
+            metric_labels,
+            metric_endpoints,
+            pipeline,
         ));
 
-        Self { buffer }
+        Self { buffer, health }
     }
 
     pub async fn insert(&self, event: MetricEvent) -> anyhow::Result<()> {
@@ -41,143 +83,621 @@ impl MetricsInserter {
         buffer.push(event);
         Ok(())
     }
+
+    pub fn health(&self) -> PipelineHealthSnapshot {
+        self.health.snapshot()
+    }
+}
+
+/// Events of one type, sent together to their route.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Batch {
+    #[serde(rename = "type")]
+    event_type: String,
+    route: String,
+    events: Vec<serde_json::Value>,
+}
+
+/// Where metric batches are sent.
+#[async_trait]
+trait MetricsSink: Send + Sync {
+    async fn send(&self, batch: &Batch) -> anyhow::Result<()>;
+}
+
+struct RedisSink(Arc<RedisClient>);
+
+#[async_trait]
+impl MetricsSink for RedisSink {
+    async fn send(&self, batch: &Batch) -> anyhow::Result<()> {
+        let message = json!({
+            "type": batch.event_type,
+            "events": batch.events
+        });
+        self.0
+            .post_queue_message(&serde_json::to_string(&message)?, Some("metrics"))
+            .await
+    }
+}
+
+struct HttpSink(Client);
+
+#[async_trait]
+impl MetricsSink for HttpSink {
+    async fn send(&self, batch: &Batch) -> anyhow::Result<()> {
+        self.0
+            .post(&batch.route)
+            .json(&batch.events)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// The pipeline health at one point in time, as shown by `/health`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PipelineHealthSnapshot {
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    /// Batches waiting in the spill file for the sink to recover
+    pub spilled_batches: usize,
+    pub spill_bytes: u64,
+}
+
+/// Health of the metrics pipeline shared with the flush task.
+#[derive(Clone, Default)]
+pub struct PipelineHealth {
+    state: Arc<std::sync::Mutex<HealthState>>,
+}
+
+#[derive(Default)]
+struct HealthState {
+    consecutive_failures: u32,
+    last_success: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    last_warning: Option<Instant>,
+    spilled_batches: usize,
+    spill_bytes: u64,
+}
+
+impl PipelineHealth {
+    fn record_success(&self, now: DateTime<Utc>) {
+        let mut state = self.lock();
+        if state.consecutive_failures > 0 {
+            info!(
+                "Metrics sink recovered after {} failed sends",
+                state.consecutive_failures
+            );
+        }
+        state.consecutive_failures = 0;
+        state.last_success = Some(now);
+        state.last_warning = None;
+    }
+
+    /// Records a failed send, warning at most once per
+    /// [`FAILURE_WARNING_INTERVAL`], and returns the consecutive failures.
+    fn record_failure(&self, error: impl Display, now: Instant) -> u32 {
+        let mut state = self.lock();
+        state.consecutive_failures += 1;
+        state.last_error = Some(error.to_string());
+        let should_warn = state
+            .last_warning
+            .is_none_or(|last| now.duration_since(last) >= FAILURE_WARNING_INTERVAL);
+        if should_warn {
+            state.last_warning = Some(now);
+            warn!(
+                "Failed to send metrics ({} consecutive failures, last success: {}): {}",
+                state.consecutive_failures,
+                state
+                    .last_success
+                    .map_or("never".to_string(), |at| at.to_rfc3339()),
+                error
+            );
+        }
+        state.consecutive_failures
+    }
+
+    fn set_spilled(&self, batches: usize, bytes: u64) {
+        let mut state = self.lock();
+        state.spilled_batches = batches;
+        state.spill_bytes = bytes;
+    }
+
+    pub fn snapshot(&self) -> PipelineHealthSnapshot {
+        let state = self.lock();
+        PipelineHealthSnapshot {
+            healthy: state.consecutive_failures == 0,
+            consecutive_failures: state.consecutive_failures,
+            last_success: state.last_success,
+            last_error: state.last_error.clone(),
+            spilled_batches: state.spilled_batches,
+            spill_bytes: state.spill_bytes,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HealthState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Batches that couldn't be sent, one JSON object per line, oldest first.
+struct SpillFile {
+    path: PathBuf,
+    max_bytes: u64,
+}
+
+impl SpillFile {
+    fn load(&self) -> io::Result<Vec<String>> {
+        match std::fs::File::open(&self.path) {
+            Ok(file) => BufReader::new(file).lines().collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Replaces the content with `lines`, evicting the oldest ones beyond
+    /// `max_bytes`, and returns the number of lines and bytes kept.
+    fn store(&self, mut lines: VecDeque<String>) -> io::Result<(usize, u64)> {
+        let line_bytes = |line: &String| line.len() as u64 + 1;
+        let mut total: u64 = lines.iter().map(line_bytes).sum();
+        let mut evicted = 0;
+        while total > self.max_bytes {
+            let Some(oldest) = lines.pop_front() else {
+                break;
+            };
+            total -= line_bytes(&oldest);
+            evicted += 1;
+        }
+        if evicted > 0 {
+            warn!(
+                "Metrics spill file is full, dropped the {} oldest batches",
+                evicted
+            );
+        }
+
+        if lines.is_empty() {
+            return match std::fs::remove_file(&self.path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok((0, 0)),
+            };
+        }
+
+        // Written aside then renamed, so a crash never leaves a partial file
+        let tmp = self.path.with_extension("tmp");
+        let mut file = io::BufWriter::new(std::fs::File::create(&tmp)?);
+        for line in &lines {
+            writeln!(file, "{line}")?;
+        }
+        file.into_inner()?.sync_all()?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok((lines.len(), total))
+    }
+
+    fn append(&self, batches: impl IntoIterator<Item = Batch>) -> io::Result<(usize, u64)> {
+        let mut lines: VecDeque<String> = self.load()?.into();
+        for batch in batches {
+            lines.push_back(serde_json::to_string(&batch)?);
+        }
+        self.store(lines)
+    }
+}
+
+struct Pipeline {
+    sink: Box<dyn MetricsSink>,
+    health: PipelineHealth,
+    spill: Option<SpillFile>,
+    failure_threshold: u32,
+}
+
+impl Pipeline {
+    /// Sends `batches` after the spilled ones, spilling them while the sink
+    /// is failing.
+    async fn deliver(&self, batches: Vec<Batch>) {
+        let mut pending: VecDeque<Batch> = batches.into();
+
+        if let Some(spill) = &self.spill {
+            if !self.replay(spill).await {
+                // Keep the order, new batches go after the spilled ones
+                self.spill(spill, pending);
+                return;
+            }
+        }
+
+        while let Some(batch) = pending.pop_front() {
+            match self.sink.send(&batch).await {
+                Ok(()) => self.health.record_success(Utc::now()),
+                Err(e) => {
+                    let failures = self.health.record_failure(&e, Instant::now());
+                    if let Some(spill) = &self.spill {
+                        if failures >= self.failure_threshold {
+                            pending.push_front(batch);
+                            self.spill(spill, pending);
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sends the spilled batches in order, returns whether they all were.
+    async fn replay(&self, spill: &SpillFile) -> bool {
+        let lines = match spill.load() {
+            Ok(lines) => lines,
+            Err(e) => {
+                error!("Failed to read the metrics spill file: {}", e);
+                return true;
+            }
+        };
+        if lines.is_empty() {
+            return true;
+        }
+
+        let mut remaining: VecDeque<String> = lines.into();
+        let mut replayed = 0;
+        let mut recovered = true;
+        while let Some(line) = remaining.front() {
+            let Ok(batch) = serde_json::from_str::<Batch>(line) else {
+                remaining.pop_front();
+                continue;
+            };
+            match self.sink.send(&batch).await {
+                Ok(()) => {
+                    self.health.record_success(Utc::now());
+                    remaining.pop_front();
+                    replayed += 1;
+                }
+                Err(e) => {
+                    self.health.record_failure(&e, Instant::now());
+                    recovered = false;
+                    break;
+                }
+            }
+        }
+
+        if replayed > 0 {
+            info!("Replayed {} spilled metric batches", replayed);
+        }
+        match spill.store(remaining) {
+            Ok((batches, bytes)) => self.health.set_spilled(batches, bytes),
+            Err(e) => error!("Failed to update the metrics spill file: {}", e),
+        }
+        recovered
+    }
+
+    fn spill(&self, spill: &SpillFile, batches: VecDeque<Batch>) {
+        if batches.is_empty() {
+            return;
+        }
+        match spill.append(batches) {
+            Ok((batches, bytes)) => self.health.set_spilled(batches, bytes),
+            Err(e) => error!("Failed to spill metrics to {:?}: {}", spill.path, e),
+        }
+    }
+}
+
+fn event_payload(event: &MetricEvent) -> (&'static str, serde_json::Value) {
+    match event {
+        MetricEvent::IngestedEvent {
+            timestamp,
+            count,
+            bytes,
+            latency,
+            route,
+            method,
+            topic,
+        } => (
+            "IngestEvent",
+            json!({
+                "timestamp": timestamp,
+                "count": count,
+                "bytes": bytes,
+                "latency": latency.as_secs_f64(),
+                "route": route.clone(),
+                "method": method,
+                "topic": topic,
+            }),
+        ),
+
+        MetricEvent::ConsumedEvent {
+            timestamp,
+            count,
+            latency,
+            bytes,
+            route,
+            method,
+        } => (
+            "ConsumptionEvent",
+            json!({
+                "timestamp": timestamp,
+                "count": count,
+                "latency": latency.as_secs_f64(),
+                "bytes": bytes,
+                "route": route.clone(),
+                "method": method,
+            }),
+        ),
+
+        MetricEvent::StreamingFunctionEvent {
+            timestamp,
+            count_in,
+            count_out,
+            bytes,
+            function_name,
+        } => (
+            "StreamingFunctionEvent",
+            json!({
+                "timestamp": timestamp,
+                "count_in": count_in,
+                "count_out": count_out,
+                "bytes": bytes,
+                "function_name": function_name,
+            }),
+        ),
+        MetricEvent::TopicToOLAPEvent {
+            timestamp,
+            count,
+            bytes,
+            consumer_group,
+            topic_name,
+        } => (
+            "TopicToOLAPEvent",
+            json!({
+                "timestamp": timestamp,
+                "count": count,
+                "bytes": bytes,
+                "consumer_group": consumer_group,
+                "topic_name": topic_name,
+            }),
+        ),
+    }
+}
+
+/// Groups `events` by type into batches for their configured route.
+fn batches(
+    events: &[MetricEvent],
+    metric_labels: Option<&serde_json::Map<String, serde_json::Value>>,
+    metric_endpoints: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> Vec<Batch> {
+    let mut event_groups: std::collections::HashMap<&str, Vec<serde_json::Value>> =
+        std::collections::HashMap::new();
+
+    for chunk in events.chunks(MAX_BATCH_SIZE) {
+        for event in chunk {
+            let (event_type, mut payload) = event_payload(event);
+            let payload_obj = payload.as_object_mut().unwrap();
+            if let Some(labels_obj) = metric_labels {
+                payload_obj.extend(labels_obj.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+
+            event_groups.entry(event_type).or_default().push(payload);
+        }
+    }
+
+    let mut batches = Vec::new();
+    for (event_type, events) in event_groups {
+        let route = match metric_endpoints
+            .and_then(|endpoints| endpoints.get(event_type))
+            .and_then(|endpoint| endpoint.as_str())
+        {
+            Some(route) => route,
+            None => {
+                error!("No endpoint found for event type: {event_type}");
+                continue;
+            }
+        };
+        batches.push(Batch {
+            event_type: event_type.to_string(),
+            route: route.to_string(),
+            events,
+        });
+    }
+    batches
 }
 
 async fn flush(
     buffer: BatchEvents,
     metric_labels: Option<serde_json::Map<String, serde_json::Value>>,
     metric_endpoints: Option<serde_json::Map<String, serde_json::Value>>,
-    redis_client: Option<Arc<RedisClient>>,
+    pipeline: Pipeline,
 ) {
     let mut interval = time::interval(Duration::from_secs(MAX_FLUSH_INTERVAL_SECONDS));
-    let client = Client::new();
 
     loop {
         interval.tick().await;
-        let mut buffer_owned = buffer.lock().await;
-        if buffer_owned.is_empty() {
+        let events = std::mem::take(&mut *buffer.lock().await);
+        if events.is_empty() && pipeline.health.snapshot().spilled_batches == 0 {
             continue;
         }
 
-        let mut event_groups: std::collections::HashMap<&str, Vec<serde_json::Value>> =
-            std::collections::HashMap::new();
-
-        for chunk in buffer_owned.chunks(MAX_BATCH_SIZE) {
-            for event in chunk {
-                let (event_type, payload) = match event {
-                    MetricEvent::IngestedEvent {
-                        timestamp,
-                        count,
-                        bytes,
-                        latency,
-                        route,
-                        method,
-                        topic,
-                    } => (
-                        "IngestEvent",
-                        &json!({
-                            "timestamp": timestamp,
-                            "count": count,
-                            "bytes": bytes,
-                            "latency": latency.as_secs_f64(),
-                            "route": route.clone(),
-                            "method": method,
-                            "topic": topic,
-                        }),
-                    ),
-
-                    MetricEvent::ConsumedEvent {
-                        timestamp,
-                        count,
-                        latency,
-                        bytes,
-                        route,
-                        method,
-                    } => (
-                        "ConsumptionEvent",
-                        &json!({
-                            "timestamp": timestamp,
-                            "count": count,
-                            "latency": latency.as_secs_f64(),
-                            "bytes": bytes,
-                            "route": route.clone(),
-                            "method": method,
-                        }),
-                    ),
-
-                    MetricEvent::StreamingFunctionEvent {
-                        timestamp,
-                        count_in,
-                        count_out,
-                        bytes,
-                        function_name,
-                    } => (
-                        "StreamingFunctionEvent",
-                        &json!({
-                            "timestamp": timestamp,
-                            "count_in": count_in,
-                            "count_out": count_out,
-                            "bytes": bytes,
-                            "function_name": function_name,
-                        }),
-                    ),
-                    MetricEvent::TopicToOLAPEvent {
-                        timestamp,
-                        count,
-                        bytes,
-                        consumer_group,
-                        topic_name,
-                    } => (
-                        "TopicToOLAPEvent",
-                        &json!({
-                            "timestamp": timestamp,
-                            "count": count,
-                            "bytes": bytes,
-                            "consumer_group": consumer_group,
-                            "topic_name": topic_name,
-                        }),
-                    ),
-                };
-
-                let mut payload = payload.clone();
-                let payload_obj = payload.as_object_mut().unwrap();
-                if let Some(labels_obj) = &metric_labels {
-                    payload_obj.extend(labels_obj.iter().map(|(k, v)| (k.clone(), v.clone())));
-                }
+        let batches = batches(&events, metric_labels.as_ref(), metric_endpoints.as_ref());
+        pipeline.deliver(batches).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
 
-                event_groups.entry(event_type).or_default().push(payload);
+    /// Records the batches it accepts, fails while `failing` is set.
+    #[derive(Clone, Default)]
+    struct FakeSink {
+        failing: Arc<AtomicBool>,
+        sent: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl MetricsSink for FakeSink {
+        async fn send(&self, batch: &Batch) -> anyhow::Result<()> {
+            if self.failing.load(Ordering::SeqCst) {
+                anyhow::bail!("sink unavailable");
             }
+            self.sent.lock().unwrap().push(batch.route.clone());
+            Ok(())
         }
+    }
 
-        for (event_type, events) in event_groups {
-            let route = match metric_endpoints
-                .as_ref()
-                .and_then(|endpoints| endpoints.get(event_type))
-                .and_then(|endpoint| endpoint.as_str())
-            {
-                Some(route) => route,
-                None => {
-                    error!("No endpoint found for event type: {event_type}");
-                    continue;
-                }
-            };
+    fn batch(route: &str) -> Batch {
+        Batch {
+            event_type: "IngestEvent".to_string(),
+            route: route.to_string(),
+            events: vec![json!({ "count": 1 })],
+        }
+    }
+
+    fn pipeline(sink: &FakeSink, dir: &tempfile::TempDir, max_bytes: u64) -> Pipeline {
+        Pipeline {
+            sink: Box::new(sink.clone()),
+            health: PipelineHealth::default(),
+            spill: Some(SpillFile {
+                path: dir.path().join("metrics-spill.jsonl"),
+                max_bytes,
+            }),
+            failure_threshold: 2,
+        }
+    }
+
+    fn spilled_routes(pipeline: &Pipeline) -> Vec<String> {
+        pipeline
+            .spill
+            .as_ref()
+            .unwrap()
+            .load()
+            .unwrap()
+            .iter()
+            .map(|line| serde_json::from_str::<Batch>(line).unwrap().route)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_spills_after_threshold_and_replays_on_recovery() {
+        let dir = tempfile::tempdir().unwrap();
+        let sink = FakeSink::default();
+        let pipeline = pipeline(&sink, &dir, 1024 * 1024);
+
+        pipeline.deliver(vec![batch("/ok")]).await;
+        let last_success = pipeline.health.snapshot().last_success;
+        assert!(last_success.is_some());
+
+        sink.failing.store(true, Ordering::SeqCst);
+        // Below the threshold the batch is lost
+        pipeline.deliver(vec![batch("/lost")]).await;
+        assert!(spilled_routes(&pipeline).is_empty());
+        pipeline.deliver(vec![batch("/a"), batch("/b")]).await;
+        pipeline.deliver(vec![batch("/c")]).await;
+        assert_eq!(spilled_routes(&pipeline), ["/a", "/b", "/c"]);
 
-            if let Some(redis_client) = &redis_client {
-                let message = json!({
-                    "type": event_type,
-                    "events": events
-                });
-                if let Ok(events_json) = serde_json::to_string(&message) {
-                    redis_client
-                        .post_queue_message(&events_json, Some("metrics"))
-                        .await
-                        .ok();
+        let health = pipeline.health.snapshot();
+        assert!(!health.healthy);
+        assert_eq!(health.consecutive_failures, 3);
+        assert_eq!(health.last_success, last_success);
+        assert_eq!(health.last_error.as_deref(), Some("sink unavailable"));
+        assert_eq!(health.spilled_batches, 3);
+        assert!(health.spill_bytes > 0);
+
+        sink.failing.store(false, Ordering::SeqCst);
+        pipeline.deliver(vec![batch("/d")]).await;
+        assert_eq!(*sink.sent.lock().unwrap(), ["/ok", "/a", "/b", "/c", "/d"]);
+        assert!(spilled_routes(&pipeline).is_empty());
+        assert!(!dir.path().join("metrics-spill.jsonl").exists());
+
+        let health = pipeline.health.snapshot();
+        assert!(health.healthy);
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.spilled_batches, 0);
+        assert_eq!(health.spill_bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_spill_evicts_oldest_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let sink = FakeSink::default();
+        let line_bytes = serde_json::to_string(&batch("/0")).unwrap().len() as u64 + 1;
+        let pipeline = pipeline(&sink, &dir, line_bytes * 3);
+        sink.failing.store(true, Ordering::SeqCst);
+
+        pipeline.deliver(vec![batch("/0")]).await;
+        for i in 1..=5 {
+            pipeline.deliver(vec![batch(&format!("/{i}"))]).await;
+        }
+
+        assert_eq!(spilled_routes(&pipeline), ["/3", "/4", "/5"]);
+        assert_eq!(pipeline.health.snapshot().spill_bytes, line_bytes * 3);
+    }
+
+    #[tokio::test]
+    async fn test_partial_replay_keeps_remaining_batches() {
+        /// Accepts `accepted` sends, then fails.
+        struct FlakySink {
+            accepted: std::sync::Mutex<usize>,
+        }
+
+        #[async_trait]
+        impl MetricsSink for FlakySink {
+            async fn send(&self, _batch: &Batch) -> anyhow::Result<()> {
+                let mut accepted = self.accepted.lock().unwrap();
+                if *accepted == 0 {
+                    anyhow::bail!("sink unavailable");
                 }
-            } else {
-                let _ = client.post(route).json(&events).send().await;
+                *accepted -= 1;
+                Ok(())
             }
         }
 
-        buffer_owned.clear();
+        let dir = tempfile::tempdir().unwrap();
+        let pipeline = Pipeline {
+            sink: Box::new(FlakySink {
+                accepted: std::sync::Mutex::new(1),
+            }),
+            ..pipeline(&FakeSink::default(), &dir, 1024 * 1024)
+        };
+        let spill = pipeline.spill.as_ref().unwrap();
+        spill.append([batch("/a"), batch("/b")]).unwrap();
+
+        pipeline.deliver(vec![batch("/c")]).await;
+
+        assert_eq!(spilled_routes(&pipeline), ["/b", "/c"]);
+        assert_eq!(pipeline.health.snapshot().spilled_batches, 2);
+    }
+
+    #[test]
+    fn test_batches_group_events_by_type() {
+        let event = MetricEvent::StreamingFunctionEvent {
+            timestamp: Utc::now(),
+            count_in: 2,
+            count_out: 1,
+            bytes: 10,
+            function_name: "fn".to_string(),
+        };
+        let labels = json!({ "env": "test" });
+        let endpoints = json!({ "StreamingFunctionEvent": "http://sink/functions" });
+
+        let batches = batches(
+            &[event.clone(), event],
+            labels.as_object(),
+            endpoints.as_object(),
+        );
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].route, "http://sink/functions");
+        assert_eq!(batches[0].events.len(), 2);
+        assert_eq!(batches[0].events[0]["env"], "test");
+        assert!(batches(
+            &[MetricEvent::StreamingFunctionEvent {
+                timestamp: Utc::now(),
+                count_in: 0,
+                count_out: 0,
+                bytes: 0,
+                function_name: "fn".to_string(),
+            }],
+            None,
+            None
+        )
+        .is_empty());
     }
 }
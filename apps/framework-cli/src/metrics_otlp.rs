@@ -0,0 +1,626 @@
+//! Export of the webserver metrics to an OpenTelemetry collector over OTLP/HTTP.
+//!
+//! The [`Metrics`] registry stays the only instrumentation: it is scraped
+//! periodically and each of its series is reported through an OTel observable
+//! instrument of the same name, so ingest latency, sync throughput and any
+//! metric registered later are exported without being recorded twice.
+
+use opentelemetry::metrics::{AsyncInstrument, Meter, MeterProvider};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{Protocol, WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::error::{OTelSdkError, OTelSdkResult};
+use opentelemetry_sdk::metrics::data::ResourceMetrics;
+use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider, Temporality};
+use opentelemetry_sdk::Resource;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::cli::settings::{OtlpProtocol, OtlpSettings};
+use crate::metrics::{Metrics, TelemetryMetadata};
+use crate::utilities::constants::CLI_VERSION;
+
+const METRICS_PATH: &str = "/v1/metrics";
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest time between two scrapes of the registry
+const MAX_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, thiserror::Error)]
+pub enum OtlpExportError {
+    #[error("Failed to create the OTLP metrics exporter: {0}")]
+    Exporter(#[from] opentelemetry_otlp::ExporterBuildError),
+
+    #[error("Failed to stop the OTLP metrics exporter: {0}")]
+    Sdk(#[from] OTelSdkError),
+
+    #[error("OTLP metrics exporter task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SeriesKind {
+    Counter,
+    Gauge,
+}
+
+/// The values of one metric at the last scrape, one per label set
+#[derive(Debug)]
+struct Series {
+    kind: SeriesKind,
+    description: Option<String>,
+    points: Vec<(f64, Vec<KeyValue>)>,
+}
+
+type Snapshot = HashMap<String, Series>;
+
+/// Converts the Prometheus text encoding of the registry to series. Histograms
+/// are exported as their `_bucket` (with an `le` attribute), `_sum` and
+/// `_count` counters.
+fn parse_registry(text: &str) -> Snapshot {
+    let lines = text.lines().map(|line| Ok(line.to_owned()));
+    let scrape = match prometheus_parse::Scrape::parse(lines) {
+        Ok(scrape) => scrape,
+        Err(e) => {
+            warn!(
+                "Failed to parse the metrics registry for OTLP export: {}",
+                e
+            );
+            return Snapshot::new();
+        }
+    };
+
+    let mut snapshot = Snapshot::new();
+    for sample in scrape.samples {
+        let attributes: Vec<KeyValue> = sample
+            .labels
+            .iter()
+            .map(|(key, value)| KeyValue::new(key.clone(), value.clone()))
+            .collect();
+        let is_total = ["_total", "_sum", "_count"]
+            .iter()
+            .any(|suffix| sample.metric.ends_with(suffix));
+
+        let points = match sample.value {
+            prometheus_parse::Value::Counter(value) => {
+                vec![(sample.metric, SeriesKind::Counter, value, attributes)]
+            }
+            prometheus_parse::Value::Gauge(value) => {
+                vec![(sample.metric, SeriesKind::Gauge, value, attributes)]
+            }
+            prometheus_parse::Value::Untyped(value) => {
+                let kind = if is_total {
+                    SeriesKind::Counter
+                } else {
+                    SeriesKind::Gauge
+                };
+                vec![(sample.metric, kind, value, attributes)]
+            }
+            // `_sum` and `_count` lines are parsed as single-bucket histograms
+            prometheus_parse::Value::Histogram(buckets) if is_total => buckets
+                .first()
+                .map(|bucket| (sample.metric, SeriesKind::Counter, bucket.count, attributes))
+                .into_iter()
+                .collect(),
+            prometheus_parse::Value::Histogram(buckets) => {
+                let name = format!(
+                    "{}_bucket",
+                    sample
+                        .metric
+                        .strip_suffix("_bucket")
+                        .unwrap_or(&sample.metric)
+                );
+                buckets
+                    .into_iter()
+                    .map(|bucket| {
+                        let le = if bucket.less_than.is_infinite() {
+                            "+Inf".to_string()
+                        } else {
+                            bucket.less_than.to_string()
+                        };
+                        let mut attributes = attributes.clone();
+                        attributes.push(KeyValue::new("le", le));
+                        (name.clone(), SeriesKind::Counter, bucket.count, attributes)
+                    })
+                    .collect()
+            }
+            prometheus_parse::Value::Summary(_) => vec![],
+        };
+
+        for (name, kind, value, attributes) in points {
+            let base = ["_bucket", "_total", "_sum", "_count"]
+                .iter()
+                .find_map(|suffix| name.strip_suffix(suffix))
+                .unwrap_or(&name);
+            let description = scrape.docs.get(base).cloned();
+            snapshot
+                .entry(name)
+                .or_insert_with(|| Series {
+                    kind,
+                    description,
+                    points: Vec::new(),
+                })
+                .points
+                .push((value, attributes));
+        }
+    }
+    snapshot
+}
+
+/// Keeps one observable instrument per series, reading the latest snapshot
+struct Bridge {
+    meter: Meter,
+    snapshot: Arc<RwLock<Snapshot>>,
+    instruments: HashSet<String>,
+}
+
+impl Bridge {
+    fn update(&mut self, snapshot: Snapshot) {
+        let added: Vec<_> = snapshot
+            .iter()
+            .filter(|(name, _)| !self.instruments.contains(*name))
+            .map(|(name, series)| (name.clone(), series.kind, series.description.clone()))
+            .collect();
+        *self.snapshot.write().unwrap() = snapshot;
+
+        for (name, kind, description) in added {
+            self.register(name, kind, description);
+        }
+    }
+
+    fn register(&mut self, name: String, kind: SeriesKind, description: Option<String>) {
+        let snapshot = self.snapshot.clone();
+        let key = name.clone();
+        let callback = move |observer: &dyn AsyncInstrument<f64>| {
+            if let Some(series) = snapshot.read().unwrap().get(&key) {
+                for (value, attributes) in &series.points {
+                    observer.observe(*value, attributes);
+                }
+            }
+        };
+        let description = description.unwrap_or_default();
+
+        // The SDK keeps the callbacks registered for the life of the provider
+        match kind {
+            SeriesKind::Counter => {
+                self.meter
+                    .f64_observable_counter(name.clone())
+                    .with_description(description)
+                    .with_callback(callback)
+                    .build();
+            }
+            SeriesKind::Gauge => {
+                self.meter
+                    .f64_observable_gauge(name.clone())
+                    .with_description(description)
+                    .with_callback(callback)
+                    .build();
+            }
+        }
+        self.instruments.insert(name);
+    }
+}
+
+/// Logs the first failed export and the recovery rather than every failure,
+/// and reports success so the reader keeps exporting.
+struct FailSoftExporter {
+    inner: opentelemetry_otlp::MetricExporter,
+    endpoint: String,
+    failing: Arc<AtomicBool>,
+}
+
+impl PushMetricExporter for FailSoftExporter {
+    async fn export(&self, metrics: &ResourceMetrics) -> OTelSdkResult {
+        match self.inner.export(metrics).await {
+            Ok(()) => {
+                if self.failing.swap(false, Ordering::Relaxed) {
+                    info!("OTLP metrics export to {} recovered", self.endpoint);
+                }
+            }
+            Err(e) => {
+                if !self.failing.swap(true, Ordering::Relaxed) {
+                    warn!(
+                        "OTLP metrics export to {} failed, retrying at every interval: {}",
+                        self.endpoint, e
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn temporality(&self) -> Temporality {
+        self.inner.temporality()
+    }
+}
+
+/// The URL metrics are posted to, `endpoint` being the collector base URL
+fn metrics_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with(METRICS_PATH) {
+        endpoint.to_string()
+    } else {
+        format!("{endpoint}{METRICS_PATH}")
+    }
+}
+
+fn resource(settings: &OtlpSettings, metadata: &TelemetryMetadata) -> Resource {
+    let mut attributes = vec![
+        KeyValue::new("service.version", CLI_VERSION),
+        KeyValue::new("moose.project_name", metadata.project_name.clone()),
+    ];
+    if !metadata.machine_id.is_empty() {
+        attributes.push(KeyValue::new(
+            "moose.machine_id",
+            metadata.machine_id.clone(),
+        ));
+    }
+
+    Resource::builder()
+        .with_service_name("moose")
+        .with_attributes(attributes)
+        .with_attributes(
+            settings
+                .resource_attributes
+                .iter()
+                .map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+        )
+        .build()
+}
+
+fn build_provider(
+    settings: &OtlpSettings,
+    resource: Resource,
+    failing: Arc<AtomicBool>,
+) -> Result<SdkMeterProvider, OtlpExportError> {
+    let protocol = match settings.protocol {
+        OtlpProtocol::HttpProtobuf => Protocol::HttpBinary,
+        OtlpProtocol::HttpJson => Protocol::HttpJson,
+    };
+    let url = metrics_url(&settings.endpoint);
+    let inner = opentelemetry_otlp::MetricExporter::builder()
+        .with_http()
+        .with_protocol(protocol)
+        .with_endpoint(url.clone())
+        .with_headers(settings.headers.clone())
+        .with_timeout(EXPORT_TIMEOUT)
+        .build()?;
+
+    let reader = PeriodicReader::builder(FailSoftExporter {
+        inner,
+        endpoint: url,
+        failing,
+    })
+    .with_interval(Duration::from_secs(settings.interval_secs.max(1)))
+    .build();
+
+    Ok(SdkMeterProvider::builder()
+        .with_resource(resource)
+        .with_reader(reader)
+        .build())
+}
+
+/// Periodic export of the [`Metrics`] registry, running until [`Self::shutdown`]
+pub struct OtlpMetricsExporter {
+    provider: SdkMeterProvider,
+    refresh: JoinHandle<()>,
+    failing: Arc<AtomicBool>,
+}
+
+impl OtlpMetricsExporter {
+    pub async fn start(
+        settings: &OtlpSettings,
+        metrics: Arc<Metrics>,
+    ) -> Result<Self, OtlpExportError> {
+        let failing = Arc::new(AtomicBool::new(false));
+        let resource = resource(settings, metrics.telemetry_metadata());
+
+        // The HTTP client of the exporter blocks, it must not be created on a
+        // runtime worker
+        let provider = {
+            let settings = settings.clone();
+            let failing = failing.clone();
+            tokio::task::spawn_blocking(move || build_provider(&settings, resource, failing))
+                .await??
+        };
+
+        let mut bridge = Bridge {
+            meter: provider.meter("moose"),
+            snapshot: Default::default(),
+            instruments: HashSet::new(),
+        };
+        bridge.update(parse_registry(
+            &metrics.get_metrics_registry_as_string().await,
+        ));
+
+        let refresh_interval =
+            Duration::from_secs(settings.interval_secs.max(1)).min(MAX_REFRESH_INTERVAL);
+        let refresh = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(refresh_interval);
+            // The first tick completes immediately
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                bridge.update(parse_registry(
+                    &metrics.get_metrics_registry_as_string().await,
+                ));
+            }
+        });
+
+        info!(
+            "Exporting metrics to {} every {}s",
+            metrics_url(&settings.endpoint),
+            settings.interval_secs.max(1)
+        );
+        Ok(OtlpMetricsExporter {
+            provider,
+            refresh,
+            failing,
+        })
+    }
+
+    /// Stops the export after exporting the latest values
+    pub async fn shutdown(self) {
+        self.refresh.abort();
+        if self.failing.load(Ordering::Relaxed) {
+            warn!("Stopping OTLP metrics export while the collector is unreachable, the last values may be lost");
+        }
+        let provider = self.provider;
+        let result: Result<(), OtlpExportError> =
+            match tokio::task::spawn_blocking(move || provider.shutdown()).await {
+                Ok(result) => result.map_err(OtlpExportError::from),
+                Err(e) => Err(e.into()),
+            };
+        if let Err(e) = result {
+            warn!("{}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::MetricEvent;
+    use http_body_util::{BodyExt, Full};
+    use hyper::body::{Bytes, Incoming};
+    use hyper::header::HeaderValue;
+    use hyper::service::service_fn;
+    use hyper::{Request, Response};
+    use hyper_util::rt::TokioIo;
+    use serde_json::Value;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// Starts an OTLP/HTTP receiver forwarding the path and body of each export
+    async fn start_receiver() -> (String, mpsc::UnboundedReceiver<(String, Bytes)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |req: Request<Incoming>| {
+                        let tx = tx.clone();
+                        async move {
+                            let path = req.uri().path().to_string();
+                            let body = req.into_body().collect().await?.to_bytes();
+                            let _ = tx.send((path, body));
+                            let mut response = Response::new(Full::new(Bytes::from("{}")));
+                            response.headers_mut().insert(
+                                hyper::header::CONTENT_TYPE,
+                                HeaderValue::from_static("application/json"),
+                            );
+                            Ok::<_, hyper::Error>(response)
+                        }
+                    });
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        (endpoint, rx)
+    }
+
+    async fn started_metrics() -> Arc<Metrics> {
+        let (metrics, rx_events) = Metrics::new(
+            TelemetryMetadata {
+                machine_id: "machine-1".to_string(),
+                is_moose_developer: false,
+                metric_labels: None,
+                metric_endpoints: None,
+                is_production: false,
+                project_name: "otlp-test".to_string(),
+                export_metrics: false,
+                metric_spill: Default::default(),
+            },
+            None,
+        );
+        metrics.start_listening_to_metrics(rx_events).await;
+        metrics
+            .send_metric_event(MetricEvent::IngestedEvent {
+                topic: "Foo".to_string(),
+                timestamp: chrono::Utc::now(),
+                count: 3,
+                bytes: 120,
+                latency: Duration::from_millis(15),
+                route: "ingest/Foo".to_string(),
+                method: "POST".to_string(),
+            })
+            .await;
+        // The event is recorded by a background task
+        while !metrics
+            .get_metrics_registry_as_string()
+            .await
+            .contains("ingest/Foo")
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        Arc::new(metrics)
+    }
+
+    fn settings(endpoint: String) -> OtlpSettings {
+        OtlpSettings {
+            endpoint,
+            headers: HashMap::from([("x-api-key".to_string(), "secret".to_string())]),
+            interval_secs: 3600,
+            resource_attributes: HashMap::from([(
+                "deployment.environment".to_string(),
+                "test".to_string(),
+            )]),
+            protocol: OtlpProtocol::HttpJson,
+        }
+    }
+
+    #[test]
+    fn test_resource_omits_empty_machine_id() {
+        let metadata = |machine_id: &str| TelemetryMetadata {
+            machine_id: machine_id.to_string(),
+            is_moose_developer: false,
+            metric_labels: None,
+            metric_endpoints: None,
+            is_production: false,
+            project_name: "otlp-test".to_string(),
+            export_metrics: false,
+            metric_spill: Default::default(),
+        };
+        let settings = settings("http://localhost:4318".to_string());
+        let machine_id = opentelemetry::Key::new("moose.machine_id");
+
+        let resource = resource(&settings, &metadata(""));
+        assert_eq!(resource.get(&machine_id), None);
+        assert_eq!(
+            resource.get(&opentelemetry::Key::new("moose.project_name")),
+            Some("otlp-test".into())
+        );
+
+        let resource = resource(&settings, &metadata("machine-1"));
+        assert_eq!(resource.get(&machine_id), Some("machine-1".into()));
+    }
+
+    fn attribute<'a>(attributes: &'a Value, key: &str) -> Option<&'a Value> {
+        attributes
+            .as_array()?
+            .iter()
+            .find(|attribute| attribute["key"] == key)
+            .map(|attribute| &attribute["value"]["stringValue"])
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_exports_registry_to_receiver() {
+        let (endpoint, mut exports) = start_receiver().await;
+        let exporter = OtlpMetricsExporter::start(&settings(endpoint), started_metrics().await)
+            .await
+            .unwrap();
+        let provider = exporter.provider.clone();
+        tokio::task::spawn_blocking(move || provider.force_flush())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let (path, body) = exports.recv().await.unwrap();
+        assert_eq!(path, METRICS_PATH);
+        let export: Value = serde_json::from_slice(&body).unwrap();
+        let resource_metrics = &export["resourceMetrics"][0];
+
+        let resource = &resource_metrics["resource"]["attributes"];
+        assert_eq!(
+            attribute(resource, "moose.project_name").unwrap(),
+            "otlp-test"
+        );
+        assert_eq!(
+            attribute(resource, "moose.machine_id").unwrap(),
+            "machine-1"
+        );
+        assert_eq!(
+            attribute(resource, "deployment.environment").unwrap(),
+            "test"
+        );
+
+        let metrics: Vec<&Value> = resource_metrics["scopeMetrics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|scope| scope["metrics"].as_array().unwrap())
+            .collect();
+        let metric = |name: &str| {
+            *metrics
+                .iter()
+                .find(|metric| metric["name"] == name)
+                .unwrap_or_else(|| panic!("{name} not exported"))
+        };
+
+        let ingested = &metric("moose_ingested_bytes_total")["sum"]["dataPoints"][0];
+        assert_eq!(ingested["asDouble"], 120.0);
+        assert_eq!(
+            attribute(&ingested["attributes"], "path").unwrap(),
+            "ingest/Foo"
+        );
+        assert_eq!(
+            attribute(&ingested["attributes"], "method").unwrap(),
+            "POST"
+        );
+
+        let latency_count = &metric("moose_latency_count")["sum"]["dataPoints"][0];
+        assert_eq!(latency_count["asDouble"], 1.0);
+        let buckets = metric("moose_latency_bucket")["sum"]["dataPoints"]
+            .as_array()
+            .unwrap();
+        assert!(buckets
+            .iter()
+            .any(|point| attribute(&point["attributes"], "le").unwrap() == "+Inf"));
+        assert!(
+            metric("moose_http_to_topic_event_count_total")["description"]
+                .as_str()
+                .is_some_and(|description| !description.is_empty())
+        );
+
+        exporter.shutdown().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_unreachable_collector_fails_soft() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let exporter = OtlpMetricsExporter::start(&settings(endpoint), started_metrics().await)
+            .await
+            .unwrap();
+        let provider = exporter.provider.clone();
+        let flushed = tokio::task::spawn_blocking(move || provider.force_flush())
+            .await
+            .unwrap();
+
+        assert!(flushed.is_ok());
+        assert!(exporter.failing.load(Ordering::Relaxed));
+        exporter.shutdown().await;
+    }
+
+    #[test]
+    fn test_metrics_url() {
+        assert_eq!(
+            metrics_url("http://collector:4318/"),
+            "http://collector:4318/v1/metrics"
+        );
+        assert_eq!(
+            metrics_url("https://collector/otlp/v1/metrics"),
+            "https://collector/otlp/v1/metrics"
+        );
+    }
+}
@@ -44,16 +44,23 @@
 //! - `project_file_location` - The location of the project file on disk
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+pub mod config_interpolation;
+pub mod config_overrides;
+pub mod config_profiles;
+pub mod config_secrets;
+pub mod config_validation;
 pub mod python_project;
 pub mod typescript_project;
 
 use std::fmt::Debug;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::cli::local_webserver::LocalWebserverConfig;
-use crate::cli::watcher::WatcherConfig;
+use crate::cli::watcher::{DevWatcherConfig, WatcherConfig};
+use crate::framework::core::partial_infrastructure_map::LifeCycle;
 use crate::framework::languages::SupportedLanguages;
 use crate::framework::versions::Version;
 use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
@@ -73,7 +80,10 @@ use crate::utilities::constants::OLD_PROJECT_CONFIG_FILE;
 use crate::utilities::constants::PROJECT_CONFIG_FILE;
 use crate::utilities::constants::{APP_DIR, CLI_PROJECT_INTERNAL_DIR, SCHEMAS_DIR};
 use crate::utilities::git::GitConfig;
-use config::{Config, ConfigError, Environment, File};
+use crate::utilities::retry::{self, RetriesConfig};
+use crate::utilities::secret_store::project_secrets;
+use config::{Config, ConfigError, Environment, File, FileFormat};
+use config_validation::ConfigIssue;
 use python_project::PythonProject;
 use serde::Deserialize;
 use serde::Serialize;
@@ -245,11 +255,81 @@ impl Default for ProjectFeatures {
 }
 
 /// Migration configuration
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MigrationConfig {
     /// Operations to ignore during migration plan generation
     #[serde(default)]
     pub ignore_operations: Vec<IgnorableOperation>,
+
+    /// What happens to the tables removed from the code
+    #[serde(default)]
+    pub drop_strategy: DropStrategy,
+
+    /// Days a table renamed by the `rename` drop strategy is kept before
+    /// `moose clean --dropped` purges it
+    #[serde(default = "default_dropped_table_retention_days")]
+    pub dropped_table_retention_days: u32,
+
+    /// Seconds to wait for a readonly ClickHouse server to accept DDL before
+    /// failing the plan, which otherwise fails right away
+    #[serde(default)]
+    pub wait_for_writable_secs: Option<u64>,
+
+    /// Seconds an operation of a plan runs before a warning names it
+    #[serde(default)]
+    pub warn_slow_operation_seconds: Option<u64>,
+
+    /// Skips checking that TTL expressions only refer to columns of their
+    /// table, for expressions the check cannot read
+    #[serde(default)]
+    pub skip_ttl_validation: bool,
+
+    /// Seconds an `ON CLUSTER` statement waits for the hosts of its cluster,
+    /// set as `distributed_ddl_task_timeout` on the session running the plan.
+    /// ClickHouse's default (180) applies when unset
+    #[serde(default)]
+    pub distributed_ddl_timeout_seconds: Option<u64>,
+}
+
+impl MigrationConfig {
+    pub fn wait_for_writable(&self) -> Option<Duration> {
+        self.wait_for_writable_secs.map(Duration::from_secs)
+    }
+
+    pub fn warn_slow_operation(&self) -> Option<Duration> {
+        self.warn_slow_operation_seconds.map(Duration::from_secs)
+    }
+}
+
+fn default_dropped_table_retention_days() -> u32 {
+    7
+}
+
+impl Default for MigrationConfig {
+    fn default() -> Self {
+        MigrationConfig {
+            ignore_operations: Vec::new(),
+            drop_strategy: DropStrategy::default(),
+            dropped_table_retention_days: default_dropped_table_retention_days(),
+            wait_for_writable_secs: None,
+            warn_slow_operation_seconds: None,
+            skip_ttl_validation: false,
+            distributed_ddl_timeout_seconds: None,
+        }
+    }
+}
+
+/// How production plans remove the tables removed from the code
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DropStrategy {
+    /// Drop the table and its data
+    #[default]
+    Drop,
+    /// Truncate the table, leaving it empty in the database
+    TruncateOnly,
+    /// Rename the table to `<name>__dropped_<timestamp>` so its data can be recovered
+    Rename,
 }
 
 /// Configuration for development mode behavior with externally managed tables
@@ -328,7 +408,7 @@ impl RemoteClickHouseConfig {
 }
 
 /// Development mode configuration
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DevConfig {
     /// Configuration for externally managed tables
     #[serde(default)]
@@ -338,10 +418,85 @@ pub struct DevConfig {
     /// No credentials stored - they go in OS keychain or env vars
     #[serde(default)]
     pub remote_clickhouse: Option<RemoteClickHouseConfig>,
+
+    /// Tools exposed by the dev server's MCP endpoint
+    #[serde(default)]
+    pub mcp: McpConfig,
+
+    /// Debounce, ignore patterns and watch paths of the file watcher
+    #[serde(default)]
+    pub watcher: DevWatcherConfig,
+
+    /// Whether database changes that lose data (dropping a table or a column,
+    /// re-creating a table) are confirmed before they are applied
+    #[serde(default = "_true")]
+    pub confirm_destructive_changes: bool,
 }
 
-/// Represents a user's Moose project
+impl Default for DevConfig {
+    fn default() -> Self {
+        DevConfig {
+            externally_managed: DevExternallyManagedTablesConfig::default(),
+            remote_clickhouse: None,
+            mcp: McpConfig::default(),
+            watcher: DevWatcherConfig::default(),
+            confirm_destructive_changes: true,
+        }
+    }
+}
+
+/// MCP server configuration, `[dev.mcp]`
 #[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpConfig {
+    /// Whether the `query_table` tool, reading rows of project tables, is exposed
+    #[serde(default = "_true")]
+    pub query_table: bool,
+    /// Names of the tools not exposed
+    #[serde(default)]
+    pub disabled_tools: Vec<String>,
+    /// Whether `/mcp` accepts requests without a token, by default only
+    /// outside production mode
+    #[serde(default)]
+    pub allow_unauthenticated: Option<bool>,
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        McpConfig {
+            query_table: true,
+            disabled_tools: Vec::new(),
+            allow_unauthenticated: None,
+        }
+    }
+}
+
+impl McpConfig {
+    pub fn tool_enabled(&self, name: &str) -> bool {
+        (name != "query_table" || self.query_table)
+            && !self.disabled_tools.iter().any(|tool| tool == name)
+    }
+}
+
+/// Defaults for the tables of one database, `[databases.<name>]`, used where
+/// a table leaves the value unset
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct DatabaseDefaults {
+    /// Lifecycle of tables that do not set one
+    #[serde(default)]
+    pub life_cycle: Option<LifeCycle>,
+    /// ON CLUSTER name of tables that do not set one, defined in
+    /// `clickhouse_config.clusters`
+    #[serde(default)]
+    pub cluster: Option<String>,
+    /// Settings of MergeTree tables, each used unless the table sets it
+    #[serde(default)]
+    pub table_settings: BTreeMap<String, String>,
+}
+
+/// Represents a user's Moose project
+///
+/// `Debug` redacts the secret fields, e.g. `clickhouse_config.password` or `jwt.secret`.
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Project {
     /// Programming language used in the project
     pub language: SupportedLanguages,
@@ -410,6 +565,116 @@ pub struct Project {
     /// Development mode configuration
     #[serde(default)]
     pub dev: DevConfig,
+    /// Table defaults by database name
+    #[serde(default)]
+    pub databases: BTreeMap<String, DatabaseDefaults>,
+    /// Retry policies for ClickHouse, HTTP, Redis and Kafka
+    #[serde(default)]
+    pub retries: RetriesConfig,
+}
+
+/// The project file in `directory`, preferring the current file name.
+fn project_file_path(directory: &Path) -> PathBuf {
+    if directory.join(PROJECT_CONFIG_FILE).exists() {
+        directory.join(PROJECT_CONFIG_FILE)
+    } else {
+        directory.join(OLD_PROJECT_CONFIG_FILE)
+    }
+}
+
+/// The parsed project file, with the selected profile merged into the base
+/// configuration and `${VAR}` references interpolated. A file that cannot be
+/// read is reported as [`ConfigError::Foreign`] like a missing project.
+fn project_file_config(path: &Path) -> Result<toml::Value, ConfigError> {
+    let content = std::fs::read_to_string(path).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+    let mut config: toml::Value = toml::from_str(&content).map_err(|e| ConfigError::FileParse {
+        uri: Some(path.display().to_string()),
+        cause: Box::new(e),
+    })?;
+
+    config_profiles::apply_profile(&mut config, config_profiles::selected_profile().as_deref())
+        .map_err(|e| ConfigError::Message(e.to_string()))?;
+    config_interpolation::interpolate_config(&mut config)
+        .map_err(|e| ConfigError::Message(format!("Invalid {}: {e}", path.display())))?;
+
+    Ok(config)
+}
+
+/// `MOOSE_<SECTION>__<KEY>` environment variables, layered over the project file.
+fn environment_source() -> Environment {
+    Environment::with_prefix(ENVIRONMENT_VARIABLE_PREFIX)
+        .prefix_separator("_")
+        .separator("__")
+}
+
+/// Field-by-field `Debug` of [`Project`], once its secret fields are redacted.
+struct ProjectFields<'a>(&'a Project);
+
+impl Debug for ProjectFields<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Destructured so a new field cannot be left out of the output
+        let Project {
+            language,
+            source_dir,
+            redpanda_config,
+            clickhouse_config,
+            http_server_config,
+            redis_config,
+            git_config,
+            temporal_config,
+            state_config,
+            migration_config,
+            language_project_config,
+            project_location,
+            is_production,
+            log_payloads,
+            supported_old_versions,
+            jwt,
+            authentication,
+            features,
+            load_infra,
+            typescript_config,
+            docker_config,
+            watcher_config,
+            dev,
+            databases,
+            retries,
+        } = self.0;
+
+        f.debug_struct("Project")
+            .field("language", language)
+            .field("source_dir", source_dir)
+            .field("redpanda_config", redpanda_config)
+            .field("clickhouse_config", clickhouse_config)
+            .field("http_server_config", http_server_config)
+            .field("redis_config", redis_config)
+            .field("git_config", git_config)
+            .field("temporal_config", temporal_config)
+            .field("state_config", state_config)
+            .field("migration_config", migration_config)
+            .field("language_project_config", language_project_config)
+            .field("project_location", project_location)
+            .field("is_production", is_production)
+            .field("log_payloads", log_payloads)
+            .field("supported_old_versions", supported_old_versions)
+            .field("jwt", jwt)
+            .field("authentication", authentication)
+            .field("features", features)
+            .field("load_infra", load_infra)
+            .field("typescript_config", typescript_config)
+            .field("docker_config", docker_config)
+            .field("watcher_config", watcher_config)
+            .field("dev", dev)
+            .field("databases", databases)
+            .field("retries", retries)
+            .finish()
+    }
+}
+
+impl Debug for Project {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        ProjectFields(&config_secrets::redact_secret_fields(self.clone())).fmt(f)
+    }
 }
 
 pub fn default_source_dir() -> String {
@@ -454,6 +719,7 @@ impl Project {
 
         debug!("Package.json file location: {:?}", location);
 
+        let redis_config = RedisConfig::for_project(&name);
         let language_project_config = match language {
             SupportedLanguages::Typescript => {
                 LanguageProjectConfig::Typescript(TypescriptProject::new(name))
@@ -468,7 +734,7 @@ impl Project {
             project_location: location.clone(),
             redpanda_config: KafkaConfig::default(),
             clickhouse_config: ClickHouseConfig::default(),
-            redis_config: RedisConfig::default(),
+            redis_config,
             http_server_config: LocalWebserverConfig::default(),
             temporal_config: TemporalConfig::default(),
             state_config: StateConfig::default(),
@@ -485,6 +751,8 @@ impl Project {
             docker_config: DockerConfig::default(),
             watcher_config: WatcherConfig::default(),
             dev: DevConfig::default(),
+            databases: BTreeMap::new(),
+            retries: RetriesConfig::default(),
         }
     }
 
@@ -512,25 +780,19 @@ impl Project {
         // 1. Load .env files first (this populates environment variables)
         crate::utilities::dotenv::load_dotenv_files(directory, environment);
 
-        let mut project_file = directory.clone();
-
         // 2. Prioritize the new project file name
-        if directory.clone().join(PROJECT_CONFIG_FILE).exists() {
-            project_file.push(PROJECT_CONFIG_FILE);
-        } else {
-            project_file.push(OLD_PROJECT_CONFIG_FILE);
-        }
-
-        // 3. Build config with TOML file + environment variables
-        let mut project_config: Project = Config::builder()
-            .add_source(File::from(project_file).required(true))
-            .add_source(
-                Environment::with_prefix(ENVIRONMENT_VARIABLE_PREFIX)
-                    .prefix_separator("_")
-                    .separator("__"),
-            )
-            .build()?
-            .try_deserialize()?;
+        let project_file = project_file_path(directory);
+
+        // 3. Build config with TOML file (selected profile merged in, ${VAR} interpolated)
+        //    + environment variables + --set overrides
+        let file_config = project_file_config(&project_file)?;
+        let file_content =
+            toml::to_string(&file_config).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+        let builder = Config::builder()
+            .add_source(File::from_str(&file_content, FileFormat::Toml))
+            .add_source(environment_source());
+        let mut project_config =
+            config_overrides::build_project_config(builder, config_overrides::config_overrides())?;
 
         project_config.project_location.clone_from(directory);
 
@@ -546,12 +808,41 @@ impl Project {
             }
         }
 
+        let project_name = project_config.name();
+        project_config
+            .redis_config
+            .resolve_key_prefixes(&project_name);
+
+        // 4. Read secrets backed by files (`*_FILE` variables, `from_file:` values) or named
+        //    secrets (`secret:` values), which are namespaced by the project name
+        let secrets = project_secrets(&project_config.name());
+        config_secrets::resolve_secrets(
+            &mut project_config,
+            &|name| std::env::var(name).ok(),
+            &secrets,
+        )
+        .map_err(|e| ConfigError::Message(e.to_string()))?;
+
+        project_config
+            .redis_config
+            .validate_leadership_timings()
+            .map_err(|e| ConfigError::Message(e.to_string()))?;
+
         // Show Redis configuration warnings for mixed configurations
         project_config.redis_config.show_config_warnings();
+        config_validation::warn_unknown_keys(&file_config, &project_config);
+        retry::set_retries_config(project_config.retries.clone());
 
         Ok(project_config)
     }
 
+    /// Strict validation of the project file this project was loaded from:
+    /// keys the CLI does not know and inconsistent settings.
+    pub fn config_issues(&self) -> Result<Vec<ConfigIssue>, ConfigError> {
+        let file_config = project_file_config(&project_file_path(&self.project_location))?;
+        Ok(config_validation::config_issues(&file_config, self))
+    }
+
     /// Loads a project from the current directory with the specified environment
     ///
     /// # Arguments
@@ -747,4 +1038,59 @@ pub mod tests {
         assert_eq!(project.language, SupportedLanguages::Python);
         assert_eq!(project.name(), "test_project");
     }
+
+    #[test]
+    fn test_load_interpolates_and_redacts_secrets() {
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let project = Project::new(
+            temp_dir.path(),
+            "test_project".to_string(),
+            SupportedLanguages::Python,
+        );
+        project.write_to_disk().unwrap();
+
+        let config_file = temp_dir.path().join(PROJECT_CONFIG_FILE);
+        let mut config: toml::Value =
+            toml::from_str(&std::fs::read_to_string(&config_file).unwrap()).unwrap();
+        config["clickhouse_config"]["password"] =
+            toml::Value::String("${MOOSE_TEST_INTERPOLATED_CH_PASSWORD}".to_string());
+        config["clickhouse_config"]["host"] =
+            toml::Value::String("${MOOSE_TEST_UNSET_CH_HOST:-ch.internal}".to_string());
+        std::fs::write(&config_file, toml::to_string(&config).unwrap()).unwrap();
+        std::env::set_var("MOOSE_TEST_INTERPOLATED_CH_PASSWORD", "pa55-from-env");
+
+        let loaded = Project::load(
+            &temp_dir.path().to_path_buf(),
+            crate::utilities::dotenv::MooseEnvironment::Development,
+        )
+        .unwrap();
+
+        assert_eq!(loaded.clickhouse_config.password, "pa55-from-env");
+        assert_eq!(loaded.clickhouse_config.host, "ch.internal");
+        let dump = format!("{loaded:?}");
+        assert!(!dump.contains("pa55-from-env"), "{dump}");
+        assert!(dump.contains("ch.internal"), "{dump}");
+    }
+
+    #[test]
+    fn test_mcp_tool_toggles() {
+        let config: McpConfig = toml::from_str(
+            r#"
+            query_table = false
+            disabled_tools = ["get_logs"]
+            "#,
+        )
+        .unwrap();
+        assert!(!config.tool_enabled("query_table"));
+        assert!(!config.tool_enabled("get_logs"));
+        assert!(config.tool_enabled("tail_logs"));
+        assert_eq!(config.allow_unauthenticated, None);
+
+        let config: McpConfig = toml::from_str(r#"disabled_tools = ["query_table"]"#).unwrap();
+        assert!(config.query_table);
+        assert!(!config.tool_enabled("query_table"));
+        assert!(McpConfig::default().tool_enabled("query_table"));
+    }
 }
@@ -0,0 +1,376 @@
+//! Per-invocation configuration overrides passed as `--set <dotted.key>=<value>`.
+//!
+//! Overrides are layered on top of `moose.config.toml` and `MOOSE_*` environment
+//! variables (file < env < `--set`) and go through the same deserialization as
+//! the rest of the project config, so values are type-checked against
+//! [`Project`]. Keys are checked against the loaded configuration first so a
+//! typo fails with the list of valid keys instead of being silently ignored.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use config::builder::DefaultState;
+use config::{ConfigBuilder, ConfigError};
+use serde_json::Value;
+use tracing::debug;
+
+use super::Project;
+
+/// Overrides from the command line, set once at startup.
+static CONFIG_OVERRIDES: OnceLock<Vec<ConfigOverride>> = OnceLock::new();
+
+/// Key fragments whose values are never written to logs.
+const SECRET_KEY_FRAGMENTS: [&str; 5] = ["password", "secret", "token", "api_key", "credential"];
+
+/// Whether values of `key`, a config key or variable name, look like secrets.
+pub(super) fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SECRET_KEY_FRAGMENTS
+        .iter()
+        .any(|fragment| key.contains(fragment))
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfigOverrideError {
+    #[error("expected <dotted.key>=<value>, got '{0}'")]
+    MissingValue(String),
+
+    #[error("invalid key '{0}'")]
+    InvalidKey(String),
+
+    #[error("unknown key '{segment}' in '{key}', expected one of: {expected}")]
+    UnknownKey {
+        key: String,
+        segment: String,
+        expected: String,
+    },
+
+    #[error("index {index} is out of range in '{key}' ({len} element(s))")]
+    IndexOutOfRange {
+        key: String,
+        index: usize,
+        len: usize,
+    },
+
+    #[error("'{parent}' holds a single value, so '{key}' cannot be set")]
+    NotNested { key: String, parent: String },
+}
+
+/// A single `--set <dotted.key>=<value>` override.
+///
+/// `Debug` and `Display` redact the value when the key looks like a secret, so
+/// overrides can be logged safely.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ConfigOverride {
+    pub key: String,
+    pub value: String,
+}
+
+impl ConfigOverride {
+    /// Whether the value should be kept out of logs.
+    pub fn is_secret(&self) -> bool {
+        is_secret_key(&self.key)
+    }
+
+    fn display_value(&self) -> &str {
+        if self.is_secret() {
+            "[REDACTED]"
+        } else {
+            &self.value
+        }
+    }
+}
+
+impl fmt::Display for ConfigOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.key, self.display_value())
+    }
+}
+
+impl fmt::Debug for ConfigOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigOverride")
+            .field("key", &self.key)
+            .field("value", &self.display_value())
+            .finish()
+    }
+}
+
+impl FromStr for ConfigOverride {
+    type Err = ConfigOverrideError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| ConfigOverrideError::MissingValue(s.to_string()))?;
+        let key = key.trim();
+        parse_key(key)?;
+        Ok(ConfigOverride {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+/// Stores the command line overrides applied by every subsequent
+/// [`Project::load`]. Only the first call has an effect.
+pub fn set_config_overrides(overrides: Vec<ConfigOverride>) {
+    let _ = CONFIG_OVERRIDES.set(overrides);
+}
+
+/// Overrides set with [`set_config_overrides`], empty if none were given.
+pub fn config_overrides() -> &'static [ConfigOverride] {
+    CONFIG_OVERRIDES.get().map_or(&[], Vec::as_slice)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum KeySegment<'a> {
+    Field(&'a str),
+    Index(usize),
+}
+
+/// Splits `clickhouse_config.additional_databases[1]` into its fields and indexes.
+fn parse_key(key: &str) -> Result<Vec<KeySegment<'_>>, ConfigOverrideError> {
+    let invalid = || ConfigOverrideError::InvalidKey(key.to_string());
+    let mut segments = Vec::new();
+
+    for part in key.split('.') {
+        let (field, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if field.is_empty()
+            || !field
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(invalid());
+        }
+        segments.push(KeySegment::Field(field));
+
+        while !rest.is_empty() {
+            let close = rest.find(']').ok_or_else(invalid)?;
+            if !rest.starts_with('[') {
+                return Err(invalid());
+            }
+            let index = rest[1..close].parse().map_err(|_| invalid())?;
+            segments.push(KeySegment::Index(index));
+            rest = &rest[close + 1..];
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Checks that `key` names a setting of `config`, the loaded project serialized
+/// to JSON. Paths under an unset optional section (`null`) cannot be checked
+/// here and are left to deserialization.
+fn validate_key(config: &Value, key: &str) -> Result<(), ConfigOverrideError> {
+    let mut current = config;
+    let mut path = String::new();
+
+    for segment in parse_key(key)? {
+        if current.is_null() {
+            return Ok(());
+        }
+        match segment {
+            KeySegment::Field(field) => {
+                let Value::Object(table) = current else {
+                    return Err(ConfigOverrideError::NotNested {
+                        key: key.to_string(),
+                        parent: path,
+                    });
+                };
+                current = table.get(field).ok_or_else(|| {
+                    let mut expected: Vec<&str> = table.keys().map(String::as_str).collect();
+                    expected.sort();
+                    ConfigOverrideError::UnknownKey {
+                        key: key.to_string(),
+                        segment: field.to_string(),
+                        expected: expected.join(", "),
+                    }
+                })?;
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(field);
+            }
+            KeySegment::Index(index) => {
+                let Value::Array(items) = current else {
+                    return Err(ConfigOverrideError::NotNested {
+                        key: key.to_string(),
+                        parent: path,
+                    });
+                };
+                // Setting the element right after the last one appends to the array
+                if index == items.len() {
+                    return Ok(());
+                }
+                current = items
+                    .get(index)
+                    .ok_or_else(|| ConfigOverrideError::IndexOutOfRange {
+                        key: key.to_string(),
+                        index,
+                        len: items.len(),
+                    })?;
+                path.push_str(&format!("[{index}]"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Deserializes the project config from `builder`, with `overrides` layered on
+/// top of every other source.
+pub(super) fn build_project_config(
+    builder: ConfigBuilder<DefaultState>,
+    overrides: &[ConfigOverride],
+) -> Result<Project, ConfigError> {
+    if overrides.is_empty() {
+        return builder.build()?.try_deserialize();
+    }
+
+    let base: Project = builder.clone().build()?.try_deserialize()?;
+    let base = serde_json::to_value(&base).map_err(|e| ConfigError::Message(e.to_string()))?;
+
+    let mut builder = builder;
+    for config_override in overrides {
+        validate_key(&base, &config_override.key)
+            .map_err(|e| ConfigError::Message(format!("Invalid --set override: {e}")))?;
+        debug!("Applying config override {}", config_override);
+        builder =
+            builder.set_override(config_override.key.as_str(), config_override.value.as_str())?;
+    }
+
+    builder
+        .build()
+        .and_then(|config| config.try_deserialize())
+        .map_err(|e| ConfigError::Message(format!("Invalid --set override: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::languages::SupportedLanguages;
+    use config::{Config, File, FileFormat};
+
+    fn builder() -> (tempfile::TempDir, ConfigBuilder<DefaultState>) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut project = Project::new(
+            dir.path(),
+            "overrides".to_string(),
+            SupportedLanguages::Typescript,
+        );
+        project.clickhouse_config.additional_databases =
+            vec!["analytics".to_string(), "logging".to_string()];
+        let toml = toml::to_string(&project).unwrap();
+        let builder = Config::builder().add_source(File::from_str(&toml, FileFormat::Toml));
+        (dir, builder)
+    }
+
+    fn set(s: &str) -> ConfigOverride {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_override() {
+        let parsed = set("clickhouse_config.host=ch.internal=1");
+        assert_eq!(parsed.key, "clickhouse_config.host");
+        assert_eq!(parsed.value, "ch.internal=1");
+
+        assert_eq!(
+            "clickhouse_config.host".parse::<ConfigOverride>(),
+            Err(ConfigOverrideError::MissingValue(
+                "clickhouse_config.host".to_string()
+            ))
+        );
+        assert_eq!(
+            "clickhouse_config..host=x".parse::<ConfigOverride>(),
+            Err(ConfigOverrideError::InvalidKey(
+                "clickhouse_config..host".to_string()
+            ))
+        );
+        assert_eq!(
+            "databases[x]=y".parse::<ConfigOverride>(),
+            Err(ConfigOverrideError::InvalidKey("databases[x]".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_overrides_nested_keys_arrays_and_booleans() {
+        let (_dir, builder) = builder();
+        let project = build_project_config(
+            builder,
+            &[
+                set("clickhouse_config.host_port=9123"),
+                set("clickhouse_config.additional_databases[1]=audit"),
+                set("features.workflows=true"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(project.clickhouse_config.host_port, 9123);
+        assert_eq!(
+            project.clickhouse_config.additional_databases,
+            vec!["analytics".to_string(), "audit".to_string()]
+        );
+        assert!(project.features.workflows);
+    }
+
+    #[test]
+    fn test_override_appends_to_array() {
+        let (_dir, builder) = builder();
+        let project = build_project_config(
+            builder,
+            &[set("clickhouse_config.additional_databases[2]=audit")],
+        )
+        .unwrap();
+
+        assert_eq!(project.clickhouse_config.additional_databases.len(), 3);
+    }
+
+    #[test]
+    fn test_unknown_key_is_rejected() {
+        let (_dir, builder) = builder();
+        let err = build_project_config(builder, &[set("clickhouse_config.hots_port=9123")])
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("unknown key 'hots_port'"), "{err}");
+        assert!(err.contains("host_port"), "{err}");
+    }
+
+    #[test]
+    fn test_out_of_range_index_is_rejected() {
+        let (_dir, builder) = builder();
+        let err = build_project_config(
+            builder,
+            &[set("clickhouse_config.additional_databases[5]=audit")],
+        )
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("index 5 is out of range"), "{err}");
+    }
+
+    #[test]
+    fn test_unparsable_value_names_the_key() {
+        let (_dir, builder) = builder();
+        let err = build_project_config(builder, &[set("clickhouse_config.host_port=abc")])
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.starts_with("Invalid --set override"), "{err}");
+        assert!(err.contains("clickhouse_config.host_port"), "{err}");
+    }
+
+    #[test]
+    fn test_secret_values_are_redacted() {
+        let secret = set("clickhouse_config.password=hunter2");
+        assert_eq!(secret.to_string(), "clickhouse_config.password=[REDACTED]");
+        assert!(!format!("{secret:?}").contains("hunter2"));
+
+        let plain = set("clickhouse_config.host=ch.internal");
+        assert_eq!(plain.to_string(), "clickhouse_config.host=ch.internal");
+    }
+}
@@ -0,0 +1,222 @@
+//! Named profiles in `moose.config.toml`.
+//!
+//! A `[profiles.<name>]` table overrides part of the base configuration, e.g.
+//! `[profiles.staging.clickhouse_config]` with only `host` and `db_name`. The
+//! profile is selected with `--profile <name>` or `MOOSE_PROFILE` and deep-merged
+//! into the file before anything else is layered on top:
+//! base < profile < `MOOSE_*` environment variables < `--set`.
+//!
+//! Tables are merged key by key, any other value (including arrays) replaces
+//! the base value.
+
+use std::sync::OnceLock;
+
+use toml::Value;
+
+/// Environment variable selecting a profile when `--profile` is not given.
+pub const PROFILE_ENV_VAR: &str = "MOOSE_PROFILE";
+
+const PROFILES_KEY: &str = "profiles";
+
+/// Profile from the command line, set once at startup.
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProfileError {
+    #[error("unknown profile '{name}', available profiles: {available}")]
+    Unknown { name: String, available: String },
+
+    #[error("profile '{0}' must be a table of settings")]
+    NotATable(String),
+}
+
+/// Stores the `--profile` flag used by every subsequent [`Project::load`].
+/// Only the first call has an effect.
+///
+/// [`Project::load`]: super::Project::load
+pub fn set_profile(profile: Option<String>) {
+    let _ = PROFILE.set(profile);
+}
+
+/// The selected profile: `--profile`, falling back to `MOOSE_PROFILE`, which
+/// may also come from a `.env` file.
+pub fn selected_profile() -> Option<String> {
+    PROFILE.get().cloned().flatten().or_else(|| {
+        std::env::var(PROFILE_ENV_VAR)
+            .ok()
+            .filter(|profile| !profile.is_empty())
+    })
+}
+
+/// Removes the `profiles` table from `config`, the parsed project file, and
+/// merges the one named `profile` into the base configuration.
+pub(super) fn apply_profile(config: &mut Value, profile: Option<&str>) -> Result<(), ProfileError> {
+    let profiles = config
+        .as_table_mut()
+        .and_then(|table| table.remove(PROFILES_KEY));
+    let Some(name) = profile else {
+        return Ok(());
+    };
+
+    let mut profiles = match profiles {
+        Some(Value::Table(profiles)) => profiles,
+        _ => toml::map::Map::new(),
+    };
+    match profiles.remove(name) {
+        Some(overrides @ Value::Table(_)) => {
+            deep_merge(config, overrides);
+            Ok(())
+        }
+        Some(_) => Err(ProfileError::NotATable(name.to_string())),
+        None => {
+            let available = if profiles.is_empty() {
+                "none defined in moose.config.toml".to_string()
+            } else {
+                profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            };
+            Err(ProfileError::Unknown {
+                name: name.to_string(),
+                available,
+            })
+        }
+    }
+}
+
+/// Merges `overrides` into `base`: tables key by key, anything else replaced.
+pub(super) fn deep_merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Table(base), Value::Table(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::languages::SupportedLanguages;
+    use crate::project::config_overrides::{build_project_config, ConfigOverride};
+    use crate::project::{environment_source, Project};
+    use config::{Config, File, FileFormat};
+
+    const PROFILES: &str = r#"
+[profiles.staging.clickhouse_config]
+host = "ch.staging"
+db_name = "staging"
+
+[profiles.staging.redpanda_config]
+broker = "kafka-1.staging:9092,kafka-2.staging:9092"
+
+[profiles.staging.features]
+workflows = true
+apis = false
+
+[profiles.prod.clickhouse_config]
+host = "ch.prod"
+"#;
+
+    fn fixture_config() -> Value {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = Project::new(
+            dir.path(),
+            "profiles".to_string(),
+            SupportedLanguages::Typescript,
+        );
+        let base = toml::to_string(&project).unwrap();
+        toml::from_str(&format!("{base}\n{PROFILES}")).unwrap()
+    }
+
+    fn load(
+        profile: Option<&str>,
+        env: &[(&str, &str)],
+        overrides: &[&str],
+    ) -> Result<Project, String> {
+        let mut config = fixture_config();
+        apply_profile(&mut config, profile).map_err(|e| e.to_string())?;
+
+        let env = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let builder = Config::builder()
+            .add_source(File::from_str(
+                &toml::to_string(&config).unwrap(),
+                FileFormat::Toml,
+            ))
+            .add_source(environment_source().source(Some(env)));
+        let overrides: Vec<ConfigOverride> = overrides.iter().map(|o| o.parse().unwrap()).collect();
+        build_project_config(builder, &overrides).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_profile_deep_merges_into_base() {
+        let base = load(None, &[], &[]).unwrap();
+        let staging = load(Some("staging"), &[], &[]).unwrap();
+
+        assert_eq!(staging.clickhouse_config.host, "ch.staging");
+        assert_eq!(staging.clickhouse_config.db_name, "staging");
+        // Keys the profile does not mention keep their base value
+        assert_eq!(staging.clickhouse_config.user, base.clickhouse_config.user);
+        assert_eq!(
+            staging.clickhouse_config.host_port,
+            base.clickhouse_config.host_port
+        );
+        assert_eq!(
+            staging.redpanda_config.broker,
+            "kafka-1.staging:9092,kafka-2.staging:9092"
+        );
+    }
+
+    #[test]
+    fn test_profile_overrides_feature_flags() {
+        let base = load(None, &[], &[]).unwrap();
+        assert!(!base.features.workflows);
+        assert!(base.features.apis);
+
+        let staging = load(Some("staging"), &[], &[]).unwrap();
+        assert!(staging.features.workflows);
+        assert!(!staging.features.apis);
+        assert_eq!(staging.features.olap, base.features.olap);
+    }
+
+    #[test]
+    fn test_precedence_base_profile_env_set() {
+        let env = [("MOOSE_CLICKHOUSE_CONFIG__HOST", "ch.env")];
+
+        let from_profile = load(Some("prod"), &[], &[]).unwrap();
+        assert_eq!(from_profile.clickhouse_config.host, "ch.prod");
+
+        let from_env = load(Some("prod"), &env, &[]).unwrap();
+        assert_eq!(from_env.clickhouse_config.host, "ch.env");
+
+        let from_set = load(Some("prod"), &env, &["clickhouse_config.host=ch.set"]).unwrap();
+        assert_eq!(from_set.clickhouse_config.host, "ch.set");
+    }
+
+    #[test]
+    fn test_unknown_profile_lists_available_profiles() {
+        let err = load(Some("qa"), &[], &[]).unwrap_err();
+        assert_eq!(
+            err,
+            "unknown profile 'qa', available profiles: prod, staging"
+        );
+
+        let mut config: Value = toml::from_str("language = \"typescript\"").unwrap();
+        assert_eq!(
+            apply_profile(&mut config, Some("qa")),
+            Err(ProfileError::Unknown {
+                name: "qa".to_string(),
+                available: "none defined in moose.config.toml".to_string(),
+            })
+        );
+    }
+}
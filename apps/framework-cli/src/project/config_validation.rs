@@ -0,0 +1,298 @@
+//! Strict checks of `moose.config.toml` beyond what deserialization enforces.
+//!
+//! Serde ignores keys it does not know, so a typo such as `clikhouse_config`
+//! silently falls back to the defaults. Rejecting unknown keys outright would
+//! break older CLIs reading newer configs, so instead the file is compared
+//! against the loaded [`Project`] serialized back to TOML: any key of the file
+//! missing from that round trip was ignored. Each one is warned about on load
+//! with the nearest valid key as a suggestion.
+//!
+//! `moose config validate` reports the same issues plus cross-field rules and
+//! exits non-zero when there is any.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use toml::Value;
+
+use super::Project;
+use crate::cli::display::{Message, MessageType};
+use crate::infrastructure::olap::clickhouse::config::ClickHouseConfig;
+
+/// Keys accepted under another name, as `(alias, key)`.
+const KEY_ALIASES: [(&str, &str); 1] = [("kafka_config", "redpanda_config")];
+
+/// ClickHouse HTTP ports that do not speak TLS, the local dev one included.
+const CLICKHOUSE_HTTP_PORTS: [i32; 2] = [8123, 18123];
+/// ClickHouse HTTPS ports, self-hosted and behind a load balancer.
+const CLICKHOUSE_HTTPS_PORTS: [i32; 2] = [8443, 443];
+
+static WARN_UNKNOWN_KEYS: AtomicBool = AtomicBool::new(true);
+
+/// A problem found in the project configuration, at `path` in the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Stops [`Project::load`] from warning about unknown keys, for commands that
+/// report them themselves.
+pub fn disable_unknown_key_warnings() {
+    WARN_UNKNOWN_KEYS.store(false, Ordering::Relaxed);
+}
+
+pub(super) fn warn_unknown_keys(file_config: &Value, project: &Project) {
+    if !WARN_UNKNOWN_KEYS.load(Ordering::Relaxed) {
+        return;
+    }
+    for issue in unknown_keys(file_config, project) {
+        show_message!(
+            MessageType::Warning,
+            Message {
+                action: "Config".to_string(),
+                details: format!("{issue}, it is ignored"),
+            }
+        );
+    }
+}
+
+/// Every issue in the project file `file_config`, from which `project` was loaded.
+pub(super) fn config_issues(file_config: &Value, project: &Project) -> Vec<ConfigIssue> {
+    let mut issues = unknown_keys(file_config, project);
+    issues.extend(ssl_port_issues(&project.clickhouse_config));
+    issues.extend(database_cluster_issues(project));
+    issues
+}
+
+/// `[databases.<name>]` defaults naming a cluster missing from
+/// `clickhouse_config.clusters`.
+pub fn database_cluster_issues(project: &Project) -> Vec<ConfigIssue> {
+    let defined: Vec<&str> = project
+        .clickhouse_config
+        .clusters
+        .iter()
+        .flatten()
+        .map(|cluster| cluster.name.as_str())
+        .collect();
+
+    project
+        .databases
+        .iter()
+        .filter_map(|(database, defaults)| {
+            let cluster = defaults.cluster.as_deref()?;
+            (!defined.contains(&cluster)).then(|| ConfigIssue {
+                path: format!("databases.{database}.cluster"),
+                message: format!(
+                    "cluster '{cluster}' is not defined in clickhouse_config.clusters"
+                ),
+            })
+        })
+        .collect()
+}
+
+fn unknown_keys(file_config: &Value, project: &Project) -> Vec<ConfigIssue> {
+    match Value::try_from(project) {
+        Ok(known) => {
+            let mut issues = Vec::new();
+            collect_unknown_keys(file_config, &known, "", &mut issues);
+            issues
+        }
+        Err(e) => {
+            tracing::debug!("Skipping the unknown key check: {e}");
+            Vec::new()
+        }
+    }
+}
+
+fn collect_unknown_keys(value: &Value, known: &Value, path: &str, issues: &mut Vec<ConfigIssue>) {
+    match (value, known) {
+        (Value::Table(table), Value::Table(known_table)) => {
+            for (key, item) in table {
+                let item_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                let known_key = KEY_ALIASES
+                    .iter()
+                    .find(|(alias, _)| path.is_empty() && alias == key)
+                    .map_or(key.as_str(), |(_, key)| key);
+
+                match known_table.get(known_key) {
+                    Some(known_item) => collect_unknown_keys(item, known_item, &item_path, issues),
+                    None => {
+                        let message = match closest_key(key, known_table.keys()) {
+                            Some(suggestion) => {
+                                format!("unknown key, did you mean '{suggestion}'?")
+                            }
+                            None => "unknown key".to_string(),
+                        };
+                        issues.push(ConfigIssue {
+                            path: item_path,
+                            message,
+                        });
+                    }
+                }
+            }
+        }
+        (Value::Array(items), Value::Array(known_items)) => {
+            for (index, (item, known_item)) in items.iter().zip(known_items).enumerate() {
+                collect_unknown_keys(item, known_item, &format!("{path}[{index}]"), issues);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The candidate nearest to `key`, if it is close enough to be a likely typo.
+pub fn closest_key<'a>(key: &str, candidates: impl Iterator<Item = &'a String>) -> Option<&'a str> {
+    let max_distance = (key.chars().count() / 3).max(2);
+    candidates
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Levenshtein distance between `a` and `b`, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// `use_ssl` must agree with the ClickHouse HTTP port it is used with.
+fn ssl_port_issues(config: &ClickHouseConfig) -> Vec<ConfigIssue> {
+    let port = config.host_port;
+    let message = if config.use_ssl && CLICKHOUSE_HTTP_PORTS.contains(&port) {
+        format!("use_ssl is true but {port} is a plain HTTP port, ClickHouse serves HTTPS on 8443")
+    } else if !config.use_ssl && CLICKHOUSE_HTTPS_PORTS.contains(&port) {
+        format!("{port} is an HTTPS port but use_ssl is false")
+    } else {
+        return Vec::new();
+    };
+
+    vec![ConfigIssue {
+        path: "clickhouse_config.host_port".to_string(),
+        message,
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::languages::SupportedLanguages;
+    use crate::project::config_profiles::deep_merge;
+
+    /// Broken configs, each merged into a valid base, with the issues expected.
+    const BROKEN_CONFIGS: &[(&str, &[&str])] = &[
+        (
+            "[clikhouse_config]\nhost = \"ch.internal\"",
+            &["clikhouse_config: unknown key, did you mean 'clickhouse_config'?"],
+        ),
+        (
+            "[clickhouse_config]\ndb_nme = \"analytics\"",
+            &["clickhouse_config.db_nme: unknown key, did you mean 'db_name'?"],
+        ),
+        (
+            "[features]\nworkflow = true\nstreaming_engin = false",
+            &[
+                "features.streaming_engin: unknown key, did you mean 'streaming_engine'?",
+                "features.workflow: unknown key, did you mean 'workflows'?",
+            ],
+        ),
+        (
+            "[telemetry_settings]\nenabled = false",
+            &["telemetry_settings: unknown key"],
+        ),
+        (
+            "[clickhouse_config]\nclusters = [{ name = \"main\", nme = \"replica\" }]",
+            &["clickhouse_config.clusters[0].nme: unknown key, did you mean 'name'?"],
+        ),
+        (
+            "[clickhouse_config]\nuse_ssl = true\nhost_port = 18123",
+            &["clickhouse_config.host_port: use_ssl is true but 18123 is a plain HTTP port, ClickHouse serves HTTPS on 8443"],
+        ),
+        (
+            "[clickhouse_config]\nuse_ssl = false\nhost_port = 8443",
+            &["clickhouse_config.host_port: 8443 is an HTTPS port but use_ssl is false"],
+        ),
+        (
+            "[databases.analytics]\ncluster = \"main\"\nlife_cycle = \"DELETION_PROTECTED\"",
+            &["databases.analytics.cluster: cluster 'main' is not defined in clickhouse_config.clusters"],
+        ),
+    ];
+
+    fn issues(snippet: &str) -> Vec<String> {
+        let dir = tempfile::TempDir::new().unwrap();
+        let base = Project::new(
+            dir.path(),
+            "validation".to_string(),
+            SupportedLanguages::Typescript,
+        );
+        let mut file_config = Value::try_from(&base).unwrap();
+        deep_merge(&mut file_config, toml::from_str(snippet).unwrap());
+
+        let project: Project = file_config.clone().try_into().unwrap();
+        config_issues(&file_config, &project)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_broken_config_corpus() {
+        for (snippet, expected) in BROKEN_CONFIGS {
+            assert_eq!(issues(snippet), *expected, "config:\n{snippet}");
+        }
+    }
+
+    #[test]
+    fn test_valid_configs_have_no_issues() {
+        assert!(issues("").is_empty());
+        assert!(issues("[clickhouse_config]\nuse_ssl = true\nhost_port = 8443").is_empty());
+        assert!(issues("[clickhouse_config]\nclusters = [{ name = \"main\" }]").is_empty());
+        assert!(issues(
+            "[clickhouse_config]\nclusters = [{ name = \"main\" }]\n\
+             [databases.analytics]\ncluster = \"main\"\ntable_settings = { storage_policy = \"s3\" }"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_aliased_key_is_known() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let base = Project::new(dir.path(), "alias".to_string(), SupportedLanguages::Python);
+        let mut file_config = Value::try_from(&base).unwrap();
+        let table = file_config.as_table_mut().unwrap();
+        let redpanda_config = table.remove("redpanda_config").unwrap();
+        table.insert("kafka_config".to_string(), redpanda_config);
+
+        let project: Project = file_config.clone().try_into().unwrap();
+        assert_eq!(config_issues(&file_config, &project), vec![]);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("clikhouse_config", "clickhouse_config"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+}
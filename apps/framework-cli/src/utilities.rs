@@ -14,10 +14,13 @@ pub mod keyring;
 pub mod machine_id;
 pub mod nodejs_version;
 pub mod package_managers;
+pub mod ports;
 pub mod process_output;
 pub mod retry;
+pub mod secret_store;
 pub mod secrets;
 pub mod system;
+pub mod telemetry;
 pub mod validate_passthrough;
 
 pub trait PathExt {
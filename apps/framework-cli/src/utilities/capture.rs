@@ -5,7 +5,8 @@
 use crate::cli::settings::Settings;
 use crate::utilities::ci_detection::detect_ci_environment;
 use crate::utilities::constants::{CLI_VERSION, CONTEXT, CTX_SESSION_ID};
-use posthog514client_rs::PostHog514Client;
+use crate::utilities::telemetry::telemetry_enabled;
+use posthog514client_rs::{Event514, MooseEventType, PostHog514Client};
 use serde::Serialize;
 use serde_json::json;
 use std::collections::HashMap;
@@ -90,10 +91,26 @@ pub enum ActivityType {
     WorkflowStatusCommand,
     #[serde(rename = "templateListCommand")]
     TemplateListCommand,
+    #[serde(rename = "templateShowCommand")]
+    TemplateShowCommand,
     #[serde(rename = "refreshListCommand")]
     RefreshListCommand,
     #[serde(rename = "dbPullCommand")]
     DbPullCommand,
+    #[serde(rename = "dbDiffCommand")]
+    DbDiffCommand,
+    #[serde(rename = "tableDescribeCommand")]
+    TableDescribeCommand,
+    #[serde(rename = "openapiCommand")]
+    OpenapiCommand,
+    #[serde(rename = "configShowCommand")]
+    ConfigShowCommand,
+    #[serde(rename = "configValidateCommand")]
+    ConfigValidateCommand,
+    #[serde(rename = "configTelemetryCommand")]
+    ConfigTelemetryCommand,
+    #[serde(rename = "secretCommand")]
+    SecretCommand,
     #[serde(rename = "feedbackCommand")]
     FeedbackCommand,
     #[serde(rename = "addCommand")]
@@ -102,21 +119,26 @@ pub enum ActivityType {
     ComponentListCommand,
 }
 
-pub fn capture_usage(
+/// A CLI usage event, before the PostHog client adds its own properties.
+#[derive(Debug, Clone)]
+pub struct UsageEvent {
+    pub project_name: Option<String>,
+    pub context: HashMap<String, serde_json::Value>,
+    pub is_moose_developer: bool,
+}
+
+/// Name of the PostHog event of CLI usage.
+pub const USAGE_EVENT_NAME: &str = "moose_cli_command";
+
+/// The usage event of `activity_type`.
+pub fn usage_event(
     activity_type: ActivityType,
     project_name: Option<String>,
     settings: &Settings,
-    machine_id: String,
     parameters: HashMap<String, String>,
-) -> Option<tokio::task::JoinHandle<()>> {
-    // Skip if telemetry is disabled
-    if !settings.telemetry.enabled {
-        return None;
-    }
-
+) -> UsageEvent {
     let sequence_id = CONTEXT.get(CTX_SESSION_ID).unwrap().clone();
     let event_id = Uuid::new_v4();
-    let is_moose_developer = settings.telemetry.is_moose_developer;
     let project = project_name.clone().unwrap_or_else(|| "N/A".to_string());
 
     // Create context for the event
@@ -144,29 +166,81 @@ pub fn capture_usage(
     }
     context.insert("is_docker".into(), json!(ci_env.is_docker));
 
-    // Create PostHog client
-    Some(tokio::task::spawn(async move {
-        let client = match PostHog514Client::from_env(machine_id) {
-            Some(client) => client,
-            None => {
-                tracing::warn!("PostHog client not configured - missing POSTHOG_API_KEY");
-                return;
+    UsageEvent {
+        project_name,
+        context,
+        is_moose_developer: settings.telemetry.is_moose_developer,
+    }
+}
+
+/// `event` as the PostHog client sends it for `machine_id`, without the API key.
+pub fn posthog_event(event: &UsageEvent, machine_id: &str) -> Event514 {
+    let mut posthog_event = Event514::new_moose(MooseEventType::MooseCliCommand)
+        .with_distinct_id(machine_id)
+        .with_project(event.project_name.clone());
+    posthog_event.set_app_version(CLI_VERSION);
+    posthog_event.set_is_developer(event.is_moose_developer);
+    posthog_event.set_environment("production");
+    posthog_event.with_properties(event.context.clone())
+}
+
+pub fn capture_usage(
+    activity_type: ActivityType,
+    project_name: Option<String>,
+    settings: &Settings,
+    machine_id: String,
+    parameters: HashMap<String, String>,
+) -> Option<tokio::task::JoinHandle<()>> {
+    capture_usage_to(
+        telemetry_enabled(settings),
+        activity_type,
+        project_name,
+        settings,
+        parameters,
+        move |event| async move {
+            let client = match PostHog514Client::from_env(machine_id) {
+                Some(client) => client,
+                None => {
+                    tracing::warn!("PostHog client not configured - missing POSTHOG_API_KEY");
+                    return;
+                }
+            };
+
+            if let Err(e) = client
+                .capture_cli_command(
+                    USAGE_EVENT_NAME,
+                    event.project_name,
+                    Some(event.context),
+                    CLI_VERSION.to_string(),
+                    event.is_moose_developer,
+                )
+                .await
+            {
+                tracing::warn!("Failed to send telemetry to PostHog: {:?}", e);
             }
-        };
+        },
+    )
+}
 
-        if let Err(e) = client
-            .capture_cli_command(
-                "moose_cli_command",
-                project_name,
-                Some(context),
-                CLI_VERSION.to_string(),
-                is_moose_developer,
-            )
-            .await
-        {
-            tracing::warn!("Failed to send telemetry to PostHog: {:?}", e);
-        }
-    }))
+/// Sends the usage event to `sink` in the background, only when `enabled`.
+fn capture_usage_to<F, Fut>(
+    enabled: bool,
+    activity_type: ActivityType,
+    project_name: Option<String>,
+    settings: &Settings,
+    parameters: HashMap<String, String>,
+    sink: F,
+) -> Option<tokio::task::JoinHandle<()>>
+where
+    F: FnOnce(UsageEvent) -> Fut,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    if !enabled {
+        return None;
+    }
+
+    let event = usage_event(activity_type, project_name, settings, parameters);
+    Some(tokio::task::spawn(sink(event)))
 }
 
 pub async fn wait_for_usage_capture(handle: Option<tokio::task::JoinHandle<()>>) {
@@ -191,8 +265,7 @@ pub fn identify_user_with_email(
     settings: &Settings,
     machine_id: String,
 ) -> Option<tokio::task::JoinHandle<()>> {
-    // Skip if telemetry is disabled
-    if !settings.telemetry.enabled {
+    if !telemetry_enabled(settings) {
         return None;
     }
 
@@ -221,3 +294,54 @@ pub fn identify_user_with_email(
         }
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    fn capture_counted(
+        enabled: bool,
+        sent: &Arc<AtomicU32>,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        let sent = sent.clone();
+        let settings: Settings = serde_json::from_value(json!({})).unwrap();
+        capture_usage_to(
+            enabled,
+            ActivityType::LsCommand,
+            Some("proj".to_string()),
+            &settings,
+            HashMap::new(),
+            move |event| async move {
+                assert_eq!(event.context["command"], json!("lsCommand"));
+                sent.fetch_add(1, Ordering::SeqCst);
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_nothing_is_sent_when_disabled() {
+        let sent = Arc::new(AtomicU32::new(0));
+
+        let handle = capture_counted(false, &sent);
+        assert!(handle.is_none());
+        wait_for_usage_capture(handle).await;
+        assert_eq!(sent.load(Ordering::SeqCst), 0);
+
+        wait_for_usage_capture(capture_counted(true, &sent)).await;
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_posthog_event_preview() {
+        let event = UsageEvent {
+            project_name: Some("proj".to_string()),
+            context: HashMap::from([("command".to_string(), json!("lsCommand"))]),
+            is_moose_developer: false,
+        };
+        let preview = serde_json::to_value(posthog_event(&event, "machine-1")).unwrap();
+        assert_eq!(preview["distinct_id"], json!("machine-1"));
+        assert_eq!(preview["command"], json!("lsCommand"));
+    }
+}
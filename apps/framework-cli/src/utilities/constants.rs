@@ -84,17 +84,35 @@ pub static NO_ANSI: AtomicBool = AtomicBool::new(false);
 /// This is set once at startup based on CLI flags
 pub static SHOW_TIMESTAMPS: AtomicBool = AtomicBool::new(false);
 
+/// Global flag to emit display messages as JSON log events
+/// When true, show_message! output goes through the logger instead of being printed,
+/// so stdout only carries JSON lines
+/// This is set once at startup when logging JSON to stdout
+pub static JSON_LOGS: AtomicBool = AtomicBool::new(false);
+
 /// Global flag to redirect display messages to stderr instead of stdout
 /// When true, all show_message! output goes to stderr, keeping stdout clean for
 /// structured/JSON output that can be parsed programmatically
 /// This is set when commands use --json or similar flags
 pub static QUIET_STDOUT: AtomicBool = AtomicBool::new(false);
 
+/// Global flag to hide all display messages except errors
+/// When true, show_message! and show_message_wrapper only print MessageType::Error,
+/// other messages are still logged
+/// This is set once at startup from the `--quiet` flag
+pub static SUPPRESS_DISPLAY: AtomicBool = AtomicBool::new(false);
+
 /// Global flag to enable timing information for operations
 /// When true, shows elapsed time like "completed in 234ms" or "completed in 2.3s" for tracked operations
 /// This is set once at startup based on CLI flags
 pub static SHOW_TIMING: AtomicBool = AtomicBool::new(false);
 
+/// Global flag to never prompt the user
+/// When true, prompts take their default answer or fail with an error naming the
+/// flag that supplies the answer, so the CLI cannot hang waiting for input in CI
+/// This is set once at startup from `--non-interactive` or `MOOSE_NON_INTERACTIVE`
+pub static NON_INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
 /// Global flag indicating we're running in dev mode (`moose dev`).
 /// When true, `tspc --watch` is handling TypeScript compilation, so we don't need
 /// to run `ensure_typescript_compiled` ourselves.
@@ -120,7 +138,11 @@ pub const WORKFLOW_TYPE: &str = "ScriptWorkflow";
 pub const PYTHON_TASK_QUEUE: &str = "python-script-queue";
 pub const TYPESCRIPT_TASK_QUEUE: &str = "typescript-script-queue";
 pub const MOOSE_CLI_NAME: &str = "moose-cli";
+/// Keychain key of the remote ClickHouse URL before it became the named secret
+/// [`REMOTE_CLICKHOUSE_URL_SECRET`]; read once and migrated forward.
 pub const KEY_REMOTE_CLICKHOUSE_URL: &str = "remote_clickhouse_url";
+/// Named secret (`moose secret`) holding the remote ClickHouse URL.
+pub const REMOTE_CLICKHOUSE_URL_SECRET: &str = "remote_clickhouse_url";
 pub const KEY_REMOTE_ADMIN_URL: &str = "remote_admin_url";
 pub const KEY_REMOTE_ADMIN_TOKEN: &str = "remote_admin_token";
 
@@ -138,6 +160,7 @@ pub const DEFAULT_SEED_LIMIT: usize = 1000;
 pub const MIGRATION_FILE: &str = "./migrations/plan.yaml";
 pub const MIGRATION_BEFORE_STATE_FILE: &str = "./migrations/remote_state.json";
 pub const MIGRATION_AFTER_STATE_FILE: &str = "./migrations/local_infra_map.json";
+pub const MIGRATION_SUMMARY_FILE: &str = "./migrations/summary.yaml";
 
 // Feedback
 /// GitHub Issues URL for bug reports filed via `moose feedback --bug`
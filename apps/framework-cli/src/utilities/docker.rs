@@ -40,6 +40,61 @@ pub struct DockerComposeContainerInfo {
     pub health: Option<String>,
 }
 
+/// Subset of `docker inspect` output used to report container status.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerInspect {
+    pub name: String,
+    #[serde(default)]
+    pub restart_count: u32,
+    pub state: ContainerInspectState,
+    pub config: ContainerInspectConfig,
+    #[serde(default)]
+    pub network_settings: ContainerInspectNetwork,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerInspectState {
+    pub status: String,
+    #[serde(default)]
+    pub health: Option<ContainerInspectHealth>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerInspectHealth {
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerInspectConfig {
+    pub image: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerInspectNetwork {
+    /// Keyed by `<container port>/<protocol>`; unpublished ports map to null
+    #[serde(default)]
+    pub ports: std::collections::BTreeMap<String, Option<Vec<ContainerPortBinding>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerPortBinding {
+    #[serde(default)]
+    pub host_ip: String,
+    pub host_port: String,
+}
+
+/// The daemon endpoint the container CLI talks to: `DOCKER_HOST` when set,
+/// the default Unix socket otherwise.
+pub fn docker_socket_path() -> String {
+    std::env::var("DOCKER_HOST").unwrap_or_else(|_| "unix:///var/run/docker.sock".to_string())
+}
+
 /// Client for interacting with container runtime (docker/finch)
 pub struct DockerClient {
     /// The container runtime CLI command to use
@@ -167,6 +222,32 @@ impl DockerClient {
         }
     }
 
+    /// Inspects the given containers, returning state, health, image and port bindings
+    pub fn inspect_containers(&self, names: &[String]) -> std::io::Result<Vec<ContainerInspect>> {
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let output = self
+            .create_command()
+            .arg("inspect")
+            .args(names)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?
+            .wait_with_output()?;
+
+        if !output.status.success() {
+            warn!(
+                "Could not inspect containers: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            return Err(std::io::Error::other("Failed to inspect Docker containers"));
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(std::io::Error::other)
+    }
+
     /// Lists names of all containers
     pub fn list_container_names(&self) -> std::io::Result<Vec<ContainerName>> {
         let child = self
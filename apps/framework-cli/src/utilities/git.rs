@@ -1,5 +1,4 @@
 use std::path::Path;
-use std::sync::Arc;
 
 use crate::framework::languages::SupportedLanguages;
 use git2::{Error, Repository, RepositoryInitOptions, Signature, StatusOptions};
@@ -35,7 +34,46 @@ pub fn is_git_repo(dir_path: &Path) -> Result<bool, Error> {
     }
 }
 
-pub fn create_init_commit(project: Arc<Project>, dir_path: &Path) {
+/// Commit checked out in a repository
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitRevision {
+    pub commit: String,
+    /// Whether the working tree has uncommitted changes, including untracked files
+    pub dirty: bool,
+}
+
+/// Commit checked out in the repository containing `dir_path`, or `None`
+/// outside a repository or before the first commit.
+pub fn current_revision(dir_path: &Path) -> Option<GitRevision> {
+    let repo = Repository::discover(dir_path).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+
+    let mut status_opts = StatusOptions::new();
+    status_opts.include_untracked(true);
+    let dirty = repo
+        .statuses(Some(&mut status_opts))
+        .map(|statuses| !statuses.is_empty())
+        .unwrap_or(false);
+
+    Some(GitRevision {
+        commit: commit.id().to_string(),
+        dirty,
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum InitRepoError {
+    #[error("failed to write .gitignore: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Git(#[from] Error),
+}
+
+/// Initializes a repository in `dir_path` and commits the generated project on
+/// `main`, after writing a `.gitignore` for the project language.
+pub fn create_init_commit(project: &Project, dir_path: &Path) -> Result<(), InitRepoError> {
     let mut git_ignore_file = project.project_location.clone();
     git_ignore_file.push(GITIGNORE);
 
@@ -89,41 +127,39 @@ pub fn create_init_commit(project: Arc<Project>, dir_path: &Path) {
     });
     let mut git_ignore = git_ignore_entries.join("\n");
     git_ignore.push_str("\n\n");
-    std::fs::write(git_ignore_file, git_ignore).unwrap();
+    std::fs::write(git_ignore_file, git_ignore)?;
 
     let mut repo_create_options = RepositoryInitOptions::new();
     repo_create_options.initial_head("main");
-    let repo = Repository::init_opts(dir_path, &repo_create_options)
-        .expect("Failed to initialize git repo");
+    let repo = Repository::init_opts(dir_path, &repo_create_options)?;
 
-    let author =
-        Signature::now("Moose CLI", "noreply@fiveonefour.com").expect("Failed to create signature");
-
-    // Now let's create an empty tree for this commit
-    let mut index = repo.index().expect("Failed to get repo index");
-
-    index
-        .add_all(["."], git2::IndexAddOption::DEFAULT, None)
-        .expect("Failed to add path to index");
+    let author = Signature::now("Moose CLI", "noreply@fiveonefour.com")?;
 
-    index.write().expect("Failed to write index");
+    let mut index = repo.index()?;
+    index.add_all(["."], git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
 
-    let tree_id = index.write_tree().expect("Failed to write tree");
-    let tree = repo.find_tree(tree_id).expect("Failed to find tree");
+    let tree_id = index.write_tree()?;
+    let tree = repo.find_tree(tree_id)?;
 
     // empty parent because it's the first commit
-    repo.commit(Some("HEAD"), &author, &author, "Initial commit", &tree, &[])
-        .expect("Failed to create initial commit");
+    repo.commit(Some("HEAD"), &author, &author, "Initial commit", &tree, &[])?;
+    Ok(())
 }
 
 /// Create a new commit with current workspace changes after code generation.
-/// Returns Ok(Some(oid)) when a commit was created, Ok(None) when there were no changes to commit.
+/// Returns Ok(Some(oid)) when a commit was created, Ok(None) when there were no changes to commit
+/// or `dir_path` is not inside a git repository.
 pub fn create_code_generation_commit(
     dir_path: &Path,
     message: &str,
 ) -> Result<Option<git2::Oid>, Error> {
     // Discover existing repository starting from dir_path
-    let repo = Repository::discover(dir_path)?;
+    let repo = match Repository::discover(dir_path) {
+        Ok(repo) => repo,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
 
     // Skip if there are no changes to commit (including untracked files)
     let mut status_opts = StatusOptions::new();
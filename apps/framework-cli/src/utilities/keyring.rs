@@ -1,8 +1,18 @@
+use std::path::PathBuf;
+
 use keyring::Entry;
+use tracing::debug;
 
+use crate::cli::display::{self, Message, MessageType};
+use crate::cli::settings::user_directory;
 use crate::utilities::constants::MOOSE_CLI_NAME;
 use thiserror::Error;
 
+/// Overrides the directory used by [`FileSecretRepository::from_env`].
+pub const SECRETS_DIR_ENV_VAR: &str = "MOOSE_SECRETS_DIR";
+
+const SECRETS_DIR: &str = "secrets";
+
 #[derive(Debug, Error)]
 pub enum SecretError {
     #[error("Storage error: {0}")]
@@ -52,3 +62,323 @@ impl SecretRepository for KeyringSecretRepository {
             .map_err(|e| SecretError::StorageError(e.to_string()))
     }
 }
+
+/// Secrets stored as files, `<root>/<project_name>/<key>`, for environments
+/// without an OS keyring such as containers. The layout matches Docker and
+/// Kubernetes secrets mounted as a directory. Values are trimmed when read.
+#[derive(Debug)]
+pub struct FileSecretRepository {
+    root: PathBuf,
+}
+
+impl FileSecretRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// `MOOSE_SECRETS_DIR`, or `secrets` in the Moose user directory.
+    pub fn from_env() -> Result<Self, SecretError> {
+        match std::env::var(SECRETS_DIR_ENV_VAR) {
+            Ok(dir) if !dir.is_empty() => Ok(Self::new(dir)),
+            _ => user_directory()
+                .map(|dir| Self::new(dir.join(SECRETS_DIR)))
+                .map_err(|e| SecretError::StorageError(e.to_string())),
+        }
+    }
+
+    fn path(&self, project_name: &str, key: &str) -> PathBuf {
+        self.root.join(project_name).join(key)
+    }
+}
+
+impl SecretRepository for FileSecretRepository {
+    fn store(&self, project_name: &str, key: &str, value: &str) -> Result<(), SecretError> {
+        let path = self.path(project_name, key);
+        if let Some(parent) = path.parent() {
+            // Only the user can list and read the secrets
+            let mut builder = std::fs::DirBuilder::new();
+            builder.recursive(true);
+            #[cfg(unix)]
+            std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+            builder
+                .create(parent)
+                .map_err(|e| SecretError::StorageError(format!("{}: {e}", parent.display())))?;
+        }
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file = options
+            .open(&path)
+            .map_err(|e| SecretError::StorageError(format!("{}: {e}", path.display())))?;
+        // The mode only applies to new files
+        #[cfg(unix)]
+        file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))
+            .map_err(|e| SecretError::StorageError(format!("{}: {e}", path.display())))?;
+        std::io::Write::write_all(&mut file, value.as_bytes())
+            .map_err(|e| SecretError::StorageError(format!("{}: {e}", path.display())))
+    }
+
+    fn get(&self, project_name: &str, key: &str) -> Result<Option<String>, SecretError> {
+        let path = self.path(project_name, key);
+        match std::fs::read_to_string(&path) {
+            Ok(value) => Ok(Some(value.trim().to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(SecretError::StorageError(format!(
+                "{}: {e}",
+                path.display()
+            ))),
+        }
+    }
+
+    fn delete(&self, project_name: &str, key: &str) -> Result<(), SecretError> {
+        let path = self.path(project_name, key);
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(SecretError::StorageError(
+                format!("{}: {e}", path.display()),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Reads from `primary`, then from `fallback` when the secret is missing or
+/// `primary` is unavailable. Stores to `fallback` only when `primary` fails.
+#[derive(Debug)]
+pub struct FallbackSecretRepository<P, F> {
+    primary: P,
+    fallback: F,
+}
+
+impl<P: SecretRepository, F: SecretRepository> FallbackSecretRepository<P, F> {
+    pub fn new(primary: P, fallback: F) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+impl<P: SecretRepository, F: SecretRepository> SecretRepository for FallbackSecretRepository<P, F> {
+    fn store(&self, project_name: &str, key: &str, value: &str) -> Result<(), SecretError> {
+        self.primary.store(project_name, key, value).or_else(|e| {
+            debug!("Storing secret '{key}' in the fallback repository: {e}");
+            display::show_message_wrapper(
+                MessageType::Warning,
+                Message::new(
+                    "Secrets".to_string(),
+                    format!(
+                        "OS keychain unavailable ({e}), storing '{key}' unencrypted in a file \
+                         readable only by you, under ${SECRETS_DIR_ENV_VAR} or ~/.moose/{SECRETS_DIR}"
+                    ),
+                ),
+            );
+            self.fallback.store(project_name, key, value)
+        })
+    }
+
+    fn get(&self, project_name: &str, key: &str) -> Result<Option<String>, SecretError> {
+        match self.primary.get(project_name, key) {
+            Ok(Some(value)) => Ok(Some(value)),
+            Ok(None) => self.fallback.get(project_name, key),
+            Err(e) => {
+                debug!("Reading secret '{key}' from the fallback repository: {e}");
+                self.fallback.get(project_name, key)
+            }
+        }
+    }
+
+    fn delete(&self, project_name: &str, key: &str) -> Result<(), SecretError> {
+        let primary = self.primary.delete(project_name, key);
+        let fallback = self.fallback.delete(project_name, key);
+        primary.or(fallback)
+    }
+}
+
+/// The OS keyring, falling back to [`FileSecretRepository::from_env`] when the
+/// keyring is unavailable, e.g. inside a container.
+pub fn default_secret_repository() -> Box<dyn SecretRepository> {
+    match FileSecretRepository::from_env() {
+        Ok(files) => Box::new(FallbackSecretRepository::new(
+            KeyringSecretRepository,
+            files,
+        )),
+        Err(e) => {
+            debug!("File secrets unavailable: {e}");
+            Box::new(KeyringSecretRepository)
+        }
+    }
+}
+
+impl<R: SecretRepository + ?Sized> SecretRepository for Box<R> {
+    fn store(&self, project_name: &str, key: &str, value: &str) -> Result<(), SecretError> {
+        (**self).store(project_name, key, value)
+    }
+
+    fn get(&self, project_name: &str, key: &str) -> Result<Option<String>, SecretError> {
+        (**self).get(project_name, key)
+    }
+
+    fn delete(&self, project_name: &str, key: &str) -> Result<(), SecretError> {
+        (**self).delete(project_name, key)
+    }
+}
+
+impl<R: SecretRepository + ?Sized> SecretRepository for &R {
+    fn store(&self, project_name: &str, key: &str, value: &str) -> Result<(), SecretError> {
+        (**self).store(project_name, key, value)
+    }
+
+    fn get(&self, project_name: &str, key: &str) -> Result<Option<String>, SecretError> {
+        (**self).get(project_name, key)
+    }
+
+    fn delete(&self, project_name: &str, key: &str) -> Result<(), SecretError> {
+        (**self).delete(project_name, key)
+    }
+}
+
+/// In-memory stand-in for the OS keyring in tests, optionally unavailable.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub(crate) struct MemorySecretRepository {
+    values: std::sync::Mutex<std::collections::HashMap<(String, String), String>>,
+    unavailable: bool,
+}
+
+#[cfg(test)]
+impl MemorySecretRepository {
+    pub(crate) fn unavailable() -> Self {
+        Self {
+            unavailable: true,
+            ..Default::default()
+        }
+    }
+
+    fn check(&self) -> Result<(), SecretError> {
+        if self.unavailable {
+            Err(SecretError::StorageError("no keyring".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+impl SecretRepository for MemorySecretRepository {
+    fn store(&self, project_name: &str, key: &str, value: &str) -> Result<(), SecretError> {
+        self.check()?;
+        self.values.lock().unwrap().insert(
+            (project_name.to_string(), key.to_string()),
+            value.to_string(),
+        );
+        Ok(())
+    }
+
+    fn get(&self, project_name: &str, key: &str) -> Result<Option<String>, SecretError> {
+        self.check()?;
+        Ok(self
+            .values
+            .lock()
+            .unwrap()
+            .get(&(project_name.to_string(), key.to_string()))
+            .cloned())
+    }
+
+    fn delete(&self, project_name: &str, key: &str) -> Result<(), SecretError> {
+        self.check()?;
+        self.values
+            .lock()
+            .unwrap()
+            .remove(&(project_name.to_string(), key.to_string()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_repository_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = FileSecretRepository::new(dir.path());
+
+        assert_eq!(repo.get("proj", "token").unwrap(), None);
+        repo.store("proj", "token", "abc").unwrap();
+        assert_eq!(repo.get("proj", "token").unwrap(), Some("abc".to_string()));
+
+        // Mounted secrets often end with a newline
+        std::fs::write(dir.path().join("proj").join("mounted"), "s3cret\n").unwrap();
+        assert_eq!(
+            repo.get("proj", "mounted").unwrap(),
+            Some("s3cret".to_string())
+        );
+
+        repo.delete("proj", "token").unwrap();
+        repo.delete("proj", "token").unwrap();
+        assert_eq!(repo.get("proj", "token").unwrap(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_files_are_readable_only_by_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = FileSecretRepository::new(dir.path().join("secrets"));
+        repo.store("proj", "token", "abc").unwrap();
+
+        let mode = |path: PathBuf| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(dir.path().join("secrets")), 0o700);
+        assert_eq!(mode(dir.path().join("secrets").join("proj")), 0o700);
+        assert_eq!(mode(repo.path("proj", "token")), 0o600);
+
+        // A file created with a wider mode is restricted when overwritten
+        let path = repo.path("proj", "token");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        repo.store("proj", "token", "def").unwrap();
+        assert_eq!(mode(path), 0o600);
+    }
+
+    #[test]
+    fn test_keyring_takes_precedence_over_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let files = FileSecretRepository::new(dir.path());
+        files.store("proj", "password", "from-file").unwrap();
+        files.store("proj", "user", "file-user").unwrap();
+
+        let keyring = MemorySecretRepository::default();
+        keyring.store("proj", "password", "from-keyring").unwrap();
+        let repo = FallbackSecretRepository::new(keyring, files);
+
+        assert_eq!(
+            repo.get("proj", "password").unwrap(),
+            Some("from-keyring".to_string())
+        );
+        // Missing from the keyring, found in files
+        assert_eq!(
+            repo.get("proj", "user").unwrap(),
+            Some("file-user".to_string())
+        );
+        assert_eq!(repo.get("proj", "missing").unwrap(), None);
+    }
+
+    #[test]
+    fn test_files_are_used_when_keyring_is_unavailable() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = FallbackSecretRepository::new(
+            MemorySecretRepository::unavailable(),
+            FileSecretRepository::new(dir.path()),
+        );
+
+        repo.store("proj", "url", "https://ch.internal").unwrap();
+        assert!(dir.path().join("proj").join("url").exists());
+        assert_eq!(
+            repo.get("proj", "url").unwrap(),
+            Some("https://ch.internal".to_string())
+        );
+
+        repo.delete("proj", "url").unwrap();
+        assert_eq!(repo.get("proj", "url").unwrap(), None);
+    }
+}
@@ -39,7 +39,15 @@ pub fn get_or_create_machine_id() -> String {
     new_id
 }
 
-fn get_machine_id_path() -> PathBuf {
+/// The machine ID if one was created, without creating it.
+pub fn read_machine_id() -> Option<String> {
+    fs::read_to_string(get_machine_id_path())
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+pub fn get_machine_id_path() -> PathBuf {
     home_dir()
         .expect("Could not determine home directory")
         .join(MACHINE_ID_FILE)
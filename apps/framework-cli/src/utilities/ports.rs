@@ -0,0 +1,135 @@
+//! Helpers for probing local TCP ports before binding servers to them.
+use std::fmt;
+use std::net::TcpListener;
+use std::process::{Command, Stdio};
+
+/// Number of ports scanned above the requested one when looking for a free port.
+pub const PORT_SEARCH_RANGE: u16 = 100;
+
+/// A process that is listening on a port, as reported by `lsof`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortHolder {
+    pub pid: u32,
+    pub command: String,
+}
+
+impl fmt::Display for PortHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (pid {})", self.command, self.pid)
+    }
+}
+
+/// Returns true if a listener can be bound to `host:port` right now.
+///
+/// The probe listener is dropped immediately, so this only detects
+/// EADDRINUSE up front; it does not reserve the port.
+pub fn is_port_available(host: &str, port: u16) -> bool {
+    TcpListener::bind((host, port)).is_ok()
+}
+
+/// Finds the first port after `start` that is not in `reserved` and for which
+/// `is_available` returns true, scanning at most [`PORT_SEARCH_RANGE`] ports.
+pub fn next_free_port_with<F>(start: u16, reserved: &[u16], mut is_available: F) -> Option<u16>
+where
+    F: FnMut(u16) -> bool,
+{
+    (1..=PORT_SEARCH_RANGE)
+        .filter_map(|offset| start.checked_add(offset))
+        .find(|port| !reserved.contains(port) && is_available(*port))
+}
+
+/// Finds the next port after `start` that can be bound on `host`, skipping
+/// the ports in `reserved` (e.g. the other ports this process is about to use).
+pub fn next_free_port(host: &str, start: u16, reserved: &[u16]) -> Option<u16> {
+    next_free_port_with(start, reserved, |port| is_port_available(host, port))
+}
+
+/// Looks up the process listening on `port` using `lsof`.
+///
+/// Returns `None` when `lsof` is not installed, the holder belongs to another
+/// user, or nothing is listening.
+pub fn port_holder(port: u16) -> Option<PortHolder> {
+    let output = Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{port}"), "-sTCP:LISTEN", "-Fpc"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    parse_lsof_listener(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parses the first process out of `lsof -F pc` output, where each field is
+/// on its own line and prefixed by its field letter (`p1234`, `cnode`).
+fn parse_lsof_listener(output: &str) -> Option<PortHolder> {
+    let mut pid = None;
+    for line in output.lines() {
+        if let Some(value) = line.strip_prefix('p') {
+            pid = value.trim().parse::<u32>().ok();
+        } else if let Some(command) = line.strip_prefix('c') {
+            if let Some(pid) = pid {
+                return Some(PortHolder {
+                    pid,
+                    command: command.trim().to_string(),
+                });
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_port_available_detects_bound_port() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(!is_port_available("127.0.0.1", port));
+
+        drop(listener);
+        assert!(is_port_available("127.0.0.1", port));
+    }
+
+    #[test]
+    fn test_next_free_port_skips_busy_and_reserved_ports() {
+        let busy = [4001, 4002];
+        let port = next_free_port_with(4000, &[4003], |p| !busy.contains(&p));
+        assert_eq!(port, Some(4004));
+    }
+
+    #[test]
+    fn test_next_free_port_gives_up_after_search_range() {
+        assert_eq!(next_free_port_with(4000, &[], |_| false), None);
+        assert_eq!(next_free_port_with(u16::MAX, &[], |_| true), None);
+    }
+
+    #[test]
+    fn test_next_free_port_finds_real_port() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let next = next_free_port("127.0.0.1", port.saturating_sub(1), &[]);
+        assert!(next.is_some_and(|p| p != port));
+    }
+
+    #[test]
+    fn test_parse_lsof_listener() {
+        let output = "p4242\ncnode\nf23\np4343\ncpython\n";
+        assert_eq!(
+            parse_lsof_listener(output),
+            Some(PortHolder {
+                pid: 4242,
+                command: "node".to_string(),
+            })
+        );
+        assert_eq!(parse_lsof_listener(""), None);
+        assert_eq!(parse_lsof_listener("cnode\n"), None);
+    }
+}
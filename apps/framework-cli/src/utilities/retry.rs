@@ -1,19 +1,224 @@
+//! Retries with exponential backoff, driven by named policies.
+//!
+//! Each kind of remote dependency has a [`RetryTarget`] whose policy can be
+//! tuned in `moose.config.toml`:
+//!
+//! ```toml
+//! [retries.clickhouse]
+//! max_attempts = 30
+//! base_delay_ms = 200
+//! max_delay_ms = 5000
+//! jitter = 0.2
+//! max_elapsed_ms = 60000
+//! ```
+//!
+//! Call sites look up their policy with [`policy`], passing the behavior they
+//! had before policies existed as the default. Keys left out of the config
+//! keep that default, so an unconfigured project behaves exactly as before.
+
+use std::future::{ready, Future};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::{Duration, Instant};
+
+/// The dependency a retried operation talks to, naming its policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryTarget {
+    ClickHouse,
+    Http,
+    Redis,
+    Kafka,
+}
+
+/// How often and how long to retry an operation.
+///
+/// The delay before retry `n` (from 0) is `base_delay * 2^n`, capped at
+/// `max_delay`, then reduced by a random fraction of up to `jitter`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts including the first, unlimited when `None`
+    pub max_attempts: Option<u32>,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Fraction of each delay that is randomized, from 0.0 to 1.0
+    pub jitter: f64,
+    /// No retry starts once this much time has passed since the first attempt
+    pub max_elapsed: Option<Duration>,
+}
+
+impl RetryPolicy {
+    /// A single attempt.
+    pub const NO_RETRY: RetryPolicy = RetryPolicy {
+        max_attempts: Some(1),
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+        jitter: 0.0,
+        max_elapsed: None,
+    };
+
+    /// `max_attempts` attempts `delay` apart.
+    pub const fn fixed(max_attempts: u32, delay: Duration) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: Some(max_attempts),
+            base_delay: delay,
+            max_delay: delay,
+            jitter: 0.0,
+            max_elapsed: None,
+        }
+    }
+
+    /// The delay before retry `retry`, counted from 0, without jitter.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.checked_pow(retry).unwrap_or(u32::MAX);
+        self.base_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+
+    /// The delay before retry `retry` with the jitter applied.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        self.backoff(retry)
+            .mul_f64(1.0 - jitter * random_fraction())
+    }
+
+    /// Whether another attempt may follow `attempts` failed ones, `elapsed`
+    /// after the first one started.
+    pub fn allows_retry(&self, attempts: u32, elapsed: Duration) -> bool {
+        self.max_attempts.is_none_or(|max| attempts < max)
+            && self.max_elapsed.is_none_or(|max| elapsed < max)
+    }
+}
+
+/// A policy in `[retries.<target>]`, each key overriding the call site default.
+/// A `max_attempts` of 0 retries without limit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicyConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_delay_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_delay_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jitter: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_elapsed_ms: Option<u64>,
+}
+
+impl RetryPolicyConfig {
+    fn apply_to(&self, default: RetryPolicy) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: match self.max_attempts {
+                Some(0) => None,
+                Some(max) => Some(max),
+                None => default.max_attempts,
+            },
+            base_delay: self
+                .base_delay_ms
+                .map_or(default.base_delay, Duration::from_millis),
+            max_delay: self
+                .max_delay_ms
+                .map_or(default.max_delay, Duration::from_millis),
+            jitter: self.jitter.unwrap_or(default.jitter),
+            max_elapsed: self
+                .max_elapsed_ms
+                .map(Duration::from_millis)
+                .or(default.max_elapsed),
+        }
+    }
+}
+
+/// The `[retries]` section of the project config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetriesConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clickhouse: Option<RetryPolicyConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<RetryPolicyConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redis: Option<RetryPolicyConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kafka: Option<RetryPolicyConfig>,
+}
+
+impl RetriesConfig {
+    /// The policy of `target`, falling back to `default` for anything not configured.
+    pub fn policy(&self, target: RetryTarget, default: RetryPolicy) -> RetryPolicy {
+        let configured = match target {
+            RetryTarget::ClickHouse => &self.clickhouse,
+            RetryTarget::Http => &self.http,
+            RetryTarget::Redis => &self.redis,
+            RetryTarget::Kafka => &self.kafka,
+        };
+        configured
+            .as_ref()
+            .map_or(default, |config| config.apply_to(default))
+    }
+}
+
+static RETRIES: RwLock<RetriesConfig> = RwLock::new(RetriesConfig {
+    clickhouse: None,
+    http: None,
+    redis: None,
+    kafka: None,
+});
+
+/// Sets the policies used by [`policy`], from the loaded project config.
+pub fn set_retries_config(config: RetriesConfig) {
+    *RETRIES.write().unwrap() = config;
+}
+
+/// The configured policy of `target`, falling back to `default`.
+pub fn policy(target: RetryTarget, default: RetryPolicy) -> RetryPolicy {
+    RETRIES.read().unwrap().policy(target, default)
+}
+
+/// A random number in `[0, 1)`, good enough to spread out retries.
+fn random_fraction() -> f64 {
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Runs `action` until it succeeds, `should_retry` rejects its error, or
+/// `policy` allows no more attempts.
 pub async fn retry<E, T, F>(
     action: impl Fn() -> F,
-    should_retry: fn(u32, &E) -> bool,
-    delay: tokio::time::Duration,
+    should_retry: impl Fn(&E) -> bool,
+    policy: &RetryPolicy,
 ) -> Result<T, E>
 where
-    F: std::future::Future<Output = Result<T, E>>,
+    F: Future<Output = Result<T, E>>,
 {
-    let mut i = 0;
+    retry_with(action, |err| ready(should_retry(err)), policy).await
+}
+
+/// Same as [`retry`] with a predicate that can await, e.g. to ask the server
+/// whether retrying is worth it. The predicate only runs when `policy` allows
+/// another attempt, and its future cannot borrow the error, so it takes what
+/// it needs from the error before returning.
+pub async fn retry_with<E, T, F, P>(
+    action: impl Fn() -> F,
+    should_retry: impl Fn(&E) -> P,
+    policy: &RetryPolicy,
+) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    P: Future<Output = bool>,
+{
+    let start = Instant::now();
+    let mut attempts = 0;
     loop {
         match action().await {
             Ok(res) => return Ok(res),
             Err(err) => {
-                if should_retry(i, &err) {
-                    i += 1;
-                    tokio::time::sleep(delay).await;
+                attempts += 1;
+                if policy.allows_retry(attempts, start.elapsed()) && should_retry(&err).await {
+                    tokio::time::sleep(policy.delay(attempts - 1)).await;
                 } else {
                     return Err(err);
                 }
@@ -21,3 +226,217 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_exponential_backoff_is_capped() {
+        let policy = RetryPolicy {
+            max_attempts: None,
+            base_delay: Duration::from_secs(5),
+            max_delay: Duration::from_secs(60),
+            jitter: 0.0,
+            max_elapsed: None,
+        };
+        let delays: Vec<u64> = (0..6).map(|n| policy.backoff(n).as_secs()).collect();
+        assert_eq!(delays, vec![5, 10, 20, 40, 60, 60]);
+        // No overflow far out
+        assert_eq!(policy.backoff(64), Duration::from_secs(60));
+
+        let fixed = RetryPolicy::fixed(21, Duration::from_millis(200));
+        assert_eq!(fixed.backoff(0), Duration::from_millis(200));
+        assert_eq!(fixed.backoff(19), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_jitter_only_shortens_delays() {
+        let policy = RetryPolicy {
+            jitter: 0.5,
+            ..RetryPolicy::fixed(10, Duration::from_millis(1000))
+        };
+        for retry in 0..50 {
+            let delay = policy.delay(retry);
+            assert!(delay <= Duration::from_millis(1000), "{delay:?}");
+            assert!(delay >= Duration::from_millis(500), "{delay:?}");
+        }
+        assert_eq!(
+            RetryPolicy::fixed(2, Duration::from_millis(10)).delay(0),
+            Duration::from_millis(10)
+        );
+    }
+
+    #[test]
+    fn test_attempt_and_elapsed_limits() {
+        let policy = RetryPolicy::fixed(3, Duration::ZERO);
+        assert!(policy.allows_retry(2, Duration::ZERO));
+        assert!(!policy.allows_retry(3, Duration::ZERO));
+        assert!(!RetryPolicy::NO_RETRY.allows_retry(1, Duration::ZERO));
+
+        let policy = RetryPolicy {
+            max_attempts: None,
+            max_elapsed: Some(Duration::from_secs(1)),
+            ..policy
+        };
+        assert!(policy.allows_retry(1000, Duration::from_millis(999)));
+        assert!(!policy.allows_retry(1, Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_policy_lookup_falls_back_to_default() {
+        let default = RetryPolicy::fixed(21, Duration::from_millis(200));
+        let config: RetriesConfig = toml::from_str(
+            "[clickhouse]\nmax_attempts = 5\njitter = 0.1\n\n[redis]\nmax_attempts = 0",
+        )
+        .unwrap();
+
+        // Unconfigured target
+        assert_eq!(config.policy(RetryTarget::Http, default), default);
+        // Configured keys override, the others keep the default
+        assert_eq!(
+            config.policy(RetryTarget::ClickHouse, default),
+            RetryPolicy {
+                max_attempts: Some(5),
+                jitter: 0.1,
+                ..default
+            }
+        );
+        // 0 attempts is unlimited
+        assert_eq!(
+            config.policy(RetryTarget::Redis, default).max_attempts,
+            None
+        );
+        assert_eq!(
+            RetriesConfig::default().policy(RetryTarget::Kafka, default),
+            default
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_at_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), &str> = retry(
+            || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err("down")
+            },
+            |_| true,
+            &RetryPolicy::fixed(4, Duration::ZERO),
+        )
+        .await;
+        assert_eq!(result, Err("down"));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        // Errors rejected by `should_retry` are returned at once
+        calls.store(0, Ordering::SeqCst);
+        let _ = retry(
+            || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>("fatal")
+            },
+            |_| false,
+            &RetryPolicy::fixed(4, Duration::ZERO),
+        )
+        .await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_backs_off_exponentially() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy {
+            max_attempts: Some(6),
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            jitter: 0.0,
+            max_elapsed: None,
+        };
+        let start = Instant::now();
+        let result: Result<(), &str> = retry(
+            || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err("down")
+            },
+            |_| true,
+            &policy,
+        )
+        .await;
+
+        assert_eq!(result, Err("down"));
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+        // 100 + 200 + 400 + 800 + 1000
+        assert_eq!(start.elapsed(), Duration::from_millis(2500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_stops_at_max_elapsed() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy {
+            max_attempts: None,
+            max_elapsed: Some(Duration::from_secs(5)),
+            ..RetryPolicy::fixed(0, Duration::from_secs(1))
+        };
+        let start = Instant::now();
+        let result: Result<(), &str> = retry(
+            || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err("down")
+            },
+            |_| true,
+            &policy,
+        )
+        .await;
+
+        assert_eq!(result, Err("down"));
+        // Attempts at 0s, 1s, ..., 5s, none after 5s have passed
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_with_async_predicate() {
+        let calls = AtomicU32::new(0);
+        let probes = AtomicU32::new(0);
+        let start = Instant::now();
+        let result: Result<u32, u32> = retry_with(
+            || async {
+                let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                Err(call)
+            },
+            |&call| {
+                let probes = &probes;
+                async move {
+                    // A slow check on the server says the first two failures are worth retrying
+                    tokio::time::sleep(Duration::from_secs(2)).await;
+                    probes.fetch_add(1, Ordering::SeqCst);
+                    call < 3
+                }
+            },
+            &RetryPolicy::fixed(10, Duration::from_secs(1)),
+        )
+        .await;
+
+        assert_eq!(result, Err(3));
+        assert_eq!(probes.load(Ordering::SeqCst), 3);
+        // Three 2s probes and two 1s delays
+        assert_eq!(start.elapsed(), Duration::from_secs(8));
+
+        // The predicate is not asked once the policy allows no more attempts
+        probes.store(0, Ordering::SeqCst);
+        let _ = retry_with(
+            || async { Err::<(), _>(0) },
+            |_| {
+                let probes = &probes;
+                async move {
+                    probes.fetch_add(1, Ordering::SeqCst);
+                    true
+                }
+            },
+            &RetryPolicy::NO_RETRY,
+        )
+        .await;
+        assert_eq!(probes.load(Ordering::SeqCst), 0);
+    }
+}
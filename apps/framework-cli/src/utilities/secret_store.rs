@@ -0,0 +1,221 @@
+//! Named secrets of a project, managed with `moose secret` and referenced from
+//! config fields as `secret:<name>`.
+//!
+//! Secrets live in a [`SecretRepository`] under the project's namespace, each
+//! one as `secret.<name>`. Repositories such as the OS keyring cannot be
+//! enumerated, so the names are also kept in an index entry for `list`.
+
+use thiserror::Error;
+
+use crate::utilities::keyring::{default_secret_repository, SecretError, SecretRepository};
+
+/// Value prefix referencing a named secret from a config field.
+pub const SECRET_REFERENCE_PREFIX: &str = "secret:";
+
+const SECRET_KEY_PREFIX: &str = "secret.";
+const INDEX_KEY: &str = "secret_names";
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SecretStoreError {
+    #[error("invalid secret name '{0}', use letters, digits, '_', '-' and '.'")]
+    InvalidName(String),
+
+    #[error("the secret index is corrupt: {0}")]
+    CorruptIndex(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Repository(#[from] SecretError),
+}
+
+/// The named secrets of one project.
+pub struct SecretStore<R> {
+    repo: R,
+    project_name: String,
+}
+
+/// The named secrets of `project_name` in the [`default_secret_repository`].
+pub fn project_secrets(project_name: &str) -> SecretStore<Box<dyn SecretRepository>> {
+    SecretStore::new(default_secret_repository(), project_name)
+}
+
+impl<R: SecretRepository> SecretStore<R> {
+    pub fn new(repo: R, project_name: &str) -> Self {
+        Self {
+            repo,
+            project_name: project_name.to_string(),
+        }
+    }
+
+    pub fn set(&self, name: &str, value: &str) -> Result<(), SecretStoreError> {
+        validate_name(name)?;
+        self.repo
+            .store(&self.project_name, &secret_key(name), value)?;
+
+        let mut names = self.list()?;
+        if !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+            self.write_index(names)?;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<String>, SecretStoreError> {
+        validate_name(name)?;
+        Ok(self.repo.get(&self.project_name, &secret_key(name))?)
+    }
+
+    /// Names of the stored secrets, sorted.
+    pub fn list(&self) -> Result<Vec<String>, SecretStoreError> {
+        let mut names: Vec<String> = match self.repo.get(&self.project_name, INDEX_KEY)? {
+            Some(index) => serde_json::from_str(&index)?,
+            None => Vec::new(),
+        };
+        names.sort();
+        Ok(names)
+    }
+
+    /// Deletes the secret, returning whether it existed.
+    pub fn delete(&self, name: &str) -> Result<bool, SecretStoreError> {
+        if self.get(name)?.is_none() {
+            return Ok(false);
+        }
+        self.repo.delete(&self.project_name, &secret_key(name))?;
+
+        let names = self
+            .list()?
+            .into_iter()
+            .filter(|existing| existing != name)
+            .collect();
+        self.write_index(names)?;
+        Ok(true)
+    }
+
+    /// Like [`SecretStore::get`], moving a value stored directly under
+    /// `legacy_key` by older versions into the secret `name` on first read.
+    pub fn get_or_migrate(
+        &self,
+        name: &str,
+        legacy_key: &str,
+    ) -> Result<Option<String>, SecretStoreError> {
+        if let Some(value) = self.get(name)? {
+            return Ok(Some(value));
+        }
+        let Some(value) = self.repo.get(&self.project_name, legacy_key)? else {
+            return Ok(None);
+        };
+
+        self.set(name, &value)?;
+        if let Err(e) = self.repo.delete(&self.project_name, legacy_key) {
+            tracing::debug!("Could not delete migrated secret '{legacy_key}': {e}");
+        }
+        Ok(Some(value))
+    }
+
+    fn write_index(&self, names: Vec<String>) -> Result<(), SecretStoreError> {
+        let index = serde_json::to_string(&names)?;
+        Ok(self.repo.store(&self.project_name, INDEX_KEY, &index)?)
+    }
+}
+
+fn secret_key(name: &str) -> String {
+    format!("{SECRET_KEY_PREFIX}{name}")
+}
+
+fn validate_name(name: &str) -> Result<(), SecretStoreError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(SecretStoreError::InvalidName(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utilities::keyring::MemorySecretRepository;
+
+    #[test]
+    fn test_set_get_list_delete() {
+        let store = SecretStore::new(MemorySecretRepository::default(), "proj");
+
+        store.set("warehouse_password", "hunter2").unwrap();
+        store.set("api-token", "abc").unwrap();
+        store.set("api-token", "def").unwrap();
+
+        assert_eq!(
+            store.get("warehouse_password").unwrap(),
+            Some("hunter2".to_string())
+        );
+        assert_eq!(store.get("api-token").unwrap(), Some("def".to_string()));
+        assert_eq!(
+            store.list().unwrap(),
+            vec!["api-token", "warehouse_password"]
+        );
+
+        assert!(store.delete("api-token").unwrap());
+        assert!(!store.delete("api-token").unwrap());
+        assert_eq!(store.get("api-token").unwrap(), None);
+        assert_eq!(store.list().unwrap(), vec!["warehouse_password"]);
+    }
+
+    #[test]
+    fn test_secrets_are_namespaced_per_project() {
+        let repo = MemorySecretRepository::default();
+        SecretStore::new(&repo, "a").set("token", "for-a").unwrap();
+
+        let other = SecretStore::new(&repo, "b");
+        assert_eq!(other.get("token").unwrap(), None);
+        assert!(other.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_invalid_names() {
+        let store = SecretStore::new(MemorySecretRepository::default(), "proj");
+        for name in ["", "../escape", "with space", "a/b"] {
+            assert!(
+                matches!(store.set(name, "v"), Err(SecretStoreError::InvalidName(_))),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_migrates_legacy_key_forward() {
+        let repo = MemorySecretRepository::default();
+        repo.store("proj", "remote_clickhouse_url", "https://old")
+            .unwrap();
+        let store = SecretStore::new(&repo, "proj");
+
+        assert_eq!(
+            store
+                .get_or_migrate("remote_clickhouse_url", "remote_clickhouse_url")
+                .unwrap(),
+            Some("https://old".to_string())
+        );
+        assert_eq!(
+            repo.get("proj", "remote_clickhouse_url").unwrap(),
+            None,
+            "legacy entry is removed once migrated"
+        );
+        assert_eq!(
+            store.get("remote_clickhouse_url").unwrap(),
+            Some("https://old".to_string())
+        );
+        assert_eq!(store.list().unwrap(), vec!["remote_clickhouse_url"]);
+
+        // The named secret wins over a legacy value written by an older CLI
+        repo.store("proj", "remote_clickhouse_url", "https://older")
+            .unwrap();
+        assert_eq!(
+            store
+                .get_or_migrate("remote_clickhouse_url", "remote_clickhouse_url")
+                .unwrap(),
+            Some("https://old".to_string())
+        );
+    }
+}
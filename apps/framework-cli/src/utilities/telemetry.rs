@@ -0,0 +1,130 @@
+//! Whether anonymous usage telemetry may be sent.
+//!
+//! Every telemetry emission site consults [`telemetry_enabled`], which combines
+//!
+//! - `telemetry.enabled` in `~/.moose/config.toml`, set with `moose config telemetry on|off`
+//! - the `MOOSE_TELEMETRY` environment variable: `0`, `false` or `off` disables
+//!   telemetry, `1`, `true` or `on` enables it in CI
+//! - CI detection: telemetry is off in CI unless `MOOSE_TELEMETRY` enables it
+//!
+//! Opting out in the settings file always wins.
+
+use std::fmt;
+
+use crate::cli::settings::Settings;
+use crate::utilities::ci_detection::{detect_ci_environment, CIEnvironment};
+
+/// Environment variable turning telemetry on or off for one run.
+pub const TELEMETRY_ENV_VAR: &str = "MOOSE_TELEMETRY";
+
+/// Whether telemetry is sent, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TelemetryStatus {
+    Enabled,
+    DisabledInSettings,
+    DisabledByEnv,
+    /// Running in CI, with the detected provider if any
+    DisabledInCi(Option<String>),
+}
+
+impl TelemetryStatus {
+    pub fn is_enabled(&self) -> bool {
+        matches!(self, TelemetryStatus::Enabled)
+    }
+}
+
+impl fmt::Display for TelemetryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TelemetryStatus::Enabled => write!(f, "enabled"),
+            TelemetryStatus::DisabledInSettings => {
+                write!(
+                    f,
+                    "disabled by telemetry.enabled = false in the CLI settings"
+                )
+            }
+            TelemetryStatus::DisabledByEnv => write!(f, "disabled by {TELEMETRY_ENV_VAR}"),
+            TelemetryStatus::DisabledInCi(Some(provider)) => write!(
+                f,
+                "disabled in CI ({provider}), set {TELEMETRY_ENV_VAR}=1 to enable"
+            ),
+            TelemetryStatus::DisabledInCi(None) => {
+                write!(f, "disabled in CI, set {TELEMETRY_ENV_VAR}=1 to enable")
+            }
+        }
+    }
+}
+
+/// The telemetry status for `settings` in the current environment.
+pub fn telemetry_status(settings: &Settings) -> TelemetryStatus {
+    let env = std::env::var(TELEMETRY_ENV_VAR).ok();
+    decide(
+        settings.telemetry.enabled,
+        env.as_deref(),
+        &detect_ci_environment(),
+    )
+}
+
+/// Whether telemetry may be sent for `settings` in the current environment.
+pub fn telemetry_enabled(settings: &Settings) -> bool {
+    telemetry_status(settings).is_enabled()
+}
+
+fn decide(enabled_in_settings: bool, env: Option<&str>, ci: &CIEnvironment) -> TelemetryStatus {
+    if !enabled_in_settings {
+        return TelemetryStatus::DisabledInSettings;
+    }
+    match env.map(|value| value.trim().to_lowercase()).as_deref() {
+        Some("0" | "false" | "off" | "no") => TelemetryStatus::DisabledByEnv,
+        Some("1" | "true" | "on" | "yes") => TelemetryStatus::Enabled,
+        _ if ci.is_ci => TelemetryStatus::DisabledInCi(ci.ci_provider.clone()),
+        _ => TelemetryStatus::Enabled,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ci(is_ci: bool) -> CIEnvironment {
+        CIEnvironment {
+            is_ci,
+            ci_provider: is_ci.then(|| "github_actions".to_string()),
+            is_docker: false,
+        }
+    }
+
+    #[test]
+    fn test_settings_opt_out_wins() {
+        for env in [None, Some("1"), Some("0")] {
+            assert_eq!(
+                decide(false, env, &ci(false)),
+                TelemetryStatus::DisabledInSettings
+            );
+        }
+    }
+
+    #[test]
+    fn test_env_var_and_ci() {
+        assert_eq!(decide(true, None, &ci(false)), TelemetryStatus::Enabled);
+        assert_eq!(
+            decide(true, Some("0"), &ci(false)),
+            TelemetryStatus::DisabledByEnv
+        );
+        assert_eq!(
+            decide(true, Some("OFF"), &ci(false)),
+            TelemetryStatus::DisabledByEnv
+        );
+        assert_eq!(
+            decide(true, None, &ci(true)),
+            TelemetryStatus::DisabledInCi(Some("github_actions".to_string()))
+        );
+        // Explicitly enabled in CI
+        assert_eq!(decide(true, Some("1"), &ci(true)), TelemetryStatus::Enabled);
+        // Unrecognized values don't change anything
+        assert_eq!(
+            decide(true, Some("maybe"), &ci(false)),
+            TelemetryStatus::Enabled
+        );
+    }
+}
@@ -33,6 +33,7 @@ from .blocks import (
     IcebergS3Engine,
     KafkaEngine,
     MergeEngine,
+    NullEngine,
     EngineConfig,
     # Legacy enum (already exported via .blocks import, but explicit for clarity)
     ClickHouseEngines,
@@ -20,6 +20,7 @@ class ClickHouseEngines(Enum):
     IcebergS3 = "IcebergS3"
     Kafka = "Kafka"
     Merge = "Merge"
+    Null = "Null"
     ReplicatedMergeTree = "ReplicatedMergeTree"
     ReplicatedReplacingMergeTree = "ReplicatedReplacingMergeTree"
     ReplicatedAggregatingMergeTree = "ReplicatedAggregatingMergeTree"
@@ -533,6 +534,19 @@ class MergeEngine(EngineConfig):
             raise ValueError("Merge engine requires 'tables_regexp'")
 
 
+@dataclass
+class NullEngine(EngineConfig):
+    """Configuration for Null engine - discards inserted rows, while materialized
+    views selecting from the table still receive them.
+
+    Note:
+        - SELECT queries on a Null table always return no rows
+        - Does not support ORDER BY, PARTITION BY, or SAMPLE BY clauses
+    """
+
+    pass
+
+
 # ==========================
 # New Table Configuration (Recommended API)
 # ==========================
@@ -77,6 +77,7 @@ from .workflow import (
     TaskConfig,
     Task,
     WorkflowConfig,
+    WorkflowRetryPolicy,
     Workflow,
 )
 
@@ -170,6 +171,7 @@ __all__ = [
     "TaskConfig",
     "Task",
     "WorkflowConfig",
+    "WorkflowRetryPolicy",
     "Workflow",
     # Lifecycle
     "LifeCycle",
@@ -209,6 +209,7 @@ class OlapConfig(BaseModel):
                 IcebergS3Engine,
                 KafkaEngine,
                 MergeEngine,
+                NullEngine,
             )
 
             # Engines that don't support ORDER BY
@@ -220,6 +221,7 @@ class OlapConfig(BaseModel):
                 KafkaEngine,
                 IcebergS3Engine,
                 MergeEngine,
+                NullEngine,
             )
             if isinstance(self.engine, engines_without_order_by):
                 engine_name = type(self.engine).__name__
@@ -239,6 +241,7 @@ class OlapConfig(BaseModel):
                 KafkaEngine,
                 IcebergS3Engine,
                 MergeEngine,
+                NullEngine,
             )
             if isinstance(self.engine, engines_without_sample_by):
                 engine_name = type(self.engine).__name__
@@ -258,6 +261,7 @@ class OlapConfig(BaseModel):
                 KafkaEngine,
                 IcebergS3Engine,
                 MergeEngine,
+                NullEngine,
             )
             if isinstance(self.engine, engines_without_partition_by):
                 engine_name = type(self.engine).__name__
@@ -277,6 +281,7 @@ class OlapConfig(BaseModel):
                 KafkaEngine,
                 IcebergS3Engine,
                 MergeEngine,
+                NullEngine,
             )
             if isinstance(self.engine, engines_without_projections):
                 engine_name = type(self.engine).__name__
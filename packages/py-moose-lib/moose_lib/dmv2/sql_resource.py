@@ -13,6 +13,7 @@ from ._registry import _sql_resources
 from ._source_capture import get_source_file_from_stack
 from .view import View
 from .materialized_view import MaterializedView
+from .life_cycle import LifeCycle
 
 
 class SqlResource:
@@ -29,6 +30,8 @@ class SqlResource:
         pushes_data_to (list[SqlObject]): List of tables/views this resource writes to.
         kind: The kind of the SQL resource (e.g., "SqlResource").
         source_file: Optional path to the source file where this resource was defined.
+        life_cycle: Optional lifecycle management policy. EXTERNALLY_MANAGED resources
+            are never created or dropped by Moose.
     """
 
     setup: list[str]
@@ -38,6 +41,7 @@ class SqlResource:
     pulls_data_from: list[Union[OlapTable, View, MaterializedView, "SqlResource"]]
     pushes_data_to: list[Union[OlapTable, View, MaterializedView, "SqlResource"]]
     source_file: Optional[str]
+    life_cycle: Optional[LifeCycle]
 
     def __init__(
         self,
@@ -51,6 +55,7 @@ class SqlResource:
             list[Union[OlapTable, View, MaterializedView, "SqlResource"]]
         ] = None,
         metadata: Optional[dict] = None,
+        life_cycle: Optional[LifeCycle] = None,
     ):
         self.name = name
         self.setup = setup
@@ -58,6 +63,7 @@ class SqlResource:
         self.pulls_data_from = pulls_data_from or []
         self.pushes_data_to = pushes_data_to or []
         self.metadata = metadata
+        self.life_cycle = life_cycle
         # Capture source file from call stack
         self.source_file = get_source_file_from_stack()
         _sql_resources[name] = self
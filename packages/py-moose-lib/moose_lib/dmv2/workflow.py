@@ -112,6 +112,26 @@ class Task(TypedMooseResource, Generic[T_none, U_none]):
         self.name = name
 
 
+@dataclasses.dataclass
+class WorkflowRetryPolicy:
+    """Retry policy of a workflow. Unset fields keep Temporal's defaults.
+
+    Attributes:
+        initial_interval: Delay before the first retry (e.g. "1s").
+        backoff_coefficient: Multiplier applied to the delay after each retry, at least 1.
+        maximum_interval: Upper bound of the delay between retries (e.g. "5m").
+        max_attempts: Total attempts including the first one, overriding `retries`.
+            0 retries until `execution_timeout`, which is then required.
+        non_retryable_error_types: Error types that fail the workflow without retrying.
+    """
+
+    initial_interval: Optional[str] = None
+    backoff_coefficient: Optional[float] = None
+    maximum_interval: Optional[str] = None
+    max_attempts: Optional[int] = None
+    non_retryable_error_types: list[str] = dataclasses.field(default_factory=list)
+
+
 @dataclasses.dataclass
 class WorkflowConfig:
     """Configuration for a workflow.
@@ -121,12 +141,18 @@ class WorkflowConfig:
         retries: Optional number of retry attempts for the entire workflow.
         timeout: Optional timeout string for the entire workflow.
         schedule: Optional cron-like schedule string for recurring execution.
+        timezone: Optional IANA time zone the schedule is read in (e.g. "Europe/Paris"), UTC by default.
+        execution_timeout: Optional limit across all runs and retries (e.g. "6h").
+        retry_policy: Optional retry policy, for tuning retries against flaky external APIs.
     """
 
     starting_task: Task[Any, Any]
     retries: Optional[int] = None
     timeout: Optional[str] = None
     schedule: Optional[str] = None
+    timezone: Optional[str] = None
+    execution_timeout: Optional[str] = None
+    retry_policy: Optional[WorkflowRetryPolicy] = None
 
 
 class Workflow:
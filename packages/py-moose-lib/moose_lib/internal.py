@@ -10,6 +10,7 @@ JSON format expected by the Moose infrastructure management system.
 from importlib import import_module
 from typing import Literal, Optional, List, Any, Dict, Union, TYPE_CHECKING
 from pydantic import BaseModel, ConfigDict, AliasGenerator, Field
+import dataclasses
 import json
 import os
 import sys
@@ -261,6 +262,12 @@ class MergeConfigDict(BaseEngineConfigDict):
     tables_regexp: str
 
 
+class NullConfigDict(BaseEngineConfigDict):
+    """Configuration for Null engine."""
+
+    engine: Literal["Null"] = "Null"
+
+
 # Discriminated union of all engine configurations
 EngineConfigDict = Union[
     MergeTreeConfigDict,
@@ -282,6 +289,7 @@ EngineConfigDict = Union[
     IcebergS3ConfigDict,
     KafkaConfigDict,
     MergeConfigDict,
+    NullConfigDict,
 ]
 
 
@@ -409,6 +417,26 @@ class InternalApiConfig(BaseModel):
     metadata: Optional[dict] = None
 
 
+class WorkflowRetryPolicyJson(BaseModel):
+    """Internal representation of a workflow retry policy for serialization.
+
+    Attributes:
+        initial_interval: Optional delay before the first retry.
+        backoff_coefficient: Optional multiplier applied to the delay after each retry.
+        maximum_interval: Optional upper bound of the delay between retries.
+        max_attempts: Optional total attempts including the first one.
+        non_retryable_error_types: Error types that fail without retrying.
+    """
+
+    model_config = model_config
+
+    initial_interval: Optional[str] = None
+    backoff_coefficient: Optional[float] = None
+    maximum_interval: Optional[str] = None
+    max_attempts: Optional[int] = None
+    non_retryable_error_types: List[str] = []
+
+
 class WorkflowJson(BaseModel):
     """Internal representation of a workflow configuration for serialization.
 
@@ -417,6 +445,9 @@ class WorkflowJson(BaseModel):
         retries: Optional number of retry attempts for the entire workflow.
         timeout: Optional timeout string for the entire workflow.
         schedule: Optional cron-like schedule string for recurring execution.
+        timezone: Optional IANA time zone the schedule is read in.
+        execution_timeout: Optional limit across all runs and retries.
+        retry_policy: Optional retry policy of the workflow.
     """
 
     model_config = model_config
@@ -425,6 +456,9 @@ class WorkflowJson(BaseModel):
     retries: Optional[int] = None
     timeout: Optional[str] = None
     schedule: Optional[str] = None
+    timezone: Optional[str] = None
+    execution_timeout: Optional[str] = None
+    retry_policy: Optional[WorkflowRetryPolicyJson] = None
 
 
 class WebAppMetadataJson(BaseModel):
@@ -488,6 +522,7 @@ class SqlResourceConfig(BaseModel):
         pushes_data_to: List of infrastructure components this resource writes to.
         source_file: Optional path to the source file where this resource is defined.
         metadata: Optional metadata for the resource.
+        life_cycle: Optional lifecycle management policy.
     """
 
     model_config = model_config
@@ -499,6 +534,7 @@ class SqlResourceConfig(BaseModel):
     pushes_data_to: list[InfrastructureSignatureJson]
     source_file: Optional[str] = None
     metadata: Optional[dict] = None
+    life_cycle: Optional[str] = None
 
 
 class MaterializedViewJson(BaseModel):
@@ -730,8 +766,13 @@ def _convert_engine_instance_to_config_dict(engine: "EngineConfig") -> EngineCon
         IcebergS3Engine,
         KafkaEngine,
         MergeEngine,
+        NullEngine,
     )
 
+    # Try Null
+    if isinstance(engine, NullEngine):
+        return NullConfigDict()
+
     # Try Merge
     if isinstance(engine, MergeEngine):
         return MergeConfigDict(
@@ -956,6 +997,7 @@ def _convert_engine_to_config_dict(
         "ReplicatedReplacingMergeTree": ReplicatedReplacingMergeTreeConfigDict,
         "ReplicatedAggregatingMergeTree": ReplicatedAggregatingMergeTreeConfigDict,
         "ReplicatedSummingMergeTree": ReplicatedSummingMergeTreeConfigDict,
+        "Null": NullConfigDict,
     }
 
     config_class = engine_map.get(engine_name)
@@ -1131,6 +1173,11 @@ def to_infra_map() -> dict:
             ],
             source_file=getattr(resource, "source_file", None),
             metadata=getattr(resource, "metadata", None),
+            life_cycle=(
+                resource.life_cycle.value
+                if getattr(resource, "life_cycle", None)
+                else None
+            ),
         )
 
     for name, workflow in get_workflows().items():
@@ -1139,6 +1186,15 @@ def to_infra_map() -> dict:
             retries=workflow.config.retries,
             timeout=workflow.config.timeout,
             schedule=workflow.config.schedule,
+            timezone=workflow.config.timezone,
+            execution_timeout=workflow.config.execution_timeout,
+            retry_policy=(
+                WorkflowRetryPolicyJson(
+                    **dataclasses.asdict(workflow.config.retry_policy)
+                )
+                if workflow.config.retry_policy
+                else None
+            ),
         )
 
     for name, web_app in get_web_apps().items():
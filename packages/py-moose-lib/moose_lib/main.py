@@ -193,6 +193,8 @@ class QueryClient:
         else:
             # Use provided ClickHouse client directly
             self.ch_client = ch_client_or_config
+        # Seconds spent in queries, reported by the API runner to the webserver
+        self.query_seconds = 0.0
 
     def __call__(self, input, variables):
         return self.execute(input, variables)
@@ -284,6 +286,7 @@ class QueryClient:
             },
         )
         secs = perf_counter() - start
+        self.query_seconds += secs
         if secs < 1:
             print(f"[QueryClient] | Query completed: {secs * 1000:.0f} ms")
         else:
@@ -0,0 +1,41 @@
+"""Tests for Null engine validation and serialization."""
+
+import pytest
+from moose_lib import OlapTable, OlapConfig
+from moose_lib.blocks import NullEngine
+from pydantic import BaseModel
+
+
+class SampleEvent(BaseModel):
+    event_id: str
+    timestamp: str
+
+
+@pytest.mark.parametrize(
+    ("config_kwargs", "expected_error"),
+    [
+        ({"order_by_fields": ["event_id"]}, "NullEngine does not support ORDER BY"),
+        (
+            {"partition_by": "toYYYYMM(timestamp)"},
+            "NullEngine does not support PARTITION BY",
+        ),
+        (
+            {"sample_by_expression": "event_id"},
+            "NullEngine does not support SAMPLE BY",
+        ),
+    ],
+)
+def test_null_engine_rejects_unsupported_clauses(config_kwargs, expected_error):
+    with pytest.raises(ValueError, match=expected_error):
+        OlapTable[SampleEvent](
+            "null_table",
+            OlapConfig(engine=NullEngine(), **config_kwargs),
+        )
+
+
+def test_null_engine_serialization():
+    from moose_lib.internal import _convert_engine_instance_to_config_dict
+
+    config_dict = _convert_engine_instance_to_config_dict(NullEngine())
+
+    assert config_dict.engine == "Null"
@@ -63,6 +63,8 @@ export class MooseClient {
 export class QueryClient {
   client: ClickHouseClient;
   query_id_prefix: string;
+  /** Milliseconds spent in queries and commands, reported to the webserver. */
+  queryTimeMs = 0;
   constructor(client: ClickHouseClient, query_id_prefix: string) {
     this.client = client;
     this.query_id_prefix = query_id_prefix;
@@ -88,6 +90,7 @@ export class QueryClient {
       },
     });
     const elapsedMs = performance.now() - start;
+    this.queryTimeMs += elapsedMs;
     console.log(
       `[QueryClient] | Query completed: ${formatElapsedTime(elapsedMs)}`,
     );
@@ -105,6 +108,7 @@ export class QueryClient {
       query_id: this.query_id_prefix + randomUUID(),
     });
     const elapsedMs = performance.now() - start;
+    this.queryTimeMs += elapsedMs;
     console.log(
       `[QueryClient] | Command completed: ${formatElapsedTime(elapsedMs)}`,
     );
@@ -268,11 +268,19 @@ const apiHandler = async (
         }
       }
 
+      // Lets the webserver tell the ClickHouse time from the handler's
+      const serverTiming = `db;dur=${queryClient.queryTimeMs.toFixed(1)}`;
       if (status) {
-        res.writeHead(status, { "Content-Type": "application/json" });
+        res.writeHead(status, {
+          "Content-Type": "application/json",
+          "Server-Timing": serverTiming,
+        });
         httpLogger(req, res, start, apiName);
       } else {
-        res.writeHead(200, { "Content-Type": "application/json" });
+        res.writeHead(200, {
+          "Content-Type": "application/json",
+          "Server-Timing": serverTiming,
+        });
         httpLogger(req, res, start, apiName);
       }
 
@@ -321,6 +321,7 @@ export enum ClickHouseEngines {
   IcebergS3 = "IcebergS3",
   Kafka = "Kafka",
   Merge = "Merge",
+  Null = "Null",
   ReplicatedMergeTree = "ReplicatedMergeTree",
   ReplicatedReplacingMergeTree = "ReplicatedReplacingMergeTree",
   ReplicatedAggregatingMergeTree = "ReplicatedAggregatingMergeTree",
@@ -59,7 +59,11 @@ export {
 } from "./sdk/stream";
 
 export { Workflow, Task } from "./sdk/workflow";
-export type { TaskContext, TaskConfig } from "./sdk/workflow";
+export type {
+  TaskContext,
+  TaskConfig,
+  WorkflowRetryPolicy,
+} from "./sdk/workflow";
 
 export { IngestApi, IngestConfig } from "./sdk/ingestApi";
 export {
@@ -31,6 +31,7 @@ import {
   S3QueueConfig,
 } from "./sdk/olapTable";
 import type { TableProjection } from "./sdk/olapTable";
+import type { WorkflowRetryPolicy } from "./sdk/workflow";
 import {
   ConsumerConfig,
   KafkaSchemaConfig,
@@ -419,6 +420,10 @@ interface MergeEngineConfig {
   tablesRegexp: string;
 }
 
+interface NullEngineConfig {
+  engine: "Null";
+}
+
 /**
  * Union type for all supported engine configurations
  */
@@ -441,7 +446,8 @@ type EngineConfig =
   | DistributedEngineConfig
   | IcebergS3EngineConfig
   | KafkaEngineConfig
-  | MergeEngineConfig;
+  | MergeEngineConfig
+  | NullEngineConfig;
 
 /**
  * JSON representation of an OLAP table configuration.
@@ -600,6 +606,9 @@ interface WorkflowJson {
   retries?: number;
   timeout?: string;
   schedule?: string;
+  timezone?: string;
+  executionTimeout?: string;
+  retryPolicy?: WorkflowRetryPolicy;
   pullsDataFrom: InfrastructureSignatureJson[];
   pushesDataTo: InfrastructureSignatureJson[];
 }
@@ -630,6 +639,8 @@ interface SqlResourceJson {
   sourceLine?: number;
   /** Optional source column number where this resource is defined. */
   sourceColumn?: number;
+  /** Optional lifecycle management policy */
+  lifeCycle?: string;
 }
 
 /**
@@ -1059,6 +1070,11 @@ function convertTableConfigToEngineConfig(
     return convertMergeEngineConfig(config);
   }
 
+  // Handle Null
+  if (engine === ClickHouseEngines.Null) {
+    return { engine: "Null" };
+  }
+
   return undefined;
 }
 
@@ -1266,6 +1282,7 @@ export const toInfraMap = (registry: MooseInternalRegistry) => {
       sourceFile: sqlResource.sourceFile,
       sourceLine: sqlResource.sourceLine,
       sourceColumn: sqlResource.sourceColumn,
+      lifeCycle: sqlResource.lifeCycle,
 
       pullsDataFrom: sqlResource.pullsDataFrom.map((r) => {
         if (r.kind === "OlapTable") {
@@ -1343,6 +1360,9 @@ export const toInfraMap = (registry: MooseInternalRegistry) => {
       retries: workflow.config.retries,
       timeout: workflow.config.timeout,
       schedule: workflow.config.schedule,
+      timezone: workflow.config.timezone,
+      executionTimeout: workflow.config.executionTimeout,
+      retryPolicy: workflow.config.retryPolicy,
       pullsDataFrom: workflowLineage?.pullsDataFrom ?? [],
       pushesDataTo: workflowLineage?.pushesDataTo ?? [],
     };
@@ -672,6 +672,34 @@ export type MergeConfig<T> = Omit<
   tablesRegexp: string;
 };
 
+/**
+ * Configuration for Null engine - discards inserted rows, while materialized
+ * views selecting from the table still receive them.
+ *
+ * @template T The data type of the records inserted into the table.
+ *
+ * @example
+ * ```typescript
+ * const rawEvents = new OlapTable<Event>("raw_events", {
+ *   engine: ClickHouseEngines.Null,
+ * });
+ * ```
+ *
+ * @remarks
+ * - SELECT queries on a Null table always return no rows
+ * - Does not support ORDER BY, PARTITION BY, or SAMPLE BY clauses
+ */
+export type NullConfig<T> = Omit<
+  BaseOlapConfig<T>,
+  | "orderByFields"
+  | "orderByExpression"
+  | "partitionBy"
+  | "sampleByExpression"
+  | "projections"
+> & {
+  engine: ClickHouseEngines.Null;
+};
+
 /**
  * Legacy configuration (backward compatibility) - defaults to MergeTree engine
  * @template T The data type of the records stored in the table.
@@ -697,7 +725,8 @@ type EngineConfig<T> =
   | DistributedConfig<T>
   | IcebergS3Config<T>
   | KafkaConfig<T>
-  | MergeConfig<T>;
+  | MergeConfig<T>
+  | NullConfig<T>;
 
 /**
  * Union of all engine-specific configurations (new API)
@@ -4,6 +4,7 @@ import { Sql, toStaticQuery } from "../../sqlHelpers";
 import { getSourceLocationFromStack } from "../utils/stackTrace";
 import { MaterializedView } from "./materializedView";
 import { View } from "./view";
+import { LifeCycle } from "./lifeCycle";
 
 type SqlObject = OlapTable<any> | SqlResource | View | MaterializedView<any>;
 
@@ -27,6 +28,10 @@ export class SqlResource {
   /** List of OlapTables or Views that this resource writes data to. */
   pushesDataTo: SqlObject[];
 
+  /** Optional lifecycle management policy for the resource.
+   * EXTERNALLY_MANAGED resources are never created or dropped by Moose. */
+  lifeCycle?: LifeCycle;
+
   /** @internal Source file path where this resource was defined */
   sourceFile?: string;
 
@@ -44,6 +49,7 @@ export class SqlResource {
    * @param options Optional configuration for specifying data dependencies.
    * @param options.pullsDataFrom Tables/Views this resource reads from.
    * @param options.pushesDataTo Tables/Views this resource writes to.
+   * @param options.lifeCycle Lifecycle management policy, FULLY_MANAGED by default.
    */
   constructor(
     name: string,
@@ -52,6 +58,7 @@ export class SqlResource {
     options?: {
       pullsDataFrom?: SqlObject[];
       pushesDataTo?: SqlObject[];
+      lifeCycle?: LifeCycle;
     },
   ) {
     const sqlResources = getMooseInternal().sqlResources;
@@ -71,6 +78,7 @@ export class SqlResource {
     );
     this.pullsDataFrom = options?.pullsDataFrom ?? [];
     this.pushesDataTo = options?.pushesDataTo ?? [];
+    this.lifeCycle = options?.lifeCycle;
 
     // Capture source location from stack trace
     const stack = new Error().stack;
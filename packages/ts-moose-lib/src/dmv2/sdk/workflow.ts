@@ -103,6 +103,29 @@ export class Task<T, R> {
   ) {}
 }
 
+/**
+ * Retry policy of a workflow. Unset fields keep Temporal's defaults.
+ */
+export interface WorkflowRetryPolicy {
+  /** Delay before the first retry (e.g., "1s") */
+  initialInterval?: string;
+
+  /** Multiplier applied to the delay after each retry, at least 1 */
+  backoffCoefficient?: number;
+
+  /** Upper bound of the delay between retries (e.g., "5m") */
+  maximumInterval?: string;
+
+  /**
+   * Total attempts including the first one, overriding `retries`.
+   * 0 retries until `executionTimeout`, which is then required.
+   */
+  maxAttempts?: number;
+
+  /** Error types that fail the workflow without retrying */
+  nonRetryableErrorTypes?: string[];
+}
+
 /**
  * Configuration options for defining a workflow.
  *
@@ -130,8 +153,17 @@ export interface WorkflowConfig {
   /** Optional timeout duration for the entire workflow execution (e.g., "10m", "1h") */
   timeout?: string;
 
+  /** Optional limit across all runs and retries of the workflow (e.g., "6h") */
+  executionTimeout?: string;
+
+  /** Optional retry policy, for tuning retries against flaky external APIs */
+  retryPolicy?: WorkflowRetryPolicy;
+
   /** Optional cron-style schedule string for automated workflow execution */
   schedule?: string;
+
+  /** Optional IANA time zone the schedule is read in (e.g., "Europe/Paris"), UTC by default */
+  timezone?: string;
 }
 
 /**